@@ -8,6 +8,7 @@ use mgo_analytics_indexer::{
     AnalyticsIndexerConfig,
 };
 use mgo_indexer::framework::IndexerBuilder;
+use mgo_indexer::metrics::CheckpointFetcherMetrics;
 use tracing::info;
 
 #[tokio::main]
@@ -37,7 +38,8 @@ async fn main() -> Result<(), AnalyticsIndexerError> {
     IndexerBuilder::new()
         .last_downloaded_checkpoint(processor.last_committed_checkpoint())
         .rest_url(&rest_url)
-        .handler(processor)
+        .fetcher_metrics(CheckpointFetcherMetrics::new(&registry))
+        .register_handler(processor)
         .run()
         .await;
 
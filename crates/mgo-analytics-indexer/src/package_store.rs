@@ -17,6 +17,7 @@ use typed_store::rocks::{DBMap, MetricConf};
 use typed_store::traits::TableSummary;
 use typed_store::traits::TypedStoreDebug;
 use typed_store::{Map, TypedStoreError};
+use typed_store_error::{retry_with_backoff, RetryPolicy};
 use typed_store_derive::DBMapUtils;
 
 const STORE: &str = "RocksDB";
@@ -52,12 +53,15 @@ impl PackageStoreTables {
             None,
         ))
     }
+    /// Writes `package`, retrying transient store errors such as a busy database or a write
+    /// stall so that they do not fail the checkpoint being processed.
     pub(crate) fn update(&self, package: &Object) -> Result<()> {
-        let mut batch = self.packages.batch();
-        batch
-            .insert_batch(&self.packages, std::iter::once((package.id(), package)))
-            .map_err(Error::TypedStore)?;
-        batch.write().map_err(Error::TypedStore)?;
+        retry_with_backoff(&RetryPolicy::default(), || {
+            let mut batch = self.packages.batch();
+            batch.insert_batch(&self.packages, std::iter::once((package.id(), package)))?;
+            batch.write()
+        })
+        .map_err(Error::TypedStore)?;
         Ok(())
     }
 }
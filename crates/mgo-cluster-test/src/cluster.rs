@@ -371,6 +371,7 @@ pub async fn new_wallet_context_from_cluster(
         }],
         active_address: Some(address),
         active_env: Some("localnet".to_string()),
+        guardrails: Default::default(),
     }
     .persisted(&wallet_config_path)
     .save()
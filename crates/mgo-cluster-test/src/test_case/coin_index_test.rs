@@ -221,12 +221,18 @@ impl TestCaseImpl for CoinIndexTest {
                 coin_object_count: old_coin_object_count,
                 total_balance,
                 locked_balance: HashMap::new(),
+                verified: None,
+                canonical_symbol: None,
+                canonical_icon_url: None,
             },
             Balance {
                 coin_type: coin_type_str.clone(),
                 coin_object_count: 1,
                 total_balance: 10000,
                 locked_balance: HashMap::new(),
+                verified: None,
+                canonical_symbol: None,
+                canonical_icon_url: None,
             },
         ];
         // Comes with asc order.
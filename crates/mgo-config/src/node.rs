@@ -19,7 +19,7 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use std::usize;
-use mgo_keys::keypair_file::{read_authority_keypair_from_file, read_keypair_from_file};
+use mgo_keys::key_source::{resolve_authority_keypair, resolve_keypair, KeySource};
 use mgo_protocol_config::{Chain, SupportedProtocolVersions};
 use mgo_types::base_types::{ObjectID, MgoAddress};
 use mgo_types::committee::EpochId;
@@ -129,6 +129,11 @@ pub struct NodeConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name_service_reverse_registry_id: Option<ObjectID>,
 
+    /// Registry object whose dynamic fields list the coin types that coin and balance responses
+    /// mark as verified. Responses carry no verification when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coin_registry_id: Option<ObjectID>,
+
     #[serde(default)]
     pub transaction_deny_config: TransactionDenyConfig,
 
@@ -150,6 +155,14 @@ pub struct NodeConfig {
     #[serde(default)]
     pub indexer_max_subscriptions: Option<usize>,
 
+    /// Levels of `All`, `Any`, `And` and `Or` the event filters of subscriptions may nest.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_filter_max_depth: Option<usize>,
+
+    /// Filters the event filter of a subscription may be made of, combining ones included.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_filter_max_nodes: Option<usize>,
+
     #[serde(default = "default_transaction_kv_store_config")]
     pub transaction_kv_store_read_config: TransactionKeyValueStoreReadConfig,
 
@@ -167,6 +180,72 @@ pub struct NodeConfig {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub run_with_range: Option<RunWithRange>,
+
+    /// When set, every dry run is compared in the background to dev inspecting the same
+    /// transaction, and gas differing by more than this many basis points in any component is
+    /// counted and logged. Also serves `mgox_debugGasComparison`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dry_run_gas_comparison_threshold_bps: Option<u64>,
+
+    /// Serves `mgox_exportAddressState` to support tooling. Exports are expensive, so this is
+    /// meant for nodes that are not exposed publicly.
+    #[serde(default)]
+    pub enable_support_api: bool,
+
+    /// Cross-origin access of browsers to the JSON-RPC server. When unset, any origin may call
+    /// any method, or those of the `ACCESS_CONTROL_ALLOW_ORIGIN` environment variable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_rpc_cors: Option<JsonRpcCorsConfig>,
+
+    /// Signing of the results of some JSON-RPC methods, as proof of what the node returned.
+    /// Results are not signed when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_rpc_response_signing: Option<JsonRpcResponseSigningConfig>,
+
+    /// Limits applied when rendering the Display of objects returned by the JSON-RPC server.
+    /// The server's defaults apply to those unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_rpc_display_limits: Option<JsonRpcDisplayLimitsConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct JsonRpcResponseSigningConfig {
+    /// File of the key signing the results, dedicated to signing them.
+    pub key_path: PathBuf,
+    /// Methods whose results are signed, the transaction responses and checkpoints when empty.
+    #[serde(default)]
+    pub methods: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct JsonRpcDisplayLimitsConfig {
+    /// `{...}` substitutions the Display template of a single object may render.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_substitutions: Option<usize>,
+    /// `.`-separated segments a field path of a Display template may have.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_field_depth: Option<usize>,
+    /// Milliseconds rendering the Display of a single object may take.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_budget_ms: Option<u64>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct JsonRpcCorsConfig {
+    /// Origins browsers may call the server from, as `scheme://host[:port]`. Any origin may
+    /// when empty.
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+    /// Headers browsers may send besides those of the client SDKs.
+    #[serde(default)]
+    pub allowed_headers: Vec<String>,
+    /// Methods that browsers of an origin are restricted to, by origin. The `*` entry restricts
+    /// all origins without entries of their own.
+    #[serde(default)]
+    pub origin_methods: BTreeMap<String, BTreeSet<String>>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
@@ -824,9 +903,10 @@ enum KeyPairLocation {
         #[serde_as(as = "Arc<KeyPairBase64>")]
         value: Arc<MgoKeyPair>,
     },
+    /// A path, as in legacy configs, or any [`KeySource`], e.g. `env:MGO_ACCOUNT_KEY`.
     File {
         #[serde(rename = "path")]
-        path: PathBuf,
+        path: KeySource,
     },
 }
 
@@ -843,14 +923,18 @@ impl KeyPairWithPath {
     }
 
     pub fn new_from_path(path: PathBuf) -> Self {
+        Self::new_from_source(KeySource::File(path))
+    }
+
+    pub fn new_from_source(source: KeySource) -> Self {
         let cell: OnceCell<Arc<MgoKeyPair>> = OnceCell::new();
         // OK to unwrap panic because authority should not start without all keypairs loaded.
-        cell.set(Arc::new(read_keypair_from_file(&path).unwrap_or_else(
-            |e| panic!("Invalid keypair file at path {:?}: {e}", &path),
-        )))
+        cell.set(Arc::new(
+            resolve_keypair(&source).unwrap_or_else(|e| panic!("Invalid keypair: {e:#}")),
+        ))
         .expect("Failed to set keypair");
         Self {
-            location: KeyPairLocation::File { path },
+            location: KeyPairLocation::File { path: source },
             keypair: cell,
         }
     }
@@ -862,9 +946,8 @@ impl KeyPairWithPath {
                 KeyPairLocation::File { path } => {
                     // OK to unwrap panic because authority should not start without all keypairs loaded.
                     Arc::new(
-                        read_keypair_from_file(path).unwrap_or_else(|e| {
-                            panic!("Invalid keypair file at path {:?}: {e}", path)
-                        }),
+                        resolve_keypair(path)
+                            .unwrap_or_else(|e| panic!("Invalid keypair: {e:#}")),
                     )
                 }
             })
@@ -887,7 +970,8 @@ pub struct AuthorityKeyPairWithPath {
 #[serde(untagged)]
 enum AuthorityKeyPairLocation {
     InPlace { value: Arc<AuthorityKeyPair> },
-    File { path: PathBuf },
+    /// A path, as in legacy configs, or any [`KeySource`], e.g. `env:MGO_PROTOCOL_KEY`.
+    File { path: KeySource },
 }
 
 impl AuthorityKeyPairWithPath {
@@ -904,15 +988,19 @@ impl AuthorityKeyPairWithPath {
     }
 
     pub fn new_from_path(path: PathBuf) -> Self {
+        Self::new_from_source(KeySource::File(path))
+    }
+
+    pub fn new_from_source(source: KeySource) -> Self {
         let cell: OnceCell<Arc<AuthorityKeyPair>> = OnceCell::new();
         // OK to unwrap panic because authority should not start without all keypairs loaded.
         cell.set(Arc::new(
-            read_authority_keypair_from_file(&path)
-                .unwrap_or_else(|_| panic!("Invalid authority keypair file at path {:?}", &path)),
+            resolve_authority_keypair(&source)
+                .unwrap_or_else(|e| panic!("Invalid authority keypair: {e:#}")),
         ))
         .expect("Failed to set authority keypair");
         Self {
-            location: AuthorityKeyPairLocation::File { path },
+            location: AuthorityKeyPairLocation::File { path: source },
             keypair: cell,
         }
     }
@@ -924,9 +1012,8 @@ impl AuthorityKeyPairWithPath {
                 AuthorityKeyPairLocation::File { path } => {
                     // OK to unwrap panic because authority should not start without all keypairs loaded.
                     Arc::new(
-                        read_authority_keypair_from_file(path).unwrap_or_else(|_| {
-                            panic!("Invalid authority keypair file {:?}", &path)
-                        }),
+                        resolve_authority_keypair(path)
+                            .unwrap_or_else(|e| panic!("Invalid authority keypair: {e:#}")),
                     )
                 }
             })
@@ -947,7 +1034,7 @@ pub struct StateDebugDumpConfig {
 mod tests {
     use std::path::PathBuf;
 
-    use fastcrypto::traits::KeyPair;
+    use fastcrypto::traits::{EncodeDecodeBase64, KeyPair};
     use rand::{rngs::StdRng, SeedableRng};
     use mgo_keys::keypair_file::{write_authority_keypair_to_file, write_keypair_to_file};
     use mgo_types::crypto::{get_key_pair_from_rng, AuthorityKeyPair, NetworkKeyPair, MgoKeyPair};
@@ -1008,6 +1095,60 @@ mod tests {
             worker_key_pair.public()
         );
     }
+
+    #[test]
+    fn load_key_pairs_from_sources() {
+        let protocol_key_pair: AuthorityKeyPair =
+            get_key_pair_from_rng(&mut StdRng::from_seed([1; 32])).1;
+        let network_key_pair: NetworkKeyPair =
+            get_key_pair_from_rng(&mut StdRng::from_seed([1; 32])).1;
+        let worker_key_pair: NetworkKeyPair =
+            get_key_pair_from_rng(&mut StdRng::from_seed([1; 32])).1;
+        write_keypair_to_file(
+            &MgoKeyPair::Ed25519(worker_key_pair.copy()),
+            PathBuf::from("worker-source.key"),
+        )
+        .unwrap();
+        std::env::set_var(
+            "MGO_CONFIG_TEST_PROTOCOL_KEY",
+            protocol_key_pair.encode_base64(),
+        );
+
+        const TEMPLATE: &str = include_str!("../data/fullnode-template-with-path.yaml");
+        let config = TEMPLATE
+            .replace("\"protocol.key\"", "\"env:MGO_CONFIG_TEST_PROTOCOL_KEY\"")
+            .replace(
+                "\"network.key\"",
+                &format!(
+                    "\"base64:{}\"",
+                    MgoKeyPair::Ed25519(network_key_pair.copy()).encode_base64()
+                ),
+            )
+            .replace("\"worker.key\"", "\"file:worker-source.key\"");
+        let config: NodeConfig = serde_yaml::from_str(&config).unwrap();
+        assert_eq!(
+            config.protocol_key_pair().public(),
+            protocol_key_pair.public()
+        );
+        assert_eq!(
+            config.network_key_pair().public(),
+            network_key_pair.public()
+        );
+        assert_eq!(
+            config.worker_key_pair().public(),
+            worker_key_pair.public()
+        );
+
+        // Sources are written back as they were read, with plain paths left without a prefix.
+        let written = serde_yaml::to_string(&config).unwrap();
+        assert!(written.contains("path: worker-source.key"));
+        assert!(written.contains("path: account.key"));
+        let reloaded: NodeConfig = serde_yaml::from_str(&written).unwrap();
+        assert_eq!(
+            reloaded.protocol_key_pair().public(),
+            protocol_key_pair.public()
+        );
+    }
 }
 
 // RunWithRange is used to specify the ending epoch/checkpoint to process.
@@ -71,7 +71,7 @@ use mgo_json_rpc_types::{
 };
 use mgo_macros::{fail_point, fail_point_async, fail_point_if};
 use mgo_protocol_config::{ProtocolConfig, SupportedProtocolVersions};
-use mgo_storage::indexes::{CoinInfo, ObjectIndexChanges};
+use mgo_storage::indexes::{events_after_cursor, CoinInfo, ObjectIndexChanges};
 use mgo_storage::key_value_store::{TransactionKeyValueStore, TransactionKeyValueStoreTrait};
 use mgo_storage::key_value_store_metrics::KeyValueStoreMetrics;
 use mgo_storage::IndexStore;
@@ -145,7 +145,7 @@ use crate::module_cache_metrics::ResolverMetrics;
 use crate::overload_monitor::{overload_monitor, AuthorityOverloadInfo};
 use crate::stake_aggregator::StakeAggregator;
 use crate::state_accumulator::{StateAccumulator, WrappedObject};
-use crate::subscription_handler::SubscriptionHandler;
+use crate::subscription_handler::{LatestCheckpoint, SubscriptionHandler};
 use crate::transaction_input_loader::TransactionInputLoader;
 use crate::transaction_manager::TransactionManager;
 
@@ -1477,6 +1477,7 @@ impl AuthorityState {
         Ok((inner_temp_store, effects, execution_error_opt.err()))
     }
 
+    #[instrument(level = "debug", skip_all, fields(tx_digest = ?transaction_digest))]
     pub async fn dry_exec_transaction(
         &self,
         transaction: TransactionData,
@@ -1664,6 +1665,7 @@ impl AuthorityState {
 
     /// The object ID for gas can be any object ID, even for an uncreated object
     #[allow(clippy::collapsible_else_if)]
+    #[instrument(level = "debug", skip_all, fields(?sender))]
     pub async fn dev_inspect_transaction_block(
         &self,
         sender: MgoAddress,
@@ -3163,6 +3165,76 @@ impl AuthorityState {
         }
     }
 
+    /// Same as `get_past_object_read` for each of `requests`, reading all the requested
+    /// versions from the store at once. Results are in the order of `requests`.
+    pub fn multi_get_past_object_reads(
+        &self,
+        requests: &[(ObjectID, SequenceNumber)],
+    ) -> MgoResult<Vec<PastObjectRead>> {
+        let mut latest_refs = HashMap::new();
+        for (object_id, _) in requests {
+            if !latest_refs.contains_key(object_id) {
+                let latest_ref = self
+                    .execution_cache
+                    .get_latest_object_ref_or_tombstone(*object_id)?;
+                latest_refs.insert(*object_id, latest_ref);
+            }
+        }
+
+        // Requests answered by the latest references alone, and the keys to read for the rest.
+        let mut reads = Vec::with_capacity(requests.len());
+        let mut keys = vec![];
+        for (object_id, version) in requests {
+            let read = match latest_refs[object_id] {
+                None => Some(PastObjectRead::ObjectNotExists(*object_id)),
+                Some(obj_ref) if *version > obj_ref.1 => Some(PastObjectRead::VersionTooHigh {
+                    object_id: *object_id,
+                    asked_version: *version,
+                    latest_version: obj_ref.1,
+                }),
+                Some(obj_ref) if *version == obj_ref.1 && !obj_ref.2.is_alive() => {
+                    Some(PastObjectRead::ObjectDeleted(obj_ref))
+                }
+                Some(_) => {
+                    keys.push(ObjectKey(*object_id, *version));
+                    None
+                }
+            };
+            reads.push(read);
+        }
+
+        let mut objects = self.execution_cache.multi_get_object_by_key(&keys)?.into_iter();
+        reads
+            .into_iter()
+            .zip(requests)
+            .map(|(read, (object_id, version))| -> MgoResult<PastObjectRead> {
+                if let Some(read) = read {
+                    return Ok(read);
+                }
+                let latest_version = latest_refs[object_id].map(|obj_ref| obj_ref.1);
+                match objects.next().flatten() {
+                    Some(object) => {
+                        let layout = self.get_object_layout(&object)?;
+                        let obj_ref = object.compute_object_reference();
+                        Ok(PastObjectRead::VersionFound(obj_ref, object, layout))
+                    }
+                    None if latest_version == Some(*version) => {
+                        error!(
+                            "Object with in parent_entry is missing from object store, datastore \
+                             is inconsistent",
+                        );
+                        Err(UserInputError::ObjectNotFound {
+                            object_id: *object_id,
+                            version: Some(*version),
+                        }
+                        .into())
+                    }
+                    None => Ok(PastObjectRead::VersionNotFound(*object_id, *version)),
+                }
+            })
+            .collect()
+    }
+
     #[instrument(level = "trace", skip_all)]
     fn read_object_at_version(
         &self,
@@ -3232,9 +3304,16 @@ impl AuthorityState {
         cursor: (String, ObjectID),
         limit: usize,
         one_coin_type_only: bool,
+        min_balance: Option<u64>,
     ) -> MgoResult<impl Iterator<Item = (String, ObjectID, CoinInfo)> + '_> {
         if let Some(indexes) = &self.indexes {
-            indexes.get_owned_coins_iterator_with_cursor(owner, cursor, limit, one_coin_type_only)
+            indexes.get_owned_coins_iterator_with_cursor(
+                owner,
+                cursor,
+                limit,
+                one_coin_type_only,
+                min_balance,
+            )
         } else {
             Err(MgoError::IndexStoreNotAvailable)
         }
@@ -3419,21 +3498,12 @@ impl AuthorityState {
         limit: Option<usize>,
         reverse: bool,
     ) -> MgoResult<Vec<TransactionDigest>> {
+        // The transactions of a checkpoint are read from its contents at once, in execution
+        // order, rather than looked up one by one in the indexes.
         if let Some(TransactionFilter::Checkpoint(sequence_number)) = filter {
             let checkpoint_contents = kv_store.get_checkpoint_contents(sequence_number).await?;
-            let iter = checkpoint_contents.iter().map(|c| c.transaction);
-            if reverse {
-                let iter = iter
-                    .rev()
-                    .skip_while(|d| cursor.is_some() && Some(*d) != cursor)
-                    .skip(usize::from(cursor.is_some()));
-                return Ok(iter.take(limit.unwrap_or(usize::max_value())).collect());
-            } else {
-                let iter = iter
-                    .skip_while(|d| cursor.is_some() && Some(*d) != cursor)
-                    .skip(usize::from(cursor.is_some()));
-                return Ok(iter.take(limit.unwrap_or(usize::max_value())).collect());
-            }
+            let digests: Vec<_> = checkpoint_contents.iter().map(|c| c.transaction).collect();
+            return checkpoint_transactions_page(&digests, cursor, limit, reverse);
         }
         self.get_indexes()?
             .get_transactions(filter, cursor, limit, reverse)
@@ -3451,6 +3521,24 @@ impl AuthorityState {
             })
     }
 
+    /// The latest executed checkpoint with the number of indexed transactions, served from
+    /// memory once a checkpoint has been executed or read since startup.
+    pub fn get_latest_checkpoint(&self) -> MgoResult<LatestCheckpoint> {
+        if let Some(latest) = self.subscription_handler.latest_checkpoint() {
+            return Ok(latest);
+        }
+        let sequence_number = self.get_latest_checkpoint_sequence_number()?;
+        let checkpoint = self
+            .get_checkpoint_by_sequence_number(sequence_number)?
+            .ok_or(MgoError::UserInputError {
+                error: UserInputError::LatestCheckpointSequenceNumberNotFound,
+            })?;
+        let latest =
+            LatestCheckpoint::new(checkpoint.data(), self.get_total_transaction_blocks().ok());
+        self.subscription_handler.update_latest_checkpoint(latest);
+        Ok(latest)
+    }
+
     #[cfg(msim)]
     pub fn get_highest_pruned_checkpoint(&self) -> MgoResult<CheckpointSequenceNumber> {
         self.database
@@ -3591,22 +3679,11 @@ impl AuthorityState {
     ) -> MgoResult<Vec<MgoEvent>> {
         let index_store = self.get_indexes()?;
 
-        //Get the tx_num from tx_digest
-        let (tx_num, event_num) = if let Some(cursor) = cursor.as_ref() {
-            let tx_seq = index_store.get_transaction_seq(&cursor.tx_digest)?.ok_or(
-                MgoError::TransactionNotFound {
-                    digest: cursor.tx_digest,
-                },
-            )?;
-            (tx_seq, cursor.event_seq as usize)
-        } else if descending {
-            (u64::MAX, usize::MAX)
-        } else {
-            (0, 0)
-        };
+        let (tx_num, event_num) = index_store.event_cursor_position(cursor.as_ref(), descending)?;
 
+        // One more event than the limit is read, for the cursor event itself.
         let limit = limit + 1;
-        let mut event_keys = match query {
+        let event_keys = match query {
             EventFilter::All(filters) => {
                 if filters.is_empty() {
                     index_store.all_events(tx_num, event_num, limit, descending)?
@@ -3652,6 +3729,8 @@ impl AuthorityState {
                 )?,
             // not using "_ =>" because we want to make sure we remember to add new variants here
             EventFilter::Package(_)
+            | EventFilter::Checkpoint(_)
+            | EventFilter::CheckpointRange { .. }
             | EventFilter::MoveEventField { .. }
             | EventFilter::Any(_)
             | EventFilter::And(_, _)
@@ -3664,15 +3743,7 @@ impl AuthorityState {
             }
         };
 
-        // skip one event if exclusive cursor is provided,
-        // otherwise truncate to the original limit.
-        if cursor.is_some() {
-            if !event_keys.is_empty() {
-                event_keys.remove(0);
-            }
-        } else {
-            event_keys.truncate(limit - 1);
-        }
+        let event_keys = events_after_cursor(event_keys, cursor.as_ref(), limit - 1);
 
         // get the unique set of digests from the event_keys
         let event_digests = event_keys
@@ -4669,6 +4740,34 @@ impl AuthorityState {
     }
 }
 
+/// The page of `digests`, the transactions of a checkpoint in execution order, after `cursor`.
+/// The cursor must be a transaction of the checkpoint, its position in the checkpoint being
+/// where the page starts.
+fn checkpoint_transactions_page(
+    digests: &[TransactionDigest],
+    cursor: Option<TransactionDigest>,
+    limit: Option<usize>,
+    reverse: bool,
+) -> MgoResult<Vec<TransactionDigest>> {
+    let position = cursor
+        .map(|cursor| {
+            digests
+                .iter()
+                .position(|digest| *digest == cursor)
+                .ok_or(MgoError::TransactionNotFound { digest: cursor })
+        })
+        .transpose()?;
+    let limit = limit.unwrap_or(usize::MAX);
+    let page = if reverse {
+        let end = position.unwrap_or(digests.len());
+        digests[..end].iter().rev().take(limit).copied().collect()
+    } else {
+        let start = position.map_or(0, |position| position + 1);
+        digests[start..].iter().take(limit).copied().collect()
+    };
+    Ok(page)
+}
+
 #[async_trait]
 impl TransactionKeyValueStoreTrait for AuthorityState {
     async fn multi_get(
@@ -31,7 +31,7 @@ use mgo_types::committee::CommitteeTrait;
 use mgo_types::crypto::{AuthoritySignInfo, AuthorityStrongQuorumSignInfo};
 use mgo_types::digests::ChainIdentifier;
 use mgo_types::error::{MgoError, MgoResult};
-use mgo_types::signature::GenericSignature;
+use mgo_types::signature::{GenericSignature, VerifyParams};
 use mgo_types::storage::InputKey;
 use mgo_types::transaction::{
     AuthenticatorStateUpdate, CertifiedTransaction, InputObjectKind, SenderSignedData,
@@ -2097,6 +2097,11 @@ impl AuthorityPerEpochStore {
         }
     }
 
+    /// The params the user signatures of transactions are verified with in this epoch.
+    pub fn signature_verify_params(&self) -> VerifyParams {
+        self.signature_verifier.verify_params()
+    }
+
     #[instrument(level = "trace", skip_all)]
     pub fn verify_transaction(&self, tx: Transaction) -> MgoResult<VerifiedTransaction> {
         self.signature_verifier
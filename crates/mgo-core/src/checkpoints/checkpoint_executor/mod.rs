@@ -333,6 +333,21 @@ impl CheckpointExecutor {
             .last_executed_checkpoint_timestamp_ms
             .set(checkpoint.timestamp_ms as i64);
         checkpoint.report_checkpoint_age_ms(&self.metrics.last_executed_checkpoint_age_ms);
+
+        let transaction_count = self
+            .checkpoint_store
+            .get_checkpoint_contents(&checkpoint.content_digest)
+            .expect("Failed to read checkpoint contents")
+            .expect("Contents of an executed checkpoint must exist")
+            .size();
+        // Taken once the transactions of the checkpoint are indexed, and only on nodes that index
+        // them.
+        let total_transaction_blocks = self.state.get_total_transaction_blocks().ok();
+        self.state.subscription_handler.process_checkpoint(
+            checkpoint.data(),
+            transaction_count as u64,
+            total_transaction_blocks,
+        );
     }
 
     #[instrument(level = "debug", skip_all)]
@@ -656,6 +656,15 @@ impl CheckpointStore {
             .map_err(Into::into)
     }
 
+    /// Stores `contents` under `digest` whatever they hash to, to corrupt the store in tests.
+    pub fn insert_checkpoint_contents_at_digest_test_only(
+        &self,
+        digest: CheckpointContentsDigest,
+        contents: &CheckpointContents,
+    ) -> Result<(), TypedStoreError> {
+        self.checkpoint_content.insert(&digest, contents)
+    }
+
     pub fn delete_highest_executed_checkpoint_test_only(&self) -> Result<(), TypedStoreError> {
         let mut wb = self.watermarks.batch();
         wb.delete_batch(
@@ -348,20 +348,24 @@ impl SignatureVerifier {
         self.jwks.read().clone()
     }
 
+    /// The params user signatures are verified with, from the JWKs currently known.
+    pub fn verify_params(&self) -> VerifyParams {
+        let jwks = self.jwks.read().clone();
+        VerifyParams::new(
+            jwks,
+            self.zk_login_params.supported_providers.clone(),
+            self.zk_login_params.env.clone(),
+            self.zk_login_params.verify_legacy_zklogin_address,
+            self.zk_login_params.accept_zklogin_in_multisig,
+        )
+    }
+
     pub fn verify_tx(&self, signed_tx: &SenderSignedData) -> MgoResult {
         self.signed_data_cache.is_verified(
             signed_tx.full_message_digest(),
             || {
                 signed_tx.verify_epoch(self.committee.epoch())?;
-                let jwks = self.jwks.read().clone();
-                let verify_params = VerifyParams::new(
-                    jwks,
-                    self.zk_login_params.supported_providers.clone(),
-                    self.zk_login_params.env.clone(),
-                    self.zk_login_params.verify_legacy_zklogin_address,
-                    self.zk_login_params.accept_zklogin_in_multisig,
-                );
-                signed_tx.verify_message_signature(&verify_params)
+                signed_tx.verify_message_signature(&self.verify_params())
             },
             || Ok(()),
         )
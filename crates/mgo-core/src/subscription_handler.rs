@@ -3,20 +3,25 @@
 
 use std::sync::Arc;
 
+use arc_swap::ArcSwapOption;
 use prometheus::{
     register_int_counter_vec_with_registry, register_int_gauge_vec_with_registry, IntCounterVec,
     IntGaugeVec, Registry,
 };
-use tokio_stream::Stream;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
 use tracing::{error, instrument, trace};
 
 use crate::streamer::Streamer;
 use mgo_json_rpc_types::{
-    EffectsWithInput, EventFilter, MgoTransactionBlockEffects, MgoTransactionBlockEvents,
-    TransactionFilter,
+    CheckpointNotification, EffectsWithInput, EventFilter, MgoTransactionBlockEffects,
+    MgoTransactionBlockEvents, TransactionFilter,
 };
 use mgo_json_rpc_types::{MgoEvent, MgoTransactionBlockEffectsAPI};
 use mgo_types::error::MgoResult;
+use mgo_types::messages_checkpoint::{CheckpointSequenceNumber, CheckpointSummary};
 use mgo_types::transaction::TransactionData;
 
 #[cfg(test)]
@@ -24,11 +29,14 @@ use mgo_types::transaction::TransactionData;
 mod subscription_handler_tests;
 
 pub const EVENT_DISPATCH_BUFFER_SIZE: usize = 1000;
+/// Number of checkpoint notifications a subscriber may lag behind before it starts missing them.
+pub const CHECKPOINT_DISPATCH_BUFFER_SIZE: usize = 100;
 
 pub struct SubscriptionMetrics {
     pub streaming_success: IntCounterVec,
     pub streaming_failure: IntCounterVec,
     pub streaming_active_subscriber_number: IntGaugeVec,
+    pub streaming_dropped: IntCounterVec,
 }
 
 impl SubscriptionMetrics {
@@ -55,6 +63,32 @@ impl SubscriptionMetrics {
                 registry,
             )
             .unwrap(),
+            streaming_dropped: register_int_counter_vec_with_registry!(
+                "streaming_dropped",
+                "Total number of items dropped for subscribers lagging too far behind",
+                &["type"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+/// The latest executed checkpoint with the number of transactions indexed by the node once it
+/// was executed, which are always updated together so that neither is observed ahead of the
+/// other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LatestCheckpoint {
+    pub sequence_number: CheckpointSequenceNumber,
+    /// `None` on nodes that do not index transactions.
+    pub total_transaction_blocks: Option<u64>,
+}
+
+impl LatestCheckpoint {
+    pub fn new(summary: &CheckpointSummary, total_transaction_blocks: Option<u64>) -> Self {
+        Self {
+            sequence_number: summary.sequence_number,
+            total_transaction_blocks,
         }
     }
 }
@@ -62,6 +96,9 @@ impl SubscriptionMetrics {
 pub struct SubscriptionHandler {
     event_streamer: Streamer<MgoEvent, MgoEvent, EventFilter>,
     transaction_streamer: Streamer<EffectsWithInput, MgoTransactionBlockEffects, TransactionFilter>,
+    checkpoint_sender: broadcast::Sender<CheckpointNotification>,
+    latest_checkpoint: ArcSwapOption<LatestCheckpoint>,
+    metrics: Arc<SubscriptionMetrics>,
 }
 
 impl SubscriptionHandler {
@@ -69,7 +106,14 @@ impl SubscriptionHandler {
         let metrics = Arc::new(SubscriptionMetrics::new(registry));
         Self {
             event_streamer: Streamer::spawn(EVENT_DISPATCH_BUFFER_SIZE, metrics.clone(), "event"),
-            transaction_streamer: Streamer::spawn(EVENT_DISPATCH_BUFFER_SIZE, metrics, "tx"),
+            transaction_streamer: Streamer::spawn(
+                EVENT_DISPATCH_BUFFER_SIZE,
+                metrics.clone(),
+                "tx",
+            ),
+            checkpoint_sender: broadcast::channel(CHECKPOINT_DISPATCH_BUFFER_SIZE).0,
+            latest_checkpoint: ArcSwapOption::empty(),
+            metrics,
         }
     }
 }
@@ -108,6 +152,55 @@ impl SubscriptionHandler {
         Ok(())
     }
 
+    /// Announces an executed checkpoint to the checkpoint subscribers, after making it the
+    /// [`Self::latest_checkpoint`] along with `total_transaction_blocks`. Never waits on them: a
+    /// subscriber lagging more than [`CHECKPOINT_DISPATCH_BUFFER_SIZE`] notifications behind
+    /// misses the oldest ones, and is told how many on its next notification.
+    pub fn process_checkpoint(
+        &self,
+        summary: &CheckpointSummary,
+        transaction_count: u64,
+        total_transaction_blocks: Option<u64>,
+    ) {
+        trace!(
+            sequence_number = summary.sequence_number,
+            "Processing checkpoint subscription"
+        );
+        self.update_latest_checkpoint(LatestCheckpoint::new(summary, total_transaction_blocks));
+        let subscribers = self.checkpoint_sender.receiver_count();
+        self.metrics
+            .streaming_active_subscriber_number
+            .with_label_values(&["checkpoint"])
+            .set(subscribers as i64);
+        // Sending only fails when nobody is subscribed.
+        if self
+            .checkpoint_sender
+            .send(CheckpointNotification::new(summary, transaction_count))
+            .is_ok()
+        {
+            self.metrics
+                .streaming_success
+                .with_label_values(&["checkpoint"])
+                .inc_by(subscribers as u64);
+        }
+    }
+
+    /// The latest checkpoint processed, or cached since startup. Never reads the store.
+    pub fn latest_checkpoint(&self) -> Option<LatestCheckpoint> {
+        self.latest_checkpoint.load().as_deref().copied()
+    }
+
+    /// Makes `latest` the latest checkpoint, unless a later one is already known, as happens
+    /// when a checkpoint is executed while `latest` is read from the store.
+    pub fn update_latest_checkpoint(&self, latest: LatestCheckpoint) {
+        self.latest_checkpoint.rcu(|current| match current {
+            Some(current) if current.sequence_number > latest.sequence_number => {
+                Some(current.clone())
+            }
+            _ => Some(Arc::new(latest)),
+        });
+    }
+
     pub fn subscribe_events(&self, filter: EventFilter) -> impl Stream<Item = MgoEvent> {
         self.event_streamer.subscribe(filter)
     }
@@ -118,4 +211,26 @@ impl SubscriptionHandler {
     ) -> impl Stream<Item = MgoTransactionBlockEffects> {
         self.transaction_streamer.subscribe(filter)
     }
+
+    /// Subscribes to the checkpoints executed from now on. Dropping the stream unsubscribes.
+    pub fn subscribe_checkpoints(&self) -> impl Stream<Item = CheckpointNotification> {
+        let dropped = self
+            .metrics
+            .streaming_dropped
+            .with_label_values(&["checkpoint"]);
+        let mut missed = 0;
+        BroadcastStream::new(self.checkpoint_sender.subscribe()).filter_map(
+            move |item| match item {
+                Ok(mut notification) => {
+                    notification.missed = std::mem::take(&mut missed);
+                    Some(notification)
+                }
+                Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                    missed += skipped;
+                    dropped.inc_by(skipped);
+                    None
+                }
+            },
+        )
+    }
 }
@@ -52,6 +52,11 @@ const LOCAL_EXECUTION_TIMEOUT: Duration = Duration::from_secs(10);
 
 const WAIT_FOR_FINALITY_TIMEOUT: Duration = Duration::from_secs(30);
 
+// How long to wait, after local execution, for the written objects to be readable before
+// local execution is reported as unconfirmed, and how often to check in the meantime.
+const WRITE_VISIBILITY_TIMEOUT: Duration = Duration::from_secs(2);
+const WRITE_VISIBILITY_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
 pub struct TransactiondOrchestrator<A: Clone> {
     quorum_driver_handler: Arc<QuorumDriverHandler<A>>,
     validator_state: Arc<AuthorityState>,
@@ -197,9 +202,11 @@ where
             QuorumDriverError::QuorumDriverInternalError(e)
         })?;
 
+        // A checkpoint executed by this node can only include a transaction executed locally.
         let wait_for_local_execution = matches!(
             request.request_type,
             ExecuteTransactionRequestType::WaitForLocalExecution
+                | ExecuteTransactionRequestType::WaitForCheckpointInclusion { .. }
         );
 
         let Ok(result) = timeout(WAIT_FOR_FINALITY_TIMEOUT, ticket).await else {
@@ -234,7 +241,7 @@ where
                     effects_cert.executed_epoch(),
                 );
 
-                match Self::execute_finalized_tx_locally_with_timeout(
+                let is_executed_locally = Self::execute_finalized_tx_locally_with_timeout(
                     &self.validator_state,
                     &epoch_store,
                     &executable_tx,
@@ -242,24 +249,26 @@ where
                     &self.metrics,
                 )
                 .await
-                {
-                    Ok(_) => Ok(ExecuteTransactionResponse::EffectsCert(Box::new((
-                        FinalizedEffects::new_from_effects_cert(effects_cert.into()),
-                        response.events,
-                        true,
-                    )))),
-                    Err(_) => Ok(ExecuteTransactionResponse::EffectsCert(Box::new((
-                        FinalizedEffects::new_from_effects_cert(effects_cert.into()),
-                        response.events,
-                        false,
-                    )))),
-                }
+                .is_ok()
+                    && Self::wait_for_written_objects_with_timeout(
+                        &self.validator_state,
+                        &effects_cert,
+                        &self.metrics,
+                    )
+                    .await
+                    .is_ok();
+                Ok(ExecuteTransactionResponse::EffectsCert(Box::new((
+                    FinalizedEffects::new_from_effects_cert(effects_cert.into()),
+                    response.events,
+                    is_executed_locally,
+                ))))
             }
         }
     }
 
     /// Submits the transaction to Quorum Driver for execution.
     /// Returns an awaitable Future.
+    #[instrument(name = "tx_orchestrator_submit", level = "debug", skip_all, fields(tx_digest = ?transaction.digest()), err)]
     async fn submit(
         &self,
         transaction: VerifiedTransaction,
@@ -375,6 +384,46 @@ where
         }
     }
 
+    /// Waits until every object written by the transaction is returned at its new version, or a
+    /// later one, by the execution cache that `get_object` and `get_object_read` read from.
+    /// Effects are reported executed as soon as they are committed, and the transaction may have
+    /// been executed by another task altogether, so local execution is only confirmed to the
+    /// client once the writes are observed through the same path its reads will take.
+    async fn wait_for_written_objects_with_timeout(
+        validator_state: &Arc<AuthorityState>,
+        effects_cert: &VerifiedCertifiedTransactionEffects,
+        metrics: &TransactionOrchestratorMetrics,
+    ) -> MgoResult {
+        let cache_reader = validator_state.get_cache_reader();
+        let written = effects_cert.data().all_changed_objects();
+        let wait_for_writes = async {
+            for ((object_id, version, _), _, _) in written {
+                // A tombstone at a later version means a later transaction deleted the object,
+                // which implies this one's write is visible too.
+                while !cache_reader
+                    .get_latest_object_ref_or_tombstone(object_id)?
+                    .is_some_and(|(_, latest, _)| latest >= version)
+                {
+                    tokio::time::sleep(WRITE_VISIBILITY_POLL_INTERVAL).await;
+                }
+            }
+            Ok::<_, MgoError>(())
+        };
+        match timeout(WRITE_VISIBILITY_TIMEOUT, wait_for_writes).await {
+            Ok(result) => result,
+            Err(_elapsed) => {
+                let tx_digest = effects_cert.data().transaction_digest();
+                debug!(
+                    ?tx_digest,
+                    "Written objects not visible within {:?} of local execution.",
+                    WRITE_VISIBILITY_TIMEOUT
+                );
+                metrics.local_execution_write_visibility_timeout.inc();
+                Err(MgoError::TimeoutError)
+            }
+        }
+    }
+
     async fn loop_execute_finalized_tx_locally(
         validator_state: Arc<AuthorityState>,
         mut effects_receiver: Receiver<QuorumDriverEffectsQueueResult>,
@@ -551,6 +600,7 @@ pub struct TransactionOrchestratorMetrics {
     local_execution_success: GenericCounter<AtomicU64>,
     local_execution_timeout: GenericCounter<AtomicU64>,
     local_execution_failure: GenericCounter<AtomicU64>,
+    local_execution_write_visibility_timeout: GenericCounter<AtomicU64>,
 
     request_latency_single_writer: Histogram,
     request_latency_shared_obj: Histogram,
@@ -670,6 +720,12 @@ impl TransactionOrchestratorMetrics {
                 registry,
             )
             .unwrap(),
+            local_execution_write_visibility_timeout: register_int_counter_with_registry!(
+                "tx_orchestrator_local_execution_write_visibility_timeout",
+                "Total number of locally executed txns whose written objects did not become readable in time",
+                registry,
+            )
+            .unwrap(),
             request_latency_single_writer: request_latency
                 .with_label_values(&[TX_TYPE_SINGLE_WRITER_TX]),
             request_latency_shared_obj: request_latency.with_label_values(&[TX_TYPE_SHARED_OBJ_TX]),
@@ -23,6 +23,7 @@ use rand::{
 };
 use serde_json::json;
 use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::fs;
 use std::{convert::TryInto, env};
 
@@ -2735,6 +2736,78 @@ async fn test_get_latest_parent_entry_genesis() {
         .is_none());
 }
 
+#[tokio::test]
+async fn test_multi_get_past_object_reads() {
+    let authority_state = TestAuthorityBuilder::new().build().await;
+    let owner = MgoAddress::random_for_testing_only();
+    let object_id = ObjectID::random();
+    let (pruned, missing, latest) = (
+        SequenceNumber::from_u64(3),
+        SequenceNumber::from_u64(4),
+        SequenceNumber::from_u64(5),
+    );
+    for version in [pruned, latest] {
+        authority_state
+            .insert_genesis_object(Object::with_id_owner_version_for_testing(
+                object_id, version, owner,
+            ))
+            .await;
+    }
+    authority_state
+        .database
+        .perpetual_tables
+        .objects
+        .remove(&ObjectKey(object_id, pruned))
+        .unwrap();
+    let never_existed = ObjectID::random();
+    let too_high = SequenceNumber::from_u64(6);
+
+    let reads = authority_state
+        .multi_get_past_object_reads(&[
+            (object_id, pruned),
+            (never_existed, latest),
+            (object_id, latest),
+            (object_id, too_high),
+            (object_id, missing),
+        ])
+        .unwrap();
+    assert_eq!(reads.len(), 5);
+    assert!(matches!(
+        reads[0],
+        PastObjectRead::VersionNotFound(id, version) if id == object_id && version == pruned
+    ));
+    assert!(matches!(
+        reads[1],
+        PastObjectRead::ObjectNotExists(id) if id == never_existed
+    ));
+    assert!(matches!(
+        &reads[2],
+        PastObjectRead::VersionFound(obj_ref, object, _)
+            if obj_ref.0 == object_id && obj_ref.1 == latest && object.version() == latest
+    ));
+    assert!(matches!(
+        reads[3],
+        PastObjectRead::VersionTooHigh { asked_version, latest_version, .. }
+            if asked_version == too_high && latest_version == latest
+    ));
+    assert!(matches!(
+        reads[4],
+        PastObjectRead::VersionNotFound(id, version) if id == object_id && version == missing
+    ));
+
+    // Each read matches the one made on its own.
+    for (read, (id, version)) in reads.iter().zip([
+        (object_id, pruned),
+        (never_existed, latest),
+        (object_id, latest),
+        (object_id, too_high),
+        (object_id, missing),
+    ]) {
+        let single = authority_state.get_past_object_read(&id, version).unwrap();
+        assert_eq!(format!("{read:?}"), format!("{single:?}"));
+    }
+}
+
 #[tokio::test]
 async fn test_get_latest_parent_entry() {
     let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
@@ -5673,3 +5746,132 @@ async fn test_publish_not_a_package_dependency() {
         failure,
     )
 }
+
+/// Serves the contents of a single checkpoint, counting how they and the checkpoints of single
+/// transactions are read.
+struct CountingCheckpointStore {
+    sequence_number: CheckpointSequenceNumber,
+    contents: CheckpointContents,
+    contents_reads: AtomicUsize,
+    transaction_checkpoint_reads: AtomicUsize,
+}
+
+#[async_trait]
+impl TransactionKeyValueStoreTrait for CountingCheckpointStore {
+    async fn multi_get(
+        &self,
+        _transactions: &[TransactionDigest],
+        _effects: &[TransactionDigest],
+        _events: &[TransactionEventsDigest],
+    ) -> MgoResult<mgo_storage::key_value_store::KVStoreTransactionData> {
+        unreachable!("No transaction should be read");
+    }
+
+    async fn multi_get_checkpoints(
+        &self,
+        checkpoint_summaries: &[CheckpointSequenceNumber],
+        checkpoint_contents: &[CheckpointSequenceNumber],
+        checkpoint_summaries_by_digest: &[CheckpointDigest],
+        checkpoint_contents_by_digest: &[CheckpointContentsDigest],
+    ) -> MgoResult<mgo_storage::key_value_store::KVStoreCheckpointData> {
+        self.contents_reads.fetch_add(1, Ordering::Relaxed);
+        let contents = checkpoint_contents
+            .iter()
+            .map(|seq| (*seq == self.sequence_number).then(|| self.contents.clone()))
+            .collect();
+        Ok((
+            vec![None; checkpoint_summaries.len()],
+            contents,
+            vec![None; checkpoint_summaries_by_digest.len()],
+            vec![None; checkpoint_contents_by_digest.len()],
+        ))
+    }
+
+    async fn deprecated_get_transaction_checkpoint(
+        &self,
+        _digest: TransactionDigest,
+    ) -> MgoResult<Option<CheckpointSequenceNumber>> {
+        self.transaction_checkpoint_reads
+            .fetch_add(1, Ordering::Relaxed);
+        Ok(Some(self.sequence_number))
+    }
+
+    async fn get_object(
+        &self,
+        _object_id: ObjectID,
+        _version: SequenceNumber,
+    ) -> MgoResult<Option<Object>> {
+        unreachable!("No object should be read");
+    }
+
+    async fn multi_get_transaction_checkpoint(
+        &self,
+        digests: &[TransactionDigest],
+    ) -> MgoResult<Vec<Option<CheckpointSequenceNumber>>> {
+        self.transaction_checkpoint_reads
+            .fetch_add(digests.len(), Ordering::Relaxed);
+        Ok(vec![Some(self.sequence_number); digests.len()])
+    }
+}
+
+#[tokio::test]
+async fn test_get_transactions_of_checkpoint_from_contents() {
+    // Without indexes, the transactions of a checkpoint can only be read from its contents.
+    let authority_state = TestAuthorityBuilder::new().disable_indexer().build().await;
+    let digests: Vec<_> = (0..200).map(|_| ExecutionDigests::random()).collect();
+    let order: Vec<_> = digests.iter().map(|d| d.transaction).collect();
+    let store = Arc::new(CountingCheckpointStore {
+        sequence_number: 7,
+        contents: CheckpointContents::new_with_digests_only_for_tests(digests),
+        contents_reads: Default::default(),
+        transaction_checkpoint_reads: Default::default(),
+    });
+    let kv_store = Arc::new(TransactionKeyValueStore::new(
+        "rocksdb",
+        KeyValueStoreMetrics::new_for_tests(),
+        store.clone(),
+    ));
+    let filter = Some(TransactionFilter::Checkpoint(7));
+
+    let mut calls = 0;
+    for reverse in [false, true] {
+        let mut expected = order.clone();
+        if reverse {
+            expected.reverse();
+        }
+        for limit in [Some(1), Some(7), Some(64), Some(200), None] {
+            let mut pages = vec![];
+            let mut cursor = None;
+            loop {
+                let page = authority_state
+                    .get_transactions(&kv_store, filter.clone(), cursor, limit, reverse)
+                    .await
+                    .unwrap();
+                calls += 1;
+                // Each page starts right after the position of the cursor in the checkpoint.
+                let start =
+                    cursor.map_or(0, |c| expected.iter().position(|d| *d == c).unwrap() + 1);
+                let end = (start + limit.unwrap_or(expected.len())).min(expected.len());
+                assert_eq!(page, expected[start..end], "limit {limit:?}, reverse {reverse}");
+                let Some(last) = page.last() else {
+                    break;
+                };
+                cursor = Some(*last);
+                pages.extend(page);
+            }
+            assert_eq!(pages, expected, "limit {limit:?}, reverse {reverse}");
+        }
+    }
+    assert_eq!(store.contents_reads.load(Ordering::Relaxed), calls);
+    // No transaction was looked up in the index of the checkpoints of transactions.
+    let transaction_reads = store.transaction_checkpoint_reads.load(Ordering::Relaxed);
+    assert_eq!(transaction_reads, 0);
+
+    // Cursors must be transactions of the checkpoint.
+    let outside = TransactionDigest::random();
+    let error = authority_state
+        .get_transactions(&kv_store, filter, Some(outside), None, false)
+        .await
+        .unwrap_err();
+    assert_eq!(error, MgoError::TransactionNotFound { digest: outside });
+}
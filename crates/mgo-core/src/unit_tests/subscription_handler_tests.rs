@@ -15,10 +15,21 @@ use serde::Serialize;
 use serde_json::json;
 use mgo_json_rpc_types::MgoMoveStruct;
 
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use prometheus::Registry;
+
 use mgo_types::base_types::ObjectID;
+use mgo_types::gas::GasCostSummary;
 use mgo_types::gas_coin::GasCoin;
+use mgo_types::messages_checkpoint::{
+    CheckpointContents, CheckpointSequenceNumber, CheckpointSummary,
+};
 use mgo_types::{MOVE_STDLIB_ADDRESS, MGO_FRAMEWORK_ADDRESS};
 
+use super::{LatestCheckpoint, SubscriptionHandler};
+
 #[test]
 fn test_to_json_value() {
     let move_event = TestEvent {
@@ -137,3 +148,79 @@ impl UTF8String {
         }
     }
 }
+
+fn checkpoint_summary(sequence_number: CheckpointSequenceNumber) -> CheckpointSummary {
+    CheckpointSummary::new(
+        0,
+        sequence_number,
+        0,
+        &CheckpointContents::new_with_digests_only_for_tests([]),
+        None,
+        GasCostSummary::default(),
+        None,
+        0,
+    )
+}
+
+/// Checkpoint `n` brings the total to `3n + 1` indexed transactions, so that a total read along
+/// with the sequence number of another checkpoint can be told apart.
+fn total_transaction_blocks(sequence_number: CheckpointSequenceNumber) -> Option<u64> {
+    Some(3 * sequence_number + 1)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_latest_checkpoint_is_monotonic_and_never_torn() {
+    const CHECKPOINTS: u64 = 2_000;
+    let handler = Arc::new(SubscriptionHandler::new(&Registry::new()));
+    assert_eq!(handler.latest_checkpoint(), None);
+
+    let done = Arc::new(AtomicBool::new(false));
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let handler = handler.clone();
+            let done = done.clone();
+            std::thread::spawn(move || {
+                let mut last: Option<LatestCheckpoint> = None;
+                while !done.load(Ordering::Relaxed) || last.is_none() {
+                    let Some(latest) = handler.latest_checkpoint() else {
+                        continue;
+                    };
+                    assert_eq!(
+                        latest.total_transaction_blocks,
+                        total_transaction_blocks(latest.sequence_number)
+                    );
+                    if let Some(last) = last {
+                        assert!(latest.sequence_number >= last.sequence_number);
+                        assert!(latest.total_transaction_blocks >= last.total_transaction_blocks);
+                    }
+                    last = Some(latest);
+                }
+            })
+        })
+        .collect();
+
+    for sequence_number in 0..CHECKPOINTS {
+        handler.process_checkpoint(
+            &checkpoint_summary(sequence_number),
+            3,
+            total_transaction_blocks(sequence_number),
+        );
+    }
+    // A checkpoint read from the store while later ones were executed is not made the latest.
+    handler.update_latest_checkpoint(LatestCheckpoint::new(
+        &checkpoint_summary(5),
+        total_transaction_blocks(5),
+    ));
+    done.store(true, Ordering::Relaxed);
+    for reader in readers {
+        reader.join().unwrap();
+    }
+
+    assert_eq!(
+        handler.latest_checkpoint(),
+        Some(LatestCheckpoint::new(
+            &checkpoint_summary(CHECKPOINTS - 1),
+            total_transaction_blocks(CHECKPOINTS - 1)
+        ))
+    );
+}
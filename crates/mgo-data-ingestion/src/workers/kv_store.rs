@@ -12,10 +12,10 @@ use aws_sdk_s3::config::{Credentials, Region};
 use backoff::backoff::Backoff;
 use backoff::ExponentialBackoff;
 use serde::{Deserialize, Serialize};
-use std::borrow::Borrow;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::iter::repeat;
 use mgo_storage::http_key_value_store::TaggedKey;
+use mgo_storage::kv_payload::{encode_kv_payload, KVPayload};
 use mgo_types::full_checkpoint_content::CheckpointData;
 use mgo_types::storage::ObjectKey;
 
@@ -70,7 +70,7 @@ impl KVStoreWorker {
         }
     }
 
-    async fn multi_set<V: Serialize>(
+    async fn multi_set<V: KVPayload>(
         &self,
         table: KVTable,
         values: impl IntoIterator<Item = (Vec<u8>, V)> + std::marker::Send,
@@ -89,7 +89,7 @@ impl KVStoreWorker {
                         .item("type", AttributeValue::S(Self::type_name(table)))
                         .item(
                             "bcs",
-                            AttributeValue::B(Blob::new(bcs::to_bytes(value.borrow())?)),
+                            AttributeValue::B(Blob::new(encode_kv_payload(&value)?)),
                         )
                         .build(),
                 ))
@@ -127,12 +127,12 @@ impl KVStoreWorker {
         Ok(())
     }
 
-    async fn upload_blob<V: Serialize + std::marker::Send>(
+    async fn upload_blob<V: KVPayload + std::marker::Send>(
         &self,
         key: Vec<u8>,
         value: V,
     ) -> anyhow::Result<()> {
-        let body = bcs::to_bytes(value.borrow())?.into();
+        let body = encode_kv_payload(&value)?.into();
         self.s3_client
             .put_object()
             .bucket(self.bucket_name.clone())
@@ -0,0 +1,70 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Counts the Move calls per package of the first checkpoints served by a fullnode, with a
+//! handler reading checkpoints through the public pipeline API only.
+//!
+//! Usage: cargo run --example move_call_counter -- <rest_api_url> <end_checkpoint>
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use mgo_indexer::framework::pipeline::{fetcher_factory, CheckpointData, Handler, Pipeline};
+use mgo_indexer::metrics::CheckpointFetcherMetrics;
+use mgo_types::base_types::ObjectID;
+use mgo_types::transaction::TransactionDataAPI;
+use prometheus::Registry;
+
+struct MoveCallCounter {
+    calls: Arc<Mutex<BTreeMap<ObjectID, usize>>>,
+}
+
+#[async_trait::async_trait]
+impl Handler for MoveCallCounter {
+    fn name(&self) -> &str {
+        "move_call_counter"
+    }
+
+    async fn process_checkpoint(&mut self, checkpoint: &CheckpointData) -> anyhow::Result<()> {
+        let mut calls = self.calls.lock().unwrap();
+        for tx in checkpoint.transactions() {
+            for (package, _, _) in tx.transaction().transaction_data().move_calls() {
+                *calls.entry(*package).or_default() += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let rest_api_url = args
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("missing <rest_api_url> argument"))?;
+    let end_checkpoint = args
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("missing <end_checkpoint> argument"))?
+        .parse()?;
+
+    let registry = Registry::default();
+    mango_metrics::init_metrics(&registry);
+    let calls = Arc::new(Mutex::new(BTreeMap::new()));
+    let pipeline = Pipeline::new()
+        .register_handler(MoveCallCounter {
+            calls: calls.clone(),
+        })
+        .end_checkpoint(end_checkpoint);
+    pipeline
+        .run(fetcher_factory(
+            mgo_rest_api::Client::new(rest_api_url),
+            CheckpointFetcherMetrics::new(&registry),
+        ))
+        .await;
+
+    println!("Move calls up to checkpoint {end_checkpoint}:");
+    for (package, count) in calls.lock().unwrap().iter() {
+        println!("  {package}: {count}");
+    }
+    Ok(())
+}
@@ -0,0 +1,45 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Queries an indexer database in-process, without running the indexer RPC server.
+//!
+//! Usage: cargo run --example read_only_indexer -- <db_url> [address]
+
+use mgo_indexer::{MgoObjectResponseQuery, MgoTransactionBlockResponseQuery, ReadOnlyIndexer};
+use mgo_types::base_types::MgoAddress;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let db_url = args
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("missing <db_url> argument"))?;
+    let indexer = ReadOnlyIndexer::connect(db_url).await?;
+
+    let latest = indexer
+        .query_transaction_blocks(
+            MgoTransactionBlockResponseQuery::default(),
+            None,
+            Some(10),
+            Some(true),
+        )
+        .await?;
+    println!("Latest transactions:");
+    for tx in latest.data {
+        println!("  {}", tx.digest);
+    }
+
+    if let Some(address) = args.next() {
+        let address: MgoAddress = address.parse()?;
+        let objects = indexer
+            .get_owned_objects(address, Some(MgoObjectResponseQuery::default()), None, None)
+            .await?;
+        println!("Objects owned by {address}:");
+        for object in objects.data {
+            if let Some(data) = object.data {
+                println!("  {} v{}", data.object_id, data.version);
+            }
+        }
+    }
+    Ok(())
+}
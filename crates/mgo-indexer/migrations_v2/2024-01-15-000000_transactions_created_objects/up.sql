@@ -0,0 +1,4 @@
+-- array of bcs serialized CreatedObjectSummary bytes, recorded at ingestion so that
+-- `showCreatedSummary` is served without fetching objects. NULL for transactions indexed
+-- before this column was added.
+ALTER TABLE transactions ADD COLUMN created_objects bytea[];
@@ -0,0 +1,3 @@
+-- serves mgox_getOwnedObjectsByCheckpoint, which pages an address's objects by
+-- (checkpoint_sequence_number, object_id) in either direction.
+CREATE INDEX objects_owner_checkpoint ON objects (owner_id, checkpoint_sequence_number, object_id) WHERE owner_type = 1;
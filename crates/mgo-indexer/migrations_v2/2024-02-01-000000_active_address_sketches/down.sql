@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS active_address_sketches;
+DROP TABLE IF EXISTS address_cohorts;
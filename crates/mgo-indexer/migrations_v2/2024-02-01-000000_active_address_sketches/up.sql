@@ -0,0 +1,18 @@
+-- first UTC day, counted from the unix epoch, each address sent a transaction on
+CREATE TABLE address_cohorts
+(
+    address                 BYTEA   PRIMARY KEY,
+    cohort_day              BIGINT  NOT NULL
+);
+CREATE INDEX address_cohorts_cohort_day ON address_cohorts (cohort_day);
+
+-- serialized distinct-sender sketch of the addresses of a cohort active on a day
+CREATE TABLE active_address_sketches
+(
+    day                     BIGINT  NOT NULL,
+    cohort_day              BIGINT  NOT NULL,
+    sketch                  BYTEA   NOT NULL,
+    last_processed_tx       BIGINT  NOT NULL,
+    PRIMARY KEY (day, cohort_day)
+);
+CREATE INDEX active_address_sketches_cohort_day ON active_address_sketches (cohort_day, day);
@@ -0,0 +1,9 @@
+-- dynamic field children a transaction loaded at runtime, with the versions it loaded
+CREATE TABLE tx_loaded_child_objects (
+    -- Transaction digest in bytes.
+    transaction_digest          BYTEA        NOT NULL,
+    -- Object Id in bytes.
+    object_id                   BYTEA        NOT NULL,
+    object_version              BIGINT       NOT NULL,
+    PRIMARY KEY(transaction_digest, object_id)
+);
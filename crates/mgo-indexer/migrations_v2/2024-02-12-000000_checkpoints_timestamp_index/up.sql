@@ -0,0 +1,3 @@
+-- serves event queries with a TimeRange filter, which are turned into a range of
+-- checkpoints by timestamp before reading the events table.
+CREATE INDEX IF NOT EXISTS checkpoints_timestamp_ms ON checkpoints (timestamp_ms, sequence_number);
@@ -0,0 +1,8 @@
+-- all data of checkpoints before `checkpoint_hi_exclusive`, and of transactions before
+-- `tx_hi_exclusive`, has been pruned or is being pruned.
+CREATE TABLE pruner_watermark (
+    -- always true, so that the table holds a single row.
+    id                          BOOLEAN      PRIMARY KEY DEFAULT TRUE CHECK (id),
+    checkpoint_hi_exclusive     BIGINT       NOT NULL,
+    tx_hi_exclusive             BIGINT       NOT NULL
+);
@@ -0,0 +1,14 @@
+-- every coin type seen through one of its coins, its treasury cap or its metadata
+CREATE TABLE coin_types (
+    -- e.g. `0x2::mgo::MGO`
+    coin_type                       TEXT         PRIMARY KEY,
+    first_seen_checkpoint           BIGINT       NOT NULL,
+    -- Non-null once the CoinMetadata object of the coin type has been seen.
+    metadata_object_id              BYTEA,
+    -- number of addresses owning at least one coin of the type with a positive balance
+    holder_count                    BIGINT       NOT NULL,
+    -- last checkpoint whose changes of holders are counted in `holder_count`
+    holders_checkpoint              BIGINT       NOT NULL
+);
+CREATE INDEX coin_types_first_seen ON coin_types (first_seen_checkpoint, coin_type);
+CREATE INDEX coin_types_holders ON coin_types (holder_count DESC, coin_type);
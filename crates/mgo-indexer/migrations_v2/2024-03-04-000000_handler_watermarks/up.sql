@@ -0,0 +1,6 @@
+-- Last checkpoint processed by each handler that keeps its own watermark, such as one-off
+-- backfills run next to regular ingestion.
+CREATE TABLE handler_watermarks (
+    handler_name                TEXT         PRIMARY KEY,
+    checkpoint_sequence_number  BIGINT       NOT NULL
+);
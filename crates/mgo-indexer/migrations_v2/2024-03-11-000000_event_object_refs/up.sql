@@ -0,0 +1,10 @@
+-- Objects referenced by an `ID` or `UID` field of events of the types the indexer is
+-- configured to index object references of.
+CREATE TABLE event_object_refs (
+    -- Object Id in bytes.
+    object_id                   BYTEA        NOT NULL,
+    tx_sequence_number          BIGINT       NOT NULL,
+    event_sequence_number       BIGINT       NOT NULL,
+    event_type                  TEXT         NOT NULL,
+    PRIMARY KEY(object_id, tx_sequence_number, event_sequence_number)
+);
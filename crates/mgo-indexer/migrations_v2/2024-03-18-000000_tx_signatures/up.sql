@@ -0,0 +1,16 @@
+-- Signatures of transactions, one row per signature and one per participant of a multisig,
+-- so that transactions can be filtered by the schemes their signers used.
+CREATE TABLE tx_signatures (
+    tx_sequence_number          BIGINT       NOT NULL,
+    -- Position of the signature among the transaction's signatures.
+    signature_index             SMALLINT     NOT NULL,
+    -- 0 for the signature itself, i + 1 for the i-th participant of a multisig.
+    participant_index           SMALLINT     NOT NULL,
+    -- Flag of the signature scheme.
+    scheme                      SMALLINT     NOT NULL,
+    public_key                  BYTEA,
+    signature                   BYTEA        NOT NULL,
+    PRIMARY KEY(tx_sequence_number, signature_index, participant_index)
+);
+
+CREATE INDEX tx_signatures_scheme ON tx_signatures (scheme, tx_sequence_number);
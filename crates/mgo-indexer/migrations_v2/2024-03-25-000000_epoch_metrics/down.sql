@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS epoch_metrics_addresses;
+DROP TABLE IF EXISTS epoch_metrics_senders;
+DROP TABLE IF EXISTS epoch_metrics;
@@ -0,0 +1,29 @@
+-- Metrics of every epoch, rolled up checkpoint by checkpoint as they are indexed.
+CREATE TABLE epoch_metrics (
+    epoch                       BIGINT       PRIMARY KEY,
+    first_checkpoint            BIGINT       NOT NULL,
+    -- Last checkpoint rolled up, checkpoints up to it are skipped when indexed again.
+    last_checkpoint             BIGINT       NOT NULL,
+    total_transactions          BIGINT       NOT NULL,
+    total_gas_used              BIGINT       NOT NULL,
+    total_storage_rebate        BIGINT       NOT NULL,
+    -- Distinct senders of user transactions.
+    active_senders              BIGINT       NOT NULL,
+    -- Senders or recipients of transactions seen for the first time.
+    new_addresses               BIGINT       NOT NULL,
+    -- Whether the last checkpoint of the epoch has been rolled up.
+    finalized                   BOOLEAN      NOT NULL
+);
+
+-- Senders of the epochs not finalized yet, so that each is counted once.
+CREATE TABLE epoch_metrics_senders (
+    epoch                       BIGINT       NOT NULL,
+    address                     BYTEA        NOT NULL,
+    PRIMARY KEY(epoch, address)
+);
+
+-- Every sender or recipient seen, with the epoch it was first seen in.
+CREATE TABLE epoch_metrics_addresses (
+    address                     BYTEA        PRIMARY KEY,
+    first_epoch                 BIGINT       NOT NULL
+);
@@ -0,0 +1,13 @@
+-- Row estimates and on-disk sizes of the tables of the indexer, sampled periodically by the
+-- writer and kept for a week, so that their growth can be reported across restarts.
+CREATE TABLE storage_stats (
+    table_name                  TEXT         NOT NULL,
+    -- Milliseconds since the unix epoch.
+    sampled_at_ms               BIGINT       NOT NULL,
+    -- Estimated by the planner statistics of the table, which lag behind its actual size.
+    row_estimate                BIGINT       NOT NULL,
+    -- Bytes of the table, its indexes and TOAST data.
+    total_bytes                 BIGINT       NOT NULL,
+    PRIMARY KEY(table_name, sampled_at_ms)
+);
+CREATE INDEX storage_stats_sampled_at_ms ON storage_stats (sampled_at_ms);
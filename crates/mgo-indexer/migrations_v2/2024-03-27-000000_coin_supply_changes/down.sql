@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS coin_supply_offsets;
+DROP TABLE IF EXISTS coin_supply_changes;
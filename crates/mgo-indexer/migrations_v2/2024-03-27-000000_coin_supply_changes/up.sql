@@ -0,0 +1,25 @@
+-- Amounts of every coin type minted and burned through its treasury cap, by checkpoint.
+CREATE TABLE coin_supply_changes (
+    -- e.g. `0x2::mgo::MGO`
+    coin_type                       TEXT         NOT NULL,
+    checkpoint_sequence_number      BIGINT       NOT NULL,
+    epoch                           BIGINT       NOT NULL,
+    timestamp_ms                    BIGINT       NOT NULL,
+    minted                          BIGINT       NOT NULL,
+    burned                          BIGINT       NOT NULL,
+    PRIMARY KEY(coin_type, checkpoint_sequence_number)
+);
+CREATE INDEX coin_supply_changes_epoch ON coin_supply_changes (coin_type, epoch);
+CREATE INDEX coin_supply_changes_timestamp ON coin_supply_changes (coin_type, timestamp_ms);
+
+-- Supply of every coin type before the first change of its supply indexed.
+CREATE TABLE coin_supply_offsets (
+    coin_type                       TEXT         PRIMARY KEY,
+    -- checkpoint of the first change indexed
+    checkpoint_sequence_number      BIGINT       NOT NULL,
+    initial_supply                  BIGINT       NOT NULL,
+    -- Whether the treasury cap existed before the first checkpoint indexed, so that the supply
+    -- was observed on it rather than counted from the creation of the coin type, and mints and
+    -- burns before that checkpoint are unknown.
+    approximate                     BOOLEAN      NOT NULL
+);
@@ -0,0 +1,19 @@
+-- Packages every version of every package links to, as recorded in its linkage table.
+CREATE TABLE package_deps (
+    package_id                      bytea        NOT NULL,
+    package_version                 BIGINT       NOT NULL,
+    -- ID of the first version of the package
+    original_package_id             bytea        NOT NULL,
+    -- ID of the first version of the dependency
+    dependency_original_id          bytea        NOT NULL,
+    -- ID and version of the dependency linked to
+    dependency_id                   bytea        NOT NULL,
+    dependency_version              BIGINT       NOT NULL,
+    checkpoint_sequence_number      BIGINT       NOT NULL,
+    -- Whether the edge is of the latest version of the package, unset once it is upgraded.
+    latest                          BOOLEAN      NOT NULL,
+    PRIMARY KEY(package_id, package_version, dependency_original_id)
+);
+CREATE INDEX package_deps_original ON package_deps (original_package_id, package_version);
+CREATE INDEX package_deps_dependents ON package_deps (dependency_original_id, original_package_id)
+    WHERE latest;
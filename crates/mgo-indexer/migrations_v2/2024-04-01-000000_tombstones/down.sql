@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS reconciler_watermark;
+DROP TABLE IF EXISTS tombstones;
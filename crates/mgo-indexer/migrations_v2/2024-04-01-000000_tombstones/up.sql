@@ -0,0 +1,22 @@
+-- Rows of checkpoints whose digest differs from the certified one, moved out of their table by
+-- the reconciler so that readers no longer serve them, and kept for inspection.
+CREATE TABLE tombstones (
+    id                              BIGSERIAL    PRIMARY KEY,
+    checkpoint_sequence_number      BIGINT       NOT NULL,
+    -- digest the checkpoint was ingested with
+    ingested_digest                 bytea        NOT NULL,
+    -- digest of the certified summary of the checkpoint
+    certified_digest                bytea        NOT NULL,
+    table_name                      TEXT         NOT NULL,
+    -- the row as a JSON object of its columns
+    row_data                        JSONB        NOT NULL,
+    tombstoned_at_ms                BIGINT       NOT NULL
+);
+CREATE INDEX tombstones_checkpoint ON tombstones (checkpoint_sequence_number, table_name);
+
+-- all checkpoints before `checkpoint_hi_exclusive` have been verified by the reconciler.
+CREATE TABLE reconciler_watermark (
+    -- always true, so that the table holds a single row.
+    id                          BOOLEAN      PRIMARY KEY DEFAULT TRUE CHECK (id),
+    checkpoint_hi_exclusive     BIGINT       NOT NULL
+);
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS objects_df_object_id;
+DROP TABLE IF EXISTS orphaned_dynamic_fields;
@@ -0,0 +1,19 @@
+-- Dynamic object fields whose value object is no longer live, found by the scanner of the
+-- parents changed since its last scan.
+CREATE TABLE orphaned_dynamic_fields (
+    -- ID of the `Field` wrapper object
+    field_id                        bytea        PRIMARY KEY,
+    parent_id                       bytea        NOT NULL,
+    df_name                         bytea        NOT NULL,
+    df_object_type                  TEXT         NOT NULL,
+    -- ID of the value object the field refers to
+    value_object_id                 bytea        NOT NULL,
+    -- checkpoint up to which the scan that first found the orphan ran
+    checkpoint_sequence_number      BIGINT       NOT NULL,
+    detected_at_ms                  BIGINT       NOT NULL
+);
+CREATE INDEX orphaned_dynamic_fields_parent ON orphaned_dynamic_fields (parent_id, field_id);
+
+-- Finds the wrappers of a value object, for the scanner to map deleted or wrapped values to
+-- their parents.
+CREATE INDEX objects_df_object_id ON objects (df_object_id) WHERE df_kind = 1;
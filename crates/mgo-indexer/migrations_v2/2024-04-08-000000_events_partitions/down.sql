@@ -0,0 +1,10 @@
+CREATE TABLE events_unpartitioned (LIKE events);
+INSERT INTO events_unpartitioned SELECT * FROM events;
+DROP TABLE events;
+ALTER TABLE events_unpartitioned RENAME TO events;
+
+ALTER TABLE events ADD CONSTRAINT events_pkey PRIMARY KEY (tx_sequence_number, event_sequence_number);
+CREATE INDEX events_package ON events (package, tx_sequence_number, event_sequence_number);
+CREATE INDEX events_package_module ON events (package, module, tx_sequence_number, event_sequence_number);
+CREATE INDEX events_event_type ON events (event_type text_pattern_ops, tx_sequence_number, event_sequence_number);
+CREATE INDEX events_checkpoint_sequence_number ON events (checkpoint_sequence_number);
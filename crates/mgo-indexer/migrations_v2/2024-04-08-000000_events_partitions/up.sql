@@ -0,0 +1,35 @@
+-- Partitions events by checkpoint like transactions and objects_history, so that the indexer
+-- creates the partition of each epoch as it enters it, and the pruner drops partitions whole.
+CREATE TABLE events_partitioned (LIKE events) PARTITION BY RANGE (checkpoint_sequence_number);
+
+-- Databases already past the first epoch get a partition for the current epoch, bounded like
+-- that of transactions, so that the indexer keeps advancing the partitions of both together.
+-- All earlier events go to the first partition.
+DO $$
+DECLARE
+    current_epoch BIGINT;
+    current_epoch_start_cp BIGINT;
+BEGIN
+    SELECT MAX(CAST(SUBSTRING(child.relname FROM '\d+$') AS BIGINT)) INTO current_epoch
+    FROM pg_inherits JOIN pg_class child ON pg_inherits.inhrelid = child.oid
+    WHERE pg_inherits.inhparent = 'transactions'::regclass;
+    IF current_epoch > 0 THEN
+        SELECT first_checkpoint_id INTO current_epoch_start_cp FROM epochs WHERE epoch = current_epoch;
+        EXECUTE format('CREATE TABLE events_partition_0 PARTITION OF events_partitioned FOR VALUES FROM (0) TO (%L)', current_epoch_start_cp);
+        EXECUTE format('CREATE TABLE events_partition_%s PARTITION OF events_partitioned FOR VALUES FROM (%L) TO (MAXVALUE)', current_epoch, current_epoch_start_cp);
+    ELSE
+        CREATE TABLE events_partition_0 PARTITION OF events_partitioned FOR VALUES FROM (0) TO (MAXVALUE);
+    END IF;
+END;
+$$;
+
+INSERT INTO events_partitioned SELECT * FROM events;
+DROP TABLE events;
+ALTER TABLE events_partitioned RENAME TO events;
+
+-- The key of a partitioned table includes its partition key.
+ALTER TABLE events ADD CONSTRAINT events_pkey PRIMARY KEY (tx_sequence_number, event_sequence_number, checkpoint_sequence_number);
+CREATE INDEX events_package ON events (package, tx_sequence_number, event_sequence_number);
+CREATE INDEX events_package_module ON events (package, module, tx_sequence_number, event_sequence_number);
+CREATE INDEX events_event_type ON events (event_type text_pattern_ops, tx_sequence_number, event_sequence_number);
+CREATE INDEX events_checkpoint_sequence_number ON events (checkpoint_sequence_number);
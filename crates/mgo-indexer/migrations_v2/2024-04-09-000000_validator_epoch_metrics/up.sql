@@ -0,0 +1,22 @@
+-- Performance of every validator of the committee of an epoch, rolled up checkpoint by
+-- checkpoint as they are indexed. Rows are created with the committee, from the checkpoint
+-- that sets it: the genesis checkpoint or the last checkpoint of the previous epoch.
+CREATE TABLE validator_epoch_metrics (
+    epoch                       BIGINT       NOT NULL,
+    validator_address           BYTEA        NOT NULL,
+    -- Index of the validator in the committee, as in the signer bitmaps of certificates.
+    committee_index             INT          NOT NULL,
+    voting_power                BIGINT       NOT NULL,
+    -- Last checkpoint rolled up, checkpoints up to it are skipped when indexed again.
+    last_checkpoint             BIGINT       NOT NULL,
+    checkpoints                 BIGINT       NOT NULL,
+    -- Checkpoints whose certificate the validator signed.
+    checkpoints_signed          BIGINT       NOT NULL,
+    -- Validators that reported the validator, and its tallying rule score, as of the end of
+    -- the epoch. NULL until the last checkpoint of the epoch has been rolled up.
+    reporters                   BIGINT,
+    tallying_score              BIGINT,
+    PRIMARY KEY(epoch, validator_address)
+);
+CREATE INDEX validator_epoch_metrics_validator
+    ON validator_epoch_metrics (validator_address, epoch);
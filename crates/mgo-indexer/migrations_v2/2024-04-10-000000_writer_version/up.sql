@@ -0,0 +1,7 @@
+-- Version of the data written by the writer, which readers compare with the version each of
+-- their endpoints needs before querying the tables or columns added in it.
+CREATE TABLE writer_version (
+    -- always true, so that the table holds a single row.
+    id                          BOOLEAN      PRIMARY KEY DEFAULT TRUE CHECK (id),
+    version                     BIGINT       NOT NULL
+);
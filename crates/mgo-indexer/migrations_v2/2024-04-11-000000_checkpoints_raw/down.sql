@@ -0,0 +1,2 @@
+ALTER TABLE checkpoints DROP COLUMN IF EXISTS raw_checkpoint_contents;
+ALTER TABLE checkpoints DROP COLUMN IF EXISTS raw_checkpoint_summary;
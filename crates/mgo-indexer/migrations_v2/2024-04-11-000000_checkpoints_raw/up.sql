@@ -0,0 +1,5 @@
+-- bcs serialized CertifiedCheckpointSummary and CheckpointContents bytes, recorded at ingestion
+-- so that `getCheckpoint` can return the bcs encoding of checkpoints. NULL for checkpoints
+-- indexed before these columns were added.
+ALTER TABLE checkpoints ADD COLUMN raw_checkpoint_summary bytea;
+ALTER TABLE checkpoints ADD COLUMN raw_checkpoint_contents bytea;
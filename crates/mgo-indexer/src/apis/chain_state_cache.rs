@@ -0,0 +1,293 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Keeps the [`ChainState`] served by `mgox_getChainState` up to date as checkpoints are
+//! committed, so that the method never reads the store.
+//!
+//! A worker refreshes the snapshot on every [`CommitNotice`]. A snapshot only moves to a
+//! checkpoint once the reference gas price and protocol version of its epoch are known: during
+//! an epoch change the previous snapshot is served until the new epoch is indexed, so that the
+//! fields always come from the same epoch.
+
+use std::sync::{Arc, OnceLock};
+
+use arc_swap::ArcSwapOption;
+use async_trait::async_trait;
+use mango_metrics::spawn_monitored_task;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, warn};
+
+use mgo_json_rpc_types::{ChainState, CheckpointId};
+use mgo_types::committee::EpochId;
+use mgo_types::digests::ChainIdentifier;
+use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
+
+use crate::errors::IndexerError;
+use crate::indexer_reader::IndexerReader;
+use crate::notifications::{CommitNotice, CommitNoticeListener};
+
+/// The latest checkpoint known to a [`ChainStateSource`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct LatestCheckpoint {
+    pub sequence_number: CheckpointSequenceNumber,
+    pub timestamp_ms: u64,
+    pub epoch: EpochId,
+}
+
+/// What clients need to know about an epoch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct EpochParameters {
+    pub reference_gas_price: u64,
+    pub protocol_version: u64,
+}
+
+/// Where a [`ChainStateCache`] is refreshed from.
+#[async_trait]
+pub(crate) trait ChainStateSource: Send + Sync + 'static {
+    async fn chain_identifier(&self) -> Result<ChainIdentifier, IndexerError>;
+
+    async fn latest_checkpoint(&self) -> Result<LatestCheckpoint, IndexerError>;
+
+    /// `None` while the epoch is not indexed yet.
+    async fn epoch_parameters(
+        &self,
+        epoch: EpochId,
+    ) -> Result<Option<EpochParameters>, IndexerError>;
+}
+
+#[async_trait]
+impl ChainStateSource for IndexerReader {
+    async fn chain_identifier(&self) -> Result<ChainIdentifier, IndexerError> {
+        let genesis = self
+            .spawn_blocking(|this| this.get_checkpoint(CheckpointId::SequenceNumber(0)))
+            .await?
+            .ok_or_else(|| {
+                IndexerError::PostgresReadError("Genesis checkpoint is not indexed".to_string())
+            })?;
+        Ok(ChainIdentifier::from(genesis.digest))
+    }
+
+    async fn latest_checkpoint(&self) -> Result<LatestCheckpoint, IndexerError> {
+        let checkpoint = self
+            .spawn_blocking(|this| this.get_latest_checkpoint_from_db())
+            .await?;
+        Ok(LatestCheckpoint {
+            sequence_number: checkpoint.sequence_number as u64,
+            timestamp_ms: checkpoint.timestamp_ms as u64,
+            epoch: checkpoint.epoch as u64,
+        })
+    }
+
+    async fn epoch_parameters(
+        &self,
+        epoch: EpochId,
+    ) -> Result<Option<EpochParameters>, IndexerError> {
+        let stored = self
+            .spawn_blocking(move |this| this.get_epoch_info_from_db(Some(epoch)))
+            .await?;
+        Ok(stored.map(|stored| EpochParameters {
+            reference_gas_price: stored.reference_gas_price as u64,
+            protocol_version: stored.protocol_version as u64,
+        }))
+    }
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct ChainStateCache {
+    /// Latest consistent snapshot, without the namespaces.
+    state: Arc<ArcSwapOption<ChainState>>,
+    namespaces: Arc<OnceLock<Vec<String>>>,
+}
+
+impl ChainStateCache {
+    /// Starts refreshing the cache from `source` on every notice of `listener`.
+    pub fn start<S: ChainStateSource>(source: S, listener: &CommitNoticeListener) -> Self {
+        let cache = Self::default();
+        spawn_monitored_task!(cache.clone().run(source, listener.subscribe()));
+        cache
+    }
+
+    /// Sets the namespaces served, once every module is registered.
+    pub fn set_namespaces(&self, namespaces: impl IntoIterator<Item = String>) {
+        let _ = self.namespaces.set(namespaces.into_iter().collect());
+    }
+
+    /// The latest snapshot, or `None` until the cache is first refreshed.
+    pub fn get(&self) -> Option<ChainState> {
+        let mut state = ChainState::clone(&*self.state.load_full()?);
+        state.namespaces = self.namespaces.get().cloned().unwrap_or_default();
+        Some(state)
+    }
+
+    async fn run<S: ChainStateSource>(
+        self,
+        source: S,
+        mut notices: broadcast::Receiver<CommitNotice>,
+    ) {
+        loop {
+            if let Err(e) = self.refresh(&source).await {
+                warn!("Failed to refresh the chain state: {e}");
+            }
+            // Missed notices only delay the refresh to the next one.
+            match notices.recv().await {
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => return,
+            }
+        }
+    }
+
+    /// Moves the snapshot to the latest checkpoint of `source`, unless the parameters of its
+    /// epoch are not known yet.
+    async fn refresh<S: ChainStateSource>(&self, source: &S) -> Result<(), IndexerError> {
+        let current = self.state.load_full();
+        let checkpoint = source.latest_checkpoint().await?;
+        if current
+            .as_ref()
+            .is_some_and(|state| state.checkpoint >= checkpoint.sequence_number)
+        {
+            return Ok(());
+        }
+
+        let (chain_identifier, parameters) = match current.as_deref() {
+            Some(state) if state.epoch == checkpoint.epoch => (
+                state.chain_identifier.clone(),
+                EpochParameters {
+                    reference_gas_price: state.reference_gas_price,
+                    protocol_version: state.protocol_version,
+                },
+            ),
+            _ => {
+                let Some(parameters) = source.epoch_parameters(checkpoint.epoch).await? else {
+                    debug!(
+                        epoch = checkpoint.epoch,
+                        "Serving the previous chain state until the epoch is indexed"
+                    );
+                    return Ok(());
+                };
+                let chain_identifier = match current.as_deref() {
+                    Some(state) => state.chain_identifier.clone(),
+                    None => source.chain_identifier().await?.to_string(),
+                };
+                (chain_identifier, parameters)
+            }
+        };
+
+        self.state.store(Some(Arc::new(ChainState {
+            chain_identifier,
+            checkpoint: checkpoint.sequence_number,
+            checkpoint_timestamp_ms: checkpoint.timestamp_ms,
+            epoch: checkpoint.epoch,
+            reference_gas_price: parameters.reference_gas_price,
+            protocol_version: parameters.protocol_version,
+            namespaces: vec![],
+        })));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    use mgo_types::digests::CheckpointDigest;
+
+    use super::*;
+
+    /// A source whose latest checkpoint and indexed epochs are set by the test.
+    #[derive(Default)]
+    struct MockSource {
+        latest: Mutex<Option<LatestCheckpoint>>,
+        epochs: Mutex<BTreeMap<EpochId, EpochParameters>>,
+    }
+
+    impl MockSource {
+        fn set_latest(&self, sequence_number: u64, epoch: EpochId) {
+            *self.latest.lock().unwrap() = Some(LatestCheckpoint {
+                sequence_number,
+                timestamp_ms: sequence_number * 1000,
+                epoch,
+            });
+        }
+
+        fn index_epoch(&self, epoch: EpochId, reference_gas_price: u64, protocol_version: u64) {
+            self.epochs.lock().unwrap().insert(
+                epoch,
+                EpochParameters {
+                    reference_gas_price,
+                    protocol_version,
+                },
+            );
+        }
+    }
+
+    #[async_trait]
+    impl ChainStateSource for MockSource {
+        async fn chain_identifier(&self) -> Result<ChainIdentifier, IndexerError> {
+            Ok(ChainIdentifier::from(CheckpointDigest::new([1; 32])))
+        }
+
+        async fn latest_checkpoint(&self) -> Result<LatestCheckpoint, IndexerError> {
+            self.latest
+                .lock()
+                .unwrap()
+                .ok_or_else(|| IndexerError::PostgresReadError("No checkpoint".to_string()))
+        }
+
+        async fn epoch_parameters(
+            &self,
+            epoch: EpochId,
+        ) -> Result<Option<EpochParameters>, IndexerError> {
+            Ok(self.epochs.lock().unwrap().get(&epoch).copied())
+        }
+    }
+
+    fn snapshot(state: &ChainState) -> (u64, u64, u64, u64) {
+        (
+            state.checkpoint,
+            state.epoch,
+            state.reference_gas_price,
+            state.protocol_version,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_chain_state_stays_in_one_epoch_across_epoch_change() {
+        let source = MockSource::default();
+        let cache = ChainStateCache::default();
+        cache.set_namespaces(["mgo".to_string(), "mgox".to_string()]);
+
+        // Nothing is served before the first checkpoint.
+        assert!(cache.refresh(&source).await.is_err());
+        assert!(cache.get().is_none());
+
+        source.index_epoch(0, 1000, 1);
+        source.set_latest(10, 0);
+        cache.refresh(&source).await.unwrap();
+        let state = cache.get().unwrap();
+        assert_eq!(snapshot(&state), (10, 0, 1000, 1));
+        assert_eq!(state.checkpoint_timestamp_ms, 10_000);
+        assert_eq!(
+            state.chain_identifier,
+            ChainIdentifier::from(CheckpointDigest::new([1; 32])).to_string()
+        );
+        assert_eq!(state.namespaces, vec!["mgo", "mgox"]);
+
+        // The first checkpoint of the next epoch is committed before the epoch is indexed:
+        // the last snapshot of the previous epoch is still served.
+        source.set_latest(11, 1);
+        cache.refresh(&source).await.unwrap();
+        assert_eq!(snapshot(&cache.get().unwrap()), (10, 0, 1000, 1));
+
+        source.index_epoch(1, 1200, 2);
+        cache.refresh(&source).await.unwrap();
+        assert_eq!(snapshot(&cache.get().unwrap()), (11, 1, 1200, 2));
+
+        // Within the epoch, its parameters are not read again.
+        source.epochs.lock().unwrap().clear();
+        source.set_latest(12, 1);
+        cache.refresh(&source).await.unwrap();
+        assert_eq!(snapshot(&cache.get().unwrap()), (12, 1, 1200, 2));
+    }
+}
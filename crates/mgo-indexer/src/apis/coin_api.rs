@@ -8,10 +8,11 @@ use jsonrpsee::RpcModule;
 use mgo_json_rpc::MgoRpcModule;
 use mgo_json_rpc_api::CoinReadApiClient;
 use mgo_json_rpc_api::CoinReadApiServer;
-use mgo_json_rpc_types::{Balance, CoinPage, MgoCoinMetadata};
+use mgo_json_rpc_types::{Balance, BalancePage, Coin, CoinPage, MgoCoinMetadata};
 use mgo_open_rpc::Module;
 use mgo_types::balance::Supply;
 use mgo_types::base_types::{ObjectID, MgoAddress};
+use mgo_types::mgo_serde::BigInt;
 
 pub(crate) struct CoinReadApi {
     fullnode: HttpClient,
@@ -33,9 +34,23 @@ impl CoinReadApiServer for CoinReadApi {
         coin_type: Option<String>,
         cursor: Option<ObjectID>,
         limit: Option<usize>,
+        include_totals: Option<bool>,
+        min_balance: Option<BigInt<u64>>,
     ) -> RpcResult<CoinPage> {
         self.fullnode
-            .get_coins(owner, coin_type, cursor, limit)
+            .get_coins(owner, coin_type, cursor, limit, include_totals, min_balance)
+            .await
+    }
+
+    async fn select_coins(
+        &self,
+        owner: MgoAddress,
+        coin_type: Option<String>,
+        target_amount: BigInt<u64>,
+        max_coins: Option<usize>,
+    ) -> RpcResult<Vec<Coin>> {
+        self.fullnode
+            .select_coins(owner, coin_type, target_amount, max_coins)
             .await
     }
 
@@ -44,8 +59,11 @@ impl CoinReadApiServer for CoinReadApi {
         owner: MgoAddress,
         cursor: Option<ObjectID>,
         limit: Option<usize>,
+        include_totals: Option<bool>,
     ) -> RpcResult<CoinPage> {
-        self.fullnode.get_all_coins(owner, cursor, limit).await
+        self.fullnode
+            .get_all_coins(owner, cursor, limit, include_totals)
+            .await
     }
 
     async fn get_balance(
@@ -60,6 +78,17 @@ impl CoinReadApiServer for CoinReadApi {
         self.fullnode.get_all_balances(owner).await
     }
 
+    async fn get_all_balances_paginated(
+        &self,
+        owner: MgoAddress,
+        cursor: Option<String>,
+        limit: Option<usize>,
+    ) -> RpcResult<BalancePage> {
+        self.fullnode
+            .get_all_balances_paginated(owner, cursor, limit)
+            .await
+    }
+
     async fn get_coin_metadata(&self, coin_type: String) -> RpcResult<Option<MgoCoinMetadata>> {
         self.fullnode.get_coin_metadata(coin_type).await
     }
@@ -5,22 +5,85 @@ use crate::indexer_reader::IndexerReader;
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::RpcModule;
-use mgo_json_rpc::coin_api::{parse_to_struct_tag, parse_to_type_tag};
+use mgo_json_rpc::coin_api::{parse_to_struct_tag, parse_to_type_tag, select_coins_largest_first};
+use mgo_json_rpc::coin_registry::CoinRegistry;
 use mgo_json_rpc::MgoRpcModule;
 use mgo_json_rpc_api::{cap_page_limit, CoinReadApiServer};
-use mgo_json_rpc_types::{Balance, CoinPage, Page, MgoCoinMetadata};
+use mgo_json_rpc_types::{Balance, BalancePage, Coin, CoinPage, Page, MgoCoinMetadata};
 use mgo_open_rpc::Module;
 use mgo_types::balance::Supply;
 use mgo_types::base_types::{ObjectID, MgoAddress};
 use mgo_types::gas_coin::{GAS, TOTAL_SUPPLY_MIST};
+use mgo_types::mgo_serde::BigInt;
+use tracing::warn;
 
 pub(crate) struct CoinReadApiV2 {
     inner: IndexerReader,
+    coin_registry: Option<CoinRegistry>,
 }
 
 impl CoinReadApiV2 {
     pub fn new(inner: IndexerReader) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            coin_registry: None,
+        }
+    }
+
+    /// Marks the coins, balances and coin metadata returned with their standing in
+    /// `coin_registry`.
+    pub fn with_coin_registry(mut self, coin_registry: CoinRegistry) -> Self {
+        self.coin_registry = Some(coin_registry);
+        self
+    }
+
+    async fn annotate_coin_page(&self, page: &mut CoinPage) {
+        if let Some(coin_registry) = &self.coin_registry {
+            coin_registry
+                .annotate_coins(&mut page.data, |ids| {
+                    self.inner.multi_get_objects_ordered_in_blocking_task(ids)
+                })
+                .await;
+            if let Some(totals) = &mut page.totals {
+                coin_registry
+                    .annotate_balances(totals, |ids| {
+                        self.inner.multi_get_objects_ordered_in_blocking_task(ids)
+                    })
+                    .await;
+            }
+        }
+    }
+
+    async fn annotate_balances(&self, balances: &mut [Balance]) {
+        if let Some(coin_registry) = &self.coin_registry {
+            coin_registry
+                .annotate_balances(balances, |ids| {
+                    self.inner.multi_get_objects_ordered_in_blocking_task(ids)
+                })
+                .await;
+        }
+    }
+
+    /// Sets the totals of `coin_type`, or of every coin type if `None`, owned by `owner` on
+    /// `page`, marking them unavailable instead of failing the page if they cannot be read.
+    async fn set_totals(&self, page: &mut CoinPage, owner: MgoAddress, coin_type: Option<String>) {
+        match self
+            .inner
+            .get_coin_balances_in_blocking_task(owner, coin_type.clone())
+            .await
+        {
+            Ok(mut totals) => {
+                if let (Some(coin_type), true) = (coin_type, totals.is_empty()) {
+                    totals.push(Balance::zero(coin_type));
+                }
+                totals.sort_by(|a, b| a.coin_type.cmp(&b.coin_type));
+                page.totals = Some(totals);
+            }
+            Err(e) => {
+                warn!(?owner, "Failed to get coin totals with error: {:?}", e);
+                page.totals_unavailable = true;
+            }
+        }
     }
 }
 
@@ -32,6 +95,8 @@ impl CoinReadApiServer for CoinReadApiV2 {
         coin_type: Option<String>,
         cursor: Option<ObjectID>,
         limit: Option<usize>,
+        include_totals: Option<bool>,
+        min_balance: Option<BigInt<u64>>,
     ) -> RpcResult<CoinPage> {
         let limit = cap_page_limit(limit);
         if limit == 0 {
@@ -49,17 +114,57 @@ impl CoinReadApiServer for CoinReadApiV2 {
         };
         let mut results = self
             .inner
-            .get_owned_coins_in_blocking_task(owner, Some(coin_type), cursor, limit + 1)
+            .get_owned_coins_in_blocking_task(
+                owner,
+                Some(coin_type.clone()),
+                cursor,
+                limit + 1,
+                min_balance.map(BigInt::into_inner),
+            )
             .await?;
 
         let has_next_page = results.len() > limit;
         results.truncate(limit);
         let next_cursor = results.last().map(|o| o.coin_object_id);
-        Ok(Page {
+        let mut page: CoinPage = Page {
             data: results,
             next_cursor,
             has_next_page,
-        })
+            applied: None,
+        }
+        .into();
+        if include_totals.unwrap_or_default() {
+            self.set_totals(&mut page, owner, Some(coin_type)).await;
+        }
+        self.annotate_coin_page(&mut page).await;
+        Ok(page)
+    }
+
+    async fn select_coins(
+        &self,
+        owner: MgoAddress,
+        coin_type: Option<String>,
+        target_amount: BigInt<u64>,
+        max_coins: Option<usize>,
+    ) -> RpcResult<Vec<Coin>> {
+        let coin_type =
+            parse_to_type_tag(coin_type)?.to_canonical_string(/* with_prefix */ true);
+        let max_coins = cap_page_limit(max_coins);
+        // One more coin than can be selected, to tell whether the owner has more coins.
+        let coins = self
+            .inner
+            .get_largest_coins_in_blocking_task(owner, coin_type.clone(), max_coins + 1)
+            .await?;
+        let mut coins =
+            select_coins_largest_first(&coin_type, coins, target_amount.into_inner(), max_coins)?;
+        if let Some(coin_registry) = &self.coin_registry {
+            coin_registry
+                .annotate_coins(&mut coins, |ids| {
+                    self.inner.multi_get_objects_ordered_in_blocking_task(ids)
+                })
+                .await;
+        }
+        Ok(coins)
     }
 
     async fn get_all_coins(
@@ -67,6 +172,7 @@ impl CoinReadApiServer for CoinReadApiV2 {
         owner: MgoAddress,
         cursor: Option<ObjectID>,
         limit: Option<usize>,
+        include_totals: Option<bool>,
     ) -> RpcResult<CoinPage> {
         let limit = cap_page_limit(limit);
         if limit == 0 {
@@ -80,17 +186,24 @@ impl CoinReadApiServer for CoinReadApiV2 {
         };
         let mut results = self
             .inner
-            .get_owned_coins_in_blocking_task(owner, None, cursor, limit + 1)
+            .get_owned_coins_in_blocking_task(owner, None, cursor, limit + 1, None)
             .await?;
 
         let has_next_page = results.len() > limit;
         results.truncate(limit);
         let next_cursor = results.last().map(|o| o.coin_object_id);
-        Ok(Page {
+        let mut page: CoinPage = Page {
             data: results,
             next_cursor,
             has_next_page,
-        })
+            applied: None,
+        }
+        .into();
+        if include_totals.unwrap_or_default() {
+            self.set_totals(&mut page, owner, None).await;
+        }
+        self.annotate_coin_page(&mut page).await;
+        Ok(page)
     }
 
     async fn get_balance(
@@ -106,25 +219,72 @@ impl CoinReadApiServer for CoinReadApiV2 {
             .inner
             .get_coin_balances_in_blocking_task(owner, Some(coin_type.clone()))
             .await?;
-        if results.is_empty() {
-            return Ok(Balance::zero(coin_type));
-        }
-        Ok(results.swap_remove(0))
+        let mut balance = if results.is_empty() {
+            Balance::zero(coin_type)
+        } else {
+            results.swap_remove(0)
+        };
+        self.annotate_balances(std::slice::from_mut(&mut balance))
+            .await;
+        Ok(balance)
     }
 
     async fn get_all_balances(&self, owner: MgoAddress) -> RpcResult<Vec<Balance>> {
-        self.inner
+        let mut balances = self
+            .inner
             .get_coin_balances_in_blocking_task(owner, None)
-            .await
-            .map_err(Into::into)
+            .await?;
+        self.annotate_balances(&mut balances).await;
+        Ok(balances)
+    }
+
+    async fn get_all_balances_paginated(
+        &self,
+        owner: MgoAddress,
+        cursor: Option<String>,
+        limit: Option<usize>,
+    ) -> RpcResult<BalancePage> {
+        let limit = cap_page_limit(limit);
+        let cursor = cursor
+            .map(|c| parse_to_struct_tag(&c).map(|tag| tag.to_string()))
+            .transpose()?;
+        let mut results = self
+            .inner
+            .get_coin_balances_in_blocking_task(owner, None)
+            .await?;
+
+        // Page on the canonical coin type strings in byte order, the same order the fullnode
+        // uses, rather than the database collation.
+        results.sort_by(|a, b| a.coin_type.cmp(&b.coin_type));
+        if let Some(cursor) = cursor {
+            results.retain(|balance| balance.coin_type > cursor);
+        }
+        let has_next_page = results.len() > limit;
+        results.truncate(limit);
+        let next_cursor = results.last().map(|balance| balance.coin_type.clone());
+        self.annotate_balances(&mut results).await;
+        Ok(Page {
+            data: results,
+            next_cursor,
+            has_next_page,
+            applied: None,
+        })
     }
 
     async fn get_coin_metadata(&self, coin_type: String) -> RpcResult<Option<MgoCoinMetadata>> {
         let coin_struct = parse_to_struct_tag(&coin_type)?;
-        self.inner
+        let mut metadata = self
+            .inner
             .get_coin_metadata_in_blocking_task(coin_struct)
-            .await
-            .map_err(Into::into)
+            .await?;
+        if let (Some(metadata), Some(coin_registry)) = (&mut metadata, &self.coin_registry) {
+            coin_registry
+                .annotate_metadata(metadata, &coin_type, |ids| {
+                    self.inner.multi_get_objects_ordered_in_blocking_task(ids)
+                })
+                .await;
+        }
+        Ok(metadata)
     }
 
     async fn get_total_supply(&self, coin_type: String) -> RpcResult<Supply> {
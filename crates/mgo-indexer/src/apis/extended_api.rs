@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use async_trait::async_trait;
+use fastcrypto::encoding::Base64;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::RpcModule;
 
@@ -10,11 +11,18 @@ use mgo_json_rpc_api::{
     validate_limit, ExtendedApiServer, QUERY_MAX_RESULT_LIMIT, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS,
 };
 use mgo_json_rpc_types::{
-    AddressMetrics, CheckpointedObjectID, EpochInfo, EpochMetricsPage, EpochPage, MoveCallMetrics,
-    NetworkMetrics, Page, QueryObjectsPage, MgoObjectDataFilter, MgoObjectResponse,
-    MgoObjectResponseQuery,
+    AbortLocation, ActiveAddressStats, AddressMetrics, ChainState, CheckpointedObjectID,
+    CoinTypeOrder, CoinTypesPage, CohortRetention, EpochInfo, EpochMetricsPage, EpochPage,
+    EventsByObjectPage, ModifiedObjectCursor, ModifiedObjectsPage, ModuleDisassembly,
+    MoveCallMetrics, MultiOwnerObjectCursor, MultiOwnerObjectsPage, NetworkMetrics,
+    ObjectOwnersHistoryPage, ObjectsPage, OrphanedDynamicFieldsPage, PackageDependency,
+    PackageDependentsPage, PackageVerification, Page, QueryObjectsPage, MgoObjectDataFilter,
+    MgoObjectResponse, MgoObjectResponseQuery, SharedObjectStats, StorageStats, SupplyGranularity,
+    SupplyHistory, TableValue, ValidatorReport,
 };
 use mgo_open_rpc::Module;
+use mgo_types::base_types::{MgoAddress, ObjectID, SequenceNumber};
+use mgo_types::event::EventID;
 use mgo_types::mgo_serde::BigInt;
 
 use crate::errors::IndexerError;
@@ -82,6 +90,7 @@ impl<S: IndexerStore> ExtendedApi<S> {
             data,
             next_cursor,
             has_next_page,
+            applied: None,
         })
     }
 }
@@ -107,18 +116,41 @@ impl<S: IndexerStore + Sync + Send + 'static> ExtendedApiServer for ExtendedApi<
             data: epochs,
             next_cursor: next_cursor.map(|id| id.into()),
             has_next_page,
+            applied: None,
         })
     }
 
     async fn get_epoch_metrics(
         &self,
+        _epoch: Option<BigInt<u64>>,
         _cursor: Option<BigInt<u64>>,
         _limit: Option<usize>,
-        _descending_order: Option<bool>,
     ) -> RpcResult<EpochMetricsPage> {
         unimplemented!();
     }
 
+    async fn get_validator_report(
+        &self,
+        _validator_address: MgoAddress,
+        _from_epoch: BigInt<u64>,
+        _to_epoch: BigInt<u64>,
+    ) -> RpcResult<Vec<ValidatorReport>> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
+    async fn get_validator_ranking(
+        &self,
+        _epoch: Option<BigInt<u64>>,
+    ) -> RpcResult<Vec<ValidatorReport>> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
     async fn get_current_epoch(&self) -> RpcResult<EpochInfo> {
         Ok(self.state.get_current_epoch().await?)
     }
@@ -132,6 +164,48 @@ impl<S: IndexerStore + Sync + Send + 'static> ExtendedApiServer for ExtendedApi<
         Ok(self.query_objects_internal(query, cursor, limit).await?)
     }
 
+    async fn get_owned_objects_by_checkpoint(
+        &self,
+        _address: MgoAddress,
+        _query: Option<MgoObjectResponseQuery>,
+        _modified_after_checkpoint: Option<BigInt<u64>>,
+        _cursor: Option<ModifiedObjectCursor>,
+        _limit: Option<usize>,
+        _descending_order: Option<bool>,
+    ) -> RpcResult<ModifiedObjectsPage> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
+    async fn get_owned_objects_multi(
+        &self,
+        _addresses: Vec<MgoAddress>,
+        _query: Option<MgoObjectResponseQuery>,
+        _cursor: Option<MultiOwnerObjectCursor>,
+        _limit: Option<usize>,
+    ) -> RpcResult<MultiOwnerObjectsPage> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
+    async fn get_owned_objects_at_checkpoint(
+        &self,
+        _address: MgoAddress,
+        _checkpoint: BigInt<u64>,
+        _query: Option<MgoObjectResponseQuery>,
+        _cursor: Option<ObjectID>,
+        _limit: Option<usize>,
+    ) -> RpcResult<ObjectsPage> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
     async fn get_network_metrics(&self) -> RpcResult<NetworkMetrics> {
         Ok(self.state.get_network_metrics().await?)
     }
@@ -167,6 +241,40 @@ impl<S: IndexerStore + Sync + Send + 'static> ExtendedApiServer for ExtendedApi<
             .collect())
     }
 
+    async fn get_active_address_stats(
+        &self,
+        _from_day: BigInt<u64>,
+        _to_day: BigInt<u64>,
+    ) -> RpcResult<ActiveAddressStats> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
+    async fn get_retention(
+        &self,
+        _cohort_day: BigInt<u64>,
+        _horizon_days: BigInt<u64>,
+    ) -> RpcResult<CohortRetention> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
+    async fn get_coin_types(
+        &self,
+        _cursor: Option<String>,
+        _limit: Option<usize>,
+        _order_by: Option<CoinTypeOrder>,
+    ) -> RpcResult<CoinTypesPage> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
     async fn get_total_transactions(&self) -> RpcResult<BigInt<u64>> {
         let latest_cp_metrics = self.state.get_latest_checkpoint_metrics().await?;
         // NOTE: tx are counted as:
@@ -179,6 +287,152 @@ impl<S: IndexerStore + Sync + Send + 'static> ExtendedApiServer for ExtendedApi<
         // rolling_total_successful_transaction_blocks.
         Ok((total_txes as u64).into())
     }
+
+    async fn get_shared_object_stats(
+        &self,
+        _object_id: ObjectID,
+        _window_checkpoints: Option<BigInt<u64>>,
+    ) -> RpcResult<SharedObjectStats> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
+    async fn get_module_disassembly(
+        &self,
+        _package: ObjectID,
+        _module: String,
+    ) -> RpcResult<ModuleDisassembly> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
+    async fn resolve_abort_location(
+        &self,
+        _package: ObjectID,
+        _module: String,
+        _function_index: u16,
+        _code_offset: u16,
+    ) -> RpcResult<AbortLocation> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
+    async fn get_table_values(
+        &self,
+        _parent_object_id: ObjectID,
+        _key_type: String,
+        _value_type: String,
+        _keys: Vec<Base64>,
+    ) -> RpcResult<Vec<TableValue>> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
+    async fn query_events_by_object(
+        &self,
+        _object_id: ObjectID,
+        _cursor: Option<EventID>,
+        _limit: Option<usize>,
+        _descending_order: Option<bool>,
+    ) -> RpcResult<EventsByObjectPage> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
+    async fn get_storage_stats(&self) -> RpcResult<StorageStats> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
+    async fn get_supply_history(
+        &self,
+        _coin_type: String,
+        _granularity: SupplyGranularity,
+        _from: BigInt<u64>,
+        _to: BigInt<u64>,
+    ) -> RpcResult<SupplyHistory> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
+    async fn get_package_dependencies(
+        &self,
+        _package_id: ObjectID,
+        _version: Option<BigInt<u64>>,
+    ) -> RpcResult<Vec<PackageDependency>> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
+    async fn get_package_dependents(
+        &self,
+        _package_id: ObjectID,
+        _cursor: Option<ObjectID>,
+        _limit: Option<usize>,
+    ) -> RpcResult<PackageDependentsPage> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
+    async fn get_orphaned_dynamic_fields(
+        &self,
+        _parent_object_id: Option<ObjectID>,
+        _cursor: Option<ObjectID>,
+        _limit: Option<usize>,
+    ) -> RpcResult<OrphanedDynamicFieldsPage> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
+    async fn get_chain_state(&self) -> RpcResult<ChainState> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
+    async fn verify_package(
+        &self,
+        _package_id: ObjectID,
+        _modules: Vec<Base64>,
+    ) -> RpcResult<PackageVerification> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
+    async fn get_object_owners_history(
+        &self,
+        _object_id: ObjectID,
+        _cursor: Option<SequenceNumber>,
+        _limit: Option<usize>,
+    ) -> RpcResult<ObjectOwnersHistoryPage> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
 }
 
 impl<S> MgoRpcModule for ExtendedApi<S>
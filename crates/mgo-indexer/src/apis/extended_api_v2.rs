@@ -1,24 +1,76 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::path::PathBuf;
+
+use crate::apis::module_debug_info::ModuleDebugInfoCache;
+use crate::apis::package_verification::verify_package;
+use crate::apis::ChainStateCache;
+use crate::errors::IndexerError;
+use crate::event_object_refs::ObjectRefEventTypes;
 use crate::indexer_reader::IndexerReader;
+use fastcrypto::encoding::Base64;
 use jsonrpsee::{core::RpcResult, RpcModule};
+use mgo_json_rpc::read_api::DisplayRenderLimits;
 use mgo_json_rpc::MgoRpcModule;
-use mgo_json_rpc_api::{validate_limit, ExtendedApiServer, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS};
+use mgo_json_rpc_api::{
+    validate_limit, ExtendedApiServer, MAX_SUPPLY_HISTORY_BUCKETS, MAX_VALIDATOR_REPORT_EPOCHS,
+    QUERY_MAX_RESULT_LIMIT, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS,
+};
 use mgo_json_rpc_types::{
-    AddressMetrics, CheckpointedObjectID, EpochInfo, EpochMetrics, EpochMetricsPage, EpochPage,
-    MoveCallMetrics, NetworkMetrics, Page, QueryObjectsPage, MgoObjectResponseQuery,
+    AbortLocation, ActiveAddressStats, AddressMetrics, ChainState, CheckpointedObjectID,
+    CoinTypeOrder, CoinTypesPage, CohortRetention, EpochInfo, EpochMetricsPage, EpochPage,
+    EventsByObjectPage, ModifiedObjectCursor, ModifiedObjectsPage, ModuleDisassembly,
+    MoveCallMetrics, MultiOwnerObjectCursor, MultiOwnerObjectsPage, NetworkMetrics,
+    ObjectOwnersHistoryPage, ObjectsPage, OrphanedDynamicFieldsPage, PackageDependency,
+    PackageDependentsPage, PackageVerification, Page, QueryObjectsPage, MgoObjectResponseQuery,
+    SharedObjectStats, StorageStats, SupplyGranularity, SupplyHistory, TableValue, ValidatorReport,
 };
 use mgo_open_rpc::Module;
+use mgo_types::base_types::{MgoAddress, ObjectID, SequenceNumber};
+use mgo_types::event::EventID;
 use mgo_types::mgo_serde::BigInt;
+use mgo_types::{parse_mgo_struct_tag, parse_mgo_type_tag};
+
+/// Default and maximum number of checkpoints `get_shared_object_stats` looks back over.
+const DEFAULT_SHARED_OBJECT_STATS_WINDOW: u64 = 100;
+const MAX_SHARED_OBJECT_STATS_WINDOW: u64 = 1000;
+/// Maximum number of days `get_active_address_stats` and `get_retention` report on.
+const MAX_ACTIVE_ADDRESS_STATS_DAYS: u64 = 366;
 
 pub(crate) struct ExtendedApiV2 {
     inner: IndexerReader,
+    display_limits: DisplayRenderLimits,
+    module_debug_info: ModuleDebugInfoCache,
+    object_ref_event_types: ObjectRefEventTypes,
+    chain_state: ChainStateCache,
 }
 
 impl ExtendedApiV2 {
-    pub fn new(inner: IndexerReader) -> Self {
-        Self { inner }
+    pub fn new(
+        inner: IndexerReader,
+        display_limits: DisplayRenderLimits,
+        source_maps_dir: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            inner,
+            display_limits,
+            module_debug_info: ModuleDebugInfoCache::new(source_maps_dir),
+            object_ref_event_types: ObjectRefEventTypes::default(),
+            chain_state: ChainStateCache::default(),
+        }
+    }
+
+    /// Event types the writer indexes object references of, as configured for it.
+    pub fn with_object_ref_event_types(mut self, types: ObjectRefEventTypes) -> Self {
+        self.object_ref_event_types = types;
+        self
+    }
+
+    /// Cache `get_chain_state` is served from, which is never refreshed unless set.
+    pub fn with_chain_state(mut self, chain_state: ChainStateCache) -> Self {
+        self.chain_state = chain_state;
+        self
     }
 }
 
@@ -49,38 +101,24 @@ impl ExtendedApiServer for ExtendedApiV2 {
             data: epochs,
             next_cursor: next_cursor.map(|id| id.into()),
             has_next_page,
+            applied: None,
         })
     }
 
     async fn get_epoch_metrics(
         &self,
+        epoch: Option<BigInt<u64>>,
         cursor: Option<BigInt<u64>>,
         limit: Option<usize>,
-        descending_order: Option<bool>,
     ) -> RpcResult<EpochMetricsPage> {
         let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS)?;
-        let epochs = self
+        let mut epoch_metrics = self
             .inner
             .spawn_blocking(move |this| {
-                this.get_epochs(
-                    cursor.map(|x| *x),
-                    limit + 1,
-                    descending_order.unwrap_or(false),
-                )
+                this.get_epoch_metrics(epoch.map(|x| *x), cursor.map(|x| *x), limit + 1)
             })
             .await?;
 
-        let mut epoch_metrics = epochs
-            .into_iter()
-            .map(|e| EpochMetrics {
-                epoch: e.epoch,
-                epoch_total_transactions: e.epoch_total_transactions,
-                first_checkpoint_id: e.first_checkpoint_id,
-                epoch_start_timestamp: e.epoch_start_timestamp,
-                end_of_epoch_info: e.end_of_epoch_info,
-            })
-            .collect::<Vec<_>>();
-
         let has_next_page = epoch_metrics.len() > limit;
         epoch_metrics.truncate(limit);
         let next_cursor = epoch_metrics.last().map(|e| e.epoch);
@@ -88,9 +126,41 @@ impl ExtendedApiServer for ExtendedApiV2 {
             data: epoch_metrics,
             next_cursor: next_cursor.map(|id| id.into()),
             has_next_page,
+            applied: None,
         })
     }
 
+    async fn get_validator_report(
+        &self,
+        validator_address: MgoAddress,
+        from_epoch: BigInt<u64>,
+        to_epoch: BigInt<u64>,
+    ) -> RpcResult<Vec<ValidatorReport>> {
+        let (from, to) = (*from_epoch, *to_epoch);
+        if from > to || to - from >= MAX_VALIDATOR_REPORT_EPOCHS {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "from_epoch must not be after to_epoch and the range must span at most {MAX_VALIDATOR_REPORT_EPOCHS} epochs, got {from}..={to}"
+            ))
+            .into());
+        }
+        let report = self
+            .inner
+            .spawn_blocking(move |this| this.get_validator_report(validator_address, from, to))
+            .await?;
+        Ok(report)
+    }
+
+    async fn get_validator_ranking(
+        &self,
+        epoch: Option<BigInt<u64>>,
+    ) -> RpcResult<Vec<ValidatorReport>> {
+        let ranking = self
+            .inner
+            .spawn_blocking(move |this| this.get_validator_ranking(epoch.map(|e| *e)))
+            .await?;
+        Ok(ranking)
+    }
+
     async fn get_current_epoch(&self) -> RpcResult<EpochInfo> {
         let stored_epoch = self
             .inner
@@ -111,6 +181,63 @@ impl ExtendedApiServer for ExtendedApiV2 {
         .into())
     }
 
+    async fn get_owned_objects_by_checkpoint(
+        &self,
+        address: MgoAddress,
+        query: Option<MgoObjectResponseQuery>,
+        modified_after_checkpoint: Option<BigInt<u64>>,
+        cursor: Option<ModifiedObjectCursor>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<ModifiedObjectsPage> {
+        Ok(self
+            .inner
+            .get_owned_objects_by_checkpoint_page(
+                address,
+                query,
+                modified_after_checkpoint.map(|c| *c),
+                cursor,
+                limit,
+                descending_order.unwrap_or(false),
+                &self.display_limits,
+            )
+            .await?)
+    }
+
+    async fn get_owned_objects_multi(
+        &self,
+        addresses: Vec<MgoAddress>,
+        query: Option<MgoObjectResponseQuery>,
+        cursor: Option<MultiOwnerObjectCursor>,
+        limit: Option<usize>,
+    ) -> RpcResult<MultiOwnerObjectsPage> {
+        Ok(self
+            .inner
+            .get_owned_objects_multi_page(addresses, query, cursor, limit, &self.display_limits)
+            .await?)
+    }
+
+    async fn get_owned_objects_at_checkpoint(
+        &self,
+        address: MgoAddress,
+        checkpoint: BigInt<u64>,
+        query: Option<MgoObjectResponseQuery>,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectsPage> {
+        Ok(self
+            .inner
+            .get_owned_objects_at_checkpoint_page(
+                address,
+                *checkpoint,
+                query,
+                cursor,
+                limit,
+                &self.display_limits,
+            )
+            .await?)
+    }
+
     async fn get_network_metrics(&self) -> RpcResult<NetworkMetrics> {
         let network_metrics = self
             .inner
@@ -154,6 +281,69 @@ impl ExtendedApiServer for ExtendedApiV2 {
         Ok(all_epoch_address_metrics)
     }
 
+    async fn get_active_address_stats(
+        &self,
+        from_day: BigInt<u64>,
+        to_day: BigInt<u64>,
+    ) -> RpcResult<ActiveAddressStats> {
+        let (from_day, to_day) = (*from_day, *to_day);
+        if from_day > to_day || to_day - from_day >= MAX_ACTIVE_ADDRESS_STATS_DAYS {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "from_day must not be after to_day and the range must span at most {MAX_ACTIVE_ADDRESS_STATS_DAYS} days, got {from_day}..={to_day}"
+            ))
+            .into());
+        }
+        let stats = self
+            .inner
+            .spawn_blocking(move |this| this.get_active_address_stats(from_day, to_day))
+            .await?;
+        Ok(stats)
+    }
+
+    async fn get_retention(
+        &self,
+        cohort_day: BigInt<u64>,
+        horizon_days: BigInt<u64>,
+    ) -> RpcResult<CohortRetention> {
+        let (cohort_day, horizon_days) = (*cohort_day, *horizon_days);
+        if horizon_days >= MAX_ACTIVE_ADDRESS_STATS_DAYS {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "horizon_days must be below {MAX_ACTIVE_ADDRESS_STATS_DAYS}, got {horizon_days}"
+            ))
+            .into());
+        }
+        let retention = self
+            .inner
+            .spawn_blocking(move |this| this.get_retention(cohort_day, horizon_days))
+            .await?;
+        Ok(retention)
+    }
+
+    async fn get_coin_types(
+        &self,
+        cursor: Option<String>,
+        limit: Option<usize>,
+        order_by: Option<CoinTypeOrder>,
+    ) -> RpcResult<CoinTypesPage> {
+        let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT)?;
+        let mut coin_types = self
+            .inner
+            .spawn_blocking(move |this| {
+                this.get_coin_types(cursor, limit + 1, order_by.unwrap_or_default())
+            })
+            .await?;
+
+        let has_next_page = coin_types.len() > limit;
+        coin_types.truncate(limit);
+        let next_cursor = coin_types.last().map(|c| c.coin_type.clone());
+        Ok(Page {
+            data: coin_types,
+            next_cursor,
+            has_next_page,
+            applied: None,
+        })
+    }
+
     async fn get_total_transactions(&self) -> RpcResult<BigInt<u64>> {
         let latest_checkpoint = self
             .inner
@@ -161,6 +351,247 @@ impl ExtendedApiServer for ExtendedApiV2 {
             .await?;
         Ok(latest_checkpoint.network_total_transactions.into())
     }
+
+    async fn get_shared_object_stats(
+        &self,
+        object_id: ObjectID,
+        window_checkpoints: Option<BigInt<u64>>,
+    ) -> RpcResult<SharedObjectStats> {
+        let window = window_checkpoints
+            .map(|w| *w)
+            .unwrap_or(DEFAULT_SHARED_OBJECT_STATS_WINDOW);
+        if window == 0 || window > MAX_SHARED_OBJECT_STATS_WINDOW {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "window_checkpoints must be between 1 and {MAX_SHARED_OBJECT_STATS_WINDOW}, got {window}"
+            ))
+            .into_invalid_params());
+        }
+        let stats = self
+            .inner
+            .spawn_blocking(move |this| this.get_shared_object_stats(object_id, window))
+            .await
+            .map_err(IndexerError::into_invalid_params)?;
+        Ok(stats)
+    }
+
+    async fn get_module_disassembly(
+        &self,
+        package: ObjectID,
+        module: String,
+    ) -> RpcResult<ModuleDisassembly> {
+        let info = self.module_debug_info.get(&self.inner, package).await?;
+        Ok(info.disassembly(&module)?)
+    }
+
+    async fn resolve_abort_location(
+        &self,
+        package: ObjectID,
+        module: String,
+        function_index: u16,
+        code_offset: u16,
+    ) -> RpcResult<AbortLocation> {
+        let info = self.module_debug_info.get(&self.inner, package).await?;
+        Ok(info.resolve_abort_location(&module, function_index, code_offset)?)
+    }
+
+    async fn get_table_values(
+        &self,
+        parent_object_id: ObjectID,
+        key_type: String,
+        value_type: String,
+        keys: Vec<Base64>,
+    ) -> RpcResult<Vec<TableValue>> {
+        let parse_type = |type_: &str| {
+            parse_mgo_type_tag(type_).map_err(|e| {
+                IndexerError::InvalidArgumentError(format!("Invalid type {type_}: {e}"))
+            })
+        };
+        let key_type = parse_type(&key_type)?;
+        let value_type = parse_type(&value_type)?;
+        let keys = keys
+            .into_iter()
+            .map(|key| {
+                key.to_vec().map_err(|e| {
+                    IndexerError::InvalidArgumentError(format!(
+                        "Invalid key {}: {e}",
+                        key.encoded()
+                    ))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(self
+            .inner
+            .get_table_values_in_blocking_task(parent_object_id, key_type, value_type, keys)
+            .await?)
+    }
+
+    async fn query_events_by_object(
+        &self,
+        object_id: ObjectID,
+        cursor: Option<EventID>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<EventsByObjectPage> {
+        let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT)?;
+        let mut events = self
+            .inner
+            .query_events_by_object_in_blocking_task(
+                object_id,
+                cursor,
+                limit + 1,
+                descending_order.unwrap_or(false),
+            )
+            .await?;
+
+        let has_next_page = events.len() > limit;
+        events.truncate(limit);
+        let next_cursor = events.last().map(|e| e.id);
+        Ok(EventsByObjectPage {
+            data: events,
+            next_cursor,
+            has_next_page,
+            searched_event_types: self.object_ref_event_types.to_canonical_strings(),
+        })
+    }
+
+    async fn get_storage_stats(&self) -> RpcResult<StorageStats> {
+        let stats = self
+            .inner
+            .spawn_blocking(|this| this.get_storage_stats())
+            .await?;
+        Ok(stats)
+    }
+
+    async fn get_supply_history(
+        &self,
+        coin_type: String,
+        granularity: SupplyGranularity,
+        from: BigInt<u64>,
+        to: BigInt<u64>,
+    ) -> RpcResult<SupplyHistory> {
+        let (from, to) = (*from, *to);
+        if from > to || to - from >= MAX_SUPPLY_HISTORY_BUCKETS {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "from must not be after to and the range must span at most {MAX_SUPPLY_HISTORY_BUCKETS} buckets, got {from}..={to}"
+            ))
+            .into());
+        }
+        let coin_type = parse_mgo_struct_tag(&coin_type).map_err(|e| {
+            IndexerError::InvalidArgumentError(format!("Invalid coin type {coin_type}: {e}"))
+        })?;
+        let history = self
+            .inner
+            .spawn_blocking(move |this| {
+                this.get_supply_history(coin_type, granularity, from, to)
+            })
+            .await?;
+        Ok(history)
+    }
+
+    async fn get_package_dependencies(
+        &self,
+        package_id: ObjectID,
+        version: Option<BigInt<u64>>,
+    ) -> RpcResult<Vec<PackageDependency>> {
+        let dependencies = self
+            .inner
+            .spawn_blocking(move |this| {
+                this.get_package_dependencies(package_id, version.map(|v| *v))
+            })
+            .await?;
+        Ok(dependencies)
+    }
+
+    async fn get_package_dependents(
+        &self,
+        package_id: ObjectID,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<PackageDependentsPage> {
+        let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT)?;
+        let mut dependents = self
+            .inner
+            .spawn_blocking(move |this| this.get_package_dependents(package_id, cursor, limit + 1))
+            .await?;
+
+        let has_next_page = dependents.len() > limit;
+        dependents.truncate(limit);
+        let next_cursor = dependents.last().map(|d| d.original_package_id);
+        Ok(Page {
+            data: dependents,
+            next_cursor,
+            has_next_page,
+            applied: None,
+        })
+    }
+    async fn get_orphaned_dynamic_fields(
+        &self,
+        parent_object_id: Option<ObjectID>,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<OrphanedDynamicFieldsPage> {
+        let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT)?;
+        let mut fields = self
+            .inner
+            .spawn_blocking(move |this| {
+                this.get_orphaned_dynamic_fields(parent_object_id, cursor, limit + 1)
+            })
+            .await?;
+
+        let has_next_page = fields.len() > limit;
+        fields.truncate(limit);
+        let next_cursor = fields.last().map(|f| f.field_id);
+        Ok(Page {
+            data: fields,
+            next_cursor,
+            has_next_page,
+            applied: None,
+        })
+    }
+
+    async fn get_chain_state(&self) -> RpcResult<ChainState> {
+        let state = self.chain_state.get().ok_or_else(|| {
+            IndexerError::PostgresReadError("Chain state is not available yet".to_string())
+        })?;
+        Ok(state)
+    }
+
+    async fn verify_package(
+        &self,
+        package_id: ObjectID,
+        modules: Vec<Base64>,
+    ) -> RpcResult<PackageVerification> {
+        let verification = self
+            .inner
+            .spawn_blocking(move |this| verify_package(&this, package_id, &modules))
+            .await?;
+        Ok(verification)
+    }
+
+    async fn get_object_owners_history(
+        &self,
+        object_id: ObjectID,
+        cursor: Option<SequenceNumber>,
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectOwnersHistoryPage> {
+        let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT)?;
+        let mut intervals = self
+            .inner
+            .spawn_blocking(move |this| {
+                this.get_object_owners_history(object_id, cursor, limit + 1)
+            })
+            .await?;
+
+        let has_next_page = intervals.len() > limit;
+        intervals.truncate(limit);
+        let next_cursor = intervals.last().map(|i| i.from_version);
+        Ok(Page {
+            data: intervals,
+            next_cursor,
+            has_next_page,
+            applied: None,
+        })
+    }
 }
 
 impl MgoRpcModule for ExtendedApiV2 {
@@ -0,0 +1,325 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Labels of the kind of filter paginated queries are called with, for
+//! [`QueryFilterMetrics`](crate::metrics::QueryFilterMetrics).
+//!
+//! Labels are the names of filter variants and never carry their values, so that the number of
+//! series stays bounded. Filters combining others are all labelled [`COMPOSITE`], whatever they
+//! combine. Matches are exhaustive, so that a new variant must be given a label.
+
+use mgo_json_rpc_types::{EventFilter, MgoObjectDataFilter, TransactionFilter};
+
+/// Label of queries without a filter.
+pub(crate) const NO_FILTER: &str = "None";
+/// Label of filters combining other filters.
+pub(crate) const COMPOSITE: &str = "Composite";
+
+pub(crate) fn transaction_filter_label(filter: Option<&TransactionFilter>) -> &'static str {
+    let Some(filter) = filter else {
+        return NO_FILTER;
+    };
+    match filter {
+        TransactionFilter::Checkpoint(_) => "Checkpoint",
+        TransactionFilter::MoveFunction { .. } => "MoveFunction",
+        TransactionFilter::InputObject(_) => "InputObject",
+        TransactionFilter::ChangedObject(_) => "ChangedObject",
+        TransactionFilter::FromAddress(_) => "FromAddress",
+        TransactionFilter::ToAddress(_) => "ToAddress",
+        TransactionFilter::FromAndToAddress { .. } => "FromAndToAddress",
+        TransactionFilter::FromOrToAddress { .. } => "FromOrToAddress",
+        TransactionFilter::TransactionKind(_) => "TransactionKind",
+        TransactionFilter::TransactionKindIn(_) => "TransactionKindIn",
+        TransactionFilter::SignatureScheme(_) => "SignatureScheme",
+    }
+}
+
+/// `All` of no filter matches every event, and is labelled as no filter.
+pub(crate) fn event_filter_label(filter: &EventFilter) -> &'static str {
+    match filter {
+        EventFilter::Sender(_) => "Sender",
+        EventFilter::Transaction(_) => "Transaction",
+        EventFilter::Package(_) => "Package",
+        EventFilter::MoveModule { .. } => "MoveModule",
+        EventFilter::MoveEventType(_) => "MoveEventType",
+        EventFilter::MoveEventModule { .. } => "MoveEventModule",
+        EventFilter::MoveEventField { .. } => "MoveEventField",
+        EventFilter::TimeRange { .. } => "TimeRange",
+        EventFilter::Checkpoint(_) => "Checkpoint",
+        EventFilter::CheckpointRange { .. } => "CheckpointRange",
+        EventFilter::All(filters) if filters.is_empty() => NO_FILTER,
+        EventFilter::All(_) | EventFilter::Any(_) | EventFilter::And(..) | EventFilter::Or(..) => {
+            COMPOSITE
+        }
+    }
+}
+
+pub(crate) fn object_filter_label(filter: Option<&MgoObjectDataFilter>) -> &'static str {
+    let Some(filter) = filter else {
+        return NO_FILTER;
+    };
+    match filter {
+        MgoObjectDataFilter::MatchAll(_)
+        | MgoObjectDataFilter::MatchAny(_)
+        | MgoObjectDataFilter::MatchNone(_) => COMPOSITE,
+        MgoObjectDataFilter::Package(_) => "Package",
+        MgoObjectDataFilter::MoveModule { .. } => "MoveModule",
+        MgoObjectDataFilter::StructType(_) => "StructType",
+        MgoObjectDataFilter::AddressOwner(_) => "AddressOwner",
+        MgoObjectDataFilter::ObjectOwner(_) => "ObjectOwner",
+        MgoObjectDataFilter::ObjectId(_) => "ObjectId",
+        MgoObjectDataFilter::ObjectIds(_) => "ObjectIds",
+        MgoObjectDataFilter::Version(_) => "Version",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use move_core_types::identifier::Identifier;
+    use prometheus::Registry;
+
+    use mgo_types::base_types::{MgoAddress, ObjectID};
+    use mgo_types::crypto::SignatureScheme;
+    use mgo_types::digests::TransactionDigest;
+    use mgo_types::gas_coin::GasCoin;
+
+    use crate::metrics::QueryFilterMetrics;
+
+    use super::*;
+
+    fn assert_labels(labels: Vec<(&'static str, &'static str)>) {
+        for (label, expected) in labels {
+            assert_eq!(label, expected);
+        }
+    }
+
+    #[test]
+    fn test_transaction_filter_labels() {
+        let address = MgoAddress::random_for_testing_only();
+        let id = ObjectID::random();
+        assert_labels(vec![
+            (transaction_filter_label(None), "None"),
+            (
+                transaction_filter_label(Some(&TransactionFilter::Checkpoint(1))),
+                "Checkpoint",
+            ),
+            (
+                transaction_filter_label(Some(&TransactionFilter::MoveFunction {
+                    package: id,
+                    module: Some("m".to_string()),
+                    function: None,
+                })),
+                "MoveFunction",
+            ),
+            (
+                transaction_filter_label(Some(&TransactionFilter::InputObject(id))),
+                "InputObject",
+            ),
+            (
+                transaction_filter_label(Some(&TransactionFilter::ChangedObject(id))),
+                "ChangedObject",
+            ),
+            (
+                transaction_filter_label(Some(&TransactionFilter::FromAddress(address))),
+                "FromAddress",
+            ),
+            (
+                transaction_filter_label(Some(&TransactionFilter::ToAddress(address))),
+                "ToAddress",
+            ),
+            (
+                transaction_filter_label(Some(&TransactionFilter::FromAndToAddress {
+                    from: address,
+                    to: address,
+                })),
+                "FromAndToAddress",
+            ),
+            (
+                transaction_filter_label(Some(&TransactionFilter::FromOrToAddress {
+                    addr: address,
+                })),
+                "FromOrToAddress",
+            ),
+            (
+                transaction_filter_label(Some(&TransactionFilter::TransactionKind(
+                    "ProgrammableTransaction".to_string(),
+                ))),
+                "TransactionKind",
+            ),
+            (
+                transaction_filter_label(Some(&TransactionFilter::TransactionKindIn(vec![]))),
+                "TransactionKindIn",
+            ),
+            (
+                transaction_filter_label(Some(&TransactionFilter::SignatureScheme(
+                    SignatureScheme::ED25519,
+                ))),
+                "SignatureScheme",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_event_filter_labels() {
+        let id = ObjectID::random();
+        let module = Identifier::new("m").unwrap();
+        let sender = EventFilter::Sender(MgoAddress::random_for_testing_only());
+        assert_labels(vec![
+            (event_filter_label(&sender), "Sender"),
+            (
+                event_filter_label(&EventFilter::Transaction(TransactionDigest::random())),
+                "Transaction",
+            ),
+            (event_filter_label(&EventFilter::Package(id)), "Package"),
+            (
+                event_filter_label(&EventFilter::MoveModule {
+                    package: id,
+                    module: module.clone(),
+                }),
+                "MoveModule",
+            ),
+            (
+                event_filter_label(&EventFilter::MoveEventType(GasCoin::type_())),
+                "MoveEventType",
+            ),
+            (
+                event_filter_label(&EventFilter::MoveEventModule {
+                    package: id,
+                    module,
+                }),
+                "MoveEventModule",
+            ),
+            (
+                event_filter_label(&EventFilter::MoveEventField {
+                    path: "/amount".to_string(),
+                    value: serde_json::json!(1),
+                }),
+                "MoveEventField",
+            ),
+            (
+                event_filter_label(&EventFilter::TimeRange {
+                    start_time: 0,
+                    end_time: 1,
+                }),
+                "TimeRange",
+            ),
+            (event_filter_label(&EventFilter::Checkpoint(1)), "Checkpoint"),
+            (
+                event_filter_label(&EventFilter::CheckpointRange { from: 1, to: 2 }),
+                "CheckpointRange",
+            ),
+            (event_filter_label(&EventFilter::All(vec![])), "None"),
+            (
+                event_filter_label(&EventFilter::All(vec![sender.clone()])),
+                "Composite",
+            ),
+            (
+                event_filter_label(&EventFilter::Any(vec![sender.clone()])),
+                "Composite",
+            ),
+            (
+                event_filter_label(&EventFilter::And(
+                    Box::new(sender.clone()),
+                    Box::new(EventFilter::Package(id)),
+                )),
+                "Composite",
+            ),
+            (
+                event_filter_label(&EventFilter::Or(
+                    Box::new(sender.clone()),
+                    Box::new(sender),
+                )),
+                "Composite",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_object_filter_labels() {
+        let id = ObjectID::random();
+        let owner = MgoObjectDataFilter::AddressOwner(MgoAddress::random_for_testing_only());
+        assert_labels(vec![
+            (object_filter_label(None), "None"),
+            (
+                object_filter_label(Some(&MgoObjectDataFilter::MatchAll(vec![owner.clone()]))),
+                "Composite",
+            ),
+            (
+                object_filter_label(Some(&MgoObjectDataFilter::MatchAny(vec![owner.clone()]))),
+                "Composite",
+            ),
+            (
+                object_filter_label(Some(&MgoObjectDataFilter::MatchNone(vec![owner.clone()]))),
+                "Composite",
+            ),
+            (
+                object_filter_label(Some(&MgoObjectDataFilter::Package(id))),
+                "Package",
+            ),
+            (
+                object_filter_label(Some(&MgoObjectDataFilter::MoveModule {
+                    package: id,
+                    module: Identifier::new("m").unwrap(),
+                })),
+                "MoveModule",
+            ),
+            (
+                object_filter_label(Some(&MgoObjectDataFilter::gas_coin())),
+                "StructType",
+            ),
+            (object_filter_label(Some(&owner)), "AddressOwner"),
+            (
+                object_filter_label(Some(&MgoObjectDataFilter::ObjectOwner(id))),
+                "ObjectOwner",
+            ),
+            (
+                object_filter_label(Some(&MgoObjectDataFilter::ObjectId(id))),
+                "ObjectId",
+            ),
+            (
+                object_filter_label(Some(&MgoObjectDataFilter::ObjectIds(vec![id]))),
+                "ObjectIds",
+            ),
+            (
+                object_filter_label(Some(&MgoObjectDataFilter::Version(1))),
+                "Version",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_query_filter_metrics_registered_once() {
+        let registry = Registry::new();
+        let metrics = QueryFilterMetrics::new(&registry);
+        metrics
+            .query_latency
+            .with_label_values(&["query_events", NO_FILTER])
+            .observe(0.1);
+        metrics
+            .query_result_size
+            .with_label_values(&["query_events", NO_FILTER])
+            .observe(3.0);
+
+        let names: Vec<_> = registry
+            .gather()
+            .into_iter()
+            .map(|family| family.get_name().to_string())
+            .collect();
+        let unique: BTreeSet<_> = names.iter().cloned().collect();
+        assert_eq!(names.len(), unique.len());
+        assert_eq!(
+            unique,
+            BTreeSet::from([
+                "reader_query_latency_by_filter".to_string(),
+                "reader_query_result_size_by_filter".to_string(),
+            ])
+        );
+
+        // Registering the same metrics again is refused rather than duplicated.
+        assert!(registry
+            .register(Box::new(metrics.query_latency.clone()))
+            .is_err());
+    }
+}
@@ -9,7 +9,9 @@ use jsonrpsee::RpcModule;
 use mgo_json_rpc::MgoRpcModule;
 use mgo_json_rpc_api::{GovernanceReadApiClient, GovernanceReadApiServer};
 use mgo_json_rpc_types::MgoCommittee;
-use mgo_json_rpc_types::{DelegatedStake, ValidatorApys};
+use mgo_json_rpc_types::{
+    DelegatedStake, OwnedStakesSummary, StorageRebateEstimate, ValidatorApys,
+};
 use mgo_open_rpc::Module;
 use mgo_types::base_types::{ObjectID, MgoAddress};
 use mgo_types::mgo_serde::BigInt;
@@ -39,6 +41,13 @@ impl GovernanceReadApiServer for GovernanceReadApi {
         self.fullnode.get_stakes(owner).await
     }
 
+    async fn get_owned_staked_objects_summary(
+        &self,
+        owner: MgoAddress,
+    ) -> RpcResult<OwnedStakesSummary> {
+        self.fullnode.get_owned_staked_objects_summary(owner).await
+    }
+
     async fn get_committee_info(&self, epoch: Option<BigInt<u64>>) -> RpcResult<MgoCommittee> {
         self.fullnode.get_committee_info(epoch).await
     }
@@ -54,6 +63,16 @@ impl GovernanceReadApiServer for GovernanceReadApi {
     async fn get_validators_apy(&self) -> RpcResult<ValidatorApys> {
         self.fullnode.get_validators_apy().await
     }
+
+    async fn estimate_storage_rebate(
+        &self,
+        object_id: ObjectID,
+        new_contents_size: Option<BigInt<u64>>,
+    ) -> RpcResult<StorageRebateEstimate> {
+        self.fullnode
+            .estimate_storage_rebate(object_id, new_contents_size)
+            .await
+    }
 }
 
 impl MgoRpcModule for GovernanceReadApi {
@@ -11,12 +11,15 @@ use cached::{proc_macro::cached, SizedCache};
 use mgo_json_rpc::{governance_api::ValidatorExchangeRates, MgoRpcModule};
 use mgo_json_rpc_api::GovernanceReadApiServer;
 use mgo_json_rpc_types::{
-    DelegatedStake, EpochInfo, StakeStatus, MgoCommittee, MgoObjectDataFilter, ValidatorApys,
+    CheckpointId, DelegatedStake, EpochInfo, StakeStatus, MgoCommittee, MgoObjectDataFilter,
+    OwnedStakesSummary, StorageRebateEstimate, ValidatorApys,
 };
 use mgo_open_rpc::Module;
+use mgo_protocol_config::{ProtocolConfig, ProtocolVersion};
 use mgo_types::{
     base_types::{MoveObjectType, ObjectID, MgoAddress},
     committee::EpochId,
+    digests::ChainIdentifier,
     governance::StakedMgo,
     mgo_serde::BigInt,
     mgo_system_state::{mgo_system_state_summary::MgoSystemStateSummary, PoolTokenExchangeRate},
@@ -41,6 +44,48 @@ impl GovernanceReadApiV2 {
         Ok(apys.get(address).copied())
     }
 
+    /// Protocol config of the latest indexed epoch, which changes as soon as the indexer has
+    /// processed the reconfiguration to a new version.
+    async fn get_latest_protocol_config(&self) -> Result<(ProtocolConfig, EpochId), IndexerError> {
+        let (latest_epoch, genesis_checkpoint) = self
+            .inner
+            .spawn_blocking(|this| {
+                let latest_epoch = this.get_latest_epoch_info_from_db()?;
+                let genesis_checkpoint = this.get_checkpoint(CheckpointId::SequenceNumber(0))?;
+                Ok::<_, IndexerError>((latest_epoch, genesis_checkpoint))
+            })
+            .await?;
+        let chain = genesis_checkpoint
+            .map(|checkpoint| ChainIdentifier::from(checkpoint.digest).chain())
+            .ok_or_else(|| {
+                IndexerError::PostgresReadError("Genesis checkpoint is not indexed".to_owned())
+            })?;
+        let version = ProtocolVersion::new(latest_epoch.protocol_version as u64);
+        let config =
+            ProtocolConfig::get_for_version_if_supported(version, chain).ok_or_else(|| {
+                IndexerError::GenericError(format!("Unsupported protocol version {version:?}"))
+            })?;
+        Ok((config, latest_epoch.epoch as EpochId))
+    }
+
+    /// Storage rebate of the latest indexed version of `object_id` at the storage price of the
+    /// latest indexed epoch.
+    async fn estimate_storage_rebate(
+        &self,
+        object_id: ObjectID,
+        new_contents_size: Option<u64>,
+    ) -> Result<StorageRebateEstimate, IndexerError> {
+        let object = self
+            .inner
+            .get_object_in_blocking_task(object_id)
+            .await?
+            .ok_or_else(|| {
+                IndexerError::InvalidArgumentError(format!("Object {object_id} not found"))
+            })?;
+        let (config, epoch) = self.get_latest_protocol_config().await?;
+        Ok(StorageRebateEstimate::new(&object, &config, epoch, new_contents_size))
+    }
+
     async fn get_validators_apy(&self) -> Result<ValidatorApys, IndexerError> {
         let system_state_summary: MgoSystemStateSummary =
             self.get_latest_mgo_system_state().await?;
@@ -82,7 +127,11 @@ impl GovernanceReadApiV2 {
         ids: Vec<ObjectID>,
     ) -> Result<Vec<DelegatedStake>, IndexerError> {
         let mut stakes = vec![];
-        for stored_object in self.inner.multi_get_objects_in_blocking_task(ids).await? {
+        for stored_object in self
+            .inner
+            .multi_get_stored_objects_in_blocking_task(ids)
+            .await?
+        {
             let object = mgo_types::object::Object::try_from(stored_object)?;
             let stake_object = StakedMgo::try_from(&object)?;
             stakes.push(stake_object);
@@ -117,6 +166,22 @@ impl GovernanceReadApiV2 {
         self.get_delegated_stakes(stakes).await
     }
 
+    /// Stakes of `owner` grouped by validator, named after the active validators of the latest
+    /// indexed system state.
+    async fn get_owned_staked_objects_summary(
+        &self,
+        owner: MgoAddress,
+    ) -> Result<OwnedStakesSummary, IndexerError> {
+        let stakes = self.get_staked_by_owner(owner).await?;
+        let system_state_summary = self.get_latest_mgo_system_state().await?;
+        Ok(OwnedStakesSummary::new(
+            owner,
+            system_state_summary.epoch,
+            stakes,
+            &system_state_summary.active_validators,
+        ))
+    }
+
     pub async fn get_delegated_stakes(
         &self,
         stakes: Vec<StakedMgo>,
@@ -310,6 +375,13 @@ impl GovernanceReadApiServer for GovernanceReadApiV2 {
         self.get_staked_by_owner(owner).await.map_err(Into::into)
     }
 
+    async fn get_owned_staked_objects_summary(
+        &self,
+        owner: MgoAddress,
+    ) -> RpcResult<OwnedStakesSummary> {
+        Ok(self.get_owned_staked_objects_summary(owner).await?)
+    }
+
     async fn get_committee_info(&self, epoch: Option<BigInt<u64>>) -> RpcResult<MgoCommittee> {
         let epoch = self.get_epoch_info(epoch.as_deref().copied()).await?;
         Ok(epoch.committee().map_err(IndexerError::from)?.into())
@@ -333,6 +405,16 @@ impl GovernanceReadApiServer for GovernanceReadApiV2 {
     async fn get_validators_apy(&self) -> RpcResult<ValidatorApys> {
         Ok(self.get_validators_apy().await?)
     }
+
+    async fn estimate_storage_rebate(
+        &self,
+        object_id: ObjectID,
+        new_contents_size: Option<BigInt<u64>>,
+    ) -> RpcResult<StorageRebateEstimate> {
+        Ok(self
+            .estimate_storage_rebate(object_id, new_contents_size.map(|size| *size))
+            .await?)
+    }
 }
 
 impl MgoRpcModule for GovernanceReadApiV2 {
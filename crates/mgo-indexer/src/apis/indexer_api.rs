@@ -13,8 +13,9 @@ use move_core_types::identifier::Identifier;
 use mgo_json_rpc::MgoRpcModule;
 use mgo_json_rpc_api::{cap_page_limit, IndexerApiClient, IndexerApiServer};
 use mgo_json_rpc_types::{
-    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, MgoObjectDataFilter,
-    MgoObjectResponse, MgoObjectResponseQuery, MgoTransactionBlockResponseQuery,
+    AppliedPagination, DynamicFieldPage, DynamicFieldsOptions, EventFilter, EventPage,
+    MgoObjectDataFilter, MgoObjectDataOptions, MgoObjectResponse, MgoObjectResponseQuery,
+    MgoTransactionBlockResponseQuery, NameServiceResolution, ObjectsPage, Page,
     TransactionBlocksPage, TransactionFilter,
 };
 use mgo_open_rpc::Module;
@@ -221,6 +222,9 @@ impl<S: IndexerStore> IndexerApi<S> {
                     )
                     .await
             }
+            Some(TransactionFilter::SignatureScheme(_)) => Err(IndexerError::NotSupportedError(
+                "SignatureScheme filter is only supported by the v2 indexer.".into(),
+            )),
             Some(TransactionFilter::TransactionKindIn(tx_kind_names)) => {
                 let indexer_seq_number = self
                     .state
@@ -268,6 +272,7 @@ impl<S: IndexerStore> IndexerApi<S> {
             data: mgo_tx_resp_vec,
             next_cursor,
             has_next_page,
+            applied: None,
         })
     }
 
@@ -317,6 +322,7 @@ impl<S: IndexerStore> IndexerApi<S> {
             data,
             next_cursor,
             has_next_page,
+            applied: None,
         })
     }
 }
@@ -332,6 +338,7 @@ where
         query: Option<MgoObjectResponseQuery>,
         cursor: Option<ObjectID>,
         limit: Option<usize>,
+        echo: Option<bool>,
     ) -> RpcResult<ObjectsPage> {
         if !self
             .migrated_methods
@@ -344,13 +351,23 @@ where
                 .start_timer();
             let owned_obj_resp = self
                 .fullnode
-                .get_owned_objects(address, query, cursor, limit)
+                .get_owned_objects(address, query, cursor, limit, echo)
                 .await;
             owned_obj_guard.stop_and_record();
             return owned_obj_resp;
         }
-        self.get_owned_objects_internal(address, query, cursor, limit)
-            .await
+        let applied = AppliedPagination::echo(
+            echo,
+            cursor,
+            cap_page_limit(limit),
+            false,
+            &(address, query.as_ref().and_then(|query| query.filter.as_ref())),
+        );
+        let mut page = self
+            .get_owned_objects_internal(address, query, cursor, limit)
+            .await?;
+        page.applied = applied;
+        Ok(page)
     }
     async fn query_transaction_blocks(
         &self,
@@ -358,6 +375,7 @@ where
         cursor: Option<TransactionDigest>,
         limit: Option<usize>,
         descending_order: Option<bool>,
+        echo: Option<bool>,
     ) -> RpcResult<TransactionBlocksPage> {
         if !self
             .migrated_methods
@@ -370,14 +388,23 @@ where
                 .start_timer();
             let query_tx_resp = self
                 .fullnode
-                .query_transaction_blocks(query, cursor, limit, descending_order)
+                .query_transaction_blocks(query, cursor, limit, descending_order, echo)
                 .await;
             query_tx_guard.stop_and_record();
             return query_tx_resp;
         }
-        Ok(self
+        let applied = AppliedPagination::echo(
+            echo,
+            cursor,
+            cap_page_limit(limit),
+            descending_order.unwrap_or_default(),
+            &query.filter,
+        );
+        let mut page = self
             .query_transaction_blocks_internal(query, cursor, limit, descending_order)
-            .await?)
+            .await?;
+        page.applied = applied;
+        Ok(page)
     }
 
     async fn query_events(
@@ -387,6 +414,7 @@ where
         cursor: Option<EventID>,
         limit: Option<usize>,
         descending_order: Option<bool>,
+        echo: Option<bool>,
     ) -> RpcResult<EventPage> {
         if !self.migrated_methods.contains(&"query_events".to_string()) {
             let query_events_guard = self
@@ -396,14 +424,23 @@ where
                 .start_timer();
             let query_events_resp = self
                 .fullnode
-                .query_events(query, cursor, limit, descending_order)
+                .query_events(query, cursor, limit, descending_order, echo)
                 .await;
             query_events_guard.stop_and_record();
             return query_events_resp;
         }
-        Ok(self
+        let applied = AppliedPagination::echo(
+            echo,
+            cursor,
+            cap_page_limit(limit),
+            descending_order.unwrap_or_default(),
+            &query,
+        );
+        let mut page = self
             .query_events_internal(query, cursor, limit, descending_order)
-            .await?)
+            .await?;
+        page.applied = applied;
+        Ok(page)
     }
 
     async fn get_dynamic_fields(
@@ -411,6 +448,8 @@ where
         parent_object_id: ObjectID,
         cursor: Option<ObjectID>,
         limit: Option<usize>,
+        options: Option<DynamicFieldsOptions>,
+        echo: Option<bool>,
     ) -> RpcResult<DynamicFieldPage> {
         let df_guard = self
             .state
@@ -419,7 +458,7 @@ where
             .start_timer();
         let df_resp = self
             .fullnode
-            .get_dynamic_fields(parent_object_id, cursor, limit)
+            .get_dynamic_fields(parent_object_id, cursor, limit, options, echo)
             .await;
         df_guard.stop_and_record();
         df_resp
@@ -429,6 +468,7 @@ where
         &self,
         parent_object_id: ObjectID,
         name: DynamicFieldName,
+        options: Option<MgoObjectDataOptions>,
     ) -> RpcResult<MgoObjectResponse> {
         let df_obj_guard = self
             .state
@@ -437,7 +477,7 @@ where
             .start_timer();
         let df_obj_resp = self
             .fullnode
-            .get_dynamic_field_object(parent_object_id, name)
+            .get_dynamic_field_object(parent_object_id, name, options)
             .await;
         df_obj_guard.stop_and_record();
         df_obj_resp
@@ -455,6 +495,10 @@ where
         Ok(())
     }
 
+    fn subscribe_checkpoint(&self, _sink: SubscriptionSink) -> SubscriptionResult {
+        Ok(())
+    }
+
     async fn resolve_name_service_address(&self, name: String) -> RpcResult<Option<MgoAddress>> {
         self.fullnode.resolve_name_service_address(name).await
     }
@@ -469,6 +513,20 @@ where
             .resolve_name_service_names(address, cursor, limit)
             .await
     }
+
+    async fn multi_resolve_name_service(
+        &self,
+        names: Vec<String>,
+    ) -> RpcResult<Vec<NameServiceResolution<MgoAddress>>> {
+        self.fullnode.multi_resolve_name_service(names).await
+    }
+
+    async fn multi_reverse_resolve(
+        &self,
+        addresses: Vec<MgoAddress>,
+    ) -> RpcResult<Vec<NameServiceResolution<String>>> {
+        self.fullnode.multi_reverse_resolve(addresses).await
+    }
 }
 
 impl<S> MgoRpcModule for IndexerApi<S>
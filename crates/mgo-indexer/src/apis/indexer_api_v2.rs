@@ -1,122 +1,90 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::apis::filter_labels::{event_filter_label, object_filter_label, transaction_filter_label};
+use crate::apis::SubscriptionHandler;
 use crate::indexer_reader::IndexerReader;
+use crate::metrics::QueryFilterMetrics;
 use crate::IndexerError;
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
-use jsonrpsee::types::SubscriptionEmptyError;
 use jsonrpsee::types::SubscriptionResult;
 use jsonrpsee::{RpcModule, SubscriptionSink};
-use mgo_json_rpc::name_service::{Domain, NameRecord, NameServiceConfig};
+use mgo_json_rpc::name_service::{
+    multi_resolve_names, multi_reverse_resolve_addresses, Domain, NameRecord, NameServiceConfig,
+    NAME_RECORD_SCAN_BATCH_SIZE, NAME_SERVICE_MAX_BULK_SIZE,
+};
+use mgo_json_rpc::read_api::DisplayRenderLimits;
 use mgo_json_rpc::MgoRpcModule;
 use mgo_json_rpc_api::{cap_page_limit, IndexerApiServer};
 use mgo_json_rpc_types::{
-    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, MgoObjectResponse,
-    MgoObjectResponseQuery, MgoTransactionBlockResponseQuery, TransactionBlocksPage,
-    TransactionFilter,
+    AppliedPagination, DynamicFieldPage, DynamicFieldsOptions, EventFilter, EventPage,
+    MgoObjectDataOptions, MgoObjectResponse, MgoObjectResponseQuery,
+    MgoTransactionBlockResponseQuery, NameServiceResolution, ObjectsPage, Page,
+    TransactionBlocksPage, TransactionFilter,
 };
 use mgo_open_rpc::Module;
 use mgo_types::base_types::{ObjectID, MgoAddress};
+use mgo_types::clock::Clock;
 use mgo_types::digests::TransactionDigest;
 use mgo_types::dynamic_field::{DynamicFieldName, Field};
-use mgo_types::error::MgoObjectResponseError;
 use mgo_types::event::EventID;
-use mgo_types::object::ObjectRead;
-use mgo_types::TypeTag;
+use mgo_types::object::Object;
+use mgo_types::MGO_CLOCK_OBJECT_ID;
+use prometheus::Registry;
 
+/// JSON-RPC front end over the paginated [`IndexerReader`] queries. Services that only need
+/// to query the indexer DB can use [`crate::ReadOnlyIndexer`] instead of running the server.
 pub(crate) struct IndexerApiV2 {
     inner: IndexerReader,
     name_service_config: NameServiceConfig,
+    display_limits: DisplayRenderLimits,
+    subscription_handler: SubscriptionHandler,
+    metrics: QueryFilterMetrics,
 }
 
 impl IndexerApiV2 {
-    pub fn new(inner: IndexerReader) -> Self {
+    pub fn new(
+        inner: IndexerReader,
+        display_limits: DisplayRenderLimits,
+        subscription_handler: SubscriptionHandler,
+    ) -> Self {
         Self {
             inner,
             // TODO allow configuring for other networks
             name_service_config: Default::default(),
+            display_limits,
+            subscription_handler,
+            metrics: QueryFilterMetrics::new(&Registry::default()),
         }
     }
 
-    async fn get_owned_objects_internal(
-        &self,
-        address: MgoAddress,
-        query: Option<MgoObjectResponseQuery>,
-        cursor: Option<ObjectID>,
-        limit: usize,
-    ) -> RpcResult<ObjectsPage> {
-        let MgoObjectResponseQuery { filter, options } = query.unwrap_or_default();
-        let options = options.unwrap_or_default();
-        let objects = self
-            .inner
-            .get_owned_objects_in_blocking_task(address, filter, cursor, limit + 1)
-            .await?;
-        let mut objects = self
-            .inner
-            .spawn_blocking(move |this| {
-                objects
-                    .into_iter()
-                    .map(|object| object.try_into_object_read(&this))
-                    .collect::<Result<Vec<_>, _>>()
-            })
-            .await?;
-        let has_next_page = objects.len() > limit;
-        objects.truncate(limit);
+    /// Registers the query metrics of the API in `registry`.
+    pub fn with_metrics(mut self, registry: &Registry) -> Self {
+        self.metrics = QueryFilterMetrics::new(registry);
+        self
+    }
 
-        let next_cursor = objects.last().map(|o_read| o_read.object_id());
-        let mut parallel_tasks = vec![];
-        for o in objects {
-            let inner_clone = self.inner.clone();
-            let options = options.clone();
-            parallel_tasks.push(tokio::task::spawn(async move {
-                match o {
-                    ObjectRead::NotExists(id) => Ok(MgoObjectResponse::new_with_error(
-                        MgoObjectResponseError::NotExists { object_id: id },
-                    )),
-                    ObjectRead::Exists(object_ref, o, layout) => {
-                        if options.show_display {
-                            match inner_clone.get_display_fields(&o, &layout).await {
-                                Ok(rendered_fields) => Ok(MgoObjectResponse::new_with_data(
-                                    (object_ref, o, layout, options, Some(rendered_fields))
-                                        .try_into()?,
-                                )),
-                                Err(e) => Ok(MgoObjectResponse::new(
-                                    Some((object_ref, o, layout, options, None).try_into()?),
-                                    Some(MgoObjectResponseError::DisplayError {
-                                        error: e.to_string(),
-                                    }),
-                                )),
-                            }
-                        } else {
-                            Ok(MgoObjectResponse::new_with_data(
-                                (object_ref, o, layout, options, None).try_into()?,
-                            ))
-                        }
-                    }
-                    ObjectRead::Deleted((object_id, version, digest)) => Ok(
-                        MgoObjectResponse::new_with_error(MgoObjectResponseError::Deleted {
-                            object_id,
-                            version,
-                            digest,
-                        }),
-                    ),
-                }
-            }));
+    /// Records the latency of a query of `method` called with a filter labelled `filter`,
+    /// until the returned closure is called with the number of items it returned.
+    fn observe_query(
+        &self,
+        method: &'static str,
+        filter: &'static str,
+    ) -> impl FnOnce(usize) + Send {
+        let timer = self
+            .metrics
+            .query_latency
+            .with_label_values(&[method, filter])
+            .start_timer();
+        let result_size = self
+            .metrics
+            .query_result_size
+            .with_label_values(&[method, filter]);
+        move |size| {
+            timer.observe_duration();
+            result_size.observe(size as f64);
         }
-        let data = futures::future::join_all(parallel_tasks)
-            .await
-            .into_iter()
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e: tokio::task::JoinError| anyhow::anyhow!(e))?
-            .into_iter()
-            .collect::<Result<Vec<_>, anyhow::Error>>()?;
-
-        Ok(Page {
-            data,
-            next_cursor,
-            has_next_page,
-        })
     }
 }
 
@@ -128,13 +96,26 @@ impl IndexerApiServer for IndexerApiV2 {
         query: Option<MgoObjectResponseQuery>,
         cursor: Option<ObjectID>,
         limit: Option<usize>,
+        echo: Option<bool>,
     ) -> RpcResult<ObjectsPage> {
-        let limit = cap_page_limit(limit);
-        if limit == 0 {
-            return Ok(ObjectsPage::empty());
-        }
-        self.get_owned_objects_internal(address, query, cursor, limit)
-            .await
+        let applied = AppliedPagination::echo(
+            echo,
+            cursor,
+            cap_page_limit(limit),
+            false,
+            &(address, query.as_ref().and_then(|query| query.filter.as_ref())),
+        );
+        let observe = self.observe_query(
+            "get_owned_objects",
+            object_filter_label(query.as_ref().and_then(|query| query.filter.as_ref())),
+        );
+        let mut page = self
+            .inner
+            .get_owned_objects_page(address, query, cursor, limit, &self.display_limits)
+            .await?;
+        observe(page.data.len());
+        page.applied = applied;
+        Ok(page)
     }
 
     async fn query_transaction_blocks(
@@ -143,31 +124,26 @@ impl IndexerApiServer for IndexerApiV2 {
         cursor: Option<TransactionDigest>,
         limit: Option<usize>,
         descending_order: Option<bool>,
+        echo: Option<bool>,
     ) -> RpcResult<TransactionBlocksPage> {
-        let limit = cap_page_limit(limit);
-        if limit == 0 {
-            return Ok(TransactionBlocksPage::empty());
-        }
-        let mut results = self
+        let applied = AppliedPagination::echo(
+            echo,
+            cursor,
+            cap_page_limit(limit),
+            descending_order.unwrap_or_default(),
+            &query.filter,
+        );
+        let observe = self.observe_query(
+            "query_transaction_blocks",
+            transaction_filter_label(query.filter.as_ref()),
+        );
+        let mut page = self
             .inner
-            .query_transaction_blocks_in_blocking_task(
-                query.filter,
-                query.options.unwrap_or_default(),
-                cursor,
-                limit + 1,
-                descending_order.unwrap_or(false),
-            )
-            .await
-            .map_err(|e: IndexerError| anyhow::anyhow!(e))?;
-
-        let has_next_page = results.len() > limit;
-        results.truncate(limit);
-        let next_cursor = results.last().map(|o| o.digest);
-        Ok(Page {
-            data: results,
-            next_cursor,
-            has_next_page,
-        })
+            .query_transaction_blocks_page(query, cursor, limit, descending_order)
+            .await?;
+        observe(page.data.len());
+        page.applied = applied;
+        Ok(page)
     }
 
     async fn query_events(
@@ -177,25 +153,23 @@ impl IndexerApiServer for IndexerApiV2 {
         cursor: Option<EventID>,
         limit: Option<usize>,
         descending_order: Option<bool>,
+        echo: Option<bool>,
     ) -> RpcResult<EventPage> {
-        let limit = cap_page_limit(limit);
-        if limit == 0 {
-            return Ok(EventPage::empty());
-        }
-        let descending_order = descending_order.unwrap_or(false);
-        let mut results = self
+        let applied = AppliedPagination::echo(
+            echo,
+            cursor,
+            cap_page_limit(limit),
+            descending_order.unwrap_or_default(),
+            &query,
+        );
+        let observe = self.observe_query("query_events", event_filter_label(&query));
+        let mut page = self
             .inner
-            .query_events_in_blocking_task(query, cursor, limit + 1, descending_order)
+            .query_events_page(query, cursor, limit, descending_order)
             .await?;
-
-        let has_next_page = results.len() > limit;
-        results.truncate(limit);
-        let next_cursor = results.last().map(|o| o.id);
-        Ok(Page {
-            data: results,
-            next_cursor,
-            has_next_page,
-        })
+        observe(page.data.len());
+        page.applied = applied;
+        Ok(page)
     }
 
     async fn get_dynamic_fields(
@@ -203,163 +177,182 @@ impl IndexerApiServer for IndexerApiV2 {
         parent_object_id: ObjectID,
         cursor: Option<ObjectID>,
         limit: Option<usize>,
+        options: Option<DynamicFieldsOptions>,
+        echo: Option<bool>,
     ) -> RpcResult<DynamicFieldPage> {
-        let limit = cap_page_limit(limit);
-        if limit == 0 {
-            return Ok(DynamicFieldPage::empty());
-        }
-        let mut results = self
+        let applied = AppliedPagination::echo(
+            echo,
+            cursor,
+            cap_page_limit(limit),
+            false,
+            &(
+                parent_object_id,
+                options.as_ref().and_then(|options| options.name_type.as_ref()),
+            ),
+        );
+        let mut page = self
             .inner
-            .get_dynamic_fields_in_blocking_task(parent_object_id, cursor, limit + 1)
+            .get_dynamic_fields_page(parent_object_id, cursor, limit, options)
             .await?;
-
-        let has_next_page = results.len() > limit;
-        results.truncate(limit);
-        let next_cursor = results.last().map(|o| o.object_id);
-        Ok(Page {
-            data: results,
-            next_cursor,
-            has_next_page,
-        })
+        page.applied = applied;
+        Ok(page)
     }
 
     async fn get_dynamic_field_object(
         &self,
         parent_object_id: ObjectID,
         name: DynamicFieldName,
+        options: Option<MgoObjectDataOptions>,
     ) -> RpcResult<MgoObjectResponse> {
-        let name_bcs_value = self
-            .inner
-            .bcs_name_from_dynamic_field_name_in_blocking_task(&name)
-            .await?;
-
-        // Try as Dynamic Field
-        let id = mgo_types::dynamic_field::derive_dynamic_field_id(
-            parent_object_id,
-            &name.type_,
-            &name_bcs_value,
-        )
-        .expect("deriving dynamic field id can't fail");
-
-        let options = mgo_json_rpc_types::MgoObjectDataOptions::full_content();
-        match self.inner.get_object_read_in_blocking_task(id).await? {
-            mgo_types::object::ObjectRead::NotExists(_)
-            | mgo_types::object::ObjectRead::Deleted(_) => {}
-            mgo_types::object::ObjectRead::Exists(object_ref, o, layout) => {
-                return Ok(MgoObjectResponse::new_with_data(
-                    (object_ref, o, layout, options, None).try_into()?,
-                ));
-            }
-        }
-
-        // Try as Dynamic Field Object
-        let dynamic_object_field_struct =
-            mgo_types::dynamic_field::DynamicFieldInfo::dynamic_object_field_wrapper(name.type_);
-        let dynamic_object_field_type = TypeTag::Struct(Box::new(dynamic_object_field_struct));
-        let dynamic_object_field_id = mgo_types::dynamic_field::derive_dynamic_field_id(
-            parent_object_id,
-            &dynamic_object_field_type,
-            &name_bcs_value,
-        )
-        .expect("deriving dynamic field id can't fail");
-        match self
+        Ok(self
             .inner
-            .get_object_read_in_blocking_task(dynamic_object_field_id)
-            .await?
-        {
-            mgo_types::object::ObjectRead::NotExists(_)
-            | mgo_types::object::ObjectRead::Deleted(_) => {}
-            mgo_types::object::ObjectRead::Exists(object_ref, o, layout) => {
-                return Ok(MgoObjectResponse::new_with_data(
-                    (object_ref, o, layout, options, None).try_into()?,
-                ));
-            }
-        }
-
-        Ok(MgoObjectResponse::new_with_error(
-            mgo_types::error::MgoObjectResponseError::DynamicFieldNotFound { parent_object_id },
-        ))
+            .get_dynamic_field_object(parent_object_id, name, options, &self.display_limits)
+            .await?)
     }
 
-    fn subscribe_event(&self, _sink: SubscriptionSink, _filter: EventFilter) -> SubscriptionResult {
-        Err(SubscriptionEmptyError)
+    fn subscribe_event(&self, sink: SubscriptionSink, filter: EventFilter) -> SubscriptionResult {
+        self.subscription_handler.subscribe_events(sink, filter);
+        Ok(())
     }
 
     fn subscribe_transaction(
         &self,
-        _sink: SubscriptionSink,
-        _filter: TransactionFilter,
+        sink: SubscriptionSink,
+        filter: TransactionFilter,
     ) -> SubscriptionResult {
-        Err(SubscriptionEmptyError)
+        self.subscription_handler
+            .subscribe_transactions(sink, filter);
+        Ok(())
+    }
+
+    fn subscribe_checkpoint(&self, _sink: SubscriptionSink) -> SubscriptionResult {
+        // Checkpoint notifications are only streamed by fullnodes.
+        Ok(())
     }
 
     async fn resolve_name_service_address(&self, name: String) -> RpcResult<Option<MgoAddress>> {
-        let domain = name.parse::<Domain>().map_err(|e| {
+        name.parse::<Domain>().map_err(|e| {
             IndexerError::InvalidArgumentError(format!(
                 "Failed to parse NameService Domain with error: {:?}",
                 e
             ))
         })?;
 
-        let record_id = self.name_service_config.record_field_id(&domain);
-
-        let field_record_object = match self.inner.get_object_in_blocking_task(record_id).await? {
-            Some(o) => o,
-            None => return Ok(None),
-        };
-
-        let record = field_record_object
-            .to_rust::<Field<Domain, NameRecord>>()
-            .ok_or_else(|| {
-                IndexerError::PersistentStorageDataCorruptionError(format!(
-                    "Malformed Object {record_id}"
-                ))
-            })?
-            .value;
-
-        Ok(record.target_address)
+        let resolution = multi_resolve_names(&self.name_service_config, &[name], |ids| {
+            self.inner.multi_get_objects_ordered_in_blocking_task(ids)
+        })
+        .await?
+        .remove(0);
+        match resolution.error {
+            Some(e) => Err(IndexerError::PersistentStorageDataCorruptionError(e).into()),
+            None => Ok(resolution.data),
+        }
     }
 
     async fn resolve_name_service_names(
         &self,
         address: MgoAddress,
-        _cursor: Option<ObjectID>,
-        _limit: Option<usize>,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
     ) -> RpcResult<Page<String, ObjectID>> {
-        let reverse_record_id = self
-            .name_service_config
-            .reverse_record_field_id(address.as_ref());
-
-        let field_reverse_record_object = match self
+        let limit = cap_page_limit(limit);
+        let now_ms = self
             .inner
-            .get_object_in_blocking_task(reverse_record_id)
+            .get_object_in_blocking_task(MGO_CLOCK_OBJECT_ID)
             .await?
-        {
-            Some(o) => o,
-            None => {
-                return Ok(Page {
-                    data: vec![],
-                    next_cursor: None,
-                    has_next_page: false,
-                })
-            }
-        };
-
-        let domain = field_reverse_record_object
-            .to_rust::<Field<MgoAddress, Domain>>()
+            .and_then(|o| o.to_rust::<Clock>())
             .ok_or_else(|| {
-                IndexerError::PersistentStorageDataCorruptionError(format!(
-                    "Malformed Object {reverse_record_id}"
-                ))
+                IndexerError::PersistentStorageDataCorruptionError(
+                    "Missing clock object".to_string(),
+                )
             })?
-            .value;
+            .timestamp_ms;
 
+        // Records are walked in field object ID order, keeping those currently pointing to
+        // `address`, until one more than a page is found.
+        let mut names = vec![];
+        let mut scan_cursor = cursor;
+        'scan: loop {
+            let fields = self
+                .inner
+                .get_dynamic_fields_raw_in_blocking_task(
+                    self.name_service_config.registry_id,
+                    scan_cursor,
+                    NAME_RECORD_SCAN_BATCH_SIZE,
+                )
+                .await?;
+            let exhausted = fields.len() < NAME_RECORD_SCAN_BATCH_SIZE;
+            for stored_object in fields {
+                let object = Object::try_from(stored_object)?;
+                let field_id = object.id();
+                scan_cursor = Some(field_id);
+                let field = object
+                    .to_rust::<Field<Domain, NameRecord>>()
+                    .ok_or_else(|| {
+                        IndexerError::PersistentStorageDataCorruptionError(format!(
+                            "Malformed Object {field_id}"
+                        ))
+                    })?;
+                if field.value.target_address == Some(address) && !field.value.is_expired(now_ms) {
+                    names.push((field.name.to_string(), field_id));
+                    if names.len() > limit {
+                        break 'scan;
+                    }
+                }
+            }
+            if exhausted {
+                break;
+            }
+        }
+
+        let has_next_page = names.len() > limit;
+        names.truncate(limit);
+        let next_cursor = names.last().map_or(cursor, |(_, id)| Some(*id));
         Ok(Page {
-            data: vec![domain.to_string()],
-            next_cursor: None,
-            has_next_page: false,
+            data: names.into_iter().map(|(name, _)| name).collect(),
+            next_cursor,
+            has_next_page,
+            applied: None,
         })
     }
+
+    async fn multi_resolve_name_service(
+        &self,
+        names: Vec<String>,
+    ) -> RpcResult<Vec<NameServiceResolution<MgoAddress>>> {
+        if names.len() > NAME_SERVICE_MAX_BULK_SIZE {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Cannot resolve more than {NAME_SERVICE_MAX_BULK_SIZE} names at once, got {}",
+                names.len()
+            ))
+            .into());
+        }
+        Ok(
+            multi_resolve_names(&self.name_service_config, &names, |ids| {
+                self.inner.multi_get_objects_ordered_in_blocking_task(ids)
+            })
+            .await?,
+        )
+    }
+
+    async fn multi_reverse_resolve(
+        &self,
+        addresses: Vec<MgoAddress>,
+    ) -> RpcResult<Vec<NameServiceResolution<String>>> {
+        if addresses.len() > NAME_SERVICE_MAX_BULK_SIZE {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Cannot resolve over {NAME_SERVICE_MAX_BULK_SIZE} addresses at once, got {}",
+                addresses.len()
+            ))
+            .into());
+        }
+        Ok(
+            multi_reverse_resolve_addresses(&self.name_service_config, &addresses, |ids| {
+                self.inner.multi_get_objects_ordered_in_blocking_task(ids)
+            })
+            .await?,
+        )
+    }
 }
 
 impl MgoRpcModule for IndexerApiV2 {
@@ -370,4 +363,14 @@ impl MgoRpcModule for IndexerApiV2 {
     fn rpc_doc_module() -> Module {
         mgo_json_rpc_api::IndexerApiOpenRpc::module_doc()
     }
+
+    /// Filtered scans cost the database far more than the point reads weighing 1.
+    fn rpc_method_weights() -> Vec<(&'static str, u32)> {
+        vec![
+            ("mgox_queryEvents", 10),
+            ("mgox_queryTransactionBlocks", 10),
+            ("mgox_getOwnedObjects", 2),
+            ("mgox_getDynamicFields", 2),
+        ]
+    }
 }
@@ -1,6 +1,7 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+pub(crate) use chain_state_cache::ChainStateCache;
 pub(crate) use coin_api::CoinReadApi;
 pub(crate) use coin_api_v2::CoinReadApiV2;
 pub(crate) use extended_api::ExtendedApi;
@@ -13,22 +14,29 @@ pub(crate) use move_utils::MoveUtilsApi;
 pub(crate) use move_utils_v2::MoveUtilsApiV2;
 pub(crate) use read_api::ReadApi;
 pub(crate) use read_api_v2::ReadApiV2;
+pub(crate) use subscription_handler::SubscriptionHandler;
 pub(crate) use transaction_builder_api::TransactionBuilderApi;
 pub(crate) use transaction_builder_api_v2::TransactionBuilderApiV2;
 pub(crate) use write_api::WriteApi;
+pub(crate) use write_api_v2::WriteApiV2;
 
+mod chain_state_cache;
 mod coin_api;
 mod coin_api_v2;
 mod extended_api;
 mod extended_api_v2;
+mod filter_labels;
 mod governance_api;
 mod governance_api_v2;
 mod indexer_api;
 mod indexer_api_v2;
+mod module_debug_info;
+mod package_verification;
 mod move_utils;
 mod move_utils_v2;
 mod read_api;
 mod read_api_v2;
+mod subscription_handler;
 mod transaction_builder_api;
 mod transaction_builder_api_v2;
 mod write_api;
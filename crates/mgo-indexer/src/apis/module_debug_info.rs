@@ -0,0 +1,441 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Structured disassembly of published Move modules, and resolution of abort locations to
+//! functions and, when a source map was published, to source locations.
+//!
+//! Source maps are published by placing a package's build output under the reader's source
+//! map directory, named after the package ID:
+//!
+//! ```text
+//! <source_maps_dir>/<package_id>/source_maps/<module>.mvsm
+//! <source_maps_dir>/<package_id>/sources/<module>.move
+//! ```
+//!
+//! which mirrors the `source_maps` and `sources` directories the Move compiler writes to
+//! `build/<package_name>`. The source file is optional and only needed for line numbers.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use cached::{Cached, SizedCache};
+use move_binary_format::access::ModuleAccess;
+use move_binary_format::file_format::{
+    Bytecode, FunctionDefinitionIndex, FunctionHandleIndex, Visibility,
+};
+use move_binary_format::CompiledModule;
+use move_bytecode_source_map::source_map::SourceMap;
+use move_bytecode_source_map::utils::source_map_from_file;
+use tracing::warn;
+
+use mgo_json_rpc_types::{
+    AbortLocation, DisassembledInstruction, FunctionDisassembly, ModuleDisassembly, SourceLocation,
+};
+use mgo_types::base_types::ObjectID;
+use mgo_types::move_package::MovePackage;
+
+use crate::errors::IndexerError;
+use crate::indexer_reader::IndexerReader;
+
+/// Number of packages whose debug info is kept in memory. Packages are immutable, so entries
+/// never go stale and are only evicted to bound memory.
+const PACKAGE_CACHE_SIZE: usize = 256;
+
+const SOURCE_MAPS_DIR: &str = "source_maps";
+const SOURCES_DIR: &str = "sources";
+const SOURCE_MAP_EXTENSION: &str = "mvsm";
+const SOURCE_EXTENSION: &str = "move";
+
+#[derive(Clone)]
+pub(crate) struct ModuleDebugInfoCache {
+    source_maps_dir: Option<PathBuf>,
+    packages: Arc<Mutex<SizedCache<ObjectID, Arc<PackageDebugInfo>>>>,
+}
+
+impl ModuleDebugInfoCache {
+    pub fn new(source_maps_dir: Option<PathBuf>) -> Self {
+        Self {
+            source_maps_dir,
+            packages: Arc::new(Mutex::new(SizedCache::with_size(PACKAGE_CACHE_SIZE))),
+        }
+    }
+
+    pub async fn get(
+        &self,
+        reader: &IndexerReader,
+        package_id: ObjectID,
+    ) -> Result<Arc<PackageDebugInfo>, IndexerError> {
+        if let Some(info) = self.packages.lock().unwrap().cache_get(&package_id) {
+            return Ok(info.clone());
+        }
+        let package = reader
+            .get_package_in_blocking_task(package_id)
+            .await?
+            .ok_or_else(|| {
+                IndexerError::InvalidArgumentError(format!("Package {package_id} not found"))
+            })?;
+        let source_maps_dir = self.source_maps_dir.clone();
+        let info = tokio::task::spawn_blocking(move || {
+            PackageDebugInfo::new(&package, source_maps_dir.as_deref())
+        })
+        .await??;
+        let info = Arc::new(info);
+        self.packages
+            .lock()
+            .unwrap()
+            .cache_set(package_id, info.clone());
+        Ok(info)
+    }
+}
+
+pub(crate) struct PackageDebugInfo {
+    package_id: ObjectID,
+    modules: BTreeMap<String, ModuleDebugInfo>,
+}
+
+struct ModuleDebugInfo {
+    functions: Vec<FunctionDisassembly>,
+    source_map: Option<SourceMap>,
+    /// Name and contents of the module's source file, if it matches the source map.
+    source: Option<(String, String)>,
+}
+
+impl PackageDebugInfo {
+    fn new(package: &MovePackage, source_maps_dir: Option<&Path>) -> Result<Self, IndexerError> {
+        let package_id = package.id();
+        let package_dir = source_maps_dir.map(|dir| dir.join(package_id.to_string()));
+        let mut modules = BTreeMap::new();
+        for (name, bytes) in package.serialized_module_map() {
+            let module = CompiledModule::deserialize_with_defaults(bytes).map_err(|e| {
+                IndexerError::ModuleResolutionError(format!(
+                    "Failed to deserialize module {package_id}::{name}: {e}"
+                ))
+            })?;
+            let (source_map, source) = match &package_dir {
+                Some(dir) => load_source_map(dir, name),
+                None => (None, None),
+            };
+            modules.insert(
+                name.clone(),
+                ModuleDebugInfo {
+                    functions: disassemble_functions(&module),
+                    source_map,
+                    source,
+                },
+            );
+        }
+        Ok(Self {
+            package_id,
+            modules,
+        })
+    }
+
+    fn module(&self, module: &str) -> Result<&ModuleDebugInfo, IndexerError> {
+        self.modules.get(module).ok_or_else(|| {
+            IndexerError::InvalidArgumentError(format!(
+                "Module {module} not found in package {}, available modules are {:?}",
+                self.package_id,
+                self.modules.keys().collect::<Vec<_>>()
+            ))
+        })
+    }
+
+    pub fn disassembly(&self, module: &str) -> Result<ModuleDisassembly, IndexerError> {
+        let info = self.module(module)?;
+        Ok(ModuleDisassembly {
+            package: self.package_id,
+            module: module.to_string(),
+            functions: info.functions.clone(),
+            has_source_map: info.source_map.is_some(),
+        })
+    }
+
+    pub fn resolve_abort_location(
+        &self,
+        module: &str,
+        function_index: u16,
+        code_offset: u16,
+    ) -> Result<AbortLocation, IndexerError> {
+        let info = self.module(module)?;
+        let function = info.functions.get(function_index as usize).ok_or_else(|| {
+            IndexerError::InvalidArgumentError(format!(
+                "Function index {function_index} out of range, module {module} defines {} functions",
+                info.functions.len()
+            ))
+        })?;
+        let instruction = function
+            .instructions
+            .get(code_offset as usize)
+            .ok_or_else(|| {
+                IndexerError::InvalidArgumentError(format!(
+                    "Code offset {code_offset} out of range, function {} has {} instructions",
+                    function.name,
+                    function.instructions.len()
+                ))
+            })?;
+        let source = info.source_map.as_ref().and_then(|source_map| {
+            let loc = source_map
+                .get_code_location(FunctionDefinitionIndex(function_index), code_offset)
+                .ok()?;
+            let (file, line, column) = match &info.source {
+                Some((file, contents)) => {
+                    let (line, column) = line_and_column(contents, loc.start() as usize);
+                    (Some(file.clone()), Some(line), Some(column))
+                }
+                None => (None, None, None),
+            };
+            Some(SourceLocation {
+                file,
+                start_byte: loc.start(),
+                end_byte: loc.end(),
+                line,
+                column,
+            })
+        });
+        Ok(AbortLocation {
+            package: self.package_id,
+            module: module.to_string(),
+            function_index,
+            function_name: function.name.clone(),
+            code_offset,
+            instruction: instruction.instruction.clone(),
+            source,
+        })
+    }
+}
+
+fn disassemble_functions(module: &CompiledModule) -> Vec<FunctionDisassembly> {
+    module
+        .function_defs()
+        .iter()
+        .enumerate()
+        .map(|(index, def)| {
+            let handle = module.function_handle_at(def.function);
+            let instructions = def
+                .code
+                .as_ref()
+                .map(|code| {
+                    code.code
+                        .iter()
+                        .enumerate()
+                        .map(|(offset, bytecode)| DisassembledInstruction {
+                            offset: offset as u16,
+                            instruction: format_instruction(module, bytecode),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            FunctionDisassembly {
+                function_index: index as u16,
+                name: module.identifier_at(handle.name).to_string(),
+                visibility: match def.visibility {
+                    Visibility::Public => "public",
+                    Visibility::Friend => "friend",
+                    Visibility::Private => "private",
+                }
+                .to_string(),
+                is_entry: def.is_entry,
+                is_native: def.code.is_none(),
+                instructions,
+            }
+        })
+        .collect()
+}
+
+/// Formats an instruction, naming the callee of calls instead of its handle index.
+fn format_instruction(module: &CompiledModule, bytecode: &Bytecode) -> String {
+    match bytecode {
+        Bytecode::Call(handle) => format!("Call({})", function_name(module, *handle)),
+        Bytecode::CallGeneric(instantiation) => {
+            let handle = module.function_instantiation_at(*instantiation).handle;
+            format!("CallGeneric({})", function_name(module, handle))
+        }
+        other => format!("{other:?}"),
+    }
+}
+
+fn function_name(module: &CompiledModule, handle: FunctionHandleIndex) -> String {
+    let handle = module.function_handle_at(handle);
+    let module_handle = module.module_handle_at(handle.module);
+    format!(
+        "{}::{}::{}",
+        module
+            .address_identifier_at(module_handle.address)
+            .to_hex_literal(),
+        module.identifier_at(module_handle.name),
+        module.identifier_at(handle.name)
+    )
+}
+
+/// Loads the source map of `module` and, if it matches, its source file. Missing or invalid
+/// files degrade to bytecode-level info rather than failing the request.
+fn load_source_map(
+    package_dir: &Path,
+    module: &str,
+) -> (Option<SourceMap>, Option<(String, String)>) {
+    let source_map_path = package_dir
+        .join(SOURCE_MAPS_DIR)
+        .join(module)
+        .with_extension(SOURCE_MAP_EXTENSION);
+    if !source_map_path.exists() {
+        return (None, None);
+    }
+    let source_map = match source_map_from_file(&source_map_path) {
+        Ok(source_map) => source_map,
+        Err(e) => {
+            warn!(
+                "Ignoring unreadable source map {}: {e}",
+                source_map_path.display()
+            );
+            return (None, None);
+        }
+    };
+
+    let source_path = package_dir
+        .join(SOURCES_DIR)
+        .join(module)
+        .with_extension(SOURCE_EXTENSION);
+    let source = std::fs::read_to_string(&source_path)
+        .ok()
+        .filter(|contents| source_map.check(contents))
+        .map(|contents| {
+            let file = source_path
+                .file_name()
+                .map(|f| f.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            (file, contents)
+        });
+    (Some(source_map), source)
+}
+
+/// One-based line and column of the byte at `offset` in `contents`.
+fn line_and_column(contents: &str, offset: usize) -> (u32, u32) {
+    let prefix = &contents.as_bytes()[..offset.min(contents.len())];
+    let line = prefix.iter().filter(|b| **b == b'\n').count() + 1;
+    let line_start = prefix
+        .iter()
+        .rposition(|b| *b == b'\n')
+        .map_or(0, |i| i + 1);
+    (line as u32, (prefix.len() - line_start + 1) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use mgo_move_build::{BuildConfig, CompiledPackage};
+    use mgo_types::object::OBJECT_START_VERSION;
+    use mgo_types::MGO_FRAMEWORK_PACKAGE_ID;
+
+    use super::*;
+
+    fn build_basics() -> CompiledPackage {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../mgo_programmability/examples/basics");
+        BuildConfig::new_for_testing().build(path).unwrap()
+    }
+
+    /// Lays out the source maps and sources of the root package like a published build.
+    fn write_source_maps(compiled: &CompiledPackage, package_dir: &Path) {
+        std::fs::create_dir_all(package_dir.join(SOURCE_MAPS_DIR)).unwrap();
+        std::fs::create_dir_all(package_dir.join(SOURCES_DIR)).unwrap();
+        for unit in &compiled.package.root_compiled_units {
+            let name = unit.unit.name.to_string();
+            std::fs::write(
+                package_dir
+                    .join(SOURCE_MAPS_DIR)
+                    .join(&name)
+                    .with_extension(SOURCE_MAP_EXTENSION),
+                unit.unit.serialize_source_map(),
+            )
+            .unwrap();
+            std::fs::copy(
+                &unit.source_path,
+                package_dir
+                    .join(SOURCES_DIR)
+                    .join(&name)
+                    .with_extension(SOURCE_EXTENSION),
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_resolve_abort_location_with_source_map() {
+        let compiled = build_basics();
+        let modules: Vec<_> = compiled.get_modules().cloned().collect();
+        let package = MovePackage::new_system(OBJECT_START_VERSION, &modules, []);
+        let dir = tempfile::tempdir().unwrap();
+        write_source_maps(&compiled, &dir.path().join(package.id().to_string()));
+
+        let info = PackageDebugInfo::new(&package, Some(dir.path())).unwrap();
+        let disassembly = info.disassembly("counter").unwrap();
+        assert!(disassembly.has_source_map);
+        let set_value = disassembly
+            .functions
+            .iter()
+            .find(|f| f.name == "set_value")
+            .unwrap();
+        assert_eq!(set_value.visibility, "public");
+        assert!(set_value.is_entry);
+        assert!(set_value
+            .instructions
+            .iter()
+            .any(|i| i.instruction.ends_with("::tx_context::sender)")));
+        let abort = set_value
+            .instructions
+            .iter()
+            .find(|i| i.instruction == "Abort")
+            .unwrap();
+
+        let location = info
+            .resolve_abort_location("counter", set_value.function_index, abort.offset)
+            .unwrap();
+        assert_eq!(location.function_name, "set_value");
+        assert_eq!(location.instruction, "Abort");
+        let source = location.source.unwrap();
+        assert_eq!(source.file.as_deref(), Some("counter.move"));
+        let contents = std::fs::read_to_string(
+            dir.path()
+                .join(package.id().to_string())
+                .join("sources/counter.move"),
+        )
+        .unwrap();
+        let line = contents
+            .lines()
+            .nth(source.line.unwrap() as usize - 1)
+            .unwrap();
+        assert!(line.contains("assert!(counter.owner == tx_context::sender(ctx), 0)"));
+
+        // Out of range locations are rejected rather than resolved to the nearest instruction.
+        assert!(info
+            .resolve_abort_location("counter", set_value.function_index, u16::MAX)
+            .is_err());
+        assert!(info.resolve_abort_location("counter", u16::MAX, 0).is_err());
+        assert!(info.disassembly("no_such_module").is_err());
+    }
+
+    #[test]
+    fn test_disassembly_without_source_map() {
+        let compiled = build_basics();
+        let modules: Vec<_> = compiled.get_mgo_framework_modules().cloned().collect();
+        let package = MovePackage::new_system(OBJECT_START_VERSION, &modules, []);
+        assert_eq!(package.id(), MGO_FRAMEWORK_PACKAGE_ID);
+        let dir = tempfile::tempdir().unwrap();
+
+        let info = PackageDebugInfo::new(&package, Some(dir.path())).unwrap();
+        let disassembly = info.disassembly("coin").unwrap();
+        assert!(!disassembly.has_source_map);
+        let split = disassembly
+            .functions
+            .iter()
+            .find(|f| f.name == "split")
+            .unwrap();
+        let location = info
+            .resolve_abort_location("coin", split.function_index, 0)
+            .unwrap();
+        assert_eq!(location.function_name, "split");
+        assert!(location.source.is_none());
+    }
+}
@@ -0,0 +1,330 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Comparison of a local build of a package to a published version of it, for
+//! `mgox_verifyPackage`.
+//!
+//! Modules are built with `0x0` as their own address, which publishing replaces with the ID of
+//! the first version of the package. The compiler may also serialize them at another bytecode
+//! version or attach metadata to them. None of these change what a module does, so they are
+//! normalized away before modules are compared. Modules are deserialized and compared one pair
+//! at a time, so that a large package is never held twice in memory.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use fastcrypto::encoding::Base64;
+use move_binary_format::access::ModuleAccess;
+use move_binary_format::file_format::AbilitySet;
+use move_binary_format::normalized::{Function, FunctionRef, Type};
+use move_binary_format::CompiledModule;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+
+use mgo_json_rpc_types::{
+    ModuleVerification, ModuleVerificationStatus, PackageVerification, PackageVersion,
+};
+use mgo_types::base_types::ObjectID;
+use mgo_types::move_package::MovePackage;
+
+use crate::errors::IndexerError;
+use crate::indexer_reader::IndexerReader;
+
+/// Compares `modules`, the Base64 bytecode of a local build, to the published package
+/// `package_id`, and lists the versions of the package.
+pub(crate) fn verify_package(
+    reader: &IndexerReader,
+    package_id: ObjectID,
+    modules: &[Base64],
+) -> Result<PackageVerification, IndexerError> {
+    let package = reader.get_package(&package_id)?.ok_or_else(|| {
+        IndexerError::InvalidArgumentError(format!("Package {package_id} not found"))
+    })?;
+    let modules = verify_modules(&package, modules)?;
+
+    let original_package_id = package.original_package_id();
+    let mut upgrade_lineage = reader.get_package_versions(original_package_id)?;
+    if !upgrade_lineage.iter().any(|v| v.package_id == package_id) {
+        upgrade_lineage.push(PackageVersion {
+            package_id,
+            version: package.version(),
+        });
+        upgrade_lineage.sort_by_key(|v| v.version);
+    }
+    Ok(PackageVerification {
+        package_id,
+        version: package.version(),
+        original_package_id,
+        upgrade_lineage,
+        matches: modules
+            .iter()
+            .all(|m| m.status == ModuleVerificationStatus::Match),
+        modules,
+    })
+}
+
+/// Compares every module of `modules` to the module of `package` of the same name, ordering
+/// the outcomes by module name.
+fn verify_modules(
+    package: &MovePackage,
+    modules: &[Base64],
+) -> Result<Vec<ModuleVerification>, IndexerError> {
+    let self_address = AccountAddress::from(package.original_package_id());
+    let published_modules = package.serialized_module_map();
+    let mut verified = BTreeMap::new();
+    for (i, encoded) in modules.iter().enumerate() {
+        let bytes = encoded.to_vec().map_err(|e| {
+            IndexerError::InvalidArgumentError(format!("Module {i} is not valid Base64: {e}"))
+        })?;
+        let mut local = CompiledModule::deserialize_with_defaults(&bytes).map_err(|e| {
+            IndexerError::InvalidArgumentError(format!("Failed to deserialize module {i}: {e}"))
+        })?;
+        set_self_address(&mut local, self_address);
+        let name = local.name().to_string();
+        let verification = match published_modules.get(&name) {
+            Some(bytes) => {
+                let published = deserialize_published(package.id(), &name, bytes)?;
+                compare_modules(name.clone(), local, published)
+            }
+            None => missing_module(
+                name.clone(),
+                &local,
+                ModuleVerificationStatus::MissingOnChain,
+            ),
+        };
+        if verified.insert(name.clone(), verification).is_some() {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Module {name} was given more than once"
+            )));
+        }
+    }
+
+    for (name, bytes) in published_modules {
+        if !verified.contains_key(name) {
+            let published = deserialize_published(package.id(), name, bytes)?;
+            verified.insert(
+                name.clone(),
+                missing_module(
+                    name.clone(),
+                    &published,
+                    ModuleVerificationStatus::MissingLocally,
+                ),
+            );
+        }
+    }
+    Ok(verified.into_values().collect())
+}
+
+fn deserialize_published(
+    package_id: ObjectID,
+    name: &str,
+    bytes: &[u8],
+) -> Result<CompiledModule, IndexerError> {
+    CompiledModule::deserialize_with_defaults(bytes).map_err(|e| {
+        IndexerError::ModuleResolutionError(format!(
+            "Failed to deserialize module {package_id}::{name}: {e}"
+        ))
+    })
+}
+
+/// Sets the address of a module built before its package was published, as publishing does.
+fn set_self_address(module: &mut CompiledModule, address: AccountAddress) {
+    let index = module.self_handle().address.0 as usize;
+    if let Some(self_address) = module.address_identifiers.get_mut(index) {
+        if *self_address == AccountAddress::ZERO {
+            *self_address = address;
+        }
+    }
+}
+
+fn compare_modules(
+    name: String,
+    mut local: CompiledModule,
+    mut published: CompiledModule,
+) -> ModuleVerification {
+    local.version = published.version;
+    local.metadata.clear();
+    published.metadata.clear();
+    if local == published {
+        return ModuleVerification {
+            module: name,
+            status: ModuleVerificationStatus::Match,
+            differing_function_handles: 0,
+            differing_functions: vec![],
+        };
+    }
+
+    let local_handles = function_handles(&local);
+    let published_handles = function_handles(&published);
+    let local_functions = functions(&local);
+    let published_functions = functions(&published);
+    let differing_functions = local_functions
+        .keys()
+        .chain(published_functions.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|f| local_functions.get(*f) != published_functions.get(*f))
+        .map(|f| f.to_string())
+        .collect();
+    ModuleVerification {
+        module: name,
+        status: ModuleVerificationStatus::Mismatch,
+        differing_function_handles: local_handles
+            .symmetric_difference(&published_handles)
+            .count(),
+        differing_functions,
+    }
+}
+
+/// Every handle and function of `module` is only found on its side.
+fn missing_module(
+    name: String,
+    module: &CompiledModule,
+    status: ModuleVerificationStatus,
+) -> ModuleVerification {
+    ModuleVerification {
+        module: name,
+        status,
+        differing_function_handles: function_handles(module).len(),
+        differing_functions: functions(module)
+            .into_keys()
+            .map(|f| f.to_string())
+            .collect(),
+    }
+}
+
+/// A function handle, with its module and the types of its signature resolved, so that
+/// handles compare equal across modules whatever their pools are laid out like.
+type FunctionHandleKey = (FunctionRef, Vec<AbilitySet>, Vec<Type>, Vec<Type>);
+
+fn function_handles(module: &CompiledModule) -> BTreeSet<FunctionHandleKey> {
+    let types = |index| -> Vec<Type> {
+        module
+            .signature_at(index)
+            .0
+            .iter()
+            .map(|token| Type::new(module, token))
+            .collect()
+    };
+    module
+        .function_handles()
+        .iter()
+        .map(|handle| {
+            (
+                FunctionRef::new(module, handle),
+                handle.type_parameters.clone(),
+                types(handle.parameters),
+                types(handle.return_),
+            )
+        })
+        .collect()
+}
+
+fn functions(module: &CompiledModule) -> BTreeMap<Identifier, Function> {
+    module
+        .function_defs()
+        .iter()
+        .map(|def| Function::new(module, def))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use move_binary_format::file_format::Bytecode;
+    use mgo_move_build::BuildConfig;
+    use mgo_types::object::OBJECT_START_VERSION;
+
+    use super::*;
+
+    /// The modules of the basics example as built, and as published at `package_id`.
+    fn build_basics(package_id: ObjectID) -> (Vec<Base64>, MovePackage) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../mgo_programmability/examples/basics");
+        let compiled = BuildConfig::new_for_testing().build(path).unwrap();
+        let local = compiled.get_package_base64(false);
+        let published: Vec<_> = compiled
+            .get_modules()
+            .cloned()
+            .map(|mut module| {
+                set_self_address(&mut module, package_id.into());
+                module
+            })
+            .collect();
+        (
+            local,
+            MovePackage::new_system(OBJECT_START_VERSION, &published, []),
+        )
+    }
+
+    fn encode(module: &CompiledModule) -> Base64 {
+        let mut bytes = vec![];
+        module.serialize(&mut bytes).unwrap();
+        Base64::from_bytes(&bytes)
+    }
+
+    #[test]
+    fn test_verify_modules_of_build() {
+        let (local, package) = build_basics(ObjectID::random());
+        let verified = verify_modules(&package, &local).unwrap();
+        assert_eq!(verified.len(), package.serialized_module_map().len());
+        assert!(verified
+            .iter()
+            .all(|m| m.status == ModuleVerificationStatus::Match));
+        assert!(verified.windows(2).all(|w| w[0].module < w[1].module));
+    }
+
+    #[test]
+    fn test_verify_modified_and_missing_modules() {
+        let (local, package) = build_basics(ObjectID::random());
+        let mut modules: Vec<_> = local
+            .iter()
+            .map(|encoded| {
+                CompiledModule::deserialize_with_defaults(&encoded.to_vec().unwrap()).unwrap()
+            })
+            .collect();
+        let counter = modules
+            .iter_mut()
+            .find(|m| m.name().as_str() == "counter")
+            .unwrap();
+        // Returning early from `set_value` changes its code, but no handle.
+        let set_value = counter
+            .function_defs
+            .iter()
+            .position(|def| {
+                counter
+                    .identifier_at(counter.function_handle_at(def.function).name)
+                    .as_str()
+                    == "set_value"
+            })
+            .unwrap();
+        counter.function_defs[set_value]
+            .code
+            .as_mut()
+            .unwrap()
+            .code
+            .insert(0, Bytecode::Ret);
+        let removed = modules
+            .iter()
+            .position(|m| m.name().as_str() != "counter")
+            .unwrap();
+        let removed = modules.remove(removed).name().to_string();
+
+        let modified: Vec<_> = modules.iter().map(encode).collect();
+        let verified = verify_modules(&package, &modified).unwrap();
+        let counter = verified.iter().find(|m| m.module == "counter").unwrap();
+        assert_eq!(counter.status, ModuleVerificationStatus::Mismatch);
+        assert_eq!(counter.differing_functions, vec!["set_value".to_string()]);
+        assert_eq!(counter.differing_function_handles, 0);
+        let removed = verified.iter().find(|m| m.module == removed).unwrap();
+        assert_eq!(removed.status, ModuleVerificationStatus::MissingLocally);
+        assert!(verified
+            .iter()
+            .filter(|m| m.module != "counter" && m.module != removed.module)
+            .all(|m| m.status == ModuleVerificationStatus::Match));
+
+        let error = verify_modules(&package, &[modified[0].clone(), modified[0].clone()])
+            .unwrap_err();
+        assert!(error.to_string().contains("more than once"), "{error}");
+    }
+}
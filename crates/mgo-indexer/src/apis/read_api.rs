@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use async_trait::async_trait;
+use fastcrypto::encoding::Base64;
 use futures::future::join_all;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::http_client::HttpClient;
@@ -10,9 +11,11 @@ use jsonrpsee::RpcModule;
 use mgo_json_rpc::MgoRpcModule;
 use mgo_json_rpc_api::{ReadApiClient, ReadApiServer};
 use mgo_json_rpc_types::{
-    Checkpoint, CheckpointId, CheckpointPage, ProtocolConfigResponse, MgoEvent,
-    MgoGetPastObjectRequest, MgoObjectDataOptions, MgoObjectResponse, MgoPastObjectResponse,
-    MgoTransactionBlockResponse, MgoTransactionBlockResponseOptions,
+    BcsResponse, Checkpoint, CheckpointId, CheckpointPage, ClientLimits, EncodedResponse,
+    ProtocolConfigResponse, MgoEvent, MgoGetPastObjectRequest, MgoObjectDataOptions,
+    MgoObjectResponse, MgoPastObjectResponse, MgoTransactionBlockResponse,
+    MgoTransactionBlockResponseOptions, EpochStartInfo, ResponseEncoding,
+    TransactionSignatureVerification,
 };
 use mgo_open_rpc::Module;
 use mgo_types::base_types::{ObjectID, SequenceNumber};
@@ -150,13 +153,17 @@ where
         &self,
         object_ids: Vec<ObjectID>,
         options: Option<MgoObjectDataOptions>,
-    ) -> RpcResult<Vec<MgoObjectResponse>> {
+        encoding: Option<ResponseEncoding>,
+    ) -> RpcResult<EncodedResponse<Vec<MgoObjectResponse>>> {
         let objs_guard = self
             .state
             .indexer_metrics()
             .multi_get_objects_latency
             .start_timer();
-        let objs_resp = self.fullnode.multi_get_objects(object_ids, options).await;
+        let objs_resp = self
+            .fullnode
+            .multi_get_objects(object_ids, options, encoding)
+            .await;
         objs_guard.stop_and_record();
         objs_resp
     }
@@ -205,10 +212,13 @@ where
         &self,
         digests: Vec<TransactionDigest>,
         options: Option<MgoTransactionBlockResponseOptions>,
-    ) -> RpcResult<Vec<MgoTransactionBlockResponse>> {
-        if !self
-            .migrated_methods
-            .contains(&"multi_get_transaction_blocks".to_string())
+        encoding: Option<ResponseEncoding>,
+    ) -> RpcResult<EncodedResponse<Vec<MgoTransactionBlockResponse>>> {
+        // The store does not keep the BCS layout of transaction blocks.
+        if encoding == Some(ResponseEncoding::Bcs)
+            || !self
+                .migrated_methods
+                .contains(&"multi_get_transaction_blocks".to_string())
         {
             let multi_tx_guard = self
                 .state
@@ -217,14 +227,15 @@ where
                 .start_timer();
             let multi_tx_resp = self
                 .fullnode
-                .multi_get_transaction_blocks(digests, options)
+                .multi_get_transaction_blocks(digests, options, encoding)
                 .await;
             multi_tx_guard.stop_and_record();
             return multi_tx_resp;
         }
-        Ok(self
-            .multi_get_transaction_blocks_internal(&digests, options)
-            .await?)
+        Ok(EncodedResponse::Json(
+            self.multi_get_transaction_blocks_internal(&digests, options)
+                .await?,
+        ))
     }
 
     async fn try_get_past_object(
@@ -284,21 +295,38 @@ where
             .into())
     }
 
-    async fn get_checkpoint(&self, id: CheckpointId) -> RpcResult<Checkpoint> {
-        if !self
-            .migrated_methods
-            .contains(&"get_checkpoint".to_string())
+    async fn get_checkpoint(
+        &self,
+        id: CheckpointId,
+        encoding: Option<ResponseEncoding>,
+    ) -> RpcResult<EncodedResponse<Checkpoint>> {
+        // The store does not keep the certified summaries and contents of checkpoints.
+        if encoding == Some(ResponseEncoding::Bcs)
+            || !self
+                .migrated_methods
+                .contains(&"get_checkpoint".to_string())
         {
             let cp_guard = self
                 .state
                 .indexer_metrics()
                 .get_checkpoint_latency
                 .start_timer();
-            let cp_resp = self.fullnode.get_checkpoint(id).await;
+            let cp_resp = self.fullnode.get_checkpoint(id, encoding).await;
             cp_guard.stop_and_record();
             return cp_resp;
         }
-        Ok(self.state.get_checkpoint(id).await?)
+        Ok(EncodedResponse::Json(self.state.get_checkpoint(id).await?))
+    }
+
+    async fn get_checkpoint_transactions(
+        &self,
+        sequence_number: BigInt<u64>,
+        cursor: Option<TransactionDigest>,
+        limit: Option<usize>,
+    ) -> RpcResult<BcsResponse> {
+        self.fullnode
+            .get_checkpoint_transactions(sequence_number, cursor, limit)
+            .await
     }
 
     async fn get_checkpoints(
@@ -306,6 +334,7 @@ where
         cursor: Option<BigInt<u64>>,
         limit: Option<usize>,
         descending_order: bool,
+        epoch: Option<BigInt<u64>>,
     ) -> RpcResult<CheckpointPage> {
         let cps_guard = self
             .state
@@ -314,7 +343,7 @@ where
             .start_timer();
         let cps_resp = self
             .fullnode
-            .get_checkpoints(cursor, limit, descending_order)
+            .get_checkpoints(cursor, limit, descending_order, epoch)
             .await;
         cps_guard.stop_and_record();
         cps_resp
@@ -326,7 +355,7 @@ where
         limit: Option<BigInt<u64>>,
         descending_order: bool,
     ) -> RpcResult<CheckpointPage> {
-        self.get_checkpoints(cursor, limit.map(|l| *l as usize), descending_order)
+        self.get_checkpoints(cursor, limit.map(|l| *l as usize), descending_order, None)
             .await
     }
 
@@ -343,13 +372,17 @@ where
     async fn get_loaded_child_objects(
         &self,
         digest: TransactionDigest,
+        options: Option<MgoObjectDataOptions>,
     ) -> RpcResult<MgoLoadedChildObjectsResponse> {
         let dynamic_fields_load_obj_guard = self
             .state
             .indexer_metrics()
             .get_loaded_child_objects_latency
             .start_timer();
-        let dyn_fields_resp = self.fullnode.get_loaded_child_objects(digest).await;
+        let dyn_fields_resp = self
+            .fullnode
+            .get_loaded_child_objects(digest, options)
+            .await;
         dynamic_fields_load_obj_guard.stop_and_record();
         dyn_fields_resp
     }
@@ -368,6 +401,10 @@ where
         protocol_config_resp
     }
 
+    async fn get_client_limits(&self) -> RpcResult<ClientLimits> {
+        self.fullnode.get_client_limits().await
+    }
+
     async fn get_chain_identifier(&self) -> RpcResult<String> {
         let ci = self
             .state
@@ -376,6 +413,20 @@ where
             .digest;
         Ok(ChainIdentifier::from(ci).to_string())
     }
+
+    async fn verify_transaction_block_signature(
+        &self,
+        tx_bytes: Base64,
+        signatures: Vec<Base64>,
+    ) -> RpcResult<TransactionSignatureVerification> {
+        self.fullnode
+            .verify_transaction_block_signature(tx_bytes, signatures)
+            .await
+    }
+
+    async fn get_epoch_start_info(&self, epoch: BigInt<u64>) -> RpcResult<EpochStartInfo> {
+        self.fullnode.get_epoch_start_info(epoch).await
+    }
 }
 
 impl<S> MgoRpcModule for ReadApi<S>
@@ -1,21 +1,29 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::{HashMap, HashSet};
+
+use itertools::Itertools;
+
 use async_trait::async_trait;
+use fastcrypto::encoding::Base64;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::RpcModule;
 use mgo_json_rpc::error::MgoRpcInputError;
+use mgo_json_rpc::read_api::DisplayRenderLimits;
 use mgo_types::error::MgoObjectResponseError;
 use mgo_types::object::ObjectRead;
 
 use crate::errors::IndexerError;
+use crate::fullnode_fallback::TransactionFallback;
 use crate::indexer_reader::IndexerReader;
 use mgo_json_rpc::MgoRpcModule;
-use mgo_json_rpc_api::{ReadApiServer, QUERY_MAX_RESULT_LIMIT};
+use mgo_json_rpc_api::{server_limits, ReadApiServer, QUERY_MAX_RESULT_LIMIT};
 use mgo_json_rpc_types::{
-    Checkpoint, CheckpointId, CheckpointPage, ProtocolConfigResponse, MgoEvent,
-    MgoGetPastObjectRequest, MgoObjectDataOptions, MgoObjectResponse, MgoPastObjectResponse,
-    MgoTransactionBlockResponse, MgoTransactionBlockResponseOptions,
+    BcsResponse, Checkpoint, CheckpointId, CheckpointPage, ClientLimits, EncodedResponse,
+    EpochStartInfo, MgoEvent, MgoGetPastObjectRequest, MgoObjectDataOptions, MgoObjectResponse,
+    MgoPastObjectResponse, MgoTransactionBlockResponse, MgoTransactionBlockResponseOptions,
+    ObjectReadBcsV1, ProtocolConfigResponse, ResponseEncoding, TransactionSignatureVerification,
 };
 use mgo_open_rpc::Module;
 use mgo_protocol_config::{ProtocolConfig, ProtocolVersion};
@@ -23,16 +31,28 @@ use mgo_types::base_types::{ObjectID, SequenceNumber};
 use mgo_types::digests::{ChainIdentifier, TransactionDigest};
 use mgo_types::mgo_serde::BigInt;
 
-use mgo_json_rpc_types::MgoLoadedChildObjectsResponse;
+use mgo_json_rpc_types::{MgoLoadedChildObject, MgoLoadedChildObjectsResponse};
 
 #[derive(Clone)]
 pub(crate) struct ReadApiV2 {
     inner: IndexerReader,
+    display_limits: DisplayRenderLimits,
+    fullnode_fallback: Option<TransactionFallback>,
 }
 
 impl ReadApiV2 {
-    pub fn new(inner: IndexerReader) -> Self {
-        Self { inner }
+    pub fn new(inner: IndexerReader, display_limits: DisplayRenderLimits) -> Self {
+        Self {
+            inner,
+            display_limits,
+            fullnode_fallback: None,
+        }
+    }
+
+    /// Reads transactions that are not indexed yet from a fullnode.
+    pub fn with_fullnode_fallback(mut self, fullnode_fallback: TransactionFallback) -> Self {
+        self.fullnode_fallback = Some(fullnode_fallback);
+        self
     }
 
     async fn get_checkpoint(&self, id: CheckpointId) -> Result<Checkpoint, IndexerError> {
@@ -59,16 +79,47 @@ impl ReadApiV2 {
         let genesis_checkpoint = self.get_checkpoint(CheckpointId::SequenceNumber(0)).await?;
         Ok(ChainIdentifier::from(genesis_checkpoint.digest))
     }
-}
 
-#[async_trait]
-impl ReadApiServer for ReadApiV2 {
-    async fn get_object(
+    /// Responses of the transactions of `digests`, in request order, reading those not indexed
+    /// yet from the fullnode fallback if there is one.
+    async fn transaction_block_responses(
+        &self,
+        digests: Vec<TransactionDigest>,
+        options: MgoTransactionBlockResponseOptions,
+    ) -> Result<Vec<MgoTransactionBlockResponse>, IndexerError> {
+        let mut txns = self
+            .inner
+            .multi_get_transaction_block_response_in_blocking_task(digests.clone(), options.clone())
+            .await?;
+
+        if let Some(fullnode_fallback) = &self.fullnode_fallback {
+            let indexed = txns.iter().map(|txn| txn.digest).collect::<HashSet<_>>();
+            let missing = digests
+                .iter()
+                .filter(|digest| !indexed.contains(digest))
+                .copied()
+                .collect::<Vec<_>>();
+            txns.extend(
+                fullnode_fallback
+                    .multi_get_transaction_blocks(&missing, &options)
+                    .await,
+            );
+        }
+
+        let positions = digests
+            .iter()
+            .enumerate()
+            .map(|(i, digest)| (*digest, i))
+            .collect::<HashMap<_, _>>();
+        txns.sort_by_key(|txn| positions.get(&txn.digest).copied());
+        Ok(txns)
+    }
+
+    async fn object_response(
         &self,
         object_id: ObjectID,
-        options: Option<MgoObjectDataOptions>,
-    ) -> RpcResult<MgoObjectResponse> {
-        let options = options.unwrap_or_default();
+        options: &MgoObjectDataOptions,
+    ) -> Result<MgoObjectResponse, IndexerError> {
         let object_read = self
             .inner
             .get_object_read_in_blocking_task(object_id)
@@ -79,13 +130,22 @@ impl ReadApiServer for ReadApiV2 {
                 MgoObjectResponseError::NotExists { object_id: id },
             )),
             ObjectRead::Exists(object_ref, o, layout) => {
+                if let Some(layout) = &layout {
+                    options
+                        .check_field_mask(layout)
+                        .map_err(|e| IndexerError::InvalidArgumentError(e.to_string()))?;
+                }
                 let mut display_fields = None;
                 if options.show_display {
-                    match self.inner.get_display_fields(&o, &layout).await {
+                    match self
+                        .inner
+                        .get_display_fields(&o, &layout, &self.display_limits)
+                        .await
+                    {
                         Ok(rendered_fields) => display_fields = Some(rendered_fields),
                         Err(e) => {
                             return Ok(MgoObjectResponse::new(
-                                Some((object_ref, o, layout, options, None).try_into()?),
+                                Some((object_ref, o, layout, options.clone(), None).try_into()?),
                                 Some(MgoObjectResponseError::DisplayError {
                                     error: e.to_string(),
                                 }),
@@ -94,7 +154,7 @@ impl ReadApiServer for ReadApiV2 {
                     }
                 }
                 Ok(MgoObjectResponse::new_with_data(
-                    (object_ref, o, layout, options, display_fields).try_into()?,
+                    (object_ref, o, layout, options.clone(), display_fields).try_into()?,
                 ))
             }
             ObjectRead::Deleted((object_id, version, digest)) => Ok(
@@ -106,30 +166,52 @@ impl ReadApiServer for ReadApiV2 {
             ),
         }
     }
+}
 
-    // For ease of implementation we just forward to the single object query, although in the
-    // future we may want to improve the performance by having a more naitive multi_get
-    // functionality
-    async fn multi_get_objects(
+#[async_trait]
+impl ReadApiServer for ReadApiV2 {
+    async fn get_object(
         &self,
-        object_ids: Vec<ObjectID>,
+        object_id: ObjectID,
         options: Option<MgoObjectDataOptions>,
-    ) -> RpcResult<Vec<MgoObjectResponse>> {
-        if object_ids.len() > *QUERY_MAX_RESULT_LIMIT {
-            return Err(
-                MgoRpcInputError::SizeLimitExceeded(QUERY_MAX_RESULT_LIMIT.to_string()).into(),
-            );
+    ) -> RpcResult<MgoObjectResponse> {
+        let options = options.unwrap_or_default();
+        let mut response = self.object_response(object_id, &options).await?;
+        if options.show_previous_transaction_details {
+            self.inner
+                .add_previous_transaction_details(response.data.iter_mut().collect())
+                .await?;
         }
+        Ok(response)
+    }
 
-        let mut futures = vec![];
-        for object_id in object_ids {
-            futures.push(self.get_object(object_id, options.clone()));
+    async fn multi_get_objects(
+        &self,
+        object_ids: Vec<ObjectID>,
+        options: Option<MgoObjectDataOptions>,
+        encoding: Option<ResponseEncoding>,
+    ) -> RpcResult<EncodedResponse<Vec<MgoObjectResponse>>> {
+        if encoding.unwrap_or_default() == ResponseEncoding::Bcs {
+            let objects = self
+                .inner
+                .multi_get_object_reads_in_blocking_task(object_ids)
+                .await?
+                .into_iter()
+                .map(ObjectReadBcsV1::from)
+                .collect::<Vec<_>>();
+            return Ok(EncodedResponse::Bcs(
+                BcsResponse::new(&objects).map_err(IndexerError::from)?,
+            ));
         }
-
-        futures::future::join_all(futures)
-            .await
-            .into_iter()
-            .collect::<Result<Vec<_>, _>>()
+        Ok(EncodedResponse::Json(
+            self.inner
+                .multi_get_objects_in_blocking_task(
+                    object_ids,
+                    options.unwrap_or_default(),
+                    &self.display_limits,
+                )
+                .await?,
+        ))
     }
 
     async fn get_total_transaction_blocks(&self) -> RpcResult<BigInt<u64>> {
@@ -143,7 +225,7 @@ impl ReadApiServer for ReadApiV2 {
         options: Option<MgoTransactionBlockResponseOptions>,
     ) -> RpcResult<MgoTransactionBlockResponse> {
         let mut txn = self
-            .multi_get_transaction_blocks(vec![digest], options)
+            .transaction_block_responses(vec![digest], options.unwrap_or_default())
             .await?;
 
         let txn = txn.pop().ok_or_else(|| {
@@ -157,7 +239,8 @@ impl ReadApiServer for ReadApiV2 {
         &self,
         digests: Vec<TransactionDigest>,
         options: Option<MgoTransactionBlockResponseOptions>,
-    ) -> RpcResult<Vec<MgoTransactionBlockResponse>> {
+        encoding: Option<ResponseEncoding>,
+    ) -> RpcResult<EncodedResponse<Vec<MgoTransactionBlockResponse>>> {
         let num_digests = digests.len();
         if num_digests > *QUERY_MAX_RESULT_LIMIT {
             Err(MgoRpcInputError::SizeLimitExceeded(
@@ -165,13 +248,25 @@ impl ReadApiServer for ReadApiV2 {
             ))?
         }
 
-        let options = options.unwrap_or_default();
-        let txns = self
-            .inner
-            .multi_get_transaction_block_response_in_blocking_task(digests, options)
-            .await?;
+        if encoding.unwrap_or_default() == ResponseEncoding::Bcs {
+            if digests.iter().unique().count() < num_digests {
+                Err(MgoRpcInputError::ContainsDuplicates)?
+            }
+            // Transactions that are not indexed yet are not read from the fullnode, whose
+            // responses carry no BCS encoding of the events.
+            let blocks = self
+                .inner
+                .multi_get_transaction_blocks_bcs_in_blocking_task(digests)
+                .await?;
+            return Ok(EncodedResponse::Bcs(
+                BcsResponse::new(&blocks).map_err(IndexerError::from)?,
+            ));
+        }
 
-        Ok(txns)
+        Ok(EncodedResponse::Json(
+            self.transaction_block_responses(digests, options.unwrap_or_default())
+                .await?,
+        ))
     }
 
     async fn try_get_past_object(
@@ -202,8 +297,36 @@ impl ReadApiServer for ReadApiV2 {
         Ok(BigInt::from(checkpoint.sequence_number))
     }
 
-    async fn get_checkpoint(&self, id: CheckpointId) -> RpcResult<Checkpoint> {
-        self.get_checkpoint(id).await.map_err(Into::into)
+    async fn get_checkpoint(
+        &self,
+        id: CheckpointId,
+        encoding: Option<ResponseEncoding>,
+    ) -> RpcResult<EncodedResponse<Checkpoint>> {
+        if encoding.unwrap_or_default() == ResponseEncoding::Bcs {
+            let checkpoint = self
+                .inner
+                .spawn_blocking(move |this| this.get_checkpoint_bcs(id))
+                .await?
+                .ok_or_else(|| {
+                    IndexerError::InvalidArgumentError(format!("Checkpoint {id:?} not found"))
+                })?;
+            return Ok(EncodedResponse::Bcs(
+                BcsResponse::new(&checkpoint).map_err(IndexerError::from)?,
+            ));
+        }
+        Ok(EncodedResponse::Json(self.get_checkpoint(id).await?))
+    }
+
+    async fn get_checkpoint_transactions(
+        &self,
+        _sequence_number: BigInt<u64>,
+        _cursor: Option<TransactionDigest>,
+        _limit: Option<usize>,
+    ) -> RpcResult<BcsResponse> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
     }
 
     async fn get_checkpoints(
@@ -211,8 +334,10 @@ impl ReadApiServer for ReadApiV2 {
         cursor: Option<BigInt<u64>>,
         limit: Option<usize>,
         descending_order: bool,
+        epoch: Option<BigInt<u64>>,
     ) -> RpcResult<CheckpointPage> {
         let cursor = cursor.map(BigInt::into_inner);
+        let epoch = epoch.map(BigInt::into_inner);
         let limit = mgo_json_rpc_api::validate_limit(
             limit,
             mgo_json_rpc_api::QUERY_MAX_RESULT_LIMIT_CHECKPOINTS,
@@ -221,7 +346,9 @@ impl ReadApiServer for ReadApiV2 {
 
         let mut checkpoints = self
             .inner
-            .spawn_blocking(move |this| this.get_checkpoints(cursor, limit + 1, descending_order))
+            .spawn_blocking(move |this| {
+                this.get_checkpoints(cursor, limit + 1, descending_order, epoch)
+            })
             .await?;
 
         let has_next_page = checkpoints.len() > limit;
@@ -233,6 +360,7 @@ impl ReadApiServer for ReadApiV2 {
             data: checkpoints,
             next_cursor,
             has_next_page,
+            applied: None,
         })
     }
 
@@ -246,6 +374,7 @@ impl ReadApiServer for ReadApiV2 {
             cursor,
             limit.map(|l| l.into_inner() as usize),
             descending_order,
+            None,
         )
         .await
     }
@@ -259,12 +388,27 @@ impl ReadApiServer for ReadApiV2 {
 
     async fn get_loaded_child_objects(
         &self,
-        _digest: TransactionDigest,
+        digest: TransactionDigest,
+        options: Option<MgoObjectDataOptions>,
     ) -> RpcResult<MgoLoadedChildObjectsResponse> {
-        Err(jsonrpsee::types::error::CallError::Custom(
-            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
-        )
-        .into())
+        if options.is_some() {
+            // Past versions of objects are not served by this indexer.
+            return Err(IndexerError::InvalidArgumentError(
+                "Contents of loaded child objects are not supported".to_string(),
+            )
+            .into());
+        }
+        let loaded_child_objects = self
+            .inner
+            .spawn_blocking(move |this| this.get_loaded_child_object_versions(digest))
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(object_id, version)| MgoLoadedChildObject::new(object_id, version))
+            .collect();
+        Ok(MgoLoadedChildObjectsResponse {
+            loaded_child_objects,
+        })
     }
 
     async fn get_protocol_config(
@@ -291,9 +435,47 @@ impl ReadApiServer for ReadApiV2 {
             .map(ProtocolConfigResponse::from)
     }
 
+    /// Limits of the protocol version of the latest indexed epoch, which change as soon as the
+    /// indexer has processed the reconfiguration to a new version.
+    async fn get_client_limits(&self) -> RpcResult<ClientLimits> {
+        let chain = self.get_chain_identifier().await?.chain();
+        let latest_epoch = self
+            .inner
+            .spawn_blocking(|this| this.get_latest_epoch_info_from_db())
+            .await?;
+        let version = ProtocolVersion::new(latest_epoch.protocol_version as u64);
+        let config =
+            ProtocolConfig::get_for_version_if_supported(version, chain).ok_or_else(|| {
+                IndexerError::GenericError(format!("Unsupported protocol version {version:?}"))
+            })?;
+        Ok(ClientLimits::new(
+            &config,
+            latest_epoch.epoch as u64,
+            server_limits(),
+        ))
+    }
+
     async fn get_chain_identifier(&self) -> RpcResult<String> {
         self.get_chain_identifier().await.map(|id| id.to_string())
     }
+
+    async fn verify_transaction_block_signature(
+        &self,
+        _tx_bytes: Base64,
+        _signatures: Vec<Base64>,
+    ) -> RpcResult<TransactionSignatureVerification> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
+
+    async fn get_epoch_start_info(&self, _epoch: BigInt<u64>) -> RpcResult<EpochStartInfo> {
+        Err(jsonrpsee::types::error::CallError::Custom(
+            jsonrpsee::types::error::ErrorCode::MethodNotFound.into(),
+        )
+        .into())
+    }
 }
 
 impl MgoRpcModule for ReadApiV2 {
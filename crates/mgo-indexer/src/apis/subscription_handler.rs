@@ -0,0 +1,270 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Streams committed transactions and events to websocket subscribers of [`IndexerApiV2`].
+//!
+//! A single worker wakes up on every [`CommitNotice`], reads the newly committed checkpoint
+//! range once and broadcasts it, and every subscription applies its own filter before writing
+//! to its sink. Broadcasting never waits on subscribers: one that falls more than
+//! [`SUBSCRIPTION_BUFFER_SIZE`] items behind is closed with an error instead of holding back
+//! the others.
+//!
+//! [`IndexerApiV2`]: crate::apis::IndexerApiV2
+
+use jsonrpsee::core::error::SubscriptionClosed;
+use jsonrpsee::core::Error as RpcError;
+use jsonrpsee::SubscriptionSink;
+use mango_metrics::spawn_monitored_task;
+use mgo_json_rpc::panic_guard::guard_notifications;
+use mgo_json_rpc_types::{
+    EffectsWithInput, EventFilter, EventFilterLimits, Filter, MgoEvent, MgoTransactionBlockEffects,
+    TransactionFilter,
+};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::StreamExt;
+use tracing::{debug, error, warn};
+
+use crate::errors::IndexerError;
+use crate::indexer_reader::IndexerReader;
+use crate::notifications::{CommitNotice, CommitNoticeListener};
+
+/// Number of transactions or events a subscriber may lag behind before it is dropped.
+pub(crate) const SUBSCRIPTION_BUFFER_SIZE: usize = 1024;
+
+/// Number of checkpoints whose transactions and events are read at once.
+const MAX_CHECKPOINTS_PER_READ: u64 = 100;
+
+#[derive(Clone)]
+pub(crate) struct SubscriptionHandler {
+    transactions: broadcast::Sender<EffectsWithInput>,
+    events: broadcast::Sender<MgoEvent>,
+    event_filter_limits: EventFilterLimits,
+}
+
+impl SubscriptionHandler {
+    /// Starts broadcasting the checkpoints announced by `listener`, to subscriptions whose
+    /// event filters are within `event_filter_limits`.
+    pub fn start(
+        reader: IndexerReader,
+        listener: CommitNoticeListener,
+        event_filter_limits: EventFilterLimits,
+    ) -> Self {
+        let handler = Self::new(SUBSCRIPTION_BUFFER_SIZE, event_filter_limits);
+        spawn_monitored_task!(handler.clone().run(reader, listener.subscribe()));
+        handler
+    }
+
+    fn new(buffer_size: usize, event_filter_limits: EventFilterLimits) -> Self {
+        Self {
+            transactions: broadcast::channel(buffer_size).0,
+            events: broadcast::channel(buffer_size).0,
+            event_filter_limits,
+        }
+    }
+
+    /// Streams the events matching `filter` to `sink`, or rejects the subscription if the
+    /// filter cannot be matched against streamed events within the limits.
+    pub fn subscribe_events(&self, mut sink: SubscriptionSink, filter: EventFilter) {
+        if let Err(e) = filter.validate_for_subscription(&self.event_filter_limits) {
+            let error = IndexerError::InvalidArgumentError(e.to_string());
+            let _ = sink.reject(RpcError::from(error));
+            return;
+        }
+        spawn_subscription::<_, MgoEvent, _>(sink, self.events.subscribe(), filter);
+    }
+
+    pub fn subscribe_transactions(&self, sink: SubscriptionSink, filter: TransactionFilter) {
+        spawn_subscription::<_, MgoTransactionBlockEffects, _>(
+            sink,
+            self.transactions.subscribe(),
+            filter,
+        );
+    }
+
+    async fn run(self, reader: IndexerReader, mut notices: broadcast::Receiver<CommitNotice>) {
+        // First checkpoint not broadcast yet, so that missed notices are caught up on the next one.
+        let mut next_checkpoint = None;
+        loop {
+            let notice = match notices.recv().await {
+                Ok(notice) => notice,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Subscription worker missed {skipped} commit notices, catching up on the next one");
+                    continue;
+                }
+                Err(RecvError::Closed) => return,
+            };
+            let mut first = next_checkpoint.unwrap_or(notice.first_checkpoint_sequence_number);
+            let last = notice.last_checkpoint_sequence_number;
+            // A long catch-up is read in chunks, and a failed chunk is retried from its start on
+            // the next notice.
+            while first <= last {
+                let chunk_last = last.min(first + MAX_CHECKPOINTS_PER_READ - 1);
+                if let Err(e) = self.broadcast_range(&reader, first, chunk_last).await {
+                    error!(
+                        "Failed to stream checkpoints {first}..={chunk_last} to subscribers: {e}"
+                    );
+                    break;
+                }
+                first = chunk_last + 1;
+            }
+            next_checkpoint = Some(first);
+        }
+    }
+
+    async fn broadcast_range(
+        &self,
+        reader: &IndexerReader,
+        first: u64,
+        last: u64,
+    ) -> Result<(), IndexerError> {
+        // Sending only fails once every subscriber is gone, so the results are ignored. The
+        // reads are skipped altogether while nobody is subscribed.
+        if self.transactions.receiver_count() > 0 {
+            let transactions = reader
+                .get_effects_with_input_in_checkpoint_range_in_blocking_task(first, last)
+                .await?;
+            for transaction in transactions {
+                let _ = self.transactions.send(transaction);
+            }
+        }
+        if self.events.receiver_count() > 0 {
+            let events = reader
+                .get_events_in_checkpoint_range_in_blocking_task(first, last)
+                .await?;
+            for event in events {
+                let _ = self.events.send(event);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn spawn_subscription<T, S, F>(
+    mut sink: SubscriptionSink,
+    receiver: broadcast::Receiver<T>,
+    filter: F,
+) where
+    T: Clone + Send + 'static,
+    S: From<T> + Serialize,
+    F: Filter<T> + Send + 'static,
+{
+    let stream = BroadcastStream::new(receiver).filter_map(move |item| match item {
+        Ok(item) if filter.matches(&item) => Some(Ok(S::from(item))),
+        Ok(_) => None,
+        Err(BroadcastStreamRecvError::Lagged(skipped)) => Some(Err(format!(
+            "Subscription fell {skipped} items behind and was closed"
+        ))),
+    });
+    let stream = guard_notifications(stream)
+        .map(|notification| notification.and_then(std::convert::identity));
+    spawn_monitored_task!(async move {
+        match sink.pipe_from_try_stream(stream).await {
+            SubscriptionClosed::Success => {
+                debug!("Subscription completed.");
+                sink.close(SubscriptionClosed::Success);
+            }
+            SubscriptionClosed::RemotePeerAborted => {
+                debug!("Subscription aborted by remote peer.");
+                sink.close(SubscriptionClosed::RemotePeerAborted);
+            }
+            SubscriptionClosed::Failed(err) => {
+                debug!("Subscription failed: {err:?}");
+                sink.close(err);
+            }
+        };
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use jsonrpsee::{rpc_params, RpcModule};
+    use mgo_types::base_types::{random_object_ref, MgoAddress};
+    use mgo_types::effects::TransactionEffects;
+    use mgo_types::transaction::TransactionData;
+
+    fn effects_with_input(sender: MgoAddress) -> EffectsWithInput {
+        EffectsWithInput {
+            effects: TransactionEffects::default().try_into().unwrap(),
+            input: TransactionData::new_transfer_mgo(
+                MgoAddress::random_for_testing_only(),
+                sender,
+                None,
+                random_object_ref(),
+                1_000_000,
+                1_000,
+            ),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_filters_are_applied_before_sending() {
+        let handler = SubscriptionHandler::new(16, EventFilterLimits::default());
+        let sender = MgoAddress::random_for_testing_only();
+        let mut module = RpcModule::new(handler.clone());
+        module
+            .register_subscription(
+                "subscribe",
+                "notification",
+                "unsubscribe",
+                move |_, sink, handler| {
+                    spawn_subscription::<_, MgoTransactionBlockEffects, _>(
+                        sink,
+                        handler.transactions.subscribe(),
+                        TransactionFilter::FromAddress(sender),
+                    );
+                    Ok(())
+                },
+            )
+            .unwrap();
+        let mut subscription = module.subscribe("subscribe", rpc_params![]).await.unwrap();
+
+        for from in [
+            MgoAddress::random_for_testing_only(),
+            sender,
+            MgoAddress::random_for_testing_only(),
+        ] {
+            handler.transactions.send(effects_with_input(from)).unwrap();
+        }
+
+        // Only the transaction of `sender` reaches the sink.
+        let received = tokio::time::timeout(
+            Duration::from_secs(1),
+            subscription.next::<MgoTransactionBlockEffects>(),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(received, Some(Ok(_))));
+        assert!(tokio::time::timeout(
+            Duration::from_millis(100),
+            subscription.next::<MgoTransactionBlockEffects>(),
+        )
+        .await
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_does_not_block_sender() {
+        let handler = SubscriptionHandler::new(4, EventFilterLimits::default());
+        let mut stream = BroadcastStream::new(handler.transactions.subscribe());
+
+        // Sending never waits on the subscriber, even well past its buffer.
+        for _ in 0..10 {
+            handler
+                .transactions
+                .send(effects_with_input(MgoAddress::random_for_testing_only()))
+                .unwrap();
+        }
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(BroadcastStreamRecvError::Lagged(6)))
+        ));
+    }
+}
@@ -1,10 +1,6 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
-// TODO remove after the functions are implemented
-#![allow(unused_variables)]
-#![allow(dead_code)]
-
 use async_trait::async_trait;
 use fastcrypto::encoding::Base64;
 use jsonrpsee::core::RpcResult;
@@ -12,7 +8,7 @@ use jsonrpsee::http_client::HttpClient;
 use jsonrpsee::RpcModule;
 
 use mgo_json_rpc::MgoRpcModule;
-use mgo_json_rpc_api::WriteApiServer;
+use mgo_json_rpc_api::{WriteApiClient, WriteApiServer};
 use mgo_json_rpc_types::{
     DevInspectArgs, DevInspectResults, DryRunTransactionBlockResponse, MgoTransactionBlockResponse,
     MgoTransactionBlockResponseOptions,
@@ -22,13 +18,34 @@ use mgo_types::base_types::MgoAddress;
 use mgo_types::quorum_driver_types::ExecuteTransactionRequestType;
 use mgo_types::mgo_serde::BigInt;
 
+use crate::dry_run_upstream::DryRunUpstream;
+use crate::errors::IndexerError;
+use crate::types::MgoTransactionBlockResponseWithOptions;
+
 pub(crate) struct WriteApiV2 {
     fullnode_client: HttpClient,
+    dry_run_upstream: Option<DryRunUpstream>,
 }
 
 impl WriteApiV2 {
     pub fn new(fullnode_client: HttpClient) -> Self {
-        Self { fullnode_client }
+        Self {
+            fullnode_client,
+            dry_run_upstream: None,
+        }
+    }
+
+    /// Serves dev inspect and dry run requests from `dry_run_upstream`. Without one, they are
+    /// rejected as not available.
+    pub fn with_dry_run_upstream(mut self, dry_run_upstream: DryRunUpstream) -> Self {
+        self.dry_run_upstream = Some(dry_run_upstream);
+        self
+    }
+
+    fn dry_run_upstream(&self, method: &str) -> Result<&DryRunUpstream, IndexerError> {
+        self.dry_run_upstream
+            .as_ref()
+            .ok_or_else(|| IndexerError::MethodNotAvailable(method.to_string()))
     }
 }
 
@@ -41,7 +58,17 @@ impl WriteApiServer for WriteApiV2 {
         options: Option<MgoTransactionBlockResponseOptions>,
         request_type: Option<ExecuteTransactionRequestType>,
     ) -> RpcResult<MgoTransactionBlockResponse> {
-        unimplemented!()
+        let fast_path_options = MgoTransactionBlockResponseOptions::full_content();
+        let mgo_transaction_response = self
+            .fullnode_client
+            .execute_transaction_block(tx_bytes, signatures, Some(fast_path_options), request_type)
+            .await?;
+
+        Ok(MgoTransactionBlockResponseWithOptions {
+            response: mgo_transaction_response,
+            options: options.unwrap_or_default(),
+        }
+        .into())
     }
 
     async fn dev_inspect_transaction_block(
@@ -52,14 +79,26 @@ impl WriteApiServer for WriteApiV2 {
         epoch: Option<BigInt<u64>>,
         additional_args: Option<DevInspectArgs>,
     ) -> RpcResult<DevInspectResults> {
-        unimplemented!()
+        Ok(self
+            .dry_run_upstream("mgo_devInspectTransactionBlock")?
+            .dev_inspect_transaction_block(
+                sender_address,
+                tx_bytes,
+                gas_price,
+                epoch,
+                additional_args,
+            )
+            .await?)
     }
 
     async fn dry_run_transaction_block(
         &self,
         tx_bytes: Base64,
     ) -> RpcResult<DryRunTransactionBlockResponse> {
-        unimplemented!()
+        Ok(self
+            .dry_run_upstream("mgo_dryRunTransactionBlock")?
+            .dry_run_transaction_block(tx_bytes)
+            .await?)
     }
 }
 
@@ -0,0 +1,99 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Bounds the blocking database tasks of the reader, so that a burst of requests is turned
+//! away once the reader is saturated instead of queueing in tokio's blocking pool until every
+//! request is slow.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use prometheus::IntGauge;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::errors::IndexerError;
+use crate::metrics::IndexerReaderMetrics;
+
+#[derive(Clone, Debug)]
+pub(crate) struct BlockingTaskLimiter {
+    permits: Arc<Semaphore>,
+    wait_timeout: Duration,
+}
+
+/// Admission of a blocking task, counted as active until dropped.
+pub(crate) struct BlockingTaskPermit {
+    _permit: OwnedSemaphorePermit,
+    active: IntGauge,
+}
+
+impl Drop for BlockingTaskPermit {
+    fn drop(&mut self) {
+        self.active.dec();
+    }
+}
+
+impl BlockingTaskLimiter {
+    pub fn new(permits: usize, wait_timeout: Duration) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(permits)),
+            wait_timeout,
+        }
+    }
+
+    /// Waits for a permit, failing with [`IndexerError::ServerOverloaded`] if none frees up
+    /// within the wait timeout.
+    pub async fn acquire(
+        &self,
+        metrics: &IndexerReaderMetrics,
+    ) -> Result<BlockingTaskPermit, IndexerError> {
+        let permit = self.permits.clone().acquire_owned();
+        match tokio::time::timeout(self.wait_timeout, permit).await {
+            Ok(permit) => {
+                metrics.blocking_tasks_active.inc();
+                Ok(BlockingTaskPermit {
+                    _permit: permit.expect("semaphore is never closed"),
+                    active: metrics.blocking_tasks_active.clone(),
+                })
+            }
+            Err(_) => {
+                metrics.total_overloaded_requests.inc();
+                Err(IndexerError::ServerOverloaded(format!(
+                    "no database task freed up within {:?}",
+                    self.wait_timeout
+                )))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prometheus::Registry;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_overloaded_once_permits_are_taken() {
+        let metrics = IndexerReaderMetrics::new(&Registry::default());
+        let limiter = BlockingTaskLimiter::new(1, Duration::from_millis(100));
+
+        // A slow query holds the only permit for longer than the wait timeout.
+        let permit = limiter.acquire(&metrics).await.unwrap();
+        let slow_query = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            std::thread::sleep(Duration::from_millis(500));
+        });
+        assert_eq!(metrics.blocking_tasks_active.get(), 1);
+
+        // The next task is turned away rather than queued behind it.
+        let err = limiter.acquire(&metrics).await.err().unwrap();
+        assert!(matches!(err, IndexerError::ServerOverloaded(_)), "{err}");
+        assert_eq!(metrics.total_overloaded_requests.get(), 1);
+
+        // It is admitted again once the slow query is done.
+        slow_query.await.unwrap();
+        assert_eq!(metrics.blocking_tasks_active.get(), 0);
+        let _permit = limiter.acquire(&metrics).await.unwrap();
+        assert_eq!(metrics.blocking_tasks_active.get(), 1);
+    }
+}
@@ -0,0 +1,180 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Proxies `devInspectTransactionBlock` and `dryRunTransactionBlock` to a fullnode, so that
+//! clients of the reader do not need a fullnode URL of their own to simulate transactions.
+
+use std::future::Future;
+use std::time::Duration;
+
+use fastcrypto::encoding::Base64;
+use jsonrpsee::core::Error as RpcError;
+use jsonrpsee::http_client::HttpClient;
+use jsonrpsee::types::error::{CallError, INVALID_PARAMS_CODE};
+use prometheus::{register_int_counter_vec_with_registry, IntCounterVec, Registry};
+use tracing::warn;
+
+use mgo_json_rpc_api::{ClientErrorCode, WriteApiClient};
+use mgo_json_rpc_types::{DevInspectArgs, DevInspectResults, DryRunTransactionBlockResponse};
+use mgo_types::base_types::MgoAddress;
+use mgo_types::mgo_serde::BigInt;
+
+use crate::errors::IndexerError;
+
+#[derive(Clone, Debug)]
+pub struct DryRunUpstreamMetrics {
+    pub upstream_dry_run_failures: IntCounterVec,
+}
+
+impl DryRunUpstreamMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            upstream_dry_run_failures: register_int_counter_vec_with_registry!(
+                "upstream_dry_run_failures",
+                "Total number of requests proxied to the dry run upstream that failed, by method and by whether the caller or the upstream is at fault",
+                &["method", "kind"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+/// Fullnode that dev inspect and dry run requests are forwarded to, within `timeout`.
+///
+/// Responses are returned as the fullnode computed them: balance and object changes depend on
+/// the state the transaction was simulated against, which is the fullnode's and may be ahead
+/// of what is indexed.
+#[derive(Clone)]
+pub struct DryRunUpstream {
+    client: HttpClient,
+    timeout: Duration,
+    metrics: DryRunUpstreamMetrics,
+}
+
+impl DryRunUpstream {
+    pub fn new(client: HttpClient, timeout: Duration, metrics: DryRunUpstreamMetrics) -> Self {
+        Self {
+            client,
+            timeout,
+            metrics,
+        }
+    }
+
+    pub async fn dev_inspect_transaction_block(
+        &self,
+        sender_address: MgoAddress,
+        tx_bytes: Base64,
+        gas_price: Option<BigInt<u64>>,
+        epoch: Option<BigInt<u64>>,
+        additional_args: Option<DevInspectArgs>,
+    ) -> Result<DevInspectResults, IndexerError> {
+        self.call(
+            "dev_inspect_transaction_block",
+            self.client.dev_inspect_transaction_block(
+                sender_address,
+                tx_bytes,
+                gas_price,
+                epoch,
+                additional_args,
+            ),
+        )
+        .await
+    }
+
+    pub async fn dry_run_transaction_block(
+        &self,
+        tx_bytes: Base64,
+    ) -> Result<DryRunTransactionBlockResponse, IndexerError> {
+        self.call(
+            "dry_run_transaction_block",
+            self.client.dry_run_transaction_block(tx_bytes),
+        )
+        .await
+    }
+
+    async fn call<T>(
+        &self,
+        method: &str,
+        request: impl Future<Output = Result<T, RpcError>>,
+    ) -> Result<T, IndexerError> {
+        let result = match tokio::time::timeout(self.timeout, request).await {
+            Ok(result) => result.map_err(upstream_error),
+            Err(_) => Err(IndexerError::FullNodeReadingError(format!(
+                "Upstream fullnode did not answer {method} within {:?}",
+                self.timeout
+            ))),
+        };
+        if let Err(e) = &result {
+            let kind = match e {
+                IndexerError::InvalidArgumentError(_) => "client",
+                _ => {
+                    warn!("Failed to proxy {method} to the upstream fullnode: {e}");
+                    "internal"
+                }
+            };
+            self.metrics
+                .upstream_dry_run_failures
+                .with_label_values(&[method, kind])
+                .inc();
+        }
+        result
+    }
+}
+
+/// Requests the fullnode rejects as invalid are the caller's fault, any other failure is
+/// reported as the upstream's.
+fn upstream_error(e: RpcError) -> IndexerError {
+    match e {
+        RpcError::Call(CallError::Custom(error_object))
+            if error_object.code() == INVALID_PARAMS_CODE
+                || ClientErrorCode::from_code(error_object.code()).is_some() =>
+        {
+            IndexerError::InvalidArgumentError(error_object.message().to_string())
+        }
+        e => IndexerError::FullNodeReadingError(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::types::ErrorObject;
+
+    #[test]
+    fn test_upstream_error_classification() {
+        let invalid_params = RpcError::Call(CallError::Custom(ErrorObject::owned(
+            INVALID_PARAMS_CODE,
+            "Invalid transaction bytes",
+            None::<()>,
+        )));
+        assert!(matches!(
+            upstream_error(invalid_params),
+            IndexerError::InvalidArgumentError(message) if message == "Invalid transaction bytes"
+        ));
+
+        let invalid_gas = RpcError::Call(CallError::Custom(ErrorObject::owned(
+            ClientErrorCode::InvalidGas.code(),
+            "Gas object does not have enough balance to cover minimal gas spend",
+            None::<()>,
+        )));
+        assert!(matches!(
+            upstream_error(invalid_gas),
+            IndexerError::InvalidArgumentError(_)
+        ));
+
+        let internal = RpcError::Call(CallError::Custom(ErrorObject::owned(
+            jsonrpsee::types::error::INTERNAL_ERROR_CODE,
+            "Storage error",
+            None::<()>,
+        )));
+        assert!(matches!(
+            upstream_error(internal),
+            IndexerError::FullNodeReadingError(_)
+        ));
+        assert!(matches!(
+            upstream_error(RpcError::RequestTimeout),
+            IndexerError::FullNodeReadingError(_)
+        ));
+    }
+}
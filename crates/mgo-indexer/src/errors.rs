@@ -3,9 +3,12 @@
 
 use fastcrypto::error::FastCryptoError;
 use jsonrpsee::core::Error as RpcError;
-use jsonrpsee::types::error::CallError;
+use jsonrpsee::types::error::{CallError, METHOD_NOT_FOUND_CODE};
+use jsonrpsee::types::ErrorObject;
 use thiserror::Error;
 
+use mgo_json_rpc_api::{DATA_PRUNED_CODE, FEATURE_NOT_AVAILABLE_CODE, SERVER_OVERLOADED_CODE};
+
 use mgo_types::base_types::ObjectIDParseError;
 use mgo_types::error::{MgoError, MgoObjectResponseError, UserInputError};
 
@@ -125,6 +128,28 @@ pub enum IndexerError {
 
     #[error("Indexer failed to send item to channel with error: `{0}`")]
     MpscChannelError(String),
+
+    #[error("Indexer database schema version mismatch, expected `{expected}` but found `{found}`")]
+    SchemaVersionMismatch { expected: String, found: String },
+
+    #[error("Events of `{0}` are not stored by this indexer, as they are on its event denylist")]
+    DeniedEventTypeError(String),
+
+    #[error("`{0}` is not available on this indexer, as it has no upstream fullnode configured")]
+    MethodNotAvailable(String),
+
+    #[error("{0} has been pruned, this indexer only serves data from checkpoint {1} on")]
+    DataPruned(String, u64),
+
+    #[error("Indexer is overloaded, please retry later: `{0}`")]
+    ServerOverloaded(String),
+
+    #[error("{feature} feature not available: requires writer >= {required}, found {found}")]
+    FeatureNotAvailable {
+        feature: String,
+        required: u64,
+        found: u64,
+    },
 }
 
 pub trait Context<T> {
@@ -138,8 +163,46 @@ impl<T> Context<T> for Result<T, IndexerError> {
 }
 
 impl From<IndexerError> for RpcError {
+    /// `DeniedEventTypeError` is the caller's fault and maps to `InvalidParams`,
+    /// `MethodNotAvailable` maps to `MethodNotFound`, `DataPruned` is reported
+    /// with its own code so that clients can fall back to an archival node, `ServerOverloaded`
+    /// with its own code so that clients can back off and retry, `FeatureNotAvailable` with its
+    /// own code so that clients can tell an outdated writer from a failure, and everything else is
+    /// reported as a server-side failure.
     fn from(e: IndexerError) -> Self {
-        RpcError::Call(CallError::Failed(e.into()))
+        match e {
+            IndexerError::DeniedEventTypeError(_) => {
+                RpcError::Call(CallError::InvalidParams(e.into()))
+            }
+            IndexerError::MethodNotAvailable(_) => RpcError::Call(CallError::Custom(
+                ErrorObject::owned(METHOD_NOT_FOUND_CODE, e.to_string(), None::<()>),
+            )),
+            IndexerError::DataPruned(..) => RpcError::Call(CallError::Custom(ErrorObject::owned(
+                DATA_PRUNED_CODE,
+                e.to_string(),
+                None::<()>,
+            ))),
+            IndexerError::ServerOverloaded(_) => RpcError::Call(CallError::Custom(
+                ErrorObject::owned(SERVER_OVERLOADED_CODE, e.to_string(), None::<()>),
+            )),
+            IndexerError::FeatureNotAvailable { .. } => RpcError::Call(CallError::Custom(
+                ErrorObject::owned(FEATURE_NOT_AVAILABLE_CODE, e.to_string(), None::<()>),
+            )),
+            _ => RpcError::Call(CallError::Failed(e.into())),
+        }
+    }
+}
+
+impl IndexerError {
+    /// Like the `RpcError` conversion, but also reports `InvalidArgumentError` as
+    /// `InvalidParams`, for endpoints whose argument errors are known to be the caller's fault.
+    pub fn into_invalid_params(self) -> RpcError {
+        match self {
+            IndexerError::InvalidArgumentError(_) => {
+                RpcError::Call(CallError::InvalidParams(self.into()))
+            }
+            _ => self.into(),
+        }
     }
 }
 
@@ -0,0 +1,347 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Event types the writer drops at ingestion, typically spam that would otherwise dominate
+//! the events table. Dropped events are counted but never stored, so the reader refuses event
+//! queries that could only match them instead of answering with an empty page.
+
+use std::collections::BTreeSet;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use axum::{extract::Extension, http::StatusCode, routing::post, Router};
+use move_core_types::language_storage::StructTag;
+use tracing::{info, warn};
+
+use mgo_json_rpc::kill_switch::MethodKillSwitch;
+use mgo_json_rpc_types::EventFilter;
+use mgo_types::base_types::ObjectID;
+use mgo_types::event::Event;
+use mgo_types::parse_mgo_struct_tag;
+
+use crate::errors::IndexerError;
+use crate::metrics::IndexerMetrics;
+
+pub const RELOAD_EVENT_DENYLIST_ROUTE: &str = "/event-denylist/reload";
+
+/// Event types that are not stored. An entry is either an exact event type, type parameters
+/// included, or a package, which denies every event type defined in it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EventDenylist {
+    types: BTreeSet<StructTag>,
+    packages: BTreeSet<ObjectID>,
+}
+
+impl EventDenylist {
+    pub fn new(
+        types: impl IntoIterator<Item = StructTag>,
+        packages: impl IntoIterator<Item = ObjectID>,
+    ) -> Self {
+        Self {
+            types: types.into_iter().collect(),
+            packages: packages.into_iter().collect(),
+        }
+    }
+
+    /// Parses one entry per line, either a package ID or an event type. Blank lines and lines
+    /// starting with `#` are ignored.
+    pub fn parse(s: &str) -> Result<Self, IndexerError> {
+        let mut denylist = Self::default();
+        for (line_number, line) in s.lines().enumerate() {
+            let entry = line.trim();
+            if entry.is_empty() || entry.starts_with('#') {
+                continue;
+            }
+            if let Ok(package) = ObjectID::from_hex_literal(entry) {
+                denylist.packages.insert(package);
+            } else {
+                let event_type = parse_mgo_struct_tag(entry).map_err(|e| {
+                    IndexerError::InvalidArgumentError(format!(
+                        "Invalid event denylist entry `{entry}` on line {}: {e}",
+                        line_number + 1
+                    ))
+                })?;
+                denylist.types.insert(event_type);
+            }
+        }
+        Ok(denylist)
+    }
+
+    pub fn read_from_file(path: &Path) -> Result<Self, IndexerError> {
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            IndexerError::InvalidArgumentError(format!(
+                "Failed to read event denylist {}: {e}",
+                path.display()
+            ))
+        })?;
+        Self::parse(&contents)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty() && self.packages.is_empty()
+    }
+
+    pub fn is_denied(&self, event_type: &StructTag) -> bool {
+        self.types.contains(event_type)
+            || self.packages.contains(&ObjectID::from(event_type.address))
+    }
+
+    /// Whether `event` should be stored, counting it as dropped in `metrics` otherwise.
+    pub fn allows(&self, event: &Event, metrics: &IndexerMetrics) -> bool {
+        if !self.is_denied(&event.type_) {
+            return true;
+        }
+        metrics
+            .dropped_denied_events
+            .with_label_values(&[&event.type_.to_string()])
+            .inc();
+        false
+    }
+
+    /// Rejects filters that can only match denied events, whose results would otherwise be
+    /// silently empty.
+    pub fn check_filter(&self, filter: &EventFilter) -> Result<(), IndexerError> {
+        match filter {
+            EventFilter::MoveEventType(event_type) if self.is_denied(event_type) => Err(
+                IndexerError::DeniedEventTypeError(format!("event type {event_type}")),
+            ),
+            EventFilter::MoveEventModule { package, module } if self.packages.contains(package) => {
+                Err(IndexerError::DeniedEventTypeError(format!(
+                    "event types of module {package}::{module}"
+                )))
+            }
+            EventFilter::And(left, right) => {
+                self.check_filter(left)?;
+                self.check_filter(right)
+            }
+            EventFilter::All(filters) => filters.iter().try_for_each(|f| self.check_filter(f)),
+            EventFilter::Or(left, right) => self
+                .check_filter(left)
+                .or_else(|_| self.check_filter(right)),
+            EventFilter::Any(filters) if !filters.is_empty() => {
+                let mut result = Ok(());
+                for filter in filters {
+                    result = self.check_filter(filter);
+                    if result.is_ok() {
+                        break;
+                    }
+                }
+                result
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// An [`EventDenylist`] shared by the writer and the reader of an indexer, which can be
+/// reloaded from the file it was read from while they run.
+#[derive(Clone, Debug, Default)]
+pub struct SharedEventDenylist {
+    path: Option<PathBuf>,
+    denylist: Arc<ArcSwap<EventDenylist>>,
+}
+
+impl SharedEventDenylist {
+    pub fn new(denylist: EventDenylist) -> Self {
+        Self {
+            path: None,
+            denylist: Arc::new(ArcSwap::from_pointee(denylist)),
+        }
+    }
+
+    /// Reads the denylist at `path`, or denies nothing when there is none.
+    pub fn from_path(path: Option<&Path>) -> Result<Self, IndexerError> {
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+        let denylist = EventDenylist::read_from_file(path)?;
+        info!(path = %path.display(), ?denylist, "Loaded event denylist");
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            denylist: Arc::new(ArcSwap::from_pointee(denylist)),
+        })
+    }
+
+    pub fn load(&self) -> Arc<EventDenylist> {
+        self.denylist.load_full()
+    }
+
+    pub fn store(&self, denylist: EventDenylist) {
+        self.denylist.store(Arc::new(denylist));
+    }
+
+    /// Reads the denylist file again. The current denylist is kept if the file is invalid.
+    pub fn reload(&self) -> Result<Arc<EventDenylist>, IndexerError> {
+        let path = self.path.as_ref().ok_or_else(|| {
+            IndexerError::NotSupportedError(
+                "Event denylist was not read from a file and cannot be reloaded".to_string(),
+            )
+        })?;
+        let denylist = EventDenylist::read_from_file(path)?;
+        info!(path = %path.display(), ?denylist, "Reloaded event denylist");
+        self.store(denylist);
+        Ok(self.load())
+    }
+}
+
+/// Serves [`RELOAD_EVENT_DENYLIST_ROUTE`], which reloads `denylist` from its file on `POST`,
+/// and the routes of `kill_switch` if the JSON-RPC server consults one.
+pub fn start_admin_server(
+    addr: SocketAddr,
+    denylist: SharedEventDenylist,
+    kill_switch: Option<&MethodKillSwitch>,
+) {
+    let mut app = Router::new()
+        .route(RELOAD_EVENT_DENYLIST_ROUTE, post(reload_event_denylist))
+        .layer(Extension(denylist));
+    if let Some(kill_switch) = kill_switch {
+        app = app.merge(kill_switch.admin_router());
+    }
+    info!("Starting indexer admin server on {addr}");
+    tokio::spawn(async move {
+        axum::Server::bind(&addr)
+            .serve(app.into_make_service())
+            .await
+            .unwrap();
+    });
+}
+
+async fn reload_event_denylist(
+    Extension(denylist): Extension<SharedEventDenylist>,
+) -> (StatusCode, String) {
+    match denylist.reload() {
+        Ok(denylist) => (StatusCode::OK, format!("{denylist:?}")),
+        Err(e) => {
+            warn!("Failed to reload event denylist: {e}");
+            (StatusCode::BAD_REQUEST, e.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use move_core_types::identifier::Identifier;
+    use prometheus::Registry;
+
+    use mgo_types::base_types::MgoAddress;
+
+    use super::*;
+
+    fn event(event_type: &str) -> Event {
+        let type_ = parse_mgo_struct_tag(event_type).unwrap();
+        Event {
+            package_id: ObjectID::from(type_.address),
+            transaction_module: type_.module.clone(),
+            sender: MgoAddress::ZERO,
+            type_,
+            contents: vec![],
+        }
+    }
+
+    #[test]
+    fn test_parse_denylist() {
+        let denylist = EventDenylist::parse(
+            "# spam\n\n0x42::spam::Airdrop\n  0x43  \n0x2::coin::CoinMetadata<0x42::spam::SPAM>\n",
+        )
+        .unwrap();
+        assert!(denylist.is_denied(&parse_mgo_struct_tag("0x42::spam::Airdrop").unwrap()));
+        assert!(!denylist.is_denied(&parse_mgo_struct_tag("0x42::spam::Other").unwrap()));
+        assert!(denylist.is_denied(&parse_mgo_struct_tag("0x43::any::Event").unwrap()));
+        assert!(denylist.is_denied(
+            &parse_mgo_struct_tag("0x2::coin::CoinMetadata<0x42::spam::SPAM>").unwrap()
+        ));
+        assert!(!denylist
+            .is_denied(&parse_mgo_struct_tag("0x2::coin::CoinMetadata<0x2::mgo::MGO>").unwrap()));
+
+        let err = EventDenylist::parse("0x42::spam::Airdrop\nnot a type").unwrap_err();
+        assert!(err.to_string().contains("line 2"), "{err}");
+    }
+
+    #[test]
+    fn test_denied_events_are_counted_and_rejected_in_queries() {
+        let registry = Registry::new();
+        let metrics = IndexerMetrics::new(&registry);
+        let denylist = EventDenylist::parse("0x42::spam::Airdrop").unwrap();
+
+        let events = [
+            event("0x42::spam::Airdrop"),
+            event("0x42::spam::Transfer"),
+            event("0x42::spam::Airdrop"),
+        ];
+        let stored = events
+            .iter()
+            .filter(|e| denylist.allows(e, &metrics))
+            .collect::<Vec<_>>();
+        assert_eq!(stored, vec![&events[1]]);
+
+        let dropped = |event_type: &str| {
+            let event_type = parse_mgo_struct_tag(event_type).unwrap().to_string();
+            metrics
+                .dropped_denied_events
+                .with_label_values(&[&event_type])
+                .get()
+        };
+        assert_eq!(dropped("0x42::spam::Airdrop"), 2);
+        assert_eq!(dropped("0x42::spam::Transfer"), 0);
+
+        let denied = EventFilter::MoveEventType(events[0].type_.clone());
+        let allowed = EventFilter::MoveEventType(events[1].type_.clone());
+        let sender = EventFilter::Sender(MgoAddress::ZERO);
+        assert!(matches!(
+            denylist.check_filter(&denied),
+            Err(IndexerError::DeniedEventTypeError(_))
+        ));
+        assert!(denylist.check_filter(&allowed).is_ok());
+        assert!(denylist.check_filter(&sender).is_ok());
+        assert!(denylist
+            .check_filter(&EventFilter::And(
+                Box::new(sender.clone()),
+                Box::new(denied.clone())
+            ))
+            .is_err());
+        assert!(denylist
+            .check_filter(&EventFilter::Or(
+                Box::new(allowed),
+                Box::new(denied.clone())
+            ))
+            .is_ok());
+        assert!(denylist
+            .check_filter(&EventFilter::Any(vec![denied.clone(), denied]))
+            .is_err());
+
+        let package_denylist = EventDenylist::new([], [ObjectID::from(events[0].type_.address)]);
+        let module = EventFilter::MoveEventModule {
+            package: ObjectID::from(events[0].type_.address),
+            module: Identifier::new("spam").unwrap(),
+        };
+        assert!(package_denylist.check_filter(&module).is_err());
+        assert!(denylist.check_filter(&module).is_ok());
+    }
+
+    #[test]
+    fn test_reload_denylist() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "0x42::spam::Airdrop").unwrap();
+        let shared = SharedEventDenylist::from_path(Some(file.path())).unwrap();
+        let airdrop = parse_mgo_struct_tag("0x42::spam::Airdrop").unwrap();
+        let transfer = parse_mgo_struct_tag("0x42::spam::Transfer").unwrap();
+        assert!(shared.load().is_denied(&airdrop));
+
+        writeln!(file, "0x42::spam::Transfer").unwrap();
+        shared.reload().unwrap();
+        assert!(shared.load().is_denied(&transfer));
+
+        // An invalid file leaves the current denylist in place.
+        writeln!(file, "not a type").unwrap();
+        assert!(shared.reload().is_err());
+        assert!(shared.load().is_denied(&airdrop));
+        assert!(shared.load().is_denied(&transfer));
+
+        assert!(SharedEventDenylist::default().load().is_empty());
+        assert!(SharedEventDenylist::default().reload().is_err());
+    }
+}
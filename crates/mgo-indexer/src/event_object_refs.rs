@@ -0,0 +1,180 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Objects referenced by events, so that the events mentioning an object can be listed without
+//! knowing their types. Only events of configured types are decoded at ingestion, for the `ID`
+//! and `UID` values anywhere in their fields, as decoding every event would slow ingestion down
+//! for types no one looks objects up by.
+
+use std::collections::BTreeSet;
+
+use move_bytecode_utils::module_cache::GetModule;
+use move_core_types::annotated_value::{MoveStruct, MoveValue};
+use move_core_types::language_storage::StructTag;
+
+use mgo_types::base_types::ObjectID;
+use mgo_types::event::Event;
+use mgo_types::id::ID;
+use mgo_types::object::MoveObject;
+use mgo_types::parse_mgo_struct_tag;
+
+use crate::errors::IndexerError;
+
+/// Event types whose object references are indexed. An entry without type parameters matches
+/// every instantiation of its type, otherwise only the exact type.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ObjectRefEventTypes {
+    types: BTreeSet<StructTag>,
+}
+
+impl ObjectRefEventTypes {
+    pub fn new(types: impl IntoIterator<Item = StructTag>) -> Self {
+        Self {
+            types: types.into_iter().collect(),
+        }
+    }
+
+    pub fn parse<S: AsRef<str>>(entries: &[S]) -> Result<Self, IndexerError> {
+        let types = entries
+            .iter()
+            .map(|entry| {
+                let entry = entry.as_ref().trim();
+                parse_mgo_struct_tag(entry).map_err(|e| {
+                    IndexerError::InvalidArgumentError(format!(
+                        "Invalid object reference event type `{entry}`: {e}"
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(types))
+    }
+
+    pub fn is_indexed(&self, event_type: &StructTag) -> bool {
+        self.types.iter().any(|indexed| {
+            indexed == event_type
+                || (indexed.type_params.is_empty()
+                    && indexed.address == event_type.address
+                    && indexed.module == event_type.module
+                    && indexed.name == event_type.name)
+        })
+    }
+
+    /// Canonical form of the configured types, as reported to clients.
+    pub fn to_canonical_strings(&self) -> Vec<String> {
+        self.types
+            .iter()
+            .map(|t| t.to_canonical_string(/* with_prefix */ true))
+            .collect()
+    }
+
+    /// Objects referenced by `event`, or none if its type is not indexed.
+    pub fn object_refs(
+        &self,
+        event: &Event,
+        module_resolver: &impl GetModule,
+    ) -> Result<Vec<ObjectID>, IndexerError> {
+        if !self.is_indexed(&event.type_) {
+            return Ok(vec![]);
+        }
+        let layout = MoveObject::get_layout_from_struct_tag(event.type_.clone(), module_resolver)?;
+        let move_struct = MoveStruct::simple_deserialize(&event.contents, &layout)
+            .map_err(|e| IndexerError::SerdeError(e.to_string()))?;
+        Ok(referenced_objects(&move_struct))
+    }
+}
+
+/// Objects referenced by an `ID` anywhere in `move_struct`, including those within a `UID`,
+/// each once.
+pub fn referenced_objects(move_struct: &MoveStruct) -> Vec<ObjectID> {
+    let mut object_ids = BTreeSet::new();
+    collect_struct(move_struct, &mut object_ids);
+    object_ids.into_iter().collect()
+}
+
+fn collect_struct(move_struct: &MoveStruct, object_ids: &mut BTreeSet<ObjectID>) {
+    if move_struct.type_ == ID::type_() {
+        if let [(_, MoveValue::Address(address))] = move_struct.fields.as_slice() {
+            object_ids.insert(ObjectID::from(*address));
+        }
+        return;
+    }
+    for (_, value) in &move_struct.fields {
+        collect_value(value, object_ids);
+    }
+}
+
+fn collect_value(value: &MoveValue, object_ids: &mut BTreeSet<ObjectID>) {
+    match value {
+        MoveValue::Struct(move_struct) => collect_struct(move_struct, object_ids),
+        MoveValue::Vector(values) => {
+            for value in values {
+                collect_value(value, object_ids);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use move_core_types::identifier::Identifier;
+
+    use mgo_types::id::UID;
+
+    use super::*;
+
+    fn id_value(id: ObjectID) -> MoveValue {
+        MoveValue::Struct(MoveStruct {
+            type_: ID::type_(),
+            fields: vec![(
+                Identifier::new("bytes").unwrap(),
+                MoveValue::Address(id.into()),
+            )],
+        })
+    }
+
+    #[test]
+    fn test_is_indexed() {
+        let types = ObjectRefEventTypes::parse(&[
+            "0x42::market::Listed",
+            "0x42::pool::Swap<0x2::mgo::MGO>",
+        ])
+        .unwrap();
+        let is_indexed = |t: &str| types.is_indexed(&parse_mgo_struct_tag(t).unwrap());
+        assert!(is_indexed("0x42::market::Listed"));
+        assert!(is_indexed("0x42::market::Listed<0x42::nft::Nft>"));
+        assert!(is_indexed("0x42::pool::Swap<0x2::mgo::MGO>"));
+        assert!(!is_indexed("0x42::pool::Swap<0x42::usdc::USDC>"));
+        assert!(!is_indexed("0x42::market::Delisted"));
+
+        assert!(ObjectRefEventTypes::parse(&["not a type"]).is_err());
+    }
+
+    #[test]
+    fn test_referenced_objects() {
+        let [listing, item, other] = [ObjectID::random(), ObjectID::random(), ObjectID::random()];
+        let uid = MoveValue::Struct(MoveStruct {
+            type_: UID::type_(),
+            fields: vec![(Identifier::new("id").unwrap(), id_value(listing))],
+        });
+        let event = MoveStruct {
+            type_: parse_mgo_struct_tag("0x42::market::Listed").unwrap(),
+            fields: vec![
+                (Identifier::new("listing").unwrap(), uid),
+                (Identifier::new("item").unwrap(), id_value(item)),
+                (
+                    Identifier::new("bundle").unwrap(),
+                    MoveValue::Vector(vec![id_value(other), id_value(item)]),
+                ),
+                (
+                    Identifier::new("seller").unwrap(),
+                    MoveValue::Address(ObjectID::random().into()),
+                ),
+            ],
+        };
+
+        let mut expected = vec![listing, item, other];
+        expected.sort();
+        assert_eq!(referenced_objects(&event), expected);
+    }
+}
@@ -0,0 +1,161 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use tokio::sync::Notify;
+use tracing::info;
+
+use crate::metrics::CheckpointFetcherMetrics;
+
+/// Number of downloaded checkpoints a fetcher holds for its handlers, unless configured
+/// otherwise.
+pub const DEFAULT_CHECKPOINT_BUFFER_SIZE: usize = 1000;
+
+/// Limits of the checkpoints a fetcher has delivered and its handlers not processed yet. The
+/// fetcher pauses while either limit is reached, and resumes once handlers catch up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BufferLimits {
+    /// High-water mark, in checkpoints.
+    pub max_checkpoints: usize,
+    /// Cap on the estimated size of the buffered checkpoints, in bytes. Unbounded when unset.
+    pub max_bytes: Option<u64>,
+}
+
+impl Default for BufferLimits {
+    fn default() -> Self {
+        Self {
+            max_checkpoints: DEFAULT_CHECKPOINT_BUFFER_SIZE,
+            max_bytes: None,
+        }
+    }
+}
+
+/// Checkpoints a fetcher delivered that its handlers have not processed yet, shared between
+/// both: the fetcher reserves room for every checkpoint before sending it, and the runner
+/// releases checkpoints once handlers processed them.
+#[derive(Clone)]
+pub struct CheckpointBuffer {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    limits: BufferLimits,
+    state: Mutex<BufferState>,
+    released: Notify,
+    metrics: CheckpointFetcherMetrics,
+}
+
+#[derive(Default)]
+struct BufferState {
+    /// Estimated size of every buffered checkpoint, in delivery order.
+    sizes: VecDeque<u64>,
+    bytes: u64,
+}
+
+impl BufferState {
+    /// A checkpoint is always admitted into an empty buffer, so that one larger than the byte
+    /// cap does not stall ingestion.
+    fn has_room(&self, limits: &BufferLimits, bytes: u64) -> bool {
+        self.sizes.is_empty()
+            || (self.sizes.len() < limits.max_checkpoints
+                && limits
+                    .max_bytes
+                    .map_or(true, |max_bytes| self.bytes.saturating_add(bytes) <= max_bytes))
+    }
+}
+
+impl CheckpointBuffer {
+    pub fn new(limits: BufferLimits, metrics: CheckpointFetcherMetrics) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                limits,
+                state: Mutex::new(BufferState::default()),
+                released: Notify::new(),
+                metrics,
+            }),
+        }
+    }
+
+    pub fn limits(&self) -> BufferLimits {
+        self.inner.limits
+    }
+
+    /// Number of checkpoints buffered, and their estimated size in bytes.
+    pub fn buffered(&self) -> (usize, u64) {
+        let state = self.inner.state.lock().unwrap();
+        (state.sizes.len(), state.bytes)
+    }
+
+    /// Waits until there is room for a checkpoint of `bytes`, then counts it as buffered.
+    pub async fn reserve(&self, bytes: u64) {
+        let limits = &self.inner.limits;
+        let mut paused_since = None;
+        loop {
+            {
+                let mut state = self.inner.state.lock().unwrap();
+                if state.has_room(limits, bytes) {
+                    state.sizes.push_back(bytes);
+                    state.bytes += bytes;
+                    self.inner.metrics.checkpoints_awaiting_handlers.inc();
+                    self.inner
+                        .metrics
+                        .checkpoint_bytes_awaiting_handlers
+                        .add(bytes as i64);
+                    break;
+                }
+                if paused_since.is_none() {
+                    info!(
+                        checkpoints = state.sizes.len(),
+                        bytes = state.bytes,
+                        "Handlers are lagging, pausing the checkpoint fetcher until they catch up"
+                    );
+                    paused_since = Some(Instant::now());
+                }
+            }
+            self.inner.released.notified().await;
+        }
+        if let Some(paused_since) = paused_since {
+            let paused = paused_since.elapsed();
+            info!(?paused, "Handlers caught up, resuming the checkpoint fetcher");
+            self.inner
+                .metrics
+                .fetcher_pause_duration
+                .observe(paused.as_secs_f64());
+        }
+    }
+
+    /// Releases the `count` oldest buffered checkpoints, once handlers processed them.
+    pub fn release(&self, count: usize) {
+        let mut state = self.inner.state.lock().unwrap();
+        let count = count.min(state.sizes.len());
+        let bytes: u64 = state.sizes.drain(..count).sum();
+        state.bytes -= bytes;
+        drop(state);
+        self.inner
+            .metrics
+            .checkpoints_awaiting_handlers
+            .sub(count as i64);
+        self.inner
+            .metrics
+            .checkpoint_bytes_awaiting_handlers
+            .sub(bytes as i64);
+        self.inner.released.notify_one();
+    }
+}
+
+impl Drop for Inner {
+    /// Checkpoints still buffered when a handler restarts with a new fetcher are dropped with
+    /// the buffer.
+    fn drop(&mut self) {
+        let state = self.state.get_mut().unwrap();
+        self.metrics
+            .checkpoints_awaiting_handlers
+            .sub(state.sizes.len() as i64);
+        self.metrics
+            .checkpoint_bytes_awaiting_handlers
+            .sub(state.bytes as i64);
+    }
+}
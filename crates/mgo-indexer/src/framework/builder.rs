@@ -1,28 +1,51 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
 use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
+use tokio::sync::watch;
 
-use super::fetcher::CheckpointFetcher;
+use super::interface::{CheckpointGap, CheckpointGapHook, ProgressStore};
+use super::pipeline::{
+    checkpoint_channel, BufferLimits, CheckpointBuffer, CheckpointFetcher, FetcherFactory,
+    LocalArchiveSource, Pipeline, Pruner, DEFAULT_CHECKPOINT_BUFFER_SIZE,
+};
 use super::Handler;
+use crate::errors::IndexerError;
+use crate::metrics::CheckpointFetcherMetrics;
 
 pub struct IndexerBuilder {
     rest_url: Option<String>,
-    handlers: Vec<Box<dyn Handler>>,
-    last_downloaded_checkpoint: Option<CheckpointSequenceNumber>,
+    secondary_rest_url: Option<String>,
+    local_archive_dir: Option<PathBuf>,
+    pipeline: Pipeline,
     checkpoint_buffer_size: usize,
+    checkpoint_buffer_max_bytes: Option<u64>,
+    checkpoint_fetch_concurrency: usize,
+    checkpoint_gap_grace_period: Duration,
+    checkpoint_gap_hooks: Vec<CheckpointGapHook>,
+    fetcher_metrics: Option<CheckpointFetcherMetrics>,
 }
 
 impl IndexerBuilder {
-    const DEFAULT_CHECKPOINT_BUFFER_SIZE: usize = 1000;
+    const DEFAULT_CHECKPOINT_GAP_GRACE_PERIOD: Duration = Duration::from_secs(60);
 
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         Self {
             rest_url: None,
-            handlers: Vec::new(),
-            last_downloaded_checkpoint: None,
-            checkpoint_buffer_size: Self::DEFAULT_CHECKPOINT_BUFFER_SIZE,
+            secondary_rest_url: None,
+            local_archive_dir: None,
+            pipeline: Pipeline::new(),
+            checkpoint_buffer_size: DEFAULT_CHECKPOINT_BUFFER_SIZE,
+            checkpoint_buffer_max_bytes: None,
+            checkpoint_fetch_concurrency: CheckpointFetcher::DEFAULT_DOWNLOAD_CONCURRENCY,
+            checkpoint_gap_grace_period: Self::DEFAULT_CHECKPOINT_GAP_GRACE_PERIOD,
+            checkpoint_gap_hooks: Vec::new(),
+            fetcher_metrics: None,
         }
     }
 
@@ -31,52 +54,207 @@ impl IndexerBuilder {
         self
     }
 
-    pub fn handler<T: Handler + 'static>(mut self, handler: T) -> Self {
-        self.handlers.push(Box::new(handler));
+    /// Fullnode whose REST API missing checkpoints are fetched from when the primary one
+    /// serves a range with a gap.
+    pub fn secondary_rest_url<T: Into<String>>(mut self, secondary_rest_url: T) -> Self {
+        self.secondary_rest_url = Some(secondary_rest_url.into());
+        self
+    }
+
+    /// Directory of archived checkpoint files that checkpoints are read from before the REST
+    /// API, which remains the source of those missing from the archive or failing to decode.
+    /// Without a REST url, checkpoints are only read from the archive.
+    pub fn local_archive_dir<T: Into<PathBuf>>(mut self, local_archive_dir: T) -> Self {
+        self.local_archive_dir = Some(local_archive_dir.into());
+        self
+    }
+
+    /// Registers a handler. Every handler is fed by its own fetcher and tracks its own
+    /// watermark, so a slow or failing handler does not hold back the others.
+    pub fn register_handler<T: Handler + 'static>(mut self, handler: T) -> Self {
+        self.pipeline = self.pipeline.register_handler(handler);
+        self
+    }
+
+    /// Same as [`Self::register_handler`].
+    pub fn handler<T: Handler + 'static>(self, handler: T) -> Self {
+        self.register_handler(handler)
+    }
+
+    /// Where handler watermarks are loaded from on start and saved to after every batch, e.g.
+    /// a store committing them together with the handlers' own writes. Defaults to an
+    /// [`InMemoryProgressStore`](super::InMemoryProgressStore).
+    pub fn with_progress_store<T: ProgressStore + 'static>(mut self, progress_store: T) -> Self {
+        self.pipeline = self.pipeline.with_progress_store(progress_store);
+        self
+    }
+
+    /// Deletes data that falls out of the retention policy of `pruner` while the indexer runs,
+    /// never past the lowest watermark of the registered handlers.
+    pub fn pruner(mut self, pruner: Pruner) -> Self {
+        self.pipeline = self.pipeline.pruner(pruner);
         self
     }
 
+    /// Checkpoint that handlers without a watermark in the progress store start right after.
     pub fn last_downloaded_checkpoint(
         mut self,
         last_downloaded_checkpoint: Option<CheckpointSequenceNumber>,
     ) -> Self {
-        self.last_downloaded_checkpoint = last_downloaded_checkpoint;
+        self.pipeline = self
+            .pipeline
+            .last_downloaded_checkpoint(last_downloaded_checkpoint);
         self
     }
 
+    /// Last checkpoint handlers process, after which [`run`](Self::run) returns once all of
+    /// them are done.
+    pub fn end_checkpoint(mut self, end_checkpoint: CheckpointSequenceNumber) -> Self {
+        self.pipeline = self.pipeline.end_checkpoint(end_checkpoint);
+        self
+    }
+
+    /// High-water mark of the checkpoints each fetcher delivers ahead of its handler: once
+    /// that many are waiting to be processed, the fetcher pauses until the handler catches up.
     pub fn checkpoint_buffer_size(mut self, checkpoint_buffer_size: usize) -> Self {
         self.checkpoint_buffer_size = checkpoint_buffer_size;
         self
     }
 
-    pub async fn run(self) {
-        let (downloaded_checkpoint_data_sender, downloaded_checkpoint_data_receiver) =
-            mango_metrics::metered_channel::channel(
-                self.checkpoint_buffer_size,
-                &mango_metrics::get_metrics()
-                    .unwrap()
-                    .channels
-                    .with_label_values(&["checkpoint_tx_downloading"]),
-            );
+    /// Cap on the estimated size of the checkpoints each fetcher delivers ahead of its handler,
+    /// past which the fetcher pauses as well. Unbounded by default.
+    pub fn checkpoint_buffer_max_bytes(mut self, checkpoint_buffer_max_bytes: u64) -> Self {
+        self.checkpoint_buffer_max_bytes = Some(checkpoint_buffer_max_bytes);
+        self
+    }
+
+    /// Maximum number of checkpoints each fetcher downloads in parallel, including downloaded
+    /// checkpoints waiting for earlier ones to arrive.
+    pub fn checkpoint_fetch_concurrency(mut self, checkpoint_fetch_concurrency: usize) -> Self {
+        self.checkpoint_fetch_concurrency = checkpoint_fetch_concurrency;
+        self
+    }
 
+    /// How long a checkpoint may be missing while later ones are available before it is
+    /// reported as a gap.
+    pub fn checkpoint_gap_grace_period(mut self, checkpoint_gap_grace_period: Duration) -> Self {
+        self.checkpoint_gap_grace_period = checkpoint_gap_grace_period;
+        self
+    }
+
+    /// Registers a hook called for every detected checkpoint gap, once per registered handler
+    /// since each handler fetches checkpoints on its own.
+    pub fn on_checkpoint_gap<F: Fn(&CheckpointGap) + Send + Sync + 'static>(
+        mut self,
+        hook: F,
+    ) -> Self {
+        self.checkpoint_gap_hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Registers a hook called once when the indexer starts.
+    pub fn on_start<F: Fn() + Send + Sync + 'static>(mut self, hook: F) -> Self {
+        self.pipeline = self.pipeline.on_start(hook);
+        self
+    }
+
+    /// Registers a hook called with the new watermark every time a handler's watermark
+    /// advances, once per registered handler.
+    pub fn on_checkpoint_committed<F: Fn(CheckpointSequenceNumber) + Send + Sync + 'static>(
+        mut self,
+        hook: F,
+    ) -> Self {
+        self.pipeline = self.pipeline.on_checkpoint_committed(hook);
+        self
+    }
+
+    /// Registers a hook called every time a handler fails, before it is restarted.
+    pub fn on_error<F: Fn(&IndexerError) + Send + Sync + 'static>(mut self, hook: F) -> Self {
+        self.pipeline = self.pipeline.on_error(hook);
+        self
+    }
+
+    /// How long ingestion waits on each hook call before skipping it with a warning.
+    pub fn hook_timeout(mut self, hook_timeout: Duration) -> Self {
+        self.pipeline = self.pipeline.hook_timeout(hook_timeout);
+        self
+    }
+
+    /// Stops the indexer once `signal` is set to `true`. See [`Pipeline::with_shutdown`].
+    pub fn with_shutdown(mut self, signal: watch::Receiver<bool>) -> Self {
+        self.pipeline = self.pipeline.with_shutdown(signal);
+        self
+    }
+
+    /// How long handlers are given to finish their batch once shutdown is requested. See
+    /// [`Pipeline::shutdown_deadline`].
+    pub fn shutdown_deadline(mut self, shutdown_deadline: Duration) -> Self {
+        self.pipeline = self.pipeline.shutdown_deadline(shutdown_deadline);
+        self
+    }
+
+    pub fn fetcher_metrics(mut self, fetcher_metrics: CheckpointFetcherMetrics) -> Self {
+        self.fetcher_metrics = Some(fetcher_metrics);
+        self
+    }
+
+    pub async fn run(self) {
         // experimental rest api route is found at `/rest` on the same interface as the jsonrpc
         // service
-        let rest_api_url = format!("{}/rest", self.rest_url.unwrap());
-        let fetcher = CheckpointFetcher::new(
-            mgo_rest_api::Client::new(rest_api_url),
-            self.last_downloaded_checkpoint,
-            downloaded_checkpoint_data_sender,
+        let rest_api_url = self.rest_url.map(|url| format!("{url}/rest"));
+        let local_archive_dir = self.local_archive_dir;
+        assert!(
+            rest_api_url.is_some() || local_archive_dir.is_some(),
+            "either a REST url or a local archive directory is required"
         );
-        mango_metrics::spawn_monitored_task!(fetcher.run());
-
-        assert!(!self.handlers.is_empty());
+        let secondary_rest_api_url = self.secondary_rest_url.map(|url| format!("{url}/rest"));
+        let metrics = self
+            .fetcher_metrics
+            .unwrap_or_else(|| CheckpointFetcherMetrics::new(&prometheus::Registry::default()));
+        let buffer_limits = BufferLimits {
+            max_checkpoints: self.checkpoint_buffer_size,
+            max_bytes: self.checkpoint_buffer_max_bytes,
+        };
+        let checkpoint_fetch_concurrency = self.checkpoint_fetch_concurrency;
+        let checkpoint_gap_grace_period = self.checkpoint_gap_grace_period;
+        let checkpoint_gap_hooks = Arc::new(self.checkpoint_gap_hooks);
+        let new_fetcher: FetcherFactory = Arc::new(move |last_downloaded_checkpoint| {
+            let (downloaded_checkpoint_data_sender, downloaded_checkpoint_data_receiver) =
+                checkpoint_channel(buffer_limits.max_checkpoints);
+            let hooks = checkpoint_gap_hooks.clone();
+            let mut fetcher = match &rest_api_url {
+                Some(rest_api_url) => {
+                    let fetcher = CheckpointFetcher::new(
+                        mgo_rest_api::Client::new(rest_api_url),
+                        last_downloaded_checkpoint,
+                        downloaded_checkpoint_data_sender,
+                        metrics.clone(),
+                    );
+                    match &local_archive_dir {
+                        Some(dir) => fetcher.with_local_archive(LocalArchiveSource::new(dir)),
+                        None => fetcher,
+                    }
+                }
+                None => CheckpointFetcher::new(
+                    LocalArchiveSource::new(local_archive_dir.as_ref().unwrap()),
+                    last_downloaded_checkpoint,
+                    downloaded_checkpoint_data_sender,
+                    metrics.clone(),
+                ),
+            }
+            .with_download_concurrency(checkpoint_fetch_concurrency)
+            .with_buffer(CheckpointBuffer::new(buffer_limits, metrics.clone()))
+            .with_gap_grace_period(checkpoint_gap_grace_period)
+            .with_gap_hooks(vec![Box::new(move |gap: &CheckpointGap| {
+                hooks.iter().for_each(|hook| hook(gap))
+            })]);
+            if let Some(secondary_rest_api_url) = &secondary_rest_api_url {
+                fetcher = fetcher
+                    .with_secondary_client(mgo_rest_api::Client::new(secondary_rest_api_url));
+            }
+            (fetcher, downloaded_checkpoint_data_receiver)
+        });
 
-        super::runner::run(
-            mango_metrics::metered_channel::ReceiverStream::new(
-                downloaded_checkpoint_data_receiver,
-            ),
-            self.handlers,
-        )
-        .await;
+        self.pipeline.run(new_fetcher).await;
     }
 }
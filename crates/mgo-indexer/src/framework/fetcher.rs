@@ -1,35 +1,260 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
 use mgo_rest_api::{CheckpointData, Client};
-use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
-use tracing::{info, warn};
+use mgo_types::messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSequenceNumber};
+use tracing::{error, info, warn};
+
+use super::backpressure::CheckpointBuffer;
+use super::interface::{
+    CertifiedCheckpointSource, CheckpointGap, CheckpointGapHook, CheckpointSource,
+};
+use crate::fullnode_fallback::FullnodeHealth;
+use crate::metrics::CheckpointFetcherMetrics;
+
+#[async_trait::async_trait]
+impl CheckpointSource for Client {
+    async fn get_latest_checkpoint_sequence_number(&self) -> Result<CheckpointSequenceNumber> {
+        Ok(*self.get_latest_checkpoint().await?.sequence_number())
+    }
+
+    async fn get_full_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<CheckpointData> {
+        Client::get_full_checkpoint(self, sequence_number).await
+    }
+}
+
+#[async_trait::async_trait]
+impl CertifiedCheckpointSource for Client {
+    async fn get_certified_summary(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<CertifiedCheckpointSummary> {
+        self.get_checkpoint_summary(sequence_number).await
+    }
+}
+
+/// Checkpoints stored in a local directory in the format fullnodes write them for data
+/// ingestion: one `<sequence number>.chk` file per checkpoint, holding its BCS blob.
+pub struct LocalArchiveSource {
+    dir: PathBuf,
+}
+
+impl LocalArchiveSource {
+    /// File extension of checkpoint files.
+    const EXTENSION: &'static str = "chk";
+    /// Encoding byte of BCS blobs, which checkpoint files start with.
+    const BCS_BLOB_ENCODING: u8 = 1;
+
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, sequence_number: CheckpointSequenceNumber) -> PathBuf {
+        self.dir
+            .join(format!("{sequence_number}.{}", Self::EXTENSION))
+    }
+
+    fn decode(bytes: &[u8]) -> Result<CheckpointData> {
+        match bytes.split_first() {
+            Some((&Self::BCS_BLOB_ENCODING, data)) => Ok(bcs::from_bytes(data)?),
+            Some((encoding, _)) => Err(anyhow!("unsupported blob encoding {encoding}")),
+            None => Err(anyhow!("empty checkpoint file")),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CheckpointSource for LocalArchiveSource {
+    /// Highest sequence number among the checkpoint files of the directory.
+    async fn get_latest_checkpoint_sequence_number(&self) -> Result<CheckpointSequenceNumber> {
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+        let mut latest = None;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(Self::EXTENSION) {
+                continue;
+            }
+            let sequence_number = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<CheckpointSequenceNumber>().ok());
+            latest = latest.max(sequence_number);
+        }
+        latest.ok_or_else(|| anyhow!("no checkpoints in {}", self.dir.display()))
+    }
+
+    async fn get_full_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<CheckpointData> {
+        let path = self.path(sequence_number);
+        let bytes = tokio::fs::read(&path)
+            .await
+            .map_err(|e| anyhow!("error reading {}: {e}", path.display()))?;
+        let checkpoint =
+            Self::decode(&bytes).map_err(|e| anyhow!("error decoding {}: {e}", path.display()))?;
+        if *checkpoint.checkpoint_summary.sequence_number() != sequence_number {
+            return Err(anyhow!(
+                "{} holds checkpoint {}",
+                path.display(),
+                checkpoint.checkpoint_summary.sequence_number()
+            ));
+        }
+        Ok(checkpoint)
+    }
+}
+
+/// Reads checkpoints from a local archive, falling back to `remote` for those whose file is
+/// missing or unreadable. The latest checkpoint is the one of `remote`, so that checkpoints past
+/// the end of the archive are still ingested.
+pub struct ArchiveWithFallbackSource {
+    local: LocalArchiveSource,
+    remote: Arc<dyn CheckpointSource>,
+}
+
+impl ArchiveWithFallbackSource {
+    pub fn new(local: LocalArchiveSource, remote: impl CheckpointSource + 'static) -> Self {
+        Self {
+            local,
+            remote: Arc::new(remote),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CheckpointSource for ArchiveWithFallbackSource {
+    async fn get_latest_checkpoint_sequence_number(&self) -> Result<CheckpointSequenceNumber> {
+        self.remote.get_latest_checkpoint_sequence_number().await
+    }
+
+    async fn get_full_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<CheckpointData> {
+        match self.local.get_full_checkpoint(sequence_number).await {
+            Ok(checkpoint) => Ok(checkpoint),
+            Err(e) => {
+                warn!(
+                    checkpoint = sequence_number,
+                    "falling back to remote source, checkpoint unavailable from local archive: {e}"
+                );
+                self.remote.get_full_checkpoint(sequence_number).await
+            }
+        }
+    }
+}
+
+/// The checkpoint the fetcher is stalled on.
+struct PendingGap {
+    first_missing: CheckpointSequenceNumber,
+    since: Instant,
+    /// Set once later checkpoints were found to be available and the gap was reported.
+    next_available: Option<CheckpointSequenceNumber>,
+}
 
 pub struct CheckpointFetcher {
-    client: Client,
+    client: Arc<dyn CheckpointSource>,
+    secondary_client: Option<Arc<dyn CheckpointSource>>,
     last_downloaded_checkpoint: Option<CheckpointSequenceNumber>,
     highest_known_checkpoint: CheckpointSequenceNumber,
     sender: mango_metrics::metered_channel::Sender<CheckpointData>,
+    download_concurrency: usize,
+    gap_grace_period: Duration,
+    gap_hooks: Vec<CheckpointGapHook>,
+    pending_gap: Option<PendingGap>,
+    health: Option<FullnodeHealth>,
+    buffer: Option<CheckpointBuffer>,
+    metrics: CheckpointFetcherMetrics,
 }
 
 impl CheckpointFetcher {
     const INTERVAL_PERIOD: std::time::Duration = std::time::Duration::from_secs(5);
-    const CHECKPOINT_DOWNLOAD_CONCURRENCY: usize = 100;
+    pub const DEFAULT_DOWNLOAD_CONCURRENCY: usize = 100;
+    /// Maximum number of checkpoints probed past a missing one when looking for the end of a gap.
+    const MAX_GAP_PROBE: u64 = 100;
 
     pub fn new(
-        client: Client,
+        client: impl CheckpointSource + 'static,
         last_downloaded_checkpoint: Option<CheckpointSequenceNumber>,
         sender: mango_metrics::metered_channel::Sender<CheckpointData>,
+        metrics: CheckpointFetcherMetrics,
     ) -> Self {
         Self {
-            client,
+            client: Arc::new(client),
+            secondary_client: None,
             last_downloaded_checkpoint,
             highest_known_checkpoint: 0,
             sender,
+            download_concurrency: Self::DEFAULT_DOWNLOAD_CONCURRENCY,
+            gap_grace_period: Duration::from_secs(60),
+            gap_hooks: Vec::new(),
+            pending_gap: None,
+            health: None,
+            buffer: None,
+            metrics,
         }
     }
 
+    /// Reads checkpoints from `local_archive` first, falling back to the source the fetcher was
+    /// created with for those the archive is missing or holds corrupt.
+    pub fn with_local_archive(mut self, local_archive: LocalArchiveSource) -> Self {
+        self.client = Arc::new(ArchiveWithFallbackSource {
+            local: local_archive,
+            remote: self.client,
+        });
+        self
+    }
+
+    /// Source that missing checkpoints are fetched from once a gap is detected.
+    pub fn with_secondary_client(mut self, client: impl CheckpointSource + 'static) -> Self {
+        self.secondary_client = Some(Arc::new(client));
+        self
+    }
+
+    /// Maximum number of checkpoints downloading or waiting to be delivered at any time.
+    pub fn with_download_concurrency(mut self, download_concurrency: usize) -> Self {
+        self.download_concurrency = download_concurrency.max(1);
+        self
+    }
+
+    /// How long a checkpoint may be missing before later checkpoints are probed for a gap.
+    pub fn with_gap_grace_period(mut self, gap_grace_period: Duration) -> Self {
+        self.gap_grace_period = gap_grace_period;
+        self
+    }
+
+    pub fn with_gap_hooks(mut self, gap_hooks: Vec<CheckpointGapHook>) -> Self {
+        self.gap_hooks = gap_hooks;
+        self
+    }
+
+    /// Health of the primary source, updated every time its latest checkpoint is polled.
+    pub fn with_health(mut self, health: FullnodeHealth) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    /// Pauses the fetcher while `buffer` is full, rather than letting checkpoints pile up
+    /// in memory while handlers lag behind. Handlers must release the checkpoints they
+    /// processed from the buffer for the fetcher to resume.
+    pub fn with_buffer(mut self, buffer: CheckpointBuffer) -> Self {
+        self.buffer = Some(buffer);
+        self
+    }
+
+    pub fn buffer(&self) -> Option<CheckpointBuffer> {
+        self.buffer.clone()
+    }
+
     pub async fn run(mut self) {
         let mut interval = tokio::time::interval(Self::INTERVAL_PERIOD);
         interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
@@ -52,46 +277,540 @@ impl CheckpointFetcher {
     }
 
     async fn update_highest_known_checkpoint(&mut self) -> Result<()> {
-        let checkpoint = self.client.get_latest_checkpoint().await?;
-        self.highest_known_checkpoint =
-            std::cmp::max(self.highest_known_checkpoint, *checkpoint.sequence_number());
+        let checkpoint = self.client.get_latest_checkpoint_sequence_number().await;
+        if let Some(health) = &self.health {
+            match &checkpoint {
+                Ok(_) => health.record_success(),
+                Err(_) => health.record_failure(),
+            }
+        }
+        self.highest_known_checkpoint = std::cmp::max(self.highest_known_checkpoint, checkpoint?);
         Ok(())
     }
 
+    fn next_checkpoint(&self) -> CheckpointSequenceNumber {
+        self.last_downloaded_checkpoint
+            .map(|i| i.checked_add(1).unwrap())
+            .unwrap_or(0)
+    }
+
+    /// Downloads up to `download_concurrency` checkpoints at a time and delivers them in
+    /// sequence number order. Checkpoints that arrive ahead of the next one to deliver are
+    /// buffered, and count towards the window: while the next checkpoint is slow, at most
+    /// `download_concurrency - 1` later ones are held in memory.
     async fn download_checkpoints(&mut self) -> Result<()> {
+        use futures::stream::FuturesUnordered;
         use futures::StreamExt;
-        use tap::Pipe;
 
-        let checkpoint_range = self
-            .last_downloaded_checkpoint
-            .map(|i| i.checked_add(1).unwrap())
-            .unwrap_or(0)..=self.highest_known_checkpoint;
+        let mut next_to_request = self.next_checkpoint();
+        let last = self.highest_known_checkpoint;
+        if next_to_request <= last {
+            info!("Starting download of checkpoints {next_to_request}..={last}");
+        }
+
+        let mut in_flight = FuturesUnordered::new();
+        let mut buffered = BTreeMap::new();
+        let result = loop {
+            while next_to_request <= last
+                && in_flight.len() + buffered.len() < self.download_concurrency
+            {
+                let client = self.client.clone();
+                let sequence_number = next_to_request;
+                in_flight.push(async move {
+                    let checkpoint = client.get_full_checkpoint(sequence_number).await;
+                    (sequence_number, checkpoint)
+                });
+                self.metrics.checkpoint_downloads_in_flight.inc();
+                next_to_request += 1;
+            }
+
+            if let Some(checkpoint) = buffered.remove(&self.next_checkpoint()) {
+                self.metrics.checkpoint_downloads_buffered.dec();
+                match checkpoint {
+                    Ok(checkpoint) => self.send_checkpoint(checkpoint).await,
+                    Err(e) => break Err(e),
+                }
+                continue;
+            }
+
+            let Some((sequence_number, checkpoint)) = in_flight.next().await else {
+                break Ok(());
+            };
+            self.metrics.checkpoint_downloads_in_flight.dec();
+            self.metrics.checkpoint_downloads_buffered.inc();
+            buffered.insert(sequence_number, checkpoint);
+        };
 
-        if !checkpoint_range.is_empty() {
-            info!("Starting download of checkpoints {checkpoint_range:?}");
+        // Downloads past a missing checkpoint are abandoned, they are retried once it is found.
+        self.metrics
+            .checkpoint_downloads_in_flight
+            .sub(in_flight.len() as i64);
+        self.metrics
+            .checkpoint_downloads_buffered
+            .sub(buffered.len() as i64);
+        drop(in_flight);
+        match result {
+            Ok(()) => Ok(()),
+            Err(e) => self.handle_missing_checkpoint(e).await,
         }
+    }
 
-        let mut checkpoint_stream = checkpoint_range
-            .map(|next| self.client.get_full_checkpoint(next))
-            .pipe(futures::stream::iter)
-            .buffered(Self::CHECKPOINT_DOWNLOAD_CONCURRENCY);
+    async fn send_checkpoint(&mut self, checkpoint: CheckpointData) {
+        let sequence_number = *checkpoint.checkpoint_summary.sequence_number();
+        self.last_downloaded_checkpoint = Some(sequence_number);
+        if self.pending_gap.take().is_some() {
+            self.metrics.checkpoint_gap_size.set(0);
+        }
 
-        while let Some(maybe_checkpoint) = checkpoint_stream.next().await {
-            let checkpoint = maybe_checkpoint?;
-            self.last_downloaded_checkpoint =
-                Some(*checkpoint.checkpoint_summary.sequence_number());
+        info!(
+            checkpoint = sequence_number,
+            "successfully downloaded checkpoint"
+        );
 
-            info!(
-                checkpoint = checkpoint.checkpoint_summary.sequence_number(),
-                "successfully downloaded checkpoint"
-            );
+        if let Some(buffer) = &self.buffer {
+            // The serialized size stands in for the memory the checkpoint holds.
+            let bytes = bcs::serialized_size(&checkpoint).unwrap_or_default() as u64;
+            buffer.reserve(bytes).await;
+        }
+        self.sender
+            .send(checkpoint)
+            .await
+            .expect("channel shouldn't be closed");
+    }
 
-            self.sender
-                .send(checkpoint)
+    /// Called when the next checkpoint could not be downloaded. Once it has been missing for
+    /// longer than the grace period while later checkpoints are available, the gap is reported
+    /// and, if configured, filled from the secondary source. Checkpoints are never skipped:
+    /// without a secondary source ingestion stays stalled on the first missing checkpoint.
+    async fn handle_missing_checkpoint(&mut self, error: anyhow::Error) -> Result<()> {
+        let missing = self.next_checkpoint();
+        if !matches!(&self.pending_gap, Some(pending) if pending.first_missing == missing) {
+            self.pending_gap = Some(PendingGap {
+                first_missing: missing,
+                since: Instant::now(),
+                next_available: None,
+            });
+        }
+        let pending = self.pending_gap.as_ref().unwrap();
+        let stalled_for = pending.since.elapsed();
+        if stalled_for < self.gap_grace_period {
+            return Err(error);
+        }
+
+        let next_available = match pending.next_available {
+            Some(next_available) => next_available,
+            None => {
+                let Some(next_available) = self.find_next_available(missing).await else {
+                    // Nothing after the missing checkpoint is available either, so the source is
+                    // lagging or unreachable rather than serving a hole.
+                    return Err(error);
+                };
+                self.report_gap(CheckpointGap {
+                    first_missing: missing,
+                    next_available,
+                    stalled_for,
+                });
+                next_available
+            }
+        };
+
+        if let Some(secondary_client) = self.secondary_client.clone() {
+            let mut fetched = Vec::new();
+            for sequence_number in missing..next_available {
+                match secondary_client.get_full_checkpoint(sequence_number).await {
+                    Ok(checkpoint) => fetched.push(checkpoint),
+                    Err(e) => {
+                        warn!(
+                            checkpoint = sequence_number,
+                            "error downloading checkpoint from secondary source: {e}"
+                        );
+                        break;
+                    }
+                }
+            }
+            let recovered = !fetched.is_empty();
+            for checkpoint in fetched {
+                self.metrics.total_checkpoints_fetched_from_secondary.inc();
+                self.send_checkpoint(checkpoint).await;
+            }
+            if recovered {
+                return Ok(());
+            }
+        }
+
+        Err(anyhow!(
+            "stalled on checkpoint {missing}, checkpoints {missing}..{next_available} are missing \
+             from the source: {error}"
+        ))
+    }
+
+    async fn find_next_available(
+        &self,
+        missing: CheckpointSequenceNumber,
+    ) -> Option<CheckpointSequenceNumber> {
+        let last = std::cmp::min(
+            self.highest_known_checkpoint,
+            missing.saturating_add(Self::MAX_GAP_PROBE),
+        );
+        for sequence_number in missing + 1..=last {
+            if self
+                .client
+                .get_full_checkpoint(sequence_number)
                 .await
-                .expect("channel shouldn't be closed");
+                .is_ok()
+            {
+                return Some(sequence_number);
+            }
         }
+        None
+    }
 
-        Ok(())
+    fn report_gap(&mut self, gap: CheckpointGap) {
+        error!(
+            first_missing = gap.first_missing,
+            next_available = gap.next_available,
+            stalled_for_secs = gap.stalled_for.as_secs(),
+            "detected gap in checkpoints served by the source"
+        );
+        self.metrics.total_checkpoint_gaps_detected.inc();
+        self.metrics
+            .checkpoint_gap_size
+            .set((gap.next_available - gap.first_missing) as i64);
+        for hook in &self.gap_hooks {
+            hook(&gap);
+        }
+        if let Some(pending) = &mut self.pending_gap {
+            pending.next_available = Some(gap.next_available);
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    use mango_metrics::metered_channel::Receiver;
+    use mgo_types::storage::ReadStore;
+    use prometheus::{IntGauge, Registry};
+    use simulacrum::Simulacrum;
+    use tokio::sync::Semaphore;
+
+    use super::*;
+
+    pub(crate) const LATEST_CHECKPOINT: CheckpointSequenceNumber = 5;
+    const OMITTED_CHECKPOINT: CheckpointSequenceNumber = 3;
+
+    pub(crate) struct MockSource {
+        pub(crate) checkpoints: BTreeMap<CheckpointSequenceNumber, CheckpointData>,
+    }
+
+    #[async_trait::async_trait]
+    impl CheckpointSource for MockSource {
+        async fn get_latest_checkpoint_sequence_number(&self) -> Result<CheckpointSequenceNumber> {
+            self.checkpoints
+                .keys()
+                .next_back()
+                .copied()
+                .ok_or_else(|| anyhow!("no checkpoints"))
+        }
+
+        async fn get_full_checkpoint(
+            &self,
+            sequence_number: CheckpointSequenceNumber,
+        ) -> Result<CheckpointData> {
+            self.checkpoints
+                .get(&sequence_number)
+                .cloned()
+                .ok_or_else(|| anyhow!("missing checkpoint"))
+        }
+    }
+
+    /// Answers later checkpoints faster than earlier ones, holds back `held` until a permit is
+    /// added to `gate`, and records every requested checkpoint.
+    struct GatedSource {
+        checkpoints: BTreeMap<CheckpointSequenceNumber, CheckpointData>,
+        held: CheckpointSequenceNumber,
+        gate: Arc<Semaphore>,
+        requested: Arc<Mutex<Vec<CheckpointSequenceNumber>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl CheckpointSource for GatedSource {
+        async fn get_latest_checkpoint_sequence_number(&self) -> Result<CheckpointSequenceNumber> {
+            Ok(LATEST_CHECKPOINT)
+        }
+
+        async fn get_full_checkpoint(
+            &self,
+            sequence_number: CheckpointSequenceNumber,
+        ) -> Result<CheckpointData> {
+            self.requested.lock().unwrap().push(sequence_number);
+            if sequence_number == self.held {
+                self.gate.acquire().await.unwrap().forget();
+            } else {
+                let delay = 10 * (LATEST_CHECKPOINT - sequence_number);
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+            }
+            Ok(self.checkpoints[&sequence_number].clone())
+        }
+    }
+
+    pub(crate) fn make_checkpoints() -> BTreeMap<CheckpointSequenceNumber, CheckpointData> {
+        let mut sim = Simulacrum::new();
+        for _ in 0..LATEST_CHECKPOINT {
+            sim.create_checkpoint();
+        }
+        (0..=LATEST_CHECKPOINT)
+            .map(|sequence_number| {
+                let checkpoint = sim
+                    .get_checkpoint_by_sequence_number(sequence_number)
+                    .unwrap()
+                    .unwrap();
+                let contents = sim
+                    .get_checkpoint_contents_by_digest(&checkpoint.content_digest)
+                    .unwrap()
+                    .unwrap();
+                let data = sim.get_checkpoint_data(checkpoint, contents).unwrap();
+                (sequence_number, data)
+            })
+            .collect()
+    }
+
+    /// A fetcher whose primary source permanently omits `OMITTED_CHECKPOINT`, recording the
+    /// gaps it reports.
+    fn make_fetcher(
+        checkpoints: &BTreeMap<CheckpointSequenceNumber, CheckpointData>,
+        gap_grace_period: Duration,
+    ) -> (
+        CheckpointFetcher,
+        Receiver<CheckpointData>,
+        CheckpointFetcherMetrics,
+        Arc<Mutex<Vec<CheckpointGap>>>,
+    ) {
+        let mut primary = checkpoints.clone();
+        primary.remove(&OMITTED_CHECKPOINT);
+        let (sender, receiver) = mango_metrics::metered_channel::channel(
+            100,
+            &IntGauge::new("test_checkpoints", "test").unwrap(),
+        );
+        let metrics = CheckpointFetcherMetrics::new(&Registry::default());
+        let gaps = Arc::new(Mutex::new(Vec::new()));
+        let reported = gaps.clone();
+        let fetcher = CheckpointFetcher::new(
+            MockSource {
+                checkpoints: primary,
+            },
+            None,
+            sender,
+            metrics.clone(),
+        )
+        .with_gap_grace_period(gap_grace_period)
+        .with_gap_hooks(vec![Box::new(move |gap: &CheckpointGap| {
+            reported.lock().unwrap().push(gap.clone())
+        })]);
+        (fetcher, receiver, metrics, gaps)
+    }
+
+    fn received(receiver: &mut Receiver<CheckpointData>) -> Vec<CheckpointSequenceNumber> {
+        std::iter::from_fn(|| receiver.try_recv().ok())
+            .map(|checkpoint| *checkpoint.checkpoint_summary.sequence_number())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_gap_is_reported_and_ingestion_stalls() {
+        let checkpoints = make_checkpoints();
+        let (mut fetcher, mut receiver, metrics, gaps) = make_fetcher(&checkpoints, Duration::ZERO);
+
+        fetcher.update_highest_known_checkpoint().await.unwrap();
+        for _ in 0..3 {
+            assert!(fetcher.download_checkpoints().await.is_err());
+        }
+
+        // Everything before the gap is delivered, nothing after it.
+        assert_eq!(
+            received(&mut receiver),
+            (0..OMITTED_CHECKPOINT).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            fetcher.last_downloaded_checkpoint,
+            Some(OMITTED_CHECKPOINT - 1)
+        );
+
+        // The gap is reported once, however long ingestion stays stalled on it.
+        let gaps = gaps.lock().unwrap();
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].first_missing, OMITTED_CHECKPOINT);
+        assert_eq!(gaps[0].next_available, OMITTED_CHECKPOINT + 1);
+        assert_eq!(metrics.total_checkpoint_gaps_detected.get(), 1);
+        assert_eq!(metrics.checkpoint_gap_size.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gap_is_not_reported_within_grace_period() {
+        let checkpoints = make_checkpoints();
+        let (mut fetcher, mut receiver, metrics, gaps) =
+            make_fetcher(&checkpoints, Duration::from_secs(3600));
+
+        fetcher.update_highest_known_checkpoint().await.unwrap();
+        assert!(fetcher.download_checkpoints().await.is_err());
+
+        assert_eq!(
+            received(&mut receiver),
+            (0..OMITTED_CHECKPOINT).collect::<Vec<_>>()
+        );
+        assert!(gaps.lock().unwrap().is_empty());
+        assert_eq!(metrics.total_checkpoint_gaps_detected.get(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_gap_is_filled_from_secondary_source() {
+        let checkpoints = make_checkpoints();
+        let (fetcher, mut receiver, metrics, gaps) = make_fetcher(&checkpoints, Duration::ZERO);
+        let mut fetcher = fetcher.with_secondary_client(MockSource {
+            checkpoints: checkpoints.clone(),
+        });
+
+        fetcher.update_highest_known_checkpoint().await.unwrap();
+        fetcher.download_checkpoints().await.unwrap();
+        fetcher.download_checkpoints().await.unwrap();
+
+        assert_eq!(
+            received(&mut receiver),
+            (0..=LATEST_CHECKPOINT).collect::<Vec<_>>()
+        );
+        assert_eq!(gaps.lock().unwrap().len(), 1);
+        assert_eq!(metrics.total_checkpoints_fetched_from_secondary.get(), 1);
+        assert_eq!(metrics.checkpoint_gap_size.get(), 0);
+    }
+
+    /// Writes `sequence_numbers` of `checkpoints` to `dir` the way fullnodes archive them.
+    fn write_archive(
+        dir: &std::path::Path,
+        checkpoints: &BTreeMap<CheckpointSequenceNumber, CheckpointData>,
+        sequence_numbers: impl IntoIterator<Item = CheckpointSequenceNumber>,
+    ) {
+        for sequence_number in sequence_numbers {
+            let mut bytes = vec![LocalArchiveSource::BCS_BLOB_ENCODING];
+            bytes.extend(bcs::to_bytes(&checkpoints[&sequence_number]).unwrap());
+            std::fs::write(dir.join(format!("{sequence_number}.chk")), bytes).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_local_archive_source() {
+        let checkpoints = make_checkpoints();
+        let dir = tempfile::tempdir().unwrap();
+        write_archive(dir.path(), &checkpoints, 0..=2);
+        std::fs::write(dir.path().join("README"), "not a checkpoint").unwrap();
+        let source = LocalArchiveSource::new(dir.path());
+
+        let latest = source.get_latest_checkpoint_sequence_number().await;
+        assert_eq!(latest.unwrap(), 2);
+        for sequence_number in 0..=2 {
+            let checkpoint = source.get_full_checkpoint(sequence_number).await.unwrap();
+            assert_eq!(
+                checkpoint.checkpoint_summary.digest(),
+                checkpoints[&sequence_number].checkpoint_summary.digest()
+            );
+        }
+        assert!(source.get_full_checkpoint(3).await.is_err());
+
+        // A file holding another checkpoint than its name says is as good as corrupt.
+        std::fs::copy(dir.path().join("0.chk"), dir.path().join("3.chk")).unwrap();
+        assert!(source.get_full_checkpoint(3).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_local_archive_falls_back_to_remote_source() {
+        const CORRUPT_CHECKPOINT: CheckpointSequenceNumber = 2;
+        let checkpoints = make_checkpoints();
+        let dir = tempfile::tempdir().unwrap();
+        // The archive stops before the latest checkpoint and holds a corrupt file, while the
+        // remote source lacks the checkpoints the archive serves.
+        write_archive(dir.path(), &checkpoints, 0..CORRUPT_CHECKPOINT);
+        let corrupt_file = dir.path().join(format!("{CORRUPT_CHECKPOINT}.chk"));
+        std::fs::write(corrupt_file, [1, 2, 3]).unwrap();
+        let remote = checkpoints
+            .range(CORRUPT_CHECKPOINT..)
+            .map(|(sequence_number, checkpoint)| (*sequence_number, checkpoint.clone()))
+            .collect();
+
+        let (sender, mut receiver) = mango_metrics::metered_channel::channel(
+            100,
+            &IntGauge::new("test_checkpoints", "test").unwrap(),
+        );
+        let mut fetcher = CheckpointFetcher::new(
+            MockSource {
+                checkpoints: remote,
+            },
+            None,
+            sender,
+            CheckpointFetcherMetrics::new(&Registry::default()),
+        )
+        .with_local_archive(LocalArchiveSource::new(dir.path()));
+
+        fetcher.update_highest_known_checkpoint().await.unwrap();
+        assert_eq!(fetcher.highest_known_checkpoint, LATEST_CHECKPOINT);
+        fetcher.download_checkpoints().await.unwrap();
+        assert_eq!(
+            received(&mut receiver),
+            (0..=LATEST_CHECKPOINT).collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_downloads_are_delivered_in_order_within_window() {
+        const WINDOW: usize = 3;
+        const HELD_CHECKPOINT: CheckpointSequenceNumber = 1;
+        let gate = Arc::new(Semaphore::new(0));
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let (sender, mut receiver) = mango_metrics::metered_channel::channel(
+            100,
+            &IntGauge::new("test_checkpoints", "test").unwrap(),
+        );
+        let metrics = CheckpointFetcherMetrics::new(&Registry::default());
+        let mut fetcher = CheckpointFetcher::new(
+            GatedSource {
+                checkpoints: make_checkpoints(),
+                held: HELD_CHECKPOINT,
+                gate: gate.clone(),
+                requested: requested.clone(),
+            },
+            None,
+            sender,
+            metrics.clone(),
+        )
+        .with_download_concurrency(WINDOW);
+        fetcher.update_highest_known_checkpoint().await.unwrap();
+        let download = tokio::spawn(async move { fetcher.download_checkpoints().await });
+
+        // The checkpoints after the held one are buffered until the window is full, and no more
+        // downloads are issued while it stays held.
+        let window_full = || {
+            requested.lock().unwrap().len() == WINDOW + 1
+                && metrics.checkpoint_downloads_buffered.get() == WINDOW as i64 - 1
+        };
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while !window_full() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(window_full());
+        assert_eq!(metrics.checkpoint_downloads_in_flight.get(), 1);
+        assert_eq!(received(&mut receiver), vec![0]);
+
+        gate.add_permits(1);
+        download.await.unwrap().unwrap();
+        assert_eq!(
+            received(&mut receiver),
+            (HELD_CHECKPOINT..=LATEST_CHECKPOINT).collect::<Vec<_>>()
+        );
+        assert_eq!(metrics.checkpoint_downloads_in_flight.get(), 0);
+        assert_eq!(metrics.checkpoint_downloads_buffered.get(), 0);
     }
 }
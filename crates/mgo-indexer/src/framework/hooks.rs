@@ -0,0 +1,107 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Callbacks letting embedders trigger their own side effects, such as cache invalidation or
+//! webhooks, as the pipeline runs, without writing a [`Handler`](super::Handler). Every hook
+//! runs on a blocking thread and is waited on for at most the hook timeout, after which
+//! ingestion moves on without it.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
+use tracing::warn;
+
+use crate::errors::IndexerError;
+
+/// How long the pipeline waits on a hook before skipping it, by default.
+pub const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Called once when the pipeline starts, before any checkpoint is fetched.
+pub type StartHook = Arc<dyn Fn() + Send + Sync>;
+
+/// Called with the new watermark of a handler, once it has been saved.
+pub type CheckpointCommittedHook = Arc<dyn Fn(CheckpointSequenceNumber) + Send + Sync>;
+
+/// Called with the error of a failed handler, before it is restarted from its watermark.
+pub type ErrorHook = Arc<dyn Fn(&IndexerError) + Send + Sync>;
+
+#[derive(Clone)]
+pub(crate) struct LifecycleHooks {
+    on_start: Vec<StartHook>,
+    on_checkpoint_committed: Vec<CheckpointCommittedHook>,
+    on_error: Vec<ErrorHook>,
+    timeout: Duration,
+}
+
+impl Default for LifecycleHooks {
+    fn default() -> Self {
+        Self {
+            on_start: Vec::new(),
+            on_checkpoint_committed: Vec::new(),
+            on_error: Vec::new(),
+            timeout: DEFAULT_HOOK_TIMEOUT,
+        }
+    }
+}
+
+impl LifecycleHooks {
+    pub fn add_on_start(&mut self, hook: StartHook) {
+        self.on_start.push(hook);
+    }
+
+    pub fn add_on_checkpoint_committed(&mut self, hook: CheckpointCommittedHook) {
+        self.on_checkpoint_committed.push(hook);
+    }
+
+    pub fn add_on_error(&mut self, hook: ErrorHook) {
+        self.on_error.push(hook);
+    }
+
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    pub async fn started(&self) {
+        for hook in &self.on_start {
+            let hook = hook.clone();
+            run_hook("on_start", self.timeout, move || hook()).await;
+        }
+    }
+
+    pub async fn checkpoint_committed(&self, checkpoint: CheckpointSequenceNumber) {
+        for hook in &self.on_checkpoint_committed {
+            let hook = hook.clone();
+            run_hook("on_checkpoint_committed", self.timeout, move || {
+                hook(checkpoint)
+            })
+            .await;
+        }
+    }
+
+    pub async fn failed(&self, error: anyhow::Error) {
+        if self.on_error.is_empty() {
+            return;
+        }
+        let error = Arc::new(
+            error
+                .downcast::<IndexerError>()
+                .unwrap_or_else(IndexerError::UncategorizedError),
+        );
+        for hook in &self.on_error {
+            let hook = hook.clone();
+            let error = error.clone();
+            run_hook("on_error", self.timeout, move || hook(&error)).await;
+        }
+    }
+}
+
+/// Runs `hook` on a blocking thread, giving up on it after `timeout`. A hook given up on keeps
+/// running in the background, but is no longer waited on.
+async fn run_hook<F: FnOnce() + Send + 'static>(name: &str, timeout: Duration, hook: F) {
+    match tokio::time::timeout(timeout, tokio::task::spawn_blocking(hook)).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => warn!("Indexer {name} hook failed: {e}"),
+        Err(_) => warn!("Indexer {name} hook did not return within {timeout:?}, skipping it"),
+    }
+}
@@ -1,10 +1,101 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::sync::Arc;
+use std::time::Duration;
+
 use anyhow::Result;
-use mgo_rest_api::CheckpointData;
-use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
+use mgo_types::committee::EpochId;
+use mgo_types::digests::CheckpointDigest;
+use mgo_types::effects::{TransactionEffects, TransactionEvents};
+use mgo_types::full_checkpoint_content;
+use mgo_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointContents, CheckpointSequenceNumber,
+};
+use mgo_types::object::Object;
+use mgo_types::transaction::Transaction;
+
+/// A checkpoint as handed to handlers: its summary and contents, and every transaction it
+/// executed with its effects, events and objects. Clones share the data rather than copy it,
+/// and accessors only borrow from it.
+#[derive(Clone, Debug)]
+pub struct CheckpointData {
+    data: Arc<full_checkpoint_content::CheckpointData>,
+}
+
+impl CheckpointData {
+    pub fn summary(&self) -> &CertifiedCheckpointSummary {
+        &self.data.checkpoint_summary
+    }
+
+    pub fn sequence_number(&self) -> CheckpointSequenceNumber {
+        self.data.checkpoint_summary.sequence_number
+    }
+
+    pub fn epoch(&self) -> EpochId {
+        self.data.checkpoint_summary.epoch
+    }
+
+    pub fn contents(&self) -> &CheckpointContents {
+        &self.data.checkpoint_contents
+    }
+
+    /// The transactions of the checkpoint, in execution order.
+    pub fn transactions(&self) -> impl ExactSizeIterator<Item = CheckpointTransaction<'_>> {
+        self.data
+            .transactions
+            .iter()
+            .map(|data| CheckpointTransaction { data })
+    }
+
+    /// The checkpoint as downloaded, for the handlers of this crate.
+    pub(crate) fn full(&self) -> &full_checkpoint_content::CheckpointData {
+        &self.data
+    }
+}
+
+impl From<full_checkpoint_content::CheckpointData> for CheckpointData {
+    fn from(data: full_checkpoint_content::CheckpointData) -> Self {
+        Self {
+            data: Arc::new(data),
+        }
+    }
+}
+
+/// A transaction of a [`CheckpointData`], borrowing from it.
+#[derive(Clone, Copy, Debug)]
+pub struct CheckpointTransaction<'a> {
+    data: &'a full_checkpoint_content::CheckpointTransaction,
+}
+
+impl<'a> CheckpointTransaction<'a> {
+    pub fn transaction(&self) -> &'a Transaction {
+        &self.data.transaction
+    }
+
+    pub fn effects(&self) -> &'a TransactionEffects {
+        &self.data.effects
+    }
+
+    /// `None` if the transaction emitted no events.
+    pub fn events(&self) -> Option<&'a TransactionEvents> {
+        self.data.events.as_ref()
+    }
+
+    /// The objects the transaction read or wrote, as they were before it executed.
+    pub fn input_objects(&self) -> &'a [Object] {
+        &self.data.input_objects
+    }
 
+    /// The objects the transaction created or wrote, as they are after it executed.
+    pub fn output_objects(&self) -> &'a [Object] {
+        &self.data.output_objects
+    }
+}
+
+/// Processes the checkpoints of its pipeline, in order. Checkpoints whose watermark was not saved,
+/// e.g. those of a batch interrupted by a crash or a shutdown, are handed to the handler again
+/// on restart, so its writes must be idempotent, e.g. upserts keyed by checkpoint.
 #[async_trait::async_trait]
 pub trait Handler: Send {
     fn name(&self) -> &str;
@@ -19,6 +110,83 @@ pub trait Handler: Send {
     }
 }
 
+/// Keeps the watermark of every handler, the last checkpoint it finished processing, so that
+/// handlers resume from their own checkpoint after a failure or restart.
+#[async_trait::async_trait]
+pub trait ProgressStore: Send + Sync {
+    /// The watermark of `handler_name`, or `None` if it has not processed any checkpoint yet.
+    async fn load(&self, handler_name: &str) -> Result<Option<CheckpointSequenceNumber>>;
+    /// Called once the handler has processed every checkpoint up to `checkpoint`. The runner
+    /// retries a failed save and does not hand the handler further checkpoints until it
+    /// succeeds.
+    async fn save(&self, handler_name: &str, checkpoint: CheckpointSequenceNumber) -> Result<()>;
+}
+
+/// Storage that old checkpoint data is deleted from by the [`Pruner`](super::Pruner).
+#[async_trait::async_trait]
+pub trait PrunableStore: Send + Sync {
+    /// Tables pruned, in the order they are pruned in.
+    fn pruned_tables(&self) -> Vec<&'static str>;
+    async fn get_checkpoint_epoch(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<Option<EpochId>>;
+    async fn get_epoch_first_checkpoint(
+        &self,
+        epoch: EpochId,
+    ) -> Result<Option<CheckpointSequenceNumber>>;
+    /// Checkpoint that the data of all checkpoints before it has been, or is being, pruned.
+    async fn get_pruned_watermark(&self) -> Result<CheckpointSequenceNumber>;
+    /// Raises the pruned watermark before any row below it is deleted, so that readers report
+    /// the data as pruned rather than serve what is left of it.
+    async fn set_pruned_watermark(&self, checkpoint: CheckpointSequenceNumber) -> Result<()>;
+    /// Drops the partitions of `table` that only hold checkpoints before `checkpoint`,
+    /// returning how many were dropped. Tables without partitions have none to drop.
+    async fn drop_partitions(
+        &self,
+        table: &str,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<usize>;
+    /// Deletes at most `batch_size` rows of `table` belonging to checkpoints before
+    /// `checkpoint`, returning how many were deleted.
+    async fn prune_table(
+        &self,
+        table: &str,
+        checkpoint: CheckpointSequenceNumber,
+        batch_size: usize,
+    ) -> Result<usize>;
+}
+
+/// Storage whose ingested checkpoints the [`Reconciler`](super::Reconciler) verifies against
+/// their certified summaries.
+#[async_trait::async_trait]
+pub trait ReconcilableStore: Send + Sync {
+    /// Checkpoint that all checkpoints before it have been verified, or pruned.
+    async fn get_reconciled_watermark(&self) -> Result<CheckpointSequenceNumber>;
+    async fn set_reconciled_watermark(&self, checkpoint: CheckpointSequenceNumber) -> Result<()>;
+    /// Digest `checkpoint` was ingested with, `None` until it is committed.
+    async fn get_checkpoint_digest(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<Option<CheckpointDigest>>;
+    /// Soft-deletes the rows ingested from `checkpoint` at once, so that readers stop serving
+    /// them while the checkpoint is ingested again, returning how many were tombstoned.
+    async fn tombstone_checkpoint(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+        ingested: CheckpointDigest,
+        certified: CheckpointDigest,
+    ) -> Result<usize>;
+}
+
+/// The checkpoint the [`Pruner`](super::Pruner) must never prune past, i.e. the lowest
+/// checkpoint every consumer of the pruned data has processed.
+#[async_trait::async_trait]
+pub trait WatermarkSource: Send + Sync {
+    /// `None` while some consumer has not processed any checkpoint yet.
+    async fn lowest_watermark(&self) -> Result<Option<CheckpointSequenceNumber>>;
+}
+
 pub trait BackfillHandler: Handler {
     fn last_processed_checkpoint(&self) -> Option<CheckpointSequenceNumber>;
 }
@@ -41,3 +209,36 @@ impl<T: OutOfOrderHandler> Handler for T {
         OutOfOrderHandler::process_checkpoints(self, checkpoints).await
     }
 }
+
+/// A source of full checkpoints for the fetcher, e.g. a fullnode's REST API.
+#[async_trait::async_trait]
+pub trait CheckpointSource: Send + Sync {
+    async fn get_latest_checkpoint_sequence_number(&self) -> Result<CheckpointSequenceNumber>;
+    async fn get_full_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<full_checkpoint_content::CheckpointData>;
+}
+
+/// A source of certified checkpoints, which ingested ones are verified against, e.g. a fullnode
+/// trusted to serve finalized checkpoints only.
+#[async_trait::async_trait]
+pub trait CertifiedCheckpointSource: CheckpointSource {
+    async fn get_certified_summary(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<CertifiedCheckpointSummary>;
+}
+
+/// A hole in the checkpoints served by the primary source: checkpoints
+/// `first_missing..next_available` could not be fetched while `next_available` could.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CheckpointGap {
+    pub first_missing: CheckpointSequenceNumber,
+    pub next_available: CheckpointSequenceNumber,
+    /// How long ingestion had been stalled on `first_missing` when the gap was reported.
+    pub stalled_for: Duration,
+}
+
+/// Called once for every gap the fetcher detects, before it stalls on it.
+pub type CheckpointGapHook = Box<dyn Fn(&CheckpointGap) + Send + Sync>;
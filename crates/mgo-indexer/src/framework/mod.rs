@@ -1,12 +1,23 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+mod backpressure;
 mod builder;
+mod fetcher;
+mod hooks;
 pub mod interface;
-
-// TODO remove the pub(crater) once indexer_v2.rs is renamed to lib.rs
-pub(crate) mod fetcher;
-pub(crate) mod runner;
+pub mod pipeline;
+mod progress;
+mod pruner;
+mod reconciler;
+mod runner;
 
 pub use builder::IndexerBuilder;
-pub use interface::Handler;
+pub use interface::{CheckpointData, CheckpointTransaction};
+pub use interface::{
+    CertifiedCheckpointSource, Handler, ProgressStore, PrunableStore, ReconcilableStore,
+    WatermarkSource,
+};
+pub use progress::InMemoryProgressStore;
+pub use pruner::{Pruner, RetentionPolicy};
+pub use reconciler::Reconciler;
@@ -0,0 +1,263 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Public entry points of the checkpoint pipeline, shared by the indexer binary and by crates
+//! embedding the pipeline with their own handlers. The fetcher and the runner behind them are
+//! internal and may change without notice.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use mango_metrics::metered_channel::Receiver;
+use mango_metrics::spawn_monitored_task;
+use mgo_types::full_checkpoint_content;
+use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
+use tokio::sync::watch;
+
+pub use super::backpressure::{BufferLimits, CheckpointBuffer, DEFAULT_CHECKPOINT_BUFFER_SIZE};
+pub use super::fetcher::{ArchiveWithFallbackSource, CheckpointFetcher, LocalArchiveSource};
+pub use super::hooks::{CheckpointCommittedHook, ErrorHook, StartHook, DEFAULT_HOOK_TIMEOUT};
+pub use super::interface::{CheckpointData, CheckpointTransaction};
+pub use super::interface::{CertifiedCheckpointSource, ReconcilableStore};
+pub use super::interface::{CheckpointGap, CheckpointGapHook, CheckpointSource};
+pub use super::interface::{Handler, ProgressStore, PrunableStore, WatermarkSource};
+pub use super::progress::InMemoryProgressStore;
+pub use super::pruner::{Pruner, RetentionPolicy};
+pub use super::reconciler::Reconciler;
+pub use super::runner::{FetcherFactory, DEFAULT_SHUTDOWN_DEADLINE};
+use super::hooks::LifecycleHooks;
+use super::pruner::HandlerWatermarks;
+use super::runner::{checkpoint_processing_batch_size, run_with_progress, Shutdown};
+use crate::errors::IndexerError;
+use crate::metrics::CheckpointFetcherMetrics;
+
+/// Creates a channel for a fetcher to send downloaded checkpoints to, holding up to `size` of
+/// them.
+pub fn checkpoint_channel(
+    size: usize,
+) -> (
+    mango_metrics::metered_channel::Sender<full_checkpoint_content::CheckpointData>,
+    Receiver<full_checkpoint_content::CheckpointData>,
+) {
+    mango_metrics::metered_channel::channel(
+        size,
+        &mango_metrics::get_metrics()
+            .unwrap()
+            .channels
+            .with_label_values(&["checkpoint_tx_downloading"]),
+    )
+}
+
+/// A [`FetcherFactory`] downloading checkpoints from `source` with the default fetcher settings.
+pub fn fetcher_factory<S: CheckpointSource + Clone + 'static>(
+    source: S,
+    metrics: CheckpointFetcherMetrics,
+) -> FetcherFactory {
+    bounded_fetcher_factory(source, BufferLimits::default(), metrics)
+}
+
+/// A [`FetcherFactory`] downloading checkpoints from `source`, each fetcher pausing while the
+/// checkpoints its handler has yet to process reach `limits`.
+pub fn bounded_fetcher_factory<S: CheckpointSource + Clone + 'static>(
+    source: S,
+    limits: BufferLimits,
+    metrics: CheckpointFetcherMetrics,
+) -> FetcherFactory {
+    Arc::new(move |last_downloaded_checkpoint| {
+        let (sender, receiver) = checkpoint_channel(limits.max_checkpoints);
+        let fetcher = CheckpointFetcher::new(
+            source.clone(),
+            last_downloaded_checkpoint,
+            sender,
+            metrics.clone(),
+        )
+        .with_buffer(CheckpointBuffer::new(limits, metrics.clone()));
+        (fetcher, receiver)
+    })
+}
+
+/// Runs registered handlers, each in its own pipeline with its own watermark. See
+/// [`IndexerBuilder`](super::IndexerBuilder) for a pipeline reading from a fullnode.
+pub struct Pipeline {
+    handlers: Vec<Box<dyn Handler>>,
+    progress_store: Option<Arc<dyn ProgressStore>>,
+    last_downloaded_checkpoint: Option<CheckpointSequenceNumber>,
+    end_checkpoint: Option<CheckpointSequenceNumber>,
+    batch_size: usize,
+    pruner: Option<Pruner>,
+    hooks: LifecycleHooks,
+    shutdown: Option<watch::Receiver<bool>>,
+    shutdown_deadline: Duration,
+}
+
+impl Pipeline {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            handlers: Vec::new(),
+            progress_store: None,
+            last_downloaded_checkpoint: None,
+            end_checkpoint: None,
+            batch_size: checkpoint_processing_batch_size(),
+            pruner: None,
+            hooks: LifecycleHooks::default(),
+            shutdown: None,
+            shutdown_deadline: DEFAULT_SHUTDOWN_DEADLINE,
+        }
+    }
+
+    pub fn register_handler<T: Handler + 'static>(mut self, handler: T) -> Self {
+        self.handlers.push(Box::new(handler));
+        self
+    }
+
+    /// Where handler watermarks are loaded from on start and saved to after every batch.
+    /// Defaults to an [`InMemoryProgressStore`].
+    pub fn with_progress_store<T: ProgressStore + 'static>(mut self, progress_store: T) -> Self {
+        self.progress_store = Some(Arc::new(progress_store));
+        self
+    }
+
+    /// Checkpoint that handlers without a watermark in the progress store start right after.
+    pub fn last_downloaded_checkpoint(
+        mut self,
+        last_downloaded_checkpoint: Option<CheckpointSequenceNumber>,
+    ) -> Self {
+        self.last_downloaded_checkpoint = last_downloaded_checkpoint;
+        self
+    }
+
+    /// Last checkpoint handlers process, after which they stop, e.g. for a one-off backfill of
+    /// checkpoints indexed before a handler existed. Handlers run until stopped by default.
+    pub fn end_checkpoint(mut self, end_checkpoint: CheckpointSequenceNumber) -> Self {
+        self.end_checkpoint = Some(end_checkpoint);
+        self
+    }
+
+    /// Maximum number of checkpoints handed to a handler at once. Defaults to the
+    /// `CHECKPOINT_PROCESSING_BATCH_SIZE` environment variable, or 25.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Prunes data with `pruner` while the pipeline runs, never past the lowest watermark of
+    /// the registered handlers.
+    pub fn pruner(mut self, pruner: Pruner) -> Self {
+        self.pruner = Some(pruner);
+        self
+    }
+
+    /// Registers a hook called once when the pipeline starts.
+    pub fn on_start<F: Fn() + Send + Sync + 'static>(mut self, hook: F) -> Self {
+        self.hooks.add_on_start(Arc::new(hook));
+        self
+    }
+
+    /// Registers a hook called every time a handler's watermark advances, with the new
+    /// watermark, once per registered handler.
+    pub fn on_checkpoint_committed<F: Fn(CheckpointSequenceNumber) + Send + Sync + 'static>(
+        mut self,
+        hook: F,
+    ) -> Self {
+        self.hooks.add_on_checkpoint_committed(Arc::new(hook));
+        self
+    }
+
+    /// Registers a hook called every time a handler fails, before it is restarted.
+    pub fn on_error<F: Fn(&IndexerError) + Send + Sync + 'static>(mut self, hook: F) -> Self {
+        self.hooks.add_on_error(Arc::new(hook));
+        self
+    }
+
+    /// How long the pipeline waits on each hook call before skipping it with a warning.
+    /// Defaults to [`DEFAULT_HOOK_TIMEOUT`].
+    pub fn hook_timeout(mut self, hook_timeout: Duration) -> Self {
+        self.hooks.set_timeout(hook_timeout);
+        self
+    }
+
+    /// Stops the pipeline once `signal` is set to `true`, e.g. on SIGTERM: handlers take no
+    /// further checkpoints, and finish the batch they are processing and save its watermark
+    /// before [`run`](Self::run) returns.
+    pub fn with_shutdown(mut self, signal: watch::Receiver<bool>) -> Self {
+        self.shutdown = Some(signal);
+        self
+    }
+
+    /// How long handlers are given to finish their batch once shutdown is requested. A batch
+    /// still unfinished by then is dropped without saving its watermark, so handlers must
+    /// tolerate processing the checkpoints of an interrupted batch again on restart. Defaults
+    /// to [`DEFAULT_SHUTDOWN_DEADLINE`].
+    pub fn shutdown_deadline(mut self, shutdown_deadline: Duration) -> Self {
+        self.shutdown_deadline = shutdown_deadline;
+        self
+    }
+
+    /// Runs until every handler has processed all checkpoints its fetcher sends, or reached the
+    /// end checkpoint, or stopped for shutdown, restarting failed handlers from their
+    /// watermark. Handlers, and the stores they hold, are only dropped once they stopped and
+    /// saved their watermark.
+    pub async fn run(self, new_fetcher: FetcherFactory) {
+        assert!(!self.handlers.is_empty());
+        let progress_store = self
+            .progress_store
+            .unwrap_or_else(|| Arc::new(InMemoryProgressStore::default()));
+        let pruner = self.pruner.map(|pruner| {
+            let watermarks = HandlerWatermarks::new(
+                self.handlers
+                    .iter()
+                    .map(|handler| handler.name().to_string())
+                    .collect(),
+                progress_store.clone(),
+            );
+            spawn_monitored_task!(pruner.run(Arc::new(watermarks)))
+        });
+        let shutdown = match self.shutdown {
+            Some(signal) => Shutdown::new(signal, self.shutdown_deadline),
+            None => Shutdown::never(),
+        };
+        self.hooks.started().await;
+        run_with_progress(
+            self.handlers,
+            progress_store,
+            new_fetcher,
+            self.last_downloaded_checkpoint,
+            self.end_checkpoint,
+            self.batch_size,
+            Arc::new(self.hooks),
+            shutdown,
+        )
+        .await;
+        if let Some(pruner) = pruner {
+            pruner.abort();
+        }
+    }
+}
+
+/// Feeds every checkpoint of `stream` to all handlers in lockstep, without watermarks.
+pub async fn run_in_lockstep<S>(stream: S, handlers: Vec<Box<dyn Handler>>)
+where
+    S: futures::Stream<Item = full_checkpoint_content::CheckpointData> + std::marker::Unpin,
+{
+    super::runner::run(stream, handlers, None, Shutdown::never()).await
+}
+
+/// Like [`run_in_lockstep`], releasing the checkpoints of `stream` from `buffer`, the buffer of
+/// the fetcher producing them, once all handlers processed them.
+///
+/// Stops once `shutdown` is set to `true`: no further checkpoints are taken, and the handlers
+/// are given `shutdown_deadline` to finish the batch they are processing before this returns
+/// and drops them.
+pub async fn run_in_lockstep_with_buffer<S>(
+    stream: S,
+    handlers: Vec<Box<dyn Handler>>,
+    buffer: CheckpointBuffer,
+    shutdown: watch::Receiver<bool>,
+    shutdown_deadline: Duration,
+) where
+    S: futures::Stream<Item = full_checkpoint_content::CheckpointData> + std::marker::Unpin,
+{
+    let shutdown = Shutdown::new(shutdown, shutdown_deadline);
+    super::runner::run(stream, handlers, Some(buffer), shutdown).await
+}
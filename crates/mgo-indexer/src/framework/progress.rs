@@ -0,0 +1,32 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
+
+use super::interface::ProgressStore;
+
+/// Keeps watermarks for the lifetime of the process only: handlers resume from their own
+/// checkpoint after a failure, but not across restarts.
+#[derive(Default)]
+pub struct InMemoryProgressStore {
+    watermarks: Mutex<HashMap<String, CheckpointSequenceNumber>>,
+}
+
+#[async_trait::async_trait]
+impl ProgressStore for InMemoryProgressStore {
+    async fn load(&self, handler_name: &str) -> Result<Option<CheckpointSequenceNumber>> {
+        Ok(self.watermarks.lock().unwrap().get(handler_name).copied())
+    }
+
+    async fn save(&self, handler_name: &str, checkpoint: CheckpointSequenceNumber) -> Result<()> {
+        self.watermarks
+            .lock()
+            .unwrap()
+            .insert(handler_name.to_string(), checkpoint);
+        Ok(())
+    }
+}
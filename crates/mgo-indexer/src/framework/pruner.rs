@@ -0,0 +1,410 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Deletes the data of checkpoints that fall out of a retention window, so that the indexer
+//! database does not grow without bound.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
+use tracing::{info, warn};
+
+use super::interface::{ProgressStore, PrunableStore, WatermarkSource};
+use crate::metrics::PrunerMetrics;
+
+/// How much history the pruner keeps, counted back from the lowest handler watermark.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetentionPolicy {
+    /// Keeps this many checkpoints, the watermark included.
+    Checkpoints(u64),
+    /// Keeps all checkpoints of this many epochs, the one of the watermark included.
+    Epochs(u64),
+}
+
+/// Periodically raises the pruned watermark of a [`PrunableStore`] to the first checkpoint
+/// its [`RetentionPolicy`] keeps, drops the partitions entirely below it, and deletes the rows
+/// left below it in bounded batches.
+pub struct Pruner {
+    store: Arc<dyn PrunableStore>,
+    policy: RetentionPolicy,
+    batch_size: usize,
+    interval: Duration,
+    metrics: PrunerMetrics,
+}
+
+impl Pruner {
+    pub const DEFAULT_BATCH_SIZE: usize = 1000;
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
+
+    pub fn new<S: PrunableStore + 'static>(
+        store: S,
+        policy: RetentionPolicy,
+        metrics: PrunerMetrics,
+    ) -> Self {
+        Self {
+            store: Arc::new(store),
+            policy,
+            batch_size: Self::DEFAULT_BATCH_SIZE,
+            interval: Self::DEFAULT_INTERVAL,
+            metrics,
+        }
+    }
+
+    /// Maximum number of rows deleted from a table in one statement, so that pruning never
+    /// holds locks on a table for long.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// How long the pruner waits between rounds.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Prunes a round every interval, never past the lowest watermark of `watermarks`. A
+    /// failed round is picked up again by the next one.
+    pub async fn run(self, watermarks: Arc<dyn WatermarkSource>) {
+        info!(policy = ?self.policy, "Starting pruner");
+        loop {
+            if let Err(e) = self.prune(watermarks.as_ref()).await {
+                warn!("Pruning round failed, retrying in {:?}: {e}", self.interval);
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+
+    /// Runs a single round, returning the pruned watermark after it.
+    pub async fn prune(
+        &self,
+        watermarks: &dyn WatermarkSource,
+    ) -> Result<CheckpointSequenceNumber> {
+        let mut pruned = self.store.get_pruned_watermark().await?;
+        if let Some(lowest) = watermarks.lowest_watermark().await? {
+            let first_kept = self.first_kept_checkpoint(lowest).await?;
+            if first_kept > pruned {
+                self.store.set_pruned_watermark(first_kept).await?;
+                info!(from = pruned, to = first_kept, "Raised pruned watermark");
+                pruned = first_kept;
+            }
+            self.metrics
+                .pruner_lag_checkpoints
+                .set(lowest.saturating_sub(pruned) as i64);
+        }
+        self.metrics.pruned_checkpoint_watermark.set(pruned as i64);
+
+        // Rows below the watermark are deleted even if it was not raised, to finish the
+        // deletions of a round that failed midway.
+        for table in self.store.pruned_tables() {
+            let dropped = self.store.drop_partitions(table, pruned).await?;
+            self.metrics
+                .pruned_partitions
+                .with_label_values(&[table])
+                .inc_by(dropped as u64);
+            loop {
+                let deleted = self
+                    .store
+                    .prune_table(table, pruned, self.batch_size)
+                    .await?;
+                self.metrics
+                    .pruned_rows
+                    .with_label_values(&[table])
+                    .inc_by(deleted as u64);
+                if deleted < self.batch_size {
+                    break;
+                }
+            }
+        }
+        Ok(pruned)
+    }
+
+    /// First checkpoint kept by the policy, which is never after `lowest_watermark`.
+    async fn first_kept_checkpoint(
+        &self,
+        lowest_watermark: CheckpointSequenceNumber,
+    ) -> Result<CheckpointSequenceNumber> {
+        let first_kept = match self.policy {
+            RetentionPolicy::Checkpoints(checkpoints) => {
+                (lowest_watermark + 1).saturating_sub(checkpoints)
+            }
+            RetentionPolicy::Epochs(epochs) => {
+                let Some(epoch) = self.store.get_checkpoint_epoch(lowest_watermark).await? else {
+                    return Ok(0);
+                };
+                match (epoch + 1).checked_sub(epochs) {
+                    Some(first_kept_epoch) if first_kept_epoch > 0 => self
+                        .store
+                        .get_epoch_first_checkpoint(first_kept_epoch)
+                        .await?
+                        .unwrap_or(0),
+                    _ => 0,
+                }
+            }
+        };
+        Ok(first_kept.min(lowest_watermark))
+    }
+}
+
+/// Lowest watermark of the named handlers in a progress store.
+pub(crate) struct HandlerWatermarks {
+    handlers: Vec<String>,
+    progress_store: Arc<dyn ProgressStore>,
+}
+
+impl HandlerWatermarks {
+    pub(crate) fn new(handlers: Vec<String>, progress_store: Arc<dyn ProgressStore>) -> Self {
+        Self {
+            handlers,
+            progress_store,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl WatermarkSource for HandlerWatermarks {
+    async fn lowest_watermark(&self) -> Result<Option<CheckpointSequenceNumber>> {
+        let mut lowest: Option<CheckpointSequenceNumber> = None;
+        for handler in &self.handlers {
+            let Some(watermark) = self.progress_store.load(handler).await? else {
+                return Ok(None);
+            };
+            lowest = Some(lowest.map_or(watermark, |lowest| lowest.min(watermark)));
+        }
+        Ok(lowest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    use mgo_types::committee::EpochId;
+    use prometheus::Registry;
+
+    use super::super::InMemoryProgressStore;
+    use super::*;
+
+    const TABLES: [&str; 2] = ["transactions", "events"];
+    const CHECKPOINTS_PER_EPOCH: u64 = 10;
+    const LATEST_CHECKPOINT: CheckpointSequenceNumber = 99;
+
+    /// Holds a row per table for every checkpoint up to [`LATEST_CHECKPOINT`]. Partitioned
+    /// tables have a partition per epoch, and count the partitions dropped from them.
+    struct MemoryStore {
+        rows: Mutex<BTreeMap<&'static str, Vec<CheckpointSequenceNumber>>>,
+        dropped_partitions: Mutex<BTreeMap<&'static str, u64>>,
+        pruned_watermark: Mutex<CheckpointSequenceNumber>,
+    }
+
+    impl MemoryStore {
+        fn new() -> Arc<Self> {
+            Self::with_partitioned_tables(&[])
+        }
+
+        fn with_partitioned_tables(tables: &[&'static str]) -> Arc<Self> {
+            Arc::new(Self {
+                rows: Mutex::new(
+                    TABLES
+                        .into_iter()
+                        .map(|table| (table, (0..=LATEST_CHECKPOINT).collect()))
+                        .collect(),
+                ),
+                dropped_partitions: Mutex::new(tables.iter().map(|table| (*table, 0)).collect()),
+                pruned_watermark: Mutex::new(0),
+            })
+        }
+
+        fn first_row(&self, table: &str) -> CheckpointSequenceNumber {
+            self.rows.lock().unwrap()[table][0]
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl PrunableStore for Arc<MemoryStore> {
+        fn pruned_tables(&self) -> Vec<&'static str> {
+            TABLES.to_vec()
+        }
+
+        async fn get_checkpoint_epoch(
+            &self,
+            checkpoint: CheckpointSequenceNumber,
+        ) -> Result<Option<EpochId>> {
+            Ok(Some(checkpoint / CHECKPOINTS_PER_EPOCH))
+        }
+
+        async fn get_epoch_first_checkpoint(
+            &self,
+            epoch: EpochId,
+        ) -> Result<Option<CheckpointSequenceNumber>> {
+            Ok(Some(epoch * CHECKPOINTS_PER_EPOCH))
+        }
+
+        async fn get_pruned_watermark(&self) -> Result<CheckpointSequenceNumber> {
+            Ok(*self.pruned_watermark.lock().unwrap())
+        }
+
+        async fn set_pruned_watermark(&self, checkpoint: CheckpointSequenceNumber) -> Result<()> {
+            *self.pruned_watermark.lock().unwrap() = checkpoint;
+            Ok(())
+        }
+
+        async fn drop_partitions(
+            &self,
+            table: &str,
+            checkpoint: CheckpointSequenceNumber,
+        ) -> Result<usize> {
+            let mut dropped_partitions = self.dropped_partitions.lock().unwrap();
+            let Some(dropped) = dropped_partitions.get_mut(table) else {
+                return Ok(0);
+            };
+            let partitions_below = checkpoint / CHECKPOINTS_PER_EPOCH;
+            if partitions_below <= *dropped {
+                return Ok(0);
+            }
+            let newly_dropped = partitions_below - *dropped;
+            *dropped = partitions_below;
+            let first_kept = partitions_below * CHECKPOINTS_PER_EPOCH;
+            let mut rows = self.rows.lock().unwrap();
+            rows.get_mut(table).unwrap().retain(|row| *row >= first_kept);
+            Ok(newly_dropped as usize)
+        }
+
+        async fn prune_table(
+            &self,
+            table: &str,
+            checkpoint: CheckpointSequenceNumber,
+            batch_size: usize,
+        ) -> Result<usize> {
+            let mut rows = self.rows.lock().unwrap();
+            let rows = rows.get_mut(table).unwrap();
+            let deleted = rows
+                .iter()
+                .take(batch_size)
+                .take_while(|row| **row < checkpoint)
+                .count();
+            rows.drain(..deleted);
+            Ok(deleted)
+        }
+    }
+
+    struct FixedWatermark(Option<CheckpointSequenceNumber>);
+
+    #[async_trait::async_trait]
+    impl WatermarkSource for FixedWatermark {
+        async fn lowest_watermark(&self) -> Result<Option<CheckpointSequenceNumber>> {
+            Ok(self.0)
+        }
+    }
+
+    fn pruner(store: &Arc<MemoryStore>, policy: RetentionPolicy) -> (Pruner, PrunerMetrics) {
+        let metrics = PrunerMetrics::new(&Registry::default());
+        let pruner = Pruner::new(store.clone(), policy, metrics.clone()).with_batch_size(7);
+        (pruner, metrics)
+    }
+
+    #[tokio::test]
+    async fn test_prune_checkpoints_in_batches() {
+        let store = MemoryStore::new();
+        let (pruner, metrics) = pruner(&store, RetentionPolicy::Checkpoints(10));
+
+        let pruned = pruner.prune(&FixedWatermark(Some(49))).await.unwrap();
+        assert_eq!(pruned, 40);
+        for table in TABLES {
+            assert_eq!(store.first_row(table), 40);
+            assert_eq!(metrics.pruned_rows.with_label_values(&[table]).get(), 40);
+        }
+        assert_eq!(metrics.pruned_checkpoint_watermark.get(), 40);
+        assert_eq!(metrics.pruner_lag_checkpoints.get(), 9);
+
+        // The watermark never moves back, even if the policy would keep more.
+        let pruned = pruner.prune(&FixedWatermark(Some(45))).await.unwrap();
+        assert_eq!(pruned, 40);
+    }
+
+    #[tokio::test]
+    async fn test_prune_epochs() {
+        let store = MemoryStore::new();
+        let (pruner, _) = pruner(&store, RetentionPolicy::Epochs(2));
+
+        // The watermark is in epoch 4, so epochs 3 and 4 are kept.
+        assert_eq!(pruner.prune(&FixedWatermark(Some(49))).await.unwrap(), 30);
+        assert_eq!(store.first_row("events"), 30);
+
+        // Nothing is pruned before there are more epochs than the policy keeps.
+        let store = MemoryStore::new();
+        let (pruner, _) = pruner(&store, RetentionPolicy::Epochs(5));
+        assert_eq!(pruner.prune(&FixedWatermark(Some(49))).await.unwrap(), 0);
+        assert_eq!(store.first_row("events"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_drop_partitions_before_deleting_rows() {
+        let store = MemoryStore::with_partitioned_tables(&["transactions"]);
+        let (pruner, metrics) = pruner(&store, RetentionPolicy::Checkpoints(10));
+        let pruned = |table: &str| {
+            (
+                metrics.pruned_partitions.with_label_values(&[table]).get(),
+                metrics.pruned_rows.with_label_values(&[table]).get(),
+            )
+        };
+
+        // The partitions of epochs 0 to 3 are dropped whole, and only the rows of epoch 4
+        // before the watermark are deleted.
+        assert_eq!(pruner.prune(&FixedWatermark(Some(54))).await.unwrap(), 45);
+        for table in TABLES {
+            assert_eq!(store.first_row(table), 45);
+        }
+        assert_eq!(pruned("transactions"), (4, 5));
+        assert_eq!(pruned("events"), (0, 45));
+
+        // The partition of epoch 4 is dropped once the watermark is past it.
+        assert_eq!(pruner.prune(&FixedWatermark(Some(59))).await.unwrap(), 50);
+        assert_eq!(store.first_row("transactions"), 50);
+        assert_eq!(pruned("transactions"), (5, 5));
+        assert_eq!(pruned("events"), (0, 50));
+    }
+
+    #[tokio::test]
+    async fn test_never_prune_past_lowest_watermark() {
+        let store = MemoryStore::new();
+        let (pruner, _) = pruner(&store, RetentionPolicy::Checkpoints(0));
+
+        // Nothing is pruned until every consumer has a watermark.
+        assert_eq!(pruner.prune(&FixedWatermark(None)).await.unwrap(), 0);
+        assert_eq!(store.first_row("transactions"), 0);
+
+        assert_eq!(pruner.prune(&FixedWatermark(Some(49))).await.unwrap(), 49);
+        assert_eq!(store.first_row("transactions"), 49);
+    }
+
+    #[tokio::test]
+    async fn test_finish_interrupted_round() {
+        // A previous round raised the watermark but failed before deleting anything.
+        let store = MemoryStore::new();
+        *store.pruned_watermark.lock().unwrap() = 20;
+        let (pruner, _) = pruner(&store, RetentionPolicy::Checkpoints(10));
+
+        assert_eq!(pruner.prune(&FixedWatermark(None)).await.unwrap(), 20);
+        for table in TABLES {
+            assert_eq!(store.first_row(table), 20);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lowest_handler_watermark() {
+        let progress_store = Arc::new(InMemoryProgressStore::default());
+        let watermarks = HandlerWatermarks::new(
+            vec!["checkpoints".to_string(), "objects".to_string()],
+            progress_store.clone(),
+        );
+
+        progress_store.save("checkpoints", 20).await.unwrap();
+        assert_eq!(watermarks.lowest_watermark().await.unwrap(), None);
+        progress_store.save("objects", 12).await.unwrap();
+        assert_eq!(watermarks.lowest_watermark().await.unwrap(), Some(12));
+    }
+}
@@ -0,0 +1,379 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Verifies the digests of ingested checkpoints against the certified summaries of a separate
+//! source, so that rows ingested from a source serving checkpoints that never finalized do not
+//! stay served. The rows of a checkpoint that fails verification are tombstoned, and the
+//! checkpoint is ingested again from the certified source.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use mgo_types::digests::CheckpointDigest;
+use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
+use tracing::{info, warn};
+
+use super::interface::{
+    CertifiedCheckpointSource, CheckpointData, Handler, ReconcilableStore, WatermarkSource,
+};
+use crate::metrics::ReconcilerMetrics;
+
+/// Periodically verifies the checkpoints of a [`ReconcilableStore`] after its reconciled
+/// watermark, in order, and has its handlers ingest the certified version of those that differ.
+pub struct Reconciler {
+    store: Arc<dyn ReconcilableStore>,
+    source: Arc<dyn CertifiedCheckpointSource>,
+    handlers: Vec<Box<dyn Handler>>,
+    batch_size: usize,
+    interval: Duration,
+    metrics: ReconcilerMetrics,
+}
+
+impl Reconciler {
+    pub const DEFAULT_BATCH_SIZE: usize = 100;
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(10);
+
+    pub fn new<S, C>(store: S, source: C, metrics: ReconcilerMetrics) -> Self
+    where
+        S: ReconcilableStore + 'static,
+        C: CertifiedCheckpointSource + 'static,
+    {
+        Self {
+            store: Arc::new(store),
+            source: Arc::new(source),
+            handlers: vec![],
+            batch_size: Self::DEFAULT_BATCH_SIZE,
+            interval: Self::DEFAULT_INTERVAL,
+            metrics,
+        }
+    }
+
+    /// Adds a handler that checkpoints failing verification are ingested again with. Its
+    /// writes must replace the tombstoned rows, e.g. the same handlers the pipeline runs.
+    pub fn register_handler<H: Handler + 'static>(mut self, handler: H) -> Self {
+        self.handlers.push(Box::new(handler));
+        self
+    }
+
+    /// Maximum number of checkpoints verified in one round.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// How long the reconciler waits between rounds.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Reconciles a round every interval, never past the lowest watermark of `watermarks`. A
+    /// failed round is picked up again by the next one.
+    pub async fn run(mut self, watermarks: Arc<dyn WatermarkSource>) {
+        info!("Starting reconciler");
+        loop {
+            if let Err(e) = self.reconcile(watermarks.as_ref()).await {
+                warn!("Reconciliation round failed, retrying in {:?}: {e}", self.interval);
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+
+    /// Runs a single round, returning the reconciled watermark after it. The round stops at
+    /// the first checkpoint that fails verification, which is verified again by the next round
+    /// once its certified version is committed.
+    pub async fn reconcile(
+        &mut self,
+        watermarks: &dyn WatermarkSource,
+    ) -> Result<CheckpointSequenceNumber> {
+        let start = self.store.get_reconciled_watermark().await?;
+        let Some(lowest) = watermarks.lowest_watermark().await? else {
+            return Ok(start);
+        };
+        let end = (lowest + 1).min(start + self.batch_size as u64);
+        let mut reconciled = start;
+        let mut result = Ok(());
+        while reconciled < end {
+            match self.verify(reconciled).await {
+                Ok(true) => reconciled += 1,
+                Ok(false) => break,
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+
+        // The progress of a round is kept even if it failed midway.
+        if reconciled > start {
+            self.store.set_reconciled_watermark(reconciled).await?;
+        }
+        self.metrics
+            .reconciled_checkpoint_watermark
+            .set(reconciled as i64);
+        result.map(|()| reconciled)
+    }
+
+    /// Whether `checkpoint` was ingested with its certified digest. If not, its certified
+    /// version is ingested again.
+    async fn verify(&mut self, checkpoint: CheckpointSequenceNumber) -> Result<bool> {
+        let certified = *self.source.get_certified_summary(checkpoint).await?.digest();
+        let ingested = self.store.get_checkpoint_digest(checkpoint).await?;
+        if ingested == Some(certified) {
+            return Ok(true);
+        }
+        self.reingest(checkpoint, ingested, certified).await?;
+        Ok(false)
+    }
+
+    /// Tombstones the rows of `checkpoint` ingested with another digest than `certified`, and
+    /// ingests its certified version. A checkpoint below the watermarks without a digest was
+    /// tombstoned by a round that failed before ingesting it again.
+    async fn reingest(
+        &mut self,
+        checkpoint: CheckpointSequenceNumber,
+        ingested: Option<CheckpointDigest>,
+        certified: CheckpointDigest,
+    ) -> Result<()> {
+        if let Some(ingested) = ingested {
+            warn!(
+                checkpoint,
+                %ingested,
+                %certified,
+                "Ingested checkpoint differs from the certified one, tombstoning its rows"
+            );
+            self.metrics.mismatched_checkpoints.inc();
+            let tombstoned = self
+                .store
+                .tombstone_checkpoint(checkpoint, ingested, certified)
+                .await?;
+            self.metrics.tombstoned_rows.inc_by(tombstoned as u64);
+        }
+
+        let data = CheckpointData::from(self.source.get_full_checkpoint(checkpoint).await?);
+        let digest = *data.summary().digest();
+        if digest != certified {
+            bail!("Certified source served checkpoint {checkpoint} with digest {digest}");
+        }
+        for handler in &mut self.handlers {
+            handler.process_checkpoint(&data).await?;
+        }
+        info!(checkpoint, "Ingested the certified checkpoint again");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    use anyhow::anyhow;
+    use mgo_types::full_checkpoint_content;
+    use mgo_types::messages_checkpoint::CertifiedCheckpointSummary;
+    use prometheus::Registry;
+
+    use super::super::fetcher::tests::{make_checkpoints, MockSource, LATEST_CHECKPOINT};
+    use super::*;
+
+    const BAD_CHECKPOINT: CheckpointSequenceNumber = 3;
+
+    #[async_trait::async_trait]
+    impl CertifiedCheckpointSource for MockSource {
+        async fn get_certified_summary(
+            &self,
+            sequence_number: CheckpointSequenceNumber,
+        ) -> Result<CertifiedCheckpointSummary> {
+            self.checkpoints
+                .get(&sequence_number)
+                .map(|data| data.checkpoint_summary.clone())
+                .ok_or_else(|| anyhow!("missing checkpoint"))
+        }
+    }
+
+    /// Holds the digest every checkpoint was ingested with, and a row per checkpoint tagged
+    /// with the checkpoint it was ingested from.
+    #[derive(Default)]
+    struct MemoryStore {
+        digests: Mutex<BTreeMap<CheckpointSequenceNumber, CheckpointDigest>>,
+        rows: Mutex<Vec<(CheckpointSequenceNumber, CheckpointDigest)>>,
+        tombstones: Mutex<Vec<(CheckpointSequenceNumber, CheckpointDigest)>>,
+        reconciled_watermark: Mutex<CheckpointSequenceNumber>,
+    }
+
+    impl MemoryStore {
+        /// The rows readers are served, tombstoned ones excluded.
+        fn query(&self) -> Vec<(CheckpointSequenceNumber, CheckpointDigest)> {
+            let mut rows = self.rows.lock().unwrap().clone();
+            rows.sort();
+            rows
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ReconcilableStore for Arc<MemoryStore> {
+        async fn get_reconciled_watermark(&self) -> Result<CheckpointSequenceNumber> {
+            Ok(*self.reconciled_watermark.lock().unwrap())
+        }
+
+        async fn set_reconciled_watermark(
+            &self,
+            checkpoint: CheckpointSequenceNumber,
+        ) -> Result<()> {
+            *self.reconciled_watermark.lock().unwrap() = checkpoint;
+            Ok(())
+        }
+
+        async fn get_checkpoint_digest(
+            &self,
+            checkpoint: CheckpointSequenceNumber,
+        ) -> Result<Option<CheckpointDigest>> {
+            Ok(self.digests.lock().unwrap().get(&checkpoint).copied())
+        }
+
+        async fn tombstone_checkpoint(
+            &self,
+            checkpoint: CheckpointSequenceNumber,
+            _ingested: CheckpointDigest,
+            _certified: CheckpointDigest,
+        ) -> Result<usize> {
+            self.digests.lock().unwrap().remove(&checkpoint);
+            let mut rows = self.rows.lock().unwrap();
+            let (tombstoned, kept): (Vec<_>, Vec<_>) =
+                rows.drain(..).partition(|(row, _)| *row == checkpoint);
+            *rows = kept;
+            let mut tombstones = self.tombstones.lock().unwrap();
+            tombstones.extend_from_slice(&tombstoned);
+            Ok(tombstoned.len())
+        }
+    }
+
+    struct IngestingHandler(Arc<MemoryStore>);
+
+    #[async_trait::async_trait]
+    impl Handler for IngestingHandler {
+        fn name(&self) -> &str {
+            "ingesting"
+        }
+
+        async fn process_checkpoint(&mut self, checkpoint: &CheckpointData) -> Result<()> {
+            let row = (checkpoint.sequence_number(), *checkpoint.summary().digest());
+            self.0.digests.lock().unwrap().insert(row.0, row.1);
+            self.0.rows.lock().unwrap().push(row);
+            Ok(())
+        }
+    }
+
+    struct FixedWatermark(Option<CheckpointSequenceNumber>);
+
+    #[async_trait::async_trait]
+    impl WatermarkSource for FixedWatermark {
+        async fn lowest_watermark(&self) -> Result<Option<CheckpointSequenceNumber>> {
+            Ok(self.0)
+        }
+    }
+
+    fn reconciler(
+        store: &Arc<MemoryStore>,
+        checkpoints: BTreeMap<CheckpointSequenceNumber, full_checkpoint_content::CheckpointData>,
+    ) -> (Reconciler, ReconcilerMetrics) {
+        let metrics = ReconcilerMetrics::new(&Registry::default());
+        let reconciler = Reconciler::new(store.clone(), MockSource { checkpoints }, metrics.clone())
+            .register_handler(IngestingHandler(store.clone()));
+        (reconciler, metrics)
+    }
+
+    fn digests(
+        checkpoints: &BTreeMap<CheckpointSequenceNumber, full_checkpoint_content::CheckpointData>,
+    ) -> Vec<(CheckpointSequenceNumber, CheckpointDigest)> {
+        checkpoints
+            .iter()
+            .map(|(sequence_number, data)| (*sequence_number, *data.checkpoint_summary.digest()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_reingest_bad_checkpoint() {
+        let checkpoints = make_checkpoints();
+        // A checkpoint of another chain stands for one that never finalized.
+        let bad = make_checkpoints().remove(&BAD_CHECKPOINT).unwrap();
+        let bad_digest = *bad.checkpoint_summary.digest();
+        let mut served = checkpoints.clone();
+        served.insert(BAD_CHECKPOINT, bad);
+
+        let store = Arc::new(MemoryStore::default());
+        let mut handler = IngestingHandler(store.clone());
+        for data in served.into_values() {
+            handler.process_checkpoint(&data.into()).await.unwrap();
+        }
+        assert!(store.query().contains(&(BAD_CHECKPOINT, bad_digest)));
+
+        // The first round stops at the bad checkpoint, after tombstoning its rows and ingesting
+        // its certified version.
+        let (mut reconciler, metrics) = reconciler(&store, checkpoints.clone());
+        let watermark = FixedWatermark(Some(LATEST_CHECKPOINT));
+        let reconciled = reconciler.reconcile(&watermark).await.unwrap();
+        assert_eq!(reconciled, BAD_CHECKPOINT);
+        assert_eq!(
+            *store.tombstones.lock().unwrap(),
+            vec![(BAD_CHECKPOINT, bad_digest)]
+        );
+        assert_eq!(metrics.mismatched_checkpoints.get(), 1);
+        assert_eq!(metrics.tombstoned_rows.get(), 1);
+
+        // The next round verifies the certified version and the checkpoints after it.
+        let reconciled = reconciler.reconcile(&watermark).await.unwrap();
+        assert_eq!(reconciled, LATEST_CHECKPOINT + 1);
+        assert_eq!(metrics.mismatched_checkpoints.get(), 1);
+        assert_eq!(
+            metrics.reconciled_checkpoint_watermark.get(),
+            reconciled as i64
+        );
+        assert_eq!(store.query(), digests(&checkpoints));
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_up_to_lowest_watermark() {
+        let checkpoints = make_checkpoints();
+        let store = Arc::new(MemoryStore::default());
+        let mut handler = IngestingHandler(store.clone());
+        for data in checkpoints.values().cloned() {
+            handler.process_checkpoint(&data.into()).await.unwrap();
+        }
+        let (reconciler, metrics) = reconciler(&store, checkpoints.clone());
+        let mut reconciler = reconciler.with_batch_size(2);
+
+        // Nothing is verified until every handler has a watermark.
+        let watermark = FixedWatermark(None);
+        assert_eq!(reconciler.reconcile(&watermark).await.unwrap(), 0);
+        let watermark = FixedWatermark(Some(2));
+        assert_eq!(reconciler.reconcile(&watermark).await.unwrap(), 2);
+        assert_eq!(reconciler.reconcile(&watermark).await.unwrap(), 3);
+        assert_eq!(reconciler.reconcile(&watermark).await.unwrap(), 3);
+        assert_eq!(metrics.mismatched_checkpoints.get(), 0);
+        assert_eq!(store.query(), digests(&checkpoints));
+    }
+
+    #[tokio::test]
+    async fn test_reingest_tombstoned_checkpoint() {
+        // A previous round tombstoned checkpoint 1 but failed before ingesting it again.
+        let checkpoints = make_checkpoints();
+        let store = Arc::new(MemoryStore::default());
+        let mut handler = IngestingHandler(store.clone());
+        for (sequence_number, data) in checkpoints.clone() {
+            if sequence_number != 1 {
+                handler.process_checkpoint(&data.into()).await.unwrap();
+            }
+        }
+        *store.reconciled_watermark.lock().unwrap() = 1;
+
+        let (mut reconciler, metrics) = reconciler(&store, checkpoints.clone());
+        let watermark = FixedWatermark(Some(LATEST_CHECKPOINT));
+        assert_eq!(reconciler.reconcile(&watermark).await.unwrap(), 1);
+        let reconciled = reconciler.reconcile(&watermark).await.unwrap();
+        assert_eq!(reconciled, LATEST_CHECKPOINT + 1);
+        assert_eq!(metrics.mismatched_checkpoints.get(), 0);
+        assert_eq!(store.query(), digests(&checkpoints));
+    }
+}
@@ -1,29 +1,811 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
-use mgo_rest_api::CheckpointData;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::time::Duration;
 
-use super::interface::Handler;
+use anyhow::Result;
+use futures::{FutureExt, StreamExt};
+use mango_metrics::metered_channel::{Receiver, ReceiverStream};
+use mango_metrics::spawn_monitored_task;
+use mgo_types::full_checkpoint_content;
+use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
+use tokio::sync::watch;
+use tracing::{error, info, warn};
 
-pub async fn run<S>(stream: S, mut handlers: Vec<Box<dyn Handler>>)
-where
-    S: futures::Stream<Item = CheckpointData> + std::marker::Unpin,
-{
-    use futures::StreamExt;
+use super::backpressure::CheckpointBuffer;
+use super::fetcher::CheckpointFetcher;
+use super::hooks::LifecycleHooks;
+use super::interface::{CheckpointData, Handler, ProgressStore};
+
+/// How long a handler that failed waits before it is restarted from its watermark.
+const HANDLER_RESTART_DELAY: Duration = Duration::from_secs(1);
+
+/// How long the runner waits before saving a watermark again after failing to.
+const SAVE_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// How long handlers are given to finish the batch they are processing once shutdown is
+/// requested, by default.
+pub const DEFAULT_SHUTDOWN_DEADLINE: Duration = Duration::from_secs(30);
+
+/// Creates a fetcher that starts right after the given checkpoint, or at genesis for `None`,
+/// together with the receiving end of the channel it sends checkpoints to.
+pub type FetcherFactory = Arc<
+    dyn Fn(
+            Option<CheckpointSequenceNumber>,
+        ) -> (
+            CheckpointFetcher,
+            Receiver<full_checkpoint_content::CheckpointData>,
+        ) + Send
+        + Sync,
+>;
+
+/// Stops the runner once `signal` is set to `true`, giving the batches in flight `deadline` to
+/// finish.
+#[derive(Clone)]
+pub(crate) struct Shutdown {
+    signal: watch::Receiver<bool>,
+    deadline: Duration,
+}
+
+impl Shutdown {
+    pub fn new(signal: watch::Receiver<bool>, deadline: Duration) -> Self {
+        Self { signal, deadline }
+    }
+
+    /// Never requested, for runners that stop on their own or get aborted.
+    pub fn never() -> Self {
+        Self::new(watch::channel(false).1, DEFAULT_SHUTDOWN_DEADLINE)
+    }
+
+    fn is_requested(&self) -> bool {
+        *self.signal.borrow()
+    }
+
+    /// Resolves once shutdown is requested, or never if it no longer can be.
+    async fn requested(&mut self) {
+        while !*self.signal.borrow_and_update() {
+            if self.signal.changed().await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        }
+    }
+}
 
-    let batch_size = std::env::var("CHECKPOINT_PROCESSING_BATCH_SIZE")
+pub(crate) fn checkpoint_processing_batch_size() -> usize {
+    std::env::var("CHECKPOINT_PROCESSING_BATCH_SIZE")
         .unwrap_or(25.to_string())
         .parse::<usize>()
-        .unwrap();
+        .unwrap()
+}
+
+/// Feeds every checkpoint of `stream` to all handlers, in lockstep, releasing the checkpoints
+/// from `buffer` once all handlers processed them.
+///
+/// Once `shutdown` is requested, no further checkpoints are taken and the handlers finish the
+/// batch they are processing before this returns, unless it takes longer than the shutdown
+/// deadline.
+pub(crate) async fn run<S>(
+    stream: S,
+    mut handlers: Vec<Box<dyn Handler>>,
+    buffer: Option<CheckpointBuffer>,
+    mut shutdown: Shutdown,
+) where
+    S: futures::Stream<Item = full_checkpoint_content::CheckpointData> + std::marker::Unpin,
+{
+    let batch_size = checkpoint_processing_batch_size();
     tracing::info!("Indexer runner is starting with {batch_size}");
     let mut chunks: futures::stream::ReadyChunks<S> = stream.ready_chunks(batch_size);
-    while let Some(checkpoints) = chunks.next().await {
+    loop {
+        let checkpoints = tokio::select! {
+            biased;
+            _ = shutdown.requested() => break,
+            checkpoints = chunks.next() => match checkpoints {
+                Some(checkpoints) => checkpoints,
+                None => break,
+            },
+        };
+        let checkpoints: Vec<_> = checkpoints.into_iter().map(CheckpointData::from).collect();
+        let last = checkpoints.last().unwrap().sequence_number();
         //TODO create tracing spans for processing
-        futures::future::join_all(
+        let deadline = shutdown.deadline;
+        let process = futures::future::join_all(
             handlers
                 .iter_mut()
                 .map(|handler| async { handler.process_checkpoints(&checkpoints).await.unwrap() }),
+        );
+        tokio::pin!(process);
+        let finished = tokio::select! {
+            _ = &mut process => true,
+            _ = shutdown.requested() => false,
+        };
+        if !finished && tokio::time::timeout(deadline, process).await.is_err() {
+            warn!(
+                "Handlers did not finish the checkpoints up to {last} within the shutdown \
+                deadline of {deadline:?}"
+            );
+            break;
+        }
+        if let Some(buffer) = &buffer {
+            buffer.release(checkpoints.len());
+        }
+    }
+    info!("Indexer runner stopped");
+}
+
+/// Runs every handler in its own pipeline, fed by its own fetcher, and records its watermark in
+/// `progress_store` after every batch. A handler that returns an error or panics is restarted
+/// from its watermark, without holding back the others. Handlers without a watermark yet start
+/// right after `default_watermark`, and stop once their watermark reaches `end_checkpoint`.
+/// `hooks` are called after every watermark saved and every handler failure.
+///
+/// Once `shutdown` is requested, handlers take no further checkpoints and finish the batch they
+/// are processing, saving its watermark, before this returns. A batch still unfinished at the
+/// shutdown deadline is dropped without saving its watermark, so that it is processed again
+/// on restart.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn run_with_progress(
+    handlers: Vec<Box<dyn Handler>>,
+    progress_store: Arc<dyn ProgressStore>,
+    new_fetcher: FetcherFactory,
+    default_watermark: Option<CheckpointSequenceNumber>,
+    end_checkpoint: Option<CheckpointSequenceNumber>,
+    batch_size: usize,
+    hooks: Arc<LifecycleHooks>,
+    shutdown: Shutdown,
+) {
+    info!(
+        "Indexer runner is starting {} handlers with {batch_size}",
+        handlers.len()
+    );
+    let pipelines = handlers.into_iter().map(|handler| {
+        spawn_monitored_task!(run_handler(
+            handler,
+            progress_store.clone(),
+            new_fetcher.clone(),
+            default_watermark,
+            end_checkpoint,
+            batch_size,
+            hooks.clone(),
+            shutdown.clone(),
+        ))
+    });
+    futures::future::join_all(pipelines).await;
+    info!("Indexer runner stopped");
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_handler(
+    mut handler: Box<dyn Handler>,
+    progress_store: Arc<dyn ProgressStore>,
+    new_fetcher: FetcherFactory,
+    default_watermark: Option<CheckpointSequenceNumber>,
+    end_checkpoint: Option<CheckpointSequenceNumber>,
+    batch_size: usize,
+    hooks: Arc<LifecycleHooks>,
+    mut shutdown: Shutdown,
+) {
+    let name = handler.name().to_string();
+    loop {
+        if shutdown.is_requested() {
+            info!(handler = %name, "Handler stopped for shutdown");
+            return;
+        }
+        let result = match progress_store.load(&name).await {
+            Ok(watermark) => {
+                let watermark = watermark.or(default_watermark);
+                if is_done(watermark, end_checkpoint) {
+                    info!(handler = %name, ?watermark, "Handler has nothing left to process");
+                    return;
+                }
+                info!(handler = %name, ?watermark, "Starting handler");
+                let (fetcher, receiver) = new_fetcher(watermark);
+                let buffer = fetcher.buffer();
+                let fetcher = spawn_monitored_task!(fetcher.run());
+                let result = AssertUnwindSafe(process_checkpoints(
+                    handler.as_mut(),
+                    &name,
+                    receiver,
+                    buffer.as_ref(),
+                    progress_store.as_ref(),
+                    end_checkpoint,
+                    batch_size,
+                    hooks.as_ref(),
+                    &mut shutdown,
+                ))
+                .catch_unwind()
+                .await;
+                // The fetcher is stopped before its channel is dropped, which it treats as fatal.
+                fetcher.abort();
+                let _ = fetcher.await;
+                match result {
+                    Ok(result) => result,
+                    Err(_) => Err(anyhow::anyhow!("handler panicked")),
+                }
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => return,
+            Err(e) => {
+                error!(
+                    handler = %name,
+                    "Handler failed, restarting it from its watermark in {HANDLER_RESTART_DELAY:?}: {e}"
+                );
+                hooks.failed(e).await;
+            }
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(HANDLER_RESTART_DELAY) => {}
+            _ = shutdown.requested() => {}
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn process_checkpoints(
+    handler: &mut dyn Handler,
+    name: &str,
+    receiver: Receiver<full_checkpoint_content::CheckpointData>,
+    buffer: Option<&CheckpointBuffer>,
+    progress_store: &dyn ProgressStore,
+    end_checkpoint: Option<CheckpointSequenceNumber>,
+    batch_size: usize,
+    hooks: &LifecycleHooks,
+    shutdown: &mut Shutdown,
+) -> Result<()> {
+    let mut chunks = ReceiverStream::new(receiver).ready_chunks(batch_size);
+    loop {
+        let mut checkpoints = tokio::select! {
+            biased;
+            _ = shutdown.requested() => return Ok(()),
+            checkpoints = chunks.next() => match checkpoints {
+                Some(checkpoints) => checkpoints,
+                None => return Ok(()),
+            },
+        };
+        let received = checkpoints.len();
+        if let Some(end_checkpoint) = end_checkpoint {
+            checkpoints.retain(|c| c.checkpoint_summary.sequence_number <= end_checkpoint);
+        }
+        let checkpoints: Vec<_> = checkpoints.into_iter().map(CheckpointData::from).collect();
+        let last = checkpoints.last().unwrap().sequence_number();
+
+        let deadline = shutdown.deadline;
+        let process = handler.process_checkpoints(&checkpoints);
+        tokio::pin!(process);
+        let result = tokio::select! {
+            result = &mut process => Some(result),
+            _ = shutdown.requested() => None,
+        };
+        let result = match result {
+            Some(result) => result,
+            None => match tokio::time::timeout(deadline, process).await {
+                Ok(result) => result,
+                Err(_) => {
+                    warn!(
+                        handler = %name,
+                        "Handler did not finish the checkpoints up to {last} within the shutdown \
+                        deadline of {deadline:?}, they will be processed again on restart"
+                    );
+                    return Ok(());
+                }
+            },
+        };
+        result?;
+        // Processed checkpoints make room for the fetcher as soon as possible, even before
+        // their watermark is saved.
+        if let Some(buffer) = buffer {
+            buffer.release(received);
+        }
+        save_watermark(progress_store, name, last).await;
+        hooks.checkpoint_committed(last).await;
+        if is_done(Some(last), end_checkpoint) {
+            info!(handler = %name, "Handler reached its end checkpoint {last}");
+            return Ok(());
+        }
+    }
+}
+
+fn is_done(
+    watermark: Option<CheckpointSequenceNumber>,
+    end_checkpoint: Option<CheckpointSequenceNumber>,
+) -> bool {
+    matches!((watermark, end_checkpoint), (Some(watermark), Some(end)) if watermark >= end)
+}
+
+/// Saves the watermark until it succeeds. Restarting the handler instead would hand it the
+/// checkpoints it just processed a second time.
+async fn save_watermark(
+    progress_store: &dyn ProgressStore,
+    name: &str,
+    checkpoint: CheckpointSequenceNumber,
+) {
+    while let Err(e) = progress_store.save(name, checkpoint).await {
+        warn!(
+            handler = %name,
+            "Failed to save watermark {checkpoint}, retrying in {SAVE_RETRY_DELAY:?}: {e}"
+        );
+        tokio::time::sleep(SAVE_RETRY_DELAY).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    use prometheus::{IntGauge, Registry};
+
+    use super::super::backpressure::BufferLimits;
+    use super::super::fetcher::tests::{make_checkpoints, MockSource, LATEST_CHECKPOINT};
+    use super::super::pipeline::Pipeline;
+    use super::super::InMemoryProgressStore;
+    use super::*;
+    use crate::metrics::CheckpointFetcherMetrics;
+
+    const FAILING_CHECKPOINT: CheckpointSequenceNumber = 3;
+
+    /// Records the checkpoints it processes, panicking the first time it sees `panic_at`.
+    struct RecordingHandler {
+        name: &'static str,
+        processed: Arc<Mutex<Vec<CheckpointSequenceNumber>>>,
+        panic_at: Option<CheckpointSequenceNumber>,
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for RecordingHandler {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn process_checkpoint(&mut self, checkpoint: &CheckpointData) -> Result<()> {
+            let sequence_number = checkpoint.sequence_number();
+            if self.panic_at == Some(sequence_number) {
+                self.panic_at = None;
+                panic!("failed to process checkpoint {sequence_number}");
+            }
+            self.processed.lock().unwrap().push(sequence_number);
+            Ok(())
+        }
+    }
+
+    /// Records the checkpoints it starts processing, taking `delay` to process `slow_at`.
+    struct SlowHandler {
+        started: Arc<Mutex<Vec<CheckpointSequenceNumber>>>,
+        slow_at: CheckpointSequenceNumber,
+        delay: Duration,
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for SlowHandler {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        async fn process_checkpoint(&mut self, checkpoint: &CheckpointData) -> Result<()> {
+            let sequence_number = checkpoint.sequence_number();
+            self.started.lock().unwrap().push(sequence_number);
+            if sequence_number == self.slow_at {
+                tokio::time::sleep(self.delay).await;
+            }
+            Ok(())
+        }
+    }
+
+    /// Takes `delay` to process every checkpoint, recording the most checkpoints and bytes
+    /// ever buffered for it by the latest fetcher in `buffers`.
+    struct LaggingHandler {
+        buffers: Arc<Mutex<Vec<CheckpointBuffer>>>,
+        peak: Arc<Mutex<(usize, u64)>>,
+        delay: Duration,
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for LaggingHandler {
+        fn name(&self) -> &str {
+            "lagging"
+        }
+
+        async fn process_checkpoint(&mut self, _checkpoint: &CheckpointData) -> Result<()> {
+            let (checkpoints, bytes) = self.buffers.lock().unwrap().last().unwrap().buffered();
+            let mut peak = self.peak.lock().unwrap();
+            *peak = (peak.0.max(checkpoints), peak.1.max(bytes));
+            drop(peak);
+            tokio::time::sleep(self.delay).await;
+            Ok(())
+        }
+    }
+
+    fn fetcher_factory(
+        checkpoints: BTreeMap<CheckpointSequenceNumber, full_checkpoint_content::CheckpointData>,
+    ) -> FetcherFactory {
+        let metrics = CheckpointFetcherMetrics::new(&Registry::default());
+        Arc::new(move |watermark| {
+            let (sender, receiver) = mango_metrics::metered_channel::channel(
+                100,
+                &IntGauge::new("test_checkpoints", "test").unwrap(),
+            );
+            let fetcher = CheckpointFetcher::new(
+                MockSource {
+                    checkpoints: checkpoints.clone(),
+                },
+                watermark,
+                sender,
+                metrics.clone(),
+            );
+            (fetcher, receiver)
+        })
+    }
+
+    /// Like [`fetcher_factory`], with fetchers bounded by `limits` and collecting their buffer
+    /// into `buffers`.
+    fn bounded_fetcher_factory(
+        limits: BufferLimits,
+        buffers: Arc<Mutex<Vec<CheckpointBuffer>>>,
+        metrics: CheckpointFetcherMetrics,
+    ) -> FetcherFactory {
+        let checkpoints = make_checkpoints();
+        Arc::new(move |watermark| {
+            let (sender, receiver) = mango_metrics::metered_channel::channel(
+                limits.max_checkpoints,
+                &IntGauge::new("test_checkpoints", "test").unwrap(),
+            );
+            let buffer = CheckpointBuffer::new(limits, metrics.clone());
+            buffers.lock().unwrap().push(buffer.clone());
+            let fetcher = CheckpointFetcher::new(
+                MockSource {
+                    checkpoints: checkpoints.clone(),
+                },
+                watermark,
+                sender,
+                metrics.clone(),
+            )
+            .with_buffer(buffer);
+            (fetcher, receiver)
+        })
+    }
+
+    async fn wait_for_watermark(
+        progress_store: &InMemoryProgressStore,
+        name: &str,
+        checkpoint: CheckpointSequenceNumber,
+    ) {
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while progress_store.load(name).await.unwrap() != Some(checkpoint) {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("{name} did not reach checkpoint {checkpoint}"));
+    }
+
+    #[tokio::test]
+    async fn test_failing_handler_restarts_from_its_own_watermark() {
+        let healthy = Arc::new(Mutex::new(Vec::new()));
+        let failing = Arc::new(Mutex::new(Vec::new()));
+        let handlers: Vec<Box<dyn Handler>> = vec![
+            Box::new(RecordingHandler {
+                name: "healthy",
+                processed: healthy.clone(),
+                panic_at: None,
+            }),
+            Box::new(RecordingHandler {
+                name: "failing",
+                processed: failing.clone(),
+                panic_at: Some(FAILING_CHECKPOINT),
+            }),
+        ];
+        let progress_store = Arc::new(InMemoryProgressStore::default());
+        let runner = tokio::spawn(run_with_progress(
+            handlers,
+            progress_store.clone(),
+            fetcher_factory(make_checkpoints()),
+            None,
+            None,
+            1,
+            Arc::new(LifecycleHooks::default()),
+            Shutdown::never(),
+        ));
+
+        // The healthy handler catches up while the failing one waits to be restarted.
+        wait_for_watermark(&progress_store, "healthy", LATEST_CHECKPOINT).await;
+        assert_eq!(
+            progress_store.load("failing").await.unwrap(),
+            Some(FAILING_CHECKPOINT - 1)
+        );
+
+        // Once restarted, it resumes right after its own watermark, skipping nothing and
+        // processing nothing twice.
+        wait_for_watermark(&progress_store, "failing", LATEST_CHECKPOINT).await;
+        runner.abort();
+        let expected = (0..=LATEST_CHECKPOINT).collect::<Vec<_>>();
+        assert_eq!(*healthy.lock().unwrap(), expected);
+        assert_eq!(*failing.lock().unwrap(), expected);
+    }
+
+    /// Runs a [`SlowHandler`] until it starts processing `slow_at`, then requests shutdown,
+    /// returning once the runner stopped.
+    async fn shut_down_while_slow(
+        progress_store: Arc<InMemoryProgressStore>,
+        started: Arc<Mutex<Vec<CheckpointSequenceNumber>>>,
+        slow_at: CheckpointSequenceNumber,
+        delay: Duration,
+        deadline: Duration,
+    ) {
+        let (stop, signal) = watch::channel(false);
+        let handlers: Vec<Box<dyn Handler>> = vec![Box::new(SlowHandler {
+            started: started.clone(),
+            slow_at,
+            delay,
+        })];
+        let runner = tokio::spawn(run_with_progress(
+            handlers,
+            progress_store,
+            fetcher_factory(make_checkpoints()),
+            None,
+            None,
+            1,
+            Arc::new(LifecycleHooks::default()),
+            Shutdown::new(signal, deadline),
+        ));
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while !started.lock().unwrap().contains(&slow_at) {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        stop.send(true).unwrap();
+        tokio::time::timeout(Duration::from_secs(10), runner)
+            .await
+            .expect("runner did not stop")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_finishes_batch_in_flight() {
+        let started = Arc::new(Mutex::new(Vec::new()));
+        let progress_store = Arc::new(InMemoryProgressStore::default());
+        shut_down_while_slow(
+            progress_store.clone(),
+            started.clone(),
+            FAILING_CHECKPOINT,
+            Duration::from_millis(200),
+            Duration::from_secs(10),
         )
         .await;
+
+        // The checkpoint in flight is finished and saved, and no further one is started.
+        assert_eq!(
+            progress_store.load("slow").await.unwrap(),
+            Some(FAILING_CHECKPOINT)
+        );
+        assert_eq!(
+            *started.lock().unwrap(),
+            (0..=FAILING_CHECKPOINT).collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_deadline_leaves_watermark_for_restart() {
+        let started = Arc::new(Mutex::new(Vec::new()));
+        let progress_store = Arc::new(InMemoryProgressStore::default());
+        shut_down_while_slow(
+            progress_store.clone(),
+            started.clone(),
+            FAILING_CHECKPOINT,
+            Duration::from_secs(3600),
+            Duration::from_millis(50),
+        )
+        .await;
+
+        // The interrupted checkpoint is not recorded as processed.
+        assert_eq!(
+            progress_store.load("slow").await.unwrap(),
+            Some(FAILING_CHECKPOINT - 1)
+        );
+
+        // The restarted handler processes it again, then carries on without gaps.
+        let handlers: Vec<Box<dyn Handler>> = vec![Box::new(SlowHandler {
+            started: started.clone(),
+            slow_at: FAILING_CHECKPOINT,
+            delay: Duration::ZERO,
+        })];
+        tokio::time::timeout(
+            Duration::from_secs(10),
+            run_with_progress(
+                handlers,
+                progress_store.clone(),
+                fetcher_factory(make_checkpoints()),
+                None,
+                Some(LATEST_CHECKPOINT),
+                1,
+                Arc::new(LifecycleHooks::default()),
+                Shutdown::never(),
+            ),
+        )
+        .await
+        .unwrap();
+        let mut expected = (0..=FAILING_CHECKPOINT).collect::<Vec<_>>();
+        expected.extend(FAILING_CHECKPOINT..=LATEST_CHECKPOINT);
+        assert_eq!(*started.lock().unwrap(), expected);
+        assert_eq!(
+            progress_store.load("slow").await.unwrap(),
+            Some(LATEST_CHECKPOINT)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetcher_pauses_while_handler_lags() {
+        let largest = make_checkpoints()
+            .values()
+            .map(|checkpoint| bcs::serialized_size(checkpoint).unwrap() as u64)
+            .max()
+            .unwrap();
+        // Bounded by the number of checkpoints, then by their size.
+        for limits in [
+            BufferLimits {
+                max_checkpoints: 2,
+                max_bytes: None,
+            },
+            BufferLimits {
+                max_checkpoints: 100,
+                max_bytes: Some(2 * largest),
+            },
+        ] {
+            let metrics = CheckpointFetcherMetrics::new(&Registry::default());
+            let buffers = Arc::new(Mutex::new(Vec::new()));
+            let peak = Arc::new(Mutex::new((0, 0)));
+            let handlers: Vec<Box<dyn Handler>> = vec![Box::new(LaggingHandler {
+                buffers: buffers.clone(),
+                peak: peak.clone(),
+                delay: Duration::from_millis(50),
+            })];
+            let progress_store = Arc::new(InMemoryProgressStore::default());
+            tokio::time::timeout(
+                Duration::from_secs(10),
+                run_with_progress(
+                    handlers,
+                    progress_store.clone(),
+                    bounded_fetcher_factory(limits, buffers.clone(), metrics.clone()),
+                    None,
+                    Some(LATEST_CHECKPOINT),
+                    1,
+                    Arc::new(LifecycleHooks::default()),
+                    Shutdown::never(),
+                ),
+            )
+            .await
+            .expect("ingestion did not complete");
+
+            // Ingestion completes, without ever buffering past the limits.
+            assert_eq!(
+                progress_store.load("lagging").await.unwrap(),
+                Some(LATEST_CHECKPOINT)
+            );
+            let (checkpoints, bytes) = *peak.lock().unwrap();
+            assert!((1..=limits.max_checkpoints).contains(&checkpoints));
+            assert!(bytes <= limits.max_bytes.unwrap_or(u64::MAX));
+            // The fetcher had to wait for the handler, and resumed every time.
+            assert!(metrics.fetcher_pause_duration.get_sample_count() > 0);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handler_stops_at_end_checkpoint() {
+        const END_CHECKPOINT: CheckpointSequenceNumber = 3;
+        let processed = Arc::new(Mutex::new(Vec::new()));
+        let progress_store = Arc::new(InMemoryProgressStore::default());
+        for _ in 0..2 {
+            let handlers: Vec<Box<dyn Handler>> = vec![Box::new(RecordingHandler {
+                name: "backfill",
+                processed: processed.clone(),
+                panic_at: None,
+            })];
+            // Returns on its own once the handler is done, without waiting for the fetcher.
+            tokio::time::timeout(
+                Duration::from_secs(10),
+                run_with_progress(
+                    handlers,
+                    progress_store.clone(),
+                    fetcher_factory(make_checkpoints()),
+                    None,
+                    Some(END_CHECKPOINT),
+                    2,
+                    Arc::new(LifecycleHooks::default()),
+                    Shutdown::never(),
+                ),
+            )
+            .await
+            .unwrap();
+        }
+
+        // The second run starts at the end checkpoint and processes nothing.
+        assert_eq!(
+            progress_store.load("backfill").await.unwrap(),
+            Some(END_CHECKPOINT)
+        );
+        assert_eq!(
+            *processed.lock().unwrap(),
+            (0..=END_CHECKPOINT).collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hooks_run_in_order() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let (started, committed, failed) = (events.clone(), events.clone(), events.clone());
+        let pipeline = Pipeline::new()
+            .register_handler(RecordingHandler {
+                name: "failing",
+                processed: Default::default(),
+                panic_at: Some(FAILING_CHECKPOINT),
+            })
+            .end_checkpoint(LATEST_CHECKPOINT)
+            .batch_size(1)
+            .on_start(move || started.lock().unwrap().push("start".to_string()))
+            .on_checkpoint_committed(move |checkpoint| {
+                committed
+                    .lock()
+                    .unwrap()
+                    .push(format!("committed {checkpoint}"))
+            })
+            .on_error(move |_| failed.lock().unwrap().push("error".to_string()));
+        tokio::time::timeout(
+            Duration::from_secs(10),
+            pipeline.run(fetcher_factory(make_checkpoints())),
+        )
+        .await
+        .unwrap();
+
+        // Every watermark is reported once saved, and the failure before the handler resumes
+        // from the last one.
+        let mut expected = vec!["start".to_string()];
+        expected
+            .extend((0..FAILING_CHECKPOINT).map(|checkpoint| format!("committed {checkpoint}")));
+        expected.push("error".to_string());
+        expected.extend(
+            (FAILING_CHECKPOINT..=LATEST_CHECKPOINT)
+                .map(|checkpoint| format!("committed {checkpoint}")),
+        );
+        assert_eq!(*events.lock().unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_slow_hook_is_skipped() {
+        let (release, blocked) = std::sync::mpsc::channel::<()>();
+        let blocked = Mutex::new(blocked);
+        let committed = Arc::new(Mutex::new(Vec::new()));
+        let recorded = committed.clone();
+        let pipeline = Pipeline::new()
+            .register_handler(RecordingHandler {
+                name: "handler",
+                processed: Default::default(),
+                panic_at: None,
+            })
+            .end_checkpoint(LATEST_CHECKPOINT)
+            .batch_size(1)
+            .hook_timeout(Duration::from_millis(100))
+            .on_checkpoint_committed(move |checkpoint| {
+                if checkpoint == 0 {
+                    blocked.lock().unwrap().recv().unwrap();
+                }
+                recorded.lock().unwrap().push(checkpoint);
+            });
+
+        // Ingestion carries on while the hook of checkpoint 0 is still blocked.
+        tokio::time::timeout(
+            Duration::from_secs(10),
+            pipeline.run(fetcher_factory(make_checkpoints())),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            *committed.lock().unwrap(),
+            (1..=LATEST_CHECKPOINT).collect::<Vec<_>>()
+        );
+        release.send(()).unwrap();
     }
 }
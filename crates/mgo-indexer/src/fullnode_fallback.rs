@@ -0,0 +1,188 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serves transaction reads the reader cannot answer yet from the fullnode the indexer ingests
+//! from, for transactions that were executed but whose checkpoint is not indexed yet. Nothing
+//! read this way is written to the database.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use futures::future::join_all;
+use jsonrpsee::http_client::HttpClient;
+use tracing::warn;
+
+use mgo_json_rpc_api::ReadApiClient;
+use mgo_json_rpc_types::{MgoTransactionBlockResponse, MgoTransactionBlockResponseOptions};
+use mgo_types::digests::TransactionDigest;
+
+/// Value of `served_from` on responses read from the fullnode.
+pub const SERVED_FROM_FULLNODE: &str = "fullnode";
+
+/// Consecutive failed requests after which a fullnode is considered unavailable.
+const FAILURE_THRESHOLD: u32 = 5;
+/// How long a fullnode is considered unavailable before it is tried again.
+const OPEN_DURATION: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Default)]
+struct HealthState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+/// Circuit breaker over requests to a fullnode. It opens after [`FAILURE_THRESHOLD`]
+/// consecutive failures and lets requests through again once [`OPEN_DURATION`] has passed,
+/// closing on the first success.
+#[derive(Clone, Debug, Default)]
+pub struct FullnodeHealth {
+    state: Arc<Mutex<HealthState>>,
+}
+
+impl FullnodeHealth {
+    /// The health of the fullnode at `url`, shared by the checkpoint fetcher and the reader
+    /// when they run in the same process.
+    pub fn for_url(url: &str) -> Self {
+        static HEALTH: OnceLock<Mutex<HashMap<String, FullnodeHealth>>> = OnceLock::new();
+        HEALTH
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap()
+            .entry(url.to_string())
+            .or_default()
+            .clone()
+    }
+
+    pub fn is_available(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state
+            .open_until
+            .map_or(true, |open_until| Instant::now() >= open_until)
+    }
+
+    pub fn record_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures = 0;
+        state.open_until = None;
+    }
+
+    pub fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures += 1;
+        if state.consecutive_failures >= FAILURE_THRESHOLD {
+            state.open_until = Some(Instant::now() + OPEN_DURATION);
+        }
+    }
+}
+
+/// Reads transactions missing from the database from a fullnode, within `timeout` and while
+/// the fullnode is healthy.
+#[derive(Clone)]
+pub struct TransactionFallback {
+    client: HttpClient,
+    timeout: Duration,
+    health: FullnodeHealth,
+}
+
+impl TransactionFallback {
+    pub fn new(client: HttpClient, timeout: Duration, health: FullnodeHealth) -> Self {
+        Self {
+            client,
+            timeout,
+            health,
+        }
+    }
+
+    /// Reads `digests` from the fullnode, skipping the ones it does not have and returning
+    /// nothing at all while it is unavailable.
+    pub async fn multi_get_transaction_blocks(
+        &self,
+        digests: &[TransactionDigest],
+        options: &MgoTransactionBlockResponseOptions,
+    ) -> Vec<MgoTransactionBlockResponse> {
+        if digests.is_empty() || !self.health.is_available() {
+            return vec![];
+        }
+        join_all(
+            digests
+                .iter()
+                .map(|digest| self.get_transaction_block(*digest, options.clone())),
+        )
+        .await
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    async fn get_transaction_block(
+        &self,
+        digest: TransactionDigest,
+        options: MgoTransactionBlockResponseOptions,
+    ) -> Option<MgoTransactionBlockResponse> {
+        let result = tokio::time::timeout(
+            self.timeout,
+            self.client.get_transaction_block(digest, Some(options)),
+        )
+        .await;
+        match result {
+            Ok(Ok(mut response)) => {
+                self.health.record_success();
+                response.served_from = Some(SERVED_FROM_FULLNODE.to_string());
+                Some(response)
+            }
+            // The fullnode answered, it just does not have the transaction either.
+            Ok(Err(jsonrpsee::core::Error::Call(_))) => {
+                self.health.record_success();
+                None
+            }
+            Ok(Err(e)) => {
+                warn!("Failed to read transaction {digest} from the fullnode: {e}");
+                self.health.record_failure();
+                None
+            }
+            Err(_) => {
+                warn!("Timed out reading transaction {digest} from the fullnode");
+                self.health.record_failure();
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit_breaker_opens_after_consecutive_failures() {
+        let health = FullnodeHealth::default();
+        for _ in 0..FAILURE_THRESHOLD - 1 {
+            health.record_failure();
+        }
+        health.record_success();
+        for _ in 0..FAILURE_THRESHOLD - 1 {
+            health.record_failure();
+        }
+        assert!(health.is_available());
+
+        health.record_failure();
+        assert!(!health.is_available());
+
+        // Once the breaker lets requests through again, a single success closes it.
+        health.state.lock().unwrap().open_until = Some(Instant::now());
+        assert!(health.is_available());
+        health.record_success();
+        assert!(health.is_available());
+        assert_eq!(health.state.lock().unwrap().consecutive_failures, 0);
+    }
+
+    #[test]
+    fn test_health_is_shared_per_url() {
+        let url = "http://fullnode.test:9000";
+        for _ in 0..FAILURE_THRESHOLD {
+            FullnodeHealth::for_url(url).record_failure();
+        }
+        assert!(!FullnodeHealth::for_url(url).is_available());
+        assert!(FullnodeHealth::for_url("http://other.test:9000").is_available());
+    }
+}
@@ -21,7 +21,7 @@ use mgo_types::mgo_system_state::{get_mgo_system_state, MgoSystemStateTrait};
 use mgo_types::MGO_SYSTEM_ADDRESS;
 
 use crate::errors::IndexerError;
-use crate::framework::interface::Handler;
+use crate::framework::interface::{self, Handler, ProgressStore};
 use crate::metrics::IndexerMetrics;
 use crate::models::checkpoints::Checkpoint;
 use crate::models::epoch::{DBEpochInfo, SystemEpochInfoEvent};
@@ -41,6 +41,9 @@ use crate::IndexerConfig;
 const CHECKPOINT_QUEUE_SIZE: usize = 1000;
 const EPOCH_QUEUE_LIMIT: usize = 20;
 
+const CHECKPOINT_HANDLER_NAME: &str = "checkpoint-transaction-and-epoch-indexer";
+const OBJECTS_HANDLER_NAME: &str = "objects-indexer";
+
 pub fn new_handlers<S>(
     state: S,
     metrics: IndexerMetrics,
@@ -120,6 +123,51 @@ where
     (checkpoint_processor, object_processor)
 }
 
+/// Watermarks of the handlers returned by [`new_handlers`], read back from the tables their
+/// commit tasks write. Handlers only hand checkpoints off to those tasks, so saving is a no-op:
+/// the tables are the only record of how far a handler actually got.
+pub struct IndexerStoreProgress<S> {
+    state: S,
+}
+
+impl<S> IndexerStoreProgress<S> {
+    pub fn new(state: S) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait::async_trait]
+impl<S> ProgressStore for IndexerStoreProgress<S>
+where
+    S: IndexerStore + Clone + Sync + Send + 'static,
+{
+    async fn load(&self, handler_name: &str) -> anyhow::Result<Option<CheckpointSequenceNumber>> {
+        let latest = match handler_name {
+            CHECKPOINT_HANDLER_NAME => {
+                self.state
+                    .get_latest_tx_checkpoint_sequence_number()
+                    .await?
+            }
+            OBJECTS_HANDLER_NAME => {
+                self.state
+                    .get_latest_object_checkpoint_sequence_number()
+                    .await?
+            }
+            _ => anyhow::bail!("No watermark is kept for handler {handler_name}"),
+        };
+        // -1 is returned when the table is empty.
+        Ok(u64::try_from(latest).ok())
+    }
+
+    async fn save(
+        &self,
+        _handler_name: &str,
+        _checkpoint: CheckpointSequenceNumber,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
 pub struct CheckpointProcessor<S> {
     state: S,
     metrics: IndexerMetrics,
@@ -133,9 +181,13 @@ where
     S: IndexerStore + Clone + Sync + Send + 'static,
 {
     fn name(&self) -> &str {
-        "checkpoint-transaction-and-epoch-indexer"
+        CHECKPOINT_HANDLER_NAME
     }
-    async fn process_checkpoint(&mut self, checkpoint_data: &CheckpointData) -> anyhow::Result<()> {
+    async fn process_checkpoint(
+        &mut self,
+        checkpoint_data: &interface::CheckpointData,
+    ) -> anyhow::Result<()> {
+        let checkpoint_data = checkpoint_data.full();
         info!(
             checkpoint_seq = checkpoint_data.checkpoint_summary.sequence_number(),
             "Checkpoint received by indexing processor"
@@ -829,9 +881,13 @@ where
     S: IndexerStore + Clone + Sync + Send + 'static,
 {
     fn name(&self) -> &str {
-        "objects-indexer"
+        OBJECTS_HANDLER_NAME
     }
-    async fn process_checkpoint(&mut self, checkpoint_data: &CheckpointData) -> anyhow::Result<()> {
+    async fn process_checkpoint(
+        &mut self,
+        checkpoint_data: &interface::CheckpointData,
+    ) -> anyhow::Result<()> {
+        let checkpoint_data = checkpoint_data.full();
         let checkpoint_seq = *checkpoint_data.checkpoint_summary.sequence_number();
         info!(checkpoint_seq, "Objects received by indexing processor");
         // Index checkpoint data
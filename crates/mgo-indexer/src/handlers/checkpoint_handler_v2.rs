@@ -12,7 +12,8 @@ use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, Mutex};
 use mgo_rest_api::CheckpointData;
 use mgo_rest_api::CheckpointTransaction;
-use mgo_types::base_types::ObjectRef;
+use mgo_types::base_types::{MgoAddress, ObjectRef};
+use mgo_types::coin::{Coin, CoinMetadata, TreasuryCap};
 use mgo_types::dynamic_field::DynamicFieldInfo;
 use mgo_types::dynamic_field::DynamicFieldName;
 use mgo_types::dynamic_field::DynamicFieldType;
@@ -20,6 +21,7 @@ use mgo_types::messages_checkpoint::{CertifiedCheckpointSummary, CheckpointConte
 use mgo_types::object::Object;
 
 use tokio::sync::watch;
+use tokio::task::JoinHandle;
 
 use std::collections::hash_map::Entry;
 use std::collections::HashSet;
@@ -28,7 +30,7 @@ use mgo_types::base_types::SequenceNumber;
 use mgo_types::effects::{TransactionEffects, TransactionEffectsAPI};
 use mgo_types::event::SystemEpochInfoEvent;
 use mgo_types::object::Owner;
-use mgo_types::transaction::TransactionDataAPI;
+use mgo_types::transaction::{TransactionData, TransactionDataAPI};
 use tap::tap::TapFallible;
 use tracing::{error, info, warn};
 
@@ -37,7 +39,9 @@ use mgo_types::mgo_system_state::mgo_system_state_summary::MgoSystemStateSummary
 use mgo_types::mgo_system_state::{get_mgo_system_state, MgoSystemStateTrait};
 
 use crate::errors::IndexerError;
-use crate::framework::interface::Handler;
+use crate::event_denylist::{EventDenylist, SharedEventDenylist};
+use crate::event_object_refs::ObjectRefEventTypes;
+use crate::framework::interface::{self, Handler};
 use crate::metrics::IndexerMetrics;
 
 use crate::store::module_resolver_v2::InterimModuleResolver;
@@ -46,7 +50,8 @@ use crate::types_v2::IndexedEpochInfo;
 use crate::types_v2::{
     IndexedCheckpoint, IndexedEvent, IndexedTransaction, IndexerResult, TransactionKind, TxIndex,
 };
-use crate::types_v2::{IndexedDeletedObject, IndexedObject, IndexedPackage};
+use crate::types_v2::{IndexedCoinHolderChange, IndexedCoinSupplyChange, IndexedCoinType};
+use crate::types_v2::{IndexedDeletedObject, IndexedObject, IndexedObjectChange, IndexedPackage};
 use crate::IndexerConfig;
 
 use super::tx_processor::EpochEndIndexingObjectStore;
@@ -57,14 +62,18 @@ use super::TransactionObjectChangesToCommit;
 
 const CHECKPOINT_QUEUE_SIZE: usize = 1000;
 
+/// A checkpoint handler together with the task committing the checkpoints it indexes, which
+/// returns once the handler is dropped and everything it indexed is committed.
 pub async fn new_handlers<S>(
     state: S,
     metrics: IndexerMetrics,
     config: &IndexerConfig,
-) -> Result<CheckpointHandler<S>, IndexerError>
+    event_denylist: SharedEventDenylist,
+) -> Result<(CheckpointHandler<S>, JoinHandle<()>), IndexerError>
     where
         S: IndexerStoreV2 + Clone + Sync + Send + 'static,
 {
+    let object_ref_event_types = ObjectRefEventTypes::parse(&config.object_ref_event_types)?;
     let checkpoint_queue_size = std::env::var("CHECKPOINT_QUEUE_SIZE")
         .unwrap_or(CHECKPOINT_QUEUE_SIZE.to_string())
         .parse::<usize>()
@@ -82,7 +91,7 @@ pub async fn new_handlers<S>(
     let metrics_clone = metrics.clone();
     let config_clone = config.clone();
     let (tx, rx) = watch::channel(None);
-    spawn_monitored_task!(start_tx_checkpoint_commit_task(
+    let committer = spawn_monitored_task!(start_tx_checkpoint_commit_task(
         state_clone,
         metrics_clone,
         config_clone,
@@ -95,9 +104,11 @@ pub async fn new_handlers<S>(
         metrics,
         indexed_checkpoint_sender,
         package_cache: IndexingPackageCache::start(rx),
+        event_denylist,
+        object_ref_event_types: Arc::new(object_ref_event_types),
     };
 
-    Ok(checkpoint_handler)
+    Ok((checkpoint_handler, committer))
 }
 
 pub struct CheckpointHandler<S> {
@@ -107,6 +118,8 @@ pub struct CheckpointHandler<S> {
     // Map from checkpoint sequence number and its starting transaction sequence number
     // This thing is small enough to be kept in memory
     package_cache: Arc<Mutex<IndexingPackageCache>>,
+    event_denylist: SharedEventDenylist,
+    object_ref_event_types: Arc<ObjectRefEventTypes>,
 }
 
 #[async_trait]
@@ -117,21 +130,16 @@ impl<S> Handler for CheckpointHandler<S>
     fn name(&self) -> &str {
         "checkpoint-handler"
     }
-    async fn process_checkpoints(&mut self, checkpoints: &[CheckpointData]) -> anyhow::Result<()> {
+    async fn process_checkpoints(
+        &mut self,
+        checkpoints: &[interface::CheckpointData],
+    ) -> anyhow::Result<()> {
         if checkpoints.is_empty() {
             return Ok(());
         }
         // Safe to unwrap, checked emptiness above
-        let first_checkpoint_seq = checkpoints
-            .first()
-            .unwrap()
-            .checkpoint_summary
-            .sequence_number();
-        let last_checkpoint_seq = checkpoints
-            .last()
-            .unwrap()
-            .checkpoint_summary
-            .sequence_number();
+        let first_checkpoint_seq = checkpoints.first().unwrap().sequence_number();
+        let last_checkpoint_seq = checkpoints.last().unwrap().sequence_number();
         info!(
             first = first_checkpoint_seq,
             last = last_checkpoint_seq,
@@ -157,9 +165,11 @@ impl<S> Handler for CheckpointHandler<S>
         let mut tasks = vec![];
         let state_clone = Arc::new(self.state.clone());
         let metrics_clone = Arc::new(self.metrics.clone());
+        // The same denylist applies to the whole batch, even if it is reloaded meanwhile.
+        let event_denylist = self.event_denylist.load();
         for checkpoint in checkpoints {
             let packages = packages_per_checkpoint
-                .remove(checkpoint.checkpoint_summary.sequence_number())
+                .remove(&checkpoint.sequence_number())
                 .unwrap_or_default();
             tasks.push(tokio::task::spawn(Self::index_one_checkpoint(
                 state_clone.clone(),
@@ -167,6 +177,8 @@ impl<S> Handler for CheckpointHandler<S>
                 metrics_clone.clone(),
                 packages,
                 module_resolver.clone(),
+                event_denylist.clone(),
+                self.object_ref_event_types.clone(),
             )));
         }
         let checkpoint_data_to_commit = futures::future::join_all(tasks)
@@ -295,11 +307,14 @@ impl<S> CheckpointHandler<S>
 
     async fn index_one_checkpoint(
         state: Arc<S>,
-        data: CheckpointData,
+        checkpoint: interface::CheckpointData,
         metrics: Arc<IndexerMetrics>,
         packages: Vec<IndexedPackage>,
         module_resolver: Arc<impl GetModule>,
+        event_denylist: Arc<EventDenylist>,
+        object_ref_event_types: Arc<ObjectRefEventTypes>,
     ) -> Result<CheckpointDataToCommit, IndexerError> {
+        let data = checkpoint.full().clone();
         let checkpoint_seq = data.checkpoint_summary.sequence_number;
         info!(checkpoint_seq, "Indexing checkpoint data blob");
 
@@ -311,6 +326,8 @@ impl<S> CheckpointHandler<S>
             Self::index_objects(data.clone(), &metrics, &module_resolver);
         let object_history_changes: TransactionObjectChangesToCommit =
             Self::index_objects_history(data.clone(), &module_resolver);
+        let (coin_types, coin_holder_changes) = Self::index_coin_types(&data);
+        let coin_supply_changes = Self::index_coin_supply_changes(&data);
 
         let (checkpoint, db_transactions, db_events, db_indices, db_displays) = {
             let CheckpointData {
@@ -324,6 +341,9 @@ impl<S> CheckpointHandler<S>
                 &checkpoint_summary,
                 &checkpoint_contents,
                 &metrics,
+                &event_denylist,
+                &object_ref_event_types,
+                module_resolver.as_ref(),
             )
                 .await?;
 
@@ -350,6 +370,9 @@ impl<S> CheckpointHandler<S>
             object_changes,
             object_history_changes,
             packages,
+            coin_types,
+            coin_holder_changes,
+            coin_supply_changes,
             epoch,
         })
     }
@@ -359,6 +382,9 @@ impl<S> CheckpointHandler<S>
         checkpoint_summary: &CertifiedCheckpointSummary,
         checkpoint_contents: &CheckpointContents,
         metrics: &IndexerMetrics,
+        event_denylist: &EventDenylist,
+        object_ref_event_types: &ObjectRefEventTypes,
+        module_resolver: &impl GetModule,
     ) -> IndexerResult<(
         Vec<IndexedTransaction>,
         Vec<IndexedEvent>,
@@ -413,16 +439,35 @@ impl<S> CheckpointHandler<S>
                 TransactionKind::ProgrammableTransaction
             };
 
-            db_events.extend(events.iter().enumerate().map(|(idx, event)| {
-                IndexedEvent::from_event(
-                    tx_sequence_number,
-                    idx as u64,
-                    *checkpoint_seq,
-                    tx_digest,
-                    event,
-                    checkpoint_summary.timestamp_ms,
-                )
-            }));
+            // Denied events keep their index in the transaction, so stored event IDs match
+            // the fullnode's.
+            db_events.extend(
+                events
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, event)| event_denylist.allows(event, metrics))
+                    .map(|(idx, event)| {
+                        let mut indexed_event = IndexedEvent::from_event(
+                            tx_sequence_number,
+                            idx as u64,
+                            *checkpoint_seq,
+                            tx_digest,
+                            event,
+                            checkpoint_summary.timestamp_ms,
+                        );
+                        indexed_event.object_refs = object_ref_event_types
+                            .object_refs(event, module_resolver)
+                            .unwrap_or_else(|e| {
+                                warn!(
+                                    ?tx_digest,
+                                    event_type = %event.type_,
+                                    "Failed to index objects referenced by event: {e}"
+                                );
+                                vec![]
+                            });
+                        indexed_event
+                    }),
+            );
 
             db_displays.extend(
                 events
@@ -431,6 +476,15 @@ impl<S> CheckpointHandler<S>
                     .map(|display| (display.object_type.clone(), display)),
             );
 
+            // Checkpoint data carries the prior state of every object the transaction changed,
+            // so children it only read are not included. Object-owned inputs can only have been
+            // loaded at runtime, as transactions cannot take them as inputs directly.
+            let loaded_child_objects = input_objects
+                .iter()
+                .filter(|o| matches!(o.owner, Owner::ObjectOwner(_)))
+                .map(|o| (o.id(), o.version()))
+                .collect::<Vec<_>>();
+
             let objects = input_objects
                 .iter()
                 .chain(output_objects.iter())
@@ -440,6 +494,10 @@ impl<S> CheckpointHandler<S>
                 TxChangesProcessor::new(&objects, metrics.clone())
                     .get_changes(tx, &fx, &tx_digest)
                     .await?;
+            let created_objects = object_changes
+                .iter()
+                .filter_map(IndexedObjectChange::created_summary)
+                .collect();
 
             let db_txn = IndexedTransaction {
                 tx_sequence_number,
@@ -457,24 +515,16 @@ impl<S> CheckpointHandler<S>
                 } else {
                     0
                 },
+                created_objects,
             };
 
             db_transactions.push(db_txn);
 
             // Input Objects
-            let input_objects = tx
-                .input_objects()
-                .expect("committed txns have been validated")
-                .into_iter()
-                .map(|obj_kind| obj_kind.object_id())
-                .collect::<Vec<_>>();
+            let input_objects = input_object_ids(tx);
 
             // Changed Objects
-            let changed_objects = fx
-                .all_changed_objects()
-                .into_iter()
-                .map(|(object_ref, _owner, _write_kind)| object_ref.0)
-                .collect::<Vec<_>>();
+            let changed_objects = changed_object_ids(&fx);
 
             // Payers
             let payers = vec![tx.gas_owner()];
@@ -510,6 +560,8 @@ impl<S> CheckpointHandler<S>
                 payers,
                 recipients,
                 move_calls,
+                loaded_child_objects,
+                signatures: sender_signed_data.data().tx_signatures().to_vec(),
             });
         }
         Ok((db_transactions, db_events, db_indices, db_displays))
@@ -593,6 +645,133 @@ impl<S> CheckpointHandler<S>
         }
     }
 
+    /// Coin types seen in the checkpoint, through one of their coins, treasury caps or metadata,
+    /// and for every address and coin type, the net change in the number of coins with a
+    /// positive balance the address owns, if any.
+    fn index_coin_types(
+        data: &CheckpointData,
+    ) -> (Vec<IndexedCoinType>, Vec<IndexedCoinHolderChange>) {
+        let checkpoint_seq = data.checkpoint_summary.sequence_number;
+        // State of every object the checkpoint changed, before its first transaction and after
+        // its last one, `None` while the object does not exist.
+        let mut initial_states: HashMap<ObjectID, Option<&Object>> = HashMap::new();
+        let mut final_states: HashMap<ObjectID, Option<&Object>> = HashMap::new();
+        for tx in &data.transactions {
+            for object in &tx.input_objects {
+                initial_states.entry(object.id()).or_insert(Some(object));
+                final_states.insert(object.id(), None);
+            }
+            for object in &tx.output_objects {
+                initial_states.entry(object.id()).or_insert(None);
+                final_states.insert(object.id(), Some(object));
+            }
+        }
+
+        let mut coin_types: BTreeMap<String, Option<ObjectID>> = BTreeMap::new();
+        for object in final_states.values().flatten() {
+            let Some(struct_tag) = object.struct_tag() else {
+                continue;
+            };
+            let is_metadata = CoinMetadata::is_coin_metadata(&struct_tag);
+            if !(Coin::is_coin(&struct_tag)
+                || is_metadata
+                || TreasuryCap::is_treasury_type(&struct_tag))
+            {
+                continue;
+            }
+            let Some(coin_type) = struct_tag.type_params.first() else {
+                continue;
+            };
+            let metadata_object_id = coin_types
+                .entry(coin_type.to_canonical_string(/* with_prefix */ true))
+                .or_default();
+            if is_metadata {
+                *metadata_object_id = Some(object.id());
+            }
+        }
+
+        let mut coin_count_deltas: BTreeMap<(MgoAddress, String), i64> = BTreeMap::new();
+        for (object_id, initial_state) in &initial_states {
+            for (state, delta) in [(*initial_state, -1), (final_states[object_id], 1)] {
+                if let Some(holding) = state.and_then(held_coin) {
+                    *coin_count_deltas.entry(holding).or_default() += delta;
+                }
+            }
+        }
+
+        let coin_types = coin_types
+            .into_iter()
+            .map(|(coin_type, metadata_object_id)| IndexedCoinType {
+                coin_type,
+                checkpoint_sequence_number: checkpoint_seq,
+                metadata_object_id,
+            })
+            .collect();
+        let coin_holder_changes = coin_count_deltas
+            .into_iter()
+            .filter(|(_, delta)| *delta != 0)
+            .map(|((owner, coin_type), delta)| IndexedCoinHolderChange {
+                owner,
+                coin_type,
+                checkpoint_sequence_number: checkpoint_seq,
+                coin_count_delta: delta,
+            })
+            .collect();
+        (coin_types, coin_holder_changes)
+    }
+
+    /// Amounts of every coin type minted and burned in the checkpoint, from the changes of
+    /// the total supply its treasury cap holds across each transaction.
+    fn index_coin_supply_changes(data: &CheckpointData) -> Vec<IndexedCoinSupplyChange> {
+        let summary = &data.checkpoint_summary;
+        let mut changes: BTreeMap<String, IndexedCoinSupplyChange> = BTreeMap::new();
+        for tx in &data.transactions {
+            let created: HashSet<ObjectID> =
+                tx.effects.created().iter().map(|(oref, _)| oref.0).collect();
+            for object in &tx.output_objects {
+                let Some((coin_type, after)) = treasury_supply(object) else {
+                    continue;
+                };
+                let cap_created = created.contains(&object.id());
+                let before = if cap_created {
+                    0
+                } else {
+                    // Caps unwrapped by the transaction have no known supply before it.
+                    let Some((_, before)) = tx
+                        .input_objects
+                        .iter()
+                        .find(|input| input.id() == object.id())
+                        .and_then(treasury_supply)
+                    else {
+                        continue;
+                    };
+                    before
+                };
+                if before == after {
+                    continue;
+                }
+                let change = changes
+                    .entry(coin_type.clone())
+                    .or_insert(IndexedCoinSupplyChange {
+                        coin_type,
+                        checkpoint_sequence_number: summary.sequence_number,
+                        epoch: summary.epoch,
+                        timestamp_ms: summary.timestamp_ms,
+                        minted: 0,
+                        burned: 0,
+                        supply_before: before,
+                        cap_created,
+                    });
+                if after > before {
+                    change.minted += after - before;
+                } else {
+                    change.burned += before - after;
+                }
+            }
+        }
+        changes.into_values().collect()
+    }
+
     // similar to index_objects, but objects_history keeps all versions of objects
     fn index_objects_history(
         data: CheckpointData,
@@ -664,12 +843,13 @@ impl<S> CheckpointHandler<S>
     }
 
     fn index_packages(
-        checkpoint_data: &[CheckpointData],
+        checkpoint_data: &[interface::CheckpointData],
         metrics: &IndexerMetrics,
     ) -> Vec<IndexedPackage> {
         let _timer = metrics.indexing_packages_latency.start_timer();
         checkpoint_data
             .iter()
+            .map(interface::CheckpointData::full)
             .flat_map(|data| {
                 let checkpoint_sequence_number = data.checkpoint_summary.sequence_number;
                 data.output_objects()
@@ -725,6 +905,48 @@ pub fn get_latest_objects(
     (latest_objects, discarded_versions)
 }
 
+/// Address owning `object` and its coin type, if it is a coin with a positive balance.
+/// Objects `tx` takes as input, indexed in `tx_input_objects`.
+pub(crate) fn input_object_ids(tx: &TransactionData) -> Vec<ObjectID> {
+    tx.input_objects()
+        .expect("committed txns have been validated")
+        .into_iter()
+        .map(|obj_kind| obj_kind.object_id())
+        .collect()
+}
+
+/// Objects created, mutated, unwrapped, wrapped or deleted by a transaction, indexed in
+/// `tx_changed_objects`.
+pub(crate) fn changed_object_ids(fx: &TransactionEffects) -> Vec<ObjectID> {
+    fx.all_changed_objects()
+        .into_iter()
+        .map(|(object_ref, _owner, _write_kind)| object_ref.0)
+        .collect()
+}
+
+fn held_coin(object: &Object) -> Option<(MgoAddress, String)> {
+    let Owner::AddressOwner(owner) = object.owner else {
+        return None;
+    };
+    let coin_type = object.coin_type_maybe()?;
+    (object.get_coin_value_unsafe() > 0)
+        .then(|| (owner, coin_type.to_canonical_string(/* with_prefix */ true)))
+}
+
+/// The coin type of `object` and the total supply it holds, if it is a treasury cap.
+fn treasury_supply(object: &Object) -> Option<(String, u64)> {
+    let struct_tag = object.struct_tag()?;
+    if !TreasuryCap::is_treasury_type(&struct_tag) {
+        return None;
+    }
+    let coin_type = struct_tag.type_params.first()?;
+    let cap = TreasuryCap::from_bcs_bytes(object.data.try_as_move()?.contents()).ok()?;
+    Some((
+        coin_type.to_canonical_string(/* with_prefix */ true),
+        cap.total_supply.value,
+    ))
+}
+
 fn try_create_dynamic_field_info(
     o: &Object,
     written: &HashMap<ObjectID, Object>,
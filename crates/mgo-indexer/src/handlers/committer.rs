@@ -7,17 +7,20 @@ use tokio::sync::watch;
 use tracing::instrument;
 
 use tap::tap::TapFallible;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
 
 use crate::metrics::IndexerMetrics;
+use crate::notifications::{publish_in_process, CommitNotice, CommittedTable};
 
 use crate::store::IndexerStoreV2;
 use crate::types_v2::IndexerResult;
 use crate::IndexerConfig;
 
-use super::{CheckpointDataToCommit, EpochToCommit};
+use super::{CheckpointDataToCommit, EpochToCommit, TransactionObjectChangesToCommit};
+use crate::models_v2::display::StoredDisplay;
+use crate::types_v2::{IndexedEvent, IndexedPackage, IndexedTransaction, TxIndex};
 
 pub async fn start_tx_checkpoint_commit_task<S>(
     state: S,
@@ -105,6 +108,9 @@ async fn commit_checkpoints<S>(
     let mut object_changes_batch = vec![];
     let mut object_history_changes_batch = vec![];
     let mut packages_batch = vec![];
+    let mut coin_types_batch = vec![];
+    let mut coin_holder_changes_batch = vec![];
+    let mut coin_supply_changes_batch = vec![];
 
     for indexed_checkpoint in indexed_checkpoint_batch {
         let CheckpointDataToCommit {
@@ -116,6 +122,9 @@ async fn commit_checkpoints<S>(
             object_changes,
             object_history_changes,
             packages,
+            coin_types,
+            coin_holder_changes,
+            coin_supply_changes,
             epoch: _,
         } = indexed_checkpoint;
         checkpoint_batch.push(checkpoint);
@@ -126,6 +135,9 @@ async fn commit_checkpoints<S>(
         object_changes_batch.push(object_changes);
         object_history_changes_batch.push(object_history_changes);
         packages_batch.push(packages);
+        coin_types_batch.extend(coin_types);
+        coin_holder_changes_batch.extend(coin_holder_changes);
+        coin_supply_changes_batch.extend(coin_supply_changes);
     }
 
     let first_checkpoint_seq = checkpoint_batch.first().as_ref().unwrap().sequence_number;
@@ -138,6 +150,15 @@ async fn commit_checkpoints<S>(
     let packages_batch = packages_batch.into_iter().flatten().collect::<Vec<_>>();
     let checkpoint_num = checkpoint_batch.len();
     let tx_count = tx_batch.len();
+    let committed_tables = committed_tables(
+        &tx_batch,
+        &tx_indices_batch,
+        &events_batch,
+        &display_updates_batch,
+        &packages_batch,
+        &object_changes_batch,
+        epoch.is_some(),
+    );
 
     {
         let _step_1_guard = metrics.checkpoint_db_commit_latency_step_1.start_timer();
@@ -149,6 +170,7 @@ async fn commit_checkpoints<S>(
             state.persist_packages(packages_batch),
             state.persist_objects(object_changes_batch.clone()),
             state.persist_object_history(object_history_changes_batch.clone()),
+            state.persist_coin_supply_changes(coin_supply_changes_batch),
         ];
         if let Some(epoch_data) = epoch.clone() {
             persist_tasks.push(state.persist_epoch(epoch_data));
@@ -166,6 +188,15 @@ async fn commit_checkpoints<S>(
             .expect("Persisting data into DB should not fail.");
     }
 
+    // Holders are counted from the coins persisted above.
+    state
+        .persist_coin_types(coin_types_batch, coin_holder_changes_batch)
+        .await
+        .tap_err(|e| {
+            error!("Failed to persist coin types with error: {}", e.to_string());
+        })
+        .expect("Persisting data into DB should not fail.");
+
     // handle partitioning on epoch boundary
     if let Some(epoch_data) = epoch {
         state
@@ -194,6 +225,14 @@ async fn commit_checkpoints<S>(
         .send(Some(last_checkpoint_seq))
         .expect("Commit watcher should not be closed");
 
+    // Wake up subscription workers. The in-process notice always goes out so that
+    // single-binary deployments work even when the DB cannot deliver notifications.
+    let notice = CommitNotice::new(first_checkpoint_seq, last_checkpoint_seq, committed_tables);
+    publish_in_process(notice.clone());
+    if let Err(e) = state.notify_commit(notice).await {
+        warn!("Failed to publish commit notice through the DB: {}", e);
+    }
+
     metrics
         .latest_tx_checkpoint_sequence_number
         .set(last_checkpoint_seq as i64);
@@ -218,3 +257,31 @@ async fn commit_checkpoints<S>(
         .thousand_transaction_avg_db_commit_latency
         .observe(elapsed * 1000.0 / tx_count as f64);
 }
+
+fn committed_tables(
+    tx_batch: &[IndexedTransaction],
+    tx_indices_batch: &[TxIndex],
+    events_batch: &[IndexedEvent],
+    display_updates_batch: &BTreeMap<String, StoredDisplay>,
+    packages_batch: &[IndexedPackage],
+    object_changes_batch: &[TransactionObjectChangesToCommit],
+    has_epoch: bool,
+) -> Vec<CommittedTable> {
+    let has_object_changes = object_changes_batch
+        .iter()
+        .any(|c| !c.changed_objects.is_empty() || !c.deleted_objects.is_empty());
+    [
+        (CommittedTable::Checkpoints, true),
+        (CommittedTable::Transactions, !tx_batch.is_empty()),
+        (CommittedTable::TxIndices, !tx_indices_batch.is_empty()),
+        (CommittedTable::Events, !events_batch.is_empty()),
+        (CommittedTable::Display, !display_updates_batch.is_empty()),
+        (CommittedTable::Packages, !packages_batch.is_empty()),
+        (CommittedTable::Objects, has_object_changes),
+        (CommittedTable::ObjectsHistory, has_object_changes),
+        (CommittedTable::Epochs, has_epoch),
+    ]
+    .into_iter()
+    .filter_map(|(table, touched)| touched.then_some(table))
+    .collect()
+}
@@ -0,0 +1,76 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-epoch metrics for dashboards, rolled up as checkpoints are indexed rather than computed
+//! from the transactions tables on every read. Checkpoints already rolled up are skipped by the
+//! store, so that re-ingesting them after a restart does not count them twice.
+
+use std::collections::BTreeSet;
+
+use async_trait::async_trait;
+use mgo_types::effects::TransactionEffectsAPI;
+use mgo_types::object::Owner;
+use mgo_types::transaction::TransactionDataAPI;
+
+use crate::framework::interface::{CheckpointData, Handler};
+use crate::store::IndexerStoreV2;
+use crate::types_v2::IndexedEpochMetrics;
+
+pub const EPOCH_METRICS_HANDLER_NAME: &str = "epoch_metrics";
+
+pub struct EpochMetricsHandler<S> {
+    state: S,
+}
+
+impl<S> EpochMetricsHandler<S> {
+    pub fn new(state: S) -> Self {
+        Self { state }
+    }
+}
+
+/// What `checkpoint` adds to the metrics of its epoch.
+pub fn epoch_metrics(checkpoint: &CheckpointData) -> IndexedEpochMetrics {
+    let mut metrics = IndexedEpochMetrics {
+        epoch: checkpoint.epoch(),
+        checkpoint_sequence_number: checkpoint.sequence_number(),
+        end_of_epoch: checkpoint.summary().end_of_epoch_data.is_some(),
+        transactions: checkpoint.transactions().len() as u64,
+        gas_used: 0,
+        storage_rebate: 0,
+        senders: BTreeSet::new(),
+        addresses: BTreeSet::new(),
+    };
+    for tx in checkpoint.transactions() {
+        let gas_cost_summary = tx.effects().gas_cost_summary();
+        metrics.gas_used += gas_cost_summary.gas_used();
+        metrics.storage_rebate += gas_cost_summary.storage_rebate;
+
+        let data = tx.transaction().transaction_data();
+        if !data.is_system_tx() {
+            metrics.senders.insert(data.sender());
+            metrics.addresses.insert(data.sender());
+        }
+        for (_, owner, _) in tx.effects().all_changed_objects() {
+            if let Owner::AddressOwner(address) = owner {
+                metrics.addresses.insert(address);
+            }
+        }
+    }
+    metrics
+}
+
+#[async_trait]
+impl<S> Handler for EpochMetricsHandler<S>
+where
+    S: IndexerStoreV2 + Clone + Sync + Send + 'static,
+{
+    fn name(&self) -> &str {
+        EPOCH_METRICS_HANDLER_NAME
+    }
+
+    async fn process_checkpoints(&mut self, checkpoints: &[CheckpointData]) -> anyhow::Result<()> {
+        let metrics = checkpoints.iter().map(epoch_metrics).collect();
+        self.state.persist_epoch_metrics(metrics).await?;
+        Ok(())
+    }
+}
@@ -1,18 +1,23 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(feature = "legacy-handlers")]
 pub mod checkpoint_handler;
 pub mod checkpoint_handler_v2;
 pub mod committer;
+pub mod epoch_metrics_handler;
+pub mod tx_object_indices_backfill;
 pub mod tx_processor;
+pub mod validator_metrics_handler;
 
 use std::collections::BTreeMap;
 
 use crate::{
     models_v2::display::StoredDisplay,
     types_v2::{
-        IndexedCheckpoint, IndexedDeletedObject, IndexedEpochInfo, IndexedEvent, IndexedObject,
-        IndexedPackage, IndexedTransaction, TxIndex,
+        IndexedCheckpoint, IndexedCoinHolderChange, IndexedCoinSupplyChange, IndexedCoinType,
+        IndexedDeletedObject, IndexedEpochInfo, IndexedEvent, IndexedObject, IndexedPackage,
+        IndexedTransaction, TxIndex,
     },
 };
 
@@ -26,6 +31,9 @@ pub struct CheckpointDataToCommit {
     pub object_changes: TransactionObjectChangesToCommit,
     pub object_history_changes: TransactionObjectChangesToCommit,
     pub packages: Vec<IndexedPackage>,
+    pub coin_types: Vec<IndexedCoinType>,
+    pub coin_holder_changes: Vec<IndexedCoinHolderChange>,
+    pub coin_supply_changes: Vec<IndexedCoinSupplyChange>,
     pub epoch: Option<EpochToCommit>,
 }
 
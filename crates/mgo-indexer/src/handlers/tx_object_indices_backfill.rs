@@ -0,0 +1,85 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! One-off backfill of `tx_input_objects` and `tx_changed_objects` for checkpoints indexed
+//! before the writer populated them, run next to regular ingestion in its own pipeline.
+
+use async_trait::async_trait;
+use tracing::info;
+
+use crate::errors::IndexerError;
+use crate::framework::interface::{CheckpointData, Handler};
+use crate::store::IndexerStoreV2;
+use crate::types_v2::TxIndex;
+
+use super::checkpoint_handler_v2::{changed_object_ids, input_object_ids};
+
+pub const TX_OBJECT_INDICES_BACKFILL_NAME: &str = "tx_object_indices_backfill";
+
+pub struct TxObjectIndicesBackfill<S> {
+    state: S,
+}
+
+impl<S> TxObjectIndicesBackfill<S> {
+    pub fn new(state: S) -> Self {
+        Self { state }
+    }
+
+    /// Indices holding only the input and changed objects of the transactions of `checkpoint`.
+    fn index_checkpoint(checkpoint: &CheckpointData) -> Result<Vec<TxIndex>, IndexerError> {
+        let checkpoint_seq = checkpoint.sequence_number();
+        let tx_seq_nums = checkpoint
+            .contents()
+            .enumerate_transactions(checkpoint.summary())
+            .map(|(seq, execution_digest)| (execution_digest.transaction, seq));
+        if checkpoint.contents().size() != checkpoint.transactions().len() {
+            return Err(IndexerError::FullNodeReadingError(format!(
+                "CheckpointContents has different size {} compared to Transactions {} for checkpoint {}",
+                checkpoint.contents().size(),
+                checkpoint.transactions().len(),
+                checkpoint_seq
+            )));
+        }
+        Ok(tx_seq_nums
+            .zip(checkpoint.transactions())
+            .map(|((transaction_digest, tx_sequence_number), tx)| TxIndex {
+                tx_sequence_number,
+                transaction_digest,
+                checkpoint_sequence_number: checkpoint_seq,
+                input_objects: input_object_ids(tx.transaction().transaction_data()),
+                changed_objects: changed_object_ids(tx.effects()),
+                payers: vec![],
+                senders: vec![],
+                recipients: vec![],
+                move_calls: vec![],
+                loaded_child_objects: vec![],
+                signatures: vec![],
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl<S> Handler for TxObjectIndicesBackfill<S>
+where
+    S: IndexerStoreV2 + Clone + Sync + Send + 'static,
+{
+    fn name(&self) -> &str {
+        TX_OBJECT_INDICES_BACKFILL_NAME
+    }
+
+    async fn process_checkpoints(&mut self, checkpoints: &[CheckpointData]) -> anyhow::Result<()> {
+        let mut indices = vec![];
+        for checkpoint in checkpoints {
+            indices.extend(Self::index_checkpoint(checkpoint)?);
+        }
+        self.state.persist_tx_object_indices(indices).await?;
+        if let Some(last) = checkpoints.last() {
+            info!(
+                "Backfilled tx object indices up to checkpoint {}",
+                last.sequence_number()
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-epoch performance of the validators, rolled up as checkpoints are indexed. Certificates
+//! name the validators that signed them by their index in the committee of the epoch, which is
+//! learned from the checkpoint that sets it, so the validators of an epoch whose committee was
+//! not indexed, e.g. because indexing started in the middle of the epoch, have no metrics.
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use mgo_types::event::ValidatorEpochInfoEventV2;
+use mgo_types::mgo_system_state::{get_mgo_system_state, MgoSystemStateTrait};
+
+use crate::errors::IndexerError;
+use crate::framework::interface::{CheckpointData, Handler};
+use crate::handlers::tx_processor::EpochEndIndexingObjectStore;
+use crate::store::IndexerStoreV2;
+use crate::types_v2::{IndexedCommittee, IndexedValidatorMetrics, IndexedValidatorReport};
+
+pub const VALIDATOR_METRICS_HANDLER_NAME: &str = "validator_metrics";
+
+pub struct ValidatorMetricsHandler<S> {
+    state: S,
+}
+
+impl<S> ValidatorMetricsHandler<S> {
+    pub fn new(state: S) -> Self {
+        Self { state }
+    }
+}
+
+/// What `checkpoint` adds to the metrics of the validators of its epoch.
+pub fn validator_metrics(
+    checkpoint: &CheckpointData,
+) -> Result<IndexedValidatorMetrics, IndexerError> {
+    let summary = checkpoint.summary();
+    let mut metrics = IndexedValidatorMetrics {
+        epoch: checkpoint.epoch(),
+        checkpoint_sequence_number: checkpoint.sequence_number(),
+        signers: summary.auth_sig().signers_map.iter().collect(),
+        committee: None,
+        reports: vec![],
+    };
+    if checkpoint.sequence_number() != 0 && summary.end_of_epoch_data.is_none() {
+        return Ok(metrics);
+    }
+
+    let object_store = EpochEndIndexingObjectStore::new(checkpoint.full());
+    let system_state = get_mgo_system_state(&object_store)?.into_mgo_system_state_summary();
+    // Committees order their validators by protocol key, as `Committee::new` does.
+    let validators: BTreeMap<_, _> = system_state
+        .active_validators
+        .into_iter()
+        .map(|v| (v.protocol_pubkey_bytes, (v.mgo_address, v.voting_power)))
+        .collect();
+    metrics.committee = Some(IndexedCommittee {
+        epoch: system_state.epoch,
+        validators: validators.into_values().collect(),
+    });
+
+    let events = checkpoint
+        .transactions()
+        .filter_map(|tx| tx.events())
+        .flat_map(|events| &events.data);
+    for event in events.filter(|event| event.is_validator_epoch_info_event()) {
+        let event: ValidatorEpochInfoEventV2 = bcs::from_bytes(&event.contents)?;
+        metrics.reports.push(IndexedValidatorReport {
+            validator_address: event.validator_address,
+            reporters: event.tallying_rule_reporters.len() as u64,
+            tallying_score: event.tallying_rule_global_score,
+        });
+    }
+    Ok(metrics)
+}
+
+#[async_trait]
+impl<S> Handler for ValidatorMetricsHandler<S>
+where
+    S: IndexerStoreV2 + Clone + Sync + Send + 'static,
+{
+    fn name(&self) -> &str {
+        VALIDATOR_METRICS_HANDLER_NAME
+    }
+
+    async fn process_checkpoints(&mut self, checkpoints: &[CheckpointData]) -> anyhow::Result<()> {
+        let metrics = checkpoints
+            .iter()
+            .map(validator_metrics)
+            .collect::<Result<_, _>>()?;
+        self.state.persist_validator_metrics(metrics).await?;
+        Ok(())
+    }
+}
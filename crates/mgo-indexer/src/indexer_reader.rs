@@ -2,75 +2,138 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    errors::IndexerError,
+    blocking_tasks::BlockingTaskLimiter,
+    errors::{Context, IndexerError},
+    event_denylist::SharedEventDenylist,
+    metrics::IndexerReaderMetrics,
     models_v2::{
+        active_address_sketches::{ActiveAddressSketch, StoredActiveAddressSketch},
         address_metrics::StoredAddressMetrics,
         checkpoints::StoredCheckpoint,
+        coin_supply::{CoinSupplyBucket, StoredCoinSupplyOffset},
+        coin_types::StoredCoinType,
         display::StoredDisplay,
         epoch::StoredEpochInfo,
+        epoch_metrics::StoredEpochMetrics,
         events::StoredEvent,
         move_call_metrics::QueriedMoveCallMetrics,
         network_metrics::StoredNetworkMetrics,
-        objects::{CoinBalance, ObjectRefColumn, StoredObject},
-        packages::StoredPackage,
+        objects::{
+            CoinBalance, ObjectRefColumn, StoredHistoryObject, StoredObject,
+            StoredOwnershipInterval,
+        },
+        orphaned_dynamic_fields::StoredOrphanedDynamicField,
+        packages::{StoredPackage, StoredPackageDependency},
+        pruner_watermark::StoredPrunerWatermark,
+        storage_stats::{StoredStorageStats, STORAGE_GROWTH_WINDOW_MS},
         transactions::StoredTransaction,
-        tx_indices::TxSequenceNumber,
+        tx_indices::{TxCheckpointPosition, TxSequenceNumber},
+        validator_metrics::StoredValidatorEpochMetrics,
     },
+    result_stream::{stream_in_batches, STREAM_BATCH_SIZE},
     schema_v2::{
-        address_metrics, checkpoints, display, epochs, events, move_call_metrics, objects,
-        objects_snapshot, packages, transactions,
+        active_address_sketches, address_cohorts, address_metrics, checkpoints,
+        coin_supply_offsets, coin_types, display, epoch_metrics, epochs, events,
+        move_call_metrics, objects, objects_history, objects_snapshot, orphaned_dynamic_fields,
+        package_deps, packages, pruner_watermark, storage_stats, transactions,
+        tx_loaded_child_objects, validator_epoch_metrics, writer_version,
     },
-    types_v2::{IndexerResult, OwnerType},
+    types_v2::{IndexerResult, ObjectStatus, OwnerType},
+    writer_version::WriterFeature,
     PgConnectionConfig, PgConnectionPoolConfig, PgPoolConnection,
 };
 use anyhow::{anyhow, Result};
+use async_trait::async_trait;
 use cached::proc_macro::cached;
 use cached::SizedCache;
 use diesel::{
-    dsl::sql, r2d2::ConnectionManager, sql_types::Bool, ExpressionMethods, OptionalExtension,
-    PgConnection, QueryDsl, RunQueryDsl, TextExpressionMethods,
+    dsl::{max, sql},
+    pg::Pg,
+    r2d2::ConnectionManager,
+    sql_types::{BigInt, Bool, Bytea, Integer, Text},
+    BoolExpressionMethods, ExpressionMethods, OptionalExtension, PgConnection, QueryDsl,
+    RunQueryDsl, TextExpressionMethods,
 };
 use fastcrypto::encoding::Encoding;
-use fastcrypto::encoding::Hex;
+use fastcrypto::encoding::{Base64, Hex};
 use itertools::{any, Itertools};
 use move_core_types::annotated_value::MoveStructLayout;
 use move_core_types::language_storage::StructTag;
+use prometheus::Registry;
 use std::{
-    collections::{BTreeMap, HashMap},
-    sync::{Arc, RwLock},
+    collections::{BTreeMap, HashMap, HashSet},
+    ops::ControlFlow,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
 };
-use mgo_json_rpc_types::DisplayFieldsResponse;
+use mgo_json_rpc::read_api::DisplayRenderLimits;
+use mgo_json_rpc::{get_balance_changes_from_effect, ObjectProvider};
+use mgo_json_rpc_api::{cap_page_limit, QUERY_MAX_OWNED_OBJECTS_ADDRESSES, QUERY_MAX_RESULT_LIMIT};
+use mgo_json_rpc_types::{CheckpointBcsV1, DisplayFieldsResponse, TransactionBlockBcsV1};
 use mgo_json_rpc_types::{
-    AddressMetrics, CheckpointId, EpochInfo, EventFilter, MoveCallMetrics, MoveFunctionName,
+    ActiveAddressStats, AddressMetrics, BalanceChange, CheckpointId, CoinTypeInfo, CoinTypeOrder,
+    CohortRetention, DailyActiveAddresses,
+    DailyRetention, EffectsWithInput, EpochInfo, EpochMetrics, EventFilter, MoveCallMetrics, MoveFunctionName,
     NetworkMetrics, MgoEvent, MgoObjectDataFilter, MgoTransactionBlockResponse, TransactionFilter,
 };
 use mgo_json_rpc_types::{
-    Balance, Coin as MgoCoin, MgoCoinMetadata, MgoTransactionBlockEffects,
-    MgoTransactionBlockEffectsAPI,
+    dynamic_fields_within_content_budget, DynamicFieldPage, DynamicFieldsOptions, EventPage,
+    ModifiedObjectCursor, ModifiedObjectsPage, MultiOwnerObjectCursor, MultiOwnerObjectsPage,
+    ObjectsPage, Page, MgoDynamicFieldInfo, MgoObjectData, MgoObjectDataOptions,
+    MgoObjectResponse, MgoObjectResponseQuery, MgoTransactionBlockResponseQuery,
+    TransactionBlocksPage,
+};
+use mgo_json_rpc_types::{
+    Balance, CheckpointTransactionCount, Coin as MgoCoin, MgoCoinMetadata, MgoMoveValue,
+    MgoTransactionBlockEffects, MgoTransactionBlockEffectsAPI, OrphanedDynamicField,
+    OwnershipInterval, PackageDependency, PackageVersion, SharedObjectStats, StorageStats,
+    SupplyBucket, SupplyGranularity, SupplyHistory, TableEntryValue, TableValue, ValidatorReport,
 };
 use mgo_types::{
-    balance::Supply, coin::TreasuryCap, dynamic_field::DynamicFieldName, object::MoveObject,
+    balance::Supply,
+    coin::TreasuryCap,
+    dynamic_field::{extract_field_from_move_struct, DynamicFieldName},
+    object::MoveObject,
 };
 use mgo_types::{
     base_types::{ObjectID, ObjectRef, SequenceNumber, MgoAddress, VersionNumber},
     committee::EpochId,
     digests::{ObjectDigest, TransactionDigest},
     dynamic_field::DynamicFieldInfo,
+    effects::{TransactionEffects, TransactionEffectsAPI},
     is_system_package,
+    messages_checkpoint::CheckpointSequenceNumber,
     move_package::MovePackage,
     object::{Object, ObjectRead},
     mgo_system_state::{mgo_system_state_summary::MgoSystemStateSummary, MgoSystemStateTrait},
 };
 use mgo_types::{coin::CoinMetadata, event::EventID};
+use mgo_types::{
+    error::{MgoObjectResponseError, UserInputError},
+    parse_mgo_type_tag,
+    transaction::{SenderSignedData, TransactionDataAPI},
+    TypeTag,
+};
 
 pub const TX_SEQUENCE_NUMBER_STR: &str = "tx_sequence_number";
 pub const TRANSACTION_DIGEST_STR: &str = "transaction_digest";
 pub const EVENT_SEQUENCE_NUMBER_STR: &str = "event_sequence_number";
+pub const CHECKPOINT_SEQUENCE_NUMBER_STR: &str = "checkpoint_sequence_number";
+/// Message of the error Postgres cancels a statement with once it runs past the timeout.
+const STATEMENT_TIMEOUT_ERROR: &str = "canceling statement due to statement timeout";
 
 #[derive(Clone)]
 pub struct IndexerReader {
     pool: crate::PgConnectionPool,
     package_cache: PackageCache,
+    event_denylist: SharedEventDenylist,
+    blocking_tasks: BlockingTaskLimiter,
+    metrics: IndexerReaderMetrics,
+    partition_pruning: bool,
+    /// Version of the writer as last read, shared by the clones of the reader.
+    writer_version: Arc<AtomicU64>,
 }
 
 // Impl for common initialization and utilities
@@ -101,10 +164,85 @@ impl IndexerReader {
         Ok(Self {
             pool,
             package_cache: Default::default(),
+            event_denylist: Default::default(),
+            blocking_tasks: BlockingTaskLimiter::new(
+                config.blocking_task_permits(),
+                config.blocking_task_wait_timeout,
+            ),
+            metrics: IndexerReaderMetrics::new(&Registry::default()),
+            partition_pruning: true,
+            writer_version: Default::default(),
         })
     }
 
+    /// Registers the metrics of the reader in `registry`.
+    pub fn with_metrics(mut self, registry: &Registry) -> Self {
+        self.metrics = IndexerReaderMetrics::new(registry);
+        self
+    }
+
+    /// Event types the writer does not store, which event queries refuse to filter on.
+    pub fn with_event_denylist(mut self, event_denylist: SharedEventDenylist) -> Self {
+        self.event_denylist = event_denylist;
+        self
+    }
+
+    /// Whether queries of the tables partitioned by checkpoint add conditions on the checkpoint
+    /// derived from their cursor and filters, so that Postgres only scans the partitions that can
+    /// hold results. The conditions never change the results, which is checked by comparing them
+    /// with those of a reader without them. On by default.
+    pub fn with_partition_pruning(mut self, partition_pruning: bool) -> Self {
+        self.partition_pruning = partition_pruning;
+        self
+    }
+
+    /// Version of the data written by the writer, as it last recorded it, or 0 if no writer
+    /// recorded one.
+    pub fn get_writer_version(&self) -> Result<u64, IndexerError> {
+        let version = self.run_query(|conn| {
+            // Writers that predate the version did not create its table.
+            let exists = diesel::select(sql::<Bool>("to_regclass('writer_version') IS NOT NULL"))
+                .get_result::<bool>(conn)?;
+            if !exists {
+                return Ok(None);
+            }
+            writer_version::table
+                .select(writer_version::version)
+                .first::<i64>(conn)
+                .optional()
+        })?;
+        let version = version.map_or(0, |version| version as u64);
+        self.writer_version.store(version, Ordering::Relaxed);
+        Ok(version)
+    }
+
+    /// Fails with [`IndexerError::FeatureNotAvailable`] unless the writer is recent enough to
+    /// fill the data of `feature`. The version of the writer is read again for as long as it is
+    /// too old, so that upgrading the writer makes the feature available without restarting the
+    /// reader.
+    pub fn require_writer_feature(&self, feature: WriterFeature) -> Result<(), IndexerError> {
+        let required = feature.min_writer_version();
+        if self.writer_version.load(Ordering::Relaxed) >= required {
+            return Ok(());
+        }
+        let found = self.get_writer_version()?;
+        if found < required {
+            return Err(IndexerError::FeatureNotAvailable {
+                feature: feature.to_string(),
+                required,
+                found,
+            });
+        }
+        Ok(())
+    }
+
+    /// Maximum number of connections of the reader.
+    pub fn pool_size(&self) -> u32 {
+        self.pool.max_size()
+    }
+
     fn get_connection(&self) -> Result<PgPoolConnection, IndexerError> {
+        let _timer = self.metrics.db_pool_wait_latency.start_timer();
         self.pool.get().map_err(|e| {
             IndexerError::PgPoolConnectionError(format!(
                 "Failed to get connection from PG connection pool with error: {:?}",
@@ -125,7 +263,7 @@ impl IndexerReader {
             .build_transaction()
             .read_only()
             .run(query)
-            .map_err(|e| IndexerError::PostgresReadError(e.to_string()))
+            .map_err(|e| self.read_error(e))
     }
 
     pub fn run_query_repeatable<T, E, F>(&self, query: F) -> Result<T, IndexerError>
@@ -141,18 +279,31 @@ impl IndexerReader {
             .read_only()
             .repeatable_read()
             .run(query)
-            .map_err(|e| IndexerError::PostgresReadError(e.to_string()))
+            .map_err(|e| self.read_error(e))
+    }
+
+    /// Counts queries cancelled by the statement timeout set on the connections of the pool.
+    fn read_error(&self, e: impl std::error::Error) -> IndexerError {
+        let message = e.to_string();
+        if message.contains(STATEMENT_TIMEOUT_ERROR) {
+            self.metrics.total_statement_timeouts.inc();
+        }
+        IndexerError::PostgresReadError(message)
     }
 
+    /// Runs `f` on the blocking pool once one of the blocking task permits of the reader is
+    /// free, failing with [`IndexerError::ServerOverloaded`] if none frees up in time.
     pub async fn spawn_blocking<F, R, E>(&self, f: F) -> Result<R, E>
     where
         F: FnOnce(Self) -> Result<R, E> + Send + 'static,
         R: Send + 'static,
-        E: Send + 'static,
+        E: From<IndexerError> + Send + 'static,
     {
+        let permit = self.blocking_tasks.acquire(&self.metrics).await?;
         let this = self.clone();
         let current_span = tracing::Span::current();
         tokio::task::spawn_blocking(move || {
+            let _permit = permit;
             CALLED_FROM_BLOCKING_POOL
                 .with(|in_blocking_pool| *in_blocking_pool.borrow_mut() = true);
             let _guard = current_span.enter();
@@ -413,6 +564,344 @@ impl IndexerReader {
             .map_err(Into::into)
     }
 
+    /// Metrics of `epoch`, or of all epochs, before `cursor`, latest first.
+    pub fn get_epoch_metrics(
+        &self,
+        epoch: Option<EpochId>,
+        cursor: Option<EpochId>,
+        limit: usize,
+    ) -> Result<Vec<EpochMetrics>, IndexerError> {
+        self.require_writer_feature(WriterFeature::EpochMetrics)?;
+        let stored: Vec<StoredEpochMetrics> = self.run_query(|conn| {
+            let mut boxed_query = epoch_metrics::table.into_boxed();
+            if let Some(epoch) = epoch {
+                boxed_query = boxed_query.filter(epoch_metrics::epoch.eq(epoch as i64));
+            }
+            if let Some(cursor) = cursor {
+                boxed_query = boxed_query.filter(epoch_metrics::epoch.lt(cursor as i64));
+            }
+            boxed_query
+                .order_by(epoch_metrics::epoch.desc())
+                .limit(limit as i64)
+                .load(conn)
+        })?;
+        Ok(stored.into_iter().map(EpochMetrics::from).collect())
+    }
+
+    /// Metrics of `validator` over the epochs from `from_epoch` to `to_epoch`, inclusive, in
+    /// order. Epochs the validator was not in the committee of are left out.
+    pub fn get_validator_report(
+        &self,
+        validator: MgoAddress,
+        from_epoch: EpochId,
+        to_epoch: EpochId,
+    ) -> Result<Vec<ValidatorReport>, IndexerError> {
+        self.require_writer_feature(WriterFeature::ValidatorMetrics)?;
+        let stored: Vec<StoredValidatorEpochMetrics> = self.run_query(|conn| {
+            validator_epoch_metrics::table
+                .filter(validator_epoch_metrics::validator_address.eq(validator.to_vec()))
+                .filter(validator_epoch_metrics::epoch.between(from_epoch as i64, to_epoch as i64))
+                .order_by(validator_epoch_metrics::epoch.asc())
+                .load(conn)
+        })?;
+        stored.into_iter().map(ValidatorReport::try_from).collect()
+    }
+
+    /// Metrics of the validators of `epoch`, or of the latest epoch with metrics, best first:
+    /// by the checkpoints they signed, then by the validators that reported them.
+    pub fn get_validator_ranking(
+        &self,
+        epoch: Option<EpochId>,
+    ) -> Result<Vec<ValidatorReport>, IndexerError> {
+        self.require_writer_feature(WriterFeature::ValidatorMetrics)?;
+        let stored: Vec<StoredValidatorEpochMetrics> = self.run_query(|conn| {
+            let epoch = match epoch {
+                Some(epoch) => epoch as i64,
+                None => {
+                    let latest = validator_epoch_metrics::table
+                        .select(max(validator_epoch_metrics::epoch))
+                        .first::<Option<i64>>(conn)?;
+                    let Some(latest) = latest else {
+                        return Ok(vec![]);
+                    };
+                    latest
+                }
+            };
+            validator_epoch_metrics::table
+                .filter(validator_epoch_metrics::epoch.eq(epoch))
+                .order_by((
+                    validator_epoch_metrics::checkpoints_signed.desc(),
+                    validator_epoch_metrics::reporters.asc(),
+                    validator_epoch_metrics::committee_index.asc(),
+                ))
+                .load(conn)
+        })?;
+        stored.into_iter().map(ValidatorReport::try_from).collect()
+    }
+
+    /// The latest sample of the storage stats of the tables, as persisted by the writer.
+    pub fn get_storage_stats(&self) -> Result<StorageStats, IndexerError> {
+        self.require_writer_feature(WriterFeature::StorageStats)?;
+        let sampled_at_ms = self.run_query(|conn| {
+            storage_stats::table
+                .select(max(storage_stats::sampled_at_ms))
+                .first::<Option<i64>>(conn)
+        })?;
+        let Some(sampled_at_ms) = sampled_at_ms else {
+            return Ok(StorageStats {
+                sampled_at_ms: None,
+                tables: vec![],
+            });
+        };
+        let (latest, baseline) = self.run_query(|conn| {
+            let latest = storage_stats::table
+                .filter(storage_stats::sampled_at_ms.eq(sampled_at_ms))
+                .order_by(storage_stats::table_name)
+                .load::<StoredStorageStats>(conn)?;
+            let baseline = StoredStorageStats::load_latest_at(
+                conn,
+                sampled_at_ms - STORAGE_GROWTH_WINDOW_MS,
+            )?;
+            Ok::<_, diesel::result::Error>((latest, baseline))
+        })?;
+        Ok(StorageStats {
+            sampled_at_ms: Some(sampled_at_ms as u64),
+            tables: StoredStorageStats::with_growth(latest, baseline),
+        })
+    }
+
+    /// The supply of `coin_type` at the end of every epoch or day from `from` to `to`, summed
+    /// from its supply changes onto the supply it had when it was first seen changing. Coin
+    /// types not seen changing yet have the supply their treasury cap holds.
+    pub fn get_supply_history(
+        &self,
+        coin_type: StructTag,
+        granularity: SupplyGranularity,
+        from: u64,
+        to: u64,
+    ) -> IndexerResult<SupplyHistory> {
+        self.require_writer_feature(WriterFeature::CoinSupplyChanges)?;
+        let coin_type_str = coin_type.to_canonical_string(/* with_prefix */ true);
+        let offset = self.run_query(|conn| {
+            coin_supply_offsets::table
+                .filter(coin_supply_offsets::coin_type.eq(&coin_type_str))
+                .first::<StoredCoinSupplyOffset>(conn)
+                .optional()
+        })?;
+        let (initial_supply, approximate) = match offset {
+            Some(offset) => (offset.initial_supply as u64, offset.approximate),
+            None => (self.get_total_supply(coin_type)?.value, true),
+        };
+
+        let bucket = match granularity {
+            SupplyGranularity::Epoch => "epoch",
+            SupplyGranularity::Day => "timestamp_ms / 86400000",
+        };
+        // Changes before the range are all summed into the bucket preceding it.
+        let query = format!(
+            "SELECT GREATEST({bucket}, $2) AS bucket, \
+                CAST(SUM(minted) AS BIGINT) AS minted, CAST(SUM(burned) AS BIGINT) AS burned \
+            FROM coin_supply_changes \
+            WHERE coin_type = $1 AND {bucket} <= $3 \
+            GROUP BY 1"
+        );
+        let stored_buckets = self.run_query(|conn| {
+            diesel::sql_query(query)
+                .bind::<Text, _>(coin_type_str.clone())
+                .bind::<BigInt, _>(from as i64 - 1)
+                .bind::<BigInt, _>(to as i64)
+                .load::<CoinSupplyBucket>(conn)
+        })?;
+        let stored_buckets: HashMap<i64, CoinSupplyBucket> = stored_buckets
+            .into_iter()
+            .map(|bucket| (bucket.bucket, bucket))
+            .collect();
+
+        let net = |bucket: &CoinSupplyBucket| bucket.minted as i128 - bucket.burned as i128;
+        let mut total_supply = initial_supply as i128
+            + stored_buckets.get(&(from as i64 - 1)).map_or(0, net);
+        let buckets = (from..=to)
+            .map(|bucket| {
+                let (minted, burned) = match stored_buckets.get(&(bucket as i64)) {
+                    Some(stored) => {
+                        total_supply += net(stored);
+                        (stored.minted as u64, stored.burned as u64)
+                    }
+                    None => (0, 0),
+                };
+                SupplyBucket {
+                    bucket,
+                    minted,
+                    burned,
+                    total_supply: total_supply.clamp(0, u64::MAX as i128) as u64,
+                }
+            })
+            .collect();
+        Ok(SupplyHistory {
+            coin_type: coin_type_str,
+            initial_supply,
+            approximate,
+            buckets,
+        })
+    }
+
+    /// The ID of the first version of the package `package_id` is a version of.
+    fn get_original_package_id(&self, package_id: ObjectID) -> IndexerResult<ObjectID> {
+        let package = self.get_package(&package_id)?.ok_or_else(|| {
+            IndexerError::InvalidArgumentError(format!("Package {package_id} not found"))
+        })?;
+        Ok(package.original_package_id())
+    }
+
+    /// The packages `version` of the package `package_id` belongs to links to, or its latest
+    /// version does when `version` is not given.
+    pub fn get_package_dependencies(
+        &self,
+        package_id: ObjectID,
+        version: Option<u64>,
+    ) -> IndexerResult<Vec<PackageDependency>> {
+        self.require_writer_feature(WriterFeature::PackageDependencies)?;
+        let original_id = self.get_original_package_id(package_id)?.to_vec();
+        let stored = self.run_query(|conn| {
+            let query = package_deps::table
+                .filter(package_deps::original_package_id.eq(original_id))
+                .order_by(package_deps::dependency_original_id.asc())
+                .into_boxed();
+            let query = match version {
+                Some(version) => query.filter(package_deps::package_version.eq(version as i64)),
+                None => query.filter(package_deps::latest.eq(true)),
+            };
+            query.load::<StoredPackageDependency>(conn)
+        })?;
+        stored.into_iter().map(PackageDependency::try_from).collect()
+    }
+
+    /// The packages whose latest version links to any version of the package `package_id`,
+    /// ordered by their original ID and starting after `cursor`.
+    pub fn get_package_dependents(
+        &self,
+        package_id: ObjectID,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> IndexerResult<Vec<PackageDependency>> {
+        self.require_writer_feature(WriterFeature::PackageDependencies)?;
+        let original_id = self.get_original_package_id(package_id)?.to_vec();
+        let stored = self.run_query(|conn| {
+            let mut query = package_deps::table
+                .filter(package_deps::dependency_original_id.eq(original_id))
+                .filter(package_deps::latest.eq(true))
+                .into_boxed();
+            if let Some(cursor) = cursor {
+                query = query.filter(package_deps::original_package_id.gt(cursor.to_vec()));
+            }
+            query
+                .order_by(package_deps::original_package_id.asc())
+                .limit(limit as i64)
+                .load::<StoredPackageDependency>(conn)
+        })?;
+        stored.into_iter().map(PackageDependency::try_from).collect()
+    }
+
+    /// The versions of the package whose first version is `original_id`, oldest first. Versions
+    /// are found through the dependencies recorded for them, which every package but the system
+    /// ones has.
+    pub fn get_package_versions(
+        &self,
+        original_id: ObjectID,
+    ) -> IndexerResult<Vec<PackageVersion>> {
+        self.require_writer_feature(WriterFeature::PackageDependencies)?;
+        let stored: Vec<(Vec<u8>, i64)> = self.run_query(|conn| {
+            package_deps::table
+                .filter(package_deps::original_package_id.eq(original_id.to_vec()))
+                .select((package_deps::package_id, package_deps::package_version))
+                .distinct()
+                .order_by(package_deps::package_version.asc())
+                .load(conn)
+        })?;
+        stored
+            .into_iter()
+            .map(|(package_id, version)| {
+                let package_id = ObjectID::from_bytes(package_id).map_err(|e| {
+                    IndexerError::PersistentStorageDataCorruptionError(format!(
+                        "Failed to parse package ID of a version of {original_id}: {e}"
+                    ))
+                })?;
+                Ok(PackageVersion {
+                    package_id,
+                    version: SequenceNumber::from_u64(version as u64),
+                })
+            })
+            .collect()
+    }
+
+    /// The dynamic object fields reported as orphans, of the parent `parent_id` if given,
+    /// ordered by the ID of their wrapper object and starting after `cursor`.
+    pub fn get_orphaned_dynamic_fields(
+        &self,
+        parent_id: Option<ObjectID>,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> IndexerResult<Vec<OrphanedDynamicField>> {
+        self.require_writer_feature(WriterFeature::OrphanedDynamicFields)?;
+        let stored = self.run_query(|conn| {
+            let mut query = orphaned_dynamic_fields::table.into_boxed();
+            if let Some(parent_id) = parent_id {
+                query = query.filter(orphaned_dynamic_fields::parent_id.eq(parent_id.to_vec()));
+            }
+            if let Some(cursor) = cursor {
+                query = query.filter(orphaned_dynamic_fields::field_id.gt(cursor.to_vec()));
+            }
+            query
+                .order_by(orphaned_dynamic_fields::field_id.asc())
+                .limit(limit as i64)
+                .load::<StoredOrphanedDynamicField>(conn)
+        })?;
+        stored
+            .into_iter()
+            .map(OrphanedDynamicField::try_from)
+            .collect()
+    }
+
+    /// The intervals over which `object_id` kept the same owner, ordered by the version of the
+    /// object starting them and starting after the version `cursor`. Versions are collapsed
+    /// into intervals as the history is queried, so that mutations that leave the owner alone
+    /// and consecutive wrapped versions do not start intervals of their own.
+    pub fn get_object_owners_history(
+        &self,
+        object_id: ObjectID,
+        cursor: Option<SequenceNumber>,
+        limit: usize,
+    ) -> IndexerResult<Vec<OwnershipInterval>> {
+        let cursor = cursor.map_or(-1, |version| version.value() as i64);
+        let query = format!(
+            "SELECT * FROM ( \
+                SELECT object_id, object_version, object_status, checkpoint_sequence_number, \
+                    serialized_object, \
+                    LEAD(checkpoint_sequence_number) OVER (ORDER BY object_version) \
+                        AS next_checkpoint_sequence_number \
+                FROM ( \
+                    SELECT *, \
+                        object_status IS DISTINCT FROM LAG(object_status) OVER w \
+                        OR owner_type IS DISTINCT FROM LAG(owner_type) OVER w \
+                        OR owner_id IS DISTINCT FROM LAG(owner_id) OVER w AS starts_interval \
+                    FROM objects_history \
+                    WHERE object_id = '\\x{}'::bytea \
+                    WINDOW w AS (ORDER BY object_version) \
+                ) versions \
+                WHERE starts_interval \
+            ) intervals \
+            WHERE object_version > {cursor} \
+            ORDER BY object_version \
+            LIMIT {limit}",
+            Hex::encode(object_id.to_vec()),
+        );
+        tracing::debug!("query object owners history: {}", query);
+        let stored = self.run_query(|conn| {
+            diesel::sql_query(query).load::<StoredOwnershipInterval>(conn)
+        })?;
+        stored.into_iter().map(OwnershipInterval::try_from).collect()
+    }
+
     pub fn get_latest_mgo_system_state(&self) -> Result<MgoSystemStateSummary, IndexerError> {
         let system_state: MgoSystemStateSummary =
             mgo_types::mgo_system_state::get_mgo_system_state(self)?
@@ -486,6 +975,39 @@ impl IndexerReader {
         Ok(Some(checkpoint))
     }
 
+    /// The certified summary and contents of a checkpoint, `None` if it is not indexed. Fails for
+    /// checkpoints indexed before they were recorded.
+    pub fn get_checkpoint_bcs(
+        &self,
+        checkpoint_id: CheckpointId,
+    ) -> Result<Option<CheckpointBcsV1>, IndexerError> {
+        self.require_writer_feature(WriterFeature::CheckpointBcs)?;
+        let stored_checkpoint = match self.get_checkpoint_from_db(checkpoint_id)? {
+            Some(stored_checkpoint) => stored_checkpoint,
+            None => return Ok(None),
+        };
+        let sequence_number = stored_checkpoint.sequence_number;
+        let (Some(summary), Some(contents)) = (
+            stored_checkpoint.raw_checkpoint_summary,
+            stored_checkpoint.raw_checkpoint_contents,
+        ) else {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Checkpoint {sequence_number} was indexed before its BCS encoding was recorded"
+            )));
+        };
+        let summary = bcs::from_bytes(&summary).map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Failed to deserialize the summary of checkpoint {sequence_number}: {e}"
+            ))
+        })?;
+        let contents = bcs::from_bytes(&contents).map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Failed to deserialize the contents of checkpoint {sequence_number}: {e}"
+            ))
+        })?;
+        Ok(Some(CheckpointBcsV1 { summary, contents }))
+    }
+
     pub fn get_latest_checkpoint(&self) -> Result<mgo_json_rpc_types::Checkpoint, IndexerError> {
         let stored_checkpoint = self.get_latest_checkpoint_from_db()?;
 
@@ -497,9 +1019,13 @@ impl IndexerReader {
         cursor: Option<u64>,
         limit: usize,
         descending_order: bool,
+        epoch: Option<u64>,
     ) -> Result<Vec<StoredCheckpoint>, IndexerError> {
         self.run_query(|conn| {
             let mut boxed_query = checkpoints::table.into_boxed();
+            if let Some(epoch) = epoch {
+                boxed_query = boxed_query.filter(checkpoints::epoch.eq(epoch as i64));
+            }
             if let Some(cursor) = cursor {
                 if descending_order {
                     boxed_query =
@@ -526,8 +1052,9 @@ impl IndexerReader {
         cursor: Option<u64>,
         limit: usize,
         descending_order: bool,
+        epoch: Option<u64>,
     ) -> Result<Vec<mgo_json_rpc_types::Checkpoint>, IndexerError> {
-        self.get_checkpoints_from_db(cursor, limit, descending_order)?
+        self.get_checkpoints_from_db(cursor, limit, descending_order, epoch)?
             .into_iter()
             .map(mgo_json_rpc_types::Checkpoint::try_from)
             .collect()
@@ -574,17 +1101,84 @@ impl IndexerReader {
         })
     }
 
+    /// The layouts of the transactions of `digests` returned with the `bcs` encoding, in the
+    /// order of `digests`. Fails if any of them is not indexed.
+    pub async fn multi_get_transaction_blocks_bcs_in_blocking_task(
+        &self,
+        digests: Vec<TransactionDigest>,
+    ) -> Result<Vec<TransactionBlockBcsV1>, IndexerError> {
+        self.spawn_blocking(move |this| {
+            let stored_txes: HashMap<_, _> = this
+                .multi_get_transactions(&digests)?
+                .into_iter()
+                .map(|tx| (tx.transaction_digest.clone(), tx))
+                .collect();
+            digests
+                .into_iter()
+                .map(|digest| match stored_txes.get(&digest.inner().to_vec()) {
+                    Some(stored_tx) => stored_tx.try_into_transaction_block_bcs(),
+                    None => Err(IndexerError::InvalidArgumentError(format!(
+                        "Transaction {digest} not found"
+                    ))),
+                })
+                .collect()
+        })
+        .await
+    }
+
     fn stored_transaction_to_transaction_block(
         &self,
         stored_txes: Vec<StoredTransaction>,
         options: mgo_json_rpc_types::MgoTransactionBlockResponseOptions,
     ) -> IndexerResult<Vec<MgoTransactionBlockResponse>> {
+        let epochs = if options.include_checkpoint {
+            self.get_checkpoint_epochs(
+                stored_txes
+                    .iter()
+                    .map(|tx| tx.checkpoint_sequence_number)
+                    .unique()
+                    .collect(),
+            )?
+        } else {
+            HashMap::new()
+        };
         stored_txes
             .into_iter()
-            .map(|stored_tx| stored_tx.try_into_mgo_transaction_block_response(&options, self))
+            .map(|stored_tx| {
+                let balance_changes = if options.show_balance_changes {
+                    self.get_balance_changes_from_history(&stored_tx)?
+                } else {
+                    None
+                };
+                let epoch = epochs.get(&stored_tx.checkpoint_sequence_number).copied();
+                let mut response =
+                    stored_tx.try_into_mgo_transaction_block_response(&options, self)?;
+                if balance_changes.is_some() {
+                    response.balance_changes = balance_changes;
+                }
+                response.epoch = epoch;
+                Ok(response)
+            })
             .collect::<IndexerResult<Vec<_>>>()
     }
 
+    /// Epochs of the checkpoints of `sequence_numbers`, read at once from the checkpoints table.
+    fn get_checkpoint_epochs(
+        &self,
+        sequence_numbers: Vec<i64>,
+    ) -> IndexerResult<HashMap<i64, EpochId>> {
+        let epochs = self.run_query(|conn| {
+            checkpoints::table
+                .filter(checkpoints::sequence_number.eq_any(sequence_numbers))
+                .select((checkpoints::sequence_number, checkpoints::epoch))
+                .load::<(i64, i64)>(conn)
+        })?;
+        Ok(epochs
+            .into_iter()
+            .map(|(checkpoint, epoch)| (checkpoint, epoch as EpochId))
+            .collect())
+    }
+
     fn multi_get_transactions_with_sequence_numbers(
         &self,
         tx_sequence_numbers: Vec<i64>,
@@ -632,136 +1226,467 @@ impl IndexerReader {
                 .limit(limit as i64)
                 .into_boxed();
             if let Some(filter) = filter {
-                match filter {
-                    MgoObjectDataFilter::StructType (struct_tag ) => {
-                        let object_type = struct_tag.to_canonical_string(/* with_prefix */ true);
-                        query = query.filter(objects::dsl::object_type.like(format!("{}%",object_type)));
-                    },
-                    MgoObjectDataFilter::MatchAny(filters) => {
-                        let mut condition = "(".to_string();
-                        for (i, filter) in filters.iter().enumerate() {
-                            if let MgoObjectDataFilter::StructType (struct_tag) = filter {
-                                let object_type = struct_tag.to_canonical_string(/* with_prefix */ true);
-                                if i == 0 {
-                                    condition += format!("objects.object_type LIKE '{}%'",object_type).as_str();
-                                } else {
-                                    condition += format!(" OR objects.object_type LIKE '{}%'",object_type).as_str();
-                                }
-                            } else {
-                                return Err(IndexerError::InvalidArgumentError(
-                                    "Invalid filter type. Only struct, MatchAny and MatchNone of struct filters are supported.".into(),
-                                ));
-                            }
-                        }
-                        condition += ")";
-                        query = query.filter(sql::<Bool>(&condition));
-                    },
-                    MgoObjectDataFilter::MatchNone(filters) => {
-                        for filter in filters {
-                            if let MgoObjectDataFilter::StructType (struct_tag) = filter {
-                                let object_type = struct_tag.to_canonical_string(/* with_prefix */ true);
-                                query = query.filter(objects::dsl::object_type.not_like(format!("{}%", object_type)));
-                            } else {
-                                return Err(IndexerError::InvalidArgumentError(
-                                    "Invalid filter type. Only struct, MatchAny and MatchNone of struct filters are supported.".into(),
-                                ));
-                            }
-                        }
-                    }
-                    _ => {
-                        return Err(IndexerError::InvalidArgumentError(
-                            "Invalid filter type. Only struct, MatchAny and MatchNone of struct filters are supported.".into(),
-                        ));
-                    }
-                }
+                query = filter_objects_by_type(query, filter)?;
             }
 
             if let Some(object_cursor) = cursor {
                 query = query.filter(objects::dsl::object_id.gt(object_cursor.to_vec()));
             }
 
-            query.load::<StoredObject>(conn).map_err(|e| IndexerError::PostgresReadError(e.to_string()))
+            query
+                .load::<StoredObject>(conn)
+                .map_err(|e| IndexerError::PostgresReadError(e.to_string()))
         })
     }
 
-    pub async fn query_events_in_blocking_task(
+    pub async fn get_owned_objects_by_checkpoint_in_blocking_task(
         &self,
-        filter: EventFilter,
-        cursor: Option<EventID>,
+        address: MgoAddress,
+        filter: Option<MgoObjectDataFilter>,
+        modified_after: Option<CheckpointSequenceNumber>,
+        cursor: Option<ModifiedObjectCursor>,
         limit: usize,
         descending_order: bool,
-    ) -> IndexerResult<Vec<MgoEvent>> {
+    ) -> Result<Vec<StoredObject>, IndexerError> {
         self.spawn_blocking(move |this| {
-            this.query_events_impl(filter, cursor, limit, descending_order)
+            this.get_owned_objects_by_checkpoint_impl(
+                address,
+                filter,
+                modified_after,
+                cursor,
+                limit,
+                descending_order,
+            )
         })
         .await
     }
 
-    fn filter_object_id_with_type(
-        &self,
-        object_ids: Vec<ObjectID>,
-        object_type: String,
-    ) -> Result<Vec<ObjectID>, IndexerError> {
-        let object_ids = object_ids.into_iter().map(|id| id.to_vec()).collect_vec();
-        let filtered_ids = self.run_query(|conn| {
-            objects::dsl::objects
-                .filter(objects::object_id.eq_any(object_ids))
-                .filter(objects::object_type.eq(object_type))
-                .select(objects::object_id)
-                .load::<Vec<u8>>(conn)
-        })?;
-        filtered_ids
-            .into_iter()
-            .map(|id| {
-                ObjectID::from_bytes(id.clone()).map_err(|_e| {
-                    IndexerError::PersistentStorageDataCorruptionError(format!(
-                        "Can't convert {:?} to ObjectID",
-                        id,
-                    ))
-                })
-            })
-            .collect::<Result<Vec<_>, _>>()
-    }
-
-    pub async fn multi_get_objects_in_blocking_task(
+    fn get_owned_objects_by_checkpoint_impl(
         &self,
-        object_ids: Vec<ObjectID>,
+        address: MgoAddress,
+        filter: Option<MgoObjectDataFilter>,
+        modified_after: Option<CheckpointSequenceNumber>,
+        cursor: Option<ModifiedObjectCursor>,
+        limit: usize,
+        descending_order: bool,
     ) -> Result<Vec<StoredObject>, IndexerError> {
-        self.spawn_blocking(move |this| this.multi_get_objects_impl(object_ids))
-            .await
-    }
+        self.run_query(|conn| {
+            let mut query = objects::dsl::objects
+                .filter(objects::dsl::owner_type.eq(OwnerType::Address as i16))
+                .filter(objects::dsl::owner_id.eq(address.to_vec()))
+                .limit(limit as i64)
+                .into_boxed();
+            if let Some(filter) = filter {
+                query = filter_objects_by_type(query, filter)?;
+            }
+            if let Some(after) = modified_after {
+                query = query.filter(objects::dsl::checkpoint_sequence_number.gt(after as i64));
+            }
 
-    fn multi_get_objects_impl(
-        &self,
-        object_ids: Vec<ObjectID>,
-    ) -> Result<Vec<StoredObject>, IndexerError> {
-        let object_ids = object_ids.into_iter().map(|id| id.to_vec()).collect_vec();
+            // The cursor is the sort key of the last object returned, so objects modified after
+            // it was handed out move to their new position instead of shifting the page boundary.
+            if descending_order {
+                if let Some(cursor) = cursor {
+                    let checkpoint = cursor.checkpoint as i64;
+                    query = query.filter(
+                        objects::dsl::checkpoint_sequence_number.lt(checkpoint).or(
+                            objects::dsl::checkpoint_sequence_number
+                                .eq(checkpoint)
+                                .and(objects::dsl::object_id.lt(cursor.object_id.to_vec())),
+                        ),
+                    );
+                }
+                query = query.order((
+                    objects::dsl::checkpoint_sequence_number.desc(),
+                    objects::dsl::object_id.desc(),
+                ));
+            } else {
+                if let Some(cursor) = cursor {
+                    let checkpoint = cursor.checkpoint as i64;
+                    query = query.filter(
+                        objects::dsl::checkpoint_sequence_number.gt(checkpoint).or(
+                            objects::dsl::checkpoint_sequence_number
+                                .eq(checkpoint)
+                                .and(objects::dsl::object_id.gt(cursor.object_id.to_vec())),
+                        ),
+                    );
+                }
+                query = query.order((
+                    objects::dsl::checkpoint_sequence_number.asc(),
+                    objects::dsl::object_id.asc(),
+                ));
+            }
 
-        self.run_query(|conn| {
-            objects::dsl::objects
-                .filter(objects::object_id.eq_any(object_ids))
+            query
                 .load::<StoredObject>(conn)
+                .map_err(|e| IndexerError::PostgresReadError(e.to_string()))
         })
     }
 
-    fn query_transaction_blocks_by_checkpoint_impl(
+    pub async fn get_owned_objects_multi_in_blocking_task(
         &self,
-        checkpoint_seq: u64,
-        options: mgo_json_rpc_types::MgoTransactionBlockResponseOptions,
-        cursor_tx_seq: Option<i64>,
+        addresses: Vec<MgoAddress>,
+        filter: Option<MgoObjectDataFilter>,
+        cursor: Option<MultiOwnerObjectCursor>,
         limit: usize,
-        is_descending: bool,
-    ) -> IndexerResult<Vec<MgoTransactionBlockResponse>> {
-        let mut query = transactions::dsl::transactions
-            .filter(transactions::dsl::checkpoint_sequence_number.eq(checkpoint_seq as i64))
-            .into_boxed();
+    ) -> Result<Vec<StoredObject>, IndexerError> {
+        self.spawn_blocking(move |this| {
+            this.get_owned_objects_multi_impl(addresses, filter, cursor, limit)
+        })
+        .await
+    }
+
+    fn get_owned_objects_multi_impl(
+        &self,
+        addresses: Vec<MgoAddress>,
+        filter: Option<MgoObjectDataFilter>,
+        cursor: Option<MultiOwnerObjectCursor>,
+        limit: usize,
+    ) -> Result<Vec<StoredObject>, IndexerError> {
+        let owners: Vec<Vec<u8>> = addresses.iter().map(|a| a.to_vec()).collect();
+        // Owners are ranked by their position in the request rather than by address.
+        let owner_position = format!(
+            "array_position(ARRAY[{}]::BYTEA[], objects.owner_id)",
+            owners
+                .iter()
+                .map(|owner| format!("'\\x{}'::BYTEA", Hex::encode(owner)))
+                .join(", ")
+        );
+        let first_owner = cursor.map_or(0, |c| c.address_index);
+        self.run_query(|conn| {
+            let mut query = objects::dsl::objects
+                .filter(objects::dsl::owner_type.eq(OwnerType::Address as i16))
+                .filter(objects::dsl::owner_id.eq_any(owners[first_owner..].to_vec()))
+                .order((
+                    sql::<Integer>(&owner_position).asc(),
+                    objects::dsl::object_id.asc(),
+                ))
+                .limit(limit as i64)
+                .into_boxed();
+            if let Some(filter) = filter {
+                query = filter_objects_by_type(query, filter)?;
+            }
+
+            if let Some(cursor) = cursor {
+                query = query.filter(
+                    objects::dsl::owner_id
+                        .ne(owners[first_owner].clone())
+                        .or(objects::dsl::object_id.gt(cursor.object_id.to_vec())),
+                );
+            }
+
+            query
+                .load::<StoredObject>(conn)
+                .map_err(|e| IndexerError::PostgresReadError(e.to_string()))
+        })
+    }
+
+    /// Objects owned by `address` at the end of `checkpoint`, ordered by object ID and starting
+    /// after `cursor`: the latest version of every object up to `checkpoint`, if that version is
+    /// still owned by `address` rather than wrapped, deleted, shared or sent elsewhere.
+    pub fn get_owned_objects_at_checkpoint(
+        &self,
+        address: MgoAddress,
+        checkpoint: CheckpointSequenceNumber,
+        filter: Option<MgoObjectDataFilter>,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> Result<Vec<StoredHistoryObject>, IndexerError> {
+        // Objects untouched since the pruned checkpoints have no history left to rebuild their
+        // ownership from, so no snapshot is complete once the history has been pruned.
+        if let Some(watermark) = self.get_pruned_watermark()? {
+            if watermark.checkpoint_hi_exclusive > 0 {
+                return Err(IndexerError::DataPruned(
+                    "Object history".to_string(),
+                    watermark.checkpoint_hi_exclusive as u64,
+                ));
+            }
+        }
+        // Later checkpoints would still change as they are indexed.
+        let latest_checkpoint = self.get_latest_checkpoint_from_db()?.sequence_number as u64;
+        if checkpoint > latest_checkpoint {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Checkpoint {checkpoint} has not been indexed yet, the latest indexed checkpoint \
+                 is {latest_checkpoint}"
+            )));
+        }
+
+        let owner = format!("'\\x{}'::BYTEA", Hex::encode(address.to_vec()));
+        let cursor_clause = cursor
+            .map(|cursor| {
+                format!(
+                    "AND object_id > '\\x{}'::BYTEA",
+                    Hex::encode(cursor.to_vec())
+                )
+            })
+            .unwrap_or_default();
+        let type_clause = filter
+            .map(|filter| object_type_condition(filter).map(|c| format!("AND {c}")))
+            .transpose()?
+            .unwrap_or_default();
+        // Only objects owned by `address` at some point up to `checkpoint` are candidates, of
+        // which the latest version as of `checkpoint` decides whether it still owns them.
+        let query = format!(
+            "
+            SELECT * FROM ( \
+                SELECT DISTINCT ON (object_id) * FROM objects_history \
+                WHERE checkpoint_sequence_number <= {checkpoint} \
+                AND object_id IN ( \
+                    SELECT object_id FROM objects_history \
+                    WHERE owner_type = {address_owner} \
+                    AND owner_id = {owner} \
+                    AND checkpoint_sequence_number <= {checkpoint} \
+                    {cursor_clause} \
+                ) \
+                ORDER BY object_id, object_version DESC \
+            ) latest \
+            WHERE object_status = {active} \
+            AND owner_type = {address_owner} \
+            AND owner_id = {owner} \
+            {type_clause} \
+            ORDER BY object_id ASC \
+            LIMIT {limit}
+        ",
+            address_owner = OwnerType::Address as i16,
+            active = ObjectStatus::Active as i16,
+        );
+        tracing::debug!("get owned objects at checkpoint query: {query}");
+        self.run_query(|conn| diesel::sql_query(query).load::<StoredHistoryObject>(conn))
+    }
+
+    /// The objects at exactly the versions of `objects` in the object history, in the order of
+    /// `objects`. Versions the history does not have, or at which the object was wrapped or
+    /// deleted, come back as `None`.
+    fn multi_get_history_objects(
+        &self,
+        objects: &[(ObjectID, SequenceNumber)],
+    ) -> Result<Vec<Option<Object>>, IndexerError> {
+        if objects.is_empty() {
+            return Ok(vec![]);
+        }
+        let query = format!(
+            "SELECT * FROM objects_history WHERE (object_id, object_version) IN ({})",
+            history_keys(objects),
+        );
+        tracing::debug!("multi get history objects query: {query}");
+        let rows =
+            self.run_query(|conn| diesel::sql_query(query).load::<StoredHistoryObject>(conn))?;
+        let versions = history_versions(rows)?;
+        Ok(objects
+            .iter()
+            .map(|key| versions.get(key).cloned().flatten())
+            .collect())
+    }
+
+    /// The latest version of each of `objects` at or before the requested version in the object
+    /// history, in the order of `objects`, with one query for the whole batch. Objects that were
+    /// wrapped or deleted as of that version come back as `None`, as they do on fullnodes.
+    fn multi_find_history_objects_lt_or_eq_version(
+        &self,
+        objects: &[(ObjectID, SequenceNumber)],
+    ) -> Result<Vec<Option<Object>>, IndexerError> {
+        if objects.is_empty() {
+            return Ok(vec![]);
+        }
+        let query = format!(
+            "
+            SELECT h.* FROM (VALUES {}) AS k(object_id, object_version) \
+            CROSS JOIN LATERAL ( \
+                SELECT * FROM objects_history \
+                WHERE object_id = k.object_id AND object_version <= k.object_version \
+                ORDER BY object_version DESC \
+                LIMIT 1 \
+            ) h
+        ",
+            history_keys(objects),
+        );
+        tracing::debug!("multi find history objects query: {query}");
+        let rows =
+            self.run_query(|conn| diesel::sql_query(query).load::<StoredHistoryObject>(conn))?;
+        let versions = history_versions(rows)?;
+        Ok(objects
+            .iter()
+            .map(|(id, version)| {
+                versions
+                    .range((*id, SequenceNumber::MIN)..=(*id, *version))
+                    .next_back()
+                    .and_then(|(_, o)| o.clone())
+            })
+            .collect())
+    }
+
+    /// Runs `f` directly when already on the blocking pool, as when building responses, and
+    /// moves it there otherwise.
+    async fn run_on_blocking_pool<R, F>(&self, f: F) -> Result<R, IndexerError>
+    where
+        F: FnOnce(&Self) -> Result<R, IndexerError> + Send + 'static,
+        R: Send + 'static,
+    {
+        if CALLED_FROM_BLOCKING_POOL.with(|in_blocking_pool| *in_blocking_pool.borrow()) {
+            f(self)
+        } else {
+            self.spawn_blocking(move |this| f(&this)).await
+        }
+    }
+
+    /// The balance changes of `stored_tx`, computed from the object history as fullnodes
+    /// compute them from their object store. Falls back to the balance changes stored with the
+    /// transaction when the history of its objects has been pruned.
+    fn get_balance_changes_from_history(
+        &self,
+        stored_tx: &StoredTransaction,
+    ) -> IndexerResult<Option<Vec<BalanceChange>>> {
+        let effects: TransactionEffects = bcs::from_bytes(&stored_tx.raw_effects).map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Can't convert raw_effects of {} into TransactionEffects. Error: {e}",
+                stored_tx.tx_sequence_number
+            ))
+        })?;
+        let sender_signed_data: SenderSignedData = bcs::from_bytes(&stored_tx.raw_transaction)
+            .map_err(|e| {
+                IndexerError::PersistentStorageDataCorruptionError(format!(
+                    "Can't convert raw_transaction of {} into SenderSignedData. Error: {e}",
+                    stored_tx.tx_sequence_number
+                ))
+            })?;
+        let input_objects = sender_signed_data.transaction_data().input_objects()?;
+        match futures::executor::block_on(get_balance_changes_from_effect(
+            self,
+            &effects,
+            input_objects,
+            None,
+        )) {
+            Ok(balance_changes) => Ok(Some(balance_changes)),
+            Err(IndexerError::UserInputError(UserInputError::ObjectNotFound { .. })) => {
+                tracing::debug!(
+                    "Object history of transaction {} is incomplete, serving stored balance \
+                     changes",
+                    effects.transaction_digest()
+                );
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    pub async fn query_events_in_blocking_task(
+        &self,
+        filter: EventFilter,
+        cursor: Option<EventID>,
+        limit: usize,
+        descending_order: bool,
+    ) -> IndexerResult<Vec<MgoEvent>> {
+        self.spawn_blocking(move |this| {
+            this.query_events_impl(filter, cursor, limit, descending_order)
+        })
+        .await
+    }
+
+    pub async fn query_events_by_object_in_blocking_task(
+        &self,
+        object_id: ObjectID,
+        cursor: Option<EventID>,
+        limit: usize,
+        descending_order: bool,
+    ) -> IndexerResult<Vec<MgoEvent>> {
+        self.spawn_blocking(move |this| {
+            this.query_events_by_object_impl(object_id, cursor, limit, descending_order)
+        })
+        .await
+    }
+
+    fn filter_object_id_with_type(
+        &self,
+        object_ids: Vec<ObjectID>,
+        object_type: String,
+    ) -> Result<Vec<ObjectID>, IndexerError> {
+        let object_ids = object_ids.into_iter().map(|id| id.to_vec()).collect_vec();
+        let filtered_ids = self.run_query(|conn| {
+            objects::dsl::objects
+                .filter(objects::object_id.eq_any(object_ids))
+                .filter(objects::object_type.eq(object_type))
+                .select(objects::object_id)
+                .load::<Vec<u8>>(conn)
+        })?;
+        filtered_ids
+            .into_iter()
+            .map(|id| {
+                ObjectID::from_bytes(id.clone()).map_err(|_e| {
+                    IndexerError::PersistentStorageDataCorruptionError(format!(
+                        "Can't convert {:?} to ObjectID",
+                        id,
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+    }
+
+    pub async fn multi_get_stored_objects_in_blocking_task(
+        &self,
+        object_ids: Vec<ObjectID>,
+    ) -> Result<Vec<StoredObject>, IndexerError> {
+        self.spawn_blocking(move |this| this.multi_get_objects_impl(object_ids))
+            .await
+    }
+
+    /// Latest versions of `object_ids`, in the order they are requested, `None` for objects not
+    /// in the DB.
+    pub async fn multi_get_objects_ordered_in_blocking_task(
+        &self,
+        object_ids: Vec<ObjectID>,
+    ) -> Result<Vec<Option<Object>>, IndexerError> {
+        self.spawn_blocking(move |this| {
+            let stored_objects: HashMap<_, _> = this
+                .multi_get_objects_impl(object_ids.clone())?
+                .into_iter()
+                .map(|object| (object.object_id.clone(), object))
+                .collect();
+            object_ids
+                .into_iter()
+                .map(|id| {
+                    stored_objects
+                        .get(&id.to_vec())
+                        .cloned()
+                        .map(Object::try_from)
+                        .transpose()
+                })
+                .collect()
+        })
+        .await
+    }
+
+    fn multi_get_objects_impl(
+        &self,
+        object_ids: Vec<ObjectID>,
+    ) -> Result<Vec<StoredObject>, IndexerError> {
+        let object_ids = object_ids.into_iter().map(|id| id.to_vec()).collect_vec();
+
+        self.run_query(|conn| {
+            objects::dsl::objects
+                .filter(objects::object_id.eq_any(object_ids))
+                .load::<StoredObject>(conn)
+        })
+    }
+
+    fn query_transaction_blocks_by_checkpoint_impl(
+        &self,
+        checkpoint_seq: u64,
+        options: mgo_json_rpc_types::MgoTransactionBlockResponseOptions,
+        cursor_tx_seq: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> IndexerResult<Vec<MgoTransactionBlockResponse>> {
+        let mut query = transactions::dsl::transactions
+            .filter(transactions::dsl::checkpoint_sequence_number.eq(checkpoint_seq as i64))
+            .into_boxed();
 
-        // Translate transaction digest cursor to tx sequence number
+        // Translate transaction digest cursor to tx sequence number, excluding the cursor itself
+        // as the other transaction filters do.
         if let Some(cursor_tx_seq) = cursor_tx_seq {
             if is_descending {
-                query = query.filter(transactions::dsl::tx_sequence_number.le(cursor_tx_seq));
+                query = query.filter(transactions::dsl::tx_sequence_number.lt(cursor_tx_seq));
             } else {
-                query = query.filter(transactions::dsl::tx_sequence_number.ge(cursor_tx_seq));
+                query = query.filter(transactions::dsl::tx_sequence_number.gt(cursor_tx_seq));
             }
         }
         if is_descending {
@@ -790,6 +1715,60 @@ impl IndexerReader {
         .await
     }
 
+    /// The watermark of the data pruned by the writer, `None` if nothing has been pruned, which
+    /// is always the case of writers that predate pruning.
+    fn get_pruned_watermark(&self) -> IndexerResult<Option<StoredPrunerWatermark>> {
+        match self.require_writer_feature(WriterFeature::PrunerWatermark) {
+            Err(IndexerError::FeatureNotAvailable { .. }) => return Ok(None),
+            result => result?,
+        }
+        self.run_query(|conn| {
+            pruner_watermark::table
+                .first::<StoredPrunerWatermark>(conn)
+                .optional()
+        })
+    }
+
+    /// Fails with `DataPruned` if `checkpoint` has been pruned.
+    fn check_checkpoint_not_pruned(&self, checkpoint: u64) -> IndexerResult<()> {
+        match self.get_pruned_watermark()? {
+            Some(watermark) if (checkpoint as i64) < watermark.checkpoint_hi_exclusive => {
+                Err(IndexerError::DataPruned(
+                    format!("Checkpoint {checkpoint}"),
+                    watermark.checkpoint_hi_exclusive as u64,
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Sequence number and checkpoint of the cursor transaction `digest`, which is reported as
+    /// pruned rather than not found once transactions have been pruned.
+    fn get_cursor_transaction(&self, digest: TransactionDigest) -> IndexerResult<(i64, i64)> {
+        let cursor = self.run_query(|conn| {
+            transactions::dsl::transactions
+                .select((
+                    transactions::tx_sequence_number,
+                    transactions::checkpoint_sequence_number,
+                ))
+                .filter(transactions::dsl::transaction_digest.eq(digest.into_inner().to_vec()))
+                .first::<(i64, i64)>(conn)
+                .optional()
+        })?;
+        match (cursor, self.get_pruned_watermark()?) {
+            (Some(cursor), _) => Ok(cursor),
+            (None, Some(watermark)) if watermark.tx_hi_exclusive > 0 => {
+                Err(IndexerError::DataPruned(
+                    format!("Cursor transaction {digest}"),
+                    watermark.checkpoint_hi_exclusive as u64,
+                ))
+            }
+            (None, _) => Err(IndexerError::PostgresReadError(
+                diesel::result::Error::NotFound.to_string(),
+            )),
+        }
+    }
+
     fn query_transaction_blocks_impl(
         &self,
         filter: Option<TransactionFilter>,
@@ -798,15 +1777,11 @@ impl IndexerReader {
         limit: usize,
         is_descending: bool,
     ) -> IndexerResult<Vec<MgoTransactionBlockResponse>> {
-        let cursor_tx_seq = if let Some(cursor) = cursor {
-            Some(self.run_query(|conn| {
-                transactions::dsl::transactions
-                    .select(transactions::tx_sequence_number)
-                    .filter(transactions::dsl::transaction_digest.eq(cursor.into_inner().to_vec()))
-                    .first::<i64>(conn)
-            })?)
+        let (cursor_tx_seq, cursor_checkpoint) = if let Some(cursor) = cursor {
+            let (tx_seq, checkpoint) = self.get_cursor_transaction(cursor)?;
+            (Some(tx_seq), Some(checkpoint))
         } else {
-            None
+            (None, None)
         };
         let cursor_clause = if let Some(cursor_tx_seq) = cursor_tx_seq {
             if is_descending {
@@ -821,13 +1796,14 @@ impl IndexerReader {
         let (table_name, main_where_clause) = match filter {
             // Processed above
             Some(TransactionFilter::Checkpoint(seq)) => {
+                self.check_checkpoint_not_pruned(seq)?;
                 return self.query_transaction_blocks_by_checkpoint_impl(
                     seq,
                     options,
                     cursor_tx_seq,
                     limit,
                     is_descending,
-                )
+                );
             }
             // FIXME: sanitize module & function
             Some(TransactionFilter::MoveFunction {
@@ -957,6 +1933,22 @@ impl IndexerReader {
                 );
                 (inner_query, "1 = 1".into())
             }
+            // Multisig participants are stored alongside the multisig itself, so a scheme matches
+            // transactions signed by multisig with a participant of that scheme too.
+            Some(TransactionFilter::SignatureScheme(scheme)) => {
+                self.require_writer_feature(WriterFeature::TransactionSignatures)?;
+                let inner_query = format!(
+                    "(SELECT DISTINCT {TX_SEQUENCE_NUMBER_STR} FROM tx_signatures \
+                    WHERE scheme = {} {} \
+                    ORDER BY {TX_SEQUENCE_NUMBER_STR} {} \
+                    LIMIT {}) AS signed",
+                    scheme.flag(),
+                    cursor_clause,
+                    order_str,
+                    limit,
+                );
+                (inner_query, "1 = 1".into())
+            }
             Some(
                 TransactionFilter::TransactionKind(_) | TransactionFilter::TransactionKindIn(_),
             ) => {
@@ -965,8 +1957,11 @@ impl IndexerReader {
                 ));
             }
             None => {
-                // apply no filter
-                ("transactions".into(), "1 = 1".into())
+                // apply no filter, other than to the partitions from the cursor on
+                (
+                    "transactions".into(),
+                    self.checkpoint_cursor_condition("", cursor_checkpoint, is_descending),
+                )
             }
         };
 
@@ -1060,6 +2055,46 @@ impl IndexerReader {
             .map_err(Into::into)
     }
 
+    /// The `(tx_sequence_number, event_sequence_number)` position that an event query starts
+    /// after: that of the event `cursor`, or one before the first event in the order of the
+    /// query if there is no cursor. Followed by the checkpoint of the cursor, if any.
+    fn event_cursor_position(
+        &self,
+        cursor: Option<EventID>,
+        descending_order: bool,
+    ) -> IndexerResult<(i64, i64, Option<i64>)> {
+        Ok(match cursor {
+            Some(EventID {
+                tx_digest,
+                event_seq,
+            }) => {
+                let (tx_seq, checkpoint) = self.get_cursor_transaction(tx_digest)?;
+                (tx_seq, event_seq as i64, Some(checkpoint))
+            }
+            None if descending_order => (i64::MAX, i64::MAX, None),
+            None => (-1, -1, None),
+        })
+    }
+
+    /// Condition on the columns prefixed with `prefix` selecting the rows of the checkpoints
+    /// from `checkpoint` on, in the order of a query, or `TRUE` without a checkpoint or with
+    /// partition pruning off. Sequence numbers grow with checkpoints, so the rows after a cursor
+    /// in `checkpoint` all match it, and it only lets Postgres skip the partitions before it.
+    fn checkpoint_cursor_condition(
+        &self,
+        prefix: &str,
+        checkpoint: Option<i64>,
+        descending: bool,
+    ) -> String {
+        match checkpoint {
+            Some(checkpoint) if self.partition_pruning => {
+                let operator = if descending { "<=" } else { ">=" };
+                format!("{prefix}{CHECKPOINT_SEQUENCE_NUMBER_STR} {operator} {checkpoint}")
+            }
+            _ => "TRUE".to_string(),
+        }
+    }
+
     fn query_events_by_tx_digest_query(
         &self,
         tx_digest: TransactionDigest,
@@ -1106,47 +2141,15 @@ impl IndexerReader {
         limit: usize,
         descending_order: bool,
     ) -> IndexerResult<Vec<MgoEvent>> {
-        let (tx_seq, event_seq) = if let Some(cursor) = cursor {
-            let EventID {
-                tx_digest,
-                event_seq,
-            } = cursor;
-            (
-                self.run_query(|conn| {
-                    transactions::dsl::transactions
-                        .select(transactions::tx_sequence_number)
-                        .filter(
-                            transactions::dsl::transaction_digest
-                                .eq(tx_digest.into_inner().to_vec()),
-                        )
-                        .first::<i64>(conn)
-                })?,
-                event_seq,
-            )
-        } else if descending_order {
-            let max_tx_seq: i64 = self.run_query(|conn| {
-                events::dsl::events
-                    .select(events::tx_sequence_number)
-                    .order(events::dsl::tx_sequence_number.desc())
-                    .first::<i64>(conn)
-            })?;
-            (max_tx_seq + 1, 0)
-        } else {
-            (-1, 0)
-        };
+        let (tx_seq, event_seq, checkpoint) =
+            self.event_cursor_position(cursor, descending_order)?;
 
         let query = if let EventFilter::Sender(sender) = &filter {
             // Need to remove ambiguities for tx_sequence_number column
-            let cursor_clause = if descending_order {
-                format!("(e.{TX_SEQUENCE_NUMBER_STR} < {} OR (e.{TX_SEQUENCE_NUMBER_STR} = {} AND e.{EVENT_SEQUENCE_NUMBER_STR} < {}))", tx_seq, tx_seq, event_seq)
-            } else {
-                format!("(e.{TX_SEQUENCE_NUMBER_STR} > {} OR (e.{TX_SEQUENCE_NUMBER_STR} = {} AND e.{EVENT_SEQUENCE_NUMBER_STR} > {}))", tx_seq, tx_seq, event_seq)
-            };
-            let order_clause = if descending_order {
-                format!("e.{TX_SEQUENCE_NUMBER_STR} DESC, e.{EVENT_SEQUENCE_NUMBER_STR} DESC")
-            } else {
-                format!("e.{TX_SEQUENCE_NUMBER_STR} ASC, e.{EVENT_SEQUENCE_NUMBER_STR} ASC")
-            };
+            let cursor_clause = event_cursor_condition("e.", tx_seq, event_seq, descending_order);
+            let partition_clause =
+                self.checkpoint_cursor_condition("e.", checkpoint, descending_order);
+            let order_clause = event_order("e.", descending_order);
             format!(
                 "( \
                     SELECT *
@@ -1154,75 +2157,36 @@ impl IndexerReader {
                     JOIN events e
                     ON e.tx_sequence_number = s.tx_sequence_number
                     AND s.sender = '\\x{}'::bytea
-                    WHERE {} \
+                    WHERE {} AND {} \
                     ORDER BY {} \
                     LIMIT {}
                 )",
                 Hex::encode(sender.to_vec()),
                 cursor_clause,
+                partition_clause,
                 order_clause,
                 limit,
             )
         } else if let EventFilter::Transaction(tx_digest) = filter {
             self.query_events_by_tx_digest_query(tx_digest, cursor, limit, descending_order)?
         } else {
-            let main_where_clause = match filter {
-                EventFilter::Package(package_id) => {
-                    format!("package = '\\x{}'::bytea", package_id.to_hex())
-                }
-                EventFilter::MoveModule { package, module } => {
-                    format!(
-                        "package = '\\x{}'::bytea AND module = '{}'",
-                        package.to_hex(),
-                        module,
-                    )
-                }
-                EventFilter::MoveEventType(struct_tag) => {
-                    format!("event_type = '{}'", struct_tag)
-                }
-                EventFilter::MoveEventModule { package, module } => {
-                    let package_module_prefix = format!("{}::{}", package.to_hex_literal(), module);
-                    format!("event_type LIKE '{package_module_prefix}::%'")
-                }
-                EventFilter::Sender(_) => {
-                    // Processed above
-                    unreachable!()
-                }
-                EventFilter::Transaction(_) => {
-                    // Processed above
-                    unreachable!()
-                }
-                EventFilter::MoveEventField { .. }
-                | EventFilter::All(_)
-                | EventFilter::Any(_)
-                | EventFilter::And(_, _)
-                | EventFilter::Or(_, _)
-                | EventFilter::TimeRange { .. } => {
-                    return Err(IndexerError::NotSupportedError(
-                        "This type of EventFilter is not supported.".into(),
-                    ));
-                }
+            let Some(main_where_clause) = self.event_filter_condition(filter)? else {
+                return Ok(vec![]);
             };
 
-            let cursor_clause = if descending_order {
-                format!("AND ({TX_SEQUENCE_NUMBER_STR} < {} OR ({TX_SEQUENCE_NUMBER_STR} = {} AND {EVENT_SEQUENCE_NUMBER_STR} < {}))", tx_seq, tx_seq, event_seq)
-            } else {
-                format!("AND ({TX_SEQUENCE_NUMBER_STR} > {} OR ({TX_SEQUENCE_NUMBER_STR} = {} AND {EVENT_SEQUENCE_NUMBER_STR} > {}))", tx_seq, tx_seq, event_seq)
-            };
-            let order_clause = if descending_order {
-                format!("{TX_SEQUENCE_NUMBER_STR} DESC, {EVENT_SEQUENCE_NUMBER_STR} DESC")
-            } else {
-                format!("{TX_SEQUENCE_NUMBER_STR} ASC, {EVENT_SEQUENCE_NUMBER_STR} ASC")
-            };
+            let cursor_clause = event_cursor_condition("", tx_seq, event_seq, descending_order);
+            let partition_clause =
+                self.checkpoint_cursor_condition("", checkpoint, descending_order);
+            let order_clause = event_order("", descending_order);
 
             format!(
                 "
                     SELECT * FROM events \
-                    WHERE {} {} \
+                    WHERE ({}) AND {} AND {} \
                     ORDER BY {} \
                     LIMIT {}
                 ",
-                main_where_clause, cursor_clause, order_clause, limit,
+                main_where_clause, cursor_clause, partition_clause, order_clause, limit,
             )
         };
         tracing::debug!("query events: {}", query);
@@ -1234,6 +2198,245 @@ impl IndexerReader {
             .collect()
     }
 
+    /// Condition on the columns of `events` selecting the events that match `filter`, or `None`
+    /// if no event can match it. The filters combined by `All`, `And`, `Any` and `Or` are joined
+    /// into one condition, so that each of them can still go through an index of the table.
+    /// Composite filters match the same events as when they filter subscriptions.
+    fn event_filter_condition(&self, filter: EventFilter) -> IndexerResult<Option<String>> {
+        let condition = match filter {
+            EventFilter::Package(package_id) => {
+                format!("package = '\\x{}'::bytea", package_id.to_hex())
+            }
+            EventFilter::MoveModule { package, module } => {
+                format!(
+                    "package = '\\x{}'::bytea AND module = '{}'",
+                    package.to_hex(),
+                    module,
+                )
+            }
+            // Event types are stored in their canonical form, with addresses in full.
+            EventFilter::MoveEventType(struct_tag) => {
+                format!("event_type = '{}'", struct_tag.to_canonical_string(true))
+            }
+            EventFilter::MoveEventModule { package, module } => {
+                let package_module_prefix =
+                    format!("{}::{}", package.to_hex_uncompressed(), module);
+                format!("event_type LIKE '{package_module_prefix}::%'")
+            }
+            EventFilter::TimeRange {
+                start_time,
+                end_time,
+            } => {
+                let Some(((start_tx_seq, end_tx_seq), (first_checkpoint, last_checkpoint))) =
+                    self.time_range_bounds(start_time, end_time)?
+                else {
+                    return Ok(None);
+                };
+                let condition = format!(
+                    "{TX_SEQUENCE_NUMBER_STR} >= {start_tx_seq} AND {TX_SEQUENCE_NUMBER_STR} < {end_tx_seq}"
+                );
+                if self.partition_pruning {
+                    format!(
+                        "{condition} AND {CHECKPOINT_SEQUENCE_NUMBER_STR} \
+                        BETWEEN {first_checkpoint} AND {last_checkpoint}"
+                    )
+                } else {
+                    condition
+                }
+            }
+            EventFilter::Checkpoint(checkpoint) => {
+                let checkpoint = checkpoint.min(i64::MAX as u64);
+                format!("{CHECKPOINT_SEQUENCE_NUMBER_STR} = {checkpoint}")
+            }
+            EventFilter::CheckpointRange { from, to } => {
+                // Bounds beyond the range of the column would otherwise be compared as numerics,
+                // which the index of the column does not serve.
+                let (from, to) = (from.min(i64::MAX as u64), to.min(i64::MAX as u64));
+                format!("{CHECKPOINT_SEQUENCE_NUMBER_STR} BETWEEN {from} AND {to}")
+            }
+            EventFilter::Sender(sender) => {
+                format!(
+                    "{TX_SEQUENCE_NUMBER_STR} IN \
+                    (SELECT {TX_SEQUENCE_NUMBER_STR} FROM tx_senders \
+                    WHERE sender = '\\x{}'::bytea)",
+                    Hex::encode(sender.to_vec()),
+                )
+            }
+            EventFilter::All(filters) => {
+                let mut conditions = vec![];
+                for filter in filters {
+                    let Some(condition) = self.event_filter_condition(filter)? else {
+                        return Ok(None);
+                    };
+                    conditions.push(format!("({condition})"));
+                }
+                if conditions.is_empty() {
+                    "TRUE".to_string()
+                } else {
+                    conditions.join(" AND ")
+                }
+            }
+            EventFilter::And(left, right) => {
+                return self.event_filter_condition(EventFilter::All(vec![*left, *right]));
+            }
+            EventFilter::Any(filters) => {
+                let mut conditions = vec![];
+                for filter in filters {
+                    if let Some(condition) = self.event_filter_condition(filter)? {
+                        conditions.push(format!("({condition})"));
+                    }
+                }
+                if conditions.is_empty() {
+                    return Ok(None);
+                }
+                conditions.join(" OR ")
+            }
+            EventFilter::Or(left, right) => {
+                return self.event_filter_condition(EventFilter::Any(vec![*left, *right]));
+            }
+            EventFilter::Transaction(_) | EventFilter::MoveEventField { .. } => {
+                return Err(IndexerError::NotSupportedError(
+                    "This type of EventFilter is not supported.".into(),
+                ));
+            }
+        };
+        Ok(Some(condition))
+    }
+
+    /// Events referencing `object_id`, among those whose object references are indexed, ordered
+    /// as by `query_events`. Both the cursor and the order go through the primary key of
+    /// `event_object_refs`, so that pages only read the references of the object.
+    fn query_events_by_object_impl(
+        &self,
+        object_id: ObjectID,
+        cursor: Option<EventID>,
+        limit: usize,
+        descending_order: bool,
+    ) -> IndexerResult<Vec<MgoEvent>> {
+        self.require_writer_feature(WriterFeature::EventObjectRefs)?;
+        let (tx_seq, event_seq, checkpoint) =
+            self.event_cursor_position(cursor, descending_order)?;
+        let cursor_clause = event_cursor_condition("r.", tx_seq, event_seq, descending_order);
+        let partition_clause = self.checkpoint_cursor_condition("e.", checkpoint, descending_order);
+        let order_clause = event_order("r.", descending_order);
+        let query = format!(
+            "SELECT e.* \
+            FROM event_object_refs r \
+            JOIN events e \
+            ON e.{TX_SEQUENCE_NUMBER_STR} = r.{TX_SEQUENCE_NUMBER_STR} \
+            AND e.{EVENT_SEQUENCE_NUMBER_STR} = r.{EVENT_SEQUENCE_NUMBER_STR} \
+            WHERE r.object_id = '\\x{}'::bytea AND {cursor_clause} AND {partition_clause} \
+            ORDER BY {order_clause} \
+            LIMIT {limit}",
+            Hex::encode(object_id.to_vec()),
+        );
+        tracing::debug!("query events by object: {}", query);
+        let stored_events =
+            self.run_query(|conn| diesel::sql_query(query).load::<StoredEvent>(conn))?;
+        stored_events
+            .into_iter()
+            .map(|se| se.try_into_mgo_event(self))
+            .collect()
+    }
+
+    /// Transaction sequence numbers, end exclusive, and first and last sequence numbers of the
+    /// checkpoints with a timestamp in `[start_time, end_time)`, or `None` if there are no such
+    /// checkpoints. Events share the timestamp of their checkpoint, so this bounds a time range
+    /// query to the events table's primary key and partitions instead of scanning it.
+    fn time_range_bounds(
+        &self,
+        start_time: u64,
+        end_time: u64,
+    ) -> IndexerResult<Option<((i64, i64), (i64, i64))>> {
+        let (start_time, end_time) = (start_time as i64, end_time as i64);
+        // Checkpoint timestamps never decrease, so the first and last checkpoints in the range
+        // are found through the timestamp index.
+        let first_checkpoint = self.run_query(|conn| {
+            checkpoints::table
+                .filter(checkpoints::timestamp_ms.ge(start_time))
+                .order((
+                    checkpoints::timestamp_ms.asc(),
+                    checkpoints::sequence_number.asc(),
+                ))
+                .select((
+                    checkpoints::sequence_number,
+                    checkpoints::network_total_transactions,
+                    checkpoints::tx_digests,
+                ))
+                .first::<(i64, i64, Vec<Option<Vec<u8>>>)>(conn)
+                .optional()
+        })?;
+        let Some((first_checkpoint, network_total_transactions, tx_digests)) = first_checkpoint
+        else {
+            return Ok(None);
+        };
+        let start_tx_seq = network_total_transactions - tx_digests.len() as i64;
+
+        let last_checkpoint = self.run_query(|conn| {
+            checkpoints::table
+                .filter(checkpoints::timestamp_ms.lt(end_time))
+                .order((
+                    checkpoints::timestamp_ms.desc(),
+                    checkpoints::sequence_number.desc(),
+                ))
+                .select((
+                    checkpoints::sequence_number,
+                    checkpoints::network_total_transactions,
+                ))
+                .first::<(i64, i64)>(conn)
+                .optional()
+        })?;
+        Ok(last_checkpoint
+            .filter(|(_, end_tx_seq)| *end_tx_seq > start_tx_seq)
+            .map(|(last_checkpoint, end_tx_seq)| {
+                ((start_tx_seq, end_tx_seq), (first_checkpoint, last_checkpoint))
+            }))
+    }
+
+    /// Dynamic field children loaded by the transaction, at the versions it loaded, or `None`
+    /// if the transaction has not been indexed. Like on the fullnode, a transaction that loaded
+    /// no children yields an empty list.
+    pub fn get_loaded_child_object_versions(
+        &self,
+        digest: TransactionDigest,
+    ) -> IndexerResult<Option<Vec<(ObjectID, SequenceNumber)>>> {
+        self.require_writer_feature(WriterFeature::LoadedChildObjects)?;
+        let digest = digest.into_inner().to_vec();
+        let (is_indexed, stored_children) = self.run_query_repeatable(|conn| {
+            let stored_children = tx_loaded_child_objects::table
+                .filter(tx_loaded_child_objects::transaction_digest.eq(digest.clone()))
+                .order(tx_loaded_child_objects::object_id.asc())
+                .select((
+                    tx_loaded_child_objects::object_id,
+                    tx_loaded_child_objects::object_version,
+                ))
+                .load::<(Vec<u8>, i64)>(conn)?;
+            let is_indexed = !stored_children.is_empty()
+                || transactions::table
+                    .filter(transactions::transaction_digest.eq(digest))
+                    .select(transactions::tx_sequence_number)
+                    .first::<i64>(conn)
+                    .optional()?
+                    .is_some();
+            Ok::<_, diesel::result::Error>((is_indexed, stored_children))
+        })?;
+        if !is_indexed {
+            return Ok(None);
+        }
+        stored_children
+            .into_iter()
+            .map(|(object_id, version)| {
+                let object_id = ObjectID::from_bytes(object_id).map_err(|e| {
+                    IndexerError::PersistentStorageDataCorruptionError(format!(
+                        "Failed to parse loaded child object id: {e}"
+                    ))
+                })?;
+                Ok((object_id, SequenceNumber::from_u64(version as u64)))
+            })
+            .collect::<IndexerResult<Vec<_>>>()
+            .map(Some)
+    }
+
     pub async fn get_transaction_events_in_blocking_task(
         &self,
         digest: TransactionDigest,
@@ -1242,6 +2445,74 @@ impl IndexerReader {
             .await
     }
 
+    fn get_effects_with_input_in_checkpoint_range(
+        &self,
+        first_checkpoint: u64,
+        last_checkpoint: u64,
+    ) -> IndexerResult<Vec<EffectsWithInput>> {
+        let stored_txes = self.run_query(|conn| {
+            transactions::table
+                .filter(
+                    transactions::checkpoint_sequence_number
+                        .between(first_checkpoint as i64, last_checkpoint as i64),
+                )
+                .order(transactions::tx_sequence_number.asc())
+                .load::<StoredTransaction>(conn)
+        })?;
+        stored_txes
+            .iter()
+            .map(StoredTransaction::try_into_effects_with_input)
+            .collect()
+    }
+
+    /// Transactions committed in checkpoints `first_checkpoint..=last_checkpoint`, in
+    /// execution order.
+    pub async fn get_effects_with_input_in_checkpoint_range_in_blocking_task(
+        &self,
+        first_checkpoint: u64,
+        last_checkpoint: u64,
+    ) -> IndexerResult<Vec<EffectsWithInput>> {
+        self.spawn_blocking(move |this| {
+            this.get_effects_with_input_in_checkpoint_range(first_checkpoint, last_checkpoint)
+        })
+        .await
+    }
+
+    fn get_events_in_checkpoint_range(
+        &self,
+        first_checkpoint: u64,
+        last_checkpoint: u64,
+    ) -> IndexerResult<Vec<MgoEvent>> {
+        let stored_events = self.run_query(|conn| {
+            events::table
+                .filter(
+                    events::checkpoint_sequence_number
+                        .between(first_checkpoint as i64, last_checkpoint as i64),
+                )
+                .order((
+                    events::tx_sequence_number.asc(),
+                    events::event_sequence_number.asc(),
+                ))
+                .load::<StoredEvent>(conn)
+        })?;
+        stored_events
+            .into_iter()
+            .map(|se| se.try_into_mgo_event(self))
+            .collect()
+    }
+
+    /// Events emitted in checkpoints `first_checkpoint..=last_checkpoint`, in emission order.
+    pub async fn get_events_in_checkpoint_range_in_blocking_task(
+        &self,
+        first_checkpoint: u64,
+        last_checkpoint: u64,
+    ) -> IndexerResult<Vec<MgoEvent>> {
+        self.spawn_blocking(move |this| {
+            this.get_events_in_checkpoint_range(first_checkpoint, last_checkpoint)
+        })
+        .await
+    }
+
     pub async fn get_dynamic_fields_in_blocking_task(
         &self,
         parent_object_id: ObjectID,
@@ -1260,8 +2531,61 @@ impl IndexerReader {
         cursor: Option<ObjectID>,
         limit: usize,
     ) -> Result<Vec<DynamicFieldInfo>, IndexerError> {
-        let objects = self.get_dynamic_fields_raw(parent_object_id, cursor, limit)?;
+        let objects = self.get_dynamic_fields_raw(parent_object_id, None, cursor, limit)?;
+        self.stored_objects_to_dynamic_field_infos(parent_object_id, objects)
+    }
 
+    /// Up to `limit` dynamic fields of `parent_object_id` whose name is of `name_type`, if set,
+    /// after the field object `cursor`, keyed by their field object IDs. With `show_content`,
+    /// each field comes with its field object.
+    fn get_dynamic_fields_with_options(
+        &self,
+        parent_object_id: ObjectID,
+        name_type: Option<TypeTag>,
+        show_content: bool,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> Result<Vec<(ObjectID, MgoDynamicFieldInfo)>, IndexerError> {
+        let objects =
+            self.get_dynamic_fields_raw(parent_object_id, name_type.as_ref(), cursor, limit)?;
+        let field_ids = objects
+            .iter()
+            .map(|o| {
+                ObjectID::from_bytes(&o.object_id).map_err(|_| {
+                    IndexerError::PersistentStorageDataCorruptionError(format!(
+                        "Can't convert {:?} to object_id",
+                        o.object_id
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // Each row is the field object `get_dynamic_field_object` derives from the name of the
+        // field, so it is read as that would.
+        let field_objects = if show_content {
+            objects
+                .iter()
+                .map(|o| dynamic_field_object_data(o.clone().try_into_object_read(self)?))
+                .collect::<Result<Vec<_>, _>>()?
+        } else {
+            vec![None; objects.len()]
+        };
+        let infos = self.stored_objects_to_dynamic_field_infos(parent_object_id, objects)?;
+        Ok(field_ids
+            .into_iter()
+            .zip(infos.into_iter().zip(field_objects))
+            .map(|(field_id, (info, object))| {
+                let mut field = MgoDynamicFieldInfo::from(info);
+                field.object = object;
+                (field_id, field)
+            })
+            .collect())
+    }
+
+    fn stored_objects_to_dynamic_field_infos(
+        &self,
+        parent_object_id: ObjectID,
+        objects: Vec<StoredObject>,
+    ) -> Result<Vec<DynamicFieldInfo>, IndexerError> {
         if any(objects.iter(), |o| o.df_object_id.is_none()) {
             return Err(IndexerError::PersistentStorageDataCorruptionError(format!(
                 "Dynamic field has empty df_object_id column for parent object {}",
@@ -1307,7 +2631,7 @@ impl IndexerReader {
         limit: usize,
     ) -> Result<Vec<StoredObject>, IndexerError> {
         self.spawn_blocking(move |this| {
-            this.get_dynamic_fields_raw(parent_object_id, cursor, limit)
+            this.get_dynamic_fields_raw(parent_object_id, None, cursor, limit)
         })
         .await
     }
@@ -1315,9 +2639,13 @@ impl IndexerReader {
     fn get_dynamic_fields_raw(
         &self,
         parent_object_id: ObjectID,
+        name_type: Option<&TypeTag>,
         cursor: Option<ObjectID>,
         limit: usize,
     ) -> Result<Vec<StoredObject>, IndexerError> {
+        // Names are stored as the BCS of their type followed by that of their value, so the
+        // fields named by a type are those whose name starts with the BCS of the type.
+        let name_type_prefix = name_type.map(bcs::to_bytes).transpose()?;
         let objects: Vec<StoredObject> = self.run_query(|conn| {
             let mut query = objects::dsl::objects
                 .filter(objects::dsl::owner_type.eq(OwnerType::Object as i16))
@@ -1325,8 +2653,12 @@ impl IndexerReader {
                 .order(objects::dsl::object_id.asc())
                 .limit(limit as i64)
                 .into_boxed();
+            if let Some(prefix) = &name_type_prefix {
+                let condition = format!("substring(df_name from 1 for {}) = ", prefix.len());
+                query = query.filter(sql::<Bool>(&condition).bind::<Bytea, _>(prefix.clone()));
+            }
             if let Some(object_cursor) = cursor {
-                query = query.filter(objects::dsl::object_id.ge(object_cursor.to_vec()));
+                query = query.filter(objects::dsl::object_id.gt(object_cursor.to_vec()));
             }
             query.load::<StoredObject>(conn)
         })?;
@@ -1426,9 +2758,12 @@ impl IndexerReader {
         coin_type: Option<String>,
         cursor: ObjectID,
         limit: usize,
+        min_balance: Option<u64>,
     ) -> Result<Vec<MgoCoin>, IndexerError> {
-        self.spawn_blocking(move |this| this.get_owned_coins(owner, coin_type, cursor, limit))
-            .await
+        self.spawn_blocking(move |this| {
+            this.get_owned_coins(owner, coin_type, cursor, limit, min_balance)
+        })
+        .await
     }
 
     fn get_owned_coins(
@@ -1438,16 +2773,41 @@ impl IndexerReader {
         coin_type: Option<String>,
         cursor: ObjectID,
         limit: usize,
+        min_balance: Option<u64>,
     ) -> Result<Vec<MgoCoin>, IndexerError> {
         let mut query = objects::dsl::objects
             .filter(objects::dsl::owner_type.eq(OwnerType::Address as i16))
             .filter(objects::dsl::owner_id.eq(owner.to_vec()))
-            .filter(objects::dsl::object_id.gt(cursor.to_vec()))
             .into_boxed();
+        // The cursor is matched on its position only, so the page resumes after the cursor coin
+        // even if its balance has since dropped below the minimum.
+        if let Some(min_balance) = min_balance {
+            let min_balance = i64::try_from(min_balance).unwrap_or(i64::MAX);
+            query = query.filter(objects::dsl::coin_balance.ge(min_balance));
+        }
         if let Some(coin_type) = coin_type {
-            query = query.filter(objects::dsl::coin_type.eq(Some(coin_type)));
+            query = query
+                .filter(objects::dsl::coin_type.eq(Some(coin_type)))
+                .filter(objects::dsl::object_id.gt(cursor.to_vec()));
         } else {
             query = query.filter(objects::dsl::coin_type.is_not_null());
+            // Coins of all types are ordered by type first, so the page resumes after the coin
+            // type and ID of the cursor, not after its ID among the coins of every type.
+            let cursor_coin_type = if cursor == ObjectID::ZERO {
+                None
+            } else {
+                self.get_cursor_coin_type(cursor)?
+            };
+            query = match cursor_coin_type {
+                Some(cursor_coin_type) => query.filter(
+                    objects::dsl::coin_type.gt(cursor_coin_type.clone()).or(
+                        objects::dsl::coin_type
+                            .eq(cursor_coin_type)
+                            .and(objects::dsl::object_id.gt(cursor.to_vec())),
+                    ),
+                ),
+                None => query.filter(objects::dsl::object_id.gt(cursor.to_vec())),
+            };
         }
         query = query
             .order((objects::dsl::coin_type.asc(), objects::dsl::object_id.asc()))
@@ -1461,6 +2821,68 @@ impl IndexerReader {
             .collect::<IndexerResult<Vec<_>>>()
     }
 
+    pub async fn get_largest_coins_in_blocking_task(
+        &self,
+        owner: MgoAddress,
+        coin_type: String,
+        limit: usize,
+    ) -> Result<Vec<MgoCoin>, IndexerError> {
+        self.spawn_blocking(move |this| this.get_largest_coins(owner, coin_type, limit))
+            .await
+    }
+
+    /// The `limit` coins of `coin_type` owned by `owner` with the largest balances, coins of
+    /// equal balance in the order of their IDs.
+    fn get_largest_coins(
+        &self,
+        owner: MgoAddress,
+        coin_type: String,
+        limit: usize,
+    ) -> Result<Vec<MgoCoin>, IndexerError> {
+        let stored_objects = self.run_query(|conn| {
+            objects::dsl::objects
+                .filter(objects::dsl::owner_type.eq(OwnerType::Address as i16))
+                .filter(objects::dsl::owner_id.eq(owner.to_vec()))
+                .filter(objects::dsl::coin_type.eq(Some(coin_type)))
+                .order((
+                    objects::dsl::coin_balance.desc(),
+                    objects::dsl::object_id.asc(),
+                ))
+                .limit(limit as i64)
+                .load::<StoredObject>(conn)
+        })?;
+
+        stored_objects
+            .into_iter()
+            .map(|o| o.try_into())
+            .collect::<IndexerResult<Vec<_>>>()
+    }
+
+    /// Coin type of the coin `cursor` points at. Cursors only carry the ID of the coin, which
+    /// may have been spent or transferred since, in which case its type is taken from history.
+    fn get_cursor_coin_type(&self, cursor: ObjectID) -> IndexerResult<Option<String>> {
+        let coin_type = self.run_query(|conn| {
+            objects::dsl::objects
+                .select(objects::dsl::coin_type)
+                .filter(objects::dsl::object_id.eq(cursor.to_vec()))
+                .first::<Option<String>>(conn)
+                .optional()
+        })?;
+        if let Some(Some(coin_type)) = coin_type {
+            return Ok(Some(coin_type));
+        }
+        let coin_type = self.run_query(|conn| {
+            objects_history::dsl::objects_history
+                .select(objects_history::dsl::coin_type)
+                .filter(objects_history::dsl::object_id.eq(cursor.to_vec()))
+                .filter(objects_history::dsl::coin_type.is_not_null())
+                .order(objects_history::dsl::checkpoint_sequence_number.desc())
+                .first::<Option<String>>(conn)
+                .optional()
+        })?;
+        Ok(coin_type.flatten())
+    }
+
     pub async fn get_coin_balances_in_blocking_task(
         &self,
         owner: MgoAddress,
@@ -1622,10 +3044,157 @@ impl IndexerReader {
             .collect())
     }
 
+    /// Coin types following the `cursor` coin type in `order_by` order.
+    pub fn get_coin_types(
+        &self,
+        cursor: Option<String>,
+        limit: usize,
+        order_by: CoinTypeOrder,
+    ) -> IndexerResult<Vec<CoinTypeInfo>> {
+        self.require_writer_feature(WriterFeature::CoinTypes)?;
+        let cursor = match cursor {
+            Some(cursor) => Some(
+                self.run_query(|conn| {
+                    coin_types::table
+                        .filter(coin_types::coin_type.eq(&cursor))
+                        .first::<StoredCoinType>(conn)
+                        .optional()
+                })?
+                .ok_or_else(|| {
+                    IndexerError::InvalidArgumentError(format!(
+                        "Cursor {cursor} is not a known coin type"
+                    ))
+                })?,
+            ),
+            None => None,
+        };
+        let stored_coin_types = self.run_query(|conn| {
+            let mut query = coin_types::table.into_boxed();
+            match order_by {
+                CoinTypeOrder::FirstSeen => {
+                    if let Some(cursor) = cursor {
+                        query = query.filter(
+                            coin_types::first_seen_checkpoint
+                                .gt(cursor.first_seen_checkpoint)
+                                .or(coin_types::first_seen_checkpoint
+                                    .eq(cursor.first_seen_checkpoint)
+                                    .and(coin_types::coin_type.gt(cursor.coin_type))),
+                        );
+                    }
+                    query = query.order_by((
+                        coin_types::first_seen_checkpoint.asc(),
+                        coin_types::coin_type.asc(),
+                    ));
+                }
+                CoinTypeOrder::Holders => {
+                    if let Some(cursor) = cursor {
+                        query = query.filter(
+                            coin_types::holder_count
+                                .lt(cursor.holder_count)
+                                .or(coin_types::holder_count
+                                    .eq(cursor.holder_count)
+                                    .and(coin_types::coin_type.gt(cursor.coin_type))),
+                        );
+                    }
+                    query = query.order_by((
+                        coin_types::holder_count.desc(),
+                        coin_types::coin_type.asc(),
+                    ));
+                }
+            }
+            query.limit(limit as i64).load::<StoredCoinType>(conn)
+        })?;
+        stored_coin_types
+            .into_iter()
+            .map(CoinTypeInfo::try_from)
+            .collect()
+    }
+
+    pub fn get_active_address_stats(
+        &self,
+        from_day: u64,
+        to_day: u64,
+    ) -> IndexerResult<ActiveAddressStats> {
+        self.require_writer_feature(WriterFeature::ActiveAddressSketches)?;
+        let stored_sketches = self.run_query(|conn| {
+            active_address_sketches::table
+                .filter(active_address_sketches::day.between(from_day as i64, to_day as i64))
+                .load::<StoredActiveAddressSketch>(conn)
+        })?;
+        // Every day has one sketch per cohort active on it, which are merged into the day's.
+        let mut daily_sketches: BTreeMap<i64, ActiveAddressSketch> = BTreeMap::new();
+        let mut range_sketch = ActiveAddressSketch::default();
+        for stored in stored_sketches {
+            let sketch = ActiveAddressSketch::from_bytes(&stored.sketch)?;
+            range_sketch.merge(&sketch);
+            daily_sketches.entry(stored.day).or_default().merge(&sketch);
+        }
+        let daily = (from_day..=to_day)
+            .map(|day| DailyActiveAddresses {
+                day,
+                active_addresses: daily_sketches
+                    .get(&(day as i64))
+                    .map_or(0, |sketch| sketch.count()),
+            })
+            .collect();
+        Ok(ActiveAddressStats {
+            from_day,
+            to_day,
+            daily,
+            active_addresses: range_sketch.count(),
+        })
+    }
+
+    pub fn get_retention(
+        &self,
+        cohort_day: u64,
+        horizon_days: u64,
+    ) -> IndexerResult<CohortRetention> {
+        self.require_writer_feature(WriterFeature::ActiveAddressSketches)?;
+        let to_day = cohort_day.saturating_add(horizon_days);
+        let (cohort_size, stored_sketches) = self.run_query_repeatable(|conn| {
+            let cohort_size = address_cohorts::table
+                .filter(address_cohorts::cohort_day.eq(cohort_day as i64))
+                .count()
+                .get_result::<i64>(conn)?;
+            let stored_sketches = active_address_sketches::table
+                .filter(active_address_sketches::cohort_day.eq(cohort_day as i64))
+                .filter(active_address_sketches::day.between(cohort_day as i64, to_day as i64))
+                .load::<StoredActiveAddressSketch>(conn)?;
+            Ok::<_, diesel::result::Error>((cohort_size as u64, stored_sketches))
+        })?;
+        let mut daily_active = HashMap::new();
+        for stored in stored_sketches {
+            let sketch = ActiveAddressSketch::from_bytes(&stored.sketch)?;
+            // Estimates of large cohorts may slightly exceed the exact cohort size.
+            daily_active.insert(stored.day, sketch.count().min(cohort_size));
+        }
+        let retention = (cohort_day..=to_day)
+            .map(|day| {
+                let active_addresses = daily_active.get(&(day as i64)).copied().unwrap_or(0);
+                DailyRetention {
+                    day,
+                    active_addresses,
+                    fraction: if cohort_size == 0 {
+                        0.0
+                    } else {
+                        active_addresses as f64 / cohort_size as f64
+                    },
+                }
+            })
+            .collect();
+        Ok(CohortRetention {
+            cohort_day,
+            cohort_size,
+            retention,
+        })
+    }
+
     pub(crate) async fn get_display_fields(
         &self,
         original_object: &mgo_types::object::Object,
         original_layout: &Option<MoveStructLayout>,
+        limits: &DisplayRenderLimits,
     ) -> Result<DisplayFieldsResponse, IndexerError> {
         let (object_type, layout) = if let Some((object_type, layout)) =
             mgo_json_rpc::read_api::get_object_type_and_struct(original_object, original_layout)
@@ -1640,8 +3209,12 @@ impl IndexerReader {
         };
 
         if let Some(display_object) = self.get_display_object_by_type(&object_type).await? {
-            return mgo_json_rpc::read_api::get_rendered_fields(display_object.fields, &layout)
-                .map_err(|e| IndexerError::GenericError(e.to_string()));
+            return mgo_json_rpc::read_api::get_rendered_fields_with_limits(
+                display_object.fields,
+                &layout,
+                limits,
+            )
+            .map_err(|e| IndexerError::GenericError(e.to_string()));
         }
         Ok(DisplayFieldsResponse {
             data: None,
@@ -1701,6 +3274,112 @@ impl IndexerReader {
         Ok(TreasuryCap::try_from(treasury_cap_obj_object)?.total_supply)
     }
 
+    pub fn get_shared_object_stats(
+        &self,
+        object_id: ObjectID,
+        window_checkpoints: u64,
+    ) -> Result<SharedObjectStats, IndexerError> {
+        let Some(stored_object) = self.get_object_from_db(&object_id, None)? else {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Object {object_id} does not exist"
+            )));
+        };
+        if stored_object.owner_type != OwnerType::Shared as i16 {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Object {object_id} is not a shared object"
+            )));
+        }
+
+        let to_checkpoint = self.get_latest_checkpoint_from_db()?.sequence_number as u64;
+        let from_checkpoint = to_checkpoint.saturating_sub(window_checkpoints.saturating_sub(1));
+        // The first transaction of a checkpoint has sequence number
+        // `network_total_transactions - cardinality(tx_digests)`.
+        let query = format!(
+            "
+            SELECT t.{TX_SEQUENCE_NUMBER_STR}, t.checkpoint_sequence_number, \
+            t.{TX_SEQUENCE_NUMBER_STR} - (c.network_total_transactions - CARDINALITY(c.tx_digests)) AS position_in_checkpoint \
+            FROM tx_input_objects i \
+            JOIN transactions t ON t.{TX_SEQUENCE_NUMBER_STR} = i.{TX_SEQUENCE_NUMBER_STR} \
+            JOIN checkpoints c ON c.sequence_number = t.checkpoint_sequence_number \
+            WHERE i.object_id = $1 \
+            AND t.checkpoint_sequence_number BETWEEN $2 AND $3 \
+            ORDER BY t.{TX_SEQUENCE_NUMBER_STR} ASC
+        "
+        );
+        let positions = self.run_query(|conn| {
+            diesel::sql_query(query)
+                .bind::<Bytea, _>(object_id.to_vec())
+                .bind::<BigInt, _>(from_checkpoint as i64)
+                .bind::<BigInt, _>(to_checkpoint as i64)
+                .load::<TxCheckpointPosition>(conn)
+        })?;
+
+        let mut counts = BTreeMap::<u64, u64>::new();
+        for p in &positions {
+            *counts
+                .entry(p.checkpoint_sequence_number as u64)
+                .or_default() += 1;
+        }
+        let transactions_per_checkpoint = counts
+            .into_iter()
+            .map(
+                |(checkpoint, transaction_count)| CheckpointTransactionCount {
+                    checkpoint,
+                    transaction_count,
+                },
+            )
+            .collect();
+        let average_position_in_checkpoint = (!positions.is_empty()).then(|| {
+            positions
+                .iter()
+                .map(|p| p.position_in_checkpoint as f64)
+                .sum::<f64>()
+                / positions.len() as f64
+        });
+        let max_position_in_checkpoint = positions
+            .iter()
+            .map(|p| p.position_in_checkpoint as u64)
+            .max();
+
+        let assigned_version_lag = match positions.last() {
+            Some(latest) => {
+                let raw_effects = self.run_query(|conn| {
+                    transactions::table
+                        .filter(transactions::tx_sequence_number.eq(latest.tx_sequence_number))
+                        .select(transactions::raw_effects)
+                        .first::<Vec<u8>>(conn)
+                })?;
+                let effects: TransactionEffects = bcs::from_bytes(&raw_effects).map_err(|e| {
+                    IndexerError::PersistentStorageDataCorruptionError(format!(
+                        "Can't convert raw_effects of tx {} into TransactionEffects. Error: {e}",
+                        latest.tx_sequence_number
+                    ))
+                })?;
+                effects
+                    .input_shared_objects()
+                    .into_iter()
+                    .map(|o| o.id_and_version())
+                    .find(|(id, _)| *id == object_id)
+                    .map(|(_, assigned_version)| {
+                        (stored_object.object_version as u64)
+                            .saturating_sub(assigned_version.value())
+                    })
+            }
+            None => None,
+        };
+
+        Ok(SharedObjectStats {
+            object_id,
+            from_checkpoint,
+            to_checkpoint,
+            total_transactions: positions.len() as u64,
+            transactions_per_checkpoint,
+            average_position_in_checkpoint,
+            max_position_in_checkpoint,
+            assigned_version_lag,
+        })
+    }
+
     pub fn get_consistent_read_range(&self) -> Result<(i64, i64), IndexerError> {
         let latest_checkpoint_sequence = self
             .run_query(|conn| {
@@ -1727,6 +3406,731 @@ impl IndexerReader {
     }
 }
 
+// Impl for paginated queries, shared by the JSON-RPC server and embedded readers.
+// Limits are capped like the RPC `limit` parameter, and a `None` limit means the default page size.
+impl IndexerReader {
+    /// Objects owned by `address`, ordered by object ID and starting after `cursor`.
+    /// Display fields are rendered under `display_limits` when requested in the query options.
+    pub async fn get_owned_objects_page(
+        &self,
+        address: MgoAddress,
+        query: Option<MgoObjectResponseQuery>,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+        display_limits: &DisplayRenderLimits,
+    ) -> Result<ObjectsPage, IndexerError> {
+        let limit = cap_page_limit(limit);
+        if limit == 0 {
+            return Ok(ObjectsPage::empty());
+        }
+        let MgoObjectResponseQuery { filter, options } = query.unwrap_or_default();
+        let options = options.unwrap_or_default();
+        let objects = self
+            .get_owned_objects_in_blocking_task(address, filter, cursor, limit + 1)
+            .await?;
+        let mut objects = self
+            .spawn_blocking(move |this| {
+                objects
+                    .into_iter()
+                    .map(|object| object.try_into_object_read(&this))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .await?;
+        let has_next_page = objects.len() > limit;
+        objects.truncate(limit);
+
+        let next_cursor = objects.last().map(|o_read| o_read.object_id());
+        let data = self
+            .render_object_reads(objects, options, display_limits)
+            .await?;
+
+        Ok(Page {
+            data,
+            next_cursor,
+            has_next_page,
+            applied: None,
+        })
+    }
+
+    /// Objects owned by `address`, ordered by the checkpoint that last modified them and then by
+    /// object ID, starting after `cursor`. Only objects modified after `modified_after` are
+    /// returned when it is set.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_owned_objects_by_checkpoint_page(
+        &self,
+        address: MgoAddress,
+        query: Option<MgoObjectResponseQuery>,
+        modified_after: Option<CheckpointSequenceNumber>,
+        cursor: Option<ModifiedObjectCursor>,
+        limit: Option<usize>,
+        descending_order: bool,
+        display_limits: &DisplayRenderLimits,
+    ) -> Result<ModifiedObjectsPage, IndexerError> {
+        let limit = cap_page_limit(limit);
+        if limit == 0 {
+            return Ok(ModifiedObjectsPage::empty());
+        }
+        let MgoObjectResponseQuery { filter, options } = query.unwrap_or_default();
+        let options = options.unwrap_or_default();
+        let mut objects = self
+            .get_owned_objects_by_checkpoint_in_blocking_task(
+                address,
+                filter,
+                modified_after,
+                cursor,
+                limit + 1,
+                descending_order,
+            )
+            .await?;
+        let has_next_page = objects.len() > limit;
+        objects.truncate(limit);
+
+        let next_cursor = objects
+            .last()
+            .map(|o| -> Result<_, IndexerError> {
+                Ok(ModifiedObjectCursor {
+                    checkpoint: o.checkpoint_sequence_number as CheckpointSequenceNumber,
+                    object_id: ObjectID::from_bytes(&o.object_id).map_err(|e| {
+                        IndexerError::PersistentStorageDataCorruptionError(e.to_string())
+                    })?,
+                })
+            })
+            .transpose()?;
+        let objects = self
+            .spawn_blocking(move |this| {
+                objects
+                    .into_iter()
+                    .map(|object| object.try_into_object_read(&this))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .await?;
+        let data = self
+            .render_object_reads(objects, options, display_limits)
+            .await?;
+
+        Ok(Page {
+            data,
+            next_cursor,
+            has_next_page,
+            applied: None,
+        })
+    }
+
+    /// Objects owned by any of `addresses`, ordered by the position of their owner in
+    /// `addresses` and then by object ID, starting after `cursor`. The owner is always included
+    /// in the returned objects so that callers can tell the owners apart.
+    pub async fn get_owned_objects_multi_page(
+        &self,
+        addresses: Vec<MgoAddress>,
+        query: Option<MgoObjectResponseQuery>,
+        cursor: Option<MultiOwnerObjectCursor>,
+        limit: Option<usize>,
+        display_limits: &DisplayRenderLimits,
+    ) -> Result<MultiOwnerObjectsPage, IndexerError> {
+        if addresses.len() > QUERY_MAX_OWNED_OBJECTS_ADDRESSES {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Cannot list the objects of more than {QUERY_MAX_OWNED_OBJECTS_ADDRESSES} \
+                 addresses at once, got {}",
+                addresses.len()
+            )));
+        }
+        if addresses.iter().collect::<HashSet<_>>().len() != addresses.len() {
+            return Err(IndexerError::InvalidArgumentError(
+                "Addresses must not contain duplicates".into(),
+            ));
+        }
+        if cursor.is_some_and(|c| c.address_index >= addresses.len()) {
+            return Err(IndexerError::InvalidArgumentError(
+                "Cursor address index is out of range of the addresses".into(),
+            ));
+        }
+        let limit = cap_page_limit(limit);
+        if limit == 0 || addresses.is_empty() {
+            return Ok(MultiOwnerObjectsPage::empty());
+        }
+        let MgoObjectResponseQuery { filter, options } = query.unwrap_or_default();
+        let options = options.unwrap_or_default().with_owner();
+        let mut objects = self
+            .get_owned_objects_multi_in_blocking_task(addresses.clone(), filter, cursor, limit + 1)
+            .await?;
+        let has_next_page = objects.len() > limit;
+        objects.truncate(limit);
+
+        let next_cursor = objects
+            .last()
+            .map(|o| -> Result<_, IndexerError> {
+                let address_index = addresses
+                    .iter()
+                    .position(|a| o.owner_id.as_deref() == Some(a.as_ref()))
+                    .ok_or_else(|| {
+                        IndexerError::PersistentStorageDataCorruptionError(format!(
+                            "Object owner {:?} is not one of the queried addresses",
+                            o.owner_id
+                        ))
+                    })?;
+                Ok(MultiOwnerObjectCursor {
+                    address_index,
+                    object_id: ObjectID::from_bytes(&o.object_id).map_err(|e| {
+                        IndexerError::PersistentStorageDataCorruptionError(e.to_string())
+                    })?,
+                })
+            })
+            .transpose()?;
+        let objects = self
+            .spawn_blocking(move |this| {
+                objects
+                    .into_iter()
+                    .map(|object| object.try_into_object_read(&this))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .await?;
+        let data = self
+            .render_object_reads(objects, options, display_limits)
+            .await?;
+
+        Ok(Page {
+            data,
+            next_cursor,
+            has_next_page,
+            applied: None,
+        })
+    }
+
+    /// Objects owned by `address` at the end of `checkpoint`, ordered by object ID and starting
+    /// after `cursor`. The history of an indexed checkpoint never changes, so neither do the
+    /// pages of its snapshot.
+    pub async fn get_owned_objects_at_checkpoint_page(
+        &self,
+        address: MgoAddress,
+        checkpoint: CheckpointSequenceNumber,
+        query: Option<MgoObjectResponseQuery>,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+        display_limits: &DisplayRenderLimits,
+    ) -> Result<ObjectsPage, IndexerError> {
+        let limit = cap_page_limit(limit);
+        if limit == 0 {
+            return Ok(ObjectsPage::empty());
+        }
+        let MgoObjectResponseQuery { filter, options } = query.unwrap_or_default();
+        let options = options.unwrap_or_default();
+        let mut objects = self
+            .spawn_blocking(move |this| {
+                this.get_owned_objects_at_checkpoint(address, checkpoint, filter, cursor, limit + 1)
+            })
+            .await?;
+        let has_next_page = objects.len() > limit;
+        objects.truncate(limit);
+
+        let objects = self
+            .spawn_blocking(move |this| {
+                objects
+                    .into_iter()
+                    .map(|object| StoredObject::try_from(object)?.try_into_object_read(&this))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .await?;
+        let next_cursor = objects.last().map(|o_read| o_read.object_id());
+        let data = self
+            .render_object_reads(objects, options, display_limits)
+            .await?;
+
+        Ok(Page {
+            data,
+            next_cursor,
+            has_next_page,
+            applied: None,
+        })
+    }
+
+    /// Reads of the objects with the given IDs, fetched with a single query and returned in the
+    /// order of `object_ids`. IDs that are not in the objects table come back as `NotExists` in
+    /// their position rather than being dropped.
+    pub async fn multi_get_object_reads_in_blocking_task(
+        &self,
+        object_ids: Vec<ObjectID>,
+    ) -> Result<Vec<ObjectRead>, IndexerError> {
+        if object_ids.len() > *QUERY_MAX_RESULT_LIMIT {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Cannot fetch more than {} objects in one request, got {}",
+                *QUERY_MAX_RESULT_LIMIT,
+                object_ids.len()
+            )));
+        }
+        self.spawn_blocking(move |this| {
+            let stored_objects: HashMap<_, _> = this
+                .multi_get_objects_impl(object_ids.clone())?
+                .into_iter()
+                .map(|object| (object.object_id.clone(), object))
+                .collect();
+            object_ids
+                .into_iter()
+                .map(|id| match stored_objects.get(&id.to_vec()) {
+                    Some(object) => object.clone().try_into_object_read(&this),
+                    None => Ok(ObjectRead::NotExists(id)),
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .await
+    }
+
+    /// Responses for the objects with the given IDs, in the order of `object_ids`, see
+    /// [`Self::multi_get_object_reads_in_blocking_task`].
+    pub async fn multi_get_objects_in_blocking_task(
+        &self,
+        object_ids: Vec<ObjectID>,
+        options: MgoObjectDataOptions,
+        display_limits: &DisplayRenderLimits,
+    ) -> Result<Vec<MgoObjectResponse>, IndexerError> {
+        let objects = self
+            .multi_get_object_reads_in_blocking_task(object_ids)
+            .await?;
+        for object in &objects {
+            if let ObjectRead::Exists(_, _, Some(layout)) = object {
+                options
+                    .check_field_mask(layout)
+                    .map_err(|e| IndexerError::InvalidArgumentError(e.to_string()))?;
+            }
+        }
+        let show_previous_transaction_details = options.show_previous_transaction_details;
+        let mut responses = self
+            .render_object_reads(objects, options, display_limits)
+            .await?;
+        if show_previous_transaction_details {
+            let objects = responses
+                .iter_mut()
+                .filter_map(|r| r.data.as_mut())
+                .collect();
+            self.add_previous_transaction_details(objects).await?;
+        }
+        Ok(responses)
+    }
+
+    /// Adds the details of their previous transaction to `objects`, looking up each transaction
+    /// once. Objects whose previous transaction was pruned are left without details.
+    pub async fn add_previous_transaction_details(
+        &self,
+        objects: Vec<&mut MgoObjectData>,
+    ) -> Result<(), IndexerError> {
+        let digests = objects
+            .iter()
+            .filter_map(|object| object.previous_transaction)
+            .unique()
+            .collect::<Vec<_>>();
+        if digests.is_empty() {
+            return Ok(());
+        }
+        let details = self
+            .spawn_blocking(move |this| {
+                this.multi_get_transactions(&digests)?
+                    .into_iter()
+                    .map(|stored| {
+                        let details = stored.try_into_previous_transaction_details()?;
+                        Ok((stored.transaction_digest, details))
+                    })
+                    .collect::<Result<HashMap<_, _>, IndexerError>>()
+            })
+            .await?;
+        for object in objects {
+            object.previous_transaction_details = object
+                .previous_transaction
+                .and_then(|digest| details.get(digest.inner().as_slice()).cloned());
+        }
+        Ok(())
+    }
+
+    /// Converts the field object of a dynamic field into its response.
+    async fn render_dynamic_field_object(
+        &self,
+        object_read: ObjectRead,
+        options: MgoObjectDataOptions,
+        display_limits: &DisplayRenderLimits,
+    ) -> Result<MgoObjectResponse, IndexerError> {
+        let mut responses = self
+            .render_object_reads(vec![object_read], options, display_limits)
+            .await?;
+        Ok(responses.remove(0))
+    }
+
+    /// Converts `objects` into responses, rendering display fields in parallel if requested.
+    /// Display queries take blocking task permits like any other, and the whole page fails if
+    /// the reader is overloaded rather than come back without display fields.
+    async fn render_object_reads(
+        &self,
+        objects: Vec<ObjectRead>,
+        options: MgoObjectDataOptions,
+        display_limits: &DisplayRenderLimits,
+    ) -> Result<Vec<MgoObjectResponse>, IndexerError> {
+        let mut parallel_tasks = vec![];
+        for o in objects {
+            let inner_clone = self.clone();
+            let options = options.clone();
+            let display_limits = display_limits.clone();
+            parallel_tasks.push(tokio::task::spawn(async move {
+                match o {
+                    ObjectRead::NotExists(id) => Ok(MgoObjectResponse::new_with_error(
+                        MgoObjectResponseError::NotExists { object_id: id },
+                    )),
+                    ObjectRead::Exists(object_ref, o, layout) => {
+                        if options.show_display {
+                            match inner_clone
+                                .get_display_fields(&o, &layout, &display_limits)
+                                .await
+                            {
+                                Ok(rendered_fields) => Ok(MgoObjectResponse::new_with_data(
+                                    (object_ref, o, layout, options, Some(rendered_fields))
+                                        .try_into()?,
+                                )),
+                                Err(e @ IndexerError::ServerOverloaded(_)) => Err(e),
+                                Err(e) => Ok(MgoObjectResponse::new(
+                                    Some((object_ref, o, layout, options, None).try_into()?),
+                                    Some(MgoObjectResponseError::DisplayError {
+                                        error: e.to_string(),
+                                    }),
+                                )),
+                            }
+                        } else {
+                            Ok(MgoObjectResponse::new_with_data(
+                                (object_ref, o, layout, options, None).try_into()?,
+                            ))
+                        }
+                    }
+                    ObjectRead::Deleted((object_id, version, digest)) => Ok(
+                        MgoObjectResponse::new_with_error(MgoObjectResponseError::Deleted {
+                            object_id,
+                            version,
+                            digest,
+                        }),
+                    ),
+                }
+            }));
+        }
+        Ok(futures::future::join_all(parallel_tasks)
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .collect::<Result<Vec<_>, IndexerError>>()?)
+    }
+
+    /// Transaction blocks matching `query`, starting after the transaction `cursor`.
+    pub async fn query_transaction_blocks_page(
+        &self,
+        query: MgoTransactionBlockResponseQuery,
+        cursor: Option<TransactionDigest>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> Result<TransactionBlocksPage, IndexerError> {
+        let limit = cap_page_limit(limit);
+        if limit == 0 {
+            return Ok(TransactionBlocksPage::empty());
+        }
+        let mut results = self
+            .query_transaction_blocks_in_blocking_task(
+                query.filter,
+                query.options.unwrap_or_default(),
+                cursor,
+                limit + 1,
+                descending_order.unwrap_or(false),
+            )
+            .await?;
+
+        let has_next_page = results.len() > limit;
+        results.truncate(limit);
+        let next_cursor = results.last().map(|o| o.digest);
+        Ok(Page {
+            data: results,
+            next_cursor,
+            has_next_page,
+            applied: None,
+        })
+    }
+
+    /// Events matching `query`, starting after the event `cursor`.
+    pub async fn query_events_page(
+        &self,
+        query: EventFilter,
+        cursor: Option<EventID>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> Result<EventPage, IndexerError> {
+        self.event_denylist.load().check_filter(&query)?;
+        let limit = cap_page_limit(limit);
+        if limit == 0 {
+            return Ok(EventPage::empty());
+        }
+        let descending_order = descending_order.unwrap_or(false);
+        let mut results = self
+            .query_events_in_blocking_task(query, cursor, limit + 1, descending_order)
+            .await?;
+
+        let has_next_page = results.len() > limit;
+        results.truncate(limit);
+        let next_cursor = results.last().map(|o| o.id);
+        Ok(Page {
+            data: results,
+            next_cursor,
+            has_next_page,
+            applied: None,
+        })
+    }
+
+    /// Hands the events matching `filter` after the event `from_cursor` to `f` in ascending
+    /// order, until `f` breaks or the events run out. Events are fetched
+    /// [`STREAM_BATCH_SIZE`] at a time, so that streams of any length take bounded memory, and
+    /// none are fetched past the batch `f` breaks in. Returns the cursor to resume from.
+    /// Queries block, so async callers stream through [`Self::spawn_blocking`].
+    pub fn stream_events(
+        &self,
+        filter: EventFilter,
+        from_cursor: Option<EventID>,
+        f: impl FnMut(MgoEvent) -> ControlFlow<()>,
+    ) -> IndexerResult<Option<EventID>> {
+        self.event_denylist.load().check_filter(&filter)?;
+        stream_in_batches(
+            STREAM_BATCH_SIZE,
+            from_cursor,
+            |cursor, limit| self.query_events_impl(filter.clone(), cursor, limit, false),
+            |event| event.id,
+            f,
+        )
+    }
+
+    /// Hands the transaction blocks matching `filter` after the transaction `from_cursor` to
+    /// `f` in ascending order, like [`Self::stream_events`].
+    pub fn stream_transactions(
+        &self,
+        filter: Option<TransactionFilter>,
+        options: mgo_json_rpc_types::MgoTransactionBlockResponseOptions,
+        from_cursor: Option<TransactionDigest>,
+        f: impl FnMut(MgoTransactionBlockResponse) -> ControlFlow<()>,
+    ) -> IndexerResult<Option<TransactionDigest>> {
+        stream_in_batches(
+            STREAM_BATCH_SIZE,
+            from_cursor,
+            |cursor, limit| {
+                let (filter, options) = (filter.clone(), options.clone());
+                self.query_transaction_blocks_impl(filter, options, cursor, limit, false)
+            },
+            |tx| tx.digest,
+            f,
+        )
+    }
+
+    /// Dynamic fields of `parent_object_id`, ordered by field object ID and starting after `cursor`.
+    /// Pages including the field objects end early once these take more than
+    /// [`mgo_json_rpc_types::DYNAMIC_FIELD_CONTENT_MAX_PAGE_BYTES`].
+    pub async fn get_dynamic_fields_page(
+        &self,
+        parent_object_id: ObjectID,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+        options: Option<DynamicFieldsOptions>,
+    ) -> Result<DynamicFieldPage, IndexerError> {
+        let limit = cap_page_limit(limit);
+        if limit == 0 {
+            return Ok(DynamicFieldPage::empty());
+        }
+        let DynamicFieldsOptions {
+            name_type,
+            show_content,
+        } = options.unwrap_or_default();
+        let name_type = name_type
+            .map(|name_type| {
+                parse_mgo_type_tag(&name_type).map_err(|e| {
+                    IndexerError::InvalidArgumentError(format!(
+                        "Invalid name type {name_type}: {e}"
+                    ))
+                })
+            })
+            .transpose()?;
+        let results = self
+            .spawn_blocking(move |this| {
+                this.get_dynamic_fields_with_options(
+                    parent_object_id,
+                    name_type,
+                    show_content,
+                    cursor,
+                    limit + 1,
+                )
+            })
+            .await?;
+
+        let mut has_next_page = results.len() > limit;
+        let (mut field_ids, mut fields): (Vec<_>, Vec<_>) = results.into_iter().take(limit).unzip();
+        if show_content {
+            let kept = dynamic_fields_within_content_budget(&fields);
+            has_next_page |= kept < fields.len();
+            fields.truncate(kept);
+            field_ids.truncate(kept);
+        }
+        // Fields are ordered by their field object IDs, which dynamic object fields do not list.
+        let next_cursor = field_ids.last().copied();
+        Ok(Page {
+            data: fields,
+            next_cursor,
+            has_next_page,
+            applied: None,
+        })
+    }
+
+    /// The dynamic field or dynamic object field of `parent_object_id` named `name`, with the
+    /// full content of its field object unless `options` say otherwise.
+    pub async fn get_dynamic_field_object(
+        &self,
+        parent_object_id: ObjectID,
+        name: DynamicFieldName,
+        options: Option<MgoObjectDataOptions>,
+        display_limits: &DisplayRenderLimits,
+    ) -> Result<MgoObjectResponse, IndexerError> {
+        let options = options.unwrap_or_else(MgoObjectDataOptions::full_content);
+        let name_bcs_value = self
+            .bcs_name_from_dynamic_field_name_in_blocking_task(&name)
+            .await?;
+
+        // Try as Dynamic Field
+        let id = mgo_types::dynamic_field::derive_dynamic_field_id(
+            parent_object_id,
+            &name.type_,
+            &name_bcs_value,
+        )
+        .expect("deriving dynamic field id can't fail");
+        let object_read = self.get_object_read_in_blocking_task(id).await?;
+        if let ObjectRead::Exists(..) = object_read {
+            return self
+                .render_dynamic_field_object(object_read, options, display_limits)
+                .await;
+        }
+
+        // Try as Dynamic Field Object
+        let dynamic_object_field_struct =
+            DynamicFieldInfo::dynamic_object_field_wrapper(name.type_);
+        let dynamic_object_field_type = TypeTag::Struct(Box::new(dynamic_object_field_struct));
+        let dynamic_object_field_id = mgo_types::dynamic_field::derive_dynamic_field_id(
+            parent_object_id,
+            &dynamic_object_field_type,
+            &name_bcs_value,
+        )
+        .expect("deriving dynamic field id can't fail");
+        let object_read = self
+            .get_object_read_in_blocking_task(dynamic_object_field_id)
+            .await?;
+        if let ObjectRead::Exists(..) = object_read {
+            return self
+                .render_dynamic_field_object(object_read, options, display_limits)
+                .await;
+        }
+
+        Ok(MgoObjectResponse::new_with_error(
+            MgoObjectResponseError::DynamicFieldNotFound { parent_object_id },
+        ))
+    }
+
+    /// The values stored under `keys`, given as BCS bytes, in the Move table `parent_object_id`
+    /// whose keys are of type `key_type` and values of type `value_type`. The `Field<K, V>`
+    /// objects holding the entries are read in a single query, and entries come back in the
+    /// order of `keys`, without a value for keys the table has no entry for.
+    pub async fn get_table_values_in_blocking_task(
+        &self,
+        parent_object_id: ObjectID,
+        key_type: TypeTag,
+        value_type: TypeTag,
+        keys: Vec<Vec<u8>>,
+    ) -> Result<Vec<TableValue>, IndexerError> {
+        if keys.len() > *QUERY_MAX_RESULT_LIMIT {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Cannot read more than {} table values in one request, got {}",
+                *QUERY_MAX_RESULT_LIMIT,
+                keys.len()
+            )));
+        }
+        self.spawn_blocking(move |this| {
+            this.get_table_values(parent_object_id, key_type, value_type, keys)
+        })
+        .await
+    }
+
+    fn get_table_values(
+        &self,
+        parent_object_id: ObjectID,
+        key_type: TypeTag,
+        value_type: TypeTag,
+        keys: Vec<Vec<u8>>,
+    ) -> Result<Vec<TableValue>, IndexerError> {
+        let field_type = DynamicFieldInfo::dynamic_field_type(key_type.clone(), value_type);
+        let field_ids = keys
+            .iter()
+            .map(|key| {
+                mgo_types::dynamic_field::derive_dynamic_field_id(parent_object_id, &key_type, key)
+                    .map_err(|e| {
+                        IndexerError::InvalidArgumentError(format!(
+                            "Cannot derive the field ID of key {}: {e}",
+                            Base64::encode(key)
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut stored_objects: HashMap<_, _> = self
+            .multi_get_objects_impl(field_ids.clone())?
+            .into_iter()
+            .map(|object| (object.object_id.clone(), object))
+            .collect();
+
+        keys.into_iter()
+            .zip(field_ids)
+            .map(|(key, field_id)| {
+                let value = stored_objects
+                    .remove(&field_id.to_vec())
+                    .map(|stored| self.decode_table_value(stored, &field_type))
+                    .transpose()
+                    .context(&format!(
+                        "Failed to decode the value of key {}",
+                        Base64::encode(&key)
+                    ))?;
+                Ok(TableValue {
+                    key,
+                    field_id,
+                    value,
+                })
+            })
+            .collect()
+    }
+
+    /// Decodes the value held by `stored`, the `Field<K, V>` object of a table entry.
+    fn decode_table_value(
+        &self,
+        stored: StoredObject,
+        field_type: &StructTag,
+    ) -> Result<TableEntryValue, IndexerError> {
+        let object: Object = stored.try_into()?;
+        let Some(move_object) = object.data.try_as_move() else {
+            return Err(IndexerError::ResolveMoveStructError(format!(
+                "Object {} is a package",
+                object.id()
+            )));
+        };
+        if object.struct_tag().as_ref() != Some(field_type) {
+            return Err(IndexerError::ResolveMoveStructError(format!(
+                "Expected an object of type {field_type}, found {}",
+                move_object.type_()
+            )));
+        }
+        let layout = object.get_layout(self)?;
+        let move_struct = move_object.to_move_struct(&layout)?;
+        let value = extract_field_from_move_struct(&move_struct, "value").ok_or_else(|| {
+            IndexerError::ResolveMoveStructError(format!(
+                "Object {} has no value field",
+                object.id()
+            ))
+        })?;
+        let bcs = bcs::to_bytes(&value.clone().undecorate())?;
+        Ok(TableEntryValue {
+            json: MgoMoveValue::from(value.clone()).to_json_value(),
+            bcs,
+        })
+    }
+}
+
 #[derive(Clone, Default)]
 struct PackageCache {
     inner: Arc<RwLock<BTreeMap<ObjectID, MovePackage>>>,
@@ -1800,6 +4204,212 @@ impl move_bytecode_utils::module_cache::GetModule for IndexerReader {
     }
 }
 
+/// Object versions are served from the object history, so balance and object changes of past
+/// transactions can be computed as they are on fullnodes.
+#[async_trait]
+impl ObjectProvider for IndexerReader {
+    type Error = IndexerError;
+
+    async fn get_object(
+        &self,
+        id: &ObjectID,
+        version: &SequenceNumber,
+    ) -> Result<Object, Self::Error> {
+        let mut objects = ObjectProvider::multi_get_objects(self, &[(*id, *version)]).await?;
+        Ok(objects.remove(0))
+    }
+
+    async fn find_object_lt_or_eq_version(
+        &self,
+        id: &ObjectID,
+        version: &SequenceNumber,
+    ) -> Result<Option<Object>, Self::Error> {
+        let mut objects = self
+            .multi_find_object_lt_or_eq_version(&[(*id, *version)])
+            .await?;
+        Ok(objects.remove(0))
+    }
+
+    async fn multi_get_objects(
+        &self,
+        objects: &[(ObjectID, SequenceNumber)],
+    ) -> Result<Vec<Object>, Self::Error> {
+        let keys = objects.to_vec();
+        let found = self
+            .run_on_blocking_pool({
+                let keys = keys.clone();
+                move |this| this.multi_get_history_objects(&keys)
+            })
+            .await?;
+        keys.into_iter()
+            .zip(found)
+            .map(|((object_id, version), o)| {
+                o.ok_or_else(|| {
+                    IndexerError::from(UserInputError::ObjectNotFound {
+                        object_id,
+                        version: Some(version),
+                    })
+                })
+            })
+            .collect()
+    }
+
+    async fn multi_find_object_lt_or_eq_version(
+        &self,
+        objects: &[(ObjectID, SequenceNumber)],
+    ) -> Result<Vec<Option<Object>>, Self::Error> {
+        let keys = objects.to_vec();
+        self.run_on_blocking_pool(move |this| {
+            this.multi_find_history_objects_lt_or_eq_version(&keys)
+        })
+        .await
+    }
+}
+
+/// `objects` as a list of `(object_id, object_version)` rows for an object history query.
+fn history_keys(objects: &[(ObjectID, SequenceNumber)]) -> String {
+    objects
+        .iter()
+        .map(|(id, version)| {
+            format!(
+                "('\\x{}'::BYTEA, {}::BIGINT)",
+                Hex::encode(id.to_vec()),
+                version.value()
+            )
+        })
+        .join(", ")
+}
+
+/// The objects of history `rows` by ID and version, `None` for the versions at which they were
+/// wrapped or deleted.
+fn history_versions(
+    rows: Vec<StoredHistoryObject>,
+) -> Result<BTreeMap<(ObjectID, SequenceNumber), Option<Object>>, IndexerError> {
+    rows.into_iter()
+        .map(|row| -> Result<_, IndexerError> {
+            let id = ObjectID::from_bytes(&row.object_id)?;
+            let version = SequenceNumber::from_u64(row.object_version as u64);
+            let object = if row.object_status == ObjectStatus::Active as i16 {
+                Some(Object::try_from(StoredObject::try_from(row)?)?)
+            } else {
+                None
+            };
+            Ok(((id, version), object))
+        })
+        .collect()
+}
+
+/// Restricts an owned-objects query to the struct types selected by `filter`.
+fn filter_objects_by_type(
+    mut query: objects::BoxedQuery<'_, Pg>,
+    filter: MgoObjectDataFilter,
+) -> Result<objects::BoxedQuery<'_, Pg>, IndexerError> {
+    let unsupported = || {
+        IndexerError::InvalidArgumentError(
+            "Invalid filter type. Only struct, MatchAny and MatchNone of struct filters are supported.".into(),
+        )
+    };
+    match filter {
+        MgoObjectDataFilter::StructType(struct_tag) => {
+            let object_type = struct_tag.to_canonical_string(/* with_prefix */ true);
+            query = query.filter(objects::dsl::object_type.like(format!("{}%", object_type)));
+        }
+        MgoObjectDataFilter::MatchAny(filters) => {
+            let mut condition = "(".to_string();
+            for (i, filter) in filters.iter().enumerate() {
+                if let MgoObjectDataFilter::StructType(struct_tag) = filter {
+                    let object_type = struct_tag.to_canonical_string(/* with_prefix */ true);
+                    if i == 0 {
+                        condition +=
+                            format!("objects.object_type LIKE '{}%'", object_type).as_str();
+                    } else {
+                        condition +=
+                            format!(" OR objects.object_type LIKE '{}%'", object_type).as_str();
+                    }
+                } else {
+                    return Err(unsupported());
+                }
+            }
+            condition += ")";
+            query = query.filter(sql::<Bool>(&condition));
+        }
+        MgoObjectDataFilter::MatchNone(filters) => {
+            for filter in filters {
+                if let MgoObjectDataFilter::StructType(struct_tag) = filter {
+                    let object_type = struct_tag.to_canonical_string(/* with_prefix */ true);
+                    query = query
+                        .filter(objects::dsl::object_type.not_like(format!("{}%", object_type)));
+                } else {
+                    return Err(unsupported());
+                }
+            }
+        }
+        _ => return Err(unsupported()),
+    }
+    Ok(query)
+}
+
+/// [`filter_objects_by_type`] as a condition on the `object_type` column, for raw SQL queries.
+fn object_type_condition(filter: MgoObjectDataFilter) -> Result<String, IndexerError> {
+    let unsupported = || {
+        IndexerError::InvalidArgumentError(
+            "Invalid filter type. Only struct, MatchAny and MatchNone of struct filters are supported.".into(),
+        )
+    };
+    let like = |filter: &MgoObjectDataFilter, operator: &str| match filter {
+        MgoObjectDataFilter::StructType(struct_tag) => Ok(format!(
+            "object_type {operator} '{}%'",
+            struct_tag.to_canonical_string(/* with_prefix */ true)
+        )),
+        _ => Err(unsupported()),
+    };
+    match filter {
+        MgoObjectDataFilter::StructType(_) => like(&filter, "LIKE"),
+        MgoObjectDataFilter::MatchAny(filters) if !filters.is_empty() => Ok(format!(
+            "({})",
+            filters
+                .iter()
+                .map(|filter| like(filter, "LIKE"))
+                .collect::<Result<Vec<_>, _>>()?
+                .join(" OR ")
+        )),
+        MgoObjectDataFilter::MatchNone(filters) if !filters.is_empty() => Ok(format!(
+            "({})",
+            filters
+                .iter()
+                .map(|filter| like(filter, "NOT LIKE"))
+                .collect::<Result<Vec<_>, _>>()?
+                .join(" AND ")
+        )),
+        MgoObjectDataFilter::MatchAny(_) => Ok("FALSE".to_string()),
+        MgoObjectDataFilter::MatchNone(_) => Ok("TRUE".to_string()),
+        _ => Err(unsupported()),
+    }
+}
+
+/// Condition selecting the events strictly after the position `(tx_seq, event_seq)` in the order
+/// of an event query, on the columns prefixed with `prefix`. Events are ordered by transaction
+/// first, so the positions compare as pairs: the event after the last one of a transaction is the
+/// first one of the next transaction, whatever their event sequence numbers, and the cursor
+/// itself is never selected again.
+fn event_cursor_condition(prefix: &str, tx_seq: i64, event_seq: i64, descending: bool) -> String {
+    let operator = if descending { "<" } else { ">" };
+    format!(
+        "({prefix}{TX_SEQUENCE_NUMBER_STR} {operator} {tx_seq} \
+        OR ({prefix}{TX_SEQUENCE_NUMBER_STR} = {tx_seq} \
+        AND {prefix}{EVENT_SEQUENCE_NUMBER_STR} {operator} {event_seq}))"
+    )
+}
+
+/// The order of an event query, matching [`event_cursor_condition`].
+fn event_order(prefix: &str, descending: bool) -> String {
+    let direction = if descending { "DESC" } else { "ASC" };
+    format!(
+        "{prefix}{TX_SEQUENCE_NUMBER_STR} {direction}, \
+        {prefix}{EVENT_SEQUENCE_NUMBER_STR} {direction}"
+    )
+}
+
 #[cached(
     type = "SizedCache<String, Option<ObjectID>>",
     create = "{ SizedCache::with_size(10000) }",
@@ -2,78 +2,140 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::apis::{
-    CoinReadApiV2, ExtendedApiV2, GovernanceReadApiV2, IndexerApiV2, MoveUtilsApiV2, ReadApiV2,
-    TransactionBuilderApiV2, WriteApi,
+    ChainStateCache, CoinReadApiV2, ExtendedApiV2, GovernanceReadApiV2, IndexerApiV2,
+    MoveUtilsApiV2, ReadApiV2, SubscriptionHandler, TransactionBuilderApiV2, WriteApiV2,
 };
+use crate::dry_run_upstream::{DryRunUpstream, DryRunUpstreamMetrics};
 use crate::errors::IndexerError;
+use crate::event_denylist::{start_admin_server, SharedEventDenylist};
+use crate::event_object_refs::ObjectRefEventTypes;
+use crate::fullnode_fallback::{FullnodeHealth, TransactionFallback};
 use crate::indexer_reader::IndexerReader;
-use crate::metrics::IndexerMetrics;
-use crate::IndexerConfig;
+use crate::metrics::{CheckpointFetcherMetrics, IndexerMetrics};
+use crate::notifications::CommitNoticeListener;
+use crate::{shutdown_on_termination, IndexerConfig};
 use anyhow::Result;
 use mango_metrics::spawn_monitored_task;
 use prometheus::Registry;
 use std::env;
 use std::net::SocketAddr;
-use mgo_json_rpc::ServerType;
+use std::sync::Arc;
+use std::time::Duration;
+use mgo_json_rpc::coin_registry::CoinRegistry;
+use mgo_json_rpc::kill_switch::MethodKillSwitch;
 use mgo_json_rpc::{JsonRpcServerBuilder, ServerHandle};
+use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
 use tokio::runtime::Handle;
-use tracing::info;
+use tokio::sync::watch;
+use tracing::{info, warn};
 
-use crate::framework::fetcher::CheckpointFetcher;
+use crate::framework::pipeline::{
+    checkpoint_channel, fetcher_factory, run_in_lockstep_with_buffer, CertifiedCheckpointSource,
+    CheckpointBuffer, CheckpointFetcher, LocalArchiveSource, Pipeline, ProgressStore,
+    PrunableStore, Pruner, ReconcilableStore, Reconciler, WatermarkSource,
+};
 use crate::handlers::checkpoint_handler_v2::new_handlers;
+use crate::handlers::epoch_metrics_handler::EpochMetricsHandler;
+use crate::handlers::tx_object_indices_backfill::TxObjectIndicesBackfill;
+use crate::handlers::validator_metrics_handler::ValidatorMetricsHandler;
 use crate::processors_v2::objects_snapshot_processor::{
     ObjectsSnapshotProcessor, SnapshotLagConfig,
 };
+use crate::processors_v2::orphaned_dynamic_fields_processor::OrphanedDynamicFieldsProcessor;
 use crate::processors_v2::processor_orchestrator_v2::ProcessorOrchestratorV2;
+use crate::processors_v2::storage_stats_processor::StorageStatsProcessor;
 use crate::store::{IndexerStoreV2, PgIndexerAnalyticalStore};
+use crate::writer_version::{check_writer_version, WRITER_VERSION};
 
 pub struct IndexerV2;
 
-const DOWNLOAD_QUEUE_SIZE: usize = 1000;
-
 impl IndexerV2 {
-    pub async fn start_writer<S: IndexerStoreV2 + Sync + Send + Clone + 'static>(
+    pub async fn start_writer<S>(
         config: &IndexerConfig,
         store: S,
         metrics: IndexerMetrics,
-    ) -> Result<(), IndexerError> {
+    ) -> Result<(), IndexerError>
+    where
+        S: IndexerStoreV2
+            + PrunableStore
+            + ProgressStore
+            + ReconcilableStore
+            + WatermarkSource
+            + Sync
+            + Send
+            + Clone
+            + 'static,
+    {
         let snapshot_config = SnapshotLagConfig::default();
-        IndexerV2::start_writer_with_config(config, store, metrics, snapshot_config).await
+        IndexerV2::start_writer_with_config(
+            config,
+            store,
+            metrics,
+            snapshot_config,
+            shutdown_on_termination(),
+        )
+        .await
     }
 
-    pub async fn start_writer_with_config<S: IndexerStoreV2 + Sync + Send + Clone + 'static>(
+    /// Runs the writer until `shutdown` is set to `true`. It then takes no further checkpoints,
+    /// and is given `config.shutdown_deadline_secs` to index the ones in flight and again to
+    /// commit them. Checkpoints are recorded in the checkpoints table last, so the ones left
+    /// uncommitted by then are indexed again on restart.
+    pub async fn start_writer_with_config<S>(
         config: &IndexerConfig,
         store: S,
         metrics: IndexerMetrics,
         snapshot_config: SnapshotLagConfig,
-    ) -> Result<(), IndexerError> {
+        shutdown: watch::Receiver<bool>,
+    ) -> Result<(), IndexerError>
+    where
+        S: IndexerStoreV2
+            + PrunableStore
+            + ProgressStore
+            + ReconcilableStore
+            + WatermarkSource
+            + Sync
+            + Send
+            + Clone
+            + 'static,
+    {
         info!(
             "Mgo indexerV2 Writer (version {:?}) started...",
             env!("CARGO_PKG_VERSION")
         );
+        store.persist_writer_version(WRITER_VERSION).await?;
 
         // None will be returned when checkpoints table is empty.
         let last_seq_from_db = store
             .get_latest_tx_checkpoint_sequence_number()
             .await
             .expect("Failed to get latest tx checkpoint sequence number from DB");
+        let buffer_limits = config.checkpoint_buffer_limits();
         let (downloaded_checkpoint_data_sender, downloaded_checkpoint_data_receiver) =
-            mango_metrics::metered_channel::channel(
-                DOWNLOAD_QUEUE_SIZE,
-                &mango_metrics::get_metrics()
-                    .unwrap()
-                    .channels
-                    .with_label_values(&["checkpoint_tx_downloading"]),
-            );
+            checkpoint_channel(buffer_limits.max_checkpoints);
+        let buffer = CheckpointBuffer::new(buffer_limits, metrics.fetcher.clone());
 
         let rest_api_url = format!("{}/rest", config.rpc_client_url);
         let rest_client = mgo_rest_api::Client::new(&rest_api_url);
-        let fetcher = CheckpointFetcher::new(
+        let mut fetcher = CheckpointFetcher::new(
             rest_client.clone(),
             last_seq_from_db,
             downloaded_checkpoint_data_sender,
-        );
-        spawn_monitored_task!(fetcher.run());
+            metrics.fetcher.clone(),
+        )
+        .with_gap_grace_period(Duration::from_secs(config.checkpoint_gap_grace_period_secs))
+        .with_download_concurrency(config.checkpoint_fetch_concurrency)
+        .with_buffer(buffer.clone())
+        .with_health(FullnodeHealth::for_url(&config.rpc_client_url));
+        if let Some(secondary_rpc_client_url) = &config.secondary_rpc_client_url {
+            fetcher = fetcher.with_secondary_client(mgo_rest_api::Client::new(format!(
+                "{secondary_rpc_client_url}/rest"
+            )));
+        }
+        if let Some(checkpoint_archive_dir) = &config.checkpoint_archive_dir {
+            fetcher = fetcher.with_local_archive(LocalArchiveSource::new(checkpoint_archive_dir));
+        }
+        let fetcher = spawn_monitored_task!(fetcher.run());
 
         let objects_snapshot_processor = ObjectsSnapshotProcessor::new_with_config(
             store.clone(),
@@ -83,19 +145,137 @@ impl IndexerV2 {
 
         spawn_monitored_task!(objects_snapshot_processor.start());
 
-        let checkpoint_handler = new_handlers(store, metrics, config).await?;
+        if let Some(policy) = config.retention_policy() {
+            let pruner = Pruner::new(store.clone(), policy, metrics.pruner.clone())
+                .with_batch_size(config.pruning_batch_size)
+                .with_interval(Duration::from_secs(config.pruning_interval_secs));
+            spawn_monitored_task!(pruner.run(Arc::new(store.clone())));
+        }
 
-        crate::framework::runner::run(
-            mango_metrics::metered_channel::ReceiverStream::new(
-                downloaded_checkpoint_data_receiver,
-            ),
-            vec![Box::new(checkpoint_handler)],
+        let storage_stats_processor =
+            StorageStatsProcessor::new(store.clone(), metrics.storage_stats.clone())
+                .with_interval(Duration::from_secs(config.storage_stats_interval_secs));
+        spawn_monitored_task!(storage_stats_processor.run());
+
+        let orphaned_dynamic_fields_processor = OrphanedDynamicFieldsProcessor::new(
+            store.clone(),
+            metrics.orphaned_dynamic_fields.clone(),
+        )
+        .with_interval(Duration::from_secs(
+            config.orphaned_dynamic_fields_scan_interval_secs,
+        ))
+        .with_batch_size(config.orphaned_dynamic_fields_scan_batch_size);
+        spawn_monitored_task!(orphaned_dynamic_fields_processor.run());
+
+        if config.backfill_tx_object_indices {
+            if let Some(end_checkpoint) = last_seq_from_db {
+                spawn_monitored_task!(IndexerV2::backfill_tx_object_indices(
+                    store.clone(),
+                    rest_client.clone(),
+                    metrics.fetcher.clone(),
+                    end_checkpoint,
+                ));
+            }
+        }
+
+        let event_denylist = start_event_denylist(config, None)?;
+        if let Some(reconciliation_rpc_client_url) = &config.reconciliation_rpc_client_url {
+            let reconciler = IndexerV2::reconciler(
+                config,
+                store.clone(),
+                metrics.clone(),
+                event_denylist.clone(),
+                mgo_rest_api::Client::new(format!("{reconciliation_rpc_client_url}/rest")),
+            )
+            .await?
+            .with_interval(Duration::from_secs(config.reconciliation_interval_secs));
+            let watermarks = Arc::new(CommittedCheckpoints(store.clone()));
+            spawn_monitored_task!(reconciler.run(watermarks));
+        }
+
+        let epoch_metrics_handler = EpochMetricsHandler::new(store.clone());
+        let validator_metrics_handler = ValidatorMetricsHandler::new(store.clone());
+        let (checkpoint_handler, mut committer) =
+            new_handlers(store, metrics, config, event_denylist).await?;
+
+        let shutdown_deadline = Duration::from_secs(config.shutdown_deadline_secs);
+        let mut checkpoints = mango_metrics::metered_channel::ReceiverStream::new(
+            downloaded_checkpoint_data_receiver,
+        );
+        run_in_lockstep_with_buffer(
+            &mut checkpoints,
+            vec![
+                Box::new(checkpoint_handler),
+                Box::new(epoch_metrics_handler),
+                Box::new(validator_metrics_handler),
+            ],
+            buffer,
+            shutdown,
+            shutdown_deadline,
         )
         .await;
+        // The fetcher is stopped before its channel is dropped, which it treats as fatal.
+        fetcher.abort();
+        let _ = fetcher.await;
+        drop(checkpoints);
+
+        // The checkpoint handler is dropped by now, so the committer returns once it committed
+        // everything the handler sent it.
+        if tokio::time::timeout(shutdown_deadline, &mut committer)
+            .await
+            .is_err()
+        {
+            warn!(
+                "Checkpoint committer did not finish within the shutdown deadline of \
+                {shutdown_deadline:?}, uncommitted checkpoints will be indexed again on restart"
+            );
+            committer.abort();
+        }
+        info!("Mgo indexerV2 Writer stopped");
 
         Ok(())
     }
 
+    /// Backfills `tx_input_objects` and `tx_changed_objects` of all checkpoints up to
+    /// `end_checkpoint` from `rest_client`, returning once done.
+    pub async fn backfill_tx_object_indices<S>(
+        store: S,
+        rest_client: mgo_rest_api::Client,
+        fetcher_metrics: CheckpointFetcherMetrics,
+        end_checkpoint: CheckpointSequenceNumber,
+    ) where
+        S: IndexerStoreV2 + ProgressStore + Sync + Send + Clone + 'static,
+    {
+        info!("Backfilling tx object indices up to checkpoint {end_checkpoint}");
+        Pipeline::new()
+            .register_handler(TxObjectIndicesBackfill::new(store.clone()))
+            .with_progress_store(store)
+            .end_checkpoint(end_checkpoint)
+            .run(fetcher_factory(rest_client, fetcher_metrics))
+            .await;
+        info!("Backfilled tx object indices up to checkpoint {end_checkpoint}");
+    }
+
+    /// A reconciler verifying the checkpoints of `store` against the certified summaries of
+    /// `source`, which ingests checkpoints failing verification again with its own checkpoint
+    /// handler.
+    pub async fn reconciler<S, C>(
+        config: &IndexerConfig,
+        store: S,
+        metrics: IndexerMetrics,
+        event_denylist: SharedEventDenylist,
+        source: C,
+    ) -> Result<Reconciler, IndexerError>
+    where
+        S: IndexerStoreV2 + ReconcilableStore + Sync + Send + Clone + 'static,
+        C: CertifiedCheckpointSource + 'static,
+    {
+        let reconciler_metrics = metrics.reconciler.clone();
+        let (checkpoint_handler, _committer) =
+            new_handlers(store.clone(), metrics, config, event_denylist).await?;
+        Ok(Reconciler::new(store, source, reconciler_metrics).register_handler(checkpoint_handler))
+    }
+
     pub async fn start_reader(
         config: &IndexerConfig,
         registry: &Registry,
@@ -105,10 +285,25 @@ impl IndexerV2 {
             "Mgo indexerV2 Reader (version {:?}) started...",
             env!("CARGO_PKG_VERSION")
         );
-        let indexer_reader = IndexerReader::new(db_url)?;
-        let handle = build_json_rpc_server(registry, indexer_reader, config, None)
-            .await
-            .expect("Json rpc server should not run into errors upon start.");
+        let commit_notices = CommitNoticeListener::start(&db_url).await;
+        let kill_switch = MethodKillSwitch::default();
+        let indexer_reader = IndexerReader::new(db_url)?
+            .with_event_denylist(start_event_denylist(config, Some(&kill_switch))?)
+            .with_metrics(registry);
+        let writer_version = indexer_reader
+            .spawn_blocking(|this| this.get_writer_version())
+            .await?;
+        check_writer_version(writer_version);
+        let handle = build_json_rpc_server(
+            registry,
+            indexer_reader,
+            config,
+            None,
+            commit_notices,
+            kill_switch,
+        )
+        .await
+        .expect("Json rpc server should not run into errors upon start.");
         tokio::spawn(async move { handle.stopped().await })
             .await
             .expect("Rpc server task failed");
@@ -130,30 +325,115 @@ impl IndexerV2 {
     }
 }
 
+/// Checkpoints the writer committed, which the reconciler verifies without waiting for the
+/// objects snapshot lagging behind them.
+struct CommittedCheckpoints<S>(S);
+
+#[async_trait::async_trait]
+impl<S: IndexerStoreV2 + Sync + Send> WatermarkSource for CommittedCheckpoints<S> {
+    async fn lowest_watermark(&self) -> Result<Option<CheckpointSequenceNumber>> {
+        Ok(self.0.get_latest_tx_checkpoint_sequence_number().await?)
+    }
+}
+
+/// Loads the configured event denylist, serving its reload route and the routes of
+/// `kill_switch` if an admin port is set.
+fn start_event_denylist(
+    config: &IndexerConfig,
+    kill_switch: Option<&MethodKillSwitch>,
+) -> Result<SharedEventDenylist, IndexerError> {
+    let event_denylist = SharedEventDenylist::from_path(config.event_denylist_path.as_deref())?;
+    if let Some(admin_port) = config.admin_port {
+        let addr = SocketAddr::new(
+            config.client_metric_host.parse().map_err(|e| {
+                IndexerError::InvalidArgumentError(format!(
+                    "Invalid admin server host {}: {e}",
+                    config.client_metric_host
+                ))
+            })?,
+            admin_port,
+        );
+        start_admin_server(addr, event_denylist.clone(), kill_switch);
+    }
+    Ok(event_denylist)
+}
+
 pub async fn build_json_rpc_server(
     prometheus_registry: &Registry,
     reader: IndexerReader,
     config: &IndexerConfig,
     custom_runtime: Option<Handle>,
+    commit_notices: CommitNoticeListener,
+    kill_switch: MethodKillSwitch,
 ) -> Result<ServerHandle, IndexerError> {
     let mut builder = JsonRpcServerBuilder::new(env!("CARGO_PKG_VERSION"), prometheus_registry);
+    builder.set_kill_switch(kill_switch);
+    builder.set_query_scheduler(config.query_scheduler_config(reader.pool_size()));
+    builder.set_rate_limiter(config.rate_limiter_config()?);
+    if let Some(cors) = config.cors_config()? {
+        builder.set_cors(cors);
+    }
+    if let Some(signing) = config.response_signing_config() {
+        builder.set_response_signer(signing.build()?);
+    }
     let http_client = crate::get_http_client(config.rpc_client_url.as_str())?;
 
-    builder.register_module(WriteApi::new(http_client.clone()))?;
-    builder.register_module(IndexerApiV2::new(reader.clone()))?;
+    let mut write_api = WriteApiV2::new(http_client.clone());
+    if let Some(dry_run_upstream_url) = &config.dry_run_upstream_url {
+        write_api = write_api.with_dry_run_upstream(DryRunUpstream::new(
+            crate::get_http_client(dry_run_upstream_url)?,
+            Duration::from_millis(config.dry_run_upstream_timeout_ms),
+            DryRunUpstreamMetrics::new(prometheus_registry),
+        ));
+    }
+    builder.register_module(write_api)?;
+    let chain_state = ChainStateCache::start(reader.clone(), &commit_notices);
+    let display_limits = config.display_render_limits();
+    builder.register_module(IndexerApiV2::new(
+        reader.clone(),
+        display_limits.clone(),
+        SubscriptionHandler::start(
+            reader.clone(),
+            commit_notices,
+            config.event_filter_limits(),
+        ),
+    )
+    .with_metrics(prometheus_registry))?;
     builder.register_module(TransactionBuilderApiV2::new(reader.clone()))?;
     builder.register_module(MoveUtilsApiV2::new(reader.clone()))?;
     builder.register_module(GovernanceReadApiV2::new(reader.clone()))?;
-    builder.register_module(ReadApiV2::new(reader.clone()))?;
-    builder.register_module(CoinReadApiV2::new(reader.clone()))?;
-    builder.register_module(ExtendedApiV2::new(reader.clone()))?;
+    let mut read_api = ReadApiV2::new(reader.clone(), display_limits.clone());
+    if let Some(fullnode_fallback_url) = &config.fullnode_fallback_url {
+        read_api = read_api.with_fullnode_fallback(TransactionFallback::new(
+            crate::get_http_client(fullnode_fallback_url)?,
+            Duration::from_millis(config.fullnode_fallback_timeout_ms),
+            FullnodeHealth::for_url(fullnode_fallback_url),
+        ));
+    }
+    builder.register_module(read_api)?;
+    let mut coin_read_api = CoinReadApiV2::new(reader.clone());
+    if let Some(coin_registry_id) = config.coin_registry_id {
+        coin_read_api = coin_read_api.with_coin_registry(CoinRegistry::new(coin_registry_id));
+    }
+    builder.register_module(coin_read_api)?;
+    builder.register_module(
+        ExtendedApiV2::new(
+            reader.clone(),
+            display_limits,
+            config.source_maps_dir.clone(),
+        )
+        .with_object_ref_event_types(ObjectRefEventTypes::parse(&config.object_ref_event_types)?)
+        .with_chain_state(chain_state.clone()),
+    )?;
+    chain_state.set_namespaces(builder.namespaces());
 
     let default_socket_addr: SocketAddr = SocketAddr::new(
         // unwrap() here is safe b/c the address is a static config.
         config.rpc_server_url.as_str().parse().unwrap(),
         config.rpc_server_port,
     );
+    // Serve websocket upgrades as well, for event and transaction subscriptions.
     Ok(builder
-        .start(default_socket_addr, custom_runtime, Some(ServerType::Http))
+        .start(default_socket_addr, custom_runtime, None)
         .await?)
 }
@@ -4,7 +4,12 @@
 
 use std::env;
 use std::net::SocketAddr;
-use std::{collections::HashMap, time::Duration};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    time::Duration,
+};
 
 use anyhow::{anyhow, Result};
 use axum::{extract::Extension, http::StatusCode, routing::get, Router};
@@ -24,28 +29,49 @@ use apis::{
     WriteApi,
 };
 use errors::IndexerError;
-use mango_metrics::{spawn_monitored_task, RegistryService};
+#[cfg(feature = "legacy-handlers")]
+use mango_metrics::spawn_monitored_task;
+use mango_metrics::RegistryService;
+#[cfg(feature = "legacy-handlers")]
 use processors::processor_orchestrator::ProcessorOrchestrator;
 use store::IndexerStore;
+use mgo_json_rpc::query_scheduler::QuerySchedulerConfig;
+use mgo_json_rpc::cors::CorsConfig;
+use mgo_json_rpc::rate_limiter::{MethodLimits, RateLimiterConfig};
+use mgo_json_rpc::response_signing::ResponseSigningConfig;
 use mgo_json_rpc::{JsonRpcServerBuilder, ServerHandle, ServerType};
 use mgo_json_rpc_api::CLIENT_SDK_TYPE_HEADER;
+use mgo_json_rpc_types::EventFilterLimits;
 use mgo_sdk::{MgoClient, MgoClientBuilder};
+use mgo_types::base_types::ObjectID;
 
 use crate::apis::MoveUtilsApi;
+use crate::framework::pipeline::BufferLimits;
+use crate::framework::RetentionPolicy;
+#[cfg(feature = "legacy-handlers")]
 use crate::framework::IndexerBuilder;
-use crate::handlers::checkpoint_handler::new_handlers;
+#[cfg(feature = "legacy-handlers")]
+use crate::handlers::checkpoint_handler::{new_handlers, IndexerStoreProgress};
 
 pub mod apis;
+mod blocking_tasks;
+pub mod dry_run_upstream;
 pub mod errors;
+pub mod event_denylist;
+pub mod event_object_refs;
 pub mod framework;
+pub mod fullnode_fallback;
 mod handlers;
 pub mod indexer_reader;
 pub mod indexer_v2;
 pub mod metrics;
 pub mod models;
 pub mod models_v2;
+pub mod notifications;
 pub mod processors;
 pub mod processors_v2;
+pub mod read_only_indexer;
+pub mod result_stream;
 pub mod schema;
 pub mod schema_v2;
 pub mod store;
@@ -53,6 +79,15 @@ pub mod test_utils;
 pub mod types;
 pub mod types_v2;
 pub mod utils;
+pub mod writer_version;
+
+pub use indexer_reader::IndexerReader;
+pub use mgo_json_rpc::read_api::DisplayRenderLimits;
+pub use mgo_json_rpc_types::{
+    DynamicFieldPage, DynamicFieldsOptions, EventFilter, EventPage, MgoObjectResponse,
+    MgoObjectResponseQuery, MgoTransactionBlockResponseQuery, ObjectsPage, TransactionBlocksPage,
+};
+pub use read_only_indexer::ReadOnlyIndexer;
 
 pub type PgConnectionPool = diesel::r2d2::Pool<ConnectionManager<PgConnection>>;
 pub type PgPoolConnection = diesel::r2d2::PooledConnection<ConnectionManager<PgConnection>>;
@@ -96,6 +131,34 @@ pub struct IndexerConfig {
     pub db_name: Option<String>,
     #[clap(long, default_value = "http://0.0.0.0:9000", global = true)]
     pub rpc_client_url: String,
+    /// Fullnode that checkpoints missing from `rpc_client_url` are fetched from.
+    #[clap(long)]
+    pub secondary_rpc_client_url: Option<String>,
+    /// Directory of archived checkpoint files, e.g. written by a fullnode for data ingestion,
+    /// that checkpoints are read from before `rpc_client_url`. Checkpoints missing from it or
+    /// failing to decode are still fetched from the fullnode.
+    #[clap(long)]
+    pub checkpoint_archive_dir: Option<PathBuf>,
+    /// Seconds a checkpoint may be missing while later ones are available before the gap is
+    /// reported.
+    #[clap(long, default_value = "60")]
+    pub checkpoint_gap_grace_period_secs: u64,
+    /// Maximum number of checkpoints downloaded in parallel, including downloaded checkpoints
+    /// waiting for earlier ones to arrive.
+    #[clap(long, default_value = "100")]
+    pub checkpoint_fetch_concurrency: usize,
+    /// Checkpoints downloaded ahead of the handlers, past which the fetcher pauses until the
+    /// handlers catch up.
+    #[clap(long, default_value = "1000")]
+    pub checkpoint_buffer_size: usize,
+    /// Cap on the estimated bytes of the checkpoints downloaded ahead of the handlers, past
+    /// which the fetcher pauses as well. Unbounded when unset.
+    #[clap(long)]
+    pub checkpoint_buffer_max_bytes: Option<u64>,
+    /// Seconds handlers are given to finish the checkpoints they are processing on SIGTERM or
+    /// ctrl-c, after which those checkpoints are processed again on restart.
+    #[clap(long, default_value = "30")]
+    pub shutdown_deadline_secs: u64,
     #[clap(long, default_value = "0.0.0.0", global = true)]
     pub client_metric_host: String,
     #[clap(long, default_value = "9184", global = true)]
@@ -119,6 +182,152 @@ pub struct IndexerConfig {
     pub skip_db_commit: bool,
     #[clap(long)]
     pub use_v2: bool,
+    /// Directory of published Move source maps, laid out as
+    /// `<package_id>/source_maps/<module>.mvsm` with sources under `<package_id>/sources`.
+    #[clap(long)]
+    pub source_maps_dir: Option<PathBuf>,
+    /// File of event types the writer does not store and the reader refuses to query, one
+    /// per line, either an exact event type or a package ID denying all types defined in it.
+    #[clap(long)]
+    pub event_denylist_path: Option<PathBuf>,
+    /// Port of the admin server on `client_metric_host`, where `POST /event-denylist/reload`
+    /// reloads `event_denylist_path` without a restart. Readers also serve the JSON-RPC method
+    /// kill switch there, under `/rpc-kill-switch`.
+    #[clap(long)]
+    pub admin_port: Option<u16>,
+    /// Fullnode that transactions not indexed yet are read from by `getTransactionBlock` and
+    /// `multiGetTransactionBlocks`, without writing them to the database. Disabled when unset.
+    #[clap(long)]
+    pub fullnode_fallback_url: Option<String>,
+    /// How long a read from `fullnode_fallback_url` may take before the transaction is reported
+    /// as not found.
+    #[clap(long, default_value = "2000")]
+    pub fullnode_fallback_timeout_ms: u64,
+    /// Fullnode that `devInspectTransactionBlock` and `dryRunTransactionBlock` are proxied to.
+    /// Both methods are reported as not found when unset.
+    #[clap(long)]
+    pub dry_run_upstream_url: Option<String>,
+    /// How long a request proxied to `dry_run_upstream_url` may take before it fails.
+    #[clap(long, default_value = "10000")]
+    pub dry_run_upstream_timeout_ms: u64,
+    /// Number of most recent checkpoints the writer keeps transactions, events and object
+    /// history of, pruning older ones. Nothing is pruned unless this or `retention_epochs` is
+    /// set.
+    #[clap(long, conflicts_with = "retention_epochs")]
+    pub retention_checkpoints: Option<u64>,
+    /// Number of most recent epochs the writer keeps transactions, events and object history
+    /// of, the current one included.
+    #[clap(long)]
+    pub retention_epochs: Option<u64>,
+    /// Maximum number of rows the pruner deletes from a table in one statement.
+    #[clap(long, default_value = "1000")]
+    pub pruning_batch_size: usize,
+    /// Seconds between pruning rounds.
+    #[clap(long, default_value = "60")]
+    pub pruning_interval_secs: u64,
+    /// Fullnode trusted to serve finalized checkpoints only, whose certified checkpoint
+    /// summaries the writer verifies ingested checkpoints against. The rows of a checkpoint
+    /// whose digest differs are tombstoned and the checkpoint is ingested again from it.
+    /// Disabled when unset.
+    #[clap(long)]
+    pub reconciliation_rpc_client_url: Option<String>,
+    /// Seconds between reconciliation rounds.
+    #[clap(long, default_value = "10")]
+    pub reconciliation_interval_secs: u64,
+    /// Seconds between the samples of the row estimates and on-disk sizes of the tables, which
+    /// the writer persists so that readers serve them and their growth without scanning.
+    #[clap(long, default_value = "600")]
+    pub storage_stats_interval_secs: u64,
+    /// Seconds between the scans of the dynamic object fields of the parents changed since the
+    /// last scan, which report the fields whose value object is no longer live.
+    #[clap(long, default_value = "60")]
+    pub orphaned_dynamic_fields_scan_interval_secs: u64,
+    /// Maximum number of checkpoints whose changed parents a scan covers.
+    #[clap(long, default_value = "1000")]
+    pub orphaned_dynamic_fields_scan_batch_size: usize,
+    /// Backfills `tx_input_objects` and `tx_changed_objects` for the checkpoints indexed when
+    /// the writer starts, from the fullnode and next to regular ingestion. An interrupted
+    /// backfill resumes from its watermark in `handler_watermarks`.
+    #[clap(long)]
+    pub backfill_tx_object_indices: bool,
+    /// Registry object whose dynamic fields list the coin types that coin and balance responses
+    /// mark as verified. Responses carry no verification when unset.
+    #[clap(long)]
+    pub coin_registry_id: Option<ObjectID>,
+    /// Event types whose `ID` and `UID` fields the writer indexes, so that `queryEventsByObject`
+    /// finds their events. An event type without type parameters covers all its instantiations.
+    #[clap(long, num_args(1..))]
+    pub object_ref_event_types: Vec<String>,
+    /// Methods the reader runs as heavy queries, which may never take the connections reserved
+    /// by `reserved_light_connections`. All other methods are light.
+    #[clap(
+        long,
+        num_args(1..),
+        default_values = ["mgox_queryEvents", "mgox_queryTransactionBlocks"]
+    )]
+    pub heavy_methods: Vec<String>,
+    /// Reader connections that only light methods use, so that point reads are served while
+    /// heavy queries saturate the rest.
+    #[clap(long, default_value = "10")]
+    pub reserved_light_connections: u32,
+    /// Weight every client IP of the reader is credited per second, which each request is
+    /// charged the weight of its method against. Clients are not rate limited when unset.
+    #[clap(long)]
+    pub rate_limit_weight_per_second: Option<u32>,
+    /// Weight a client may spend at once after being idle.
+    #[clap(long, default_value = "100")]
+    pub rate_limit_burst: u32,
+    /// Weights of methods overriding those their module declares, as `method=weight`.
+    #[clap(long, num_args(1..))]
+    pub method_weights: Vec<String>,
+    /// Requests of each method the reader serves at once across all clients, refusing the
+    /// others. Uncapped when unset.
+    #[clap(long)]
+    pub max_concurrent_requests_per_method: Option<usize>,
+    /// Overrides of `max_concurrent_requests_per_method` for single methods, as
+    /// `method=limit`.
+    #[clap(long, num_args(1..))]
+    pub method_max_concurrent_requests: Vec<String>,
+    /// Subscriptions a websocket connection to the reader may hold at once.
+    #[clap(long, default_value = "100")]
+    pub max_subscriptions_per_connection: u32,
+    /// Levels of `All`, `Any`, `And` and `Or` the event filters of subscriptions may nest.
+    #[clap(long, default_value = "8")]
+    pub subscription_filter_max_depth: usize,
+    /// Filters the event filter of a subscription may be made of, combining ones included.
+    #[clap(long, default_value = "64")]
+    pub subscription_filter_max_nodes: usize,
+    /// Identifies clients by the `x-forwarded-for` header, for readers behind a proxy that
+    /// sets it.
+    #[clap(long)]
+    pub rate_limit_use_forwarded_for: bool,
+    /// Origins browsers may call the reader from, as `scheme://host[:port]`. Any origin may
+    /// when unset, or those of the `ACCESS_CONTROL_ALLOW_ORIGIN` environment variable.
+    #[clap(long, num_args(1..))]
+    pub cors_allowed_origins: Vec<String>,
+    /// Headers browsers may send to the reader besides those of the client SDKs.
+    #[clap(long, num_args(1..))]
+    pub cors_allowed_headers: Vec<String>,
+    /// Methods that browsers of an origin are restricted to, as `origin=method`, with `*` as
+    /// the origin restricting all origins without entries of their own.
+    #[clap(long, num_args(1..))]
+    pub cors_origin_methods: Vec<String>,
+    /// File of the key signing the results of some methods of the reader, as proof of what it
+    /// returned. Results are not signed when unset.
+    #[clap(long)]
+    pub response_signing_key_path: Option<PathBuf>,
+    /// Methods whose results are signed, the transaction responses and checkpoints when unset.
+    #[clap(long, num_args(1..))]
+    pub response_signing_methods: Vec<String>,
+    /// `{...}` substitutions the Display template of a single object may render.
+    #[clap(long, default_value = "256")]
+    pub display_max_substitutions: usize,
+    /// `.`-separated segments a field path of a Display template may have.
+    #[clap(long, default_value = "10")]
+    pub display_max_field_depth: usize,
+    /// Milliseconds rendering the Display of a single object may take.
+    #[clap(long, default_value = "100")]
+    pub display_time_budget_ms: u64,
 }
 
 impl IndexerConfig {
@@ -136,6 +345,109 @@ impl IndexerConfig {
         ))
     }
 
+    pub fn retention_policy(&self) -> Option<RetentionPolicy> {
+        match (self.retention_checkpoints, self.retention_epochs) {
+            (Some(checkpoints), _) => Some(RetentionPolicy::Checkpoints(checkpoints)),
+            (None, Some(epochs)) => Some(RetentionPolicy::Epochs(epochs)),
+            (None, None) => None,
+        }
+    }
+
+    /// Scheduling of the reader methods over its `pool_size` connections: heavy methods run on
+    /// all but the reserved connections, and light methods on all of them.
+    pub fn query_scheduler_config(&self, pool_size: u32) -> QuerySchedulerConfig {
+        let heavy_concurrency = pool_size
+            .saturating_sub(self.reserved_light_connections)
+            .max(1);
+        QuerySchedulerConfig {
+            heavy_methods: self.heavy_methods.iter().cloned().collect(),
+            heavy_concurrency: heavy_concurrency as usize,
+            light_concurrency: pool_size as usize,
+        }
+    }
+
+    /// Limits of the reader clients, with the method overrides parsed.
+    pub fn rate_limiter_config(&self) -> Result<RateLimiterConfig, IndexerError> {
+        let mut method_limits = BTreeMap::<String, MethodLimits>::new();
+        for entry in &self.method_weights {
+            let (method, weight) = parse_method_limit(entry)?;
+            method_limits.entry(method).or_default().weight = Some(weight);
+        }
+        for entry in &self.method_max_concurrent_requests {
+            let (method, max_concurrent) = parse_method_limit(entry)?;
+            method_limits.entry(method).or_default().max_concurrent = Some(max_concurrent);
+        }
+        Ok(RateLimiterConfig {
+            weight_per_second: self.rate_limit_weight_per_second,
+            burst: self.rate_limit_burst,
+            default_max_concurrent: self.max_concurrent_requests_per_method,
+            method_limits,
+            max_subscriptions_per_connection: self.max_subscriptions_per_connection,
+            use_forwarded_for: self.rate_limit_use_forwarded_for,
+        })
+    }
+
+    /// Cross-origin access of the reader, when any of it is configured.
+    pub fn cors_config(&self) -> Result<Option<CorsConfig>, IndexerError> {
+        if self.cors_allowed_origins.is_empty()
+            && self.cors_allowed_headers.is_empty()
+            && self.cors_origin_methods.is_empty()
+        {
+            return Ok(None);
+        }
+        let mut origin_methods = BTreeMap::<String, BTreeSet<String>>::new();
+        for entry in &self.cors_origin_methods {
+            let (origin, method) = entry.split_once('=').ok_or_else(|| {
+                IndexerError::InvalidArgumentError(format!(
+                    "Invalid origin method {entry}, expected `origin=method`"
+                ))
+            })?;
+            origin_methods
+                .entry(origin.to_string())
+                .or_default()
+                .insert(method.to_string());
+        }
+        Ok(Some(CorsConfig {
+            allowed_origins: self.cors_allowed_origins.clone(),
+            allowed_headers: self.cors_allowed_headers.clone(),
+            origin_methods,
+        }))
+    }
+
+    /// Signing of the results of the reader, when a signing key is configured.
+    pub fn response_signing_config(&self) -> Option<ResponseSigningConfig> {
+        let key_path = self.response_signing_key_path.clone()?;
+        Some(ResponseSigningConfig {
+            key_path,
+            methods: self.response_signing_methods.clone(),
+        })
+    }
+
+    /// Limits applied when rendering the Display of objects returned by the reader.
+    pub fn display_render_limits(&self) -> DisplayRenderLimits {
+        DisplayRenderLimits {
+            max_substitutions: self.display_max_substitutions,
+            max_field_depth: self.display_max_field_depth,
+            time_budget: Duration::from_millis(self.display_time_budget_ms),
+        }
+    }
+
+    /// Bounds on the event filters of subscriptions to the reader.
+    pub fn event_filter_limits(&self) -> EventFilterLimits {
+        EventFilterLimits {
+            max_depth: self.subscription_filter_max_depth,
+            max_nodes: self.subscription_filter_max_nodes,
+        }
+    }
+
+    /// Limits of the checkpoints the fetcher downloads ahead of the handlers.
+    pub fn checkpoint_buffer_limits(&self) -> BufferLimits {
+        BufferLimits {
+            max_checkpoints: self.checkpoint_buffer_size,
+            max_bytes: self.checkpoint_buffer_max_bytes,
+        }
+    }
+
     pub fn all_implemented_methods() -> Vec<String> {
         IMPLEMENTED_METHODS.iter().map(|&s| s.to_string()).collect()
     }
@@ -164,6 +476,13 @@ impl Default for IndexerConfig {
             db_port: None,
             db_name: None,
             rpc_client_url: "http://127.0.0.1:9000".to_string(),
+            secondary_rpc_client_url: None,
+            checkpoint_archive_dir: None,
+            checkpoint_gap_grace_period_secs: 60,
+            checkpoint_fetch_concurrency: 100,
+            checkpoint_buffer_size: 1000,
+            checkpoint_buffer_max_bytes: None,
+            shutdown_deadline_secs: 30,
             client_metric_host: "0.0.0.0".to_string(),
             client_metric_port: 9184,
             rpc_server_url: "0.0.0.0".to_string(),
@@ -175,10 +494,67 @@ impl Default for IndexerConfig {
             analytical_worker: false,
             skip_db_commit: false,
             use_v2: false,
+            source_maps_dir: None,
+            event_denylist_path: None,
+            admin_port: None,
+            fullnode_fallback_url: None,
+            fullnode_fallback_timeout_ms: 2000,
+            dry_run_upstream_url: None,
+            dry_run_upstream_timeout_ms: 10000,
+            retention_checkpoints: None,
+            retention_epochs: None,
+            pruning_batch_size: 1000,
+            pruning_interval_secs: 60,
+            reconciliation_rpc_client_url: None,
+            reconciliation_interval_secs: 10,
+            storage_stats_interval_secs: 600,
+            orphaned_dynamic_fields_scan_interval_secs: 60,
+            orphaned_dynamic_fields_scan_batch_size: 1000,
+            backfill_tx_object_indices: false,
+            coin_registry_id: None,
+            object_ref_event_types: vec![],
+            heavy_methods: vec![
+                "mgox_queryEvents".to_string(),
+                "mgox_queryTransactionBlocks".to_string(),
+            ],
+            reserved_light_connections: 10,
+            rate_limit_weight_per_second: None,
+            rate_limit_burst: 100,
+            method_weights: vec![],
+            max_concurrent_requests_per_method: None,
+            method_max_concurrent_requests: vec![],
+            max_subscriptions_per_connection: 100,
+            subscription_filter_max_depth: EventFilterLimits::DEFAULT_MAX_DEPTH,
+            subscription_filter_max_nodes: EventFilterLimits::DEFAULT_MAX_NODES,
+            rate_limit_use_forwarded_for: false,
+            cors_allowed_origins: vec![],
+            cors_allowed_headers: vec![],
+            cors_origin_methods: vec![],
+            response_signing_key_path: None,
+            response_signing_methods: vec![],
+            display_max_substitutions: DisplayRenderLimits::DEFAULT_MAX_SUBSTITUTIONS,
+            display_max_field_depth: DisplayRenderLimits::DEFAULT_MAX_FIELD_DEPTH,
+            display_time_budget_ms: DisplayRenderLimits::DEFAULT_TIME_BUDGET.as_millis() as u64,
         }
     }
 }
 
+/// Parses a `method=value` override of a method limit.
+fn parse_method_limit<T: FromStr>(entry: &str) -> Result<(String, T), IndexerError>
+where
+    T::Err: std::fmt::Display,
+{
+    let (method, value) = entry.split_once('=').ok_or_else(|| {
+        IndexerError::InvalidArgumentError(format!(
+            "Invalid method limit {entry}, expected `method=value`"
+        ))
+    })?;
+    let value = value.parse().map_err(|e| {
+        IndexerError::InvalidArgumentError(format!("Invalid limit of method {method}: {e}"))
+    })?;
+    Ok((method.to_string(), value))
+}
+
 pub struct Indexer;
 
 impl Indexer {
@@ -201,37 +577,107 @@ impl Indexer {
                 .expect("Json rpc server should not run into errors upon start.");
             handle.stopped().await;
         } else if config.fullnode_sync_worker {
-            info!("Starting indexer with only fullnode sync");
-            let mut processor_orchestrator =
-                ProcessorOrchestrator::new(store.clone(), metrics.clone());
-            spawn_monitored_task!(processor_orchestrator.run_forever());
-
-            // -1 will be returned when checkpoints table is empty.
-            let last_seq_from_db = store
-                .get_latest_tx_checkpoint_sequence_number()
-                .await
-                .expect("Failed to get latest tx checkpoint sequence number from DB");
-            let last_downloaded_checkpoint = if last_seq_from_db < 0 {
-                None
-            } else {
-                Some(last_seq_from_db as u64)
-            };
-
-            let (checkpoint_handler, object_handler) = new_handlers(store, metrics, config);
-
-            IndexerBuilder::new()
-                .last_downloaded_checkpoint(last_downloaded_checkpoint)
-                .rest_url(&config.rpc_client_url)
-                .handler(checkpoint_handler)
-                .handler(object_handler)
-                .run()
-                .await;
+            Self::start_fullnode_sync(config, store, metrics).await?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "legacy-handlers"))]
+    async fn start_fullnode_sync<S: IndexerStore + Sync + Send + Clone + 'static>(
+        _config: &IndexerConfig,
+        _store: S,
+        _metrics: IndexerMetrics,
+    ) -> Result<(), IndexerError> {
+        Err(IndexerError::NotSupportedError(
+            "Fullnode sync of the v1 indexer requires the `legacy-handlers` feature".to_string(),
+        ))
+    }
+
+    #[cfg(feature = "legacy-handlers")]
+    async fn start_fullnode_sync<S: IndexerStore + Sync + Send + Clone + 'static>(
+        config: &IndexerConfig,
+        store: S,
+        metrics: IndexerMetrics,
+    ) -> Result<(), IndexerError> {
+        info!("Starting indexer with only fullnode sync");
+        let mut processor_orchestrator =
+            ProcessorOrchestrator::new(store.clone(), metrics.clone());
+        spawn_monitored_task!(processor_orchestrator.run_forever());
+
+        // -1 will be returned when checkpoints table is empty.
+        let last_seq_from_db = store
+            .get_latest_tx_checkpoint_sequence_number()
+            .await
+            .expect("Failed to get latest tx checkpoint sequence number from DB");
+        let last_downloaded_checkpoint = if last_seq_from_db < 0 {
+            None
+        } else {
+            Some(last_seq_from_db as u64)
+        };
+
+        let fetcher_metrics = metrics.fetcher.clone();
+        let progress_store = IndexerStoreProgress::new(store.clone());
+        let (checkpoint_handler, object_handler) = new_handlers(store, metrics, config);
+
+        let mut builder = IndexerBuilder::new()
+            .last_downloaded_checkpoint(last_downloaded_checkpoint)
+            .rest_url(&config.rpc_client_url)
+            .checkpoint_gap_grace_period(Duration::from_secs(
+                config.checkpoint_gap_grace_period_secs,
+            ))
+            .checkpoint_fetch_concurrency(config.checkpoint_fetch_concurrency)
+            .checkpoint_buffer_size(config.checkpoint_buffer_size)
+            .with_shutdown(shutdown_on_termination())
+            .shutdown_deadline(Duration::from_secs(config.shutdown_deadline_secs))
+            .fetcher_metrics(fetcher_metrics)
+            .with_progress_store(progress_store)
+            .register_handler(checkpoint_handler)
+            .register_handler(object_handler);
+        if let Some(secondary_rpc_client_url) = &config.secondary_rpc_client_url {
+            builder = builder.secondary_rest_url(secondary_rpc_client_url);
+        }
+        if let Some(checkpoint_archive_dir) = &config.checkpoint_archive_dir {
+            builder = builder.local_archive_dir(checkpoint_archive_dir);
+        }
+        if let Some(checkpoint_buffer_max_bytes) = config.checkpoint_buffer_max_bytes {
+            builder = builder.checkpoint_buffer_max_bytes(checkpoint_buffer_max_bytes);
         }
+        builder.run().await;
 
         Ok(())
     }
 }
 
+/// A signal set once the process receives SIGTERM or ctrl-c.
+pub(crate) fn shutdown_on_termination() -> tokio::sync::watch::Receiver<bool> {
+    let (sender, receiver) = tokio::sync::watch::channel(false);
+    tokio::spawn(async move {
+        let ctrl_c = async {
+            tokio::signal::ctrl_c()
+                .await
+                .expect("failed to install Ctrl+C handler");
+        };
+        #[cfg(unix)]
+        let terminate = async {
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+                .expect("failed to install signal handler")
+                .recv()
+                .await;
+        };
+        #[cfg(not(unix))]
+        let terminate = std::future::pending::<()>();
+
+        tokio::select! {
+            _ = ctrl_c => {},
+            _ = terminate => {},
+        }
+        info!("Termination signal received, shutting down the indexer");
+        let _ = sender.send(true);
+    });
+    receiver
+}
+
 // TODO(gegaowp): this is only used in validation now, will remove in a separate PR
 // together with the validation codes.
 pub async fn new_rpc_client(http_url: &str) -> Result<MgoClient, IndexerError> {
@@ -296,12 +742,17 @@ pub struct PgConnectionPoolConfig {
     pool_size: u32,
     connection_timeout: Duration,
     statement_timeout: Duration,
+    /// Blocking tasks of the reader running at once, as many as connections in the pool if not
+    /// set.
+    blocking_task_permits: Option<usize>,
+    blocking_task_wait_timeout: Duration,
 }
 
 impl PgConnectionPoolConfig {
     const DEFAULT_POOL_SIZE: u32 = 100;
     const DEFAULT_CONNECTION_TIMEOUT: u64 = 30;
     const DEFAULT_STATEMENT_TIMEOUT: u64 = 30;
+    const DEFAULT_BLOCKING_TASK_WAIT_TIMEOUT: u64 = 10;
 
     fn connection_config(&self) -> PgConnectionConfig {
         PgConnectionConfig {
@@ -321,6 +772,19 @@ impl PgConnectionPoolConfig {
     pub fn set_statement_timeout(&mut self, timeout: Duration) {
         self.statement_timeout = timeout;
     }
+
+    pub fn set_blocking_task_permits(&mut self, permits: usize) {
+        self.blocking_task_permits = Some(permits);
+    }
+
+    /// How long a reader task waits for a permit before it is turned away as overloaded.
+    pub fn set_blocking_task_wait_timeout(&mut self, timeout: Duration) {
+        self.blocking_task_wait_timeout = timeout;
+    }
+
+    fn blocking_task_permits(&self) -> usize {
+        self.blocking_task_permits.unwrap_or(self.pool_size as usize)
+    }
 }
 
 impl Default for PgConnectionPoolConfig {
@@ -337,11 +801,20 @@ impl Default for PgConnectionPoolConfig {
             .ok()
             .and_then(|s| s.parse::<u64>().ok())
             .unwrap_or(Self::DEFAULT_STATEMENT_TIMEOUT);
+        let blocking_task_permits = std::env::var("DB_BLOCKING_TASK_PERMITS")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok());
+        let blocking_task_wait_timeout_secs = std::env::var("DB_BLOCKING_TASK_WAIT_TIMEOUT")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(Self::DEFAULT_BLOCKING_TASK_WAIT_TIMEOUT);
 
         Self {
             pool_size: db_pool_size,
             connection_timeout: Duration::from_secs(conn_timeout_secs),
             statement_timeout: Duration::from_secs(statement_timeout_secs),
+            blocking_task_permits,
+            blocking_task_wait_timeout: Duration::from_secs(blocking_task_wait_timeout_secs),
         }
     }
 }
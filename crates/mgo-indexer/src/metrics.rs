@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use prometheus::{
-    register_histogram_with_registry, register_int_counter_with_registry,
-    register_int_gauge_with_registry, Histogram, IntCounter, IntGauge, Registry,
+    register_histogram_vec_with_registry, register_histogram_with_registry,
+    register_int_counter_vec_with_registry, register_int_counter_with_registry,
+    register_int_gauge_vec_with_registry, register_int_gauge_with_registry, Histogram,
+    HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Registry,
 };
 
 /// Prometheus metrics for mgo-indexer.
@@ -13,6 +15,11 @@ const LATENCY_SEC_BUCKETS: &[f64] = &[
     80.0, 100.0, 200.0,
 ];
 
+/// Number of items returned by a paginated query.
+const RESULT_SIZE_BUCKETS: &[f64] = &[
+    0.0, 1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0,
+];
+
 const DB_COMMIT_LATENCY_SEC_BUCKETS: &[f64] = &[
     0.001, 0.005, 0.01, 0.02, 0.05, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0, 2.0, 3.0,
     5.0, 10.0, 20.0, 40.0, 60.0, 80.0, 100.0, 200.0,
@@ -35,6 +42,7 @@ pub struct IndexerMetrics {
     // analytical
     pub latest_move_call_metrics_tx_seq: IntGauge,
     pub latest_address_metrics_tx_seq: IntGauge,
+    pub latest_active_address_stats_tx_seq: IntGauge,
     pub latest_network_metrics_cp_seq: IntGauge,
     // checkpoint E2E latency is:
     // fullnode_download_latency + checkpoint_index_latency + db_commit_latency
@@ -49,6 +57,7 @@ pub struct IndexerMetrics {
     pub indexing_get_object_db_hit: IntCounter,
     pub indexing_module_resolver_in_mem_hit: IntCounter,
     pub indexing_module_resolver_in_mem_miss: IntCounter,
+    pub dropped_denied_events: IntCounterVec,
     pub indexing_packages_latency: Histogram,
     pub checkpoint_objects_index_latency: Histogram,
     pub checkpoint_db_commit_latency: Histogram,
@@ -108,6 +117,11 @@ pub struct IndexerMetrics {
 
     pub address_processor_failure: IntCounter,
     pub checkpoint_metrics_processor_failure: IntCounter,
+    pub fetcher: CheckpointFetcherMetrics,
+    pub pruner: PrunerMetrics,
+    pub reconciler: ReconcilerMetrics,
+    pub storage_stats: StorageStatsMetrics,
+    pub orphaned_dynamic_fields: OrphanedDynamicFieldsMetrics,
 }
 
 impl IndexerMetrics {
@@ -194,6 +208,11 @@ impl IndexerMetrics {
                 "Latest address metrics tx seq",
                 registry,
             ).unwrap(),
+            latest_active_address_stats_tx_seq: register_int_gauge_with_registry!(
+                "latest_active_address_stats_tx_seq",
+                "Latest tx seq folded into the active address sketches",
+                registry,
+            ).unwrap(),
             latest_network_metrics_cp_seq: register_int_gauge_with_registry!(
                 "latest_network_metrics_cp_seq",
                 "Latest network metrics cp seq",
@@ -280,6 +299,13 @@ impl IndexerMetrics {
                 registry,
             )
             .unwrap(),
+            dropped_denied_events: register_int_counter_vec_with_registry!(
+                "dropped_denied_events",
+                "Total number of events not stored because their type is denylisted",
+                &["event_type"],
+                registry,
+            )
+            .unwrap(),
             checkpoint_objects_index_latency: register_histogram_with_registry!(
                 "checkpoint_object_index_latency",
                 "Time spent in indexing a checkpoint objects",
@@ -614,6 +640,11 @@ impl IndexerMetrics {
                 registry,
             )
             .unwrap(),
+            fetcher: CheckpointFetcherMetrics::new(registry),
+            pruner: PrunerMetrics::new(registry),
+            reconciler: ReconcilerMetrics::new(registry),
+            storage_stats: StorageStatsMetrics::new(registry),
+            orphaned_dynamic_fields: OrphanedDynamicFieldsMetrics::new(registry),
         }
     }
 }
@@ -642,3 +673,291 @@ impl IndexerObjectProcessorMetrics {
         }
     }
 }
+
+#[derive(Clone, Debug)]
+pub struct CheckpointFetcherMetrics {
+    pub total_checkpoint_gaps_detected: IntCounter,
+    pub checkpoint_gap_size: IntGauge,
+    pub total_checkpoints_fetched_from_secondary: IntCounter,
+    pub checkpoint_downloads_in_flight: IntGauge,
+    pub checkpoint_downloads_buffered: IntGauge,
+    pub checkpoints_awaiting_handlers: IntGauge,
+    pub checkpoint_bytes_awaiting_handlers: IntGauge,
+    pub fetcher_pause_duration: Histogram,
+}
+
+impl CheckpointFetcherMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            total_checkpoint_gaps_detected: register_int_counter_with_registry!(
+                "total_checkpoint_gaps_detected",
+                "Total number of gaps detected in the checkpoints served by the primary source",
+                registry,
+            )
+            .unwrap(),
+            checkpoint_gap_size: register_int_gauge_with_registry!(
+                "checkpoint_gap_size",
+                "Number of checkpoints missing in the gap the fetcher is stalled on, 0 if none",
+                registry,
+            )
+            .unwrap(),
+            total_checkpoints_fetched_from_secondary: register_int_counter_with_registry!(
+                "total_checkpoints_fetched_from_secondary",
+                "Total number of missing checkpoints fetched from the secondary source",
+                registry,
+            )
+            .unwrap(),
+            checkpoint_downloads_in_flight: register_int_gauge_with_registry!(
+                "checkpoint_downloads_in_flight",
+                "Number of checkpoint downloads the fetcher is waiting on",
+                registry,
+            )
+            .unwrap(),
+            checkpoint_downloads_buffered: register_int_gauge_with_registry!(
+                "checkpoint_downloads_buffered",
+                "Number of downloaded checkpoints held until the ones before them are delivered",
+                registry,
+            )
+            .unwrap(),
+            checkpoints_awaiting_handlers: register_int_gauge_with_registry!(
+                "checkpoints_awaiting_handlers",
+                "Number of checkpoints delivered by fetchers that handlers have not processed yet",
+                registry,
+            )
+            .unwrap(),
+            checkpoint_bytes_awaiting_handlers: register_int_gauge_with_registry!(
+                "checkpoint_bytes_awaiting_handlers",
+                "Estimated size in bytes of the checkpoints awaiting handlers",
+                registry,
+            )
+            .unwrap(),
+            fetcher_pause_duration: register_histogram_with_registry!(
+                "checkpoint_fetcher_pause_duration",
+                "Time fetchers spent paused for their handlers to catch up",
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct IndexerReaderMetrics {
+    pub db_pool_wait_latency: Histogram,
+    pub blocking_tasks_active: IntGauge,
+    pub total_statement_timeouts: IntCounter,
+    pub total_overloaded_requests: IntCounter,
+}
+
+impl IndexerReaderMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            db_pool_wait_latency: register_histogram_with_registry!(
+                "reader_db_pool_wait_latency",
+                "Time spent by the reader waiting for a connection from its pool",
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+            blocking_tasks_active: register_int_gauge_with_registry!(
+                "reader_blocking_tasks_active",
+                "Number of blocking database tasks of the reader holding a permit",
+                registry,
+            )
+            .unwrap(),
+            total_statement_timeouts: register_int_counter_with_registry!(
+                "total_reader_statement_timeouts",
+                "Total number of reader queries cancelled by the statement timeout",
+                registry,
+            )
+            .unwrap(),
+            total_overloaded_requests: register_int_counter_with_registry!(
+                "total_reader_overloaded_requests",
+                "Total number of reader tasks turned away as no permit freed up in time",
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+/// Latency and result size of the paginated query methods of the reader, by method and by
+/// the kind of filter they were called with.
+#[derive(Clone, Debug)]
+pub struct QueryFilterMetrics {
+    pub query_latency: HistogramVec,
+    pub query_result_size: HistogramVec,
+}
+
+impl QueryFilterMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            query_latency: register_histogram_vec_with_registry!(
+                "reader_query_latency_by_filter",
+                "Latency of paginated queries by method and kind of filter",
+                &["method", "filter"],
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+            query_result_size: register_histogram_vec_with_registry!(
+                "reader_query_result_size_by_filter",
+                "Number of items returned by paginated queries by method and kind of filter",
+                &["method", "filter"],
+                RESULT_SIZE_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PrunerMetrics {
+    pub pruned_rows: IntCounterVec,
+    pub pruned_partitions: IntCounterVec,
+    pub pruned_checkpoint_watermark: IntGauge,
+    pub pruner_lag_checkpoints: IntGauge,
+}
+
+impl PrunerMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            pruned_rows: register_int_counter_vec_with_registry!(
+                "pruned_rows",
+                "Total number of rows deleted by the pruner, by table",
+                &["table"],
+                registry,
+            )
+            .unwrap(),
+            pruned_partitions: register_int_counter_vec_with_registry!(
+                "pruned_partitions",
+                "Total number of partitions dropped by the pruner, by table",
+                &["table"],
+                registry,
+            )
+            .unwrap(),
+            pruned_checkpoint_watermark: register_int_gauge_with_registry!(
+                "pruned_checkpoint_watermark",
+                "Checkpoint that the data of all checkpoints before it has been pruned",
+                registry,
+            )
+            .unwrap(),
+            pruner_lag_checkpoints: register_int_gauge_with_registry!(
+                "pruner_lag_checkpoints",
+                "Number of checkpoints between the pruned watermark and the lowest handler watermark",
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ReconcilerMetrics {
+    pub reconciled_checkpoint_watermark: IntGauge,
+    pub mismatched_checkpoints: IntCounter,
+    pub tombstoned_rows: IntCounter,
+}
+
+impl ReconcilerMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            reconciled_checkpoint_watermark: register_int_gauge_with_registry!(
+                "reconciled_checkpoint_watermark",
+                "Checkpoint that all checkpoints before it have been verified by the reconciler",
+                registry,
+            )
+            .unwrap(),
+            mismatched_checkpoints: register_int_counter_with_registry!(
+                "reconciler_mismatched_checkpoints",
+                "Total number of ingested checkpoints whose digest differed from the certified one",
+                registry,
+            )
+            .unwrap(),
+            tombstoned_rows: register_int_counter_with_registry!(
+                "reconciler_tombstoned_rows",
+                "Total number of rows of mismatched checkpoints tombstoned by the reconciler",
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+/// The latest sample of the storage stats of the tables, by table.
+#[derive(Clone, Debug)]
+pub struct StorageStatsMetrics {
+    pub table_row_estimate: IntGaugeVec,
+    pub table_total_bytes: IntGaugeVec,
+    pub table_rows_per_day: IntGaugeVec,
+    pub table_bytes_per_day: IntGaugeVec,
+}
+
+impl StorageStatsMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            table_row_estimate: register_int_gauge_vec_with_registry!(
+                "table_row_estimate",
+                "Estimated number of rows of a table",
+                &["table"],
+                registry,
+            )
+            .unwrap(),
+            table_total_bytes: register_int_gauge_vec_with_registry!(
+                "table_total_bytes",
+                "Bytes on disk of a table, its indexes and TOAST data",
+                &["table"],
+                registry,
+            )
+            .unwrap(),
+            table_rows_per_day: register_int_gauge_vec_with_registry!(
+                "table_rows_per_day",
+                "Rows a table grew by per day, once it has been sampled for a day",
+                &["table"],
+                registry,
+            )
+            .unwrap(),
+            table_bytes_per_day: register_int_gauge_vec_with_registry!(
+                "table_bytes_per_day",
+                "Bytes a table grew by per day, once it has been sampled for a day",
+                &["table"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct OrphanedDynamicFieldsMetrics {
+    pub latest_scanned_checkpoint: IntGauge,
+    pub scanned_parents: IntCounter,
+    pub orphaned_dynamic_fields: IntGauge,
+}
+
+impl OrphanedDynamicFieldsMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            latest_scanned_checkpoint: register_int_gauge_with_registry!(
+                "orphaned_dynamic_fields_latest_scanned_checkpoint",
+                "Last checkpoint whose changed parents were scanned for orphaned dynamic fields",
+                registry,
+            )
+            .unwrap(),
+            scanned_parents: register_int_counter_with_registry!(
+                "orphaned_dynamic_fields_scanned_parents",
+                "Total number of parents whose dynamic object fields were scanned",
+                registry,
+            )
+            .unwrap(),
+            orphaned_dynamic_fields: register_int_gauge_with_registry!(
+                "orphaned_dynamic_fields",
+                "Number of dynamic object fields reported as orphans",
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
@@ -0,0 +1,288 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Bytea};
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::IndexerError;
+use crate::schema_v2::{active_address_sketches, address_cohorts};
+
+pub const MS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+/// Number of distinct addresses a sketch counts exactly before switching to HyperLogLog.
+pub const EXACT_SKETCH_THRESHOLD: usize = 1024;
+
+/// log2 of the number of HyperLogLog registers, giving a standard error of about 1.6%.
+const HLL_PRECISION: u32 = 12;
+const HLL_REGISTERS: usize = 1 << HLL_PRECISION;
+
+/// UTC day, counted from the unix epoch, that `timestamp_ms` falls on.
+pub fn day_of_timestamp(timestamp_ms: i64) -> i64 {
+    timestamp_ms.div_euclid(MS_PER_DAY)
+}
+
+/// Distinct count of a set of addresses. The addresses themselves are kept until there are more
+/// than [`EXACT_SKETCH_THRESHOLD`] of them, after which only HyperLogLog registers are.
+///
+/// Inserting an address that was already counted leaves the sketch unchanged, so replaying a
+/// range of transactions into a persisted sketch never inflates it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActiveAddressSketch {
+    Exact(BTreeSet<Vec<u8>>),
+    HyperLogLog(Vec<u8>),
+}
+
+impl Default for ActiveAddressSketch {
+    fn default() -> Self {
+        Self::Exact(BTreeSet::new())
+    }
+}
+
+impl ActiveAddressSketch {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, IndexerError> {
+        bcs::from_bytes(bytes).map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Failed to deserialize active address sketch: {e}"
+            ))
+        })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, IndexerError> {
+        bcs::to_bytes(self).map_err(|e| {
+            IndexerError::SerdeError(format!("Failed to serialize active address sketch: {e}"))
+        })
+    }
+
+    pub fn insert(&mut self, address: &[u8]) {
+        match self {
+            Self::Exact(addresses) => {
+                if addresses.insert(address.to_vec()) && addresses.len() > EXACT_SKETCH_THRESHOLD {
+                    *self = Self::HyperLogLog(hll_registers(addresses.iter()));
+                }
+            }
+            Self::HyperLogLog(registers) => hll_insert(registers, address),
+        }
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        match other {
+            Self::Exact(addresses) => {
+                for address in addresses {
+                    self.insert(address);
+                }
+            }
+            Self::HyperLogLog(other_registers) => match self {
+                Self::Exact(addresses) => {
+                    let mut registers = other_registers.clone();
+                    for address in addresses.iter() {
+                        hll_insert(&mut registers, address);
+                    }
+                    *self = Self::HyperLogLog(registers);
+                }
+                Self::HyperLogLog(registers) => {
+                    for (register, other) in registers.iter_mut().zip(other_registers) {
+                        *register = (*register).max(*other);
+                    }
+                }
+            },
+        }
+    }
+
+    /// Number of distinct addresses, exact below [`EXACT_SKETCH_THRESHOLD`].
+    pub fn count(&self) -> u64 {
+        match self {
+            Self::Exact(addresses) => addresses.len() as u64,
+            Self::HyperLogLog(registers) => hll_estimate(registers),
+        }
+    }
+}
+
+fn hll_registers<'a>(addresses: impl Iterator<Item = &'a Vec<u8>>) -> Vec<u8> {
+    let mut registers = vec![0; HLL_REGISTERS];
+    for address in addresses {
+        hll_insert(&mut registers, address);
+    }
+    registers
+}
+
+fn hll_insert(registers: &mut [u8], address: &[u8]) {
+    let digest = Blake2b256::digest(address).digest;
+    let hash = u64::from_be_bytes(digest[..8].try_into().unwrap());
+    let index = (hash >> (64 - HLL_PRECISION)) as usize;
+    // Rank of the first set bit among the remaining bits, capped when all of them are zero.
+    let rank = ((hash << HLL_PRECISION).leading_zeros() + 1).min(64 - HLL_PRECISION + 1) as u8;
+    registers[index] = registers[index].max(rank);
+}
+
+fn hll_estimate(registers: &[u8]) -> u64 {
+    let m = registers.len() as f64;
+    let alpha = 0.7213 / (1.0 + 1.079 / m);
+    let sum: f64 = registers.iter().map(|r| 2f64.powi(-(*r as i32))).sum();
+    let estimate = alpha * m * m / sum;
+    let zeros = registers.iter().filter(|r| **r == 0).count();
+    // Linear counting is more accurate while many registers are still empty.
+    if estimate <= 2.5 * m && zeros > 0 {
+        (m * (m / zeros as f64).ln()).round() as u64
+    } else {
+        estimate.round() as u64
+    }
+}
+
+/// First day an address sent a transaction on.
+#[derive(Clone, Debug, Queryable, Insertable)]
+#[diesel(table_name = address_cohorts)]
+pub struct StoredAddressCohort {
+    pub address: Vec<u8>,
+    pub cohort_day: i64,
+}
+
+/// Addresses of the `cohort_day` cohort that sent a transaction on `day`.
+#[derive(Clone, Debug, Queryable, Insertable)]
+#[diesel(table_name = active_address_sketches)]
+pub struct StoredActiveAddressSketch {
+    pub day: i64,
+    pub cohort_day: i64,
+    pub sketch: Vec<u8>,
+    pub last_processed_tx: i64,
+}
+
+/// Sender of a transaction and the day it was executed on.
+#[derive(Clone, Debug, QueryableByName)]
+pub struct StoredSenderDay {
+    #[diesel(sql_type = BigInt)]
+    pub tx_sequence_number: i64,
+    #[diesel(sql_type = Bytea)]
+    pub address: Vec<u8>,
+    #[diesel(sql_type = BigInt)]
+    pub day: i64,
+}
+
+/// Folds `senders`, in transaction order, into the cohorts and the per day and cohort sketches.
+/// `cohorts` must hold the already known cohort of every sender; senders seen for the first
+/// time join the cohort of the day of their first transaction and are returned.
+pub fn fold_active_senders(
+    senders: &[StoredSenderDay],
+    cohorts: &mut HashMap<Vec<u8>, i64>,
+    sketches: &mut BTreeMap<(i64, i64), ActiveAddressSketch>,
+) -> Vec<StoredAddressCohort> {
+    let mut new_cohorts = vec![];
+    for sender in senders {
+        let cohort_day = *cohorts.entry(sender.address.clone()).or_insert_with(|| {
+            new_cohorts.push(StoredAddressCohort {
+                address: sender.address.clone(),
+                cohort_day: sender.day,
+            });
+            sender.day
+        });
+        sketches
+            .entry((sender.day, cohort_day))
+            .or_default()
+            .insert(&sender.address);
+    }
+    new_cohorts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(i: u32) -> Vec<u8> {
+        let mut address = vec![0; 32];
+        address[..4].copy_from_slice(&i.to_be_bytes());
+        address
+    }
+
+    fn sender(i: u32, day: i64) -> StoredSenderDay {
+        StoredSenderDay {
+            tx_sequence_number: 0,
+            address: address(i),
+            day,
+        }
+    }
+
+    fn daily_active(sketches: &BTreeMap<(i64, i64), ActiveAddressSketch>, day: i64) -> u64 {
+        let mut merged = ActiveAddressSketch::default();
+        for (_, sketch) in sketches.range((day, i64::MIN)..=(day, i64::MAX)) {
+            merged.merge(sketch);
+        }
+        merged.count()
+    }
+
+    #[test]
+    fn test_daily_active_and_retention_are_exact_below_threshold() {
+        // Day 0: senders 0..100. Day 1: senders 50..150. Day 2: senders 0..25 and 140..200.
+        let senders = (0..100)
+            .map(|i| sender(i, 0))
+            .chain((50..150).map(|i| sender(i, 1)))
+            .chain((0..25).chain(140..200).map(|i| sender(i, 2)))
+            // Repeated transactions of the same senders do not count twice.
+            .chain((0..25).map(|i| sender(i, 2)))
+            .collect::<Vec<_>>();
+
+        let mut cohorts = HashMap::new();
+        let mut sketches = BTreeMap::new();
+        let (first_batch, second_batch) = senders.split_at(130);
+        let mut new_cohorts = fold_active_senders(first_batch, &mut cohorts, &mut sketches);
+
+        // Restarting replays part of the first batch against the persisted state.
+        let mut persisted = sketches
+            .iter()
+            .map(|(key, sketch)| (*key, sketch.to_bytes().unwrap()))
+            .map(|(key, bytes)| (key, ActiveAddressSketch::from_bytes(&bytes).unwrap()))
+            .collect::<BTreeMap<_, _>>();
+        let replayed = &senders[80..];
+        new_cohorts.extend(fold_active_senders(replayed, &mut cohorts, &mut persisted));
+        assert_eq!(new_cohorts.len(), 200);
+        fold_active_senders(second_batch, &mut cohorts, &mut sketches);
+        assert_eq!(persisted, sketches);
+
+        assert_eq!(daily_active(&sketches, 0), 100);
+        assert_eq!(daily_active(&sketches, 1), 100);
+        assert_eq!(daily_active(&sketches, 2), 85);
+
+        let cohort_size = |day| cohorts.values().filter(|d| **d == day).count() as u64;
+        assert_eq!(cohort_size(0), 100);
+        assert_eq!(cohort_size(1), 50);
+        assert_eq!(cohort_size(2), 50);
+
+        // Cohort of day 0: 50 of 100 active on day 1, 25 on day 2.
+        assert_eq!(sketches[&(1, 0)].count(), 50);
+        assert_eq!(sketches[&(2, 0)].count(), 25);
+        // Cohort of day 1: senders 100..150, of which 140..150 active on day 2.
+        assert_eq!(sketches[&(2, 1)].count(), 10);
+        assert!(!sketches.contains_key(&(1, 2)));
+    }
+
+    #[test]
+    fn test_sketch_switches_to_hyperloglog_above_threshold() {
+        let total = 20_000;
+        let mut sketch = ActiveAddressSketch::default();
+        for i in 0..EXACT_SKETCH_THRESHOLD as u32 {
+            sketch.insert(&address(i));
+        }
+        assert!(matches!(sketch, ActiveAddressSketch::Exact(_)));
+        assert_eq!(sketch.count(), EXACT_SKETCH_THRESHOLD as u64);
+
+        let mut other = ActiveAddressSketch::default();
+        for i in EXACT_SKETCH_THRESHOLD as u32..total {
+            sketch.insert(&address(i));
+            other.insert(&address(i));
+        }
+        assert!(matches!(sketch, ActiveAddressSketch::HyperLogLog(_)));
+
+        // Merging a subset and reinserting known addresses leaves the estimate unchanged.
+        let estimate = sketch.count();
+        sketch.merge(&other);
+        sketch.insert(&address(0));
+        assert_eq!(sketch.count(), estimate);
+        let error = (estimate as f64 - total as f64).abs() / total as f64;
+        assert!(error < 0.05, "estimate {estimate} too far from {total}");
+
+        let roundtrip = ActiveAddressSketch::from_bytes(&sketch.to_bytes().unwrap()).unwrap();
+        assert_eq!(roundtrip, sketch);
+    }
+}
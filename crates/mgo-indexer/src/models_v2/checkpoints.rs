@@ -31,6 +31,8 @@ pub struct StoredCheckpoint {
     pub checkpoint_commitments: Vec<u8>,
     pub validator_signature: Vec<u8>,
     pub end_of_epoch_data: Option<Vec<u8>>,
+    pub raw_checkpoint_summary: Option<Vec<u8>>,
+    pub raw_checkpoint_contents: Option<Vec<u8>>,
 }
 
 impl From<&IndexedCheckpoint> for StoredCheckpoint {
@@ -62,6 +64,8 @@ impl From<&IndexedCheckpoint> for StoredCheckpoint {
                 .as_ref()
                 .map(|d| bcs::to_bytes(d).unwrap()),
             end_of_epoch: c.end_of_epoch_data.is_some(),
+            raw_checkpoint_summary: Some(bcs::to_bytes(&c.certified_summary).unwrap()),
+            raw_checkpoint_contents: Some(bcs::to_bytes(&c.contents).unwrap()),
         }
     }
 }
@@ -0,0 +1,64 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+
+use crate::schema_v2::{coin_supply_changes, coin_supply_offsets};
+use crate::types_v2::IndexedCoinSupplyChange;
+
+#[derive(Clone, Debug, Queryable, Insertable)]
+#[diesel(table_name = coin_supply_changes)]
+pub struct StoredCoinSupplyChange {
+    pub coin_type: String,
+    pub checkpoint_sequence_number: i64,
+    pub epoch: i64,
+    pub timestamp_ms: i64,
+    pub minted: i64,
+    pub burned: i64,
+}
+
+#[derive(Clone, Debug, Queryable, Insertable)]
+#[diesel(table_name = coin_supply_offsets)]
+pub struct StoredCoinSupplyOffset {
+    pub coin_type: String,
+    pub checkpoint_sequence_number: i64,
+    pub initial_supply: i64,
+    pub approximate: bool,
+}
+
+/// Amounts minted and burned over an epoch or a day.
+#[derive(Clone, Debug, QueryableByName)]
+pub struct CoinSupplyBucket {
+    #[diesel(sql_type = BigInt)]
+    pub bucket: i64,
+    #[diesel(sql_type = BigInt)]
+    pub minted: i64,
+    #[diesel(sql_type = BigInt)]
+    pub burned: i64,
+}
+
+impl From<&IndexedCoinSupplyChange> for StoredCoinSupplyChange {
+    fn from(change: &IndexedCoinSupplyChange) -> Self {
+        Self {
+            coin_type: change.coin_type.clone(),
+            checkpoint_sequence_number: change.checkpoint_sequence_number as i64,
+            epoch: change.epoch as i64,
+            timestamp_ms: change.timestamp_ms as i64,
+            minted: change.minted as i64,
+            burned: change.burned as i64,
+        }
+    }
+}
+
+impl From<&IndexedCoinSupplyChange> for StoredCoinSupplyOffset {
+    /// The offset of the coin type of `change`, if it is the first change of its supply.
+    fn from(change: &IndexedCoinSupplyChange) -> Self {
+        Self {
+            coin_type: change.coin_type.clone(),
+            checkpoint_sequence_number: change.checkpoint_sequence_number as i64,
+            initial_supply: change.supply_before as i64,
+            approximate: !change.cap_created,
+        }
+    }
+}
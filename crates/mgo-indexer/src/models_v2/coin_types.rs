@@ -0,0 +1,59 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use mgo_json_rpc_types::CoinTypeInfo;
+use mgo_types::base_types::ObjectID;
+
+use crate::errors::IndexerError;
+use crate::schema_v2::coin_types;
+use crate::types_v2::IndexedCoinType;
+
+#[derive(Clone, Debug, Queryable, Insertable)]
+#[diesel(table_name = coin_types)]
+pub struct StoredCoinType {
+    pub coin_type: String,
+    pub first_seen_checkpoint: i64,
+    pub metadata_object_id: Option<Vec<u8>>,
+    pub holder_count: i64,
+    pub holders_checkpoint: i64,
+}
+
+impl From<&IndexedCoinType> for StoredCoinType {
+    fn from(coin_type: &IndexedCoinType) -> Self {
+        Self {
+            coin_type: coin_type.coin_type.clone(),
+            first_seen_checkpoint: coin_type.checkpoint_sequence_number as i64,
+            metadata_object_id: coin_type.metadata_object_id.map(|id| id.to_vec()),
+            holder_count: 0,
+            // Holder changes of the checkpoint the type is first seen in are yet to be counted.
+            holders_checkpoint: coin_type.checkpoint_sequence_number as i64 - 1,
+        }
+    }
+}
+
+impl TryFrom<StoredCoinType> for CoinTypeInfo {
+    type Error = IndexerError;
+
+    fn try_from(stored: StoredCoinType) -> Result<Self, Self::Error> {
+        let metadata_object_id = stored
+            .metadata_object_id
+            .map(|id| {
+                ObjectID::from_bytes(&id).map_err(|e| {
+                    IndexerError::PersistentStorageDataCorruptionError(format!(
+                        "Failed to parse metadata object ID of coin type {}: {e}",
+                        stored.coin_type
+                    ))
+                })
+            })
+            .transpose()?;
+        Ok(Self {
+            coin_type: stored.coin_type,
+            first_seen_checkpoint: stored.first_seen_checkpoint as u64,
+            metadata_object_id,
+            // The count is approximate, and never reported below zero.
+            holder_count: stored.holder_count.max(0) as u64,
+        })
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use mgo_json_rpc_types::EpochMetrics;
+
+use crate::schema_v2::{epoch_metrics, epoch_metrics_addresses, epoch_metrics_senders};
+use crate::types_v2::IndexedEpochMetrics;
+
+#[derive(Clone, Debug, PartialEq, Eq, Queryable, Insertable)]
+#[diesel(table_name = epoch_metrics)]
+pub struct StoredEpochMetrics {
+    pub epoch: i64,
+    pub first_checkpoint: i64,
+    pub last_checkpoint: i64,
+    pub total_transactions: i64,
+    pub total_gas_used: i64,
+    pub total_storage_rebate: i64,
+    pub active_senders: i64,
+    pub new_addresses: i64,
+    pub finalized: bool,
+}
+
+#[derive(Clone, Debug, Insertable)]
+#[diesel(table_name = epoch_metrics_senders)]
+pub struct StoredEpochMetricsSender {
+    pub epoch: i64,
+    pub address: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Insertable)]
+#[diesel(table_name = epoch_metrics_addresses)]
+pub struct StoredEpochMetricsAddress {
+    pub address: Vec<u8>,
+    pub first_epoch: i64,
+}
+
+impl StoredEpochMetrics {
+    /// Whether the metrics already count `checkpoint`.
+    pub fn counts(&self, checkpoint: u64) -> bool {
+        self.last_checkpoint >= checkpoint as i64
+    }
+
+    /// The metrics of the epoch of `metrics` once its checkpoint is rolled up into `stored`, the
+    /// metrics of the checkpoints before it, if any. `new_senders` and `new_addresses` are the
+    /// senders and addresses of the checkpoint not seen before in the epoch and ever.
+    pub fn roll_up(
+        stored: Option<Self>,
+        metrics: &IndexedEpochMetrics,
+        new_senders: usize,
+        new_addresses: usize,
+    ) -> Self {
+        let stored = stored.unwrap_or(Self {
+            epoch: metrics.epoch as i64,
+            first_checkpoint: metrics.checkpoint_sequence_number as i64,
+            last_checkpoint: metrics.checkpoint_sequence_number as i64,
+            total_transactions: 0,
+            total_gas_used: 0,
+            total_storage_rebate: 0,
+            active_senders: 0,
+            new_addresses: 0,
+            finalized: false,
+        });
+        Self {
+            last_checkpoint: metrics.checkpoint_sequence_number as i64,
+            total_transactions: stored.total_transactions + metrics.transactions as i64,
+            total_gas_used: stored.total_gas_used + metrics.gas_used as i64,
+            total_storage_rebate: stored.total_storage_rebate + metrics.storage_rebate as i64,
+            active_senders: stored.active_senders + new_senders as i64,
+            new_addresses: stored.new_addresses + new_addresses as i64,
+            finalized: metrics.end_of_epoch,
+            ..stored
+        }
+    }
+}
+
+impl From<StoredEpochMetrics> for EpochMetrics {
+    fn from(stored: StoredEpochMetrics) -> Self {
+        Self {
+            epoch: stored.epoch as u64,
+            epoch_total_transactions: stored.total_transactions as u64,
+            first_checkpoint_id: stored.first_checkpoint as u64,
+            last_checkpoint_id: stored.last_checkpoint as u64,
+            total_gas_used: stored.total_gas_used as u64,
+            total_storage_rebate: stored.total_storage_rebate as u64,
+            active_senders: stored.active_senders as u64,
+            new_addresses: stored.new_addresses as u64,
+            finalized: stored.finalized,
+        }
+    }
+}
@@ -16,7 +16,7 @@ use mgo_types::object::MoveObject;
 use mgo_types::parse_mgo_struct_tag;
 
 use crate::errors::IndexerError;
-use crate::schema_v2::events;
+use crate::schema_v2::{event_object_refs, events};
 use crate::types_v2::IndexedEvent;
 
 #[derive(Queryable, QueryableByName, Insertable, Debug, Clone)]
@@ -74,6 +74,30 @@ impl From<IndexedEvent> for StoredEvent {
     }
 }
 
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = event_object_refs)]
+pub struct StoredEventObjectRef {
+    pub object_id: Vec<u8>,
+    pub tx_sequence_number: i64,
+    pub event_sequence_number: i64,
+    pub event_type: String,
+}
+
+impl StoredEventObjectRef {
+    pub fn from_event(event: &IndexedEvent) -> Vec<Self> {
+        event
+            .object_refs
+            .iter()
+            .map(|object_id| Self {
+                object_id: object_id.to_vec(),
+                tx_sequence_number: event.tx_sequence_number as i64,
+                event_sequence_number: event.event_sequence_number as i64,
+                event_type: event.event_type.clone(),
+            })
+            .collect()
+    }
+}
+
 impl StoredEvent {
     pub fn try_into_mgo_event(
         self,
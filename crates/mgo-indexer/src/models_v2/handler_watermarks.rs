@@ -0,0 +1,13 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use crate::schema_v2::handler_watermarks;
+
+#[derive(Clone, Debug, Queryable, Insertable)]
+#[diesel(table_name = handler_watermarks)]
+pub struct StoredHandlerWatermark {
+    pub handler_name: String,
+    pub checkpoint_sequence_number: i64,
+}
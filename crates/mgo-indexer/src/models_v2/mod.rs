@@ -1,15 +1,26 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod active_address_sketches;
 pub mod address_metrics;
 pub mod checkpoints;
+pub mod coin_supply;
+pub mod coin_types;
 pub mod display;
 pub mod epoch;
+pub mod epoch_metrics;
 pub mod events;
+pub mod handler_watermarks;
 pub mod move_call_metrics;
 pub mod network_metrics;
 pub mod objects;
+pub mod orphaned_dynamic_fields;
 pub mod packages;
+pub mod pruner_watermark;
+pub mod reconciler_watermark;
+pub mod storage_stats;
 pub mod transactions;
 pub mod tx_count_metrics;
 pub mod tx_indices;
+pub mod validator_metrics;
+pub mod writer_version;
@@ -7,7 +7,7 @@ use mgo_json_rpc::coin_api::parse_to_struct_tag;
 
 use diesel::prelude::*;
 use move_bytecode_utils::module_cache::GetModule;
-use mgo_json_rpc_types::{Balance, Coin as MgoCoin};
+use mgo_json_rpc_types::{Balance, Coin as MgoCoin, HistoricalOwner, OwnershipInterval};
 use mgo_types::base_types::{ObjectID, ObjectRef, SequenceNumber};
 use mgo_types::digests::ObjectDigest;
 use mgo_types::dynamic_field::{DynamicFieldInfo, DynamicFieldName, DynamicFieldType, Field};
@@ -102,6 +102,42 @@ impl From<StoredObject> for StoredHistoryObject {
     }
 }
 
+impl TryFrom<StoredHistoryObject> for StoredObject {
+    type Error = IndexerError;
+
+    /// Only versions of active objects can be converted, wrapped or deleted versions have no
+    /// contents.
+    fn try_from(o: StoredHistoryObject) -> Result<Self, Self::Error> {
+        let missing = |column: &str| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "History of object {:?} at version {} has no {column}",
+                o.object_id, o.object_version
+            ))
+        };
+        let object_digest = o.object_digest.ok_or_else(|| missing("digest"))?;
+        let owner_type = o.owner_type.ok_or_else(|| missing("owner type"))?;
+        let serialized_object = o
+            .serialized_object
+            .ok_or_else(|| missing("serialized object"))?;
+        Ok(Self {
+            object_id: o.object_id,
+            object_version: o.object_version,
+            object_digest,
+            checkpoint_sequence_number: o.checkpoint_sequence_number,
+            owner_type,
+            owner_id: o.owner_id,
+            object_type: o.object_type,
+            serialized_object,
+            coin_type: o.coin_type,
+            coin_balance: o.coin_balance,
+            df_kind: o.df_kind,
+            df_name: o.df_name,
+            df_object_type: o.df_object_type,
+            df_object_id: o.df_object_id,
+        })
+    }
+}
+
 #[derive(Queryable, Insertable, Debug, Identifiable, Clone, QueryableByName)]
 #[diesel(table_name = objects, primary_key(object_id))]
 pub struct StoredDeletedObject {
@@ -140,6 +176,63 @@ impl From<StoredDeletedObject> for StoredDeletedHistoryObject {
     }
 }
 
+/// The version of an object starting an interval over which it kept the same owner, with the
+/// checkpoint the next interval starts at.
+#[derive(QueryableByName, Debug)]
+pub struct StoredOwnershipInterval {
+    #[diesel(sql_type = diesel::sql_types::Bytea)]
+    pub object_id: Vec<u8>,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub object_version: i64,
+    #[diesel(sql_type = diesel::sql_types::SmallInt)]
+    pub object_status: i16,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub checkpoint_sequence_number: i64,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Bytea>)]
+    pub serialized_object: Option<Vec<u8>>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::BigInt>)]
+    pub next_checkpoint_sequence_number: Option<i64>,
+}
+
+impl TryFrom<StoredOwnershipInterval> for OwnershipInterval {
+    type Error = IndexerError;
+
+    fn try_from(stored: StoredOwnershipInterval) -> Result<Self, Self::Error> {
+        let (owner, acquiring_tx) = match ObjectStatus::try_from(stored.object_status)? {
+            ObjectStatus::Active => {
+                let bytes = stored.serialized_object.ok_or_else(|| {
+                    IndexerError::PersistentStorageDataCorruptionError(format!(
+                        "History of object {:?} at version {} has no serialized object",
+                        stored.object_id, stored.object_version
+                    ))
+                })?;
+                let object: Object = bcs::from_bytes(&bytes).map_err(|e| {
+                    IndexerError::SerdeError(format!(
+                        "Failed to deserialize object: {:?}, error: {e}",
+                        stored.object_id
+                    ))
+                })?;
+                (
+                    HistoricalOwner::Owner(object.owner),
+                    Some(object.previous_transaction),
+                )
+            }
+            // An object that is live again was unwrapped, deleted objects never come back.
+            ObjectStatus::WrappedOrDeleted if stored.next_checkpoint_sequence_number.is_some() => {
+                (HistoricalOwner::Wrapped, None)
+            }
+            ObjectStatus::WrappedOrDeleted => (HistoricalOwner::WrappedOrDeleted, None),
+        };
+        Ok(OwnershipInterval {
+            owner,
+            from_version: SequenceNumber::from_u64(stored.object_version as u64),
+            from_checkpoint: stored.checkpoint_sequence_number as u64,
+            to_checkpoint: stored.next_checkpoint_sequence_number.map(|c| c as u64),
+            acquiring_tx,
+        })
+    }
+}
+
 impl From<IndexedObject> for StoredObject {
     fn from(o: IndexedObject) -> Self {
         Self {
@@ -354,6 +447,9 @@ impl TryFrom<StoredObject> for MgoCoin {
             digest,
             balance: balance as u64,
             previous_transaction: object.previous_transaction,
+            verified: None,
+            canonical_symbol: None,
+            canonical_icon_url: None,
         })
     }
 }
@@ -385,6 +481,9 @@ impl TryFrom<CoinBalance> for Balance {
             // TODO: deal with overflow
             total_balance: c.coin_balance as u128,
             locked_balance: HashMap::default(),
+            verified: None,
+            canonical_symbol: None,
+            canonical_icon_url: None,
         })
     }
 }
@@ -0,0 +1,74 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+use diesel::sql_types::Bytea;
+
+use mgo_json_rpc_types::OrphanedDynamicField;
+use mgo_types::base_types::ObjectID;
+use mgo_types::dynamic_field::DynamicFieldName;
+use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
+
+use crate::errors::IndexerError;
+use crate::schema_v2::orphaned_dynamic_fields;
+
+#[derive(Queryable, Insertable, Clone, Debug, PartialEq, Eq)]
+#[diesel(table_name = orphaned_dynamic_fields)]
+pub struct StoredOrphanedDynamicField {
+    pub field_id: Vec<u8>,
+    pub parent_id: Vec<u8>,
+    pub df_name: Vec<u8>,
+    pub df_object_type: String,
+    pub value_object_id: Vec<u8>,
+    pub checkpoint_sequence_number: i64,
+    pub detected_at_ms: i64,
+}
+
+/// A parent of dynamic object fields changed in the checkpoints scanned.
+#[derive(QueryableByName, Clone, Debug)]
+pub struct ChangedParent {
+    #[diesel(sql_type = Bytea)]
+    pub parent_id: Vec<u8>,
+}
+
+/// Outcome of a scan of the parents changed in a range of checkpoints.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OrphanedDynamicFieldsScan {
+    /// Last checkpoint scanned
+    pub checkpoint: CheckpointSequenceNumber,
+    pub parents_scanned: usize,
+    /// Orphans reported for the first time
+    pub orphans_found: usize,
+    /// Orphans no longer reported, because their field was removed or their value is live again
+    pub orphans_cleared: usize,
+    /// Orphans reported after the scan
+    pub orphan_count: u64,
+}
+
+impl TryFrom<StoredOrphanedDynamicField> for OrphanedDynamicField {
+    type Error = IndexerError;
+
+    fn try_from(stored: StoredOrphanedDynamicField) -> Result<Self, Self::Error> {
+        let id = |bytes: Vec<u8>| {
+            ObjectID::from_bytes(bytes).map_err(|e| {
+                IndexerError::PersistentStorageDataCorruptionError(format!(
+                    "Failed to parse object ID of an orphaned dynamic field: {e}"
+                ))
+            })
+        };
+        let field_id = id(stored.field_id)?;
+        let name: DynamicFieldName = bcs::from_bytes(&stored.df_name).map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Orphaned dynamic field {field_id} has an invalid df_name: {e}"
+            ))
+        })?;
+        Ok(OrphanedDynamicField {
+            field_id,
+            parent_id: id(stored.parent_id)?,
+            name,
+            value_object_id: id(stored.value_object_id)?,
+            value_object_type: stored.df_object_type,
+            detected_at_checkpoint: stored.checkpoint_sequence_number as u64,
+        })
+    }
+}
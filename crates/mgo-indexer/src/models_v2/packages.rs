@@ -1,10 +1,13 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::schema_v2::packages;
+use crate::errors::IndexerError;
+use crate::schema_v2::{package_deps, packages};
 use crate::types_v2::IndexedPackage;
 
 use diesel::prelude::*;
+use mgo_json_rpc_types::PackageDependency;
+use mgo_types::base_types::{ObjectID, SequenceNumber};
 
 #[derive(Queryable, Insertable, Clone, Debug, Identifiable)]
 #[diesel(table_name = packages, primary_key(package_id))]
@@ -21,3 +24,61 @@ impl From<IndexedPackage> for StoredPackage {
         }
     }
 }
+
+#[derive(Queryable, Insertable, Clone, Debug, PartialEq, Eq)]
+#[diesel(table_name = package_deps)]
+pub struct StoredPackageDependency {
+    pub package_id: Vec<u8>,
+    pub package_version: i64,
+    pub original_package_id: Vec<u8>,
+    pub dependency_original_id: Vec<u8>,
+    pub dependency_id: Vec<u8>,
+    pub dependency_version: i64,
+    pub checkpoint_sequence_number: i64,
+    pub latest: bool,
+}
+
+impl StoredPackageDependency {
+    /// The packages `package` links to, as the latest edges of the package.
+    pub fn from_package(package: &IndexedPackage) -> Vec<Self> {
+        let move_package = &package.move_package;
+        let original_package_id = move_package.original_package_id().to_vec();
+        move_package
+            .linkage_table()
+            .iter()
+            .map(|(dependency_original_id, upgrade_info)| Self {
+                package_id: package.package_id.to_vec(),
+                package_version: move_package.version().value() as i64,
+                original_package_id: original_package_id.clone(),
+                dependency_original_id: dependency_original_id.to_vec(),
+                dependency_id: upgrade_info.upgraded_id.to_vec(),
+                dependency_version: upgrade_info.upgraded_version.value() as i64,
+                checkpoint_sequence_number: package.checkpoint_sequence_number as i64,
+                latest: true,
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<StoredPackageDependency> for PackageDependency {
+    type Error = IndexerError;
+
+    fn try_from(stored: StoredPackageDependency) -> Result<Self, Self::Error> {
+        let id = |bytes: Vec<u8>| {
+            ObjectID::from_bytes(bytes).map_err(|e| {
+                IndexerError::PersistentStorageDataCorruptionError(format!(
+                    "Failed to parse package ID of a package dependency: {e}"
+                ))
+            })
+        };
+        Ok(PackageDependency {
+            package_id: id(stored.package_id)?,
+            package_version: SequenceNumber::from_u64(stored.package_version as u64),
+            original_package_id: id(stored.original_package_id)?,
+            dependency_id: id(stored.dependency_id)?,
+            dependency_version: SequenceNumber::from_u64(stored.dependency_version as u64),
+            dependency_original_id: id(stored.dependency_original_id)?,
+            checkpoint: stored.checkpoint_sequence_number as u64,
+        })
+    }
+}
@@ -0,0 +1,14 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use crate::schema_v2::pruner_watermark;
+
+#[derive(Clone, Debug, Queryable, Insertable)]
+#[diesel(table_name = pruner_watermark)]
+pub struct StoredPrunerWatermark {
+    pub id: bool,
+    pub checkpoint_hi_exclusive: i64,
+    pub tx_hi_exclusive: i64,
+}
@@ -0,0 +1,13 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use crate::schema_v2::reconciler_watermark;
+
+#[derive(Clone, Debug, Queryable, Insertable)]
+#[diesel(table_name = reconciler_watermark)]
+pub struct StoredReconcilerWatermark {
+    pub id: bool,
+    pub checkpoint_hi_exclusive: i64,
+}
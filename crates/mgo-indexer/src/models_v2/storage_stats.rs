@@ -0,0 +1,116 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use diesel::prelude::*;
+
+use mgo_json_rpc_types::TableStorageStats;
+
+use crate::schema_v2::storage_stats;
+
+/// Window the growth of tables is measured over.
+pub const STORAGE_GROWTH_WINDOW_MS: i64 = 24 * 60 * 60 * 1000;
+
+#[derive(Clone, Debug, PartialEq, Eq, Queryable, QueryableByName, Insertable)]
+#[diesel(table_name = storage_stats)]
+pub struct StoredStorageStats {
+    pub table_name: String,
+    pub sampled_at_ms: i64,
+    pub row_estimate: i64,
+    pub total_bytes: i64,
+}
+
+impl StoredStorageStats {
+    /// The latest sample of every table taken at or before `at_ms`.
+    pub fn load_latest_at(conn: &mut PgConnection, at_ms: i64) -> QueryResult<Vec<Self>> {
+        storage_stats::table
+            .filter(storage_stats::sampled_at_ms.le(at_ms))
+            .distinct_on(storage_stats::table_name)
+            .order_by((
+                storage_stats::table_name,
+                storage_stats::sampled_at_ms.desc(),
+            ))
+            .load(conn)
+    }
+
+    /// The stats of the tables sampled in `latest`, with their growth per day since their
+    /// sample in `baseline`, which were taken at least [`STORAGE_GROWTH_WINDOW_MS`] earlier.
+    pub fn with_growth(latest: Vec<Self>, baseline: Vec<Self>) -> Vec<TableStorageStats> {
+        let baseline: BTreeMap<_, _> = baseline
+            .into_iter()
+            .map(|stats| (stats.table_name.clone(), stats))
+            .collect();
+        latest
+            .into_iter()
+            .map(|stats| {
+                let growth = baseline.get(&stats.table_name).and_then(|base| {
+                    let elapsed_ms = stats.sampled_at_ms - base.sampled_at_ms;
+                    (elapsed_ms >= STORAGE_GROWTH_WINDOW_MS).then(|| {
+                        let per_day = |now: i64, then: i64| {
+                            ((now - then) as i128 * STORAGE_GROWTH_WINDOW_MS as i128
+                                / elapsed_ms as i128) as i64
+                        };
+                        (
+                            per_day(stats.row_estimate, base.row_estimate),
+                            per_day(stats.total_bytes, base.total_bytes),
+                        )
+                    })
+                });
+                TableStorageStats {
+                    table_name: stats.table_name,
+                    row_estimate: stats.row_estimate.max(0) as u64,
+                    total_bytes: stats.total_bytes.max(0) as u64,
+                    rows_per_day: growth.map(|(rows, _)| rows),
+                    bytes_per_day: growth.map(|(_, bytes)| bytes),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(table_name: &str, sampled_at_ms: i64, rows: i64, bytes: i64) -> StoredStorageStats {
+        StoredStorageStats {
+            table_name: table_name.to_string(),
+            sampled_at_ms,
+            row_estimate: rows,
+            total_bytes: bytes,
+        }
+    }
+
+    #[test]
+    fn test_growth_is_scaled_to_a_day() {
+        let day = STORAGE_GROWTH_WINDOW_MS;
+        let latest = vec![
+            sample("events", 3 * day, 1_000, 80_000),
+            sample("objects", 3 * day, 500, 10_000),
+            sample("transactions", 3 * day, 300, 9_000),
+        ];
+        let baseline = vec![
+            // Sampled two days before, so growth is halved.
+            sample("events", day, 600, 40_000),
+            // Shrunk since.
+            sample("objects", 2 * day, 700, 12_000),
+        ];
+
+        let stats = StoredStorageStats::with_growth(latest, baseline);
+        let growth: Vec<_> = stats
+            .iter()
+            .map(|t| (t.table_name.as_str(), t.rows_per_day, t.bytes_per_day))
+            .collect();
+        assert_eq!(
+            growth,
+            vec![
+                ("events", Some(200), Some(20_000)),
+                ("objects", Some(-200), Some(-2_000)),
+                ("transactions", None, None),
+            ]
+        );
+        assert_eq!(stats[0].row_estimate, 1_000);
+        assert_eq!(stats[0].total_bytes, 80_000);
+    }
+}
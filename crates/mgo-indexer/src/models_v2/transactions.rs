@@ -4,17 +4,24 @@ use diesel::prelude::*;
 
 use move_bytecode_utils::module_cache::GetModule;
 use mgo_json_rpc_types::BalanceChange;
+use mgo_json_rpc_types::CreatedObjectSummary;
+use mgo_json_rpc_types::EffectsWithInput;
 use mgo_json_rpc_types::ObjectChange;
+use mgo_json_rpc_types::MgoPreviousTransactionDetails;
 use mgo_json_rpc_types::MgoTransactionBlock;
 use mgo_json_rpc_types::MgoTransactionBlockEffects;
 use mgo_json_rpc_types::MgoTransactionBlockEvents;
 use mgo_json_rpc_types::MgoTransactionBlockResponse;
 use mgo_json_rpc_types::MgoTransactionBlockResponseOptions;
+use mgo_json_rpc_types::MgoTransactionSignature;
+use mgo_json_rpc_types::TransactionBlockBcsV1;
 use mgo_types::digests::TransactionDigest;
 use mgo_types::effects::TransactionEffects;
+use mgo_types::effects::TransactionEffectsAPI;
 use mgo_types::effects::TransactionEvents;
 use mgo_types::event::Event;
 use mgo_types::transaction::SenderSignedData;
+use mgo_types::transaction::TransactionDataAPI;
 
 use crate::errors::IndexerError;
 use crate::schema_v2::transactions;
@@ -36,6 +43,7 @@ pub struct StoredTransaction {
     pub events: Vec<Option<Vec<u8>>>,
     pub transaction_kind: i16,
     pub success_command_count: i16,
+    pub created_objects: Option<Vec<Option<Vec<u8>>>>,
 }
 
 #[derive(Debug, Queryable)]
@@ -95,6 +103,12 @@ impl From<&IndexedTransaction> for StoredTransaction {
             timestamp_ms: tx.timestamp_ms as i64,
             transaction_kind: tx.transaction_kind.clone() as i16,
             success_command_count: tx.successful_tx_num as i16,
+            created_objects: Some(
+                tx.created_objects
+                    .iter()
+                    .map(|co| Some(bcs::to_bytes(&co).unwrap()))
+                    .collect(),
+            ),
         }
     }
 }
@@ -205,6 +219,25 @@ impl StoredTransaction {
             None
         };
 
+        let created_summary = if options.show_created_summary {
+            Some(self.try_into_created_summary(tx_digest)?)
+        } else {
+            None
+        };
+
+        let signatures = if options.show_signatures {
+            let sender_signed_data = self.try_into_sender_signed_data()?;
+            Some(
+                sender_signed_data
+                    .tx_signatures()
+                    .iter()
+                    .map(MgoTransactionSignature::from)
+                    .collect(),
+            )
+        } else {
+            None
+        };
+
         Ok(MgoTransactionBlockResponse {
             digest: tx_digest,
             transaction,
@@ -213,14 +246,64 @@ impl StoredTransaction {
             events,
             object_changes,
             balance_changes,
+            created_summary,
+            signatures,
             timestamp_ms: Some(self.timestamp_ms as u64),
+            checkpoint_timestamp_ms: Some(self.timestamp_ms as u64),
+            observed_execution_timestamp_ms: None,
             checkpoint: Some(self.checkpoint_sequence_number as u64),
             confirmed_local_execution: None,
+            executed_at_checkpoint_hint: None,
+            written_objects: None,
+            served_from: None,
+            checkpoint_digest: None,
+            epoch: None,
             errors: vec![],
             raw_effects: self.raw_effects,
         })
     }
 
+    /// Transactions indexed before `created_objects` was recorded fall back to the `Created`
+    /// entries of their object changes.
+    fn try_into_created_summary(
+        &self,
+        tx_digest: TransactionDigest,
+    ) -> IndexerResult<Vec<CreatedObjectSummary>> {
+        let Some(created_objects) = &self.created_objects else {
+            return Ok(self
+                .object_changes
+                .iter()
+                .flatten()
+                .map(|object_change| {
+                    bcs::from_bytes::<IndexedObjectChange>(object_change).map_err(|e| {
+                        IndexerError::PersistentStorageDataCorruptionError(format!(
+                            "Can't convert object_change bytes into IndexedObjectChange. tx_digest={:?} Error: {e}",
+                            tx_digest
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?
+                .iter()
+                .filter_map(IndexedObjectChange::created_summary)
+                .collect());
+        };
+        created_objects
+            .iter()
+            .map(|created_object| match created_object {
+                Some(created_object) => bcs::from_bytes(created_object).map_err(|e| {
+                    IndexerError::PersistentStorageDataCorruptionError(format!(
+                        "Can't convert created_object bytes into CreatedObjectSummary. tx_digest={:?} Error: {e}",
+                        tx_digest
+                    ))
+                }),
+                None => Err(IndexerError::PersistentStorageDataCorruptionError(format!(
+                    "created_object should not be null, tx_digest={:?}",
+                    tx_digest
+                ))),
+            })
+            .collect()
+    }
+
     fn try_into_sender_signed_data(&self) -> IndexerResult<SenderSignedData> {
         let sender_signed_data: SenderSignedData =
             bcs::from_bytes(&self.raw_transaction).map_err(|e| {
@@ -242,4 +325,74 @@ impl StoredTransaction {
         let effects = MgoTransactionBlockEffects::try_from(effects)?;
         Ok(effects)
     }
+
+    /// The layout of the transaction returned by `multiGetTransactionBlocks` with the `bcs`
+    /// encoding.
+    pub fn try_into_transaction_block_bcs(&self) -> IndexerResult<TransactionBlockBcsV1> {
+        let digest =
+            TransactionDigest::try_from(self.transaction_digest.as_slice()).map_err(|e| {
+                IndexerError::PersistentStorageDataCorruptionError(format!(
+                    "Can't convert {:?} as tx_digest. Error: {e}",
+                    self.transaction_digest
+                ))
+            })?;
+        let effects: TransactionEffects = bcs::from_bytes(&self.raw_effects).map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Can't convert raw_effects of {} into TransactionEffects. Error: {e}",
+                self.tx_sequence_number
+            ))
+        })?;
+        let events = if effects.events_digest().is_some() {
+            let data = self
+                .events
+                .iter()
+                .map(|event| {
+                    let event = event.as_ref().ok_or_else(|| {
+                        IndexerError::PersistentStorageDataCorruptionError(format!(
+                            "Event should not be null, tx_digest={:?}",
+                            digest
+                        ))
+                    })?;
+                    bcs::from_bytes::<Event>(event).map_err(|e| {
+                        IndexerError::PersistentStorageDataCorruptionError(format!(
+                            "Can't convert event bytes into Event. tx_digest={:?} Error: {e}",
+                            digest
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Some(TransactionEvents { data })
+        } else {
+            None
+        };
+        Ok(TransactionBlockBcsV1 {
+            digest,
+            transaction: self.try_into_sender_signed_data()?,
+            effects,
+            events,
+            checkpoint: Some(self.checkpoint_sequence_number as u64),
+            timestamp_ms: Some(self.timestamp_ms as u64),
+        })
+    }
+
+    /// The summary of the transaction returned with the objects it created or last mutated.
+    pub fn try_into_previous_transaction_details(
+        &self,
+    ) -> IndexerResult<MgoPreviousTransactionDetails> {
+        let sender_signed_data = self.try_into_sender_signed_data()?;
+        Ok(MgoPreviousTransactionDetails {
+            sender: sender_signed_data.transaction_data().sender(),
+            checkpoint: Some(self.checkpoint_sequence_number as u64),
+            timestamp_ms: Some(self.timestamp_ms as u64),
+        })
+    }
+
+    /// Effects paired with the transaction data, as matched by `TransactionFilter`.
+    pub fn try_into_effects_with_input(&self) -> IndexerResult<EffectsWithInput> {
+        let sender_signed_data = self.try_into_sender_signed_data()?;
+        Ok(EffectsWithInput {
+            effects: self.try_into_mgo_transaction_effects()?,
+            input: sender_signed_data.transaction_data().clone(),
+        })
+    }
 }
@@ -2,10 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    schema_v2::{tx_calls, tx_changed_objects, tx_input_objects, tx_recipients, tx_senders},
+    schema_v2::{
+        tx_calls, tx_changed_objects, tx_input_objects, tx_loaded_child_objects, tx_recipients,
+        tx_senders, tx_signatures,
+    },
     types_v2::TxIndex,
 };
 use diesel::prelude::*;
+use mgo_json_rpc_types::MgoTransactionSignature;
 
 #[derive(QueryableByName)]
 pub struct TxSequenceNumber {
@@ -13,6 +17,17 @@ pub struct TxSequenceNumber {
     pub tx_sequence_number: i64,
 }
 
+/// A transaction together with its zero-based position inside its checkpoint.
+#[derive(QueryableByName)]
+pub struct TxCheckpointPosition {
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub tx_sequence_number: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub checkpoint_sequence_number: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub position_in_checkpoint: i64,
+}
+
 #[derive(QueryableByName)]
 pub struct TxDigest {
     #[diesel(sql_type = diesel::sql_types::Bytea)]
@@ -56,6 +71,44 @@ pub struct StoredTxCalls {
     pub func: String,
 }
 
+#[derive(Queryable, Insertable, Debug, Clone, Default)]
+#[diesel(table_name = tx_loaded_child_objects)]
+pub struct StoredTxLoadedChildObject {
+    pub transaction_digest: Vec<u8>,
+    pub object_id: Vec<u8>,
+    pub object_version: i64,
+}
+
+#[derive(Queryable, Insertable, Debug, Clone, Default)]
+#[diesel(table_name = tx_signatures)]
+pub struct StoredTxSignature {
+    pub tx_sequence_number: i64,
+    pub signature_index: i16,
+    /// 0 for the signature itself, i + 1 for the i-th participant of a multisig.
+    pub participant_index: i16,
+    pub scheme: i16,
+    pub public_key: Option<Vec<u8>>,
+    pub signature: Vec<u8>,
+}
+
+impl StoredTxSignature {
+    fn new(
+        tx_sequence_number: i64,
+        signature_index: usize,
+        participant_index: usize,
+        signature: &MgoTransactionSignature,
+    ) -> Self {
+        Self {
+            tx_sequence_number,
+            signature_index: signature_index as i16,
+            participant_index: participant_index as i16,
+            scheme: signature.scheme.flag() as i16,
+            public_key: signature.public_key.clone(),
+            signature: signature.signature.clone(),
+        }
+    }
+}
+
 #[allow(clippy::type_complexity)]
 impl TxIndex {
     pub fn split(
@@ -66,6 +119,8 @@ impl TxIndex {
         Vec<StoredTxInputObject>,
         Vec<StoredTxChangedObject>,
         Vec<StoredTxCalls>,
+        Vec<StoredTxLoadedChildObject>,
+        Vec<StoredTxSignature>,
     ) {
         let tx_sequence_number = self.tx_sequence_number as i64;
         let tx_senders = self
@@ -110,12 +165,41 @@ impl TxIndex {
                 func: f.to_string(),
             })
             .collect();
+        let tx_loaded_child_objects = self
+            .loaded_child_objects
+            .iter()
+            .map(|(id, version)| StoredTxLoadedChildObject {
+                transaction_digest: self.transaction_digest.into_inner().to_vec(),
+                object_id: id.to_vec(),
+                object_version: version.value() as i64,
+            })
+            .collect();
+        let tx_signatures = self
+            .signatures
+            .iter()
+            .map(MgoTransactionSignature::from)
+            .enumerate()
+            .flat_map(|(i, signature)| {
+                let participants = signature
+                    .participants
+                    .iter()
+                    .enumerate()
+                    .map(|(j, participant)| {
+                        StoredTxSignature::new(tx_sequence_number, i, j + 1, participant)
+                    })
+                    .collect::<Vec<_>>();
+                std::iter::once(StoredTxSignature::new(tx_sequence_number, i, 0, &signature))
+                    .chain(participants)
+            })
+            .collect();
         (
             tx_senders,
             tx_recipients,
             tx_input_objects,
             tx_changed_objects,
             tx_calls,
+            tx_loaded_child_objects,
+            tx_signatures,
         )
     }
 }
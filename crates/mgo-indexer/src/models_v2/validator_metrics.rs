@@ -0,0 +1,73 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use mgo_json_rpc_types::ValidatorReport;
+use mgo_types::base_types::MgoAddress;
+
+use crate::errors::IndexerError;
+use crate::schema_v2::validator_epoch_metrics;
+use crate::types_v2::IndexedCommittee;
+
+#[derive(Clone, Debug, PartialEq, Eq, Queryable, Insertable)]
+#[diesel(table_name = validator_epoch_metrics)]
+pub struct StoredValidatorEpochMetrics {
+    pub epoch: i64,
+    pub validator_address: Vec<u8>,
+    pub committee_index: i32,
+    pub voting_power: i64,
+    pub last_checkpoint: i64,
+    pub checkpoints: i64,
+    pub checkpoints_signed: i64,
+    pub reporters: Option<i64>,
+    pub tallying_score: Option<i64>,
+}
+
+impl StoredValidatorEpochMetrics {
+    /// Metrics of the validators of `committee`, set by `checkpoint`, before any checkpoint of
+    /// their epoch is rolled up.
+    pub fn from_committee(committee: &IndexedCommittee, checkpoint: u64) -> Vec<Self> {
+        committee
+            .validators
+            .iter()
+            .enumerate()
+            .map(|(index, (address, voting_power))| Self {
+                epoch: committee.epoch as i64,
+                validator_address: address.to_vec(),
+                committee_index: index as i32,
+                voting_power: *voting_power as i64,
+                // Only the checkpoints after the one setting the committee count for it, but
+                // the genesis checkpoint sets the committee of its own epoch.
+                last_checkpoint: checkpoint as i64 - 1,
+                checkpoints: 0,
+                checkpoints_signed: 0,
+                reporters: None,
+                tallying_score: None,
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<StoredValidatorEpochMetrics> for ValidatorReport {
+    type Error = IndexerError;
+
+    fn try_from(stored: StoredValidatorEpochMetrics) -> Result<Self, Self::Error> {
+        let epoch = stored.epoch;
+        let validator_address = MgoAddress::from_bytes(stored.validator_address).map_err(|e| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Failed to parse validator address of epoch {epoch}: {e}"
+            ))
+        })?;
+        Ok(ValidatorReport {
+            epoch: epoch as u64,
+            validator_address,
+            voting_power: stored.voting_power as u64,
+            checkpoints: stored.checkpoints as u64,
+            checkpoints_signed: stored.checkpoints_signed as u64,
+            transactions_certified: None,
+            reporters: stored.reporters.map(|r| r as u64),
+            tallying_score: stored.tallying_score.map(|s| s as u64),
+        })
+    }
+}
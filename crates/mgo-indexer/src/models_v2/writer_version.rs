@@ -0,0 +1,13 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use crate::schema_v2::writer_version;
+
+#[derive(Clone, Debug, Queryable, Insertable)]
+#[diesel(table_name = writer_version)]
+pub struct StoredWriterVersion {
+    pub id: bool,
+    pub version: i64,
+}
@@ -0,0 +1,231 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Commit notices published by the checkpoint commit task after every committed batch,
+//! so that RPC-side subscription workers can wake up and read only the newly committed
+//! range instead of polling tables on a timer.
+//!
+//! Notices are delivered over Postgres `NOTIFY` when the reader can `LISTEN` on the DB,
+//! and over an in-process broadcast channel otherwise, which keeps single-binary
+//! deployments (writer and reader in one process) working without the DB mechanism.
+
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tokio_postgres::{AsyncMessage, NoTls};
+use tracing::{info, warn};
+
+use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
+
+/// Postgres channel the writer publishes commit notices on.
+pub const COMMIT_NOTICE_CHANNEL: &str = "mgo_indexer_checkpoint_committed";
+
+/// Capacity of the broadcast channels fanning notices out to subscription workers.
+/// Workers that fall further behind than this receive `RecvError::Lagged` and should
+/// resume from the last checkpoint they processed.
+const COMMIT_NOTICE_BUFFER_SIZE: usize = 1024;
+
+/// Delay before re-establishing a dropped `LISTEN` connection.
+const LISTEN_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Tables that can be touched by a single committed batch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommittedTable {
+    Checkpoints,
+    Transactions,
+    TxIndices,
+    Events,
+    Display,
+    Packages,
+    Objects,
+    ObjectsHistory,
+    Epochs,
+}
+
+/// A compact notice describing one committed batch of checkpoints.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitNotice {
+    pub first_checkpoint_sequence_number: CheckpointSequenceNumber,
+    pub last_checkpoint_sequence_number: CheckpointSequenceNumber,
+    pub tables: Vec<CommittedTable>,
+    /// Wall-clock time the batch was committed, used to measure delivery latency.
+    pub committed_at_ms: u64,
+}
+
+impl CommitNotice {
+    pub fn new(
+        first_checkpoint_sequence_number: CheckpointSequenceNumber,
+        last_checkpoint_sequence_number: CheckpointSequenceNumber,
+        tables: Vec<CommittedTable>,
+    ) -> Self {
+        Self {
+            first_checkpoint_sequence_number,
+            last_checkpoint_sequence_number,
+            tables,
+            committed_at_ms: now_ms(),
+        }
+    }
+
+    pub fn touches(&self, table: CommittedTable) -> bool {
+        self.tables.contains(&table)
+    }
+
+    /// Milliseconds elapsed between the commit and now.
+    pub fn delivery_latency_ms(&self) -> u64 {
+        now_ms().saturating_sub(self.committed_at_ms)
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+fn in_process_sender() -> &'static broadcast::Sender<CommitNotice> {
+    static SENDER: OnceLock<broadcast::Sender<CommitNotice>> = OnceLock::new();
+    SENDER.get_or_init(|| broadcast::channel(COMMIT_NOTICE_BUFFER_SIZE).0)
+}
+
+/// Publishes a notice to in-process listeners. Sending without any listener is not an error.
+pub fn publish_in_process(notice: CommitNotice) {
+    let _ = in_process_sender().send(notice);
+}
+
+/// Source of commit notices for RPC-side subscription workers.
+#[derive(Clone)]
+pub struct CommitNoticeListener {
+    sender: broadcast::Sender<CommitNotice>,
+}
+
+impl CommitNoticeListener {
+    /// Listener fed only by writers running in the same process.
+    pub fn in_process() -> Self {
+        Self {
+            sender: in_process_sender().clone(),
+        }
+    }
+
+    /// Listens on the Postgres commit notice channel, falling back to the in-process
+    /// channel when `LISTEN` cannot be established.
+    pub async fn start(db_url: &str) -> Self {
+        let (client, connection) = match tokio_postgres::connect(db_url, NoTls).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!(
+                    "Failed to connect to Postgres for commit notices, falling back to in-process notices: {e}"
+                );
+                return Self::in_process();
+            }
+        };
+        let (sender, _) = broadcast::channel(COMMIT_NOTICE_BUFFER_SIZE);
+        let listen_sender = sender.clone();
+        let db_url = db_url.to_string();
+        tokio::spawn(async move {
+            let mut next = Some((client, connection));
+            loop {
+                let (client, connection) = match next.take() {
+                    Some(conn) => conn,
+                    None => {
+                        tokio::time::sleep(LISTEN_RECONNECT_DELAY).await;
+                        match tokio_postgres::connect(&db_url, NoTls).await {
+                            Ok(conn) => conn,
+                            Err(e) => {
+                                warn!("Failed to reconnect for commit notices: {e}");
+                                continue;
+                            }
+                        }
+                    }
+                };
+                listen(client, connection, &listen_sender).await;
+                info!("Commit notice connection closed, reconnecting");
+            }
+        });
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<CommitNotice> {
+        self.sender.subscribe()
+    }
+}
+
+/// Drives a `LISTEN` connection until it fails, forwarding every notice to `sender`.
+async fn listen<S, T>(
+    client: tokio_postgres::Client,
+    mut connection: tokio_postgres::Connection<S, T>,
+    sender: &broadcast::Sender<CommitNotice>,
+) where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+    T: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let mut messages = futures::stream::poll_fn(move |cx| connection.poll_message(cx));
+    let listen = client.batch_execute(&format!("LISTEN {COMMIT_NOTICE_CHANNEL}"));
+    tokio::pin!(listen);
+    let mut listening = false;
+    loop {
+        tokio::select! {
+            res = &mut listen, if !listening => {
+                if let Err(e) = res {
+                    warn!("Failed to LISTEN on {COMMIT_NOTICE_CHANNEL}: {e}");
+                    return;
+                }
+                listening = true;
+            }
+            message = messages.next() => match message {
+                Some(Ok(AsyncMessage::Notification(n))) => {
+                    match serde_json::from_str::<CommitNotice>(n.payload()) {
+                        Ok(notice) => {
+                            let _ = sender.send(notice);
+                        }
+                        Err(e) => warn!("Malformed commit notice {:?}: {e}", n.payload()),
+                    }
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => {
+                    warn!("Commit notice connection failed: {e}");
+                    return;
+                }
+                None => return,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_in_process_delivery() {
+        let listener = CommitNoticeListener::in_process();
+        let mut receiver = listener.subscribe();
+        publish_in_process(CommitNotice::new(
+            1,
+            3,
+            vec![CommittedTable::Checkpoints, CommittedTable::Events],
+        ));
+        let notice = tokio::time::timeout(Duration::from_millis(100), receiver.recv())
+            .await
+            .expect("in-process notice should be delivered promptly")
+            .unwrap();
+        assert_eq!(notice.last_checkpoint_sequence_number, 3);
+        assert!(notice.touches(CommittedTable::Events));
+        assert!(!notice.touches(CommittedTable::Objects));
+    }
+
+    #[test]
+    fn test_notice_payload_roundtrip() {
+        let notice = CommitNotice::new(5, 5, vec![CommittedTable::TxIndices]);
+        let payload = serde_json::to_string(&notice).unwrap();
+        assert!(payload.contains("\"tx_indices\""));
+        assert_eq!(
+            serde_json::from_str::<CommitNotice>(&payload).unwrap(),
+            notice
+        );
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use tracing::info;
+
+use crate::metrics::IndexerMetrics;
+use crate::store::IndexerAnalyticalStore;
+use crate::types_v2::IndexerResult;
+
+const ACTIVE_ADDRESS_STATS_PROCESSOR_BATCH_SIZE: usize = 10000;
+
+/// Maintains the per day distinct sender sketches and the first-seen day of every sender.
+/// Transaction ranges are processed one at a time and in order, since the cohort of an address
+/// is the day of the first range it shows up in.
+pub struct ActiveAddressStatsProcessor<S> {
+    pub store: S,
+    metrics: IndexerMetrics,
+    pub active_address_stats_processor_batch_size: usize,
+}
+
+impl<S> ActiveAddressStatsProcessor<S>
+where
+    S: IndexerAnalyticalStore + Clone + Sync + Send + 'static,
+{
+    pub fn new(store: S, metrics: IndexerMetrics) -> ActiveAddressStatsProcessor<S> {
+        let active_address_stats_processor_batch_size =
+            std::env::var("ACTIVE_ADDRESS_STATS_PROCESSOR_BATCH_SIZE")
+                .map(|s| {
+                    s.parse::<usize>()
+                        .unwrap_or(ACTIVE_ADDRESS_STATS_PROCESSOR_BATCH_SIZE)
+                })
+                .unwrap_or(ACTIVE_ADDRESS_STATS_PROCESSOR_BATCH_SIZE);
+        Self {
+            store,
+            metrics,
+            active_address_stats_processor_batch_size,
+        }
+    }
+
+    pub async fn start(&self) -> IndexerResult<()> {
+        info!("Indexer active address stats async processor started...");
+        let last_processed_tx_seq = self
+            .store
+            .get_active_address_sketches_last_processed_tx_seq()
+            .await?;
+        let mut next_tx_seq = last_processed_tx_seq.map_or(0, |tx_seq| tx_seq.seq + 1);
+        loop {
+            let latest_tx_seq = self
+                .store
+                .get_latest_stored_transaction()
+                .await?
+                .map(|tx| tx.tx_sequence_number);
+            let Some(latest_tx_seq) = latest_tx_seq.filter(|seq| *seq >= next_tx_seq) else {
+                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                continue;
+            };
+            let end_tx_seq = (latest_tx_seq + 1)
+                .min(next_tx_seq + self.active_address_stats_processor_batch_size as i64);
+            let store = self.store.clone();
+            tokio::task::spawn_blocking(move || {
+                store.persist_active_address_sketches_in_tx_range(next_tx_seq, end_tx_seq)
+            })
+            .await??;
+            next_tx_seq = end_tx_seq;
+            info!(
+                "Persisted active address sketches for tx seq: {}",
+                next_tx_seq - 1,
+            );
+            self.metrics
+                .latest_active_address_stats_tx_seq
+                .set(next_tx_seq - 1);
+        }
+    }
+}
@@ -1,8 +1,11 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod active_address_stats_processor;
 pub mod address_metrics_processor;
 pub mod move_call_metrics_processor;
 pub mod network_metrics_processor;
 pub mod objects_snapshot_processor;
+pub mod orphaned_dynamic_fields_processor;
 pub mod processor_orchestrator_v2;
+pub mod storage_stats_processor;
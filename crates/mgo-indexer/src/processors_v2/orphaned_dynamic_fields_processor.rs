@@ -0,0 +1,106 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tracing::{info, warn};
+
+use crate::metrics::OrphanedDynamicFieldsMetrics;
+use crate::models_v2::orphaned_dynamic_fields::OrphanedDynamicFieldsScan;
+use crate::store::IndexerStoreV2;
+use crate::types_v2::IndexerResult;
+
+/// Periodically checks the dynamic object fields of the parents changed since its last scan
+/// for value objects that were deleted or wrapped without the field being removed, and reports
+/// them in `orphaned_dynamic_fields`. Scans resume from a watermark, so that only the parents
+/// changed in new checkpoints are checked, never every field.
+pub struct OrphanedDynamicFieldsProcessor<S> {
+    store: S,
+    metrics: OrphanedDynamicFieldsMetrics,
+    interval: Duration,
+    batch_size: usize,
+}
+
+impl<S> OrphanedDynamicFieldsProcessor<S>
+where
+    S: IndexerStoreV2 + Sync + Send + 'static,
+{
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
+    pub const DEFAULT_BATCH_SIZE: usize = 1000;
+
+    pub fn new(store: S, metrics: OrphanedDynamicFieldsMetrics) -> Self {
+        Self {
+            store,
+            metrics,
+            interval: Self::DEFAULT_INTERVAL,
+            batch_size: Self::DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    /// How long the processor waits between scans.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Maximum number of checkpoints whose changed parents a single scan covers.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Scans every interval. A failed scan is retried after the interval.
+    pub async fn run(self) {
+        info!(
+            interval = ?self.interval,
+            batch_size = self.batch_size,
+            "Starting orphaned dynamic fields processor"
+        );
+        loop {
+            if let Err(e) = self.scan().await {
+                warn!(
+                    "Failed to scan for orphaned dynamic fields, retrying in {:?}: {e}",
+                    self.interval
+                );
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+
+    /// Scans the parents changed in the next batch of checkpoints, returning `None` when no
+    /// checkpoint was indexed since the last scan.
+    pub async fn scan(&self) -> IndexerResult<Option<OrphanedDynamicFieldsScan>> {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Current time should be after the unix epoch")
+            .as_millis() as u64;
+        let Some(scan) = self
+            .store
+            .scan_orphaned_dynamic_fields(self.batch_size, now_ms)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        self.metrics
+            .latest_scanned_checkpoint
+            .set(scan.checkpoint as i64);
+        self.metrics
+            .scanned_parents
+            .inc_by(scan.parents_scanned as u64);
+        self.metrics
+            .orphaned_dynamic_fields
+            .set(scan.orphan_count as i64);
+        if scan.orphans_found > 0 || scan.orphans_cleared > 0 {
+            info!(
+                checkpoint = scan.checkpoint,
+                found = scan.orphans_found,
+                cleared = scan.orphans_cleared,
+                total = scan.orphan_count,
+                "Scanned {} parents for orphaned dynamic fields",
+                scan.parents_scanned
+            );
+        }
+        Ok(Some(scan))
+    }
+}
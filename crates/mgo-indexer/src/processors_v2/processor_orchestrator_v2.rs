@@ -7,6 +7,7 @@ use tracing::{error, info};
 use crate::metrics::IndexerMetrics;
 use crate::store::IndexerAnalyticalStore;
 
+use super::active_address_stats_processor::ActiveAddressStatsProcessor;
 use super::address_metrics_processor::AddressMetricsProcessor;
 use super::move_call_metrics_processor::MoveCallMetricsProcessor;
 use super::network_metrics_processor::NetworkMetricsProcessor;
@@ -56,6 +57,21 @@ where
             }
         });
 
+        let active_address_stats_processor =
+            ActiveAddressStatsProcessor::new(self.store.clone(), self.metrics.clone());
+        let active_address_stats_handle = tokio::task::spawn(async move {
+            loop {
+                let active_address_stats_res = active_address_stats_processor.start().await;
+                if let Err(e) = active_address_stats_res {
+                    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                    error!(
+                        "Indexer active address stats processor failed with error {:?}, retrying in 5s...",
+                        e
+                    );
+                }
+            }
+        });
+
         let move_call_metrics_processor =
             MoveCallMetricsProcessor::new(self.store.clone(), self.metrics.clone());
         let move_call_metrics_handle = tokio::task::spawn(async move {
@@ -74,6 +90,7 @@ where
         try_join_all(vec![
             network_metrics_handle,
             addr_metrics_handle,
+            active_address_stats_handle,
             move_call_metrics_handle,
         ])
         .await
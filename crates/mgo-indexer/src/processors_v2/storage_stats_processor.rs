@@ -0,0 +1,95 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tracing::{info, warn};
+
+use mgo_json_rpc_types::TableStorageStats;
+
+use crate::metrics::StorageStatsMetrics;
+use crate::store::IndexerStoreV2;
+use crate::types_v2::IndexerResult;
+
+/// How long samples are kept for.
+const STORAGE_STATS_RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Periodically samples the row estimates and on-disk sizes of the tables of the store into
+/// `storage_stats`, from which readers serve them without scanning the tables, and publishes
+/// the latest sample as metrics.
+pub struct StorageStatsProcessor<S> {
+    store: S,
+    metrics: StorageStatsMetrics,
+    interval: Duration,
+}
+
+impl<S> StorageStatsProcessor<S>
+where
+    S: IndexerStoreV2 + Sync + Send + 'static,
+{
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(600);
+
+    pub fn new(store: S, metrics: StorageStatsMetrics) -> Self {
+        Self {
+            store,
+            metrics,
+            interval: Self::DEFAULT_INTERVAL,
+        }
+    }
+
+    /// How long the processor waits between samples.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Samples the tables every interval. A failed sample is skipped.
+    pub async fn run(self) {
+        info!(interval = ?self.interval, "Starting storage stats processor");
+        loop {
+            if let Err(e) = self.sample().await {
+                warn!("Failed to sample storage stats, retrying in {:?}: {e}", self.interval);
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+
+    /// Takes a single sample, returning the sampled tables with their growth.
+    pub async fn sample(&self) -> IndexerResult<Vec<TableStorageStats>> {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Current time should be after the unix epoch")
+            .as_millis() as u64;
+        let retain_from_ms = now_ms.saturating_sub(STORAGE_STATS_RETENTION.as_millis() as u64);
+        let tables = self
+            .store
+            .persist_storage_stats(now_ms, retain_from_ms)
+            .await?;
+
+        for table in &tables {
+            let name = table.table_name.as_str();
+            self.metrics
+                .table_row_estimate
+                .with_label_values(&[name])
+                .set(table.row_estimate as i64);
+            self.metrics
+                .table_total_bytes
+                .with_label_values(&[name])
+                .set(table.total_bytes as i64);
+            if let Some(rows_per_day) = table.rows_per_day {
+                self.metrics
+                    .table_rows_per_day
+                    .with_label_values(&[name])
+                    .set(rows_per_day);
+            }
+            if let Some(bytes_per_day) = table.bytes_per_day {
+                self.metrics
+                    .table_bytes_per_day
+                    .with_label_values(&[name])
+                    .set(bytes_per_day);
+            }
+        }
+        info!("Sampled storage stats of {} tables", tables.len());
+        Ok(tables)
+    }
+}
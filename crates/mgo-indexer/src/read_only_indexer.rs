@@ -0,0 +1,138 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Embedded, read-only access to an indexer v2 database.
+//!
+//! [`ReadOnlyIndexer`] exposes the same paginated queries the JSON-RPC server serves, for
+//! services that want to query the indexer DB in-process without running the RPC server.
+
+use mgo_json_rpc::read_api::DisplayRenderLimits;
+use mgo_json_rpc_types::{
+    DynamicFieldPage, DynamicFieldsOptions, EventFilter, EventPage, MgoObjectDataOptions,
+    MgoObjectResponse, MgoObjectResponseQuery, MgoTransactionBlockResponseQuery, ObjectsPage,
+    TransactionBlocksPage,
+};
+use mgo_types::base_types::{MgoAddress, ObjectID};
+use mgo_types::digests::TransactionDigest;
+use mgo_types::dynamic_field::DynamicFieldName;
+use mgo_types::event::EventID;
+
+use crate::errors::IndexerError;
+use crate::indexer_reader::IndexerReader;
+use crate::utils::{applied_schema_version, expected_schema_version};
+use crate::PgConnectionPoolConfig;
+
+/// A read-only handle on an indexer v2 database whose schema has been validated against the
+/// migrations this crate was built with.
+#[derive(Clone)]
+pub struct ReadOnlyIndexer {
+    reader: IndexerReader,
+    display_limits: DisplayRenderLimits,
+}
+
+impl ReadOnlyIndexer {
+    /// Connects to the indexer database at `db_url` with the default pool configuration.
+    ///
+    /// Fails with [`IndexerError::SchemaVersionMismatch`] if the latest migration applied to
+    /// the database is not the latest migration known to this crate.
+    pub async fn connect<T: Into<String>>(db_url: T) -> Result<Self, IndexerError> {
+        Self::connect_with_config(db_url, PgConnectionPoolConfig::default()).await
+    }
+
+    pub async fn connect_with_config<T: Into<String>>(
+        db_url: T,
+        config: PgConnectionPoolConfig,
+    ) -> Result<Self, IndexerError> {
+        let db_url = db_url.into();
+        // Building the pool eagerly opens connections, so keep it off the async threads.
+        let reader =
+            tokio::task::spawn_blocking(move || IndexerReader::new_with_config(db_url, config))
+                .await??;
+
+        let expected = expected_schema_version(/* use_v2 */ true)?;
+        let found = reader
+            .spawn_blocking(|this| this.run_query(applied_schema_version))
+            .await?;
+        if found.as_deref() != Some(expected.as_str()) {
+            return Err(IndexerError::SchemaVersionMismatch {
+                expected,
+                found: found.unwrap_or_else(|| "none".to_string()),
+            });
+        }
+
+        Ok(Self {
+            reader,
+            display_limits: DisplayRenderLimits::default(),
+        })
+    }
+
+    /// Overrides the limits used when rendering Display fields for owned objects.
+    pub fn with_display_limits(mut self, display_limits: DisplayRenderLimits) -> Self {
+        self.display_limits = display_limits;
+        self
+    }
+
+    /// The underlying reader, for queries not wrapped here.
+    pub fn reader(&self) -> &IndexerReader {
+        &self.reader
+    }
+
+    pub async fn get_owned_objects(
+        &self,
+        address: MgoAddress,
+        query: Option<MgoObjectResponseQuery>,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> Result<ObjectsPage, IndexerError> {
+        self.reader
+            .get_owned_objects_page(address, query, cursor, limit, &self.display_limits)
+            .await
+    }
+
+    pub async fn query_events(
+        &self,
+        query: EventFilter,
+        cursor: Option<EventID>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> Result<EventPage, IndexerError> {
+        self.reader
+            .query_events_page(query, cursor, limit, descending_order)
+            .await
+    }
+
+    pub async fn query_transaction_blocks(
+        &self,
+        query: MgoTransactionBlockResponseQuery,
+        cursor: Option<TransactionDigest>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> Result<TransactionBlocksPage, IndexerError> {
+        self.reader
+            .query_transaction_blocks_page(query, cursor, limit, descending_order)
+            .await
+    }
+
+    pub async fn get_dynamic_fields(
+        &self,
+        parent_object_id: ObjectID,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+        options: Option<DynamicFieldsOptions>,
+    ) -> Result<DynamicFieldPage, IndexerError> {
+        self.reader
+            .get_dynamic_fields_page(parent_object_id, cursor, limit, options)
+            .await
+    }
+
+    pub async fn get_dynamic_field_object(
+        &self,
+        parent_object_id: ObjectID,
+        name: DynamicFieldName,
+        options: Option<MgoObjectDataOptions>,
+    ) -> Result<MgoObjectResponse, IndexerError> {
+        self.reader
+            .get_dynamic_field_object(parent_object_id, name, options, &self.display_limits)
+            .await
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Streaming of large query results through a callback of the caller. Results are fetched a
+//! batch at a time, each batch starting after the last result of the previous one, so that a
+//! stream never holds more than one batch in memory however many results it goes through. A
+//! callback breaking stops the stream before the next batch is fetched.
+
+use std::ops::ControlFlow;
+
+/// Results fetched at once by the streams of the reader.
+pub const STREAM_BATCH_SIZE: usize = 500;
+
+/// Hands the results after `cursor` to `f` in order, until `f` breaks or the results run out.
+/// `fetch` returns up to `limit` results after a cursor, and `cursor_of` the cursor of a result.
+/// Returns the cursor of the last result handed to `f`, to resume the stream from.
+pub(crate) fn stream_in_batches<T, C: Clone, E>(
+    batch_size: usize,
+    mut cursor: Option<C>,
+    mut fetch: impl FnMut(Option<C>, usize) -> Result<Vec<T>, E>,
+    cursor_of: impl Fn(&T) -> C,
+    mut f: impl FnMut(T) -> ControlFlow<()>,
+) -> Result<Option<C>, E> {
+    loop {
+        let batch = fetch(cursor.clone(), batch_size)?;
+        let exhausted = batch.len() < batch_size;
+        for result in batch {
+            cursor = Some(cursor_of(&result));
+            if f(result).is_break() {
+                return Ok(cursor);
+            }
+        }
+        if exhausted {
+            return Ok(cursor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EVENTS: u64 = 10_000;
+
+    /// Fetches the synthetic events `0..EVENTS`, counting the rows it fetched.
+    fn fetch_events(
+        fetched: &mut usize,
+    ) -> impl FnMut(Option<u64>, usize) -> Result<Vec<u64>, ()> + '_ {
+        move |cursor, limit| {
+            let start = cursor.map_or(0, |cursor| cursor + 1);
+            let batch: Vec<_> = (start..EVENTS).take(limit).collect();
+            *fetched += batch.len();
+            Ok(batch)
+        }
+    }
+
+    #[test]
+    fn test_stream_stops_fetching_once_callback_breaks() {
+        let mut fetched = 0;
+        let mut streamed = vec![];
+        let cursor = stream_in_batches(
+            STREAM_BATCH_SIZE,
+            None,
+            fetch_events(&mut fetched),
+            |event| *event,
+            |event| {
+                streamed.push(event);
+                if streamed.len() == 5_000 {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            },
+        )
+        .unwrap();
+        assert_eq!(streamed, (0..5_000).collect::<Vec<_>>());
+        assert_eq!(cursor, Some(4_999));
+        // Only the batch the callback broke in was fetched past it.
+        assert!(
+            (5_000..5_000 + STREAM_BATCH_SIZE).contains(&fetched),
+            "{fetched}"
+        );
+
+        // The stream resumes after the last event handed to the callback.
+        let mut fetched = 0;
+        let mut resumed = vec![];
+        let cursor = stream_in_batches(
+            STREAM_BATCH_SIZE,
+            cursor,
+            fetch_events(&mut fetched),
+            |event| *event,
+            |event| {
+                resumed.push(event);
+                ControlFlow::Continue(())
+            },
+        )
+        .unwrap();
+        assert_eq!(resumed, (5_000..EVENTS).collect::<Vec<_>>());
+        assert_eq!(cursor, Some(EVENTS - 1));
+        assert_eq!(fetched, 5_000);
+    }
+
+    #[test]
+    fn test_stream_ends_with_results() {
+        for batch_size in [1, 7, STREAM_BATCH_SIZE, EVENTS as usize, 2 * EVENTS as usize] {
+            let mut fetches = 0;
+            let mut streamed = 0;
+            let cursor = stream_in_batches(
+                batch_size,
+                None,
+                |cursor: Option<u64>, limit| {
+                    fetches += 1;
+                    let start = cursor.map_or(0, |cursor| cursor + 1);
+                    Ok::<_, ()>((start..EVENTS).take(limit).collect())
+                },
+                |event| *event,
+                |_| {
+                    streamed += 1;
+                    ControlFlow::Continue(())
+                },
+            )
+            .unwrap();
+            assert_eq!(streamed, EVENTS);
+            assert_eq!(cursor, Some(EVENTS - 1));
+            // Only a batch as full as the others needs a last fetch to tell the stream ended.
+            assert_eq!(
+                fetches,
+                EVENTS as usize / batch_size + 1,
+                "batch {batch_size}"
+            );
+        }
+
+        // Streams past the last result hand over nothing.
+        let cursor = stream_in_batches(
+            STREAM_BATCH_SIZE,
+            Some(EVENTS - 1),
+            fetch_events(&mut 0),
+            |event| *event,
+            |_| -> ControlFlow<()> { panic!("No event should be streamed") },
+        )
+        .unwrap();
+        assert_eq!(cursor, Some(EVENTS - 1));
+
+        let error = stream_in_batches(
+            STREAM_BATCH_SIZE,
+            None,
+            |_, _| Err::<Vec<u64>, _>("connection lost"),
+            |event| *event,
+            |_| ControlFlow::Continue(()),
+        )
+        .unwrap_err();
+        assert_eq!(error, "connection lost");
+    }
+}
@@ -2,6 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    active_address_sketches (day, cohort_day) {
+        day -> Int8,
+        cohort_day -> Int8,
+        sketch -> Bytea,
+        last_processed_tx -> Int8,
+    }
+}
+
 diesel::table! {
     active_addresses (address) {
         address -> Bytea,
@@ -12,6 +21,13 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    address_cohorts (address) {
+        address -> Bytea,
+        cohort_day -> Int8,
+    }
+}
+
 diesel::table! {
     address_metrics (checkpoint) {
         checkpoint -> Int8,
@@ -51,6 +67,38 @@ diesel::table! {
         checkpoint_commitments -> Bytea,
         validator_signature -> Bytea,
         end_of_epoch_data -> Nullable<Bytea>,
+        raw_checkpoint_summary -> Nullable<Bytea>,
+        raw_checkpoint_contents -> Nullable<Bytea>,
+    }
+}
+
+diesel::table! {
+    coin_supply_changes (coin_type, checkpoint_sequence_number) {
+        coin_type -> Text,
+        checkpoint_sequence_number -> Int8,
+        epoch -> Int8,
+        timestamp_ms -> Int8,
+        minted -> Int8,
+        burned -> Int8,
+    }
+}
+
+diesel::table! {
+    coin_supply_offsets (coin_type) {
+        coin_type -> Text,
+        checkpoint_sequence_number -> Int8,
+        initial_supply -> Int8,
+        approximate -> Bool,
+    }
+}
+
+diesel::table! {
+    coin_types (coin_type) {
+        coin_type -> Text,
+        first_seen_checkpoint -> Int8,
+        metadata_object_id -> Nullable<Bytea>,
+        holder_count -> Int8,
+        holders_checkpoint -> Int8,
     }
 }
 
@@ -71,6 +119,34 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    epoch_metrics (epoch) {
+        epoch -> Int8,
+        first_checkpoint -> Int8,
+        last_checkpoint -> Int8,
+        total_transactions -> Int8,
+        total_gas_used -> Int8,
+        total_storage_rebate -> Int8,
+        active_senders -> Int8,
+        new_addresses -> Int8,
+        finalized -> Bool,
+    }
+}
+
+diesel::table! {
+    epoch_metrics_addresses (address) {
+        address -> Bytea,
+        first_epoch -> Int8,
+    }
+}
+
+diesel::table! {
+    epoch_metrics_senders (epoch, address) {
+        epoch -> Int8,
+        address -> Bytea,
+    }
+}
+
 diesel::table! {
     epochs (epoch) {
         epoch -> Int8,
@@ -96,7 +172,16 @@ diesel::table! {
 }
 
 diesel::table! {
-    events (tx_sequence_number, event_sequence_number) {
+    event_object_refs (object_id, tx_sequence_number, event_sequence_number) {
+        object_id -> Bytea,
+        tx_sequence_number -> Int8,
+        event_sequence_number -> Int8,
+        event_type -> Text,
+    }
+}
+
+diesel::table! {
+    events (tx_sequence_number, event_sequence_number, checkpoint_sequence_number) {
         tx_sequence_number -> Int8,
         event_sequence_number -> Int8,
         transaction_digest -> Bytea,
@@ -110,6 +195,28 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    events_partition_0 (tx_sequence_number, event_sequence_number, checkpoint_sequence_number) {
+        tx_sequence_number -> Int8,
+        event_sequence_number -> Int8,
+        transaction_digest -> Bytea,
+        checkpoint_sequence_number -> Int8,
+        senders -> Array<Nullable<Bytea>>,
+        package -> Bytea,
+        module -> Text,
+        event_type -> Text,
+        timestamp_ms -> Int8,
+        bcs -> Bytea,
+    }
+}
+
+diesel::table! {
+    handler_watermarks (handler_name) {
+        handler_name -> Text,
+        checkpoint_sequence_number -> Int8,
+    }
+}
+
 diesel::table! {
     move_call_metrics (id) {
         id -> Int8,
@@ -212,6 +319,31 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    orphaned_dynamic_fields (field_id) {
+        field_id -> Bytea,
+        parent_id -> Bytea,
+        df_name -> Bytea,
+        df_object_type -> Text,
+        value_object_id -> Bytea,
+        checkpoint_sequence_number -> Int8,
+        detected_at_ms -> Int8,
+    }
+}
+
+diesel::table! {
+    package_deps (package_id, package_version, dependency_original_id) {
+        package_id -> Bytea,
+        package_version -> Int8,
+        original_package_id -> Bytea,
+        dependency_original_id -> Bytea,
+        dependency_id -> Bytea,
+        dependency_version -> Int8,
+        checkpoint_sequence_number -> Int8,
+        latest -> Bool,
+    }
+}
+
 diesel::table! {
     packages (package_id) {
         package_id -> Bytea,
@@ -219,6 +351,42 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    pruner_watermark (id) {
+        id -> Bool,
+        checkpoint_hi_exclusive -> Int8,
+        tx_hi_exclusive -> Int8,
+    }
+}
+
+diesel::table! {
+    reconciler_watermark (id) {
+        id -> Bool,
+        checkpoint_hi_exclusive -> Int8,
+    }
+}
+
+diesel::table! {
+    storage_stats (table_name, sampled_at_ms) {
+        table_name -> Text,
+        sampled_at_ms -> Int8,
+        row_estimate -> Int8,
+        total_bytes -> Int8,
+    }
+}
+
+diesel::table! {
+    tombstones (id) {
+        id -> Int8,
+        checkpoint_sequence_number -> Int8,
+        ingested_digest -> Bytea,
+        certified_digest -> Bytea,
+        table_name -> Text,
+        row_data -> Jsonb,
+        tombstoned_at_ms -> Int8,
+    }
+}
+
 diesel::table! {
     transactions (tx_sequence_number, checkpoint_sequence_number) {
         tx_sequence_number -> Int8,
@@ -232,6 +400,7 @@ diesel::table! {
         events -> Array<Nullable<Bytea>>,
         transaction_kind -> Int2,
         success_command_count -> Int2,
+        created_objects -> Nullable<Array<Nullable<Bytea>>>,
     }
 }
 
@@ -248,6 +417,7 @@ diesel::table! {
         events -> Array<Nullable<Bytea>>,
         transaction_kind -> Int2,
         success_command_count -> Int2,
+        created_objects -> Nullable<Array<Nullable<Bytea>>>,
     }
 }
 
@@ -285,6 +455,14 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    tx_loaded_child_objects (transaction_digest, object_id) {
+        transaction_digest -> Bytea,
+        object_id -> Bytea,
+        object_version -> Int8,
+    }
+}
+
 diesel::table! {
     tx_recipients (recipient, tx_sequence_number) {
         tx_sequence_number -> Int8,
@@ -299,22 +477,71 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    tx_signatures (tx_sequence_number, signature_index, participant_index) {
+        tx_sequence_number -> Int8,
+        signature_index -> Int2,
+        participant_index -> Int2,
+        scheme -> Int2,
+        public_key -> Nullable<Bytea>,
+        signature -> Bytea,
+    }
+}
+
+diesel::table! {
+    validator_epoch_metrics (epoch, validator_address) {
+        epoch -> Int8,
+        validator_address -> Bytea,
+        committee_index -> Int4,
+        voting_power -> Int8,
+        last_checkpoint -> Int8,
+        checkpoints -> Int8,
+        checkpoints_signed -> Int8,
+        reporters -> Nullable<Int8>,
+        tallying_score -> Nullable<Int8>,
+    }
+}
+
+diesel::table! {
+    writer_version (id) {
+        id -> Bool,
+        version -> Int8,
+    }
+}
+
 diesel::allow_tables_to_appear_in_same_query!(
+    active_address_sketches,
     active_addresses,
+    address_cohorts,
     address_metrics,
     addresses,
     checkpoints,
+    coin_supply_changes,
+    coin_supply_offsets,
+    coin_types,
     display,
     epoch_peak_tps,
+    epoch_metrics,
+    epoch_metrics_addresses,
+    epoch_metrics_senders,
     epochs,
+    event_object_refs,
     events,
+    events_partition_0,
+    handler_watermarks,
     move_call_metrics,
     move_calls,
     objects,
     objects_history,
     objects_history_partition_0,
     objects_snapshot,
+    orphaned_dynamic_fields,
+    package_deps,
     packages,
+    pruner_watermark,
+    reconciler_watermark,
+    storage_stats,
+    tombstones,
     transactions,
     transactions_partition_0,
     tx_calls,
@@ -323,4 +550,6 @@ diesel::allow_tables_to_appear_in_same_query!(
     tx_input_objects,
     tx_recipients,
     tx_senders,
+    validator_epoch_metrics,
+    writer_version,
 );
@@ -64,6 +64,16 @@ pub trait IndexerAnalyticalStore {
     ) -> IndexerResult<()>;
     async fn calculate_and_persist_address_metrics(&self, checkpoint: i64) -> IndexerResult<()>;
 
+    // for daily active addresses and retention cohorts
+    async fn get_active_address_sketches_last_processed_tx_seq(
+        &self,
+    ) -> IndexerResult<Option<TxSeq>>;
+    fn persist_active_address_sketches_in_tx_range(
+        &self,
+        start_tx_seq: i64,
+        end_tx_seq: i64,
+    ) -> IndexerResult<()>;
+
     // for move call metrics
     async fn get_latest_move_call_metrics(&self) -> IndexerResult<Option<StoredMoveCallMetrics>>;
     async fn get_latest_move_call_tx_seq(&self) -> IndexerResult<Option<TxSeq>>;
@@ -7,6 +7,7 @@ use move_bytecode_utils::module_cache::GetModule;
 use std::collections::BTreeMap;
 use std::sync::Arc;
 
+use mgo_json_rpc_types::TableStorageStats;
 use mgo_types::base_types::{ObjectID, SequenceNumber};
 use mgo_types::object::ObjectRead;
 
@@ -14,8 +15,12 @@ use crate::errors::IndexerError;
 use crate::handlers::{EpochToCommit, TransactionObjectChangesToCommit};
 
 use crate::models_v2::display::StoredDisplay;
+use crate::models_v2::orphaned_dynamic_fields::OrphanedDynamicFieldsScan;
+use crate::notifications::CommitNotice;
 use crate::types_v2::{
-    IndexedCheckpoint, IndexedEvent, IndexedPackage, IndexedTransaction, TxIndex,
+    IndexedCheckpoint, IndexedCoinHolderChange, IndexedCoinSupplyChange, IndexedCoinType,
+    IndexedEpochMetrics, IndexedEvent, IndexedPackage, IndexedTransaction, IndexedValidatorMetrics,
+    TxIndex,
 };
 
 #[async_trait]
@@ -62,6 +67,9 @@ pub trait IndexerStoreV2 {
 
     async fn persist_tx_indices(&self, indices: Vec<TxIndex>) -> Result<(), IndexerError>;
 
+    /// Writes only the input and changed objects of `indices`, skipping rows already present.
+    async fn persist_tx_object_indices(&self, indices: Vec<TxIndex>) -> Result<(), IndexerError>;
+
     async fn persist_events(&self, events: Vec<IndexedEvent>) -> Result<(), IndexerError>;
     async fn persist_displays(
         &self,
@@ -70,10 +78,65 @@ pub trait IndexerStoreV2 {
 
     async fn persist_packages(&self, packages: Vec<IndexedPackage>) -> Result<(), IndexerError>;
 
+    /// Records newly seen coin types and updates their holder counts, which are derived from
+    /// the coins already persisted by `persist_objects` for the same checkpoints.
+    async fn persist_coin_types(
+        &self,
+        coin_types: Vec<IndexedCoinType>,
+        holder_changes: Vec<IndexedCoinHolderChange>,
+    ) -> Result<(), IndexerError>;
+
+    /// Records the amounts of coin types minted and burned, and the supply of the types seen
+    /// changing for the first time.
+    async fn persist_coin_supply_changes(
+        &self,
+        changes: Vec<IndexedCoinSupplyChange>,
+    ) -> Result<(), IndexerError>;
+
     async fn persist_epoch(&self, epoch: EpochToCommit) -> Result<(), IndexerError>;
 
     async fn advance_epoch(&self, epoch: EpochToCommit) -> Result<(), IndexerError>;
 
+    /// Rolls up the metrics of checkpoints, in order, into the metrics of their epochs,
+    /// skipping checkpoints already rolled up.
+    async fn persist_epoch_metrics(
+        &self,
+        metrics: Vec<IndexedEpochMetrics>,
+    ) -> Result<(), IndexerError>;
+
+    /// Rolls up the metrics of checkpoints, in order, into the metrics of the validators of
+    /// their epochs, skipping checkpoints already rolled up.
+    async fn persist_validator_metrics(
+        &self,
+        metrics: Vec<IndexedValidatorMetrics>,
+    ) -> Result<(), IndexerError>;
+
+    /// Samples the estimated row count and on-disk size of every table, persisting them as
+    /// sampled at `sampled_at_ms` and deleting the samples taken before `retain_from_ms`.
+    /// Returns the sampled tables with their growth.
+    async fn persist_storage_stats(
+        &self,
+        sampled_at_ms: u64,
+        retain_from_ms: u64,
+    ) -> Result<Vec<TableStorageStats>, IndexerError>;
+
+    /// Checks the dynamic object fields of the parents changed in the next `batch_size`
+    /// checkpoints after the last scan for values that are no longer live, reporting them as
+    /// orphans found at `detected_at_ms`, and clears the orphans fixed since. Returns `None`
+    /// when no checkpoint was indexed since the last scan.
+    async fn scan_orphaned_dynamic_fields(
+        &self,
+        batch_size: usize,
+        detected_at_ms: u64,
+    ) -> Result<Option<OrphanedDynamicFieldsScan>, IndexerError>;
+
+    /// Publishes a commit notice to readers listening on the store.
+    async fn notify_commit(&self, notice: CommitNotice) -> Result<(), IndexerError>;
+
+    /// Records `version` as the version of the data written to the store, replacing the version
+    /// of the previous writer.
+    async fn persist_writer_version(&self, version: u64) -> Result<(), IndexerError>;
+
     async fn get_network_total_transactions_by_end_of_epoch(
         &self,
         epoch: u64,
@@ -18,6 +18,7 @@ mod pg_indexer_store;
 mod pg_indexer_store_v2;
 mod pg_partition_manager;
 mod query;
+pub(crate) mod write_conflict;
 
 pub(crate) mod diesel_macro {
     macro_rules! read_only_blocking {
@@ -76,7 +77,24 @@ pub(crate) mod diesel_macro {
         }};
     }
 
+    /// Like `transactional_blocking`, but runs the transaction again, up to `$max_retries`
+    /// times, when it conflicts with a concurrent writer.
+    macro_rules! transactional_blocking_with_conflict_retry {
+        ($pool:expr, $query:expr, $max_retries:expr) => {{
+            crate::store::write_conflict::retry_on_write_conflict($max_retries, || {
+                let mut pg_pool_conn = crate::get_pg_pool_connection($pool)?;
+                pg_pool_conn
+                    .build_transaction()
+                    .serializable()
+                    .read_write()
+                    .run($query)
+            })
+            .map_err(|e| IndexerError::PostgresWriteError(e.to_string()))
+        }};
+    }
+
     pub(crate) use read_only_blocking;
     pub(crate) use transactional_blocking;
+    pub(crate) use transactional_blocking_with_conflict_retry;
     pub(crate) use transactional_blocking_with_retry;
 }
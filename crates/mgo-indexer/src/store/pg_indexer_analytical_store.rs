@@ -1,18 +1,24 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 use tap::tap::TapFallible;
 use tracing::{error, info};
 
 use async_trait::async_trait;
 use core::result::Result::Ok;
-use diesel::dsl::count;
+use diesel::dsl::{count, max};
+use diesel::upsert::excluded;
 use diesel::{ExpressionMethods, OptionalExtension};
 use diesel::{QueryDsl, RunQueryDsl};
 use mgo_types::base_types::ObjectID;
 
 use crate::errors::{Context, IndexerError};
+use crate::models_v2::active_address_sketches::{
+    fold_active_senders, ActiveAddressSketch, StoredActiveAddressSketch, StoredSenderDay,
+    MS_PER_DAY,
+};
 use crate::models_v2::address_metrics::StoredAddressMetrics;
 use crate::models_v2::checkpoints::StoredCheckpoint;
 use crate::models_v2::move_call_metrics::{
@@ -25,8 +31,8 @@ use crate::models_v2::transactions::{
 };
 use crate::models_v2::tx_count_metrics::StoredTxCountMetrics;
 use crate::schema_v2::{
-    active_addresses, address_metrics, addresses, checkpoints, epoch_peak_tps, move_call_metrics,
-    move_calls, transactions, tx_count_metrics,
+    active_address_sketches, active_addresses, address_cohorts, address_metrics, addresses,
+    checkpoints, epoch_peak_tps, move_call_metrics, move_calls, transactions, tx_count_metrics,
 };
 use crate::store::diesel_macro::{read_only_blocking, transactional_blocking_with_retry};
 use crate::types_v2::IndexerResult;
@@ -34,6 +40,8 @@ use crate::PgConnectionPool;
 
 use super::IndexerAnalyticalStore;
 
+const PG_COMMIT_CHUNK_SIZE: usize = 1000;
+
 #[derive(Clone)]
 pub struct PgIndexerAnalyticalStore {
     blocking_cp: PgConnectionPool,
@@ -350,6 +358,101 @@ impl IndexerAnalyticalStore for PgIndexerAnalyticalStore {
         Ok(())
     }
 
+    async fn get_active_address_sketches_last_processed_tx_seq(
+        &self,
+    ) -> IndexerResult<Option<TxSeq>> {
+        let last_processed_tx_seq = read_only_blocking!(&self.blocking_cp, |conn| {
+            active_address_sketches::dsl::active_address_sketches
+                .select(max(active_address_sketches::dsl::last_processed_tx))
+                .first::<Option<i64>>(conn)
+        })
+        .context("Failed to read active address sketches last processed tx sequence.")?;
+        Ok(last_processed_tx_seq.map(|seq| TxSeq { seq }))
+    }
+
+    fn persist_active_address_sketches_in_tx_range(
+        &self,
+        start_tx_seq: i64,
+        end_tx_seq: i64,
+    ) -> IndexerResult<()> {
+        let sender_days_query = construct_sender_days_query(start_tx_seq, end_tx_seq);
+        // Cohorts and sketches are read and written in a single transaction, and inserting a
+        // sender into a sketch that already counts it is a no-op, so replaying a range after a
+        // restart leaves the persisted state unchanged.
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                let senders =
+                    diesel::sql_query(sender_days_query.clone()).load::<StoredSenderDay>(conn)?;
+                let Some(last_processed_tx) = senders.iter().map(|s| s.tx_sequence_number).max()
+                else {
+                    return Ok::<(), IndexerError>(());
+                };
+                let addresses = senders
+                    .iter()
+                    .map(|s| s.address.clone())
+                    .collect::<Vec<_>>();
+                let days = senders.iter().map(|s| s.day).collect::<Vec<_>>();
+
+                let mut cohorts = address_cohorts::table
+                    .filter(address_cohorts::address.eq_any(addresses))
+                    .select((address_cohorts::address, address_cohorts::cohort_day))
+                    .load::<(Vec<u8>, i64)>(conn)?
+                    .into_iter()
+                    .collect::<HashMap<_, _>>();
+                let mut sketches = BTreeMap::new();
+                for stored in active_address_sketches::table
+                    .filter(active_address_sketches::day.eq_any(days))
+                    .load::<StoredActiveAddressSketch>(conn)?
+                {
+                    sketches.insert(
+                        (stored.day, stored.cohort_day),
+                        ActiveAddressSketch::from_bytes(&stored.sketch)?,
+                    );
+                }
+
+                let new_cohorts = fold_active_senders(&senders, &mut cohorts, &mut sketches);
+                for cohort_chunk in new_cohorts.chunks(PG_COMMIT_CHUNK_SIZE) {
+                    diesel::insert_into(address_cohorts::table)
+                        .values(cohort_chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)?;
+                }
+                let stored_sketches = sketches
+                    .into_iter()
+                    .map(|((day, cohort_day), sketch)| {
+                        Ok(StoredActiveAddressSketch {
+                            day,
+                            cohort_day,
+                            sketch: sketch.to_bytes()?,
+                            last_processed_tx,
+                        })
+                    })
+                    .collect::<IndexerResult<Vec<_>>>()?;
+                for sketch_chunk in stored_sketches.chunks(PG_COMMIT_CHUNK_SIZE) {
+                    diesel::insert_into(active_address_sketches::table)
+                        .values(sketch_chunk)
+                        .on_conflict((
+                            active_address_sketches::day,
+                            active_address_sketches::cohort_day,
+                        ))
+                        .do_update()
+                        .set((
+                            active_address_sketches::sketch
+                                .eq(excluded(active_address_sketches::sketch)),
+                            active_address_sketches::last_processed_tx
+                                .eq(excluded(active_address_sketches::last_processed_tx)),
+                        ))
+                        .execute(conn)?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(10)
+        )
+        .context("Failed persisting active address sketches to PostgresDB")?;
+        Ok(())
+    }
+
     async fn get_latest_move_call_tx_seq(&self) -> IndexerResult<Option<TxSeq>> {
         let last_processed_tx_seq = read_only_blocking!(&self.blocking_cp, |conn| {
             move_calls::dsl::move_calls
@@ -619,6 +722,22 @@ fn construct_active_address_persisting_query(start_tx_seq: i64, end_tx_seq: i64)
     )
 }
 
+fn construct_sender_days_query(start_tx_seq: i64, end_tx_seq: i64) -> String {
+    format!(
+        "SELECT
+            s.tx_sequence_number,
+            s.sender AS address,
+            t.timestamp_ms / {} AS day
+        FROM tx_senders s
+        JOIN transactions t
+        ON s.tx_sequence_number = t.tx_sequence_number
+        WHERE s.tx_sequence_number >= {} AND s.tx_sequence_number < {}
+        ORDER BY s.tx_sequence_number;
+    ",
+        MS_PER_DAY, start_tx_seq, end_tx_seq
+    )
+}
+
 fn construct_move_call_persist_query(start_tx_seq: i64, end_tx_seq: i64) -> String {
     format!(
         "INSERT INTO move_calls
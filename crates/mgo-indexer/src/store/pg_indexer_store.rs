@@ -1,7 +1,7 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -62,15 +62,21 @@ use crate::schema::{
     epochs, events, input_objects, move_calls, objects, objects_history, packages, recipients,
     system_states, transactions, validators,
 };
-use crate::store::diesel_macro::{read_only_blocking, transactional_blocking};
+use crate::store::diesel_macro::{
+    read_only_blocking, transactional_blocking, transactional_blocking_with_conflict_retry,
+};
 use crate::store::module_resolver::IndexerModuleResolver;
 use crate::store::query::DBFilter;
+use crate::store::write_conflict;
 use crate::store::TransactionObjectChanges;
 use crate::store::{IndexerStore, TemporaryEpochStore};
 use crate::PgConnectionPool;
 
 const MAX_EVENT_PAGE_SIZE: usize = 1000;
 const PG_COMMIT_CHUNK_SIZE: usize = 1000;
+/// Times a commit of object changes is run again when it conflicts with a concurrent writer,
+/// before the checkpoint fails to commit.
+const PG_COMMIT_CONFLICT_RETRIES: usize = 5;
 
 const GET_PARTITION_SQL: &str = r"
 SELECT parent.relname                           AS table_name,
@@ -468,6 +474,7 @@ impl PgIndexerStore {
             data: mgo_event_vec,
             next_cursor,
             has_next_page,
+            applied: None,
         })
     }
 
@@ -575,11 +582,20 @@ impl PgIndexerStore {
             raw_transaction: raw_tx,
             effects: effects_opt,
             confirmed_local_execution: tx.confirmed_local_execution,
+            executed_at_checkpoint_hint: None,
+            written_objects: None,
+            served_from: None,
+            checkpoint_digest: None,
+            epoch: None,
             timestamp_ms: tx.timestamp_ms.map(|t| t as u64),
+            checkpoint_timestamp_ms: tx.timestamp_ms.map(|t| t as u64),
+            observed_execution_timestamp_ms: None,
             checkpoint: tx.checkpoint_sequence_number.map(|c| c as u64),
             events,
             object_changes,
             balance_changes,
+            created_summary: None,
+            signatures: None,
             errors: vec![],
             raw_effects: vec![],
         })
@@ -1295,15 +1311,19 @@ impl PgIndexerStore {
             .map(|changed_object| changed_object.to_owned())
             .collect();
 
-        transactional_blocking!(&self.blocking_cp, |conn| {
-            persist_object_mutations(
-                conn,
-                mutated_objects,
-                object_mutation_latency,
-                object_commit_chunk_counter.clone(),
-            )?;
-            Ok::<(), IndexerError>(())
-        })?;
+        transactional_blocking_with_conflict_retry!(
+            &self.blocking_cp,
+            |conn| {
+                persist_object_mutations(
+                    conn,
+                    mutated_objects.clone(),
+                    object_mutation_latency.clone(),
+                    object_commit_chunk_counter.clone(),
+                )?;
+                Ok::<(), IndexerError>(())
+            },
+            PG_COMMIT_CONFLICT_RETRIES
+        )?;
 
         let deleted_objects: Vec<Object> = tx_object_changes
             .iter()
@@ -1316,20 +1336,34 @@ impl PgIndexerStore {
 
         // commit object deletions after mutations b/c objects cannot be mutated after deletion,
         // otherwise object mutations might override object deletions.
-        transactional_blocking!(&self.blocking_cp, |conn| {
-            persist_object_deletions(
-                conn,
-                deleted_objects,
-                object_deletion_latency,
-                object_commit_chunk_counter,
-            )?;
-            Ok::<(), IndexerError>(())
-        })?;
+        transactional_blocking_with_conflict_retry!(
+            &self.blocking_cp,
+            |conn| {
+                persist_object_deletions(
+                    conn,
+                    deleted_objects.clone(),
+                    object_deletion_latency.clone(),
+                    object_commit_chunk_counter.clone(),
+                )?;
+                Ok::<(), IndexerError>(())
+            },
+            PG_COMMIT_CONFLICT_RETRIES
+        )?;
         Ok(())
     }
 
     fn persist_events(&self, events: &[Event]) -> Result<(), IndexerError> {
+        // Events have no natural key, so those of transactions indexed again, e.g. after a
+        // shutdown interrupted their checkpoint, replace the ones written the first time.
+        let digests = events
+            .iter()
+            .map(|event| event.transaction_digest.clone())
+            .collect::<BTreeSet<_>>();
         transactional_blocking!(&self.blocking_cp, |conn| {
+            diesel::delete(events::table.filter(events::transaction_digest.eq_any(&digests)))
+                .execute(conn)
+                .map_err(IndexerError::from)
+                .context("Failed deleting re-indexed events from PostgresDB")?;
             for event_chunk in events.chunks(PG_COMMIT_CHUNK_SIZE) {
                 diesel::insert_into(events::table)
                     .values(event_chunk)
@@ -1416,7 +1450,45 @@ impl PgIndexerStore {
         move_calls: &[MoveCall],
         recipients: &[Recipient],
     ) -> Result<(), IndexerError> {
+        // These tables have no natural key either, so the rows of checkpoints indexed again
+        // replace the ones written the first time.
+        let checkpoints = input_objects
+            .iter()
+            .map(|o| o.checkpoint_sequence_number)
+            .chain(changed_objects.iter().map(|o| o.checkpoint_sequence_number))
+            .chain(move_calls.iter().map(|c| c.checkpoint_sequence_number))
+            .chain(recipients.iter().map(|r| r.checkpoint_sequence_number))
+            .collect::<BTreeSet<_>>();
         transactional_blocking!(&self.blocking_cp, |conn| {
+            diesel::delete(
+                move_calls::table
+                    .filter(move_calls::checkpoint_sequence_number.eq_any(&checkpoints)),
+            )
+            .execute(conn)
+            .map_err(IndexerError::from)
+            .context("Failed deleting re-indexed move_calls from PostgresDB")?;
+            diesel::delete(
+                input_objects::table
+                    .filter(input_objects::checkpoint_sequence_number.eq_any(&checkpoints)),
+            )
+            .execute(conn)
+            .map_err(IndexerError::from)
+            .context("Failed deleting re-indexed input_objects from PostgresDB")?;
+            diesel::delete(
+                changed_objects::table
+                    .filter(changed_objects::checkpoint_sequence_number.eq_any(&checkpoints)),
+            )
+            .execute(conn)
+            .map_err(IndexerError::from)
+            .context("Failed deleting re-indexed changed_objects from PostgresDB")?;
+            diesel::delete(
+                recipients::table
+                    .filter(recipients::checkpoint_sequence_number.eq_any(&checkpoints)),
+            )
+            .execute(conn)
+            .map_err(IndexerError::from)
+            .context("Failed deleting re-indexed recipients from PostgresDB")?;
+
             // Commit indexed move calls
             for move_calls_chunk in move_calls.chunks(PG_COMMIT_CHUNK_SIZE) {
                 diesel::insert_into(move_calls::table)
@@ -1669,6 +1741,8 @@ impl PgIndexerStore {
         transactional_blocking!(&self.blocking_cp, |conn| {
             diesel::insert_into(address_stats::dsl::address_stats)
                 .values(addr_stats)
+                .on_conflict(address_stats::checkpoint)
+                .do_nothing()
                 .execute(conn)
         })
         .context("Failed persisting address stats to PostgresDB")?;
@@ -2493,12 +2567,12 @@ fn persist_object_mutations(
         diesel::sql_query(insert_update_query)
             .execute(conn)
             .map_err(|e| {
-                IndexerError::PostgresWriteError(format!(
-                    "Failed writing mutated objects to PostgresDB with error: {:?}. Chunk length: {}, total length: {}",
+                object_write_error(
                     e,
+                    "mutated",
                     mutated_object_change_chunk.len(),
                     mutated_objects.len(),
-                ))
+                )
             })?;
     }
     object_mutation_guard.stop_and_record();
@@ -2527,12 +2601,12 @@ fn persist_object_deletions(
             ))
             .execute(conn)
             .map_err(|e| {
-                IndexerError::PostgresWriteError(format!(
-                    "Failed writing deleted objects to PostgresDB with error: {:?}. Chunk length: {}, total length: {}",
+                object_write_error(
                     e,
+                    "deleted",
                     deleted_object_change_chunk.len(),
                     deleted_objects.len(),
-                ))
+                )
             })?;
         object_commit_chunk_counter.inc();
     }
@@ -2540,6 +2614,25 @@ fn persist_object_deletions(
     Ok(())
 }
 
+/// Error of writing a chunk of `kind` objects, which keeps write conflicts as they are for the
+/// commit to be retried on them.
+fn object_write_error(
+    e: diesel::result::Error,
+    kind: &str,
+    chunk_len: usize,
+    total_len: usize,
+) -> IndexerError {
+    let message = format!(
+        "Failed writing {kind} objects to PostgresDB with error: {:?}. Chunk length: {}, total length: {}",
+        e, chunk_len, total_len,
+    );
+    let e = IndexerError::from(e);
+    if write_conflict::is_write_conflict(&e) {
+        return e;
+    }
+    IndexerError::PostgresWriteError(message)
+}
+
 #[derive(Clone)]
 struct PartitionManager {
     cp: PgConnectionPool,
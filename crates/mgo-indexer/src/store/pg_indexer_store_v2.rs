@@ -13,6 +13,7 @@ use tap::Tap;
 
 use async_trait::async_trait;
 use diesel::dsl::max;
+use diesel::pg::PgConnection;
 use diesel::upsert::excluded;
 use diesel::ExpressionMethods;
 use diesel::OptionalExtension;
@@ -20,31 +21,55 @@ use diesel::{QueryDsl, RunQueryDsl};
 use move_bytecode_utils::module_cache::SyncModuleCache;
 use tracing::info;
 
+use mgo_json_rpc_types::TableStorageStats;
 use mgo_types::base_types::{ObjectID, SequenceNumber};
+use mgo_types::committee::EpochId;
+use mgo_types::digests::CheckpointDigest;
+use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
 use mgo_types::object::ObjectRead;
 
 use crate::errors::{Context, IndexerError};
+use crate::framework::{ProgressStore, PrunableStore, ReconcilableStore, WatermarkSource};
 use crate::handlers::EpochToCommit;
 use crate::handlers::TransactionObjectChangesToCommit;
 use crate::metrics::IndexerMetrics;
 
 use crate::models_v2::checkpoints::StoredCheckpoint;
+use crate::models_v2::coin_supply::{StoredCoinSupplyChange, StoredCoinSupplyOffset};
+use crate::models_v2::coin_types::StoredCoinType;
 use crate::models_v2::display::StoredDisplay;
 use crate::models_v2::epoch::StoredEpochInfo;
-use crate::models_v2::events::StoredEvent;
+use crate::models_v2::epoch_metrics::{
+    StoredEpochMetrics, StoredEpochMetricsAddress, StoredEpochMetricsSender,
+};
+use crate::models_v2::events::{StoredEvent, StoredEventObjectRef};
+use crate::models_v2::handler_watermarks::StoredHandlerWatermark;
 use crate::models_v2::objects::{
     StoredDeletedHistoryObject, StoredDeletedObject, StoredHistoryObject, StoredObject,
 };
-use crate::models_v2::packages::StoredPackage;
+use crate::models_v2::orphaned_dynamic_fields::{ChangedParent, OrphanedDynamicFieldsScan};
+use crate::models_v2::packages::{StoredPackage, StoredPackageDependency};
+use crate::models_v2::pruner_watermark::StoredPrunerWatermark;
+use crate::models_v2::reconciler_watermark::StoredReconcilerWatermark;
+use crate::models_v2::storage_stats::{StoredStorageStats, STORAGE_GROWTH_WINDOW_MS};
 use crate::models_v2::transactions::StoredTransaction;
+use crate::models_v2::validator_metrics::StoredValidatorEpochMetrics;
+use crate::models_v2::writer_version::StoredWriterVersion;
+use crate::notifications::{CommitNotice, COMMIT_NOTICE_CHANNEL};
 use crate::schema_v2::{
-    checkpoints, display, epochs, events, objects, objects_history, objects_snapshot, packages,
-    transactions, tx_calls, tx_changed_objects, tx_input_objects, tx_recipients, tx_senders,
+    checkpoints, coin_supply_changes, coin_supply_offsets, coin_types, display, epoch_metrics,
+    epoch_metrics_addresses, epoch_metrics_senders, epochs, event_object_refs, events,
+    handler_watermarks, objects, objects_history, objects_snapshot, orphaned_dynamic_fields,
+    package_deps, packages, pruner_watermark, reconciler_watermark, storage_stats, transactions,
+    tx_calls, tx_changed_objects, tx_input_objects, tx_loaded_child_objects, tx_recipients,
+    tx_senders, tx_signatures, validator_epoch_metrics, writer_version,
 };
 use crate::store::diesel_macro::{read_only_blocking, transactional_blocking_with_retry};
 use crate::store::module_resolver_v2::IndexerStoreModuleResolver;
 use crate::types_v2::{
-    IndexedCheckpoint, IndexedEvent, IndexedPackage, IndexedTransaction, TxIndex,
+    IndexedCheckpoint, IndexedCoinHolderChange, IndexedCoinSupplyChange, IndexedCoinType,
+    IndexedEpochMetrics, IndexedEvent, IndexedPackage, IndexedTransaction, IndexedValidatorMetrics,
+    ObjectStatus, OwnerType, TxIndex,
 };
 use crate::PgConnectionPool;
 
@@ -104,6 +129,139 @@ SET object_version = EXCLUDED.object_version,
     df_object_id = EXCLUDED.df_object_id;
 ";
 
+/// Samples the row estimate and on-disk size of every table of the current schema. Partitions
+/// are sampled as tables of their own, and partitioned tables, which hold no data, are not.
+const SAMPLE_STORAGE_STATS_QUERY: &str = r"
+INSERT INTO storage_stats (table_name, sampled_at_ms, row_estimate, total_bytes)
+SELECT c.relname, $1, GREATEST(c.reltuples, 0)::BIGINT, pg_total_relation_size(c.oid)
+FROM pg_class c
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+WHERE n.nspname = current_schema() AND c.relkind = 'r'
+ON CONFLICT DO NOTHING
+RETURNING table_name, sampled_at_ms, row_estimate, total_bytes;
+";
+
+/// Name of the watermark of the orphaned dynamic field scanner in `handler_watermarks`.
+const ORPHANED_DYNAMIC_FIELDS_SCANNER: &str = "orphaned_dynamic_fields";
+
+/// Parents of the dynamic object fields written in checkpoints `($1, $2]`, and of those whose
+/// value object was deleted or wrapped in them. Value deletions in checkpoints pruned from
+/// `objects_history` before they are scanned are only found once the field changes.
+const CHANGED_DYNAMIC_FIELD_PARENTS_QUERY: &str = r"
+SELECT owner_id AS parent_id
+FROM objects
+WHERE df_kind = 1 AND checkpoint_sequence_number > $1 AND checkpoint_sequence_number <= $2
+UNION
+SELECT f.owner_id AS parent_id
+FROM objects_history h
+         JOIN objects f ON f.df_object_id = h.object_id AND f.df_kind = 1
+WHERE h.object_status = 1 AND h.checkpoint_sequence_number > $1
+  AND h.checkpoint_sequence_number <= $2;
+";
+
+/// Clears the reported orphans whose field was removed or now refers to another value, or
+/// whose value object is live again.
+const CLEAR_ORPHANED_DYNAMIC_FIELDS_QUERY: &str = r"
+DELETE FROM orphaned_dynamic_fields r
+WHERE NOT EXISTS (SELECT 1 FROM objects f
+                  WHERE f.object_id = r.field_id AND f.df_object_id = r.value_object_id)
+   OR EXISTS (SELECT 1 FROM objects v WHERE v.object_id = r.value_object_id);
+";
+
+/// Reports the dynamic object fields of the parents `$1` whose value object is not live, as
+/// found by the scan up to checkpoint `$2` at `$3`. Orphans already reported keep the
+/// checkpoint they were first found at.
+const INSERT_ORPHANED_DYNAMIC_FIELDS_QUERY: &str = r"
+INSERT INTO orphaned_dynamic_fields (field_id, parent_id, df_name, df_object_type, value_object_id, checkpoint_sequence_number, detected_at_ms)
+SELECT f.object_id, f.owner_id, f.df_name, f.df_object_type, f.df_object_id, $2, $3
+FROM objects f
+WHERE f.owner_id = ANY($1) AND f.df_kind = 1
+  AND NOT EXISTS (SELECT 1 FROM objects v WHERE v.object_id = f.df_object_id)
+ON CONFLICT (field_id) DO NOTHING;
+";
+
+/// Tables deleted from by the pruner, in the order they are pruned in: transaction indices
+/// before the transactions they point to. Each with its primary key and the column compared
+/// to the pruned watermark, either a checkpoint or a transaction sequence number.
+const PRUNED_TABLES: [(&str, &str, &str); 10] = [
+    (
+        "tx_senders",
+        "sender, tx_sequence_number",
+        "tx_sequence_number",
+    ),
+    (
+        "tx_recipients",
+        "recipient, tx_sequence_number",
+        "tx_sequence_number",
+    ),
+    (
+        "tx_input_objects",
+        "object_id, tx_sequence_number",
+        "tx_sequence_number",
+    ),
+    (
+        "tx_changed_objects",
+        "object_id, tx_sequence_number",
+        "tx_sequence_number",
+    ),
+    (
+        "tx_calls",
+        "package, tx_sequence_number",
+        "tx_sequence_number",
+    ),
+    (
+        "tx_signatures",
+        "tx_sequence_number, signature_index, participant_index",
+        "tx_sequence_number",
+    ),
+    (
+        "transactions",
+        "tx_sequence_number, checkpoint_sequence_number",
+        "checkpoint_sequence_number",
+    ),
+    (
+        "event_object_refs",
+        "object_id, tx_sequence_number, event_sequence_number",
+        "tx_sequence_number",
+    ),
+    (
+        "events",
+        "tx_sequence_number, event_sequence_number",
+        "checkpoint_sequence_number",
+    ),
+    (
+        "objects_history",
+        "checkpoint_sequence_number, object_id, object_version",
+        "checkpoint_sequence_number",
+    ),
+];
+
+/// Restores the live objects written by checkpoint `$1` to their latest version before it, if
+/// that version was not deleted or wrapped, and no later checkpoint wrote, deleted or wrapped
+/// them since.
+const RESTORE_OBJECTS_QUERY: &str = "\
+INSERT INTO objects (object_id, object_version, object_digest, checkpoint_sequence_number, \
+    owner_type, owner_id, object_type, serialized_object, coin_type, coin_balance, df_kind, \
+    df_name, df_object_type, df_object_id) \
+SELECT object_id, object_version, object_digest, checkpoint_sequence_number, owner_type, \
+    owner_id, object_type, serialized_object, coin_type, coin_balance, df_kind, df_name, \
+    df_object_type, df_object_id \
+FROM (SELECT DISTINCT ON (object_id) * FROM objects_history \
+    WHERE object_id IN (SELECT object_id FROM objects_history \
+        WHERE checkpoint_sequence_number = $1) \
+    AND checkpoint_sequence_number < $1 \
+    ORDER BY object_id, object_version DESC) latest \
+WHERE object_status = 0 \
+AND NOT EXISTS (SELECT 1 FROM objects_history later \
+    WHERE later.checkpoint_sequence_number > $1 AND later.object_id = latest.object_id) \
+ON CONFLICT (object_id) DO NOTHING";
+
+/// Deletes the live objects `$1` older than the versions `$2` they were deleted or wrapped at,
+/// leaving those written again at a later version, e.g. unwrapped, in place.
+const DELETE_OBJECTS_QUERY: &str = "\
+DELETE FROM objects USING unnest($1::bytea[], $2::bigint[]) AS deleted(object_id, object_version) \
+WHERE objects.object_id = deleted.object_id AND objects.object_version < deleted.object_version";
+
 #[derive(Clone)]
 pub struct PgIndexerStoreV2 {
     blocking_cp: PgConnectionPool,
@@ -240,9 +398,23 @@ impl PgIndexerStoreV2 {
             }
         }
 
+        // Objects of a checkpoint ingested again, e.g. by the reconciler, may have been written,
+        // deleted or wrapped by a later checkpoint since: only versions newer than the live one
+        // are written, and versions older than a later deletion are not written at all.
         transactional_blocking_with_retry!(
             &self.blocking_cp,
             |conn| {
+                let deleted_since = deleted_since(conn, &mutated_objects)?;
+                let mutated_objects: Vec<_> = mutated_objects
+                    .iter()
+                    .filter(|o| {
+                        deleted_since
+                            .get(&o.object_id)
+                            .map_or(true, |deleted| *deleted < o.object_version)
+                    })
+                    .cloned()
+                    .collect();
+
                 // Persist mutated objects
                 for mutated_object_change_chunk in
                     mutated_objects.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX)
@@ -268,6 +440,7 @@ impl PgIndexerStoreV2 {
                             objects::df_object_type.eq(excluded(objects::df_object_type)),
                             objects::df_object_id.eq(excluded(objects::df_object_id)),
                         ))
+                        .filter(objects::object_version.lt(excluded(objects::object_version)))
                         .execute(conn)
                         .map_err(IndexerError::from)
                         .context("Failed to write object mutation to PostgresDB")?;
@@ -277,19 +450,18 @@ impl PgIndexerStoreV2 {
                 for deleted_objects_chunk in
                     deleted_object_ids.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX)
                 {
-                    diesel::delete(
-                        objects::table.filter(
-                            objects::object_id.eq_any(
-                                deleted_objects_chunk
-                                    .iter()
-                                    .map(|o| o.object_id.clone())
-                                    .collect::<Vec<_>>(),
-                            ),
-                        ),
-                    )
-                    .execute(conn)
-                    .map_err(IndexerError::from)
-                    .context("Failed to write object deletion to PostgresDB")?;
+                    let (object_ids, object_versions): (Vec<_>, Vec<_>) = deleted_objects_chunk
+                        .iter()
+                        .map(|o| (o.object_id.clone(), o.object_version))
+                        .unzip();
+                    diesel::sql_query(DELETE_OBJECTS_QUERY)
+                        .bind::<diesel::sql_types::Array<diesel::sql_types::Bytea>, _>(object_ids)
+                        .bind::<diesel::sql_types::Array<diesel::sql_types::BigInt>, _>(
+                            object_versions,
+                        )
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write object deletion to PostgresDB")?;
                 }
 
                 Ok::<(), IndexerError>(())
@@ -465,6 +637,10 @@ impl PgIndexerStoreV2 {
             .checkpoint_db_commit_latency_events_chunks
             .start_timer();
         let len = events.len();
+        let object_refs = events
+            .iter()
+            .flat_map(StoredEventObjectRef::from_event)
+            .collect::<Vec<_>>();
         let events = events
             .into_iter()
             .map(StoredEvent::from)
@@ -481,6 +657,14 @@ impl PgIndexerStoreV2 {
                         .map_err(IndexerError::from)
                         .context("Failed to write events to PostgresDB")?;
                 }
+                for object_refs_chunk in object_refs.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    diesel::insert_into(event_object_refs::table)
+                        .values(object_refs_chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write event object references to PostgresDB")?;
+                }
                 Ok::<(), IndexerError>(())
             },
             Duration::from_secs(60)
@@ -499,6 +683,21 @@ impl PgIndexerStoreV2 {
             .metrics
             .checkpoint_db_commit_latency_packages
             .start_timer();
+        let dependencies = packages
+            .iter()
+            .flat_map(StoredPackageDependency::from_package)
+            .collect::<Vec<_>>();
+        // Upgrades supersede the edges of the earlier versions of the package.
+        let upgrades = packages
+            .iter()
+            .filter(|p| p.move_package.version().value() > 1)
+            .map(|p| {
+                (
+                    p.move_package.original_package_id().to_vec(),
+                    p.move_package.version().value() as i64,
+                )
+            })
+            .collect::<Vec<_>>();
         let packages = packages
             .into_iter()
             .map(StoredPackage::from)
@@ -519,6 +718,24 @@ impl PgIndexerStoreV2 {
                         .map_err(IndexerError::from)
                         .context("Failed to write packages to PostgresDB")?;
                 }
+                for dependencies_chunk in dependencies.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    diesel::insert_into(package_deps::table)
+                        .values(dependencies_chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write package dependencies to PostgresDB")?;
+                }
+                for (original_package_id, version) in &upgrades {
+                    diesel::update(package_deps::table)
+                        .filter(package_deps::original_package_id.eq(original_package_id))
+                        .filter(package_deps::package_version.lt(version))
+                        .filter(package_deps::latest.eq(true))
+                        .set(package_deps::latest.eq(false))
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to supersede package dependencies in PostgresDB")?;
+                }
                 Ok::<(), IndexerError>(())
             },
             Duration::from_secs(60)
@@ -529,38 +746,198 @@ impl PgIndexerStoreV2 {
         })
     }
 
+    fn persist_coin_types(
+        &self,
+        indexed_coin_types: Vec<IndexedCoinType>,
+        holder_changes: Vec<IndexedCoinHolderChange>,
+    ) -> Result<(), IndexerError> {
+        // Checkpoints come in order, so the first time a type is seen is its earliest.
+        let mut new_coin_types: BTreeMap<String, StoredCoinType> = BTreeMap::new();
+        for coin_type in &indexed_coin_types {
+            let stored = new_coin_types
+                .entry(coin_type.coin_type.clone())
+                .or_insert_with(|| StoredCoinType::from(coin_type));
+            if stored.metadata_object_id.is_none() {
+                stored.metadata_object_id = coin_type.metadata_object_id.map(|id| id.to_vec());
+            }
+        }
+        let mut last_checkpoints: HashMap<String, i64> = HashMap::new();
+        for change in &holder_changes {
+            let last = last_checkpoints
+                .entry(change.coin_type.clone())
+                .or_default();
+            *last = (*last).max(change.checkpoint_sequence_number as i64);
+        }
+        let changed_coin_types = last_checkpoints.keys().cloned().collect::<Vec<_>>();
+        let owners = holder_changes
+            .iter()
+            .map(|change| change.owner.to_vec())
+            .unique()
+            .collect::<Vec<_>>();
+
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                let stored_coin_types = new_coin_types.values().cloned().collect::<Vec<_>>();
+                for coin_types_chunk in stored_coin_types.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    diesel::insert_into(coin_types::table)
+                        .values(coin_types_chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write coin types to PostgresDB")?;
+                }
+                for stored in &stored_coin_types {
+                    let Some(metadata_object_id) = &stored.metadata_object_id else {
+                        continue;
+                    };
+                    diesel::update(
+                        coin_types::table
+                            .filter(coin_types::coin_type.eq(&stored.coin_type))
+                            .filter(coin_types::metadata_object_id.is_null()),
+                    )
+                    .set(coin_types::metadata_object_id.eq(metadata_object_id))
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to write coin metadata object IDs to PostgresDB")?;
+                }
+                if holder_changes.is_empty() {
+                    return Ok::<(), IndexerError>(());
+                }
+
+                // Changes of checkpoints already counted, before a restart, are skipped.
+                let holders_checkpoints: HashMap<String, i64> = coin_types::table
+                    .filter(coin_types::coin_type.eq_any(changed_coin_types.clone()))
+                    .select((coin_types::coin_type, coin_types::holders_checkpoint))
+                    .load::<(String, i64)>(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to read coin type holder checkpoints from PostgresDB")?
+                    .into_iter()
+                    .collect();
+                let mut coin_count_deltas: HashMap<(Vec<u8>, &str), i64> = HashMap::new();
+                for change in &holder_changes {
+                    let Some(holders_checkpoint) = holders_checkpoints.get(&change.coin_type)
+                    else {
+                        continue;
+                    };
+                    if change.checkpoint_sequence_number as i64 > *holders_checkpoint {
+                        *coin_count_deltas
+                            .entry((change.owner.to_vec(), change.coin_type.as_str()))
+                            .or_default() += change.coin_count_delta;
+                    }
+                }
+
+                // An owner holds a coin type while it owns at least one coin of it with a
+                // positive balance, and the objects table already reflects the batch.
+                let coin_counts: HashMap<(Vec<u8>, String), i64> = objects::table
+                    .filter(objects::owner_type.eq(OwnerType::Address as i16))
+                    .filter(objects::owner_id.eq_any(owners.clone()))
+                    .filter(objects::coin_type.eq_any(changed_coin_types.clone()))
+                    .filter(objects::coin_balance.gt(0))
+                    .group_by((objects::owner_id, objects::coin_type))
+                    .select((
+                        objects::owner_id,
+                        objects::coin_type,
+                        diesel::dsl::count_star(),
+                    ))
+                    .load::<(Option<Vec<u8>>, Option<String>, i64)>(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to read coin counts from PostgresDB")?
+                    .into_iter()
+                    .filter_map(|(owner, coin_type, count)| Some(((owner?, coin_type?), count)))
+                    .collect();
+                let mut holder_deltas: HashMap<&str, i64> = HashMap::new();
+                for ((owner, coin_type), delta) in coin_count_deltas {
+                    let after = coin_counts
+                        .get(&(owner, coin_type.to_string()))
+                        .copied()
+                        .unwrap_or(0);
+                    let before = after - delta;
+                    *holder_deltas.entry(coin_type).or_default() +=
+                        (after > 0) as i64 - (before > 0) as i64;
+                }
+
+                for (coin_type, last_checkpoint) in &last_checkpoints {
+                    let delta = holder_deltas.get(coin_type.as_str()).copied().unwrap_or(0);
+                    diesel::update(
+                        coin_types::table
+                            .filter(coin_types::coin_type.eq(coin_type))
+                            .filter(coin_types::holders_checkpoint.lt(*last_checkpoint)),
+                    )
+                    .set((
+                        coin_types::holder_count.eq(coin_types::holder_count + delta),
+                        coin_types::holders_checkpoint.eq(*last_checkpoint),
+                    ))
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to write coin type holder counts to PostgresDB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+        .tap(|_| {
+            info!(
+                "Persisted {} coin types and {} coin holder changes",
+                new_coin_types.len(),
+                holder_changes.len()
+            )
+        })
+    }
+
     async fn persist_tx_indices_chunk(&self, indices: Vec<TxIndex>) -> Result<(), IndexerError> {
         let guard = self
             .metrics
             .checkpoint_db_commit_latency_tx_indices_chunks
             .start_timer();
         let len = indices.len();
-        let (senders, recipients, input_objects, changed_objects, calls) =
-            indices.into_iter().map(|i| i.split()).fold(
-                (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new()),
-                |(
-                    mut tx_senders,
-                    mut tx_recipients,
-                    mut tx_input_objects,
-                    mut tx_changed_objects,
-                    mut tx_calls,
-                ),
-                 index| {
-                    tx_senders.extend(index.0);
-                    tx_recipients.extend(index.1);
-                    tx_input_objects.extend(index.2);
-                    tx_changed_objects.extend(index.3);
-                    tx_calls.extend(index.4);
-
-                    (
-                        tx_senders,
-                        tx_recipients,
-                        tx_input_objects,
-                        tx_changed_objects,
-                        tx_calls,
-                    )
-                },
-            );
+        let (
+            senders,
+            recipients,
+            input_objects,
+            changed_objects,
+            calls,
+            loaded_child_objects,
+            signatures,
+        ) = indices.into_iter().map(|i| i.split()).fold(
+            (
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+            ),
+            |(
+                mut tx_senders,
+                mut tx_recipients,
+                mut tx_input_objects,
+                mut tx_changed_objects,
+                mut tx_calls,
+                mut tx_loaded_child_objects,
+                mut tx_signatures,
+            ),
+             index| {
+                tx_senders.extend(index.0);
+                tx_recipients.extend(index.1);
+                tx_input_objects.extend(index.2);
+                tx_changed_objects.extend(index.3);
+                tx_calls.extend(index.4);
+                tx_loaded_child_objects.extend(index.5);
+                tx_signatures.extend(index.6);
+
+                (
+                    tx_senders,
+                    tx_recipients,
+                    tx_input_objects,
+                    tx_changed_objects,
+                    tx_calls,
+                    tx_loaded_child_objects,
+                    tx_signatures,
+                )
+            },
+        );
 
         let mut futures = vec![];
         futures.push(self.spawn_blocking_task(move |this| {
@@ -676,6 +1053,60 @@ impl PgIndexerStoreV2 {
                 info!(elapsed, "Persisted {} rows to tx_calls tables", calls_len);
             })
         }));
+        futures.push(self.spawn_blocking_task(move |this| {
+            let now = Instant::now();
+            let loaded_child_objects_len = loaded_child_objects.len();
+            transactional_blocking_with_retry!(
+                &this.blocking_cp,
+                |conn| {
+                    for chunk in loaded_child_objects.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                        diesel::insert_into(tx_loaded_child_objects::table)
+                            .values(chunk)
+                            .on_conflict_do_nothing()
+                            .execute(conn)
+                            .map_err(IndexerError::from)
+                            .context(
+                                "Failed to write tx_loaded_child_objects chunk to PostgresDB",
+                            )?;
+                    }
+                    Ok::<(), IndexerError>(())
+                },
+                Duration::from_secs(60)
+            )
+            .tap(|_| {
+                let elapsed = now.elapsed().as_secs_f64();
+                info!(
+                    elapsed,
+                    "Persisted {} rows to tx_loaded_child_objects table", loaded_child_objects_len,
+                );
+            })
+        }));
+        futures.push(self.spawn_blocking_task(move |this| {
+            let now = Instant::now();
+            let signatures_len = signatures.len();
+            transactional_blocking_with_retry!(
+                &this.blocking_cp,
+                |conn| {
+                    for chunk in signatures.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                        diesel::insert_into(tx_signatures::table)
+                            .values(chunk)
+                            .on_conflict_do_nothing()
+                            .execute(conn)
+                            .map_err(IndexerError::from)
+                            .context("Failed to write tx_signatures chunk to PostgresDB")?;
+                    }
+                    Ok::<(), IndexerError>(())
+                },
+                Duration::from_secs(60)
+            )
+            .tap(|_| {
+                let elapsed = now.elapsed().as_secs_f64();
+                info!(
+                    elapsed,
+                    "Persisted {} rows to tx_signatures table", signatures_len
+                );
+            })
+        }));
         futures::future::join_all(futures)
             .await
             .into_iter()
@@ -691,6 +1122,377 @@ impl PgIndexerStoreV2 {
         Ok(())
     }
 
+    fn persist_tx_object_indices(&self, indices: Vec<TxIndex>) -> Result<(), IndexerError> {
+        let (input_objects, changed_objects): (Vec<_>, Vec<_>) = indices
+            .into_iter()
+            .map(|index| {
+                let (_, _, input_objects, changed_objects, _, _, _) = index.split();
+                (input_objects, changed_objects)
+            })
+            .unzip();
+        let input_objects = input_objects.into_iter().flatten().collect::<Vec<_>>();
+        let changed_objects = changed_objects.into_iter().flatten().collect::<Vec<_>>();
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for chunk in input_objects.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    diesel::insert_into(tx_input_objects::table)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write tx_input_objects chunk to PostgresDB")?;
+                }
+                for chunk in changed_objects.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    diesel::insert_into(tx_changed_objects::table)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write tx_changed_objects chunk to PostgresDB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )?;
+        info!(
+            "Persisted {} rows to tx_input_objects and {} rows to tx_changed_objects",
+            input_objects.len(),
+            changed_objects.len()
+        );
+        Ok(())
+    }
+
+    fn persist_epoch_metrics(&self, metrics: Vec<IndexedEpochMetrics>) -> Result<(), IndexerError> {
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for checkpoint in &metrics {
+                    let epoch = checkpoint.epoch as i64;
+                    let stored = epoch_metrics::table
+                        .find(epoch)
+                        .first::<StoredEpochMetrics>(conn)
+                        .optional()
+                        .map_err(IndexerError::from)
+                        .context("Failed to read epoch metrics from PostgresDB")?;
+                    // Checkpoints indexed again after a restart were counted the first time.
+                    if stored
+                        .as_ref()
+                        .is_some_and(|stored| stored.counts(checkpoint.checkpoint_sequence_number))
+                    {
+                        continue;
+                    }
+
+                    let senders = checkpoint
+                        .senders
+                        .iter()
+                        .map(|sender| StoredEpochMetricsSender {
+                            epoch,
+                            address: sender.to_vec(),
+                        })
+                        .collect::<Vec<_>>();
+                    let mut new_senders = 0;
+                    for chunk in senders.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                        new_senders += diesel::insert_into(epoch_metrics_senders::table)
+                            .values(chunk)
+                            .on_conflict_do_nothing()
+                            .execute(conn)
+                            .map_err(IndexerError::from)
+                            .context("Failed to write epoch senders to PostgresDB")?;
+                    }
+                    let addresses = checkpoint
+                        .addresses
+                        .iter()
+                        .map(|address| StoredEpochMetricsAddress {
+                            address: address.to_vec(),
+                            first_epoch: epoch,
+                        })
+                        .collect::<Vec<_>>();
+                    let mut new_addresses = 0;
+                    for chunk in addresses.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                        new_addresses += diesel::insert_into(epoch_metrics_addresses::table)
+                            .values(chunk)
+                            .on_conflict_do_nothing()
+                            .execute(conn)
+                            .map_err(IndexerError::from)
+                            .context("Failed to write epoch addresses to PostgresDB")?;
+                    }
+
+                    let rolled_up =
+                        StoredEpochMetrics::roll_up(stored, checkpoint, new_senders, new_addresses);
+                    diesel::insert_into(epoch_metrics::table)
+                        .values(&rolled_up)
+                        .on_conflict(epoch_metrics::epoch)
+                        .do_update()
+                        .set((
+                            epoch_metrics::last_checkpoint
+                                .eq(excluded(epoch_metrics::last_checkpoint)),
+                            epoch_metrics::total_transactions
+                                .eq(excluded(epoch_metrics::total_transactions)),
+                            epoch_metrics::total_gas_used
+                                .eq(excluded(epoch_metrics::total_gas_used)),
+                            epoch_metrics::total_storage_rebate
+                                .eq(excluded(epoch_metrics::total_storage_rebate)),
+                            epoch_metrics::active_senders
+                                .eq(excluded(epoch_metrics::active_senders)),
+                            epoch_metrics::new_addresses.eq(excluded(epoch_metrics::new_addresses)),
+                            epoch_metrics::finalized.eq(excluded(epoch_metrics::finalized)),
+                        ))
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write epoch metrics to PostgresDB")?;
+
+                    // Senders only matter to the checkpoints left in the epoch.
+                    if checkpoint.end_of_epoch {
+                        diesel::delete(
+                            epoch_metrics_senders::table
+                                .filter(epoch_metrics_senders::epoch.eq(epoch)),
+                        )
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to delete epoch senders from PostgresDB")?;
+                    }
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )?;
+        info!("Rolled up epoch metrics of {} checkpoints", metrics.len());
+        Ok(())
+    }
+
+    fn persist_validator_metrics(
+        &self,
+        metrics: Vec<IndexedValidatorMetrics>,
+    ) -> Result<(), IndexerError> {
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for checkpoint in &metrics {
+                    let epoch = checkpoint.epoch as i64;
+                    let sequence_number = checkpoint.checkpoint_sequence_number as i64;
+                    if let Some(committee) = &checkpoint.committee {
+                        let validators = StoredValidatorEpochMetrics::from_committee(
+                            committee,
+                            checkpoint.checkpoint_sequence_number,
+                        );
+                        for chunk in validators.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                            diesel::insert_into(validator_epoch_metrics::table)
+                                .values(chunk)
+                                .on_conflict_do_nothing()
+                                .execute(conn)
+                                .map_err(IndexerError::from)
+                                .context("Failed to write validator committee to PostgresDB")?;
+                        }
+                    }
+
+                    // Checkpoints indexed again after a restart were counted the first time.
+                    let signers = checkpoint.signers.iter().map(|index| *index as i32);
+                    diesel::update(
+                        validator_epoch_metrics::table
+                            .filter(validator_epoch_metrics::epoch.eq(epoch))
+                            .filter(validator_epoch_metrics::last_checkpoint.lt(sequence_number))
+                            .filter(validator_epoch_metrics::committee_index.eq_any(signers)),
+                    )
+                    .set(
+                        validator_epoch_metrics::checkpoints_signed
+                            .eq(validator_epoch_metrics::checkpoints_signed + 1),
+                    )
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to write validator signatures to PostgresDB")?;
+                    diesel::update(
+                        validator_epoch_metrics::table
+                            .filter(validator_epoch_metrics::epoch.eq(epoch))
+                            .filter(validator_epoch_metrics::last_checkpoint.lt(sequence_number)),
+                    )
+                    .set((
+                        validator_epoch_metrics::checkpoints
+                            .eq(validator_epoch_metrics::checkpoints + 1),
+                        validator_epoch_metrics::last_checkpoint.eq(sequence_number),
+                    ))
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to write validator metrics to PostgresDB")?;
+
+                    for report in &checkpoint.reports {
+                        diesel::update(
+                            validator_epoch_metrics::table
+                                .filter(validator_epoch_metrics::epoch.eq(epoch))
+                                .filter(
+                                    validator_epoch_metrics::validator_address
+                                        .eq(report.validator_address.to_vec()),
+                                ),
+                        )
+                        .set((
+                            validator_epoch_metrics::reporters.eq(report.reporters as i64),
+                            validator_epoch_metrics::tallying_score
+                                .eq(report.tallying_score as i64),
+                        ))
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write validator reports to PostgresDB")?;
+                    }
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )?;
+        info!("Rolled up validator metrics of {} checkpoints", metrics.len());
+        Ok(())
+    }
+
+    fn persist_coin_supply_changes(
+        &self,
+        changes: Vec<IndexedCoinSupplyChange>,
+    ) -> Result<(), IndexerError> {
+        // Checkpoints come in order, so the first change of a type is the one its supply is
+        // offset from.
+        let mut offsets: BTreeMap<String, StoredCoinSupplyOffset> = BTreeMap::new();
+        for change in &changes {
+            offsets
+                .entry(change.coin_type.clone())
+                .or_insert_with(|| StoredCoinSupplyOffset::from(change));
+        }
+        let offsets = offsets.into_values().collect::<Vec<_>>();
+        let changes = changes
+            .iter()
+            .map(StoredCoinSupplyChange::from)
+            .collect::<Vec<_>>();
+
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for offsets_chunk in offsets.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    diesel::insert_into(coin_supply_offsets::table)
+                        .values(offsets_chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write coin supply offsets to PostgresDB")?;
+                }
+                for changes_chunk in changes.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    diesel::insert_into(coin_supply_changes::table)
+                        .values(changes_chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write coin supply changes to PostgresDB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+    }
+
+    fn persist_storage_stats(
+        &self,
+        sampled_at_ms: u64,
+        retain_from_ms: u64,
+    ) -> Result<Vec<TableStorageStats>, IndexerError> {
+        let sampled = transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                let sampled = diesel::sql_query(SAMPLE_STORAGE_STATS_QUERY)
+                    .bind::<diesel::sql_types::BigInt, _>(sampled_at_ms as i64)
+                    .load::<StoredStorageStats>(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to write storage stats to PostgresDB")?;
+                diesel::delete(
+                    storage_stats::table
+                        .filter(storage_stats::sampled_at_ms.lt(retain_from_ms as i64)),
+                )
+                .execute(conn)
+                .map_err(IndexerError::from)
+                .context("Failed to delete storage stats from PostgresDB")?;
+                Ok::<_, IndexerError>(sampled)
+            },
+            Duration::from_secs(60)
+        )?;
+        let baseline = read_only_blocking!(&self.blocking_cp, |conn| {
+            StoredStorageStats::load_latest_at(
+                conn,
+                sampled_at_ms as i64 - STORAGE_GROWTH_WINDOW_MS,
+            )
+        })
+        .context("Failed reading storage stats from PostgresDB")?;
+        Ok(StoredStorageStats::with_growth(sampled, baseline))
+    }
+
+    fn scan_orphaned_dynamic_fields(
+        &self,
+        batch_size: usize,
+        detected_at_ms: u64,
+    ) -> Result<Option<OrphanedDynamicFieldsScan>, IndexerError> {
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                let lo = handler_watermarks::table
+                    .filter(
+                        handler_watermarks::handler_name.eq(ORPHANED_DYNAMIC_FIELDS_SCANNER),
+                    )
+                    .select(handler_watermarks::checkpoint_sequence_number)
+                    .first::<i64>(conn)
+                    .optional()?
+                    .unwrap_or(-1);
+                let latest = checkpoints::table
+                    .select(max(checkpoints::sequence_number))
+                    .first::<Option<i64>>(conn)?;
+                let Some(hi) = latest
+                    .map(|latest| latest.min(lo.saturating_add(batch_size as i64)))
+                    .filter(|hi| *hi > lo)
+                else {
+                    return Ok::<_, IndexerError>(None);
+                };
+
+                let parents: Vec<_> = diesel::sql_query(CHANGED_DYNAMIC_FIELD_PARENTS_QUERY)
+                    .bind::<diesel::sql_types::BigInt, _>(lo)
+                    .bind::<diesel::sql_types::BigInt, _>(hi)
+                    .load::<ChangedParent>(conn)?
+                    .into_iter()
+                    .map(|parent| parent.parent_id)
+                    .collect();
+                let orphans_cleared =
+                    diesel::sql_query(CLEAR_ORPHANED_DYNAMIC_FIELDS_QUERY).execute(conn)?;
+                let orphans_found = if parents.is_empty() {
+                    0
+                } else {
+                    diesel::sql_query(INSERT_ORPHANED_DYNAMIC_FIELDS_QUERY)
+                        .bind::<diesel::sql_types::Array<diesel::sql_types::Bytea>, _>(&parents)
+                        .bind::<diesel::sql_types::BigInt, _>(hi)
+                        .bind::<diesel::sql_types::BigInt, _>(detected_at_ms as i64)
+                        .execute(conn)?
+                };
+                let orphan_count = orphaned_dynamic_fields::table
+                    .count()
+                    .get_result::<i64>(conn)?;
+
+                diesel::insert_into(handler_watermarks::table)
+                    .values(StoredHandlerWatermark {
+                        handler_name: ORPHANED_DYNAMIC_FIELDS_SCANNER.to_string(),
+                        checkpoint_sequence_number: hi,
+                    })
+                    .on_conflict(handler_watermarks::handler_name)
+                    .do_update()
+                    .set(
+                        handler_watermarks::checkpoint_sequence_number
+                            .eq(excluded(handler_watermarks::checkpoint_sequence_number)),
+                    )
+                    .execute(conn)?;
+
+                Ok(Some(OrphanedDynamicFieldsScan {
+                    checkpoint: hi as u64,
+                    parents_scanned: parents.len(),
+                    orphans_found,
+                    orphans_cleared,
+                    orphan_count: orphan_count as u64,
+                }))
+            },
+            Duration::from_secs(60)
+        )
+        .context("Failed to scan for orphaned dynamic fields in PostgresDB")
+    }
+
     fn persist_epoch(&self, epoch: EpochToCommit) -> Result<(), IndexerError> {
         let guard = self
             .metrics
@@ -787,6 +1589,360 @@ impl PgIndexerStoreV2 {
         Ok(())
     }
 
+    fn get_checkpoint_epoch(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<Option<EpochId>, IndexerError> {
+        read_only_blocking!(&self.blocking_cp, |conn| {
+            checkpoints::table
+                .filter(checkpoints::sequence_number.eq(checkpoint as i64))
+                .select(checkpoints::epoch)
+                .first::<i64>(conn)
+                .optional()
+                .map(|epoch| epoch.map(|epoch| epoch as u64))
+        })
+        .context("Failed reading checkpoint epoch from PostgresDB")
+    }
+
+    fn get_epoch_first_checkpoint(
+        &self,
+        epoch: EpochId,
+    ) -> Result<Option<CheckpointSequenceNumber>, IndexerError> {
+        read_only_blocking!(&self.blocking_cp, |conn| {
+            epochs::table
+                .filter(epochs::epoch.eq(epoch as i64))
+                .select(epochs::first_checkpoint_id)
+                .first::<i64>(conn)
+                .optional()
+                .map(|checkpoint| checkpoint.map(|checkpoint| checkpoint as u64))
+        })
+        .context("Failed reading epoch first checkpoint from PostgresDB")
+    }
+
+    fn get_pruned_watermark(&self) -> Result<CheckpointSequenceNumber, IndexerError> {
+        read_only_blocking!(&self.blocking_cp, |conn| {
+            pruner_watermark::table
+                .select(pruner_watermark::checkpoint_hi_exclusive)
+                .first::<i64>(conn)
+                .optional()
+                .map(|checkpoint| checkpoint.unwrap_or(0) as u64)
+        })
+        .context("Failed reading pruned watermark from PostgresDB")
+    }
+
+    /// Sequence number of the first transaction of `checkpoint`.
+    fn get_first_tx_sequence_number(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<u64, IndexerError> {
+        if checkpoint == 0 {
+            return Ok(0);
+        }
+        read_only_blocking!(&self.blocking_cp, |conn| {
+            checkpoints::table
+                .filter(checkpoints::sequence_number.eq(checkpoint as i64 - 1))
+                .select(checkpoints::network_total_transactions)
+                .first::<i64>(conn)
+                .map(|total| total as u64)
+        })
+        .context("Failed reading first transaction of checkpoint from PostgresDB")
+    }
+
+    fn set_pruned_watermark(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<(), IndexerError> {
+        let watermark = StoredPrunerWatermark {
+            id: true,
+            checkpoint_hi_exclusive: checkpoint as i64,
+            tx_hi_exclusive: self.get_first_tx_sequence_number(checkpoint)? as i64,
+        };
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                diesel::insert_into(pruner_watermark::table)
+                    .values(watermark.clone())
+                    .on_conflict(pruner_watermark::id)
+                    .do_update()
+                    .set((
+                        pruner_watermark::checkpoint_hi_exclusive
+                            .eq(excluded(pruner_watermark::checkpoint_hi_exclusive)),
+                        pruner_watermark::tx_hi_exclusive
+                            .eq(excluded(pruner_watermark::tx_hi_exclusive)),
+                    ))
+                    .execute(conn)
+            },
+            Duration::from_secs(10)
+        )
+        .context("Failed to write pruned watermark to PostgresDB")?;
+        Ok(())
+    }
+
+    fn persist_writer_version(&self, version: u64) -> Result<(), IndexerError> {
+        let stored_version = StoredWriterVersion {
+            id: true,
+            version: version as i64,
+        };
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                diesel::insert_into(writer_version::table)
+                    .values(stored_version.clone())
+                    .on_conflict(writer_version::id)
+                    .do_update()
+                    .set(writer_version::version.eq(excluded(writer_version::version)))
+                    .execute(conn)
+            },
+            Duration::from_secs(10)
+        )
+        .context("Failed to write writer version to PostgresDB")?;
+        Ok(())
+    }
+
+    fn get_handler_watermark(
+        &self,
+        handler_name: &str,
+    ) -> Result<Option<CheckpointSequenceNumber>, IndexerError> {
+        read_only_blocking!(&self.blocking_cp, |conn| {
+            handler_watermarks::table
+                .filter(handler_watermarks::handler_name.eq(handler_name))
+                .select(handler_watermarks::checkpoint_sequence_number)
+                .first::<i64>(conn)
+                .optional()
+                .map(|checkpoint| checkpoint.map(|checkpoint| checkpoint as u64))
+        })
+        .context("Failed reading handler watermark from PostgresDB")
+    }
+
+    fn set_handler_watermark(
+        &self,
+        handler_name: &str,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<(), IndexerError> {
+        let watermark = StoredHandlerWatermark {
+            handler_name: handler_name.to_string(),
+            checkpoint_sequence_number: checkpoint as i64,
+        };
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                diesel::insert_into(handler_watermarks::table)
+                    .values(watermark.clone())
+                    .on_conflict(handler_watermarks::handler_name)
+                    .do_update()
+                    .set(
+                        handler_watermarks::checkpoint_sequence_number
+                            .eq(excluded(handler_watermarks::checkpoint_sequence_number)),
+                    )
+                    .execute(conn)
+            },
+            Duration::from_secs(10)
+        )
+        .context("Failed to write handler watermark to PostgresDB")?;
+        Ok(())
+    }
+
+    fn drop_partitions(
+        &self,
+        table: &str,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<usize, IndexerError> {
+        let dropped = self
+            .partition_manager
+            .drop_partitions_before(table, checkpoint)
+            .context(&format!("Failed to drop partitions of table {table}"))?;
+        Ok(dropped.len())
+    }
+
+    /// Rows are deleted by primary key, which also works for partitioned tables.
+    fn prune_table(
+        &self,
+        table: &str,
+        checkpoint: CheckpointSequenceNumber,
+        batch_size: usize,
+    ) -> Result<usize, IndexerError> {
+        let Some((_, primary_key, pruned_by)) = PRUNED_TABLES.iter().find(|(t, ..)| *t == table)
+        else {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Table {table} is not pruned"
+            )));
+        };
+        let bound = if *pruned_by == "tx_sequence_number" {
+            self.get_first_tx_sequence_number(checkpoint)?
+        } else {
+            checkpoint
+        };
+        let query = format!(
+            "DELETE FROM {table} WHERE ({primary_key}) IN \
+            (SELECT {primary_key} FROM {table} WHERE {pruned_by} < $1 LIMIT $2)"
+        );
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                RunQueryDsl::execute(
+                    diesel::sql_query(query.clone())
+                        .bind::<diesel::sql_types::BigInt, _>(bound as i64)
+                        .bind::<diesel::sql_types::BigInt, _>(batch_size as i64),
+                    conn,
+                )
+            },
+            Duration::from_secs(60)
+        )
+        .context(&format!("Failed to prune table {table}"))
+    }
+
+    /// The reconciled watermark, never below the pruned one since pruned checkpoints cannot be
+    /// verified anymore.
+    fn get_reconciled_watermark(&self) -> Result<CheckpointSequenceNumber, IndexerError> {
+        let reconciled = read_only_blocking!(&self.blocking_cp, |conn| {
+            reconciler_watermark::table
+                .select(reconciler_watermark::checkpoint_hi_exclusive)
+                .first::<i64>(conn)
+                .optional()
+                .map(|checkpoint| checkpoint.unwrap_or(0) as u64)
+        })
+        .context("Failed reading reconciled watermark from PostgresDB")?;
+        Ok(reconciled.max(self.get_pruned_watermark()?))
+    }
+
+    fn set_reconciled_watermark(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<(), IndexerError> {
+        let watermark = StoredReconcilerWatermark {
+            id: true,
+            checkpoint_hi_exclusive: checkpoint as i64,
+        };
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                diesel::insert_into(reconciler_watermark::table)
+                    .values(watermark.clone())
+                    .on_conflict(reconciler_watermark::id)
+                    .do_update()
+                    .set(
+                        reconciler_watermark::checkpoint_hi_exclusive
+                            .eq(excluded(reconciler_watermark::checkpoint_hi_exclusive)),
+                    )
+                    .execute(conn)
+            },
+            Duration::from_secs(10)
+        )
+        .context("Failed to write reconciled watermark to PostgresDB")?;
+        Ok(())
+    }
+
+    fn get_checkpoint_digest(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<Option<CheckpointDigest>, IndexerError> {
+        let digest = read_only_blocking!(&self.blocking_cp, |conn| {
+            checkpoints::table
+                .filter(checkpoints::sequence_number.eq(checkpoint as i64))
+                .select(checkpoints::checkpoint_digest)
+                .first::<Vec<u8>>(conn)
+                .optional()
+        })
+        .context("Failed reading checkpoint digest from PostgresDB")?;
+        digest
+            .map(|digest| {
+                CheckpointDigest::try_from(digest).map_err(|e| {
+                    IndexerError::PersistentStorageDataCorruptionError(format!(
+                        "Failed to decode digest of checkpoint {checkpoint}: {e:?}"
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    /// Moves the rows of `checkpoint` into `tombstones` in one transaction, so that readers see
+    /// either all of them or none. Live objects last written by the checkpoint are moved as
+    /// well, and the objects it wrote are restored to their version before it from the object
+    /// history, until the checkpoint is ingested again.
+    fn tombstone_checkpoint(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+        ingested: CheckpointDigest,
+        certified: CheckpointDigest,
+    ) -> Result<usize, IndexerError> {
+        let first_tx = self.get_first_tx_sequence_number(checkpoint)? as i64;
+        let checkpoint = checkpoint as i64;
+        let ingested = ingested.into_inner().to_vec();
+        let certified = certified.into_inner().to_vec();
+        // Moves the rows of `table` whose `tombstoned_by` column is within `range`.
+        let tombstone_rows = |conn: &mut PgConnection,
+                              table: &str,
+                              tombstoned_by: &str,
+                              (start, end): (i64, i64)| {
+            let query = format!(
+                "WITH tombstoned AS (DELETE FROM {table} \
+                WHERE {tombstoned_by} >= $1 AND {tombstoned_by} < $2 RETURNING *) \
+                INSERT INTO tombstones (checkpoint_sequence_number, ingested_digest, \
+                certified_digest, table_name, row_data, tombstoned_at_ms) \
+                SELECT $3, $4, $5, '{table}', to_jsonb(tombstoned), \
+                (EXTRACT(EPOCH FROM NOW()) * 1000)::BIGINT FROM tombstoned"
+            );
+            RunQueryDsl::execute(
+                diesel::sql_query(query)
+                    .bind::<diesel::sql_types::BigInt, _>(start)
+                    .bind::<diesel::sql_types::BigInt, _>(end)
+                    .bind::<diesel::sql_types::BigInt, _>(checkpoint)
+                    .bind::<diesel::sql_types::Bytea, _>(ingested.clone())
+                    .bind::<diesel::sql_types::Bytea, _>(certified.clone()),
+                conn,
+            )
+        };
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                let end_tx = checkpoints::table
+                    .filter(checkpoints::sequence_number.eq(checkpoint))
+                    .select(checkpoints::network_total_transactions)
+                    .first::<i64>(conn)
+                    .optional()?
+                    .unwrap_or(first_tx);
+                let checkpoint_range = (checkpoint, checkpoint + 1);
+                let mut tombstoned = tombstone_rows(
+                    conn,
+                    "objects",
+                    "checkpoint_sequence_number",
+                    checkpoint_range,
+                )?;
+                RunQueryDsl::execute(
+                    diesel::sql_query(RESTORE_OBJECTS_QUERY)
+                        .bind::<diesel::sql_types::BigInt, _>(checkpoint),
+                    conn,
+                )?;
+                tombstoned +=
+                    tombstone_rows(conn, "checkpoints", "sequence_number", checkpoint_range)?;
+                for (table, _, pruned_by) in PRUNED_TABLES {
+                    let range = if pruned_by == "tx_sequence_number" {
+                        (first_tx, end_tx)
+                    } else {
+                        checkpoint_range
+                    };
+                    tombstoned += tombstone_rows(conn, table, pruned_by, range)?;
+                }
+                Ok::<usize, IndexerError>(tombstoned)
+            },
+            Duration::from_secs(60)
+        )
+        .context(&format!("Failed to tombstone checkpoint {checkpoint}"))
+    }
+
+    fn notify_commit(&self, notice: CommitNotice) -> Result<(), IndexerError> {
+        let payload = serde_json::to_string(&notice)
+            .map_err(|e| IndexerError::SerdeError(e.to_string()))?;
+        let mut conn = crate::get_pg_pool_connection(&self.blocking_cp)?;
+        diesel::sql_query("SELECT pg_notify($1, $2)")
+            .bind::<diesel::sql_types::Text, _>(COMMIT_NOTICE_CHANNEL)
+            .bind::<diesel::sql_types::Text, _>(payload)
+            .execute(&mut conn)
+            .map_err(|e| IndexerError::PostgresWriteError(e.to_string()))
+            .context("Failed to publish commit notice")?;
+        Ok(())
+    }
+
     fn get_network_total_transactions_by_end_of_epoch(
         &self,
         epoch: u64,
@@ -1071,6 +2227,31 @@ impl IndexerStoreV2 for PgIndexerStoreV2 {
             .await
     }
 
+    async fn persist_coin_types(
+        &self,
+        coin_types: Vec<IndexedCoinType>,
+        holder_changes: Vec<IndexedCoinHolderChange>,
+    ) -> Result<(), IndexerError> {
+        if coin_types.is_empty() && holder_changes.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| {
+            this.persist_coin_types(coin_types, holder_changes)
+        })
+        .await
+    }
+
+    async fn persist_coin_supply_changes(
+        &self,
+        changes: Vec<IndexedCoinSupplyChange>,
+    ) -> Result<(), IndexerError> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_coin_supply_changes(changes))
+            .await
+    }
+
     async fn persist_tx_indices(&self, indices: Vec<TxIndex>) -> Result<(), IndexerError> {
         if indices.is_empty() {
             return Ok(());
@@ -1105,6 +2286,14 @@ impl IndexerStoreV2 for PgIndexerStoreV2 {
         Ok(())
     }
 
+    async fn persist_tx_object_indices(&self, indices: Vec<TxIndex>) -> Result<(), IndexerError> {
+        if indices.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_tx_object_indices(indices))
+            .await
+    }
+
     async fn persist_epoch(&self, epoch: EpochToCommit) -> Result<(), IndexerError> {
         self.execute_in_blocking_worker(move |this| this.persist_epoch(epoch))
             .await
@@ -1115,6 +2304,60 @@ impl IndexerStoreV2 for PgIndexerStoreV2 {
             .await
     }
 
+    async fn persist_epoch_metrics(
+        &self,
+        metrics: Vec<IndexedEpochMetrics>,
+    ) -> Result<(), IndexerError> {
+        if metrics.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_epoch_metrics(metrics))
+            .await
+    }
+
+    async fn persist_validator_metrics(
+        &self,
+        metrics: Vec<IndexedValidatorMetrics>,
+    ) -> Result<(), IndexerError> {
+        if metrics.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_validator_metrics(metrics))
+            .await
+    }
+
+    async fn persist_storage_stats(
+        &self,
+        sampled_at_ms: u64,
+        retain_from_ms: u64,
+    ) -> Result<Vec<TableStorageStats>, IndexerError> {
+        self.execute_in_blocking_worker(move |this| {
+            this.persist_storage_stats(sampled_at_ms, retain_from_ms)
+        })
+        .await
+    }
+
+    async fn scan_orphaned_dynamic_fields(
+        &self,
+        batch_size: usize,
+        detected_at_ms: u64,
+    ) -> Result<Option<OrphanedDynamicFieldsScan>, IndexerError> {
+        self.execute_in_blocking_worker(move |this| {
+            this.scan_orphaned_dynamic_fields(batch_size, detected_at_ms)
+        })
+        .await
+    }
+
+    async fn notify_commit(&self, notice: CommitNotice) -> Result<(), IndexerError> {
+        self.execute_in_blocking_worker(move |this| this.notify_commit(notice))
+            .await
+    }
+
+    async fn persist_writer_version(&self, version: u64) -> Result<(), IndexerError> {
+        self.execute_in_blocking_worker(move |this| this.persist_writer_version(version))
+            .await
+    }
+
     async fn get_network_total_transactions_by_end_of_epoch(
         &self,
         epoch: u64,
@@ -1133,6 +2376,150 @@ impl IndexerStoreV2 for PgIndexerStoreV2 {
 /// Construct deleted objects and mutated objects to commit.
 /// In particular, filter mutated objects updates that would
 /// be override immediately.
+#[async_trait]
+impl PrunableStore for PgIndexerStoreV2 {
+    fn pruned_tables(&self) -> Vec<&'static str> {
+        PRUNED_TABLES.iter().map(|(table, ..)| *table).collect()
+    }
+
+    async fn get_checkpoint_epoch(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> anyhow::Result<Option<EpochId>> {
+        Ok(self
+            .execute_in_blocking_worker(move |this| this.get_checkpoint_epoch(checkpoint))
+            .await?)
+    }
+
+    async fn get_epoch_first_checkpoint(
+        &self,
+        epoch: EpochId,
+    ) -> anyhow::Result<Option<CheckpointSequenceNumber>> {
+        Ok(self
+            .execute_in_blocking_worker(move |this| this.get_epoch_first_checkpoint(epoch))
+            .await?)
+    }
+
+    async fn get_pruned_watermark(&self) -> anyhow::Result<CheckpointSequenceNumber> {
+        Ok(self
+            .execute_in_blocking_worker(|this| this.get_pruned_watermark())
+            .await?)
+    }
+
+    async fn set_pruned_watermark(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> anyhow::Result<()> {
+        Ok(self
+            .execute_in_blocking_worker(move |this| this.set_pruned_watermark(checkpoint))
+            .await?)
+    }
+
+    async fn drop_partitions(
+        &self,
+        table: &str,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> anyhow::Result<usize> {
+        let table = table.to_string();
+        Ok(self
+            .execute_in_blocking_worker(move |this| this.drop_partitions(&table, checkpoint))
+            .await?)
+    }
+
+    async fn prune_table(
+        &self,
+        table: &str,
+        checkpoint: CheckpointSequenceNumber,
+        batch_size: usize,
+    ) -> anyhow::Result<usize> {
+        let table = table.to_string();
+        Ok(self
+            .execute_in_blocking_worker(move |this| {
+                this.prune_table(&table, checkpoint, batch_size)
+            })
+            .await?)
+    }
+}
+
+#[async_trait]
+impl ReconcilableStore for PgIndexerStoreV2 {
+    async fn get_reconciled_watermark(&self) -> anyhow::Result<CheckpointSequenceNumber> {
+        Ok(self
+            .execute_in_blocking_worker(|this| this.get_reconciled_watermark())
+            .await?)
+    }
+
+    async fn set_reconciled_watermark(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> anyhow::Result<()> {
+        Ok(self
+            .execute_in_blocking_worker(move |this| this.set_reconciled_watermark(checkpoint))
+            .await?)
+    }
+
+    async fn get_checkpoint_digest(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> anyhow::Result<Option<CheckpointDigest>> {
+        Ok(self
+            .execute_in_blocking_worker(move |this| this.get_checkpoint_digest(checkpoint))
+            .await?)
+    }
+
+    async fn tombstone_checkpoint(
+        &self,
+        checkpoint: CheckpointSequenceNumber,
+        ingested: CheckpointDigest,
+        certified: CheckpointDigest,
+    ) -> anyhow::Result<usize> {
+        Ok(self
+            .execute_in_blocking_worker(move |this| {
+                this.tombstone_checkpoint(checkpoint, ingested, certified)
+            })
+            .await?)
+    }
+}
+
+/// Watermarks of handlers run in their own pipeline, such as backfills, kept in the
+/// `handler_watermarks` table so that they resume where they stopped after a restart.
+#[async_trait]
+impl ProgressStore for PgIndexerStoreV2 {
+    async fn load(&self, handler_name: &str) -> anyhow::Result<Option<CheckpointSequenceNumber>> {
+        let handler_name = handler_name.to_string();
+        Ok(self
+            .execute_in_blocking_worker(move |this| this.get_handler_watermark(&handler_name))
+            .await?)
+    }
+
+    async fn save(
+        &self,
+        handler_name: &str,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> anyhow::Result<()> {
+        let handler_name = handler_name.to_string();
+        Ok(self
+            .execute_in_blocking_worker(move |this| {
+                this.set_handler_watermark(&handler_name, checkpoint)
+            })
+            .await?)
+    }
+}
+
+/// The writer commits checkpoints in order, and the objects snapshot is built from the object
+/// history of the checkpoints after its own watermark, so neither may be pruned past.
+#[async_trait]
+impl WatermarkSource for PgIndexerStoreV2 {
+    async fn lowest_watermark(&self) -> anyhow::Result<Option<CheckpointSequenceNumber>> {
+        let checkpoint = IndexerStoreV2::get_latest_tx_checkpoint_sequence_number(self).await?;
+        let snapshot =
+            IndexerStoreV2::get_latest_object_snapshot_checkpoint_sequence_number(self).await?;
+        Ok(checkpoint
+            .zip(snapshot)
+            .map(|(checkpoint, snapshot)| checkpoint.min(snapshot)))
+    }
+}
+
 fn make_final_list_of_objects_to_commit(
     tx_object_changes: Vec<TransactionObjectChangesToCommit>,
 ) -> Vec<ObjectChangeToCommit> {
@@ -1199,6 +2586,36 @@ fn make_objects_history_to_commit(
         .collect()
 }
 
+/// The latest version each of `objects` was deleted or wrapped at by a checkpoint after the
+/// earliest one of `objects`, according to the object history.
+fn deleted_since(
+    conn: &mut PgConnection,
+    objects: &[StoredObject],
+) -> Result<HashMap<Vec<u8>, i64>, IndexerError> {
+    let Some(since) = objects.iter().map(|o| o.checkpoint_sequence_number).min() else {
+        return Ok(HashMap::new());
+    };
+    let deletions = objects_history::table
+        .filter(objects_history::checkpoint_sequence_number.gt(since))
+        .filter(objects_history::object_status.eq(ObjectStatus::WrappedOrDeleted as i16))
+        .filter(
+            objects_history::object_id.eq_any(
+                objects
+                    .iter()
+                    .map(|o| o.object_id.clone())
+                    .collect::<Vec<_>>(),
+            ),
+        )
+        .select((objects_history::object_id, objects_history::object_version))
+        .load::<(Vec<u8>, i64)>(conn)?;
+    let mut deleted_since = HashMap::new();
+    for (object_id, object_version) in deletions {
+        let deleted = deleted_since.entry(object_id).or_insert(object_version);
+        *deleted = (*deleted).max(object_version);
+    }
+    Ok(deleted_since)
+}
+
 #[allow(clippy::large_enum_variant)]
 enum ObjectChangeToCommit {
     MutatedObject(StoredObject),
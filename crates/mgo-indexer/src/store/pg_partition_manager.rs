@@ -25,6 +25,21 @@ WHERE parent.relkind = 'p'
 GROUP BY table_name;
 ";
 
+/// Partitions of the table `$1` that have an upper bound, with that bound: the first checkpoint
+/// after them. The partition of the current epoch, which has none, is left out.
+const GET_BOUNDED_PARTITIONS_SQL: &str = r"
+SELECT partition_name, checkpoint_hi_exclusive
+FROM (SELECT child.relname AS partition_name,
+             CAST(SUBSTRING(pg_get_expr(child.relpartbound, child.oid)
+                            FROM 'TO \(''?(\d+)''?\)') AS BIGINT) AS checkpoint_hi_exclusive
+      FROM pg_inherits
+               JOIN pg_class parent ON pg_inherits.inhparent = parent.oid
+               JOIN pg_class child ON pg_inherits.inhrelid = child.oid
+      WHERE parent.relname = $1) bounds
+WHERE checkpoint_hi_exclusive IS NOT NULL
+ORDER BY checkpoint_hi_exclusive;
+";
+
 #[derive(Clone)]
 pub struct PgPartitionManager {
     cp: PgConnectionPool,
@@ -125,4 +140,50 @@ impl PgPartitionManager {
         }
         Ok(())
     }
+
+    /// Drops the partitions of `table` that only hold checkpoints before `checkpoint`, which
+    /// takes much less than deleting their rows, and returns their names. Tables without
+    /// partitions have none to drop.
+    pub fn drop_partitions_before(
+        &self,
+        table: &str,
+        checkpoint: u64,
+    ) -> Result<Vec<String>, IndexerError> {
+        #[derive(QueryableByName, Debug, Clone)]
+        struct BoundedPartition {
+            #[diesel(sql_type = VarChar)]
+            partition_name: String,
+            #[diesel(sql_type = BigInt)]
+            checkpoint_hi_exclusive: i64,
+        }
+
+        let partitions: Vec<BoundedPartition> =
+            read_only_blocking!(&self.cp, |conn| diesel::RunQueryDsl::load(
+                diesel::sql_query(GET_BOUNDED_PARTITIONS_SQL).bind::<VarChar, _>(table),
+                conn
+            ))?;
+        let mut dropped = vec![];
+        for partition in partitions {
+            if partition.checkpoint_hi_exclusive as u64 > checkpoint {
+                break;
+            }
+            let name = partition.partition_name;
+            transactional_blocking_with_retry!(
+                &self.cp,
+                |conn| {
+                    RunQueryDsl::execute(
+                        diesel::sql_query(format!("DROP TABLE IF EXISTS {name}")),
+                        conn,
+                    )
+                },
+                Duration::from_secs(10)
+            )?;
+            info!(
+                "Dropped partition {} of table {}, holding checkpoints before {}",
+                name, table, partition.checkpoint_hi_exclusive
+            );
+            dropped.push(name);
+        }
+        Ok(dropped)
+    }
 }
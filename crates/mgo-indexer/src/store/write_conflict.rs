@@ -0,0 +1,143 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Retries of serializable transactions that conflict with a concurrent writer of the same rows.
+//! Postgres aborts one of the racing transactions with a serialization failure, which running
+//! the whole transaction again resolves, so only those are retried, with jittered backoff so
+//! that the writers do not collide again.
+
+use std::time::Duration;
+
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use tracing::warn;
+
+use crate::errors::IndexerError;
+
+const INITIAL_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+const MAX_RETRY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Whether `error` aborted a transaction that raced a concurrent writer.
+pub(crate) fn is_write_conflict(error: &IndexerError) -> bool {
+    match error {
+        IndexerError::PostgresError(DieselError::DatabaseError(
+            DatabaseErrorKind::SerializationFailure,
+            _,
+        )) => true,
+        IndexerError::ErrorWithContext(_, error) => is_write_conflict(error),
+        _ => false,
+    }
+}
+
+/// Runs `transaction` until it succeeds, fails with anything but a write conflict, or has
+/// conflicted `max_retries` times after its first run. Returns the last result.
+pub(crate) fn retry_on_write_conflict<T>(
+    max_retries: usize,
+    mut transaction: impl FnMut() -> Result<T, IndexerError>,
+) -> Result<T, IndexerError> {
+    let backoff = backoff::ExponentialBackoff {
+        initial_interval: INITIAL_RETRY_INTERVAL,
+        max_interval: MAX_RETRY_INTERVAL,
+        max_elapsed_time: None,
+        ..Default::default()
+    };
+    let mut retries = 0;
+    backoff::retry(backoff, || match transaction() {
+        Err(e) if is_write_conflict(&e) && retries < max_retries => {
+            retries += 1;
+            warn!(
+                retries,
+                "Transaction conflicted with a concurrent write: {e}"
+            );
+            Err(backoff::Error::transient(e))
+        }
+        result => result.map_err(backoff::Error::permanent),
+    })
+    .map_err(|e| match e {
+        backoff::Error::Permanent(err) | backoff::Error::Transient { err, .. } => err,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Barrier, Mutex};
+
+    use crate::errors::Context;
+
+    use super::*;
+
+    fn conflict() -> IndexerError {
+        IndexerError::PostgresError(DieselError::DatabaseError(
+            DatabaseErrorKind::SerializationFailure,
+            Box::new("could not serialize access due to concurrent update".to_string()),
+        ))
+    }
+
+    /// Row written by optimistic transactions, which fail to commit if the row changed since
+    /// they read it.
+    #[derive(Default)]
+    struct Row {
+        version: u64,
+        writers: Vec<&'static str>,
+    }
+
+    #[test]
+    fn test_concurrent_writers_retry_conflicts() {
+        let row = Mutex::new(Row::default());
+        // Both writers read the row before either commits, so that one of them conflicts.
+        let read = Barrier::new(2);
+        let mut attempts: Vec<usize> = std::thread::scope(|s| {
+            let writers: Vec<_> = ["a", "b"]
+                .into_iter()
+                .map(|writer| {
+                    let (row, read) = (&row, &read);
+                    s.spawn(move || {
+                        let mut attempts = 0;
+                        retry_on_write_conflict(3, || {
+                            attempts += 1;
+                            let version = row.lock().unwrap().version;
+                            if attempts == 1 {
+                                read.wait();
+                            }
+                            let mut row = row.lock().unwrap();
+                            if row.version != version {
+                                return Err(conflict());
+                            }
+                            row.version += 1;
+                            row.writers.push(writer);
+                            Ok(())
+                        })
+                        .unwrap();
+                        attempts
+                    })
+                })
+                .collect();
+            writers.into_iter().map(|w| w.join().unwrap()).collect()
+        });
+
+        attempts.sort();
+        assert_eq!(attempts, vec![1, 2]);
+        // Neither write is lost.
+        let mut writers = row.into_inner().unwrap().writers;
+        writers.sort();
+        assert_eq!(writers, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_conflicts_bubble_up_after_max_retries() {
+        let mut attempts = 0;
+        let result: Result<(), _> = retry_on_write_conflict(2, || {
+            attempts += 1;
+            Err(conflict()).context("Failed to write objects")
+        });
+        assert!(is_write_conflict(&result.unwrap_err()));
+        assert_eq!(attempts, 3);
+
+        let mut attempts = 0;
+        let result: Result<(), _> = retry_on_write_conflict(2, || {
+            attempts += 1;
+            Err(IndexerError::PostgresWriteError("disk full".to_string()))
+        });
+        assert!(!is_write_conflict(&result.unwrap_err()));
+        assert_eq!(attempts, 1);
+    }
+}
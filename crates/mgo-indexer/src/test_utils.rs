@@ -5,6 +5,7 @@ use anyhow::anyhow;
 use diesel::connection::SimpleConnection;
 use mango_metrics::init_metrics;
 use prometheus::Registry;
+use tokio::sync::watch;
 use tokio::task::JoinHandle;
 
 use std::env;
@@ -21,14 +22,46 @@ use crate::{new_pg_connection_pool, Indexer, IndexerConfig};
 use crate::{new_pg_connection_pool_impl, IndexerMetrics};
 
 pub enum ReaderWriterConfig {
-    Reader { reader_mode_rpc_url: String },
-    Writer { snapshot_config: SnapshotLagConfig },
+    Reader {
+        reader_mode_rpc_url: String,
+        fullnode_fallback_url: Option<String>,
+        dry_run_upstream_url: Option<String>,
+    },
+    Writer {
+        snapshot_config: SnapshotLagConfig,
+    },
 }
 
 impl ReaderWriterConfig {
     pub fn reader_mode(reader_mode_rpc_url: String) -> Self {
         Self::Reader {
             reader_mode_rpc_url,
+            fullnode_fallback_url: None,
+            dry_run_upstream_url: None,
+        }
+    }
+
+    /// A reader that serves transactions it has not indexed yet from `fullnode_fallback_url`.
+    pub fn reader_mode_with_fullnode_fallback(
+        reader_mode_rpc_url: String,
+        fullnode_fallback_url: String,
+    ) -> Self {
+        Self::Reader {
+            reader_mode_rpc_url,
+            fullnode_fallback_url: Some(fullnode_fallback_url),
+            dry_run_upstream_url: None,
+        }
+    }
+
+    /// A reader that proxies dev inspect and dry run requests to `dry_run_upstream_url`.
+    pub fn reader_mode_with_dry_run_upstream(
+        reader_mode_rpc_url: String,
+        dry_run_upstream_url: String,
+    ) -> Self {
+        Self::Reader {
+            reader_mode_rpc_url,
+            fullnode_fallback_url: None,
+            dry_run_upstream_url: Some(dry_run_upstream_url),
         }
     }
 
@@ -61,6 +94,44 @@ pub async fn start_test_indexer_v2_impl(
     use_indexer_experimental_methods: bool,
     reader_writer_config: ReaderWriterConfig,
     new_database: Option<String>,
+) -> (PgIndexerStoreV2, JoinHandle<Result<(), IndexerError>>) {
+    start_indexer(
+        db_url,
+        rpc_url,
+        use_indexer_experimental_methods,
+        reader_writer_config,
+        new_database,
+        vec![],
+    )
+    .await
+}
+
+/// Like [`start_test_indexer_v2`], indexing the objects referenced by events of
+/// `object_ref_event_types`.
+pub async fn start_test_indexer_v2_with_object_ref_event_types(
+    db_url: Option<String>,
+    rpc_url: String,
+    reader_writer_config: ReaderWriterConfig,
+    object_ref_event_types: Vec<String>,
+) -> (PgIndexerStoreV2, JoinHandle<Result<(), IndexerError>>) {
+    start_indexer(
+        db_url,
+        rpc_url,
+        true,
+        reader_writer_config,
+        None,
+        object_ref_event_types,
+    )
+    .await
+}
+
+async fn start_indexer(
+    db_url: Option<String>,
+    rpc_url: String,
+    use_indexer_experimental_methods: bool,
+    reader_writer_config: ReaderWriterConfig,
+    new_database: Option<String>,
+    object_ref_event_types: Vec<String>,
 ) -> (PgIndexerStoreV2, JoinHandle<Result<(), IndexerError>>) {
     // Reduce the connection pool size to 10 for testing
     // to prevent maxing out
@@ -89,6 +160,7 @@ pub async fn start_test_indexer_v2_impl(
         fullnode_sync_worker: true,
         rpc_server_worker: false,
         use_v2: true,
+        object_ref_event_types,
         ..Default::default()
     };
 
@@ -126,6 +198,8 @@ pub async fn start_test_indexer_v2_impl(
     let handle = match reader_writer_config {
         ReaderWriterConfig::Reader {
             reader_mode_rpc_url,
+            fullnode_fallback_url,
+            dry_run_upstream_url,
         } => {
             let reader_mode_rpc_url = reader_mode_rpc_url
                 .parse::<SocketAddr>()
@@ -134,6 +208,8 @@ pub async fn start_test_indexer_v2_impl(
             config.rpc_server_worker = true;
             config.rpc_server_url = reader_mode_rpc_url.ip().to_string();
             config.rpc_server_port = reader_mode_rpc_url.port();
+            config.fullnode_fallback_url = fullnode_fallback_url;
+            config.dry_run_upstream_url = dry_run_upstream_url;
 
             tokio::spawn(async move { IndexerV2::start_reader(&config, &registry, db_url).await })
         }
@@ -149,6 +225,7 @@ pub async fn start_test_indexer_v2_impl(
                     store_clone,
                     indexer_metrics,
                     snapshot_config,
+                    watch::channel(false).1,
                 )
                 .await
             })
@@ -290,6 +367,8 @@ impl<'a> MgoTransactionBlockResponseBuilder<'a> {
             events: self.response.events,
             balance_changes: self.response.balance_changes,
             object_changes: self.response.object_changes,
+            created_summary: self.response.created_summary,
+            signatures: self.response.signatures,
             // Use full response for any fields that aren't showable
             ..self.full_response.clone()
         }
@@ -54,9 +54,18 @@ impl TryFrom<MgoTransactionBlockResponse> for CheckpointTransactionBlockResponse
             events,
             object_changes: _,
             balance_changes: _,
+            created_summary: _,
+            signatures: _,
             timestamp_ms,
+            checkpoint_timestamp_ms: _,
+            observed_execution_timestamp_ms: _,
             confirmed_local_execution,
+            executed_at_checkpoint_hint: _,
+            written_objects: _,
+            served_from: _,
             checkpoint,
+            checkpoint_digest: _,
+            epoch: _,
             errors,
             raw_effects: _,
         } = response;
@@ -341,9 +350,24 @@ impl From<MgoTransactionBlockResponseWithOptions> for MgoTransactionBlockRespons
                 .show_balance_changes
                 .then_some(response.balance_changes)
                 .flatten(),
+            created_summary: options
+                .show_created_summary
+                .then_some(response.created_summary)
+                .flatten(),
+            signatures: options
+                .show_signatures
+                .then_some(response.signatures)
+                .flatten(),
             timestamp_ms: response.timestamp_ms,
+            checkpoint_timestamp_ms: response.checkpoint_timestamp_ms,
+            observed_execution_timestamp_ms: response.observed_execution_timestamp_ms,
             confirmed_local_execution: response.confirmed_local_execution,
+            executed_at_checkpoint_hint: response.executed_at_checkpoint_hint,
+            written_objects: response.written_objects,
+            served_from: response.served_from,
             checkpoint: response.checkpoint,
+            checkpoint_digest: response.checkpoint_digest,
+            epoch: response.epoch,
             errors: vec![],
             raw_effects: options
                 .show_raw_effects
@@ -5,7 +5,8 @@ use crate::errors::IndexerError;
 use move_core_types::language_storage::StructTag;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
-use mgo_json_rpc_types::ObjectChange;
+use std::collections::BTreeSet;
+use mgo_json_rpc_types::{CreatedObjectSummary, ObjectChange};
 use mgo_types::base_types::{ObjectDigest, SequenceNumber};
 use mgo_types::base_types::{ObjectID, MgoAddress};
 use mgo_types::crypto::AggregateAuthoritySignature;
@@ -14,10 +15,12 @@ use mgo_types::dynamic_field::DynamicFieldInfo;
 use mgo_types::effects::TransactionEffects;
 use mgo_types::event::SystemEpochInfoEvent;
 use mgo_types::messages_checkpoint::{
-    CertifiedCheckpointSummary, CheckpointCommitment, CheckpointDigest, EndOfEpochData,
+    CertifiedCheckpointSummary, CheckpointCommitment, CheckpointContents, CheckpointDigest,
+    EndOfEpochData,
 };
 use mgo_types::move_package::MovePackage;
 use mgo_types::object::{Object, Owner};
+use mgo_types::signature::GenericSignature;
 use mgo_types::mgo_serde::MgoStructTag;
 use mgo_types::mgo_system_state::mgo_system_state_summary::MgoSystemStateSummary;
 use mgo_types::transaction::SenderSignedData;
@@ -43,6 +46,8 @@ pub struct IndexedCheckpoint {
     pub successful_tx_num: usize,
     pub end_of_epoch_data: Option<EndOfEpochData>,
     pub end_of_epoch: bool,
+    pub certified_summary: CertifiedCheckpointSummary,
+    pub contents: CheckpointContents,
 }
 
 impl IndexedCheckpoint {
@@ -76,6 +81,8 @@ impl IndexedCheckpoint {
             timestamp_ms: checkpoint.timestamp_ms,
             validator_signature: auth_sig.clone(),
             checkpoint_commitments: checkpoint.checkpoint_commitments.clone(),
+            certified_summary: checkpoint.clone(),
+            contents: contents.clone(),
         }
     }
 }
@@ -175,6 +182,8 @@ pub struct IndexedEvent {
     pub event_type: String,
     pub bcs: Vec<u8>,
     pub timestamp_ms: u64,
+    /// Objects the event references, if its type is one whose object references are indexed.
+    pub object_refs: Vec<ObjectID>,
 }
 
 impl IndexedEvent {
@@ -197,6 +206,7 @@ impl IndexedEvent {
             event_type: event.type_.to_canonical_string(/* with_prefix */ true),
             bcs: event.contents.clone(),
             timestamp_ms,
+            object_refs: vec![],
         }
     }
 }
@@ -316,6 +326,85 @@ pub struct IndexedDeletedObject {
     pub checkpoint_sequence_number: u64,
 }
 
+/// A coin type seen in a checkpoint, with the ID of its `CoinMetadata` if written in it.
+#[derive(Clone, Debug)]
+pub struct IndexedCoinType {
+    pub coin_type: String,
+    pub checkpoint_sequence_number: u64,
+    pub metadata_object_id: Option<ObjectID>,
+}
+
+/// Net change over a checkpoint in the number of coins of `coin_type` with a positive balance
+/// that `owner` owns.
+#[derive(Clone, Debug)]
+pub struct IndexedCoinHolderChange {
+    pub owner: MgoAddress,
+    pub coin_type: String,
+    pub checkpoint_sequence_number: u64,
+    pub coin_count_delta: i64,
+}
+
+/// Amounts of `coin_type` minted and burned over a checkpoint through its treasury cap.
+#[derive(Clone, Debug)]
+pub struct IndexedCoinSupplyChange {
+    pub coin_type: String,
+    pub checkpoint_sequence_number: u64,
+    pub epoch: u64,
+    pub timestamp_ms: u64,
+    pub minted: u64,
+    pub burned: u64,
+    /// Supply before the checkpoint, as held by the treasury cap.
+    pub supply_before: u64,
+    /// Whether the treasury cap was created in the checkpoint.
+    pub cap_created: bool,
+}
+
+/// What a checkpoint adds to the metrics of its epoch.
+#[derive(Clone, Debug)]
+pub struct IndexedEpochMetrics {
+    pub epoch: u64,
+    pub checkpoint_sequence_number: u64,
+    /// Whether the checkpoint is the last of its epoch.
+    pub end_of_epoch: bool,
+    pub transactions: u64,
+    pub gas_used: u64,
+    pub storage_rebate: u64,
+    /// Senders of the user transactions of the checkpoint.
+    pub senders: BTreeSet<MgoAddress>,
+    /// Senders and recipients of the transactions of the checkpoint.
+    pub addresses: BTreeSet<MgoAddress>,
+}
+
+/// What a checkpoint adds to the metrics of the validators of its epoch.
+#[derive(Clone, Debug)]
+pub struct IndexedValidatorMetrics {
+    pub epoch: u64,
+    pub checkpoint_sequence_number: u64,
+    /// Committee indices of the validators that signed the certificate of the checkpoint.
+    pub signers: Vec<u32>,
+    /// The committee of the next epoch, in committee order, on the checkpoints that set it:
+    /// the last checkpoint of every epoch, and the genesis checkpoint for the first epoch.
+    pub committee: Option<IndexedCommittee>,
+    /// How the validators of the epoch were reported, on the last checkpoint of the epoch.
+    pub reports: Vec<IndexedValidatorReport>,
+}
+
+#[derive(Clone, Debug)]
+pub struct IndexedCommittee {
+    pub epoch: u64,
+    /// Address and voting power of every validator, at its committee index.
+    pub validators: Vec<(MgoAddress, u64)>,
+}
+
+#[derive(Clone, Debug)]
+pub struct IndexedValidatorReport {
+    pub validator_address: MgoAddress,
+    /// Number of validators that reported the validator during the epoch.
+    pub reporters: u64,
+    /// Score of the validator under the tallying rule, 0 if it was slashed and 1 otherwise.
+    pub tallying_score: u64,
+}
+
 #[derive(Debug)]
 pub struct IndexedPackage {
     pub package_id: ObjectID,
@@ -342,6 +431,7 @@ pub struct IndexedTransaction {
     pub events: Vec<mgo_types::event::Event>,
     pub transaction_kind: TransactionKind,
     pub successful_tx_num: u64,
+    pub created_objects: Vec<CreatedObjectSummary>,
 }
 
 #[derive(Debug, Clone)]
@@ -355,6 +445,10 @@ pub struct TxIndex {
     pub senders: Vec<MgoAddress>,
     pub recipients: Vec<MgoAddress>,
     pub move_calls: Vec<(ObjectID, String, String)>,
+    /// Dynamic field children loaded by the transaction, at the versions it loaded.
+    pub loaded_child_objects: Vec<(ObjectID, SequenceNumber)>,
+    /// Signatures of the transaction, stored with multisig decomposed into its participants.
+    pub signatures: Vec<GenericSignature>,
 }
 
 // ObjectChange is not bcs deserializable, IndexedObjectChange is.
@@ -588,3 +682,23 @@ impl From<IndexedObjectChange> for ObjectChange {
         }
     }
 }
+
+impl IndexedObjectChange {
+    /// Summary of the object if this change created it. Published packages have no object type
+    /// and are not summarized.
+    pub fn created_summary(&self) -> Option<CreatedObjectSummary> {
+        match self {
+            Self::Created {
+                owner,
+                object_type,
+                object_id,
+                ..
+            } => Some(CreatedObjectSummary {
+                object_id: *object_id,
+                object_type: object_type.clone(),
+                owner: *owner,
+            }),
+            _ => None,
+        }
+    }
+}
@@ -3,8 +3,9 @@
 
 use crate::PgPoolConnection;
 use anyhow::anyhow;
-use diesel::migration::MigrationSource;
-use diesel::{PgConnection, RunQueryDsl};
+use diesel::migration::{MigrationName, MigrationSource};
+use diesel::pg::Pg;
+use diesel::{OptionalExtension, PgConnection, RunQueryDsl};
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use tracing::info;
 
@@ -64,3 +65,26 @@ pub fn drop_all_tables(conn: &mut PgConnection) -> Result<(), diesel::result::Er
     info!("Dropped all tables in the database");
     Ok(())
 }
+
+/// Version of the latest migration embedded in this binary, i.e. the schema version the
+/// indexer code expects to find in the database.
+pub fn expected_schema_version(use_v2: bool) -> Result<String, anyhow::Error> {
+    let migration = if use_v2 { MIGRATIONS_V2 } else { MIGRATIONS };
+    MigrationSource::<Pg>::migrations(&migration)
+        .map_err(|e| anyhow!("Failed to load embedded migrations {e}"))?
+        .iter()
+        .map(|m| m.name().version().to_string())
+        .max()
+        .ok_or_else(|| anyhow!("No embedded migrations found"))
+}
+
+/// Version of the latest migration applied to the database, if any.
+pub fn applied_schema_version(
+    conn: &mut PgConnection,
+) -> Result<Option<String>, diesel::result::Error> {
+    diesel::dsl::sql::<diesel::sql_types::Text>(
+        "SELECT version FROM __diesel_schema_migrations ORDER BY version DESC LIMIT 1",
+    )
+    .get_result(conn)
+    .optional()
+}
@@ -0,0 +1,119 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Version of the data the writer indexes. The writer records its version when it starts, and
+//! readers check it before serving the endpoints backed by tables or columns that only later
+//! versions fill, rather than failing on them with SQL errors.
+
+use std::fmt;
+
+use tracing::{info, warn};
+
+/// Version of the data written by this writer. Bumped whenever the writer starts filling a
+/// table or column that an endpoint reads, with a [`WriterFeature`] for that endpoint. Writers
+/// that predate the version are at version 0.
+pub const WRITER_VERSION: u64 = 2;
+
+/// Features of the reader backed by data that writers only fill from some version on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WriterFeature {
+    /// Metrics of the validators of every epoch, behind the validator reports and rankings.
+    ValidatorMetrics,
+    /// Signatures of transactions, behind the signature scheme filter of transaction queries.
+    TransactionSignatures,
+    /// Metrics of every epoch, rolled up from its checkpoints.
+    EpochMetrics,
+    /// Mints and burns of every coin type, behind its supply history.
+    CoinSupplyChanges,
+    /// Objects referenced by events, behind the queries of events by object.
+    EventObjectRefs,
+    /// Packages every package version links to, behind its dependencies, dependents and
+    /// versions.
+    PackageDependencies,
+    /// Sizes of the tables of the indexer, sampled by the writer.
+    StorageStats,
+    /// Dynamic object fields whose value object is no longer live.
+    OrphanedDynamicFields,
+    /// Dynamic field children loaded by transactions.
+    LoadedChildObjects,
+    /// Coin types seen, with their holder counts.
+    CoinTypes,
+    /// Sketches of the addresses active every day, behind the active address stats and the
+    /// retention of cohorts.
+    ActiveAddressSketches,
+    /// Watermark of the data pruned by the writer.
+    PrunerWatermark,
+    /// BCS encoding of the summary and contents of checkpoints.
+    CheckpointBcs,
+}
+
+impl WriterFeature {
+    pub const ALL: [WriterFeature; 13] = [
+        WriterFeature::ValidatorMetrics,
+        WriterFeature::TransactionSignatures,
+        WriterFeature::EpochMetrics,
+        WriterFeature::CoinSupplyChanges,
+        WriterFeature::EventObjectRefs,
+        WriterFeature::PackageDependencies,
+        WriterFeature::StorageStats,
+        WriterFeature::OrphanedDynamicFields,
+        WriterFeature::LoadedChildObjects,
+        WriterFeature::CoinTypes,
+        WriterFeature::ActiveAddressSketches,
+        WriterFeature::PrunerWatermark,
+        WriterFeature::CheckpointBcs,
+    ];
+
+    /// Earliest writer version filling the data of the feature.
+    pub fn min_writer_version(&self) -> u64 {
+        match self {
+            WriterFeature::ValidatorMetrics
+            | WriterFeature::TransactionSignatures
+            | WriterFeature::EpochMetrics
+            | WriterFeature::CoinSupplyChanges
+            | WriterFeature::EventObjectRefs
+            | WriterFeature::PackageDependencies
+            | WriterFeature::StorageStats
+            | WriterFeature::OrphanedDynamicFields
+            | WriterFeature::LoadedChildObjects
+            | WriterFeature::CoinTypes
+            | WriterFeature::ActiveAddressSketches
+            | WriterFeature::PrunerWatermark => 1,
+            WriterFeature::CheckpointBcs => 2,
+        }
+    }
+}
+
+impl fmt::Display for WriterFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WriterFeature::ValidatorMetrics => write!(f, "Validator metrics"),
+            WriterFeature::TransactionSignatures => write!(f, "Transaction signatures"),
+            WriterFeature::EpochMetrics => write!(f, "Epoch metrics"),
+            WriterFeature::CoinSupplyChanges => write!(f, "Coin supply changes"),
+            WriterFeature::EventObjectRefs => write!(f, "Event object references"),
+            WriterFeature::PackageDependencies => write!(f, "Package dependencies"),
+            WriterFeature::StorageStats => write!(f, "Storage stats"),
+            WriterFeature::OrphanedDynamicFields => write!(f, "Orphaned dynamic fields"),
+            WriterFeature::LoadedChildObjects => write!(f, "Loaded child objects"),
+            WriterFeature::CoinTypes => write!(f, "Coin types"),
+            WriterFeature::ActiveAddressSketches => write!(f, "Active address sketches"),
+            WriterFeature::PrunerWatermark => write!(f, "Pruner watermark"),
+            WriterFeature::CheckpointBcs => write!(f, "BCS-encoded checkpoints"),
+        }
+    }
+}
+
+/// Logs the version of the writer found by a reader when it starts, warning about the features
+/// the reader cannot serve until the writer is upgraded.
+pub fn check_writer_version(found: u64) {
+    info!("Indexer writer version {found}, reader version {WRITER_VERSION}");
+    for feature in WriterFeature::ALL {
+        let required = feature.min_writer_version();
+        if found < required {
+            warn!(
+                "{feature} feature not available until the writer is upgraded: requires writer >= {required}, found {found}"
+            );
+        }
+    }
+}
@@ -0,0 +1,22 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Coin minted at publish to two holders, counted by the indexer in tests.
+module coin_types::gold {
+    use std::option;
+    use mgo::coin;
+    use mgo::transfer;
+    use mgo::tx_context::{Self, TxContext};
+
+    struct GOLD has drop {}
+
+    fun init(witness: GOLD, ctx: &mut TxContext) {
+        let (treasury, metadata) = coin::create_currency(
+            witness, 9, b"GOLD", b"", b"", option::none(), ctx
+        );
+        transfer::public_freeze_object(metadata);
+        coin::mint_and_transfer(&mut treasury, 100, @0xA, ctx);
+        coin::mint_and_transfer(&mut treasury, 200, @0xB, ctx);
+        transfer::public_transfer(treasury, tx_context::sender(ctx))
+    }
+}
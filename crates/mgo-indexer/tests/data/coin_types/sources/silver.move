@@ -0,0 +1,21 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Coin minted at publish to a single holder, counted by the indexer in tests.
+module coin_types::silver {
+    use std::option;
+    use mgo::coin;
+    use mgo::transfer;
+    use mgo::tx_context::{Self, TxContext};
+
+    struct SILVER has drop {}
+
+    fun init(witness: SILVER, ctx: &mut TxContext) {
+        let (treasury, metadata) = coin::create_currency(
+            witness, 9, b"SILVER", b"", b"", option::none(), ctx
+        );
+        transfer::public_freeze_object(metadata);
+        coin::mint_and_transfer(&mut treasury, 300, @0xA, ctx);
+        transfer::public_transfer(treasury, tx_context::sender(ctx))
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Emits events of several types referencing the same shared item, whose events are looked up
+/// by object in indexer tests.
+module object_refs::market {
+    use mgo::event;
+    use mgo::object::{Self, ID, UID};
+    use mgo::transfer;
+    use mgo::tx_context::{Self, TxContext};
+
+    struct Item has key {
+        id: UID,
+    }
+
+    struct Listed has copy, drop {
+        item: ID,
+        price: u64,
+    }
+
+    struct Sold has copy, drop {
+        items: vector<ID>,
+        buyer: address,
+    }
+
+    /// References the item too, but is not among the types whose references are indexed.
+    struct Viewed has copy, drop {
+        item: ID,
+    }
+
+    fun init(ctx: &mut TxContext) {
+        let item = Item { id: object::new(ctx) };
+        let item_id = object::id(&item);
+        event::emit(Listed { item: item_id, price: 100 });
+        event::emit(Viewed { item: item_id });
+        event::emit(Sold { items: vector[item_id], buyer: tx_context::sender(ctx) });
+        transfer::share_object(item)
+    }
+}
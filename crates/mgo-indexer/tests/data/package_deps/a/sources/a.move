@@ -0,0 +1,9 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Package depended on by `package_deps_b`, in indexer tests of package dependencies.
+module package_deps_a::a {
+    public fun value(): u64 {
+        42
+    }
+}
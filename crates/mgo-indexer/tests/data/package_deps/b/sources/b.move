@@ -0,0 +1,11 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Package depending on `package_deps_a`, in indexer tests of package dependencies.
+module package_deps_b::b {
+    use package_deps_a::a;
+
+    public fun value(): u64 {
+        a::value() + 1
+    }
+}
@@ -0,0 +1,29 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Shared registry holding a table of struct values, read back by key in indexer tests.
+module table_values::registry {
+    use std::string::{Self, String};
+    use mgo::object::{Self, UID};
+    use mgo::table::{Self, Table};
+    use mgo::transfer;
+    use mgo::tx_context::TxContext;
+
+    struct Entry has store {
+        score: u64,
+        label: String,
+    }
+
+    struct Registry has key {
+        id: UID,
+        entries: Table<u64, Entry>,
+    }
+
+    fun init(ctx: &mut TxContext) {
+        let entries = table::new(ctx);
+        table::add(&mut entries, 1, Entry { score: 10, label: string::utf8(b"one") });
+        table::add(&mut entries, 2, Entry { score: 20, label: string::utf8(b"two") });
+        table::add(&mut entries, 3, Entry { score: 30, label: string::utf8(b"three") });
+        transfer::share_object(Registry { id: object::new(ctx), entries })
+    }
+}
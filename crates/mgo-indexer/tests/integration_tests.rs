@@ -60,6 +60,7 @@ pub mod pg_integration_test {
                 )),
                 None,
                 None,
+                None,
             )
             .await?
             .data
@@ -147,6 +148,7 @@ pub mod pg_integration_test {
                 )),
                 None,
                 None,
+                None,
             )
             .await?
             .data
@@ -285,7 +287,7 @@ pub mod pg_integration_test {
         let checkpoint_seq_query =
             MgoTransactionBlockResponseQuery::new_with_filter(TransactionFilter::Checkpoint(2u64));
         let mut checkpoint_query_tx_digest_vec = indexer_rpc_client
-            .query_transaction_blocks(checkpoint_seq_query, None, None, None)
+            .query_transaction_blocks(checkpoint_seq_query, None, None, None, None)
             .await
             .unwrap()
             .data
@@ -293,9 +295,11 @@ pub mod pg_integration_test {
             .map(|tx| tx.digest)
             .collect::<Vec<_>>();
         let mut checkpoint_tx_digest_vec = indexer_rpc_client
-            .get_checkpoint(CheckpointId::SequenceNumber(2u64))
+            .get_checkpoint(CheckpointId::SequenceNumber(2u64), None)
             .await
             .unwrap()
+            .into_json()
+            .unwrap()
             .transactions;
         checkpoint_query_tx_digest_vec.sort();
         checkpoint_tx_digest_vec.sort();
@@ -323,7 +327,7 @@ pub mod pg_integration_test {
             TransactionFilter::FromAddress(sender),
         );
         let tx_from_query_response = indexer_rpc_client
-            .query_transaction_blocks(from_query, None, None, None)
+            .query_transaction_blocks(from_query, None, None, None, None)
             .await?;
         assert!(!tx_from_query_response.has_next_page);
         assert_eq!(tx_from_query_response.data.len(), 3);
@@ -332,7 +336,7 @@ pub mod pg_integration_test {
             TransactionFilter::TransactionKind("ProgrammableTransaction".to_string()),
         );
         let tx_kind_query_response = indexer_rpc_client
-            .query_transaction_blocks(tx_kind_query, None, None, None)
+            .query_transaction_blocks(tx_kind_query, None, None, None, None)
             .await?;
         assert!(!tx_kind_query_response.has_next_page);
         assert_eq!(tx_kind_query_response.data.len(), 3);
@@ -342,7 +346,7 @@ pub mod pg_integration_test {
             TransactionFilter::ToAddress(recipient),
         );
         let tx_to_query_response = indexer_rpc_client
-            .query_transaction_blocks(to_query, None, None, None)
+            .query_transaction_blocks(to_query, None, None, None, None)
             .await?;
         // the address has received 2 transactions, one is genesis
         assert!(!tx_to_query_response.has_next_page);
@@ -356,7 +360,7 @@ pub mod pg_integration_test {
             },
         );
         let tx_from_to_query_response = indexer_rpc_client
-            .query_transaction_blocks(from_to_query, None, None, None)
+            .query_transaction_blocks(from_to_query, None, None, None, None)
             .await?;
         assert!(!tx_from_to_query_response.has_next_page);
         assert_eq!(tx_from_to_query_response.data.len(), 1);
@@ -370,7 +374,7 @@ pub mod pg_integration_test {
             TransactionFilter::ChangedObject(*gas_objects.first().unwrap()),
         );
         let tx_mutation_query_response = indexer_rpc_client
-            .query_transaction_blocks(mutation_query, None, None, None)
+            .query_transaction_blocks(mutation_query, None, None, None, None)
             .await?;
         // the coin is first created by genesis tx, then transferred by the above tx
         assert!(!tx_mutation_query_response.has_next_page);
@@ -381,7 +385,7 @@ pub mod pg_integration_test {
             TransactionFilter::InputObject(*gas_objects.first().unwrap()),
         );
         let tx_input_query_response = indexer_rpc_client
-            .query_transaction_blocks(input_query, None, None, None)
+            .query_transaction_blocks(input_query, None, None, None, None)
             .await?;
         assert_eq!(tx_input_query_response.data.len(), 2);
 
@@ -393,7 +397,7 @@ pub mod pg_integration_test {
                 function: None,
             });
         let tx_move_call_query_response = indexer_rpc_client
-            .query_transaction_blocks(move_call_query, None, None, None)
+            .query_transaction_blocks(move_call_query, None, None, None, None)
             .await?;
         assert_eq!(tx_move_call_query_response.data.len(), 1);
         assert_eq!(
@@ -421,8 +425,10 @@ pub mod pg_integration_test {
             .multi_get_transaction_blocks(
                 vec![tx_response.digest, nft_digest],
                 Some(MgoTransactionBlockResponseOptions::full_content()),
+                None,
             )
-            .await?;
+            .await?
+            .into_json()?;
         assert_eq!(tx_multi_read_tx_response_1.len(), 2);
         assert_eq!(tx_multi_read_tx_response_1[0].digest, tx_response.digest);
         assert_eq!(tx_multi_read_tx_response_1[1].digest, nft_digest);
@@ -431,8 +437,10 @@ pub mod pg_integration_test {
             .multi_get_transaction_blocks(
                 vec![nft_digest, tx_response.digest],
                 Some(MgoTransactionBlockResponseOptions::full_content()),
+                None,
             )
-            .await?;
+            .await?
+            .into_json()?;
         assert_eq!(tx_multi_read_tx_response_2.len(), 2);
         assert_eq!(tx_multi_read_tx_response_2[0].digest, nft_digest);
         assert_eq!(tx_multi_read_tx_response_2[1].digest, tx_response.digest);
@@ -458,7 +466,7 @@ pub mod pg_integration_test {
         // Test various ways of querying events
         let filter_on_sender = EventFilter::Sender(sender);
         let query_response = indexer_rpc_client
-            .query_events(filter_on_sender, None, None, None)
+            .query_events(filter_on_sender, None, None, None, None)
             .await?;
         let target_struct_tag =
             StructTag::from_str(&format!("{package_id}::devnet_nft::MintNFTEvent")).unwrap();
@@ -473,7 +481,7 @@ pub mod pg_integration_test {
 
         let filter_on_transaction = EventFilter::Transaction(digest_one);
         let query_response = indexer_rpc_client
-            .query_events(filter_on_transaction, None, None, None)
+            .query_events(filter_on_transaction, None, None, None, None)
             .await?;
         assert_eq!(query_response.data.len(), 1);
         assert_eq!(
@@ -486,7 +494,7 @@ pub mod pg_integration_test {
             module: Identifier::new("devnet_nft").unwrap(),
         };
         let query_response = indexer_rpc_client
-            .query_events(filter_on_module, None, None, None)
+            .query_events(filter_on_module, None, None, None, None)
             .await?;
         assert_eq!(query_response.data.len(), 2);
         assert_eq!(digest_one, query_response.data[0].id.tx_digest);
@@ -494,7 +502,7 @@ pub mod pg_integration_test {
 
         let filter_on_event_type = EventFilter::MoveEventType(target_struct_tag.clone());
         let query_response = indexer_rpc_client
-            .query_events(filter_on_event_type.clone(), None, None, None)
+            .query_events(filter_on_event_type.clone(), None, None, None, None)
             .await?;
         assert_eq!(query_response.data.len(), 2);
         assert_eq!(digest_one, query_response.data[0].id.tx_digest);
@@ -503,7 +511,7 @@ pub mod pg_integration_test {
         // check parsed event data with FN
         let fn_query_response = test_cluster
             .rpc_client()
-            .query_events(filter_on_event_type, None, None, None)
+            .query_events(filter_on_event_type, None, None, None, None)
             .await?;
 
         assert_eq!(fn_query_response.data.len(), 2);
@@ -550,14 +558,14 @@ pub mod pg_integration_test {
             module: Identifier::new("devnet_nft").unwrap(),
         };
         let query_response = indexer_rpc_client
-            .query_events(filter_on_module, None, None, None)
+            .query_events(filter_on_module, None, None, None, None)
             .await?;
         assert_eq!(query_response.data.len(), 5);
 
         let mint_nft_event = &format!("{package_id}::devnet_nft::MintNFTEvent");
         let filter = get_filter_on_event_type(mint_nft_event);
         let query_response = indexer_rpc_client
-            .query_events(filter, None, Some(2), None)
+            .query_events(filter, None, Some(2), None, None)
             .await?;
         assert!(query_response.has_next_page);
         assert_eq!(query_response.data.len(), 2);
@@ -565,7 +573,7 @@ pub mod pg_integration_test {
         let filter = get_filter_on_event_type(mint_nft_event);
         let cursor = query_response.next_cursor;
         let query_response = indexer_rpc_client
-            .query_events(filter, cursor, Some(4), None)
+            .query_events(filter, cursor, Some(4), None, None)
             .await?;
         assert!(!query_response.has_next_page);
         assert_eq!(query_response.data.len(), 3);
@@ -574,7 +582,7 @@ pub mod pg_integration_test {
         let burn_nft_event = &format!("{package_id}::devnet_nft::BurnNFTEvent");
         let filter = get_filter_on_event_type(burn_nft_event);
         let query_response = indexer_rpc_client
-            .query_events(filter, None, Some(4), None)
+            .query_events(filter, None, Some(4), None, None)
             .await?;
         assert!(!query_response.has_next_page);
         assert_eq!(query_response.data.len(), 0);
@@ -596,6 +604,9 @@ pub mod pg_integration_test {
             show_content: true,
             show_bcs: true,
             show_storage_rebate: true,
+            field_mask: None,
+            resolve_type_to_latest: false,
+            show_previous_transaction_details: false,
         };
         let resp = indexer_rpc_client
             .get_object(source_object_id, Some(show_all_content.clone()))
@@ -883,8 +894,13 @@ pub mod pg_integration_test {
         // cross check with FN
         let fn_cp = test_cluster
             .rpc_client()
-            .get_checkpoint(CheckpointId::SequenceNumber(prev_epoch_last_checkpoint_id))
+            .get_checkpoint(
+                CheckpointId::SequenceNumber(prev_epoch_last_checkpoint_id),
+                None,
+            )
             .await
+            .unwrap()
+            .into_json()
             .unwrap();
 
         assert_eq!(fn_cp, checkpoint);
@@ -903,7 +919,7 @@ pub mod pg_integration_test {
         let fullnode_client = test_cluster.rpc_client();
 
         let object_from_fullnode = fullnode_client
-            .get_owned_objects(address, None, None, None)
+            .get_owned_objects(address, None, None, None, None)
             .await
             .unwrap();
 
@@ -1165,8 +1181,10 @@ pub mod pg_integration_test {
         }
         let cp = cp_res.unwrap() as u64;
         let first_checkpoint = indexer_rpc_client
-            .get_checkpoint(CheckpointId::SequenceNumber(cp))
+            .get_checkpoint(CheckpointId::SequenceNumber(cp), None)
             .await
+            .unwrap()
+            .into_json()
             .unwrap();
 
         let current_epoch = store.get_current_epoch().await.unwrap();
@@ -1180,8 +1198,10 @@ pub mod pg_integration_test {
         // Check if checkpoint validator sig matches
         let fullnode_checkpoint = test_cluster
             .rpc_client()
-            .get_checkpoint(cp.into())
+            .get_checkpoint(cp.into(), None)
             .await
+            .unwrap()
+            .into_json()
             .unwrap();
 
         assert_eq!(
@@ -1207,8 +1227,9 @@ pub mod pg_integration_test {
             .await?;
         let next_cp = tx_response.checkpoint.unwrap();
         let next_checkpoint = indexer_rpc_client
-            .get_checkpoint(CheckpointId::SequenceNumber(next_cp))
-            .await?;
+            .get_checkpoint(CheckpointId::SequenceNumber(next_cp), None)
+            .await?
+            .into_json()?;
         let current_epoch = store.get_current_epoch().await.unwrap();
 
         assert_eq!(next_checkpoint.epoch, current_epoch.epoch);
@@ -1222,8 +1243,9 @@ pub mod pg_integration_test {
         let mut curr_checkpoint = next_checkpoint;
         for i in (first_checkpoint.sequence_number..curr_checkpoint.sequence_number).rev() {
             let prev_checkpoint = indexer_rpc_client
-                .get_checkpoint(CheckpointId::SequenceNumber(i))
-                .await?;
+                .get_checkpoint(CheckpointId::SequenceNumber(i), None)
+                .await?
+                .into_json()?;
             assert_eq!(
                 curr_checkpoint.previous_digest,
                 Some(prev_checkpoint.digest)
@@ -0,0 +1,326 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runs the checkpoint pipeline the way a crate embedding it would, through
+//! `framework::pipeline` only.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use mgo_indexer::framework::pipeline::{
+    fetcher_factory, CheckpointData, CheckpointSource, Handler, InMemoryProgressStore, Pipeline,
+    ProgressStore,
+};
+use mgo_indexer::metrics::CheckpointFetcherMetrics;
+use mgo_rest_api::CheckpointData as FullCheckpointData;
+use mgo_types::base_types::ObjectID;
+use mgo_types::gas_coin::GAS;
+use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
+use mgo_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use mgo_types::storage::ReadStore;
+use mgo_types::transaction::{Argument, Transaction, TransactionData, TransactionDataAPI};
+use mgo_types::MGO_FRAMEWORK_PACKAGE_ID;
+use move_core_types::ident_str;
+use prometheus::Registry;
+use simulacrum::Simulacrum;
+
+const LATEST_CHECKPOINT: CheckpointSequenceNumber = 5;
+
+#[derive(Clone)]
+struct SimulacrumSource {
+    checkpoints: Arc<BTreeMap<CheckpointSequenceNumber, FullCheckpointData>>,
+}
+
+impl SimulacrumSource {
+    fn new() -> Self {
+        let mut sim = Simulacrum::new();
+        for _ in 0..LATEST_CHECKPOINT {
+            sim.create_checkpoint();
+        }
+        Self::from_simulacrum(&sim)
+    }
+
+    /// Serves the checkpoints `sim` created, which must be [`LATEST_CHECKPOINT`] past genesis.
+    fn from_simulacrum(sim: &Simulacrum) -> Self {
+        assert_eq!(
+            *sim.get_latest_checkpoint().unwrap().sequence_number(),
+            LATEST_CHECKPOINT
+        );
+        let checkpoints = (0..=LATEST_CHECKPOINT)
+            .map(|sequence_number| {
+                let checkpoint = sim
+                    .get_checkpoint_by_sequence_number(sequence_number)
+                    .unwrap()
+                    .unwrap();
+                let contents = sim
+                    .get_checkpoint_contents_by_digest(&checkpoint.content_digest)
+                    .unwrap()
+                    .unwrap();
+                let data = sim.get_checkpoint_data(checkpoint, contents).unwrap();
+                (sequence_number, data)
+            })
+            .collect();
+        Self {
+            checkpoints: Arc::new(checkpoints),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CheckpointSource for SimulacrumSource {
+    async fn get_latest_checkpoint_sequence_number(&self) -> Result<CheckpointSequenceNumber> {
+        Ok(LATEST_CHECKPOINT)
+    }
+
+    async fn get_full_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<FullCheckpointData> {
+        self.checkpoints
+            .get(&sequence_number)
+            .cloned()
+            .ok_or_else(|| anyhow!("missing checkpoint {sequence_number}"))
+    }
+}
+
+/// Records the checkpoints it processes, never finishing `stall_at` if set.
+struct CountingHandler {
+    processed: Arc<Mutex<Vec<CheckpointSequenceNumber>>>,
+    stall_at: Option<CheckpointSequenceNumber>,
+}
+
+#[async_trait::async_trait]
+impl Handler for CountingHandler {
+    fn name(&self) -> &str {
+        "counting"
+    }
+
+    async fn process_checkpoint(&mut self, checkpoint: &CheckpointData) -> Result<()> {
+        let sequence_number = checkpoint.sequence_number();
+        if self.stall_at == Some(sequence_number) {
+            std::future::pending::<()>().await;
+        }
+        self.processed.lock().unwrap().push(sequence_number);
+        Ok(())
+    }
+}
+
+/// Counts the Move calls of every transaction per package, reading checkpoints through the
+/// public view only.
+struct MoveCallCounter {
+    calls: Arc<Mutex<BTreeMap<ObjectID, usize>>>,
+}
+
+#[async_trait::async_trait]
+impl Handler for MoveCallCounter {
+    fn name(&self) -> &str {
+        "move_calls"
+    }
+
+    async fn process_checkpoint(&mut self, checkpoint: &CheckpointData) -> Result<()> {
+        let mut calls = self.calls.lock().unwrap();
+        for tx in checkpoint.transactions() {
+            for (package, _, _) in tx.transaction().transaction_data().move_calls() {
+                *calls.entry(*package).or_default() += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A transaction splitting `splits` coins off the gas coin of the first account of `sim`, one
+/// `pay::split` call each.
+fn split_gas_transaction(sim: &Simulacrum, splits: usize) -> Transaction {
+    let (sender, key) = sim.keystore().accounts().next().unwrap();
+    let gas = sim
+        .store()
+        .owned_objects(*sender)
+        .find(|object| object.is_gas_coin())
+        .unwrap();
+    let mut builder = ProgrammableTransactionBuilder::new();
+    for _ in 0..splits {
+        let amount = builder.pure(1u64).unwrap();
+        builder.programmable_move_call(
+            MGO_FRAMEWORK_PACKAGE_ID,
+            ident_str!("pay").to_owned(),
+            ident_str!("split").to_owned(),
+            vec![GAS::type_tag()],
+            vec![Argument::GasCoin, amount],
+        );
+    }
+    let data = TransactionData::new_programmable(
+        *sender,
+        vec![gas.compute_object_reference()],
+        builder.finish(),
+        1_000_000_000,
+        sim.reference_gas_price(),
+    );
+    Transaction::from_data_and_signer(data, vec![key])
+}
+
+/// Shares an [`InMemoryProgressStore`] between the pipeline and the test, failing the first
+/// `failing_saves` saves.
+struct SharedProgressStore {
+    inner: Arc<InMemoryProgressStore>,
+    failing_saves: Mutex<usize>,
+}
+
+impl SharedProgressStore {
+    fn new(inner: Arc<InMemoryProgressStore>) -> Self {
+        Self {
+            inner,
+            failing_saves: Mutex::new(0),
+        }
+    }
+
+    fn failing_saves(self, failing_saves: usize) -> Self {
+        *self.failing_saves.lock().unwrap() = failing_saves;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl ProgressStore for SharedProgressStore {
+    async fn load(&self, handler_name: &str) -> Result<Option<CheckpointSequenceNumber>> {
+        self.inner.load(handler_name).await
+    }
+
+    async fn save(&self, handler_name: &str, checkpoint: CheckpointSequenceNumber) -> Result<()> {
+        {
+            let mut failing_saves = self.failing_saves.lock().unwrap();
+            if *failing_saves > 0 {
+                *failing_saves -= 1;
+                return Err(anyhow!("failed to save watermark {checkpoint}"));
+            }
+        }
+        self.inner.save(handler_name, checkpoint).await
+    }
+}
+
+async fn wait_for_watermark(
+    progress_store: &InMemoryProgressStore,
+    name: &str,
+    checkpoint: CheckpointSequenceNumber,
+) {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while progress_store.load(name).await.unwrap() != Some(checkpoint) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("handler did not reach checkpoint {checkpoint}"));
+}
+
+#[tokio::test]
+async fn test_pipeline_runs_handler_registered_outside_the_crate() {
+    let registry = Registry::default();
+    mango_metrics::init_metrics(&registry);
+
+    let processed = Arc::new(Mutex::new(Vec::new()));
+    let progress_store = Arc::new(InMemoryProgressStore::default());
+    let pipeline = Pipeline::new()
+        .register_handler(CountingHandler {
+            processed: processed.clone(),
+            stall_at: None,
+        })
+        .with_progress_store(SharedProgressStore::new(progress_store.clone()))
+        .last_downloaded_checkpoint(Some(0))
+        .batch_size(2);
+    let new_fetcher = fetcher_factory(
+        SimulacrumSource::new(),
+        CheckpointFetcherMetrics::new(&registry),
+    );
+    let runner = tokio::spawn(pipeline.run(new_fetcher));
+    wait_for_watermark(&progress_store, "counting", LATEST_CHECKPOINT).await;
+    runner.abort();
+
+    // The pipeline starts right after the given checkpoint and delivers the rest in order.
+    let expected = (1..=LATEST_CHECKPOINT).collect::<Vec<_>>();
+    assert_eq!(*processed.lock().unwrap(), expected);
+}
+
+#[tokio::test]
+async fn test_restarted_pipeline_resumes_from_stored_watermark() {
+    let registry = Registry::default();
+    mango_metrics::init_metrics(&registry);
+    let metrics = CheckpointFetcherMetrics::new(&registry);
+    let source = SimulacrumSource::new();
+    let processed = Arc::new(Mutex::new(Vec::new()));
+    let progress_store = Arc::new(InMemoryProgressStore::default());
+    let stall_at = 3;
+
+    // The first run fails to save every watermark once, then gets killed while checkpoint 3
+    // is being processed.
+    let pipeline = Pipeline::new()
+        .register_handler(CountingHandler {
+            processed: processed.clone(),
+            stall_at: Some(stall_at),
+        })
+        .with_progress_store(
+            SharedProgressStore::new(progress_store.clone()).failing_saves(stall_at as usize),
+        )
+        .batch_size(1);
+    let runner = tokio::spawn(pipeline.run(fetcher_factory(source.clone(), metrics.clone())));
+    wait_for_watermark(&progress_store, "counting", stall_at - 1).await;
+    runner.abort();
+    let _ = runner.await;
+    let expected = (0..stall_at).collect::<Vec<_>>();
+    assert_eq!(*processed.lock().unwrap(), expected);
+
+    // The restarted pipeline picks up right after the stored watermark.
+    let pipeline = Pipeline::new()
+        .register_handler(CountingHandler {
+            processed: processed.clone(),
+            stall_at: None,
+        })
+        .with_progress_store(SharedProgressStore::new(progress_store.clone()))
+        .batch_size(1);
+    let runner = tokio::spawn(pipeline.run(fetcher_factory(source, metrics)));
+    wait_for_watermark(&progress_store, "counting", LATEST_CHECKPOINT).await;
+    runner.abort();
+
+    // Failed saves were retried rather than reprocessed, and no checkpoint was delivered twice
+    // across the restart.
+    let expected = (0..=LATEST_CHECKPOINT).collect::<Vec<_>>();
+    assert_eq!(*processed.lock().unwrap(), expected);
+}
+
+#[tokio::test]
+async fn test_handler_reads_transactions_through_checkpoint_view() {
+    let registry = Registry::default();
+    mango_metrics::init_metrics(&registry);
+
+    // Odd checkpoints hold a transaction with as many Move calls as their sequence number, the
+    // others none.
+    let mut sim = Simulacrum::new();
+    for checkpoint in 1..=LATEST_CHECKPOINT {
+        if checkpoint % 2 == 1 {
+            let transaction = split_gas_transaction(&sim, checkpoint as usize);
+            let (_, err) = sim.execute_transaction(transaction).unwrap();
+            assert!(err.is_none());
+        }
+        sim.create_checkpoint();
+    }
+
+    let calls = Arc::new(Mutex::new(BTreeMap::new()));
+    let progress_store = Arc::new(InMemoryProgressStore::default());
+    let pipeline = Pipeline::new()
+        .register_handler(MoveCallCounter {
+            calls: calls.clone(),
+        })
+        .with_progress_store(SharedProgressStore::new(progress_store.clone()))
+        .batch_size(2);
+    let new_fetcher = fetcher_factory(
+        SimulacrumSource::from_simulacrum(&sim),
+        CheckpointFetcherMetrics::new(&registry),
+    );
+    let runner = tokio::spawn(pipeline.run(new_fetcher));
+    wait_for_watermark(&progress_store, "move_calls", LATEST_CHECKPOINT).await;
+    runner.abort();
+
+    // Every `pay::split` call is counted once, against the framework package only.
+    let expected = BTreeMap::from([(MGO_FRAMEWORK_PACKAGE_ID, 1 + 3 + 5)]);
+    assert_eq!(*calls.lock().unwrap(), expected);
+}
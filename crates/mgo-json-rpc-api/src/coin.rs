@@ -3,15 +3,19 @@
 
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
-use mgo_json_rpc_types::{Balance, CoinPage, MgoCoinMetadata};
+use mgo_json_rpc_types::{Balance, BalancePage, Coin, CoinPage, MgoCoinMetadata};
 use mgo_open_rpc_macros::open_rpc;
 use mgo_types::balance::Supply;
 use mgo_types::base_types::{ObjectID, MgoAddress};
+use mgo_types::mgo_serde::BigInt;
 
 #[open_rpc(namespace = "mgox", tag = "Coin Query API")]
 #[rpc(server, client, namespace = "mgox")]
 pub trait CoinReadApi {
-    /// Return all Coin<`coin_type`> objects owned by an address.
+    /// Return all Coin<`coin_type`> objects owned by an address, along with the number of
+    /// those coins and their total balance if `include_totals` is set. Coins with a balance
+    /// below `min_balance` are left out, and a page resumes after its cursor even if the balance
+    /// of the cursor coin has since dropped below `min_balance`.
     #[method(name = "getCoins")]
     async fn get_coins(
         &self,
@@ -23,9 +27,33 @@ pub trait CoinReadApi {
         cursor: Option<ObjectID>,
         /// maximum number of items per page
         limit: Option<usize>,
+        /// whether to also return the number of coins and total balance of the coin type, default to false
+        include_totals: Option<bool>,
+        /// optional minimum balance of the coins returned
+        min_balance: Option<BigInt<u64>>,
     ) -> RpcResult<CoinPage>;
 
-    /// Return all Coin objects owned by an address.
+    /// Return Coin<`coin_type`> objects owned by an address whose balances add up to at least
+    /// `target_amount`, picking the largest coins first and coins of equal balance in the order
+    /// of their IDs, so that the same coins are picked again as long as the coins owned do not
+    /// change. Only Coin objects owned by the address are picked, never staked or otherwise
+    /// wrapped coins. Fails with the amount missing if no `max_coins` coins add up to
+    /// `target_amount`.
+    #[method(name = "selectCoins")]
+    async fn select_coins(
+        &self,
+        /// the owner's Mgo address
+        owner: MgoAddress,
+        /// optional type name for the coin (e.g., 0x168da5bf1f48dafc111b0a488fa454aca95e0b5e::usdc::USDC), default to 0x2::mgo::MGO if not specified.
+        coin_type: Option<String>,
+        /// the amount the selected coins must add up to
+        target_amount: BigInt<u64>,
+        /// maximum number of coins to select, default to the maximum page size
+        max_coins: Option<usize>,
+    ) -> RpcResult<Vec<Coin>>;
+
+    /// Return all Coin objects owned by an address, along with the number of coins and total
+    /// balance of each coin type if `include_totals` is set.
     #[method(name = "getAllCoins")]
     async fn get_all_coins(
         &self,
@@ -35,6 +63,8 @@ pub trait CoinReadApi {
         cursor: Option<ObjectID>,
         /// maximum number of items per page
         limit: Option<usize>,
+        /// whether to also return the number of coins and total balance of each coin type, default to false
+        include_totals: Option<bool>,
     ) -> RpcResult<CoinPage>;
 
     /// Return the total coin balance for one coin type, owned by the address owner.
@@ -55,6 +85,19 @@ pub trait CoinReadApi {
         owner: MgoAddress,
     ) -> RpcResult<Vec<Balance>>;
 
+    /// Return the total coin balance and coin object count for each coin type owned by the
+    /// address owner, one page at a time in the order of the coin type names.
+    #[method(name = "getAllBalancesPaginated")]
+    async fn get_all_balances_paginated(
+        &self,
+        /// the owner's Mgo address
+        owner: MgoAddress,
+        /// optional paging cursor, the last coin type of the previous page
+        cursor: Option<String>,
+        /// maximum number of coin types per page
+        limit: Option<usize>,
+    ) -> RpcResult<BalancePage>;
+
     /// Return metadata(e.g., symbol, decimals) for a coin
     #[method(name = "getCoinMetadata")]
     async fn get_coin_metadata(
@@ -0,0 +1,22 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use fastcrypto::encoding::Base64;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+
+use mgo_json_rpc_types::GasComparison;
+use mgo_open_rpc_macros::open_rpc;
+
+#[open_rpc(namespace = "mgox", tag = "Debug API")]
+#[rpc(server, client, namespace = "mgox")]
+pub trait DebugApi {
+    /// Run the transaction both as a dry run and in dev-inspect mode, and return the gas each
+    /// of them charges side by side. Only served by fullnodes with gas comparison enabled.
+    #[method(name = "debugGasComparison")]
+    async fn debug_gas_comparison(
+        &self,
+        /// BCS serialized transaction data bytes without its type tag, as base-64 encoded string.
+        tx_bytes: Base64,
+    ) -> RpcResult<GasComparison>;
+}
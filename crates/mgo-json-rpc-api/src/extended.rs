@@ -1,14 +1,22 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+use fastcrypto::encoding::Base64;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
 
 use mgo_json_rpc_types::{
-    AddressMetrics, CheckpointedObjectID, EpochInfo, EpochMetricsPage, EpochPage, MoveCallMetrics,
-    NetworkMetrics, QueryObjectsPage, MgoObjectResponseQuery,
+    AbortLocation, ActiveAddressStats, AddressMetrics, ChainState, CheckpointedObjectID,
+    CoinTypeOrder, CoinTypesPage, CohortRetention, EpochInfo, EpochMetricsPage, EpochPage,
+    EventsByObjectPage, ModifiedObjectCursor, ModifiedObjectsPage, ModuleDisassembly,
+    MoveCallMetrics, MultiOwnerObjectCursor, MultiOwnerObjectsPage, NetworkMetrics,
+    ObjectOwnersHistoryPage, ObjectsPage, OrphanedDynamicFieldsPage, PackageDependency,
+    PackageDependentsPage, PackageVerification, QueryObjectsPage, MgoObjectResponseQuery,
+    SharedObjectStats, StorageStats, SupplyGranularity, SupplyHistory, TableValue, ValidatorReport,
 };
 use mgo_open_rpc_macros::open_rpc;
+use mgo_types::base_types::{MgoAddress, ObjectID, SequenceNumber};
+use mgo_types::event::EventID;
 use mgo_types::mgo_serde::BigInt;
 
 #[open_rpc(namespace = "mgox", tag = "Extended API")]
@@ -26,18 +34,41 @@ pub trait ExtendedApi {
         descending_order: Option<bool>,
     ) -> RpcResult<EpochPage>;
 
-    /// Return a list of epoch metrics, which is a subset of epoch info
+    /// Return the transaction, gas and address metrics of epochs, latest first
     #[method(name = "getEpochMetrics")]
     async fn get_epoch_metrics(
         &self,
+        /// optional epoch to return the metrics of, instead of all epochs
+        epoch: Option<BigInt<u64>>,
         /// optional paging cursor
         cursor: Option<BigInt<u64>>,
         /// maximum number of items per page
         limit: Option<usize>,
-        /// flag to return results in descending order
-        descending_order: Option<bool>,
     ) -> RpcResult<EpochMetricsPage>;
 
+    /// Return the performance of a validator in every epoch of a range of at most
+    /// [MAX_VALIDATOR_REPORT_EPOCHS] it was in the committee of, in order. Metrics that cannot
+    /// be derived from the indexed checkpoints are null rather than zero.
+    #[method(name = "getValidatorReport")]
+    async fn get_validator_report(
+        &self,
+        /// the Mgo address of the validator
+        validator_address: MgoAddress,
+        /// the first epoch of the range
+        from_epoch: BigInt<u64>,
+        /// the last epoch of the range, inclusive
+        to_epoch: BigInt<u64>,
+    ) -> RpcResult<Vec<ValidatorReport>>;
+
+    /// Return the performance of every validator of an epoch, ranked by the checkpoints they
+    /// signed and then by the number of validators that reported them
+    #[method(name = "getValidatorRanking")]
+    async fn get_validator_ranking(
+        &self,
+        /// the epoch to rank the validators of, the latest epoch indexed by default
+        epoch: Option<BigInt<u64>>,
+    ) -> RpcResult<Vec<ValidatorReport>>;
+
     /// Return current epoch info
     #[method(name = "getCurrentEpoch")]
     async fn get_current_epoch(&self) -> RpcResult<EpochInfo>;
@@ -54,6 +85,60 @@ pub trait ExtendedApi {
         limit: Option<usize>,
     ) -> RpcResult<QueryObjectsPage>;
 
+    /// Return the objects owned by an address, ordered by the checkpoint that last modified them
+    /// and then by object ID. The cursor holds that sort key, so objects modified between two
+    /// page requests are neither skipped nor returned twice at the page boundary.
+    #[method(name = "getOwnedObjectsByCheckpoint")]
+    async fn get_owned_objects_by_checkpoint(
+        &self,
+        /// the owner's Mgo address
+        address: MgoAddress,
+        /// the objects query criteria.
+        query: Option<MgoObjectResponseQuery>,
+        /// only return objects last modified after this checkpoint
+        modified_after_checkpoint: Option<BigInt<u64>>,
+        /// An optional paging cursor. If provided, the query will start from the next item after the specified cursor. Default to start from the first item if not specified.
+        cursor: Option<ModifiedObjectCursor>,
+        /// Max number of items returned per page, default to [QUERY_MAX_RESULT_LIMIT] if not specified.
+        limit: Option<usize>,
+        /// flag to return results in descending order
+        descending_order: Option<bool>,
+    ) -> RpcResult<ModifiedObjectsPage>;
+
+    /// Return the objects owned by any of up to [QUERY_MAX_OWNED_OBJECTS_ADDRESSES] addresses.
+    /// Pages walk the addresses in the order given and the objects of each address by object ID,
+    /// and every object carries its owner.
+    #[method(name = "getOwnedObjectsMulti")]
+    async fn get_owned_objects_multi(
+        &self,
+        /// the owners' Mgo addresses
+        addresses: Vec<MgoAddress>,
+        /// the objects query criteria.
+        query: Option<MgoObjectResponseQuery>,
+        /// An optional paging cursor. If provided, the query will start from the next item after the specified cursor. Default to start from the first item if not specified.
+        cursor: Option<MultiOwnerObjectCursor>,
+        /// Max number of items returned per page, default to [QUERY_MAX_RESULT_LIMIT] if not specified.
+        limit: Option<usize>,
+    ) -> RpcResult<MultiOwnerObjectsPage>;
+
+    /// Return the objects owned by an address at the end of a checkpoint, ordered by object ID.
+    /// Ownership is rebuilt from the history of the objects, so the pages of a checkpoint stay
+    /// the same however many checkpoints are indexed after it.
+    #[method(name = "getOwnedObjectsAtCheckpoint")]
+    async fn get_owned_objects_at_checkpoint(
+        &self,
+        /// the owner's Mgo address
+        address: MgoAddress,
+        /// the checkpoint to list the objects of the owner at
+        checkpoint: BigInt<u64>,
+        /// the objects query criteria.
+        query: Option<MgoObjectResponseQuery>,
+        /// An optional paging cursor. If provided, the query will start from the next item after the specified cursor. Default to start from the first item if not specified.
+        cursor: Option<ObjectID>,
+        /// Max number of items returned per page, default to [QUERY_MAX_RESULT_LIMIT] if not specified.
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectsPage>;
+
     /// Return Network metrics
     #[method(name = "getNetworkMetrics")]
     async fn get_network_metrics(&self) -> RpcResult<NetworkMetrics>;
@@ -73,6 +158,200 @@ pub trait ExtendedApi {
         descending_order: Option<bool>,
     ) -> RpcResult<Vec<AddressMetrics>>;
 
+    /// Return the number of distinct senders of every UTC day in a range, days being counted
+    /// from the unix epoch
+    #[method(name = "getActiveAddressStats")]
+    async fn get_active_address_stats(
+        &self,
+        /// the first day of the range, inclusive
+        from_day: BigInt<u64>,
+        /// the last day of the range, inclusive
+        to_day: BigInt<u64>,
+    ) -> RpcResult<ActiveAddressStats>;
+
+    /// Return the fraction of the addresses first seen on a UTC day that were active on each of
+    /// the following days
+    #[method(name = "getRetention")]
+    async fn get_retention(
+        &self,
+        /// the day the cohort's addresses sent their first transaction on
+        cohort_day: BigInt<u64>,
+        /// number of days after `cohort_day` to report the retention for
+        horizon_days: BigInt<u64>,
+    ) -> RpcResult<CohortRetention>;
+
+    /// Return every coin type seen on chain, with the number of addresses holding it. Holder
+    /// counts are maintained incrementally while indexing and may be approximate.
+    #[method(name = "getCoinTypes")]
+    async fn get_coin_types(
+        &self,
+        /// optional paging cursor, the last coin type of the previous page
+        cursor: Option<String>,
+        /// maximum number of items per page
+        limit: Option<usize>,
+        /// order of the coin types, earliest seen first by default
+        order_by: Option<CoinTypeOrder>,
+    ) -> RpcResult<CoinTypesPage>;
+
     #[method(name = "getTotalTransactions")]
     async fn get_total_transactions(&self) -> RpcResult<BigInt<u64>>;
+
+    /// Return contention statistics for a shared object over the most recent checkpoints
+    #[method(name = "getSharedObjectStats")]
+    async fn get_shared_object_stats(
+        &self,
+        /// the ID of the shared object
+        object_id: ObjectID,
+        /// number of most recent checkpoints to compute the statistics over
+        window_checkpoints: Option<BigInt<u64>>,
+    ) -> RpcResult<SharedObjectStats>;
+
+    /// Return the bytecode of every function in a published Move module
+    #[method(name = "getModuleDisassembly")]
+    async fn get_module_disassembly(
+        &self,
+        /// the ID of the package containing the module
+        package: ObjectID,
+        /// the name of the module
+        module: String,
+    ) -> RpcResult<ModuleDisassembly>;
+
+    /// Map an abort's function index and instruction offset to the function, and to the
+    /// source location when a source map was published for the module
+    #[method(name = "resolveAbortLocation")]
+    async fn resolve_abort_location(
+        &self,
+        /// the ID of the package containing the module
+        package: ObjectID,
+        /// the name of the module
+        module: String,
+        /// the definition index of the function the abort happened in
+        function_index: u16,
+        /// the offset of the aborting instruction within the function's code
+        code_offset: u16,
+    ) -> RpcResult<AbortLocation>;
+
+    /// Return the decoded values stored under up to [QUERY_MAX_RESULT_LIMIT] keys of a Move
+    /// table, in the order of the keys, with no value for keys the table has no entry for
+    #[method(name = "getTableValues")]
+    async fn get_table_values(
+        &self,
+        /// the ID of the table
+        parent_object_id: ObjectID,
+        /// the type of the table's keys, e.g. `address`
+        key_type: String,
+        /// the type of the table's values
+        value_type: String,
+        /// the BCS bytes of the keys to read
+        keys: Vec<Base64>,
+    ) -> RpcResult<Vec<TableValue>>;
+
+    /// Return the events referencing an object through an `ID` or `UID` field, oldest first by
+    /// default. Only events of the types the indexer is configured to index object references
+    /// of are searched, and these types are returned with the page.
+    #[method(name = "queryEventsByObject")]
+    async fn query_events_by_object(
+        &self,
+        /// the ID of the referenced object
+        object_id: ObjectID,
+        /// optional paging cursor
+        cursor: Option<EventID>,
+        /// maximum number of items per page, default to [QUERY_MAX_RESULT_LIMIT] if not specified.
+        limit: Option<usize>,
+        /// query result ordering, default to false (ascending order), oldest record first.
+        descending_order: Option<bool>,
+    ) -> RpcResult<EventsByObjectPage>;
+
+    /// Return the estimated row count and on-disk size of every table of the indexer, with
+    /// their growth per day, as last sampled by its writer
+    #[method(name = "getStorageStats")]
+    async fn get_storage_stats(&self) -> RpcResult<StorageStats>;
+
+    /// Return the supply of a coin type at the end of every epoch or day of a range of at most
+    /// [MAX_SUPPLY_HISTORY_BUCKETS], with the amounts minted and burned within each
+    #[method(name = "getSupplyHistory")]
+    async fn get_supply_history(
+        &self,
+        /// the coin type, e.g. `0x2::mgo::MGO`
+        coin_type: String,
+        /// whether buckets are epochs or days
+        granularity: SupplyGranularity,
+        /// the first epoch or day of the range, days counted since the unix epoch
+        from: BigInt<u64>,
+        /// the last epoch or day of the range, inclusive
+        to: BigInt<u64>,
+    ) -> RpcResult<SupplyHistory>;
+
+    /// Return the packages the latest version of a package links to, or those a given version
+    /// of it links to
+    #[method(name = "getPackageDependencies")]
+    async fn get_package_dependencies(
+        &self,
+        /// the ID of any version of the package
+        package_id: ObjectID,
+        /// the version of the package, the latest by default
+        version: Option<BigInt<u64>>,
+    ) -> RpcResult<Vec<PackageDependency>>;
+
+    /// Return the packages whose latest version links to any version of a package, ordered by
+    /// their original ID
+    #[method(name = "getPackageDependents")]
+    async fn get_package_dependents(
+        &self,
+        /// the ID of any version of the package
+        package_id: ObjectID,
+        /// optional paging cursor, the original ID of the last dependent of the previous page
+        cursor: Option<ObjectID>,
+        /// maximum number of items per page, default to [QUERY_MAX_RESULT_LIMIT] if not specified.
+        limit: Option<usize>,
+    ) -> RpcResult<PackageDependentsPage>;
+
+    /// Return the dynamic object fields whose value object was deleted or wrapped without the
+    /// field being removed, ordered by the ID of their `Field` wrapper object. The indexer finds
+    /// them by periodically checking the fields of the parents changed since its last scan, so
+    /// recent orphans may not be reported yet.
+    #[method(name = "getOrphanedDynamicFields")]
+    async fn get_orphaned_dynamic_fields(
+        &self,
+        /// optional parent object to return the orphaned fields of, instead of all parents
+        parent_object_id: Option<ObjectID>,
+        /// optional paging cursor, the ID of the last field of the previous page
+        cursor: Option<ObjectID>,
+        /// maximum number of items per page, default to [QUERY_MAX_RESULT_LIMIT] if not specified.
+        limit: Option<usize>,
+    ) -> RpcResult<OrphanedDynamicFieldsPage>;
+
+    /// Return the chain identifier, the latest checkpoint with its epoch, reference gas price
+    /// and protocol version, and the namespaces the server serves, in a single call for clients
+    /// to initialize from. Served from values the server keeps up to date as checkpoints are
+    /// indexed, which always come from the same epoch as the checkpoint.
+    #[method(name = "getChainState")]
+    async fn get_chain_state(&self) -> RpcResult<ChainState>;
+
+    /// Compare the modules of a local build of a package to those of a published version of
+    /// it, once the addresses and metadata that publishing sets are normalized, and return
+    /// whether each module matches, with the versions of the package
+    #[method(name = "verifyPackage")]
+    async fn verify_package(
+        &self,
+        /// the ID of the published version of the package
+        package_id: ObjectID,
+        /// the bytecode of every module of the local build, as built with `0x0` as its address
+        modules: Vec<Base64>,
+    ) -> RpcResult<PackageVerification>;
+
+    /// Return the history of the owners of an object, as the intervals of checkpoints over
+    /// which it kept the same owner, oldest first. Consecutive versions with the same owner
+    /// are collapsed into one interval, and the periods the object spent wrapped appear as
+    /// intervals of their own. The last interval is still open if the object still exists.
+    #[method(name = "getObjectOwnersHistory")]
+    async fn get_object_owners_history(
+        &self,
+        /// the ID of the object
+        object_id: ObjectID,
+        /// optional paging cursor, the version starting the last interval of the previous page
+        cursor: Option<SequenceNumber>,
+        /// maximum number of items per page, default to [QUERY_MAX_RESULT_LIMIT] if not specified.
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectOwnersHistoryPage>;
 }
@@ -4,7 +4,9 @@
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
 
-use mgo_json_rpc_types::{DelegatedStake, MgoCommittee, ValidatorApys};
+use mgo_json_rpc_types::{
+    DelegatedStake, MgoCommittee, OwnedStakesSummary, StorageRebateEstimate, ValidatorApys,
+};
 use mgo_open_rpc_macros::open_rpc;
 use mgo_types::base_types::{ObjectID, MgoAddress};
 use mgo_types::mgo_serde::BigInt;
@@ -24,6 +26,15 @@ pub trait GovernanceReadApi {
     #[method(name = "getStakes")]
     async fn get_stakes(&self, owner: MgoAddress) -> RpcResult<Vec<DelegatedStake>>;
 
+    /// Return the stakes of `owner` grouped by validator, with the name and image of each
+    /// validator, the principal, estimated rewards, number and earliest activation epoch of the
+    /// stakes with it, and the totals across validators.
+    #[method(name = "getOwnedStakedObjectsSummary")]
+    async fn get_owned_staked_objects_summary(
+        &self,
+        owner: MgoAddress,
+    ) -> RpcResult<OwnedStakesSummary>;
+
     /// Return the committee information for the asked `epoch`.
     #[method(name = "getCommitteeInfo")]
     async fn get_committee_info(
@@ -40,7 +51,22 @@ pub trait GovernanceReadApi {
     #[method(name = "getReferenceGasPrice")]
     async fn get_reference_gas_price(&self) -> RpcResult<BigInt<u64>>;
 
-    /// Return the validator APY
+    /// Return the APY of every active validator, as the geometric mean of the returns of its
+    /// staking pool over the recent epochs it was active in, or 0 for validators with too short
+    /// a history
     #[method(name = "getValidatorsApy")]
     async fn get_validators_apy(&self) -> RpcResult<ValidatorApys>;
+
+    /// Return the MGO the sender of a transaction gets back from the storage rebate of an object
+    /// when deleting it, and when mutating it if `new_contents_size` is given, at the storage
+    /// price of the current protocol config. Immutable objects and system objects have no rebate
+    /// to return, so all their amounts are zero and the reason is given.
+    #[method(name = "estimateStorageRebate")]
+    async fn estimate_storage_rebate(
+        &self,
+        /// the ID of the object
+        object_id: ObjectID,
+        /// the size of the object's contents after the mutation to estimate, in bytes
+        new_contents_size: Option<BigInt<u64>>,
+    ) -> RpcResult<StorageRebateEstimate>;
 }
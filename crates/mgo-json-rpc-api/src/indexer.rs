@@ -4,11 +4,11 @@
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
 
-use mgo_json_rpc_types::MgoTransactionBlockEffects;
+use mgo_json_rpc_types::{CheckpointNotification, MgoTransactionBlockEffects};
 use mgo_json_rpc_types::{
-    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, MgoEvent, MgoObjectResponse,
-    MgoObjectResponseQuery, MgoTransactionBlockResponseQuery, TransactionBlocksPage,
-    TransactionFilter,
+    DynamicFieldPage, DynamicFieldsOptions, EventFilter, EventPage, NameServiceResolution,
+    ObjectsPage, Page, MgoEvent, MgoObjectDataOptions, MgoObjectResponse, MgoObjectResponseQuery,
+    MgoTransactionBlockResponseQuery, TransactionBlocksPage, TransactionFilter,
 };
 use mgo_open_rpc_macros::open_rpc;
 use mgo_types::base_types::{ObjectID, MgoAddress};
@@ -35,6 +35,8 @@ pub trait IndexerApi {
         cursor: Option<ObjectID>,
         /// Max number of items returned per page, default to [QUERY_MAX_RESULT_LIMIT] if not specified.
         limit: Option<usize>,
+        /// flag to return the cursor, limit, ordering and filter hash the server applied, default to false
+        echo: Option<bool>,
     ) -> RpcResult<ObjectsPage>;
 
     /// Return list of transactions for a specified query criteria.
@@ -49,6 +51,8 @@ pub trait IndexerApi {
         limit: Option<usize>,
         /// query result ordering, default to false (ascending order), oldest record first.
         descending_order: Option<bool>,
+        /// flag to return the cursor, limit, ordering and filter hash the server applied, default to false
+        echo: Option<bool>,
     ) -> RpcResult<TransactionBlocksPage>;
 
     /// Return list of events for a specified query criteria.
@@ -63,6 +67,8 @@ pub trait IndexerApi {
         limit: Option<usize>,
         /// query result ordering, default to false (ascending order), oldest record first.
         descending_order: Option<bool>,
+        /// flag to return the cursor, limit, ordering and filter hash the server applied, default to false
+        echo: Option<bool>,
     ) -> RpcResult<EventPage>;
 
     /// Subscribe to a stream of Mgo event
@@ -77,7 +83,12 @@ pub trait IndexerApi {
     #[subscription(name = "subscribeTransaction", item = MgoTransactionBlockEffects)]
     fn subscribe_transaction(&self, filter: TransactionFilter);
 
-    /// Return the list of dynamic field objects owned by an object.
+    /// Subscribe to a stream of the checkpoints executed by the node
+    #[subscription(name = "subscribeCheckpoint", item = CheckpointNotification)]
+    fn subscribe_checkpoint(&self);
+
+    /// Return the list of dynamic field objects owned by an object. Pages including the field
+    /// objects end early once these take more than 4 MiB.
     #[method(name = "getDynamicFields")]
     async fn get_dynamic_fields(
         &self,
@@ -87,6 +98,10 @@ pub trait IndexerApi {
         cursor: Option<ObjectID>,
         /// Maximum item returned per page, default to [QUERY_MAX_RESULT_LIMIT] if not specified.
         limit: Option<usize>,
+        /// Filters the fields by the type of their name and whether to include their field objects, default to all fields without their field objects if not specified.
+        options: Option<DynamicFieldsOptions>,
+        /// flag to return the cursor, limit, ordering and filter hash the server applied, default to false
+        echo: Option<bool>,
     ) -> RpcResult<DynamicFieldPage>;
 
     /// Return the dynamic field object information for a specified object
@@ -97,6 +112,8 @@ pub trait IndexerApi {
         parent_object_id: ObjectID,
         /// The Name of the dynamic field
         name: DynamicFieldName,
+        /// options for specifying the content to be returned, default to the full content if not specified
+        options: Option<MgoObjectDataOptions>,
     ) -> RpcResult<MgoObjectResponse>;
 
     /// Return the resolved address given resolver and name
@@ -107,14 +124,35 @@ pub trait IndexerApi {
         name: String,
     ) -> RpcResult<Option<MgoAddress>>;
 
-    /// Return the resolved names given address,
-    /// if multiple names are resolved, the first one is the primary name.
+    /// Return the unexpired names whose record points to the given address, ordered by the ID of
+    /// the record.
     #[method(name = "resolveNameServiceNames")]
     async fn resolve_name_service_names(
         &self,
         /// The address to resolve
         address: MgoAddress,
+        /// An optional paging cursor. If provided, the query will start from the next item after the specified cursor. Default to start from the first item if not specified.
         cursor: Option<ObjectID>,
+        /// Max number of items returned per page, default to [QUERY_MAX_RESULT_LIMIT] if not specified.
         limit: Option<usize>,
     ) -> RpcResult<Page<String, ObjectID>>;
+
+    /// Return the resolved address of each of the given names, in the same order. Expired names
+    /// do not resolve. At most 100 names can be resolved at once.
+    #[method(name = "multiResolveNameService")]
+    async fn multi_resolve_name_service(
+        &self,
+        /// The names to resolve
+        names: Vec<String>,
+    ) -> RpcResult<Vec<NameServiceResolution<MgoAddress>>>;
+
+    /// Return the name the reverse record of each of the given addresses points to, in the same
+    /// order, as long as that name is unexpired and resolves back to the address. At most 100
+    /// addresses can be resolved at once.
+    #[method(name = "multiReverseResolve")]
+    async fn multi_reverse_resolve(
+        &self,
+        /// The addresses to resolve
+        addresses: Vec<MgoAddress>,
+    ) -> RpcResult<Vec<NameServiceResolution<String>>>;
 }
@@ -1,12 +1,16 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::anyhow;
+use std::fmt;
+
 use mango_metrics::histogram::Histogram;
 
 pub use coin::CoinReadApiClient;
 pub use coin::CoinReadApiOpenRpc;
 pub use coin::CoinReadApiServer;
+pub use debug::DebugApiClient;
+pub use debug::DebugApiOpenRpc;
+pub use debug::DebugApiServer;
 pub use extended::ExtendedApiClient;
 pub use extended::ExtendedApiOpenRpc;
 pub use extended::ExtendedApiServer;
@@ -16,6 +20,7 @@ pub use governance::GovernanceReadApiServer;
 pub use indexer::IndexerApiClient;
 pub use indexer::IndexerApiOpenRpc;
 pub use indexer::IndexerApiServer;
+use mgo_json_rpc_types::ServerLimits;
 pub use move_utils::MoveUtilsClient;
 pub use move_utils::MoveUtilsOpenRpc;
 pub use move_utils::MoveUtilsServer;
@@ -24,22 +29,31 @@ use prometheus::{register_int_counter_with_registry, IntCounter};
 pub use read::ReadApiClient;
 pub use read::ReadApiOpenRpc;
 pub use read::ReadApiServer;
+pub use support::SupportApiClient;
+pub use support::SupportApiOpenRpc;
+pub use support::SupportApiServer;
 use tap::TapFallible;
 use tracing::warn;
 pub use transaction_builder::TransactionBuilderClient;
 pub use transaction_builder::TransactionBuilderOpenRpc;
 pub use transaction_builder::TransactionBuilderServer;
+pub use verification::VerificationApiClient;
+pub use verification::VerificationApiOpenRpc;
+pub use verification::VerificationApiServer;
 pub use write::WriteApiClient;
 pub use write::WriteApiOpenRpc;
 pub use write::WriteApiServer;
 
 mod coin;
+mod debug;
 mod extended;
 mod governance;
 mod indexer;
 mod move_utils;
 mod read;
+mod support;
 mod transaction_builder;
+mod verification;
 mod write;
 
 const RPC_QUERY_MAX_RESULT_LIMIT: &str = "RPC_QUERY_MAX_RESULT_LIMIT";
@@ -52,6 +66,25 @@ pub static QUERY_MAX_RESULT_LIMIT: Lazy<usize> = Lazy::new(|| {
 // TODOD(chris): make this configurable
 pub const QUERY_MAX_RESULT_LIMIT_CHECKPOINTS: usize = 100;
 
+/// Maximum number of owners whose objects can be listed in a single request.
+pub const QUERY_MAX_OWNED_OBJECTS_ADDRESSES: usize = 20;
+
+/// Maximum number of epochs or days the supply history of a coin type is returned for at once.
+pub const MAX_SUPPLY_HISTORY_BUCKETS: u64 = 366;
+
+/// Maximum number of epochs the report of a validator is returned for at once.
+pub const MAX_VALIDATOR_REPORT_EPOCHS: u64 = 366;
+
+/// Server-side caps on request sizes, as reported to clients by `mgo_getClientLimits`.
+pub fn server_limits() -> ServerLimits {
+    ServerLimits {
+        max_page_size: *QUERY_MAX_RESULT_LIMIT as u64,
+        max_checkpoint_page_size: QUERY_MAX_RESULT_LIMIT_CHECKPOINTS as u64,
+        max_multi_get_batch_size: *QUERY_MAX_RESULT_LIMIT as u64,
+        max_owned_objects_addresses: QUERY_MAX_OWNED_OBJECTS_ADDRESSES as u64,
+    }
+}
+
 pub fn cap_page_limit(limit: Option<usize>) -> usize {
     let limit = limit.unwrap_or_default();
     if limit > *QUERY_MAX_RESULT_LIMIT || limit == 0 {
@@ -63,13 +96,33 @@ pub fn cap_page_limit(limit: Option<usize>) -> usize {
 
 pub fn validate_limit(limit: Option<usize>, max: usize) -> Result<usize, anyhow::Error> {
     match limit {
-        Some(l) if l > max => Err(anyhow!("Page size limit {l} exceeds max limit {max}")),
-        Some(0) => Err(anyhow!("Page size limit cannot be smaller than 1")),
+        Some(l) if l > max => Err(PageLimitError::TooLarge { limit: l, max }.into()),
+        Some(0) => Err(PageLimitError::Zero.into()),
         Some(l) => Ok(l),
         None => Ok(max),
     }
 }
 
+/// Error of [`validate_limit`], which servers downcast to answer with a [`ClientErrorCode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageLimitError {
+    TooLarge { limit: usize, max: usize },
+    Zero,
+}
+
+impl fmt::Display for PageLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLarge { limit, max } => {
+                write!(f, "Page size limit {limit} exceeds max limit {max}")
+            }
+            Self::Zero => write!(f, "Page size limit cannot be smaller than 1"),
+        }
+    }
+}
+
+impl std::error::Error for PageLimitError {}
+
 #[derive(Clone)]
 pub struct JsonRpcMetrics {
     pub get_objects_limit: Histogram,
@@ -105,6 +158,9 @@ pub struct JsonRpcMetrics {
 
     pub orchestrator_latency_ms: Histogram,
     pub post_orchestrator_latency_ms: Histogram,
+
+    pub dry_run_gas_comparisons: IntCounter,
+    pub dry_run_gas_discrepancies: IntCounter,
 }
 
 impl JsonRpcMetrics {
@@ -269,6 +325,18 @@ impl JsonRpcMetrics {
                 "The latency of response processing after transaction orchestrator, in ms",
                 registry,
             ),
+            dry_run_gas_comparisons: register_int_counter_with_registry!(
+                "json_rpc_dry_run_gas_comparisons",
+                "The number of transactions whose dry run gas was compared to dev inspect",
+                registry
+            )
+            .unwrap(),
+            dry_run_gas_discrepancies: register_int_counter_with_registry!(
+                "json_rpc_dry_run_gas_discrepancies",
+                "The number of compared transactions whose dry run and dev inspect gas differ beyond the threshold",
+                registry
+            )
+            .unwrap(),
         }
     }
 
@@ -300,3 +368,114 @@ pub const CLIENT_TARGET_API_VERSION_HEADER: &str = "client-target-api-version";
 
 pub const TRANSIENT_ERROR_CODE: i32 = -32050;
 pub const TRANSACTION_EXECUTION_CLIENT_ERROR_CODE: i32 = -32002;
+/// Returned for methods an operator has temporarily disabled.
+pub const METHOD_DISABLED_CODE: i32 = -32003;
+/// Returned by indexers for data older than they retain, which an archival node may still serve.
+pub const DATA_PRUNED_CODE: i32 = -32004;
+/// Returned by servers too busy to take the request, which may be retried later.
+pub const SERVER_OVERLOADED_CODE: i32 = -32005;
+/// Returned for methods that the origin a browser sent the request from may not call.
+pub const METHOD_NOT_ALLOWED_FOR_ORIGIN_CODE: i32 = -32006;
+/// Returned by indexers whose writer is too old to have filled the data a method reads.
+pub const FEATURE_NOT_AVAILABLE_CODE: i32 = -32007;
+
+/// Errors caused by the request rather than by the node, each answered with its own code in
+/// the -32100 to -32199 range so that clients can tell them apart without matching on messages.
+/// The `data` of these errors names the code next to the values involved, as in
+/// `{ "code": "OBJECT_DELETED", "object_id": "0x…", "version": 3, "digest": "…" }`.
+/// Codes are never renumbered, new ones are appended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClientErrorCode {
+    InvalidInput = -32100,
+    ObjectNotFound = -32101,
+    ObjectDeleted = -32102,
+    ObjectVersionUnavailable = -32103,
+    PackageNotFound = -32104,
+    InvalidCursor = -32105,
+    LimitExceeded = -32106,
+    CheckpointNotFound = -32107,
+    TransactionNotFound = -32108,
+    CoinTypeNotFound = -32109,
+    TreasuryCapNotFound = -32110,
+    InvalidGas = -32111,
+    InvalidSignature = -32112,
+    TransactionDenied = -32113,
+    Unsupported = -32114,
+    EpochNotFound = -32115,
+}
+
+impl ClientErrorCode {
+    pub const ALL: [Self; 16] = [
+        Self::InvalidInput,
+        Self::ObjectNotFound,
+        Self::ObjectDeleted,
+        Self::ObjectVersionUnavailable,
+        Self::PackageNotFound,
+        Self::InvalidCursor,
+        Self::LimitExceeded,
+        Self::CheckpointNotFound,
+        Self::TransactionNotFound,
+        Self::CoinTypeNotFound,
+        Self::TreasuryCapNotFound,
+        Self::InvalidGas,
+        Self::InvalidSignature,
+        Self::TransactionDenied,
+        Self::Unsupported,
+        Self::EpochNotFound,
+    ];
+
+    pub fn code(self) -> i32 {
+        self as i32
+    }
+
+    pub fn from_code(code: i32) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.code() == code)
+    }
+
+    /// Name of the code in the `data` of its errors.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::InvalidInput => "INVALID_INPUT",
+            Self::ObjectNotFound => "OBJECT_NOT_FOUND",
+            Self::ObjectDeleted => "OBJECT_DELETED",
+            Self::ObjectVersionUnavailable => "OBJECT_VERSION_UNAVAILABLE",
+            Self::PackageNotFound => "PACKAGE_NOT_FOUND",
+            Self::InvalidCursor => "INVALID_CURSOR",
+            Self::LimitExceeded => "LIMIT_EXCEEDED",
+            Self::CheckpointNotFound => "CHECKPOINT_NOT_FOUND",
+            Self::TransactionNotFound => "TRANSACTION_NOT_FOUND",
+            Self::CoinTypeNotFound => "COIN_TYPE_NOT_FOUND",
+            Self::TreasuryCapNotFound => "TREASURY_CAP_NOT_FOUND",
+            Self::InvalidGas => "INVALID_GAS",
+            Self::InvalidSignature => "INVALID_SIGNATURE",
+            Self::TransactionDenied => "TRANSACTION_DENIED",
+            Self::Unsupported => "UNSUPPORTED",
+            Self::EpochNotFound => "EPOCH_NOT_FOUND",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::InvalidInput => "The request is invalid in a way no other code covers",
+            Self::ObjectNotFound => "The object does not exist, or not at the requested version",
+            Self::ObjectDeleted => "The object was deleted or wrapped",
+            Self::ObjectVersionUnavailable => {
+                "The requested version of the object is not its current one"
+            }
+            Self::PackageNotFound => "The package does not exist",
+            Self::InvalidCursor => "The cursor does not point into the queried data",
+            Self::LimitExceeded => "The page size or input size is above what the node accepts",
+            Self::CheckpointNotFound => "The checkpoint does not exist on this node",
+            Self::TransactionNotFound => "The transaction does not exist on this node",
+            Self::CoinTypeNotFound => "The coin type does not exist",
+            Self::TreasuryCapNotFound => "The supply of the coin type cannot be read",
+            Self::InvalidGas => "The gas payment, budget or price is invalid",
+            Self::InvalidSignature => "The transaction is not signed by its sender",
+            Self::TransactionDenied => "The transaction is denied by the node",
+            Self::Unsupported => "The feature is not supported by this node",
+            Self::EpochNotFound => {
+                "The epoch has not started yet, or its committee is not stored on this node"
+            }
+        }
+    }
+}
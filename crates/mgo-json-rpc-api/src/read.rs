@@ -1,15 +1,19 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+use fastcrypto::encoding::Base64;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
 
 use mgo_json_rpc_types::{
-    Checkpoint, CheckpointId, CheckpointPage, MgoEvent, MgoGetPastObjectRequest,
-    MgoObjectDataOptions, MgoObjectResponse, MgoPastObjectResponse, MgoTransactionBlockResponse,
-    MgoTransactionBlockResponseOptions,
+    BcsResponse, Checkpoint, CheckpointId, CheckpointPage, ClientLimits, EncodedResponse,
+    EpochStartInfo, MgoEvent, MgoGetPastObjectRequest, MgoObjectDataOptions, MgoObjectResponse,
+    MgoPastObjectResponse, MgoTransactionBlockResponse, MgoTransactionBlockResponseOptions,
+    ResponseEncoding,
+};
+use mgo_json_rpc_types::{
+    ProtocolConfigResponse, MgoLoadedChildObjectsResponse, TransactionSignatureVerification,
 };
-use mgo_json_rpc_types::{ProtocolConfigResponse, MgoLoadedChildObjectsResponse};
 use mgo_open_rpc_macros::open_rpc;
 use mgo_types::base_types::{ObjectID, SequenceNumber, TransactionDigest};
 use mgo_types::mgo_serde::BigInt;
@@ -37,7 +41,9 @@ pub trait ReadApi {
         digests: Vec<TransactionDigest>,
         /// config options to control which fields to fetch
         options: Option<MgoTransactionBlockResponseOptions>,
-    ) -> RpcResult<Vec<MgoTransactionBlockResponse>>;
+        /// encoding of the response, default to json. With bcs, the transactions, effects and events are returned in request order, the response decodes to `Vec<TransactionBlockBcsV1>`, `options` are ignored and an unknown digest is an error.
+        encoding: Option<ResponseEncoding>,
+    ) -> RpcResult<EncodedResponse<Vec<MgoTransactionBlockResponse>>>;
 
     /// Return the object information for a specified object
     #[method(name = "getObject")]
@@ -57,7 +63,9 @@ pub trait ReadApi {
         object_ids: Vec<ObjectID>,
         /// options for specifying the content to be returned
         options: Option<MgoObjectDataOptions>,
-    ) -> RpcResult<Vec<MgoObjectResponse>>;
+        /// encoding of the response, default to json. With bcs, the objects are returned in request order, the response decodes to `Vec<ObjectReadBcsV1>` and `options` are ignored.
+        encoding: Option<ResponseEncoding>,
+    ) -> RpcResult<EncodedResponse<Vec<MgoObjectResponse>>>;
 
     /// Note there is no software-level guarantee/SLA that objects with past versions
     /// can be retrieved by this API, even if the object and version exists/existed.
@@ -87,10 +95,17 @@ pub trait ReadApi {
         options: Option<MgoObjectDataOptions>,
     ) -> RpcResult<Vec<MgoPastObjectResponse>>;
 
+    /// Return the child objects loaded by the transaction with the given digest, and the versions
+    /// they were loaded at.
     #[method(name = "getLoadedChildObjects")]
     async fn get_loaded_child_objects(
         &self,
+        /// the digest of the queried transaction
         digest: TransactionDigest,
+        /// options for the contents of the child objects at their loaded versions, which are only
+        /// returned when set. Children that have been pruned, or are past the number of children
+        /// a single request resolves, are returned with their version only and marked as such.
+        options: Option<MgoObjectDataOptions>,
     ) -> RpcResult<MgoLoadedChildObjectsResponse>;
 
     /// Return a checkpoint
@@ -99,7 +114,23 @@ pub trait ReadApi {
         &self,
         /// Checkpoint identifier, can use either checkpoint digest, or checkpoint sequence number as input.
         id: CheckpointId,
-    ) -> RpcResult<Checkpoint>;
+        /// encoding of the response, default to json. With bcs, the certified summary and contents of the checkpoint are returned, the response decodes to `CheckpointBcsV1`.
+        encoding: Option<ResponseEncoding>,
+    ) -> RpcResult<EncodedResponse<Checkpoint>>;
+
+    /// Return the BCS encoded transactions, effects and events of a checkpoint, in checkpoint order.
+    /// The result decodes to `CheckpointTransactionsBcsV1`. Transactions whose data is no longer
+    /// available are still listed, with the missing parts left out.
+    #[method(name = "getCheckpointTransactions")]
+    async fn get_checkpoint_transactions(
+        &self,
+        /// Sequence number of the checkpoint.
+        sequence_number: BigInt<u64>,
+        /// An optional paging cursor. If provided, the query will start from the transaction after the specified one in the checkpoint. Default to start from the first transaction if not specified.
+        cursor: Option<TransactionDigest>,
+        /// Maximum item returned per page, default to [QUERY_MAX_RESULT_LIMIT] if not specified.
+        limit: Option<usize>,
+    ) -> RpcResult<BcsResponse>;
 
     /// Return paginated list of checkpoints
     #[method(name = "getCheckpoints")]
@@ -111,6 +142,8 @@ pub trait ReadApi {
         limit: Option<usize>,
         /// query result ordering, default to false (ascending order), oldest record first.
         descending_order: bool,
+        /// If provided, only checkpoints of this epoch are returned.
+        epoch: Option<BigInt<u64>>,
     ) -> RpcResult<CheckpointPage>;
 
     #[method(name = "getCheckpoints", version <= "0.31")]
@@ -149,7 +182,37 @@ pub trait ReadApi {
         version: Option<BigInt<u64>>,
     ) -> RpcResult<ProtocolConfigResponse>;
 
+    /// Return the limits a client has to respect when building and submitting requests: the
+    /// relevant constants of the current protocol config, and this server's caps on page and
+    /// batch sizes. The result is tagged with the protocol version and epoch it was taken from.
+    #[method(name = "getClientLimits")]
+    async fn get_client_limits(&self) -> RpcResult<ClientLimits>;
+
     /// Return the first four bytes of the chain's genesis checkpoint digest.
     #[method(name = "getChainIdentifier")]
     async fn get_chain_identifier(&self) -> RpcResult<String>;
+
+    /// Verify the signatures of a transaction block against the current epoch without executing
+    /// it, reporting whether each signature holds and whether together they authorize the
+    /// transaction block.
+    #[method(name = "verifyTransactionBlockSignature")]
+    async fn verify_transaction_block_signature(
+        &self,
+        /// BCS serialized transaction data bytes without its type tag, as base-64 encoded string.
+        tx_bytes: Base64,
+        /// A list of signatures (`flag || signature || pubkey` bytes, as base-64 encoded string),
+        /// as they would be submitted with the transaction block.
+        signatures: Vec<Base64>,
+    ) -> RpcResult<TransactionSignatureVerification>;
+
+    /// Return the committee of an epoch with the first checkpoint of the epoch, together with
+    /// the committee and the last checkpoint of the previous epoch, which hands over to the
+    /// committee of the epoch. Light clients walk these from genesis to trust the committee of
+    /// later epochs.
+    #[method(name = "getEpochStartInfo")]
+    async fn get_epoch_start_info(
+        &self,
+        /// the epoch, 0 for the genesis committee
+        epoch: BigInt<u64>,
+    ) -> RpcResult<EpochStartInfo>;
 }
@@ -0,0 +1,37 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+
+use mgo_json_rpc_types::{AddressStateExport, CheckpointVerificationReport};
+use mgo_open_rpc_macros::open_rpc;
+use mgo_types::base_types::MgoAddress;
+use mgo_types::mgo_serde::BigInt;
+
+#[open_rpc(namespace = "mgox", tag = "Support API")]
+#[rpc(server, client, namespace = "mgox")]
+pub trait SupportApi {
+    /// Return in one document the owned objects, balances, stakes, latest transactions and
+    /// pending object locks of an address, as support needs them to look into its situation.
+    /// Sections that cannot be read report their error in place. Only served by fullnodes with
+    /// the support API enabled.
+    #[method(name = "exportAddressState")]
+    async fn export_address_state(
+        &self,
+        /// the address to export the state of
+        address: MgoAddress,
+    ) -> RpcResult<AddressStateExport>;
+
+    /// Check that the checkpoints stored by the node from `start` to `end`, both included, still
+    /// hash to the digests linking them, and return every mismatch found. At most 1,000
+    /// checkpoints are checked per call.
+    #[method(name = "verifyCheckpointRange")]
+    async fn verify_checkpoint_range(
+        &self,
+        /// the first checkpoint to check
+        start: BigInt<u64>,
+        /// the last checkpoint to check
+        end: BigInt<u64>,
+    ) -> RpcResult<CheckpointVerificationReport>;
+}
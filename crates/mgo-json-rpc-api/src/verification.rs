@@ -0,0 +1,23 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+
+use mgo_json_rpc_types::{SignatureVerificationRequest, SignatureVerificationResult};
+use mgo_open_rpc_macros::open_rpc;
+
+#[open_rpc(namespace = "mgox", tag = "Verification API")]
+#[rpc(server, client, namespace = "mgox")]
+pub trait VerificationApi {
+    /// Check up to 200 user signatures at once, returning for each, in the order given, whether
+    /// it is valid and why not when it is not. Signatures of a scheme are verified as a batch
+    /// where the scheme supports it. Multisig and zkLogin signatures are not verified in batches
+    /// and are reported as unsupported.
+    #[method(name = "verifySignatures")]
+    async fn verify_signatures(
+        &self,
+        /// the signatures to check, with the data they sign and the address expected to sign it
+        items: Vec<SignatureVerificationRequest>,
+    ) -> RpcResult<Vec<SignatureVerificationResult>>;
+}
@@ -26,6 +26,9 @@ pub trait WriteApi {
     ///     makes sure this node is aware of this transaction when client fires subsequent queries.
     ///     However if the node fails to execute the transaction locally in a timely manner,
     ///     a bool type in the response is set to false to indicated the case.
+    /// 3. WaitForCheckpointInclusion: also waits for a checkpoint executed by the node to include
+    ///     the transaction, whose sequence number and digest are returned. If none does within
+    ///     `timeout_ms`, capped by the node, the error data holds the digest and `submitted: true`.
     /// request_type is default to be `WaitForEffectsCert` unless options.show_events or options.show_effects is true
     #[method(name = "executeTransactionBlock")]
     async fn execute_transaction_block(
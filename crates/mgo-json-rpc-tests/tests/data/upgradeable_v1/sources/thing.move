@@ -0,0 +1,16 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+module upgradeable::thing {
+    use mgo::object::{Self, UID};
+    use mgo::transfer;
+    use mgo::tx_context::{Self, TxContext};
+
+    struct Thing has key, store {
+        id: UID,
+    }
+
+    public entry fun create(ctx: &mut TxContext) {
+        transfer::public_transfer(Thing { id: object::new(ctx) }, tx_context::sender(ctx))
+    }
+}
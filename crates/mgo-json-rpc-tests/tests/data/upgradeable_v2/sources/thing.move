@@ -0,0 +1,25 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+module upgradeable::thing {
+    use mgo::object::{Self, UID};
+    use mgo::transfer;
+    use mgo::tx_context::{Self, TxContext};
+
+    struct Thing has key, store {
+        id: UID,
+    }
+
+    /// Introduced by the upgrade, so defined by the upgraded package.
+    struct Gadget has key, store {
+        id: UID,
+    }
+
+    public entry fun create(ctx: &mut TxContext) {
+        transfer::public_transfer(Thing { id: object::new(ctx) }, tx_context::sender(ctx))
+    }
+
+    public entry fun create_gadget(ctx: &mut TxContext) {
+        transfer::public_transfer(Gadget { id: object::new(ctx) }, tx_context::sender(ctx))
+    }
+}
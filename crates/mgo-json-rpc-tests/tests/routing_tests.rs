@@ -5,18 +5,30 @@ use async_trait::async_trait;
 use hyper::header::HeaderValue;
 use hyper::HeaderMap;
 use jsonrpsee::core::client::ClientT;
-use jsonrpsee::core::RpcResult;
+use jsonrpsee::core::{Error as RpcError, RpcResult};
 use jsonrpsee::http_client::HttpClientBuilder;
 use jsonrpsee::proc_macros::rpc;
 use jsonrpsee::rpc_params;
+use jsonrpsee::types::error::{CallError, INTERNAL_ERROR_CODE};
 use jsonrpsee::RpcModule;
 use prometheus::Registry;
+use std::collections::{BTreeMap, BTreeSet};
 use std::env;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use mgo_config::local_ip_utils;
+use mgo_json_rpc::cors::CorsConfig;
+use mgo_json_rpc::kill_switch::{DisabledMethodData, MethodKillSwitch, DEFAULT_HINT};
+use mgo_json_rpc::query_scheduler::QuerySchedulerConfig;
+use mgo_json_rpc::rate_limiter::{MethodLimits, RateLimiterConfig, ThrottledData};
 use mgo_json_rpc::{JsonRpcServerBuilder, MgoRpcModule};
-use mgo_json_rpc_api::CLIENT_TARGET_API_VERSION_HEADER;
+use mgo_json_rpc_api::{
+    CLIENT_TARGET_API_VERSION_HEADER, METHOD_DISABLED_CODE, METHOD_NOT_ALLOWED_FOR_ORIGIN_CODE,
+    SERVER_OVERLOADED_CODE,
+};
 use mgo_open_rpc::Module;
 use mgo_open_rpc_macros::open_rpc;
+use tokio::sync::Semaphore;
 
 #[tokio::test]
 async fn test_rpc_backward_compatibility() {
@@ -131,6 +143,470 @@ async fn test_disable_routing() {
     assert!(response.is_err());
 }
 
+#[tokio::test]
+async fn test_method_kill_switch() {
+    let kill_switch = MethodKillSwitch::default();
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(TestApiModule).unwrap();
+    builder.set_kill_switch(kill_switch.clone());
+
+    let address = local_ip_utils::new_local_tcp_socket_for_testing();
+    let _handle = builder.start(address, None, None).await.unwrap();
+    let client = HttpClientBuilder::default()
+        .build(format!("http://0.0.0.0:{}", address.port()))
+        .unwrap();
+
+    let admin_address = local_ip_utils::new_local_tcp_socket_for_testing();
+    let admin_server =
+        axum::Server::bind(&admin_address).serve(kill_switch.admin_router().into_make_service());
+    tokio::spawn(async move { admin_server.await.unwrap() });
+    let admin_url = format!("http://{admin_address}");
+    let admin = reqwest::Client::new();
+
+    let response: String = client.request("test_foo", rpc_params!(true)).await.unwrap();
+    assert_eq!("Some string", response);
+
+    // Disabling a method refuses it without touching the others.
+    admin
+        .post(format!(
+            "{admin_url}/rpc-kill-switch/disable?method=test_foo&hint=Use%20test_bar"
+        ))
+        .send()
+        .await
+        .unwrap()
+        .error_for_status()
+        .unwrap();
+    let listed = admin
+        .get(format!("{admin_url}/rpc-kill-switch"))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert_eq!(listed, "test_foo: Use test_bar\n");
+
+    let error = client
+        .request::<String, _>("test_foo", rpc_params!(true))
+        .await
+        .unwrap_err();
+    let RpcError::Call(CallError::Custom(error)) = error else {
+        panic!("Unexpected error {error:?}");
+    };
+    assert_eq!(error.code(), METHOD_DISABLED_CODE);
+    assert_eq!(
+        error.message(),
+        "Method `test_foo` is temporarily disabled by the operator"
+    );
+    let data: DisabledMethodData = serde_json::from_str(error.data().unwrap().get()).unwrap();
+    assert_eq!(data.hint, "Use test_bar");
+
+    // Clients targeting the old version of the method are refused as well.
+    let mut versioned_header = HeaderMap::new();
+    versioned_header.insert(
+        CLIENT_TARGET_API_VERSION_HEADER,
+        HeaderValue::from_static("1.5"),
+    );
+    let versioned_client = HttpClientBuilder::default()
+        .set_headers(versioned_header)
+        .build(format!("http://0.0.0.0:{}", address.port()))
+        .unwrap();
+    let response: RpcResult<String> = versioned_client
+        .request("test_foo", rpc_params!("string"))
+        .await;
+    assert!(response.is_err());
+
+    // Enabling it again serves it as before.
+    admin
+        .post(format!(
+            "{admin_url}/rpc-kill-switch/enable?method=test_foo"
+        ))
+        .send()
+        .await
+        .unwrap()
+        .error_for_status()
+        .unwrap();
+    let response: String = client.request("test_foo", rpc_params!(true)).await.unwrap();
+    assert_eq!("Some string", response);
+
+    // Methods disabled without a hint get the default one.
+    kill_switch.disable("test_foo", None);
+    let Err(RpcError::Call(CallError::Custom(error))) = client
+        .request::<String, _>("test_foo", rpc_params!(true))
+        .await
+    else {
+        panic!("test_foo should be disabled");
+    };
+    let data: DisabledMethodData = serde_json::from_str(error.data().unwrap().get()).unwrap();
+    assert_eq!(data.hint, DEFAULT_HINT);
+}
+
+#[tokio::test]
+async fn test_handler_panic() {
+    let registry = Registry::new();
+    let mut builder = JsonRpcServerBuilder::new("1.5", &registry);
+    builder.register_module(TestApiModule).unwrap();
+    builder.register_module(FaultyApiModule).unwrap();
+
+    let address = local_ip_utils::new_local_tcp_socket_for_testing();
+    let _handle = builder.start(address, None, None).await.unwrap();
+    let client = HttpClientBuilder::default()
+        .build(format!("http://0.0.0.0:{}", address.port()))
+        .unwrap();
+
+    let error = client
+        .request::<String, _>("faulty_panic", rpc_params!())
+        .await
+        .unwrap_err();
+    let RpcError::Call(CallError::Custom(error)) = error else {
+        panic!("Unexpected error {error:?}");
+    };
+    assert_eq!(error.code(), INTERNAL_ERROR_CODE);
+    assert!(!error.message().contains("handler blew up"));
+    let data: serde_json::Value = serde_json::from_str(error.data().unwrap().get()).unwrap();
+    let correlation_id = data["correlationId"].as_str().unwrap();
+    assert_eq!(
+        error.message(),
+        format!("Internal error, correlation id {correlation_id}")
+    );
+
+    let panics = registry
+        .gather()
+        .into_iter()
+        .find(|family| family.get_name() == "rpc_handler_panic")
+        .unwrap();
+    let [metric] = panics.get_metric() else {
+        panic!("Expected a single panicking method");
+    };
+    assert_eq!(metric.get_label()[0].get_value(), "faulty_panic");
+    assert_eq!(metric.get_counter().get_value(), 1.0);
+
+    // The server keeps serving other requests.
+    let response: String = client.request("test_foo", rpc_params!(true)).await.unwrap();
+    assert_eq!("Some string", response);
+}
+
+fn queue_depth(registry: &Registry, tier: &str) -> f64 {
+    registry
+        .gather()
+        .into_iter()
+        .find(|family| family.get_name() == "rpc_scheduler_queue_depth")
+        .unwrap()
+        .get_metric()
+        .iter()
+        .find(|metric| metric.get_label()[0].get_value() == tier)
+        .unwrap()
+        .get_gauge()
+        .get_value()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_light_queries_not_starved_by_heavy_queries() {
+    let registry = Registry::new();
+    let mut builder = JsonRpcServerBuilder::new("1.5", &registry);
+    builder
+        .register_module(LoadApiModule {
+            connections: Arc::new(Semaphore::new(CONNECTIONS)),
+        })
+        .unwrap();
+    builder.set_query_scheduler(QuerySchedulerConfig {
+        heavy_methods: BTreeSet::from(["load_scan".to_string()]),
+        heavy_concurrency: CONNECTIONS - 2,
+        light_concurrency: CONNECTIONS,
+    });
+
+    let address = local_ip_utils::new_local_tcp_socket_for_testing();
+    let _handle = builder.start(address, None, None).await.unwrap();
+    let client = Arc::new(
+        HttpClientBuilder::default()
+            .build(format!("http://0.0.0.0:{}", address.port()))
+            .unwrap(),
+    );
+
+    // Ten times more scans than the heavy tier admits at once, keeping it saturated for seconds.
+    let scans: Vec<_> = (0..10 * CONNECTIONS)
+        .map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move { client.request::<(), _>("load_scan", rpc_params!()).await })
+        })
+        .collect();
+    while queue_depth(&registry, "heavy") == 0.0 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let mut latencies = vec![];
+    for _ in 0..100 {
+        let start = Instant::now();
+        let () = client.request("load_get", rpc_params!()).await.unwrap();
+        latencies.push(start.elapsed());
+    }
+    assert!(queue_depth(&registry, "heavy") > 0.0);
+
+    // Waiting for a connection held by a scan would take the whole scan.
+    latencies.sort();
+    let p99 = latencies[98];
+    assert!(
+        p99 < SCAN_DURATION / 2,
+        "Light query p99 latency of {p99:?} under heavy load"
+    );
+
+    for scan in scans {
+        scan.await.unwrap().unwrap();
+    }
+    assert_eq!(queue_depth(&registry, "heavy"), 0.0);
+    assert_eq!(queue_depth(&registry, "light"), 0.0);
+}
+
+fn throttled(registry: &Registry, method: &str, limit: &str) -> f64 {
+    registry
+        .gather()
+        .into_iter()
+        .find(|family| family.get_name() == "rpc_throttled_requests")
+        .and_then(|family| {
+            family
+                .get_metric()
+                .iter()
+                .find(|metric| {
+                    metric.get_label()[0].get_value() == method
+                        && metric.get_label()[1].get_value() == limit
+                })
+                .map(|metric| metric.get_counter().get_value())
+        })
+        .unwrap_or_default()
+}
+
+/// A client of `address` identified as `ip` by the proxy header the server trusts.
+fn client_as(address: std::net::SocketAddr, ip: &str) -> jsonrpsee::http_client::HttpClient {
+    let mut headers = HeaderMap::new();
+    headers.insert("x-forwarded-for", HeaderValue::from_str(ip).unwrap());
+    HttpClientBuilder::default()
+        .set_headers(headers)
+        .build(format!("http://0.0.0.0:{}", address.port()))
+        .unwrap()
+}
+
+/// Asserts `result` was refused by the rate limiter, returning its retry hint.
+fn assert_throttled<T: std::fmt::Debug>(result: Result<T, RpcError>) -> u64 {
+    let error = result.unwrap_err();
+    let RpcError::Call(CallError::Custom(error)) = error else {
+        panic!("Unexpected error {error:?}");
+    };
+    assert_eq!(error.code(), SERVER_OVERLOADED_CODE);
+    let data: ThrottledData = serde_json::from_str(error.data().unwrap().get()).unwrap();
+    data.retry_after_ms
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rate_limits_spare_other_clients_and_methods() {
+    let registry = Registry::new();
+    let mut builder = JsonRpcServerBuilder::new("1.5", &registry);
+    builder.register_module(ThrottleApiModule).unwrap();
+    builder.set_rate_limiter(RateLimiterConfig {
+        weight_per_second: Some(10),
+        burst: 50,
+        method_limits: BTreeMap::from([(
+            "throttle_scan".to_string(),
+            MethodLimits {
+                weight: None,
+                max_concurrent: Some(2),
+            },
+        )]),
+        use_forwarded_for: true,
+        ..Default::default()
+    });
+
+    let address = local_ip_utils::new_local_tcp_socket_for_testing();
+    let _handle = builder.start(address, None, None).await.unwrap();
+    let abusive = Arc::new(client_as(address, "10.0.0.1"));
+    let other = client_as(address, "10.0.0.2");
+
+    // Ten times more scans than may run at once are refused right away rather than queued,
+    // while other methods are still served.
+    let scans: Vec<_> = (0..20)
+        .map(|_| {
+            let abusive = abusive.clone();
+            tokio::spawn(
+                async move { abusive.request::<(), _>("throttle_scan", rpc_params!()).await },
+            )
+        })
+        .collect();
+    let () = other.request("throttle_get", rpc_params!()).await.unwrap();
+    let mut admitted = 0;
+    for scan in scans {
+        match scan.await.unwrap() {
+            Ok(()) => admitted += 1,
+            result => assert!(assert_throttled(result) > 0),
+        }
+    }
+    assert!(admitted >= 2);
+    assert!(admitted < 20);
+    assert_eq!(
+        throttled(&registry, "throttle_scan", "concurrency"),
+        (20 - admitted) as f64
+    );
+
+    // Once the abusive client spent its burst, it is told when it may retry, and other
+    // clients are served as usual.
+    let mut retry_after_ms = None;
+    for _ in 0..100 {
+        let result = abusive.request::<(), _>("throttle_get", rpc_params!()).await;
+        if result.is_err() {
+            retry_after_ms = Some(assert_throttled(result));
+            break;
+        }
+    }
+    let retry_after_ms = retry_after_ms.expect("the abusive client should be rate limited");
+    assert!(retry_after_ms > 0 && retry_after_ms <= 100);
+    assert!(throttled(&registry, "throttle_get", "rate") >= 1.0);
+    let () = other.request("throttle_get", rpc_params!()).await.unwrap();
+    let () = other.request("throttle_scan", rpc_params!()).await.unwrap();
+
+    // The client may retry once its bucket refilled.
+    tokio::time::sleep(Duration::from_millis(retry_after_ms)).await;
+    let () = abusive.request("throttle_get", rpc_params!()).await.unwrap();
+}
+
+const WALLET_ORIGIN: &str = "https://wallet.example.com";
+const DAPP_ORIGIN: &str = "https://dapp.example.com";
+const OTHER_ORIGIN: &str = "https://other.example.com";
+
+/// The origin the server at `url` allows a browser at `origin` to read responses from, when
+/// preflighting a request with the `x-req-id` header.
+async fn preflight(url: &str, origin: &str) -> Option<String> {
+    let response = reqwest::Client::new()
+        .request(reqwest::Method::OPTIONS, url)
+        .header("origin", origin)
+        .header("access-control-request-method", "POST")
+        .header("access-control-request-headers", "content-type,x-req-id")
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let allowed_headers = response
+        .headers()
+        .get("access-control-allow-headers")
+        .unwrap()
+        .to_str()
+        .unwrap();
+    assert!(allowed_headers.contains("x-req-id"), "{allowed_headers}");
+    response
+        .headers()
+        .get("access-control-allow-origin")
+        .map(|origin| origin.to_str().unwrap().to_string())
+}
+
+/// Calls `method` at `url` from a browser at `origin`, returning the origin allowed to read the
+/// response and the response.
+async fn call_from(url: &str, origin: &str, method: &str) -> (Option<String>, serde_json::Value) {
+    let response = reqwest::Client::new()
+        .post(url)
+        .header("origin", origin)
+        .json(&serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": [],
+        }))
+        .send()
+        .await
+        .unwrap();
+    let allowed_origin = response
+        .headers()
+        .get("access-control-allow-origin")
+        .map(|origin| origin.to_str().unwrap().to_string());
+    (allowed_origin, response.json().await.unwrap())
+}
+
+#[tokio::test]
+async fn test_cors_origins_and_methods() {
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(ThrottleApiModule).unwrap();
+    builder.set_cors(CorsConfig {
+        allowed_origins: vec![WALLET_ORIGIN.to_string(), DAPP_ORIGIN.to_string()],
+        allowed_headers: vec!["x-req-id".to_string()],
+        origin_methods: BTreeMap::from([
+            ("*".to_string(), BTreeSet::from(["throttle_get".to_string()])),
+            (
+                WALLET_ORIGIN.to_string(),
+                BTreeSet::from(["throttle_get".to_string(), "throttle_scan".to_string()]),
+            ),
+        ]),
+    });
+
+    let address = local_ip_utils::new_local_tcp_socket_for_testing();
+    let _handle = builder.start(address, None, None).await.unwrap();
+    let url = format!("http://0.0.0.0:{}", address.port());
+
+    // Preflight requests are answered with the origin of allowed browsers only.
+    let allowed_origin = preflight(&url, WALLET_ORIGIN).await;
+    assert_eq!(allowed_origin.as_deref(), Some(WALLET_ORIGIN));
+    let allowed_origin = preflight(&url, DAPP_ORIGIN).await;
+    assert_eq!(allowed_origin.as_deref(), Some(DAPP_ORIGIN));
+    assert_eq!(preflight(&url, OTHER_ORIGIN).await, None);
+
+    // Allowed origins may call the methods they are restricted to.
+    let (allowed_origin, response) = call_from(&url, WALLET_ORIGIN, "throttle_scan").await;
+    assert_eq!(allowed_origin.as_deref(), Some(WALLET_ORIGIN));
+    assert!(response["error"].is_null(), "{response}");
+    let (allowed_origin, response) = call_from(&url, DAPP_ORIGIN, "throttle_get").await;
+    assert_eq!(allowed_origin.as_deref(), Some(DAPP_ORIGIN));
+    assert!(response["error"].is_null(), "{response}");
+
+    // Other methods are refused before they are dispatched.
+    let (_, response) = call_from(&url, DAPP_ORIGIN, "throttle_scan").await;
+    assert_eq!(
+        response["error"]["code"],
+        METHOD_NOT_ALLOWED_FOR_ORIGIN_CODE
+    );
+    assert_eq!(
+        response["error"]["message"],
+        format!("Method `throttle_scan` may not be called from origin `{DAPP_ORIGIN}`")
+    );
+
+    // Browsers may not read responses from other origins, which are restricted like any
+    // origin without methods of its own.
+    let (allowed_origin, response) = call_from(&url, OTHER_ORIGIN, "throttle_scan").await;
+    assert_eq!(allowed_origin, None);
+    assert_eq!(
+        response["error"]["code"],
+        METHOD_NOT_ALLOWED_FOR_ORIGIN_CODE
+    );
+
+    // Requests without an origin are not sent by browsers, and are not restricted.
+    let client = HttpClientBuilder::default().build(&url).unwrap();
+    let () = client.request("throttle_scan", rpc_params!()).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_misconfigured_cors_rejected_at_startup() {
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(ThrottleApiModule).unwrap();
+    builder.set_cors(CorsConfig {
+        allowed_origins: vec!["https://wallet.example.com/".to_string()],
+        ..Default::default()
+    });
+    let address = local_ip_utils::new_local_tcp_socket_for_testing();
+    let Err(error) = builder.start(address, None, None).await else {
+        panic!("The server should not start with a misconfigured origin");
+    };
+    let expected = "Invalid CORS configuration: Invalid origin `https://wallet.example.com/`";
+    assert!(error.to_string().starts_with(expected), "{error}");
+
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(ThrottleApiModule).unwrap();
+    builder.set_cors(CorsConfig {
+        origin_methods: BTreeMap::from([(
+            WALLET_ORIGIN.to_string(),
+            BTreeSet::from(["throttle_put".to_string()]),
+        )]),
+        ..Default::default()
+    });
+    let Err(error) = builder.to_router(None) else {
+        panic!("The server should not start with an unknown method");
+    };
+    assert!(error.to_string().contains("Unknown method `throttle_put`"), "{error}");
+}
+
 // TODO(chris): clean up this after March 27th, 2023
 // #[tokio::test]
 // async fn test_rpc_backward_compatibility_batched_request() {
@@ -229,3 +705,112 @@ impl MgoRpcModule for TestApiModule {
         TestApiOpenRpc::module_doc()
     }
 }
+
+#[open_rpc(namespace = "faulty")]
+#[rpc(server, client, namespace = "faulty")]
+trait FaultyApi {
+    #[method(name = "panic")]
+    async fn panic(&self) -> RpcResult<String>;
+}
+
+struct FaultyApiModule;
+
+#[async_trait]
+impl FaultyApiServer for FaultyApiModule {
+    async fn panic(&self) -> RpcResult<String> {
+        panic!("handler blew up")
+    }
+}
+
+impl MgoRpcModule for FaultyApiModule {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+    fn rpc_doc_module() -> Module {
+        FaultyApiOpenRpc::module_doc()
+    }
+}
+
+/// Connections of the database that the load API simulates.
+const CONNECTIONS: usize = 8;
+const SCAN_DURATION: Duration = Duration::from_millis(500);
+const GET_DURATION: Duration = Duration::from_millis(5);
+
+#[open_rpc(namespace = "load")]
+#[rpc(server, client, namespace = "load")]
+trait LoadApi {
+    #[method(name = "scan")]
+    async fn scan(&self) -> RpcResult<()>;
+
+    #[method(name = "get")]
+    async fn get(&self) -> RpcResult<()>;
+}
+
+/// Holds one of its connections for the duration of each query.
+struct LoadApiModule {
+    connections: Arc<Semaphore>,
+}
+
+impl LoadApiModule {
+    async fn query(&self, duration: Duration) -> RpcResult<()> {
+        let _connection = self.connections.acquire().await.unwrap();
+        tokio::time::sleep(duration).await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LoadApiServer for LoadApiModule {
+    async fn scan(&self) -> RpcResult<()> {
+        self.query(SCAN_DURATION).await
+    }
+
+    async fn get(&self) -> RpcResult<()> {
+        self.query(GET_DURATION).await
+    }
+}
+
+impl MgoRpcModule for LoadApiModule {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+    fn rpc_doc_module() -> Module {
+        LoadApiOpenRpc::module_doc()
+    }
+}
+
+#[open_rpc(namespace = "throttle")]
+#[rpc(server, client, namespace = "throttle")]
+trait ThrottleApi {
+    #[method(name = "scan")]
+    async fn scan(&self) -> RpcResult<()>;
+
+    #[method(name = "get")]
+    async fn get(&self) -> RpcResult<()>;
+}
+
+struct ThrottleApiModule;
+
+#[async_trait]
+impl ThrottleApiServer for ThrottleApiModule {
+    async fn scan(&self) -> RpcResult<()> {
+        tokio::time::sleep(SCAN_DURATION).await;
+        Ok(())
+    }
+
+    async fn get(&self) -> RpcResult<()> {
+        Ok(())
+    }
+}
+
+impl MgoRpcModule for ThrottleApiModule {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+    fn rpc_doc_module() -> Module {
+        ThrottleApiOpenRpc::module_doc()
+    }
+    fn rpc_method_weights() -> Vec<(&'static str, u32)> {
+        vec![("throttle_scan", 5)]
+    }
+}
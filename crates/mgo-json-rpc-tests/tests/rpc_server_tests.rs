@@ -6,29 +6,56 @@ use std::path::{Path, PathBuf};
 #[cfg(not(msim))]
 use std::str::FromStr;
 use std::time::Duration;
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::Error as RpcError;
+use jsonrpsee::rpc_params;
+use jsonrpsee::types::error::CallError;
+use mgo_config::node::JsonRpcResponseSigningConfig;
 use mgo_json::{call_args, type_args};
 use mgo_json_rpc_api::{
-    CoinReadApiClient, GovernanceReadApiClient, IndexerApiClient, ReadApiClient,
-    TransactionBuilderClient, WriteApiClient,
+    ClientErrorCode, CoinReadApiClient, DebugApiClient, GovernanceReadApiClient, IndexerApiClient,
+    ReadApiClient, SupportApiClient, TransactionBuilderClient, WriteApiClient,
+    QUERY_MAX_RESULT_LIMIT, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS,
 };
 use mgo_json_rpc_types::ObjectChange;
+use mgo_json_rpc_types::{
+    get_new_package_obj_from_response, get_new_package_upgrade_cap_from_response,
+};
 use mgo_json_rpc_types::ObjectsPage;
 use mgo_json_rpc_types::{
-    Balance, CoinPage, DelegatedStake, StakeStatus, MgoCoinMetadata, MgoExecutionStatus,
-    MgoObjectDataOptions, MgoObjectResponse, MgoObjectResponseQuery, MgoTransactionBlockEffectsAPI,
-    MgoTransactionBlockResponse, MgoTransactionBlockResponseOptions, TransactionBlockBytes,
+    filter_hash, Balance, Checkpoint, CheckpointBcsV1, CheckpointId, CheckpointMismatch,
+    CheckpointMismatchKind, CoinPage, DelegatedStake, MgoCoinMetadata, MgoExecutionStatus,
+    MgoObjectDataOptions, MgoObjectResponse, MgoObjectResponseQuery,
+    MgoTransactionBlockEffectsAPI, MgoTransactionBlockResponse, MgoTransactionBlockResponseOptions,
+    MgoTransactionBlockResponseQuery, NoStorageRebateReason, ObjectReadBcsV1, ResponseEncoding,
+    StakeStatus, TransactionBlockBytes, TransactionFilter,
 };
+use mgo_json_rpc_types::verify_committee_handoffs;
+use mgo_json_rpc_types::{verify_signed_response, RESPONSE_SIGNATURE_FIELD};
+use mgo_keys::keypair_file::write_keypair_to_file;
 use mgo_macros::sim_test;
 use mgo_move_build::BuildConfig;
+use rand::rngs::OsRng;
 use mgo_swarm_config::genesis_config::{DEFAULT_GAS_AMOUNT, DEFAULT_NUMBER_OF_OBJECT_PER_ACCOUNT};
 use mgo_types::balance::Supply;
 use mgo_types::base_types::ObjectID;
 use mgo_types::base_types::SequenceNumber;
+use mgo_types::base_types::MgoAddress;
+use mgo_types::crypto::{get_key_pair, MgoKeyPair};
 use mgo_types::coin::{TreasuryCap, COIN_MODULE_NAME};
-use mgo_types::digests::ObjectDigest;
+use mgo_types::digests::{ObjectDigest, TransactionDigest};
 use mgo_types::gas_coin::GAS;
 use mgo_types::quorum_driver_types::ExecuteTransactionRequestType;
-use mgo_types::{parse_mgo_struct_tag, MGO_FRAMEWORK_ADDRESS};
+use mgo_test_transaction_builder::{
+    make_staking_transaction, make_transfer_mgo_transaction, TestTransactionBuilder,
+};
+use mgo_types::move_package::UpgradePolicy;
+use mgo_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use mgo_types::transaction::{ObjectArg, TransactionDataAPI};
+use mgo_types::{
+    parse_mgo_struct_tag, Identifier, MGO_CLOCK_OBJECT_ID, MGO_FRAMEWORK_ADDRESS,
+    MGO_FRAMEWORK_PACKAGE_ID,
+};
 use test_cluster::TestClusterBuilder;
 use tokio::time::sleep;
 
@@ -47,6 +74,7 @@ async fn test_get_objects() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?;
     assert_eq!(5, objects.data.len());
@@ -58,11 +86,264 @@ async fn test_get_objects() -> Result<(), anyhow::Error> {
         .map(|o| o.object().unwrap().object_id)
         .collect();
 
-    let object_resp = http_client.multi_get_objects(object_digests, None).await?;
+    let object_resp = http_client
+        .multi_get_objects(object_digests, None, None)
+        .await?
+        .into_json()?;
     assert_eq!(5, object_resp.len());
     Ok(())
 }
 
+#[sim_test]
+async fn test_paginated_request_echo() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+
+    let http_client = cluster.rpc_client();
+    let address = cluster.get_address_0();
+
+    let objects = http_client
+        .get_owned_objects(address, None, None, Some(2), None)
+        .await?;
+    assert!(objects.applied.is_none());
+
+    let objects = http_client
+        .get_owned_objects(address, None, None, Some(2), Some(true))
+        .await?;
+    let applied = objects.applied.unwrap();
+    assert_eq!(applied.cursor, None);
+    assert_eq!(applied.limit, 2);
+    assert!(!applied.descending);
+
+    // The cursor is echoed, and the filter hash stays the same across the pages of a query.
+    let next = http_client
+        .get_owned_objects(address, None, objects.next_cursor, Some(2), Some(true))
+        .await?;
+    let next_applied = next.applied.unwrap();
+    assert_eq!(next_applied.cursor, objects.next_cursor);
+    assert_eq!(next_applied.filter_hash, applied.filter_hash);
+
+    // Limits above the maximum are capped, and the capped limit is echoed.
+    let filter = TransactionFilter::FromAddress(address);
+    let transactions = http_client
+        .query_transaction_blocks(
+            MgoTransactionBlockResponseQuery::new_with_filter(filter.clone()),
+            None,
+            Some(*QUERY_MAX_RESULT_LIMIT + 1),
+            Some(true),
+            Some(true),
+        )
+        .await?;
+    let applied = transactions.applied.unwrap();
+    assert_eq!(applied.limit, *QUERY_MAX_RESULT_LIMIT);
+    assert!(applied.descending);
+    assert_eq!(applied.filter_hash, filter_hash(&Some(filter)));
+    assert_ne!(applied.filter_hash, next_applied.filter_hash);
+    Ok(())
+}
+
+#[sim_test]
+async fn test_multi_get_objects_bcs() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+    let http_client = cluster.rpc_client();
+    let address = cluster.get_address_0();
+
+    let objects = http_client
+        .get_owned_objects(
+            address,
+            Some(MgoObjectResponseQuery::new_with_options(
+                MgoObjectDataOptions::new().with_owner(),
+            )),
+            None,
+            None,
+            None,
+        )
+        .await?
+        .data;
+    let mut object_ids: Vec<ObjectID> = objects
+        .iter()
+        .map(|o| o.object().unwrap().object_id)
+        .collect();
+    let missing_id = ObjectID::random();
+    object_ids.push(missing_id);
+
+    let json_objects = http_client
+        .multi_get_objects(
+            object_ids.clone(),
+            Some(MgoObjectDataOptions::new().with_owner()),
+            None,
+        )
+        .await?
+        .into_json()?;
+    let bcs_objects: Vec<ObjectReadBcsV1> = http_client
+        .multi_get_objects(object_ids, None, Some(ResponseEncoding::Bcs))
+        .await?
+        .into_bcs()?
+        .decode()?;
+
+    assert_eq!(bcs_objects.len(), json_objects.len());
+    for (bcs_object, json_object) in bcs_objects.iter().zip(&json_objects[..objects.len()]) {
+        let ObjectReadBcsV1::Exists(object) = bcs_object else {
+            panic!("Expected object to exist, got {bcs_object:?}");
+        };
+        let data = json_object.object()?;
+        assert_eq!(object.compute_object_reference(), data.object_ref());
+        assert_eq!(Some(object.owner), data.owner);
+    }
+    assert_eq!(
+        bcs_objects.last(),
+        Some(&ObjectReadBcsV1::NotExists(missing_id))
+    );
+    Ok(())
+}
+
+#[sim_test]
+async fn test_get_checkpoint_bcs() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+    let http_client = cluster.rpc_client();
+
+    let id = CheckpointId::SequenceNumber(0);
+    let json_checkpoint = http_client.get_checkpoint(id, None).await?.into_json()?;
+    let CheckpointBcsV1 { summary, contents } = http_client
+        .get_checkpoint(id, Some(ResponseEncoding::Bcs))
+        .await?
+        .into_bcs()?
+        .decode()?;
+
+    let signature = summary.auth_sig().signature.clone();
+    assert_eq!(
+        Checkpoint::from((summary.into_data(), contents, signature)),
+        json_checkpoint
+    );
+    Ok(())
+}
+
+#[sim_test]
+async fn test_get_checkpoints_of_epoch() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+    cluster.trigger_reconfiguration().await;
+    let http_client = cluster.rpc_client();
+
+    // Page through the checkpoints of epoch 0 from its end.
+    let mut checkpoints = vec![];
+    let mut cursor = None;
+    loop {
+        let page = http_client
+            .get_checkpoints(cursor, Some(2), true, Some(0.into()))
+            .await?;
+        checkpoints.extend(page.data);
+        if !page.has_next_page {
+            break;
+        }
+        cursor = page.next_cursor;
+    }
+
+    assert!(checkpoints.iter().all(|checkpoint| checkpoint.epoch == 0));
+    assert!(checkpoints[0].end_of_epoch_data.is_some());
+    assert_eq!(checkpoints.last().unwrap().sequence_number, 0);
+    assert!(checkpoints
+        .windows(2)
+        .all(|pair| pair[0].sequence_number == pair[1].sequence_number + 1));
+
+    let epoch_1 = http_client
+        .get_checkpoints(None, None, false, Some(1.into()))
+        .await?;
+    assert!(epoch_1.data.iter().all(|checkpoint| checkpoint.epoch == 1));
+    assert_eq!(
+        epoch_1
+            .data
+            .first()
+            .map(|checkpoint| checkpoint.sequence_number),
+        Some(checkpoints[0].sequence_number + 1)
+    );
+    Ok(())
+}
+
+#[sim_test]
+async fn test_get_epoch_start_info() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+    cluster.trigger_reconfiguration().await;
+    let http_client = cluster.rpc_client();
+
+    let genesis = http_client.get_epoch_start_info(0.into()).await?;
+    assert_eq!(genesis.first_checkpoint.sequence_number, 0);
+    assert!(genesis.previous_committee.is_none());
+    assert!(genesis.previous_epoch_last_checkpoint.is_none());
+
+    let epoch_1 = http_client.get_epoch_start_info(1.into()).await?;
+    assert_eq!(epoch_1.first_checkpoint.epoch, 1);
+    let last_checkpoint = epoch_1.previous_epoch_last_checkpoint.as_ref().unwrap();
+    assert!(last_checkpoint.end_of_epoch_data.is_some());
+    assert_eq!(
+        epoch_1.first_checkpoint.sequence_number,
+        last_checkpoint.sequence_number + 1
+    );
+
+    // The genesis committee hands over to the committee of epoch 1.
+    let epochs = [epoch_1.clone()];
+    let committee = verify_committee_handoffs(&genesis.committee, &epochs)?;
+    assert_eq!(committee.epoch, 1);
+
+    // A handoff that skips the trusted committee is rejected.
+    let error = verify_committee_handoffs(&epoch_1.committee, &epochs).unwrap_err();
+    assert!(error.to_string().starts_with("Epoch 1: "), "{error}");
+
+    let RpcError::Call(CallError::Custom(error)) = http_client
+        .get_epoch_start_info(2.into())
+        .await
+        .unwrap_err()
+    else {
+        panic!("Expected a client error");
+    };
+    assert_eq!(error.code(), ClientErrorCode::EpochNotFound.code());
+    Ok(())
+}
+
+#[sim_test]
+async fn test_client_error_codes() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+    let http_client = cluster.rpc_client();
+
+    fn client_error(error: RpcError) -> (ClientErrorCode, serde_json::Value) {
+        let RpcError::Call(CallError::Custom(error)) = error else {
+            panic!("Unexpected error {error:?}");
+        };
+        let data: serde_json::Value = serde_json::from_str(error.data().unwrap().get()).unwrap();
+        (ClientErrorCode::from_code(error.code()).unwrap(), data)
+    }
+
+    // A cursor that is not a transaction of the checkpoint.
+    let cursor = TransactionDigest::random();
+    let error = http_client
+        .get_checkpoint_transactions(0.into(), Some(cursor), None)
+        .await
+        .unwrap_err();
+    let (code, data) = client_error(error);
+    assert_eq!(code, ClientErrorCode::InvalidCursor);
+    assert_eq!(data["code"], "INVALID_CURSOR");
+    assert_eq!(data["cursor"], cursor.to_string());
+    assert_eq!(data["checkpoint"], 0);
+
+    let error = http_client
+        .get_checkpoint_transactions(u64::MAX.into(), None, None)
+        .await
+        .unwrap_err();
+    let (code, data) = client_error(error);
+    assert_eq!(code, ClientErrorCode::CheckpointNotFound);
+    assert_eq!(data["code"], "CHECKPOINT_NOT_FOUND");
+
+    let limit = QUERY_MAX_RESULT_LIMIT_CHECKPOINTS + 1;
+    let error = http_client
+        .get_checkpoints(None, Some(limit), false, None)
+        .await
+        .unwrap_err();
+    let (code, data) = client_error(error);
+    assert_eq!(code, ClientErrorCode::LimitExceeded);
+    assert_eq!(data["code"], "LIMIT_EXCEEDED");
+    assert_eq!(data["limit"], limit);
+    assert_eq!(data["max"], QUERY_MAX_RESULT_LIMIT_CHECKPOINTS);
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_get_package_with_display_should_not_fail() -> Result<(), anyhow::Error> {
     let cluster = TestClusterBuilder::new().build().await;
@@ -102,6 +383,7 @@ async fn test_public_transfer_object() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?
         .data;
@@ -184,6 +466,7 @@ async fn test_publish() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?;
     let gas = objects.data.first().unwrap().object().unwrap();
@@ -238,6 +521,7 @@ async fn test_move_call() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?
         .data;
@@ -298,6 +582,7 @@ async fn test_get_object_info() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?
         .data;
@@ -330,6 +615,7 @@ async fn test_get_object_data_with_content() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?
         .data;
@@ -355,24 +641,47 @@ async fn test_get_coins() -> Result<(), anyhow::Error> {
     let http_client = cluster.rpc_client();
     let address = cluster.get_address_0();
 
-    let result: CoinPage = http_client.get_coins(address, None, None, None).await?;
+    let result: CoinPage = http_client
+        .get_coins(address, None, None, None, None, None)
+        .await?;
     assert_eq!(5, result.data.len());
     assert!(!result.has_next_page);
 
     let result: CoinPage = http_client
-        .get_coins(address, Some("0x2::mgo::TestCoin".into()), None, None)
+        .get_coins(
+            address,
+            Some("0x2::mgo::TestCoin".into()),
+            None,
+            None,
+            None,
+            None,
+        )
         .await?;
     assert_eq!(0, result.data.len());
 
     let result: CoinPage = http_client
-        .get_coins(address, Some("0x2::mgo::MGO".into()), None, None)
+        .get_coins(
+            address,
+            Some("0x2::mgo::MGO".into()),
+            None,
+            None,
+            None,
+            None,
+        )
         .await?;
     assert_eq!(5, result.data.len());
     assert!(!result.has_next_page);
 
     // Test paging
     let result: CoinPage = http_client
-        .get_coins(address, Some("0x2::mgo::MGO".into()), None, Some(3))
+        .get_coins(
+            address,
+            Some("0x2::mgo::MGO".into()),
+            None,
+            Some(3),
+            None,
+            None,
+        )
         .await?;
     assert_eq!(3, result.data.len());
     assert!(result.has_next_page);
@@ -383,6 +692,8 @@ async fn test_get_coins() -> Result<(), anyhow::Error> {
             Some("0x2::mgo::MGO".into()),
             result.next_cursor,
             Some(3),
+            None,
+            None,
         )
         .await?;
     assert_eq!(2, result.data.len(), "{:?}", result);
@@ -394,6 +705,8 @@ async fn test_get_coins() -> Result<(), anyhow::Error> {
             Some("0x2::mgo::MGO".into()),
             result.next_cursor,
             None,
+            None,
+            None,
         )
         .await?;
     assert_eq!(0, result.data.len(), "{:?}", result);
@@ -402,6 +715,109 @@ async fn test_get_coins() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_get_coins_with_totals() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+    let http_client = cluster.rpc_client();
+    let address = cluster.get_address_0();
+
+    // Totals are only returned on request.
+    let result: CoinPage = http_client
+        .get_coins(address, None, None, Some(1), None, None)
+        .await?;
+    assert_eq!(result.totals, None);
+    assert!(!result.totals_unavailable);
+
+    let first_page: CoinPage = http_client
+        .get_coins(address, None, None, Some(1), Some(true), None)
+        .await?;
+    let totals = first_page.totals.clone().unwrap();
+    assert_eq!(1, totals.len());
+
+    // The totals cover every coin of the type, not only the ones on the page.
+    let mut coins = first_page.data;
+    let mut cursor = first_page.next_cursor;
+    let mut has_next_page = first_page.has_next_page;
+    while has_next_page {
+        let page: CoinPage = http_client
+            .get_coins(address, None, cursor, Some(1), None, None)
+            .await?;
+        coins.extend(page.data);
+        cursor = page.next_cursor;
+        has_next_page = page.has_next_page;
+    }
+    assert_eq!(coins.len(), totals[0].coin_object_count);
+    assert_eq!(
+        coins.iter().map(|coin| coin.balance as u128).sum::<u128>(),
+        totals[0].total_balance
+    );
+
+    let result: CoinPage = http_client
+        .get_all_coins(address, None, Some(1), Some(true))
+        .await?;
+    assert_eq!(result.totals, Some(totals));
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_get_coins_with_min_balance_and_select_coins() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+    let http_client = cluster.rpc_client();
+    let address = cluster.get_address_0();
+
+    // Every genesis coin holds the same amount.
+    let result: CoinPage = http_client
+        .get_coins(
+            address,
+            None,
+            None,
+            None,
+            None,
+            Some(DEFAULT_GAS_AMOUNT.into()),
+        )
+        .await?;
+    assert_eq!(DEFAULT_NUMBER_OF_OBJECT_PER_ACCOUNT, result.data.len());
+    let result: CoinPage = http_client
+        .get_coins(
+            address,
+            None,
+            None,
+            None,
+            None,
+            Some((DEFAULT_GAS_AMOUNT + 1).into()),
+        )
+        .await?;
+    assert!(result.data.is_empty());
+
+    // Coins of equal balance are selected in the order of their IDs, the same on every call.
+    let target_amount = DEFAULT_GAS_AMOUNT + 1;
+    let selected = http_client
+        .select_coins(address, None, target_amount.into(), None)
+        .await?;
+    let ids: Vec<_> = selected.iter().map(|coin| coin.coin_object_id).collect();
+    assert_eq!(2, ids.len());
+    assert!(ids[0] < ids[1]);
+    let again = http_client
+        .select_coins(address, None, target_amount.into(), None)
+        .await?;
+    assert_eq!(again, selected);
+
+    let total = DEFAULT_NUMBER_OF_OBJECT_PER_ACCOUNT as u64 * DEFAULT_GAS_AMOUNT;
+    let error = http_client
+        .select_coins(address, None, (total + 1).into(), None)
+        .await
+        .unwrap_err();
+    let RpcError::Call(CallError::Custom(error)) = error else {
+        panic!("Unexpected error {error:?}");
+    };
+    assert_eq!(error.code(), ClientErrorCode::InvalidInput.code());
+    let data: serde_json::Value = serde_json::from_str(error.data().unwrap().get()).unwrap();
+    assert_eq!(data["shortfall"], 1);
+
+    Ok(())
+}
+
 #[sim_test]
 async fn test_get_balance() -> Result<(), anyhow::Error> {
     let cluster = TestClusterBuilder::new().build().await;
@@ -441,6 +857,7 @@ async fn test_get_metadata() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?
         .data;
@@ -526,6 +943,7 @@ async fn test_get_total_supply() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?
         .data;
@@ -663,6 +1081,7 @@ async fn test_staking() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?;
     assert_eq!(5, objects.data.len());
@@ -722,6 +1141,135 @@ async fn test_staking() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_estimate_storage_rebate() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+    let http_client = cluster.rpc_client();
+    let address = cluster.get_address_0();
+
+    let objects: ObjectsPage = http_client
+        .get_owned_objects(address, None, None, None, None)
+        .await?;
+    let object_id = objects.data[0].object()?.object_id;
+    let object = http_client
+        .get_object(object_id, Some(MgoObjectDataOptions::bcs_lossless()))
+        .await?
+        .into_object()?;
+
+    let estimate = http_client
+        .estimate_storage_rebate(object_id, Some(1_000.into()))
+        .await?;
+    assert_eq!(object_id, estimate.object_id);
+    assert_eq!(object.version, estimate.version);
+    assert_eq!(object.storage_rebate, Some(estimate.storage_rebate));
+    assert!(estimate.deletion_rebate <= estimate.storage_rebate);
+    assert_eq!(None, estimate.no_rebate_reason);
+    let new_storage_cost = estimate.new_storage_cost.unwrap();
+    assert!(new_storage_cost > estimate.storage_rebate);
+    assert_eq!(
+        Some(estimate.deletion_rebate as i64 - new_storage_cost as i64),
+        estimate.mutation_rebate_delta
+    );
+
+    let estimate = http_client
+        .estimate_storage_rebate(MGO_CLOCK_OBJECT_ID, None)
+        .await?;
+    assert_eq!(
+        Some(NoStorageRebateReason::SystemObject),
+        estimate.no_rebate_reason
+    );
+    assert_eq!(0, estimate.deletion_rebate);
+
+    let estimate = http_client
+        .estimate_storage_rebate(MGO_FRAMEWORK_PACKAGE_ID, None)
+        .await?;
+    assert_eq!(
+        Some(NoStorageRebateReason::Immutable),
+        estimate.no_rebate_reason
+    );
+
+    let error = http_client
+        .estimate_storage_rebate(ObjectID::random(), None)
+        .await
+        .unwrap_err();
+    let (code, _) = client_error(error);
+    assert_eq!(code, ClientErrorCode::ObjectNotFound);
+    Ok(())
+}
+
+#[sim_test]
+async fn test_owned_staked_objects_summary() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+
+    let http_client = cluster.rpc_client();
+    let address = cluster.get_address_0();
+
+    let summary = http_client.get_owned_staked_objects_summary(address).await?;
+    assert!(summary.validators.is_empty());
+    assert_eq!(0, summary.total_principal);
+    assert_eq!(0, summary.stake_count);
+
+    let objects: ObjectsPage = http_client
+        .get_owned_objects(address, None, None, None, None)
+        .await?;
+    let validators = http_client
+        .get_latest_mgo_system_state()
+        .await?
+        .active_validators;
+
+    // Two stakes with the first validator and one with the second.
+    for (object, validator) in objects.data[..3]
+        .iter()
+        .zip([&validators[0], &validators[0], &validators[1]])
+    {
+        let transaction_bytes: TransactionBlockBytes = http_client
+            .request_add_stake(
+                address,
+                vec![object.object()?.object_id],
+                Some(1000000000.into()),
+                validator.mgo_address,
+                None,
+                100_000_000.into(),
+            )
+            .await?;
+        let tx = cluster
+            .wallet
+            .sign_transaction(&transaction_bytes.to_data()?);
+        let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
+        http_client
+            .execute_transaction_block(
+                tx_bytes,
+                signatures,
+                Some(MgoTransactionBlockResponseOptions::new()),
+                Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+            )
+            .await?;
+    }
+
+    let summary = http_client.get_owned_staked_objects_summary(address).await?;
+    assert_eq!(address, summary.owner);
+    assert_eq!(3_000_000_000, summary.total_principal);
+    assert_eq!(0, summary.total_estimated_reward);
+    assert_eq!(3, summary.stake_count);
+    assert_eq!(2, summary.validators.len());
+    // Largest principal first.
+    for (validator_summary, (validator, stake_count)) in summary
+        .validators
+        .iter()
+        .zip([(&validators[0], 2), (&validators[1], 1)])
+    {
+        assert_eq!(validator.mgo_address, validator_summary.validator_address);
+        assert_eq!(validator.staking_pool_id, validator_summary.staking_pool);
+        assert_eq!(Some(&validator.name), validator_summary.name.as_ref());
+        assert_eq!(stake_count, validator_summary.stake_count);
+        assert_eq!(stake_count * 1_000_000_000, validator_summary.principal);
+        // Pending stakes activate at the next epoch and earn nothing yet.
+        assert_eq!(0, validator_summary.estimated_reward);
+        assert_eq!(1, validator_summary.earliest_activation_epoch);
+    }
+    Ok(())
+}
+
 #[ignore]
 #[sim_test]
 async fn test_unstaking() -> Result<(), anyhow::Error> {
@@ -733,7 +1281,9 @@ async fn test_unstaking() -> Result<(), anyhow::Error> {
     let http_client = cluster.rpc_client();
     let address = cluster.get_address_0();
 
-    let coins: CoinPage = http_client.get_coins(address, None, None, None).await?;
+    let coins: CoinPage = http_client
+        .get_coins(address, None, None, None, None, None)
+        .await?;
     assert_eq!(5, coins.data.len());
 
     // Check StakedMgo object before test
@@ -866,7 +1416,9 @@ async fn test_staking_multiple_coins() -> Result<(), anyhow::Error> {
     let http_client = cluster.rpc_client();
     let address = cluster.get_address_0();
 
-    let coins: CoinPage = http_client.get_coins(address, None, None, None).await?;
+    let coins: CoinPage = http_client
+        .get_coins(address, None, None, None, None, None)
+        .await?;
     assert_eq!(5, coins.data.len());
 
     let genesis_coin_amount = coins.data[0].balance;
@@ -940,7 +1492,9 @@ async fn test_staking_multiple_coins() -> Result<(), anyhow::Error> {
     ));
 
     // Coins should be merged into one and returned to the sender.
-    let coins: CoinPage = http_client.get_coins(address, None, None, None).await?;
+    let coins: CoinPage = http_client
+        .get_coins(address, None, None, None, None, None)
+        .await?;
     assert_eq!(3, coins.data.len());
 
     // Find the new coin
@@ -953,3 +1507,420 @@ async fn test_staking_multiple_coins() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[sim_test]
+async fn test_debug_gas_comparison() -> Result<(), anyhow::Error> {
+    let mut cluster = TestClusterBuilder::new().build().await;
+    let mut config = cluster
+        .fullnode_config_builder()
+        .build(&mut OsRng, cluster.swarm.config());
+    config.dry_run_gas_comparison_threshold_bps = Some(100);
+    let fullnode = cluster.start_fullnode_from_config(config).await;
+
+    let validator = cluster
+        .rpc_client()
+        .get_latest_mgo_system_state()
+        .await?
+        .active_validators[0]
+        .mgo_address;
+    let transfer = make_transfer_mgo_transaction(&cluster.wallet, None, Some(1)).await;
+    let stake = make_staking_transaction(&cluster.wallet, validator).await;
+
+    for tx in [transfer, stake] {
+        let (tx_bytes, _) = tx.to_tx_bytes_and_signatures();
+        let comparison = fullnode.rpc_client.debug_gas_comparison(tx_bytes).await?;
+        assert!(comparison.dry_run.computation_cost > 0);
+        assert!(comparison.dry_run.storage_cost > 0);
+        assert!(comparison.dev_inspect.computation_cost > 0);
+        assert!(comparison.dev_inspect.storage_cost > 0);
+    }
+
+    // Dry runs keep being answered by nodes comparing gas in the background.
+    let (tx_bytes, _) = make_transfer_mgo_transaction(&cluster.wallet, None, Some(1))
+        .await
+        .to_tx_bytes_and_signatures();
+    let dry_run = fullnode
+        .rpc_client
+        .dry_run_transaction_block(tx_bytes)
+        .await?;
+    assert_eq!(MgoExecutionStatus::Success, *dry_run.effects.status());
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_export_address_state() -> Result<(), anyhow::Error> {
+    let mut cluster = TestClusterBuilder::new().build().await;
+    let mut config = cluster
+        .fullnode_config_builder()
+        .build(&mut OsRng, cluster.swarm.config());
+    config.enable_support_api = true;
+    let fullnode = cluster.start_fullnode_from_config(config).await;
+
+    let validator = fullnode
+        .rpc_client
+        .get_latest_mgo_system_state()
+        .await?
+        .active_validators[0]
+        .mgo_address;
+    let stake = make_staking_transaction(&cluster.wallet, validator).await;
+    let address = stake.data().transaction_data().sender();
+    let (tx_bytes, signatures) = stake.to_tx_bytes_and_signatures();
+    let response = fullnode
+        .rpc_client
+        .execute_transaction_block(
+            tx_bytes,
+            signatures,
+            Some(MgoTransactionBlockResponseOptions::new()),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+
+    let export = fullnode.rpc_client.export_address_state(address).await?;
+    assert_eq!(address, export.address);
+
+    // The whole of one gas coin was staked.
+    let objects = export.owned_objects.data.unwrap();
+    assert_eq!(DEFAULT_NUMBER_OF_OBJECT_PER_ACCOUNT, objects.len());
+    let staked = objects
+        .iter()
+        .filter(|object| object.type_.ends_with("::staking_pool::StakedMgo"))
+        .count();
+    assert_eq!(1, staked);
+
+    let balances = export.balances.data.unwrap();
+    assert_eq!(1, balances.len());
+    assert_eq!("0x2::mgo::MGO", balances[0].coin_type);
+    assert_eq!(
+        DEFAULT_NUMBER_OF_OBJECT_PER_ACCOUNT - 1,
+        balances[0].coin_object_count
+    );
+
+    let stakes = export.stakes.data.unwrap();
+    assert_eq!(1, stakes.len());
+    assert_eq!(validator, stakes[0].validator_address);
+    assert_eq!(DEFAULT_GAS_AMOUNT, stakes[0].stakes[0].principal);
+    assert!(matches!(stakes[0].stakes[0].status, StakeStatus::Pending));
+
+    // Genesis objects were not sent by the address, so the stake is its only transaction.
+    assert_eq!(
+        vec![response.digest],
+        export.recent_transactions.data.unwrap()
+    );
+
+    // Fullnodes do not sign transactions, so nothing is locked pending execution.
+    assert_eq!(Some(vec![]), export.pending_locks.data);
+    for error in [
+        export.owned_objects.error,
+        export.balances.error,
+        export.stakes.error,
+        export.recent_transactions.error,
+        export.pending_locks.error,
+    ] {
+        assert_eq!(None, error);
+    }
+
+    // Nodes that do not enable the support API do not serve exports.
+    assert!(cluster
+        .rpc_client()
+        .export_address_state(address)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_verify_checkpoint_range() -> Result<(), anyhow::Error> {
+    let mut cluster = TestClusterBuilder::new().build().await;
+    let mut config = cluster
+        .fullnode_config_builder()
+        .build(&mut OsRng, cluster.swarm.config());
+    config.enable_support_api = true;
+    let fullnode = cluster.start_fullnode_from_config(config).await;
+    let client = &fullnode.rpc_client;
+
+    let latest = loop {
+        let latest = *client.get_latest_checkpoint_sequence_number().await?;
+        if latest >= 4 {
+            break latest;
+        }
+        sleep(Duration::from_millis(100)).await;
+    };
+    let report = client
+        .verify_checkpoint_range(0.into(), latest.into())
+        .await?;
+    assert_eq!(latest + 1, report.checked);
+    assert_eq!(Vec::<CheckpointMismatch>::new(), report.mismatches);
+
+    // Store the contents of checkpoint 3 in place of those of checkpoint 2.
+    fullnode.mgo_node.with(|node| {
+        let store = node.state().get_checkpoint_store().clone();
+        let corrupted = store.get_checkpoint_by_sequence_number(2).unwrap().unwrap();
+        let next = store.get_checkpoint_by_sequence_number(3).unwrap().unwrap();
+        let contents = store
+            .get_checkpoint_contents(&next.content_digest)
+            .unwrap()
+            .unwrap();
+        store
+            .insert_checkpoint_contents_at_digest_test_only(corrupted.content_digest, &contents)
+            .unwrap();
+    });
+
+    let report = client
+        .verify_checkpoint_range(1.into(), latest.into())
+        .await?;
+    assert_eq!(latest, report.checked);
+    assert_eq!(1, report.mismatches.len());
+    assert_eq!(2, report.mismatches[0].sequence_number);
+    assert!(matches!(
+        report.mismatches[0].kind,
+        CheckpointMismatchKind::ContentsDigest { .. }
+    ));
+
+    // Ranges are bounded, and must not be reversed.
+    assert!(client
+        .verify_checkpoint_range(0.into(), 1000.into())
+        .await
+        .is_err());
+    assert!(client
+        .verify_checkpoint_range(2.into(), 1.into())
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_get_object_resolves_type_to_latest() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+    let http_client = cluster.rpc_client();
+    let context = &cluster.wallet;
+    let address = cluster.get_address_0();
+    let gas_price = context.get_reference_gas_price().await?;
+    let options = MgoObjectDataOptions::new()
+        .with_type()
+        .with_type_resolved_to_latest();
+
+    let mut v1_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    v1_path.extend(["tests", "data", "upgradeable_v1"]);
+    let gas = context
+        .get_one_gas_object_owned_by_address(address)
+        .await?
+        .unwrap();
+    let data = TestTransactionBuilder::new(address, gas, gas_price)
+        .publish(v1_path)
+        .build();
+    let response = context
+        .execute_transaction_must_succeed(context.sign_transaction(&data))
+        .await;
+    let v1_package = get_new_package_obj_from_response(&response).unwrap().0;
+    let upgrade_cap = get_new_package_upgrade_cap_from_response(&response).unwrap();
+
+    let gas = context
+        .get_one_gas_object_owned_by_address(address)
+        .await?
+        .unwrap();
+    let data = TestTransactionBuilder::new(address, gas, gas_price)
+        .move_call(v1_package, "thing", "create", vec![])
+        .build();
+    let response = context
+        .execute_transaction_must_succeed(context.sign_transaction(&data))
+        .await;
+    let thing = response.effects.unwrap().created()[0].reference.object_id;
+
+    // Neither the thing's package nor the framework defining the gas coin have been upgraded.
+    let object = http_client.get_object(thing, Some(options.clone())).await?;
+    let object = object.object()?;
+    assert_eq!(object.latest_type, object.type_);
+    let object = http_client.get_object(gas.0, Some(options.clone())).await?;
+    let object = object.object()?;
+    assert_eq!(object.latest_type, object.type_);
+
+    let mut v2_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    v2_path.extend(["tests", "data", "upgradeable_v2"]);
+    let compiled_package = BuildConfig::new_for_testing().build(v2_path)?;
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let cap = builder.obj(ObjectArg::ImmOrOwnedObject(upgrade_cap))?;
+        let policy = builder.pure(UpgradePolicy::COMPATIBLE)?;
+        let digest = builder.pure(compiled_package.get_package_digest(false).to_vec())?;
+        let ticket = builder.programmable_move_call(
+            MGO_FRAMEWORK_PACKAGE_ID,
+            Identifier::new("package")?,
+            Identifier::new("authorize_upgrade")?,
+            vec![],
+            vec![cap, policy, digest],
+        );
+        let receipt = builder.upgrade(
+            v1_package,
+            ticket,
+            compiled_package.get_dependency_original_package_ids(),
+            compiled_package.get_package_bytes(false),
+        );
+        builder.programmable_move_call(
+            MGO_FRAMEWORK_PACKAGE_ID,
+            Identifier::new("package")?,
+            Identifier::new("commit_upgrade")?,
+            vec![],
+            vec![cap, receipt],
+        );
+        builder.finish()
+    };
+    let gas = context
+        .get_one_gas_object_owned_by_address(address)
+        .await?
+        .unwrap();
+    let data = TestTransactionBuilder::new(address, gas, gas_price)
+        .programmable(pt)
+        .build();
+    let response = context
+        .execute_transaction_must_succeed(context.sign_transaction(&data))
+        .await;
+    let v2_package = get_new_package_obj_from_response(&response).unwrap().0;
+
+    // The thing keeps the type it was created with, and resolves to the upgraded package.
+    let object = http_client.get_object(thing, Some(options.clone())).await?;
+    let object = object.object()?;
+    assert_eq!(
+        object.type_.as_ref().unwrap().to_string(),
+        format!("{v1_package}::thing::Thing")
+    );
+    assert_eq!(
+        object.latest_type.as_ref().unwrap().to_string(),
+        format!("{v2_package}::thing::Thing")
+    );
+
+    // Past versions resolve to the latest package as well.
+    let past_object = http_client
+        .try_get_past_object(thing, object.version, Some(options.clone()))
+        .await?;
+    let past_object = past_object.into_object()?;
+    assert_eq!(past_object.latest_type, object.latest_type);
+
+    // Types introduced by the upgrade are already defined by the latest package.
+    let gas = context
+        .get_one_gas_object_owned_by_address(address)
+        .await?
+        .unwrap();
+    let data = TestTransactionBuilder::new(address, gas, gas_price)
+        .move_call(v2_package, "thing", "create_gadget", vec![])
+        .build();
+    let response = context
+        .execute_transaction_must_succeed(context.sign_transaction(&data))
+        .await;
+    let gadget = response.effects.unwrap().created()[0].reference.object_id;
+    let object = http_client.get_object(gadget, Some(options)).await?;
+    let object = object.object()?;
+    assert_eq!(
+        object.type_.as_ref().unwrap().to_string(),
+        format!("{v2_package}::thing::Gadget")
+    );
+    assert_eq!(object.latest_type, object.type_);
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_get_object_with_previous_transaction_details() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+    let http_client = cluster.rpc_client();
+    let context = &cluster.wallet;
+    let address = cluster.get_address_0();
+    let gas_price = context.get_reference_gas_price().await?;
+    let gas = context
+        .get_one_gas_object_owned_by_address(address)
+        .await?
+        .unwrap();
+    let data = TestTransactionBuilder::new(address, gas, gas_price)
+        .transfer_mgo(Some(1_000), cluster.get_address_1())
+        .build();
+    let response = context
+        .execute_transaction_must_succeed(context.sign_transaction(&data))
+        .await;
+    let coin = response.effects.as_ref().unwrap().created()[0]
+        .reference
+        .object_id;
+
+    // Details are compared once the transaction is checkpointed.
+    let transaction = loop {
+        let transaction = http_client
+            .get_transaction_block(response.digest, None)
+            .await?;
+        if transaction.checkpoint.is_some() {
+            break transaction;
+        }
+        sleep(Duration::from_millis(100)).await;
+    };
+
+    let options = MgoObjectDataOptions::new().with_previous_transaction_details();
+    let object = http_client.get_object(coin, Some(options.clone())).await?;
+    let object = object.object()?;
+    assert_eq!(object.previous_transaction, Some(response.digest));
+    let details = object.previous_transaction_details.as_ref().unwrap();
+    assert_eq!(details.sender, address);
+    assert_eq!(details.checkpoint, transaction.checkpoint);
+    assert_eq!(details.timestamp_ms, transaction.timestamp_ms);
+
+    // The new coin and the gas coin share their previous transaction.
+    let objects = http_client
+        .multi_get_objects(vec![coin, gas.0], Some(options), None)
+        .await?
+        .into_json()?;
+    for object in &objects {
+        assert_eq!(
+            object.object()?.previous_transaction_details.as_ref(),
+            Some(details)
+        );
+    }
+
+    let object = http_client.get_object(coin, None).await?;
+    assert!(object.object()?.previous_transaction_details.is_none());
+    Ok(())
+}
+
+#[sim_test]
+async fn test_signed_responses() -> Result<(), anyhow::Error> {
+    let mut cluster = TestClusterBuilder::new().build().await;
+    let key = MgoKeyPair::Ed25519(get_key_pair().1);
+    let signer = MgoAddress::from(&key.public());
+    let key_path = cluster.swarm.dir().join("response-signing.key");
+    write_keypair_to_file(&key, &key_path)?;
+    let mut config = cluster
+        .fullnode_config_builder()
+        .build(&mut OsRng, cluster.swarm.config());
+    config.json_rpc_response_signing = Some(JsonRpcResponseSigningConfig {
+        key_path,
+        methods: vec!["mgo_getCheckpoint".to_string()],
+    });
+    let fullnode = cluster.start_fullnode_from_config(config).await;
+
+    let checkpoint: serde_json::Value = fullnode
+        .rpc_client
+        .request("mgo_getCheckpoint", rpc_params!["0"])
+        .await?;
+    let signature = verify_signed_response(&checkpoint, signer)?;
+    assert_eq!(signature.signer, signer);
+    // Clients unaware of signatures still read the result as before.
+    let parsed: Checkpoint = serde_json::from_value(checkpoint.clone())?;
+    assert_eq!(parsed.sequence_number, 0);
+
+    // Any change to the result or to its signature is detected.
+    let mut tampered = checkpoint.clone();
+    tampered["networkTotalTransactions"] = serde_json::json!("1000");
+    assert!(verify_signed_response(&tampered, signer).is_err());
+    let mut tampered = checkpoint.clone();
+    tampered[RESPONSE_SIGNATURE_FIELD]["timestampMs"] = serde_json::json!("0");
+    assert!(verify_signed_response(&tampered, signer).is_err());
+    let other = MgoAddress::from(&MgoKeyPair::Ed25519(get_key_pair().1).public());
+    assert!(verify_signed_response(&checkpoint, other).is_err());
+
+    // The results of the other methods are left unsigned.
+    let transaction: serde_json::Value = fullnode
+        .rpc_client
+        .request("mgo_getTransactionBlock", rpc_params![parsed.transactions[0]])
+        .await?;
+    assert!(transaction.get(RESPONSE_SIGNATURE_FIELD).is_none());
+    assert!(verify_signed_response(&transaction, signer).is_err());
+    Ok(())
+}
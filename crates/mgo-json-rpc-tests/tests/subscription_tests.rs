@@ -10,7 +10,8 @@ use tokio::time::timeout;
 
 use mgo_core::test_utils::wait_for_tx;
 use mgo_json_rpc_types::{
-    MgoTransactionBlockEffects, MgoTransactionBlockEffectsAPI, TransactionFilter,
+    CheckpointNotification, MgoTransactionBlockEffects, MgoTransactionBlockEffectsAPI,
+    TransactionFilter,
 };
 use test_cluster::TestClusterBuilder;
 
@@ -46,3 +47,52 @@ async fn test_subscribe_transaction() -> Result<(), anyhow::Error> {
     assert_eq!(&digest, effects.transaction_digest());
     Ok(())
 }
+
+#[tokio::test]
+async fn test_subscribe_checkpoint() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+    let ws_client = cluster.fullnode_handle.ws_client().await;
+
+    let mut sub: Subscription<CheckpointNotification> = ws_client
+        .subscribe(
+            "mgox_subscribeCheckpoint",
+            rpc_params![],
+            "mgox_unsubscribeCheckpoint",
+        )
+        .await
+        .unwrap();
+
+    cluster.trigger_reconfiguration().await;
+
+    // Checkpoints are announced in order, up to the last one of epoch 0.
+    let mut previous: Option<CheckpointNotification> = None;
+    let last = loop {
+        let checkpoint = match timeout(Duration::from_secs(30), sub.next()).await {
+            Ok(Some(Ok(checkpoint))) => checkpoint,
+            _ => panic!("Failed to get checkpoint"),
+        };
+        assert_eq!(checkpoint.missed, 0);
+        if let Some(previous) = &previous {
+            assert_eq!(checkpoint.sequence_number, previous.sequence_number + 1);
+        }
+        if checkpoint.end_of_epoch {
+            break checkpoint;
+        }
+        previous = Some(checkpoint);
+    };
+
+    assert_eq!(last.epoch, 0);
+    assert!(last.transaction_count > 0);
+    let summary = cluster
+        .fullnode_handle
+        .mgo_node
+        .with(|node| {
+            node.state()
+                .get_checkpoint_store()
+                .get_checkpoint_by_sequence_number(last.sequence_number)
+        })
+        .unwrap()
+        .unwrap();
+    assert_eq!(summary.digest(), &last.digest);
+    Ok(())
+}
@@ -0,0 +1,194 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Spans of the transaction execution RPC path, as an exporter would receive them. The
+//! simulator installs its own global subscriber, so these only run on tokio.
+#![cfg(not(msim))]
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use mgo_json_rpc_api::{TransactionBuilderClient, WriteApiClient};
+use mgo_json_rpc_types::{MgoTransactionBlockResponseOptions, TransactionBlockBytes};
+use mgo_types::quorum_driver_types::ExecuteTransactionRequestType;
+use mgo_types::transaction::TransactionDataAPI;
+use test_cluster::TestClusterBuilder;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+#[derive(Clone, Debug)]
+struct CollectedSpan {
+    name: &'static str,
+    parent: Option<usize>,
+    fields: BTreeMap<String, String>,
+}
+
+impl CollectedSpan {
+    fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(String::as_str)
+    }
+}
+
+/// Keeps every span created in the process, with the values recorded on it.
+#[derive(Clone, Default)]
+struct SpanCollector {
+    spans: Arc<Mutex<Vec<CollectedSpan>>>,
+    // Span ids are reused once closed, so they are mapped to the latest span created with them.
+    ids: Arc<Mutex<HashMap<Id, usize>>>,
+}
+
+impl SpanCollector {
+    fn spans(&self) -> Vec<CollectedSpan> {
+        self.spans.lock().unwrap().clone()
+    }
+}
+
+struct FieldVisitor<'a>(&'a mut BTreeMap<String, String>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}"));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for SpanCollector {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut ids = self.ids.lock().unwrap();
+        let parent = ctx
+            .span(id)
+            .and_then(|span| span.parent())
+            .and_then(|parent| ids.get(&parent.id()).copied());
+        let mut fields = BTreeMap::new();
+        attrs.record(&mut FieldVisitor(&mut fields));
+
+        let mut spans = self.spans.lock().unwrap();
+        ids.insert(id.clone(), spans.len());
+        spans.push(CollectedSpan {
+            name: attrs.metadata().name(),
+            parent,
+            fields,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+        let ids = self.ids.lock().unwrap();
+        if let Some(index) = ids.get(id) {
+            values.record(&mut FieldVisitor(
+                &mut self.spans.lock().unwrap()[*index].fields,
+            ));
+        }
+    }
+}
+
+/// Whether `span` descends from a span named `ancestor`.
+fn descends_from(spans: &[CollectedSpan], span: &CollectedSpan, ancestor: &str) -> bool {
+    let mut parent = span.parent;
+    while let Some(index) = parent {
+        if spans[index].name == ancestor {
+            return true;
+        }
+        parent = spans[index].parent;
+    }
+    false
+}
+
+#[tokio::test]
+async fn test_execution_rpc_spans() -> Result<(), anyhow::Error> {
+    // No exporter is installed, the spans are created all the same.
+    let collector = SpanCollector::default();
+    tracing::subscriber::set_global_default(
+        tracing_subscriber::registry().with(collector.clone()),
+    )?;
+
+    let cluster = TestClusterBuilder::new().build().await;
+    let http_client = cluster.rpc_client();
+    let address = cluster.get_address_0();
+    let recipient = cluster.get_address_1();
+
+    let gas = cluster
+        .wallet
+        .get_one_gas_object_owned_by_address(address)
+        .await?
+        .unwrap();
+    let transaction_bytes: TransactionBlockBytes = http_client
+        .transfer_mgo(address, gas.0, 10_000_000.into(), recipient, Some(1.into()))
+        .await?;
+    let tx_data = transaction_bytes.to_data()?;
+    let tx = cluster.wallet.sign_transaction(&tx_data);
+    let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
+
+    http_client
+        .dry_run_transaction_block(tx_bytes.clone())
+        .await?;
+    let response = http_client
+        .execute_transaction_block(
+            tx_bytes.clone(),
+            signatures.clone(),
+            Some(MgoTransactionBlockResponseOptions::new()),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+
+    let digest = response.digest.to_string();
+    let sender = address.to_string();
+    let gas_budget = tx_data.gas_budget().to_string();
+    let spans = collector.spans();
+    let rpc_span = |name| {
+        spans
+            .iter()
+            .find(|span| span.name == name && span.field("tx_digest") == Some(digest.as_str()))
+            .unwrap_or_else(|| panic!("missing {name} span"))
+    };
+
+    for name in ["execute_transaction_block", "dry_run_transaction_block"] {
+        let span = rpc_span(name);
+        assert_eq!(span.field("sender"), Some(sender.as_str()));
+        assert_eq!(span.field("gas_budget"), Some(gas_budget.as_str()));
+    }
+
+    // Submission and local execution happen under the RPC call, even though the orchestrator
+    // runs in its own task.
+    for name in [
+        "tx_orchestrator_execute_transaction",
+        "tx_orchestrator_submit",
+        "tx_orchestrator_execute_finalized_tx_locally_with_timeout",
+    ] {
+        let span = spans
+            .iter()
+            .filter(|span| span.name == name)
+            .find(|span| span.field("tx_digest").is_some_and(|d| d.contains(&digest)))
+            .unwrap_or_else(|| panic!("missing {name} span"));
+        assert!(
+            descends_from(&spans, span, "execute_transaction_block"),
+            "{name} span is not linked to the RPC call"
+        );
+    }
+    let dry_exec = spans
+        .iter()
+        .find(|span| span.name == "dry_exec_transaction")
+        .expect("missing dry_exec_transaction span");
+    assert!(descends_from(&spans, dry_exec, "dry_run_transaction_block"));
+
+    // Raw transaction bytes and signatures are never attached.
+    let tx_bytes = tx_bytes.encoded();
+    let signatures = signatures.iter().map(|s| s.encoded()).collect::<Vec<_>>();
+    for span in &spans {
+        assert!(!span.fields.contains_key("tx_bytes"), "{span:?}");
+        assert!(!span.fields.contains_key("signatures"), "{span:?}");
+        for value in span.fields.values() {
+            assert!(!value.contains(&tx_bytes), "{span:?}");
+            assert!(signatures.iter().all(|s| !value.contains(s)), "{span:?}");
+        }
+    }
+    Ok(())
+}
@@ -1,18 +1,25 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+use mgo_json_rpc_types::CheckpointId;
 use mgo_json_rpc_types::MgoTransactionBlockResponseQuery;
 use mgo_json_rpc_types::TransactionFilter;
+use mgo_json_rpc_types::{CreatedObjectSummary, ObjectChange};
 use mgo_json_rpc_types::{
     MgoObjectDataOptions, MgoObjectResponseQuery, MgoTransactionBlockResponse,
     MgoTransactionBlockResponseOptions, TransactionBlockBytes,
 };
+use mgo_json_rpc_types::{ResponseEncoding, TransactionBlockBcsV1};
 use mgo_macros::sim_test;
+use mgo_test_transaction_builder::{
+    create_devnet_nft, publish_nfts_package, TestTransactionBuilder,
+};
+use mgo_types::effects::TransactionEffects;
 use mgo_types::quorum_driver_types::ExecuteTransactionRequestType;
 use mgo_types::transaction::SenderSignedData;
 use test_cluster::TestClusterBuilder;
 
-use mgo_json_rpc_api::{IndexerApiClient, TransactionBuilderClient, WriteApiClient};
+use mgo_json_rpc_api::{IndexerApiClient, ReadApiClient, TransactionBuilderClient, WriteApiClient};
 
 #[sim_test]
 async fn test_get_transaction_block() -> Result<(), anyhow::Error> {
@@ -31,6 +38,7 @@ async fn test_get_transaction_block() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?
         .data;
@@ -114,6 +122,7 @@ async fn test_get_raw_transaction() -> Result<(), anyhow::Error> {
             )),
             None,
             None,
+            None,
         )
         .await?
         .data;
@@ -148,6 +157,256 @@ async fn test_get_raw_transaction() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_multi_get_transaction_blocks_bcs() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+    let http_client = cluster.rpc_client();
+    let address = cluster.get_address_0();
+
+    let objects = http_client
+        .get_owned_objects(
+            address,
+            Some(MgoObjectResponseQuery::new_with_options(
+                MgoObjectDataOptions::new(),
+            )),
+            None,
+            None,
+            None,
+        )
+        .await?
+        .data;
+    let gas_id = objects.last().unwrap().object().unwrap().object_id;
+
+    let mut digests = vec![];
+    for obj in &objects[..2] {
+        let transaction_bytes: TransactionBlockBytes = http_client
+            .transfer_object(
+                address,
+                obj.object().unwrap().object_id,
+                Some(gas_id),
+                1_000_000.into(),
+                address,
+            )
+            .await?;
+        let tx = cluster
+            .wallet
+            .sign_transaction(&transaction_bytes.to_data()?);
+        let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
+        let response = http_client
+            .execute_transaction_block(
+                tx_bytes,
+                signatures,
+                None,
+                Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+            )
+            .await?;
+        digests.push(response.digest);
+    }
+
+    let json_responses = http_client
+        .multi_get_transaction_blocks(
+            digests.clone(),
+            Some(
+                MgoTransactionBlockResponseOptions::new()
+                    .with_raw_input()
+                    .with_raw_effects()
+                    .with_events(),
+            ),
+            None,
+        )
+        .await?
+        .into_json()?;
+    let blocks: Vec<TransactionBlockBcsV1> = http_client
+        .multi_get_transaction_blocks(digests.clone(), None, Some(ResponseEncoding::Bcs))
+        .await?
+        .into_bcs()?
+        .decode()?;
+
+    assert_eq!(blocks.len(), json_responses.len());
+    for (block, json) in blocks.iter().zip(json_responses) {
+        assert_eq!(block.digest, json.digest);
+        let transaction: SenderSignedData = bcs::from_bytes(&json.raw_transaction)?;
+        assert_eq!(block.transaction, transaction);
+        let effects: TransactionEffects = bcs::from_bytes(&json.raw_effects)?;
+        assert_eq!(block.effects, effects);
+        assert_eq!(
+            block.events.as_ref().map_or(0, |events| events.data.len()),
+            json.events.unwrap().data.len()
+        );
+        // The transaction may have been checkpointed in between the two reads.
+        if json.checkpoint.is_some() {
+            assert_eq!(block.checkpoint, json.checkpoint);
+            assert_eq!(block.timestamp_ms, json.timestamp_ms);
+        }
+    }
+
+    // Duplicated digests are rejected, as in the JSON endpoint.
+    assert!(http_client
+        .multi_get_transaction_blocks(
+            vec![digests[0], digests[0]],
+            None,
+            Some(ResponseEncoding::Bcs)
+        )
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_created_summary_matches_object_changes() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+    let http_client = cluster.rpc_client();
+
+    let (package_id, _, publish_digest) = publish_nfts_package(&cluster.wallet).await;
+    let (_, nft_id, mint_digest) = create_devnet_nft(&cluster.wallet, package_id).await;
+
+    let responses = http_client
+        .multi_get_transaction_blocks(
+            vec![publish_digest, mint_digest],
+            Some(
+                MgoTransactionBlockResponseOptions::new()
+                    .with_object_changes()
+                    .with_created_summary(),
+            ),
+            None,
+        )
+        .await?
+        .into_json()?;
+    for response in &responses {
+        let expected = response
+            .object_changes
+            .as_ref()
+            .unwrap()
+            .iter()
+            .filter_map(|change| match change {
+                ObjectChange::Created {
+                    owner,
+                    object_type,
+                    object_id,
+                    ..
+                } => Some(CreatedObjectSummary {
+                    object_id: *object_id,
+                    object_type: object_type.clone(),
+                    owner: *owner,
+                }),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert!(!expected.is_empty());
+        assert_eq!(response.created_summary.as_ref(), Some(&expected));
+    }
+    assert!(responses[1]
+        .created_summary
+        .as_ref()
+        .unwrap()
+        .iter()
+        .any(|created| created.object_id == nft_id));
+
+    // The summary is only returned when asked for.
+    let response = http_client
+        .get_transaction_block(
+            mint_digest,
+            Some(MgoTransactionBlockResponseOptions::new().with_object_changes()),
+        )
+        .await?;
+    assert!(response.created_summary.is_none());
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_read_after_local_execution_is_not_stale() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+    let http_client = cluster.rpc_client();
+    let address = cluster.get_address_0();
+    let gas_price = cluster.wallet.get_reference_gas_price().await?;
+    let mut gas = cluster
+        .wallet
+        .get_one_gas_object_owned_by_address(address)
+        .await?
+        .unwrap();
+
+    // Every iteration mutates the gas object and reads it back straight away, which used to
+    // occasionally return the version from before the transaction.
+    for _ in 0..50 {
+        let data = TestTransactionBuilder::new(address, gas, gas_price)
+            .transfer_mgo(Some(1), address)
+            .build();
+        let (tx_bytes, signatures) = cluster
+            .wallet
+            .sign_transaction(&data)
+            .to_tx_bytes_and_signatures();
+        let response = http_client
+            .execute_transaction_block(
+                tx_bytes,
+                signatures,
+                None,
+                Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+            )
+            .await?;
+        assert_eq!(response.confirmed_local_execution, Some(true));
+        assert!(response.executed_at_checkpoint_hint.is_some());
+
+        let written = response
+            .written_objects
+            .unwrap()
+            .into_iter()
+            .find(|object_ref| object_ref.object_id == gas.0)
+            .unwrap();
+        let object = http_client.get_object(gas.0, None).await?;
+        let object = object.object()?;
+        assert_eq!(object.version, written.version);
+        gas = object.object_ref();
+    }
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_execute_waiting_for_checkpoint_inclusion() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+    let http_client = cluster.rpc_client();
+    let address = cluster.get_address_0();
+    let gas_price = cluster.wallet.get_reference_gas_price().await?;
+    let gas = cluster
+        .wallet
+        .get_one_gas_object_owned_by_address(address)
+        .await?
+        .unwrap();
+
+    let data = TestTransactionBuilder::new(address, gas, gas_price)
+        .transfer_mgo(Some(1), address)
+        .build();
+    let tx = cluster.wallet.sign_transaction(&data);
+    let digest = *tx.digest();
+    let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
+    let response = http_client
+        .execute_transaction_block(
+            tx_bytes,
+            signatures,
+            Some(MgoTransactionBlockResponseOptions::new().with_effects()),
+            Some(ExecuteTransactionRequestType::WaitForCheckpointInclusion { timeout_ms: 30_000 }),
+        )
+        .await?;
+    assert_eq!(response.digest, digest);
+    assert!(response.status_ok().unwrap());
+
+    // The returned checkpoint is the one that includes the transaction.
+    let sequence_number = response.checkpoint.expect("checkpoint should be returned");
+    let checkpoint = http_client
+        .get_checkpoint(CheckpointId::SequenceNumber(sequence_number), None)
+        .await?
+        .into_json()?;
+    assert_eq!(Some(checkpoint.digest), response.checkpoint_digest);
+    assert_eq!(
+        Some(checkpoint.timestamp_ms),
+        response.checkpoint_timestamp_ms
+    );
+    assert!(checkpoint.transactions.contains(&digest));
+    Ok(())
+}
+
 #[sim_test]
 async fn test_get_fullnode_transaction() -> Result<(), anyhow::Error> {
     let cluster = TestClusterBuilder::new().build().await;
@@ -1,11 +1,16 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::BTreeMap;
+
+use fastcrypto::encoding::{Encoding, Hex};
+use fastcrypto::hash::{Blake2b256, HashFunction};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 pub use balance_changes::*;
 pub use object_changes::*;
+pub use mgo_bcs::*;
 pub use mgo_checkpoint::*;
 pub use mgo_coin::*;
 pub use mgo_event::*;
@@ -14,9 +19,11 @@ pub use mgo_governance::*;
 pub use mgo_move::*;
 pub use mgo_object::*;
 pub use mgo_protocol::*;
+pub use mgo_response_signature::*;
+pub use mgo_support::*;
 pub use mgo_transaction::*;
+pub use mgo_verification::*;
 use mgo_types::base_types::ObjectID;
-use mgo_types::dynamic_field::DynamicFieldInfo;
 
 #[cfg(test)]
 #[path = "unit_tests/rpc_types_tests.rs"]
@@ -25,6 +32,7 @@ mod rpc_types_tests;
 mod balance_changes;
 mod displays;
 mod object_changes;
+mod mgo_bcs;
 mod mgo_checkpoint;
 mod mgo_coin;
 mod mgo_event;
@@ -33,9 +41,12 @@ mod mgo_governance;
 mod mgo_move;
 mod mgo_object;
 mod mgo_protocol;
+mod mgo_response_signature;
+mod mgo_support;
 mod mgo_transaction;
+mod mgo_verification;
 
-pub type DynamicFieldPage = Page<DynamicFieldInfo, ObjectID>;
+pub type DynamicFieldPage = Page<MgoDynamicFieldInfo, ObjectID>;
 /// `next_cursor` points to the last item in the page;
 /// Reading with `next_cursor` will start from the next item after `next_cursor` if
 /// `next_cursor` is `Some`, otherwise it will start from the first item.
@@ -45,6 +56,10 @@ pub struct Page<T, C> {
     pub data: Vec<T>,
     pub next_cursor: Option<C>,
     pub has_next_page: bool,
+    /// The pagination the server applied to produce the page. Only returned when requested with
+    /// `echo`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub applied: Option<AppliedPagination<C>>,
 }
 
 impl<T, C> Page<T, C> {
@@ -53,6 +68,96 @@ impl<T, C> Page<T, C> {
             data: vec![],
             next_cursor: None,
             has_next_page: false,
+            applied: None,
+        }
+    }
+}
+
+/// The cursor, limit and ordering a paginated request was served with, after the server filled
+/// in its defaults and capped the limit, so that client logs say what was actually queried.
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedPagination<C> {
+    pub cursor: Option<C>,
+    pub limit: usize,
+    pub descending: bool,
+    /// Hex encoded Blake2b256 hash of the filter in canonical JSON, with object keys sorted, to
+    /// correlate requests with the same filter without logging the filter itself
+    pub filter_hash: String,
+}
+
+impl<C> AppliedPagination<C> {
+    /// The pagination to echo in a page when `echo` is set, which is only built then.
+    pub fn echo<F: Serialize>(
+        echo: Option<bool>,
+        cursor: Option<C>,
+        limit: usize,
+        descending: bool,
+        filter: &F,
+    ) -> Option<Self> {
+        echo.unwrap_or_default().then(|| Self::new(cursor, limit, descending, filter))
+    }
+
+    pub fn new<F: Serialize>(
+        cursor: Option<C>,
+        limit: usize,
+        descending: bool,
+        filter: &F,
+    ) -> Self {
+        Self {
+            cursor,
+            limit,
+            descending,
+            filter_hash: filter_hash(filter),
+        }
+    }
+}
+
+/// Hash of `filter` that does not depend on the order its fields are serialized in.
+pub fn filter_hash<F: Serialize>(filter: &F) -> String {
+    fn canonicalize(value: serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::Object(map) => {
+                let sorted: BTreeMap<_, _> = map
+                    .into_iter()
+                    .map(|(key, value)| (key, canonicalize(value)))
+                    .collect();
+                serde_json::Value::Object(sorted.into_iter().collect())
+            }
+            serde_json::Value::Array(values) => {
+                serde_json::Value::Array(values.into_iter().map(canonicalize).collect())
+            }
+            value => value,
+        }
+    }
+
+    let canonical = serde_json::to_value(filter)
+        .map(canonicalize)
+        .expect("Filter serialization should not fail");
+    let digest = Blake2b256::digest(canonical.to_string().as_bytes());
+    Hex::encode(digest)
+}
+
+/// Result of resolving one item of a bulk name service request. `data` is `None` when nothing
+/// resolves for the item, and `error` is set when it could not be resolved.
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NameServiceResolution<T> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl<T> NameServiceResolution<T> {
+    pub fn new(data: Option<T>) -> Self {
+        Self { data, error: None }
+    }
+
+    pub fn error(error: String) -> Self {
+        Self {
+            data: None,
+            error: Some(error),
         }
     }
 }
@@ -0,0 +1,164 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! BCS encoding of read responses.
+//!
+//! `mgo_multiGetObjects`, `mgo_getCheckpoint` and `mgo_multiGetTransactionBlocks` return a
+//! [`BcsResponse`] in place of their JSON response when called with the `bcs`
+//! [`ResponseEncoding`], and `mgo_getCheckpointTransactions` always does. Its `bcs` field holds
+//! the BCS encoding of one of the versioned structs below. The layouts are part of the RPC
+//! contract: a released version is never changed in place, any change adds a new `*V{n}` type
+//! and bumps [`BCS_RESPONSE_VERSION`].
+
+use anyhow::anyhow;
+use fastcrypto::encoding::Base64;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use mgo_types::base_types::{ObjectID, ObjectRef, TransactionDigest};
+use mgo_types::effects::{TransactionEffects, TransactionEvents};
+use mgo_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointContents, CheckpointSequenceNumber,
+};
+use mgo_types::object::{Object, ObjectRead};
+use mgo_types::transaction::SenderSignedData;
+
+/// Version of the layouts currently produced by the BCS read endpoints.
+pub const BCS_RESPONSE_VERSION: u8 = 1;
+
+/// Encoding of the response of the read endpoints that accept one.
+#[derive(Clone, Copy, Debug, Default, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseEncoding {
+    /// The JSON response of the endpoint.
+    #[default]
+    Json,
+    /// A [`BcsResponse`] holding the BCS layout of the endpoint.
+    Bcs,
+}
+
+/// Response of a read endpoint that accepts a [`ResponseEncoding`]: the JSON response, or a
+/// [`BcsResponse`] if `bcs` was requested.
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum EncodedResponse<T> {
+    Json(T),
+    Bcs(BcsResponse),
+}
+
+impl<T> EncodedResponse<T> {
+    /// The JSON response, failing if the response is BCS encoded.
+    pub fn into_json(self) -> anyhow::Result<T> {
+        match self {
+            Self::Json(response) => Ok(response),
+            Self::Bcs(_) => Err(anyhow!("Expected a JSON response, got a BCS response")),
+        }
+    }
+
+    /// The BCS response, failing if the response is JSON encoded.
+    pub fn into_bcs(self) -> anyhow::Result<BcsResponse> {
+        match self {
+            Self::Json(_) => Err(anyhow!("Expected a BCS response, got a JSON response")),
+            Self::Bcs(response) => Ok(response),
+        }
+    }
+}
+
+/// Result of a BCS encoded read.
+#[serde_as]
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BcsResponse {
+    /// Version of the layout encoded in `bcs`.
+    pub version: u8,
+    /// Base64 string of the BCS encoded response.
+    #[serde_as(as = "Base64")]
+    #[schemars(with = "Base64")]
+    pub bcs: Vec<u8>,
+}
+
+impl BcsResponse {
+    /// Encode `value` with the current [`BCS_RESPONSE_VERSION`].
+    pub fn new<T: Serialize>(value: &T) -> Result<Self, bcs::Error> {
+        Ok(Self {
+            version: BCS_RESPONSE_VERSION,
+            bcs: bcs::to_bytes(value)?,
+        })
+    }
+
+    /// Decode the payload, failing if it was produced with a layout version this client does
+    /// not know about.
+    pub fn decode<T: DeserializeOwned>(&self) -> anyhow::Result<T> {
+        if self.version != BCS_RESPONSE_VERSION {
+            return Err(anyhow!(
+                "Unsupported BCS response version {}, expected {BCS_RESPONSE_VERSION}",
+                self.version
+            ));
+        }
+        Ok(bcs::from_bytes(&self.bcs)?)
+    }
+}
+
+/// Version 1 layout of a single object read. `mgo_multiGetObjects` encodes a
+/// `Vec<ObjectReadBcsV1>` with one entry per requested ID, in request order.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ObjectReadBcsV1 {
+    Exists(Object),
+    NotExists(ObjectID),
+    Deleted(ObjectRef),
+}
+
+impl From<ObjectRead> for ObjectReadBcsV1 {
+    fn from(read: ObjectRead) -> Self {
+        match read {
+            ObjectRead::Exists(_, object, _) => Self::Exists(object),
+            ObjectRead::NotExists(id) => Self::NotExists(id),
+            ObjectRead::Deleted(object_ref) => Self::Deleted(object_ref),
+        }
+    }
+}
+
+/// Version 1 layout of `mgo_getCheckpoint`: the certified summary together with the full
+/// checkpoint contents.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CheckpointBcsV1 {
+    pub summary: CertifiedCheckpointSummary,
+    pub contents: CheckpointContents,
+}
+
+/// Version 1 layout of a single transaction block. `mgo_multiGetTransactionBlocks` encodes a
+/// `Vec<TransactionBlockBcsV1>` in request order.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TransactionBlockBcsV1 {
+    pub digest: TransactionDigest,
+    pub transaction: SenderSignedData,
+    pub effects: TransactionEffects,
+    /// `None` if the transaction emitted no events.
+    pub events: Option<TransactionEvents>,
+    /// `None` if the transaction has not been included in a checkpoint yet.
+    pub checkpoint: Option<CheckpointSequenceNumber>,
+    pub timestamp_ms: Option<u64>,
+}
+
+/// Version 1 layout of `mgo_getCheckpointTransactions`: a page of the transactions of a
+/// checkpoint, in checkpoint order.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CheckpointTransactionsBcsV1 {
+    pub checkpoint: CheckpointSequenceNumber,
+    pub data: Vec<CheckpointTransactionBcsV1>,
+    /// Digest of the last transaction of the page, to pass as cursor for the next one. `None` if
+    /// the page ends the checkpoint.
+    pub next_cursor: Option<TransactionDigest>,
+}
+
+/// A transaction of a checkpoint. Parts the node no longer has, typically because they were
+/// pruned, are `None` rather than failing the whole page.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CheckpointTransactionBcsV1 {
+    pub digest: TransactionDigest,
+    pub transaction: Option<SenderSignedData>,
+    pub effects: Option<TransactionEffects>,
+    /// `None` if the transaction emitted no events, or if its effects or events are missing.
+    pub events: Option<TransactionEvents>,
+}
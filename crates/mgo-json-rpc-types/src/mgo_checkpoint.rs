@@ -1,7 +1,8 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::Page;
+use crate::{MgoCommittee, Page};
+use anyhow::{anyhow, bail};
 use fastcrypto::encoding::Base64;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -109,6 +110,48 @@ impl
     }
 }
 
+/// Announcement of a checkpoint executed by the node, streamed to checkpoint subscribers.
+#[serde_as]
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointNotification {
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub epoch: EpochId,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub sequence_number: CheckpointSequenceNumber,
+    pub digest: CheckpointDigest,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub timestamp_ms: CheckpointTimestamp,
+    /// Number of transactions in the checkpoint
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub transaction_count: u64,
+    /// Whether this is the final checkpoint of its epoch
+    pub end_of_epoch: bool,
+    /// Number of notifications dropped since the previous one, because the subscriber fell too
+    /// far behind.
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub missed: u64,
+}
+
+impl CheckpointNotification {
+    pub fn new(summary: &CheckpointSummary, transaction_count: u64) -> Self {
+        Self {
+            epoch: summary.epoch,
+            sequence_number: summary.sequence_number,
+            digest: summary.digest(),
+            timestamp_ms: summary.timestamp_ms,
+            transaction_count,
+            end_of_epoch: summary.end_of_epoch_data.is_some(),
+            missed: 0,
+        }
+    }
+}
+
 #[serde_as]
 #[derive(Clone, Copy, Debug, JsonSchema, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -132,3 +175,91 @@ impl From<CheckpointDigest> for CheckpointId {
         Self::Digest(digest)
     }
 }
+
+/// What a light client needs to carry its trust in the committee of an epoch over to the
+/// committee of the next one: the last checkpoint of the previous epoch, certified by the
+/// previous committee, commits to the committee of the epoch, and the first checkpoint of the
+/// epoch links to it.
+#[serde_as]
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpochStartInfo {
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub epoch: EpochId,
+    pub committee: MgoCommittee,
+    pub first_checkpoint: Checkpoint,
+    /// The committee of the previous epoch, `None` for epoch 0, whose committee is the genesis
+    /// committee
+    pub previous_committee: Option<MgoCommittee>,
+    /// The last checkpoint of the previous epoch, `None` for epoch 0
+    pub previous_epoch_last_checkpoint: Option<Checkpoint>,
+}
+
+/// Checks that every epoch of `epochs`, in order, hands its committee over from the one
+/// before it, starting from the already trusted committee `trusted`, e.g. the genesis
+/// committee. Returns the committee of the last epoch, which can be trusted as much as
+/// `trusted` once the signatures of the end of epoch checkpoints are verified as well.
+pub fn verify_committee_handoffs<'a>(
+    trusted: &'a MgoCommittee,
+    epochs: &'a [EpochStartInfo],
+) -> anyhow::Result<&'a MgoCommittee> {
+    let mut trusted = trusted;
+    for info in epochs {
+        verify_committee_handoff(trusted, info)
+            .map_err(|e| anyhow!("Epoch {}: {e}", info.epoch))?;
+        trusted = &info.committee;
+    }
+    Ok(trusted)
+}
+
+fn verify_committee_handoff(previous: &MgoCommittee, info: &EpochStartInfo) -> anyhow::Result<()> {
+    if info.epoch != previous.epoch + 1 {
+        bail!("does not follow epoch {}", previous.epoch);
+    }
+    if info.committee.epoch != info.epoch {
+        bail!("committee is the one of epoch {}", info.committee.epoch);
+    }
+    if let Some(previous_committee) = &info.previous_committee {
+        if !same_validators(previous_committee, previous) {
+            bail!("previous committee is not the trusted one");
+        }
+    }
+    let Some(last) = &info.previous_epoch_last_checkpoint else {
+        bail!("last checkpoint of the previous epoch is missing");
+    };
+    let sequence_number = last.sequence_number;
+    if last.epoch != previous.epoch {
+        bail!("checkpoint {sequence_number} is not part of the previous epoch");
+    }
+    let Some(end_of_epoch_data) = &last.end_of_epoch_data else {
+        bail!("checkpoint {sequence_number} does not end the previous epoch");
+    };
+    let handed_over = MgoCommittee {
+        epoch: info.epoch,
+        validators: end_of_epoch_data.next_epoch_committee.clone(),
+    };
+    if !same_validators(&handed_over, &info.committee) {
+        bail!("committee differs from the one checkpoint {sequence_number} hands over to");
+    }
+    let first = &info.first_checkpoint;
+    if first.epoch != info.epoch
+        || first.sequence_number != sequence_number + 1
+        || first.previous_digest != Some(last.digest)
+    {
+        bail!(
+            "first checkpoint {} does not follow checkpoint {sequence_number}",
+            first.sequence_number
+        );
+    }
+    Ok(())
+}
+
+/// Whether both committees have the same validators with the same stakes, in any order.
+fn same_validators(a: &MgoCommittee, b: &MgoCommittee) -> bool {
+    let mut a = a.validators.clone();
+    let mut b = b.validators.clone();
+    a.sort();
+    b.sort();
+    a == b
+}
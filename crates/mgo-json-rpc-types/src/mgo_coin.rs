@@ -17,7 +17,44 @@ use mgo_types::object::Object;
 use mgo_types::mgo_serde::BigInt;
 use mgo_types::mgo_serde::SequenceNumber as AsSequenceNumber;
 
-pub type CoinPage = Page<Coin, ObjectID>;
+/// A page of coins, laid out like [`Page`], with the totals of the coin types the request
+/// covers when they are asked for.
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinPage {
+    pub data: Vec<Coin>,
+    pub next_cursor: Option<ObjectID>,
+    pub has_next_page: bool,
+    /// Number of coins and total balance of each coin type the request covers, across all
+    /// pages. Only returned when requested with `includeTotals`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub totals: Option<Vec<Balance>>,
+    /// Set when totals were requested but the balance index could not provide them. The page
+    /// itself is unaffected.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub totals_unavailable: bool,
+}
+
+impl CoinPage {
+    pub fn empty() -> Self {
+        Page::empty().into()
+    }
+}
+
+impl From<Page<Coin, ObjectID>> for CoinPage {
+    fn from(page: Page<Coin, ObjectID>) -> Self {
+        Self {
+            data: page.data,
+            next_cursor: page.next_cursor,
+            has_next_page: page.has_next_page,
+            totals: None,
+            totals_unavailable: false,
+        }
+    }
+}
+
+/// Balances keyed by the canonical coin type string of the last balance on the page.
+pub type BalancePage = Page<Balance, String>;
 
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, JsonSchema, PartialEq, Eq, Clone)]
@@ -32,6 +69,16 @@ pub struct Balance {
     #[schemars(with = "HashMap<BigInt<u64>, BigInt<u128>>")]
     #[serde_as(as = "HashMap<BigInt<u64>, BigInt<u128>>")]
     pub locked_balance: HashMap<EpochId, u128>,
+    /// Whether the coin type is listed in the coin registry of the node, omitted when the node
+    /// has none configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verified: Option<bool>,
+    /// Symbol the coin registry lists the coin type under.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical_symbol: Option<String>,
+    /// Icon URL the coin registry lists the coin type under.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical_icon_url: Option<String>,
 }
 
 impl Balance {
@@ -41,8 +88,17 @@ impl Balance {
             coin_object_count: 0,
             total_balance: 0,
             locked_balance: HashMap::new(),
+            verified: None,
+            canonical_symbol: None,
+            canonical_icon_url: None,
         }
     }
+
+    pub fn set_verification(&mut self, verification: CoinVerification) {
+        self.verified = Some(verification.verified);
+        self.canonical_symbol = verification.symbol;
+        self.canonical_icon_url = verification.icon_url;
+    }
 }
 
 #[serde_as]
@@ -59,12 +115,28 @@ pub struct Coin {
     #[serde_as(as = "BigInt<u64>")]
     pub balance: u64,
     pub previous_transaction: TransactionDigest,
+    /// Whether the coin type is listed in the coin registry of the node, omitted when the node
+    /// has none configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verified: Option<bool>,
+    /// Symbol the coin registry lists the coin type under.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical_symbol: Option<String>,
+    /// Icon URL the coin registry lists the coin type under.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical_icon_url: Option<String>,
 }
 
 impl Coin {
     pub fn object_ref(&self) -> ObjectRef {
         (self.coin_object_id, self.version, self.digest)
     }
+
+    pub fn set_verification(&mut self, verification: CoinVerification) {
+        self.verified = Some(verification.verified);
+        self.canonical_symbol = verification.symbol;
+        self.canonical_icon_url = verification.icon_url;
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
@@ -82,6 +154,24 @@ pub struct MgoCoinMetadata {
     pub icon_url: Option<String>,
     /// Object id for the CoinMetadata object
     pub id: Option<ObjectID>,
+    /// Whether the coin type is listed in the coin registry of the node, omitted when the node
+    /// has none configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verified: Option<bool>,
+    /// Symbol the coin registry lists the coin type under.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical_symbol: Option<String>,
+    /// Icon URL the coin registry lists the coin type under.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical_icon_url: Option<String>,
+}
+
+impl MgoCoinMetadata {
+    pub fn set_verification(&mut self, verification: CoinVerification) {
+        self.verified = Some(verification.verified);
+        self.canonical_symbol = verification.symbol;
+        self.canonical_icon_url = verification.icon_url;
+    }
 }
 
 impl TryFrom<Object> for MgoCoinMetadata {
@@ -103,6 +193,18 @@ impl TryFrom<Object> for MgoCoinMetadata {
             symbol,
             description,
             icon_url,
+            verified: None,
+            canonical_symbol: None,
+            canonical_icon_url: None,
         })
     }
 }
+
+/// Standing of a coin type in the coin registry configured on the node, set on the coins,
+/// balances and metadata of that type.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CoinVerification {
+    pub verified: bool,
+    pub symbol: Option<String>,
+    pub icon_url: Option<String>,
+}
@@ -1,6 +1,7 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::bail;
 use fastcrypto::encoding::{Base58, Base64};
 use move_core_types::annotated_value::MoveStructLayout;
 use move_core_types::identifier::Identifier;
@@ -15,6 +16,7 @@ use std::fmt::Display;
 use mgo_types::base_types::{ObjectID, MgoAddress, TransactionDigest};
 use mgo_types::error::MgoResult;
 use mgo_types::event::{Event, EventEnvelope, EventID};
+use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
 use mgo_types::mgo_serde::BigInt;
 
 use json_to_table::json_to_table;
@@ -28,6 +30,19 @@ use std::str::FromStr;
 
 pub type EventPage = Page<MgoEvent, EventID>;
 
+/// A page of the events referencing an object, laid out like [`Page`], with the event types
+/// whose object references are indexed, as events of other types are never found.
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EventsByObjectPage {
+    pub data: Vec<MgoEvent>,
+    pub next_cursor: Option<EventID>,
+    pub has_next_page: bool,
+    /// Event types searched for references to the object. An event type without type
+    /// parameters covers all its instantiations.
+    pub searched_event_types: Vec<String>,
+}
+
 #[serde_as]
 #[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename = "Event", rename_all = "camelCase")]
@@ -260,6 +275,23 @@ pub enum EventFilter {
         #[serde_as(as = "BigInt<u64>")]
         end_time: u64,
     },
+    /// Return events emitted in the given checkpoint. Only supported by the indexer, as
+    /// streamed events do not carry their checkpoint.
+    Checkpoint(
+        #[schemars(with = "BigInt<u64>")]
+        #[serde_as(as = "BigInt<u64>")]
+        CheckpointSequenceNumber,
+    ),
+    /// Return events emitted in checkpoints [from, to], both inclusive. Only supported by the
+    /// indexer, as streamed events do not carry their checkpoint.
+    CheckpointRange {
+        #[schemars(with = "BigInt<u64>")]
+        #[serde_as(as = "BigInt<u64>")]
+        from: CheckpointSequenceNumber,
+        #[schemars(with = "BigInt<u64>")]
+        #[serde_as(as = "BigInt<u64>")]
+        to: CheckpointSequenceNumber,
+    },
 
     All(Vec<EventFilter>),
     Any(Vec<EventFilter>),
@@ -267,6 +299,32 @@ pub enum EventFilter {
     Or(Box<EventFilter>, Box<EventFilter>),
 }
 
+/// Bounds on the event filters subscriptions are created with. Every streamed event is matched
+/// against the filter of every subscription, so that filters combining many others would let a
+/// single client slow all of them down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EventFilterLimits {
+    /// Levels of `All`, `Any`, `And` and `Or` a filter may nest, a filter combining no others
+    /// being one level deep.
+    pub max_depth: usize,
+    /// Filters a filter may be made of, itself and the combining ones included.
+    pub max_nodes: usize,
+}
+
+impl EventFilterLimits {
+    pub const DEFAULT_MAX_DEPTH: usize = 8;
+    pub const DEFAULT_MAX_NODES: usize = 64;
+}
+
+impl Default for EventFilterLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: Self::DEFAULT_MAX_DEPTH,
+            max_nodes: Self::DEFAULT_MAX_NODES,
+        }
+    }
+}
+
 impl EventFilter {
     fn try_matches(&self, item: &MgoEvent) -> MgoResult<bool> {
         Ok(match self {
@@ -279,14 +337,24 @@ impl EventFilter {
             EventFilter::MoveModule { package, module } => {
                 &item.transaction_module == module && &item.package_id == package
             }
-            EventFilter::All(filters) => filters.iter().all(|f| f.matches(item)),
-            EventFilter::Any(filters) => filters.iter().any(|f| f.matches(item)),
-            EventFilter::And(f1, f2) => {
-                EventFilter::All(vec![*(*f1).clone(), *(*f2).clone()]).matches(item)
+            EventFilter::All(filters) => {
+                for filter in filters {
+                    if !filter.try_matches(item)? {
+                        return Ok(false);
+                    }
+                }
+                true
             }
-            EventFilter::Or(f1, f2) => {
-                EventFilter::Any(vec![*(*f1).clone(), *(*f2).clone()]).matches(item)
+            EventFilter::Any(filters) => {
+                for filter in filters {
+                    if filter.try_matches(item)? {
+                        return Ok(true);
+                    }
+                }
+                false
             }
+            EventFilter::And(f1, f2) => f1.try_matches(item)? && f2.try_matches(item)?,
+            EventFilter::Or(f1, f2) => f1.try_matches(item)? || f2.try_matches(item)?,
             EventFilter::Transaction(digest) => digest == &item.id.tx_digest,
 
             EventFilter::TimeRange {
@@ -302,9 +370,44 @@ impl EventFilter {
             EventFilter::MoveEventModule { package, module } => {
                 &item.type_.module == module && &ObjectID::from(item.type_.address) == package
             }
+            EventFilter::Checkpoint(_) | EventFilter::CheckpointRange { .. } => false,
         })
     }
 
+    /// Checks that the filter can be matched against streamed events within `limits`, or
+    /// returns why not. Subscriptions check their filter when they are created, so that
+    /// clients are refused up front rather than never notified.
+    pub fn validate_for_subscription(&self, limits: &EventFilterLimits) -> anyhow::Result<()> {
+        let mut nodes = 0;
+        let mut pending = vec![(self, 1)];
+        while let Some((filter, depth)) = pending.pop() {
+            nodes += 1;
+            if nodes > limits.max_nodes {
+                bail!("Event filter combines more than {} filters", limits.max_nodes);
+            }
+            if depth > limits.max_depth {
+                bail!("Event filter nests filters deeper than {} levels", limits.max_depth);
+            }
+            match filter {
+                EventFilter::All(filters) | EventFilter::Any(filters) => {
+                    pending.extend(filters.iter().map(|filter| (filter, depth + 1)));
+                }
+                EventFilter::And(left, right) | EventFilter::Or(left, right) => {
+                    pending.push((left, depth + 1));
+                    pending.push((right, depth + 1));
+                }
+                EventFilter::Checkpoint(_) | EventFilter::CheckpointRange { .. } => {
+                    bail!(
+                        "Checkpoint filters are not supported by subscriptions, as streamed \
+                        events do not carry their checkpoint"
+                    );
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
     pub fn and(self, other_filter: EventFilter) -> Self {
         Self::All(vec![self, other_filter])
     }
@@ -3,25 +3,38 @@
 
 use std::collections::BTreeMap;
 
+use fastcrypto::encoding::Base64;
 use fastcrypto::traits::ToFromBytes;
 use move_core_types::identifier::Identifier;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
+use serde_json::Value;
 use serde_with::serde_as;
 use serde_with::DisplayFromStr;
 
 use mgo_types::base_types::AuthorityName;
-use mgo_types::base_types::{EpochId, ObjectID};
+use mgo_types::base_types::{EpochId, MgoAddress, ObjectID, SequenceNumber};
 use mgo_types::committee::Committee;
+use mgo_types::digests::TransactionDigest;
+use mgo_types::dynamic_field::DynamicFieldName;
 use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
 use mgo_types::mgo_serde::BigInt;
+use mgo_types::mgo_serde::SequenceNumber as AsSequenceNumber;
 use mgo_types::mgo_system_state::mgo_system_state_summary::MgoValidatorSummary;
+use mgo_types::object::Owner;
 
 use crate::Page;
 
 pub type EpochPage = Page<EpochInfo, BigInt<u64>>;
 pub type EpochMetricsPage = Page<EpochMetrics, BigInt<u64>>;
+pub type CoinTypesPage = Page<CoinTypeInfo, String>;
+/// Dependents of a package, by the original ID of the dependent.
+pub type PackageDependentsPage = Page<PackageDependency, ObjectID>;
+/// Orphaned dynamic fields, by the ID of their `Field` wrapper object.
+pub type OrphanedDynamicFieldsPage = Page<OrphanedDynamicField, ObjectID>;
+/// Ownership intervals of an object, by the version of the object starting them.
+pub type ObjectOwnersHistoryPage = Page<OwnershipInterval, SequenceNumber>;
 
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -59,24 +72,80 @@ impl EpochInfo {
     }
 }
 
-/// a light-weight version of `EpochInfo` for faster loading
+/// Metrics of an epoch, rolled up by the indexer as the checkpoints of the epoch are indexed
 #[serde_as]
-#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct EpochMetrics {
     #[schemars(with = "BigInt<u64>")]
     #[serde_as(as = "BigInt<u64>")]
     pub epoch: EpochId,
+    /// count of tx in epoch
     #[schemars(with = "BigInt<u64>")]
     #[serde_as(as = "BigInt<u64>")]
     pub epoch_total_transactions: u64,
+    /// first, last checkpoint sequence numbers counted so far
     #[schemars(with = "BigInt<u64>")]
     #[serde_as(as = "BigInt<u64>")]
     pub first_checkpoint_id: CheckpointSequenceNumber,
     #[schemars(with = "BigInt<u64>")]
     #[serde_as(as = "BigInt<u64>")]
-    pub epoch_start_timestamp: u64,
-    pub end_of_epoch_info: Option<EndOfEpochInfo>,
+    pub last_checkpoint_id: CheckpointSequenceNumber,
+    /// computation and storage costs of the tx in epoch
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub total_gas_used: u64,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub total_storage_rebate: u64,
+    /// count of distinct senders of user tx in epoch
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub active_senders: u64,
+    /// count of senders or recipients seen for the first time in epoch
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub new_addresses: u64,
+    /// whether the epoch has ended and its metrics are final
+    pub finalized: bool,
+}
+
+/// Performance of a validator over an epoch. Metrics that cannot be derived from what the
+/// indexer ingests are `None`, rather than zero.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorReport {
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub epoch: EpochId,
+    pub validator_address: MgoAddress,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub voting_power: u64,
+    /// count of checkpoints of the epoch indexed so far
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub checkpoints: u64,
+    /// count of those checkpoints whose certificate the validator signed
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub checkpoints_signed: u64,
+    /// count of transactions the validator signed certificates of, which checkpoints do not
+    /// record, so always `None` for now
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub transactions_certified: Option<u64>,
+    /// count of validators that reported the validator during the epoch, `None` until the
+    /// epoch has ended
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub reporters: Option<u64>,
+    /// score of the validator under the tallying rule, 0 if it was slashed and 1 otherwise,
+    /// `None` until the epoch has ended
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub tallying_score: Option<u64>,
 }
 
 #[serde_as]
@@ -194,3 +263,469 @@ pub struct AddressMetrics {
     pub cumulative_active_addresses: u64,
     pub daily_active_addresses: u64,
 }
+
+/// Distinct senders per UTC day, days being counted from the unix epoch.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveAddressStats {
+    /// First day of the range, inclusive
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub from_day: u64,
+    /// Last day of the range, inclusive
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub to_day: u64,
+    /// Active addresses of every day in the range
+    pub daily: Vec<DailyActiveAddresses>,
+    /// Addresses active on at least one day of the range
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub active_addresses: u64,
+}
+
+/// Share of the addresses first seen on `cohort_day` that were active on each later day.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CohortRetention {
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub cohort_day: u64,
+    /// Number of addresses that sent their first transaction on `cohort_day`
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub cohort_size: u64,
+    /// One entry per day from `cohort_day` to `cohort_day + horizon_days`, inclusive
+    pub retention: Vec<DailyRetention>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyActiveAddresses {
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub day: u64,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub active_addresses: u64,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyRetention {
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub day: u64,
+    /// Addresses of the cohort that sent a transaction on `day`
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub active_addresses: u64,
+    /// `active_addresses` over the cohort size, 0 for an empty cohort
+    pub fraction: f64,
+}
+
+/// A coin type seen on chain, through one of its coins, its treasury cap or its metadata.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinTypeInfo {
+    /// e.g. `0x2::mgo::MGO`
+    pub coin_type: String,
+    /// Checkpoint the coin type was first seen in
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub first_seen_checkpoint: CheckpointSequenceNumber,
+    /// ID of the `CoinMetadata` object of the coin type, once it has been seen
+    pub metadata_object_id: Option<ObjectID>,
+    /// Approximate number of addresses owning a positive balance of the coin type
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub holder_count: u64,
+}
+
+/// Order of the coin types returned by `mgox_getCoinTypes`.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CoinTypeOrder {
+    /// Earliest seen first
+    #[default]
+    FirstSeen,
+    /// Most held first
+    Holders,
+}
+
+/// Contention statistics for a shared object over a window of recent checkpoints.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedObjectStats {
+    pub object_id: ObjectID,
+    /// First checkpoint of the window, inclusive
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub from_checkpoint: CheckpointSequenceNumber,
+    /// Last checkpoint of the window, inclusive
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub to_checkpoint: CheckpointSequenceNumber,
+    /// Number of transactions in the window taking the object as input
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub total_transactions: u64,
+    /// Transactions touching the object, for every checkpoint in the window that had any
+    pub transactions_per_checkpoint: Vec<CheckpointTransactionCount>,
+    /// Average zero-based position of those transactions within their checkpoints
+    pub average_position_in_checkpoint: Option<f64>,
+    /// Largest zero-based position of those transactions within their checkpoints
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub max_position_in_checkpoint: Option<u64>,
+    /// How many versions the object has advanced past the version assigned to the most
+    /// recent transaction in the window, when it can be derived from that transaction's effects
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub assigned_version_lag: Option<u64>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointTransactionCount {
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub checkpoint: CheckpointSequenceNumber,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub transaction_count: u64,
+}
+
+/// Bytecode-level disassembly of a published Move module.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleDisassembly {
+    pub package: ObjectID,
+    pub module: String,
+    /// Functions in definition order, so that a function's position is its definition index
+    pub functions: Vec<FunctionDisassembly>,
+    /// Whether a source map was published for the module, making source locations resolvable
+    pub has_source_map: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionDisassembly {
+    /// Definition index of the function, as reported in abort locations
+    pub function_index: u16,
+    pub name: String,
+    /// One of `public`, `friend` or `private`
+    pub visibility: String,
+    pub is_entry: bool,
+    pub is_native: bool,
+    pub instructions: Vec<DisassembledInstruction>,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DisassembledInstruction {
+    /// Offset of the instruction within its function's code
+    pub offset: u16,
+    pub instruction: String,
+}
+
+/// The function and instruction an abort happened at.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AbortLocation {
+    pub package: ObjectID,
+    pub module: String,
+    pub function_index: u16,
+    pub function_name: String,
+    pub code_offset: u16,
+    /// The instruction at `code_offset`
+    pub instruction: String,
+    /// Source location of the instruction, when a source map was published for the module
+    pub source: Option<SourceLocation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceLocation {
+    /// Name of the source file, when it was published alongside the source map
+    pub file: Option<String>,
+    /// Byte range of the source span within the file
+    pub start_byte: u32,
+    pub end_byte: u32,
+    /// One-based line and column of the start of the span, when the source file is available
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+/// The entry stored under a key of a Move table, as read by `getTableValues`.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableValue {
+    /// BCS bytes of the key, as requested
+    #[serde_as(as = "Base64")]
+    #[schemars(with = "Base64")]
+    pub key: Vec<u8>,
+    /// ID of the `Field<K, V>` object the entry is stored in
+    pub field_id: ObjectID,
+    /// The value stored under the key, `None` if the table has no entry for it
+    pub value: Option<TableEntryValue>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableEntryValue {
+    /// The value decoded with the layout of the table's value type
+    pub json: Value,
+    /// BCS bytes of the value
+    #[serde_as(as = "Base64")]
+    #[schemars(with = "Base64")]
+    pub bcs: Vec<u8>,
+}
+
+/// Sizes of the tables of an indexer, as last sampled by its writer.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageStats {
+    /// When the tables were last sampled, in milliseconds since the unix epoch, or `None` if
+    /// they have not been sampled yet
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub sampled_at_ms: Option<u64>,
+    pub tables: Vec<TableStorageStats>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableStorageStats {
+    pub table_name: String,
+    /// Number of rows, estimated from the planner statistics of the database
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub row_estimate: u64,
+    /// Bytes on disk of the table, its indexes and TOAST data
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub total_bytes: u64,
+    /// Rows the table grew by per day, measured against its latest sample at least a day old.
+    /// `None` until the table has been sampled for a day
+    #[schemars(with = "Option<BigInt<i64>>")]
+    #[serde_as(as = "Option<BigInt<i64>>")]
+    pub rows_per_day: Option<i64>,
+    /// Bytes the table grew by per day, measured like `rows_per_day`
+    #[schemars(with = "Option<BigInt<i64>>")]
+    #[serde_as(as = "Option<BigInt<i64>>")]
+    pub bytes_per_day: Option<i64>,
+}
+
+/// Buckets of the supply history returned by `mgox_getSupplyHistory`.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, Copy, PartialEq, Eq)]
+pub enum SupplyGranularity {
+    /// One bucket per epoch, numbered by epoch
+    Epoch,
+    /// One bucket per UTC day, numbered by days since the unix epoch
+    Day,
+}
+
+/// Supply of a coin type over a range of buckets, from its amounts minted and burned through
+/// its treasury cap.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SupplyHistory {
+    pub coin_type: String,
+    /// Supply of the coin type when the indexer first saw it change
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub initial_supply: u64,
+    /// Whether the coin type was minted before the indexer saw its treasury cap created, in
+    /// which case the initial supply was read from the cap rather than summed from its history
+    pub approximate: bool,
+    /// Every bucket of the requested range, in order
+    pub buckets: Vec<SupplyBucket>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SupplyBucket {
+    /// The epoch or day of the bucket
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub bucket: u64,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub minted: u64,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub burned: u64,
+    /// Supply at the end of the bucket
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub total_supply: u64,
+}
+
+/// A package a version of another package links to, as recorded in the linkage table of the
+/// depending package when it was published or upgraded.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageDependency {
+    /// The depending package, at the version linking to the dependency
+    pub package_id: ObjectID,
+    #[schemars(with = "AsSequenceNumber")]
+    #[serde_as(as = "AsSequenceNumber")]
+    pub package_version: SequenceNumber,
+    /// ID of the first version of the depending package
+    pub original_package_id: ObjectID,
+    /// The dependency, at the version linked to
+    pub dependency_id: ObjectID,
+    #[schemars(with = "AsSequenceNumber")]
+    #[serde_as(as = "AsSequenceNumber")]
+    pub dependency_version: SequenceNumber,
+    /// ID of the first version of the dependency
+    pub dependency_original_id: ObjectID,
+    /// Checkpoint the depending package was published in
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub checkpoint: CheckpointSequenceNumber,
+}
+
+/// A dynamic object field whose value object is no longer live, because it was deleted or
+/// wrapped without the field being removed from its parent.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedDynamicField {
+    /// The `Field` wrapper object left behind
+    pub field_id: ObjectID,
+    /// The object the field was added to
+    pub parent_id: ObjectID,
+    pub name: DynamicFieldName,
+    /// The value object the field still refers to
+    pub value_object_id: ObjectID,
+    pub value_object_type: String,
+    /// Checkpoint up to which the scan that found the orphan ran
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub detected_at_checkpoint: CheckpointSequenceNumber,
+}
+
+/// What a client needs to know about the chain to start issuing requests, as of the latest
+/// checkpoint. Every field is taken from the same epoch as the checkpoint.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainState {
+    pub chain_identifier: String,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub checkpoint: CheckpointSequenceNumber,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub checkpoint_timestamp_ms: u64,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub epoch: EpochId,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub reference_gas_price: u64,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub protocol_version: u64,
+    /// The namespaces of the methods the server serves, such as `mgo` and `mgox`
+    pub namespaces: Vec<String>,
+}
+
+/// Outcome of comparing a local build of a package to a published version of it.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageVerification {
+    pub package_id: ObjectID,
+    #[schemars(with = "AsSequenceNumber")]
+    #[serde_as(as = "AsSequenceNumber")]
+    pub version: SequenceNumber,
+    /// ID of the first version of the package
+    pub original_package_id: ObjectID,
+    /// Every indexed version of the package, oldest first
+    pub upgrade_lineage: Vec<PackageVersion>,
+    /// Whether every module matches, and no module is missing on either side
+    pub matches: bool,
+    /// Modules of either side, ordered by name
+    pub modules: Vec<ModuleVerification>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageVersion {
+    pub package_id: ObjectID,
+    #[schemars(with = "AsSequenceNumber")]
+    #[serde_as(as = "AsSequenceNumber")]
+    pub version: SequenceNumber,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleVerification {
+    pub module: String,
+    pub status: ModuleVerificationStatus,
+    /// Number of function handles, declared or called, found on only one side
+    pub differing_function_handles: usize,
+    /// Functions defined on only one side, or whose signature or code differ
+    pub differing_functions: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleVerificationStatus {
+    /// The bytecode is the same, once the module's self address is set
+    Match,
+    Mismatch,
+    /// The module was given but is not part of the published package
+    MissingOnChain,
+    /// The module is part of the published package but was not given
+    MissingLocally,
+}
+
+/// A stretch of the history of an object over which it kept the same owner, however many
+/// versions it went through.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnershipInterval {
+    pub owner: HistoricalOwner,
+    /// Version of the object the interval starts at
+    #[schemars(with = "AsSequenceNumber")]
+    #[serde_as(as = "AsSequenceNumber")]
+    pub from_version: SequenceNumber,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub from_checkpoint: CheckpointSequenceNumber,
+    /// Checkpoint the next interval starts at, or none if the interval is the last one
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub to_checkpoint: Option<CheckpointSequenceNumber>,
+    /// Transaction that handed the object to the owner. Only the versions of live objects are
+    /// stored, so it is unknown for wrapped objects.
+    pub acquiring_tx: Option<TransactionDigest>,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, Copy, PartialEq, Eq)]
+pub enum HistoricalOwner {
+    /// Owned by an address or an object, shared or frozen
+    Owner(Owner),
+    /// Wrapped in another object, and unwrapped since
+    Wrapped,
+    /// Wrapped in another object or deleted, as it has not been live since
+    WrappedOrDeleted,
+}
@@ -7,6 +7,7 @@ use serde_with::serde_as;
 use mgo_types::base_types::{AuthorityName, EpochId, ObjectID, MgoAddress};
 use mgo_types::committee::{Committee, StakeUnit};
 use mgo_types::mgo_serde::BigInt;
+use mgo_types::mgo_system_state::mgo_system_state_summary::MgoValidatorSummary;
 
 /// RPC representation of the [Committee] type.
 #[serde_as]
@@ -73,6 +74,115 @@ pub struct Stake {
     pub status: StakeStatus,
 }
 
+/// The stakes of an owner grouped by the validator they are staked with, as returned by
+/// `mgox_getOwnedStakedObjectsSummary`.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnedStakesSummary {
+    pub owner: MgoAddress,
+    /// Epoch the rewards are estimated at.
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub epoch: EpochId,
+    /// Validators with the largest principal first.
+    pub validators: Vec<ValidatorStakesSummary>,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub total_principal: u64,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub total_estimated_reward: u64,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub stake_count: u64,
+}
+
+/// The stakes of an owner with a single validator.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorStakesSummary {
+    pub validator_address: MgoAddress,
+    pub staking_pool: ObjectID,
+    /// Name of the validator, or `None` if it is no longer active.
+    pub name: Option<String>,
+    /// Image of the validator, or `None` if it is no longer active or has none.
+    pub image_url: Option<String>,
+    /// Principal of all the stakes, pending ones included.
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub principal: u64,
+    /// Rewards estimated for the active stakes.
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub estimated_reward: u64,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub stake_count: u64,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub earliest_activation_epoch: EpochId,
+}
+
+impl OwnedStakesSummary {
+    /// Summarizes the stakes of `owner` as of `epoch`, naming the validators after their
+    /// summary among `active_validators`, matched by staking pool.
+    pub fn new(
+        owner: MgoAddress,
+        epoch: EpochId,
+        stakes: Vec<DelegatedStake>,
+        active_validators: &[MgoValidatorSummary],
+    ) -> Self {
+        let mut validators: Vec<_> = stakes
+            .into_iter()
+            .filter(|delegated| !delegated.stakes.is_empty())
+            .map(|delegated| {
+                let metadata = active_validators
+                    .iter()
+                    .find(|validator| validator.staking_pool_id == delegated.staking_pool);
+                ValidatorStakesSummary {
+                    validator_address: delegated.validator_address,
+                    staking_pool: delegated.staking_pool,
+                    name: metadata.map(|validator| validator.name.clone()),
+                    image_url: metadata
+                        .map(|validator| validator.image_url.clone())
+                        .filter(|url| !url.is_empty()),
+                    principal: delegated.stakes.iter().map(|stake| stake.principal).sum(),
+                    estimated_reward: delegated
+                        .stakes
+                        .iter()
+                        .map(|stake| match stake.status {
+                            StakeStatus::Active { estimated_reward } => estimated_reward,
+                            StakeStatus::Pending | StakeStatus::Unstaked => 0,
+                        })
+                        .sum(),
+                    stake_count: delegated.stakes.len() as u64,
+                    earliest_activation_epoch: delegated
+                        .stakes
+                        .iter()
+                        .map(|stake| stake.stake_active_epoch)
+                        .min()
+                        .unwrap_or_default(),
+                }
+            })
+            .collect();
+        validators.sort_by(|a, b| {
+            b.principal
+                .cmp(&a.principal)
+                .then(a.validator_address.cmp(&b.validator_address))
+        });
+        Self {
+            owner,
+            epoch,
+            total_principal: validators.iter().map(|v| v.principal).sum(),
+            total_estimated_reward: validators.iter().map(|v| v.estimated_reward).sum(),
+            stake_count: validators.iter().map(|v| v.stake_count).sum(),
+            validators,
+        }
+    }
+}
+
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct ValidatorApys {
@@ -9,7 +9,7 @@ use std::fmt::{Display, Formatter};
 
 use anyhow::anyhow;
 use colored::Colorize;
-use fastcrypto::encoding::Base64;
+use fastcrypto::encoding::{Base58, Base64};
 use move_bytecode_utils::module_cache::GetModule;
 use move_core_types::annotated_value::{MoveStruct, MoveStructLayout};
 use move_core_types::identifier::Identifier;
@@ -26,6 +26,7 @@ use mgo_types::base_types::{
     ObjectDigest, ObjectID, ObjectInfo, ObjectRef, ObjectType, SequenceNumber, MgoAddress,
     TransactionDigest,
 };
+use mgo_types::dynamic_field::{DynamicFieldInfo, DynamicFieldName, DynamicFieldType};
 use mgo_types::error::{ExecutionError, MgoObjectResponseError, UserInputError, UserInputResult};
 use mgo_types::gas_coin::GasCoin;
 use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
@@ -34,6 +35,7 @@ use mgo_types::object::{Data, MoveObject, Object, ObjectInner, ObjectRead, Owner
 use mgo_types::mgo_serde::BigInt;
 use mgo_types::mgo_serde::SequenceNumber as AsSequenceNumber;
 use mgo_types::mgo_serde::MgoStructTag;
+use mgo_types::mgo_serde::Readable;
 
 use crate::{Page, MgoMoveStruct, MgoMoveValue};
 
@@ -181,6 +183,13 @@ pub struct MgoObjectData {
     #[serde_as(as = "Option<DisplayFromStr>")]
     #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
     pub type_: Option<ObjectType>,
+    /// The type of the object with the package of each type in it resolved to the latest upgrade
+    /// of that package, the same as `type` if none of them was upgraded. Default to be None
+    /// unless MgoObjectDataOptions.resolveTypeToLatest is set to true
+    #[schemars(with = "Option<String>")]
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_type: Option<ObjectType>,
     // Default to be None because otherwise it will be repeated for the getOwnedObjects endpoint
     /// The owner of this object. Default to be None unless MgoObjectDataOptions.showOwner is set to true
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -189,6 +198,11 @@ pub struct MgoObjectData {
     /// MgoObjectDataOptions.showPreviousTransaction is set to true
     #[serde(skip_serializing_if = "Option::is_none")]
     pub previous_transaction: Option<TransactionDigest>,
+    /// The sender, checkpoint and timestamp of the previous transaction. Default to be None unless
+    /// MgoObjectDataOptions.showPreviousTransactionDetails is set to true, and also None if the
+    /// transaction was pruned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_transaction_details: Option<MgoPreviousTransactionDetails>,
     /// The amount of MGO we would rebate if this object gets deleted.
     /// This number is re-calculated each time the object is mutated based on
     /// the present storage gas price.
@@ -209,6 +223,24 @@ pub struct MgoObjectData {
     pub bcs: Option<MgoRawData>,
 }
 
+/// Summary of the transaction that created or last mutated an object.
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Eq, PartialEq)]
+#[serde(rename_all = "camelCase", rename = "PreviousTransactionDetails")]
+pub struct MgoPreviousTransactionDetails {
+    pub sender: MgoAddress,
+    /// The checkpoint that includes the transaction, None if it is not checkpointed yet
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<CheckpointSequenceNumber>,
+    /// The timestamp of the checkpoint that includes the transaction, in milliseconds
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp_ms: Option<u64>,
+}
+
 impl MgoObjectData {
     pub fn object_ref(&self) -> ObjectRef {
         (self.object_id, self.version, self.digest)
@@ -339,6 +371,17 @@ pub struct MgoObjectDataOptions {
     pub show_bcs: bool,
     /// Whether to show the storage rebate of the object. Default to be False
     pub show_storage_rebate: bool,
+    /// If set, only these top-level fields of a Move object are included in its content.
+    /// Unrequested fields are skipped without being parsed. Default to be None (all fields)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field_mask: Option<Vec<String>>,
+    /// Whether to also show the type of the object with the package of each type in it resolved
+    /// to the latest upgrade of that package. Default to be False
+    pub resolve_type_to_latest: bool,
+    /// Whether to show the sender, checkpoint and timestamp of the previous transaction of the
+    /// object, along with its digest. Supported by getObject and multiGetObjects. Default to
+    /// be False
+    pub show_previous_transaction_details: bool,
 }
 
 impl MgoObjectDataOptions {
@@ -356,6 +399,9 @@ impl MgoObjectDataOptions {
             show_display: false,
             show_content: false,
             show_storage_rebate: true,
+            field_mask: None,
+            resolve_type_to_latest: false,
+            show_previous_transaction_details: false,
         }
     }
 
@@ -369,6 +415,9 @@ impl MgoObjectDataOptions {
             show_display: false,
             show_content: true,
             show_storage_rebate: true,
+            field_mask: None,
+            resolve_type_to_latest: false,
+            show_previous_transaction_details: false,
         }
     }
 
@@ -402,11 +451,56 @@ impl MgoObjectDataOptions {
         self
     }
 
+    pub fn with_field_mask(mut self, field_mask: Vec<String>) -> Self {
+        self.field_mask = Some(field_mask);
+        self
+    }
+
+    pub fn with_type_resolved_to_latest(mut self) -> Self {
+        self.resolve_type_to_latest = true;
+        self
+    }
+
+    pub fn with_previous_transaction_details(mut self) -> Self {
+        self.show_previous_transaction_details = true;
+        self
+    }
+
+    /// Checks that every name in the field mask, if any, is a top-level field of `layout`.
+    pub fn check_field_mask(&self, layout: &MoveStructLayout) -> Result<(), anyhow::Error> {
+        match &self.field_mask {
+            Some(field_mask) => check_field_mask(field_mask, layout),
+            None => Ok(()),
+        }
+    }
+
     pub fn is_not_in_object_info(&self) -> bool {
         self.show_bcs || self.show_content || self.show_display || self.show_storage_rebate
     }
 }
 
+fn check_field_mask(field_mask: &[String], layout: &MoveStructLayout) -> Result<(), anyhow::Error> {
+    let is_field = |name: &String| layout.fields.iter().any(|f| f.name.as_str() == name);
+    let unknown = field_mask
+        .iter()
+        .filter(|name| !is_field(name))
+        .collect::<Vec<_>>();
+    if !unknown.is_empty() {
+        let available = layout
+            .fields
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect::<Vec<_>>();
+        return Err(anyhow!(
+            "Unknown fields {:?} in field mask for {}, available fields are {:?}",
+            unknown,
+            layout.type_,
+            available
+        ));
+    }
+    Ok(())
+}
+
 impl TryFrom<(ObjectRead, MgoObjectDataOptions)> for MgoObjectResponse {
     type Error = anyhow::Error;
 
@@ -450,9 +544,11 @@ impl TryFrom<(ObjectInfo, MgoObjectDataOptions)> for MgoObjectResponse {
             version: object_info.version,
             digest: object_info.digest,
             type_: show_type.then_some(object_info.type_),
+            latest_type: None,
             owner: show_owner.then_some(object_info.owner),
             previous_transaction: show_previous_transaction
                 .then_some(object_info.previous_transaction),
+            previous_transaction_details: None,
             storage_rebate: None,
             display: None,
             content: None,
@@ -486,6 +582,8 @@ impl
             show_content,
             show_bcs,
             show_storage_rebate,
+            field_mask,
+            show_previous_transaction_details,
             ..
         } = options;
 
@@ -520,7 +618,14 @@ impl
                     let layout = layout.ok_or_else(|| {
                         anyhow!("Layout is required to convert Move object to json")
                     })?;
-                    MgoParsedData::try_from_object(m, layout)?
+                    match &field_mask {
+                        Some(field_mask) => MgoParsedData::MoveObject(
+                            MgoParsedMoveObject::try_from_layout_with_field_mask(
+                                m, layout, field_mask,
+                            )?,
+                        ),
+                        None => MgoParsedData::try_from_object(m, layout)?,
+                    }
                 }
                 Data::Package(p) => MgoParsedData::try_from_package(p)?,
             };
@@ -534,17 +639,17 @@ impl
             version,
             digest,
             type_,
+            latest_type: None,
             owner: if show_owner { Some(o.owner) } else { None },
             storage_rebate: if show_storage_rebate {
                 Some(o.storage_rebate)
             } else {
                 None
             },
-            previous_transaction: if show_previous_transaction {
-                Some(o.previous_transaction)
-            } else {
-                None
-            },
+            // The details of the previous transaction are looked up by its digest.
+            previous_transaction: (show_previous_transaction || show_previous_transaction_details)
+                .then_some(o.previous_transaction),
+            previous_transaction_details: None,
             content,
             bcs,
             display: None,
@@ -879,22 +984,7 @@ impl MgoMoveObject for MgoParsedMoveObject {
         layout: MoveStructLayout,
     ) -> Result<Self, anyhow::Error> {
         let move_struct = object.to_move_struct(&layout)?.into();
-
-        Ok(
-            if let MgoMoveStruct::WithTypes { type_, fields } = move_struct {
-                MgoParsedMoveObject {
-                    type_,
-                    has_public_transfer: object.has_public_transfer(),
-                    fields: MgoMoveStruct::WithFields(fields),
-                }
-            } else {
-                MgoParsedMoveObject {
-                    type_: object.type_().clone().into(),
-                    has_public_transfer: object.has_public_transfer(),
-                    fields: move_struct,
-                }
-            },
-        )
+        Ok(Self::from_move_struct(&object, move_struct))
     }
 
     fn type_(&self) -> &StructTag {
@@ -903,6 +993,34 @@ impl MgoMoveObject for MgoParsedMoveObject {
 }
 
 impl MgoParsedMoveObject {
+    /// Like [`MgoMoveObject::try_from_layout`], but only parses the top-level fields named in
+    /// `field_mask`, failing if any of them is not a field of `layout`.
+    pub fn try_from_layout_with_field_mask(
+        object: MoveObject,
+        layout: MoveStructLayout,
+        field_mask: &[String],
+    ) -> Result<Self, anyhow::Error> {
+        check_field_mask(field_mask, &layout)?;
+        let move_struct = object.to_partial_move_struct(&layout, field_mask)?.into();
+        Ok(Self::from_move_struct(&object, move_struct))
+    }
+
+    fn from_move_struct(object: &MoveObject, move_struct: MgoMoveStruct) -> Self {
+        if let MgoMoveStruct::WithTypes { type_, fields } = move_struct {
+            MgoParsedMoveObject {
+                type_,
+                has_public_transfer: object.has_public_transfer(),
+                fields: MgoMoveStruct::WithFields(fields),
+            }
+        } else {
+            MgoParsedMoveObject {
+                type_: object.type_().clone().into(),
+                has_public_transfer: object.has_public_transfer(),
+                fields: move_struct,
+            }
+        }
+    }
+
     pub fn try_from_object_read(object_read: ObjectRead) -> Result<Self, anyhow::Error> {
         let parsed_data = MgoParsedData::try_from_object_read(object_read)?;
         match parsed_data {
@@ -1116,6 +1234,33 @@ pub struct CheckpointedObjectID {
     pub at_checkpoint: Option<CheckpointSequenceNumber>,
 }
 
+pub type ModifiedObjectsPage = Page<MgoObjectResponse, ModifiedObjectCursor>;
+
+/// Position in a listing of objects ordered by the checkpoint that last modified them. It holds
+/// the full sort key of the last object returned, so the next page starts at the same place even
+/// if that object is modified in the meantime.
+#[serde_as]
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModifiedObjectCursor {
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub checkpoint: CheckpointSequenceNumber,
+    pub object_id: ObjectID,
+}
+
+pub type MultiOwnerObjectsPage = Page<MgoObjectResponse, MultiOwnerObjectCursor>;
+
+/// Position in a listing of the objects owned by several addresses, which walks the addresses in
+/// request order and the objects of each address by object ID.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiOwnerObjectCursor {
+    /// Index in the requested addresses of the owner of the last object returned.
+    pub address_index: usize,
+    pub object_id: ObjectID,
+}
+
 #[serde_as]
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Eq, PartialEq)]
 #[serde(rename = "GetPastObjectRequest", rename_all = "camelCase")]
@@ -1183,20 +1328,10 @@ impl MgoObjectDataFilter {
             MgoObjectDataFilter::MatchAll(filters) => !filters.iter().any(|f| !f.matches(object)),
             MgoObjectDataFilter::MatchAny(filters) => filters.iter().any(|f| f.matches(object)),
             MgoObjectDataFilter::MatchNone(filters) => !filters.iter().any(|f| f.matches(object)),
+            // If people do not provide type_params, we will match all type_params
+            // e.g. `0x2::coin::Coin` can match `0x2::coin::Coin<0x2::mgo::MGO>`
             MgoObjectDataFilter::StructType(s) => {
-                let obj_tag: StructTag = match &object.type_ {
-                    ObjectType::Package => return false,
-                    ObjectType::Struct(s) => s.clone().into(),
-                };
-                // If people do not provide type_params, we will match all type_params
-                // e.g. `0x2::coin::Coin` can match `0x2::coin::Coin<0x2::mgo::MGO>`
-                if !s.type_params.is_empty() && s.type_params != obj_tag.type_params {
-                    false
-                } else {
-                    obj_tag.address == s.address
-                        && obj_tag.module == s.module
-                        && obj_tag.name == s.name
-                }
+                matches!(&object.type_, ObjectType::Struct(o) if o.matches_type_fuzzy_generics(s))
             }
             MgoObjectDataFilter::MoveModule { package, module } => {
                 matches!(&object.type_, ObjectType::Struct(s) if &ObjectID::from(s.address()) == package
@@ -1246,3 +1381,70 @@ impl MgoObjectResponseQuery {
         }
     }
 }
+
+/// Most bytes, as JSON, of the field objects a page of dynamic fields inlines. Pages whose field
+/// objects take more end early.
+pub const DYNAMIC_FIELD_CONTENT_MAX_PAGE_BYTES: usize = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Eq, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DynamicFieldsOptions {
+    /// If set, only the fields whose name is of this type, e.g. `u64`, are returned. Default to be
+    /// None (all fields)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name_type: Option<String>,
+    /// Whether to include the field object of each field, with its content. Default to be False
+    pub show_content: bool,
+}
+
+/// A dynamic field, along with its field object if [`DynamicFieldsOptions::show_content`] was
+/// set. Serializes as [`DynamicFieldInfo`] does when there is no field object.
+#[serde_as]
+#[derive(Clone, Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(rename_all = "camelCase", rename = "DynamicFieldInfo")]
+pub struct MgoDynamicFieldInfo {
+    pub name: DynamicFieldName,
+    #[schemars(with = "Base58")]
+    #[serde_as(as = "Readable<Base58, _>")]
+    pub bcs_name: Vec<u8>,
+    pub type_: DynamicFieldType,
+    pub object_type: String,
+    pub object_id: ObjectID,
+    pub version: SequenceNumber,
+    pub digest: ObjectDigest,
+    /// The object `mgox_getDynamicFieldObject` returns for the name of this field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub object: Option<MgoObjectData>,
+}
+
+impl From<DynamicFieldInfo> for MgoDynamicFieldInfo {
+    fn from(info: DynamicFieldInfo) -> Self {
+        Self {
+            name: info.name,
+            bcs_name: info.bcs_name,
+            type_: info.type_,
+            object_type: info.object_type,
+            object_id: info.object_id,
+            version: info.version,
+            digest: info.digest,
+            object: None,
+        }
+    }
+}
+
+/// Number of leading `fields` whose field objects fit in [`DYNAMIC_FIELD_CONTENT_MAX_PAGE_BYTES`].
+/// The first field is always kept, so that walking the pages makes progress.
+pub fn dynamic_fields_within_content_budget(fields: &[MgoDynamicFieldInfo]) -> usize {
+    let mut bytes = 0;
+    fields
+        .iter()
+        .position(|field| {
+            bytes += field
+                .object
+                .as_ref()
+                .and_then(|object| serde_json::to_vec(object).ok())
+                .map_or(0, |json| json.len());
+            bytes > DYNAMIC_FIELD_CONTENT_MAX_PAGE_BYTES
+        })
+        .map_or(fields.len(), |over_budget| over_budget.max(1))
+}
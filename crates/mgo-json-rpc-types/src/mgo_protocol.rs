@@ -8,8 +8,17 @@ use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use serde_with::DisplayFromStr;
 use mgo_protocol_config::{ProtocolConfig, ProtocolConfigValue, ProtocolVersion};
+use mgo_types::base_types::{ObjectID, SequenceNumber};
+use mgo_types::committee::EpochId;
+use mgo_types::gas::GasCostSummary;
 use mgo_types::mgo_serde::Readable;
+use mgo_types::mgo_serde::SequenceNumber as AsSequenceNumber;
 use mgo_types::mgo_serde::{AsProtocolVersion, BigInt};
+use mgo_types::object::Object;
+use mgo_types::{
+    MGO_AUTHENTICATOR_STATE_OBJECT_ID, MGO_CLOCK_OBJECT_ID, MGO_DENY_LIST_OBJECT_ID,
+    MGO_RANDOMNESS_STATE_OBJECT_ID, MGO_SYSTEM_STATE_OBJECT_ID,
+};
 
 #[serde_as]
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, PartialEq)]
@@ -80,3 +89,226 @@ impl From<ProtocolConfig> for ProtocolConfigResponse {
         }
     }
 }
+
+/// Limits a client has to respect to build valid transactions and requests: the relevant
+/// subset of the protocol config in effect for `epoch`, together with the caps of the server.
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientLimits {
+    #[schemars(with = "AsProtocolVersion")]
+    #[serde_as(as = "Readable<AsProtocolVersion, _>")]
+    pub protocol_version: ProtocolVersion,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub epoch: EpochId,
+    /// Maximum size of a serialized transaction, in bytes
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub max_tx_size_bytes: Option<u64>,
+    /// Maximum number of input objects of a transaction
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub max_input_objects: Option<u64>,
+    /// Maximum number of gas payment objects of a transaction
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub max_gas_payment_objects: Option<u64>,
+    /// Maximum gas budget of a transaction, in MIST
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub max_gas_budget: Option<u64>,
+    /// Maximum size of a pure argument, in bytes
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub max_pure_argument_size: Option<u64>,
+    /// Maximum number of arguments of a programmable transaction command
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub max_arguments: Option<u64>,
+    /// Maximum number of type arguments of a Move call
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub max_type_arguments: Option<u64>,
+    /// Maximum number of commands of a programmable transaction
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub max_programmable_tx_commands: Option<u64>,
+    /// Maximum number of modules in a published package
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub max_modules_in_publish: Option<u64>,
+    /// Maximum size of a Move object, in bytes
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub max_move_object_size: Option<u64>,
+    pub server: ServerLimits,
+}
+
+impl ClientLimits {
+    pub fn new(config: &ProtocolConfig, epoch: EpochId, server: ServerLimits) -> Self {
+        Self {
+            protocol_version: config.version,
+            epoch,
+            max_tx_size_bytes: config.max_tx_size_bytes_as_option(),
+            max_input_objects: config.max_input_objects_as_option(),
+            max_gas_payment_objects: config.max_gas_payment_objects_as_option().map(u64::from),
+            max_gas_budget: config.max_tx_gas_as_option(),
+            max_pure_argument_size: config.max_pure_argument_size_as_option().map(u64::from),
+            max_arguments: config.max_arguments_as_option().map(u64::from),
+            max_type_arguments: config.max_type_arguments_as_option().map(u64::from),
+            max_programmable_tx_commands: config
+                .max_programmable_tx_commands_as_option()
+                .map(u64::from),
+            max_modules_in_publish: config.max_modules_in_publish_as_option().map(u64::from),
+            max_move_object_size: config.max_move_object_size_as_option(),
+            server,
+        }
+    }
+}
+
+/// Caps the RPC server applies to requests, independently of the protocol.
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerLimits {
+    /// Maximum number of items returned in a page
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub max_page_size: u64,
+    /// Maximum number of checkpoints returned in a page
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub max_checkpoint_page_size: u64,
+    /// Maximum number of objects or transactions requested in a single multi-get
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub max_multi_get_batch_size: u64,
+    /// Maximum number of owners whose objects can be listed in a single request
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub max_owned_objects_addresses: u64,
+}
+
+/// Why an object has no storage rebate to estimate.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+pub enum NoStorageRebateReason {
+    /// Immutable objects, packages included, can be neither deleted nor mutated.
+    Immutable,
+    /// Singleton system objects, such as the system state and the clock, are only written by
+    /// the system.
+    SystemObject,
+}
+
+/// MGO returned to the sender of a transaction deleting or mutating an object, estimated with
+/// the storage price of the protocol config in effect for `epoch`. Objects that cannot be
+/// deleted or mutated by a transaction have a `noRebateReason`, and all their amounts are zero.
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageRebateEstimate {
+    pub object_id: ObjectID,
+    #[schemars(with = "AsSequenceNumber")]
+    #[serde_as(as = "AsSequenceNumber")]
+    pub version: SequenceNumber,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub epoch: EpochId,
+    /// Storage price per unit, in MIST
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub storage_gas_price: u64,
+    /// Size of the object charged for storage, in bytes, metadata included
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub size: u64,
+    /// Storage rebate stored in the object, which is the storage fee paid when it was last
+    /// written, in MIST
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub storage_rebate: u64,
+    /// Part of the storage rebate returned to the sender deleting the object, in MIST. The rest
+    /// is kept by the storage fund.
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub deletion_rebate: u64,
+    /// Size of the object's contents the mutation is estimated for, in bytes
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub new_contents_size: Option<u64>,
+    /// Storage fee charged for the mutated object, in MIST
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub new_storage_cost: Option<u64>,
+    /// MGO returned to the sender by the mutation, the deletion rebate minus the new storage
+    /// fee, in MIST. Negative when the mutation costs more storage than it returns.
+    #[schemars(with = "Option<BigInt<i64>>")]
+    #[serde_as(as = "Option<BigInt<i64>>")]
+    pub mutation_rebate_delta: Option<i64>,
+    pub no_rebate_reason: Option<NoStorageRebateReason>,
+}
+
+impl StorageRebateEstimate {
+    /// Estimates the rebate of deleting `object`, and of mutating it so that its contents are
+    /// `new_contents_size` bytes long if given, under `config`.
+    pub fn new(
+        object: &Object,
+        config: &ProtocolConfig,
+        epoch: EpochId,
+        new_contents_size: Option<u64>,
+    ) -> Self {
+        let no_rebate_reason = if object.is_immutable() {
+            Some(NoStorageRebateReason::Immutable)
+        } else if SYSTEM_OBJECT_IDS.contains(&object.id()) {
+            Some(NoStorageRebateReason::SystemObject)
+        } else {
+            None
+        };
+        let size = object.object_size_for_gas_metering() as u64;
+        let mut estimate = Self {
+            object_id: object.id(),
+            version: object.version(),
+            epoch,
+            storage_gas_price: config.storage_gas_price(),
+            size,
+            storage_rebate: 0,
+            deletion_rebate: 0,
+            new_contents_size: None,
+            new_storage_cost: None,
+            mutation_rebate_delta: None,
+            no_rebate_reason,
+        };
+        if no_rebate_reason.is_some() {
+            return estimate;
+        }
+
+        estimate.storage_rebate = object.storage_rebate;
+        estimate.deletion_rebate = GasCostSummary::new(0, 0, object.storage_rebate, 0)
+            .sender_rebate(config.storage_rebate_rate());
+        if let Some(new_contents_size) = new_contents_size {
+            let contents_size = object
+                .data
+                .try_as_move()
+                .map_or(0, |move_object| move_object.contents().len() as u64);
+            let new_storage_cost = (size - contents_size)
+                .saturating_add(new_contents_size)
+                .saturating_mul(config.obj_data_cost_refundable())
+                .saturating_mul(config.storage_gas_price());
+            let delta = estimate.deletion_rebate as i128 - new_storage_cost as i128;
+            estimate.new_contents_size = Some(new_contents_size);
+            estimate.new_storage_cost = Some(new_storage_cost);
+            estimate.mutation_rebate_delta =
+                Some(delta.clamp(i64::MIN.into(), i64::MAX.into()) as i64);
+        }
+        estimate
+    }
+}
+
+/// Singleton objects only the system writes, which a transaction can neither delete nor mutate.
+const SYSTEM_OBJECT_IDS: [ObjectID; 5] = [
+    MGO_SYSTEM_STATE_OBJECT_ID,
+    MGO_CLOCK_OBJECT_ID,
+    MGO_AUTHENTICATOR_STATE_OBJECT_ID,
+    MGO_RANDOMNESS_STATE_OBJECT_ID,
+    MGO_DENY_LIST_OBJECT_ID,
+];
@@ -0,0 +1,144 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signatures servers may add to the results of some methods, as non-repudiable proof of what
+//! they returned. A signature covers a canonical serialization of the result, so that it can
+//! be verified from the result as parsed by any JSON library, together with the time of
+//! signing and the address of the signing key.
+
+use anyhow::{anyhow, bail};
+use itertools::Itertools;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::{Map, Value};
+use serde_with::serde_as;
+use shared_crypto::intent::{Intent, IntentMessage, PersonalMessage};
+
+use mgo_types::base_types::MgoAddress;
+use mgo_types::crypto::{MgoKeyPair, MgoSignature, Signature};
+use mgo_types::key_material::KeyMaterial;
+use mgo_types::mgo_serde::BigInt;
+
+/// Member of signed results holding their [ResponseSignature].
+pub const RESPONSE_SIGNATURE_FIELD: &str = "responseSignature";
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseSignature {
+    /// Address of the key that signed the result, identifying the server
+    pub signer: MgoAddress,
+    /// When the server signed the result, in milliseconds since the Unix epoch
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub timestamp_ms: u64,
+    /// Signature of the result and of the fields above, as a personal message
+    pub signature: Signature,
+}
+
+/// The message a [ResponseSignature] signs.
+#[derive(Serialize)]
+struct SignedResponse<'a> {
+    body: &'a [u8],
+    timestamp_ms: u64,
+    signer: MgoAddress,
+}
+
+impl ResponseSignature {
+    /// Signs `result` at `timestamp_ms` with `key`.
+    pub fn sign(result: &Value, timestamp_ms: u64, key: &MgoKeyPair) -> Self {
+        let signer = MgoAddress::from(&key.public());
+        let message = signed_message(&canonical_response_body(result), timestamp_ms, signer);
+        Self {
+            signer,
+            timestamp_ms,
+            signature: Signature::new_secure(&message, key),
+        }
+    }
+
+    /// Checks that the signer signed `result`, whether or not it holds this signature.
+    pub fn verify(&self, result: &Value) -> anyhow::Result<()> {
+        let body = canonical_response_body(result);
+        let message = signed_message(&body, self.timestamp_ms, self.signer);
+        self.signature
+            .verify_secure(&message, self.signer, self.signature.scheme())
+            .map_err(|e| anyhow!("Invalid signature of the response by {}: {e}", self.signer))
+    }
+}
+
+fn signed_message(
+    body: &[u8],
+    timestamp_ms: u64,
+    signer: MgoAddress,
+) -> IntentMessage<PersonalMessage> {
+    let response = SignedResponse {
+        body,
+        timestamp_ms,
+        signer,
+    };
+    let message = bcs::to_bytes(&response).expect("Serializing a response cannot fail");
+    IntentMessage::new(Intent::personal_message(), PersonalMessage { message })
+}
+
+/// Returns the signature of a signed `result`, once checked to be a signature of the rest of
+/// the result by `signer`, the key trusted to sign the responses of the server.
+pub fn verify_signed_response(
+    result: &Value,
+    signer: MgoAddress,
+) -> anyhow::Result<ResponseSignature> {
+    let signature = result
+        .get(RESPONSE_SIGNATURE_FIELD)
+        .ok_or_else(|| anyhow!("The response is not signed"))?;
+    let signature: ResponseSignature = serde_json::from_value(signature.clone())?;
+    if KeyMaterial::from(&signature.signer) != KeyMaterial::from(&signer) {
+        bail!(
+            "The response is signed by {}, instead of {signer}",
+            signature.signer
+        );
+    }
+    signature.verify(result)?;
+    Ok(signature)
+}
+
+/// The bytes of `result` that its signature signs: compact JSON with the members of objects
+/// ordered by key, leaving out the signature itself.
+pub fn canonical_response_body(result: &Value) -> Vec<u8> {
+    let mut body = vec![];
+    let mut serializer = serde_json::Serializer::new(&mut body);
+    let serialized = match result {
+        Value::Object(members) => {
+            serialize_members(members, Some(RESPONSE_SIGNATURE_FIELD), &mut serializer)
+        }
+        result => Canonical(result).serialize(&mut serializer),
+    };
+    serialized.expect("Serializing JSON to memory cannot fail");
+    body
+}
+
+/// A JSON value serialized with the members of its objects ordered by key, whatever order
+/// they were parsed or built in.
+struct Canonical<'a>(&'a Value);
+
+impl Serialize for Canonical<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Value::Object(members) => serialize_members(members, None, serializer),
+            Value::Array(values) => serializer.collect_seq(values.iter().map(Canonical)),
+            value => value.serialize(serializer),
+        }
+    }
+}
+
+fn serialize_members<S: Serializer>(
+    members: &Map<String, Value>,
+    skipped: Option<&str>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(
+        members
+            .iter()
+            .filter(|(key, _)| Some(key.as_str()) != skipped)
+            .sorted_by_key(|(key, _)| *key)
+            .map(|(key, value)| (key, Canonical(value))),
+    )
+}
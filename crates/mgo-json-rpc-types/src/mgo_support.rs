@@ -0,0 +1,139 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use mgo_types::base_types::{MgoAddress, ObjectID, SequenceNumber};
+use mgo_types::digests::{CheckpointContentsDigest, CheckpointDigest, TransactionDigest};
+use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
+use mgo_types::mgo_serde::BigInt;
+
+use crate::{Balance, DelegatedStake, MgoObjectRef};
+
+/// The state of an address as read by `exportAddressState`, for support tooling. Sections are
+/// read one after the other, no earlier than `as_of_checkpoint`, and a section that cannot be
+/// read has its error in place of its data.
+#[serde_as]
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressStateExport {
+    pub address: MgoAddress,
+    /// Latest checkpoint executed by the node when the export started
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub as_of_checkpoint: CheckpointSequenceNumber,
+    /// Objects owned by the address, up to the limit of the export
+    pub owned_objects: ExportSection<Vec<OwnedObjectSummary>>,
+    pub balances: ExportSection<Vec<Balance>>,
+    /// Staked MGO of the address, with the rewards estimated for active stakes
+    pub stakes: ExportSection<Vec<DelegatedStake>>,
+    /// Latest transactions sent by the address, latest first
+    pub recent_transactions: ExportSection<Vec<TransactionDigest>>,
+    /// Owned objects locked by a transaction signed in the current epoch but not executed yet
+    pub pending_locks: ExportSection<Vec<PendingLock>>,
+}
+
+/// One section of an [`AddressStateExport`]: `data` when it could be read, `error` otherwise.
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSection<T> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl<T> ExportSection<T> {
+    pub fn error(error: String) -> Self {
+        Self {
+            data: None,
+            error: Some(error),
+        }
+    }
+}
+
+impl<T, E: ToString> From<Result<T, E>> for ExportSection<T> {
+    fn from(result: Result<T, E>) -> Self {
+        match result {
+            Ok(data) => Self {
+                data: Some(data),
+                error: None,
+            },
+            Err(e) => Self::error(e.to_string()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnedObjectSummary {
+    pub object_id: ObjectID,
+    pub version: SequenceNumber,
+    #[serde(rename = "type")]
+    pub type_: String,
+}
+
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingLock {
+    pub object: MgoObjectRef,
+    /// The transaction the object is locked by
+    pub transaction: TransactionDigest,
+}
+
+/// What `verifyCheckpointRange` found checking the stored checkpoints from `start` to `end`,
+/// both included.
+#[serde_as]
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointVerificationReport {
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub start: CheckpointSequenceNumber,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub end: CheckpointSequenceNumber,
+    /// Checkpoints found in the range, with or without mismatches
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub checked: u64,
+    /// Every mismatch found, in checkpoint order
+    pub mismatches: Vec<CheckpointMismatch>,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointMismatch {
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub sequence_number: CheckpointSequenceNumber,
+    #[serde(flatten)]
+    pub kind: CheckpointMismatchKind,
+}
+
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "mismatch", rename_all = "camelCase")]
+pub enum CheckpointMismatchKind {
+    /// The summary of the checkpoint is not stored
+    MissingSummary,
+    /// The contents of the checkpoint are not stored, which is expected once they are pruned
+    #[serde(rename_all = "camelCase")]
+    MissingContents {
+        contents_digest: CheckpointContentsDigest,
+    },
+    /// The stored contents do not hash to the digest in the summary
+    #[serde(rename_all = "camelCase")]
+    ContentsDigest {
+        expected: CheckpointContentsDigest,
+        actual: CheckpointContentsDigest,
+    },
+    /// The summary does not link to the digest of the summary before it
+    #[serde(rename_all = "camelCase")]
+    PreviousDigest {
+        expected: Option<CheckpointDigest>,
+        actual: Option<CheckpointDigest>,
+    },
+}
@@ -2,9 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::balance_changes::BalanceChange;
-use crate::object_changes::ObjectChange;
+use crate::object_changes::{CreatedObjectSummary, ObjectChange};
 use crate::mgo_transaction::GenericSignature::Signature;
-use crate::{Filter, Page, MgoEvent, MgoObjectRef};
+use crate::{Filter, Page, MgoEvent, MgoObjectData, MgoObjectRef};
 use enum_dispatch::enum_dispatch;
 use fastcrypto::encoding::Base64;
 use move_binary_format::access::ModuleAccess;
@@ -24,13 +24,16 @@ use mgo_types::authenticator_state::ActiveJwk;
 use mgo_types::base_types::{
     EpochId, ObjectID, ObjectRef, SequenceNumber, MgoAddress, TransactionDigest,
 };
-use mgo_types::crypto::MgoSignature;
-use mgo_types::digests::{ConsensusCommitDigest, ObjectDigest, TransactionEventsDigest};
+use mgo_types::crypto::{CompressedSignature, MgoSignature, SignatureScheme};
+use mgo_types::digests::{
+    CheckpointDigest, ConsensusCommitDigest, ObjectDigest, TransactionEventsDigest,
+};
 use mgo_types::effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents};
 use mgo_types::error::{ExecutionError, MgoError, MgoResult};
 use mgo_types::execution_status::ExecutionStatus;
 use mgo_types::gas::GasCostSummary;
 use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
+use mgo_types::multisig::MultiSig;
 use mgo_types::object::{MoveObject, Owner};
 use mgo_types::parse_mgo_type_tag;
 use mgo_types::quorum_driver_types::ExecuteTransactionRequestType;
@@ -107,6 +110,17 @@ pub struct MgoTransactionBlockResponseOptions {
     pub show_balance_changes: bool,
     /// Whether to show raw transaction effects. Default to be False
     pub show_raw_effects: bool,
+    /// Whether to show the id, type and owner of created objects, a cheaper subset of
+    /// object_changes. Default to be False
+    pub show_created_summary: bool,
+    /// Whether to show the scheme and signer public key of each transaction signature, with
+    /// multisig decomposed into its participants. Only supported by the indexer. Default to be
+    /// False
+    pub show_signatures: bool,
+    /// Whether to show the checkpoint, its epoch and its timestamp. Without other options, only
+    /// these are read, for the whole page at once, instead of the whole transactions. Default to
+    /// be False
+    pub include_checkpoint: bool,
 }
 
 impl MgoTransactionBlockResponseOptions {
@@ -125,6 +139,10 @@ impl MgoTransactionBlockResponseOptions {
             // This field is added for graphql execution. We keep it false here
             // so current users of `full_content` will not get raw effects unexpectedly.
             show_raw_effects: false,
+            // Covered by `show_object_changes` already.
+            show_created_summary: false,
+            show_signatures: false,
+            include_checkpoint: false,
         }
     }
 
@@ -163,6 +181,21 @@ impl MgoTransactionBlockResponseOptions {
         self
     }
 
+    pub fn with_created_summary(mut self) -> Self {
+        self.show_created_summary = true;
+        self
+    }
+
+    pub fn with_signatures(mut self) -> Self {
+        self.show_signatures = true;
+        self
+    }
+
+    pub fn with_checkpoint(mut self) -> Self {
+        self.include_checkpoint = true;
+        self
+    }
+
     /// default to return `WaitForEffectsCert` unless some options require
     /// local execution
     pub fn default_execution_request_type(&self) -> ExecuteTransactionRequestType {
@@ -175,7 +208,7 @@ impl MgoTransactionBlockResponseOptions {
     }
 
     pub fn require_local_execution(&self) -> bool {
-        self.show_balance_changes || self.show_object_changes
+        self.show_balance_changes || self.show_object_changes || self.show_created_summary
     }
 
     pub fn require_input(&self) -> bool {
@@ -188,11 +221,17 @@ impl MgoTransactionBlockResponseOptions {
             || self.show_balance_changes
             || self.show_object_changes
             || self.show_raw_effects
+            || self.show_created_summary
     }
 
     pub fn only_digest(&self) -> bool {
         self == &Self::default()
     }
+
+    /// Whether nothing but the checkpoint of the transactions is requested.
+    pub fn only_checkpoint(&self) -> bool {
+        self == &Self::default().with_checkpoint()
+    }
 }
 
 #[serde_as]
@@ -218,17 +257,64 @@ pub struct MgoTransactionBlockResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub balance_changes: Option<Vec<BalanceChange>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_summary: Option<Vec<CreatedObjectSummary>>,
+    /// Schemes and signer public keys of the transaction signatures. Only returned by the
+    /// indexer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signatures: Option<Vec<MgoTransactionSignature>>,
+    /// Timestamp of the checkpoint that included the transaction, kept for compatibility.
+    /// Same as `checkpoint_timestamp_ms`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     #[schemars(with = "Option<BigInt<u64>>")]
     #[serde_as(as = "Option<BigInt<u64>>")]
     pub timestamp_ms: Option<u64>,
+    /// Timestamp of the checkpoint that included the transaction. This is not when the
+    /// transaction was executed, which can be earlier.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub checkpoint_timestamp_ms: Option<u64>,
+    /// When the fullnode serving the request saw the transaction's effects, according to its
+    /// own clock. Only returned by the fullnode that executed the transaction locally.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub observed_execution_timestamp_ms: Option<u64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub confirmed_local_execution: Option<bool>,
+    /// Highest checkpoint the fullnode had executed once the transaction's writes became
+    /// readable. Only returned with confirmed local execution; reads served by a fullnode that
+    /// has not executed this checkpoint yet may not reflect the transaction.
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executed_at_checkpoint_hint: Option<CheckpointSequenceNumber>,
+    /// References of the objects the transaction wrote, at their new versions. A read returning
+    /// a lower version of one of these objects is stale.
+    /// This is only returned in the transaction execution api.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub written_objects: Option<Vec<MgoObjectRef>>,
+    /// Set to `fullnode` when an indexer served the transaction from its fullnode because it
+    /// has not indexed it yet. Such responses have no checkpoint until it is indexed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub served_from: Option<String>,
     /// The checkpoint number when this transaction was included and hence finalized.
-    /// This is only returned in the read api, not in the transaction execution api.
+    /// This is only returned in the read api, and in the transaction execution api when waiting
+    /// for checkpoint inclusion.
     #[schemars(with = "Option<BigInt<u64>>")]
     #[serde_as(as = "Option<BigInt<u64>>")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub checkpoint: Option<CheckpointSequenceNumber>,
+    /// Digest of the checkpoint that included the transaction. Only returned in the transaction
+    /// execution api when waiting for checkpoint inclusion.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint_digest: Option<CheckpointDigest>,
+    /// Epoch of the checkpoint that included the transaction. Only returned when requested with
+    /// `include_checkpoint`.
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epoch: Option<EpochId>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub errors: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
@@ -246,6 +332,12 @@ impl MgoTransactionBlockResponse {
     pub fn status_ok(&self) -> Option<bool> {
         self.effects.as_ref().map(|e| e.status().is_ok())
     }
+
+    /// Sets the checkpoint timestamp under both its current and legacy names.
+    pub fn set_checkpoint_timestamp_ms(&mut self, timestamp_ms: Option<u64>) {
+        self.timestamp_ms = timestamp_ms;
+        self.checkpoint_timestamp_ms = timestamp_ms;
+    }
 }
 
 /// We are specifically ignoring events for now until events become more stable.
@@ -1142,6 +1234,43 @@ impl DevInspectResults {
     }
 }
 
+/// Gas charged for the same transaction by `dryRunTransactionBlock` and by
+/// `devInspectTransactionBlock`, component by component.
+#[serde_as]
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GasComparison {
+    pub dry_run: GasCostSummary,
+    pub dev_inspect: GasCostSummary,
+    /// Net gas usage of dev inspect minus that of dry run.
+    #[schemars(with = "BigInt<i64>")]
+    #[serde_as(as = "BigInt<i64>")]
+    pub net_gas_usage_difference: i64,
+    /// Whether the computation cost, storage cost or storage rebate of dev inspect differs from
+    /// that of dry run by more than the threshold the comparison was made with.
+    pub discrepancy: bool,
+}
+
+impl GasComparison {
+    /// Compares `dev_inspect` to `dry_run`, which is taken as the reference: a component is
+    /// discrepant when it differs by more than `threshold_bps` basis points of its dry run value.
+    pub fn new(dry_run: GasCostSummary, dev_inspect: GasCostSummary, threshold_bps: u64) -> Self {
+        let exceeds = |dry_run: u64, dev_inspect: u64| {
+            dry_run.abs_diff(dev_inspect) as u128 * 10_000
+                > dry_run.max(1) as u128 * threshold_bps as u128
+        };
+        let discrepancy = exceeds(dry_run.computation_cost, dev_inspect.computation_cost)
+            || exceeds(dry_run.storage_cost, dev_inspect.storage_cost)
+            || exceeds(dry_run.storage_rebate, dev_inspect.storage_rebate);
+        Self {
+            net_gas_usage_difference: dev_inspect.net_gas_usage() - dry_run.net_gas_usage(),
+            dry_run,
+            dev_inspect,
+            discrepancy,
+        }
+    }
+}
+
 #[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum MgoTransactionBlockBuilderMode {
     /// Regular Mgo Transactions that are committed on chain
@@ -1354,6 +1483,83 @@ impl MgoTransactionBlock {
     }
 }
 
+/// A signature of a transaction block, with the scheme it was made with. A multisig lists the
+/// signatures of the participants that signed, each with their own scheme.
+#[serde_as]
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename = "TransactionBlockSignature", rename_all = "camelCase")]
+pub struct MgoTransactionSignature {
+    pub scheme: SignatureScheme,
+    /// Public key of the signer, none for a multisig, whose participants carry their own.
+    #[serde_as(as = "Option<Base64>")]
+    #[schemars(with = "Option<Base64>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<Vec<u8>>,
+    /// The serialized signature. For participants of a multisig, only their signature, without
+    /// flag or public key.
+    #[serde_as(as = "Base64")]
+    #[schemars(with = "Base64")]
+    pub signature: Vec<u8>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub participants: Vec<MgoTransactionSignature>,
+}
+
+impl From<&GenericSignature> for MgoTransactionSignature {
+    fn from(signature: &GenericSignature) -> Self {
+        let (scheme, public_key, participants) = match signature {
+            Signature(s) => (s.scheme(), Some(s.public_key_bytes().to_vec()), vec![]),
+            GenericSignature::ZkLoginAuthenticator(s) => (
+                SignatureScheme::ZkLoginAuthenticator,
+                s.get_pk().ok().map(|pk| pk.as_ref().to_vec()),
+                vec![],
+            ),
+            GenericSignature::MultiSig(s) => {
+                (SignatureScheme::MultiSig, None, multisig_participants(s))
+            }
+            GenericSignature::MultiSigLegacy(s) => (
+                SignatureScheme::MultiSig,
+                None,
+                MultiSig::try_from(s.clone())
+                    .map(|s| multisig_participants(&s))
+                    .unwrap_or_default(),
+            ),
+        };
+        Self {
+            scheme,
+            public_key,
+            signature: signature.as_ref().to_vec(),
+            participants,
+        }
+    }
+}
+
+/// Signatures of the participants of `multisig`, in the order of its public key.
+fn multisig_participants(multisig: &MultiSig) -> Vec<MgoTransactionSignature> {
+    let public_keys = multisig.get_pk().pubkeys();
+    let indices = multisig.get_indices().unwrap_or_default();
+    multisig
+        .get_sigs()
+        .iter()
+        .zip(indices)
+        .map(|(signature, index)| {
+            let scheme = match signature {
+                CompressedSignature::Ed25519(_) => SignatureScheme::ED25519,
+                CompressedSignature::Secp256k1(_) => SignatureScheme::Secp256k1,
+                CompressedSignature::Secp256r1(_) => SignatureScheme::Secp256r1,
+                CompressedSignature::ZkLogin(_) => SignatureScheme::ZkLoginAuthenticator,
+            };
+            MgoTransactionSignature {
+                scheme,
+                public_key: public_keys
+                    .get(index as usize)
+                    .map(|(pk, _)| pk.as_ref().to_vec()),
+                signature: signature.as_ref().to_vec(),
+                participants: vec![],
+            }
+        })
+        .collect()
+}
+
 impl Display for MgoTransactionBlock {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let mut builder = TableBuilder::default();
@@ -2098,6 +2304,23 @@ pub struct MgoLoadedChildObject {
     #[schemars(with = "AsSequenceNumber")]
     #[serde_as(as = "AsSequenceNumber")]
     sequence_number: SequenceNumber,
+    /// The object at `sequence_number`, when its contents were requested and resolved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    data: Option<MgoObjectData>,
+    /// Why the contents were not resolved, when they were requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    unresolved: Option<MgoUnresolvedChildObject>,
+}
+
+/// Reason the contents of a loaded child object are missing from a response that requested
+/// them.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "UnresolvedChildObject", rename_all = "camelCase")]
+pub enum MgoUnresolvedChildObject {
+    /// The object at this version has been pruned from the node.
+    Pruned,
+    /// The request already resolved as many child objects as a single call may.
+    LimitExceeded,
 }
 
 impl MgoLoadedChildObject {
@@ -2105,9 +2328,21 @@ impl MgoLoadedChildObject {
         Self {
             object_id,
             sequence_number,
+            data: None,
+            unresolved: None,
         }
     }
 
+    pub fn with_data(mut self, data: MgoObjectData) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    pub fn with_unresolved(mut self, unresolved: MgoUnresolvedChildObject) -> Self {
+        self.unresolved = Some(unresolved);
+        self
+    }
+
     pub fn object_id(&self) -> ObjectID {
         self.object_id
     }
@@ -2115,6 +2350,14 @@ impl MgoLoadedChildObject {
     pub fn sequence_number(&self) -> SequenceNumber {
         self.sequence_number
     }
+
+    pub fn data(&self) -> Option<&MgoObjectData> {
+        self.data.as_ref()
+    }
+
+    pub fn unresolved(&self) -> Option<MgoUnresolvedChildObject> {
+        self.unresolved
+    }
 }
 
 #[serde_as]
@@ -2167,6 +2410,8 @@ pub enum TransactionFilter {
     TransactionKind(String),
     /// Query transactions of any given kind in the input.
     TransactionKindIn(Vec<String>),
+    /// Query by signature scheme, including the schemes of multisig participants.
+    SignatureScheme(SignatureScheme),
 }
 
 impl Filter<EffectsWithInput> for TransactionFilter {
@@ -2210,6 +2455,7 @@ impl Filter<EffectsWithInput> for TransactionFilter {
             // these filters are not supported, rpc will reject these filters on subscription
             TransactionFilter::Checkpoint(_) => false,
             TransactionFilter::FromOrToAddress { addr: _ } => false,
+            TransactionFilter::SignatureScheme(_) => false,
         }
     }
 }
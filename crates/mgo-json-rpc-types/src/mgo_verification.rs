@@ -0,0 +1,79 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use fastcrypto::encoding::Base64;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use mgo_types::base_types::MgoAddress;
+
+/// A user signature to check with `verifySignatures`.
+#[serde_as]
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureVerificationRequest {
+    /// The address expected to have signed
+    pub address: MgoAddress,
+    /// The scope of the intent the data was signed under, e.g. 3 for a personal message
+    pub intent_scope: u8,
+    /// BCS bytes of the signed value, which are signed after the intent
+    #[serde_as(as = "Base64")]
+    #[schemars(with = "Base64")]
+    pub data_b64: Vec<u8>,
+    /// The signature, as `flag || signature || public key`
+    #[serde_as(as = "Base64")]
+    #[schemars(with = "Base64")]
+    pub signature_b64: Vec<u8>,
+}
+
+/// Whether a [`SignatureVerificationRequest`] holds, and why not when it does not.
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureVerificationResult {
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl SignatureVerificationResult {
+    pub fn valid() -> Self {
+        Self {
+            valid: true,
+            error: None,
+        }
+    }
+
+    pub fn invalid(error: String) -> Self {
+        Self {
+            valid: false,
+            error: Some(error),
+        }
+    }
+}
+
+/// Whether a transaction is validly signed, as checked by `verifyTransactionBlockSignature`
+/// without executing it.
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionSignatureVerification {
+    /// Whether every signature holds and the sender, and the sponsor if any, have each signed
+    pub valid: bool,
+    /// Why the signatures do not authorize the transaction together, e.g. a missing signer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The result of every signature, in the order they were given
+    pub signatures: Vec<TransactionSignatureResult>,
+}
+
+/// Whether a signature of a transaction holds, and why not when it does not.
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionSignatureResult {
+    /// The address the signature was made by, when it could be recovered
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signer: Option<MgoAddress>,
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
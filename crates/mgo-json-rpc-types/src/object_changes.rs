@@ -256,3 +256,16 @@ impl Display for ObjectChange {
         }
     }
 }
+
+/// Id, type and owner of an object created by a transaction. A lightweight alternative to the
+/// `Created` entries of `object_changes` that can be served without fetching object contents.
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedObjectSummary {
+    pub object_id: ObjectID,
+    #[schemars(with = "String")]
+    #[serde_as(as = "MgoStructTag")]
+    pub object_type: StructTag,
+    pub owner: Owner,
+}
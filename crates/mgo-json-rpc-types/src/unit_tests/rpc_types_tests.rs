@@ -8,15 +8,31 @@ use move_core_types::annotated_value::{MoveStruct, MoveValue};
 use move_core_types::ident_str;
 use move_core_types::identifier::Identifier;
 use move_core_types::language_storage::{StructTag, TypeTag};
+use mgo_protocol_config::{Chain, ProtocolConfig, ProtocolVersion};
 use serde_json::json;
 
 use mgo_types::base_types::{ObjectDigest, SequenceNumber};
 use mgo_types::base_types::{ObjectID, MgoAddress};
+use mgo_types::crypto::{get_key_pair, MgoKeyPair};
+use mgo_types::digests::TransactionDigest;
+use mgo_types::dynamic_field::{DynamicFieldInfo, DynamicFieldName, DynamicFieldType};
+use mgo_types::gas::GasCostSummary;
 use mgo_types::gas_coin::GasCoin;
-use mgo_types::object::{MoveObject, Owner};
-use mgo_types::{parse_mgo_struct_tag, MOVE_STDLIB_ADDRESS, MGO_FRAMEWORK_ADDRESS};
+use mgo_types::mgo_system_state::mgo_system_state_summary::MgoValidatorSummary;
+use mgo_types::object::{MoveObject, Object, Owner};
+use mgo_types::{
+    parse_mgo_struct_tag, MGO_CLOCK_OBJECT_ID, MOVE_STDLIB_ADDRESS, MGO_FRAMEWORK_ADDRESS,
+};
 
-use crate::{ObjectChange, MgoMoveStruct, MgoMoveValue};
+use crate::{
+    canonical_response_body, dynamic_fields_within_content_budget, filter_hash,
+    verify_signed_response, AppliedPagination, ClientLimits, DelegatedStake, EventFilter,
+    EventFilterLimits, Filter, GasComparison, MgoDynamicFieldInfo, MgoEvent, MgoMoveStruct,
+    MgoMoveValue, MgoObjectData, MgoRawData, MgoRawMoveObject, MgoTransactionBlockResponse,
+    NoStorageRebateReason, ObjectChange, OwnedStakesSummary, Page, ResponseSignature,
+    ServerLimits, Stake, StakeStatus, StorageRebateEstimate, DYNAMIC_FIELD_CONTENT_MAX_PAGE_BYTES,
+    RESPONSE_SIGNATURE_FIELD,
+};
 
 #[test]
 fn test_move_value_to_mgo_coin() {
@@ -196,3 +212,494 @@ fn test_type_tag_struct_tag_devnet_inc_222() {
         assert_eq!(oc, deser);
     }
 }
+
+#[test]
+fn test_client_limits_follow_protocol_config() {
+    let server = ServerLimits {
+        max_page_size: 50,
+        max_checkpoint_page_size: 100,
+        max_multi_get_batch_size: 50,
+        max_owned_objects_addresses: 20,
+    };
+    let v1 = ProtocolConfig::get_for_version(ProtocolVersion::new(1), Chain::Unknown);
+    let v2 = ProtocolConfig::get_for_version(ProtocolVersion::new(2), Chain::Unknown);
+    let before = ClientLimits::new(&v1, 0, server.clone());
+    let after = ClientLimits::new(&v2, 1, server.clone());
+
+    assert_eq!(before.protocol_version, ProtocolVersion::new(1));
+    assert_eq!(after.protocol_version, ProtocolVersion::new(2));
+    assert_eq!(before.max_gas_budget, v1.max_tx_gas_as_option());
+    assert_eq!(after.max_gas_budget, v2.max_tx_gas_as_option());
+    assert_ne!(before.max_gas_budget, after.max_gas_budget);
+    assert_eq!(after.epoch, 1);
+    assert_eq!(after.server, server);
+
+    // Overridden constants are picked up as soon as the config is loaded again.
+    let _guard = ProtocolConfig::apply_overrides_for_testing(|_, mut config| {
+        config.set_max_tx_size_bytes_for_testing(1024);
+        config
+    });
+    let overridden = ProtocolConfig::get_for_version(ProtocolVersion::new(2), Chain::Unknown);
+    let limits = ClientLimits::new(&overridden, 1, server);
+    assert_eq!(limits.max_tx_size_bytes, Some(1024));
+    assert_ne!(limits.max_tx_size_bytes, after.max_tx_size_bytes);
+
+    let json = serde_json::to_value(&limits).unwrap();
+    assert_eq!(json["maxTxSizeBytes"], json!("1024"));
+    assert_eq!(
+        serde_json::from_value::<ClientLimits>(json).unwrap(),
+        limits
+    );
+}
+
+#[test]
+fn test_transaction_timestamps_are_compatible_with_old_clients() {
+    let digest = TransactionDigest::random();
+
+    // Responses from servers that predate the explicit timestamps still deserialize.
+    let legacy = json!({
+        "digest": digest,
+        "timestampMs": "1000",
+        "checkpoint": "5",
+    });
+    let response: MgoTransactionBlockResponse = serde_json::from_value(legacy.clone()).unwrap();
+    assert_eq!(response.timestamp_ms, Some(1000));
+    assert_eq!(response.checkpoint_timestamp_ms, None);
+    assert_eq!(response.observed_execution_timestamp_ms, None);
+
+    // Without the new timestamps, the response serializes exactly as it used to.
+    let mut response = MgoTransactionBlockResponse::new(digest);
+    response.timestamp_ms = Some(1000);
+    response.checkpoint = Some(5);
+    assert_eq!(serde_json::to_value(&response).unwrap(), legacy);
+
+    // The legacy field carries the checkpoint timestamp, so old clients read the same value.
+    response.set_checkpoint_timestamp_ms(Some(2000));
+    response.observed_execution_timestamp_ms = Some(1500);
+    let value = serde_json::to_value(&response).unwrap();
+    assert_eq!(value["timestampMs"], json!("2000"));
+    assert_eq!(value["checkpointTimestampMs"], json!("2000"));
+    assert_eq!(value["observedExecutionTimestampMs"], json!("1500"));
+
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct OldClientResponse {
+        digest: TransactionDigest,
+        timestamp_ms: Option<String>,
+    }
+    let old: OldClientResponse = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(old.digest, digest);
+    assert_eq!(old.timestamp_ms.as_deref(), Some("2000"));
+
+    let round_trip: MgoTransactionBlockResponse = serde_json::from_value(value).unwrap();
+    assert_eq!(round_trip.timestamp_ms, Some(2000));
+    assert_eq!(round_trip.checkpoint_timestamp_ms, Some(2000));
+    assert_eq!(round_trip.observed_execution_timestamp_ms, Some(1500));
+}
+
+#[test]
+fn test_gas_comparison() {
+    let dry_run = GasCostSummary::new(1_000_000, 2_000_000, 500_000, 5_000);
+    let same = GasComparison::new(dry_run.clone(), dry_run.clone(), 100);
+    assert!(!same.discrepancy);
+    assert_eq!(same.net_gas_usage_difference, 0);
+
+    // 1% more computation is within a 1% threshold, anything above it is not.
+    let mut dev_inspect = dry_run.clone();
+    dev_inspect.computation_cost = 1_010_000;
+    let comparison = GasComparison::new(dry_run.clone(), dev_inspect.clone(), 100);
+    assert!(!comparison.discrepancy);
+    assert_eq!(comparison.net_gas_usage_difference, 10_000);
+    dev_inspect.computation_cost = 1_010_001;
+    assert!(GasComparison::new(dry_run.clone(), dev_inspect, 100).discrepancy);
+
+    // A rebate only one side reports is a discrepancy whatever the threshold.
+    let mut dev_inspect = dry_run.clone();
+    dev_inspect.storage_rebate = 0;
+    let comparison = GasComparison::new(dry_run, dev_inspect, 5_000);
+    assert!(comparison.discrepancy);
+    assert_eq!(comparison.net_gas_usage_difference, 500_000);
+
+    let value = serde_json::to_value(&comparison).unwrap();
+    assert_eq!(value["netGasUsageDifference"], json!("500000"));
+    assert_eq!(value["devInspect"]["storageRebate"], json!("0"));
+    assert_eq!(value["dryRun"]["computationCost"], json!("1000000"));
+}
+
+fn dynamic_field_with_object_bytes(object_bytes: Option<usize>) -> MgoDynamicFieldInfo {
+    let object_id = ObjectID::random();
+    let info = DynamicFieldInfo {
+        name: DynamicFieldName {
+            type_: TypeTag::U64,
+            value: json!("1"),
+        },
+        bcs_name: bcs::to_bytes(&1u64).unwrap(),
+        type_: DynamicFieldType::DynamicField,
+        object_type: "u64".to_string(),
+        object_id,
+        version: SequenceNumber::from_u64(1),
+        digest: ObjectDigest::random(),
+    };
+    let mut field = MgoDynamicFieldInfo::from(info);
+    field.object = object_bytes.map(|bytes| MgoObjectData {
+        object_id,
+        version: SequenceNumber::from_u64(1),
+        digest: ObjectDigest::random(),
+        type_: None,
+        latest_type: None,
+        owner: None,
+        previous_transaction: None,
+        previous_transaction_details: None,
+        storage_rebate: None,
+        display: None,
+        content: None,
+        bcs: Some(MgoRawData::MoveObject(MgoRawMoveObject {
+            type_: GasCoin::type_(),
+            has_public_transfer: true,
+            version: SequenceNumber::from_u64(1),
+            bcs_bytes: vec![0; bytes],
+        })),
+    });
+    field
+}
+
+#[test]
+fn test_dynamic_fields_within_content_budget() {
+    let without_objects: Vec<_> = (0..3)
+        .map(|_| dynamic_field_with_object_bytes(None))
+        .collect();
+    assert_eq!(dynamic_fields_within_content_budget(&without_objects), 3);
+    // Fields without a field object serialize as DynamicFieldInfo does.
+    assert!(serde_json::to_value(&without_objects[0])
+        .unwrap()
+        .get("object")
+        .is_none());
+
+    // Each field object takes more than half the budget once base64 encoded.
+    let half = DYNAMIC_FIELD_CONTENT_MAX_PAGE_BYTES / 2;
+    let with_objects: Vec<_> = (0..3)
+        .map(|_| dynamic_field_with_object_bytes(Some(half)))
+        .collect();
+    assert_eq!(dynamic_fields_within_content_budget(&with_objects), 1);
+
+    // A field object over the budget on its own still makes a page.
+    let oversized = vec![dynamic_field_with_object_bytes(Some(
+        DYNAMIC_FIELD_CONTENT_MAX_PAGE_BYTES,
+    ))];
+    assert_eq!(dynamic_fields_within_content_budget(&oversized), 1);
+    assert_eq!(dynamic_fields_within_content_budget(&[]), 0);
+}
+
+#[test]
+fn test_filter_hash_is_independent_of_field_order() {
+    let hash = filter_hash(&json!({ "Sender": "0x1", "nested": { "a": 1, "b": [2, 3] } }));
+    assert_eq!(
+        hash,
+        filter_hash(&json!({ "nested": { "b": [2, 3], "a": 1 }, "Sender": "0x1" }))
+    );
+    assert_ne!(
+        hash,
+        filter_hash(&json!({ "Sender": "0x1", "nested": { "a": 1, "b": [3, 2] } }))
+    );
+}
+
+#[test]
+fn test_applied_pagination_is_only_echoed_on_request() {
+    let page: Page<u64, ObjectID> = Page::empty();
+    assert!(!serde_json::to_string(&page).unwrap().contains("applied"));
+
+    let cursor = ObjectID::random();
+    assert_eq!(AppliedPagination::echo(None, Some(cursor), 10, false, &()), None);
+    let applied = AppliedPagination::echo(Some(true), Some(cursor), 10, true, &()).unwrap();
+    assert_eq!(applied.cursor, Some(cursor));
+    assert_eq!(applied.limit, 10);
+    assert!(applied.descending);
+    assert_eq!(applied.filter_hash, filter_hash(&()));
+}
+
+#[test]
+fn test_composite_event_filters() {
+    let event = MgoEvent::random_for_testing();
+    let sender = EventFilter::Sender(event.sender);
+    let other_sender = EventFilter::Sender(MgoAddress::random_for_testing_only());
+    let package = EventFilter::Package(event.package_id);
+
+    assert!(EventFilter::All(vec![]).matches(&event));
+    assert!(!EventFilter::Any(vec![]).matches(&event));
+    assert!(EventFilter::All(vec![sender.clone(), package.clone()]).matches(&event));
+    assert!(!EventFilter::All(vec![sender.clone(), other_sender.clone()]).matches(&event));
+    assert!(EventFilter::Any(vec![other_sender.clone(), package.clone()]).matches(&event));
+    assert!(!EventFilter::Any(vec![other_sender.clone()]).matches(&event));
+    assert!(sender.clone().and(package.clone()).matches(&event));
+    assert!(other_sender.clone().or(package.clone()).matches(&event));
+    assert!(EventFilter::And(Box::new(sender.clone()), Box::new(package.clone())).matches(&event));
+    assert!(!EventFilter::And(Box::new(sender), Box::new(other_sender.clone())).matches(&event));
+    assert!(EventFilter::Or(Box::new(other_sender.clone()), Box::new(package)).matches(&event));
+    assert!(!EventFilter::Or(
+        Box::new(other_sender.clone()),
+        Box::new(EventFilter::Any(vec![other_sender]))
+    )
+    .matches(&event));
+}
+
+#[test]
+fn test_subscription_filters_are_bounded() {
+    let limits = EventFilterLimits {
+        max_depth: 3,
+        max_nodes: 5,
+    };
+    let leaf = || EventFilter::Package(ObjectID::random());
+    let nested = |depth: usize| (1..depth).fold(leaf(), |filter, _| EventFilter::Any(vec![filter]));
+
+    assert!(leaf().validate_for_subscription(&limits).is_ok());
+    assert!(nested(3).validate_for_subscription(&limits).is_ok());
+    let err = nested(4).validate_for_subscription(&limits).unwrap_err();
+    assert!(err.to_string().contains("deeper than 3 levels"), "{err}");
+    let err = EventFilter::And(Box::new(leaf()), Box::new(nested(3)))
+        .validate_for_subscription(&limits)
+        .unwrap_err();
+    assert!(err.to_string().contains("deeper than 3 levels"), "{err}");
+
+    assert!(EventFilter::All((0..4).map(|_| leaf()).collect())
+        .validate_for_subscription(&limits)
+        .is_ok());
+    let err = EventFilter::All((0..5).map(|_| leaf()).collect())
+        .validate_for_subscription(&limits)
+        .unwrap_err();
+    assert!(err.to_string().contains("more than 5 filters"), "{err}");
+
+    // Streamed events do not carry their checkpoint, so that such filters would never match.
+    let err = EventFilter::Any(vec![leaf(), EventFilter::Checkpoint(1)])
+        .validate_for_subscription(&limits)
+        .unwrap_err();
+    assert!(err.to_string().contains("Checkpoint filters"), "{err}");
+}
+
+fn stake(principal: u64, active_epoch: u64, status: StakeStatus) -> Stake {
+    Stake {
+        staked_mgo_id: ObjectID::random(),
+        stake_request_epoch: active_epoch - 1,
+        stake_active_epoch: active_epoch,
+        principal,
+        status,
+    }
+}
+
+#[test]
+fn test_stakes_summary_groups_by_validator() {
+    let owner = MgoAddress::random_for_testing_only();
+    let validator = |name: &str, image_url: &str| MgoValidatorSummary {
+        mgo_address: MgoAddress::random_for_testing_only(),
+        staking_pool_id: ObjectID::random(),
+        name: name.to_string(),
+        image_url: image_url.to_string(),
+        ..Default::default()
+    };
+    let (alice, bob) = (validator("alice", "https://alice.png"), validator("bob", ""));
+    let delegated = |validator: &MgoValidatorSummary, stakes| DelegatedStake {
+        validator_address: validator.mgo_address,
+        staking_pool: validator.staking_pool_id,
+        stakes,
+    };
+    let active = |estimated_reward| StakeStatus::Active { estimated_reward };
+    // No longer active, so not among the validators of the system state.
+    let departed = validator("carol", "https://carol.png");
+
+    let summary = OwnedStakesSummary::new(
+        owner,
+        7,
+        vec![
+            delegated(
+                &bob,
+                vec![
+                    stake(300, 4, active(20)),
+                    stake(500, 3, active(30)),
+                    stake(400, 8, StakeStatus::Pending),
+                ],
+            ),
+            delegated(&alice, vec![stake(100, 6, active(1))]),
+            delegated(&departed, vec![stake(2_000, 2, StakeStatus::Pending)]),
+        ],
+        &[alice.clone(), bob.clone()],
+    );
+
+    let validators: Vec<_> = summary
+        .validators
+        .iter()
+        .map(|v| {
+            (
+                v.validator_address,
+                v.name.as_deref(),
+                v.image_url.as_deref(),
+                v.principal,
+                v.estimated_reward,
+                v.stake_count,
+                v.earliest_activation_epoch,
+            )
+        })
+        .collect();
+    assert_eq!(
+        validators,
+        vec![
+            (departed.mgo_address, None, None, 2_000, 0, 1, 2),
+            (bob.mgo_address, Some("bob"), None, 1_200, 50, 3, 3),
+            (alice.mgo_address, Some("alice"), Some("https://alice.png"), 100, 1, 1, 6),
+        ]
+    );
+    assert_eq!(summary.epoch, 7);
+    assert_eq!(summary.total_principal, 3_300);
+    assert_eq!(summary.total_estimated_reward, 51);
+    assert_eq!(summary.stake_count, 5);
+}
+
+#[test]
+fn test_empty_stakes_summary() {
+    let owner = MgoAddress::random_for_testing_only();
+    let summary = OwnedStakesSummary::new(owner, 3, vec![], &[MgoValidatorSummary::default()]);
+    assert_eq!(
+        summary,
+        OwnedStakesSummary {
+            owner,
+            epoch: 3,
+            validators: vec![],
+            total_principal: 0,
+            total_estimated_reward: 0,
+            stake_count: 0,
+        }
+    );
+}
+
+fn with_storage_rebate(object: Object, storage_rebate: u64) -> Object {
+    let mut inner = object.into_inner();
+    inner.storage_rebate = storage_rebate;
+    inner.into()
+}
+
+#[test]
+fn test_storage_rebate_estimate() {
+    let config = ProtocolConfig::get_for_version(ProtocolVersion::new(1), Chain::Unknown);
+    let owner = MgoAddress::random_for_testing_only();
+    let object = with_storage_rebate(
+        Object::with_id_owner_gas_for_testing(ObjectID::random(), owner, 10),
+        1_000_000,
+    );
+    let size = object.object_size_for_gas_metering() as u64;
+
+    let deletion = StorageRebateEstimate::new(&object, &config, 3, None);
+    assert_eq!(deletion.object_id, object.id());
+    assert_eq!(deletion.version, object.version());
+    assert_eq!(deletion.epoch, 3);
+    assert_eq!(deletion.storage_gas_price, config.storage_gas_price());
+    assert_eq!(deletion.size, size);
+    assert_eq!(deletion.storage_rebate, 1_000_000);
+    // 99% of the rebate is returned, the rest is kept by the storage fund.
+    assert_eq!(deletion.deletion_rebate, 990_000);
+    assert_eq!(deletion.new_storage_cost, None);
+    assert_eq!(deletion.mutation_rebate_delta, None);
+    assert_eq!(deletion.no_rebate_reason, None);
+
+    // A gas coin's contents are its ID and balance, 40 bytes that grow to 100.
+    let mutation = StorageRebateEstimate::new(&object, &config, 3, Some(100));
+    let new_storage_cost = (size + 60) * 100 * config.storage_gas_price();
+    assert_eq!(mutation.deletion_rebate, 990_000);
+    assert_eq!(mutation.new_contents_size, Some(100));
+    assert_eq!(mutation.new_storage_cost, Some(new_storage_cost));
+    assert_eq!(
+        mutation.mutation_rebate_delta,
+        Some(990_000 - new_storage_cost as i64)
+    );
+
+    // Mutations costing more storage than the object returns have a negative delta.
+    let cheap = with_storage_rebate(object, 15);
+    let mutation = StorageRebateEstimate::new(&cheap, &config, 3, Some(40));
+    assert_eq!(mutation.deletion_rebate, 15);
+    assert_eq!(
+        mutation.mutation_rebate_delta,
+        Some(15 - (size * 100 * config.storage_gas_price()) as i64)
+    );
+
+    let json = serde_json::to_value(&mutation).unwrap();
+    assert_eq!(json["deletionRebate"], json!("15"));
+    assert_eq!(
+        serde_json::from_value::<StorageRebateEstimate>(json).unwrap(),
+        mutation
+    );
+}
+
+#[test]
+fn test_storage_rebate_estimate_without_rebate() {
+    let config = ProtocolConfig::get_for_version(ProtocolVersion::new(1), Chain::Unknown);
+    let immutable = with_storage_rebate(Object::immutable_for_testing(), 1_000);
+    let estimate = StorageRebateEstimate::new(&immutable, &config, 3, Some(100));
+    assert_eq!(
+        estimate.no_rebate_reason,
+        Some(NoStorageRebateReason::Immutable)
+    );
+    assert_eq!(estimate.storage_rebate, 0);
+    assert_eq!(estimate.deletion_rebate, 0);
+    assert_eq!(estimate.new_storage_cost, None);
+    assert_eq!(estimate.mutation_rebate_delta, None);
+
+    let clock = with_storage_rebate(
+        Object::with_id_owner_gas_for_testing(MGO_CLOCK_OBJECT_ID, MgoAddress::ZERO, 0),
+        1_000,
+    );
+    let estimate = StorageRebateEstimate::new(&clock, &config, 3, None);
+    assert_eq!(
+        estimate.no_rebate_reason,
+        Some(NoStorageRebateReason::SystemObject)
+    );
+    assert_eq!(estimate.deletion_rebate, 0);
+
+    let json = serde_json::to_value(&estimate).unwrap();
+    assert_eq!(json["noRebateReason"], json!("SystemObject"));
+    assert_eq!(json["storageRebate"], json!("0"));
+}
+
+#[test]
+fn test_signed_response() {
+    let key = MgoKeyPair::Ed25519(get_key_pair().1);
+    let signer = MgoAddress::from(&key.public());
+    let mut result = json!({
+        "digest": "8V7T3rV9Zx1hTqvK1e2Z5kD3qH3p1aVqz2Yv7hD4kF6m",
+        "timestampMs": "1700000000000",
+        "transactions": [{"b": 2, "a": 1}],
+    });
+    let signature = ResponseSignature::sign(&result, 1_700_000_000_001, &key);
+    result[RESPONSE_SIGNATURE_FIELD] = serde_json::to_value(&signature).unwrap();
+    assert_eq!(verify_signed_response(&result, signer).unwrap(), signature);
+
+    // The signature holds whatever order the members of the result are parsed in.
+    let reordered = json!({
+        RESPONSE_SIGNATURE_FIELD: result[RESPONSE_SIGNATURE_FIELD].clone(),
+        "transactions": [{"a": 1, "b": 2}],
+        "timestampMs": "1700000000000",
+        "digest": "8V7T3rV9Zx1hTqvK1e2Z5kD3qH3p1aVqz2Yv7hD4kF6m",
+    });
+    assert_eq!(
+        canonical_response_body(&reordered),
+        canonical_response_body(&result)
+    );
+    verify_signed_response(&reordered, signer).unwrap();
+
+    let mut tampered = result.clone();
+    tampered["transactions"][0]["a"] = json!(3);
+    assert!(verify_signed_response(&tampered, signer).is_err());
+    let mut tampered = result.clone();
+    tampered["extra"] = json!(true);
+    assert!(verify_signed_response(&tampered, signer).is_err());
+    let mut tampered = result.clone();
+    tampered[RESPONSE_SIGNATURE_FIELD]["timestampMs"] = json!("1700000000002");
+    assert!(verify_signed_response(&tampered, signer).is_err());
+
+    // Signatures are only trusted from the expected key.
+    let other = MgoKeyPair::Ed25519(get_key_pair().1);
+    let mut forged = result.clone();
+    let forged_signature = ResponseSignature::sign(&forged, 1_700_000_000_001, &other);
+    forged[RESPONSE_SIGNATURE_FIELD] = serde_json::to_value(forged_signature).unwrap();
+    assert!(verify_signed_response(&forged, signer).is_err());
+    verify_signed_response(&forged, MgoAddress::from(&other.public())).unwrap();
+
+    result.as_object_mut().unwrap().remove(RESPONSE_SIGNATURE_FIELD);
+    assert!(verify_signed_response(&result, signer).is_err());
+}
@@ -6,30 +6,34 @@ use arc_swap::Guard;
 use async_trait::async_trait;
 use move_core_types::language_storage::TypeTag;
 use mango_metrics::spawn_monitored_task;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::sync::Arc;
 use mgo_core::authority::authority_per_epoch_store::AuthorityPerEpochStore;
 use mgo_core::authority::AuthorityState;
 use mgo_core::in_mem_execution_cache::ExecutionCacheRead;
 use mgo_core::subscription_handler::SubscriptionHandler;
 use mgo_json_rpc_types::{
-    Coin as MgoCoin, DevInspectResults, DryRunTransactionBlockResponse, EventFilter, MgoEvent,
-    MgoObjectDataFilter, TransactionFilter,
+    CheckpointMismatch, CheckpointMismatchKind, CheckpointVerificationReport, Coin as MgoCoin,
+    DevInspectResults, DryRunTransactionBlockResponse, EventFilter, MgoEvent, MgoObjectDataFilter,
+    TransactionFilter,
 };
 use mgo_storage::indexes::TotalBalance;
 use mgo_storage::key_value_store::{
     KVStoreCheckpointData, KVStoreTransactionData, TransactionKeyValueStore,
     TransactionKeyValueStoreTrait,
 };
+use mgo_types::balance::Supply;
 use mgo_types::base_types::{
     MoveObjectType, ObjectID, ObjectInfo, ObjectRef, SequenceNumber, MgoAddress,
 };
+use mgo_types::coin::{CoinMetadata, TreasuryCap};
 use mgo_types::committee::{Committee, EpochId};
 use mgo_types::digests::{ChainIdentifier, TransactionDigest, TransactionEventsDigest};
-use mgo_types::dynamic_field::DynamicFieldInfo;
-use mgo_types::effects::TransactionEffects;
+use mgo_types::dynamic_field::{DynamicFieldInfo, Field};
+use mgo_types::effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents};
 use mgo_types::error::{MgoError, UserInputError};
 use mgo_types::event::EventID;
+use mgo_types::gas_coin::{GAS, TOTAL_SUPPLY_MIST};
 use mgo_types::governance::StakedMgo;
 use mgo_types::messages_checkpoint::{
     CheckpointContents, CheckpointContentsDigest, CheckpointDigest, CheckpointSequenceNumber,
@@ -38,7 +42,10 @@ use mgo_types::messages_checkpoint::{
 use mgo_types::object::{Object, ObjectRead, PastObjectRead};
 use mgo_types::storage::{BackingPackageStore, ObjectStore, WriteKind};
 use mgo_types::mgo_serde::BigInt;
-use mgo_types::mgo_system_state::MgoSystemState;
+use mgo_types::id::ID;
+use mgo_types::mgo_system_state::{
+    get_validator_from_table, MgoSystemState, MgoSystemStateTrait, PoolTokenExchangeRate,
+};
 use mgo_types::transaction::{Transaction, TransactionData, TransactionKind};
 use thiserror::Error;
 use tokio::task::JoinError;
@@ -46,10 +53,29 @@ use tokio::task::JoinError;
 #[cfg(test)]
 use mockall::automock;
 
+use crate::governance_api::ValidatorExchangeRates;
+use crate::read_api::calculate_checkpoint_numbers;
 use crate::ObjectProvider;
 
 pub type StateReadResult<T = ()> = Result<T, StateReadError>;
 
+/// Page of the transactions of a checkpoint read by [`StateRead::get_checkpoint_transactions`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CheckpointTransactionsOptions {
+    /// Start after this transaction of the checkpoint, or at its first transaction if `None`.
+    pub cursor: Option<TransactionDigest>,
+    pub limit: usize,
+}
+
+/// A transaction of a checkpoint, with whichever of its parts the node still has.
+#[derive(Clone, Debug)]
+pub struct CheckpointTransactionData {
+    pub digest: TransactionDigest,
+    pub transaction: Option<Transaction>,
+    pub effects: Option<TransactionEffects>,
+    pub events: Option<TransactionEvents>,
+}
+
 /// Trait for AuthorityState methods commonly used by at least two api.
 #[cfg_attr(test, automock)]
 #[async_trait]
@@ -77,8 +103,15 @@ pub trait StateRead: Send + Sync {
         version: SequenceNumber,
     ) -> StateReadResult<PastObjectRead>;
 
+    fn multi_get_past_object_reads(
+        &self,
+        requests: &[(ObjectID, SequenceNumber)],
+    ) -> StateReadResult<Vec<PastObjectRead>>;
+
     async fn get_object(&self, object_id: &ObjectID) -> StateReadResult<Option<Object>>;
 
+    async fn get_objects(&self, object_ids: &[ObjectID]) -> StateReadResult<Vec<Option<Object>>>;
+
     fn load_epoch_store_one_call_per_task(&self) -> Guard<Arc<AuthorityPerEpochStore>>;
 
     fn get_dynamic_fields(
@@ -167,6 +200,13 @@ pub trait StateRead: Send + Sync {
     async fn get_staked_mgo(&self, owner: MgoAddress) -> StateReadResult<Vec<StakedMgo>>;
     fn get_system_state(&self) -> StateReadResult<MgoSystemState>;
     fn get_or_latest_committee(&self, epoch: Option<BigInt<u64>>) -> StateReadResult<Committee>;
+    /// Exchange rates of the staking pools of the active and inactive validators over the
+    /// current epoch and the `epochs_back` before it, newest first. Epochs a pool has no rate
+    /// for, such as those its validator was inactive in, are skipped.
+    fn get_validator_exchange_rates(
+        &self,
+        epochs_back: u64,
+    ) -> StateReadResult<Vec<ValidatorExchangeRates>>;
 
     // coin_api
     fn find_publish_txn_digest(&self, package_id: ObjectID) -> StateReadResult<TransactionDigest>;
@@ -176,6 +216,7 @@ pub trait StateRead: Send + Sync {
         cursor: (String, ObjectID),
         limit: usize,
         one_coin_type_only: bool,
+        min_balance: Option<u64>,
     ) -> StateReadResult<Vec<MgoCoin>>;
     async fn get_executed_transaction_and_effects(
         &self,
@@ -191,6 +232,20 @@ pub trait StateRead: Send + Sync {
         &self,
         owner: MgoAddress,
     ) -> StateReadResult<Arc<HashMap<TypeTag, TotalBalance>>>;
+    /// Balances of `owner` for at most `limit` coin types, ordered by coin type string and
+    /// starting after the coin type `cursor`.
+    async fn get_all_balance_paginated(
+        &self,
+        owner: MgoAddress,
+        cursor: Option<String>,
+        limit: usize,
+    ) -> StateReadResult<Vec<(TypeTag, TotalBalance)>>;
+    /// Total supply of `coin_type`, read from its treasury cap.
+    async fn get_total_supply(
+        &self,
+        coin_type: TypeTag,
+        kv_store: Arc<TransactionKeyValueStore>,
+    ) -> StateReadResult<Supply>;
 
     // read_api
     fn get_verified_checkpoint_by_sequence_number(
@@ -223,6 +278,8 @@ pub trait StateRead: Send + Sync {
         sequence_numbers: &[CheckpointSequenceNumber],
     ) -> StateReadResult<Vec<Option<VerifiedCheckpoint>>>;
 
+    /// Number of transactions indexed by the node as of the latest checkpoint, consistent with
+    /// [`Self::get_latest_checkpoint_sequence_number`].
     fn get_total_transaction_blocks(&self) -> StateReadResult<u64>;
 
     fn get_checkpoint_by_sequence_number(
@@ -230,14 +287,51 @@ pub trait StateRead: Send + Sync {
         sequence_number: CheckpointSequenceNumber,
     ) -> StateReadResult<Option<VerifiedCheckpoint>>;
 
+    /// The latest executed checkpoint, which is kept in memory and only read from the store
+    /// until a checkpoint is executed after startup.
     fn get_latest_checkpoint_sequence_number(&self) -> StateReadResult<CheckpointSequenceNumber>;
 
+    /// At most `limit` checkpoints after `cursor`, in ascending or descending order, and only
+    /// those of `epoch` if given.
+    fn get_checkpoints(
+        &self,
+        cursor: Option<CheckpointSequenceNumber>,
+        limit: u64,
+        descending_order: bool,
+        epoch: Option<EpochId>,
+    ) -> StateReadResult<Vec<VerifiedCheckpoint>>;
+
+    /// Transactions of checkpoint `sequence_number` in checkpoint order, with their effects and
+    /// events. Transactions whose parts were pruned are still returned, without those parts.
+    async fn get_checkpoint_transactions(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+        options: CheckpointTransactionsOptions,
+    ) -> StateReadResult<Vec<CheckpointTransactionData>>;
+
     fn loaded_child_object_versions(
         &self,
         transaction_digest: &TransactionDigest,
     ) -> StateReadResult<Option<Vec<(ObjectID, SequenceNumber)>>>;
 
     fn get_chain_identifier(&self) -> StateReadResult<ChainIdentifier>;
+
+    // support_api
+    /// The transaction locking `object_ref` in the current epoch, if any.
+    async fn get_transaction_lock(
+        &self,
+        object_ref: &ObjectRef,
+    ) -> StateReadResult<Option<TransactionDigest>>;
+
+    /// Checks that the stored checkpoints from `start` to `end`, both included, hash to the
+    /// digests they are linked by: the contents of each to the contents digest of its summary,
+    /// and each summary to the previous digest of the next one. Mismatches are reported rather
+    /// than failing the check.
+    fn verify_checkpoint_range(
+        &self,
+        start: CheckpointSequenceNumber,
+        end: CheckpointSequenceNumber,
+    ) -> StateReadResult<CheckpointVerificationReport>;
 }
 
 #[async_trait]
@@ -286,6 +380,10 @@ impl StateRead for AuthorityState {
         Ok(self.get_object(object_id).await?)
     }
 
+    async fn get_objects(&self, object_ids: &[ObjectID]) -> StateReadResult<Vec<Option<Object>>> {
+        Ok(self.get_objects(object_ids).await?)
+    }
+
     fn get_past_object_read(
         &self,
         object_id: &ObjectID,
@@ -294,6 +392,13 @@ impl StateRead for AuthorityState {
         Ok(self.get_past_object_read(object_id, version)?)
     }
 
+    fn multi_get_past_object_reads(
+        &self,
+        requests: &[(ObjectID, SequenceNumber)],
+    ) -> StateReadResult<Vec<PastObjectRead>> {
+        Ok(self.multi_get_past_object_reads(requests)?)
+    }
+
     fn load_epoch_store_one_call_per_task(&self) -> Guard<Arc<AuthorityPerEpochStore>> {
         self.load_epoch_store_one_call_per_task()
     }
@@ -431,9 +536,107 @@ impl StateRead for AuthorityState {
         Ok(self.database.get_mgo_system_state_object_unsafe()?)
     }
     fn get_or_latest_committee(&self, epoch: Option<BigInt<u64>>) -> StateReadResult<Committee> {
-        Ok(self
+        match self
             .committee_store()
-            .get_or_latest_committee(epoch.map(|e| *e))?)
+            .get_or_latest_committee(epoch.map(|e| *e))
+        {
+            Err(MgoError::MissingCommitteeAtEpoch(epoch)) => {
+                Err(StateReadClientError::CommitteeNotFound(epoch).into())
+            }
+            committee => Ok(committee?),
+        }
+    }
+
+    fn get_validator_exchange_rates(
+        &self,
+        epochs_back: u64,
+    ) -> StateReadResult<Vec<ValidatorExchangeRates>> {
+        let system_state_summary =
+            StateRead::get_system_state(self)?.into_mgo_system_state_summary();
+        let object_store = StateRead::get_object_store(self);
+        let current_epoch = system_state_summary.epoch;
+        let first_epoch = current_epoch.saturating_sub(epochs_back);
+
+        let mut tables = vec![];
+        for validator in system_state_summary.active_validators {
+            tables.push((validator, true));
+        }
+        for (_, df) in StateRead::get_dynamic_fields(
+            self,
+            system_state_summary.inactive_pools_id,
+            None,
+            system_state_summary.inactive_pools_size as usize,
+        )? {
+            let pool_id: ID =
+                bcs::from_bytes(&df.bcs_name).map_err(|e| MgoError::ObjectDeserializationError {
+                    error: e.to_string(),
+                })?;
+            let validator = get_validator_from_table(
+                object_store.as_ref(),
+                system_state_summary.inactive_pools_id,
+                &pool_id,
+            )?;
+            tables.push((validator, false));
+        }
+
+        let read_rate = |field_id: ObjectID| -> StateReadResult<(EpochId, PoolTokenExchangeRate)> {
+            let object = object_store.get_object(&field_id)?.ok_or_else(|| {
+                MgoError::DynamicFieldReadError(format!("Exchange rate {field_id} not found"))
+            })?;
+            let move_object = object.data.try_as_move().ok_or_else(|| {
+                MgoError::DynamicFieldReadError(format!(
+                    "Exchange rate {field_id} is not a Move object"
+                ))
+            })?;
+            let field: Field<EpochId, PoolTokenExchangeRate> =
+                bcs::from_bytes(move_object.contents()).map_err(|e| {
+                    MgoError::ObjectDeserializationError {
+                        error: e.to_string(),
+                    }
+                })?;
+            Ok((field.name, field.value))
+        };
+
+        let mut exchange_rates = vec![];
+        for (validator, active) in tables {
+            let table_id = validator.exchange_rates_id;
+            // Walking the whole table is cheaper than looking up every epoch since genesis.
+            let field_ids = if first_epoch == 0 {
+                StateRead::get_dynamic_fields(
+                    self,
+                    table_id,
+                    None,
+                    validator.exchange_rates_size as usize,
+                )?
+                .into_iter()
+                .map(|(field_id, _)| field_id)
+                .collect::<Vec<_>>()
+            } else {
+                let mut field_ids = vec![];
+                for epoch in first_epoch..=current_epoch {
+                    let name = bcs::to_bytes(&epoch).expect("Epochs should serialize");
+                    if let Some(field_id) =
+                        StateRead::get_dynamic_field_object_id(self, table_id, TypeTag::U64, &name)?
+                    {
+                        field_ids.push(field_id);
+                    }
+                }
+                field_ids
+            };
+            let mut rates = field_ids
+                .into_iter()
+                .map(read_rate)
+                .collect::<StateReadResult<Vec<_>>>()?;
+            rates.sort_by(|(a, _), (b, _)| a.cmp(b).reverse());
+
+            exchange_rates.push(ValidatorExchangeRates {
+                address: validator.mgo_address,
+                pool_id: validator.staking_pool_id,
+                active,
+                rates,
+            });
+        }
+        Ok(exchange_rates)
     }
 
     fn find_publish_txn_digest(&self, package_id: ObjectID) -> StateReadResult<TransactionDigest> {
@@ -445,9 +648,16 @@ impl StateRead for AuthorityState {
         cursor: (String, ObjectID),
         limit: usize,
         one_coin_type_only: bool,
+        min_balance: Option<u64>,
     ) -> StateReadResult<Vec<MgoCoin>> {
         Ok(self
-            .get_owned_coins_iterator_with_cursor(owner, cursor, limit, one_coin_type_only)?
+            .get_owned_coins_iterator_with_cursor(
+                owner,
+                cursor,
+                limit,
+                one_coin_type_only,
+                min_balance,
+            )?
             .map(|(coin_type, coin_object_id, coin)| MgoCoin {
                 coin_type,
                 coin_object_id,
@@ -455,6 +665,9 @@ impl StateRead for AuthorityState {
                 digest: coin.digest,
                 balance: coin.balance,
                 previous_transaction: coin.previous_transaction,
+                verified: None,
+                canonical_symbol: None,
+                canonical_icon_url: None,
             })
             .collect::<Vec<_>>())
     }
@@ -494,6 +707,28 @@ impl StateRead for AuthorityState {
             .await?)
     }
 
+    async fn get_all_balance_paginated(
+        &self,
+        owner: MgoAddress,
+        cursor: Option<String>,
+        limit: usize,
+    ) -> StateReadResult<Vec<(TypeTag, TotalBalance)>> {
+        Ok(self
+            .indexes
+            .as_ref()
+            .ok_or(MgoError::IndexStoreNotAvailable)?
+            .get_all_balance_paginated(owner, cursor, limit)
+            .await?)
+    }
+
+    async fn get_total_supply(
+        &self,
+        coin_type: TypeTag,
+        kv_store: Arc<TransactionKeyValueStore>,
+    ) -> StateReadResult<Supply> {
+        find_total_supply(self, &coin_type, kv_store).await
+    }
+
     fn get_verified_checkpoint_by_sequence_number(
         &self,
         sequence_number: CheckpointSequenceNumber,
@@ -541,7 +776,11 @@ impl StateRead for AuthorityState {
     }
 
     fn get_total_transaction_blocks(&self) -> StateReadResult<u64> {
-        Ok(self.get_total_transaction_blocks()?)
+        match self.get_latest_checkpoint()?.total_transaction_blocks {
+            Some(total_transaction_blocks) => Ok(total_transaction_blocks),
+            // Fails the same way on nodes that do not index transactions.
+            None => Ok(self.get_total_transaction_blocks()?),
+        }
     }
 
     fn get_checkpoint_by_sequence_number(
@@ -552,7 +791,113 @@ impl StateRead for AuthorityState {
     }
 
     fn get_latest_checkpoint_sequence_number(&self) -> StateReadResult<CheckpointSequenceNumber> {
-        Ok(self.get_latest_checkpoint_sequence_number()?)
+        Ok(self.get_latest_checkpoint()?.sequence_number)
+    }
+
+    fn get_checkpoints(
+        &self,
+        cursor: Option<CheckpointSequenceNumber>,
+        limit: u64,
+        descending_order: bool,
+        epoch: Option<EpochId>,
+    ) -> StateReadResult<Vec<VerifiedCheckpoint>> {
+        let max_checkpoint = self.get_latest_checkpoint_sequence_number()?;
+        // The checkpoints of an epoch run from the one after the last checkpoint of the
+        // previous epoch to its own last checkpoint, or to the latest one while it is ongoing.
+        let (first_checkpoint, last_checkpoint) = match epoch {
+            None => (0, max_checkpoint),
+            Some(epoch) => {
+                let checkpoint_store = self.get_checkpoint_store();
+                let first_checkpoint = match epoch.checked_sub(1) {
+                    None => 0,
+                    Some(previous_epoch) => {
+                        match checkpoint_store.get_epoch_last_checkpoint(previous_epoch)? {
+                            Some(checkpoint) => checkpoint.sequence_number + 1,
+                            // The epoch has not started yet.
+                            None => return Ok(vec![]),
+                        }
+                    }
+                };
+                let last_checkpoint = checkpoint_store
+                    .get_epoch_last_checkpoint(epoch)?
+                    .map_or(max_checkpoint, |checkpoint| checkpoint.sequence_number);
+                (first_checkpoint, last_checkpoint)
+            }
+        };
+        let sequence_numbers = calculate_checkpoint_numbers(
+            cursor,
+            limit,
+            descending_order,
+            first_checkpoint,
+            last_checkpoint,
+        );
+        Ok(self
+            .multi_get_checkpoint_by_sequence_number(&sequence_numbers)?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    async fn get_checkpoint_transactions(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+        options: CheckpointTransactionsOptions,
+    ) -> StateReadResult<Vec<CheckpointTransactionData>> {
+        let checkpoint = self
+            .get_checkpoint_by_sequence_number(sequence_number)?
+            .ok_or(StateReadClientError::CheckpointNotFound(sequence_number))?;
+        let contents = self.get_checkpoint_contents(checkpoint.content_digest)?;
+        let mut digests = contents.iter().map(|digests| digests.transaction);
+        if let Some(cursor) = options.cursor {
+            digests.by_ref().find(|digest| *digest == cursor).ok_or(
+                StateReadClientError::TransactionNotInCheckpoint(cursor, sequence_number),
+            )?;
+        }
+        let digests = digests.take(options.limit).collect::<Vec<_>>();
+
+        let (transactions, effects, _) =
+            <AuthorityState as TransactionKeyValueStoreTrait>::multi_get(
+                self,
+                &digests,
+                &digests,
+                &[],
+            )
+            .await?;
+        let event_digests = effects
+            .iter()
+            .flatten()
+            .filter_map(|effects| effects.events_digest().copied())
+            .collect::<Vec<_>>();
+        let (_, _, events) = <AuthorityState as TransactionKeyValueStoreTrait>::multi_get(
+            self,
+            &[],
+            &[],
+            &event_digests,
+        )
+        .await?;
+        let events = event_digests
+            .into_iter()
+            .zip(events)
+            .filter_map(|(digest, events)| Some((digest, events?)))
+            .collect::<HashMap<_, _>>();
+
+        Ok(digests
+            .into_iter()
+            .zip(transactions)
+            .zip(effects)
+            .map(|((digest, transaction), effects)| {
+                let events = effects
+                    .as_ref()
+                    .and_then(|effects| effects.events_digest())
+                    .and_then(|digest| events.get(digest).cloned());
+                CheckpointTransactionData {
+                    digest,
+                    transaction,
+                    effects,
+                    events,
+                }
+            })
+            .collect())
     }
 
     fn loaded_child_object_versions(
@@ -567,6 +912,89 @@ impl StateRead for AuthorityState {
             .get_chain_identifier()
             .ok_or(anyhow!("Chain identifier not found"))?)
     }
+
+    async fn get_transaction_lock(
+        &self,
+        object_ref: &ObjectRef,
+    ) -> StateReadResult<Option<TransactionDigest>> {
+        let epoch_store = self.load_epoch_store_one_call_per_task();
+        Ok(self
+            .get_transaction_lock(object_ref, &epoch_store)
+            .await?
+            .map(|transaction| *transaction.digest()))
+    }
+
+    fn verify_checkpoint_range(
+        &self,
+        start: CheckpointSequenceNumber,
+        end: CheckpointSequenceNumber,
+    ) -> StateReadResult<CheckpointVerificationReport> {
+        // The checkpoint before the range is read too, to check the link of the first one.
+        let first = start.saturating_sub(1);
+        let sequence_numbers: Vec<_> = (first..=end).collect();
+        let summaries = self.multi_get_checkpoint_by_sequence_number(&sequence_numbers)?;
+        let (before, in_range) = summaries.split_at((start - first) as usize);
+        let contents_digests: Vec<_> = in_range
+            .iter()
+            .flatten()
+            .map(|summary| summary.content_digest)
+            .collect();
+        let mut contents = self
+            .get_checkpoint_store()
+            .multi_get_checkpoint_content(&contents_digests)
+            .map_err(MgoError::from)?
+            .into_iter();
+
+        let mut report = CheckpointVerificationReport {
+            start,
+            end,
+            checked: 0,
+            mismatches: vec![],
+        };
+        let mut previous = before.first().and_then(Option::as_ref);
+        for (sequence_number, summary) in (start..=end).zip(in_range) {
+            let mut mismatch = |kind| {
+                report.mismatches.push(CheckpointMismatch {
+                    sequence_number,
+                    kind,
+                })
+            };
+            let Some(summary) = summary else {
+                mismatch(CheckpointMismatchKind::MissingSummary);
+                previous = None;
+                continue;
+            };
+            match contents.next().flatten() {
+                None => mismatch(CheckpointMismatchKind::MissingContents {
+                    contents_digest: summary.content_digest,
+                }),
+                Some(stored) if *stored.digest() != summary.content_digest => {
+                    mismatch(CheckpointMismatchKind::ContentsDigest {
+                        expected: summary.content_digest,
+                        actual: *stored.digest(),
+                    })
+                }
+                Some(_) => {}
+            }
+            // The link to a missing summary cannot be checked, and the summary is reported.
+            let expected = match previous {
+                _ if sequence_number == 0 => Some(None),
+                Some(previous) => Some(Some(*previous.digest())),
+                None => None,
+            };
+            if let Some(expected) = expected {
+                if summary.previous_digest != expected {
+                    mismatch(CheckpointMismatchKind::PreviousDigest {
+                        expected,
+                        actual: summary.previous_digest,
+                    });
+                }
+            }
+            report.checked += 1;
+            previous = Some(summary);
+        }
+        Ok(report)
+    }
 }
 
 /// This implementation allows `S` to be a dynamically sized type (DST) that implements ObjectProvider
@@ -595,6 +1023,13 @@ impl<S: ?Sized + StateRead> ObjectProvider for Arc<S> {
             .await
             .map_err(StateReadError::from)
     }
+
+    async fn multi_find_object_lt_or_eq_version(
+        &self,
+        objects: &[(ObjectID, SequenceNumber)],
+    ) -> Result<Vec<Option<Object>>, Self::Error> {
+        multi_find_object_lt_or_eq_version(self.get_cache_reader(), objects).await
+    }
 }
 
 #[async_trait]
@@ -630,6 +1065,85 @@ impl<S: ?Sized + StateRead> ObjectProvider for (Arc<S>, Arc<TransactionKeyValueS
             .await
             .map_err(StateReadError::from)
     }
+
+    async fn multi_find_object_lt_or_eq_version(
+        &self,
+        objects: &[(ObjectID, SequenceNumber)],
+    ) -> Result<Vec<Option<Object>>, Self::Error> {
+        multi_find_object_lt_or_eq_version(self.0.get_cache_reader(), objects).await
+    }
+}
+
+/// Looks up every distinct `(id, version)` of `objects` in a single task, returning the results
+/// in the order of `objects`.
+async fn multi_find_object_lt_or_eq_version(
+    cache: Arc<dyn ExecutionCacheRead>,
+    objects: &[(ObjectID, SequenceNumber)],
+) -> StateReadResult<Vec<Option<Object>>> {
+    let keys = objects.iter().copied().collect::<BTreeSet<_>>();
+    let found = spawn_monitored_task!(async move {
+        keys.into_iter()
+            .map(|key| (key, cache.find_object_lt_or_eq_version(key.0, key.1)))
+            .collect::<BTreeMap<_, _>>()
+    })
+    .await
+    .map_err(StateReadError::from)?;
+    Ok(objects.iter().map(|key| found[key].clone()).collect())
+}
+
+/// Reads the supply of `coin_type` from the treasury cap created when its package was
+/// published, which is only readable while the cap is not wrapped or deleted. The MGO supply is
+/// minted in full at genesis and is not tracked by any object, so it is the genesis total.
+pub(crate) async fn find_total_supply(
+    state: &dyn StateRead,
+    coin_type: &TypeTag,
+    kv_store: Arc<TransactionKeyValueStore>,
+) -> StateReadResult<Supply> {
+    let coin_type_not_found = || {
+        StateReadError::from(StateReadClientError::CoinTypeNotFound(
+            coin_type.to_string(),
+        ))
+    };
+    let TypeTag::Struct(coin_struct) = coin_type else {
+        return Err(coin_type_not_found());
+    };
+    if GAS::is_gas(coin_struct) {
+        return Ok(Supply {
+            value: TOTAL_SUPPLY_MIST,
+        });
+    }
+
+    let package_id = ObjectID::from(coin_struct.address);
+    if !matches!(state.get_object_read(&package_id)?, ObjectRead::Exists(..)) {
+        return Err(coin_type_not_found());
+    }
+    let publish_txn_digest = state.find_publish_txn_digest(package_id)?;
+    let (_, effects) = state
+        .get_executed_transaction_and_effects(publish_txn_digest, kv_store)
+        .await?;
+
+    let treasury_cap_type = TreasuryCap::type_(*coin_struct.clone());
+    let coin_metadata_type = CoinMetadata::type_(*coin_struct.clone());
+    let mut has_coin_metadata = false;
+    for ((id, _, _), _) in effects.created() {
+        let Ok(object) = state.get_object_read(&id)?.into_object() else {
+            continue;
+        };
+        let Some(move_object) = object.data.try_as_move() else {
+            continue;
+        };
+        if move_object.type_().is(&treasury_cap_type) {
+            return Ok(TreasuryCap::from_bcs_bytes(move_object.contents())?.total_supply);
+        }
+        has_coin_metadata |= move_object.type_().is(&coin_metadata_type);
+    }
+    // A package that published the coin metadata but no readable treasury cap does define the
+    // coin type, its supply is just not readable anymore.
+    Err(if has_coin_metadata {
+        StateReadClientError::TreasuryCapNotFound(coin_type.to_string()).into()
+    } else {
+        coin_type_not_found()
+    })
 }
 
 #[derive(Debug, Error)]
@@ -648,6 +1162,18 @@ pub enum StateReadClientError {
     MgoError(#[from] MgoError),
     #[error(transparent)]
     UserInputError(#[from] UserInputError),
+    #[error("Coin type {0} does not exist")]
+    CoinTypeNotFound(String),
+    #[error("Coin type {0} has no treasury cap to read its supply from")]
+    TreasuryCapNotFound(String),
+    #[error("Checkpoint {0} was not found")]
+    CheckpointNotFound(CheckpointSequenceNumber),
+    #[error("Transaction {0} is not part of checkpoint {1}")]
+    TransactionNotInCheckpoint(TransactionDigest, CheckpointSequenceNumber),
+    #[error("Epoch {0} has not started yet")]
+    EpochNotStarted(EpochId),
+    #[error("The committee of epoch {0} is not stored on this node")]
+    CommitteeNotFound(EpochId),
 }
 
 /// `StateReadError` is the error type for callers to work with.
@@ -1,8 +1,10 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 
+use axum::extract::ConnectInfo;
 use axum::extract::Json;
 use axum::extract::State;
 use futures::StreamExt;
@@ -18,6 +20,12 @@ use jsonrpsee::types::{ErrorObject, Id, InvalidRequest, Params, Request};
 use jsonrpsee::{core::server::rpc_module::Methods, server::logger::Logger};
 use serde_json::value::RawValue;
 
+use crate::cors::OriginMethods;
+use crate::kill_switch::MethodKillSwitch;
+use crate::panic_guard::HandlerPanicGuard;
+use crate::query_scheduler::QueryScheduler;
+use crate::rate_limiter::RateLimiter;
+use crate::response_signing::ResponseSigner;
 use crate::routing_layer::RpcRouter;
 use mgo_json_rpc_api::CLIENT_TARGET_API_VERSION_HEADER;
 
@@ -32,13 +40,35 @@ pub struct JsonRpcService<L> {
     /// Registered server methods.
     methods: Methods,
     rpc_router: RpcRouter,
+    kill_switch: MethodKillSwitch,
+    origin_methods: OriginMethods,
+    response_signer: Option<ResponseSigner>,
+    panic_guard: HandlerPanicGuard,
+    query_scheduler: QueryScheduler,
+    rate_limiter: RateLimiter,
 }
 
 impl<L> JsonRpcService<L> {
-    pub fn new(methods: Methods, rpc_router: RpcRouter, logger: L) -> Self {
+    pub fn new(
+        methods: Methods,
+        rpc_router: RpcRouter,
+        kill_switch: MethodKillSwitch,
+        origin_methods: OriginMethods,
+        response_signer: Option<ResponseSigner>,
+        panic_guard: HandlerPanicGuard,
+        query_scheduler: QueryScheduler,
+        rate_limiter: RateLimiter,
+        logger: L,
+    ) -> Self {
         Self {
             methods,
             rpc_router,
+            kill_switch,
+            origin_methods,
+            response_signer,
+            panic_guard,
+            query_scheduler,
+            rate_limiter,
             logger,
             id_provider: Arc::new(RandomIntegerIdProvider),
         }
@@ -46,11 +76,19 @@ impl<L> JsonRpcService<L> {
 }
 
 impl<L: Logger> JsonRpcService<L> {
-    fn call_data(&self) -> CallData<'_, L> {
+    fn call_data<'a>(&'a self, client: Option<IpAddr>, origin: Option<&'a str>) -> CallData<'a, L> {
         CallData {
             logger: &self.logger,
             methods: &self.methods,
             rpc_router: &self.rpc_router,
+            kill_switch: &self.kill_switch,
+            origin_methods: &self.origin_methods,
+            response_signer: self.response_signer.as_ref(),
+            panic_guard: &self.panic_guard,
+            query_scheduler: &self.query_scheduler,
+            rate_limiter: &self.rate_limiter,
+            client,
+            origin,
             max_response_body_size: MAX_RESPONSE_SIZE,
             request_start: self.logger.on_request(TransportProtocol::Http),
         }
@@ -60,10 +98,20 @@ impl<L: Logger> JsonRpcService<L> {
         &'a self,
         bounded_subscriptions: BoundedSubscriptions,
         sink: &'b MethodSink,
+        client: Option<IpAddr>,
+        origin: Option<&'b str>,
     ) -> ws::WsCallData<'c, L> {
         ws::WsCallData {
             logger: &self.logger,
             methods: &self.methods,
+            kill_switch: &self.kill_switch,
+            origin_methods: &self.origin_methods,
+            response_signer: self.response_signer.as_ref(),
+            panic_guard: &self.panic_guard,
+            query_scheduler: &self.query_scheduler,
+            rate_limiter: &self.rate_limiter,
+            client,
+            origin,
             max_response_body_size: MAX_RESPONSE_SIZE,
             request_start: self.logger.on_request(TransportProtocol::Http),
             bounded_subscriptions,
@@ -99,6 +147,7 @@ pub(crate) fn ok_response(body: String) -> hyper::Response<hyper::Body> {
 
 pub async fn json_rpc_handler<L: Logger>(
     State(service): State<JsonRpcService<L>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
     headers: HeaderMap,
     Json(raw_request): Json<Box<RawValue>>,
 ) -> impl axum::response::IntoResponse {
@@ -106,7 +155,18 @@ pub async fn json_rpc_handler<L: Logger>(
     let api_version = headers
         .get(CLIENT_TARGET_API_VERSION_HEADER)
         .and_then(|h| h.to_str().ok());
-    let response = process_raw_request(&service, api_version, raw_request.get()).await;
+    let client = service
+        .rate_limiter
+        .client(connect_info.map(|ConnectInfo(peer)| peer), &headers);
+    let origin = OriginMethods::origin(&headers);
+    let response = process_raw_request(
+        &service,
+        api_version,
+        raw_request.get(),
+        client,
+        origin,
+    )
+    .await;
 
     ok_response(response.result)
 }
@@ -115,9 +175,11 @@ async fn process_raw_request<L: Logger>(
     service: &JsonRpcService<L>,
     api_version: Option<&str>,
     raw_request: &str,
+    client: Option<IpAddr>,
+    origin: Option<&str>,
 ) -> MethodResponse {
     if let Ok(request) = serde_json::from_str::<Request>(raw_request) {
-        process_request(request, api_version, service.call_data()).await
+        process_request(request, api_version, service.call_data(client, origin)).await
     } else if let Ok(_batch) = serde_json::from_str::<Vec<&RawValue>>(raw_request) {
         MethodResponse::error(
             Id::Null,
@@ -137,6 +199,14 @@ async fn process_request<L: Logger>(
     let CallData {
         methods,
         rpc_router,
+        kill_switch,
+        origin_methods,
+        response_signer,
+        panic_guard,
+        query_scheduler,
+        rate_limiter,
+        client,
+        origin,
         logger,
         max_response_body_size,
         request_start,
@@ -147,6 +217,34 @@ async fn process_request<L: Logger>(
     let name = rpc_router.route(&req.method, api_version);
     let id = req.id;
 
+    let refusal = kill_switch
+        .check(&req.method)
+        .or_else(|| origin_methods.check(origin, &req.method));
+    let admission = match refusal {
+        Some(error) => Err(error),
+        None => rate_limiter.acquire(name, client),
+    };
+    let _rate_permit = match admission {
+        Ok(permit) => permit,
+        Err(error) => {
+            logger.on_call(
+                name,
+                params,
+                logger::MethodKind::MethodCall,
+                TransportProtocol::Http,
+            );
+            let response = MethodResponse::error(id, error);
+            logger.on_result(
+                name,
+                response.success,
+                response.error_code,
+                request_start,
+                TransportProtocol::Http,
+            );
+            return response;
+        }
+    };
+
     let response = match methods.method_with_name(name) {
         None => {
             logger.on_call(
@@ -165,7 +263,13 @@ async fn process_request<L: Logger>(
                     logger::MethodKind::MethodCall,
                     TransportProtocol::Http,
                 );
-                (callback)(id, params, max_response_body_size as usize)
+                let id = id.into_owned();
+                let _permit = query_scheduler.acquire(&req.method).await;
+                panic_guard
+                    .call(name, || {
+                        (callback)(id.clone(), params, max_response_body_size as usize)
+                    })
+                    .unwrap_or_else(|error| MethodResponse::error(id, error))
             }
             MethodKind::Async(callback) => {
                 logger.on_call(
@@ -177,8 +281,21 @@ async fn process_request<L: Logger>(
 
                 let id = id.into_owned();
                 let params = params.into_owned();
+                let _permit = query_scheduler.acquire(&req.method).await;
 
-                (callback)(id, params, conn_id, max_response_body_size as usize, None).await
+                panic_guard
+                    .call_async(
+                        name,
+                        (callback)(
+                            id.clone(),
+                            params,
+                            conn_id,
+                            max_response_body_size as usize,
+                            None,
+                        ),
+                    )
+                    .await
+                    .unwrap_or_else(|error| MethodResponse::error(id, error))
             }
             MethodKind::Subscription(_) | MethodKind::Unsubscription(_) => {
                 logger.on_call(
@@ -192,6 +309,10 @@ async fn process_request<L: Logger>(
             }
         },
     };
+    let response = match response_signer {
+        Some(signer) => signer.sign(&req.method, response),
+        None => response,
+    };
 
     logger.on_result(
         name,
@@ -217,6 +338,14 @@ pub(crate) struct CallData<'a, L: Logger> {
     logger: &'a L,
     methods: &'a Methods,
     rpc_router: &'a RpcRouter,
+    kill_switch: &'a MethodKillSwitch,
+    origin_methods: &'a OriginMethods,
+    response_signer: Option<&'a ResponseSigner>,
+    panic_guard: &'a HandlerPanicGuard,
+    query_scheduler: &'a QueryScheduler,
+    rate_limiter: &'a RateLimiter,
+    client: Option<IpAddr>,
+    origin: Option<&'a str>,
     max_response_body_size: u32,
     request_start: L::Instant,
 }
@@ -246,6 +375,14 @@ pub mod ws {
         pub bounded_subscriptions: BoundedSubscriptions,
         pub id_provider: &'a dyn IdProvider,
         pub methods: &'a Methods,
+        pub kill_switch: &'a MethodKillSwitch,
+        pub origin_methods: &'a OriginMethods,
+        pub response_signer: Option<&'a ResponseSigner>,
+        pub panic_guard: &'a HandlerPanicGuard,
+        pub query_scheduler: &'a QueryScheduler,
+        pub rate_limiter: &'a RateLimiter,
+        pub client: Option<IpAddr>,
+        pub origin: Option<&'a str>,
         pub max_response_body_size: u32,
         pub sink: &'a MethodSink,
         pub logger: &'a L,
@@ -258,23 +395,42 @@ pub mod ws {
     pub async fn ws_json_rpc_upgrade<L: Logger>(
         ws: WebSocketUpgrade,
         State(service): State<JsonRpcService<L>>,
+        connect_info: Option<ConnectInfo<SocketAddr>>,
+        headers: HeaderMap,
     ) -> Response {
-        ws.on_upgrade(|ws| ws_json_rpc_handler(ws, service))
+        let client = service
+            .rate_limiter
+            .client(connect_info.map(|ConnectInfo(peer)| peer), &headers);
+        let origin = OriginMethods::origin(&headers).map(str::to_string);
+        ws.on_upgrade(move |ws| ws_json_rpc_handler(ws, service, client, origin))
     }
 
-    async fn ws_json_rpc_handler<L: Logger>(mut socket: WebSocket, service: JsonRpcService<L>) {
+    async fn ws_json_rpc_handler<L: Logger>(
+        mut socket: WebSocket,
+        service: JsonRpcService<L>,
+        client: Option<IpAddr>,
+        origin: Option<String>,
+    ) {
         #[allow(clippy::disallowed_methods)]
         let (tx, mut rx) = mpsc::unbounded::<String>();
         let sink = MethodSink::new_with_limit(tx, MAX_RESPONSE_SIZE, MAX_RESPONSE_SIZE);
-        let bounded_subscriptions = BoundedSubscriptions::new(100);
+        let bounded_subscriptions =
+            BoundedSubscriptions::new(service.rate_limiter.max_subscriptions_per_connection());
 
         loop {
             tokio::select! {
                 maybe_message = socket.recv() => {
                     if let Some(Ok(message)) = maybe_message {
                         if let Message::Text(msg) = message {
-                            let response =
-                                process_raw_request(&service, &msg, bounded_subscriptions.clone(), &sink).await;
+                            let response = process_raw_request(
+                                &service,
+                                &msg,
+                                bounded_subscriptions.clone(),
+                                &sink,
+                                client,
+                                origin.as_deref(),
+                            )
+                            .await;
                             if let Some(response) = response {
                                 let _ = sink.send_raw(response.result);
                             }
@@ -297,9 +453,12 @@ pub mod ws {
         raw_request: &str,
         bounded_subscriptions: BoundedSubscriptions,
         sink: &MethodSink,
+        client: Option<IpAddr>,
+        origin: Option<&str>,
     ) -> Option<MethodResponse> {
         if let Ok(request) = serde_json::from_str::<Request>(raw_request) {
-            process_request(request, service.ws_call_data(bounded_subscriptions, sink)).await
+            let call = service.ws_call_data(bounded_subscriptions, sink, client, origin);
+            process_request(request, call).await
         } else if let Ok(_batch) = serde_json::from_str::<Vec<&RawValue>>(raw_request) {
             Some(MethodResponse::error(
                 Id::Null,
@@ -317,6 +476,14 @@ pub mod ws {
     ) -> Option<MethodResponse> {
         let WsCallData {
             methods,
+            kill_switch,
+            origin_methods,
+            response_signer,
+            panic_guard,
+            query_scheduler,
+            rate_limiter,
+            client,
+            origin,
             logger,
             max_response_body_size,
             request_start,
@@ -330,6 +497,34 @@ pub mod ws {
         let name = &req.method;
         let id = req.id;
 
+        let refusal = kill_switch
+            .check(name)
+            .or_else(|| origin_methods.check(origin, name));
+        let admission = match refusal {
+            Some(error) => Err(error),
+            None => rate_limiter.acquire(name, client),
+        };
+        let _rate_permit = match admission {
+            Ok(permit) => permit,
+            Err(error) => {
+                logger.on_call(
+                    name,
+                    params,
+                    logger::MethodKind::MethodCall,
+                    TransportProtocol::WebSocket,
+                );
+                let response = MethodResponse::error(id, error);
+                logger.on_result(
+                    name,
+                    response.success,
+                    response.error_code,
+                    request_start,
+                    TransportProtocol::WebSocket,
+                );
+                return Some(response);
+            }
+        };
+
         let response = match methods.method_with_name(name) {
             None => {
                 logger.on_call(
@@ -351,7 +546,15 @@ pub mod ws {
                         logger::MethodKind::MethodCall,
                         TransportProtocol::Http,
                     );
-                    Some((callback)(id, params, max_response_body_size as usize))
+                    let id = id.into_owned();
+                    let _permit = query_scheduler.acquire(name).await;
+                    Some(
+                        panic_guard
+                            .call(name, || {
+                                (callback)(id.clone(), params, max_response_body_size as usize)
+                            })
+                            .unwrap_or_else(|error| MethodResponse::error(id, error)),
+                    )
                 }
                 MethodKind::Async(callback) => {
                     logger.on_call(
@@ -363,10 +566,22 @@ pub mod ws {
 
                     let id = id.into_owned();
                     let params = params.into_owned();
+                    let _permit = query_scheduler.acquire(name).await;
 
                     Some(
-                        (callback)(id, params, conn_id, max_response_body_size as usize, None)
-                            .await,
+                        panic_guard
+                            .call_async(
+                                name,
+                                (callback)(
+                                    id.clone(),
+                                    params,
+                                    conn_id,
+                                    max_response_body_size as usize,
+                                    None,
+                                ),
+                            )
+                            .await
+                            .unwrap_or_else(|error| MethodResponse::error(id, error)),
                     )
                 }
 
@@ -383,9 +598,17 @@ pub mod ws {
                             close_notify: cn,
                             id_provider,
                         };
-                        callback(id.clone(), params, sink.clone(), conn_state, None).await;
-                        None
+                        // The callback answers through the sink, unless it panicked.
+                        panic_guard
+                            .subscribe(
+                                name,
+                                callback(id.clone(), params, sink.clone(), conn_state, None),
+                            )
+                            .await
+                            .err()
+                            .map(|error| MethodResponse::error(id, error))
                     } else {
+                        rate_limiter.on_subscription_rejected(name);
                         Some(MethodResponse::error(
                             id,
                             reject_too_many_subscriptions(bounded_subscriptions.max()),
@@ -410,6 +633,10 @@ pub mod ws {
                 }
             },
         };
+        let response = match response_signer {
+            Some(signer) => response.map(|response| signer.sign(name, response)),
+            None => response,
+        };
 
         if let Some(response) = &response {
             logger.on_result(
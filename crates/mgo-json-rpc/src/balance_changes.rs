@@ -124,10 +124,13 @@ async fn fetch_coins<P: ObjectProvider<Error = E>, E>(
     object_provider: &P,
     objects: &[(ObjectID, SequenceNumber, Option<ObjectDigest>)],
 ) -> Result<Vec<(Owner, TypeTag, u64)>, E> {
+    let keys = objects
+        .iter()
+        .map(|(id, version, _)| (*id, *version))
+        .collect::<Vec<_>>();
+    let fetched = object_provider.multi_get_objects(&keys).await?;
     let mut all_mutated_coins = vec![];
-    for (id, version, digest_opt) in objects {
-        // TODO: use multi get object
-        let o = object_provider.get_object(id, version).await?;
+    for ((_, _, digest_opt), o) in objects.iter().zip(fetched) {
         if let Some(type_) = o.type_() {
             if type_.is_coin() {
                 if let Some(digest) = digest_opt {
@@ -165,6 +168,34 @@ pub trait ObjectProvider {
         id: &ObjectID,
         version: &SequenceNumber,
     ) -> Result<Option<Object>, Self::Error>;
+
+    /// Gets each of `objects` as [`Self::get_object`] does, returning the results in the order
+    /// of `objects`. Like [`Self::multi_find_object_lt_or_eq_version`], providers backed by a
+    /// store should fetch the whole batch at once.
+    async fn multi_get_objects(
+        &self,
+        objects: &[(ObjectID, SequenceNumber)],
+    ) -> Result<Vec<Object>, Self::Error> {
+        let mut results = Vec::with_capacity(objects.len());
+        for (id, version) in objects {
+            results.push(self.get_object(id, version).await?);
+        }
+        Ok(results)
+    }
+
+    /// Looks up each of `objects` as [`Self::find_object_lt_or_eq_version`] does, returning the
+    /// results in the order of `objects`. Providers backed by a store should resolve the whole
+    /// batch at once rather than one object at a time, as this default does.
+    async fn multi_find_object_lt_or_eq_version(
+        &self,
+        objects: &[(ObjectID, SequenceNumber)],
+    ) -> Result<Vec<Option<Object>>, Self::Error> {
+        let mut results = Vec::with_capacity(objects.len());
+        for (id, version) in objects {
+            results.push(self.find_object_lt_or_eq_version(id, version).await?);
+        }
+        Ok(results)
+    }
 }
 
 pub struct ObjectProviderCache<P> {
@@ -237,6 +268,46 @@ where
         Ok(o)
     }
 
+    async fn multi_get_objects(
+        &self,
+        objects: &[(ObjectID, SequenceNumber)],
+    ) -> Result<Vec<Object>, Self::Error> {
+        let cached = {
+            let object_cache = self.object_cache.read().await;
+            objects
+                .iter()
+                .map(|key| object_cache.get(key).cloned())
+                .collect::<Vec<_>>()
+        };
+        let misses = objects
+            .iter()
+            .zip(&cached)
+            .filter(|(_, o)| o.is_none())
+            .map(|(key, _)| *key)
+            .collect::<Vec<_>>();
+        let mut fetched = if misses.is_empty() {
+            vec![]
+        } else {
+            self.provider.multi_get_objects(&misses).await?
+        }
+        .into_iter();
+
+        let mut object_cache = self.object_cache.write().await;
+        Ok(objects
+            .iter()
+            .zip(cached)
+            .map(|(key, o)| {
+                o.unwrap_or_else(|| {
+                    let o = fetched
+                        .next()
+                        .expect("provider should return an object for each of the misses");
+                    object_cache.insert(*key, o.clone());
+                    o
+                })
+            })
+            .collect())
+    }
+
     async fn find_object_lt_or_eq_version(
         &self,
         id: &ObjectID,
@@ -263,4 +334,263 @@ where
             Ok(None)
         }
     }
+
+    async fn multi_find_object_lt_or_eq_version(
+        &self,
+        objects: &[(ObjectID, SequenceNumber)],
+    ) -> Result<Vec<Option<Object>>, Self::Error> {
+        let cached_versions = {
+            let last_version_cache = self.last_version_cache.read().await;
+            objects
+                .iter()
+                .map(|key| last_version_cache.get(key).copied())
+                .collect::<Vec<_>>()
+        };
+        let mut results = Vec::with_capacity(objects.len());
+        let mut misses = vec![];
+        for (index, ((id, version), cached_version)) in
+            objects.iter().zip(cached_versions).enumerate()
+        {
+            match cached_version {
+                Some(cached_version) => {
+                    results.push(self.get_object(id, &cached_version).await.ok())
+                }
+                None => {
+                    misses.push((index, (*id, *version)));
+                    results.push(None);
+                }
+            }
+        }
+        if misses.is_empty() {
+            return Ok(results);
+        }
+
+        let keys = misses.iter().map(|(_, key)| *key).collect::<Vec<_>>();
+        let found = self
+            .provider
+            .multi_find_object_lt_or_eq_version(&keys)
+            .await?;
+        let mut object_cache = self.object_cache.write().await;
+        let mut last_version_cache = self.last_version_cache.write().await;
+        for ((index, (id, version)), o) in misses.into_iter().zip(found) {
+            if let Some(o) = &o {
+                object_cache.insert((id, o.version()), o.clone());
+                last_version_cache.insert((id, version), o.version());
+            }
+            results[index] = o;
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use mgo_types::base_types::MgoAddress;
+    use mgo_types::effects::ObjectRemoveKind;
+
+    use super::*;
+    use crate::get_object_changes;
+
+    /// Holds a single version of each of its objects and counts how it is queried.
+    #[derive(Default)]
+    struct CountingProvider {
+        objects: BTreeMap<ObjectID, Object>,
+        single_lookups: AtomicUsize,
+        batch_lookups: AtomicUsize,
+        batch_gets: AtomicUsize,
+    }
+
+    impl CountingProvider {
+        fn find(&self, id: &ObjectID, version: &SequenceNumber) -> Option<Object> {
+            self.objects
+                .get(id)
+                .filter(|o| o.version() <= *version)
+                .cloned()
+        }
+    }
+
+    #[async_trait]
+    impl ObjectProvider for CountingProvider {
+        type Error = ();
+
+        async fn get_object(
+            &self,
+            id: &ObjectID,
+            version: &SequenceNumber,
+        ) -> Result<Object, Self::Error> {
+            self.find(id, version)
+                .filter(|o| o.version() == *version)
+                .ok_or(())
+        }
+
+        async fn find_object_lt_or_eq_version(
+            &self,
+            id: &ObjectID,
+            version: &SequenceNumber,
+        ) -> Result<Option<Object>, Self::Error> {
+            self.single_lookups.fetch_add(1, Ordering::Relaxed);
+            Ok(self.find(id, version))
+        }
+
+        async fn multi_find_object_lt_or_eq_version(
+            &self,
+            objects: &[(ObjectID, SequenceNumber)],
+        ) -> Result<Vec<Option<Object>>, Self::Error> {
+            self.batch_lookups.fetch_add(1, Ordering::Relaxed);
+            Ok(objects.iter().map(|(id, v)| self.find(id, v)).collect())
+        }
+
+        async fn multi_get_objects(
+            &self,
+            objects: &[(ObjectID, SequenceNumber)],
+        ) -> Result<Vec<Object>, Self::Error> {
+            self.batch_gets.fetch_add(1, Ordering::Relaxed);
+            objects
+                .iter()
+                .map(|(id, v)| self.find(id, v).filter(|o| o.version() == *v).ok_or(()))
+                .collect()
+        }
+    }
+
+    /// 500 lookups: 200 objects at version 5 requested twice each, half of them at a version
+    /// before they existed, and 100 objects the provider does not have.
+    fn set_up() -> (CountingProvider, Vec<(ObjectID, SequenceNumber)>) {
+        let version = SequenceNumber::from_u64(5);
+        let mut provider = CountingProvider::default();
+        let mut lookups = vec![];
+        for i in 0..200 {
+            let id = ObjectID::random();
+            let o = Object::with_id_owner_version_for_testing(id, version, MgoAddress::ZERO);
+            provider.objects.insert(id, o);
+            let requested = if i % 2 == 0 {
+                version
+            } else {
+                version.one_before().unwrap()
+            };
+            lookups.push((id, requested));
+            lookups.push((id, requested));
+        }
+        for _ in 0..100 {
+            lookups.push((ObjectID::random(), version));
+        }
+        (provider, lookups)
+    }
+
+    #[tokio::test]
+    async fn test_multi_find_object_lt_or_eq_version_batches_lookups() {
+        let (provider, lookups) = set_up();
+        let cache = ObjectProviderCache::new(provider);
+
+        let results = cache
+            .multi_find_object_lt_or_eq_version(&lookups)
+            .await
+            .unwrap();
+        assert_eq!(cache.provider.batch_lookups.load(Ordering::Relaxed), 1);
+        assert_eq!(cache.provider.single_lookups.load(Ordering::Relaxed), 0);
+        assert_eq!(results.len(), lookups.len());
+        for ((id, version), result) in lookups.iter().zip(&results) {
+            assert_eq!(result, &cache.provider.find(id, version));
+        }
+
+        // Found objects are served from the cache, only the missing ones are looked up again.
+        let again = cache
+            .multi_find_object_lt_or_eq_version(&lookups)
+            .await
+            .unwrap();
+        assert_eq!(again, results);
+        assert_eq!(cache.provider.batch_lookups.load(Ordering::Relaxed), 2);
+        assert_eq!(cache.provider.single_lookups.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn test_multi_get_objects_fetches_misses_in_one_batch() {
+        let (provider, lookups) = set_up();
+        let existing = lookups
+            .into_iter()
+            .filter(|(id, version)| {
+                provider
+                    .find(id, version)
+                    .is_some_and(|o| o.version() == *version)
+            })
+            .collect::<Vec<_>>();
+        let cache = ObjectProviderCache::new(provider);
+
+        let objects = cache.multi_get_objects(&existing).await.unwrap();
+        assert_eq!(cache.provider.batch_gets.load(Ordering::Relaxed), 1);
+        for ((id, version), o) in existing.iter().zip(&objects) {
+            assert_eq!((o.id(), o.version()), (*id, *version));
+        }
+
+        // Every object is cached now, so the provider is not asked again.
+        let again = cache.multi_get_objects(&existing).await.unwrap();
+        assert_eq!(again, objects);
+        assert_eq!(cache.provider.batch_gets.load(Ordering::Relaxed), 1);
+
+        // A missing object fails the whole batch.
+        let mut missing = existing.clone();
+        missing.push((ObjectID::random(), SequenceNumber::from_u64(5)));
+        assert!(cache.multi_get_objects(&missing).await.is_err());
+    }
+
+    /// Provider relying on the default `multi_find_object_lt_or_eq_version`.
+    struct SingleLookupProvider<'a>(&'a CountingProvider);
+
+    #[async_trait]
+    impl ObjectProvider for SingleLookupProvider<'_> {
+        type Error = ();
+
+        async fn get_object(
+            &self,
+            id: &ObjectID,
+            version: &SequenceNumber,
+        ) -> Result<Object, Self::Error> {
+            self.0.get_object(id, version).await
+        }
+
+        async fn find_object_lt_or_eq_version(
+            &self,
+            id: &ObjectID,
+            version: &SequenceNumber,
+        ) -> Result<Option<Object>, Self::Error> {
+            self.0.find_object_lt_or_eq_version(id, version).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_default_multi_find_object_lt_or_eq_version() {
+        let (provider, lookups) = set_up();
+        let results = SingleLookupProvider(&provider)
+            .multi_find_object_lt_or_eq_version(&lookups)
+            .await
+            .unwrap();
+        assert_eq!(
+            provider.single_lookups.load(Ordering::Relaxed),
+            lookups.len()
+        );
+        for ((id, version), result) in lookups.iter().zip(&results) {
+            assert_eq!(result, &provider.find(id, version));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_object_changes_look_up_removed_objects_in_one_batch() {
+        let (provider, lookups) = set_up();
+        let removed = lookups
+            .iter()
+            .map(|(id, version)| ((*id, *version, ObjectDigest::MIN), ObjectRemoveKind::Delete))
+            .collect::<Vec<_>>();
+        let expected_deleted = lookups
+            .iter()
+            .filter(|(id, version)| provider.find(id, version).is_some())
+            .count();
+
+        let changes = get_object_changes(&provider, MgoAddress::ZERO, vec![], vec![], removed)
+            .await
+            .unwrap();
+        assert_eq!(provider.batch_lookups.load(Ordering::Relaxed), 1);
+        assert_eq!(provider.single_lookups.load(Ordering::Relaxed), 0);
+        assert_eq!(changes.len(), expected_deleted);
+    }
 }
@@ -18,14 +18,15 @@ use mango_metrics::spawn_monitored_task;
 use mgo_core::authority::AuthorityState;
 use mgo_json_rpc_api::{cap_page_limit, CoinReadApiOpenRpc, CoinReadApiServer, JsonRpcMetrics};
 use mgo_json_rpc_types::Balance;
-use mgo_json_rpc_types::{CoinPage, MgoCoinMetadata};
+use mgo_json_rpc_types::{BalancePage, Coin, CoinPage, MgoCoinMetadata};
 use mgo_open_rpc::Module;
 use mgo_storage::key_value_store::TransactionKeyValueStore;
 use mgo_types::balance::Supply;
 use mgo_types::base_types::{ObjectID, MgoAddress};
-use mgo_types::coin::{CoinMetadata, TreasuryCap};
+use mgo_types::coin::CoinMetadata;
 use mgo_types::effects::TransactionEffectsAPI;
-use mgo_types::gas_coin::{GAS, TOTAL_SUPPLY_MIST};
+use mgo_types::gas_coin::GAS;
+use mgo_types::mgo_serde::BigInt;
 use mgo_types::object::Object;
 use mgo_types::parse_mgo_struct_tag;
 
@@ -33,7 +34,8 @@ use mgo_types::parse_mgo_struct_tag;
 use mockall::automock;
 
 use crate::authority_state::StateRead;
-use crate::error::{Error, RpcInterimResult, MgoRpcInputError};
+use crate::coin_registry::CoinRegistry;
+use crate::error::{RpcInterimResult, MgoRpcInputError};
 use crate::{with_tracing, MgoRpcModule};
 
 pub fn parse_to_struct_tag(coin_type: &str) -> Result<StructTag, MgoRpcInputError> {
@@ -51,6 +53,7 @@ pub fn parse_to_type_tag(coin_type: Option<String>) -> Result<TypeTag, MgoRpcInp
 pub struct CoinReadApi {
     // Trait object w/ Box as we do not need to share this across multiple threads
     internal: Box<dyn CoinReadInternal + Send + Sync>,
+    coin_registry: Option<CoinRegistry>,
 }
 
 impl CoinReadApi {
@@ -65,6 +68,47 @@ impl CoinReadApi {
                 transaction_kv_store,
                 metrics,
             )),
+            coin_registry: None,
+        }
+    }
+
+    /// Marks the coins, balances and coin metadata returned with their standing in
+    /// `coin_registry`.
+    pub fn with_coin_registry(mut self, coin_registry: CoinRegistry) -> Self {
+        self.coin_registry = Some(coin_registry);
+        self
+    }
+
+    /// Reads the registry fields `object_ids` for the coin registry.
+    async fn multi_get_objects(
+        &self,
+        object_ids: Vec<ObjectID>,
+    ) -> RpcInterimResult<Vec<Option<Object>>> {
+        let mut objects = Vec::with_capacity(object_ids.len());
+        for object_id in object_ids {
+            objects.push(self.internal.get_object(&object_id).await?);
+        }
+        Ok(objects)
+    }
+
+    async fn annotate_coin_page(&self, page: &mut CoinPage) {
+        if let Some(coin_registry) = &self.coin_registry {
+            coin_registry
+                .annotate_coins(&mut page.data, |ids| self.multi_get_objects(ids))
+                .await;
+            if let Some(totals) = &mut page.totals {
+                coin_registry
+                    .annotate_balances(totals, |ids| self.multi_get_objects(ids))
+                    .await;
+            }
+        }
+    }
+
+    async fn annotate_balances(&self, balances: &mut [Balance]) {
+        if let Some(coin_registry) = &self.coin_registry {
+            coin_registry
+                .annotate_balances(balances, |ids| self.multi_get_objects(ids))
+                .await;
         }
     }
 }
@@ -89,6 +133,8 @@ impl CoinReadApiServer for CoinReadApi {
         // exclusive cursor if `Some`, otherwise start from the beginning
         cursor: Option<ObjectID>,
         limit: Option<usize>,
+        include_totals: Option<bool>,
+        min_balance: Option<BigInt<u64>>,
     ) -> RpcResult<CoinPage> {
         with_tracing!(async move {
             let coin_type_tag = parse_to_type_tag(coin_type)?;
@@ -99,11 +145,61 @@ impl CoinReadApiServer for CoinReadApi {
                 None => (coin_type_tag.to_string(), ObjectID::ZERO),
             };
 
-            self.internal
+            let mut coins = self
+                .internal
                 .get_coins_iterator(
-                    owner, cursor, limit, true, // only care about one type of coin
+                    owner,
+                    cursor,
+                    limit,
+                    true, // only care about one type of coin
+                    min_balance.map(BigInt::into_inner),
                 )
-                .await
+                .await?;
+
+            if include_totals.unwrap_or_default() {
+                match self
+                    .internal
+                    .get_balance(owner, coin_type_tag.clone())
+                    .await
+                {
+                    Ok(balance) => coins.totals = Some(vec![to_balance(&coin_type_tag, &balance)]),
+                    Err(e) => {
+                        debug!(?owner, "Failed to get coin totals with error: {:?}", e);
+                        coins.totals_unavailable = true;
+                    }
+                }
+            }
+            self.annotate_coin_page(&mut coins).await;
+            Ok(coins)
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn select_coins(
+        &self,
+        owner: MgoAddress,
+        coin_type: Option<String>,
+        target_amount: BigInt<u64>,
+        max_coins: Option<usize>,
+    ) -> RpcResult<Vec<Coin>> {
+        with_tracing!(async move {
+            let coin_type_tag = parse_to_type_tag(coin_type)?;
+            let coins = self
+                .internal
+                .get_all_coins_of_type(owner, coin_type_tag.clone())
+                .await?;
+            let mut coins = select_coins_largest_first(
+                &coin_type_tag.to_string(),
+                coins,
+                target_amount.into_inner(),
+                cap_page_limit(max_coins),
+            )?;
+            if let Some(coin_registry) = &self.coin_registry {
+                coin_registry
+                    .annotate_coins(&mut coins, |ids| self.multi_get_objects(ids))
+                    .await;
+            }
+            Ok(coins)
         })
     }
 
@@ -114,6 +210,7 @@ impl CoinReadApiServer for CoinReadApi {
         // exclusive cursor if `Some`, otherwise start from the beginning
         cursor: Option<ObjectID>,
         limit: Option<usize>,
+        include_totals: Option<bool>,
     ) -> RpcResult<CoinPage> {
         with_tracing!(async move {
             let cursor = match cursor {
@@ -141,13 +238,34 @@ impl CoinReadApiServer for CoinReadApi {
                 }
             }?;
 
-            let coins = self
+            let mut coins = self
                 .internal
                 .get_coins_iterator(
-                    owner, cursor, limit, false, // return all types of coins
+                    owner,
+                    cursor,
+                    limit,
+                    false, // return all types of coins
+                    None,
                 )
                 .await?;
 
+            if include_totals.unwrap_or_default() {
+                match self.internal.get_all_balance(owner).await {
+                    Ok(all_balance) => {
+                        let mut totals: Vec<_> = all_balance
+                            .iter()
+                            .map(|(coin_type, balance)| to_balance(coin_type, balance))
+                            .collect();
+                        totals.sort_by(|a, b| a.coin_type.cmp(&b.coin_type));
+                        coins.totals = Some(totals);
+                    }
+                    Err(e) => {
+                        debug!(?owner, "Failed to get coin totals with error: {:?}", e);
+                        coins.totals_unavailable = true;
+                    }
+                }
+            }
+            self.annotate_coin_page(&mut coins).await;
             Ok(coins)
         })
     }
@@ -167,13 +285,10 @@ impl CoinReadApiServer for CoinReadApi {
                 .tap_err(|e| {
                     debug!(?owner, "Failed to get balance with error: {:?}", e);
                 })?;
-            Ok(Balance {
-                coin_type: coin_type_tag.to_string(),
-                coin_object_count: balance.num_coins as usize,
-                total_balance: balance.balance as u128,
-                // note: LockedCoin is deprecated
-                locked_balance: Default::default(),
-            })
+            let mut balance = to_balance(&coin_type_tag, &balance);
+            self.annotate_balances(std::slice::from_mut(&mut balance))
+                .await;
+            Ok(balance)
         })
     }
 
@@ -183,18 +298,37 @@ impl CoinReadApiServer for CoinReadApi {
             let all_balance = self.internal.get_all_balance(owner).await.tap_err(|e| {
                 debug!(?owner, "Failed to get all balance with error: {:?}", e);
             })?;
-            Ok(all_balance
+            let mut balances: Vec<_> = all_balance
                 .iter()
-                .map(|(coin_type, balance)| {
-                    Balance {
-                        coin_type: coin_type.to_string(),
-                        coin_object_count: balance.num_coins as usize,
-                        total_balance: balance.balance as u128,
-                        // note: LockedCoin is deprecated
-                        locked_balance: Default::default(),
-                    }
-                })
-                .collect())
+                .map(|(coin_type, balance)| to_balance(coin_type, balance))
+                .collect();
+            self.annotate_balances(&mut balances).await;
+            Ok(balances)
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_all_balances_paginated(
+        &self,
+        owner: MgoAddress,
+        cursor: Option<String>,
+        limit: Option<usize>,
+    ) -> RpcResult<BalancePage> {
+        with_tracing!(async move {
+            // Coin types are keyed by their canonical string, so a cursor spelled differently
+            // (e.g. `0x2::mgo::MGO`) still resumes at the right place.
+            let cursor = cursor
+                .map(|c| parse_to_struct_tag(&c).map(|tag| tag.to_string()))
+                .transpose()?;
+            let mut page = self
+                .internal
+                .get_all_balance_paginated(owner, cursor, limit)
+                .await
+                .tap_err(|e| {
+                    debug!(?owner, "Failed to get balances page with error: {:?}", e);
+                })?;
+            self.annotate_balances(&mut page.data).await;
+            Ok(page)
         })
     }
 
@@ -210,43 +344,85 @@ impl CoinReadApiServer for CoinReadApi {
                 )
                 .await
                 .ok();
-            Ok(metadata_object.and_then(|v: Object| v.try_into().ok()))
+            let mut metadata: Option<MgoCoinMetadata> =
+                metadata_object.and_then(|v: Object| v.try_into().ok());
+            if let (Some(metadata), Some(coin_registry)) = (&mut metadata, &self.coin_registry) {
+                coin_registry
+                    .annotate_metadata(metadata, &coin_type, |ids| self.multi_get_objects(ids))
+                    .await;
+            }
+            Ok(metadata)
         })
     }
 
     #[instrument(skip(self))]
     async fn get_total_supply(&self, coin_type: String) -> RpcResult<Supply> {
         with_tracing!(async move {
-            let coin_struct = parse_to_struct_tag(&coin_type)?;
-            Ok(if GAS::is_gas(&coin_struct) {
-                Supply {
-                    value: TOTAL_SUPPLY_MIST,
-                }
-            } else {
-                let treasury_cap_object = self
-                    .internal
-                    .find_package_object(
-                        &coin_struct.address.into(),
-                        TreasuryCap::type_(coin_struct),
-                    )
-                    .await?;
-                let treasury_cap = TreasuryCap::from_bcs_bytes(
-                    treasury_cap_object.data.try_as_move().unwrap().contents(),
-                )
-                .map_err(Error::from)?;
-                treasury_cap.total_supply
-            })
+            let coin_type = TypeTag::Struct(Box::new(parse_to_struct_tag(&coin_type)?));
+            self.internal.get_total_supply(coin_type).await
         })
     }
 }
 
+/// Picks the largest of `coins` until their balances add up to `target_amount`, taking coins of
+/// equal balance in the order of their IDs, so that the same coins are picked for the same
+/// `coins` whatever order they come in.
+pub fn select_coins_largest_first(
+    coin_type: &str,
+    mut coins: Vec<Coin>,
+    target_amount: u64,
+    max_coins: usize,
+) -> Result<Vec<Coin>, MgoRpcInputError> {
+    coins.sort_by(|a, b| {
+        b.balance
+            .cmp(&a.balance)
+            .then_with(|| a.coin_object_id.cmp(&b.coin_object_id))
+    });
+    let mut coins = coins.into_iter();
+    let mut selected = vec![];
+    let mut total = 0u128;
+    while total < target_amount as u128 && selected.len() < max_coins {
+        let Some(coin) = coins.next() else {
+            break;
+        };
+        total += coin.balance as u128;
+        selected.push(coin);
+    }
+    if total < target_amount as u128 {
+        // Short of a `u64` target, so the total fits in a `u64` too.
+        let available = total as u64;
+        return Err(MgoRpcInputError::InsufficientCoinBalance {
+            coin_type: coin_type.to_string(),
+            target_amount,
+            available,
+            shortfall: target_amount - available,
+            coin_count: selected.len(),
+            max_coins_reached: coins.next().is_some(),
+        });
+    }
+    Ok(selected)
+}
+
+pub(crate) fn to_balance(coin_type: &TypeTag, balance: &TotalBalance) -> Balance {
+    Balance {
+        coin_type: coin_type.to_string(),
+        coin_object_count: balance.num_coins as usize,
+        total_balance: balance.balance as u128,
+        // note: LockedCoin is deprecated
+        locked_balance: Default::default(),
+        verified: None,
+        canonical_symbol: None,
+        canonical_icon_url: None,
+    }
+}
+
 #[cached(
     type = "SizedCache<String, ObjectID>",
     create = "{ SizedCache::with_size(10000) }",
     convert = r#"{ format!("{}{}", package_id, object_struct_tag) }"#,
     result = true
 )]
-async fn find_package_object_id(
+pub(crate) async fn find_package_object_id(
     state: Arc<dyn StateRead>,
     package_id: ObjectID,
     object_struct_tag: StructTag,
@@ -293,18 +469,31 @@ pub trait CoinReadInternal {
         &self,
         owner: MgoAddress,
     ) -> RpcInterimResult<Arc<HashMap<TypeTag, TotalBalance>>>;
+    async fn get_all_balance_paginated(
+        &self,
+        owner: MgoAddress,
+        cursor: Option<String>,
+        limit: Option<usize>,
+    ) -> RpcInterimResult<BalancePage>;
     async fn find_package_object(
         &self,
         package_id: &ObjectID,
         object_struct_tag: StructTag,
     ) -> RpcInterimResult<Object>;
+    async fn get_total_supply(&self, coin_type: TypeTag) -> RpcInterimResult<Supply>;
     async fn get_coins_iterator(
         &self,
         owner: MgoAddress,
         cursor: (String, ObjectID),
         limit: Option<usize>,
         one_coin_type_only: bool,
+        min_balance: Option<u64>,
     ) -> RpcInterimResult<CoinPage>;
+    async fn get_all_coins_of_type(
+        &self,
+        owner: MgoAddress,
+        coin_type: TypeTag,
+    ) -> RpcInterimResult<Vec<Coin>>;
 }
 
 pub struct CoinReadInternalImpl {
@@ -353,6 +542,43 @@ impl CoinReadInternal for CoinReadInternalImpl {
         Ok(self.state.get_all_balance(owner).await?)
     }
 
+    async fn get_all_balance_paginated(
+        &self,
+        owner: MgoAddress,
+        cursor: Option<String>,
+        limit: Option<usize>,
+    ) -> RpcInterimResult<BalancePage> {
+        let limit = cap_page_limit(limit);
+        let mut balances = self
+            .state
+            .get_all_balance_paginated(owner, cursor, limit + 1)
+            .await?;
+
+        let has_next_page = balances.len() > limit;
+        balances.truncate(limit);
+
+        let data: Vec<_> = balances
+            .into_iter()
+            .map(|(coin_type, balance)| Balance {
+                coin_type: coin_type.to_string(),
+                coin_object_count: balance.num_coins as usize,
+                total_balance: balance.balance as u128,
+                // note: LockedCoin is deprecated
+                locked_balance: Default::default(),
+                verified: None,
+                canonical_symbol: None,
+                canonical_icon_url: None,
+            })
+            .collect();
+        let next_cursor = data.last().map(|balance| balance.coin_type.clone());
+        Ok(BalancePage {
+            data,
+            next_cursor,
+            has_next_page,
+            applied: None,
+        })
+    }
+
     async fn find_package_object(
         &self,
         package_id: &ObjectID,
@@ -365,18 +591,26 @@ impl CoinReadInternal for CoinReadInternalImpl {
         Ok(self.state.get_object_read(&object_id)?.into_object()?)
     }
 
+    async fn get_total_supply(&self, coin_type: TypeTag) -> RpcInterimResult<Supply> {
+        Ok(self
+            .state
+            .get_total_supply(coin_type, self.transaction_kv_store.clone())
+            .await?)
+    }
+
     async fn get_coins_iterator(
         &self,
         owner: MgoAddress,
         cursor: (String, ObjectID),
         limit: Option<usize>,
         one_coin_type_only: bool,
+        min_balance: Option<u64>,
     ) -> RpcInterimResult<CoinPage> {
         let limit = cap_page_limit(limit);
         self.metrics.get_coins_limit.report(limit as u64);
         let state = self.get_state();
         let mut data = spawn_monitored_task!(async move {
-            state.get_owned_coins(owner, cursor, limit + 1, one_coin_type_only)
+            state.get_owned_coins(owner, cursor, limit + 1, one_coin_type_only, min_balance)
         })
         .await??;
 
@@ -392,7 +626,24 @@ impl CoinReadInternal for CoinReadInternalImpl {
             data,
             next_cursor,
             has_next_page,
+            totals: None,
+            totals_unavailable: false,
+        })
+    }
+
+    async fn get_all_coins_of_type(
+        &self,
+        owner: MgoAddress,
+        coin_type: TypeTag,
+    ) -> RpcInterimResult<Vec<Coin>> {
+        let state = self.get_state();
+        // The coin index only holds coin objects owned by an address, so coins wrapped in other
+        // objects, such as staked coins, are never among them.
+        let cursor = (coin_type.to_string(), ObjectID::ZERO);
+        Ok(spawn_monitored_task!(async move {
+            state.get_owned_coins(owner, cursor, usize::MAX, true, None)
         })
+        .await??)
     }
 }
 
@@ -484,6 +735,7 @@ mod tests {
             let kv_store = kv_store.unwrap_or_else(|| Arc::new(MockKeyValueStore::new()));
             Self {
                 internal: Box::new(CoinReadInternalImpl::new_for_tests(state, Some(kv_store))),
+                coin_registry: None,
             }
         }
     }
@@ -533,6 +785,9 @@ mod tests {
             digest: ObjectDigest::from(arr),
             balance,
             previous_transaction: TransactionDigest::from(arr),
+            verified: None,
+            canonical_symbol: None,
+            canonical_icon_url: None,
         }
     }
 
@@ -576,11 +831,14 @@ mod tests {
                     predicate::eq((GAS::type_().to_string(), ObjectID::ZERO)),
                     predicate::eq(51),
                     predicate::eq(true),
+                    predicate::eq(None),
                 )
-                .return_once(move |_, _, _, _| Ok(vec![gas_coin_clone]));
+                .return_once(move |_, _, _, _, _| Ok(vec![gas_coin_clone]));
 
             let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
-            let response = coin_read_api.get_coins(owner, None, None, None).await;
+            let response = coin_read_api
+                .get_coins(owner, None, None, None, None, None)
+                .await;
             assert!(response.is_ok());
             let result = response.unwrap();
             assert_eq!(
@@ -589,6 +847,8 @@ mod tests {
                     data: vec![gas_coin.clone()],
                     next_cursor: Some(gas_coin.coin_object_id),
                     has_next_page: false,
+                    totals: None,
+                    totals_unavailable: false,
                 }
             );
         }
@@ -611,12 +871,20 @@ mod tests {
                     predicate::eq((GAS::type_().to_string(), coins[0].coin_object_id)),
                     predicate::eq(limit + 1),
                     predicate::eq(true),
+                    predicate::eq(None),
                 )
-                .return_once(move |_, _, _, _| Ok(coins_clone));
+                .return_once(move |_, _, _, _, _| Ok(coins_clone));
 
             let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
             let response = coin_read_api
-                .get_coins(owner, None, Some(coins[0].coin_object_id), Some(limit))
+                .get_coins(
+                    owner,
+                    None,
+                    Some(coins[0].coin_object_id),
+                    Some(limit),
+                    None,
+                    None,
+                )
                 .await;
             assert!(response.is_ok());
             let result = response.unwrap();
@@ -626,6 +894,8 @@ mod tests {
                     data: coins[..limit].to_vec(),
                     next_cursor: Some(coins[limit - 1].coin_object_id),
                     has_next_page: true,
+                    totals: None,
+                    totals_unavailable: false,
                 }
             );
         }
@@ -648,12 +918,13 @@ mod tests {
                     predicate::eq((coin_type_tag.to_string(), ObjectID::ZERO)),
                     predicate::eq(51),
                     predicate::eq(true),
+                    predicate::eq(None),
                 )
-                .return_once(move |_, _, _, _| Ok(vec![coin_clone]));
+                .return_once(move |_, _, _, _, _| Ok(vec![coin_clone]));
 
             let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
             let response = coin_read_api
-                .get_coins(owner, Some(coin_type), None, None)
+                .get_coins(owner, Some(coin_type), None, None, None, None)
                 .await;
 
             assert!(response.is_ok());
@@ -664,6 +935,8 @@ mod tests {
                     data: vec![coin.clone()],
                     next_cursor: Some(coin.coin_object_id),
                     has_next_page: false,
+                    totals: None,
+                    totals_unavailable: false,
                 }
             );
         }
@@ -692,12 +965,20 @@ mod tests {
                     predicate::eq((coin_type_tag.to_string(), coins[0].coin_object_id)),
                     predicate::eq(limit + 1),
                     predicate::eq(true),
+                    predicate::eq(None),
                 )
-                .return_once(move |_, _, _, _| Ok(coins_clone));
+                .return_once(move |_, _, _, _, _| Ok(coins_clone));
 
             let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
             let response = coin_read_api
-                .get_coins(owner, Some(coin_type), Some(cursor), Some(limit))
+                .get_coins(
+                    owner,
+                    Some(coin_type),
+                    Some(cursor),
+                    Some(limit),
+                    None,
+                    None,
+                )
                 .await;
 
             assert!(response.is_ok());
@@ -708,6 +989,8 @@ mod tests {
                     data: coins[..limit].to_vec(),
                     next_cursor: Some(coins[limit - 1].coin_object_id),
                     has_next_page: true,
+                    totals: None,
+                    totals_unavailable: false,
                 }
             );
         }
@@ -720,7 +1003,7 @@ mod tests {
             let mock_state = MockStateRead::new();
             let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
             let response = coin_read_api
-                .get_coins(owner, Some(coin_type.to_string()), None, None)
+                .get_coins(owner, Some(coin_type.to_string()), None, None, None, None)
                 .await;
 
             assert!(response.is_err());
@@ -739,7 +1022,7 @@ mod tests {
             let mock_state = MockStateRead::new();
             let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
             let response = coin_read_api
-                .get_coins(owner, Some(coin_type.to_string()), None, None)
+                .get_coins(owner, Some(coin_type.to_string()), None, None, None, None)
                 .await;
 
             assert!(response.is_err());
@@ -760,14 +1043,14 @@ mod tests {
             let mut mock_state = MockStateRead::new();
             mock_state
                 .expect_get_owned_coins()
-                .returning(move |_, _, _, _| {
+                .returning(move |_, _, _, _, _| {
                     Err(StateReadError::Client(
                         MgoError::IndexStoreNotAvailable.into(),
                     ))
                 });
             let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
             let response = coin_read_api
-                .get_coins(owner, Some(coin_type.to_string()), None, None)
+                .get_coins(owner, Some(coin_type.to_string()), None, None, None, None)
                 .await;
 
             assert!(response.is_err());
@@ -775,7 +1058,7 @@ mod tests {
             let error_object: ErrorObjectOwned = error_result.into();
             assert_eq!(
                 error_object.code(),
-                jsonrpsee::types::error::INVALID_PARAMS_CODE
+                mgo_json_rpc_api::ClientErrorCode::Unsupported.code()
             );
             let expected = expect!["Index store not available on this Fullnode."];
             expected.assert_eq(error_object.message());
@@ -788,12 +1071,12 @@ mod tests {
             let mut mock_state = MockStateRead::new();
             mock_state
                 .expect_get_owned_coins()
-                .returning(move |_, _, _, _| {
+                .returning(move |_, _, _, _, _| {
                     Err(MgoError::Storage("mock rocksdb error".to_string()).into())
                 });
             let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
             let response = coin_read_api
-                .get_coins(owner, Some(coin_type.to_string()), None, None)
+                .get_coins(owner, Some(coin_type.to_string()), None, None, None, None)
                 .await;
 
             assert!(response.is_err());
@@ -803,20 +1086,78 @@ mod tests {
                 error_object.code(),
                 jsonrpsee::types::error::INTERNAL_ERROR_CODE
             );
-            let expected = expect!["Storage error: mock rocksdb error"];
+            let expected = expect!["Internal error occurred while reading state."];
             expected.assert_eq(error_object.message());
         }
-    }
 
-    mod get_all_coins_tests {
-        use mgo_types::object::{MoveObject, Owner};
+        #[tokio::test]
+        async fn test_include_totals() {
+            let owner = get_test_owner();
+            let gas_coin = get_test_coin(None, CoinType::Gas);
+            let gas_coin_clone = gas_coin.clone();
+            let mut mock_state = MockStateRead::new();
+            mock_state
+                .expect_get_owned_coins()
+                .return_once(move |_, _, _, _, _| Ok(vec![gas_coin_clone]));
+            mock_state
+                .expect_get_balance()
+                .with(predicate::eq(owner), predicate::eq(GAS::type_tag()))
+                .return_once(|_, _| {
+                    Ok(TotalBalance {
+                        balance: 42,
+                        num_coins: 1,
+                    })
+                });
 
-        use super::super::*;
-        use super::*;
+            let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
+            let result = coin_read_api
+                .get_coins(owner, None, None, None, Some(true), None)
+                .await
+                .unwrap();
+            assert_eq!(result.data, vec![gas_coin.clone()]);
+            assert_eq!(
+                result.totals,
+                Some(vec![Balance {
+                    coin_type: gas_coin.coin_type,
+                    coin_object_count: 1,
+                    total_balance: 42,
+                    locked_balance: Default::default(),
+                    verified: None,
+                    canonical_symbol: None,
+                    canonical_icon_url: None,
+                }])
+            );
+            assert!(!result.totals_unavailable);
+        }
 
-        // Success scenarios
         #[tokio::test]
-        async fn test_no_cursor() {
+        async fn test_include_totals_index_store_not_available() {
+            let owner = get_test_owner();
+            let gas_coin = get_test_coin(None, CoinType::Gas);
+            let gas_coin_clone = gas_coin.clone();
+            let mut mock_state = MockStateRead::new();
+            mock_state
+                .expect_get_owned_coins()
+                .return_once(move |_, _, _, _, _| Ok(vec![gas_coin_clone]));
+            mock_state.expect_get_balance().returning(move |_, _| {
+                Err(StateReadError::Client(
+                    MgoError::IndexStoreNotAvailable.into(),
+                ))
+            });
+
+            // The page is still returned, only without totals.
+            let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
+            let result = coin_read_api
+                .get_coins(owner, None, None, None, Some(true), None)
+                .await
+                .unwrap();
+            assert_eq!(result.data, vec![gas_coin]);
+            assert_eq!(result.totals, None);
+            assert!(result.totals_unavailable);
+        }
+
+        #[tokio::test]
+        async fn test_min_balance() {
             let owner = get_test_owner();
             let gas_coin = get_test_coin(None, CoinType::Gas);
             let gas_coin_clone = gas_coin.clone();
@@ -825,105 +1166,367 @@ mod tests {
                 .expect_get_owned_coins()
                 .with(
                     predicate::eq(owner),
-                    predicate::eq((String::from_utf8([0u8].to_vec()).unwrap(), ObjectID::ZERO)),
+                    predicate::eq((GAS::type_().to_string(), ObjectID::ZERO)),
                     predicate::eq(51),
-                    predicate::eq(false),
+                    predicate::eq(true),
+                    predicate::eq(Some(40)),
                 )
-                .return_once(move |_, _, _, _| Ok(vec![gas_coin_clone]));
+                .return_once(move |_, _, _, _, _| Ok(vec![gas_coin_clone]));
+
             let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
-            let response = coin_read_api
-                .get_all_coins(owner, None, Some(51))
+            let result = coin_read_api
+                .get_coins(owner, None, None, None, None, Some(BigInt::from(40)))
                 .await
                 .unwrap();
-            assert_eq!(response.data.len(), 1);
-            assert_eq!(response.data[0], gas_coin);
+            assert_eq!(result.data, vec![gas_coin]);
         }
 
         #[tokio::test]
-        async fn test_with_cursor() {
+        async fn test_min_balance_with_cursor_below_minimum() {
+            // The cursor coin was spent down below the minimum since the previous page, but the
+            // next page still resumes right after it rather than failing or starting over.
             let owner = get_test_owner();
-            let limit = 2;
+            let mut cursor_coin = get_test_coin(Some("0xA"), CoinType::Gas);
+            cursor_coin.balance = 1;
             let coins = vec![
-                get_test_coin(Some("0xA"), CoinType::Gas),
                 get_test_coin(Some("0xAA"), CoinType::Gas),
                 get_test_coin(Some("0xAAA"), CoinType::Gas),
             ];
             let coins_clone = coins.clone();
-            let coin_move_object = MoveObject::new_gas_coin(
-                coins[0].version,
-                coins[0].coin_object_id,
-                coins[0].balance,
-            );
-            let coin_object = Object::new_move(
-                coin_move_object,
-                Owner::Immutable,
-                coins[0].previous_transaction,
-            );
             let mut mock_state = MockStateRead::new();
-            mock_state
-                .expect_get_object()
-                .return_once(move |_| Ok(Some(coin_object)));
             mock_state
                 .expect_get_owned_coins()
                 .with(
                     predicate::eq(owner),
-                    predicate::eq((coins[0].coin_type.clone(), coins[0].coin_object_id)),
-                    predicate::eq(limit + 1),
-                    predicate::eq(false),
+                    predicate::eq((GAS::type_().to_string(), cursor_coin.coin_object_id)),
+                    predicate::eq(3),
+                    predicate::eq(true),
+                    predicate::eq(Some(40)),
                 )
-                .return_once(move |_, _, _, _| Ok(coins_clone));
+                .return_once(move |_, _, _, _, _| Ok(coins_clone));
+
             let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
-            let response = coin_read_api
-                .get_all_coins(owner, Some(coins[0].coin_object_id), Some(limit))
+            let result = coin_read_api
+                .get_coins(
+                    owner,
+                    None,
+                    Some(cursor_coin.coin_object_id),
+                    Some(2),
+                    None,
+                    Some(BigInt::from(40)),
+                )
                 .await
                 .unwrap();
-            assert_eq!(response.data.len(), limit);
-            assert_eq!(response.data, coins[..limit].to_vec());
+            assert_eq!(
+                result,
+                CoinPage {
+                    data: coins.clone(),
+                    next_cursor: Some(coins[1].coin_object_id),
+                    has_next_page: false,
+                    totals: None,
+                    totals_unavailable: false,
+                }
+            );
         }
+    }
 
-        // Expected error scenarios
-        #[tokio::test]
-        async fn test_object_is_not_coin() {
-            let owner = get_test_owner();
-            let object_id = get_test_package_id();
-            let (_, _, _, _, treasury_cap_object) = get_test_treasury_cap_peripherals(object_id);
-            let mut mock_state = MockStateRead::new();
-            mock_state.expect_get_object().returning(move |obj_id| {
-                if obj_id == &object_id {
-                    Ok(Some(treasury_cap_object.clone()))
-                } else {
-                    panic!("should not be called with any other object id")
-                }
-            });
-            let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
-            let response = coin_read_api
-                .get_all_coins(owner, Some(object_id), None)
-                .await;
+    mod select_coins_tests {
+        use super::super::*;
+        use super::*;
 
-            assert!(response.is_err());
-            let error_result = response.unwrap_err();
-            let error_object: ErrorObjectOwned = error_result.into();
-            assert_eq!(error_object.code(), -32602);
-            let expected = expect!["-32602"];
-            expected.assert_eq(&error_object.code().to_string());
-            let expected = expect!["cursor is not a coin"];
-            expected.assert_eq(error_object.message());
+        fn get_test_coins(balances: &[(&str, u64)]) -> Vec<Coin> {
+            balances
+                .iter()
+                .map(|(id, balance)| Coin {
+                    balance: *balance,
+                    ..get_test_coin(Some(*id), CoinType::Gas)
+                })
+                .collect()
+        }
+
+        fn mock_state_with_coins(owner: MgoAddress, coins: Vec<Coin>) -> MockStateRead {
+            let mut mock_state = MockStateRead::new();
+            mock_state
+                .expect_get_owned_coins()
+                .with(
+                    predicate::eq(owner),
+                    predicate::eq((GAS::type_().to_string(), ObjectID::ZERO)),
+                    predicate::eq(usize::MAX),
+                    predicate::eq(true),
+                    predicate::eq(None),
+                )
+                .return_once(move |_, _, _, _, _| Ok(coins));
+            mock_state
         }
 
         #[tokio::test]
-        async fn test_object_not_found() {
+        async fn test_largest_first() {
             let owner = get_test_owner();
-            let object_id = get_test_package_id();
-            let mut mock_state = MockStateRead::new();
-            mock_state.expect_get_object().returning(move |_| Ok(None));
+            let coins = get_test_coins(&[("0x1", 10), ("0x3", 40), ("0x2", 40), ("0x4", 25)]);
+            let coin_read_api =
+                CoinReadApi::new_for_tests(Arc::new(mock_state_with_coins(owner, coins)), None);
+            let selected = coin_read_api
+                .select_coins(owner, None, BigInt::from(70), None)
+                .await
+                .unwrap();
+            // Coins of equal balance are picked in the order of their IDs.
+            assert_eq!(selected, get_test_coins(&[("0x2", 40), ("0x3", 40)]));
+        }
 
-            let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
-            let response = coin_read_api
-                .get_all_coins(owner, Some(object_id), None)
-                .await;
+        #[test]
+        fn test_selection_is_deterministic() {
+            let coins = get_test_coins(&[("0x1", 10), ("0x2", 30), ("0x3", 30), ("0x4", 20)]);
+            let select = |coins: Vec<Coin>| {
+                select_coins_largest_first("0x2::mgo::MGO", coins, 50, 50).unwrap()
+            };
+            let expected = select(coins.clone());
+            assert_eq!(expected, get_test_coins(&[("0x2", 30), ("0x3", 30)]));
+            let mut reversed = coins;
+            reversed.reverse();
+            assert_eq!(select(reversed), expected);
+            // Selecting nothing is enough to reach nothing.
+            assert_eq!(
+                select_coins_largest_first("0x2::mgo::MGO", vec![], 0, 50).unwrap(),
+                vec![]
+            );
+        }
 
-            assert!(response.is_err());
-            let error_result = response.unwrap_err();
+        #[tokio::test]
+        async fn test_insufficient_balance() {
+            let owner = get_test_owner();
+            let coins = get_test_coins(&[("0x1", 10), ("0x2", 30)]);
+            let coin_read_api =
+                CoinReadApi::new_for_tests(Arc::new(mock_state_with_coins(owner, coins)), None);
+            let error_object: ErrorObjectOwned = coin_read_api
+                .select_coins(owner, None, BigInt::from(100), None)
+                .await
+                .unwrap_err()
+                .into();
+            assert_eq!(
+                error_object.code(),
+                mgo_json_rpc_api::ClientErrorCode::InvalidInput.code()
+            );
+            assert_eq!(
+                error_object.message(),
+                format!(
+                    "Cannot select coins of type {} worth 100: the 2 coins owned add up to 40, \
+                     60 short",
+                    GAS::type_()
+                )
+            );
+            let data: serde_json::Value =
+                serde_json::from_str(error_object.data().unwrap().get()).unwrap();
+            assert_eq!(data["code"], "INVALID_INPUT");
+            assert_eq!(data["available"], 40);
+            assert_eq!(data["shortfall"], 60);
+        }
+
+        #[tokio::test]
+        async fn test_max_coins_reached() {
+            let owner = get_test_owner();
+            let coins = get_test_coins(&[("0x1", 10), ("0x2", 30), ("0x3", 20)]);
+            let coin_read_api =
+                CoinReadApi::new_for_tests(Arc::new(mock_state_with_coins(owner, coins)), None);
+            let error_object: ErrorObjectOwned = coin_read_api
+                .select_coins(owner, None, BigInt::from(60), Some(2))
+                .await
+                .unwrap_err()
+                .into();
+            assert_eq!(
+                error_object.message(),
+                format!(
+                    "Cannot select coins of type {} worth 60: the 2 largest coins add up to 50, \
+                     10 short",
+                    GAS::type_()
+                )
+            );
+        }
+    }
+
+    mod get_all_coins_tests {
+        use mgo_types::object::{MoveObject, Owner};
+
+        use super::super::*;
+        use super::*;
+
+        // Success scenarios
+        #[tokio::test]
+        async fn test_no_cursor() {
+            let owner = get_test_owner();
+            let gas_coin = get_test_coin(None, CoinType::Gas);
+            let gas_coin_clone = gas_coin.clone();
+            let mut mock_state = MockStateRead::new();
+            mock_state
+                .expect_get_owned_coins()
+                .with(
+                    predicate::eq(owner),
+                    predicate::eq((String::from_utf8([0u8].to_vec()).unwrap(), ObjectID::ZERO)),
+                    predicate::eq(51),
+                    predicate::eq(false),
+                    predicate::eq(None),
+                )
+                .return_once(move |_, _, _, _, _| Ok(vec![gas_coin_clone]));
+            let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
+            let response = coin_read_api
+                .get_all_coins(owner, None, Some(51), None)
+                .await
+                .unwrap();
+            assert_eq!(response.data.len(), 1);
+            assert_eq!(response.data[0], gas_coin);
+        }
+
+        #[tokio::test]
+        async fn test_include_totals() {
+            let owner = get_test_owner();
+            let gas_coin = get_test_coin(None, CoinType::Gas);
+            let gas_coin_type_tag = get_test_coin_type_tag(gas_coin.coin_type.clone());
+            let usdc_coin = get_test_coin(None, CoinType::Usdc);
+            let usdc_coin_type_tag = get_test_coin_type_tag(usdc_coin.coin_type.clone());
+            let coins = vec![gas_coin.clone(), usdc_coin.clone()];
+            let mut mock_state = MockStateRead::new();
+            mock_state
+                .expect_get_owned_coins()
+                .return_once(move |_, _, _, _, _| Ok(coins));
+            mock_state
+                .expect_get_all_balance()
+                .with(predicate::eq(owner))
+                .return_once(move |_| {
+                    let mut hash_map = HashMap::new();
+                    hash_map.insert(
+                        usdc_coin_type_tag,
+                        TotalBalance {
+                            balance: 24,
+                            num_coins: 1,
+                        },
+                    );
+                    hash_map.insert(
+                        gas_coin_type_tag,
+                        TotalBalance {
+                            balance: 42,
+                            num_coins: 1,
+                        },
+                    );
+                    Ok(Arc::new(hash_map))
+                });
+            let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
+            let response = coin_read_api
+                .get_all_coins(owner, None, None, Some(true))
+                .await
+                .unwrap();
+
+            // Totals are sorted by coin type.
+            let mut expected = vec![
+                Balance {
+                    coin_type: gas_coin.coin_type,
+                    coin_object_count: 1,
+                    total_balance: 42,
+                    locked_balance: Default::default(),
+                    verified: None,
+                    canonical_symbol: None,
+                    canonical_icon_url: None,
+                },
+                Balance {
+                    coin_type: usdc_coin.coin_type,
+                    coin_object_count: 1,
+                    total_balance: 24,
+                    locked_balance: Default::default(),
+                    verified: None,
+                    canonical_symbol: None,
+                    canonical_icon_url: None,
+                },
+            ];
+            expected.sort_by(|a, b| a.coin_type.cmp(&b.coin_type));
+            assert_eq!(response.data.len(), 2);
+            assert_eq!(response.totals, Some(expected));
+            assert!(!response.totals_unavailable);
+        }
+
+        #[tokio::test]
+        async fn test_with_cursor() {
+            let owner = get_test_owner();
+            let limit = 2;
+            let coins = vec![
+                get_test_coin(Some("0xA"), CoinType::Gas),
+                get_test_coin(Some("0xAA"), CoinType::Gas),
+                get_test_coin(Some("0xAAA"), CoinType::Gas),
+            ];
+            let coins_clone = coins.clone();
+            let coin_move_object = MoveObject::new_gas_coin(
+                coins[0].version,
+                coins[0].coin_object_id,
+                coins[0].balance,
+            );
+            let coin_object = Object::new_move(
+                coin_move_object,
+                Owner::Immutable,
+                coins[0].previous_transaction,
+            );
+            let mut mock_state = MockStateRead::new();
+            mock_state
+                .expect_get_object()
+                .return_once(move |_| Ok(Some(coin_object)));
+            mock_state
+                .expect_get_owned_coins()
+                .with(
+                    predicate::eq(owner),
+                    predicate::eq((coins[0].coin_type.clone(), coins[0].coin_object_id)),
+                    predicate::eq(limit + 1),
+                    predicate::eq(false),
+                    predicate::eq(None),
+                )
+                .return_once(move |_, _, _, _, _| Ok(coins_clone));
+            let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
+            let response = coin_read_api
+                .get_all_coins(owner, Some(coins[0].coin_object_id), Some(limit), None)
+                .await
+                .unwrap();
+            assert_eq!(response.data.len(), limit);
+            assert_eq!(response.data, coins[..limit].to_vec());
+        }
+
+        // Expected error scenarios
+        #[tokio::test]
+        async fn test_object_is_not_coin() {
+            let owner = get_test_owner();
+            let object_id = get_test_package_id();
+            let (_, _, _, _, treasury_cap_object) = get_test_treasury_cap_peripherals(object_id);
+            let mut mock_state = MockStateRead::new();
+            mock_state.expect_get_object().returning(move |obj_id| {
+                if obj_id == &object_id {
+                    Ok(Some(treasury_cap_object.clone()))
+                } else {
+                    panic!("should not be called with any other object id")
+                }
+            });
+            let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
+            let response = coin_read_api
+                .get_all_coins(owner, Some(object_id), None, None)
+                .await;
+
+            assert!(response.is_err());
+            let error_result = response.unwrap_err();
+            let error_object: ErrorObjectOwned = error_result.into();
+            assert_eq!(error_object.code(), -32602);
+            let expected = expect!["-32602"];
+            expected.assert_eq(&error_object.code().to_string());
+            let expected = expect!["cursor is not a coin"];
+            expected.assert_eq(error_object.message());
+        }
+
+        #[tokio::test]
+        async fn test_object_not_found() {
+            let owner = get_test_owner();
+            let object_id = get_test_package_id();
+            let mut mock_state = MockStateRead::new();
+            mock_state.expect_get_object().returning(move |_| Ok(None));
+
+            let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
+            let response = coin_read_api
+                .get_all_coins(owner, Some(object_id), None, None)
+                .await;
+
+            assert!(response.is_err());
+            let error_result = response.unwrap_err();
             let error_object: ErrorObjectOwned = error_result.into();
             let expected = expect!["-32602"];
             expected.assert_eq(&error_object.code().to_string());
@@ -966,7 +1569,10 @@ mod tests {
                     coin_type: gas_coin.coin_type,
                     coin_object_count: 9,
                     total_balance: 7,
-                    locked_balance: Default::default()
+                    locked_balance: Default::default(),
+                    verified: None,
+                    canonical_symbol: None,
+                    canonical_icon_url: None,
                 }
             );
         }
@@ -1002,7 +1608,10 @@ mod tests {
                     coin_type: coin.coin_type,
                     coin_object_count: 11,
                     total_balance: 10,
-                    locked_balance: Default::default()
+                    locked_balance: Default::default(),
+                    verified: None,
+                    canonical_symbol: None,
+                    canonical_icon_url: None,
                 }
             );
         }
@@ -1048,7 +1657,7 @@ mod tests {
             let error_object: ErrorObjectOwned = error_result.into();
             assert_eq!(
                 error_object.code(),
-                jsonrpsee::types::error::INVALID_PARAMS_CODE
+                mgo_json_rpc_api::ClientErrorCode::Unsupported.code()
             );
             let expected = expect!["Index store not available on this Fullnode."];
             expected.assert_eq(error_object.message());
@@ -1076,7 +1685,7 @@ mod tests {
                 error_object.code(),
                 jsonrpsee::types::error::INTERNAL_ERROR_CODE
             );
-            let expected = expect!["Error executing mock db error"];
+            let expected = expect!["Internal error occurred while reading state."];
             expected.assert_eq(error_object.message());
         }
     }
@@ -1084,6 +1693,7 @@ mod tests {
     mod get_all_balances_tests {
         use super::super::*;
         use super::*;
+        use crate::coin_registry::CoinRegistryEntry;
         use jsonrpsee::types::ErrorObjectOwned;
 
         // Success scenarios
@@ -1126,12 +1736,18 @@ mod tests {
                     coin_object_count: 9,
                     total_balance: 7,
                     locked_balance: Default::default(),
+                    verified: None,
+                    canonical_symbol: None,
+                    canonical_icon_url: None,
                 },
                 Balance {
                     coin_type: usdc_coin.coin_type,
                     coin_object_count: 11,
                     total_balance: 10,
                     locked_balance: Default::default(),
+                    verified: None,
+                    canonical_symbol: None,
+                    canonical_icon_url: None,
                 },
             ];
             // This is because the underlying result is a hashmap, so order is not guaranteed
@@ -1146,6 +1762,56 @@ mod tests {
             assert!(result.is_empty());
         }
 
+        #[tokio::test]
+        async fn test_verified_by_coin_registry() {
+            let owner = get_test_owner();
+            let gas_coin_type = get_test_coin(None, CoinType::Gas).coin_type;
+            let usdc_coin_type = get_test_coin(None, CoinType::Usdc).coin_type;
+            let coin_registry = CoinRegistry::new(ObjectID::random());
+            let entry = coin_registry.entry_object_for_testing(
+                &usdc_coin_type,
+                CoinRegistryEntry {
+                    symbol: Some("USDC".to_string()),
+                    icon_url: None,
+                },
+            );
+            let entry_id = entry.id();
+
+            let mut mock_state = MockStateRead::new();
+            let balance_coin_types = [&gas_coin_type, &usdc_coin_type]
+                .map(|coin_type| get_test_coin_type_tag(coin_type.clone()));
+            mock_state
+                .expect_get_all_balance()
+                .with(predicate::eq(owner))
+                .return_once(move |_| {
+                    Ok(Arc::new(
+                        balance_coin_types
+                            .into_iter()
+                            .map(|coin_type| (coin_type, TotalBalance::default()))
+                            .collect(),
+                    ))
+                });
+            mock_state
+                .expect_get_object()
+                .times(2)
+                .returning(move |id| Ok((*id == entry_id).then(|| entry.clone())));
+            let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None)
+                .with_coin_registry(coin_registry);
+
+            let mut result = coin_read_api.get_all_balances(owner).await.unwrap();
+            result.sort_by(|a, b| a.coin_type.cmp(&b.coin_type));
+            let verification: Vec<_> = result
+                .iter()
+                .map(|b| (b.coin_type.clone(), b.verified, b.canonical_symbol.clone()))
+                .collect();
+            let mut expected = vec![
+                (gas_coin_type, Some(false), None),
+                (usdc_coin_type, Some(true), Some("USDC".to_string())),
+            ];
+            expected.sort();
+            assert_eq!(verification, expected);
+        }
+
         // Unexpected error scenarios
         #[tokio::test]
         async fn test_index_store_not_available() {
@@ -1162,10 +1828,165 @@ mod tests {
             assert!(response.is_err());
             let error_result = response.unwrap_err();
             let error_object: ErrorObjectOwned = error_result.into();
+            assert_eq!(
+                error_object.code(),
+                mgo_json_rpc_api::ClientErrorCode::Unsupported.code()
+            );
+            let expected = expect!["Index store not available on this Fullnode."];
+            expected.assert_eq(error_object.message());
+        }
+    }
+
+    mod get_all_balances_paginated_tests {
+        use super::super::*;
+        use super::*;
+        use jsonrpsee::types::ErrorObjectOwned;
+
+        fn total_balance(balance: i128, num_coins: i64) -> TotalBalance {
+            TotalBalance { balance, num_coins }
+        }
+
+        // Success scenarios
+        #[tokio::test]
+        async fn test_first_page() {
+            let owner = get_test_owner();
+            let gas_coin_type_tag = get_test_coin_type_tag(GAS::type_().to_string());
+            let usdc_coin = get_test_coin(None, CoinType::Usdc);
+            let usdc_coin_type_tag = get_test_coin_type_tag(usdc_coin.coin_type.clone());
+            let mut mock_state = MockStateRead::new();
+            mock_state
+                .expect_get_all_balance_paginated()
+                .with(predicate::eq(owner), predicate::eq(None), predicate::eq(2))
+                .return_once(move |_, _, _| {
+                    Ok(vec![
+                        (gas_coin_type_tag, total_balance(7, 9)),
+                        (usdc_coin_type_tag, total_balance(10, 11)),
+                    ])
+                });
+            let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
+            let response = coin_read_api
+                .get_all_balances_paginated(owner, None, Some(1))
+                .await
+                .unwrap();
+
+            assert_eq!(
+                response.data,
+                vec![Balance {
+                    coin_type: GAS::type_().to_string(),
+                    coin_object_count: 9,
+                    total_balance: 7,
+                    locked_balance: Default::default(),
+                    verified: None,
+                    canonical_symbol: None,
+                    canonical_icon_url: None,
+                }]
+            );
+            assert_eq!(response.next_cursor, Some(GAS::type_().to_string()));
+            assert!(response.has_next_page);
+        }
+
+        #[tokio::test]
+        async fn test_cursor_is_canonicalized() {
+            let owner = get_test_owner();
+            let mut mock_state = MockStateRead::new();
+            mock_state
+                .expect_get_all_balance_paginated()
+                .with(
+                    predicate::eq(owner),
+                    predicate::eq(Some(GAS::type_().to_string())),
+                    predicate::eq(51),
+                )
+                .return_once(move |_, _, _| Ok(vec![]));
+            let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
+            let response = coin_read_api
+                .get_all_balances_paginated(owner, Some("0x2::mgo::MGO".to_string()), None)
+                .await
+                .unwrap();
+
+            assert!(response.data.is_empty());
+            assert_eq!(response.next_cursor, None);
+            assert!(!response.has_next_page);
+        }
+
+        #[tokio::test]
+        async fn test_cursor_at_fully_spent_coin_type() {
+            // The owner held USDC when the previous page was served, but has spent every USDC
+            // coin since. The next page resumes at the first coin type after USDC.
+            let owner = get_test_owner();
+            let usdc_coin = get_test_coin(None, CoinType::Usdc);
+            let later_coin_type = format!("{}::zzz::ZZZ", get_test_package_id());
+            let later_coin_type_tag = get_test_coin_type_tag(later_coin_type.clone());
+            let mut mock_state = MockStateRead::new();
+            mock_state
+                .expect_get_all_balance_paginated()
+                .with(
+                    predicate::eq(owner),
+                    predicate::eq(Some(usdc_coin.coin_type.clone())),
+                    predicate::eq(3),
+                )
+                .return_once(move |_, _, _| Ok(vec![(later_coin_type_tag, total_balance(5, 1))]));
+            let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
+            let response = coin_read_api
+                .get_all_balances_paginated(owner, Some(usdc_coin.coin_type), Some(2))
+                .await
+                .unwrap();
+
+            let later_coin_type = parse_to_struct_tag(&later_coin_type).unwrap().to_string();
+            assert_eq!(
+                response.data,
+                vec![Balance {
+                    coin_type: later_coin_type.clone(),
+                    coin_object_count: 1,
+                    total_balance: 5,
+                    locked_balance: Default::default(),
+                    verified: None,
+                    canonical_symbol: None,
+                    canonical_icon_url: None,
+                }]
+            );
+            assert_eq!(response.next_cursor, Some(later_coin_type));
+            assert!(!response.has_next_page);
+        }
+
+        // Expected error scenarios
+        #[tokio::test]
+        async fn test_invalid_cursor() {
+            let owner = get_test_owner();
+            let mock_state = MockStateRead::new();
+            let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
+            let response = coin_read_api
+                .get_all_balances_paginated(owner, Some("invalid".to_string()), None)
+                .await;
+
+            let error_object: ErrorObjectOwned = response.unwrap_err().into();
             assert_eq!(
                 error_object.code(),
                 jsonrpsee::types::error::INVALID_PARAMS_CODE
             );
+        }
+
+        // Unexpected error scenarios
+        #[tokio::test]
+        async fn test_index_store_not_available() {
+            let owner = get_test_owner();
+            let mut mock_state = MockStateRead::new();
+            mock_state
+                .expect_get_all_balance_paginated()
+                .returning(move |_, _, _| {
+                    Err(StateReadError::Client(
+                        MgoError::IndexStoreNotAvailable.into(),
+                    ))
+                });
+            let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
+            let response = coin_read_api
+                .get_all_balances_paginated(owner, None, None)
+                .await;
+
+            let error_object: ErrorObjectOwned = response.unwrap_err().into();
+            assert_eq!(
+                error_object.code(),
+                mgo_json_rpc_api::ClientErrorCode::Unsupported.code()
+            );
             let expected = expect!["Index store not available on this Fullnode."];
             expected.assert_eq(error_object.message());
         }
@@ -1210,6 +2031,7 @@ mod tests {
 
             let coin_read_api = CoinReadApi {
                 internal: Box::new(mock_internal),
+                coin_registry: None,
             };
 
             let response = coin_read_api.get_coin_metadata(coin_name.clone()).await;
@@ -1268,6 +2090,7 @@ mod tests {
 
             let coin_read_api = CoinReadApi {
                 internal: Box::new(mock_internal),
+                coin_registry: None,
             };
 
             let response = coin_read_api.get_coin_metadata(coin_name.clone()).await;
@@ -1280,18 +2103,129 @@ mod tests {
     mod get_total_supply_tests {
         use super::super::*;
         use super::*;
+        use crate::authority_state::{find_total_supply, StateReadClientError};
         use mockall::predicate;
+        use mgo_types::digests::ObjectDigest;
+        use mgo_types::execution_status::ExecutionStatus;
+        use mgo_types::gas::GasCostSummary;
+        use mgo_types::gas_coin::TOTAL_SUPPLY_MIST;
         use mgo_types::id::UID;
+        use mgo_types::object::{ObjectRead, Owner};
+
+        fn get_test_kv_store() -> Arc<TransactionKeyValueStore> {
+            Arc::new(TransactionKeyValueStore::new(
+                "rocksdb",
+                KeyValueStoreMetrics::new_for_tests(),
+                Arc::new(MockKeyValueStore::new()),
+            ))
+        }
+
+        /// Effects of publishing a package that created `created`.
+        fn get_test_publish_effects(created: &[ObjectID]) -> TransactionEffects {
+            TransactionEffects::new_from_execution_v1(
+                ExecutionStatus::Success,
+                0,
+                GasCostSummary::default(),
+                vec![],
+                vec![],
+                TransactionDigest::from([0; 32]),
+                created
+                    .iter()
+                    .map(|id| {
+                        (
+                            (*id, SequenceNumber::from_u64(1), ObjectDigest::MIN),
+                            Owner::Immutable,
+                        )
+                    })
+                    .collect(),
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                (
+                    (ObjectID::ZERO, SequenceNumber::new(), ObjectDigest::MIN),
+                    Owner::AddressOwner(get_test_owner()),
+                ),
+                None,
+                vec![],
+            )
+        }
+
+        /// State in which the test coin package is published, and the objects it created are
+        /// readable if they are in `objects`.
+        fn get_test_publish_state(objects: Vec<Object>) -> MockStateRead {
+            let package_id = get_test_package_id();
+            let created: Vec<_> = objects.iter().map(|object| object.id()).collect();
+            let mut mock_state = MockStateRead::new();
+            mock_state.expect_get_object_read().returning(move |id| {
+                if id == &package_id {
+                    let package = Object::immutable_with_id_for_testing(package_id);
+                    return Ok(ObjectRead::Exists(
+                        package.compute_object_reference(),
+                        package,
+                        None,
+                    ));
+                }
+                Ok(match objects.iter().find(|object| &object.id() == id) {
+                    Some(object) => {
+                        ObjectRead::Exists(object.compute_object_reference(), object.clone(), None)
+                    }
+                    None => ObjectRead::NotExists(*id),
+                })
+            });
+            mock_state
+                .expect_find_publish_txn_digest()
+                .with(predicate::eq(package_id))
+                .return_once(|_| Ok(TransactionDigest::from([0; 32])));
+            mock_state
+                .expect_get_executed_transaction_and_effects()
+                .return_once(move |_, _| {
+                    Ok((
+                        create_fake_transaction(),
+                        get_test_publish_effects(&created),
+                    ))
+                });
+            mock_state
+        }
+
+        fn get_test_coin_metadata_object(coin_struct: StructTag) -> Object {
+            let coin_metadata = CoinMetadata {
+                id: UID::new(ObjectID::from_hex_literal("0x11").unwrap()),
+                decimals: 2,
+                name: "test_coin".to_string(),
+                symbol: "TEST".to_string(),
+                description: "test coin".to_string(),
+                icon_url: None,
+            };
+            Object::coin_metadata_for_testing(coin_struct, coin_metadata)
+        }
+
+        fn get_test_treasury_cap_object(coin_struct: StructTag) -> Object {
+            let treasury_cap = TreasuryCap {
+                id: UID::new(ObjectID::from_hex_literal("0x12").unwrap()),
+                total_supply: Supply { value: 420 },
+            };
+            Object::treasury_cap_for_testing(coin_struct, treasury_cap)
+        }
 
         #[tokio::test]
         async fn test_success_response_for_gas_coin() {
-            let coin_type = "0x2::mgo::MGO";
-            let mock_internal = MockCoinReadInternal::new();
-            let coin_read_api = CoinReadApi {
-                internal: Box::new(mock_internal),
-            };
+            let gas_type = TypeTag::Struct(Box::new(GAS::type_()));
+            let mut mock_state = MockStateRead::new();
+            mock_state
+                .expect_get_total_supply()
+                .with(predicate::eq(gas_type), predicate::always())
+                .return_once(|_, _| {
+                    Ok(Supply {
+                        value: TOTAL_SUPPLY_MIST,
+                    })
+                });
+            let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
 
-            let response = coin_read_api.get_total_supply(coin_type.to_string()).await;
+            let response = coin_read_api
+                .get_total_supply("0x2::mgo::MGO".to_string())
+                .await;
 
             let supply = response.unwrap();
             let expected = expect!["10000000000000000000"];
@@ -1301,98 +2235,138 @@ mod tests {
         #[tokio::test]
         async fn test_success_response_for_other_coin() {
             let package_id = get_test_package_id();
-            let (coin_name, _, treasury_cap_struct, _, treasury_cap_object) =
-                get_test_treasury_cap_peripherals(package_id);
+            let coin_name = get_test_coin_type(package_id);
+            let coin_type = get_test_coin_type_tag(coin_name.clone());
             let mut mock_internal = MockCoinReadInternal::new();
             mock_internal
-                .expect_find_package_object()
-                .with(predicate::always(), predicate::eq(treasury_cap_struct))
-                .returning(move |object_id, _| {
-                    if object_id == &package_id {
-                        Ok(treasury_cap_object.clone())
-                    } else {
-                        panic!("should not be called with any other object id")
-                    }
-                });
+                .expect_get_total_supply()
+                .with(predicate::eq(coin_type))
+                .return_once(|_| Ok(Supply { value: 420 }));
             let coin_read_api = CoinReadApi {
                 internal: Box::new(mock_internal),
+                coin_registry: None,
             };
 
-            let response = coin_read_api.get_total_supply(coin_name.clone()).await;
+            let response = coin_read_api.get_total_supply(coin_name).await;
 
-            assert!(response.is_ok());
             let result = response.unwrap();
             let expected = expect!["420"];
             expected.assert_eq(&result.value.to_string());
         }
 
         #[tokio::test]
-        async fn test_object_not_found() {
-            let package_id = get_test_package_id();
-            let (coin_name, _, _, _, _) = get_test_treasury_cap_peripherals(package_id);
-            let transaction_digest = TransactionDigest::from([0; 32]);
-            let transaction_effects = TransactionEffects::default();
-
+        async fn test_treasury_cap_not_found() {
+            let coin_name = get_test_coin_type(get_test_package_id());
             let mut mock_state = MockStateRead::new();
             mock_state
-                .expect_find_publish_txn_digest()
-                .return_once(move |_| Ok(transaction_digest));
-            mock_state
-                .expect_get_executed_transaction_and_effects()
-                .return_once(move |_, _| Ok((create_fake_transaction(), transaction_effects)));
-
+                .expect_get_total_supply()
+                .return_once(|coin_type, _| {
+                    Err(StateReadError::Client(
+                        StateReadClientError::TreasuryCapNotFound(coin_type.to_string()),
+                    ))
+                });
             let coin_read_api = CoinReadApi::new_for_tests(Arc::new(mock_state), None);
-            let response = coin_read_api.get_total_supply(coin_name.clone()).await;
 
-            assert!(response.is_err());
-            let error_result = response.unwrap_err();
-            let error_object: ErrorObjectOwned = error_result.into();
-            let expected = expect!["-32602"];
-            expected.assert_eq(&error_object.code().to_string());
-            let expected = expect!["Cannot find object [0x2::coin::TreasuryCap<0xf::test_coin::TEST_COIN>] from [0x000000000000000000000000000000000000000000000000000000000000000f] package event."];
+            let response = coin_read_api.get_total_supply(coin_name).await;
+
+            let error_object: ErrorObjectOwned = response.unwrap_err().into();
+            assert_eq!(
+                error_object.code(),
+                mgo_json_rpc_api::ClientErrorCode::TreasuryCapNotFound.code()
+            );
+            let expected = expect!["Coin type 0x000000000000000000000000000000000000000000000000000000000000000f::test_coin::TEST_COIN has no treasury cap to read its supply from"];
             expected.assert_eq(error_object.message());
         }
 
         #[tokio::test]
-        async fn test_find_package_object_not_treasury_cap() {
-            let package_id = get_test_package_id();
-            let (coin_name, input_coin_struct, treasury_cap_struct, _, _) =
-                get_test_treasury_cap_peripherals(package_id);
-            let coin_metadata = CoinMetadata {
-                id: UID::new(get_test_package_id()),
-                decimals: 2,
-                name: "test_coin".to_string(),
-                symbol: "TEST".to_string(),
-                description: "test coin".to_string(),
-                icon_url: None,
-            };
-            let coin_metadata_object =
-                Object::coin_metadata_for_testing(input_coin_struct.clone(), coin_metadata);
-            let mut mock_internal = MockCoinReadInternal::new();
-            mock_internal
-                .expect_find_package_object()
-                .with(predicate::always(), predicate::eq(treasury_cap_struct))
-                .returning(move |object_id, _| {
-                    if object_id == &package_id {
-                        Ok(coin_metadata_object.clone())
-                    } else {
-                        panic!("should not be called with any other object id")
-                    }
-                });
+        async fn test_find_total_supply_of_gas_coin() {
+            // The MGO supply is not read from any object.
+            let mock_state = MockStateRead::new();
+            let gas_type = TypeTag::Struct(Box::new(GAS::type_()));
 
-            let coin_read_api = CoinReadApi {
-                internal: Box::new(mock_internal),
-            };
+            let supply = find_total_supply(&mock_state, &gas_type, get_test_kv_store())
+                .await
+                .unwrap();
 
-            let response = coin_read_api.get_total_supply(coin_name.clone()).await;
-            let error_result = response.unwrap_err();
-            let error_object: ErrorObjectOwned = error_result.into();
-            assert_eq!(
-                error_object.code(),
-                jsonrpsee::types::error::CALL_EXECUTION_FAILED_CODE
-            );
-            let expected = expect!["Failure deserializing object in the requested format: \"Unable to deserialize TreasuryCap object: remaining input\""];
-            expected.assert_eq(error_object.message());
+            assert_eq!(supply.value, TOTAL_SUPPLY_MIST);
+        }
+
+        #[tokio::test]
+        async fn test_find_total_supply_of_other_coin() {
+            let coin_name = get_test_coin_type(get_test_package_id());
+            let coin_struct = parse_mgo_struct_tag(&coin_name).unwrap();
+            let mock_state = get_test_publish_state(vec![
+                get_test_coin_metadata_object(coin_struct.clone()),
+                get_test_treasury_cap_object(coin_struct),
+            ]);
+
+            let supply = find_total_supply(
+                &mock_state,
+                &get_test_coin_type_tag(coin_name),
+                get_test_kv_store(),
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(supply.value, 420);
+        }
+
+        #[tokio::test]
+        async fn test_find_total_supply_without_treasury_cap() {
+            // The treasury cap was created on publish, but has since been wrapped.
+            let coin_name = get_test_coin_type(get_test_package_id());
+            let coin_struct = parse_mgo_struct_tag(&coin_name).unwrap();
+            let mock_state =
+                get_test_publish_state(vec![get_test_coin_metadata_object(coin_struct)]);
+
+            let error = find_total_supply(
+                &mock_state,
+                &get_test_coin_type_tag(coin_name),
+                get_test_kv_store(),
+            )
+            .await
+            .unwrap_err();
+
+            assert!(matches!(
+                error,
+                StateReadError::Client(StateReadClientError::TreasuryCapNotFound(_))
+            ));
+        }
+
+        #[tokio::test]
+        async fn test_find_total_supply_of_missing_coin_type() {
+            let coin_name = get_test_coin_type(get_test_package_id());
+            let mut mock_state = MockStateRead::new();
+            mock_state
+                .expect_get_object_read()
+                .returning(|id| Ok(ObjectRead::NotExists(*id)));
+
+            let error = find_total_supply(
+                &mock_state,
+                &get_test_coin_type_tag(coin_name.clone()),
+                get_test_kv_store(),
+            )
+            .await
+            .unwrap_err();
+
+            assert!(matches!(
+                error,
+                StateReadError::Client(StateReadClientError::CoinTypeNotFound(_))
+            ));
+
+            // A published package that does not define the coin is reported the same way.
+            let error = find_total_supply(
+                &get_test_publish_state(vec![]),
+                &get_test_coin_type_tag(coin_name),
+                get_test_kv_store(),
+            )
+            .await
+            .unwrap_err();
+
+            assert!(matches!(
+                error,
+                StateReadError::Client(StateReadClientError::CoinTypeNotFound(_))
+            ));
         }
     }
 }
@@ -0,0 +1,384 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Allowlist of vetted coin types, kept in a registry object so that wallets can tell a coin
+//! apart from impersonators of its name. The registry lists a coin type with a dynamic field
+//! named by the canonical coin type, without `0x` prefix, as a `0x1::ascii::String`, whose value
+//! is a [`CoinRegistryEntry`].
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use cached::{Cached, TimedSizedCache};
+use move_core_types::language_storage::StructTag;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use mgo_json_rpc_types::{Balance, Coin, CoinVerification, MgoCoinMetadata};
+use mgo_types::base_types::{ObjectID, RESOLVED_ASCII_STR};
+use mgo_types::dynamic_field::{derive_dynamic_field_id, Field};
+use mgo_types::object::Object;
+use mgo_types::{parse_mgo_struct_tag, TypeTag};
+
+/// Number of coin types whose standing is cached.
+pub const COIN_REGISTRY_CACHE_SIZE: usize = 10_000;
+
+/// How long the standing of a coin type is cached, which bounds how long changes to the
+/// registry take to show.
+pub const COIN_REGISTRY_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Rust version of the value of a registry field.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CoinRegistryEntry {
+    pub symbol: Option<String>,
+    pub icon_url: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct CoinRegistry {
+    registry_id: ObjectID,
+    cache: Arc<Mutex<TimedSizedCache<String, CoinVerification>>>,
+}
+
+impl CoinRegistry {
+    pub fn new(registry_id: ObjectID) -> Self {
+        Self {
+            registry_id,
+            cache: Arc::new(Mutex::new(TimedSizedCache::with_size_and_lifespan(
+                COIN_REGISTRY_CACHE_SIZE,
+                COIN_REGISTRY_CACHE_TTL.as_secs(),
+            ))),
+        }
+    }
+
+    pub fn registry_id(&self) -> ObjectID {
+        self.registry_id
+    }
+
+    /// The field listing `coin_type` with `entry`.
+    #[cfg(test)]
+    pub(crate) fn entry_object_for_testing(
+        &self,
+        coin_type: &str,
+        entry: CoinRegistryEntry,
+    ) -> Object {
+        use mgo_types::base_types::SequenceNumber;
+        use mgo_types::digests::TransactionDigest;
+        use mgo_types::id::UID;
+        use mgo_types::object::{MoveObject, Owner};
+
+        let key = registry_key(coin_type).unwrap();
+        let field = Field {
+            id: UID::new(self.entry_field_id(&key)),
+            name: key,
+            value: entry,
+        };
+        // SAFETY: the field is never transferred, so public transfer is irrelevant. Only its
+        // contents are read, so its type is left as that of the key.
+        let move_object = unsafe {
+            MoveObject::new_from_execution_with_limit(
+                ascii_string_type().into(),
+                false,
+                SequenceNumber::new(),
+                bcs::to_bytes(&field).unwrap(),
+                u64::MAX,
+            )
+            .unwrap()
+        };
+        Object::new_move(
+            move_object,
+            Owner::ObjectOwner(self.registry_id.into()),
+            TransactionDigest::genesis_marker(),
+        )
+    }
+
+    /// ID of the field listing the coin type of canonical form `key`.
+    pub fn entry_field_id(&self, key: &str) -> ObjectID {
+        let key_type = TypeTag::Struct(Box::new(ascii_string_type()));
+        derive_dynamic_field_id(self.registry_id, &key_type, &bcs::to_bytes(key).unwrap()).unwrap()
+    }
+
+    /// Standing of each of `coin_types` in the registry. Coin types not cached are read with one
+    /// call to `multi_get_objects`, and those that do not parse are not verified.
+    pub async fn verify<F, Fut, E>(
+        &self,
+        coin_types: &[String],
+        multi_get_objects: F,
+    ) -> Result<Vec<CoinVerification>, E>
+    where
+        F: FnOnce(Vec<ObjectID>) -> Fut,
+        Fut: Future<Output = Result<Vec<Option<Object>>, E>>,
+    {
+        let keys = coin_types
+            .iter()
+            .map(|coin_type| registry_key(coin_type))
+            .collect::<Vec<_>>();
+
+        let mut resolved = HashMap::new();
+        let mut missing = BTreeSet::new();
+        {
+            let mut cache = self.cache.lock().unwrap();
+            for key in keys.iter().flatten() {
+                match cache.cache_get(key) {
+                    Some(verification) => {
+                        resolved.insert(key.clone(), verification.clone());
+                    }
+                    None => {
+                        missing.insert(key.clone());
+                    }
+                }
+            }
+        }
+
+        if !missing.is_empty() {
+            let field_ids = missing.iter().map(|key| self.entry_field_id(key)).collect();
+            let objects = multi_get_objects(field_ids).await?;
+            let mut cache = self.cache.lock().unwrap();
+            for (key, object) in missing.into_iter().zip(objects) {
+                let verification = object.map_or_else(CoinVerification::default, |object| {
+                    registry_entry(&key, &object)
+                });
+                cache.cache_set(key.clone(), verification.clone());
+                resolved.insert(key, verification);
+            }
+        }
+
+        Ok(keys
+            .iter()
+            .map(|key| {
+                key.as_ref()
+                    .and_then(|key| resolved.get(key).cloned())
+                    .unwrap_or_default()
+            })
+            .collect())
+    }
+
+    /// Sets the standing of their coin type on `coins`, leaving it unset if the registry
+    /// cannot be read.
+    pub async fn annotate_coins<F, Fut, E>(&self, coins: &mut [Coin], multi_get_objects: F)
+    where
+        F: FnOnce(Vec<ObjectID>) -> Fut,
+        Fut: Future<Output = Result<Vec<Option<Object>>, E>>,
+        E: Display,
+    {
+        let coin_types = coins.iter().map(|coin| coin.coin_type.clone()).collect();
+        let verifications = self.verify_or_skip(coin_types, multi_get_objects).await;
+        for (coin, verification) in coins.iter_mut().zip(verifications) {
+            coin.set_verification(verification);
+        }
+    }
+
+    /// Sets the standing of their coin type on `balances`, leaving it unset if the registry
+    /// cannot be read.
+    pub async fn annotate_balances<F, Fut, E>(&self, balances: &mut [Balance], multi_get_objects: F)
+    where
+        F: FnOnce(Vec<ObjectID>) -> Fut,
+        Fut: Future<Output = Result<Vec<Option<Object>>, E>>,
+        E: Display,
+    {
+        let coin_types = balances
+            .iter()
+            .map(|balance| balance.coin_type.clone())
+            .collect();
+        let verifications = self.verify_or_skip(coin_types, multi_get_objects).await;
+        for (balance, verification) in balances.iter_mut().zip(verifications) {
+            balance.set_verification(verification);
+        }
+    }
+
+    /// Sets the standing of `coin_type` on its `metadata`, leaving it unset if the registry
+    /// cannot be read.
+    pub async fn annotate_metadata<F, Fut, E>(
+        &self,
+        metadata: &mut MgoCoinMetadata,
+        coin_type: &str,
+        multi_get_objects: F,
+    ) where
+        F: FnOnce(Vec<ObjectID>) -> Fut,
+        Fut: Future<Output = Result<Vec<Option<Object>>, E>>,
+        E: Display,
+    {
+        let verifications = self
+            .verify_or_skip(vec![coin_type.to_string()], multi_get_objects)
+            .await;
+        if let Some(verification) = verifications.into_iter().next() {
+            metadata.set_verification(verification);
+        }
+    }
+
+    /// Like [`Self::verify`], but logs the error and returns no standing at all if the
+    /// registry cannot be read, so that coin responses do not fail on it.
+    async fn verify_or_skip<F, Fut, E>(
+        &self,
+        coin_types: Vec<String>,
+        multi_get_objects: F,
+    ) -> Vec<CoinVerification>
+    where
+        F: FnOnce(Vec<ObjectID>) -> Fut,
+        Fut: Future<Output = Result<Vec<Option<Object>>, E>>,
+        E: Display,
+    {
+        if coin_types.is_empty() {
+            return vec![];
+        }
+        self.verify(&coin_types, multi_get_objects)
+            .await
+            .unwrap_or_else(|e| {
+                warn!(registry_id = ?self.registry_id, "Failed to read coin registry: {e}");
+                vec![]
+            })
+    }
+}
+
+fn ascii_string_type() -> StructTag {
+    let (address, module, name) = RESOLVED_ASCII_STR;
+    StructTag {
+        address: *address,
+        module: module.to_owned(),
+        name: name.to_owned(),
+        type_params: vec![],
+    }
+}
+
+/// Key the registry lists `coin_type` under, or `None` if it is not a struct type.
+fn registry_key(coin_type: &str) -> Option<String> {
+    parse_mgo_struct_tag(coin_type)
+        .ok()
+        .map(|tag| tag.to_canonical_string(/* with_prefix */ false))
+}
+
+/// Standing of the coin type of canonical form `key`, listed by the registry field `object`.
+/// Malformed fields do not verify the coin type.
+fn registry_entry(key: &str, object: &Object) -> CoinVerification {
+    match object.to_rust::<Field<String, CoinRegistryEntry>>() {
+        Some(field) if field.name == key => CoinVerification {
+            verified: true,
+            symbol: field.value.symbol,
+            icon_url: field.value.icon_url,
+        },
+        _ => {
+            warn!(
+                field_id = ?object.id(),
+                "Malformed coin registry field for {key}, not verifying the coin type"
+            );
+            CoinVerification::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const LISTED_COIN: &str = "0xa::usdc::USDC";
+    const UNLISTED_COIN: &str = "0xb::usdc::USDC";
+
+    /// A registry listing `LISTED_COIN`, with a count of the objects read from it.
+    struct Store {
+        registry: CoinRegistry,
+        objects: HashMap<ObjectID, Object>,
+        reads: AtomicUsize,
+    }
+
+    impl Store {
+        fn new() -> Self {
+            let registry = CoinRegistry::new(ObjectID::random());
+            let object = registry.entry_object_for_testing(
+                LISTED_COIN,
+                CoinRegistryEntry {
+                    symbol: Some("USDC".to_string()),
+                    icon_url: Some("https://example.com/usdc.png".to_string()),
+                },
+            );
+            Self {
+                registry,
+                objects: HashMap::from([(object.id(), object)]),
+                reads: AtomicUsize::new(0),
+            }
+        }
+
+        async fn multi_get_objects(
+            &self,
+            object_ids: Vec<ObjectID>,
+        ) -> anyhow::Result<Vec<Option<Object>>> {
+            self.reads.fetch_add(object_ids.len(), Ordering::Relaxed);
+            Ok(object_ids
+                .iter()
+                .map(|id| self.objects.get(id).cloned())
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify() {
+        let store = Store::new();
+        // The listed coin type is spelled differently from its registry key.
+        let coin_types = [
+            "0x000000000000000000000000000000000000000000000000000000000000000a::usdc::USDC",
+            UNLISTED_COIN,
+            "not a coin type",
+        ]
+        .map(String::from);
+
+        let verifications = store
+            .registry
+            .verify(&coin_types, |ids| store.multi_get_objects(ids))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            verifications,
+            vec![
+                CoinVerification {
+                    verified: true,
+                    symbol: Some("USDC".to_string()),
+                    icon_url: Some("https://example.com/usdc.png".to_string()),
+                },
+                CoinVerification::default(),
+                CoinVerification::default(),
+            ]
+        );
+        assert_eq!(store.reads.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn test_verify_is_cached() {
+        let store = Store::new();
+        let coin_types = [LISTED_COIN, UNLISTED_COIN, LISTED_COIN].map(String::from);
+
+        let first = store
+            .registry
+            .verify(&coin_types, |ids| store.multi_get_objects(ids))
+            .await
+            .unwrap();
+        let second = store
+            .registry
+            .verify(&coin_types, |ids| store.multi_get_objects(ids))
+            .await
+            .unwrap();
+
+        assert_eq!(first, second);
+        assert!(first[0].verified && !first[1].verified && first[2].verified);
+        // Both coin types are read once, the second lookup being served from the cache.
+        assert_eq!(store.reads.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn test_annotate_skips_unreadable_registry() {
+        let registry = CoinRegistry::new(ObjectID::random());
+        let mut balances = vec![Balance::zero(LISTED_COIN.to_string())];
+
+        registry
+            .annotate_balances(&mut balances, |_| async {
+                Err::<Vec<Option<Object>>, _>(anyhow::anyhow!("store unavailable"))
+            })
+            .await;
+
+        assert_eq!(balances[0].verified, None);
+        assert_eq!(balances[0].canonical_symbol, None);
+    }
+}
@@ -0,0 +1,284 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Access of browser clients from other origins. Browsers only call the server from the origins
+//! and with the headers it allows, which the CORS layer answers their preflight requests with.
+//! Origins may further be restricted to some methods, such as keeping transaction execution to
+//! the origins of trusted dapps. Restrictions are enforced on every request before it is
+//! dispatched, and requests to other methods are answered with
+//! [`METHOD_NOT_ALLOWED_FOR_ORIGIN_CODE`].
+//!
+//! Only browsers are bound by the origin they send, so requests without an `origin` header may
+//! call any method. Origins are compared with the allowed ones as [`KeyMaterial`], in constant
+//! time.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use hyper::header::{HeaderName, HeaderValue, ORIGIN};
+use hyper::{HeaderMap, Method, Uri};
+use jsonrpsee::types::ErrorObjectOwned;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use mgo_json_rpc_api::{
+    CLIENT_SDK_TYPE_HEADER, CLIENT_SDK_VERSION_HEADER, CLIENT_TARGET_API_VERSION_HEADER,
+    METHOD_NOT_ALLOWED_FOR_ORIGIN_CODE,
+};
+use mgo_types::key_material::KeyMaterial;
+
+use crate::error::Error;
+use crate::APP_NAME_HEADER;
+
+/// Stands for any origin, in [`CorsConfig::allowed_origins`] and as the key of the methods of
+/// the origins without their own entry in [`CorsConfig::origin_methods`].
+pub const ANY_ORIGIN: &str = "*";
+
+/// Cross-origin access of the server. The default configuration allows any origin to call any
+/// method.
+#[derive(Clone, Debug, Default)]
+pub struct CorsConfig {
+    /// Origins browsers may call the server from, as `scheme://host[:port]`. Any origin may when
+    /// empty or [`ANY_ORIGIN`].
+    pub allowed_origins: Vec<String>,
+    /// Headers browsers may send besides `content-type` and the headers of the client SDKs.
+    pub allowed_headers: Vec<String>,
+    /// Methods that requests from an origin are restricted to, by origin. The entry of
+    /// [`ANY_ORIGIN`] restricts the origins without their own entry, which may call any method
+    /// otherwise.
+    pub origin_methods: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl CorsConfig {
+    /// The CORS layer answering preflight requests, and the method restrictions of origins, as
+    /// configured for a server of `methods`. Origins, headers and methods that are malformed or
+    /// unknown are rejected, rather than silently denying browsers access.
+    pub fn build(&self, methods: &[&str]) -> Result<(CorsLayer, OriginMethods), Error> {
+        let any_origin = self.allowed_origins.is_empty()
+            || self.allowed_origins.iter().any(|origin| origin == ANY_ORIGIN);
+        let allow_origin = if any_origin {
+            if self.allowed_origins.len() > 1 {
+                return Err(invalid(format!(
+                    "`{ANY_ORIGIN}` allows any origin and cannot be listed with other origins"
+                )));
+            }
+            AllowOrigin::any()
+        } else {
+            let allowed_origins = self
+                .allowed_origins
+                .iter()
+                .map(|origin| parse_origin(origin))
+                .collect::<Result<Vec<_>, _>>()?;
+            AllowOrigin::predicate(move |origin, _| {
+                KeyMaterial::from(origin).is_any_of(&allowed_origins)
+            })
+        };
+
+        let mut headers = sdk_headers();
+        for header in &self.allowed_headers {
+            headers.push(
+                HeaderName::from_str(header)
+                    .map_err(|e| invalid(format!("Invalid header `{header}`: {e}")))?,
+            );
+        }
+
+        for (origin, allowed) in &self.origin_methods {
+            if origin != ANY_ORIGIN {
+                parse_origin(origin)?;
+                if !any_origin && !KeyMaterial::from(origin).is_any_of(&self.allowed_origins) {
+                    return Err(invalid(format!(
+                        "Methods are restricted for origin `{origin}`, which is not allowed"
+                    )));
+                }
+            }
+            if let Some(method) = allowed.iter().find(|m| !methods.contains(&m.as_str())) {
+                return Err(invalid(format!(
+                    "Unknown method `{method}` in the methods of origin `{origin}`"
+                )));
+            }
+        }
+
+        let layer = CorsLayer::new()
+            .allow_methods([Method::POST])
+            .allow_origin(allow_origin)
+            .allow_headers(headers);
+        let origin_methods = OriginMethods {
+            allowed: Arc::new(self.origin_methods.clone()),
+        };
+        Ok((layer, origin_methods))
+    }
+}
+
+/// Headers the client SDKs send, which browsers may always send.
+pub fn sdk_headers() -> Vec<HeaderName> {
+    vec![
+        hyper::header::CONTENT_TYPE,
+        HeaderName::from_static(CLIENT_SDK_TYPE_HEADER),
+        HeaderName::from_static(CLIENT_SDK_VERSION_HEADER),
+        HeaderName::from_static(CLIENT_TARGET_API_VERSION_HEADER),
+        HeaderName::from_static(APP_NAME_HEADER),
+    ]
+}
+
+/// `origin` as browsers send it in the `origin` header, with a scheme and a host but no path.
+fn parse_origin(origin: &str) -> Result<HeaderValue, Error> {
+    let malformed = || {
+        invalid(format!(
+            "Invalid origin `{origin}`, expected `scheme://host[:port]` such as \
+            `https://app.example.com`, without a path or a trailing slash"
+        ))
+    };
+    let uri = Uri::from_str(origin).map_err(|_| malformed())?;
+    let valid = matches!(uri.scheme_str(), Some("http" | "https"))
+        && uri.path_and_query().is_none()
+        && uri
+            .authority()
+            .is_some_and(|authority| !authority.as_str().contains('@'))
+        && origin == origin.to_ascii_lowercase();
+    if !valid {
+        return Err(malformed());
+    }
+    HeaderValue::from_str(origin).map_err(|_| malformed())
+}
+
+fn invalid(message: String) -> Error {
+    Error::InvalidCorsConfig(message)
+}
+
+/// Methods that requests from some origins are restricted to. Clones share the same
+/// restrictions, and the default restricts no origin.
+#[derive(Clone, Debug, Default)]
+pub struct OriginMethods {
+    allowed: Arc<BTreeMap<String, BTreeSet<String>>>,
+}
+
+impl OriginMethods {
+    /// The origin of a request with `headers`, if it was sent by a browser.
+    pub fn origin(headers: &HeaderMap) -> Option<&str> {
+        headers.get(ORIGIN)?.to_str().ok()
+    }
+
+    /// The error to answer `method` with, if it is called from an `origin` restricted to other
+    /// methods.
+    pub fn check(&self, origin: Option<&str>, method: &str) -> Option<ErrorObjectOwned> {
+        let origin = origin?;
+        let allowed = self
+            .allowed
+            .iter()
+            .find(|(allowed, _)| KeyMaterial::from(*allowed) == KeyMaterial::from(origin))
+            .map(|(_, methods)| methods)
+            .or_else(|| self.allowed.get(ANY_ORIGIN))?;
+        if allowed.contains(method) {
+            return None;
+        }
+        Some(ErrorObjectOwned::owned(
+            METHOD_NOT_ALLOWED_FOR_ORIGIN_CODE,
+            format!("Method `{method}` may not be called from origin `{origin}`"),
+            None::<()>,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METHODS: &[&str] = &["mgo_getObject", "mgo_executeTransactionBlock"];
+
+    fn config(
+        allowed_origins: &[&str],
+        origin_methods: &[(&str, &[&str])],
+    ) -> Result<(CorsLayer, OriginMethods), Error> {
+        CorsConfig {
+            allowed_origins: allowed_origins.iter().map(|o| o.to_string()).collect(),
+            allowed_headers: vec![],
+            origin_methods: origin_methods
+                .iter()
+                .map(|(origin, methods)| {
+                    let methods = methods.iter().map(|m| m.to_string()).collect();
+                    (origin.to_string(), methods)
+                })
+                .collect(),
+        }
+        .build(METHODS)
+    }
+
+    fn error(result: Result<(CorsLayer, OriginMethods), Error>) -> String {
+        result.unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_misconfigured_origins_are_rejected() {
+        for origin in [
+            "app.example.com",
+            "https://app.example.com/",
+            "https://app.example.com/path",
+            "https://user@app.example.com",
+            "https://App.example.com",
+            "ftp://app.example.com",
+        ] {
+            let error = error(config(&[origin], &[]));
+            assert!(
+                error.contains(&format!("Invalid origin `{origin}`")),
+                "{error}"
+            );
+        }
+        let origins = ["https://app.example.com", "http://localhost:3000"];
+        config(&origins, &[]).unwrap();
+
+        let error = error(config(&["*", "https://app.example.com"], &[]));
+        assert!(
+            error.contains("cannot be listed with other origins"),
+            "{error}"
+        );
+
+        let error = error(config(
+            &["https://app.example.com"],
+            &[("https://other.example.com", &["mgo_getObject"])],
+        ));
+        assert!(error.contains("which is not allowed"), "{error}");
+
+        let error = error(config(&[], &[("*", &["mgo_getObjects"])]));
+        assert!(
+            error.contains("Unknown method `mgo_getObjects`"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_origin_method_restrictions() {
+        let (_, origin_methods) = config(
+            &[],
+            &[
+                ("*", &["mgo_getObject"]),
+                (
+                    "https://wallet.example.com",
+                    &["mgo_getObject", "mgo_executeTransactionBlock"],
+                ),
+            ],
+        )
+        .unwrap();
+        let execute = "mgo_executeTransactionBlock";
+        assert!(origin_methods
+            .check(Some("https://wallet.example.com"), execute)
+            .is_none());
+        assert!(origin_methods.check(None, execute).is_none());
+        let error = origin_methods
+            .check(Some("https://dapp.example.com"), execute)
+            .unwrap();
+        assert_eq!(error.code(), METHOD_NOT_ALLOWED_FOR_ORIGIN_CODE);
+        assert!(origin_methods
+            .check(Some("https://dapp.example.com"), "mgo_getObject")
+            .is_none());
+
+        // Without an entry for any origin, only the listed origins are restricted.
+        let restrictions: &[(&str, &[&str])] = &[("https://dapp.example.com", &["mgo_getObject"])];
+        let (_, origin_methods) = config(&[], restrictions).unwrap();
+        assert!(origin_methods
+            .check(Some("https://dapp.example.com"), execute)
+            .is_some());
+        assert!(origin_methods
+            .check(Some("https://wallet.example.com"), execute)
+            .is_none());
+    }
+}
@@ -0,0 +1,118 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use fastcrypto::encoding::Base64;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+use tracing::{instrument, warn};
+
+use mgo_json_rpc_api::{DebugApiOpenRpc, DebugApiServer, JsonRpcMetrics};
+use mgo_json_rpc_types::{GasComparison, MgoTransactionBlockEffectsAPI};
+use mgo_open_rpc::Module;
+use mgo_types::crypto::default_hash;
+use mgo_types::digests::TransactionDigest;
+use mgo_types::transaction::{TransactionData, TransactionDataAPI};
+
+use crate::authority_state::StateRead;
+use crate::error::{Error, MgoRpcInputError};
+use crate::{with_tracing, MgoRpcModule};
+
+/// Runs transactions both as a dry run and in dev-inspect mode, counting and logging those
+/// whose gas differs by more than `threshold_bps` basis points.
+#[derive(Clone)]
+pub struct GasComparator {
+    state: Arc<dyn StateRead>,
+    threshold_bps: u64,
+    metrics: Arc<JsonRpcMetrics>,
+}
+
+impl GasComparator {
+    pub fn new(
+        state: Arc<dyn StateRead>,
+        threshold_bps: u64,
+        metrics: Arc<JsonRpcMetrics>,
+    ) -> Self {
+        Self {
+            state,
+            threshold_bps,
+            metrics,
+        }
+    }
+
+    /// Dev inspects `tx_data` with the sender, gas price, budget, owner and payment of the
+    /// transaction, so that both paths charge the same transaction.
+    pub async fn compare(&self, tx_data: TransactionData) -> Result<GasComparison, Error> {
+        let digest = TransactionDigest::new(default_hash(&tx_data));
+        let (dry_run, _, _, _) = self
+            .state
+            .dry_exec_transaction(tx_data.clone(), digest)
+            .await?;
+        let dev_inspect = self
+            .state
+            .dev_inspect_transaction_block(
+                tx_data.sender(),
+                tx_data.kind().clone(),
+                Some(tx_data.gas_price()),
+                Some(tx_data.gas_budget()),
+                Some(tx_data.gas_owner()),
+                Some(tx_data.gas().to_vec()),
+                None,
+                None,
+            )
+            .await?;
+        let comparison = GasComparison::new(
+            dry_run.effects.gas_cost_summary().clone(),
+            dev_inspect.effects.gas_cost_summary().clone(),
+            self.threshold_bps,
+        );
+
+        self.metrics.dry_run_gas_comparisons.inc();
+        if comparison.discrepancy {
+            self.metrics.dry_run_gas_discrepancies.inc();
+            warn!(
+                tx_digest = %digest,
+                dry_run = ?comparison.dry_run,
+                dev_inspect = ?comparison.dev_inspect,
+                "Dry run and dev inspect gas differ beyond {} bps",
+                self.threshold_bps
+            );
+        }
+        Ok(comparison)
+    }
+}
+
+pub struct DebugApi {
+    comparator: GasComparator,
+}
+
+impl DebugApi {
+    pub fn new(comparator: GasComparator) -> Self {
+        Self { comparator }
+    }
+}
+
+#[async_trait]
+impl DebugApiServer for DebugApi {
+    #[instrument(skip_all)]
+    async fn debug_gas_comparison(&self, tx_bytes: Base64) -> RpcResult<GasComparison> {
+        with_tracing!(async move {
+            let tx_data: TransactionData =
+                bcs::from_bytes(&tx_bytes.to_vec().map_err(MgoRpcInputError::from)?)
+                    .map_err(MgoRpcInputError::from)?;
+            self.comparator.compare(tx_data).await
+        })
+    }
+}
+
+impl MgoRpcModule for DebugApi {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        DebugApiOpenRpc::module_doc()
+    }
+}
@@ -0,0 +1,97 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Values that RPC handlers read on every call but that only change at epoch boundaries, such as
+//! the committee, kept in memory for the epoch they were read in. The epoch is taken from the
+//! epoch store of the caller, so that a value is read again as soon as the epoch store swaps.
+
+use std::sync::Arc;
+
+use arc_swap::ArcSwapOption;
+use mgo_types::committee::EpochId;
+
+pub struct EpochCache<T> {
+    entry: ArcSwapOption<(EpochId, Arc<T>)>,
+}
+
+impl<T> Default for EpochCache<T> {
+    fn default() -> Self {
+        Self {
+            entry: ArcSwapOption::empty(),
+        }
+    }
+}
+
+impl<T> EpochCache<T> {
+    /// The value cached for `epoch`, if any.
+    pub fn get(&self, epoch: EpochId) -> Option<Arc<T>> {
+        self.entry
+            .load()
+            .as_ref()
+            .filter(|entry| entry.0 == epoch)
+            .map(|entry| entry.1.clone())
+    }
+
+    /// Caches `value` for `epoch`, unless a value of a later epoch is already cached, as
+    /// happens when the epoch changes while `value` is being read.
+    pub fn insert(&self, epoch: EpochId, value: Arc<T>) {
+        self.entry.rcu(|current| match current {
+            Some(entry) if entry.0 > epoch => Some(entry.clone()),
+            _ => Some(Arc::new((epoch, value.clone()))),
+        });
+    }
+
+    /// The value cached for `epoch`, or the one returned by `read`, which is then cached.
+    pub fn get_or_try_insert_with<E>(
+        &self,
+        epoch: EpochId,
+        read: impl FnOnce() -> Result<T, E>,
+    ) -> Result<Arc<T>, E> {
+        if let Some(value) = self.get(epoch) {
+            return Ok(value);
+        }
+        let value = Arc::new(read()?);
+        self.insert(epoch, value.clone());
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::convert::Infallible;
+
+    use super::*;
+
+    #[test]
+    fn test_reads_once_per_epoch() {
+        let cache = EpochCache::default();
+        let reads = Cell::new(0);
+        for epoch in 0..3 {
+            for _ in 0..1000 {
+                let value = cache
+                    .get_or_try_insert_with(epoch, || {
+                        reads.set(reads.get() + 1);
+                        Ok::<_, Infallible>(epoch * 10)
+                    })
+                    .unwrap();
+                assert_eq!(*value, epoch * 10);
+            }
+        }
+        assert_eq!(reads.get(), 3);
+    }
+
+    #[test]
+    fn test_keeps_later_epoch() {
+        let cache = EpochCache::default();
+        cache.insert(2, Arc::new("epoch 2"));
+        // A read started before the epoch changed must not replace the new epoch's value.
+        cache.insert(1, Arc::new("epoch 1"));
+        assert_eq!(cache.get(1), None);
+        assert_eq!(cache.get(2).as_deref(), Some(&"epoch 2"));
+
+        cache.insert(3, Arc::new("epoch 3"));
+        assert_eq!(cache.get(2), None);
+        assert_eq!(cache.get(3).as_deref(), Some(&"epoch 3"));
+    }
+}
@@ -7,14 +7,19 @@ use itertools::Itertools;
 use jsonrpsee::core::Error as RpcError;
 use jsonrpsee::types::error::{CallError, INTERNAL_ERROR_CODE};
 use jsonrpsee::types::ErrorObject;
+use serde_json::{json, Value};
 use std::collections::BTreeMap;
-use mgo_json_rpc_api::{TRANSACTION_EXECUTION_CLIENT_ERROR_CODE, TRANSIENT_ERROR_CODE};
+use mgo_json_rpc_api::{
+    ClientErrorCode, PageLimitError, TRANSACTION_EXECUTION_CLIENT_ERROR_CODE, TRANSIENT_ERROR_CODE,
+};
+use mgo_types::digests::TransactionDigest;
 use mgo_types::error::{MgoError, MgoObjectResponseError, UserInputError};
 use mgo_types::quorum_driver_types::QuorumDriverError;
 use thiserror::Error;
 use tokio::task::JoinError;
+use tracing::error;
 
-use crate::authority_state::StateReadError;
+use crate::authority_state::{StateReadClientError, StateReadError};
 
 pub type RpcInterimResult<T = ()> = Result<T, Error>;
 
@@ -58,12 +63,25 @@ pub enum Error {
     #[error(transparent)]
     MgoRpcInputError(#[from] MgoRpcInputError),
 
-    // TODO(wlmyng): convert StateReadError::Internal message to generic internal error message.
     #[error(transparent)]
     StateReadError(#[from] StateReadError),
 
     #[error("Unsupported Feature: {0}")]
     UnsupportedFeature(String),
+
+    #[error("Invalid CORS configuration: {0}")]
+    InvalidCorsConfig(String),
+
+    #[error("Invalid response signing configuration: {0}")]
+    InvalidResponseSigningConfig(String),
+
+    #[error(
+        "Transaction {digest} was submitted, but no checkpoint included it within {timeout_ms}ms"
+    )]
+    CheckpointInclusionTimeout {
+        digest: TransactionDigest,
+        timeout_ms: u64,
+    },
 }
 
 impl From<MgoError> for Error {
@@ -81,10 +99,10 @@ impl From<MgoError> for Error {
 }
 
 impl From<Error> for RpcError {
-    /// `InvalidParams`/`INVALID_PARAMS_CODE` for client errors.
+    /// A [`ClientErrorCode`] or `InvalidParams`/`INVALID_PARAMS_CODE` for client errors.
     fn from(e: Error) -> RpcError {
         match e {
-            Error::UserInputError(_) => RpcError::Call(CallError::InvalidParams(e.into())),
+            Error::UserInputError(err) => user_input_client_error(&err),
             Error::UnsupportedFeature(_) => RpcError::Call(CallError::InvalidParams(e.into())),
             Error::MgoObjectResponseError(err) => match err {
                 MgoObjectResponseError::NotExists { .. }
@@ -95,7 +113,17 @@ impl From<Error> for RpcError {
                 }
                 _ => RpcError::Call(CallError::Failed(err.into())),
             },
-            Error::MgoRpcInputError(err) => RpcError::Call(CallError::InvalidParams(err.into())),
+            Error::MgoRpcInputError(err) => err.into(),
+            // The transaction may still be included later, so clients must not resubmit it as a
+            // new one.
+            Error::CheckpointInclusionTimeout { digest, .. } => {
+                let error_object = ErrorObject::owned(
+                    TRANSIENT_ERROR_CODE,
+                    e.to_string(),
+                    Some(json!({ "submitted": true, "digest": digest })),
+                );
+                RpcError::Call(CallError::Custom(error_object))
+            }
             Error::MgoError(mgo_error) => match mgo_error {
                 MgoError::TransactionNotFound { .. }
                 | MgoError::TransactionsNotFound { .. }
@@ -105,11 +133,14 @@ impl From<Error> for RpcError {
                 _ => RpcError::Call(CallError::Failed(mgo_error.into())),
             },
             Error::StateReadError(err) => match err {
-                StateReadError::Client(_) => RpcError::Call(CallError::InvalidParams(err.into())),
-                _ => {
+                StateReadError::Client(err) => state_read_client_error(&err),
+                StateReadError::Internal(err) => {
+                    // The details of internal errors are only logged, they mean nothing to
+                    // clients and may leak the internals of the node.
+                    error!("Internal error while reading state: {err:?}");
                     let error_object = ErrorObject::owned(
-                        jsonrpsee::types::error::INTERNAL_ERROR_CODE,
-                        err.to_string(),
+                        INTERNAL_ERROR_CODE,
+                        "Internal error occurred while reading state.",
                         None::<()>,
                     );
                     RpcError::Call(CallError::Custom(error_object))
@@ -262,6 +293,21 @@ pub enum MgoRpcInputError {
     #[error("{0}")]
     CannotParseMgoStructTag(String),
 
+    #[error(
+        "Cannot select coins of type {coin_type} worth {target_amount}: {}, {shortfall} short",
+        describe_selected_coins(.coin_count, .max_coins_reached, .available)
+    )]
+    InsufficientCoinBalance {
+        coin_type: String,
+        target_amount: u64,
+        available: u64,
+        shortfall: u64,
+        coin_count: usize,
+        /// Whether more coins would have been needed than could be selected, rather than the
+        /// owner not having enough.
+        max_coins_reached: bool,
+    },
+
     #[error(transparent)]
     Base64(#[from] eyre::Report),
 
@@ -280,8 +326,182 @@ pub enum MgoRpcInputError {
 
 impl From<MgoRpcInputError> for RpcError {
     fn from(e: MgoRpcInputError) -> Self {
-        RpcError::Call(CallError::InvalidParams(e.into()))
+        match &e {
+            MgoRpcInputError::UserInputError(err) => user_input_client_error(err),
+            MgoRpcInputError::SizeLimitExceeded(limit) => client_error(
+                ClientErrorCode::LimitExceeded,
+                e.to_string(),
+                json!({ "max": limit }),
+            ),
+            MgoRpcInputError::Anyhow(err) => match err.downcast_ref::<PageLimitError>() {
+                Some(PageLimitError::TooLarge { limit, max }) => client_error(
+                    ClientErrorCode::LimitExceeded,
+                    e.to_string(),
+                    json!({ "limit": limit, "max": max }),
+                ),
+                Some(PageLimitError::Zero) => {
+                    client_error(ClientErrorCode::InvalidInput, e.to_string(), json!({}))
+                }
+                None => RpcError::Call(CallError::InvalidParams(e.into())),
+            },
+            MgoRpcInputError::InsufficientCoinBalance {
+                coin_type,
+                target_amount,
+                available,
+                shortfall,
+                ..
+            } => client_error(
+                ClientErrorCode::InvalidInput,
+                e.to_string(),
+                json!({
+                    "coin_type": coin_type,
+                    "target_amount": target_amount,
+                    "available": available,
+                    "shortfall": shortfall,
+                }),
+            ),
+            _ => RpcError::Call(CallError::InvalidParams(e.into())),
+        }
+    }
+}
+
+fn describe_selected_coins(
+    coin_count: &usize,
+    max_coins_reached: &bool,
+    available: &u64,
+) -> String {
+    match (coin_count, max_coins_reached) {
+        (0, _) => "no coins are owned".to_string(),
+        (_, true) => format!("the {coin_count} largest coins add up to {available}"),
+        (_, false) => format!("the {coin_count} coins owned add up to {available}"),
+    }
+}
+
+/// Error with the given [`ClientErrorCode`], whose data names the code next to `fields`.
+fn client_error(code: ClientErrorCode, message: String, fields: Value) -> RpcError {
+    let mut data = serde_json::Map::new();
+    data.insert("code".to_string(), code.name().into());
+    if let Value::Object(fields) = fields {
+        data.extend(fields);
     }
+    let error_object = ErrorObject::owned(code.code(), message, Some(data));
+    RpcError::Call(CallError::Custom(error_object))
+}
+
+fn state_read_client_error(error: &StateReadClientError) -> RpcError {
+    let (code, fields) = match error {
+        StateReadClientError::UserInputError(err)
+        | StateReadClientError::MgoError(MgoError::UserInputError { error: err }) => {
+            return user_input_client_error(err)
+        }
+        StateReadClientError::MgoError(err) => match err {
+            MgoError::TransactionNotFound { digest } => (
+                ClientErrorCode::TransactionNotFound,
+                json!({ "digest": digest }),
+            ),
+            MgoError::IndexStoreNotAvailable | MgoError::UnsupportedFeatureError { .. } => {
+                (ClientErrorCode::Unsupported, json!({}))
+            }
+            _ => (ClientErrorCode::InvalidInput, json!({})),
+        },
+        StateReadClientError::CoinTypeNotFound(coin_type) => (
+            ClientErrorCode::CoinTypeNotFound,
+            json!({ "coin_type": coin_type }),
+        ),
+        StateReadClientError::TreasuryCapNotFound(coin_type) => (
+            ClientErrorCode::TreasuryCapNotFound,
+            json!({ "coin_type": coin_type }),
+        ),
+        StateReadClientError::CheckpointNotFound(checkpoint) => (
+            ClientErrorCode::CheckpointNotFound,
+            json!({ "checkpoint": checkpoint }),
+        ),
+        StateReadClientError::TransactionNotInCheckpoint(cursor, checkpoint) => (
+            ClientErrorCode::InvalidCursor,
+            json!({ "cursor": cursor, "checkpoint": checkpoint }),
+        ),
+        StateReadClientError::EpochNotStarted(epoch)
+        | StateReadClientError::CommitteeNotFound(epoch) => {
+            (ClientErrorCode::EpochNotFound, json!({ "epoch": epoch }))
+        }
+    };
+    client_error(code, error.to_string(), fields)
+}
+
+fn user_input_client_error(error: &UserInputError) -> RpcError {
+    // Matched exhaustively, so that new variants are given a code on purpose.
+    let code = match error {
+        UserInputError::ObjectNotFound { .. } => ClientErrorCode::ObjectNotFound,
+        UserInputError::ObjectDeleted { .. } => ClientErrorCode::ObjectDeleted,
+        UserInputError::ObjectVersionUnavailableForConsumption { .. }
+        | UserInputError::ObjectSequenceNumberTooHigh { .. } => {
+            ClientErrorCode::ObjectVersionUnavailable
+        }
+        UserInputError::DependentPackageNotFound { .. } => ClientErrorCode::PackageNotFound,
+        UserInputError::TransactionCursorNotFound(_) => ClientErrorCode::InvalidCursor,
+        UserInputError::SizeLimitExceeded { .. }
+        | UserInputError::MaxPublishCountExceeded { .. } => ClientErrorCode::LimitExceeded,
+        UserInputError::VerifiedCheckpointNotFound(_)
+        | UserInputError::VerifiedCheckpointDigestNotFound(_)
+        | UserInputError::LatestCheckpointSequenceNumberNotFound
+        | UserInputError::CheckpointContentsNotFound(_) => ClientErrorCode::CheckpointNotFound,
+        UserInputError::GenesisTransactionNotFound => ClientErrorCode::TransactionNotFound,
+        UserInputError::MissingGasPayment
+        | UserInputError::GasObjectNotOwnedObject { .. }
+        | UserInputError::GasBudgetTooHigh { .. }
+        | UserInputError::GasBudgetTooLow { .. }
+        | UserInputError::GasBalanceTooLow { .. }
+        | UserInputError::GasPriceUnderRGP { .. }
+        | UserInputError::GasPriceTooHigh { .. }
+        | UserInputError::InvalidGasObject { .. }
+        | UserInputError::InsufficientBalanceToCoverMinimalGas
+        | UserInputError::UnexpectedGasPaymentObject => ClientErrorCode::InvalidGas,
+        UserInputError::IncorrectUserSignature { .. } => ClientErrorCode::InvalidSignature,
+        UserInputError::TransactionDenied { .. }
+        | UserInputError::BlockedMoveFunction
+        | UserInputError::AddressDeniedForCoin { .. } => ClientErrorCode::TransactionDenied,
+        UserInputError::Unsupported(_) | UserInputError::UnsupportedSponsoredTransactionKind => {
+            ClientErrorCode::Unsupported
+        }
+        UserInputError::MutableObjectUsedMoreThanOnce { .. }
+        | UserInputError::ObjectInputArityViolation
+        | UserInputError::PackageVerificationTimedout { .. }
+        | UserInputError::ImmutableParameterExpectedError { .. }
+        | UserInputError::InvalidChildObjectArgument { .. }
+        | UserInputError::InvalidObjectDigest { .. }
+        | UserInputError::InvalidSequenceNumber
+        | UserInputError::MovePackageAsObject { .. }
+        | UserInputError::MoveObjectAsPackage { .. }
+        | UserInputError::NotSharedObjectError
+        | UserInputError::DuplicateObjectRefInput
+        | UserInputError::InvalidBatchTransaction { .. }
+        | UserInputError::EmptyInputCoins
+        | UserInputError::SharedObjectStartingVersionMismatch
+        | UserInputError::TransferObjectWithoutPublicTransferError { .. }
+        | UserInputError::EmptyCommandInput
+        | UserInputError::MoveFunctionInputError(_)
+        | UserInputError::InaccessibleSystemObject { .. }
+        | UserInputError::MutableParameterExpected { .. } => ClientErrorCode::InvalidInput,
+    };
+    let fields = match error {
+        UserInputError::ObjectDeleted {
+            object_ref: (object_id, version, digest),
+        } => json!({ "object_id": object_id, "version": version, "digest": digest }),
+        UserInputError::TransactionCursorNotFound(cursor) => json!({ "cursor": cursor }),
+        UserInputError::VerifiedCheckpointNotFound(checkpoint) => {
+            json!({ "checkpoint": checkpoint })
+        }
+        // Struct variants serialize as `{ "Variant": { fields } }`, whose fields are kept as is.
+        _ => match serde_json::to_value(error) {
+            Ok(Value::Object(variant)) => variant
+                .into_iter()
+                .next()
+                .map(|(_, f)| f)
+                .unwrap_or_default(),
+            _ => json!({}),
+        },
+    };
+    client_error(code, error.to_string(), fields)
 }
 
 #[cfg(test)]
@@ -490,5 +710,125 @@ mod tests {
             let expected_message = expect!["Transaction is not processed because 10 of validators by stake are overloaded with certificates pending execution."];
             expected_message.assert_eq(error_object.message());
         }
+
+        #[test]
+        fn test_checkpoint_inclusion_timeout() {
+            let rpc_error: RpcError = Error::CheckpointInclusionTimeout {
+                digest: TransactionDigest::default(),
+                timeout_ms: 5000,
+            }
+            .into();
+
+            let error_object: ErrorObjectOwned = rpc_error.into();
+            let expected_code = expect!["-32050"];
+            expected_code.assert_eq(&error_object.code().to_string());
+            let expected_message = expect!["Transaction 11111111111111111111111111111111 was submitted, but no checkpoint included it within 5000ms"];
+            expected_message.assert_eq(error_object.message());
+            let expected_data =
+                expect![[r#"{"submitted":true,"digest":"11111111111111111111111111111111"}"#]];
+            expected_data.assert_eq(&error_object.data().unwrap().to_string());
+        }
+    }
+
+    mod match_client_error_tests {
+        use super::*;
+        use crate::authority_state::{StateReadClientError, StateReadInternalError};
+        use mgo_json_rpc_api::validate_limit;
+
+        #[test]
+        fn test_object_deleted() {
+            let state_read_error = StateReadError::from(UserInputError::ObjectDeleted {
+                object_ref: test_object_ref(),
+            });
+
+            let rpc_error: RpcError = Error::StateReadError(state_read_error).into();
+
+            let error_object: ErrorObjectOwned = rpc_error.into();
+            assert_eq!(error_object.code(), ClientErrorCode::ObjectDeleted.code());
+            let expected_data = expect![[
+                r#"{"code":"OBJECT_DELETED","object_id":"0x0000000000000000000000000000000000000000000000000000000000000000","version":0,"digest":"11111111111111111111111111111111"}"#
+            ]];
+            expected_data.assert_eq(&error_object.data().unwrap().to_string());
+        }
+
+        #[test]
+        fn test_object_not_found() {
+            let user_input_error = UserInputError::ObjectNotFound {
+                object_id: ObjectID::ZERO,
+                version: Some(SequenceNumber::from_u64(3)),
+            };
+
+            let rpc_error: RpcError = Error::UserInputError(user_input_error).into();
+
+            let error_object: ErrorObjectOwned = rpc_error.into();
+            assert_eq!(error_object.code(), ClientErrorCode::ObjectNotFound.code());
+            let expected_data = expect![[
+                r#"{"code":"OBJECT_NOT_FOUND","object_id":"0x0000000000000000000000000000000000000000000000000000000000000000","version":3}"#
+            ]];
+            expected_data.assert_eq(&error_object.data().unwrap().to_string());
+        }
+
+        #[test]
+        fn test_invalid_cursor() {
+            let state_read_error = StateReadError::Client(
+                StateReadClientError::TransactionNotInCheckpoint(TransactionDigest::default(), 7),
+            );
+
+            let rpc_error: RpcError = Error::StateReadError(state_read_error).into();
+
+            let error_object: ErrorObjectOwned = rpc_error.into();
+            assert_eq!(error_object.code(), ClientErrorCode::InvalidCursor.code());
+            let expected_message =
+                expect!["Transaction 11111111111111111111111111111111 is not part of checkpoint 7"];
+            expected_message.assert_eq(error_object.message());
+            let expected_data = expect![[
+                r#"{"code":"INVALID_CURSOR","cursor":"11111111111111111111111111111111","checkpoint":7}"#
+            ]];
+            expected_data.assert_eq(&error_object.data().unwrap().to_string());
+        }
+
+        #[test]
+        fn test_committee_not_found() {
+            let state_read_error =
+                StateReadError::Client(StateReadClientError::CommitteeNotFound(3));
+
+            let rpc_error: RpcError = Error::StateReadError(state_read_error).into();
+
+            let error_object: ErrorObjectOwned = rpc_error.into();
+            assert_eq!(error_object.code(), ClientErrorCode::EpochNotFound.code());
+            let expected_message = expect!["The committee of epoch 3 is not stored on this node"];
+            expected_message.assert_eq(error_object.message());
+            let expected_data = expect![[r#"{"code":"EPOCH_NOT_FOUND","epoch":3}"#]];
+            expected_data.assert_eq(&error_object.data().unwrap().to_string());
+        }
+
+        #[test]
+        fn test_limit_exceeded() {
+            let limit_error = validate_limit(Some(51), 50).unwrap_err();
+
+            let rpc_error: RpcError = Error::MgoRpcInputError(limit_error.into()).into();
+
+            let error_object: ErrorObjectOwned = rpc_error.into();
+            assert_eq!(error_object.code(), ClientErrorCode::LimitExceeded.code());
+            let expected_message = expect!["Page size limit 51 exceeds max limit 50"];
+            expected_message.assert_eq(error_object.message());
+            let expected_data = expect![[r#"{"code":"LIMIT_EXCEEDED","limit":51,"max":50}"#]];
+            expected_data.assert_eq(&error_object.data().unwrap().to_string());
+        }
+
+        #[test]
+        fn test_internal_error_is_not_leaked() {
+            let state_read_error = StateReadError::Internal(StateReadInternalError::MgoError(
+                MgoError::Storage("secret rocksdb path".to_string()),
+            ));
+
+            let rpc_error: RpcError = Error::StateReadError(state_read_error).into();
+
+            let error_object: ErrorObjectOwned = rpc_error.into();
+            assert_eq!(error_object.code(), INTERNAL_ERROR_CODE);
+            let expected_message = expect!["Internal error occurred while reading state."];
+            expected_message.assert_eq(error_object.message());
+            assert!(error_object.data().is_none());
+        }
     }
 }
@@ -8,7 +8,6 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use cached::proc_macro::cached;
 use cached::SizedCache;
-use itertools::Itertools;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::RpcModule;
 use tracing::{info, instrument};
@@ -16,23 +15,24 @@ use tracing::{info, instrument};
 use mango_metrics::spawn_monitored_task;
 use mgo_core::authority::AuthorityState;
 use mgo_json_rpc_api::{GovernanceReadApiOpenRpc, GovernanceReadApiServer, JsonRpcMetrics};
-use mgo_json_rpc_types::{DelegatedStake, Stake, StakeStatus};
+use mgo_json_rpc_types::{
+    DelegatedStake, OwnedStakesSummary, Stake, StakeStatus, StorageRebateEstimate,
+};
 use mgo_json_rpc_types::{MgoCommittee, ValidatorApy, ValidatorApys};
 use mgo_open_rpc::Module;
 use mgo_types::base_types::{ObjectID, MgoAddress};
-use mgo_types::committee::EpochId;
-use mgo_types::dynamic_field::get_dynamic_field_from_store;
-use mgo_types::error::{MgoError, UserInputError};
+use mgo_types::committee::{Committee, EpochId};
+use mgo_types::error::UserInputError;
 use mgo_types::governance::StakedMgo;
-use mgo_types::id::ID;
 use mgo_types::object::ObjectRead;
 use mgo_types::mgo_serde::BigInt;
 use mgo_types::mgo_system_state::mgo_system_state_summary::MgoSystemStateSummary;
 use mgo_types::mgo_system_state::PoolTokenExchangeRate;
 use mgo_types::mgo_system_state::MgoSystemStateTrait;
-use mgo_types::mgo_system_state::{get_validator_from_table, MgoSystemState};
+use mgo_types::mgo_system_state::MgoSystemState;
 
 use crate::authority_state::StateRead;
+use crate::epoch_cache::EpochCache;
 use crate::error::{Error, RpcInterimResult, MgoRpcInputError};
 use crate::{with_tracing, ObjectProvider, MgoRpcModule};
 
@@ -40,11 +40,18 @@ use crate::{with_tracing, ObjectProvider, MgoRpcModule};
 pub struct GovernanceReadApi {
     state: Arc<dyn StateRead>,
     pub metrics: Arc<JsonRpcMetrics>,
+    system_state: Arc<EpochCache<MgoSystemState>>,
+    committee: Arc<EpochCache<Committee>>,
 }
 
 impl GovernanceReadApi {
     pub fn new(state: Arc<AuthorityState>, metrics: Arc<JsonRpcMetrics>) -> Self {
-        Self { state, metrics }
+        Self {
+            state,
+            metrics,
+            system_state: Default::default(),
+            committee: Default::default(),
+        }
     }
 
     async fn get_staked_mgo(&self, owner: MgoAddress) -> Result<Vec<StakedMgo>, Error> {
@@ -109,7 +116,7 @@ impl GovernanceReadApi {
         self.get_delegated_stakes(stakes).await
     }
 
-    async fn get_stakes(&self, owner: MgoAddress) -> Result<Vec<DelegatedStake>, Error> {
+    pub(crate) async fn get_stakes(&self, owner: MgoAddress) -> Result<Vec<DelegatedStake>, Error> {
         let timer = self.metrics.get_stake_mgo_latency.start_timer();
         let stakes = self.get_staked_mgo(owner).await?;
         if stakes.is_empty() {
@@ -126,6 +133,51 @@ impl GovernanceReadApi {
         .await?
     }
 
+    /// Stakes of `owner` grouped by validator, named after the active validators of the
+    /// system state the rewards are estimated with.
+    async fn get_owned_staked_objects_summary(
+        &self,
+        owner: MgoAddress,
+    ) -> Result<OwnedStakesSummary, Error> {
+        let stakes = self.get_stakes(owner).await?;
+        let system_state_summary = self
+            .get_system_state()?
+            .as_ref()
+            .clone()
+            .into_mgo_system_state_summary();
+        Ok(OwnedStakesSummary::new(
+            owner,
+            system_state_summary.epoch,
+            stakes,
+            &system_state_summary.active_validators,
+        ))
+    }
+
+    /// Storage rebate of the latest version of `object_id` at the storage price of the current
+    /// epoch.
+    async fn estimate_storage_rebate(
+        &self,
+        object_id: ObjectID,
+        new_contents_size: Option<u64>,
+    ) -> Result<StorageRebateEstimate, Error> {
+        let state = self.state.clone();
+        let object_read =
+            spawn_monitored_task!(async move { state.get_object_read(&object_id) }).await??;
+        let ObjectRead::Exists(_, object, _) = object_read else {
+            return Err(Error::UserInputError(UserInputError::ObjectNotFound {
+                object_id,
+                version: None,
+            }));
+        };
+        let epoch_store = self.state.load_epoch_store_one_call_per_task();
+        Ok(StorageRebateEstimate::new(
+            &object,
+            epoch_store.protocol_config(),
+            epoch_store.epoch(),
+            new_contents_size,
+        ))
+    }
+
     async fn get_delegated_stakes(
         &self,
         stakes: Vec<(StakedMgo, bool)>,
@@ -142,10 +194,13 @@ impl GovernanceReadApi {
         );
 
         let system_state = self.get_system_state()?;
-        let system_state_summary: MgoSystemStateSummary =
-            system_state.clone().into_mgo_system_state_summary();
+        let system_state_summary: MgoSystemStateSummary = system_state
+            .as_ref()
+            .clone()
+            .into_mgo_system_state_summary();
 
-        let rates = exchange_rates(&self.state, system_state_summary.epoch)
+        // Rewards are estimated from the rate of the epoch stakes were activated in.
+        let rates = exchange_rates(&self.state, system_state_summary.epoch, u64::MAX)
             .await?
             .into_iter()
             .map(|rates| (rates.pool_id, rates))
@@ -206,8 +261,40 @@ impl GovernanceReadApi {
         Ok(delegated_stakes)
     }
 
-    fn get_system_state(&self) -> Result<MgoSystemState, Error> {
-        Ok(self.state.get_system_state()?)
+    /// System state of the current epoch, read from the database once per epoch.
+    fn get_system_state(&self) -> Result<Arc<MgoSystemState>, Error> {
+        let epoch = self.state.load_epoch_store_one_call_per_task().epoch();
+        match self.system_state.get(epoch) {
+            Some(system_state) => Ok(system_state),
+            None => self.get_latest_system_state(),
+        }
+    }
+
+    /// System state read from the database, including what changed since the cached one was
+    /// read, such as pending stakes. It replaces the cached system state of the epoch.
+    pub fn get_latest_system_state(&self) -> Result<Arc<MgoSystemState>, Error> {
+        let epoch = self.state.load_epoch_store_one_call_per_task().epoch();
+        let system_state = Arc::new(self.state.get_system_state()?);
+        // The epoch store only swaps after the system state has advanced to the next epoch.
+        if system_state.epoch() == epoch {
+            self.system_state.insert(epoch, system_state.clone());
+        }
+        Ok(system_state)
+    }
+
+    /// Committee of `epoch`, or of the current epoch if `None`. Only the committee of the current
+    /// epoch is cached.
+    fn get_committee(&self, epoch: Option<BigInt<u64>>) -> Result<Arc<Committee>, Error> {
+        let current_epoch = self.state.load_epoch_store_one_call_per_task().epoch();
+        match epoch {
+            Some(epoch) if *epoch != current_epoch => {
+                Ok(Arc::new(self.state.get_or_latest_committee(Some(epoch))?))
+            }
+            _ => Ok(self.committee.get_or_try_insert_with(current_epoch, || {
+                self.state
+                    .get_or_latest_committee(Some(current_epoch.into()))
+            })?),
+        }
     }
 }
 
@@ -226,13 +313,19 @@ impl GovernanceReadApiServer for GovernanceReadApi {
         with_tracing!(async move { self.get_stakes(owner).await })
     }
 
+    #[instrument(skip(self))]
+    async fn get_owned_staked_objects_summary(
+        &self,
+        owner: MgoAddress,
+    ) -> RpcResult<OwnedStakesSummary> {
+        with_tracing!(async move { self.get_owned_staked_objects_summary(owner).await })
+    }
+
     #[instrument(skip(self))]
     async fn get_committee_info(&self, epoch: Option<BigInt<u64>>) -> RpcResult<MgoCommittee> {
         with_tracing!(async move {
-            self.state
-                .get_or_latest_committee(epoch)
-                .map(|committee| committee.into())
-                .map_err(Error::from)
+            self.get_committee(epoch)
+                .map(|committee| committee.as_ref().clone().into())
         })
     }
 
@@ -240,9 +333,9 @@ impl GovernanceReadApiServer for GovernanceReadApi {
     async fn get_latest_mgo_system_state(&self) -> RpcResult<MgoSystemStateSummary> {
         with_tracing!(async move {
             Ok(self
-                .state
-                .get_system_state()
-                .map_err(Error::from)?
+                .get_system_state()?
+                .as_ref()
+                .clone()
                 .into_mgo_system_state_summary())
         })
     }
@@ -261,9 +354,13 @@ impl GovernanceReadApiServer for GovernanceReadApi {
         let system_state_summary: MgoSystemStateSummary =
             self.get_latest_mgo_system_state().await?;
 
-        let exchange_rate_table = exchange_rates(&self.state, system_state_summary.epoch)
-            .await
-            .map_err(Error::from)?;
+        let exchange_rate_table = exchange_rates(
+            &self.state,
+            system_state_summary.epoch,
+            VALIDATOR_APY_EPOCHS,
+        )
+        .await
+        .map_err(Error::from)?;
 
         let apys = calculate_apys(
             system_state_summary.stake_subsidy_start_epoch,
@@ -275,8 +372,27 @@ impl GovernanceReadApiServer for GovernanceReadApi {
             epoch: system_state_summary.epoch,
         })
     }
+
+    #[instrument(skip(self))]
+    async fn estimate_storage_rebate(
+        &self,
+        object_id: ObjectID,
+        new_contents_size: Option<BigInt<u64>>,
+    ) -> RpcResult<StorageRebateEstimate> {
+        with_tracing!(async move {
+            self.estimate_storage_rebate(object_id, new_contents_size.map(|size| *size))
+                .await
+        })
+    }
 }
 
+/// Number of epochs before the current one the APY of validators is averaged over.
+pub const VALIDATOR_APY_EPOCHS: u64 = 30;
+
+/// APY of every active validator, as the geometric mean of the returns of its pool over the
+/// last [VALIDATOR_APY_EPOCHS] epochs it was active in since the stake subsidy started, with
+/// `exchange_rate_table` newest first. Validators with fewer than two consecutive rates, such
+/// as those that just joined, have an APY of 0.
 pub fn calculate_apys(
     stake_subsidy_start_epoch: u64,
     exchange_rate_table: Vec<ValidatorExchangeRates>,
@@ -285,31 +401,35 @@ pub fn calculate_apys(
 
     for rates in exchange_rate_table.into_iter().filter(|r| r.active) {
         // we start the apy calculation from the epoch when the stake subsidy starts
-        let exchange_rates = rates.rates.into_iter().filter_map(|(epoch, rate)| {
-            if epoch >= stake_subsidy_start_epoch {
-                Some(rate)
-            } else {
-                None
-            }
-        });
+        let exchange_rates = rates
+            .rates
+            .into_iter()
+            .filter(|(epoch, _)| *epoch >= stake_subsidy_start_epoch)
+            .collect::<Vec<_>>();
+
+        // Returns are only measured across consecutive epochs, which skips the epochs the
+        // validator was inactive in.
+        let returns = exchange_rates
+            .windows(2)
+            .filter_map(|pair| {
+                let [(epoch_e_1, rate_e_1), (epoch_e, rate_e)] = pair else {
+                    return None;
+                };
+                (*epoch_e + 1 == *epoch_e_1).then(|| rate_e.rate() / rate_e_1.rate())
+            })
+            .filter(|r| {
+                let apy = annualize(*r);
+                apy > 0.0 && apy < 0.1
+            })
+            .take(VALIDATOR_APY_EPOCHS as usize)
+            .collect::<Vec<_>>();
 
-        // we need at least 2 data points to calculate apy
-        let average_apy = if exchange_rates.clone().count() >= 2 {
-            // rates are sorted by epoch in descending order.
-            let er_e = exchange_rates.clone().dropping(1);
-            // rate e+1
-            let er_e_1 = exchange_rates.dropping_back(1);
-            let apys = er_e
-                .zip(er_e_1)
-                .map(calculate_apy)
-                .filter(|apy| *apy > 0.0 && *apy < 0.1)
-                .take(30)
-                .collect::<Vec<_>>();
-
-            let apy_counts = apys.len() as f64;
-            apys.iter().sum::<f64>() / apy_counts
-        } else {
+        let average_apy = if returns.is_empty() {
             0.0
+        } else {
+            let mean_log_return =
+                returns.iter().map(|r| r.ln()).sum::<f64>() / returns.len() as f64;
+            annualize(mean_log_return.exp())
         };
         apys.push(ValidatorApy {
             address: rates.address,
@@ -351,96 +471,25 @@ fn test_apys_calculation_filter_outliers() {
     }
 }
 
-// APY_e = (ER_e+1 / ER_e) ^ 365
-fn calculate_apy((rate_e, rate_e_1): (PoolTokenExchangeRate, PoolTokenExchangeRate)) -> f64 {
-    (rate_e.rate() / rate_e_1.rate()).powf(365.0) - 1.0
+// APY = (ER_e / ER_e+1) ^ 365 - 1, from the return of an epoch
+fn annualize(epoch_return: f64) -> f64 {
+    epoch_return.powf(365.0) - 1.0
 }
 
-/// Cached exchange rates for validators for the given epoch, the cache size is 1, it will be cleared when the epoch changes.
-/// rates are in descending order by epoch.
+/// Cached exchange rates of validators over the current epoch and the `epochs_back` before
+/// it, newest first. The cache is cleared when the epoch changes.
 #[cached(
-    type = "SizedCache<EpochId, Vec<ValidatorExchangeRates>>",
-    create = "{ SizedCache::with_size(1) }",
-    convert = "{ _current_epoch }",
+    type = "SizedCache<(EpochId, u64), Vec<ValidatorExchangeRates>>",
+    create = "{ SizedCache::with_size(2) }",
+    convert = "{ (_current_epoch, epochs_back) }",
     result = true
 )]
 async fn exchange_rates(
     state: &Arc<dyn StateRead>,
     _current_epoch: EpochId,
+    epochs_back: u64,
 ) -> RpcInterimResult<Vec<ValidatorExchangeRates>> {
-    let system_state = state.get_system_state()?;
-    let system_state_summary: MgoSystemStateSummary = system_state.into_mgo_system_state_summary();
-
-    // Get validator rate tables
-    let mut tables = vec![];
-
-    for validator in system_state_summary.active_validators {
-        tables.push((
-            validator.mgo_address,
-            validator.staking_pool_id,
-            validator.exchange_rates_id,
-            validator.exchange_rates_size,
-            true,
-        ));
-    }
-
-    // Get inactive validator rate tables
-    for df in state.get_dynamic_fields(
-        system_state_summary.inactive_pools_id,
-        None,
-        system_state_summary.inactive_pools_size as usize,
-    )? {
-        let pool_id: ID =
-            bcs::from_bytes(&df.1.bcs_name).map_err(|e| MgoError::ObjectDeserializationError {
-                error: e.to_string(),
-            })?;
-        let validator = get_validator_from_table(
-            state.get_object_store().as_ref(),
-            system_state_summary.inactive_pools_id,
-            &pool_id,
-        )?; // TODO(wlmyng): roll this into StateReadError
-        tables.push((
-            validator.mgo_address,
-            validator.staking_pool_id,
-            validator.exchange_rates_id,
-            validator.exchange_rates_size,
-            false,
-        ));
-    }
-
-    let mut exchange_rates = vec![];
-    // Get exchange rates for each validator
-    for (address, pool_id, exchange_rates_id, exchange_rates_size, active) in tables {
-        let mut rates = state
-            .get_dynamic_fields(exchange_rates_id, None, exchange_rates_size as usize)?
-            .into_iter()
-            .map(|df| {
-                let epoch: EpochId = bcs::from_bytes(&df.1.bcs_name).map_err(|e| {
-                    MgoError::ObjectDeserializationError {
-                        error: e.to_string(),
-                    }
-                })?;
-
-                let exchange_rate: PoolTokenExchangeRate = get_dynamic_field_from_store(
-                    &state.get_object_store().as_ref(),
-                    exchange_rates_id,
-                    &epoch,
-                )?;
-
-                Ok::<_, MgoError>((epoch, exchange_rate))
-            })
-            .collect::<Result<Vec<_>, _>>()?;
-
-        rates.sort_by(|(a, _), (b, _)| a.cmp(b).reverse());
-
-        exchange_rates.push(ValidatorExchangeRates {
-            address,
-            pool_id,
-            active,
-            rates,
-        });
-    }
-    Ok(exchange_rates)
+    Ok(state.get_validator_exchange_rates(epochs_back)?)
 }
 
 #[derive(Clone, Debug)]
@@ -460,3 +509,116 @@ impl MgoRpcModule for GovernanceReadApi {
         GovernanceReadApiOpenRpc::module_doc()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authority_state::MockStateRead;
+    use mockall::predicate;
+
+    const POOL_TOKENS: u64 = 1_000_000_000_000;
+
+    /// Rates of a pool returning `apy` a year from epoch 0, at the given epochs.
+    fn rates(
+        apy: f64,
+        epochs: impl IntoIterator<Item = u64>,
+    ) -> Vec<(EpochId, PoolTokenExchangeRate)> {
+        let epoch_return = (1.0 + apy).powf(1.0 / 365.0);
+        let mut rates = epochs
+            .into_iter()
+            .map(|epoch| {
+                let mgo_amount = POOL_TOKENS as f64 * epoch_return.powi(epoch as i32);
+                (
+                    epoch,
+                    PoolTokenExchangeRate::new(mgo_amount.round() as u64, POOL_TOKENS),
+                )
+            })
+            .collect::<Vec<_>>();
+        rates.sort_by(|(a, _), (b, _)| a.cmp(b).reverse());
+        rates
+    }
+
+    fn validator(
+        active: bool,
+        rates: Vec<(EpochId, PoolTokenExchangeRate)>,
+    ) -> ValidatorExchangeRates {
+        ValidatorExchangeRates {
+            address: MgoAddress::random_for_testing_only(),
+            pool_id: ObjectID::random(),
+            active,
+            rates,
+        }
+    }
+
+    #[test]
+    fn test_apy_is_geometric_mean_of_returns() {
+        let mut series = rates(0.05, 0..=20);
+        // A worse latest epoch lowers the APY below the steady return of the others.
+        series[0].1 = PoolTokenExchangeRate::new(
+            series[0].1.mgo_amount() - POOL_TOKENS / 100_000,
+            POOL_TOKENS,
+        );
+        let apys = calculate_apys(
+            0,
+            vec![validator(true, rates(0.05, 0..=20)), validator(true, series)],
+        );
+        assert!((apys[0].apy - 0.05).abs() < 1e-4, "{}", apys[0].apy);
+        assert!(apys[1].apy < apys[0].apy);
+    }
+
+    #[test]
+    fn test_apy_skips_inactive_epochs() {
+        // The validator left after epoch 4 and rejoined at epoch 10 with its pool worth barely
+        // more, which is not the return of any epoch it was active in.
+        let rejoined = rates(0.05, 4..=8).into_iter().map(|(epoch, rate)| {
+            let mgo_amount = rate.mgo_amount() as f64 * (1.0 + 1e-5);
+            (
+                epoch + 6,
+                PoolTokenExchangeRate::new(mgo_amount.round() as u64, POOL_TOKENS),
+            )
+        });
+        let mut series = rates(0.05, 0..=4);
+        series.splice(0..0, rejoined);
+        let apys = calculate_apys(0, vec![validator(true, series)]);
+        assert!((apys[0].apy - 0.05).abs() < 1e-4, "{}", apys[0].apy);
+    }
+
+    #[test]
+    fn test_apy_of_short_histories_is_zero() {
+        let apys = calculate_apys(
+            10,
+            vec![
+                // Just joined.
+                validator(true, rates(0.05, [12])),
+                validator(true, vec![]),
+                // Only active in epochs before the stake subsidy started.
+                validator(true, rates(0.05, 0..10)),
+                // Inactive validators are not reported.
+                validator(false, rates(0.05, 0..=20)),
+            ],
+        );
+        let apys = apys.iter().map(|a| a.apy).collect::<Vec<_>>();
+        assert_eq!(apys, vec![0.0, 0.0, 0.0]);
+    }
+
+    #[tokio::test]
+    async fn test_exchange_rates_are_read_once_per_epoch() {
+        let mut state = MockStateRead::new();
+        state
+            .expect_get_validator_exchange_rates()
+            .with(predicate::eq(VALIDATOR_APY_EPOCHS))
+            .times(1)
+            .returning(|_| Ok(vec![validator(true, rates(0.05, 70..=100))]));
+        let state: Arc<dyn StateRead> = Arc::new(state);
+
+        // An epoch no other test reads rates at, as the cache is shared.
+        let epoch = 100;
+        for _ in 0..2 {
+            let table = exchange_rates(&state, epoch, VALIDATOR_APY_EPOCHS)
+                .await
+                .unwrap();
+            let apys = calculate_apys(0, table);
+            assert!((apys[0].apy - 0.05).abs() < 1e-4, "{}", apys[0].apy);
+        }
+    }
+}
@@ -5,7 +5,7 @@ use anyhow::bail;
 use async_trait::async_trait;
 use futures::Stream;
 use jsonrpsee::{
-    core::{error::SubscriptionClosed, RpcResult},
+    core::{error::SubscriptionClosed, Error as RpcError, RpcResult},
     types::SubscriptionResult,
     RpcModule, SubscriptionSink,
 };
@@ -22,18 +22,23 @@ use mgo_json_rpc_api::{
     ReadApiServer, QUERY_MAX_RESULT_LIMIT,
 };
 use mgo_json_rpc_types::{
-    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, MgoObjectDataOptions,
-    MgoObjectResponse, MgoObjectResponseQuery, MgoTransactionBlockResponse,
-    MgoTransactionBlockResponseQuery, TransactionBlocksPage, TransactionFilter,
+    dynamic_fields_within_content_budget, AppliedPagination, DynamicFieldPage,
+    DynamicFieldsOptions, EventFilter, EventFilterLimits, EventPage, MgoDynamicFieldInfo,
+    MgoObjectDataOptions, MgoObjectResponse, MgoObjectResponseQuery, MgoTransactionBlockResponse,
+    MgoTransactionBlockResponseQuery, NameServiceResolution, ObjectsPage, Page,
+    TransactionBlocksPage, TransactionFilter,
 };
 use mgo_open_rpc::Module;
 use mgo_storage::key_value_store::TransactionKeyValueStore;
 use mgo_types::{
     base_types::{ObjectID, MgoAddress},
+    clock::Clock,
     digests::TransactionDigest,
-    dynamic_field::{DynamicFieldName, Field},
+    dynamic_field::{DynamicFieldInfo, DynamicFieldName, Field},
     error::MgoObjectResponseError,
     event::EventID,
+    object::Object,
+    parse_mgo_type_tag, MGO_CLOCK_OBJECT_ID,
 };
 use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tracing::{debug, instrument, warn};
@@ -41,7 +46,12 @@ use tracing::{debug, instrument, warn};
 use crate::{
     authority_state::StateRead,
     error::{Error, MgoRpcInputError},
-    name_service::{Domain, NameRecord, NameServiceConfig},
+    name_service::{
+        multi_resolve_names, multi_reverse_resolve_addresses, Domain, NameRecord,
+        NameServiceConfig, NAME_RECORD_SCAN_BATCH_SIZE, NAME_SERVICE_MAX_BULK_SIZE,
+    },
+    panic_guard::guard_notifications,
+    read_api::transaction_checkpoint_responses,
     with_tracing, MgoRpcModule,
 };
 
@@ -53,9 +63,10 @@ pub fn spawn_subscription<S, T>(
     S: Stream<Item = T> + Unpin + Send + 'static,
     T: Serialize,
 {
+    let rx = guard_notifications(rx);
     spawn_monitored_task!(async move {
         let _permit = permit;
-        match sink.pipe_from_stream(rx).await {
+        match sink.pipe_from_try_stream(rx).await {
             SubscriptionClosed::Success => {
                 debug!("Subscription completed.");
                 sink.close(SubscriptionClosed::Success);
@@ -80,6 +91,7 @@ pub struct IndexerApi<R> {
     name_service_config: NameServiceConfig,
     pub metrics: Arc<JsonRpcMetrics>,
     subscription_semaphore: Arc<Semaphore>,
+    event_filter_limits: EventFilterLimits,
 }
 
 impl<R: ReadApiServer> IndexerApi<R> {
@@ -90,6 +102,7 @@ impl<R: ReadApiServer> IndexerApi<R> {
         name_service_config: NameServiceConfig,
         metrics: Arc<JsonRpcMetrics>,
         max_subscriptions: Option<usize>,
+        event_filter_limits: EventFilterLimits,
     ) -> Self {
         let max_subscriptions = max_subscriptions.unwrap_or(DEFAULT_MAX_SUBSCRIPTIONS);
         Self {
@@ -99,6 +112,7 @@ impl<R: ReadApiServer> IndexerApi<R> {
             name_service_config,
             metrics,
             subscription_semaphore: Arc::new(Semaphore::new(max_subscriptions)),
+            event_filter_limits,
         }
     }
 
@@ -117,6 +131,46 @@ impl<R: ReadApiServer> IndexerApi<R> {
         Ok((name_type, name_bcs_value))
     }
 
+    /// Up to `limit` dynamic fields of `parent_object_id` after `cursor`, keyed by their field
+    /// object IDs. Fields are scanned in batches when only those named by `name_type` are kept.
+    fn get_dynamic_fields_of_name_type(
+        &self,
+        parent_object_id: ObjectID,
+        name_type: Option<&TypeTag>,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> Result<Vec<(ObjectID, DynamicFieldInfo)>, Error> {
+        let Some(name_type) = name_type else {
+            return Ok(self
+                .state
+                .get_dynamic_fields(parent_object_id, cursor, limit)?);
+        };
+        let mut fields = vec![];
+        let mut scan_cursor = cursor;
+        loop {
+            let batch = self
+                .state
+                .get_dynamic_fields(parent_object_id, scan_cursor, limit)?;
+            let exhausted = batch.len() < limit;
+            scan_cursor = batch.last().map(|(id, _)| *id);
+            for (id, info) in batch {
+                if &info.name.type_ == name_type {
+                    fields.push((id, info));
+                    if fields.len() == limit {
+                        return Ok(fields);
+                    }
+                }
+            }
+            if exhausted {
+                return Ok(fields);
+            }
+        }
+    }
+
+    async fn get_objects(&self, object_ids: Vec<ObjectID>) -> Result<Vec<Option<Object>>, Error> {
+        Ok(self.state.get_objects(&object_ids).await?)
+    }
+
     fn acquire_subscribe_permit(&self) -> anyhow::Result<OwnedSemaphorePermit> {
         match self.subscription_semaphore.clone().try_acquire_owned() {
             Ok(p) => Ok(p),
@@ -134,6 +188,7 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
         query: Option<MgoObjectResponseQuery>,
         cursor: Option<ObjectID>,
         limit: Option<usize>,
+        echo: Option<bool>,
     ) -> RpcResult<ObjectsPage> {
         with_tracing!(async move {
             let limit =
@@ -141,6 +196,7 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
             self.metrics.get_owned_objects_limit.report(limit as u64);
             let MgoObjectResponseQuery { filter, options } = query.unwrap_or_default();
             let options = options.unwrap_or_default();
+            let applied = AppliedPagination::echo(echo, cursor, limit, false, &(address, &filter));
             let mut objects = self
                 .state
                 .get_owner_objects_with_limit(address, cursor, limit + 1, filter)
@@ -158,8 +214,9 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
                 true => {
                     let object_ids = objects.iter().map(|obj| obj.object_id).collect();
                     self.read_api
-                        .multi_get_objects(object_ids, Some(options))
+                        .multi_get_objects(object_ids, Some(options), None)
                         .await?
+                        .into_json()?
                 }
                 false => objects
                     .into_iter()
@@ -177,6 +234,7 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
                 data,
                 next_cursor,
                 has_next_page,
+                applied,
             })
         })
     }
@@ -189,12 +247,14 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
         cursor: Option<TransactionDigest>,
         limit: Option<usize>,
         descending_order: Option<bool>,
+        echo: Option<bool>,
     ) -> RpcResult<TransactionBlocksPage> {
         with_tracing!(async move {
             let limit = cap_page_limit(limit);
             self.metrics.query_tx_blocks_limit.report(limit as u64);
             let descending = descending_order.unwrap_or_default();
             let opts = query.options.unwrap_or_default();
+            let applied = AppliedPagination::echo(echo, cursor, limit, descending, &query.filter);
 
             // Retrieve 1 extra item for next cursor
             let mut digests = self
@@ -219,10 +279,13 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
                     .into_iter()
                     .map(MgoTransactionBlockResponse::new)
                     .collect()
+            } else if opts.only_checkpoint() {
+                transaction_checkpoint_responses(&self.transaction_kv_store, digests).await?
             } else {
                 self.read_api
-                    .multi_get_transaction_blocks(digests, Some(opts))
+                    .multi_get_transaction_blocks(digests, Some(opts), None)
                     .await?
+                    .into_json()?
             };
 
             self.metrics
@@ -235,6 +298,7 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
                 data,
                 next_cursor,
                 has_next_page,
+                applied,
             })
         })
     }
@@ -246,11 +310,13 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
         cursor: Option<EventID>,
         limit: Option<usize>,
         descending_order: Option<bool>,
+        echo: Option<bool>,
     ) -> RpcResult<EventPage> {
         with_tracing!(async move {
             let descending = descending_order.unwrap_or_default();
             let limit = cap_page_limit(limit);
             self.metrics.query_events_limit.report(limit as u64);
+            let applied = AppliedPagination::echo(echo, cursor, limit, descending, &query);
             // Retrieve 1 extra item for next cursor
             let mut data = self
                 .state
@@ -276,12 +342,21 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
                 data,
                 next_cursor,
                 has_next_page,
+                applied,
             })
         })
     }
 
     #[instrument(skip(self))]
-    fn subscribe_event(&self, sink: SubscriptionSink, filter: EventFilter) -> SubscriptionResult {
+    fn subscribe_event(
+        &self,
+        mut sink: SubscriptionSink,
+        filter: EventFilter,
+    ) -> SubscriptionResult {
+        if let Err(e) = filter.validate_for_subscription(&self.event_filter_limits) {
+            let _ = sink.reject(RpcError::from(MgoRpcInputError::GenericInvalid(e.to_string())));
+            return Ok(());
+        }
         let permit = self.acquire_subscribe_permit()?;
         spawn_subscription(
             sink,
@@ -309,6 +384,18 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
         Ok(())
     }
 
+    fn subscribe_checkpoint(&self, sink: SubscriptionSink) -> SubscriptionResult {
+        let permit = self.acquire_subscribe_permit()?;
+        spawn_subscription(
+            sink,
+            self.state
+                .get_subscription_handler()
+                .subscribe_checkpoints(),
+            Some(permit),
+        );
+        Ok(())
+    }
+
     #[instrument(skip(self))]
     async fn get_dynamic_fields(
         &self,
@@ -316,27 +403,70 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
         // If `Some`, the query will start from the next item after the specified cursor
         cursor: Option<ObjectID>,
         limit: Option<usize>,
+        options: Option<DynamicFieldsOptions>,
+        echo: Option<bool>,
     ) -> RpcResult<DynamicFieldPage> {
         with_tracing!(async move {
             let limit = cap_page_limit(limit);
             self.metrics.get_dynamic_fields_limit.report(limit as u64);
-            let mut data = self
-                .state
-                .get_dynamic_fields(parent_object_id, cursor, limit + 1)
-                .map_err(Error::from)?;
-            let has_next_page = data.len() > limit;
+            let options = options.unwrap_or_default();
+            let applied = AppliedPagination::echo(
+                echo,
+                cursor,
+                limit,
+                false,
+                &(parent_object_id, &options.name_type),
+            );
+            let name_type = options
+                .name_type
+                .map(|name_type| {
+                    parse_mgo_type_tag(&name_type).map_err(|e| {
+                        MgoRpcInputError::GenericInvalid(format!(
+                            "Invalid name type {name_type}: {e}"
+                        ))
+                    })
+                })
+                .transpose()?;
+            let mut data = self.get_dynamic_fields_of_name_type(
+                parent_object_id,
+                name_type.as_ref(),
+                cursor,
+                limit + 1,
+            )?;
+            let mut has_next_page = data.len() > limit;
             data.truncate(limit);
-            let next_cursor = data.last().cloned().map_or(cursor, |c| Some(c.0));
+            let (mut field_ids, mut fields): (Vec<_>, Vec<MgoDynamicFieldInfo>) =
+                data.into_iter().map(|(id, info)| (id, info.into())).unzip();
+            if options.show_content {
+                // The objects `get_dynamic_field_object` returns, which for dynamic object fields
+                // are the objects themselves rather than their fields.
+                let ids = fields.iter().map(|field| field.object_id).collect();
+                let objects = self
+                    .read_api
+                    .multi_get_objects(ids, Some(MgoObjectDataOptions::full_content()), None)
+                    .await
+                    .map_err(Error::from)?
+                    .into_json()?;
+                for (field, object) in fields.iter_mut().zip(objects) {
+                    field.object = object.data;
+                }
+                let kept = dynamic_fields_within_content_budget(&fields);
+                has_next_page |= kept < fields.len();
+                fields.truncate(kept);
+                field_ids.truncate(kept);
+            }
+            let next_cursor = field_ids.last().copied().or(cursor);
             self.metrics
                 .get_dynamic_fields_result_size
-                .report(data.len() as u64);
+                .report(fields.len() as u64);
             self.metrics
                 .get_dynamic_fields_result_size_total
-                .inc_by(data.len() as u64);
+                .inc_by(fields.len() as u64);
             Ok(DynamicFieldPage {
-                data: data.into_iter().map(|(_, w)| w).collect(),
+                data: fields,
                 next_cursor,
                 has_next_page,
+                applied,
             })
         })
     }
@@ -346,6 +476,7 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
         &self,
         parent_object_id: ObjectID,
         name: DynamicFieldName,
+        options: Option<MgoObjectDataOptions>,
     ) -> RpcResult<MgoObjectResponse> {
         with_tracing!(async move {
             let (name_type, name_bcs_value) = self.extract_values_from_dynamic_field_name(name)?;
@@ -354,10 +485,10 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
                 .state
                 .get_dynamic_field_object_id(parent_object_id, name_type, &name_bcs_value)
                 .map_err(Error::from)?;
-            // TODO(chris): add options to `get_dynamic_field_object` API as well
             if let Some(id) = id {
+                let options = options.unwrap_or_else(MgoObjectDataOptions::full_content);
                 self.read_api
-                    .get_object(id, Some(MgoObjectDataOptions::full_content()))
+                    .get_object(id, Some(options))
                     .await
                     .map_err(Error::from)
             } else {
@@ -371,26 +502,22 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
     #[instrument(skip(self))]
     async fn resolve_name_service_address(&self, name: String) -> RpcResult<Option<MgoAddress>> {
         with_tracing!(async move {
-            let domain = Domain::from_str(&name).map_err(|e| {
+            Domain::from_str(&name).map_err(|e| {
                 Error::UnexpectedError(format!(
                     "Failed to parse NameService Domain with error: {:?}",
                     e
                 ))
             })?;
 
-            let record_id = self.name_service_config.record_field_id(&domain);
-
-            let field_record_object = match self.state.get_object(&record_id).await? {
-                Some(o) => o,
-                None => return Ok(None),
-            };
-
-            let record = field_record_object
-                .to_rust::<Field<Domain, NameRecord>>()
-                .ok_or_else(|| Error::UnexpectedError(format!("Malformed Object {record_id}")))?
-                .value;
-
-            Ok(record.target_address)
+            let resolution = multi_resolve_names(&self.name_service_config, &[name], |ids| {
+                self.get_objects(ids)
+            })
+            .await?
+            .remove(0);
+            match resolution.error {
+                Some(e) => Err(Error::UnexpectedError(e)),
+                None => Ok(resolution.data),
+            }
         })
     }
 
@@ -398,38 +525,104 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
     async fn resolve_name_service_names(
         &self,
         address: MgoAddress,
-        _cursor: Option<ObjectID>,
-        _limit: Option<usize>,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
     ) -> RpcResult<Page<String, ObjectID>> {
         with_tracing!(async move {
-            let reverse_record_id = self
-                .name_service_config
-                .reverse_record_field_id(address.as_ref());
-
-            let field_reverse_record_object =
-                match self.state.get_object(&reverse_record_id).await? {
-                    Some(o) => o,
-                    None => {
-                        return Ok(Page {
-                            data: vec![],
-                            next_cursor: None,
-                            has_next_page: false,
-                        })
+            let limit = cap_page_limit(limit);
+            let now_ms = self
+                .state
+                .get_object(&MGO_CLOCK_OBJECT_ID)
+                .await?
+                .and_then(|o| o.to_rust::<Clock>())
+                .ok_or_else(|| Error::UnexpectedError("Missing clock object".to_string()))?
+                .timestamp_ms;
+
+            // Records are walked in field object ID order, keeping those currently pointing to
+            // `address`, until one more than a page is found.
+            let mut names = vec![];
+            let mut scan_cursor = cursor;
+            'scan: loop {
+                let fields = self
+                    .state
+                    .get_dynamic_fields(
+                        self.name_service_config.registry_id,
+                        scan_cursor,
+                        NAME_RECORD_SCAN_BATCH_SIZE,
+                    )
+                    .map_err(Error::from)?;
+                let exhausted = fields.len() < NAME_RECORD_SCAN_BATCH_SIZE;
+                for (field_id, _) in fields {
+                    scan_cursor = Some(field_id);
+                    let Some(object) = self.state.get_object(&field_id).await? else {
+                        continue;
+                    };
+                    let field = object
+                        .to_rust::<Field<Domain, NameRecord>>()
+                        .ok_or_else(|| {
+                            Error::UnexpectedError(format!("Malformed Object {field_id}"))
+                        })?;
+                    if field.value.target_address == Some(address)
+                        && !field.value.is_expired(now_ms)
+                    {
+                        names.push((field.name.to_string(), field_id));
+                        if names.len() > limit {
+                            break 'scan;
+                        }
                     }
-                };
-
-            let domain = field_reverse_record_object
-                .to_rust::<Field<MgoAddress, Domain>>()
-                .ok_or_else(|| {
-                    Error::UnexpectedError(format!("Malformed Object {reverse_record_id}"))
-                })?
-                .value;
+                }
+                if exhausted {
+                    break;
+                }
+            }
 
+            let has_next_page = names.len() > limit;
+            names.truncate(limit);
+            let next_cursor = names.last().map_or(cursor, |(_, id)| Some(*id));
             Ok(Page {
-                data: vec![domain.to_string()],
-                next_cursor: None,
-                has_next_page: false,
+                data: names.into_iter().map(|(name, _)| name).collect(),
+                next_cursor,
+                has_next_page,
+                applied: None,
+            })
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn multi_resolve_name_service(
+        &self,
+        names: Vec<String>,
+    ) -> RpcResult<Vec<NameServiceResolution<MgoAddress>>> {
+        with_tracing!(async move {
+            if names.len() > NAME_SERVICE_MAX_BULK_SIZE {
+                return Err(MgoRpcInputError::SizeLimitExceeded(
+                    NAME_SERVICE_MAX_BULK_SIZE.to_string(),
+                )
+                .into());
+            }
+            multi_resolve_names(&self.name_service_config, &names, |ids| {
+                self.get_objects(ids)
+            })
+            .await
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn multi_reverse_resolve(
+        &self,
+        addresses: Vec<MgoAddress>,
+    ) -> RpcResult<Vec<NameServiceResolution<String>>> {
+        with_tracing!(async move {
+            if addresses.len() > NAME_SERVICE_MAX_BULK_SIZE {
+                return Err(MgoRpcInputError::SizeLimitExceeded(
+                    NAME_SERVICE_MAX_BULK_SIZE.to_string(),
+                )
+                .into());
+            }
+            multi_reverse_resolve_addresses(&self.name_service_config, &addresses, |ids| {
+                self.get_objects(ids)
             })
+            .await
         })
     }
 }
@@ -0,0 +1,120 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Methods an operator has disabled at runtime, typically a single pathological method during
+//! an incident. Requests to a disabled method are answered with [`METHOD_DISABLED_CODE`] before
+//! they are dispatched.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use axum::extract::{Extension, Query};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::Router;
+use jsonrpsee::types::ErrorObjectOwned;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use mgo_json_rpc_api::METHOD_DISABLED_CODE;
+
+pub const KILL_SWITCH_ROUTE: &str = "/rpc-kill-switch";
+pub const DISABLE_METHOD_ROUTE: &str = "/rpc-kill-switch/disable";
+pub const ENABLE_METHOD_ROUTE: &str = "/rpc-kill-switch/enable";
+
+/// Hint returned for methods disabled without one.
+pub const DEFAULT_HINT: &str =
+    "Retry later, the method will be enabled again once the issue is resolved";
+
+/// `data` of the error returned for a disabled method.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DisabledMethodData {
+    pub hint: String,
+}
+
+/// Disabled method names, mapped to the hint returned to their callers. Clones share the same
+/// set, and checking a method only loads the current set without taking a lock.
+#[derive(Clone, Debug, Default)]
+pub struct MethodKillSwitch {
+    disabled: Arc<ArcSwap<BTreeMap<String, String>>>,
+}
+
+impl MethodKillSwitch {
+    /// The error to answer `method` with, if it is disabled.
+    pub fn check(&self, method: &str) -> Option<ErrorObjectOwned> {
+        let disabled = self.disabled.load();
+        let hint = disabled.get(method)?;
+        Some(ErrorObjectOwned::owned(
+            METHOD_DISABLED_CODE,
+            format!("Method `{method}` is temporarily disabled by the operator"),
+            Some(DisabledMethodData { hint: hint.clone() }),
+        ))
+    }
+
+    pub fn disable(&self, method: &str, hint: Option<String>) {
+        let hint = hint.unwrap_or_else(|| DEFAULT_HINT.to_string());
+        self.disabled.rcu(|disabled| {
+            let mut disabled = BTreeMap::clone(disabled);
+            disabled.insert(method.to_string(), hint.clone());
+            disabled
+        });
+        info!(method, "Disabled JSON-RPC method");
+    }
+
+    pub fn enable(&self, method: &str) {
+        self.disabled.rcu(|disabled| {
+            let mut disabled = BTreeMap::clone(disabled);
+            disabled.remove(method);
+            disabled
+        });
+        info!(method, "Enabled JSON-RPC method");
+    }
+
+    pub fn disabled_methods(&self) -> Arc<BTreeMap<String, String>> {
+        self.disabled.load_full()
+    }
+
+    /// Admin routes listing the disabled methods on `GET` [`KILL_SWITCH_ROUTE`], and disabling
+    /// or enabling `?method=` on `POST` [`DISABLE_METHOD_ROUTE`] and [`ENABLE_METHOD_ROUTE`].
+    /// A disabled method is returned `&hint=` if given, or [`DEFAULT_HINT`].
+    pub fn admin_router(&self) -> Router {
+        Router::new()
+            .route(KILL_SWITCH_ROUTE, get(list_disabled_methods))
+            .route(DISABLE_METHOD_ROUTE, post(disable_method))
+            .route(ENABLE_METHOD_ROUTE, post(enable_method))
+            .layer(Extension(self.clone()))
+    }
+}
+
+#[derive(Deserialize)]
+struct MethodQuery {
+    method: String,
+    hint: Option<String>,
+}
+
+async fn list_disabled_methods(
+    Extension(kill_switch): Extension<MethodKillSwitch>,
+) -> (StatusCode, String) {
+    let mut output = String::new();
+    for (method, hint) in kill_switch.disabled_methods().iter() {
+        output.push_str(&format!("{method}: {hint}\n"));
+    }
+    (StatusCode::OK, output)
+}
+
+async fn disable_method(
+    Extension(kill_switch): Extension<MethodKillSwitch>,
+    Query(MethodQuery { method, hint }): Query<MethodQuery>,
+) -> (StatusCode, String) {
+    kill_switch.disable(&method, hint);
+    (StatusCode::OK, format!("{method} disabled\n"))
+}
+
+async fn enable_method(
+    Extension(kill_switch): Extension<MethodKillSwitch>,
+    Query(MethodQuery { method, .. }): Query<MethodQuery>,
+) -> (StatusCode, String) {
+    kill_switch.enable(&method);
+    (StatusCode::OK, format!("{method} enabled\n"))
+}
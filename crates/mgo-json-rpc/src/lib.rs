@@ -1,11 +1,11 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::{BTreeMap, BTreeSet};
 use std::env;
 use std::net::SocketAddr;
 use std::str::FromStr;
 
-use hyper::header::HeaderName;
 use hyper::header::HeaderValue;
 use hyper::Body;
 use hyper::Method;
@@ -19,31 +19,46 @@ use tracing::info;
 
 pub use balance_changes::*;
 pub use object_changes::*;
-use mgo_json_rpc_api::{
-    CLIENT_SDK_TYPE_HEADER, CLIENT_SDK_VERSION_HEADER, CLIENT_TARGET_API_VERSION_HEADER,
-};
+use mgo_json_rpc_api::ClientErrorCode;
 use mgo_open_rpc::{Module, Project};
 
+use crate::cors::{CorsConfig, OriginMethods};
 use crate::error::Error;
+use crate::kill_switch::MethodKillSwitch;
+use crate::response_signing::ResponseSigner;
 use crate::metrics::MetricsLogger;
+use crate::panic_guard::HandlerPanicGuard;
+use crate::query_scheduler::{QueryScheduler, QuerySchedulerConfig};
+use crate::rate_limiter::{RateLimiter, RateLimiterConfig};
 use crate::routing_layer::RpcRouter;
 
 pub mod authority_state;
 pub mod axum_router;
 mod balance_changes;
 pub mod coin_api;
+pub mod coin_registry;
+pub mod cors;
+pub mod debug_api;
+pub mod epoch_cache;
 pub mod error;
 pub mod governance_api;
 pub mod indexer_api;
+pub mod kill_switch;
 pub mod logger;
 mod metrics;
 pub mod move_utils;
 pub mod name_service;
 mod object_changes;
+pub mod panic_guard;
+pub mod query_scheduler;
+pub mod rate_limiter;
 pub mod read_api;
+pub mod response_signing;
 mod routing_layer;
+pub mod support_api;
 pub mod transaction_builder_api;
 pub mod transaction_execution_api;
+pub mod verification_api;
 
 pub const APP_NAME_HEADER: &str = "app-name";
 
@@ -53,10 +68,16 @@ pub struct JsonRpcServerBuilder {
     module: RpcModule<()>,
     rpc_doc: Project,
     registry: Registry,
+    kill_switch: MethodKillSwitch,
+    query_scheduler: Option<QuerySchedulerConfig>,
+    rate_limiter: Option<RateLimiterConfig>,
+    cors: Option<CorsConfig>,
+    response_signer: Option<ResponseSigner>,
+    method_weights: BTreeMap<String, u32>,
 }
 
 pub fn mgo_rpc_doc(version: &str) -> Project {
-    Project::new(
+    let mut project = Project::new(
         version,
         "Mgo JSON-RPC",
         "Mgo JSON-RPC API for interaction with Mgo Full node. Make RPC calls using https://fullnode.NETWORK.mangonetwork.io:443, where NETWORK is the network you want to use (testnet, devnet, mainnet). By default, local networks use port 9000.",
@@ -65,7 +86,11 @@ pub fn mgo_rpc_doc(version: &str) -> Project {
         "build@mangonetworklabs.com",
         "Apache-2.0",
         "https://raw.githubusercontent.com/MangoNetworkOs/Mango/main/LICENSE",
-    )
+    );
+    for code in ClientErrorCode::ALL {
+        project.add_error(code.name(), code.code(), code.description());
+    }
+    project
 }
 
 pub enum ServerType {
@@ -79,14 +104,64 @@ impl JsonRpcServerBuilder {
             module: RpcModule::new(()),
             rpc_doc: mgo_rpc_doc(version),
             registry: prometheus_registry.clone(),
+            kill_switch: MethodKillSwitch::default(),
+            query_scheduler: None,
+            rate_limiter: None,
+            cors: None,
+            response_signer: None,
+            method_weights: BTreeMap::new(),
         }
     }
 
+    /// Consults `kill_switch` before dispatching every request, so that methods it disables
+    /// are refused while the server runs.
+    pub fn set_kill_switch(&mut self, kill_switch: MethodKillSwitch) {
+        self.kill_switch = kill_switch;
+    }
+
+    /// Runs the handlers of heavy and light methods in separate tiers of bounded concurrency,
+    /// instead of all of them as soon as they are requested.
+    pub fn set_query_scheduler(&mut self, config: QuerySchedulerConfig) {
+        self.query_scheduler = Some(config);
+    }
+
+    /// Throttles clients to the limits of `config` before dispatching their requests, with the
+    /// weights registered modules declare for their methods.
+    pub fn set_rate_limiter(&mut self, config: RateLimiterConfig) {
+        self.rate_limiter = Some(config);
+    }
+
+    /// Answers browsers from the origins and with the methods of `config`, instead of the
+    /// origins of the `ACCESS_CONTROL_ALLOW_ORIGIN` environment variable. The configuration is
+    /// validated when the server is built.
+    pub fn set_cors(&mut self, config: CorsConfig) {
+        self.cors = Some(config);
+    }
+
+    /// Signs the successful results of the methods of `signer` before returning them.
+    pub fn set_response_signer(&mut self, signer: ResponseSigner) {
+        self.response_signer = Some(signer);
+    }
+
     pub fn register_module<T: MgoRpcModule>(&mut self, module: T) -> Result<(), Error> {
         self.rpc_doc.add_module(T::rpc_doc_module());
+        self.method_weights.extend(
+            T::rpc_method_weights()
+                .into_iter()
+                .map(|(method, weight)| (method.to_string(), weight)),
+        );
         Ok(self.module.merge(module.rpc())?)
     }
 
+    /// The namespaces of the methods of the modules registered so far, such as `mgo` for
+    /// `mgo_getObject`.
+    pub fn namespaces(&self) -> BTreeSet<String> {
+        self.module
+            .method_names()
+            .filter_map(|name| Some(name.split_once('_')?.0.to_string()))
+            .collect()
+    }
+
     fn cors() -> Result<CorsLayer, Error> {
         let acl = match env::var("ACCESS_CONTROL_ALLOW_ORIGIN") {
             Ok(value) => {
@@ -105,13 +180,7 @@ impl JsonRpcServerBuilder {
             .allow_methods([Method::POST])
             // Allow requests from any origin
             .allow_origin(acl)
-            .allow_headers([
-                hyper::header::CONTENT_TYPE,
-                HeaderName::from_static(CLIENT_SDK_TYPE_HEADER),
-                HeaderName::from_static(CLIENT_SDK_VERSION_HEADER),
-                HeaderName::from_static(CLIENT_TARGET_API_VERSION_HEADER),
-                HeaderName::from_static(APP_NAME_HEADER),
-            ]);
+            .allow_headers(cors::sdk_headers());
         Ok(cors)
     }
 
@@ -164,13 +233,39 @@ impl JsonRpcServerBuilder {
         let methods_names = module.method_names().collect::<Vec<_>>();
 
         let metrics_logger = MetricsLogger::new(&self.registry, &methods_names);
+        let panic_guard = HandlerPanicGuard::new(&self.registry);
+        let query_scheduler = self
+            .query_scheduler
+            .clone()
+            .map(|config| QueryScheduler::new(config, &self.registry))
+            .unwrap_or_default();
+        let rate_limiter = self
+            .rate_limiter
+            .clone()
+            .map(|config| {
+                RateLimiter::new(config, &self.method_weights, &methods_names, &self.registry)
+            })
+            .unwrap_or_default();
 
+        let (cors, origin_methods) = match &self.cors {
+            Some(config) => config.build(&methods_names)?,
+            None => (Self::cors()?, OriginMethods::default()),
+        };
         let middleware = tower::ServiceBuilder::new()
             .layer(Self::trace_layer())
-            .layer(Self::cors()?);
-
-        let service =
-            crate::axum_router::JsonRpcService::new(module.into(), rpc_router, metrics_logger);
+            .layer(cors);
+
+        let service = crate::axum_router::JsonRpcService::new(
+            module.into(),
+            rpc_router,
+            self.kill_switch.clone(),
+            origin_methods,
+            self.response_signer.clone(),
+            panic_guard,
+            query_scheduler,
+            rate_limiter,
+            metrics_logger,
+        );
 
         let mut router = axum::Router::new();
 
@@ -224,7 +319,9 @@ impl JsonRpcServerBuilder {
     ) -> Result<ServerHandle, Error> {
         let app = self.to_router(server_type)?;
 
-        let server = axum::Server::bind(&listen_address).serve(app.into_make_service());
+        // Peer addresses identify the clients of the rate limiter.
+        let server = axum::Server::bind(&listen_address)
+            .serve(app.into_make_service_with_connect_info::<SocketAddr>());
 
         let addr = server.local_addr();
         let handle = tokio::spawn(async move { server.await.unwrap() });
@@ -259,4 +356,10 @@ where
 {
     fn rpc(self) -> RpcModule<Self>;
     fn rpc_doc_module() -> Module;
+
+    /// Weights of the methods costlier to serve than a default weight of
+    /// [`rate_limiter::DEFAULT_METHOD_WEIGHT`], charged to the rate limit of their callers.
+    fn rpc_method_weights() -> Vec<(&'static str, u32)> {
+        vec![]
+    }
 }
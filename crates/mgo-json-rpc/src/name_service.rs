@@ -1,17 +1,23 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::anyhow;
 use move_core_types::ident_str;
 use move_core_types::identifier::IdentStr;
 use move_core_types::language_storage::StructTag;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::future::Future;
 use std::marker::PhantomData;
 use std::str::FromStr;
+use mgo_json_rpc_types::NameServiceResolution;
 use mgo_types::base_types::{ObjectID, MgoAddress};
+use mgo_types::clock::Clock;
 use mgo_types::collection_types::VecMap;
+use mgo_types::dynamic_field::Field;
 use mgo_types::id::ID;
-use mgo_types::TypeTag;
+use mgo_types::object::Object;
+use mgo_types::{TypeTag, MGO_CLOCK_OBJECT_ID};
 
 const NAME_SERVICE_DOMAIN_MODULE: &IdentStr = ident_str!("domain");
 const NAME_SERVICE_DOMAIN_STRUCT: &IdentStr = ident_str!("Domain");
@@ -24,6 +30,12 @@ const NAME_SERVICE_DEFAULT_REVERSE_REGISTRY: &str =
 const _NAME_SERVICE_OBJECT_ADDRESS: &str =
     "0x6e0ddefc0ad98889c04bab9639e512c21766c5e6366f89e696956d9be6952871";
 
+/// Number of registry records read at a time when looking up the names pointing to an address.
+pub const NAME_RECORD_SCAN_BATCH_SIZE: usize = 100;
+
+/// Maximum number of names or addresses resolved by a single bulk request.
+pub const NAME_SERVICE_MAX_BULK_SIZE: usize = 100;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Registry {
     /// The `registry` table maps `Domain` to `NameRecord`.
@@ -224,3 +236,288 @@ pub struct NameRecord {
     /// Additional data which may be stored in a record
     pub data: VecMap<String, String>,
 }
+
+impl NameRecord {
+    /// Whether the record had expired at `timestamp_ms`, normally the on-chain clock time.
+    pub fn is_expired(&self, timestamp_ms: u64) -> bool {
+        self.expiration_timestamp_ms < timestamp_ms
+    }
+
+    /// The address the record points to at `timestamp_ms`, `None` once it has expired.
+    pub fn resolve(&self, timestamp_ms: u64) -> Option<MgoAddress> {
+        if self.is_expired(timestamp_ms) {
+            None
+        } else {
+            self.target_address
+        }
+    }
+}
+
+fn clock_timestamp_ms(clock: Option<Object>) -> anyhow::Result<u64> {
+    clock
+        .and_then(|o| o.to_rust::<Clock>())
+        .map(|clock| clock.timestamp_ms)
+        .ok_or_else(|| anyhow!("Missing clock object"))
+}
+
+fn name_record(object: &Object) -> Result<NameRecord, String> {
+    object
+        .to_rust::<Field<Domain, NameRecord>>()
+        .map(|field| field.value)
+        .ok_or_else(|| format!("Malformed Object {}", object.id()))
+}
+
+/// Resolves each of `names` to the address its unexpired record points to. The clock and all
+/// records are read with a single call to `multi_get_objects`, which must return the objects in
+/// the order of the requested IDs, `None` for missing ones.
+pub async fn multi_resolve_names<F, Fut, E>(
+    config: &NameServiceConfig,
+    names: &[String],
+    multi_get_objects: F,
+) -> Result<Vec<NameServiceResolution<MgoAddress>>, E>
+where
+    F: FnOnce(Vec<ObjectID>) -> Fut,
+    Fut: Future<Output = Result<Vec<Option<Object>>, E>>,
+    E: From<anyhow::Error>,
+{
+    let domains = names
+        .iter()
+        .map(|name| Domain::from_str(name))
+        .collect::<Vec<_>>();
+    let object_ids = std::iter::once(MGO_CLOCK_OBJECT_ID)
+        .chain(domains.iter().flatten().map(|d| config.record_field_id(d)))
+        .collect();
+    let mut objects = multi_get_objects(object_ids).await?.into_iter();
+    let now_ms = clock_timestamp_ms(objects.next().flatten())?;
+
+    Ok(domains
+        .into_iter()
+        .map(|domain| {
+            if let Err(e) = domain {
+                return NameServiceResolution::error(format!(
+                    "Failed to parse NameService Domain with error: {:?}",
+                    e
+                ));
+            }
+            match objects.next().flatten().as_ref().map(name_record) {
+                None => NameServiceResolution::new(None),
+                Some(Ok(record)) => NameServiceResolution::new(record.resolve(now_ms)),
+                Some(Err(e)) => NameServiceResolution::error(e),
+            }
+        })
+        .collect())
+}
+
+/// Resolves each of `addresses` to its reverse record name, as long as the record of that name
+/// is unexpired and still points to the address. Reads the clock and the reverse records with
+/// one call to `multi_get_objects`, and the records of the names found with a second one.
+pub async fn multi_reverse_resolve_addresses<F, Fut, E>(
+    config: &NameServiceConfig,
+    addresses: &[MgoAddress],
+    multi_get_objects: F,
+) -> Result<Vec<NameServiceResolution<String>>, E>
+where
+    F: Fn(Vec<ObjectID>) -> Fut,
+    Fut: Future<Output = Result<Vec<Option<Object>>, E>>,
+    E: From<anyhow::Error>,
+{
+    let object_ids = std::iter::once(MGO_CLOCK_OBJECT_ID)
+        .chain(
+            addresses
+                .iter()
+                .map(|address| config.reverse_record_field_id(address.as_ref())),
+        )
+        .collect();
+    let mut objects = multi_get_objects(object_ids).await?.into_iter();
+    let now_ms = clock_timestamp_ms(objects.next().flatten())?;
+    let domains = objects
+        .map(|object| {
+            object
+                .map(|o| {
+                    o.to_rust::<Field<MgoAddress, Domain>>()
+                        .map(|field| field.value)
+                        .ok_or_else(|| format!("Malformed Object {}", o.id()))
+                })
+                .transpose()
+        })
+        .collect::<Vec<_>>();
+
+    let record_ids = domains
+        .iter()
+        .flatten()
+        .flatten()
+        .map(|domain| config.record_field_id(domain))
+        .collect::<Vec<_>>();
+    let mut records = if record_ids.is_empty() {
+        vec![]
+    } else {
+        multi_get_objects(record_ids).await?
+    }
+    .into_iter();
+
+    Ok(addresses
+        .iter()
+        .zip(domains)
+        .map(|(address, domain)| match domain {
+            Ok(None) => NameServiceResolution::new(None),
+            Ok(Some(domain)) => match records.next().flatten().as_ref().map(name_record) {
+                None => NameServiceResolution::new(None),
+                Some(Ok(record)) => NameServiceResolution::new(
+                    (record.resolve(now_ms) == Some(*address)).then(|| domain.to_string()),
+                ),
+                Some(Err(e)) => NameServiceResolution::error(e),
+            },
+            Err(e) => NameServiceResolution::error(e),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mgo_types::base_types::SequenceNumber;
+    use mgo_types::digests::TransactionDigest;
+    use mgo_types::id::UID;
+    use mgo_types::object::{MoveObject, Owner};
+    use std::collections::HashMap;
+
+    const NOW_MS: u64 = 1_000_000;
+
+    /// An object with `contents`. The resolution helpers only read the contents, so every object
+    /// is given the clock type.
+    fn move_object<T: Serialize>(contents: &T) -> Object {
+        // SAFETY: none of the objects are transferred, so public transfer is irrelevant.
+        let move_object = unsafe {
+            MoveObject::new_from_execution_with_limit(
+                Clock::type_().into(),
+                false,
+                SequenceNumber::new(),
+                bcs::to_bytes(contents).unwrap(),
+                u64::MAX,
+            )
+            .unwrap()
+        };
+        Object::new_move(
+            move_object,
+            Owner::Immutable,
+            TransactionDigest::genesis_marker(),
+        )
+    }
+
+    fn record(target_address: MgoAddress, expiration_timestamp_ms: u64) -> NameRecord {
+        NameRecord {
+            nft_id: ID::new(ObjectID::random()),
+            expiration_timestamp_ms,
+            target_address: Some(target_address),
+            data: VecMap { contents: vec![] },
+        }
+    }
+
+    struct Store {
+        config: NameServiceConfig,
+        objects: HashMap<ObjectID, Object>,
+    }
+
+    impl Store {
+        fn new() -> Self {
+            let clock = Clock {
+                id: UID::new(MGO_CLOCK_OBJECT_ID),
+                timestamp_ms: NOW_MS,
+            };
+            Self {
+                config: NameServiceConfig::default(),
+                objects: HashMap::from([(MGO_CLOCK_OBJECT_ID, move_object(&clock))]),
+            }
+        }
+
+        fn register(&mut self, name: &str, record: NameRecord) {
+            let domain = Domain::from_str(name).unwrap();
+            let id = self.config.record_field_id(&domain);
+            let field = Field {
+                id: UID::new(id),
+                name: domain,
+                value: record,
+            };
+            self.objects.insert(id, move_object(&field));
+        }
+
+        fn set_reverse(&mut self, address: MgoAddress, name: &str) {
+            let id = self.config.reverse_record_field_id(address.as_ref());
+            let field = Field {
+                id: UID::new(id),
+                name: address,
+                value: Domain::from_str(name).unwrap(),
+            };
+            self.objects.insert(id, move_object(&field));
+        }
+
+        async fn multi_get_objects(
+            &self,
+            object_ids: Vec<ObjectID>,
+        ) -> anyhow::Result<Vec<Option<Object>>> {
+            Ok(object_ids
+                .iter()
+                .map(|id| self.objects.get(id).cloned())
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_multi_resolve_names() {
+        let mut store = Store::new();
+        let owner = MgoAddress::random_for_testing_only();
+        store.register("registered.mgo", record(owner, NOW_MS + 1));
+        store.register("expired.mgo", record(owner, NOW_MS - 1));
+
+        let names = [
+            "registered.mgo",
+            "unregistered.mgo",
+            "expired.mgo",
+            "not a name",
+        ]
+        .map(String::from);
+        let resolutions =
+            multi_resolve_names(&store.config, &names, |ids| store.multi_get_objects(ids))
+                .await
+                .unwrap();
+
+        assert_eq!(resolutions.len(), names.len());
+        assert_eq!(resolutions[0], NameServiceResolution::new(Some(owner)));
+        assert_eq!(resolutions[1], NameServiceResolution::new(None));
+        assert_eq!(resolutions[2], NameServiceResolution::new(None));
+        assert!(resolutions[3].data.is_none() && resolutions[3].error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_multi_reverse_resolve_addresses() {
+        let mut store = Store::new();
+        let registered = MgoAddress::random_for_testing_only();
+        let expired = MgoAddress::random_for_testing_only();
+        let moved = MgoAddress::random_for_testing_only();
+        let unregistered = MgoAddress::random_for_testing_only();
+        store.register("registered.mgo", record(registered, NOW_MS + 1));
+        store.set_reverse(registered, "registered.mgo");
+        store.register("expired.mgo", record(expired, NOW_MS - 1));
+        store.set_reverse(expired, "expired.mgo");
+        // The name now points somewhere else, so it no longer resolves back to `moved`.
+        store.register("moved.mgo", record(registered, NOW_MS + 1));
+        store.set_reverse(moved, "moved.mgo");
+
+        let addresses = [registered, unregistered, expired, moved];
+        let resolutions = multi_reverse_resolve_addresses(&store.config, &addresses, |ids| {
+            store.multi_get_objects(ids)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            resolutions,
+            vec![
+                NameServiceResolution::new(Some("registered.mgo".to_string())),
+                NameServiceResolution::new(None),
+                NameServiceResolution::new(None),
+                NameServiceResolution::new(None),
+            ]
+        );
+    }
+}
@@ -3,7 +3,7 @@
 
 use std::collections::BTreeMap;
 
-use mgo_json_rpc_types::ObjectChange;
+use mgo_json_rpc_types::{CreatedObjectSummary, ObjectChange};
 use mgo_types::base_types::{ObjectID, ObjectRef, SequenceNumber, MgoAddress};
 use mgo_types::effects::ObjectRemoveKind;
 use mgo_types::object::Owner;
@@ -11,7 +11,7 @@ use mgo_types::storage::WriteKind;
 
 use crate::ObjectProvider;
 
-pub async fn get_object_changes<P: ObjectProvider<Error = E>, E>(
+pub async fn get_object_changes<P: ObjectProvider<Error = E> + Sync, E>(
     object_provider: &P,
     sender: MgoAddress,
     modified_at_versions: Vec<(ObjectID, SequenceNumber)>,
@@ -63,10 +63,15 @@ pub async fn get_object_changes<P: ObjectProvider<Error = E>, E>(
         };
     }
 
-    for ((id, version, _), kind) in all_removed_objects {
-        let o = object_provider
-            .find_object_lt_or_eq_version(&id, &version)
-            .await?;
+    let removed_objects = object_provider
+        .multi_find_object_lt_or_eq_version(
+            &all_removed_objects
+                .iter()
+                .map(|((id, version, _), _)| (*id, *version))
+                .collect::<Vec<_>>(),
+        )
+        .await?;
+    for (((id, version, _), kind), o) in all_removed_objects.into_iter().zip(removed_objects) {
         if let Some(o) = o {
             if let Some(type_) = o.type_() {
                 let object_type = type_.clone().into();
@@ -90,3 +95,24 @@ pub async fn get_object_changes<P: ObjectProvider<Error = E>, E>(
 
     Ok(object_changes)
 }
+
+/// Summarizes the objects created by a transaction from its `created` effects. Only the created
+/// versions are looked up, which are usually still in the provider's cache, and packages are
+/// skipped as they have no object type.
+pub async fn get_created_objects_summary<P: ObjectProvider<Error = E>, E>(
+    object_provider: &P,
+    created: Vec<(ObjectRef, Owner)>,
+) -> Result<Vec<CreatedObjectSummary>, E> {
+    let mut summary = vec![];
+    for ((object_id, version, _), owner) in created {
+        let o = object_provider.get_object(&object_id, &version).await?;
+        if let Some(type_) = o.type_() {
+            summary.push(CreatedObjectSummary {
+                object_id,
+                object_type: type_.clone().into(),
+                owner,
+            });
+        }
+    }
+    Ok(summary)
+}
@@ -0,0 +1,147 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Turns panics of RPC handlers into internal JSON-RPC errors, so that a faulty handler neither
+//! tears down the connection of the request nor skips its bookkeeping in the request metrics.
+//! Clients only get a correlation id, under which the panic and its backtrace are logged.
+
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::panic::AssertUnwindSafe;
+use std::sync::Once;
+
+use futures::{FutureExt, Stream, StreamExt};
+use jsonrpsee::types::error::INTERNAL_ERROR_CODE;
+use jsonrpsee::types::ErrorObjectOwned;
+use prometheus::{register_int_counter_vec_with_registry, IntCounterVec, Registry};
+use serde_json::json;
+use tracing::error;
+
+thread_local! {
+    /// Backtrace of the latest panic of this thread, captured by the panic hook.
+    static PANIC_BACKTRACE: RefCell<Option<Backtrace>> = RefCell::new(None);
+}
+
+tokio::task_local! {
+    /// Guard and method of the subscription being set up, inherited by its notifications.
+    static SUBSCRIPTION_GUARD: (HandlerPanicGuard, String);
+}
+
+static INSTALL_BACKTRACE_HOOK: Once = Once::new();
+
+/// Chains a panic hook recording the backtrace of every panic for the guard to log, since the
+/// backtrace is gone by the time the panic is caught.
+fn install_backtrace_hook() {
+    INSTALL_BACKTRACE_HOOK.call_once(|| {
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            PANIC_BACKTRACE.with(|backtrace| {
+                *backtrace.borrow_mut() = Some(Backtrace::force_capture());
+            });
+            previous_hook(info);
+        }));
+    });
+}
+
+#[derive(Clone, Debug)]
+pub struct HandlerPanicGuard {
+    panics: IntCounterVec,
+}
+
+impl HandlerPanicGuard {
+    pub fn new(registry: &Registry) -> Self {
+        install_backtrace_hook();
+        Self {
+            panics: register_int_counter_vec_with_registry!(
+                "rpc_handler_panic",
+                "Number of RPC handlers that panicked, by method",
+                &["method"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+
+    /// Runs the handler `f` of `method`, turning a panic into an internal error.
+    pub fn call<R>(&self, method: &str, f: impl FnOnce() -> R) -> Result<R, ErrorObjectOwned> {
+        std::panic::catch_unwind(AssertUnwindSafe(f)).map_err(|panic| self.on_panic(method, panic))
+    }
+
+    /// Runs the handler `future` of `method`, turning a panic into an internal error.
+    pub async fn call_async<F: Future>(
+        &self,
+        method: &str,
+        future: F,
+    ) -> Result<F::Output, ErrorObjectOwned> {
+        AssertUnwindSafe(future)
+            .catch_unwind()
+            .await
+            .map_err(|panic| self.on_panic(method, panic))
+    }
+
+    /// Sets up the subscription `method` with `future`, whose notifications are guarded in turn
+    /// when they go through [`guard_notifications`].
+    pub async fn subscribe<F: Future>(
+        &self,
+        method: &str,
+        future: F,
+    ) -> Result<F::Output, ErrorObjectOwned> {
+        SUBSCRIPTION_GUARD
+            .scope(
+                (self.clone(), method.to_string()),
+                self.call_async(method, future),
+            )
+            .await
+    }
+
+    fn on_panic(&self, method: &str, panic: Box<dyn Any + Send>) -> ErrorObjectOwned {
+        self.panics.with_label_values(&[method]).inc();
+        panic_error(method, panic)
+    }
+}
+
+/// Ends `notifications` with an internal error if producing one of them panics. Panics are
+/// counted when the notifications belong to a subscription set up through
+/// [`HandlerPanicGuard::subscribe`], which this must then be called within.
+pub fn guard_notifications<S: Stream>(
+    notifications: S,
+) -> impl Stream<Item = Result<S::Item, String>> {
+    let guard = SUBSCRIPTION_GUARD.try_with(Clone::clone).ok();
+    AssertUnwindSafe(notifications)
+        .catch_unwind()
+        .map(move |notification| {
+            notification.map_err(|panic| {
+                let error = match &guard {
+                    Some((guard, method)) => guard.on_panic(method, panic),
+                    None => panic_error("subscription", panic),
+                };
+                error.message().to_string()
+            })
+        })
+}
+
+/// Logs `panic` of `method` under a new correlation id, returned to the client in its stead.
+fn panic_error(method: &str, panic: Box<dyn Any + Send>) -> ErrorObjectOwned {
+    let correlation_id = format!("{:016x}", RandomState::new().build_hasher().finish());
+    let message = panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("non-string panic payload");
+    let backtrace = PANIC_BACKTRACE
+        .with(|backtrace| backtrace.borrow_mut().take())
+        .map_or_else(|| "backtrace unavailable".to_string(), |b| b.to_string());
+    error!(
+        method,
+        correlation_id, "RPC handler panicked: {message}\n{backtrace}"
+    );
+    ErrorObjectOwned::owned(
+        INTERNAL_ERROR_CODE,
+        format!("Internal error, correlation id {correlation_id}"),
+        Some(json!({ "correlationId": correlation_id })),
+    )
+}
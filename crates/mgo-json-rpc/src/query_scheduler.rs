@@ -0,0 +1,114 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Two-tier scheduling of RPC handlers, so that heavy queries such as event scans cannot starve
+//! cheap point reads of the database connections they share. Each tier admits a bounded number
+//! of concurrent handlers, and heavy handlers are admitted fewer than the connections available,
+//! leaving the rest to light handlers.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use prometheus::{register_int_gauge_vec_with_registry, IntGauge, IntGaugeVec, Registry};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Methods scheduled as heavy, and how many handlers of each tier run at once.
+#[derive(Clone, Debug)]
+pub struct QuerySchedulerConfig {
+    /// Methods scheduled as heavy, all others are light.
+    pub heavy_methods: BTreeSet<String>,
+    pub heavy_concurrency: usize,
+    pub light_concurrency: usize,
+}
+
+/// Admits handlers to their tier. The default scheduler admits every handler right away.
+#[derive(Clone, Debug, Default)]
+pub struct QueryScheduler {
+    tiers: Option<Arc<Tiers>>,
+}
+
+#[derive(Debug)]
+struct Tiers {
+    heavy_methods: BTreeSet<String>,
+    heavy: Tier,
+    light: Tier,
+}
+
+#[derive(Debug)]
+struct Tier {
+    permits: Semaphore,
+    queued: IntGauge,
+    running: IntGauge,
+}
+
+/// Admission of a handler, which leaves its tier when dropped.
+pub struct QueryPermit<'a> {
+    _permit: SemaphorePermit<'a>,
+    _running: GaugeGuard,
+}
+
+/// Counts itself in a gauge for as long as it lives.
+struct GaugeGuard(IntGauge);
+
+impl GaugeGuard {
+    fn new(gauge: &IntGauge) -> Self {
+        gauge.inc();
+        Self(gauge.clone())
+    }
+}
+
+impl Drop for GaugeGuard {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+impl QueryScheduler {
+    pub fn new(config: QuerySchedulerConfig, registry: &Registry) -> Self {
+        let queued = register_int_gauge_vec_with_registry!(
+            "rpc_scheduler_queue_depth",
+            "Number of requests waiting for their scheduling tier",
+            &["tier"],
+            registry,
+        )
+        .unwrap();
+        let running = register_int_gauge_vec_with_registry!(
+            "rpc_scheduler_running",
+            "Number of requests running in their scheduling tier",
+            &["tier"],
+            registry,
+        )
+        .unwrap();
+        let tier = |name: &str, concurrency: usize| Tier {
+            permits: Semaphore::new(concurrency),
+            queued: queued.with_label_values(&[name]),
+            running: running.with_label_values(&[name]),
+        };
+        Self {
+            tiers: Some(Arc::new(Tiers {
+                heavy: tier("heavy", config.heavy_concurrency),
+                light: tier("light", config.light_concurrency),
+                heavy_methods: config.heavy_methods,
+            })),
+        }
+    }
+
+    /// Waits until a handler of `method` may run in its tier. Returns no permit when nothing is
+    /// scheduled.
+    pub async fn acquire(&self, method: &str) -> Option<QueryPermit<'_>> {
+        let tiers = self.tiers.as_ref()?;
+        let tier = if tiers.heavy_methods.contains(method) {
+            &tiers.heavy
+        } else {
+            &tiers.light
+        };
+        let queued = GaugeGuard::new(&tier.queued);
+        // The semaphore is never closed.
+        let permit = tier.permits.acquire().await.ok()?;
+        drop(queued);
+        Some(QueryPermit {
+            _permit: permit,
+            _running: GaugeGuard::new(&tier.running),
+        })
+    }
+}
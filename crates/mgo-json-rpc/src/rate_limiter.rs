@@ -0,0 +1,265 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Throttling of RPC clients, so that a single client cannot starve the others of a public
+//! server. Every client IP is given a token bucket that each request is charged the weight of
+//! its method against, and methods may be capped to a number of requests in flight across all
+//! clients. Throttled requests are answered with [`SERVER_OVERLOADED_CODE`] and a hint of when
+//! to retry, before they are dispatched.
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use hyper::HeaderMap;
+use jsonrpsee::types::ErrorObjectOwned;
+use prometheus::{register_int_counter_vec_with_registry, IntCounterVec, Registry};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use mgo_json_rpc_api::SERVER_OVERLOADED_CODE;
+
+/// Weight of methods that neither their module nor the configuration weigh.
+pub const DEFAULT_METHOD_WEIGHT: u32 = 1;
+/// Subscriptions a websocket connection may hold at once, unless configured otherwise.
+pub const DEFAULT_MAX_SUBSCRIPTIONS_PER_CONNECTION: u32 = 100;
+/// Hint returned to requests refused by a concurrency cap, which frees up as soon as any of the
+/// requests in flight completes.
+const CONCURRENCY_RETRY_AFTER: Duration = Duration::from_millis(100);
+/// Number of clients tracked before the buckets of idle clients are dropped.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+const FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+
+/// Limits of the clients of a server. The default limits throttle nothing.
+#[derive(Clone, Debug)]
+pub struct RateLimiterConfig {
+    /// Weight every client is credited per second. Clients are not rate limited when unset.
+    pub weight_per_second: Option<u32>,
+    /// Weight a client may spend at once after being idle.
+    pub burst: u32,
+    /// Requests of each method in flight at once, across all clients, unless overridden.
+    pub default_max_concurrent: Option<usize>,
+    /// Overrides of the weights modules declare for their methods, and of the concurrency cap,
+    /// by method name.
+    pub method_limits: BTreeMap<String, MethodLimits>,
+    /// Subscriptions a websocket connection may hold at once.
+    pub max_subscriptions_per_connection: u32,
+    /// Identifies clients by the first address of their `x-forwarded-for` header rather than by
+    /// their peer address. Only for servers behind a proxy that sets the header.
+    pub use_forwarded_for: bool,
+}
+
+impl Default for RateLimiterConfig {
+    fn default() -> Self {
+        Self {
+            weight_per_second: None,
+            burst: 0,
+            default_max_concurrent: None,
+            method_limits: BTreeMap::new(),
+            max_subscriptions_per_connection: DEFAULT_MAX_SUBSCRIPTIONS_PER_CONNECTION,
+            use_forwarded_for: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MethodLimits {
+    pub weight: Option<u32>,
+    pub max_concurrent: Option<usize>,
+}
+
+/// `data` of the error returned for a throttled request.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ThrottledData {
+    /// How long the client should wait before retrying.
+    pub retry_after_ms: u64,
+}
+
+/// Admits requests within the limits of their client and method. The default limiter admits
+/// every request.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    /// Weight credited per second and the most a bucket holds, if clients are rate limited.
+    rate: Option<(f64, f64)>,
+    weights: BTreeMap<String, u32>,
+    concurrency: BTreeMap<String, Arc<Semaphore>>,
+    buckets: Mutex<HashMap<IpAddr, Bucket>>,
+    max_subscriptions_per_connection: u32,
+    use_forwarded_for: bool,
+    throttled: Option<IntCounterVec>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Admission of a request, which releases its concurrency slot when dropped.
+pub struct RatePermit {
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                rate: None,
+                weights: BTreeMap::new(),
+                concurrency: BTreeMap::new(),
+                buckets: Mutex::new(HashMap::new()),
+                max_subscriptions_per_connection: DEFAULT_MAX_SUBSCRIPTIONS_PER_CONNECTION,
+                use_forwarded_for: false,
+                throttled: None,
+            }),
+        }
+    }
+}
+
+impl RateLimiter {
+    /// Limits `methods` as `config` sets, weighing them as `module_weights` declares unless
+    /// `config` overrides their weight.
+    pub fn new(
+        config: RateLimiterConfig,
+        module_weights: &BTreeMap<String, u32>,
+        methods: &[&str],
+        registry: &Registry,
+    ) -> Self {
+        let throttled = register_int_counter_vec_with_registry!(
+            "rpc_throttled_requests",
+            "Number of requests refused by the rate limiter, by method and limit",
+            &["method", "limit"],
+            registry,
+        )
+        .unwrap();
+
+        let mut weights = module_weights.clone();
+        let mut concurrency = BTreeMap::new();
+        for method in methods {
+            let limits = config
+                .method_limits
+                .get(*method)
+                .cloned()
+                .unwrap_or_default();
+            if let Some(weight) = limits.weight {
+                weights.insert(method.to_string(), weight);
+            }
+            if let Some(max_concurrent) = limits.max_concurrent.or(config.default_max_concurrent)
+            {
+                concurrency.insert(method.to_string(), Arc::new(Semaphore::new(max_concurrent)));
+            }
+        }
+        let rate = config.weight_per_second.map(|weight_per_second| {
+            // A bucket always holds at least a second worth of weight.
+            let burst = config.burst.max(weight_per_second);
+            (weight_per_second as f64, burst as f64)
+        });
+
+        Self {
+            inner: Arc::new(Inner {
+                rate,
+                weights,
+                concurrency,
+                buckets: Mutex::new(HashMap::new()),
+                max_subscriptions_per_connection: config.max_subscriptions_per_connection,
+                use_forwarded_for: config.use_forwarded_for,
+                throttled: Some(throttled),
+            }),
+        }
+    }
+
+    pub fn max_subscriptions_per_connection(&self) -> u32 {
+        self.inner.max_subscriptions_per_connection
+    }
+
+    /// The client a request is throttled as, from its peer address and `headers`.
+    pub fn client(&self, peer: Option<SocketAddr>, headers: &HeaderMap) -> Option<IpAddr> {
+        let forwarded = self
+            .inner
+            .use_forwarded_for
+            .then(|| headers.get(FORWARDED_FOR_HEADER)?.to_str().ok())
+            .flatten()
+            .and_then(|value| value.split(',').next()?.trim().parse().ok());
+        forwarded.or(peer.map(|peer| peer.ip()))
+    }
+
+    /// Admits a request of `method` from `client`, or returns the error to answer it with.
+    pub fn acquire(
+        &self,
+        method: &str,
+        client: Option<IpAddr>,
+    ) -> Result<RatePermit, ErrorObjectOwned> {
+        let permit = match self.inner.concurrency.get(method) {
+            Some(semaphore) => match semaphore.clone().try_acquire_owned() {
+                Ok(permit) => Some(permit),
+                Err(_) => {
+                    return Err(self.throttle(method, "concurrency", CONCURRENCY_RETRY_AFTER))
+                }
+            },
+            None => None,
+        };
+
+        if let (Some((rate, burst)), Some(client)) = (self.inner.rate, client) {
+            let weight = self
+                .inner
+                .weights
+                .get(method)
+                .copied()
+                .unwrap_or(DEFAULT_METHOD_WEIGHT) as f64;
+            // Methods weighing more than a full bucket would never be admitted otherwise.
+            let weight = weight.min(burst);
+            let now = Instant::now();
+            let mut buckets = self.inner.buckets.lock().unwrap();
+            if buckets.len() >= MAX_TRACKED_CLIENTS {
+                buckets.retain(|_, bucket| bucket.refilled(now, rate, burst) < burst);
+            }
+            let bucket = buckets.entry(client).or_insert(Bucket {
+                tokens: burst,
+                updated: now,
+            });
+            bucket.tokens = bucket.refilled(now, rate, burst);
+            bucket.updated = now;
+            if bucket.tokens < weight {
+                let retry_after = Duration::from_secs_f64((weight - bucket.tokens) / rate);
+                return Err(self.throttle(method, "rate", retry_after));
+            }
+            bucket.tokens -= weight;
+        }
+        Ok(RatePermit { _permit: permit })
+    }
+
+    /// Counts a subscription of `method` refused for the subscriptions its connection holds.
+    pub fn on_subscription_rejected(&self, method: &str) {
+        if let Some(throttled) = &self.inner.throttled {
+            throttled
+                .with_label_values(&[method, "subscriptions"])
+                .inc();
+        }
+    }
+
+    fn throttle(&self, method: &str, limit: &str, retry_after: Duration) -> ErrorObjectOwned {
+        if let Some(throttled) = &self.inner.throttled {
+            throttled.with_label_values(&[method, limit]).inc();
+        }
+        ErrorObjectOwned::owned(
+            SERVER_OVERLOADED_CODE,
+            format!("Too many requests to `{method}`, retry later"),
+            Some(ThrottledData {
+                retry_after_ms: retry_after.as_millis().max(1) as u64,
+            }),
+        )
+    }
+}
+
+impl Bucket {
+    fn refilled(&self, now: Instant, rate: f64, burst: f64) -> f64 {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        (self.tokens + elapsed * rate).min(burst)
+    }
+}
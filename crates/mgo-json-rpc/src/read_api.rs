@@ -1,11 +1,13 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use anyhow::anyhow;
 use async_trait::async_trait;
+use fastcrypto::encoding::Base64;
 use futures::future::join_all;
 use indexmap::map::IndexMap;
 use itertools::Itertools;
@@ -13,51 +15,108 @@ use jsonrpsee::core::RpcResult;
 use jsonrpsee::RpcModule;
 use move_bytecode_utils::module_cache::GetModule;
 use move_core_types::annotated_value::{MoveStruct, MoveStructLayout, MoveValue};
-use move_core_types::language_storage::StructTag;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::language_storage::{StructTag, TypeTag};
 use tap::TapFallible;
 use tracing::{debug, error, info, instrument, trace, warn};
 
 use mango_metrics::spawn_monitored_task;
 use mgo_core::authority::AuthorityState;
 use mgo_json_rpc_api::{
-    validate_limit, JsonRpcMetrics, ReadApiOpenRpc, ReadApiServer, QUERY_MAX_RESULT_LIMIT,
-    QUERY_MAX_RESULT_LIMIT_CHECKPOINTS,
+    server_limits, validate_limit, JsonRpcMetrics, ReadApiOpenRpc, ReadApiServer,
+    QUERY_MAX_RESULT_LIMIT, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS,
 };
 use mgo_json_rpc_types::{
-    BalanceChange, Checkpoint, CheckpointId, CheckpointPage, DisplayFieldsResponse, EventFilter,
-    ObjectChange, ProtocolConfigResponse, MgoEvent, MgoGetPastObjectRequest, MgoMoveStruct,
-    MgoMoveValue, MgoObjectDataOptions, MgoObjectResponse, MgoPastObjectResponse,
+    BalanceChange, BcsResponse, Checkpoint, CheckpointBcsV1, CheckpointId, CheckpointPage,
+    CheckpointTransactionBcsV1, CheckpointTransactionsBcsV1, ClientLimits, CreatedObjectSummary,
+    DisplayFieldsResponse, EncodedResponse, EpochStartInfo, EventFilter, ObjectChange,
+    ObjectReadBcsV1, ProtocolConfigResponse, ResponseEncoding,
+    MgoEvent, MgoGetPastObjectRequest, MgoMoveStruct, MgoMoveValue,
+    MgoObjectData, MgoObjectDataOptions, MgoObjectResponse, MgoPastObjectResponse,
+    MgoPreviousTransactionDetails,
     MgoTransactionBlock, MgoTransactionBlockEvents, MgoTransactionBlockResponse,
-    MgoTransactionBlockResponseOptions,
+    MgoTransactionBlockResponseOptions, TransactionBlockBcsV1, TransactionSignatureVerification,
+};
+use mgo_json_rpc_types::{
+    MgoLoadedChildObject, MgoLoadedChildObjectsResponse, MgoUnresolvedChildObject,
 };
-use mgo_json_rpc_types::{MgoLoadedChildObject, MgoLoadedChildObjectsResponse};
 use mgo_open_rpc::Module;
 use mgo_protocol_config::{ProtocolConfig, ProtocolVersion};
 use mgo_storage::key_value_store::TransactionKeyValueStore;
-use mgo_types::base_types::{ObjectID, SequenceNumber, TransactionDigest};
+use mgo_types::base_types::{ObjectID, ObjectType, SequenceNumber, TransactionDigest};
 use mgo_types::collection_types::VecMap;
+use mgo_types::committee::EpochId;
 use mgo_types::crypto::AggregateAuthoritySignature;
 use mgo_types::digests::TransactionEventsDigest;
 use mgo_types::display::DisplayVersionUpdatedEvent;
 use mgo_types::effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents};
-use mgo_types::error::{MgoError, MgoObjectResponseError};
+use mgo_types::error::{MgoError, MgoObjectResponseError, UserInputError};
 use mgo_types::messages_checkpoint::{
     CheckpointContents, CheckpointContentsDigest, CheckpointSequenceNumber, CheckpointSummary,
     CheckpointTimestamp,
 };
+use mgo_types::move_package::UpgradeCap;
 use mgo_types::object::{Object, ObjectRead, PastObjectRead};
 use mgo_types::mgo_serde::BigInt;
+use mgo_types::signature::{GenericSignature, VerifyParams};
 use mgo_types::transaction::Transaction;
-use mgo_types::transaction::TransactionDataAPI;
+use mgo_types::storage::BackingPackageStore;
+use mgo_types::transaction::{TransactionData, TransactionDataAPI};
+use mgo_types::is_system_package;
+use shared_crypto::intent::{Intent, IntentMessage};
 
-use crate::authority_state::{StateRead, StateReadError, StateReadResult};
+use crate::authority_state::{
+    CheckpointTransactionsOptions, StateRead, StateReadClientError, StateReadError, StateReadResult,
+};
+use crate::coin_api::find_package_object_id;
 use crate::error::{Error, RpcInterimResult, MgoRpcInputError};
+use crate::verification_api::{
+    decode_transaction_signature, verify_transaction_signatures, MAX_SIGNATURE_VERIFICATION_BATCH,
+};
 use crate::with_tracing;
 use crate::{
-    get_balance_changes_from_effect, get_object_changes, ObjectProviderCache, MgoRpcModule,
+    get_balance_changes_from_effect, get_created_objects_summary, get_object_changes,
+    ObjectProvider, ObjectProviderCache, MgoRpcModule,
 };
 
-const MAX_DISPLAY_NESTED_LEVEL: usize = 10;
+/// Hard limits applied when rendering the Display template of a single object.
+#[derive(Clone, Debug)]
+pub struct DisplayRenderLimits {
+    /// Maximum number of `{...}` substitutions across all fields of the template.
+    pub max_substitutions: usize,
+    /// Maximum number of `.`-separated segments in a single field path.
+    pub max_field_depth: usize,
+    /// Wall-clock budget for rendering all fields of the object.
+    pub time_budget: Duration,
+}
+
+impl DisplayRenderLimits {
+    pub const DEFAULT_MAX_SUBSTITUTIONS: usize = 256;
+    pub const DEFAULT_MAX_FIELD_DEPTH: usize = 10;
+    pub const DEFAULT_TIME_BUDGET: Duration = Duration::from_millis(100);
+}
+
+impl Default for DisplayRenderLimits {
+    fn default() -> Self {
+        Self {
+            max_substitutions: Self::DEFAULT_MAX_SUBSTITUTIONS,
+            max_field_depth: Self::DEFAULT_MAX_FIELD_DEPTH,
+            time_budget: Self::DEFAULT_TIME_BUDGET,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum DisplayLimitExceeded {
+    #[error("Display template exceeds the limit of {0} substitutions")]
+    Substitutions(usize),
+
+    #[error("Display template field path exceeds the maximum depth of {0}")]
+    FieldDepth(usize),
+
+    #[error("Display rendering exceeded the time budget of {0:?}")]
+    TimeBudget(Duration),
+}
 
 // An implementation of the read portion of the JSON-RPC interface intended for use in
 // Fullnodes.
@@ -66,6 +125,10 @@ pub struct ReadApi {
     pub state: Arc<dyn StateRead>,
     pub transaction_kv_store: Arc<TransactionKeyValueStore>,
     pub metrics: Arc<JsonRpcMetrics>,
+    /// Maximum number of past objects read by a single `tryMultiGetPastObjects` request.
+    pub max_past_objects_batch_size: usize,
+    /// Limits applied when rendering the Display of each returned object.
+    pub display_limits: DisplayRenderLimits,
 }
 
 // Internal data structure to make it easy to work with data returned from
@@ -80,7 +143,9 @@ struct IntermediateTransactionResponse {
     checkpoint_seq: Option<CheckpointSequenceNumber>,
     balance_changes: Option<Vec<BalanceChange>>,
     object_changes: Option<Vec<ObjectChange>>,
+    created_summary: Option<Vec<CreatedObjectSummary>>,
     timestamp: Option<CheckpointTimestamp>,
+    epoch: Option<EpochId>,
     errors: Vec<String>,
 }
 
@@ -107,7 +172,269 @@ impl ReadApi {
             state,
             transaction_kv_store,
             metrics,
+            max_past_objects_batch_size: *QUERY_MAX_RESULT_LIMIT,
+            display_limits: DisplayRenderLimits::default(),
+        }
+    }
+
+    pub fn with_max_past_objects_batch_size(mut self, max_past_objects_batch_size: usize) -> Self {
+        self.max_past_objects_batch_size = max_past_objects_batch_size;
+        self
+    }
+
+    pub fn with_display_limits(mut self, display_limits: DisplayRenderLimits) -> Self {
+        self.display_limits = display_limits;
+        self
+    }
+
+    async fn past_object_response(
+        &self,
+        past_read: PastObjectRead,
+        version: SequenceNumber,
+        options: MgoObjectDataOptions,
+    ) -> Result<MgoPastObjectResponse, Error> {
+        match past_read {
+            PastObjectRead::ObjectNotExists(id) => Ok(MgoPastObjectResponse::ObjectNotExists(id)),
+            PastObjectRead::VersionFound(object_ref, o, layout) => {
+                if let Some(layout) = &layout {
+                    options
+                        .check_field_mask(layout)
+                        .map_err(|e| MgoRpcInputError::GenericInvalid(e.to_string()))?;
+                }
+                let latest_type = self.get_latest_type(&o, &options).await?;
+                let display_fields = if options.show_display {
+                    // TODO (jian): api breaking change to also modify past objects.
+                    Some(
+                        get_display_fields(self, &self.transaction_kv_store, &o, &layout)
+                            .await
+                            .map_err(|e| {
+                                Error::UnexpectedError(format!(
+                                    "Unable to render object at version {version}: {e}"
+                                ))
+                            })?,
+                    )
+                } else {
+                    None
+                };
+                let mut data: MgoObjectData =
+                    (object_ref, o, layout, options, display_fields).try_into()?;
+                data.latest_type = latest_type;
+                Ok(MgoPastObjectResponse::VersionFound(data))
+            }
+            PastObjectRead::ObjectDeleted(oref) => {
+                Ok(MgoPastObjectResponse::ObjectDeleted(oref.into()))
+            }
+            PastObjectRead::VersionNotFound(id, seq_num) => {
+                Ok(MgoPastObjectResponse::VersionNotFound(id, seq_num))
+            }
+            PastObjectRead::VersionTooHigh {
+                object_id,
+                asked_version,
+                latest_version,
+            } => Ok(MgoPastObjectResponse::VersionTooHigh {
+                object_id,
+                asked_version,
+                latest_version,
+            }),
+        }
+    }
+
+    fn get_object_layout(&self, object: &Object) -> Result<Option<MoveStructLayout>, Error> {
+        let Some(move_object) = object.data.try_as_move() else {
+            return Ok(None);
+        };
+        let epoch_store = self.state.load_epoch_store_one_call_per_task();
+        let backing_package_store = self.state.get_backing_package_store();
+        let layout = epoch_store
+            .executor()
+            .type_layout_resolver(Box::new(backing_package_store.as_ref()))
+            .get_annotated_layout(&move_object.type_().clone().into())?;
+        Ok(Some(layout))
+    }
+
+    /// The loaded child objects `children` with their contents at the loaded version, as
+    /// selected by `options`, for at most `max_past_objects_batch_size` of them.
+    async fn resolve_loaded_child_objects(
+        &self,
+        children: Vec<(ObjectID, SequenceNumber)>,
+        options: MgoObjectDataOptions,
+    ) -> Result<Vec<MgoLoadedChildObject>, Error> {
+        let object_provider = (self.state.clone(), self.transaction_kv_store.clone());
+        let reads = read_loaded_child_objects(
+            &object_provider,
+            &children,
+            self.max_past_objects_batch_size,
+        )
+        .await?;
+        let mut loaded_child_objects = Vec::with_capacity(children.len());
+        for ((object_id, version), read) in children.into_iter().zip(reads) {
+            let child = MgoLoadedChildObject::new(object_id, version);
+            let object = match read {
+                Ok(object) => object,
+                Err(unresolved) => {
+                    loaded_child_objects.push(child.with_unresolved(unresolved));
+                    continue;
+                }
+            };
+            let layout = self.get_object_layout(&object)?;
+            let past_read =
+                PastObjectRead::VersionFound(object.compute_object_reference(), object, layout);
+            match self
+                .past_object_response(past_read, version, options.clone())
+                .await?
+            {
+                MgoPastObjectResponse::VersionFound(data) => {
+                    loaded_child_objects.push(child.with_data(data))
+                }
+                response => {
+                    return Err(Error::UnexpectedError(format!(
+                        "Unexpected response for loaded child object {object_id}: {response:?}"
+                    )))
+                }
+            }
+        }
+        Ok(loaded_child_objects)
+    }
+
+    async fn object_read(&self, object_id: ObjectID) -> Result<ObjectRead, Error> {
+        let state = self.state.clone();
+        spawn_monitored_task!(async move {
+            state.get_object_read(&object_id).map_err(|e| {
+                warn!(?object_id, "Failed to get object: {:?}", e);
+                Error::from(e)
+            })
+        })
+        .await
+        .map_err(Error::from)?
+    }
+
+    /// Rejects the field mask of `options` if it names fields the object of `object_read` does
+    /// not have, before any response is built.
+    fn check_field_mask(
+        object_read: &ObjectRead,
+        options: &MgoObjectDataOptions,
+    ) -> Result<(), Error> {
+        if let ObjectRead::Exists(_, _, Some(layout)) = object_read {
+            options
+                .check_field_mask(layout)
+                .map_err(|e| MgoRpcInputError::GenericInvalid(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    async fn object_response(
+        &self,
+        object_read: ObjectRead,
+        options: &MgoObjectDataOptions,
+    ) -> Result<MgoObjectResponse, Error> {
+        match object_read {
+            ObjectRead::NotExists(id) => Ok(MgoObjectResponse::new_with_error(
+                MgoObjectResponseError::NotExists { object_id: id },
+            )),
+            ObjectRead::Exists(object_ref, o, layout) => {
+                let latest_type = self.get_latest_type(&o, options).await?;
+                let mut display_fields = None;
+                let mut display_error = None;
+                if options.show_display {
+                    match get_display_fields(self, &self.transaction_kv_store, &o, &layout).await {
+                        Ok(rendered_fields) => display_fields = Some(rendered_fields),
+                        Err(e) => {
+                            display_error = Some(MgoObjectResponseError::DisplayError {
+                                error: e.to_string(),
+                            })
+                        }
+                    }
+                }
+                let mut data: MgoObjectData =
+                    (object_ref, o, layout, options.clone(), display_fields).try_into()?;
+                data.latest_type = latest_type;
+                Ok(MgoObjectResponse::new(Some(data), display_error))
+            }
+            ObjectRead::Deleted((object_id, version, digest)) => Ok(
+                MgoObjectResponse::new_with_error(MgoObjectResponseError::Deleted {
+                    object_id,
+                    version,
+                    digest,
+                }),
+            ),
+        }
+    }
+
+    /// Adds the details of their previous transaction to `objects`, looking up each transaction
+    /// once. Objects whose previous transaction was pruned are left without details.
+    async fn add_previous_transaction_details(
+        &self,
+        objects: Vec<&mut MgoObjectData>,
+    ) -> Result<(), Error> {
+        let digests = objects
+            .iter()
+            .filter_map(|object| object.previous_transaction)
+            .unique()
+            .collect::<Vec<_>>();
+        if digests.is_empty() {
+            return Ok(());
+        }
+        let (transactions, _, _) = self.state.multi_get(&digests, &[], &[]).await?;
+        let checkpoints = self
+            .transaction_kv_store
+            .multi_get_transaction_checkpoint(&digests)
+            .await?;
+
+        let checkpoint_numbers = checkpoints
+            .iter()
+            .flatten()
+            .copied()
+            .unique()
+            .collect::<Vec<CheckpointSequenceNumber>>();
+        let summaries = self
+            .transaction_kv_store
+            .multi_get_checkpoints_summaries(&checkpoint_numbers)
+            .await?;
+        let checkpoint_to_timestamp = checkpoint_numbers
+            .into_iter()
+            .zip(summaries)
+            .filter_map(|(seq, summary)| Some((seq, summary?.timestamp_ms)))
+            .collect::<HashMap<_, _>>();
+
+        let details = digests
+            .into_iter()
+            .zip(transactions)
+            .zip(checkpoints)
+            .filter_map(|((digest, transaction), checkpoint)| {
+                let details = MgoPreviousTransactionDetails {
+                    sender: transaction?.data().transaction_data().sender(),
+                    checkpoint,
+                    timestamp_ms: checkpoint
+                        .and_then(|seq| checkpoint_to_timestamp.get(&seq).copied()),
+                };
+                Some((digest, details))
+            })
+            .collect::<HashMap<_, _>>();
+        for object in objects {
+            object.previous_transaction_details = object
+                .previous_transaction
+                .and_then(|digest| details.get(&digest).cloned());
+        }
+        Ok(())
+    }
+
+    /// The type of `object` with its packages resolved to their latest upgrades, if requested.
+    async fn get_latest_type(
+        &self,
+        object: &Object,
+        options: &MgoObjectDataOptions,
+    ) -> Result<Option<ObjectType>, Error> {
+        if !options.resolve_type_to_latest {
+            return Ok(None);
         }
+        Ok(Some(match ObjectType::from(object) {
+            ObjectType::Package => ObjectType::Package,
+            ObjectType::Struct(type_) => ObjectType::Struct(
+                get_latest_struct_tag(&self.state, &self.transaction_kv_store, &type_.into())
+                    .await?
+                    .into(),
+            ),
+        }))
     }
 
     async fn get_checkpoint_internal(&self, id: CheckpointId) -> Result<Checkpoint, Error> {
@@ -139,6 +466,29 @@ impl ReadApi {
         })
     }
 
+    async fn get_checkpoint_bcs_internal(
+        &self,
+        id: CheckpointId,
+    ) -> Result<CheckpointBcsV1, Error> {
+        let summary = match id {
+            CheckpointId::SequenceNumber(seq) => {
+                self.transaction_kv_store
+                    .get_checkpoint_summary(seq)
+                    .await?
+            }
+            CheckpointId::Digest(digest) => {
+                self.transaction_kv_store
+                    .get_checkpoint_summary_by_digest(digest)
+                    .await?
+            }
+        };
+        let contents = self
+            .transaction_kv_store
+            .get_checkpoint_contents_by_digest(summary.content_digest)
+            .await?;
+        Ok(CheckpointBcsV1 { summary, contents })
+    }
+
     pub async fn get_checkpoints_internal(
         state: Arc<dyn StateRead>,
         transaction_kv_store: Arc<TransactionKeyValueStore>,
@@ -146,21 +496,15 @@ impl ReadApi {
         cursor: Option<CheckpointSequenceNumber>,
         limit: u64,
         descending_order: bool,
+        epoch: Option<EpochId>,
     ) -> StateReadResult<Vec<Checkpoint>> {
-        let max_checkpoint = state.get_latest_checkpoint_sequence_number()?;
-        let checkpoint_numbers =
-            calculate_checkpoint_numbers(cursor, limit, descending_order, max_checkpoint);
-
-        let verified_checkpoints = transaction_kv_store
-            .multi_get_checkpoints_summaries(&checkpoint_numbers)
-            .await?;
+        let verified_checkpoints = state.get_checkpoints(cursor, limit, descending_order, epoch)?;
 
         let checkpoint_summaries_and_signatures: Vec<(
             CheckpointSummary,
             AggregateAuthoritySignature,
         )> = verified_checkpoints
             .into_iter()
-            .flatten()
             .map(|check| {
                 (
                     check.clone().into_summary_and_sequence().1,
@@ -195,6 +539,207 @@ impl ReadApi {
         Ok(checkpoints)
     }
 
+    async fn get_epoch_start_info_internal(&self, epoch: EpochId) -> Result<EpochStartInfo, Error> {
+        let first_checkpoint = self
+            .state
+            .get_checkpoints(None, 1, false, Some(epoch))?
+            .pop()
+            .ok_or(StateReadError::Client(StateReadClientError::EpochNotStarted(epoch)))?
+            .sequence_number;
+        let committee = self.state.get_or_latest_committee(Some(epoch.into()))?;
+        let (previous_committee, previous_epoch_last_checkpoint) = match epoch.checked_sub(1) {
+            None => (None, None),
+            Some(previous_epoch) => {
+                let previous_committee = self
+                    .state
+                    .get_or_latest_committee(Some(previous_epoch.into()))?;
+                // The first checkpoint of an epoch directly follows the last one of the previous
+                // epoch.
+                let last_checkpoint = self
+                    .get_checkpoint_internal(CheckpointId::SequenceNumber(first_checkpoint - 1))
+                    .await?;
+                (Some(previous_committee.into()), Some(last_checkpoint))
+            }
+        };
+        Ok(EpochStartInfo {
+            epoch,
+            committee: committee.into(),
+            first_checkpoint: self
+                .get_checkpoint_internal(CheckpointId::SequenceNumber(first_checkpoint))
+                .await?,
+            previous_committee,
+            previous_epoch_last_checkpoint,
+        })
+    }
+
+    /// Check `signatures` against `tx_data` as of the current epoch.
+    pub fn verify_transaction_block_signature_internal(
+        state: &dyn StateRead,
+        tx_data: TransactionData,
+        signatures: &[Base64],
+    ) -> StateReadResult<TransactionSignatureVerification> {
+        let epoch = state.get_or_latest_committee(None)?.epoch;
+        let signatures: Vec<_> = signatures
+            .iter()
+            .map(decode_transaction_signature)
+            .collect();
+        // Only zkLogin signatures, alone or within a multisig, depend on the JWKs of the epoch.
+        let verify_params = if signatures.iter().any(|signature| {
+            matches!(
+                signature,
+                Ok(GenericSignature::ZkLoginAuthenticator(_) | GenericSignature::MultiSig(_))
+            )
+        }) {
+            state
+                .load_epoch_store_one_call_per_task()
+                .signature_verify_params()
+        } else {
+            VerifyParams::default()
+        };
+        Ok(verify_transaction_signatures(
+            &IntentMessage::new(Intent::mgo_transaction(), tx_data),
+            signatures,
+            epoch,
+            &verify_params,
+        ))
+    }
+
+    /// At most `limit` transactions of checkpoint `sequence_number`, starting after `cursor`.
+    pub async fn get_checkpoint_transactions_internal(
+        state: Arc<dyn StateRead>,
+        sequence_number: CheckpointSequenceNumber,
+        cursor: Option<TransactionDigest>,
+        limit: usize,
+    ) -> StateReadResult<CheckpointTransactionsBcsV1> {
+        let mut transactions = state
+            .get_checkpoint_transactions(
+                sequence_number,
+                CheckpointTransactionsOptions {
+                    cursor,
+                    limit: limit + 1,
+                },
+            )
+            .await?;
+
+        let has_next_page = transactions.len() > limit;
+        transactions.truncate(limit);
+        let next_cursor = if has_next_page {
+            transactions.last().map(|tx| tx.digest)
+        } else {
+            None
+        };
+
+        Ok(CheckpointTransactionsBcsV1 {
+            checkpoint: sequence_number,
+            data: transactions
+                .into_iter()
+                .map(|tx| CheckpointTransactionBcsV1 {
+                    digest: tx.digest,
+                    transaction: tx.transaction.map(Transaction::into_data),
+                    effects: tx.effects,
+                    events: tx.events,
+                })
+                .collect(),
+            next_cursor,
+        })
+    }
+
+    async fn multi_get_transaction_blocks_bcs_internal(
+        &self,
+        digests: Vec<TransactionDigest>,
+    ) -> Result<Vec<TransactionBlockBcsV1>, Error> {
+        if digests.len() > *QUERY_MAX_RESULT_LIMIT {
+            Err(MgoRpcInputError::SizeLimitExceeded(
+                QUERY_MAX_RESULT_LIMIT.to_string(),
+            ))?
+        }
+        self.metrics
+            .get_tx_blocks_limit
+            .report(digests.len() as u64);
+        if digests.iter().unique().count() < digests.len() {
+            Err(MgoRpcInputError::ContainsDuplicates)?
+        }
+
+        let (transactions, effects, _) = self
+            .transaction_kv_store
+            .multi_get(&digests, &digests, &[])
+            .await?;
+        let checkpoints = self
+            .transaction_kv_store
+            .multi_get_transaction_checkpoint(&digests)
+            .await?;
+
+        let mut blocks = Vec::with_capacity(digests.len());
+        for (((digest, transaction), effects), checkpoint) in digests
+            .iter()
+            .zip(transactions)
+            .zip(effects)
+            .zip(checkpoints)
+        {
+            let (Some(transaction), Some(effects)) = (transaction, effects) else {
+                Err(MgoRpcInputError::GenericNotFound(format!(
+                    "Transaction {digest} was not found"
+                )))?
+            };
+            blocks.push(TransactionBlockBcsV1 {
+                digest: *digest,
+                transaction: transaction.into_data(),
+                effects,
+                events: None,
+                checkpoint,
+                timestamp_ms: None,
+            });
+        }
+
+        let event_digests = blocks
+            .iter()
+            .filter_map(|block| block.effects.events_digest().cloned())
+            .collect::<Vec<TransactionEventsDigest>>();
+        let events = self
+            .transaction_kv_store
+            .multi_get_events(&event_digests)
+            .await?;
+        let event_digest_to_events = event_digests
+            .into_iter()
+            .zip(events)
+            .collect::<HashMap<_, _>>();
+
+        let checkpoint_numbers = blocks
+            .iter()
+            .filter_map(|block| block.checkpoint)
+            .unique()
+            .collect::<Vec<CheckpointSequenceNumber>>();
+        let summaries = self
+            .transaction_kv_store
+            .multi_get_checkpoints_summaries(&checkpoint_numbers)
+            .await?;
+        let checkpoint_to_timestamp = checkpoint_numbers
+            .into_iter()
+            .zip(summaries)
+            .filter_map(|(seq, summary)| Some((seq, summary?.timestamp_ms)))
+            .collect::<HashMap<_, _>>();
+
+        for block in &mut blocks {
+            if let Some(event_digest) = block.effects.events_digest() {
+                let events = event_digest_to_events
+                    .get(event_digest)
+                    .cloned()
+                    .flatten()
+                    .ok_or_else(|| {
+                        Error::UnexpectedError(format!(
+                            "Failed to fetch events with event digest {event_digest:?} for txn {}",
+                            block.digest
+                        ))
+                    })?;
+                block.events = Some(events);
+            }
+            block.timestamp_ms = block
+                .checkpoint
+                .and_then(|seq| checkpoint_to_timestamp.get(&seq).copied());
+        }
+        Ok(blocks)
+    }
+
     async fn multi_get_transaction_blocks_internal(
         &self,
         digests: Vec<TransactionDigest>,
@@ -279,7 +824,7 @@ impl ReadApi {
             .unique()
             .collect::<Vec<CheckpointSequenceNumber>>();
 
-        // fetch timestamp from the DB
+        // fetch timestamp and epoch from the DB
         trace!("getting checkpoint summaries");
         let timestamps = self
             .transaction_kv_store
@@ -289,19 +834,19 @@ impl ReadApi {
                 Error::UnexpectedError(format!("Failed to fetch checkpoint summaries by these checkpoint ids: {unique_checkpoint_numbers:?} with error: {e:?}"))
             })?
             .into_iter()
-            .map(|c| c.map(|checkpoint| checkpoint.timestamp_ms));
+            .map(|c| c.map(|checkpoint| (checkpoint.timestamp_ms, checkpoint.epoch)));
 
-        // construct a hashmap of checkpoint -> timestamp for fast lookup
+        // construct a hashmap of checkpoint -> (timestamp, epoch) for fast lookup
         let checkpoint_to_timestamp = unique_checkpoint_numbers
             .into_iter()
             .zip(timestamps)
             .collect::<HashMap<_, _>>();
 
-        // fill cache with the timestamp
+        // fill cache with the timestamp and epoch
         for (_, cache_entry) in temp_response.iter_mut() {
             if cache_entry.checkpoint_seq.is_some() {
                 // safe to unwrap because is_some is checked
-                cache_entry.timestamp = *checkpoint_to_timestamp
+                let timestamp_and_epoch = *checkpoint_to_timestamp
                     .get(
                         cache_entry
                             .checkpoint_seq
@@ -311,6 +856,8 @@ impl ReadApi {
                     )
                     // Safe to unwrap because checkpoint_seq is guaranteed to exist in checkpoint_to_timestamp
                     .unwrap();
+                cache_entry.timestamp = timestamp_and_epoch.map(|(timestamp, _)| timestamp);
+                cache_entry.epoch = timestamp_and_epoch.map(|(_, epoch)| epoch);
             }
         }
 
@@ -458,6 +1005,33 @@ impl ReadApi {
             }
         }
 
+        if opts.show_created_summary {
+            trace!("getting created objects summary");
+
+            let mut results = vec![];
+            for resp in temp_response.values() {
+                let effects = resp.effects.as_ref().ok_or_else(|| {
+                    MgoRpcInputError::GenericNotFound(
+                        "unable to derive created objects because effect is empty".to_string(),
+                    )
+                })?;
+                results.push(get_created_objects_summary(
+                    &object_cache,
+                    effects.created(),
+                ));
+            }
+            let results = join_all(results).await;
+            for (result, entry) in results.into_iter().zip(temp_response.iter_mut()) {
+                match result {
+                    Ok(created_summary) => entry.1.created_summary = Some(created_summary),
+                    Err(e) => entry
+                        .1
+                        .errors
+                        .push(format!("Failed to fetch created objects {e:?}")),
+                }
+            }
+        }
+
         let epoch_store = self.state.load_epoch_store_one_call_per_task();
         let converted_tx_block_resps = temp_response
             .into_iter()
@@ -486,50 +1060,15 @@ impl ReadApiServer for ReadApi {
         options: Option<MgoObjectDataOptions>,
     ) -> RpcResult<MgoObjectResponse> {
         with_tracing!(async move {
-            let state = self.state.clone();
-            let object_read = spawn_monitored_task!(async move {
-                state.get_object_read(&object_id).map_err(|e| {
-                    warn!(?object_id, "Failed to get object: {:?}", e);
-                    Error::from(e)
-                })
-            })
-            .await
-            .map_err(Error::from)??;
             let options = options.unwrap_or_default();
-
-            match object_read {
-                ObjectRead::NotExists(id) => Ok(MgoObjectResponse::new_with_error(
-                    MgoObjectResponseError::NotExists { object_id: id },
-                )),
-                ObjectRead::Exists(object_ref, o, layout) => {
-                    let mut display_fields = None;
-                    if options.show_display {
-                        match get_display_fields(self, &self.transaction_kv_store, &o, &layout)
-                            .await
-                        {
-                            Ok(rendered_fields) => display_fields = Some(rendered_fields),
-                            Err(e) => {
-                                return Ok(MgoObjectResponse::new(
-                                    Some((object_ref, o, layout, options, None).try_into()?),
-                                    Some(MgoObjectResponseError::DisplayError {
-                                        error: e.to_string(),
-                                    }),
-                                ));
-                            }
-                        }
-                    }
-                    Ok(MgoObjectResponse::new_with_data(
-                        (object_ref, o, layout, options, display_fields).try_into()?,
-                    ))
-                }
-                ObjectRead::Deleted((object_id, version, digest)) => Ok(
-                    MgoObjectResponse::new_with_error(MgoObjectResponseError::Deleted {
-                        object_id,
-                        version,
-                        digest,
-                    }),
-                ),
+            let object_read = self.object_read(object_id).await?;
+            Self::check_field_mask(&object_read, &options)?;
+            let mut response = self.object_response(object_read, &options).await?;
+            if options.show_previous_transaction_details {
+                self.add_previous_transaction_details(response.data.iter_mut().collect())
+                    .await?;
             }
+            Ok(response)
         })
     }
 
@@ -538,40 +1077,53 @@ impl ReadApiServer for ReadApi {
         &self,
         object_ids: Vec<ObjectID>,
         options: Option<MgoObjectDataOptions>,
-    ) -> RpcResult<Vec<MgoObjectResponse>> {
+        encoding: Option<ResponseEncoding>,
+    ) -> RpcResult<EncodedResponse<Vec<MgoObjectResponse>>> {
         with_tracing!(async move {
             if object_ids.len() <= *QUERY_MAX_RESULT_LIMIT {
                 self.metrics
                     .get_objects_limit
                     .report(object_ids.len() as u64);
+                let options = options.unwrap_or_default();
                 let mut futures = vec![];
                 for object_id in object_ids {
-                    futures.push(self.get_object(object_id, options.clone()));
+                    futures.push(self.object_read(object_id));
                 }
-                let results = join_all(futures).await;
+                let object_reads = collect_object_results(join_all(futures).await)?;
+                let num_objects = object_reads.len() as u64;
 
-                let objects_result: Result<Vec<MgoObjectResponse>, String> = results
-                    .into_iter()
-                    .map(|result| match result {
-                        Ok(response) => Ok(response),
-                        Err(error) => {
-                            error!("Failed to fetch object with error: {error:?}");
-                            Err(format!("Error: {}", error))
-                        }
-                    })
-                    .collect();
+                let response = if encoding.unwrap_or_default() == ResponseEncoding::Bcs {
+                    let objects = object_reads
+                        .into_iter()
+                        .map(ObjectReadBcsV1::from)
+                        .collect::<Vec<_>>();
+                    EncodedResponse::Bcs(BcsResponse::new(&objects)?)
+                } else {
+                    // A field mask that does not fit one of the objects is the caller's fault, and
+                    // must not be reported as a failure to fetch that object.
+                    for object_read in &object_reads {
+                        Self::check_field_mask(object_read, &options)?;
+                    }
 
-                let objects = objects_result.map_err(|err| {
-                    Error::UnexpectedError(format!("Failed to fetch objects with error: {}", err))
-                })?;
+                    let mut futures = vec![];
+                    for object_read in object_reads {
+                        futures.push(self.object_response(object_read, &options));
+                    }
+                    let mut objects = collect_object_results(join_all(futures).await)?;
+                    if options.show_previous_transaction_details {
+                        self.add_previous_transaction_details(
+                            objects.iter_mut().filter_map(|o| o.data.as_mut()).collect(),
+                        )
+                        .await?;
+                    }
+                    EncodedResponse::Json(objects)
+                };
 
-                self.metrics
-                    .get_objects_result_size
-                    .report(objects.len() as u64);
+                self.metrics.get_objects_result_size.report(num_objects);
                 self.metrics
                     .get_objects_result_size_total
-                    .inc_by(objects.len() as u64);
-                Ok(objects)
+                    .inc_by(num_objects);
+                Ok(response)
             } else {
                 Err(MgoRpcInputError::SizeLimitExceeded(
                     QUERY_MAX_RESULT_LIMIT.to_string(),
@@ -595,46 +1147,8 @@ impl ReadApiServer for ReadApi {
                 error!("Failed to call try_get_past_object for object: {object_id:?} version: {version:?} with error: {e:?}");
                 Error::from(e)
             })}).await.map_err(Error::from)??;
-            let options = options.unwrap_or_default();
-            match past_read {
-                PastObjectRead::ObjectNotExists(id) => {
-                    Ok(MgoPastObjectResponse::ObjectNotExists(id))
-                }
-                PastObjectRead::VersionFound(object_ref, o, layout) => {
-                    let display_fields = if options.show_display {
-                        // TODO (jian): api breaking change to also modify past objects.
-                        Some(
-                            get_display_fields(self, &self.transaction_kv_store, &o, &layout)
-                                .await
-                                .map_err(|e| {
-                                    Error::UnexpectedError(format!(
-                                        "Unable to render object at version {version}: {e}"
-                                    ))
-                                })?,
-                        )
-                    } else {
-                        None
-                    };
-                    Ok(MgoPastObjectResponse::VersionFound(
-                        (object_ref, o, layout, options, display_fields).try_into()?,
-                    ))
-                }
-                PastObjectRead::ObjectDeleted(oref) => {
-                    Ok(MgoPastObjectResponse::ObjectDeleted(oref.into()))
-                }
-                PastObjectRead::VersionNotFound(id, seq_num) => {
-                    Ok(MgoPastObjectResponse::VersionNotFound(id, seq_num))
-                }
-                PastObjectRead::VersionTooHigh {
-                    object_id,
-                    asked_version,
-                    latest_version,
-                } => Ok(MgoPastObjectResponse::VersionTooHigh {
-                    object_id,
-                    asked_version,
-                    latest_version,
-                }),
-            }
+            self.past_object_response(past_read, version, options.unwrap_or_default())
+                .await
         })
     }
 
@@ -645,11 +1159,25 @@ impl ReadApiServer for ReadApi {
         options: Option<MgoObjectDataOptions>,
     ) -> RpcResult<Vec<MgoPastObjectResponse>> {
         with_tracing!(async move {
-            if past_objects.len() <= *QUERY_MAX_RESULT_LIMIT {
+            if past_objects.len() <= self.max_past_objects_batch_size {
+                let requests = past_objects
+                    .iter()
+                    .map(|past_object| (past_object.object_id, past_object.version))
+                    .collect::<Vec<_>>();
+                let state = self.state.clone();
+                let past_reads = spawn_monitored_task!(async move {
+                    state.multi_get_past_object_reads(&requests).map_err(|e| {
+                        error!("Failed to call try_multi_get_past_objects with error: {e:?}");
+                        Error::from(e)
+                    })
+                })
+                .await
+                .map_err(Error::from)??;
+                let options = options.unwrap_or_default();
                 let mut futures = vec![];
-                for past_object in past_objects {
-                    futures.push(self.try_get_past_object(
-                        past_object.object_id,
+                for (past_read, past_object) in past_reads.into_iter().zip(past_objects) {
+                    futures.push(self.past_object_response(
+                        past_read,
                         past_object.version,
                         options.clone(),
                     ));
@@ -671,7 +1199,7 @@ impl ReadApiServer for ReadApi {
                 }
             } else {
                 Err(MgoRpcInputError::SizeLimitExceeded(
-                    QUERY_MAX_RESULT_LIMIT.to_string(),
+                    self.max_past_objects_batch_size.to_string(),
                 ))?
             }
         })
@@ -763,6 +1291,7 @@ impl ReadApiServer for ReadApi {
                 }).await.map_err(Error::from)??;
                 // TODO(chris): we don't need to fetch the whole checkpoint summary
                 temp_response.timestamp = Some(checkpoint.timestamp_ms);
+                temp_response.epoch = Some(checkpoint.epoch);
             }
 
             if opts.show_events && temp_response.effects.is_some() {
@@ -840,9 +1369,25 @@ impl ReadApiServer for ReadApi {
                     }
                 }
             }
-            let epoch_store = self.state.load_epoch_store_one_call_per_task();
-            convert_to_response(temp_response, &opts, epoch_store.module_cache())
-        })
+
+            if opts.show_created_summary {
+                if let Some(effects) = &temp_response.effects {
+                    let created_summary =
+                        get_created_objects_summary(&object_cache, effects.created()).await;
+
+                    if let Ok(created_summary) = created_summary {
+                        temp_response.created_summary = Some(created_summary);
+                    } else {
+                        temp_response.errors.push(format!(
+                            "Cannot retrieve created objects: {}",
+                            created_summary.unwrap_err()
+                        ));
+                    }
+                }
+            }
+            let epoch_store = self.state.load_epoch_store_one_call_per_task();
+            convert_to_response(temp_response, &opts, epoch_store.module_cache())
+        })
     }
 
     #[instrument(skip(self))]
@@ -850,13 +1395,22 @@ impl ReadApiServer for ReadApi {
         &self,
         digests: Vec<TransactionDigest>,
         opts: Option<MgoTransactionBlockResponseOptions>,
-    ) -> RpcResult<Vec<MgoTransactionBlockResponse>> {
+        encoding: Option<ResponseEncoding>,
+    ) -> RpcResult<EncodedResponse<Vec<MgoTransactionBlockResponse>>> {
         with_tracing!(async move {
             let cloned_self = self.clone();
             spawn_monitored_task!(async move {
-                cloned_self
-                    .multi_get_transaction_blocks_internal(digests, opts)
-                    .await
+                if encoding.unwrap_or_default() == ResponseEncoding::Bcs {
+                    let blocks = cloned_self
+                        .multi_get_transaction_blocks_bcs_internal(digests)
+                        .await?;
+                    Ok(EncodedResponse::Bcs(BcsResponse::new(&blocks)?))
+                } else {
+                    cloned_self
+                        .multi_get_transaction_blocks_internal(digests, opts)
+                        .await
+                        .map(EncodedResponse::Json)
+                }
             })
             .await
             .map_err(Error::from)?
@@ -922,8 +1476,49 @@ impl ReadApiServer for ReadApi {
     }
 
     #[instrument(skip(self))]
-    async fn get_checkpoint(&self, id: CheckpointId) -> RpcResult<Checkpoint> {
-        with_tracing!(self.get_checkpoint_internal(id))
+    async fn get_checkpoint(
+        &self,
+        id: CheckpointId,
+        encoding: Option<ResponseEncoding>,
+    ) -> RpcResult<EncodedResponse<Checkpoint>> {
+        with_tracing!(async move {
+            if encoding.unwrap_or_default() == ResponseEncoding::Bcs {
+                let checkpoint = self.get_checkpoint_bcs_internal(id).await?;
+                Ok(EncodedResponse::Bcs(BcsResponse::new(&checkpoint)?))
+            } else {
+                self.get_checkpoint_internal(id)
+                    .await
+                    .map(EncodedResponse::Json)
+            }
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_epoch_start_info(&self, epoch: BigInt<u64>) -> RpcResult<EpochStartInfo> {
+        with_tracing!(self.get_epoch_start_info_internal(*epoch))
+    }
+
+    #[instrument(skip(self))]
+    async fn get_checkpoint_transactions(
+        &self,
+        sequence_number: BigInt<u64>,
+        cursor: Option<TransactionDigest>,
+        limit: Option<usize>,
+    ) -> RpcResult<BcsResponse> {
+        with_tracing!(async move {
+            let limit =
+                validate_limit(limit, *QUERY_MAX_RESULT_LIMIT).map_err(MgoRpcInputError::from)?;
+            let page = spawn_monitored_task!(Self::get_checkpoint_transactions_internal(
+                self.state.clone(),
+                *sequence_number,
+                cursor,
+                limit,
+            ))
+            .await
+            .map_err(Error::from)?
+            .map_err(Error::from)?;
+            Ok(BcsResponse::new(&page)?)
+        })
     }
 
     #[instrument(skip(self))]
@@ -933,6 +1528,7 @@ impl ReadApiServer for ReadApi {
         cursor: Option<BigInt<u64>>,
         limit: Option<usize>,
         descending_order: bool,
+        epoch: Option<BigInt<u64>>,
     ) -> RpcResult<CheckpointPage> {
         with_tracing!(async move {
             let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS)
@@ -949,6 +1545,7 @@ impl ReadApiServer for ReadApi {
                 cursor.map(|s| *s),
                 limit as u64 + 1,
                 descending_order,
+                epoch.map(|e| *e),
             ))
             .await
             .map_err(Error::from)?
@@ -974,6 +1571,7 @@ impl ReadApiServer for ReadApi {
                 data,
                 next_cursor,
                 has_next_page,
+                applied: None,
             })
         })
     }
@@ -986,7 +1584,7 @@ impl ReadApiServer for ReadApi {
         descending_order: bool,
     ) -> RpcResult<CheckpointPage> {
         with_tracing!(async move {
-            self.get_checkpoints(cursor, limit.map(|l| *l as usize), descending_order)
+            self.get_checkpoints(cursor, limit.map(|l| *l as usize), descending_order, None)
                 .await
                 .map_err(Error::from)
         })
@@ -996,24 +1594,26 @@ impl ReadApiServer for ReadApi {
     async fn get_loaded_child_objects(
         &self,
         digest: TransactionDigest,
+        options: Option<MgoObjectDataOptions>,
     ) -> RpcResult<MgoLoadedChildObjectsResponse> {
         with_tracing!(async move {
+            let children = self
+                .state
+                .loaded_child_object_versions(&digest)
+                .map_err(|e| {
+                    error!("Failed to get loaded child objects at {digest:?} with error: {e:?}");
+                    Error::StateReadError(e)
+                })?
+                .unwrap_or_default();
+            let loaded_child_objects = match options {
+                Some(options) => self.resolve_loaded_child_objects(children, options).await?,
+                None => children
+                    .into_iter()
+                    .map(|q| MgoLoadedChildObject::new(q.0, q.1))
+                    .collect::<Vec<_>>(),
+            };
             Ok(MgoLoadedChildObjectsResponse {
-                loaded_child_objects: match self
-                    .state
-                    .loaded_child_object_versions(&digest)
-                    .map_err(|e| {
-                        error!(
-                            "Failed to get loaded child objects at {digest:?} with error: {e:?}"
-                        );
-                        Error::StateReadError(e)
-                    })? {
-                    Some(v) => v
-                        .into_iter()
-                        .map(|q| MgoLoadedChildObject::new(q.0, q.1))
-                        .collect::<Vec<_>>(),
-                    None => vec![],
-                },
+                loaded_child_objects,
             })
         })
     }
@@ -1045,6 +1645,18 @@ impl ReadApiServer for ReadApi {
         })
     }
 
+    #[instrument(skip(self))]
+    async fn get_client_limits(&self) -> RpcResult<ClientLimits> {
+        with_tracing!(async move {
+            let epoch_store = self.state.load_epoch_store_one_call_per_task();
+            Ok(ClientLimits::new(
+                epoch_store.protocol_config(),
+                epoch_store.epoch(),
+                server_limits(),
+            ))
+        })
+    }
+
     #[instrument(skip(self))]
     async fn get_chain_identifier(&self) -> RpcResult<String> {
         with_tracing!(async move {
@@ -1052,6 +1664,33 @@ impl ReadApiServer for ReadApi {
             Ok(ci.to_string())
         })
     }
+
+    #[instrument(skip(self, tx_bytes, signatures))]
+    async fn verify_transaction_block_signature(
+        &self,
+        tx_bytes: Base64,
+        signatures: Vec<Base64>,
+    ) -> RpcResult<TransactionSignatureVerification> {
+        with_tracing!(async move {
+            if signatures.len() > MAX_SIGNATURE_VERIFICATION_BATCH {
+                Err(MgoRpcInputError::SizeLimitExceeded(format!(
+                    "{MAX_SIGNATURE_VERIFICATION_BATCH} signatures"
+                )))?
+            }
+            let tx_bytes = tx_bytes.to_vec().map_err(MgoRpcInputError::from)?;
+            let tx_data: TransactionData =
+                bcs::from_bytes(&tx_bytes).map_err(MgoRpcInputError::from)?;
+            let state = self.state.clone();
+            Ok(tokio::task::spawn_blocking(move || {
+                Self::verify_transaction_block_signature_internal(
+                    state.as_ref(),
+                    tx_data,
+                    &signatures,
+                )
+            })
+            .await??)
+        })
+    }
 }
 
 impl MgoRpcModule for ReadApi {
@@ -1064,6 +1703,35 @@ impl MgoRpcModule for ReadApi {
     }
 }
 
+/// Reads the first `limit` of `children` at their loaded versions, leaving the rest unresolved.
+/// Children no longer found at their version, as once pruned, are reported as such rather than
+/// failing the whole read.
+async fn read_loaded_child_objects<P: ObjectProvider<Error = StateReadError>>(
+    object_provider: &P,
+    children: &[(ObjectID, SequenceNumber)],
+    limit: usize,
+) -> Result<Vec<Result<Object, MgoUnresolvedChildObject>>, StateReadError> {
+    let reads = join_all(
+        children
+            .iter()
+            .take(limit)
+            .map(|(object_id, version)| object_provider.get_object(object_id, version)),
+    )
+    .await;
+    let mut results = Vec::with_capacity(children.len());
+    for read in reads {
+        results.push(match read {
+            Ok(object) => Ok(object),
+            Err(StateReadError::Client(StateReadClientError::UserInputError(
+                UserInputError::ObjectNotFound { .. },
+            ))) => Err(MgoUnresolvedChildObject::Pruned),
+            Err(e) => return Err(e),
+        });
+    }
+    results.resize(children.len(), Err(MgoUnresolvedChildObject::LimitExceeded));
+    Ok(results)
+}
+
 fn to_mgo_transaction_events(
     fullnode_api: &ReadApi,
     tx_digest: TransactionDigest,
@@ -1101,6 +1769,29 @@ pub enum ObjectDisplayError {
 
     #[error(transparent)]
     StateReadError(#[from] StateReadError),
+
+    #[error(transparent)]
+    Template(#[from] Error),
+
+    #[error(transparent)]
+    LimitExceeded(#[from] DisplayLimitExceeded),
+}
+
+/// Collects the results of reading several objects, failing as a whole on the first failure.
+fn collect_object_results<T>(results: Vec<Result<T, Error>>) -> Result<Vec<T>, Error> {
+    let objects_result: Result<Vec<T>, String> = results
+        .into_iter()
+        .map(|result| match result {
+            Ok(response) => Ok(response),
+            Err(error) => {
+                error!("Failed to fetch object with error: {error:?}");
+                Err(format!("Error: {}", error))
+            }
+        })
+        .collect();
+    objects_result.map_err(|err| {
+        Error::UnexpectedError(format!("Failed to fetch objects with error: {}", err))
+    })
 }
 
 async fn get_display_fields(
@@ -1119,7 +1810,11 @@ async fn get_display_fields(
     if let Some(display_object) =
         get_display_object_by_type(kv_store, fullnode_api, &object_type).await?
     {
-        return get_rendered_fields(display_object.fields, &layout);
+        return get_rendered_fields_with_limits(
+            display_object.fields,
+            &layout,
+            &fullnode_api.display_limits,
+        );
     }
     Ok(DisplayFieldsResponse {
         data: None,
@@ -1154,6 +1849,116 @@ async fn get_display_object_by_type(
     }
 }
 
+/// `type_` with the package of each type in it replaced by the latest upgrade of that package.
+async fn get_latest_struct_tag(
+    state: &Arc<dyn StateRead>,
+    kv_store: &Arc<TransactionKeyValueStore>,
+    type_: &StructTag,
+) -> Result<StructTag, Error> {
+    let mut packages = BTreeSet::new();
+    collect_struct_packages(type_, &mut packages);
+    let mut latest_packages = BTreeMap::new();
+    for package_id in packages {
+        let latest_id = get_latest_package_id(state, kv_store, package_id.into()).await?;
+        latest_packages.insert(package_id, AccountAddress::from(latest_id));
+    }
+    Ok(replace_struct_packages(type_, &latest_packages))
+}
+
+/// The latest upgrade of the package `package_id` was upgraded from, read from the upgrade cap
+/// created when the original package was published. Packages resolve to themselves if they were
+/// never upgraded, if their upgrade cap was wrapped or deleted, or if they are system packages,
+/// which are upgraded in place.
+async fn get_latest_package_id(
+    state: &Arc<dyn StateRead>,
+    kv_store: &Arc<TransactionKeyValueStore>,
+    package_id: ObjectID,
+) -> Result<ObjectID, Error> {
+    if is_system_package(package_id) {
+        return Ok(package_id);
+    }
+    let package_store = state.get_backing_package_store();
+    let Some(package) = package_store.get_package_object(&package_id)? else {
+        return Ok(package_id);
+    };
+    let original_id = package.move_package().original_package_id();
+    let Ok(upgrade_cap_id) = find_package_object_id(
+        state.clone(),
+        original_id,
+        UpgradeCap::type_(),
+        kv_store.clone(),
+    )
+    .await
+    else {
+        return Ok(package_id);
+    };
+    let Ok(upgrade_cap) = state.get_object_read(&upgrade_cap_id)?.into_object() else {
+        return Ok(package_id);
+    };
+    let Some(upgrade_cap) = upgrade_cap.data.try_as_move() else {
+        return Ok(package_id);
+    };
+    let latest_id = bcs::from_bytes::<UpgradeCap>(upgrade_cap.contents())?
+        .package
+        .bytes;
+    // Only follow the cap to a later version of the same package.
+    Ok(match package_store.get_package_object(&latest_id)? {
+        Some(latest)
+            if latest.move_package().original_package_id() == original_id
+                && latest.move_package().version() >= package.move_package().version() =>
+        {
+            latest_id
+        }
+        _ => package_id,
+    })
+}
+
+fn collect_struct_packages(type_: &StructTag, packages: &mut BTreeSet<AccountAddress>) {
+    packages.insert(type_.address);
+    for type_param in &type_.type_params {
+        collect_type_packages(type_param, packages);
+    }
+}
+
+fn collect_type_packages(type_: &TypeTag, packages: &mut BTreeSet<AccountAddress>) {
+    match type_ {
+        TypeTag::Struct(struct_tag) => collect_struct_packages(struct_tag, packages),
+        TypeTag::Vector(type_) => collect_type_packages(type_, packages),
+        _ => {}
+    }
+}
+
+fn replace_struct_packages(
+    type_: &StructTag,
+    packages: &BTreeMap<AccountAddress, AccountAddress>,
+) -> StructTag {
+    StructTag {
+        address: packages
+            .get(&type_.address)
+            .copied()
+            .unwrap_or(type_.address),
+        type_params: type_
+            .type_params
+            .iter()
+            .map(|type_param| replace_type_packages(type_param, packages))
+            .collect(),
+        ..type_.clone()
+    }
+}
+
+fn replace_type_packages(
+    type_: &TypeTag,
+    packages: &BTreeMap<AccountAddress, AccountAddress>,
+) -> TypeTag {
+    match type_ {
+        TypeTag::Struct(struct_tag) => {
+            TypeTag::Struct(Box::new(replace_struct_packages(struct_tag, packages)))
+        }
+        TypeTag::Vector(type_) => TypeTag::Vector(Box::new(replace_type_packages(type_, packages))),
+        type_ => type_.clone(),
+    }
+}
+
 pub fn get_object_type_and_struct(
     o: &Object,
     layout: &Option<MoveStructLayout>,
@@ -1180,25 +1985,39 @@ fn get_move_struct(
 pub fn get_rendered_fields(
     fields: VecMap<String, String>,
     move_struct: &MoveStruct,
+) -> Result<DisplayFieldsResponse, ObjectDisplayError> {
+    get_rendered_fields_with_limits(fields, move_struct, &DisplayRenderLimits::default())
+}
+
+/// Renders the Display `fields` of an object, giving up on the whole object with a
+/// `DisplayError` naming the limit hit as soon as any of `limits` is exceeded.
+pub fn get_rendered_fields_with_limits(
+    fields: VecMap<String, String>,
+    move_struct: &MoveStruct,
+    limits: &DisplayRenderLimits,
 ) -> Result<DisplayFieldsResponse, ObjectDisplayError> {
     let mgo_move_value: MgoMoveValue = MoveValue::Struct(move_struct.clone()).into();
     if let MgoMoveValue::Struct(move_struct) = mgo_move_value {
-        let fields =
-            fields
-                .contents
-                .iter()
-                .map(|entry| match parse_template(&entry.value, &move_struct) {
-                    Ok(value) => Ok((entry.key.clone(), value)),
-                    Err(e) => Err(e),
-                });
-        let (oks, errs): (Vec<_>, Vec<_>) = fields.partition(Result::is_ok);
-        let success = oks.into_iter().filter_map(Result::ok).collect();
-        let errors: Vec<_> = errs.into_iter().filter_map(Result::err).collect();
-        let error_string = errors
-            .iter()
-            .map(|e| e.to_string())
-            .collect::<Vec<String>>()
-            .join("; ");
+        let mut budget = RenderBudget::new(limits);
+        let mut success = BTreeMap::new();
+        let mut errors = vec![];
+        for entry in fields.contents.iter() {
+            match parse_template(&entry.value, &move_struct, &mut budget) {
+                Ok(value) => {
+                    success.insert(entry.key.clone(), value);
+                }
+                Err(ObjectDisplayError::LimitExceeded(e)) => {
+                    return Ok(DisplayFieldsResponse {
+                        data: None,
+                        error: Some(MgoObjectResponseError::DisplayError {
+                            error: e.to_string(),
+                        }),
+                    });
+                }
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+        let error_string = errors.join("; ");
         let error = if !error_string.is_empty() {
             Some(MgoObjectResponseError::DisplayError {
                 error: anyhow!("{error_string}").to_string(),
@@ -1215,7 +2034,46 @@ pub fn get_rendered_fields(
     Err(ObjectDisplayError::NotMoveStruct)?
 }
 
-fn parse_template(template: &str, move_struct: &MgoMoveStruct) -> Result<String, Error> {
+/// Tracks the limits consumed while rendering the fields of one object.
+struct RenderBudget<'a> {
+    limits: &'a DisplayRenderLimits,
+    substitutions: usize,
+    started_at: Instant,
+}
+
+impl<'a> RenderBudget<'a> {
+    fn new(limits: &'a DisplayRenderLimits) -> Self {
+        Self {
+            limits,
+            substitutions: 0,
+            started_at: Instant::now(),
+        }
+    }
+
+    fn charge_substitution(&mut self, var_name: &str) -> Result<(), DisplayLimitExceeded> {
+        if self.started_at.elapsed() > self.limits.time_budget {
+            return Err(DisplayLimitExceeded::TimeBudget(self.limits.time_budget));
+        }
+        self.substitutions += 1;
+        if self.substitutions > self.limits.max_substitutions {
+            return Err(DisplayLimitExceeded::Substitutions(
+                self.limits.max_substitutions,
+            ));
+        }
+        if var_name.split('.').count() > self.limits.max_field_depth {
+            return Err(DisplayLimitExceeded::FieldDepth(
+                self.limits.max_field_depth,
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn parse_template(
+    template: &str,
+    move_struct: &MgoMoveStruct,
+    budget: &mut RenderBudget,
+) -> Result<String, ObjectDisplayError> {
     let mut output = template.to_string();
     let mut var_name = String::new();
     let mut in_braces = false;
@@ -1233,6 +2091,7 @@ fn parse_template(template: &str, move_struct: &MgoMoveStruct) -> Result<String,
             }
             '}' if !escaped => {
                 in_braces = false;
+                budget.charge_substitution(&var_name)?;
                 let value = get_value_from_move_struct(move_struct, &var_name)?;
                 output = output.replace(&format!("{{{}}}", var_name), &value.to_string());
             }
@@ -1257,12 +2116,6 @@ fn get_value_from_move_struct(
     if parts.is_empty() {
         Err(anyhow!("Display template value cannot be empty"))?;
     }
-    if parts.len() > MAX_DISPLAY_NESTED_LEVEL {
-        Err(anyhow!(
-            "Display template value nested depth cannot exist {}",
-            MAX_DISPLAY_NESTED_LEVEL
-        ))?;
-    }
     let mut current_value = &MgoMoveValue::Struct(move_struct.clone());
     // iterate over the parts and try to access the corresponding field
     for part in parts {
@@ -1342,7 +2195,10 @@ fn convert_to_response(
     }
 
     response.checkpoint = cache.checkpoint_seq;
-    response.timestamp_ms = cache.timestamp;
+    response.set_checkpoint_timestamp_ms(cache.timestamp);
+    if opts.include_checkpoint {
+        response.epoch = cache.epoch;
+    }
 
     if opts.show_events {
         response.events = cache.events;
@@ -1355,51 +2211,94 @@ fn convert_to_response(
     if opts.show_object_changes {
         response.object_changes = cache.object_changes;
     }
+
+    if opts.show_created_summary {
+        response.created_summary = cache.created_summary;
+    }
     Ok(response)
 }
 
-fn calculate_checkpoint_numbers(
+/// Responses to `digests` with only the checkpoint of each transaction, its epoch and its
+/// timestamp, read with a single lookup of the transaction-to-checkpoint index for all of them,
+/// then of the summaries of their checkpoints. Transactions not in a checkpoint yet only have a
+/// digest.
+pub(crate) async fn transaction_checkpoint_responses(
+    transaction_kv_store: &TransactionKeyValueStore,
+    digests: Vec<TransactionDigest>,
+) -> Result<Vec<MgoTransactionBlockResponse>, Error> {
+    let checkpoints = transaction_kv_store
+        .multi_get_transaction_checkpoint(&digests)
+        .await?;
+    let checkpoint_numbers = checkpoints
+        .iter()
+        .flatten()
+        .copied()
+        .unique()
+        .collect::<Vec<CheckpointSequenceNumber>>();
+    let summaries = transaction_kv_store
+        .multi_get_checkpoints_summaries(&checkpoint_numbers)
+        .await?;
+    let summaries = checkpoint_numbers
+        .into_iter()
+        .zip(summaries)
+        .filter_map(|(seq, summary)| Some((seq, summary?)))
+        .collect::<HashMap<_, _>>();
+
+    Ok(digests
+        .into_iter()
+        .zip(checkpoints)
+        .map(|(digest, checkpoint)| {
+            let mut response = MgoTransactionBlockResponse::new(digest);
+            response.checkpoint = checkpoint;
+            if let Some(summary) = checkpoint.and_then(|seq| summaries.get(&seq)) {
+                response.epoch = Some(summary.epoch);
+                response.set_checkpoint_timestamp_ms(Some(summary.timestamp_ms));
+            }
+            response
+        })
+        .collect())
+}
+
+/// Sequence numbers of at most `limit` checkpoints of `first_checkpoint..=last_checkpoint`
+/// after `cursor`, in ascending or descending order.
+pub(crate) fn calculate_checkpoint_numbers(
     // If `Some`, the query will start from the next item after the specified cursor
     cursor: Option<CheckpointSequenceNumber>,
     limit: u64,
     descending_order: bool,
-    max_checkpoint: CheckpointSequenceNumber,
+    first_checkpoint: CheckpointSequenceNumber,
+    last_checkpoint: CheckpointSequenceNumber,
 ) -> Vec<CheckpointSequenceNumber> {
-    let (start_index, end_index) = match cursor {
-        Some(t) => {
-            if descending_order {
-                let start = std::cmp::min(t.saturating_sub(1), max_checkpoint);
-                let end = start.saturating_sub(limit - 1);
-                (end, start)
-            } else {
-                let start =
-                    std::cmp::min(t.checked_add(1).unwrap_or(max_checkpoint), max_checkpoint);
-                let end = std::cmp::min(
-                    start.checked_add(limit - 1).unwrap_or(max_checkpoint),
-                    max_checkpoint,
-                );
-                (start, end)
-            }
-        }
-        None => {
-            if descending_order {
-                (max_checkpoint.saturating_sub(limit - 1), max_checkpoint)
-            } else {
-                (0, std::cmp::min(limit - 1, max_checkpoint))
-            }
-        }
-    };
-
+    let limit = limit as usize;
     if descending_order {
-        (start_index..=end_index).rev().collect()
+        let end = match cursor {
+            Some(cursor) if cursor <= first_checkpoint => return vec![],
+            Some(cursor) => std::cmp::min(cursor - 1, last_checkpoint),
+            None => last_checkpoint,
+        };
+        (first_checkpoint..=end).rev().take(limit).collect()
     } else {
-        (start_index..=end_index).collect()
+        let start = match cursor {
+            Some(cursor) if cursor >= last_checkpoint => return vec![],
+            Some(cursor) => std::cmp::max(cursor + 1, first_checkpoint),
+            None => first_checkpoint,
+        };
+        (start..=last_checkpoint).take(limit).collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use mgo_json_rpc_types::MgoParsedData;
+    use mgo_storage::key_value_store::{
+        KVStoreCheckpointData, KVStoreTransactionData, TransactionKeyValueStoreTrait,
+    };
+    use mgo_types::collection_types::Entry;
+    use mgo_types::error::MgoResult;
+    use mgo_types::messages_checkpoint::CheckpointDigest;
+    use move_core_types::account_address::AccountAddress;
+    use move_core_types::identifier::Identifier;
 
     #[test]
     fn test_calculate_checkpoint_numbers() {
@@ -1409,7 +2308,7 @@ mod tests {
         let max_checkpoint = 15;
 
         let checkpoint_numbers =
-            calculate_checkpoint_numbers(cursor, limit, descending_order, max_checkpoint);
+            calculate_checkpoint_numbers(cursor, limit, descending_order, 0, max_checkpoint);
 
         assert_eq!(checkpoint_numbers, vec![9, 8, 7, 6, 5]);
     }
@@ -1422,7 +2321,7 @@ mod tests {
         let max_checkpoint = 15;
 
         let checkpoint_numbers =
-            calculate_checkpoint_numbers(cursor, limit, descending_order, max_checkpoint);
+            calculate_checkpoint_numbers(cursor, limit, descending_order, 0, max_checkpoint);
 
         assert_eq!(checkpoint_numbers, vec![15, 14, 13, 12, 11]);
     }
@@ -1435,7 +2334,7 @@ mod tests {
         let max_checkpoint = 15;
 
         let checkpoint_numbers =
-            calculate_checkpoint_numbers(cursor, limit, descending_order, max_checkpoint);
+            calculate_checkpoint_numbers(cursor, limit, descending_order, 0, max_checkpoint);
 
         assert_eq!(checkpoint_numbers, vec![0, 1, 2, 3, 4]);
     }
@@ -1448,7 +2347,7 @@ mod tests {
         let max_checkpoint = 15;
 
         let checkpoint_numbers =
-            calculate_checkpoint_numbers(cursor, limit, descending_order, max_checkpoint);
+            calculate_checkpoint_numbers(cursor, limit, descending_order, 0, max_checkpoint);
 
         assert_eq!(checkpoint_numbers, vec![11, 12, 13, 14, 15]);
     }
@@ -1461,7 +2360,7 @@ mod tests {
         let max_checkpoint = 15;
 
         let checkpoint_numbers =
-            calculate_checkpoint_numbers(cursor, limit, descending_order, max_checkpoint);
+            calculate_checkpoint_numbers(cursor, limit, descending_order, 0, max_checkpoint);
 
         assert_eq!(checkpoint_numbers, (0..=15).collect::<Vec<_>>());
     }
@@ -1474,8 +2373,634 @@ mod tests {
         let max_checkpoint = 15;
 
         let checkpoint_numbers =
-            calculate_checkpoint_numbers(cursor, limit, descending_order, max_checkpoint);
+            calculate_checkpoint_numbers(cursor, limit, descending_order, 0, max_checkpoint);
 
         assert_eq!(checkpoint_numbers, (0..=15).rev().collect::<Vec<_>>());
     }
+
+    #[test]
+    fn test_calculate_checkpoint_numbers_within_epoch() {
+        // Epoch spanning checkpoints 10 to 20.
+        assert_eq!(
+            calculate_checkpoint_numbers(None, 5, false, 10, 20),
+            vec![10, 11, 12, 13, 14]
+        );
+        assert_eq!(
+            calculate_checkpoint_numbers(None, 5, true, 10, 20),
+            vec![20, 19, 18, 17, 16]
+        );
+        assert_eq!(
+            calculate_checkpoint_numbers(Some(3), 3, false, 10, 20),
+            vec![10, 11, 12]
+        );
+        assert_eq!(
+            calculate_checkpoint_numbers(Some(30), 3, true, 10, 20),
+            vec![20, 19, 18]
+        );
+        assert!(calculate_checkpoint_numbers(Some(20), 3, false, 10, 20).is_empty());
+        assert!(calculate_checkpoint_numbers(Some(10), 3, true, 10, 20).is_empty());
+    }
+
+    fn nested_struct(depth: usize) -> MoveStruct {
+        let type_ = StructTag {
+            address: AccountAddress::TWO,
+            module: Identifier::new("display_test").unwrap(),
+            name: Identifier::new("Node").unwrap(),
+            type_params: vec![],
+        };
+        let mut fields = vec![(Identifier::new("name").unwrap(), MoveValue::U8(7))];
+        if depth > 0 {
+            fields.push((
+                Identifier::new("next").unwrap(),
+                MoveValue::Struct(nested_struct(depth - 1)),
+            ));
+        }
+        MoveStruct { type_, fields }
+    }
+
+    fn display_fields(templates: &[(&str, String)]) -> VecMap<String, String> {
+        VecMap {
+            contents: templates
+                .iter()
+                .map(|(key, value)| Entry {
+                    key: key.to_string(),
+                    value: value.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    fn display_error(response: DisplayFieldsResponse) -> String {
+        assert!(response.data.is_none());
+        match response.error {
+            Some(MgoObjectResponseError::DisplayError { error }) => error,
+            other => panic!("Expected a display error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_render_within_limits() {
+        let fields = display_fields(&[("name", "node {next.next.name}".to_string())]);
+        let response = get_rendered_fields(fields, &nested_struct(2)).unwrap();
+        assert!(response.error.is_none());
+        assert_eq!(response.data.unwrap()["name"], "node 7");
+    }
+
+    #[test]
+    fn test_render_exceeds_field_depth() {
+        let limits = DisplayRenderLimits::default();
+        let path = vec!["next"; limits.max_field_depth]
+            .into_iter()
+            .chain(["name"])
+            .join(".");
+        let fields = display_fields(&[("deep", format!("{{{path}}}"))]);
+        let response = get_rendered_fields_with_limits(
+            fields,
+            &nested_struct(limits.max_field_depth),
+            &limits,
+        )
+        .unwrap();
+        assert_eq!(
+            display_error(response),
+            DisplayLimitExceeded::FieldDepth(limits.max_field_depth).to_string()
+        );
+    }
+
+    #[test]
+    fn test_render_exceeds_substitutions() {
+        let limits = DisplayRenderLimits {
+            max_substitutions: 16,
+            ..Default::default()
+        };
+        // Substitutions are counted across all fields of the object.
+        let fields = display_fields(&[
+            ("first", "{name}".repeat(10)),
+            ("second", "{name}".repeat(10)),
+        ]);
+        let response = get_rendered_fields_with_limits(fields, &nested_struct(0), &limits).unwrap();
+        assert_eq!(
+            display_error(response),
+            DisplayLimitExceeded::Substitutions(16).to_string()
+        );
+    }
+
+    #[test]
+    fn test_render_exceeds_time_budget() {
+        let limits = DisplayRenderLimits {
+            max_substitutions: usize::MAX,
+            time_budget: Duration::ZERO,
+            ..Default::default()
+        };
+        let fields = display_fields(&[("slow", "{next.next.next.name}".repeat(1000))]);
+        let response = get_rendered_fields_with_limits(fields, &nested_struct(3), &limits).unwrap();
+        assert_eq!(
+            display_error(response),
+            DisplayLimitExceeded::TimeBudget(Duration::ZERO).to_string()
+        );
+    }
+
+    /// A `0x42::blob::Blob { id, huge: vector<u8>, small: u64 }` object and its layout.
+    fn blob_object(huge_len: usize) -> (Object, MoveStructLayout) {
+        use mgo_types::id::UID;
+        use mgo_types::object::{MoveObject, Owner, OBJECT_START_VERSION};
+        use move_core_types::annotated_value::{MoveFieldLayout, MoveTypeLayout};
+
+        let type_: StructTag = "0x42::blob::Blob".parse().unwrap();
+        let layout = MoveStructLayout {
+            type_: type_.clone(),
+            fields: vec![
+                MoveFieldLayout::new(
+                    Identifier::new("id").unwrap(),
+                    MoveTypeLayout::Struct(UID::layout()),
+                ),
+                MoveFieldLayout::new(
+                    Identifier::new("huge").unwrap(),
+                    MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8)),
+                ),
+                MoveFieldLayout::new(Identifier::new("small").unwrap(), MoveTypeLayout::U64),
+            ],
+        };
+        let mut contents = bcs::to_bytes(&ObjectID::random()).unwrap();
+        contents.extend(bcs::to_bytes(&vec![1u8; huge_len]).unwrap());
+        contents.extend(bcs::to_bytes(&7u64).unwrap());
+        let move_object = unsafe {
+            MoveObject::new_from_execution_with_limit(
+                type_.into(),
+                true,
+                OBJECT_START_VERSION,
+                contents,
+                u64::MAX,
+            )
+            .unwrap()
+        };
+        let object = Object::new_move(
+            move_object,
+            Owner::Immutable,
+            TransactionDigest::genesis_marker(),
+        );
+        (object, layout)
+    }
+
+    fn masked_fields(
+        object: &Object,
+        layout: &MoveStructLayout,
+        field_mask: &[&str],
+    ) -> Result<MgoMoveStruct, anyhow::Error> {
+        let options = MgoObjectDataOptions::new()
+            .with_content()
+            .with_field_mask(field_mask.iter().map(|f| f.to_string()).collect());
+        let data: MgoObjectData = (
+            object.compute_object_reference(),
+            object.clone(),
+            Some(layout.clone()),
+            options,
+        )
+            .try_into()?;
+        assert!(data.bcs.is_none());
+        match data.content {
+            Some(MgoParsedData::MoveObject(o)) => Ok(o.fields),
+            other => panic!("Expected Move object content, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_field_mask_projects_content() {
+        const HUGE_LEN: usize = 4 * 1024 * 1024;
+        let (object, layout) = blob_object(HUGE_LEN);
+
+        let MgoMoveStruct::WithFields(small) = masked_fields(&object, &layout, &["small"]).unwrap()
+        else {
+            panic!("Expected named fields");
+        };
+        assert_eq!(small.len(), 1);
+        assert_eq!(
+            small.get("small"),
+            Some(&MgoMoveValue::String("7".to_string()))
+        );
+
+        let MgoMoveStruct::WithFields(huge) = masked_fields(&object, &layout, &["huge"]).unwrap()
+        else {
+            panic!("Expected named fields");
+        };
+        assert_eq!(huge.len(), 1);
+        let Some(MgoMoveValue::Vector(bytes)) = huge.get("huge") else {
+            panic!("Expected the huge vector field, got {huge:?}");
+        };
+        assert_eq!(bytes.len(), HUGE_LEN);
+    }
+
+    #[test]
+    fn test_field_mask_rejects_unknown_fields() {
+        let (object, layout) = blob_object(16);
+        let err = masked_fields(&object, &layout, &["small", "missing"]).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("missing"), "{message}");
+        assert!(message.contains(r#"["id", "huge", "small"]"#), "{message}");
+
+        let options = MgoObjectDataOptions::new().with_field_mask(vec!["missing".to_string()]);
+        assert!(options.check_field_mask(&layout).is_err());
+        assert!(MgoObjectDataOptions::new()
+            .check_field_mask(&layout)
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_multi_get_objects_rejects_field_mask_as_invalid_params() {
+        use crate::authority_state::MockStateRead;
+        use jsonrpsee::core::Error as RpcError;
+        use jsonrpsee::types::error::CallError;
+        use mgo_storage::key_value_store_metrics::KeyValueStoreMetrics;
+
+        let (object, layout) = blob_object(16);
+        let mut mock_state = MockStateRead::new();
+        mock_state.expect_get_object_read().returning(move |_| {
+            Ok(ObjectRead::Exists(
+                object.compute_object_reference(),
+                object.clone(),
+                Some(layout.clone()),
+            ))
+        });
+        let read_api = ReadApi {
+            state: Arc::new(mock_state),
+            transaction_kv_store: Arc::new(TransactionKeyValueStore::new(
+                "rocksdb",
+                KeyValueStoreMetrics::new_for_tests(),
+                Arc::new(MockKeyValueStore::new()),
+            )),
+            metrics: Arc::new(JsonRpcMetrics::new_for_tests()),
+            max_past_objects_batch_size: *QUERY_MAX_RESULT_LIMIT,
+            display_limits: DisplayRenderLimits::default(),
+        };
+
+        let options = MgoObjectDataOptions::new()
+            .with_content()
+            .with_field_mask(vec!["small".to_string(), "missing".to_string()]);
+        let err = read_api
+            .multi_get_objects(
+                vec![ObjectID::random(), ObjectID::random()],
+                Some(options),
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, RpcError::Call(CallError::InvalidParams(_))),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_transactions_with_pruned_effects() {
+        use crate::authority_state::{CheckpointTransactionData, MockStateRead};
+        use mockall::predicate;
+        use mgo_types::utils::create_fake_transaction;
+
+        let transactions = (0..3)
+            .map(|_| {
+                let transaction = create_fake_transaction();
+                CheckpointTransactionData {
+                    digest: *transaction.digest(),
+                    effects: Some(TransactionEffects::new_with_tx(transaction.data())),
+                    transaction: Some(transaction),
+                    events: None,
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut returned = transactions.clone();
+        returned[1].effects = None;
+
+        let mut mock_state = MockStateRead::new();
+        mock_state
+            .expect_get_checkpoint_transactions()
+            .with(
+                predicate::eq(7),
+                predicate::eq(CheckpointTransactionsOptions {
+                    cursor: None,
+                    limit: 3,
+                }),
+            )
+            .return_once(move |_, _| Ok(returned));
+
+        let page = ReadApi::get_checkpoint_transactions_internal(Arc::new(mock_state), 7, None, 2)
+            .await
+            .unwrap();
+
+        assert_eq!(page.checkpoint, 7);
+        assert_eq!(page.next_cursor, Some(transactions[1].digest));
+        assert_eq!(page.data.len(), 2);
+        assert_eq!(page.data[0].digest, transactions[0].digest);
+        assert_eq!(page.data[0].effects, transactions[0].effects);
+        // The pruned effects are reported on their entry, the rest of it is still returned.
+        assert_eq!(page.data[1].digest, transactions[1].digest);
+        assert_eq!(page.data[1].effects, None);
+        assert_eq!(
+            page.data[1].transaction,
+            transactions[1]
+                .transaction
+                .clone()
+                .map(Transaction::into_data)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_loaded_child_objects_with_pruned_versions() {
+        use crate::authority_state::MockStateRead;
+
+        let (found, _) = blob_object(1);
+        let found_child = (found.id(), found.version());
+        let pruned_child = (ObjectID::random(), SequenceNumber::from_u64(3));
+        let unread_child = (ObjectID::random(), SequenceNumber::from_u64(5));
+
+        let mut mock_state = MockStateRead::new();
+        let object = found.clone();
+        mock_state
+            .expect_get_past_object_read()
+            .times(2)
+            .returning(move |object_id, version| {
+                Ok(if *object_id == object.id() {
+                    PastObjectRead::VersionFound(
+                        object.compute_object_reference(),
+                        object.clone(),
+                        None,
+                    )
+                } else {
+                    PastObjectRead::VersionNotFound(*object_id, version)
+                })
+            });
+
+        let reads = read_loaded_child_objects(
+            &Arc::new(mock_state),
+            &[found_child, pruned_child, unread_child],
+            2,
+        )
+        .await
+        .unwrap();
+
+        // The pruned child and the one past the limit degrade to their version only.
+        assert_eq!(reads.len(), 3);
+        assert_eq!(reads[0].as_ref().unwrap().digest(), found.digest());
+        assert_eq!(reads[1], Err(MgoUnresolvedChildObject::Pruned));
+        assert_eq!(reads[2], Err(MgoUnresolvedChildObject::LimitExceeded));
+    }
+
+    mod verify_transaction_block_signature {
+        use super::*;
+        use crate::authority_state::MockStateRead;
+        use fastcrypto::ed25519::Ed25519KeyPair;
+        use mgo_types::base_types::{random_object_ref, MgoAddress};
+        use mgo_types::committee::Committee;
+        use mgo_json_rpc_types::TransactionSignatureResult;
+        use mgo_types::crypto::{get_key_pair, Signature};
+        use shared_crypto::intent::{IntentScope, PersonalMessage};
+
+        fn mock_state() -> MockStateRead {
+            let committee = Committee::new_simple_test_committee().0;
+            let mut mock_state = MockStateRead::new();
+            mock_state
+                .expect_get_or_latest_committee()
+                .returning(move |_| Ok(committee.clone()));
+            mock_state
+        }
+
+        fn transfer(sender: MgoAddress, sponsor: MgoAddress) -> TransactionData {
+            TransactionData::new_transfer_mgo_allow_sponsor(
+                MgoAddress::random_for_testing_only(),
+                sender,
+                Some(1),
+                random_object_ref(),
+                1_000_000,
+                1000,
+                sponsor,
+            )
+        }
+
+        fn sign(tx_data: &TransactionData, keypair: &Ed25519KeyPair) -> Base64 {
+            let signature = Signature::new_secure(
+                &IntentMessage::new(Intent::mgo_transaction(), tx_data.clone()),
+                keypair,
+            );
+            Base64::from_bytes(signature.as_ref())
+        }
+
+        #[test]
+        fn test_valid_ed25519_signature() {
+            let (sender, keypair) = get_key_pair::<Ed25519KeyPair>();
+            let tx_data = transfer(sender, sender);
+            let signatures = [sign(&tx_data, &keypair)];
+
+            let result = ReadApi::verify_transaction_block_signature_internal(
+                &mock_state(),
+                tx_data,
+                &signatures,
+            )
+            .unwrap();
+            assert!(result.valid);
+            assert_eq!(result.error, None);
+            assert_eq!(
+                result.signatures,
+                vec![TransactionSignatureResult {
+                    signer: Some(sender),
+                    valid: true,
+                    error: None,
+                }]
+            );
+        }
+
+        #[test]
+        fn test_signature_under_wrong_intent() {
+            let (sender, keypair) = get_key_pair::<Ed25519KeyPair>();
+            let tx_data = transfer(sender, sender);
+            // The transaction bytes signed as a personal message rather than as a transaction.
+            let signature = Signature::new_secure(
+                &IntentMessage::new(
+                    Intent::mgo_app(IntentScope::PersonalMessage),
+                    PersonalMessage {
+                        message: bcs::to_bytes(&tx_data).unwrap(),
+                    },
+                ),
+                &keypair,
+            );
+            let signatures = [Base64::from_bytes(signature.as_ref())];
+
+            let result = ReadApi::verify_transaction_block_signature_internal(
+                &mock_state(),
+                tx_data,
+                &signatures,
+            )
+            .unwrap();
+            assert!(!result.valid);
+            // The signer signed, just not this transaction.
+            assert_eq!(result.error, None);
+            assert_eq!(result.signatures[0].signer, Some(sender));
+            assert!(!result.signatures[0].valid);
+            assert!(result.signatures[0].error.is_some());
+        }
+
+        #[test]
+        fn test_signature_of_non_sender() {
+            let (sender, _) = get_key_pair::<Ed25519KeyPair>();
+            let (other, other_keypair) = get_key_pair::<Ed25519KeyPair>();
+            let tx_data = transfer(sender, sender);
+            let signatures = [sign(&tx_data, &other_keypair)];
+
+            let result = ReadApi::verify_transaction_block_signature_internal(
+                &mock_state(),
+                tx_data,
+                &signatures,
+            )
+            .unwrap();
+            assert!(!result.valid);
+            assert_eq!(result.error, Some(format!("Missing signature of {sender}")));
+            assert_eq!(result.signatures[0].signer, Some(other));
+            assert_eq!(
+                result.signatures[0].error,
+                Some(format!(
+                    "Signer {other} is neither the sender nor the sponsor of the transaction"
+                ))
+            );
+        }
+
+        #[test]
+        fn test_sponsored_transaction_and_malformed_signatures() {
+            let (sender, sender_keypair) = get_key_pair::<Ed25519KeyPair>();
+            let (sponsor, sponsor_keypair) = get_key_pair::<Ed25519KeyPair>();
+            let tx_data = transfer(sender, sponsor);
+            let sender_signature = sign(&tx_data, &sender_keypair);
+            let sponsor_signature = sign(&tx_data, &sponsor_keypair);
+
+            let result = ReadApi::verify_transaction_block_signature_internal(
+                &mock_state(),
+                tx_data.clone(),
+                &[sponsor_signature, sender_signature.clone()],
+            )
+            .unwrap();
+            assert!(result.valid);
+            assert_eq!(result.signatures[0].signer, Some(sponsor));
+            assert_eq!(result.signatures[1].signer, Some(sender));
+
+            // Without the sponsor, and with bytes that are no signature at all.
+            let result = ReadApi::verify_transaction_block_signature_internal(
+                &mock_state(),
+                tx_data,
+                &[sender_signature, Base64::from_bytes(&[0, 1, 2])],
+            )
+            .unwrap();
+            assert!(!result.valid);
+            assert_eq!(result.error, Some(format!("Missing signature of {sponsor}")));
+            assert!(result.signatures[0].valid);
+            assert_eq!(result.signatures[1].signer, None);
+            assert!(result.signatures[1]
+                .error
+                .as_ref()
+                .unwrap()
+                .starts_with("Invalid signature bytes"));
+        }
+    }
+
+    mockall::mock! {
+        KeyValueStore {}
+        #[async_trait]
+        impl TransactionKeyValueStoreTrait for KeyValueStore {
+            async fn multi_get(
+                &self,
+                transactions: &[TransactionDigest],
+                effects: &[TransactionDigest],
+                events: &[TransactionEventsDigest],
+            ) -> MgoResult<KVStoreTransactionData>;
+
+            async fn multi_get_checkpoints(
+                &self,
+                checkpoint_summaries: &[CheckpointSequenceNumber],
+                checkpoint_contents: &[CheckpointSequenceNumber],
+                checkpoint_summaries_by_digest: &[CheckpointDigest],
+                checkpoint_contents_by_digest: &[CheckpointContentsDigest],
+            ) -> MgoResult<KVStoreCheckpointData>;
+
+            async fn deprecated_get_transaction_checkpoint(
+                &self,
+                digest: TransactionDigest,
+            ) -> MgoResult<Option<CheckpointSequenceNumber>>;
+
+            async fn get_object(
+                &self,
+                object_id: ObjectID,
+                version: SequenceNumber,
+            ) -> MgoResult<Option<Object>>;
+
+            async fn multi_get_transaction_checkpoint(
+                &self,
+                digests: &[TransactionDigest],
+            ) -> MgoResult<Vec<Option<CheckpointSequenceNumber>>>;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transaction_checkpoint_responses_in_batch() {
+        use mgo_storage::key_value_store_metrics::KeyValueStoreMetrics;
+        use mgo_types::committee::Committee;
+        use mgo_types::messages_checkpoint::CertifiedCheckpointSummary;
+
+        // A page of 50 transactions over checkpoints 10 to 12, of epochs 1 and 2, but the last
+        // one, which is not in a checkpoint yet.
+        let digests: Vec<_> = (0..50).map(|_| TransactionDigest::random()).collect();
+        let checkpoints: Vec<_> = (0..50).map(|i| (i < 49).then_some(10 + i / 20)).collect();
+        let (committee, keys) = Committee::new_simple_test_committee_of_size(1);
+        let summaries: HashMap<_, _> = (10..=12)
+            .map(|seq| {
+                let summary = CheckpointSummary::new(
+                    1 + seq / 12,
+                    seq,
+                    0,
+                    &CheckpointContents::new_with_digests_only_for_tests([]),
+                    None,
+                    Default::default(),
+                    None,
+                    1000 * seq,
+                );
+                let summary = CertifiedCheckpointSummary::new_from_keypairs_for_testing(
+                    summary, &keys, &committee,
+                );
+                (seq, summary)
+            })
+            .collect();
+
+        let mut store = MockKeyValueStore::new();
+        let (expected_digests, returned) = (digests.clone(), checkpoints.clone());
+        store
+            .expect_multi_get_transaction_checkpoint()
+            .times(1)
+            .withf(move |digests| digests == expected_digests)
+            .return_once(move |_| Ok(returned));
+        store
+            .expect_multi_get_checkpoints()
+            .times(1)
+            .withf(|summaries, contents, summaries_by_digest, contents_by_digest| {
+                summaries == [10, 11, 12]
+                    && contents.is_empty()
+                    && summaries_by_digest.is_empty()
+                    && contents_by_digest.is_empty()
+            })
+            .return_once(move |seqs, _, _, _| {
+                let summaries = seqs.iter().map(|seq| summaries.get(seq).cloned()).collect();
+                Ok((summaries, vec![], vec![], vec![]))
+            });
+        let kv_store = TransactionKeyValueStore::new(
+            "rocksdb",
+            KeyValueStoreMetrics::new_for_tests(),
+            Arc::new(store),
+        );
+
+        let responses = transaction_checkpoint_responses(&kv_store, digests.clone())
+            .await
+            .unwrap();
+        assert_eq!(responses.len(), 50);
+        for ((response, digest), checkpoint) in responses.iter().zip(&digests).zip(checkpoints) {
+            assert_eq!(response.digest, *digest);
+            assert_eq!(response.checkpoint, checkpoint);
+            assert_eq!(response.epoch, checkpoint.map(|seq| 1 + seq / 12));
+            assert_eq!(response.timestamp_ms, checkpoint.map(|seq| 1000 * seq));
+            assert_eq!(response.checkpoint_timestamp_ms, response.timestamp_ms);
+            assert!(response.transaction.is_none() && response.effects.is_none());
+        }
+    }
 }
@@ -0,0 +1,135 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signing of the results of some methods with a key of the server, for clients that keep
+//! non-repudiable proof of what the server returned. Signatures are added to the results under
+//! [`RESPONSE_SIGNATURE_FIELD`], and are verified with
+//! [`mgo_json_rpc_types::verify_signed_response`]. Servers without a [`ResponseSigner`] answer
+//! as if signing did not exist.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use jsonrpsee::core::server::helpers::MethodResponse;
+use serde_json::Value;
+use tracing::warn;
+
+use mgo_json_rpc_types::{ResponseSignature, RESPONSE_SIGNATURE_FIELD};
+use mgo_keys::keypair_file::read_keypair_from_file;
+use mgo_types::base_types::MgoAddress;
+use mgo_types::crypto::MgoKeyPair;
+
+use crate::error::Error;
+
+/// Methods whose results may be signed, the transaction responses and checkpoints. The results
+/// of other methods are not all JSON objects that a signature can be added to.
+pub const SIGNABLE_METHODS: &[&str] = &[
+    "mgo_executeTransactionBlock",
+    "mgo_getTransactionBlock",
+    "mgo_getCheckpoint",
+    "mgo_getCheckpointTransactions",
+];
+
+/// Signing of the results of the server.
+#[derive(Clone, Debug)]
+pub struct ResponseSigningConfig {
+    /// File of the signing key, as read by [`read_keypair_from_file`].
+    pub key_path: PathBuf,
+    /// Methods whose results are signed, among [`SIGNABLE_METHODS`]. All of them when empty.
+    pub methods: Vec<String>,
+}
+
+impl ResponseSigningConfig {
+    /// The signer of the configured methods, with the key of the configured file.
+    pub fn build(&self) -> Result<ResponseSigner, Error> {
+        let key = read_keypair_from_file(&self.key_path).map_err(|e| {
+            invalid(format!(
+                "Cannot read the signing key from {}: {e}",
+                self.key_path.display()
+            ))
+        })?;
+        ResponseSigner::new(key, &self.methods)
+    }
+}
+
+/// Signs the successful results of some methods. Clones share the same key.
+#[derive(Clone)]
+pub struct ResponseSigner {
+    key: Arc<MgoKeyPair>,
+    signer: MgoAddress,
+    methods: Arc<BTreeSet<String>>,
+}
+
+impl ResponseSigner {
+    /// Signs the results of `methods` with `key`, or of all [`SIGNABLE_METHODS`] if `methods`
+    /// is empty.
+    pub fn new(key: MgoKeyPair, methods: &[String]) -> Result<Self, Error> {
+        if let Some(method) = methods
+            .iter()
+            .find(|m| !SIGNABLE_METHODS.contains(&m.as_str()))
+        {
+            return Err(invalid(format!(
+                "The results of `{method}` cannot be signed, only those of {}",
+                SIGNABLE_METHODS.join(", ")
+            )));
+        }
+        let methods = if methods.is_empty() {
+            SIGNABLE_METHODS.iter().map(|m| m.to_string()).collect()
+        } else {
+            methods.iter().cloned().collect()
+        };
+        Ok(Self {
+            signer: MgoAddress::from(&key.public()),
+            key: Arc::new(key),
+            methods: Arc::new(methods),
+        })
+    }
+
+    /// Address of the signing key, which clients verify signatures against.
+    pub fn signer(&self) -> MgoAddress {
+        self.signer
+    }
+
+    /// `response` to a call of `method`, with its result signed if it is successful and the
+    /// results of `method` are signed.
+    pub(crate) fn sign(&self, method: &str, mut response: MethodResponse) -> MethodResponse {
+        if !response.success || !self.methods.contains(method) {
+            return response;
+        }
+        let mut envelope: Value = match serde_json::from_str(&response.result) {
+            Ok(envelope) => envelope,
+            Err(e) => {
+                warn!(method, "Cannot sign a response that is not JSON: {e}");
+                return response;
+            }
+        };
+        let Some(result) = envelope.get_mut("result").filter(|result| result.is_object()) else {
+            warn!(method, "Cannot sign a result that is not a JSON object");
+            return response;
+        };
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        let signature = ResponseSignature::sign(result, timestamp_ms, &self.key);
+        result[RESPONSE_SIGNATURE_FIELD] =
+            serde_json::to_value(signature).expect("Serializing a signature cannot fail");
+        response.result = envelope.to_string();
+        response
+    }
+}
+
+impl fmt::Debug for ResponseSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResponseSigner")
+            .field("signer", &self.signer)
+            .field("methods", &self.methods)
+            .finish_non_exhaustive()
+    }
+}
+
+fn invalid(message: String) -> Error {
+    Error::InvalidResponseSigningConfig(message)
+}
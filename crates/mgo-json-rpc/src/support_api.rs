@@ -0,0 +1,178 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+use tracing::instrument;
+
+use mango_metrics::spawn_monitored_task;
+use mgo_core::authority::AuthorityState;
+use mgo_json_rpc_api::{JsonRpcMetrics, SupportApiOpenRpc, SupportApiServer};
+use mgo_json_rpc_types::{
+    AddressStateExport, Balance, CheckpointVerificationReport, ExportSection, OwnedObjectSummary,
+    PendingLock, TransactionFilter,
+};
+use mgo_open_rpc::Module;
+use mgo_storage::key_value_store::TransactionKeyValueStore;
+use mgo_types::base_types::{MgoAddress, ObjectInfo};
+use mgo_types::mgo_serde::BigInt;
+
+use crate::authority_state::{StateRead, StateReadResult};
+use crate::coin_api::to_balance;
+use crate::error::{Error, MgoRpcInputError};
+use crate::governance_api::GovernanceReadApi;
+use crate::{with_tracing, MgoRpcModule};
+
+/// Most owned objects exported, which are also the objects checked for pending locks.
+pub const EXPORT_OWNED_OBJECTS_LIMIT: usize = 1000;
+/// Most transactions exported.
+pub const EXPORT_TRANSACTIONS_LIMIT: usize = 50;
+/// Most checkpoints verified per call.
+pub const MAX_CHECKPOINT_VERIFICATION_RANGE: u64 = 1000;
+
+/// Serves the state of an address and checks of the checkpoint store to support tooling. Not
+/// meant to be exposed publicly, as a single export reads up to [`EXPORT_OWNED_OBJECTS_LIMIT`]
+/// object locks and a single check hashes up to [`MAX_CHECKPOINT_VERIFICATION_RANGE`]
+/// checkpoints.
+#[derive(Clone)]
+pub struct SupportApi {
+    state: Arc<dyn StateRead>,
+    transaction_kv_store: Arc<TransactionKeyValueStore>,
+    governance: GovernanceReadApi,
+}
+
+impl SupportApi {
+    pub fn new(
+        state: Arc<AuthorityState>,
+        transaction_kv_store: Arc<TransactionKeyValueStore>,
+        metrics: Arc<JsonRpcMetrics>,
+    ) -> Self {
+        Self {
+            governance: GovernanceReadApi::new(state.clone(), metrics),
+            state,
+            transaction_kv_store,
+        }
+    }
+
+    async fn export(&self, address: MgoAddress) -> Result<AddressStateExport, Error> {
+        let as_of_checkpoint = self.state.get_latest_checkpoint_sequence_number()?;
+
+        let owned_objects = self.state.get_owner_objects_with_limit(
+            address,
+            None,
+            EXPORT_OWNED_OBJECTS_LIMIT,
+            None,
+        );
+        let pending_locks = match &owned_objects {
+            Ok(objects) => self.pending_locks(objects).await.into(),
+            Err(_) => ExportSection::error("Owned objects could not be read".to_string()),
+        };
+        let owned_objects = owned_objects
+            .map(|objects| {
+                objects
+                    .into_iter()
+                    .map(|object| OwnedObjectSummary {
+                        object_id: object.object_id,
+                        version: object.version,
+                        type_: object.type_.to_string(),
+                    })
+                    .collect()
+            })
+            .into();
+
+        let balances = self.balances(address).await.into();
+        let stakes = self.governance.get_stakes(address).await.into();
+        let recent_transactions = self
+            .state
+            .get_transactions(
+                &self.transaction_kv_store,
+                Some(TransactionFilter::FromAddress(address)),
+                None,
+                Some(EXPORT_TRANSACTIONS_LIMIT),
+                true,
+            )
+            .await
+            .into();
+
+        Ok(AddressStateExport {
+            address,
+            as_of_checkpoint,
+            owned_objects,
+            balances,
+            stakes,
+            recent_transactions,
+            pending_locks,
+        })
+    }
+
+    async fn balances(&self, address: MgoAddress) -> StateReadResult<Vec<Balance>> {
+        let all_balance = self.state.get_all_balance(address).await?;
+        let mut balances: Vec<_> = all_balance
+            .iter()
+            .map(|(coin_type, balance)| to_balance(coin_type, balance))
+            .collect();
+        balances.sort_by(|a, b| a.coin_type.cmp(&b.coin_type));
+        Ok(balances)
+    }
+
+    async fn pending_locks(&self, objects: &[ObjectInfo]) -> StateReadResult<Vec<PendingLock>> {
+        let mut locks = vec![];
+        for object in objects {
+            let object_ref = (object.object_id, object.version, object.digest);
+            if let Some(transaction) = self.state.get_transaction_lock(&object_ref).await? {
+                locks.push(PendingLock {
+                    object: object_ref.into(),
+                    transaction,
+                });
+            }
+        }
+        Ok(locks)
+    }
+}
+
+#[async_trait]
+impl SupportApiServer for SupportApi {
+    #[instrument(skip(self))]
+    async fn export_address_state(&self, address: MgoAddress) -> RpcResult<AddressStateExport> {
+        with_tracing!(async move { self.export(address).await })
+    }
+
+    #[instrument(skip(self))]
+    async fn verify_checkpoint_range(
+        &self,
+        start: BigInt<u64>,
+        end: BigInt<u64>,
+    ) -> RpcResult<CheckpointVerificationReport> {
+        with_tracing!(async move {
+            let (start, end) = (*start, *end);
+            if end < start {
+                Err(MgoRpcInputError::GenericInvalid(format!(
+                    "End checkpoint {end} is before start checkpoint {start}"
+                )))?
+            }
+            if end - start >= MAX_CHECKPOINT_VERIFICATION_RANGE {
+                Err(MgoRpcInputError::SizeLimitExceeded(format!(
+                    "{MAX_CHECKPOINT_VERIFICATION_RANGE} checkpoints"
+                )))?
+            }
+            let state = self.state.clone();
+            Ok(
+                spawn_monitored_task!(async move { state.verify_checkpoint_range(start, end) })
+                    .await??,
+            )
+        })
+    }
+}
+
+impl MgoRpcModule for SupportApi {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        SupportApiOpenRpc::module_doc()
+    }
+}
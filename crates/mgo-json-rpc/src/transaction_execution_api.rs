@@ -7,6 +7,7 @@ use std::time::Duration;
 use async_trait::async_trait;
 use fastcrypto::encoding::Base64;
 use fastcrypto::traits::ToFromBytes;
+use futures::{Stream, StreamExt};
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::RpcModule;
 
@@ -17,13 +18,15 @@ use mgo_core::authority_client::NetworkAuthorityClient;
 use mgo_core::transaction_orchestrator::TransactiondOrchestrator;
 use mgo_json_rpc_api::{JsonRpcMetrics, WriteApiOpenRpc, WriteApiServer};
 use mgo_json_rpc_types::{
-    DevInspectArgs, DevInspectResults, DryRunTransactionBlockResponse, MgoTransactionBlock,
-    MgoTransactionBlockEvents, MgoTransactionBlockResponse, MgoTransactionBlockResponseOptions,
+    CheckpointNotification, DevInspectArgs, DevInspectResults, DryRunTransactionBlockResponse,
+    MgoTransactionBlock, MgoTransactionBlockEvents, MgoTransactionBlockResponse,
+    MgoTransactionBlockResponseOptions,
 };
 use mgo_open_rpc::Module;
 use mgo_types::base_types::MgoAddress;
 use mgo_types::crypto::default_hash;
 use mgo_types::digests::TransactionDigest;
+use mgo_types::messages_checkpoint::VerifiedCheckpoint;
 use mgo_types::effects::TransactionEffectsAPI;
 use mgo_types::quorum_driver_types::{
     ExecuteTransactionRequest, ExecuteTransactionRequestType, ExecuteTransactionResponse,
@@ -33,19 +36,26 @@ use mgo_types::mgo_serde::BigInt;
 use mgo_types::transaction::{
     InputObjectKind, Transaction, TransactionData, TransactionDataAPI, TransactionKind,
 };
-use tracing::instrument;
+use tracing::field::{display, Empty};
+use tracing::{instrument, warn, Instrument, Span};
 
 use crate::authority_state::StateRead;
+use crate::debug_api::GasComparator;
 use crate::error::{Error, MgoRpcInputError};
 use crate::{
-    get_balance_changes_from_effect, get_object_changes, with_tracing, ObjectProviderCache,
-    MgoRpcModule,
+    get_balance_changes_from_effect, get_created_objects_summary, get_object_changes,
+    with_tracing, ObjectProviderCache, MgoRpcModule,
 };
 
+/// Longest a request may wait for its transaction to be included in a checkpoint, whatever the
+/// timeout it asks for.
+pub const MAX_CHECKPOINT_INCLUSION_WAIT: Duration = Duration::from_secs(60);
+
 pub struct TransactionExecutionApi {
     state: Arc<dyn StateRead>,
     transaction_orchestrator: Arc<TransactiondOrchestrator<NetworkAuthorityClient>>,
     metrics: Arc<JsonRpcMetrics>,
+    gas_comparator: Option<GasComparator>,
 }
 
 impl TransactionExecutionApi {
@@ -58,9 +68,17 @@ impl TransactionExecutionApi {
             state,
             transaction_orchestrator,
             metrics,
+            gas_comparator: None,
         }
     }
 
+    /// Compares the gas of every dry run to that of dev inspecting the same transaction with
+    /// `gas_comparator`, in the background so that dry runs are answered as fast as before.
+    pub fn with_gas_comparator(mut self, gas_comparator: GasComparator) -> Self {
+        self.gas_comparator = Some(gas_comparator);
+        self
+    }
+
     pub fn convert_bytes<T: serde::de::DeserializeOwned>(
         &self,
         tx_bytes: Base64,
@@ -104,6 +122,7 @@ impl TransactionExecutionApi {
             sigs.push(GenericSignature::from_bytes(&sig.to_vec()?)?);
         }
         let txn = Transaction::from_generic_sig_data(tx_data, sigs);
+        record_transaction_fields(txn.digest(), txn.data().transaction_data());
         let raw_transaction = if opts.show_raw_input {
             bcs::to_bytes(txn.data())?
         } else {
@@ -140,18 +159,37 @@ impl TransactionExecutionApi {
             self.prepare_execute_transaction_block(tx_bytes, signatures, opts, request_type)?;
         let digest = *txn.digest();
 
+        // Subscribed to before submitting, so that no checkpoint executed in between is missed.
+        let checkpoint_inclusion = match &request_type {
+            ExecuteTransactionRequestType::WaitForCheckpointInclusion { timeout_ms } => Some((
+                Duration::from_millis(*timeout_ms).min(MAX_CHECKPOINT_INCLUSION_WAIT),
+                self.state
+                    .get_subscription_handler()
+                    .subscribe_checkpoints(),
+            )),
+            _ => None,
+        };
+
         let transaction_orchestrator = self.transaction_orchestrator.clone();
         let orch_timer = self.metrics.orchestrator_latency_ms.start_timer();
-        let response = spawn_monitored_task!(transaction_orchestrator.execute_transaction_block(
-            ExecuteTransactionRequest {
+        let response = spawn_monitored_task!(transaction_orchestrator
+            .execute_transaction_block(ExecuteTransactionRequest {
                 transaction: txn,
                 request_type,
-            }
-        ))
+            })
+            .instrument(Span::current()))
         .await?
         .map_err(Error::from)?;
         drop(orch_timer);
 
+        let checkpoint = match checkpoint_inclusion {
+            Some((timeout, checkpoints)) => Some(
+                self.wait_for_checkpoint_inclusion(digest, checkpoints, timeout)
+                    .await?,
+            ),
+            None => None,
+        };
+
         let _post_orch_timer = self.metrics.post_orchestrator_latency_ms.start_timer();
         let ExecuteTransactionResponse::EffectsCert(cert) = response;
         let (effects, transaction_events, is_executed_locally) = *cert;
@@ -193,6 +231,11 @@ impl TransactionExecutionApi {
         } else {
             None
         };
+        let created_summary = if opts.show_created_summary && is_executed_locally {
+            Some(get_created_objects_summary(&object_cache, effects.effects.created()).await?)
+        } else {
+            None
+        };
 
         let raw_effects = if opts.show_raw_effects {
             bcs::to_bytes(&effects.effects)?
@@ -200,6 +243,24 @@ impl TransactionExecutionApi {
             vec![]
         };
 
+        // The orchestrator only confirms local execution once the written objects are readable,
+        // so any checkpoint executed by now is a safe lower bound for a read reflecting them.
+        let executed_at_checkpoint_hint = if is_executed_locally {
+            self.state.get_latest_checkpoint_sequence_number().ok()
+        } else {
+            None
+        };
+        // Unless waiting for a checkpoint, the transaction is not in one yet, but a locally
+        // executed one was just executed by this fullnode.
+        let observed_execution_timestamp_ms =
+            is_executed_locally.then(AuthorityState::unixtime_now_ms);
+        let written_objects = effects
+            .effects
+            .all_changed_objects()
+            .into_iter()
+            .map(|(object_ref, _, _)| object_ref.into())
+            .collect();
+
         Ok(MgoTransactionBlockResponse {
             digest,
             transaction,
@@ -208,14 +269,65 @@ impl TransactionExecutionApi {
             events,
             object_changes,
             balance_changes,
-            timestamp_ms: None,
+            created_summary,
+            signatures: None,
+            timestamp_ms: checkpoint.as_ref().map(|c| c.timestamp_ms),
+            checkpoint_timestamp_ms: checkpoint.as_ref().map(|c| c.timestamp_ms),
+            observed_execution_timestamp_ms,
             confirmed_local_execution: Some(is_executed_locally),
-            checkpoint: None,
+            executed_at_checkpoint_hint,
+            written_objects: Some(written_objects),
+            served_from: None,
+            checkpoint: checkpoint.as_ref().map(|c| c.sequence_number),
+            checkpoint_digest: checkpoint.as_ref().map(|c| *c.digest()),
+            epoch: checkpoint
+                .as_ref()
+                .filter(|_| opts.include_checkpoint)
+                .map(|c| c.epoch),
             errors: vec![],
             raw_effects,
         })
     }
 
+    /// Waits for a checkpoint executed by this node to include the transaction `digest`, checking
+    /// the transaction's checkpoint again whenever `checkpoints` announces a new one.
+    async fn wait_for_checkpoint_inclusion(
+        &self,
+        digest: TransactionDigest,
+        checkpoints: impl Stream<Item = CheckpointNotification>,
+        timeout: Duration,
+    ) -> Result<VerifiedCheckpoint, Error> {
+        let wait = async {
+            let mut checkpoints = std::pin::pin!(checkpoints);
+            loop {
+                if let Some((_, sequence_number)) =
+                    self.state.deprecated_get_transaction_checkpoint(&digest)?
+                {
+                    let checkpoint = self
+                        .state
+                        .get_checkpoint_by_sequence_number(sequence_number)?
+                        .ok_or_else(|| {
+                            Error::UnexpectedError(format!(
+                                "Checkpoint {sequence_number} of transaction {digest} not found"
+                            ))
+                        })?;
+                    return Ok(checkpoint);
+                }
+                if checkpoints.next().await.is_none() {
+                    return Err(Error::UnexpectedError(
+                        "Checkpoint notifications ended".to_string(),
+                    ));
+                }
+            }
+        };
+        tokio::time::timeout(timeout, wait).await.map_err(|_| {
+            Error::CheckpointInclusionTimeout {
+                digest,
+                timeout_ms: timeout.as_millis() as u64,
+            }
+        })?
+    }
+
     pub fn prepare_dry_run_transaction_block(
         &self,
         tx_bytes: Base64,
@@ -231,6 +343,7 @@ impl TransactionExecutionApi {
             tx_data,
         );
         let txn_digest = TransactionDigest::new(default_hash(&intent_msg.value));
+        record_transaction_fields(&txn_digest, &intent_msg.value);
         Ok((intent_msg.value, txn_digest, input_objs))
     }
 
@@ -262,6 +375,14 @@ impl TransactionExecutionApi {
         )
         .await?;
 
+        if let Some(gas_comparator) = self.gas_comparator.clone() {
+            spawn_monitored_task!(async move {
+                if let Err(e) = gas_comparator.compare(txn_data).await {
+                    warn!(tx_digest = %txn_digest, "Failed to compare dry run gas: {e}");
+                }
+            });
+        }
+
         Ok(DryRunTransactionBlockResponse {
             effects: resp.effects,
             events: resp.events,
@@ -272,9 +393,21 @@ impl TransactionExecutionApi {
     }
 }
 
+/// Records the transaction on the current span. Transaction bytes and signatures are never
+/// recorded.
+fn record_transaction_fields(digest: &TransactionDigest, tx_data: &TransactionData) {
+    let span = Span::current();
+    span.record("tx_digest", display(digest));
+    span.record("sender", display(tx_data.sender()));
+    span.record("gas_budget", tx_data.gas_budget());
+}
+
 #[async_trait]
 impl WriteApiServer for TransactionExecutionApi {
-    #[instrument(skip(self))]
+    #[instrument(
+        skip_all,
+        fields(tx_digest = Empty, sender = Empty, gas_budget = Empty, ?request_type)
+    )]
     async fn execute_transaction_block(
         &self,
         tx_bytes: Base64,
@@ -282,13 +415,26 @@ impl WriteApiServer for TransactionExecutionApi {
         opts: Option<MgoTransactionBlockResponseOptions>,
         request_type: Option<ExecuteTransactionRequestType>,
     ) -> RpcResult<MgoTransactionBlockResponse> {
-        with_tracing!(Duration::from_secs(10), async move {
+        // Waiting for checkpoint inclusion takes longer by design.
+        let time_spent_threshold = match &request_type {
+            Some(ExecuteTransactionRequestType::WaitForCheckpointInclusion { .. }) => {
+                Duration::from_secs(10) + MAX_CHECKPOINT_INCLUSION_WAIT
+            }
+            _ => Duration::from_secs(10),
+        };
+        with_tracing!(time_spent_threshold, async move {
             self.execute_transaction_block(tx_bytes, signatures, opts, request_type)
                 .await
         })
     }
 
-    #[instrument(skip(self))]
+    #[instrument(
+        skip_all,
+        fields(
+            sender = %sender_address,
+            gas_budget = additional_args.as_ref().and_then(|args| args.gas_budget).map(|b| *b),
+        )
+    )]
     async fn dev_inspect_transaction_block(
         &self,
         sender_address: MgoAddress,
@@ -322,7 +468,7 @@ impl WriteApiServer for TransactionExecutionApi {
         })
     }
 
-    #[instrument(skip(self))]
+    #[instrument(skip_all, fields(tx_digest = Empty, sender = Empty, gas_budget = Empty))]
     async fn dry_run_transaction_block(
         &self,
         tx_bytes: Base64,
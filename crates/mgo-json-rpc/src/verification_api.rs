@@ -0,0 +1,412 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use fastcrypto::encoding::Base64;
+use fastcrypto::hash::HashFunction;
+use fastcrypto::traits::{ToFromBytes, VerifyingKey};
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+use tracing::instrument;
+
+use mgo_json_rpc_api::{VerificationApiOpenRpc, VerificationApiServer};
+use mgo_json_rpc_types::{
+    SignatureVerificationRequest, SignatureVerificationResult, TransactionSignatureResult,
+    TransactionSignatureVerification,
+};
+use mgo_open_rpc::Module;
+use mgo_types::base_types::MgoAddress;
+use mgo_types::committee::EpochId;
+use mgo_types::crypto::{
+    DefaultHash, Ed25519MgoSignature, Secp256k1MgoSignature, Secp256r1MgoSignature, Signature,
+    MgoSignatureInner,
+};
+use mgo_types::error::MgoResult;
+use mgo_types::key_material::KeyMaterial;
+use mgo_types::signature::{AuthenticatorTrait, GenericSignature, VerifyParams};
+use mgo_types::transaction::{TransactionData, TransactionDataAPI};
+use shared_crypto::intent::{Intent, IntentMessage, IntentScope};
+
+use crate::error::MgoRpcInputError;
+use crate::{with_tracing, MgoRpcModule};
+
+/// Most signatures checked per call.
+pub const MAX_SIGNATURE_VERIFICATION_BATCH: usize = 200;
+
+/// Checks user signatures in batches, for light clients that verify many of them.
+#[derive(Clone, Default)]
+pub struct VerificationApi;
+
+impl VerificationApi {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// A signature of a scheme that can be verified, with the digest it should sign.
+struct Pending<S> {
+    index: usize,
+    address: MgoAddress,
+    digest: [u8; 32],
+    signature: S,
+}
+
+/// Check every item, verifying the signatures of each scheme as one batch and falling back to
+/// verifying them one by one when the batch fails, to tell which are invalid.
+pub fn verify_signatures(
+    items: &[SignatureVerificationRequest],
+) -> Vec<SignatureVerificationResult> {
+    let mut results = vec![SignatureVerificationResult::valid(); items.len()];
+    let mut ed25519 = vec![];
+    let mut secp256k1 = vec![];
+    let mut secp256r1 = vec![];
+
+    for (index, item) in items.iter().enumerate() {
+        let (digest, signature) = match prepare(item) {
+            Ok(prepared) => prepared,
+            Err(error) => {
+                results[index] = SignatureVerificationResult::invalid(error);
+                continue;
+            }
+        };
+        let address = item.address;
+        match signature {
+            Signature::Ed25519MgoSignature(signature) => ed25519.push(Pending {
+                index,
+                address,
+                digest,
+                signature,
+            }),
+            Signature::Secp256k1MgoSignature(signature) => secp256k1.push(Pending {
+                index,
+                address,
+                digest,
+                signature,
+            }),
+            Signature::Secp256r1MgoSignature(signature) => secp256r1.push(Pending {
+                index,
+                address,
+                digest,
+                signature,
+            }),
+        }
+    }
+
+    verify_batch::<Ed25519MgoSignature>(ed25519, &mut results);
+    verify_batch::<Secp256k1MgoSignature>(secp256k1, &mut results);
+    verify_batch::<Secp256r1MgoSignature>(secp256r1, &mut results);
+    results
+}
+
+/// Decode the signature of an item and hash the intent message it should sign.
+fn prepare(item: &SignatureVerificationRequest) -> Result<([u8; 32], Signature), String> {
+    let scope = IntentScope::try_from(item.intent_scope)
+        .map_err(|_| format!("Invalid intent scope {}", item.intent_scope))?;
+    let signature = match GenericSignature::from_bytes(&item.signature_b64) {
+        Ok(GenericSignature::Signature(signature)) => signature,
+        Ok(GenericSignature::MultiSig(_) | GenericSignature::MultiSigLegacy(_)) => {
+            return Err("Multisig signatures are unsupported in batch".to_string())
+        }
+        Ok(GenericSignature::ZkLoginAuthenticator(_)) => {
+            return Err("zkLogin signatures are unsupported in batch".to_string())
+        }
+        Err(e) => return Err(format!("Invalid signature bytes: {e}")),
+    };
+
+    let mut hasher = DefaultHash::default();
+    hasher.update(
+        bcs::to_bytes(&Intent::mgo_app(scope)).expect("Intent serialization should not fail"),
+    );
+    hasher.update(&item.data_b64);
+    Ok((hasher.finalize().digest, signature))
+}
+
+fn verify_batch<S: MgoSignatureInner>(
+    pending: Vec<Pending<S>>,
+    results: &mut [SignatureVerificationResult],
+) {
+    let mut batch = vec![];
+    for Pending {
+        index,
+        address,
+        digest,
+        signature,
+    } in pending
+    {
+        let (signature, public_key) = match signature.get_verification_inputs() {
+            Ok(inputs) => inputs,
+            Err(e) => {
+                results[index] = SignatureVerificationResult::invalid(e.to_string());
+                continue;
+            }
+        };
+        let signer = MgoAddress::from(&public_key);
+        if KeyMaterial::from(&signer) != KeyMaterial::from(&address) {
+            results[index] = SignatureVerificationResult::invalid(format!(
+                "Incorrect signer, expected {address}, got {signer}"
+            ));
+            continue;
+        }
+        batch.push((index, digest, public_key, signature));
+    }
+
+    let digests: Vec<_> = batch.iter().map(|(_, digest, _, _)| &digest[..]).collect();
+    let public_keys: Vec<_> = batch.iter().map(|(_, _, pk, _)| pk.clone()).collect();
+    let signatures: Vec<_> = batch.iter().map(|(_, _, _, sig)| sig.clone()).collect();
+    if batch.is_empty()
+        || S::PubKey::verify_batch_empty_fail_different_msg(&digests, &public_keys, &signatures)
+            .is_ok()
+    {
+        return;
+    }
+
+    for (index, digest, public_key, signature) in batch {
+        if let Err(e) = public_key.verify(&digest, &signature) {
+            results[index] =
+                SignatureVerificationResult::invalid(format!("Fail to verify user sig {e}"));
+        }
+    }
+}
+
+/// Decode a signature of a transaction, as `flag || signature || public key` for single
+/// signatures.
+pub fn decode_transaction_signature(signature: &Base64) -> Result<GenericSignature, String> {
+    let bytes = signature
+        .to_vec()
+        .map_err(|e| format!("Invalid signature encoding: {e}"))?;
+    GenericSignature::from_bytes(&bytes).map_err(|e| format!("Invalid signature bytes: {e}"))
+}
+
+/// Check `signatures` against the transaction of `intent_message` as of `epoch`, the way it
+/// would be checked when submitted: every signature must hold and be made by the sender or the
+/// sponsor of the transaction, each of which must sign exactly once.
+pub fn verify_transaction_signatures(
+    intent_message: &IntentMessage<TransactionData>,
+    signatures: Vec<Result<GenericSignature, String>>,
+    epoch: EpochId,
+    verify_params: &VerifyParams,
+) -> TransactionSignatureVerification {
+    let required: Vec<_> = intent_message.value.signers().into_iter().collect();
+    let mut signed: Vec<MgoAddress> = vec![];
+    let results: Vec<_> = signatures
+        .into_iter()
+        .map(|signature| {
+            let signature = match signature {
+                Ok(signature) => signature,
+                Err(error) => {
+                    return TransactionSignatureResult {
+                        signer: None,
+                        valid: false,
+                        error: Some(error),
+                    }
+                }
+            };
+            let signer = match transaction_signer(&signature, &required, verify_params) {
+                Ok(signer) => signer,
+                Err(e) => {
+                    return TransactionSignatureResult {
+                        signer: None,
+                        valid: false,
+                        error: Some(e.to_string()),
+                    }
+                }
+            };
+            let error = if !KeyMaterial::from(&signer).is_any_of(&required) {
+                Some(format!(
+                    "Signer {signer} is neither the sender nor the sponsor of the transaction"
+                ))
+            } else if KeyMaterial::from(&signer).is_any_of(&signed) {
+                Some(format!("Duplicate signature of {signer}"))
+            } else {
+                signed.push(signer);
+                signature
+                    .verify_authenticator(intent_message, signer, Some(epoch), verify_params)
+                    .err()
+                    .map(|e| e.to_string())
+            };
+            TransactionSignatureResult {
+                signer: Some(signer),
+                valid: error.is_none(),
+                error,
+            }
+        })
+        .collect();
+
+    let missing: Vec<_> = required
+        .iter()
+        .filter(|signer| !KeyMaterial::from(*signer).is_any_of(&signed))
+        .map(|signer| signer.to_string())
+        .collect();
+    let error = (!missing.is_empty())
+        .then(|| format!("Missing signature of {}", missing.join(", ")));
+    TransactionSignatureVerification {
+        valid: error.is_none() && results.iter().all(|result| result.valid),
+        error,
+        signatures: results,
+    }
+}
+
+/// The address `signature` is made by. zkLogin signatures may still be made by their legacy
+/// padded address, when the epoch accepts them.
+fn transaction_signer(
+    signature: &GenericSignature,
+    required: &[MgoAddress],
+    verify_params: &VerifyParams,
+) -> MgoResult<MgoAddress> {
+    if let GenericSignature::ZkLoginAuthenticator(zklogin) = signature {
+        if verify_params.verify_legacy_zklogin_address {
+            let padded = MgoAddress::try_from_padded(&zklogin.inputs)?;
+            if KeyMaterial::from(&padded).is_any_of(required) {
+                return Ok(padded);
+            }
+        }
+    }
+    MgoAddress::try_from(signature)
+}
+
+#[async_trait]
+impl VerificationApiServer for VerificationApi {
+    #[instrument(skip(self, items))]
+    async fn verify_signatures(
+        &self,
+        items: Vec<SignatureVerificationRequest>,
+    ) -> RpcResult<Vec<SignatureVerificationResult>> {
+        with_tracing!(async move {
+            if items.len() > MAX_SIGNATURE_VERIFICATION_BATCH {
+                Err(MgoRpcInputError::SizeLimitExceeded(format!(
+                    "{MAX_SIGNATURE_VERIFICATION_BATCH} signatures"
+                )))?
+            }
+            Ok(tokio::task::spawn_blocking(move || verify_signatures(&items)).await?)
+        })
+    }
+}
+
+impl MgoRpcModule for VerificationApi {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        VerificationApiOpenRpc::module_doc()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fastcrypto::ed25519::Ed25519KeyPair;
+    use fastcrypto::secp256k1::Secp256k1KeyPair;
+    use fastcrypto::secp256r1::Secp256r1KeyPair;
+    use mgo_types::crypto::{get_key_pair, MgoKeyPair};
+    use mgo_types::multisig::{MultiSig, MultiSigPublicKey};
+    use shared_crypto::intent::{IntentMessage, PersonalMessage};
+
+    fn sign(keypair: &MgoKeyPair, message: &[u8]) -> SignatureVerificationRequest {
+        let message = PersonalMessage {
+            message: message.to_vec(),
+        };
+        let signature = Signature::new_secure(
+            &IntentMessage::new(Intent::mgo_app(IntentScope::PersonalMessage), message.clone()),
+            keypair,
+        );
+        SignatureVerificationRequest {
+            address: MgoAddress::from(&keypair.public()),
+            intent_scope: IntentScope::PersonalMessage as u8,
+            data_b64: bcs::to_bytes(&message).unwrap(),
+            signature_b64: signature.as_ref().to_vec(),
+        }
+    }
+
+    fn keypairs() -> Vec<MgoKeyPair> {
+        vec![
+            MgoKeyPair::Ed25519(get_key_pair::<Ed25519KeyPair>().1),
+            MgoKeyPair::Secp256k1(get_key_pair::<Secp256k1KeyPair>().1),
+            MgoKeyPair::Secp256r1(get_key_pair::<Secp256r1KeyPair>().1),
+        ]
+    }
+
+    #[test]
+    fn test_valid_signatures_across_schemes() {
+        let items: Vec<_> = keypairs()
+            .iter()
+            .flat_map(|kp| [sign(kp, b"claim 1"), sign(kp, b"claim 2")])
+            .collect();
+        let results = verify_signatures(&items);
+        assert_eq!(results, vec![SignatureVerificationResult::valid(); 6]);
+    }
+
+    #[test]
+    fn test_invalid_signatures_are_reported_per_item() {
+        let keypairs = keypairs();
+        let mut items = vec![];
+        for kp in &keypairs {
+            let valid = sign(kp, b"claim");
+
+            let mut corrupted = sign(kp, b"claim");
+            corrupted.data_b64 = bcs::to_bytes(&PersonalMessage {
+                message: b"forged".to_vec(),
+            })
+            .unwrap();
+
+            let mut wrong_address = sign(kp, b"claim");
+            wrong_address.address = MgoAddress::random_for_testing_only();
+
+            items.extend([valid, corrupted, wrong_address]);
+        }
+
+        let results = verify_signatures(&items);
+        for scheme in results.chunks(3) {
+            assert!(scheme[0].valid);
+            assert!(!scheme[1].valid);
+            assert!(scheme[1]
+                .error
+                .as_ref()
+                .unwrap()
+                .contains("Fail to verify user sig"));
+            assert!(!scheme[2].valid);
+            assert!(scheme[2]
+                .error
+                .as_ref()
+                .unwrap()
+                .contains("Incorrect signer"));
+        }
+    }
+
+    #[test]
+    fn test_malformed_and_unsupported_signatures() {
+        let keypairs = keypairs();
+        let single = sign(&keypairs[0], b"claim");
+
+        let multisig_pk = MultiSigPublicKey::new(vec![keypairs[0].public()], vec![1], 1).unwrap();
+        let multisig = MultiSig::combine(
+            vec![GenericSignature::from_bytes(&single.signature_b64).unwrap()],
+            multisig_pk.clone(),
+        )
+        .unwrap();
+        let multisig = SignatureVerificationRequest {
+            address: MgoAddress::from(&multisig_pk),
+            signature_b64: GenericSignature::MultiSig(multisig).as_ref().to_vec(),
+            ..single.clone()
+        };
+
+        let mut truncated = single.clone();
+        truncated.signature_b64.truncate(10);
+
+        let mut bad_scope = single.clone();
+        bad_scope.intent_scope = 200;
+
+        let results = verify_signatures(&[single, multisig, truncated, bad_scope]);
+        assert!(results[0].valid);
+        assert_eq!(
+            results[1].error.as_deref(),
+            Some("Multisig signatures are unsupported in batch")
+        );
+        assert!(results[2]
+            .error
+            .as_ref()
+            .unwrap()
+            .starts_with("Invalid signature bytes"));
+        assert_eq!(results[3].error.as_deref(), Some("Invalid intent scope 200"));
+    }
+}
@@ -0,0 +1,334 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Session agent that keeps unlocked keys in memory for a limited time.
+//!
+//! `mgo keytool unlock` hands a key to a [`KeyAgent`] listening on a unix socket next to the
+//! keystore, and the wallet signs through an [`AgentClient`] before falling back to the keystore.
+//! Every connection carries a single JSON request, terminated by the client shutting down its
+//! write half, and a single JSON response.
+//!
+//! Keys are dropped, which zeroes their private bytes, as soon as they expire or are locked, and
+//! the buffers that carried an encoded key are zeroed after use. The agent exits once the last
+//! key it held is gone.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{ErrorKind, Read, Write};
+use std::net::Shutdown;
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context};
+use fastcrypto::hash::HashFunction;
+use serde::{Deserialize, Serialize};
+use shared_crypto::intent::{Intent, IntentMessage};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::keystore::Keystore;
+use mgo_types::base_types::MgoAddress;
+use mgo_types::crypto::{DefaultHash, EncodeDecodeBase64, MgoKeyPair, Signature};
+
+/// File name of the agent socket, created in the same directory as the keystore it serves.
+pub const AGENT_SOCKET_FILENAME: &str = "mgo.agent.sock";
+
+/// How often an idle agent wakes up to drop expired keys.
+const SWEEP_INTERVAL: Duration = Duration::from_millis(50);
+/// Bound on how long either side waits for the other while exchanging a request.
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+/// Requests are a few hundred bytes; the cap keeps the request buffer from ever reallocating,
+/// which would leave unzeroed copies of an encoded key behind.
+const MAX_REQUEST_SIZE: usize = 4096;
+
+/// Path of the agent socket serving the keystore at `keystore_path`.
+pub fn agent_socket_path(keystore_path: &Path) -> PathBuf {
+    keystore_path.with_file_name(AGENT_SOCKET_FILENAME)
+}
+
+#[derive(Serialize, Deserialize)]
+enum AgentRequest {
+    Unlock {
+        key: String,
+        ttl_ms: u64,
+    },
+    SignHashed {
+        address: MgoAddress,
+        digest: Vec<u8>,
+    },
+    Lock {
+        address: Option<MgoAddress>,
+    },
+    List,
+}
+
+impl Drop for AgentRequest {
+    fn drop(&mut self) {
+        if let AgentRequest::Unlock { key, .. } = self {
+            key.zeroize();
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum AgentResponse {
+    Unlocked(Vec<UnlockedKeyInfo>),
+    Signature(String),
+    NotUnlocked,
+    Error(String),
+}
+
+/// A key held by the agent and the time left before it is dropped.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UnlockedKeyInfo {
+    pub address: MgoAddress,
+    pub expires_in_ms: u64,
+}
+
+struct UnlockedKey {
+    keypair: MgoKeyPair,
+    expires_at: Instant,
+}
+
+pub struct KeyAgent {
+    listener: UnixListener,
+    path: PathBuf,
+    keys: BTreeMap<MgoAddress, UnlockedKey>,
+}
+
+impl KeyAgent {
+    /// Binds the agent socket at `path`, accessible to the current user only. A socket left
+    /// behind by an agent that is no longer running is replaced.
+    pub fn bind(path: &Path) -> Result<Self, anyhow::Error> {
+        if path.exists() {
+            if UnixStream::connect(path).is_ok() {
+                bail!("A key agent is already listening on {}", path.display());
+            }
+            fs::remove_file(path)
+                .with_context(|| format!("Cannot remove stale agent socket {}", path.display()))?;
+        }
+        let listener = UnixListener::bind(path)
+            .with_context(|| format!("Cannot bind agent socket {}", path.display()))?;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            path: path.to_path_buf(),
+            keys: BTreeMap::new(),
+        })
+    }
+
+    /// Serves requests until the agent holds no keys anymore, after at least one was unlocked.
+    pub fn run(mut self) -> Result<(), anyhow::Error> {
+        let mut had_keys = false;
+        loop {
+            let now = Instant::now();
+            self.keys.retain(|_, key| key.expires_at > now);
+            if had_keys && self.keys.is_empty() {
+                return Ok(());
+            }
+            match self.listener.accept() {
+                // A misbehaving client only fails its own request.
+                Ok((stream, _)) => {
+                    let _ = self.serve_connection(stream);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => thread::sleep(SWEEP_INTERVAL),
+                Err(e) => return Err(e.into()),
+            }
+            had_keys |= !self.keys.is_empty();
+        }
+    }
+
+    fn serve_connection(&mut self, mut stream: UnixStream) -> Result<(), anyhow::Error> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+        stream.set_write_timeout(Some(IO_TIMEOUT))?;
+        let mut request = Zeroizing::new(Vec::with_capacity(MAX_REQUEST_SIZE));
+        (&mut stream)
+            .take(MAX_REQUEST_SIZE as u64 - 1)
+            .read_to_end(&mut request)?;
+        let response = match serde_json::from_slice::<AgentRequest>(&request) {
+            Ok(request) => self.handle(&request),
+            Err(e) => AgentResponse::Error(format!("Invalid agent request: {e}")),
+        };
+        serde_json::to_writer(&mut stream, &response)?;
+        Ok(())
+    }
+
+    fn handle(&mut self, request: &AgentRequest) -> AgentResponse {
+        match request {
+            AgentRequest::Unlock { key, ttl_ms } => match MgoKeyPair::decode_base64(key) {
+                Ok(keypair) => {
+                    self.keys.insert(
+                        MgoAddress::from(&keypair.public()),
+                        UnlockedKey {
+                            keypair,
+                            expires_at: Instant::now() + Duration::from_millis(*ttl_ms),
+                        },
+                    );
+                    AgentResponse::Unlocked(self.unlocked())
+                }
+                Err(e) => AgentResponse::Error(format!("Invalid key: {e}")),
+            },
+            AgentRequest::SignHashed { address, digest } => match self.keys.get(address) {
+                Some(key) => AgentResponse::Signature(
+                    Signature::new_hashed(digest, &key.keypair).encode_base64(),
+                ),
+                None => AgentResponse::NotUnlocked,
+            },
+            AgentRequest::Lock { address } => {
+                match address {
+                    Some(address) => {
+                        self.keys.remove(address);
+                    }
+                    None => self.keys.clear(),
+                }
+                AgentResponse::Unlocked(self.unlocked())
+            }
+            AgentRequest::List => AgentResponse::Unlocked(self.unlocked()),
+        }
+    }
+
+    fn unlocked(&self) -> Vec<UnlockedKeyInfo> {
+        let now = Instant::now();
+        self.keys
+            .iter()
+            .map(|(address, key)| UnlockedKeyInfo {
+                address: *address,
+                expires_in_ms: key.expires_at.saturating_duration_since(now).as_millis() as u64,
+            })
+            .collect()
+    }
+}
+
+impl Drop for KeyAgent {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Talks to the [`KeyAgent`] listening on a socket.
+#[derive(Clone, Debug)]
+pub struct AgentClient {
+    path: PathBuf,
+}
+
+impl AgentClient {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Client for the agent serving `keystore`, if it is backed by a file.
+    pub fn for_keystore(keystore: &Keystore) -> Option<Self> {
+        match keystore {
+            Keystore::File(keystore) => keystore.path().map(|p| Self::new(agent_socket_path(p))),
+            Keystore::InMem(_) => None,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn is_running(&self) -> bool {
+        UnixStream::connect(&self.path).is_ok()
+    }
+
+    /// Hands `keypair` to the agent for `ttl`, replacing the expiry if it is already unlocked.
+    pub fn unlock(
+        &self,
+        keypair: &MgoKeyPair,
+        ttl: Duration,
+    ) -> Result<Vec<UnlockedKeyInfo>, anyhow::Error> {
+        let request = AgentRequest::Unlock {
+            key: keypair.encode_base64(),
+            ttl_ms: ttl.as_millis() as u64,
+        };
+        self.expect_unlocked(self.request(&request)?)
+    }
+
+    /// Drops the key for `address` from the agent, or every key if `address` is `None`.
+    pub fn lock(&self, address: Option<MgoAddress>) -> Result<Vec<UnlockedKeyInfo>, anyhow::Error> {
+        self.expect_unlocked(self.request(&AgentRequest::Lock { address })?)
+    }
+
+    pub fn list(&self) -> Result<Vec<UnlockedKeyInfo>, anyhow::Error> {
+        self.expect_unlocked(self.request(&AgentRequest::List)?)
+    }
+
+    /// Signs `msg` with the agent's copy of the key for `address`, hashing it the same way as
+    /// [`AccountKeystore::sign_secure`]. Returns `None` if no agent is running or the key is not
+    /// unlocked, so that callers can fall back to the keystore.
+    ///
+    /// [`AccountKeystore::sign_secure`]: crate::keystore::AccountKeystore::sign_secure
+    pub fn sign_secure<T>(
+        &self,
+        address: &MgoAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Option<Signature>, anyhow::Error>
+    where
+        T: Serialize,
+    {
+        let mut hasher = DefaultHash::default();
+        hasher.update(bcs::to_bytes(&IntentMessage::new(intent, msg))?);
+        let request = AgentRequest::SignHashed {
+            address: *address,
+            digest: hasher.finalize().digest.to_vec(),
+        };
+        let response = match self.request(&request) {
+            Ok(response) => response,
+            Err(e) if is_not_running(&e) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        match response {
+            AgentResponse::Signature(signature) => {
+                Ok(Some(Signature::decode_base64(&signature).map_err(|e| {
+                    anyhow!("Invalid signature from key agent: {e}")
+                })?))
+            }
+            AgentResponse::NotUnlocked => Ok(None),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    fn request(&self, request: &AgentRequest) -> Result<AgentResponse, anyhow::Error> {
+        let mut stream = UnixStream::connect(&self.path)?;
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+        stream.set_write_timeout(Some(IO_TIMEOUT))?;
+        let mut buf = Zeroizing::new(Vec::with_capacity(MAX_REQUEST_SIZE));
+        serde_json::to_writer(&mut *buf, request)?;
+        stream.write_all(&buf)?;
+        stream.shutdown(Shutdown::Write)?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+        serde_json::from_str(&response)
+            .with_context(|| format!("Invalid response from key agent at {}", self.path.display()))
+    }
+
+    fn expect_unlocked(
+        &self,
+        response: AgentResponse,
+    ) -> Result<Vec<UnlockedKeyInfo>, anyhow::Error> {
+        match response {
+            AgentResponse::Unlocked(keys) => Ok(keys),
+            response => Err(unexpected(response)),
+        }
+    }
+}
+
+fn is_not_running(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<std::io::Error>()
+        .is_some_and(|e| matches!(e.kind(), ErrorKind::NotFound | ErrorKind::ConnectionRefused))
+}
+
+fn unexpected(response: AgentResponse) -> anyhow::Error {
+    match response {
+        AgentResponse::Error(e) => anyhow!("Key agent error: {e}"),
+        _ => anyhow!("Unexpected response from key agent"),
+    }
+}
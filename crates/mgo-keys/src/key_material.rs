@@ -0,0 +1,54 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Validation of public key material. Keys are compared with
+//! [`mgo_types::key_material::KeyMaterial`], in constant time.
+
+use fastcrypto::bls12381::min_sig::BLS12381PublicKey;
+use fastcrypto::ed25519::Ed25519PublicKey;
+use fastcrypto::secp256k1::Secp256k1PublicKey;
+use fastcrypto::secp256r1::Secp256r1PublicKey;
+use fastcrypto::traits::VerifyingKey;
+use mgo_types::crypto::SignatureScheme;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum PublicKeyError {
+    #[error("{0} public keys cannot be validated on their own")]
+    UnsupportedScheme(String),
+    #[error("{scheme} public keys are {expected} bytes long, not {actual}")]
+    InvalidLength {
+        scheme: String,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("Bytes do not encode a valid {0} public key")]
+    InvalidPoint(String),
+}
+
+/// Checks that `bytes` are a public key of `scheme`: that they have the length of its keys,
+/// and encode a point of its curve, in its subgroup for BLS12381. Multisig and zkLogin public
+/// keys are not points, and are rejected.
+pub fn validate_pubkey_bytes(scheme: SignatureScheme, bytes: &[u8]) -> Result<(), PublicKeyError> {
+    match scheme {
+        SignatureScheme::ED25519 => validate::<Ed25519PublicKey>(scheme, bytes),
+        SignatureScheme::Secp256k1 => validate::<Secp256k1PublicKey>(scheme, bytes),
+        SignatureScheme::Secp256r1 => validate::<Secp256r1PublicKey>(scheme, bytes),
+        SignatureScheme::BLS12381 => validate::<BLS12381PublicKey>(scheme, bytes),
+        SignatureScheme::MultiSig | SignatureScheme::ZkLoginAuthenticator => {
+            Err(PublicKeyError::UnsupportedScheme(scheme.to_string()))
+        }
+    }
+}
+
+fn validate<K: VerifyingKey>(scheme: SignatureScheme, bytes: &[u8]) -> Result<(), PublicKeyError> {
+    if bytes.len() != K::LENGTH {
+        return Err(PublicKeyError::InvalidLength {
+            scheme: scheme.to_string(),
+            expected: K::LENGTH,
+            actual: bytes.len(),
+        });
+    }
+    K::from_bytes(bytes)
+        .map(|_| ())
+        .map_err(|_| PublicKeyError::InvalidPoint(scheme.to_string()))
+}
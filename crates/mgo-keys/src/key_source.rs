@@ -0,0 +1,172 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Where a keypair is read from, for deployments that inject keys through environment variables
+//! or mounted secrets rather than long-lived key files.
+
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context};
+use fastcrypto::traits::EncodeDecodeBase64;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use zeroize::Zeroizing;
+
+use mgo_types::crypto::{AuthorityKeyPair, MgoKeyPair, NetworkKeyPair};
+
+use crate::keypair_file::{
+    decode_keypair, network_keypair, read_authority_keypair_from_file, read_keypair_from_file,
+};
+
+const FILE_PREFIX: &str = "file:";
+const ENV_PREFIX: &str = "env:";
+const INLINE_PREFIX: &str = "base64:";
+
+/// Where a keypair is read from, parsed from `file:<path>`, `env:<variable>` or
+/// `base64:<keypair>`. Strings without any of these prefixes are paths, as in configs written
+/// before sources were introduced.
+///
+/// Keys held by a source are read as a PKCS#8 PEM or as Base64 encoded `flag || privkey`, like
+/// key files, and never appear in its `Display` or `Debug` output nor in the errors of reading
+/// them.
+#[derive(Clone, PartialEq, Eq)]
+pub enum KeySource {
+    File(PathBuf),
+    /// Name of the environment variable holding the key.
+    Env(String),
+    /// The key itself.
+    Inline(String),
+}
+
+impl KeySource {
+    fn kind(&self) -> &'static str {
+        match self {
+            KeySource::File(_) => "file",
+            KeySource::Env(_) => "environment variable",
+            KeySource::Inline(_) => "inline value",
+        }
+    }
+
+    /// The string this source is parsed from, with paths without a prefix unless they would be
+    /// parsed as another source.
+    fn to_source_string(&self) -> Zeroizing<String> {
+        Zeroizing::new(match self {
+            KeySource::File(path) => {
+                let path = path.display().to_string();
+                if has_source_prefix(&path) {
+                    format!("{FILE_PREFIX}{path}")
+                } else {
+                    path
+                }
+            }
+            KeySource::Env(name) => format!("{ENV_PREFIX}{name}"),
+            KeySource::Inline(value) => format!("{INLINE_PREFIX}{value}"),
+        })
+    }
+}
+
+fn read_env(name: &str) -> anyhow::Result<Zeroizing<String>> {
+    std::env::var(name)
+        .map(Zeroizing::new)
+        .map_err(|_| anyhow!("Environment variable `{name}` is not set or not unicode"))
+}
+
+fn has_source_prefix(s: &str) -> bool {
+    [FILE_PREFIX, ENV_PREFIX, INLINE_PREFIX]
+        .iter()
+        .any(|prefix| s.starts_with(prefix))
+}
+
+impl FromStr for KeySource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let source = if let Some(path) = s.strip_prefix(FILE_PREFIX) {
+            KeySource::File(PathBuf::from(path))
+        } else if let Some(name) = s.strip_prefix(ENV_PREFIX) {
+            KeySource::Env(name.to_string())
+        } else if let Some(value) = s.strip_prefix(INLINE_PREFIX) {
+            KeySource::Inline(value.to_string())
+        } else {
+            KeySource::File(PathBuf::from(s))
+        };
+        match &source {
+            KeySource::File(path) if path.as_os_str().is_empty() => {
+                Err(anyhow!("Key source has an empty path"))
+            }
+            KeySource::Env(name) if name.is_empty() => {
+                Err(anyhow!("Key source has an empty environment variable name"))
+            }
+            KeySource::Inline(value) if value.trim().is_empty() => {
+                Err(anyhow!("Key source has an empty inline value"))
+            }
+            _ => Ok(source),
+        }
+    }
+}
+
+impl From<PathBuf> for KeySource {
+    fn from(path: PathBuf) -> Self {
+        KeySource::File(path)
+    }
+}
+
+impl fmt::Display for KeySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeySource::File(path) => write!(f, "{} {}", self.kind(), path.display()),
+            KeySource::Env(name) => write!(f, "{} `{name}`", self.kind()),
+            KeySource::Inline(_) => f.write_str(self.kind()),
+        }
+    }
+}
+
+impl fmt::Debug for KeySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "KeySource({self})")
+    }
+}
+
+impl Serialize for KeySource {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_source_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for KeySource {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = Zeroizing::new(String::deserialize(deserializer)?);
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Read the keypair held by `source`, as a PKCS#8 PEM or as Base64 encoded `flag || privkey`.
+pub fn resolve_keypair(source: &KeySource) -> anyhow::Result<MgoKeyPair> {
+    match source {
+        KeySource::File(path) => read_keypair_from_file(path),
+        KeySource::Env(name) => decode_keypair(&read_env(name)?),
+        KeySource::Inline(value) => decode_keypair(value),
+    }
+    .with_context(|| format!("Failed to read keypair from {source}"))
+}
+
+/// Read the authority keypair held by `source`, as Base64 encoded `privkey`.
+pub fn resolve_authority_keypair(source: &KeySource) -> anyhow::Result<AuthorityKeyPair> {
+    let decode = |value: &str| {
+        AuthorityKeyPair::decode_base64(value.trim())
+            .map_err(|_| anyhow!("Invalid Base64 encoded authority keypair"))
+    };
+    match source {
+        KeySource::File(path) => read_authority_keypair_from_file(path),
+        KeySource::Env(name) => decode(&read_env(name)?),
+        KeySource::Inline(value) => decode(value),
+    }
+    .with_context(|| format!("Failed to read authority keypair from {source}"))
+}
+
+/// Read the network keypair held by `source`, as Base64 encoded `flag || privkey`.
+pub fn resolve_network_keypair(source: &KeySource) -> anyhow::Result<NetworkKeyPair> {
+    network_keypair(resolve_keypair(source)?)
+        .with_context(|| format!("Failed to read network keypair from {source}"))
+}
@@ -1,9 +1,231 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::anyhow;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context};
+use argon2::{Algorithm, Argon2, Params, Version};
+use bip32::DerivationPath;
+use bip39::{ErrorKind, Language, Mnemonic, MnemonicType, Seed};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
 use fastcrypto::traits::EncodeDecodeBase64;
-use mgo_types::crypto::{AuthorityKeyPair, NetworkKeyPair, MgoKeyPair};
+use mgo_types::base_types::MgoAddress;
+use mgo_types::crypto::{AuthorityKeyPair, MgoKeyPair, NetworkKeyPair, Signature, SignatureScheme};
+use mgo_types::key_material::ct_eq;
+use mgo_types::signature::GenericSignature;
+use mgo_types::transaction::{SenderSignedData, Transaction, TransactionData};
+use pkcs8::der::asn1::AnyRef;
+use pkcs8::der::pem::LineEnding;
+use pkcs8::der::SecretDocument;
+use pkcs8::{AlgorithmIdentifier, ObjectIdentifier, PrivateKeyInfo};
+use rand::RngCore;
+use shared_crypto::intent::{Intent, IntentMessage};
+use zeroize::Zeroizing;
+
+use crate::key_derive::derive_key_pair_from_path;
+
+/// Start of every encrypted keypair file. It contains characters outside of the Base64
+/// alphabet, so it never starts a plaintext keypair file.
+const ENCRYPTED_KEYPAIR_MAGIC: &[u8; 8] = b"MGO-KEY\0";
+const ENCRYPTED_KEYPAIR_VERSION: u8 = 1;
+const KDF_ARGON2ID: u8 = 1;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const KEY_LENGTH: usize = 32;
+const TAG_LENGTH: usize = 16;
+/// magic || version || kdf || m_cost || t_cost || p_cost || salt || nonce
+const HEADER_LENGTH: usize = 8 + 1 + 1 + 4 + 4 + 4 + SALT_LENGTH + NONCE_LENGTH;
+
+/// Argon2id parameters for new files: 64 MiB of memory, 3 passes, 1 lane.
+const DEFAULT_ARGON2_M_COST: u32 = 64 * 1024;
+const DEFAULT_ARGON2_T_COST: u32 = 3;
+const DEFAULT_ARGON2_P_COST: u32 = 1;
+
+/// Length of the mnemonics written by [`write_keypair_as_mnemonic_to_file`].
+const MNEMONIC_TYPE: MnemonicType = MnemonicType::Words24;
+
+const PEM_PRIVATE_KEY_LABEL: &str = "PRIVATE KEY";
+const PEM_PUBLIC_KEY_LABEL: &str = "PUBLIC KEY";
+/// id-Ed25519, RFC 8410.
+const ED25519_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.101.112");
+/// id-ecPublicKey, RFC 5480, with the curve as parameters.
+const EC_PUBLIC_KEY_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.2.1");
+const SECP256K1_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.132.0.10");
+const SECP256R1_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.3.1.7");
+const PRIVATE_KEY_LENGTH: usize = 32;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum KeypairFileError {
+    #[error("Keypair file is encrypted, a passphrase is required to read it")]
+    PassphraseRequired,
+    #[error("Keypair file is not encrypted")]
+    NotEncrypted,
+    #[error("Encrypted keypair file is truncated")]
+    Truncated,
+    #[error("Unsupported encrypted keypair file version {0}")]
+    UnsupportedVersion(u8),
+    #[error("Unsupported key derivation function {0} in encrypted keypair file")]
+    UnsupportedKdf(u8),
+    #[error("Failed to decrypt keypair file, the passphrase is wrong or the file is corrupted")]
+    DecryptionFailed,
+    #[error("Mnemonic has {0} words, expected 12, 15, 18, 21 or 24")]
+    InvalidMnemonicLength(usize),
+    #[error("Mnemonic contains a word that is not in the BIP39 English word list")]
+    InvalidMnemonicWord,
+    #[error("Mnemonic checksum does not match its last word, a word may be mistyped or misplaced")]
+    InvalidMnemonicChecksum,
+    #[error("PEM holds a public key only, a private key is required")]
+    PemPublicKeyOnly,
+    #[error("Unsupported PEM label `{0}`, expected `PRIVATE KEY`")]
+    UnsupportedPemLabel(String),
+    #[error("Unsupported private key algorithm {0}")]
+    UnsupportedKeyAlgorithm(String),
+    #[error("Malformed {0} private key in PEM")]
+    MalformedPemKey(String),
+    #[error("Public key in PEM does not match the {0} private key, the curve may be wrong")]
+    PemPublicKeyMismatch(String),
+}
+
+/// Header of an encrypted keypair file, authenticated together with the encrypted key.
+struct EncryptedKeypairHeader {
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+    salt: [u8; SALT_LENGTH],
+    nonce: [u8; NONCE_LENGTH],
+}
+
+impl EncryptedKeypairHeader {
+    fn new() -> Self {
+        let mut salt = [0; SALT_LENGTH];
+        let mut nonce = [0; NONCE_LENGTH];
+        rand::thread_rng().fill_bytes(&mut salt);
+        rand::thread_rng().fill_bytes(&mut nonce);
+        Self {
+            m_cost: DEFAULT_ARGON2_M_COST,
+            t_cost: DEFAULT_ARGON2_T_COST,
+            p_cost: DEFAULT_ARGON2_P_COST,
+            salt,
+            nonce,
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LENGTH);
+        bytes.extend_from_slice(ENCRYPTED_KEYPAIR_MAGIC);
+        bytes.push(ENCRYPTED_KEYPAIR_VERSION);
+        bytes.push(KDF_ARGON2ID);
+        bytes.extend_from_slice(&self.m_cost.to_le_bytes());
+        bytes.extend_from_slice(&self.t_cost.to_le_bytes());
+        bytes.extend_from_slice(&self.p_cost.to_le_bytes());
+        bytes.extend_from_slice(&self.salt);
+        bytes.extend_from_slice(&self.nonce);
+        bytes
+    }
+
+    /// Parses the header at the start of `bytes`, which must already be known to start with
+    /// [`ENCRYPTED_KEYPAIR_MAGIC`].
+    fn from_bytes(bytes: &[u8]) -> Result<Self, KeypairFileError> {
+        if bytes.len() < HEADER_LENGTH + TAG_LENGTH {
+            return Err(KeypairFileError::Truncated);
+        }
+        let version = bytes[8];
+        if version != ENCRYPTED_KEYPAIR_VERSION {
+            return Err(KeypairFileError::UnsupportedVersion(version));
+        }
+        let kdf = bytes[9];
+        if kdf != KDF_ARGON2ID {
+            return Err(KeypairFileError::UnsupportedKdf(kdf));
+        }
+        let u32_at =
+            |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        let salt_start = 22;
+        let nonce_start = salt_start + SALT_LENGTH;
+        Ok(Self {
+            m_cost: u32_at(10),
+            t_cost: u32_at(14),
+            p_cost: u32_at(18),
+            salt: bytes[salt_start..nonce_start].try_into().unwrap(),
+            nonce: bytes[nonce_start..HEADER_LENGTH].try_into().unwrap(),
+        })
+    }
+
+    fn cipher(&self, passphrase: &str) -> anyhow::Result<ChaCha20Poly1305> {
+        let params = Params::new(self.m_cost, self.t_cost, self.p_cost, Some(KEY_LENGTH))
+            .map_err(|e| anyhow!("Invalid key derivation parameters: {e}"))?;
+        let mut key = Zeroizing::new([0; KEY_LENGTH]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &self.salt, key.as_mut())
+            .map_err(|e| anyhow!("Failed to derive key from passphrase: {e}"))?;
+        Ok(ChaCha20Poly1305::new(Key::from_slice(key.as_ref())))
+    }
+}
+
+fn is_encrypted(contents: &[u8]) -> bool {
+    contents.starts_with(ENCRYPTED_KEYPAIR_MAGIC)
+}
+
+/// Encrypts `contents` with a key derived from `passphrase` and writes it to file.
+fn write_encrypted_file<P: AsRef<std::path::Path>>(
+    contents: &str,
+    path: P,
+    passphrase: &str,
+) -> anyhow::Result<()> {
+    let header = EncryptedKeypairHeader::new();
+    let mut bytes = header.to_bytes();
+    let ciphertext = header
+        .cipher(passphrase)?
+        .encrypt(
+            Nonce::from_slice(&header.nonce),
+            Payload {
+                msg: contents.as_bytes(),
+                aad: &bytes,
+            },
+        )
+        .map_err(|_| anyhow!("Failed to encrypt keypair"))?;
+    bytes.extend_from_slice(&ciphertext);
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Reads an encrypted file and returns its decrypted contents.
+fn read_encrypted_file<P: AsRef<std::path::Path>>(
+    path: P,
+    passphrase: &str,
+) -> anyhow::Result<Zeroizing<String>> {
+    let bytes = std::fs::read(path)?;
+    if !is_encrypted(&bytes) {
+        return Err(KeypairFileError::NotEncrypted.into());
+    }
+    let header = EncryptedKeypairHeader::from_bytes(&bytes)?;
+    let (header_bytes, ciphertext) = bytes.split_at(HEADER_LENGTH);
+    let plaintext = Zeroizing::new(
+        header
+            .cipher(passphrase)?
+            .decrypt(
+                Nonce::from_slice(&header.nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: header_bytes,
+                },
+            )
+            .map_err(|_| KeypairFileError::DecryptionFailed)?,
+    );
+    let contents =
+        std::str::from_utf8(&plaintext).map_err(|_| KeypairFileError::DecryptionFailed)?;
+    Ok(Zeroizing::new(contents.to_string()))
+}
+
+/// Reads a plaintext file, failing with [`KeypairFileError::PassphraseRequired`] if it is
+/// encrypted.
+fn read_plaintext_file<P: AsRef<std::path::Path>>(path: P) -> anyhow::Result<String> {
+    let bytes = std::fs::read(path)?;
+    if is_encrypted(&bytes) {
+        return Err(KeypairFileError::PassphraseRequired.into());
+    }
+    Ok(String::from_utf8(bytes)?)
+}
 
 /// Write Base64 encoded `flag || privkey` to file.
 pub fn write_keypair_to_file<P: AsRef<std::path::Path>>(
@@ -15,6 +237,120 @@ pub fn write_keypair_to_file<P: AsRef<std::path::Path>>(
     Ok(())
 }
 
+/// Write Base64 encoded `flag || privkey`, encrypted with `passphrase`, to file.
+pub fn write_keypair_to_encrypted_file<P: AsRef<std::path::Path>>(
+    keypair: &MgoKeyPair,
+    path: P,
+    passphrase: &str,
+) -> anyhow::Result<()> {
+    let contents = Zeroizing::new(keypair.encode_base64());
+    write_encrypted_file(&contents, path, passphrase)
+}
+
+/// Encodes `keypair` as a PKCS#8 PEM `PRIVATE KEY`, with ed25519 keys as in RFC 8410 and
+/// secp256k1 and secp256r1 keys as SEC1 `ECPrivateKey`s of the named curve, as expected by
+/// OpenSSL and cloud KMS imports.
+pub fn export_keypair_as_pem(keypair: &MgoKeyPair) -> String {
+    let bytes = Zeroizing::new(keypair.to_bytes());
+    let secret = &bytes[1..];
+    let (curve, private_key) = match keypair {
+        MgoKeyPair::Ed25519(_) => (None, Zeroizing::new(der_octet_string(secret))),
+        MgoKeyPair::Secp256k1(_) => (Some(SECP256K1_OID), sec1_private_key(secret)),
+        MgoKeyPair::Secp256r1(_) => (Some(SECP256R1_OID), sec1_private_key(secret)),
+    };
+    let algorithm = match &curve {
+        None => AlgorithmIdentifier {
+            oid: ED25519_OID,
+            parameters: None,
+        },
+        Some(curve) => AlgorithmIdentifier {
+            oid: EC_PUBLIC_KEY_OID,
+            parameters: Some(AnyRef::from(curve)),
+        },
+    };
+    let document = SecretDocument::try_from(PrivateKeyInfo::new(algorithm, &private_key))
+        .expect("Encoding a PKCS#8 private key cannot fail");
+    document
+        .to_pem(PEM_PRIVATE_KEY_LABEL, LineEnding::LF)
+        .expect("Encoding a PKCS#8 private key cannot fail")
+        .to_string()
+}
+
+/// Decodes a PKCS#8 PEM `PRIVATE KEY` written by [`export_keypair_as_pem`] or by other tools,
+/// such as OpenSSL. A public key included next to the private key must match it, which catches
+/// keys declared for another curve than the one they were generated for.
+pub fn import_keypair_from_pem(pem: &str) -> anyhow::Result<MgoKeyPair> {
+    let (label, document) =
+        SecretDocument::from_pem(pem.trim()).map_err(|e| anyhow!("Failed to decode PEM: {e}"))?;
+    match label {
+        PEM_PRIVATE_KEY_LABEL => {}
+        PEM_PUBLIC_KEY_LABEL => return Err(KeypairFileError::PemPublicKeyOnly.into()),
+        label => return Err(KeypairFileError::UnsupportedPemLabel(label.to_string()).into()),
+    }
+    let info = PrivateKeyInfo::try_from(document.as_bytes())
+        .map_err(|e| anyhow!("Failed to decode PKCS#8 private key: {e}"))?;
+
+    let oid = info.algorithm.oid;
+    let (scheme, secret, public_key) = if oid == ED25519_OID {
+        if info.algorithm.parameters.is_some() {
+            return Err(KeypairFileError::MalformedPemKey("ed25519".to_string()).into());
+        }
+        let secret = parse_der_octet_string(info.private_key)
+            .ok_or_else(|| KeypairFileError::MalformedPemKey("ed25519".to_string()))?;
+        (SignatureScheme::ED25519, secret, info.public_key)
+    } else if oid == EC_PUBLIC_KEY_OID {
+        let curve = info
+            .algorithm
+            .parameters_oid()
+            .map_err(|_| KeypairFileError::MalformedPemKey("EC".to_string()))?;
+        let scheme = if curve == SECP256K1_OID {
+            SignatureScheme::Secp256k1
+        } else if curve == SECP256R1_OID {
+            SignatureScheme::Secp256r1
+        } else {
+            return Err(KeypairFileError::UnsupportedKeyAlgorithm(format!(
+                "EC key on curve {curve}"
+            ))
+            .into());
+        };
+        let (secret, public_key) = parse_sec1_private_key(info.private_key, curve)
+            .ok_or_else(|| KeypairFileError::MalformedPemKey(scheme.to_string()))?;
+        (scheme, secret, public_key.or(info.public_key))
+    } else {
+        return Err(KeypairFileError::UnsupportedKeyAlgorithm(oid.to_string()).into());
+    };
+    if secret.len() != PRIVATE_KEY_LENGTH {
+        return Err(KeypairFileError::MalformedPemKey(scheme.to_string()).into());
+    }
+
+    let mut bytes = Zeroizing::new(vec![scheme.flag()]);
+    bytes.extend_from_slice(secret);
+    let keypair = MgoKeyPair::from_bytes(&bytes)
+        .map_err(|_| KeypairFileError::MalformedPemKey(scheme.to_string()))?;
+    if let Some(public_key) = public_key {
+        let expected = keypair.public();
+        let matches = match scheme {
+            SignatureScheme::ED25519 => ct_eq(public_key, expected.as_ref()),
+            _ => compress_ec_point(public_key)
+                .is_some_and(|point| ct_eq(&point, expected.as_ref())),
+        };
+        if !matches {
+            return Err(KeypairFileError::PemPublicKeyMismatch(scheme.to_string()).into());
+        }
+    }
+    Ok(keypair)
+}
+
+/// Write `keypair` to file as a PKCS#8 PEM, see [`export_keypair_as_pem`].
+pub fn write_keypair_to_pem_file<P: AsRef<std::path::Path>>(
+    keypair: &MgoKeyPair,
+    path: P,
+) -> anyhow::Result<()> {
+    let contents = Zeroizing::new(export_keypair_as_pem(keypair));
+    std::fs::write(path, contents.as_bytes())?;
+    Ok(())
+}
+
 /// Write Base64 encoded `privkey` to file.
 pub fn write_authority_keypair_to_file<P: AsRef<std::path::Path>>(
     keypair: &AuthorityKeyPair,
@@ -25,28 +361,266 @@ pub fn write_authority_keypair_to_file<P: AsRef<std::path::Path>>(
     Ok(())
 }
 
+/// Write Base64 encoded `privkey`, encrypted with `passphrase`, to file.
+pub fn write_authority_keypair_to_encrypted_file<P: AsRef<std::path::Path>>(
+    keypair: &AuthorityKeyPair,
+    path: P,
+    passphrase: &str,
+) -> anyhow::Result<()> {
+    let contents = Zeroizing::new(keypair.encode_base64());
+    write_encrypted_file(&contents, path, passphrase)
+}
+
 /// Read from file as Base64 encoded `privkey` and return a AuthorityKeyPair.
 pub fn read_authority_keypair_from_file<P: AsRef<std::path::Path>>(
     path: P,
 ) -> anyhow::Result<AuthorityKeyPair> {
-    let contents = std::fs::read_to_string(path)?;
+    let contents = read_plaintext_file(path)?;
+    AuthorityKeyPair::decode_base64(contents.as_str().trim()).map_err(|e| anyhow!(e))
+}
+
+/// Read from file as Base64 encoded `privkey` encrypted with `passphrase` and return a
+/// AuthorityKeyPair.
+pub fn read_authority_keypair_from_encrypted_file<P: AsRef<std::path::Path>>(
+    path: P,
+    passphrase: &str,
+) -> anyhow::Result<AuthorityKeyPair> {
+    let contents = read_encrypted_file(path, passphrase)?;
     AuthorityKeyPair::decode_base64(contents.as_str().trim()).map_err(|e| anyhow!(e))
 }
 
-/// Read from file as Base64 encoded `flag || privkey` and return a MgoKeypair.
+/// Read from file as a PKCS#8 PEM or as Base64 encoded `flag || privkey` and return a
+/// MgoKeypair.
 pub fn read_keypair_from_file<P: AsRef<std::path::Path>>(path: P) -> anyhow::Result<MgoKeyPair> {
-    let contents = std::fs::read_to_string(path)?;
-    MgoKeyPair::decode_base64(contents.as_str().trim()).map_err(|e| anyhow!(e))
+    let contents = Zeroizing::new(read_plaintext_file(path)?);
+    decode_keypair(&contents)
+}
+
+/// Read from file as a PKCS#8 PEM or as Base64 encoded `flag || privkey`, encrypted with
+/// `passphrase`, and return a MgoKeypair.
+pub fn read_keypair_from_encrypted_file<P: AsRef<std::path::Path>>(
+    path: P,
+    passphrase: &str,
+) -> anyhow::Result<MgoKeyPair> {
+    let contents = read_encrypted_file(path, passphrase)?;
+    decode_keypair(&contents)
 }
 
 /// Read from file as Base64 encoded `flag || privkey` and return a NetworkKeyPair.
 pub fn read_network_keypair_from_file<P: AsRef<std::path::Path>>(
     path: P,
 ) -> anyhow::Result<NetworkKeyPair> {
-    let kp = read_keypair_from_file(path)?;
+    network_keypair(read_keypair_from_file(path)?)
+}
+
+/// Read from file as Base64 encoded `flag || privkey` encrypted with `passphrase` and return a
+/// NetworkKeyPair.
+pub fn read_network_keypair_from_encrypted_file<P: AsRef<std::path::Path>>(
+    path: P,
+    passphrase: &str,
+) -> anyhow::Result<NetworkKeyPair> {
+    network_keypair(read_keypair_from_encrypted_file(path, passphrase)?)
+}
+
+/// Generate a keypair from a new 24-word BIP39 mnemonic and write the mnemonic to file. Keys
+/// are derived from mnemonics and not the other way round, so a keypair backed up as a
+/// mnemonic has to be created this way. `derivation_path` defaults to the standard path of
+/// `key_scheme`.
+pub fn write_keypair_as_mnemonic_to_file<P: AsRef<std::path::Path>>(
+    path: P,
+    key_scheme: SignatureScheme,
+    derivation_path: Option<DerivationPath>,
+) -> anyhow::Result<(MgoAddress, MgoKeyPair)> {
+    let mnemonic = Mnemonic::new(MNEMONIC_TYPE, Language::English);
+    let keypair = keypair_from_mnemonic(&mnemonic, &key_scheme, derivation_path)?;
+    std::fs::write(path, mnemonic.phrase())?;
+    Ok(keypair)
+}
+
+/// Read a BIP39 mnemonic from file and derive the `key_scheme` keypair at `derivation_path`,
+/// or at the standard path of the scheme. Words may be separated by any whitespace, including
+/// line breaks.
+pub fn read_keypair_from_mnemonic_file<P: AsRef<std::path::Path>>(
+    path: P,
+    key_scheme: SignatureScheme,
+    derivation_path: Option<DerivationPath>,
+) -> anyhow::Result<MgoKeyPair> {
+    let contents = Zeroizing::new(read_plaintext_file(path)?);
+    let phrase = Zeroizing::new(
+        contents
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+    let mnemonic =
+        Mnemonic::from_phrase(&phrase, Language::English).map_err(invalid_mnemonic_error)?;
+    let (_, keypair) = keypair_from_mnemonic(&mnemonic, &key_scheme, derivation_path)?;
+    Ok(keypair)
+}
+
+/// Sign `tx_data` under `intent` with the keypair of each of `keyfile_paths`, which may be of
+/// different schemes, returning the signatures in the order of the paths. All files are read
+/// before anything is signed, and the first one that cannot be read as a keypair fails the
+/// whole batch with its index and path.
+pub fn sign_transaction_with_keyfiles(
+    tx_data: &TransactionData,
+    keyfile_paths: &[PathBuf],
+    intent: Intent,
+) -> anyhow::Result<Vec<Signature>> {
+    let keypairs = keyfile_paths
+        .iter()
+        .enumerate()
+        .map(|(index, path)| {
+            read_keypair_from_file(path)
+                .with_context(|| format!("Failed to read key file {index} at {}", path.display()))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let intent_msg = IntentMessage::new(intent, tx_data);
+    Ok(keypairs
+        .iter()
+        .map(|keypair| Signature::new_secure(&intent_msg, keypair))
+        .collect())
+}
+
+/// Like [`sign_transaction_with_keyfiles`], with the signatures assembled into a transaction,
+/// one per signer, e.g. for a sender and a gas sponsor.
+pub fn sign_transaction_with_keyfiles_into_transaction(
+    tx_data: TransactionData,
+    keyfile_paths: &[PathBuf],
+    intent: Intent,
+) -> anyhow::Result<Transaction> {
+    let signatures = sign_transaction_with_keyfiles(&tx_data, keyfile_paths, intent.clone())?;
+    Ok(Transaction::new(SenderSignedData::new(
+        tx_data,
+        intent,
+        signatures.into_iter().map(GenericSignature::from).collect(),
+    )))
+}
+
+fn invalid_mnemonic_error(e: anyhow::Error) -> anyhow::Error {
+    match e.downcast_ref::<ErrorKind>() {
+        Some(ErrorKind::InvalidWordLength(words)) => {
+            KeypairFileError::InvalidMnemonicLength(*words).into()
+        }
+        Some(ErrorKind::InvalidWord) => KeypairFileError::InvalidMnemonicWord.into(),
+        Some(ErrorKind::InvalidChecksum) => KeypairFileError::InvalidMnemonicChecksum.into(),
+        _ => e,
+    }
+}
+
+fn keypair_from_mnemonic(
+    mnemonic: &Mnemonic,
+    key_scheme: &SignatureScheme,
+    derivation_path: Option<DerivationPath>,
+) -> anyhow::Result<(MgoAddress, MgoKeyPair)> {
+    let seed = Seed::new(mnemonic, "");
+    derive_key_pair_from_path(seed.as_bytes(), derivation_path, key_scheme)
+        .map_err(|e| anyhow!("Failed to derive keypair from mnemonic: {:?}", e))
+}
+
+pub(crate) fn network_keypair(kp: MgoKeyPair) -> anyhow::Result<NetworkKeyPair> {
     if let MgoKeyPair::Ed25519(kp) = kp {
         Ok(kp)
     } else {
         Err(anyhow!("Invalid scheme for network keypair"))
     }
 }
+
+/// Decodes a keypair file, as a PEM if it has a PEM header and as Base64 `flag || privkey`
+/// otherwise.
+pub(crate) fn decode_keypair(contents: &str) -> anyhow::Result<MgoKeyPair> {
+    let contents = contents.trim();
+    if contents.starts_with("-----BEGIN ") {
+        import_keypair_from_pem(contents)
+    } else {
+        MgoKeyPair::decode_base64(contents).map_err(|e| anyhow!(e))
+    }
+}
+
+fn der_octet_string(bytes: &[u8]) -> Vec<u8> {
+    let mut der = vec![0x04, bytes.len() as u8];
+    der.extend_from_slice(bytes);
+    der
+}
+
+/// SEC1 `ECPrivateKey` holding only the version and the private key, the curve being given by
+/// the PKCS#8 algorithm parameters.
+fn sec1_private_key(secret: &[u8]) -> Zeroizing<Vec<u8>> {
+    let mut contents = vec![0x02, 0x01, 0x01];
+    contents.extend(der_octet_string(secret));
+    let mut der = vec![0x30, contents.len() as u8];
+    der.extend(contents);
+    Zeroizing::new(der)
+}
+
+/// Splits the DER TLV at the start of `bytes` into its tag, its contents and the bytes after it.
+fn parse_der_tlv(bytes: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, bytes) = bytes.split_first()?;
+    let (&length, mut bytes) = bytes.split_first()?;
+    let length = if length < 0x80 {
+        length as usize
+    } else {
+        let length_bytes = (length & 0x7f) as usize;
+        if length_bytes == 0 || length_bytes > 2 || bytes.len() < length_bytes {
+            return None;
+        }
+        let (length, rest) = bytes.split_at(length_bytes);
+        bytes = rest;
+        length
+            .iter()
+            .fold(0usize, |length, byte| (length << 8) | *byte as usize)
+    };
+    (bytes.len() >= length).then(|| (tag, &bytes[..length], &bytes[length..]))
+}
+
+fn parse_der_octet_string(bytes: &[u8]) -> Option<&[u8]> {
+    match parse_der_tlv(bytes)? {
+        (0x04, contents, []) => Some(contents),
+        _ => None,
+    }
+}
+
+/// Private key and public key, if any, of a SEC1 `ECPrivateKey`, whose curve parameters, if
+/// any, must be `curve`.
+fn parse_sec1_private_key(bytes: &[u8], curve: ObjectIdentifier) -> Option<(&[u8], Option<&[u8]>)> {
+    let (0x30, contents, []) = parse_der_tlv(bytes)? else {
+        return None;
+    };
+    let (0x02, [0x01], contents) = parse_der_tlv(contents)? else {
+        return None;
+    };
+    let (0x04, secret, mut contents) = parse_der_tlv(contents)? else {
+        return None;
+    };
+    let mut public_key = None;
+    while !contents.is_empty() {
+        let (tag, field, rest) = parse_der_tlv(contents)?;
+        match (tag, parse_der_tlv(field)?) {
+            (0xa0, (0x06, oid, [])) => {
+                if ObjectIdentifier::from_bytes(oid).ok()? != curve {
+                    return None;
+                }
+            }
+            // A BIT STRING without unused bits.
+            (0xa1, (0x03, [0x00, point @ ..], [])) => public_key = Some(point),
+            _ => return None,
+        }
+        contents = rest;
+    }
+    Some((secret, public_key))
+}
+
+/// Compressed form of an uncompressed or compressed SEC1 curve point.
+fn compress_ec_point(point: &[u8]) -> Option<Vec<u8>> {
+    match point {
+        [0x04, coordinates @ ..] if coordinates.len() == 2 * PRIVATE_KEY_LENGTH => {
+            let (x, y) = coordinates.split_at(PRIVATE_KEY_LENGTH);
+            let mut compressed = vec![0x02 | (y[PRIVATE_KEY_LENGTH - 1] & 1)];
+            compressed.extend_from_slice(x);
+            Some(compressed)
+        }
+        [0x02 | 0x03, x @ ..] if x.len() == PRIVATE_KEY_LENGTH => Some(point.to_vec()),
+        _ => None,
+    }
+}
@@ -1,7 +1,10 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod agent;
 pub mod key_derive;
+pub mod key_material;
+pub mod key_source;
 pub mod keypair_file;
 pub mod keystore;
 pub mod random_names;
@@ -47,6 +47,18 @@ use tracing::info;
 // Reset tracing to the TRACE_FILTER env var.
 //
 //   $ curl -X POST 'http://127.0.0.1:1337/reset-tracing'
+//
+// Temporarily disable a JSON-RPC method, answering its callers with the given hint:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/rpc-kill-switch/disable?method=mgox_queryEvents&hint=Retry%20in%20an%20hour'
+//
+// Enable it again:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/rpc-kill-switch/enable?method=mgox_queryEvents'
+//
+// List the disabled JSON-RPC methods:
+//
+//   $ curl 'http://127.0.0.1:1337/rpc-kill-switch'
 
 const LOGGING_ROUTE: &str = "/logging";
 const TRACING_ROUTE: &str = "/enable-tracing";
@@ -65,6 +77,7 @@ struct AppState {
 pub async fn run_admin_server(node: Arc<MgoNode>, port: u16, tracing_handle: TracingHandle) {
     let filter = tracing_handle.get_log().unwrap();
 
+    let kill_switch_router = node.rpc_kill_switch.admin_router();
     let app_state = AppState {
         node,
         tracing_handle,
@@ -86,7 +99,8 @@ pub async fn run_admin_server(node: Arc<MgoNode>, port: u16, tracing_handle: Tra
         .route(FORCE_CLOSE_EPOCH, post(force_close_epoch))
         .route(TRACING_ROUTE, post(enable_tracing))
         .route(TRACING_RESET_ROUTE, post(reset_tracing))
-        .with_state(Arc::new(app_state));
+        .with_state(Arc::new(app_state))
+        .merge(kill_switch_router);
 
     let socket_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
     info!(
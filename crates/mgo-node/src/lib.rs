@@ -26,6 +26,7 @@ use mgo_core::authority::CHAIN_IDENTIFIER;
 use mgo_core::consensus_adapter::SubmitToConsensus;
 use mgo_core::epoch::randomness::RandomnessManager;
 use mgo_json_rpc_api::JsonRpcMetrics;
+use mgo_json_rpc_types::EventFilterLimits;
 use mgo_types::base_types::ConciseableName;
 use mgo_types::digests::ChainIdentifier;
 use mgo_types::message_envelope::get_google_jwk_bytes;
@@ -86,12 +87,19 @@ use mgo_core::{
     authority_client::NetworkAuthorityClient,
 };
 use mgo_json_rpc::coin_api::CoinReadApi;
+use mgo_json_rpc::coin_registry::CoinRegistry;
+use mgo_json_rpc::cors::CorsConfig;
 use mgo_json_rpc::governance_api::GovernanceReadApi;
 use mgo_json_rpc::indexer_api::IndexerApi;
+use mgo_json_rpc::debug_api::{DebugApi, GasComparator};
+use mgo_json_rpc::kill_switch::MethodKillSwitch;
 use mgo_json_rpc::move_utils::MoveUtils;
-use mgo_json_rpc::read_api::ReadApi;
+use mgo_json_rpc::read_api::{DisplayRenderLimits, ReadApi};
+use mgo_json_rpc::response_signing::ResponseSigningConfig;
+use mgo_json_rpc::support_api::SupportApi;
 use mgo_json_rpc::transaction_builder_api::TransactionBuilderApi;
 use mgo_json_rpc::transaction_execution_api::TransactionExecutionApi;
+use mgo_json_rpc::verification_api::VerificationApi;
 use mgo_json_rpc::JsonRpcServerBuilder;
 use mgo_macros::{fail_point_async, replay_log};
 use mgo_network::api::ValidatorServer;
@@ -203,6 +211,8 @@ pub struct MgoNode {
     validator_components: Mutex<Option<ValidatorComponents>>,
     /// The http server responsible for serving JSON-RPC as well as the experimental rest service
     _http_server: Option<tokio::task::JoinHandle<()>>,
+    /// JSON-RPC methods disabled from the admin server
+    rpc_kill_switch: MethodKillSwitch,
     state: Arc<AuthorityState>,
     transaction_orchestrator: Option<Arc<TransactiondOrchestrator<NetworkAuthorityClient>>>,
     registry_service: RegistryService,
@@ -642,6 +652,7 @@ impl MgoNode {
             None
         };
 
+        let rpc_kill_switch = MethodKillSwitch::default();
         let http_server = build_http_server(
             state.clone(),
             state_sync_store,
@@ -649,6 +660,7 @@ impl MgoNode {
             &config,
             &prometheus_registry,
             custom_rpc_runtime,
+            rpc_kill_switch.clone(),
         )?;
 
         let accumulator = Arc::new(StateAccumulator::new(store));
@@ -707,6 +719,7 @@ impl MgoNode {
             config,
             validator_components: Mutex::new(validator_components),
             _http_server: http_server,
+            rpc_kill_switch,
             state,
             transaction_orchestrator,
             registry_service,
@@ -1738,6 +1751,7 @@ pub fn build_http_server(
     config: &NodeConfig,
     prometheus_registry: &Registry,
     _custom_runtime: Option<Handle>,
+    kill_switch: MethodKillSwitch,
 ) -> Result<Option<tokio::task::JoinHandle<()>>> {
     // Validators do not expose these APIs
     if config.consensus_config().is_some() {
@@ -1748,20 +1762,50 @@ pub fn build_http_server(
 
     let json_rpc_router = {
         let mut server = JsonRpcServerBuilder::new(env!("CARGO_PKG_VERSION"), prometheus_registry);
+        server.set_kill_switch(kill_switch);
+        if let Some(cors) = &config.json_rpc_cors {
+            server.set_cors(CorsConfig {
+                allowed_origins: cors.allowed_origins.clone(),
+                allowed_headers: cors.allowed_headers.clone(),
+                origin_methods: cors.origin_methods.clone(),
+            });
+        }
+        if let Some(signing) = &config.json_rpc_response_signing {
+            let signing = ResponseSigningConfig {
+                key_path: signing.key_path.clone(),
+                methods: signing.methods.clone(),
+            };
+            server.set_response_signer(signing.build()?);
+        }
+
+        let display_limits = match &config.json_rpc_display_limits {
+            Some(limits) => DisplayRenderLimits {
+                max_substitutions: limits
+                    .max_substitutions
+                    .unwrap_or(DisplayRenderLimits::DEFAULT_MAX_SUBSTITUTIONS),
+                max_field_depth: limits
+                    .max_field_depth
+                    .unwrap_or(DisplayRenderLimits::DEFAULT_MAX_FIELD_DEPTH),
+                time_budget: limits.time_budget_ms.map_or(
+                    DisplayRenderLimits::DEFAULT_TIME_BUDGET,
+                    Duration::from_millis,
+                ),
+            },
+            None => DisplayRenderLimits::default(),
+        };
 
         let kv_store = build_kv_store(&state, config, prometheus_registry)?;
 
         let metrics = Arc::new(JsonRpcMetrics::new(prometheus_registry));
-        server.register_module(ReadApi::new(
-            state.clone(),
-            kv_store.clone(),
-            metrics.clone(),
-        ))?;
-        server.register_module(CoinReadApi::new(
-            state.clone(),
-            kv_store.clone(),
-            metrics.clone(),
-        ))?;
+        server.register_module(
+            ReadApi::new(state.clone(), kv_store.clone(), metrics.clone())
+                .with_display_limits(display_limits.clone()),
+        )?;
+        let mut coin_read_api = CoinReadApi::new(state.clone(), kv_store.clone(), metrics.clone());
+        if let Some(coin_registry_id) = config.coin_registry_id {
+            coin_read_api = coin_read_api.with_coin_registry(CoinRegistry::new(coin_registry_id));
+        }
+        server.register_module(coin_read_api)?;
 
         // if run_with_range is enabled we want to prevent any transactions
         // run_with_range = None is normal operating conditions
@@ -1770,12 +1814,31 @@ pub fn build_http_server(
         }
         server.register_module(GovernanceReadApi::new(state.clone(), metrics.clone()))?;
 
+        let gas_comparator = config
+            .dry_run_gas_comparison_threshold_bps
+            .map(|threshold| GasComparator::new(state.clone(), threshold, metrics.clone()));
+        if let Some(gas_comparator) = &gas_comparator {
+            server.register_module(DebugApi::new(gas_comparator.clone()))?;
+        }
+        if config.enable_support_api {
+            server.register_module(SupportApi::new(
+                state.clone(),
+                kv_store.clone(),
+                metrics.clone(),
+            ))?;
+        }
+
         if let Some(transaction_orchestrator) = transaction_orchestrator {
-            server.register_module(TransactionExecutionApi::new(
+            let mut transaction_execution_api = TransactionExecutionApi::new(
                 state.clone(),
                 transaction_orchestrator.clone(),
                 metrics.clone(),
-            ))?;
+            );
+            if let Some(gas_comparator) = gas_comparator {
+                transaction_execution_api =
+                    transaction_execution_api.with_gas_comparator(gas_comparator);
+            }
+            server.register_module(transaction_execution_api)?;
         }
 
         let name_service_config =
@@ -1795,13 +1858,23 @@ pub fn build_http_server(
 
         server.register_module(IndexerApi::new(
             state.clone(),
-            ReadApi::new(state.clone(), kv_store.clone(), metrics.clone()),
+            ReadApi::new(state.clone(), kv_store.clone(), metrics.clone())
+                .with_display_limits(display_limits),
             kv_store,
             name_service_config,
             metrics,
             config.indexer_max_subscriptions,
+            EventFilterLimits {
+                max_depth: config
+                    .subscription_filter_max_depth
+                    .unwrap_or(EventFilterLimits::DEFAULT_MAX_DEPTH),
+                max_nodes: config
+                    .subscription_filter_max_nodes
+                    .unwrap_or(EventFilterLimits::DEFAULT_MAX_NODES),
+            },
         ))?;
         server.register_module(MoveUtils::new(state))?;
+        server.register_module(VerificationApi::new())?;
 
         server.to_router(None)?
     };
@@ -54,6 +54,11 @@ struct Args {
 
     #[clap(long, group = "exclusive")]
     run_with_range_checkpoint: Option<CheckpointSequenceNumber>,
+
+    /// Export spans to the OpenTelemetry collector at this endpoint, e.g.
+    /// `http://localhost:4317`. `TRACE_FILTER` selects the exported spans, defaulting to `info`.
+    #[clap(long)]
+    otlp_endpoint: Option<String>,
 }
 
 fn main() {
@@ -91,10 +96,13 @@ fn main() {
     let prometheus_registry = registry_service.default_registry();
 
     // Initialize logging
-    let (_guard, filter_handle) = telemetry_subscribers::TelemetryConfig::new()
+    let mut telemetry_config = telemetry_subscribers::TelemetryConfig::new()
         .with_env()
-        .with_prom_registry(&prometheus_registry)
-        .init();
+        .with_prom_registry(&prometheus_registry);
+    if let Some(otlp_endpoint) = &args.otlp_endpoint {
+        telemetry_config = telemetry_config.with_otlp_endpoint(otlp_endpoint);
+    }
+    let (_guard, filter_handle) = telemetry_config.init();
 
     drop(metrics_rt);
 
@@ -25,16 +25,15 @@ use mgo_json_rpc_types::{
     MoveFunctionArgType, ObjectChange, ObjectValueKind::ByImmutableReference,
     ObjectValueKind::ByMutableReference, ObjectValueKind::ByValue, ObjectsPage, OwnedObjectRef,
     Page, ProtocolConfigResponse, RPCTransactionRequestParams, Stake, StakeStatus, MgoCoinMetadata,
-    MgoCommittee, MgoData, MgoEvent, MgoExecutionStatus, MgoGetPastObjectRequest,
-    MgoLoadedChildObject, MgoLoadedChildObjectsResponse, MgoMoveAbility, MgoMoveAbilitySet,
-    MgoMoveNormalizedFunction, MgoMoveNormalizedModule, MgoMoveNormalizedStruct,
+    MgoCommittee, MgoData, MgoDynamicFieldInfo, MgoEvent, MgoExecutionStatus,
+    MgoGetPastObjectRequest, MgoLoadedChildObject, MgoLoadedChildObjectsResponse, MgoMoveAbility,
+    MgoMoveAbilitySet, MgoMoveNormalizedFunction, MgoMoveNormalizedModule, MgoMoveNormalizedStruct,
     MgoMoveNormalizedType, MgoMoveVisibility, MgoObjectData, MgoObjectDataFilter,
     MgoObjectDataOptions, MgoObjectRef, MgoObjectResponse, MgoObjectResponseQuery, MgoParsedData,
-    MgoPastObjectResponse, MgoTransactionBlock, MgoTransactionBlockData,
-    MgoTransactionBlockEffects, MgoTransactionBlockEffectsV1, MgoTransactionBlockEvents,
-    MgoTransactionBlockResponse, MgoTransactionBlockResponseOptions,
-    MgoTransactionBlockResponseQuery, TransactionBlockBytes, TransactionBlocksPage,
-    TransactionFilter, TransferObjectParams,
+    MgoPastObjectResponse, MgoTransactionBlock, MgoTransactionBlockData, MgoTransactionBlockEffects,
+    MgoTransactionBlockEffectsV1, MgoTransactionBlockEvents, MgoTransactionBlockResponse,
+    MgoTransactionBlockResponseOptions, MgoTransactionBlockResponseQuery, NameServiceResolution,
+    TransactionBlockBytes, TransactionBlocksPage, TransactionFilter, TransferObjectParams,
 };
 use mgo_json_rpc_types::{MgoTypeTag, ValidatorApy, ValidatorApys};
 use mgo_open_rpc::ExamplePairing;
@@ -49,7 +48,7 @@ use mgo_types::base_types::{
 use mgo_types::committee::Committee;
 use mgo_types::crypto::{get_key_pair_from_rng, AccountKeyPair, AggregateAuthoritySignature};
 use mgo_types::digests::TransactionEventsDigest;
-use mgo_types::dynamic_field::{DynamicFieldInfo, DynamicFieldName, DynamicFieldType};
+use mgo_types::dynamic_field::{DynamicFieldName, DynamicFieldType};
 use mgo_types::event::EventID;
 use mgo_types::gas::GasCostSummary;
 use mgo_types::gas_coin::GasCoin;
@@ -143,6 +142,8 @@ impl RpcExampleProvider {
             self.mgox_resolve_name_service_address(),
             self.mgox_resolve_name_service_names(),
             self.mgo_try_multi_get_past_objects(),
+            self.mgox_multi_resolve_name_service(),
+            self.mgox_multi_reverse_resolve(),
         ]
         .into_iter()
         .map(|example| (example.function_name, example.examples))
@@ -344,11 +345,13 @@ impl RpcExampleProvider {
                         self.rng.gen(),
                     )))),
                     previous_transaction: Some(TransactionDigest::new(self.rng.gen())),
+                    previous_transaction_details: None,
                     storage_rebate: Some(100),
                     object_id,
                     version: SequenceNumber::from_u64(1),
                     digest: ObjectDigest::new(self.rng.gen()),
                     type_: Some(ObjectType::Struct(MoveObjectType::gas_coin())),
+                    latest_type: None,
                     bcs: None,
                     display: None,
                 })
@@ -388,11 +391,13 @@ impl RpcExampleProvider {
                 self.rng.gen(),
             )))),
             previous_transaction: Some(TransactionDigest::new(self.rng.gen())),
+            previous_transaction_details: None,
             storage_rebate: Some(100),
             object_id,
             version: SequenceNumber::from_u64(4),
             digest: ObjectDigest::new(self.rng.gen()),
             type_: Some(ObjectType::Struct(MoveObjectType::gas_coin())),
+            latest_type: None,
             bcs: None,
             display: None,
         });
@@ -460,6 +465,7 @@ impl RpcExampleProvider {
             data: page,
             next_cursor: Some((seq + pagelen).into()),
             has_next_page: true,
+            applied: None,
         };
 
         Examples::new(
@@ -490,8 +496,10 @@ impl RpcExampleProvider {
                 version: Default::default(),
                 digest: ObjectDigest::new(self.rng.gen()),
                 type_: Some(ObjectType::Struct(MoveObjectType::gas_coin())),
+                latest_type: None,
                 owner: Some(Owner::AddressOwner(owner)),
                 previous_transaction: Some(TransactionDigest::new(self.rng.gen())),
+                previous_transaction_details: None,
                 storage_rebate: None,
                 display: None,
                 content: None,
@@ -574,6 +582,7 @@ impl RpcExampleProvider {
             data,
             next_cursor,
             has_next_page,
+            applied: None,
         };
         Examples::new(
             "mgox_queryTransactionBlocks",
@@ -759,14 +768,23 @@ impl RpcExampleProvider {
             events: None,
             object_changes: Some(vec![object_change]),
             balance_changes: None,
+            created_summary: None,
+            signatures: None,
             timestamp_ms: None,
+            checkpoint_timestamp_ms: None,
+            observed_execution_timestamp_ms: None,
             transaction: Some(MgoTransactionBlock {
                 data: MgoTransactionBlockData::try_from(data1, &&mut NoOpsModuleResolver).unwrap(),
                 tx_signatures: signatures.clone(),
             }),
             raw_transaction,
             confirmed_local_execution: None,
+            executed_at_checkpoint_hint: None,
+            written_objects: None,
+            served_from: None,
             checkpoint: None,
+            checkpoint_digest: None,
+            epoch: None,
             errors: vec![],
             raw_effects: vec![],
         };
@@ -795,6 +813,7 @@ impl RpcExampleProvider {
             data: vec![event],
             next_cursor: Some((tx_dig, 5).into()),
             has_next_page: false,
+            applied: None,
         };
         Examples::new(
             "mgo_getEvents",
@@ -845,6 +864,9 @@ impl RpcExampleProvider {
             coin_object_count: 15,
             total_balance: 3000000000,
             locked_balance: HashMap::new(),
+            verified: None,
+            canonical_symbol: None,
+            canonical_icon_url: None,
         };
         Examples::new(
             "mgox_getAllBalances",
@@ -870,12 +892,17 @@ impl RpcExampleProvider {
                 balance: 200000000,
                 //locked_until_epoch: None,
                 previous_transaction: TransactionDigest::new(self.rng.gen()),
+                verified: None,
+                canonical_symbol: None,
+                canonical_icon_url: None,
             })
             .collect::<Vec<_>>();
         let page = CoinPage {
             data: coins,
             next_cursor: Some(next),
             has_next_page: true,
+            totals: None,
+            totals_unavailable: false,
         };
 
         Examples::new(
@@ -900,6 +927,9 @@ impl RpcExampleProvider {
             coin_object_count: 15,
             total_balance: 15,
             locked_balance: HashMap::new(),
+            verified: None,
+            canonical_symbol: None,
+            canonical_icon_url: None,
         };
 
         Examples::new(
@@ -920,6 +950,9 @@ impl RpcExampleProvider {
             description: "Stable coin.".to_string(),
             icon_url: None,
             id: Some(ObjectID::new(self.rng.gen())),
+            verified: None,
+            canonical_symbol: None,
+            canonical_icon_url: None,
         };
 
         Examples::new(
@@ -959,6 +992,9 @@ impl RpcExampleProvider {
                 balance: 200000000,
                 //locked_until_epoch: None,
                 previous_transaction: TransactionDigest::new(self.rng.gen()),
+                verified: None,
+                canonical_symbol: None,
+                canonical_icon_url: None,
             })
             .collect::<Vec<_>>();
 
@@ -968,6 +1004,8 @@ impl RpcExampleProvider {
             data: coins,
             next_cursor: Some(next_cursor),
             has_next_page: true,
+            totals: None,
+            totals_unavailable: false,
         };
 
         Examples::new(
@@ -1183,7 +1221,7 @@ impl RpcExampleProvider {
     fn mgox_get_dynamic_fields(&mut self) -> Examples {
         let object_id = ObjectID::new(self.rng.gen());
         let dynamic_fields = (0..3)
-            .map(|_| DynamicFieldInfo {
+            .map(|_| MgoDynamicFieldInfo {
                 name: DynamicFieldName {
                     type_: TypeTag::from_str("0x9::test::TestField").unwrap(),
                     value: serde_json::Value::String("some_value".to_string()),
@@ -1194,6 +1232,7 @@ impl RpcExampleProvider {
                 object_id: ObjectID::new(self.rng.gen()),
                 version: SequenceNumber::from_u64(1),
                 digest: ObjectDigest::new(self.rng.gen()),
+                object: None,
             })
             .collect::<Vec<_>>();
 
@@ -1203,6 +1242,7 @@ impl RpcExampleProvider {
             data: dynamic_fields,
             next_cursor: Some(next_cursor),
             has_next_page: true,
+            applied: None,
         };
 
         Examples::new("mgox_getDynamicFields",
@@ -1249,6 +1289,7 @@ impl RpcExampleProvider {
                 self.rng.gen(),
             )))),
             previous_transaction: Some(TransactionDigest::new(self.rng.gen())),
+            previous_transaction_details: None,
             storage_rebate: Some(100),
             object_id: parent_object_id,
             version: SequenceNumber::from_u64(1),
@@ -1256,6 +1297,7 @@ impl RpcExampleProvider {
             type_: Some(ObjectType::Struct(MoveObjectType::from(
                 parse_mgo_struct_tag("0x9::test::TestField").unwrap(),
             ))),
+            latest_type: None,
             bcs: None,
             display: None,
         });
@@ -1297,11 +1339,13 @@ impl RpcExampleProvider {
                     content: None,
                     owner: Some(Owner::AddressOwner(owner)),
                     previous_transaction: Some(TransactionDigest::new(self.rng.gen())),
+                    previous_transaction_details: None,
                     storage_rebate: Some(100),
                     object_id: ObjectID::new(self.rng.gen()),
                     version: SequenceNumber::from_u64(version),
                     digest: ObjectDigest::new(self.rng.gen()),
                     type_: Some(ObjectType::Struct(MoveObjectType::gas_coin())),
+                    latest_type: None,
                     bcs: None,
                     display: None,
                 })
@@ -1313,6 +1357,7 @@ impl RpcExampleProvider {
             data: items,
             next_cursor: Some(next_cursor.unwrap()),
             has_next_page: true,
+            applied: None,
         };
 
         Examples::new(
@@ -1357,6 +1402,7 @@ impl RpcExampleProvider {
             data,
             next_cursor,
             has_next_page,
+            applied: None,
         };
         Examples::new(
             "mgox_queryEvents",
@@ -1507,11 +1553,12 @@ impl RpcExampleProvider {
             data: vec!["example.mgo".to_string()],
             next_cursor,
             has_next_page: false,
+            applied: None,
         };
         Examples::new(
             "mgox_resolveNameServiceNames",
             vec![ExamplePairing::new(
-                "Returns the MgoNS name for the address the request provides. Currently, the API returns only the first name in cases where there are multiple. Future support will use the cursor ID and limit values in the request to control pagination of the response for addresses with multiple names.",
+                "Returns a page of the unexpired MgoNS names pointing to the address the request provides, starting after the name record with the given cursor ID.",
                 vec![
                     ("address", json!(object_id)),
                     ("cursor", json!(next_cursor)),
@@ -1522,6 +1569,44 @@ impl RpcExampleProvider {
         )
     }
 
+    fn mgox_multi_resolve_name_service(&mut self) -> Examples {
+        let address = MgoAddress::from(ObjectID::new(self.rng.gen()));
+        let result = vec![
+            NameServiceResolution::new(Some(address)),
+            NameServiceResolution::new(None),
+        ];
+        Examples::new(
+            "mgox_multiResolveNameService",
+            vec![ExamplePairing::new(
+                "Returns the addresses the names the request provides resolve to, in the same order, omitting the data of unregistered or expired names.",
+                vec![(
+                    "names",
+                    json!(vec!["example.mgo".to_string(), "unregistered.mgo".to_string()]),
+                )],
+                json!(result),
+            )],
+        )
+    }
+
+    fn mgox_multi_reverse_resolve(&mut self) -> Examples {
+        let addresses = vec![
+            MgoAddress::from(ObjectID::new(self.rng.gen())),
+            MgoAddress::from(ObjectID::new(self.rng.gen())),
+        ];
+        let result = vec![
+            NameServiceResolution::new(Some("example.mgo".to_string())),
+            NameServiceResolution::new(None),
+        ];
+        Examples::new(
+            "mgox_multiReverseResolve",
+            vec![ExamplePairing::new(
+                "Returns the names the addresses the request provides resolve to, in the same order, omitting the data of addresses without a name resolving back to them.",
+                vec![("addresses", json!(addresses))],
+                json!(result),
+            )],
+        )
+    }
+
     fn mgo_try_multi_get_past_objects(&mut self) -> Examples {
         let object_id = ObjectID::new(self.rng.gen());
         let object_id2 = ObjectID::new(self.rng.gen());
@@ -1549,11 +1634,13 @@ impl RpcExampleProvider {
                     self.rng.gen(),
                 )))),
                 previous_transaction: Some(TransactionDigest::new(self.rng.gen())),
+                previous_transaction_details: None,
                 storage_rebate: Some(100),
                 object_id,
                 version: SequenceNumber::from_u64(4),
                 digest: ObjectDigest::new(self.rng.gen()),
                 type_: Some(ObjectType::Struct(MoveObjectType::gas_coin())),
+                latest_type: None,
                 bcs: None,
                 display: None,
             }),
@@ -1569,11 +1656,13 @@ impl RpcExampleProvider {
                     self.rng.gen(),
                 )))),
                 previous_transaction: Some(TransactionDigest::new(self.rng.gen())),
+                previous_transaction_details: None,
                 storage_rebate: Some(100),
                 object_id: object_id2,
                 version: version2,
                 digest: ObjectDigest::new(self.rng.gen()),
                 type_: Some(ObjectType::Struct(MoveObjectType::gas_coin())),
+                latest_type: None,
                 bcs: None,
                 display: None,
             }),
@@ -60,6 +60,7 @@ impl Project {
             components: Components {
                 content_descriptors: Default::default(),
                 schemas: Default::default(),
+                errors: Default::default(),
             },
             method_routing: Default::default(),
         }
@@ -77,6 +78,17 @@ impl Project {
         self.method_routing.extend(module.method_routing);
     }
 
+    /// Documents an error the server may answer any method with, under `name`.
+    pub fn add_error(&mut self, name: &str, code: i32, message: &str) {
+        self.components.errors.insert(
+            name.to_string(),
+            ErrorObject {
+                code,
+                message: message.to_string(),
+            },
+        );
+    }
+
     pub fn add_examples(&mut self, mut example_provider: BTreeMap<String, Vec<ExamplePairing>>) {
         for method in &mut self.methods {
             if let Occupied(entry) = example_provider.entry(method.name.clone()) {
@@ -114,6 +126,13 @@ pub struct Module {
     method_routing: BTreeMap<String, MethodRouting>,
 }
 
+impl Module {
+    /// Names of the documented methods, prefixed with their namespace.
+    pub fn method_names(&self) -> impl Iterator<Item = &str> {
+        self.methods.iter().map(|method| method.name.as_str())
+    }
+}
+
 pub struct RpcModuleDocBuilder {
     schema_generator: SchemaGenerator,
     methods: BTreeMap<String, Method>,
@@ -334,6 +353,7 @@ impl RpcModuleDocBuilder {
                     .into_iter()
                     .map(|(name, schema)| (name, schema.into_object()))
                     .collect::<BTreeMap<_, _>>(),
+                errors: Default::default(),
             },
             method_routing: self.method_routing,
         }
@@ -445,4 +465,12 @@ struct Components {
     content_descriptors: BTreeMap<String, ContentDescriptor>,
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     schemas: BTreeMap<String, SchemaObject>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    errors: BTreeMap<String, ErrorObject>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct ErrorObject {
+    code: i32,
+    message: String,
 }
@@ -68,6 +68,9 @@ async fn main() -> Result<(), anyhow::Error> {
         show_content: true,
         show_bcs: true,
         show_storage_rebate: true,
+        field_mask: None,
+        resolve_type_to_latest: false,
+        show_previous_transaction_details: false,
     };
 
     let past_object = mgo
@@ -126,6 +129,9 @@ async fn main() -> Result<(), anyhow::Error> {
                 show_object_changes: true,
                 show_balance_changes: true,
                 show_raw_effects: true,
+                show_created_summary: true,
+                show_signatures: false,
+                include_checkpoint: false,
             },
         )
         .await?;
@@ -8,29 +8,44 @@ use futures_core::Stream;
 use jsonrpsee::core::client::Subscription;
 use std::collections::BTreeMap;
 use std::future;
+use std::future::Future;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use mgo_json_rpc_types::DevInspectArgs;
+use mgo_json_rpc_types::EventsByObjectPage;
 use mgo_json_rpc_types::MgoData;
 
 use crate::error::{Error, MgoRpcResult};
 use crate::RpcClient;
 use mgo_json_rpc_api::{
-    CoinReadApiClient, GovernanceReadApiClient, IndexerApiClient, MoveUtilsClient, ReadApiClient,
-    WriteApiClient,
+    CoinReadApiClient, ExtendedApiClient, GovernanceReadApiClient, IndexerApiClient,
+    MoveUtilsClient, ReadApiClient, SupportApiClient, WriteApiClient,
 };
 use mgo_json_rpc_types::{
-    Balance, Checkpoint, CheckpointId, Coin, CoinPage, DelegatedStake, DevInspectResults,
-    DryRunTransactionBlockResponse, DynamicFieldPage, EventFilter, EventPage, ObjectsPage,
-    ProtocolConfigResponse, MgoCoinMetadata, MgoCommittee, MgoEvent, MgoGetPastObjectRequest,
-    MgoMoveNormalizedModule, MgoObjectDataOptions, MgoObjectResponse, MgoObjectResponseQuery,
-    MgoPastObjectResponse, MgoTransactionBlockEffects, MgoTransactionBlockResponse,
-    MgoTransactionBlockResponseOptions, MgoTransactionBlockResponseQuery, TransactionBlocksPage,
-    TransactionFilter,
+    AbortLocation, ActiveAddressStats, AddressMetrics, AddressStateExport, Balance, Checkpoint,
+    ChainState, CheckpointId, CheckpointedObjectID, Coin, CoinPage, CoinTypeOrder, CoinTypesPage,
+    CohortRetention,
+    DelegatedStake, DevInspectResults, DryRunTransactionBlockResponse, DynamicFieldPage, EpochInfo,
+    EpochMetricsPage, EpochPage, EpochStartInfo, EventFilter, EventPage, ModifiedObjectCursor,
+    ModifiedObjectsPage,
+    ModuleDisassembly, MoveCallMetrics, MultiOwnerObjectCursor, MultiOwnerObjectsPage,
+    NetworkMetrics, ObjectOwnersHistoryPage, ObjectsPage, OrphanedDynamicFieldsPage,
+    OwnedStakesSummary, PackageDependency,
+    PackageDependentsPage, PackageVerification, Page, ProtocolConfigResponse, QueryObjectsPage,
+    SharedObjectStats, StorageRebateEstimate, StorageStats, SupplyGranularity, SupplyHistory,
+    MgoCoinMetadata, MgoCommittee, MgoEvent, MgoGetPastObjectRequest, MgoMoveNormalizedModule,
+    MgoObjectDataOptions, MgoObjectResponse, MgoObjectResponseQuery, MgoPastObjectResponse,
+    MgoTransactionBlockEffects, MgoTransactionBlockResponse, MgoTransactionBlockResponseOptions,
+    MgoTransactionBlockResponseQuery, TableValue, TransactionBlocksPage, TransactionFilter,
+    TransactionSignatureVerification, ValidatorReport,
+};
+use mgo_json_rpc_types::{
+    verify_committee_handoffs, CheckpointPage, CheckpointVerificationReport,
+    MgoLoadedChildObjectsResponse,
 };
-use mgo_json_rpc_types::{CheckpointPage, MgoLoadedChildObjectsResponse};
 use mgo_types::balance::Supply;
 use mgo_types::base_types::{ObjectID, SequenceNumber, MgoAddress, TransactionDigest};
+use mgo_types::committee::EpochId;
 use mgo_types::dynamic_field::DynamicFieldName;
 use mgo_types::event::EventID;
 use mgo_types::messages_checkpoint::CheckpointSequenceNumber;
@@ -84,7 +99,7 @@ impl ReadApi {
         Ok(self
             .api
             .http
-            .get_owned_objects(address, query, cursor, limit)
+            .get_owned_objects(address, query, cursor, limit, None)
             .await?)
     }
 
@@ -137,7 +152,7 @@ impl ReadApi {
         Ok(self
             .api
             .http
-            .get_dynamic_fields(object_id, cursor, limit)
+            .get_dynamic_fields(object_id, cursor, limit, None, None)
             .await?)
     }
 
@@ -150,7 +165,7 @@ impl ReadApi {
         Ok(self
             .api
             .http
-            .get_dynamic_field_object(parent_object_id, name)
+            .get_dynamic_field_object(parent_object_id, name, None)
             .await?)
     }
 
@@ -200,6 +215,9 @@ impl ReadApi {
     ///                 show_content: true,
     ///                 show_bcs: true,
     ///                 show_storage_rebate: true,
+    ///                 field_mask: None,
+    ///                 resolve_type_to_latest: false,
+    ///                 show_previous_transaction_details: false,
     ///             },
     ///         )
     ///         .await?;
@@ -263,6 +281,9 @@ impl ReadApi {
     ///                 show_content: true,
     ///                 show_bcs: true,
     ///                 show_storage_rebate: true,
+    ///                 field_mask: None,
+    ///                 resolve_type_to_latest: false,
+    ///                 show_previous_transaction_details: false,
     ///             },
     ///         )
     ///         .await?;
@@ -282,6 +303,9 @@ impl ReadApi {
     ///                 show_content: true,
     ///                 show_bcs: true,
     ///                 show_storage_rebate: true,
+    ///                 field_mask: None,
+    ///                 resolve_type_to_latest: false,
+    ///                 show_previous_transaction_details: false,
     ///             },
     ///         )
     ///         .await?;
@@ -341,6 +365,9 @@ impl ReadApi {
     ///                 show_content: true,
     ///                 show_bcs: true,
     ///                 show_storage_rebate: true,
+    ///                 field_mask: None,
+    ///                 resolve_type_to_latest: false,
+    ///                 show_previous_transaction_details: false,
     ///             },
     ///         ).await?;
     ///     Ok(())
@@ -393,6 +420,9 @@ impl ReadApi {
     ///                 show_content: true,
     ///                 show_bcs: true,
     ///                 show_storage_rebate: true,
+    ///                 field_mask: None,
+    ///                 resolve_type_to_latest: false,
+    ///                 show_previous_transaction_details: false,
     ///             },
     ///         ).await?;
     ///     Ok(())
@@ -403,11 +433,12 @@ impl ReadApi {
         object_ids: Vec<ObjectID>,
         options: MgoObjectDataOptions,
     ) -> MgoRpcResult<Vec<MgoObjectResponse>> {
-        Ok(self
-            .api
+        self.api
             .http
-            .multi_get_objects(object_ids, Some(options))
-            .await?)
+            .multi_get_objects(object_ids, Some(options), None)
+            .await?
+            .into_json()
+            .map_err(|e| Error::DataError(e.to_string()))
     }
 
     /// Return An object's bcs content [`Vec<u8>`] based on the provided [ObjectID], or an error upon failure.
@@ -471,11 +502,12 @@ impl ReadApi {
         digests: Vec<TransactionDigest>,
         options: MgoTransactionBlockResponseOptions,
     ) -> MgoRpcResult<Vec<MgoTransactionBlockResponse>> {
-        Ok(self
-            .api
+        self.api
             .http
-            .multi_get_transaction_blocks(digests, Some(options))
-            .await?)
+            .multi_get_transaction_blocks(digests, Some(options), None)
+            .await?
+            .into_json()
+            .map_err(|e| Error::DataError(e.to_string()))
     }
 
     /// Return the [MgoCommittee] information for the provided `epoch`, or an error upon failure.
@@ -517,7 +549,7 @@ impl ReadApi {
         Ok(self
             .api
             .http
-            .query_transaction_blocks(query, cursor, limit, Some(descending_order))
+            .query_transaction_blocks(query, cursor, limit, Some(descending_order), None)
             .await?)
     }
 
@@ -531,7 +563,12 @@ impl ReadApi {
     /// A Mgo checkpoint is a sequence of transaction sets that a quorum of validators
     /// agree upon as having been executed within the Mgo system.
     pub async fn get_checkpoint(&self, id: CheckpointId) -> MgoRpcResult<Checkpoint> {
-        Ok(self.api.http.get_checkpoint(id).await?)
+        self.api
+            .http
+            .get_checkpoint(id, None)
+            .await?
+            .into_json()
+            .map_err(|e| Error::DataError(e.to_string()))
     }
 
     /// Return a paginated list of checkpoints, or an error upon failure.
@@ -544,7 +581,22 @@ impl ReadApi {
         Ok(self
             .api
             .http
-            .get_checkpoints(cursor, limit, descending_order)
+            .get_checkpoints(cursor, limit, descending_order, None)
+            .await?)
+    }
+
+    /// Return a paginated list of the checkpoints of `epoch`, or an error upon failure.
+    pub async fn get_epoch_checkpoints(
+        &self,
+        epoch: EpochId,
+        cursor: Option<BigInt<u64>>,
+        limit: Option<usize>,
+        descending_order: bool,
+    ) -> MgoRpcResult<CheckpointPage> {
+        Ok(self
+            .api
+            .http
+            .get_checkpoints(cursor, limit, descending_order, Some(epoch.into()))
             .await?)
     }
 
@@ -694,7 +746,26 @@ impl ReadApi {
         &self,
         digest: TransactionDigest,
     ) -> MgoRpcResult<MgoLoadedChildObjectsResponse> {
-        Ok(self.api.http.get_loaded_child_objects(digest).await?)
+        Ok(self.api.http.get_loaded_child_objects(digest, None).await?)
+    }
+
+    /// Return the loaded child objects response for the provided digest, with the contents of
+    /// each child object at its loaded version as selected by the options, or an error upon
+    /// failure.
+    ///
+    /// Children whose version has been pruned, or that are past the number of children a single
+    /// request resolves, are returned without contents and marked with the reason in
+    /// [MgoLoadedChildObject::unresolved](mgo_json_rpc_types::MgoLoadedChildObject::unresolved).
+    pub async fn get_loaded_child_objects_with_options(
+        &self,
+        digest: TransactionDigest,
+        options: MgoObjectDataOptions,
+    ) -> MgoRpcResult<MgoLoadedChildObjectsResponse> {
+        Ok(self
+            .api
+            .http
+            .get_loaded_child_objects(digest, Some(options))
+            .await?)
     }
 
     /// Return the protocol config, or an error upon failure.
@@ -704,6 +775,43 @@ impl ReadApi {
     ) -> MgoRpcResult<ProtocolConfigResponse> {
         Ok(self.api.http.get_protocol_config(version).await?)
     }
+
+    /// Return whether the signatures of a transaction hold and together authorize it in the
+    /// current epoch, without executing it, or an error upon failure.
+    pub async fn verify_transaction_block_signature(
+        &self,
+        tx: &Transaction,
+    ) -> MgoRpcResult<TransactionSignatureVerification> {
+        let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
+        Ok(self
+            .api
+            .http
+            .verify_transaction_block_signature(tx_bytes, signatures)
+            .await?)
+    }
+
+    /// Return the committee of an epoch and the first checkpoint of the epoch, with the
+    /// committee and the last checkpoint of the previous epoch, or an error upon failure.
+    pub async fn get_epoch_start_info(&self, epoch: EpochId) -> MgoRpcResult<EpochStartInfo> {
+        Ok(self.api.http.get_epoch_start_info(epoch.into()).await?)
+    }
+
+    /// Walk the committee handoffs from the `trusted` committee up to epoch `end`, returning the
+    /// committee of `end` once every epoch is found to hand over to the next, or an error
+    /// naming the first epoch that does not, or whose committee the node does not store.
+    pub async fn verify_committee_handoffs(
+        &self,
+        trusted: &MgoCommittee,
+        end: EpochId,
+    ) -> MgoRpcResult<MgoCommittee> {
+        let mut epochs = vec![];
+        for epoch in trusted.epoch + 1..=end {
+            epochs.push(self.get_epoch_start_info(epoch).await?);
+        }
+        let committee = verify_committee_handoffs(trusted, &epochs)
+            .map_err(|e| Error::DataError(e.to_string()))?;
+        Ok(committee.clone())
+    }
 }
 
 /// Coin Read API provides the functionality needed to get information from the Mgo network regarding the coins owned by an address.
@@ -750,7 +858,7 @@ impl CoinReadApi {
         Ok(self
             .api
             .http
-            .get_coins(owner, coin_type, cursor, limit)
+            .get_coins(owner, coin_type, cursor, limit, None, None)
             .await?)
     }
     /// Return a paginated response with all the coins for the given address, or an error upon failure.
@@ -781,7 +889,11 @@ impl CoinReadApi {
         cursor: Option<ObjectID>,
         limit: Option<usize>,
     ) -> MgoRpcResult<CoinPage> {
-        Ok(self.api.http.get_all_coins(owner, cursor, limit).await?)
+        Ok(self
+            .api
+            .http
+            .get_all_coins(owner, cursor, limit, None)
+            .await?)
     }
 
     /// Return the coins for the given address as a stream.
@@ -892,6 +1004,45 @@ impl CoinReadApi {
         Ok(coins)
     }
 
+    /// Return coins for the given address whose balances add up to at least `amount`, or an
+    /// error naming the amount missing.
+    ///
+    /// Unlike [`Self::select_coins`], the coins are selected by the server, largest first, so the
+    /// same coins are selected again when retrying as long as the coins owned do not change. At
+    /// most `max_coins` coins are selected, by default the maximum page size of the server.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use mgo_sdk::MgoClientBuilder;
+    /// use mgo_types::base_types::MgoAddress;
+    /// use std::str::FromStr;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), anyhow::Error> {
+    ///     let mgo = MgoClientBuilder::default().build_localnet().await?;
+    ///     let address = MgoAddress::from_str("0x0000....0000")?;
+    ///     let coins = mgo
+    ///         .coin_read_api()
+    ///         .select_coins_largest_first(address, None, 5, None)
+    ///         .await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn select_coins_largest_first(
+        &self,
+        address: MgoAddress,
+        coin_type: Option<String>,
+        amount: u64,
+        max_coins: Option<usize>,
+    ) -> MgoRpcResult<Vec<Coin>> {
+        Ok(self
+            .api
+            .http
+            .select_coins(address, coin_type, amount.into(), max_coins)
+            .await?)
+    }
+
     /// Return the balance for the given coin type owned by address, or an error upon failure.
     ///
     /// Note that this function sums up all the balances of all the coins matching
@@ -1071,7 +1222,7 @@ impl EventApi {
         Ok(self
             .api
             .http
-            .query_events(query, cursor, limit, Some(descending_order))
+            .query_events(query, cursor, limit, Some(descending_order), None)
             .await?)
     }
 
@@ -1147,7 +1298,8 @@ impl QuorumDriverApi {
                 .await?;
 
             match request_type {
-                ExecuteTransactionRequestType::WaitForEffectsCert => {
+                ExecuteTransactionRequestType::WaitForEffectsCert
+                | ExecuteTransactionRequestType::WaitForCheckpointInclusion { .. } => {
                     return Ok(response);
                 }
                 ExecuteTransactionRequestType::WaitForLocalExecution => {
@@ -1184,6 +1336,15 @@ impl GovernanceApi {
         Ok(self.api.http.get_stakes(owner).await?)
     }
 
+    /// Return the stakes of the given address grouped by validator, with the validators' names
+    /// and images and the totals of the stakes, or an error upon failure.
+    pub async fn get_owned_staked_objects_summary(
+        &self,
+        owner: MgoAddress,
+    ) -> MgoRpcResult<OwnedStakesSummary> {
+        Ok(self.api.http.get_owned_staked_objects_summary(owner).await?)
+    }
+
     /// Return the [MgoCommittee] information for the given `epoch`, or an error upon failure.
     ///
     /// The argument `epoch` is the known epoch id or `None` for the current epoch.
@@ -1223,4 +1384,703 @@ impl GovernanceApi {
     pub async fn get_reference_gas_price(&self) -> MgoRpcResult<u64> {
         Ok(*self.api.http.get_reference_gas_price().await?)
     }
+
+    /// Return the MGO returned to the sender deleting the given object, and mutating it so that
+    /// its contents are `new_contents_size` bytes long if given, or an error upon failure.
+    pub async fn estimate_storage_rebate(
+        &self,
+        object_id: ObjectID,
+        new_contents_size: Option<u64>,
+    ) -> MgoRpcResult<StorageRebateEstimate> {
+        Ok(self
+            .api
+            .http
+            .estimate_storage_rebate(object_id, new_contents_size.map(BigInt::from))
+            .await?)
+    }
+}
+
+/// Support API, only served by fullnodes that enable it for support tooling.
+#[derive(Debug, Clone)]
+pub struct SupportApi {
+    api: Arc<RpcClient>,
+}
+
+impl SupportApi {
+    pub(crate) fn new(api: Arc<RpcClient>) -> Self {
+        Self { api }
+    }
+
+    /// Return the owned objects, balances, stakes, latest transactions and pending object locks
+    /// of `address` in one [AddressStateExport], or an error if the export could not start.
+    /// Sections that could not be read carry their error in place of their data.
+    pub async fn export_address_state(
+        &self,
+        address: MgoAddress,
+    ) -> MgoRpcResult<AddressStateExport> {
+        Ok(self.api.http.export_address_state(address).await?)
+    }
+
+    /// Return the mismatches found checking that the checkpoints stored by the node from `start`
+    /// to `end`, both included, still hash to the digests linking them.
+    pub async fn verify_checkpoint_range(
+        &self,
+        start: CheckpointSequenceNumber,
+        end: CheckpointSequenceNumber,
+    ) -> MgoRpcResult<CheckpointVerificationReport> {
+        Ok(self
+            .api
+            .http
+            .verify_checkpoint_range(start.into(), end.into())
+            .await?)
+    }
+}
+
+/// Extended API with the endpoints only served by the indexer, such as epochs, network metrics
+/// and object queries by checkpoint. Requests are retried as set by the [RetryConfig] of the
+/// client, and every paginated endpoint can also be walked as a stream of pages.
+#[derive(Debug, Clone)]
+pub struct ExtendedApi {
+    api: Arc<RpcClient>,
+    retry_config: RetryConfig,
+}
+
+impl ExtendedApi {
+    /// Names of the RPC methods wrapped by this API.
+    pub const METHODS: &'static [&'static str] = &[
+        "mgox_getActiveAddressStats",
+        "mgox_getAllEpochAddressMetrics",
+        "mgox_getChainState",
+        "mgox_getCheckpointAddressMetrics",
+        "mgox_getCoinTypes",
+        "mgox_getCurrentEpoch",
+        "mgox_getEpochMetrics",
+        "mgox_getEpochs",
+        "mgox_getLatestAddressMetrics",
+        "mgox_getModuleDisassembly",
+        "mgox_getMoveCallMetrics",
+        "mgox_getNetworkMetrics",
+        "mgox_getObjectOwnersHistory",
+        "mgox_getOrphanedDynamicFields",
+        "mgox_getOwnedObjectsAtCheckpoint",
+        "mgox_getOwnedObjectsByCheckpoint",
+        "mgox_getOwnedObjectsMulti",
+        "mgox_getPackageDependencies",
+        "mgox_getPackageDependents",
+        "mgox_getRetention",
+        "mgox_getSharedObjectStats",
+        "mgox_getStorageStats",
+        "mgox_getSupplyHistory",
+        "mgox_getTableValues",
+        "mgox_getTotalTransactions",
+        "mgox_getValidatorRanking",
+        "mgox_getValidatorReport",
+        "mgox_queryEventsByObject",
+        "mgox_queryObjects",
+        "mgox_resolveAbortLocation",
+        "mgox_verifyPackage",
+    ];
+
+    pub(crate) fn new(api: Arc<RpcClient>, retry_config: RetryConfig) -> Self {
+        Self { api, retry_config }
+    }
+
+    /// Return a paginated response with the info of the epochs, or an error upon failure.
+    pub async fn get_epochs(
+        &self,
+        cursor: Option<BigInt<u64>>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> MgoRpcResult<EpochPage> {
+        self.retry_config
+            .retry(|| self.api.http.get_epochs(cursor, limit, descending_order))
+            .await
+    }
+
+    /// Return a stream of the pages of [Self::get_epochs], ending after the first error.
+    pub fn get_epochs_pages(
+        &self,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> impl Stream<Item = MgoRpcResult<EpochPage>> + '_ {
+        pages(move |cursor| self.get_epochs(cursor, limit, descending_order))
+    }
+
+    /// Return a paginated response with the metrics of the epochs, or of `epoch` only, latest
+    /// first, or an error upon failure.
+    pub async fn get_epoch_metrics(
+        &self,
+        epoch: Option<BigInt<u64>>,
+        cursor: Option<BigInt<u64>>,
+        limit: Option<usize>,
+    ) -> MgoRpcResult<EpochMetricsPage> {
+        self.retry_config
+            .retry(|| self.api.http.get_epoch_metrics(epoch, cursor, limit))
+            .await
+    }
+
+    /// Return a stream of the pages of [Self::get_epoch_metrics], ending after the first error.
+    pub fn get_epoch_metrics_pages(
+        &self,
+        epoch: Option<BigInt<u64>>,
+        limit: Option<usize>,
+    ) -> impl Stream<Item = MgoRpcResult<EpochMetricsPage>> + '_ {
+        pages(move |cursor| self.get_epoch_metrics(epoch, cursor, limit))
+    }
+
+    /// Return the performance of a validator in every epoch from `from_epoch` to `to_epoch` it
+    /// was in the committee of, or an error upon failure.
+    pub async fn get_validator_report(
+        &self,
+        validator_address: MgoAddress,
+        from_epoch: BigInt<u64>,
+        to_epoch: BigInt<u64>,
+    ) -> MgoRpcResult<Vec<ValidatorReport>> {
+        self.retry_config
+            .retry(|| {
+                self.api
+                    .http
+                    .get_validator_report(validator_address, from_epoch, to_epoch)
+            })
+            .await
+    }
+
+    /// Return the performance of every validator of `epoch`, or of the latest epoch, best
+    /// first, or an error upon failure.
+    pub async fn get_validator_ranking(
+        &self,
+        epoch: Option<BigInt<u64>>,
+    ) -> MgoRpcResult<Vec<ValidatorReport>> {
+        self.retry_config
+            .retry(|| self.api.http.get_validator_ranking(epoch))
+            .await
+    }
+
+    /// Return the info of the current epoch, or an error upon failure.
+    pub async fn get_current_epoch(&self) -> MgoRpcResult<EpochInfo> {
+        self.retry_config
+            .retry(|| self.api.http.get_current_epoch())
+            .await
+    }
+
+    /// Return a paginated response with the objects matching `query`, or an error upon failure.
+    pub async fn query_objects(
+        &self,
+        query: MgoObjectResponseQuery,
+        cursor: Option<CheckpointedObjectID>,
+        limit: Option<usize>,
+    ) -> MgoRpcResult<QueryObjectsPage> {
+        self.retry_config
+            .retry(|| self.api.http.query_objects(query.clone(), cursor, limit))
+            .await
+    }
+
+    /// Return a stream of the pages of [Self::query_objects], ending after the first error.
+    pub fn query_objects_pages(
+        &self,
+        query: MgoObjectResponseQuery,
+        limit: Option<usize>,
+    ) -> impl Stream<Item = MgoRpcResult<QueryObjectsPage>> + '_ {
+        pages(move |cursor| self.query_objects(query.clone(), cursor, limit))
+    }
+
+    /// Return a paginated response with the objects owned by `address`, ordered by the checkpoint
+    /// that last modified them, or an error upon failure.
+    pub async fn get_owned_objects_by_checkpoint(
+        &self,
+        address: MgoAddress,
+        query: Option<MgoObjectResponseQuery>,
+        modified_after_checkpoint: Option<BigInt<u64>>,
+        cursor: Option<ModifiedObjectCursor>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> MgoRpcResult<ModifiedObjectsPage> {
+        self.retry_config
+            .retry(|| {
+                self.api.http.get_owned_objects_by_checkpoint(
+                    address,
+                    query.clone(),
+                    modified_after_checkpoint,
+                    cursor,
+                    limit,
+                    descending_order,
+                )
+            })
+            .await
+    }
+
+    /// Return a stream of the pages of [Self::get_owned_objects_by_checkpoint], ending after the
+    /// first error.
+    pub fn get_owned_objects_by_checkpoint_pages(
+        &self,
+        address: MgoAddress,
+        query: Option<MgoObjectResponseQuery>,
+        modified_after_checkpoint: Option<BigInt<u64>>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> impl Stream<Item = MgoRpcResult<ModifiedObjectsPage>> + '_ {
+        pages(move |cursor| {
+            self.get_owned_objects_by_checkpoint(
+                address,
+                query.clone(),
+                modified_after_checkpoint,
+                cursor,
+                limit,
+                descending_order,
+            )
+        })
+    }
+
+    /// Return a paginated response with the objects owned by any of `addresses`, or an error upon
+    /// failure.
+    pub async fn get_owned_objects_multi(
+        &self,
+        addresses: Vec<MgoAddress>,
+        query: Option<MgoObjectResponseQuery>,
+        cursor: Option<MultiOwnerObjectCursor>,
+        limit: Option<usize>,
+    ) -> MgoRpcResult<MultiOwnerObjectsPage> {
+        self.retry_config
+            .retry(|| {
+                self.api.http.get_owned_objects_multi(
+                    addresses.clone(),
+                    query.clone(),
+                    cursor,
+                    limit,
+                )
+            })
+            .await
+    }
+
+    /// Return a stream of the pages of [Self::get_owned_objects_multi], ending after the first
+    /// error.
+    pub fn get_owned_objects_multi_pages(
+        &self,
+        addresses: Vec<MgoAddress>,
+        query: Option<MgoObjectResponseQuery>,
+        limit: Option<usize>,
+    ) -> impl Stream<Item = MgoRpcResult<MultiOwnerObjectsPage>> + '_ {
+        pages(move |cursor| {
+            self.get_owned_objects_multi(addresses.clone(), query.clone(), cursor, limit)
+        })
+    }
+
+    /// Return a paginated response with the objects owned by `address` at the end of
+    /// `checkpoint`, or an error upon failure.
+    pub async fn get_owned_objects_at_checkpoint(
+        &self,
+        address: MgoAddress,
+        checkpoint: BigInt<u64>,
+        query: Option<MgoObjectResponseQuery>,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> MgoRpcResult<ObjectsPage> {
+        self.retry_config
+            .retry(|| {
+                self.api.http.get_owned_objects_at_checkpoint(
+                    address,
+                    checkpoint,
+                    query.clone(),
+                    cursor,
+                    limit,
+                )
+            })
+            .await
+    }
+
+    /// Return a stream of the pages of [Self::get_owned_objects_at_checkpoint], ending after the
+    /// first error.
+    pub fn get_owned_objects_at_checkpoint_pages(
+        &self,
+        address: MgoAddress,
+        checkpoint: BigInt<u64>,
+        query: Option<MgoObjectResponseQuery>,
+        limit: Option<usize>,
+    ) -> impl Stream<Item = MgoRpcResult<ObjectsPage>> + '_ {
+        pages(move |cursor| {
+            self.get_owned_objects_at_checkpoint(address, checkpoint, query.clone(), cursor, limit)
+        })
+    }
+
+    /// Return the network metrics, or an error upon failure.
+    pub async fn get_network_metrics(&self) -> MgoRpcResult<NetworkMetrics> {
+        self.retry_config
+            .retry(|| self.api.http.get_network_metrics())
+            .await
+    }
+
+    /// Return the Move call metrics, or an error upon failure.
+    pub async fn get_move_call_metrics(&self) -> MgoRpcResult<MoveCallMetrics> {
+        self.retry_config
+            .retry(|| self.api.http.get_move_call_metrics())
+            .await
+    }
+
+    /// Return the address metrics of the latest checkpoint, or an error upon failure.
+    pub async fn get_latest_address_metrics(&self) -> MgoRpcResult<AddressMetrics> {
+        self.retry_config
+            .retry(|| self.api.http.get_latest_address_metrics())
+            .await
+    }
+
+    /// Return the address metrics of `checkpoint`, or an error upon failure.
+    pub async fn get_checkpoint_address_metrics(
+        &self,
+        checkpoint: u64,
+    ) -> MgoRpcResult<AddressMetrics> {
+        self.retry_config
+            .retry(|| self.api.http.get_checkpoint_address_metrics(checkpoint))
+            .await
+    }
+
+    /// Return the address metrics of the last checkpoint of every epoch, or an error upon failure.
+    pub async fn get_all_epoch_address_metrics(
+        &self,
+        descending_order: Option<bool>,
+    ) -> MgoRpcResult<Vec<AddressMetrics>> {
+        self.retry_config
+            .retry(|| {
+                self.api
+                    .http
+                    .get_all_epoch_address_metrics(descending_order)
+            })
+            .await
+    }
+
+    /// Return the number of distinct senders of every UTC day from `from_day` to `to_day`, or an
+    /// error upon failure.
+    pub async fn get_active_address_stats(
+        &self,
+        from_day: BigInt<u64>,
+        to_day: BigInt<u64>,
+    ) -> MgoRpcResult<ActiveAddressStats> {
+        self.retry_config
+            .retry(|| self.api.http.get_active_address_stats(from_day, to_day))
+            .await
+    }
+
+    /// Return the retention of the addresses first seen on `cohort_day` over the following
+    /// `horizon_days`, or an error upon failure.
+    pub async fn get_retention(
+        &self,
+        cohort_day: BigInt<u64>,
+        horizon_days: BigInt<u64>,
+    ) -> MgoRpcResult<CohortRetention> {
+        self.retry_config
+            .retry(|| self.api.http.get_retention(cohort_day, horizon_days))
+            .await
+    }
+
+    /// Return a paginated response with the coin types seen on chain, or an error upon failure.
+    pub async fn get_coin_types(
+        &self,
+        cursor: Option<String>,
+        limit: Option<usize>,
+        order_by: Option<CoinTypeOrder>,
+    ) -> MgoRpcResult<CoinTypesPage> {
+        self.retry_config
+            .retry(|| {
+                self.api
+                    .http
+                    .get_coin_types(cursor.clone(), limit, order_by)
+            })
+            .await
+    }
+
+    /// Return a stream of the pages of [Self::get_coin_types], ending after the first error.
+    pub fn get_coin_types_pages(
+        &self,
+        limit: Option<usize>,
+        order_by: Option<CoinTypeOrder>,
+    ) -> impl Stream<Item = MgoRpcResult<CoinTypesPage>> + '_ {
+        pages(move |cursor| self.get_coin_types(cursor, limit, order_by))
+    }
+
+    /// Return the total number of transactions, or an error upon failure.
+    pub async fn get_total_transactions(&self) -> MgoRpcResult<u64> {
+        Ok(*self
+            .retry_config
+            .retry(|| self.api.http.get_total_transactions())
+            .await?)
+    }
+
+    /// Return contention statistics for a shared object over the most recent checkpoints, or an
+    /// error upon failure.
+    pub async fn get_shared_object_stats(
+        &self,
+        object_id: ObjectID,
+        window_checkpoints: Option<BigInt<u64>>,
+    ) -> MgoRpcResult<SharedObjectStats> {
+        self.retry_config
+            .retry(|| {
+                self.api
+                    .http
+                    .get_shared_object_stats(object_id, window_checkpoints)
+            })
+            .await
+    }
+
+    /// Return the bytecode of every function of a published Move module, or an error upon
+    /// failure.
+    pub async fn get_module_disassembly(
+        &self,
+        package: ObjectID,
+        module: String,
+    ) -> MgoRpcResult<ModuleDisassembly> {
+        self.retry_config
+            .retry(|| {
+                self.api
+                    .http
+                    .get_module_disassembly(package, module.clone())
+            })
+            .await
+    }
+
+    /// Return the function, and the source location when known, of an abort, or an error upon
+    /// failure.
+    pub async fn resolve_abort_location(
+        &self,
+        package: ObjectID,
+        module: String,
+        function_index: u16,
+        code_offset: u16,
+    ) -> MgoRpcResult<AbortLocation> {
+        self.retry_config
+            .retry(|| {
+                self.api.http.resolve_abort_location(
+                    package,
+                    module.clone(),
+                    function_index,
+                    code_offset,
+                )
+            })
+            .await
+    }
+
+    /// Return the decoded values stored under the BCS encoded `keys` of a Move table, or an error
+    /// upon failure.
+    pub async fn get_table_values(
+        &self,
+        parent_object_id: ObjectID,
+        key_type: String,
+        value_type: String,
+        keys: Vec<Base64>,
+    ) -> MgoRpcResult<Vec<TableValue>> {
+        self.retry_config
+            .retry(|| {
+                self.api.http.get_table_values(
+                    parent_object_id,
+                    key_type.clone(),
+                    value_type.clone(),
+                    keys.clone(),
+                )
+            })
+            .await
+    }
+
+    /// Return a paginated response with the events referencing an object, among the event types
+    /// the indexer indexes object references of, or an error upon failure.
+    pub async fn query_events_by_object(
+        &self,
+        object_id: ObjectID,
+        cursor: Option<EventID>,
+        limit: Option<usize>,
+        descending_order: bool,
+    ) -> MgoRpcResult<EventsByObjectPage> {
+        self.retry_config
+            .retry(|| {
+                self.api.http.query_events_by_object(
+                    object_id,
+                    cursor,
+                    limit,
+                    Some(descending_order),
+                )
+            })
+            .await
+    }
+
+    /// Return the estimated row count, on-disk size and growth of every table of the indexer,
+    /// as last sampled by its writer, or an error upon failure.
+    pub async fn get_storage_stats(&self) -> MgoRpcResult<StorageStats> {
+        self.retry_config
+            .retry(|| self.api.http.get_storage_stats())
+            .await
+    }
+
+    /// Return the supply of a coin type at the end of every epoch or day from `from` to `to`,
+    /// with the amounts minted and burned within each, or an error upon failure.
+    pub async fn get_supply_history(
+        &self,
+        coin_type: String,
+        granularity: SupplyGranularity,
+        from: BigInt<u64>,
+        to: BigInt<u64>,
+    ) -> MgoRpcResult<SupplyHistory> {
+        self.retry_config
+            .retry(|| {
+                self.api
+                    .http
+                    .get_supply_history(coin_type.clone(), granularity, from, to)
+            })
+            .await
+    }
+
+    /// Return the packages a package links to, at its latest version unless `version` is
+    /// given, or an error upon failure.
+    pub async fn get_package_dependencies(
+        &self,
+        package_id: ObjectID,
+        version: Option<BigInt<u64>>,
+    ) -> MgoRpcResult<Vec<PackageDependency>> {
+        self.retry_config
+            .retry(|| self.api.http.get_package_dependencies(package_id, version))
+            .await
+    }
+
+    /// Return a paginated response with the packages whose latest version links to a package,
+    /// or an error upon failure.
+    pub async fn get_package_dependents(
+        &self,
+        package_id: ObjectID,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> MgoRpcResult<PackageDependentsPage> {
+        self.retry_config
+            .retry(|| self.api.http.get_package_dependents(package_id, cursor, limit))
+            .await
+    }
+
+    /// Return a stream of the pages of [Self::get_package_dependents], ending after the first
+    /// error.
+    pub fn get_package_dependents_pages(
+        &self,
+        package_id: ObjectID,
+        limit: Option<usize>,
+    ) -> impl Stream<Item = MgoRpcResult<PackageDependentsPage>> + '_ {
+        pages(move |cursor| self.get_package_dependents(package_id, cursor, limit))
+    }
+
+    /// Return a paginated response with the dynamic object fields whose value object was
+    /// deleted or wrapped without the field being removed, of all parents or of a single one,
+    /// or an error upon failure.
+    pub async fn get_orphaned_dynamic_fields(
+        &self,
+        parent_object_id: Option<ObjectID>,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> MgoRpcResult<OrphanedDynamicFieldsPage> {
+        self.retry_config
+            .retry(|| {
+                self.api
+                    .http
+                    .get_orphaned_dynamic_fields(parent_object_id, cursor, limit)
+            })
+            .await
+    }
+
+    /// Return the chain identifier, the latest checkpoint with the reference gas price and
+    /// protocol version of its epoch, and the namespaces the server serves, in a single call,
+    /// or an error upon failure.
+    pub async fn get_chain_state(&self) -> MgoRpcResult<ChainState> {
+        self.retry_config
+            .retry(|| self.api.http.get_chain_state())
+            .await
+    }
+
+    /// Return how the modules of a local build of a package compare to those of a published
+    /// version of it, with the versions of the package, or an error upon failure.
+    pub async fn verify_package(
+        &self,
+        package_id: ObjectID,
+        modules: Vec<Base64>,
+    ) -> MgoRpcResult<PackageVerification> {
+        self.retry_config
+            .retry(|| self.api.http.verify_package(package_id, modules.clone()))
+            .await
+    }
+
+    /// Return a paginated response with the intervals of checkpoints over which an object kept
+    /// the same owner, oldest first, or an error upon failure.
+    pub async fn get_object_owners_history(
+        &self,
+        object_id: ObjectID,
+        cursor: Option<SequenceNumber>,
+        limit: Option<usize>,
+    ) -> MgoRpcResult<ObjectOwnersHistoryPage> {
+        self.retry_config
+            .retry(|| {
+                self.api
+                    .http
+                    .get_object_owners_history(object_id, cursor, limit)
+            })
+            .await
+    }
+}
+
+/// How the [ExtendedApi] retries failed requests. Only requests that failed to reach the server
+/// or timed out are retried, errors returned by the server are not.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryConfig {
+    /// Number of retries after the first attempt, `0` to never retry.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for every following one.
+    pub initial_backoff: Duration,
+    /// Longest delay between two attempts.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Delay before the retry following `retries` previous ones.
+    fn backoff(&self, retries: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retries))
+            .min(self.max_backoff)
+    }
+
+    async fn retry<T, F, Fut>(&self, mut request: F) -> MgoRpcResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, jsonrpsee::core::Error>>,
+    {
+        let mut retries = 0;
+        loop {
+            match request().await {
+                Err(
+                    jsonrpsee::core::Error::Transport(_) | jsonrpsee::core::Error::RequestTimeout,
+                ) if retries < self.max_retries => {
+                    tokio::time::sleep(self.backoff(retries)).await;
+                    retries += 1;
+                }
+                result => return Ok(result?),
+            }
+        }
+    }
+}
+
+/// Return a stream of the pages of a paginated endpoint, fetched with `fetch` from the first page
+/// on. The stream ends after the last page, or after the first error.
+fn pages<T, C, F, Fut>(fetch: F) -> impl Stream<Item = MgoRpcResult<Page<T, C>>>
+where
+    C: Clone,
+    F: FnMut(Option<C>) -> Fut,
+    Fut: Future<Output = MgoRpcResult<Page<T, C>>>,
+{
+    stream::unfold((Some(None), fetch), |(cursor, mut fetch)| async move {
+        let page = fetch(cursor?).await;
+        let next_cursor = match &page {
+            Ok(page) if page.has_next_page => page.next_cursor.clone().map(Some),
+            _ => None,
+        };
+        Some((page, (next_cursor, fetch)))
+    })
 }
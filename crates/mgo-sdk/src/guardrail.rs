@@ -0,0 +1,138 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Policies checked against the dry run of a transaction before the wallet signs it, to catch
+//! mistyped amounts or recipients before they cost anything. All policies are off by default.
+
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use mgo_json_rpc_types::{DryRunTransactionBlockResponse, ObjectChange};
+use mgo_types::base_types::MgoAddress;
+use mgo_types::gas_coin::GAS;
+use mgo_types::object::Owner;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GuardrailConfig {
+    /// Largest amount of MIST, gas included, a transaction may take out of the sender's balance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_mgo_outflow: Option<u64>,
+    /// Deny transactions sending objects to addresses that are neither managed by the wallet
+    /// nor in `known_recipients`.
+    #[serde(default)]
+    pub deny_unknown_recipients: bool,
+    /// Deny transactions publishing a package.
+    #[serde(default)]
+    pub deny_publish: bool,
+    /// Recipients confirmed by the user, which no longer trigger `deny_unknown_recipients`.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub known_recipients: BTreeSet<MgoAddress>,
+}
+
+/// What the wallet does with a transaction breaking any of the policies.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GuardrailConfirmation {
+    /// Ask for confirmation on the terminal.
+    Prompt,
+    /// Refuse to sign the transaction.
+    #[default]
+    Reject,
+    /// Sign the transaction anyway, as `--yes` does.
+    Accept,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GuardrailViolation {
+    MgoOutflowExceeded { outflow: u64, max: u64 },
+    UnknownRecipient(MgoAddress),
+    Publish,
+}
+
+impl Display for GuardrailViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MgoOutflowExceeded { outflow, max } => write!(
+                f,
+                "Transaction spends {outflow} MIST, more than the maximum of {max} MIST"
+            ),
+            Self::UnknownRecipient(address) => {
+                write!(f, "Transaction sends objects to unknown address {address}")
+            }
+            Self::Publish => write!(f, "Transaction publishes a package"),
+        }
+    }
+}
+
+impl GuardrailConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.max_mgo_outflow.is_some() || self.deny_unknown_recipients || self.deny_publish
+    }
+
+    /// Policies broken by the transaction of `sender` whose dry run is `dry_run`. `managed` are
+    /// the addresses of the wallet, which are always known recipients.
+    pub fn check(
+        &self,
+        sender: MgoAddress,
+        dry_run: &DryRunTransactionBlockResponse,
+        managed: &[MgoAddress],
+    ) -> Vec<GuardrailViolation> {
+        let mut violations = vec![];
+
+        if let Some(max) = self.max_mgo_outflow {
+            let gas = GAS::type_tag();
+            let balance_change: i128 = dry_run
+                .balance_changes
+                .iter()
+                .filter(|change| {
+                    change.owner == Owner::AddressOwner(sender) && change.coin_type == gas
+                })
+                .map(|change| change.amount)
+                .sum();
+            let outflow = u64::try_from(-balance_change.min(0)).unwrap_or(u64::MAX);
+            if outflow > max {
+                violations.push(GuardrailViolation::MgoOutflowExceeded { outflow, max });
+            }
+        }
+
+        if self.deny_unknown_recipients {
+            let recipients = dry_run
+                .object_changes
+                .iter()
+                .filter_map(|change| match change {
+                    ObjectChange::Transferred { recipient, .. } => Some(recipient),
+                    ObjectChange::Created { owner, .. } | ObjectChange::Mutated { owner, .. } => {
+                        Some(owner)
+                    }
+                    _ => None,
+                })
+                .filter_map(|owner| match owner {
+                    Owner::AddressOwner(address) => Some(*address),
+                    _ => None,
+                })
+                .collect::<BTreeSet<_>>();
+            violations.extend(
+                recipients
+                    .into_iter()
+                    .filter(|address| {
+                        *address != sender
+                            && !managed.contains(address)
+                            && !self.known_recipients.contains(address)
+                    })
+                    .map(GuardrailViolation::UnknownRecipient),
+            );
+        }
+
+        if self.deny_publish
+            && dry_run
+                .object_changes
+                .iter()
+                .any(|change| matches!(change, ObjectChange::Published { .. }))
+        {
+            violations.push(GuardrailViolation::Publish);
+        }
+
+        violations
+    }
+}
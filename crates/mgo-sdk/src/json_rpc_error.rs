@@ -1,7 +1,9 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 use jsonrpsee::types::{error::UNKNOWN_ERROR_CODE, ErrorObjectOwned};
-pub use mgo_json_rpc_api::{TRANSACTION_EXECUTION_CLIENT_ERROR_CODE, TRANSIENT_ERROR_CODE};
+pub use mgo_json_rpc_api::{
+    ClientErrorCode, TRANSACTION_EXECUTION_CLIENT_ERROR_CODE, TRANSIENT_ERROR_CODE,
+};
 use thiserror::Error;
 
 #[derive(Error, Debug, Clone)]
@@ -37,7 +39,12 @@ impl Error {
                 | METHOD_NOT_FOUND_CODE
                 | BATCHES_NOT_SUPPORTED_CODE
                 | TRANSACTION_EXECUTION_CLIENT_ERROR_CODE
-        )
+        ) || self.client_error_code().is_some()
+    }
+
+    /// What the request did wrong, for errors the server answered with a [`ClientErrorCode`].
+    pub fn client_error_code(&self) -> Option<ClientErrorCode> {
+        ClientErrorCode::from_code(self.code)
     }
 
     pub fn is_execution_error(&self) -> bool {
@@ -12,11 +12,15 @@
 //! as following:
 //! * [CoinReadApi] - provides read-only functions to work with the coins
 //! * [EventApi] - provides event related functions functions to
+//! * [ExtendedApi] - provides the endpoints only served by the indexer, such as
+//! epochs, network metrics, and object queries by checkpoint
 //! * [GovernanceApi] - provides functionality related to staking
 //! * [QuorumDriverApi] - provides functionality to execute a transaction
 //! block and submit it to the fullnode(s)
 //! * [ReadApi] - provides functions for retrieving data about different
 //! objects and transactions
+//! * [SupportApi] - provides the state export served to support tooling by
+//! fullnodes that enable it
 //! * [TransactionBuilder] - provides functions for building transactions
 //!
 //! # Usage
@@ -95,11 +99,15 @@ use mgo_transaction_builder::{DataReader, TransactionBuilder};
 pub use mgo_types as types;
 use mgo_types::base_types::{ObjectID, ObjectInfo, MgoAddress};
 
-use crate::apis::{CoinReadApi, EventApi, GovernanceApi, QuorumDriverApi, ReadApi};
+use crate::apis::{
+    CoinReadApi, EventApi, ExtendedApi, GovernanceApi, QuorumDriverApi, ReadApi, RetryConfig,
+    SupportApi,
+};
 use crate::error::{Error, MgoRpcResult};
 
 pub mod apis;
 pub mod error;
+pub mod guardrail;
 pub mod json_rpc_error;
 pub mod mgo_client_config;
 pub mod wallet_context;
@@ -117,7 +125,8 @@ pub const MGO_TESTNET_URL: &str = "https://fullnode.testnet.mangonetwork.io:443"
 /// `max_concurrent_requests` function, and the `request_timeout` function.
 /// If you use the WebSocket, consider setting the `ws_ping_interval` field to a
 /// value of your choice to prevent the inactive WS subscription being
-/// disconnected due to proxy timeout.
+/// disconnected due to proxy timeout. Requests of the [ExtendedApi] are retried
+/// as set by the `retry_config` function.
 ///
 /// # Examples
 ///
@@ -138,6 +147,7 @@ pub struct MgoClientBuilder {
     max_concurrent_requests: usize,
     ws_url: Option<String>,
     ws_ping_interval: Option<Duration>,
+    retry_config: RetryConfig,
 }
 
 impl Default for MgoClientBuilder {
@@ -147,6 +157,7 @@ impl Default for MgoClientBuilder {
             max_concurrent_requests: 256,
             ws_url: None,
             ws_ping_interval: None,
+            retry_config: RetryConfig::default(),
         }
     }
 }
@@ -176,6 +187,12 @@ impl MgoClientBuilder {
         self
     }
 
+    /// Set how the requests of the [ExtendedApi] are retried
+    pub fn retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
     /// Returns a [MgoClient] object connected to the Mgo network running at the URI provided.
     ///
     /// # Examples
@@ -240,6 +257,8 @@ impl MgoClientBuilder {
         let transaction_builder = TransactionBuilder::new(read_api.clone());
         let coin_read_api = CoinReadApi::new(api.clone());
         let governance_api = GovernanceApi::new(api.clone());
+        let extended_api = ExtendedApi::new(api.clone(), self.retry_config);
+        let support_api = SupportApi::new(api.clone());
 
         Ok(MgoClient {
             api,
@@ -249,6 +268,8 @@ impl MgoClientBuilder {
             event_api,
             quorum_driver_api,
             governance_api,
+            extended_api,
+            support_api,
         })
     }
 
@@ -413,6 +434,8 @@ pub struct MgoClient {
     event_api: EventApi,
     quorum_driver_api: QuorumDriverApi,
     governance_api: GovernanceApi,
+    extended_api: ExtendedApi,
+    support_api: SupportApi,
 }
 
 pub(crate) struct RpcClient {
@@ -480,6 +503,11 @@ impl MgoClient {
         &self.event_api
     }
 
+    /// Returns a reference to the extended API, which is only served by the indexer.
+    pub fn extended_api(&self) -> &ExtendedApi {
+        &self.extended_api
+    }
+
     /// Returns a reference to the governance API.
     pub fn governance_api(&self) -> &GovernanceApi {
         &self.governance_api
@@ -495,6 +523,11 @@ impl MgoClient {
         &self.read_api
     }
 
+    /// Returns a reference to the support API, which is only served by fullnodes that enable it.
+    pub fn support_api(&self) -> &SupportApi {
+        &self.support_api
+    }
+
     /// Returns a reference to the transaction builder API.
     pub fn transaction_builder(&self) -> &TransactionBuilder {
         &self.transaction_builder
@@ -537,6 +570,7 @@ impl DataReader for ReadApi {
                 data,
                 next_cursor,
                 has_next_page,
+                ..
             } = self
                 .get_owned_objects(address, query.clone(), cursor, None)
                 .await?;
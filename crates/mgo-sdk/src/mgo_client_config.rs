@@ -7,6 +7,7 @@ use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
+use crate::guardrail::GuardrailConfig;
 use crate::{MgoClient, MgoClientBuilder, MGO_DEVNET_URL, MGO_LOCAL_NETWORK_URL, MGO_TESTNET_URL};
 use mgo_config::Config;
 use mgo_keys::keystore::{AccountKeystore, Keystore};
@@ -19,6 +20,9 @@ pub struct MgoClientConfig {
     pub envs: Vec<MgoEnv>,
     pub active_env: Option<String>,
     pub active_address: Option<MgoAddress>,
+    /// Policies checked before signing a transaction, see [`GuardrailConfig`].
+    #[serde(default)]
+    pub guardrails: GuardrailConfig,
 }
 
 impl MgoClientConfig {
@@ -28,6 +32,7 @@ impl MgoClientConfig {
             envs: vec![],
             active_env: None,
             active_address: None,
+            guardrails: GuardrailConfig::default(),
         }
     }
 
@@ -1,10 +1,12 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::guardrail::{GuardrailConfirmation, GuardrailViolation};
 use crate::mgo_client_config::MgoClientConfig;
 use crate::MgoClient;
-use anyhow::anyhow;
+use anyhow::{anyhow, bail};
 use colored::Colorize;
+use serde::Serialize;
 use shared_crypto::intent::Intent;
 use std::collections::BTreeSet;
 use std::path::Path;
@@ -14,8 +16,10 @@ use mgo_json_rpc_types::{
     MgoObjectData, MgoObjectDataFilter, MgoObjectDataOptions, MgoObjectResponse,
     MgoObjectResponseQuery, MgoTransactionBlockResponse, MgoTransactionBlockResponseOptions,
 };
+use mgo_keys::agent::AgentClient;
 use mgo_keys::keystore::AccountKeystore;
 use mgo_types::base_types::{ObjectID, ObjectRef, MgoAddress};
+use mgo_types::crypto::Signature;
 use mgo_types::gas_coin::GasCoin;
 use mgo_types::transaction::{Transaction, TransactionData, TransactionDataAPI};
 use tokio::sync::RwLock;
@@ -26,6 +30,7 @@ pub struct WalletContext {
     request_timeout: Option<std::time::Duration>,
     client: Arc<RwLock<Option<MgoClient>>>,
     max_concurrent_requests: Option<u64>,
+    guardrail_confirmation: GuardrailConfirmation,
 }
 
 impl WalletContext {
@@ -47,10 +52,16 @@ impl WalletContext {
             request_timeout,
             client: Default::default(),
             max_concurrent_requests,
+            guardrail_confirmation: GuardrailConfirmation::default(),
         };
         Ok(context)
     }
 
+    /// How transactions breaking the guardrail policies are handled, rejected by default.
+    pub fn set_guardrail_confirmation(&mut self, confirmation: GuardrailConfirmation) {
+        self.guardrail_confirmation = confirmation;
+    }
+
     pub fn get_addresses(&self) -> Vec<MgoAddress> {
         self.config.keystore.addresses()
     }
@@ -278,11 +289,93 @@ impl WalletContext {
         Ok(gas_price)
     }
 
+    /// Sign `msg` for `address` with the key unlocked in the session agent, or with the
+    /// keystore if the agent is not running or does not hold that key.
+    pub fn sign_secure<T>(
+        &self,
+        address: &MgoAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, anyhow::Error>
+    where
+        T: Serialize,
+    {
+        if let Some(agent) = AgentClient::for_keystore(&self.config.keystore) {
+            match agent.sign_secure(address, msg, intent.clone()) {
+                Ok(Some(signature)) => return Ok(signature),
+                Ok(None) => (),
+                Err(e) => warn!("Cannot sign with the key agent, using the keystore instead: {e}"),
+            }
+        }
+        Ok(self.config.keystore.sign_secure(address, msg, intent)?)
+    }
+
+    /// Dry run `tx_data`, with the gas coins it will be executed with, and check the result
+    /// against the guardrail policies of the config. A transaction breaking any of them is
+    /// handled as the [`GuardrailConfirmation`] of the context says, and the recipients it was
+    /// confirmed for are remembered as known ones.
+    pub async fn check_guardrails(&mut self, tx_data: &TransactionData) -> anyhow::Result<()> {
+        if !self.config.guardrails.is_enabled() {
+            return Ok(());
+        }
+        let client = self.get_client().await?;
+        let dry_run = client
+            .read_api()
+            .dry_run_transaction_block(tx_data.clone())
+            .await?;
+        let violations =
+            self.config
+                .guardrails
+                .check(tx_data.sender(), &dry_run, &self.get_addresses());
+        if violations.is_empty() {
+            return Ok(());
+        }
+
+        for violation in &violations {
+            eprintln!("{}", format!("[guardrail] {violation}").yellow().bold());
+        }
+        match self.guardrail_confirmation {
+            GuardrailConfirmation::Prompt => {
+                eprint!("Sign the transaction anyway? [y/N] ");
+                let mut answer = String::new();
+                std::io::stdin().read_line(&mut answer)?;
+                if answer.trim().to_lowercase() != "y" {
+                    bail!("Transaction was not confirmed");
+                }
+            }
+            GuardrailConfirmation::Reject => bail!(
+                "Transaction rejected by the wallet guardrails: {}. Pass `--yes` to sign it anyway",
+                violations
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            ),
+            GuardrailConfirmation::Accept => {
+                warn!("Signing a transaction breaking the guardrails, as they are overridden")
+            }
+        }
+
+        let confirmed_recipients = violations
+            .into_iter()
+            .filter_map(|violation| match violation {
+                GuardrailViolation::UnknownRecipient(address) => Some(address),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if !confirmed_recipients.is_empty() {
+            self.config
+                .guardrails
+                .known_recipients
+                .extend(confirmed_recipients);
+            self.config.save()?;
+        }
+        Ok(())
+    }
+
     /// Sign a transaction with a key currently managed by the WalletContext
     pub fn sign_transaction(&self, data: &TransactionData) -> Transaction {
         let sig = self
-            .config
-            .keystore
             .sign_secure(&data.sender(), data, Intent::mgo_transaction())
             .unwrap();
         // TODO: To support sponsored transaction, we should also look at the gas owner.
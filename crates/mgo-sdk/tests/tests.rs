@@ -1,8 +1,11 @@
 // Copyright (c) MangoNet Labs Ltd.
 // SPDX-License-Identifier: Apache-2.0
+use std::collections::BTreeSet;
 use tempfile::TempDir;
 
+use mgo_json_rpc_api::ExtendedApiOpenRpc;
 use mgo_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
+use mgo_sdk::apis::ExtendedApi;
 use mgo_types::crypto::Ed25519MgoSignature;
 use mgo_types::crypto::{SignatureScheme, MgoSignatureInner};
 #[test]
@@ -32,3 +35,16 @@ fn keystore_display_test() -> Result<(), anyhow::Error> {
     assert!(!keystore.to_string().contains("keys:"));
     Ok(())
 }
+
+#[test]
+fn extended_api_methods_test() {
+    let server_methods: BTreeSet<_> = ExtendedApiOpenRpc::module_doc()
+        .method_names()
+        .map(str::to_string)
+        .collect();
+    let client_methods: BTreeSet<_> = ExtendedApi::METHODS
+        .iter()
+        .map(|method| method.to_string())
+        .collect();
+    assert_eq!(client_methods, server_methods);
+}
@@ -5,11 +5,10 @@ use async_trait::async_trait;
 use bytes::Bytes;
 use futures::stream::{self, StreamExt};
 use hyper::client::HttpConnector;
-use hyper::header::{HeaderValue, CONTENT_LENGTH};
-use hyper::Client;
-use hyper::Uri;
+use hyper::header::{HeaderMap, HeaderValue, CONTENT_LENGTH};
+use hyper::{Body, Client, Response, StatusCode, Uri};
 use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use std::str::FromStr;
 use std::sync::Arc;
 use mgo_types::base_types::{ObjectID, SequenceNumber, VersionNumber};
@@ -26,12 +25,17 @@ use mgo_types::{
     },
     transaction::Transaction,
 };
-use tap::TapFallible;
 use tracing::{error, info, instrument, trace, warn};
+use typed_store::TypedStoreError;
 use url::Url;
 
 use crate::key_value_store::{TransactionKeyValueStore, TransactionKeyValueStoreTrait};
 use crate::key_value_store_metrics::KeyValueStoreMetrics;
+use crate::kv_payload::{decode_kv_payload, KVPayload, KVPayloadError};
+
+/// Header carrying the [`TypedStoreError`] code of a read that failed on the server, whose
+/// message is the body of the response. Failed responses without it read as a missing key.
+pub const STORE_ERROR_CODE_HEADER: &str = "x-mgo-store-error-code";
 
 pub struct HttpKVStore {
     base_url: Url,
@@ -182,26 +186,74 @@ impl HttpKVStore {
                 .get(CONTENT_LENGTH)
                 .unwrap_or(&HeaderValue::from_static("0"))
         );
-        // return None if 400
         if resp.status().is_success() {
             hyper::body::to_bytes(resp.into_body())
                 .await
                 .map(Some)
                 .into_mgo_result()
+        } else if let Some(error) = read_store_error(resp).await {
+            warn!("Remote store failed to read uri {}: {:?}", uri, error);
+            Err(MgoError::RemoteStorage(error))
         } else {
+            // return None if 400
             Ok(None)
         }
     }
 }
 
-fn deser<K, T>(key: &K, bytes: &[u8]) -> Option<T>
+/// Response of a key-value store server to a read that failed with `error`, which
+/// [`HttpKVStore`] decodes back into the same error.
+pub fn store_error_response(error: &TypedStoreError) -> Response<Body> {
+    let (code, message) = error.to_code_and_message();
+    let status = if error.is_retryable() {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    Response::builder()
+        .status(status)
+        .header(STORE_ERROR_CODE_HEADER, code)
+        .body(Body::from(message))
+        .expect("store error response is valid")
+}
+
+fn store_error_code(headers: &HeaderMap) -> Option<u32> {
+    headers
+        .get(STORE_ERROR_CODE_HEADER)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+}
+
+/// The store error carried by a failed response, if it carries one.
+async fn read_store_error(resp: Response<Body>) -> Option<TypedStoreError> {
+    let code = store_error_code(resp.headers())?;
+    let message = match hyper::body::to_bytes(resp.into_body()).await {
+        Ok(body) => String::from_utf8_lossy(&body).into_owned(),
+        Err(e) => format!("failed to read error message: {e}"),
+    };
+    Some(TypedStoreError::from_code_and_message(code, message))
+}
+
+/// Undecodable payloads are logged and treated as missing, except for payloads written in a
+/// version newer than this node reads, which fail the whole read.
+fn deser<K, T>(key: &K, bytes: &[u8]) -> MgoResult<Option<T>>
 where
     K: std::fmt::Debug,
-    T: for<'de> Deserialize<'de>,
+    T: KVPayload,
 {
-    bcs::from_bytes(bytes)
-        .tap_err(|e| warn!("Error deserializing data for key {:?}: {:?}", key, e))
-        .ok()
+    match decode_kv_payload(bytes) {
+        Ok(value) => Ok(Some(value)),
+        Err(e @ KVPayloadError::UnsupportedVersion(_)) => {
+            error!("Error deserializing data for key {:?}: {}", key, e);
+            Err(MgoError::Storage(e.to_string()))
+        }
+        Err(e) => {
+            warn!("Error deserializing data for key {:?}: {}", key, e);
+            Ok(None)
+        }
+    }
 }
 
 fn map_fetch<'a, K>(fetch: (&'a MgoResult<Option<Bytes>>, &'a K)) -> Option<(&'a Bytes, &'a K)>
@@ -236,12 +288,12 @@ fn deser_check_digest<T, D: std::fmt::Debug>(
     digest: &D,
     bytes: &Bytes,
     get_expected_digest: impl FnOnce(&T) -> D,
-) -> Option<T>
+) -> MgoResult<Option<T>>
 where
     D: std::fmt::Debug + PartialEq,
-    T: for<'de> Deserialize<'de>,
+    T: KVPayload,
 {
-    deser(digest, bytes).and_then(|o: T| {
+    Ok(deser(digest, bytes)?.and_then(|o: T| {
         let expected_digest = get_expected_digest(&o);
         if expected_digest == *digest {
             Some(o)
@@ -252,7 +304,7 @@ where
             );
             None
         }
-    })
+    }))
 }
 
 #[async_trait]
@@ -290,11 +342,11 @@ impl TransactionKeyValueStoreTrait for HttpKVStore {
             .zip(transactions.iter())
             .map(map_fetch)
             .map(|maybe_bytes| {
-                maybe_bytes.and_then(|(bytes, digest)| {
+                maybe_bytes.map_or(Ok(None), |(bytes, digest)| {
                     deser_check_digest(digest, bytes, |tx: &Transaction| *tx.digest())
                 })
             })
-            .collect::<Vec<_>>();
+            .collect::<MgoResult<Vec<_>>>()?;
 
         let fx_results = fx_slice
             .iter()
@@ -302,13 +354,13 @@ impl TransactionKeyValueStoreTrait for HttpKVStore {
             .zip(effects.iter())
             .map(map_fetch)
             .map(|maybe_bytes| {
-                maybe_bytes.and_then(|(bytes, digest)| {
+                maybe_bytes.map_or(Ok(None), |(bytes, digest)| {
                     deser_check_digest(digest, bytes, |fx: &TransactionEffects| {
                         *fx.transaction_digest()
                     })
                 })
             })
-            .collect::<Vec<_>>();
+            .collect::<MgoResult<Vec<_>>>()?;
 
         let events_results = events_slice
             .iter()
@@ -316,11 +368,11 @@ impl TransactionKeyValueStoreTrait for HttpKVStore {
             .zip(events.iter())
             .map(map_fetch)
             .map(|maybe_bytes| {
-                maybe_bytes.and_then(|(bytes, digest)| {
+                maybe_bytes.map_or(Ok(None), |(bytes, digest)| {
                     deser_check_digest(digest, bytes, |events: &TransactionEvents| events.digest())
                 })
             })
-            .collect::<Vec<_>>();
+            .collect::<MgoResult<Vec<_>>>()?;
 
         Ok((txn_results, fx_results, events_results))
     }
@@ -379,41 +431,44 @@ impl TransactionKeyValueStoreTrait for HttpKVStore {
             .zip(checkpoint_summaries.iter())
             .map(map_fetch)
             .map(|maybe_bytes| {
-                maybe_bytes
-                    .and_then(|(bytes, seq)| deser::<_, CertifiedCheckpointSummary>(seq, bytes))
+                maybe_bytes.map_or(Ok(None), |(bytes, seq)| {
+                    deser::<_, CertifiedCheckpointSummary>(seq, bytes)
+                })
             })
-            .collect::<Vec<_>>();
+            .collect::<MgoResult<Vec<_>>>()?;
 
         let contents_results = result_slices[1]
             .iter()
             .zip(checkpoint_contents.iter())
             .map(map_fetch)
             .map(|maybe_bytes| {
-                maybe_bytes.and_then(|(bytes, seq)| deser::<_, CheckpointContents>(seq, bytes))
+                maybe_bytes.map_or(Ok(None), |(bytes, seq)| {
+                    deser::<_, CheckpointContents>(seq, bytes)
+                })
             })
-            .collect::<Vec<_>>();
+            .collect::<MgoResult<Vec<_>>>()?;
 
         let summaries_by_digest_results = result_slices[2]
             .iter()
             .zip(checkpoint_summaries_by_digest.iter())
             .map(map_fetch)
             .map(|maybe_bytes| {
-                maybe_bytes.and_then(|(bytes, digest)| {
+                maybe_bytes.map_or(Ok(None), |(bytes, digest)| {
                     deser_check_digest(digest, bytes, |s: &CertifiedCheckpointSummary| *s.digest())
                 })
             })
-            .collect::<Vec<_>>();
+            .collect::<MgoResult<Vec<_>>>()?;
 
         let contents_by_digest_results = result_slices[3]
             .iter()
             .zip(checkpoint_contents_by_digest.iter())
             .map(map_fetch)
             .map(|maybe_bytes| {
-                maybe_bytes.and_then(|(bytes, digest)| {
+                maybe_bytes.map_or(Ok(None), |(bytes, digest)| {
                     deser_check_digest(digest, bytes, |c: &CheckpointContents| *c.digest())
                 })
             })
-            .collect::<Vec<_>>();
+            .collect::<MgoResult<Vec<_>>>()?;
 
         Ok((
             summaries_results,
@@ -429,9 +484,10 @@ impl TransactionKeyValueStoreTrait for HttpKVStore {
         digest: TransactionDigest,
     ) -> MgoResult<Option<CheckpointSequenceNumber>> {
         let key = Key::TxToCheckpoint(digest);
-        self.fetch(key).await.map(|maybe| {
-            maybe.and_then(|bytes| deser::<_, CheckpointSequenceNumber>(&key, bytes.as_ref()))
-        })
+        match self.fetch(key).await? {
+            Some(bytes) => deser::<_, CheckpointSequenceNumber>(&key, bytes.as_ref()),
+            None => Ok(None),
+        }
     }
 
     #[instrument(level = "trace", skip_all)]
@@ -441,9 +497,10 @@ impl TransactionKeyValueStoreTrait for HttpKVStore {
         version: SequenceNumber,
     ) -> MgoResult<Option<Object>> {
         let key = Key::ObjectKey(object_id, version);
-        self.fetch(key)
-            .await
-            .map(|maybe| maybe.and_then(|bytes| deser::<_, Object>(&key, bytes.as_ref())))
+        match self.fetch(key).await? {
+            Some(bytes) => deser::<_, Object>(&key, bytes.as_ref()),
+            None => Ok(None),
+        }
     }
 
     #[instrument(level = "trace", skip_all)]
@@ -463,11 +520,71 @@ impl TransactionKeyValueStoreTrait for HttpKVStore {
             .zip(digests.iter())
             .map(map_fetch)
             .map(|maybe_bytes| {
-                maybe_bytes
-                    .and_then(|(bytes, key)| deser::<_, CheckpointSequenceNumber>(&key, bytes))
+                maybe_bytes.map_or(Ok(None), |(bytes, key)| {
+                    deser::<_, CheckpointSequenceNumber>(&key, bytes)
+                })
             })
-            .collect::<Vec<_>>();
+            .collect::<MgoResult<Vec<_>>>()?;
 
         Ok(results)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store_errors() -> Vec<TypedStoreError> {
+        vec![
+            TypedStoreError::RocksDBError("Resource busy: ".to_string()),
+            TypedStoreError::RocksDBError("Corruption: block checksum mismatch".to_string()),
+            TypedStoreError::SerializationError("unexpected end of input".to_string()),
+            TypedStoreError::UnregisteredColumn("objects".to_string()),
+            TypedStoreError::CrossDBBatch,
+            TypedStoreError::MetricsReporting,
+            TypedStoreError::RetryableTransactionError,
+            TypedStoreError::RetryableConflict {
+                key_hint: "objects".to_string(),
+            },
+            TypedStoreError::from_code_and_message(1000, "added by a newer node".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_store_error_serde_round_trip() {
+        for error in store_errors() {
+            let bytes = bcs::to_bytes(&error).unwrap();
+            assert_eq!(bcs::from_bytes::<TypedStoreError>(&bytes).unwrap(), error);
+            let json = serde_json::to_value(&error).unwrap();
+            assert_eq!(json["code"], error.code());
+            assert_eq!(
+                serde_json::from_value::<TypedStoreError>(json).unwrap(),
+                error
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_store_error_response_round_trip() {
+        for error in store_errors() {
+            let response = store_error_response(&error);
+            assert_eq!(
+                response.status() == StatusCode::SERVICE_UNAVAILABLE,
+                error.is_retryable()
+            );
+            let decoded = read_store_error(response).await.unwrap();
+            assert_eq!(decoded, error);
+            assert_eq!(
+                MgoError::RemoteStorage(decoded).is_retryable(),
+                (error.is_retryable(), true)
+            );
+        }
+
+        // Failed responses without an error code are missing keys.
+        let not_found = Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(read_store_error(not_found).await, None);
+    }
+}
@@ -4,7 +4,6 @@
 //! IndexStore supports creation of various ancillary indexes of state in MgoDataStore.
 //! The main user of this data is the explorer.
 
-use std::cmp::{max, min};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -31,6 +30,7 @@ use mgo_types::digests::TransactionEventsDigest;
 use mgo_types::dynamic_field::{self, DynamicFieldInfo};
 use mgo_types::effects::TransactionEvents;
 use mgo_types::error::{MgoError, MgoResult, UserInputError};
+use mgo_types::event::EventID;
 use mgo_types::inner_temporary_store::TxCoins;
 use mgo_types::object::{Object, Owner};
 use mgo_types::parse_mgo_struct_tag;
@@ -923,6 +923,28 @@ impl IndexStore {
         Ok(self.tables.transactions_seq.get(digest)?)
     }
 
+    /// The position `(tx_seq, event_seq)` that the event indexes are read from for a query
+    /// starting after `cursor`: the cursor event itself, which [`events_after_cursor`] then
+    /// drops, or the first event in the order of the query if there is no cursor.
+    pub fn event_cursor_position(
+        &self,
+        cursor: Option<&EventID>,
+        descending: bool,
+    ) -> MgoResult<(TxSequenceNumber, usize)> {
+        Ok(match cursor {
+            Some(cursor) => {
+                let tx_seq = self.get_transaction_seq(&cursor.tx_digest)?.ok_or(
+                    MgoError::TransactionNotFound {
+                        digest: cursor.tx_digest,
+                    },
+                )?;
+                (tx_seq, cursor.event_seq as usize)
+            }
+            None if descending => (u64::MAX, usize::MAX),
+            None => (0, 0),
+        })
+    }
+
     pub fn all_events(
         &self,
         tx_seq: TxSequenceNumber,
@@ -965,11 +987,17 @@ impl IndexStore {
         let seq = self
             .get_transaction_seq(digest)?
             .ok_or(MgoError::TransactionNotFound { digest: *digest })?;
+        // A position in another transaction is before or after all of the events of this one.
         Ok(if descending {
+            let position = if tx_seq > seq {
+                (seq, usize::MAX)
+            } else {
+                (tx_seq, event_seq)
+            };
             self.tables
                 .event_order
                 .unbounded_iter()
-                .skip_prior_to(&(min(tx_seq, seq), event_seq))?
+                .skip_prior_to(&position)?
                 .reverse()
                 .take_while(|((tx, _), _)| tx == &seq)
                 .take(limit)
@@ -978,10 +1006,15 @@ impl IndexStore {
                 })
                 .collect()
         } else {
+            let position = if tx_seq < seq {
+                (seq, 0)
+            } else {
+                (tx_seq, event_seq)
+            };
             self.tables
                 .event_order
                 .unbounded_iter()
-                .skip_to(&(max(tx_seq, seq), event_seq))?
+                .skip_to(&position)?
                 .take_while(|((tx, _), _)| tx == &seq)
                 .take(limit)
                 .map(|((_, event_seq), (digest, tx_digest, time))| {
@@ -1243,25 +1276,27 @@ impl IndexStore {
             .map(|((_, coin_type, obj_id), coin)| (coin_type, obj_id, coin)))
     }
 
+    /// Coins with a balance below `min_balance` are skipped without counting towards `limit`.
+    /// The cursor is a position in the index rather than a coin that has to match, so a page
+    /// resumes after the cursor even if the balance of the cursor coin has since dropped below
+    /// `min_balance`.
     pub fn get_owned_coins_iterator_with_cursor(
         &self,
         owner: MgoAddress,
         cursor: (String, ObjectID),
         limit: usize,
         one_coin_type_only: bool,
+        min_balance: Option<u64>,
     ) -> MgoResult<impl Iterator<Item = (String, ObjectID, CoinInfo)> + '_> {
         let (starting_coin_type, starting_object_id) = cursor;
+        let min_balance = min_balance.unwrap_or_default();
         Ok(self
             .tables
             .coin_index
             .unbounded_iter()
             .skip_to(&(owner, starting_coin_type.clone(), starting_object_id))?
             .filter(move |((_, _, obj_id), _)| obj_id != &starting_object_id)
-            .enumerate()
-            .take_while(move |(index, ((addr, coin_type, _), _))| {
-                if *index >= limit {
-                    return false;
-                }
+            .take_while(move |((addr, coin_type, _), _)| {
                 if addr != &owner {
                     return false;
                 }
@@ -1270,11 +1305,15 @@ impl IndexStore {
                 }
                 true
             })
-            .map(|(_, ((_, coin_type, obj_id), coin))| (coin_type, obj_id, coin)))
+            .filter(move |(_, coin)| coin.balance >= min_balance)
+            .take(limit)
+            .map(|((_, coin_type, obj_id), coin)| (coin_type, obj_id, coin)))
     }
 
     /// starting_object_id can be used to implement pagination, where a client remembers the last
     /// object id of each page, and use it to query the next page.
+    /// The filter is applied while iterating the index, so objects it excludes do not count
+    /// towards a page: `take(limit)` on the iterator yields a full page of matching objects.
     pub fn get_owner_objects_iterator(
         &self,
         owner: MgoAddress,
@@ -1457,6 +1496,75 @@ impl IndexStore {
         Ok(TotalBalance { balance, num_coins })
     }
 
+    /// Balances of `owner` for at most `limit` coin types, in the order of their canonical coin
+    /// type strings and starting after the coin type `cursor`. Pages are always read from the
+    /// database rather than the all-balances cache, so that a page costs `limit` coin types
+    /// however many types the owner holds.
+    pub async fn get_all_balance_paginated(
+        &self,
+        owner: MgoAddress,
+        cursor: Option<String>,
+        limit: usize,
+    ) -> MgoResult<Vec<(TypeTag, TotalBalance)>> {
+        let metrics_cloned = self.metrics.clone();
+        let coin_index_cloned = self.tables.coin_index.clone();
+        spawn_blocking(move || {
+            Self::get_balances_page_from_db(metrics_cloned, coin_index_cloned, owner, cursor, limit)
+        })
+        .await
+        .unwrap()
+        .map_err(|e| MgoError::ExecutionError(format!("Failed to read balances from DB: {:?}", e)))
+    }
+
+    /// Read a page of balances for a `MgoAddress` from the backend database. A `cursor` naming
+    /// a coin type the owner no longer holds resumes at the first coin type sorting after it.
+    pub fn get_balances_page_from_db(
+        metrics: Arc<IndexStoreMetrics>,
+        coin_index: DBMap<CoinIndexKey, CoinInfo>,
+        owner: MgoAddress,
+        cursor: Option<String>,
+        limit: usize,
+    ) -> MgoResult<Vec<(TypeTag, TotalBalance)>> {
+        metrics.all_balance_lookup_from_db.inc();
+        let start = match &cursor {
+            // No object ID sorts after `ObjectID::MAX`, so this lands after every coin of the
+            // cursor's type.
+            Some(cursor) => (owner, cursor.clone(), ObjectID::MAX),
+            None => (
+                owner,
+                String::from_utf8([0u8].to_vec()).unwrap(),
+                ObjectID::ZERO,
+            ),
+        };
+        let coins_by_type = coin_index
+            .unbounded_iter()
+            .skip_to(&start)?
+            .take_while(|((addr, _, _), _)| addr == &owner)
+            .filter(|((_, coin_type, _), _)| Some(coin_type) != cursor.as_ref())
+            .group_by(|((_, coin_type, _), _)| coin_type.clone());
+        let mut balances = vec![];
+        for (coin_type, coins) in (&coins_by_type).into_iter().take(limit) {
+            let mut total_balance = 0i128;
+            let mut coin_object_count = 0;
+            for (_, coin_info) in coins {
+                total_balance += coin_info.balance as i128;
+                coin_object_count += 1;
+            }
+            let coin_type =
+                TypeTag::Struct(Box::new(parse_mgo_struct_tag(&coin_type).map_err(|e| {
+                    MgoError::ExecutionError(format!("Failed to parse coin type: {:?}", e))
+                })?));
+            balances.push((
+                coin_type,
+                TotalBalance {
+                    num_coins: coin_object_count,
+                    balance: total_balance,
+                },
+            ));
+        }
+        Ok(balances)
+    }
+
     /// Read all balances for a `MgoAddress` from the backend database
     pub fn get_all_balances_from_db(
         metrics: Arc<IndexStoreMetrics>,
@@ -1582,18 +1690,48 @@ impl IndexStore {
     }
 }
 
+/// The page of at most `limit` events following `cursor`, out of `event_keys` read from the
+/// position of the cursor by one of the event indexes. These include the cursor event when it
+/// matches the query, and it is dropped then; otherwise the first event read already follows it.
+/// Either way, the page starts strictly after the cursor, including when the cursor is the last
+/// event of a transaction and the page starts with the next transaction.
+pub fn events_after_cursor(
+    mut event_keys: Vec<(TransactionEventsDigest, TransactionDigest, usize, u64)>,
+    cursor: Option<&EventID>,
+    limit: usize,
+) -> Vec<(TransactionEventsDigest, TransactionDigest, usize, u64)> {
+    if let Some(cursor) = cursor {
+        if event_keys.first().is_some_and(|(_, tx_digest, event_seq, _)| {
+            *tx_digest == cursor.tx_digest && *event_seq as u64 == cursor.event_seq
+        }) {
+            event_keys.remove(0);
+        }
+    }
+    event_keys.truncate(limit);
+    event_keys
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::indexes::ObjectIndexChanges;
+    use crate::indexes::{events_after_cursor, ObjectIndexChanges};
     use crate::IndexStore;
     use move_core_types::account_address::AccountAddress;
+    use move_core_types::identifier::Identifier;
+    use move_core_types::language_storage::StructTag;
     use prometheus::Registry;
     use std::collections::BTreeMap;
     use std::env::temp_dir;
-    use mgo_types::base_types::{ObjectInfo, ObjectType, MgoAddress};
-    use mgo_types::digests::TransactionDigest;
+    use mgo_json_rpc_types::MgoObjectDataFilter;
+    use mgo_types::base_types::{
+        MoveObjectType, ObjectDigest, ObjectID, ObjectInfo, ObjectType, SequenceNumber, MgoAddress,
+        TxSequenceNumber,
+    };
+    use mgo_types::digests::{TransactionDigest, TransactionEventsDigest};
     use mgo_types::effects::TransactionEvents;
-    use mgo_types::gas_coin::GAS;
+    use mgo_types::error::MgoResult;
+    use mgo_types::event::{Event, EventID};
+    use mgo_types::gas_coin::{GasCoin, GAS};
+    use mgo_types::governance::StakedMgo;
     use mgo_types::object;
     use mgo_types::object::Owner;
 
@@ -1723,4 +1861,326 @@ mod tests {
 
         Ok(())
     }
+
+    /// Indexes the coins `written` to `address` by a transaction that took `inputs`.
+    async fn index_coins(
+        index_store: &IndexStore,
+        address: MgoAddress,
+        inputs: &[object::Object],
+        written: &[object::Object],
+    ) -> anyhow::Result<()> {
+        let new_owners = written
+            .iter()
+            .map(|object| {
+                (
+                    (address, object.id()),
+                    ObjectInfo {
+                        object_id: object.id(),
+                        version: object.version(),
+                        digest: object.digest(),
+                        type_: ObjectType::Struct(object.type_().unwrap().clone()),
+                        owner: Owner::AddressOwner(address),
+                        previous_transaction: object.previous_transaction,
+                    },
+                )
+            })
+            .collect();
+        let object_index_changes = ObjectIndexChanges {
+            deleted_owners: vec![],
+            deleted_dynamic_fields: vec![],
+            new_owners,
+            new_dynamic_fields: vec![],
+        };
+        let by_id = |objects: &[object::Object]| {
+            objects
+                .iter()
+                .map(|object| (object.id(), object.clone()))
+                .collect::<BTreeMap<_, _>>()
+        };
+        index_store
+            .index_tx(
+                address,
+                vec![].into_iter(),
+                vec![].into_iter(),
+                vec![].into_iter(),
+                &TransactionEvents { data: vec![] },
+                object_index_changes,
+                &TransactionDigest::random(),
+                1234,
+                Some((by_id(inputs), by_id(written))),
+                &BTreeMap::new(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_owned_coins_with_min_balance() -> anyhow::Result<()> {
+        const MIN_BALANCE: u64 = 50;
+        const PAGE_SIZE: usize = 2;
+
+        let dir = tempfile::tempdir()?;
+        let index_store =
+            IndexStore::new(dir.path().to_path_buf(), &Registry::default(), Some(128));
+        let address: MgoAddress = AccountAddress::random().into();
+        let coins: Vec<_> = (1..=10)
+            .map(|i| object::Object::new_gas_with_balance_and_owner_for_testing(i * 10, address))
+            .collect();
+        index_coins(&index_store, address, &[], &coins).await?;
+
+        let page = |cursor: ObjectID| -> Vec<ObjectID> {
+            index_store
+                .get_owned_coins_iterator_with_cursor(
+                    address,
+                    (GAS::type_tag().to_string(), cursor),
+                    PAGE_SIZE,
+                    true,
+                    Some(MIN_BALANCE),
+                )
+                .unwrap()
+                .map(|(_, object_id, coin)| {
+                    assert!(coin.balance >= MIN_BALANCE);
+                    object_id
+                })
+                .collect()
+        };
+        let list_from = |mut cursor: ObjectID| {
+            let mut listed = vec![];
+            loop {
+                let next_page = page(cursor);
+                let Some(last) = next_page.last() else {
+                    return listed;
+                };
+                cursor = *last;
+                listed.extend(next_page);
+            }
+        };
+        let mut expected: Vec<_> = coins
+            .iter()
+            .filter(|coin| coin.as_coin_maybe().unwrap().value() >= MIN_BALANCE)
+            .map(|coin| coin.id())
+            .collect();
+        expected.sort();
+
+        // Coins below the minimum do not count towards a page.
+        let first_page = page(ObjectID::ZERO);
+        assert_eq!(first_page, expected[..PAGE_SIZE]);
+
+        // The cursor coin is spent down below the minimum before the next page is read, which
+        // still resumes right after it.
+        let cursor = *first_page.last().unwrap();
+        let input = coins.iter().find(|coin| coin.id() == cursor).unwrap();
+        let spent = object::Object::with_id_owner_gas_for_testing(cursor, address, 5);
+        index_coins(&index_store, address, &[input.clone()], &[spent]).await?;
+
+        assert_eq!([first_page, list_from(cursor)].concat(), expected);
+
+        // The spent coin is no longer listed from the start.
+        expected.retain(|object_id| object_id != &cursor);
+        assert_eq!(list_from(ObjectID::ZERO), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_owner_objects_excluding_types_fills_pages() {
+        const NUM_COINS: usize = 80;
+        const NUM_OTHER_OBJECTS: usize = 20;
+        const PAGE_SIZE: usize = 7;
+
+        let dir = tempfile::tempdir().unwrap();
+        let index_store =
+            IndexStore::new(dir.path().to_path_buf(), &Registry::default(), Some(128));
+        let address: MgoAddress = AccountAddress::random().into();
+        let nft_type = StructTag {
+            address: AccountAddress::random(),
+            module: Identifier::new("nft").unwrap(),
+            name: Identifier::new("Nft").unwrap(),
+            type_params: vec![],
+        };
+        let types = std::iter::repeat(MoveObjectType::gas_coin())
+            .take(NUM_COINS)
+            .chain(std::iter::repeat(MoveObjectType::from(nft_type)).take(NUM_OTHER_OBJECTS));
+        let new_owners = types
+            .map(|type_| {
+                let object_id = ObjectID::random();
+                (
+                    (address, object_id),
+                    ObjectInfo {
+                        object_id,
+                        version: SequenceNumber::from_u64(1),
+                        digest: ObjectDigest::random(),
+                        type_: ObjectType::Struct(type_),
+                        owner: Owner::AddressOwner(address),
+                        previous_transaction: TransactionDigest::random(),
+                    },
+                )
+            })
+            .collect();
+        index_store
+            .insert_genesis_objects(ObjectIndexChanges {
+                deleted_owners: vec![],
+                deleted_dynamic_fields: vec![],
+                new_owners,
+                new_dynamic_fields: vec![],
+            })
+            .unwrap();
+
+        // Everything except coins and staked objects, unparameterized so that coins of any type
+        // are excluded.
+        let coin_type = StructTag {
+            type_params: vec![],
+            ..GasCoin::type_()
+        };
+        let filter = MgoObjectDataFilter::MatchNone(vec![
+            MgoObjectDataFilter::StructType(coin_type),
+            MgoObjectDataFilter::StructType(StakedMgo::type_()),
+        ]);
+
+        let mut cursor = None;
+        let mut pages = vec![];
+        loop {
+            let page = index_store
+                .get_owner_objects(address, cursor, PAGE_SIZE, Some(filter.clone()))
+                .unwrap();
+            let Some(last) = page.last() else {
+                break;
+            };
+            cursor = Some(last.object_id);
+            pages.push(page);
+        }
+
+        let (last_page, full_pages) = pages.split_last().unwrap();
+        assert!(full_pages.iter().all(|page| page.len() == PAGE_SIZE));
+        assert_eq!(last_page.len(), NUM_OTHER_OBJECTS % PAGE_SIZE);
+        let objects: Vec<_> = pages.iter().flatten().collect();
+        assert_eq!(objects.len(), NUM_OTHER_OBJECTS);
+        assert!(objects
+            .iter()
+            .all(|o| matches!(&o.type_, ObjectType::Struct(s) if !s.is_coin())));
+    }
+
+    /// Walks the pages of `limit` events of a query as `query_events` reads them: from the
+    /// position of the last event of the previous page, one event more than the limit.
+    fn walk_events(
+        index_store: &IndexStore,
+        limit: usize,
+        descending: bool,
+        read: impl Fn(
+            TxSequenceNumber,
+            usize,
+            usize,
+        ) -> MgoResult<Vec<(TransactionEventsDigest, TransactionDigest, usize, u64)>>,
+    ) -> Vec<EventID> {
+        let mut cursor = None;
+        let mut walked = vec![];
+        loop {
+            let (tx_seq, event_seq) = index_store
+                .event_cursor_position(cursor.as_ref(), descending)
+                .unwrap();
+            let event_keys = read(tx_seq, event_seq, limit + 1).unwrap();
+            let page: Vec<_> = events_after_cursor(event_keys, cursor.as_ref(), limit)
+                .into_iter()
+                .map(|(_, tx_digest, event_seq, _)| EventID {
+                    tx_digest,
+                    event_seq: event_seq as u64,
+                })
+                .collect();
+            let Some(last) = page.last() else {
+                return walked;
+            };
+            cursor = Some(*last);
+            walked.extend(page);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_event_pagination_across_transactions() -> anyhow::Result<()> {
+        const EVENTS_PER_TRANSACTION: [usize; 6] = [1, 2, 5, 1, 5, 2];
+
+        let dir = tempfile::tempdir()?;
+        let index_store = IndexStore::new(dir.path().to_path_buf(), &Registry::default(), None);
+        let sender: MgoAddress = AccountAddress::random().into();
+        let mut digests = vec![];
+        let mut expected = vec![];
+        for num_events in EVENTS_PER_TRANSACTION {
+            let digest = TransactionDigest::random();
+            let events = TransactionEvents {
+                data: (0..num_events)
+                    .map(|_| Event {
+                        sender,
+                        ..Event::random_for_testing()
+                    })
+                    .collect(),
+            };
+            index_store
+                .index_tx(
+                    sender,
+                    vec![].into_iter(),
+                    vec![].into_iter(),
+                    vec![].into_iter(),
+                    &events,
+                    ObjectIndexChanges {
+                        deleted_owners: vec![],
+                        deleted_dynamic_fields: vec![],
+                        new_owners: vec![],
+                        new_dynamic_fields: vec![],
+                    },
+                    &digest,
+                    1234,
+                    None,
+                    &BTreeMap::new(),
+                )
+                .await?;
+            digests.push(digest);
+            expected.extend((0..num_events as u64).map(|event_seq| EventID {
+                tx_digest: digest,
+                event_seq,
+            }));
+        }
+
+        // Pages of every size end on every position within and across transactions, and cover
+        // every event exactly once in either direction.
+        for limit in 1..=expected.len() {
+            for descending in [false, true] {
+                let mut expected = expected.clone();
+                if descending {
+                    expected.reverse();
+                }
+                let all = walk_events(&index_store, limit, descending, |tx_seq, event_seq, n| {
+                    index_store.all_events(tx_seq, event_seq, n, descending)
+                });
+                assert_eq!(all, expected, "all events, limit {limit}, {descending}");
+                let by_sender =
+                    walk_events(&index_store, limit, descending, |tx_seq, event_seq, n| {
+                        index_store.events_by_sender(&sender, tx_seq, event_seq, n, descending)
+                    });
+                assert_eq!(by_sender, expected, "by sender, limit {limit}, {descending}");
+            }
+        }
+
+        // A cursor from another transaction is before or after all the events of a transaction,
+        // whatever its event sequence number.
+        let events_of_transaction = |tx_digest, cursor: EventID, descending| {
+            let (tx_seq, event_seq) = index_store
+                .event_cursor_position(Some(&cursor), descending)
+                .unwrap();
+            let event_keys = index_store
+                .events_by_transaction(&tx_digest, tx_seq, event_seq, 10, descending)
+                .unwrap();
+            events_after_cursor(event_keys, Some(&cursor), 10).len()
+        };
+        let before = EventID {
+            tx_digest: digests[1],
+            event_seq: 1,
+        };
+        let after = EventID {
+            tx_digest: digests[3],
+            event_seq: 0,
+        };
+        assert_eq!(events_of_transaction(digests[2], before, false), 5);
+        assert_eq!(events_of_transaction(digests[2], after, false), 0);
+        assert_eq!(events_of_transaction(digests[2], after, true), 5);
+        assert_eq!(events_of_transaction(digests[2], before, true), 0);
+        Ok(())
+    }
 }
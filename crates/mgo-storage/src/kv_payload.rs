@@ -0,0 +1,103 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Versioned envelope of the values kept in the remote key-value store: a version byte followed
+//! by the BCS of the value, laid out as it was at that version. Writers always emit
+//! [`KV_PAYLOAD_VERSION`], while readers decode every version they know of into the current
+//! in-memory type, so that entries written before an upgrade stay readable after it.
+
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use mgo_types::effects::{TransactionEffects, TransactionEvents};
+use mgo_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointContents, CheckpointSequenceNumber, CheckpointSummary,
+};
+use mgo_types::object::Object;
+use mgo_types::transaction::Transaction;
+
+/// Version of the payloads written by this node.
+pub const KV_PAYLOAD_VERSION: u8 = 1;
+
+/// Layout of a payload read from the store.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KVPayloadVersion {
+    /// Entries written before payloads were versioned, holding the BCS of the value alone.
+    Legacy,
+    /// A version byte of 1 followed by the BCS of the value.
+    V1,
+}
+
+#[derive(Debug)]
+pub enum KVPayloadError {
+    Deserialize(bcs::Error),
+    /// The payload was written by a newer node, in a version this one does not know of.
+    UnsupportedVersion(u8),
+}
+
+impl fmt::Display for KVPayloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Deserialize(e) => write!(f, "Failed to deserialize KV store payload: {e}"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "KV store payload has version {version}, but this node only reads versions up \
+                to {KV_PAYLOAD_VERSION}; the node is too old and must be upgraded"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for KVPayloadError {}
+
+/// A value kept in the key-value store.
+pub trait KVPayload: Serialize + DeserializeOwned {
+    /// Decodes `bytes` laid out as at `version` and up-converts them to the current type. Types
+    /// whose layout changes get a new [`KVPayloadVersion`], and keep decoding the older ones
+    /// into their previous layout before converting it.
+    fn decode_version(version: KVPayloadVersion, bytes: &[u8]) -> Result<Self, bcs::Error> {
+        match version {
+            KVPayloadVersion::Legacy | KVPayloadVersion::V1 => bcs::from_bytes(bytes),
+        }
+    }
+}
+
+impl KVPayload for Transaction {}
+impl KVPayload for TransactionEffects {}
+impl KVPayload for TransactionEvents {}
+impl KVPayload for CheckpointContents {}
+impl KVPayload for CheckpointSummary {}
+impl KVPayload for CertifiedCheckpointSummary {}
+impl KVPayload for Object {}
+impl KVPayload for CheckpointSequenceNumber {}
+
+/// Encodes `value` in the latest payload version.
+pub fn encode_kv_payload<T: KVPayload>(value: &T) -> Result<Vec<u8>, bcs::Error> {
+    let mut bytes = vec![KV_PAYLOAD_VERSION];
+    bytes.extend(bcs::to_bytes(value)?);
+    Ok(bytes)
+}
+
+/// Decodes a payload of any version up to [`KV_PAYLOAD_VERSION`].
+///
+/// Legacy entries carry no version byte, so a payload whose enveloped form does not decode is
+/// read as a legacy entry before it is reported as undecodable, or as too new if its version
+/// byte is one this node does not know of. Readers of digest-addressed values check the digest
+/// of what they decoded, which rules out legacy bytes mistaken for an envelope.
+pub fn decode_kv_payload<T: KVPayload>(bytes: &[u8]) -> Result<T, KVPayloadError> {
+    let enveloped = match bytes.split_first() {
+        Some((&1, rest)) => Some(T::decode_version(KVPayloadVersion::V1, rest)),
+        _ => None,
+    };
+    match enveloped {
+        Some(Ok(value)) => Ok(value),
+        _ => T::decode_version(KVPayloadVersion::Legacy, bytes).map_err(|e| match bytes.first() {
+            Some(&version) if version > KV_PAYLOAD_VERSION => {
+                KVPayloadError::UnsupportedVersion(version)
+            }
+            _ => KVPayloadError::Deserialize(e),
+        }),
+    }
+}
@@ -23,6 +23,7 @@ use mgo_types::messages_checkpoint::{
 use mgo_types::transaction::Transaction;
 
 use mgo_storage::key_value_store::*;
+use mgo_storage::kv_payload::*;
 use mgo_storage::key_value_store_metrics::KeyValueStoreMetrics;
 use mgo_types::object::Object;
 use mgo_types::storage::ObjectKey;
@@ -421,6 +422,43 @@ async fn test_get_tx_from_fallback() {
     );
 }
 
+#[test]
+fn test_kv_payload_round_trip() {
+    let fx = random_fx();
+    let bytes = encode_kv_payload(&fx).unwrap();
+    assert_eq!(bytes[0], KV_PAYLOAD_VERSION);
+    assert_eq!(fx, decode_kv_payload::<TransactionEffects>(&bytes).unwrap());
+}
+
+#[test]
+fn test_kv_payload_decodes_legacy_entries() {
+    let fx = random_fx();
+    let legacy = bcs::to_bytes(&fx).unwrap();
+    assert_eq!(
+        fx,
+        decode_kv_payload::<TransactionEffects>(&legacy).unwrap()
+    );
+
+    // A checkpoint sequence number written before payloads were versioned, and as of version 1.
+    let legacy: &[u8] = &[42, 0, 0, 0, 0, 0, 0, 0];
+    assert_eq!(
+        42,
+        decode_kv_payload::<CheckpointSequenceNumber>(legacy).unwrap()
+    );
+    let enveloped: &[u8] = &[1, 42, 0, 0, 0, 0, 0, 0, 0];
+    assert_eq!(
+        42,
+        decode_kv_payload::<CheckpointSequenceNumber>(enveloped).unwrap()
+    );
+}
+
+#[test]
+fn test_kv_payload_from_newer_node() {
+    let err = decode_kv_payload::<TransactionEffects>(&[2, 0xff]).unwrap_err();
+    assert!(matches!(err, KVPayloadError::UnsupportedVersion(2)));
+    assert!(err.to_string().contains("too old"));
+}
+
 #[cfg(msim)]
 mod simtests {
 
@@ -173,6 +173,7 @@ impl ValidatorConfigBuilder {
             name_service_package_address: None,
             name_service_registry_id: None,
             name_service_reverse_registry_id: None,
+            coin_registry_id: None,
             transaction_deny_config: Default::default(),
             certificate_deny_config: Default::default(),
             state_debug_dump_config: Default::default(),
@@ -180,6 +181,8 @@ impl ValidatorConfigBuilder {
             state_archive_read_config: vec![],
             state_snapshot_write_config: StateSnapshotConfig::default(),
             indexer_max_subscriptions: Default::default(),
+            subscription_filter_max_depth: None,
+            subscription_filter_max_nodes: None,
             transaction_kv_store_read_config: Default::default(),
             transaction_kv_store_write_config: None,
             enable_experimental_rest_api: true,
@@ -190,6 +193,11 @@ impl ValidatorConfigBuilder {
             zklogin_oauth_providers: default_zklogin_oauth_providers(),
             overload_threshold_config: self.overload_threshold_config.unwrap_or_default(),
             run_with_range: None,
+            dry_run_gas_comparison_threshold_bps: None,
+            enable_support_api: false,
+            json_rpc_cors: None,
+            json_rpc_response_signing: None,
+            json_rpc_display_limits: None,
         }
     }
 
@@ -421,6 +429,7 @@ impl FullnodeConfigBuilder {
             name_service_package_address: None,
             name_service_registry_id: None,
             name_service_reverse_registry_id: None,
+            coin_registry_id: None,
             transaction_deny_config: Default::default(),
             certificate_deny_config: Default::default(),
             state_debug_dump_config: Default::default(),
@@ -428,6 +437,8 @@ impl FullnodeConfigBuilder {
             state_archive_read_config: vec![],
             state_snapshot_write_config: StateSnapshotConfig::default(),
             indexer_max_subscriptions: Default::default(),
+            subscription_filter_max_depth: None,
+            subscription_filter_max_nodes: None,
             transaction_kv_store_read_config: Default::default(),
             transaction_kv_store_write_config: Default::default(),
             enable_experimental_rest_api: true,
@@ -436,6 +447,11 @@ impl FullnodeConfigBuilder {
             zklogin_oauth_providers: default_zklogin_oauth_providers(),
             overload_threshold_config: Default::default(),
             run_with_range: self.run_with_range,
+            dry_run_gas_comparison_threshold_bps: None,
+            enable_support_api: false,
+            json_rpc_cors: None,
+            json_rpc_response_signing: None,
+            json_rpc_display_limits: None,
         }
     }
 }
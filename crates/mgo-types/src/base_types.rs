@@ -306,6 +306,17 @@ impl MoveObjectType {
         }
     }
 
+    /// Return true if `self` is the struct type `other`. If `other` has no type parameters it
+    /// matches any instantiation, e.g. `0x2::coin::Coin` matches `0x2::coin::Coin<0x2::mgo::MGO>`.
+    /// Unlike converting to a `StructTag` first, this does not allocate unless `other` has type
+    /// parameters.
+    pub fn matches_type_fuzzy_generics(&self, other: &StructTag) -> bool {
+        self.address() == other.address
+            && self.module() == other.module.as_ident_str()
+            && self.name() == other.name.as_ident_str()
+            && (other.type_params.is_empty() || self.type_params() == other.type_params)
+    }
+
     pub fn is_upgrade_cap(&self) -> bool {
         self.address() == MGO_FRAMEWORK_ADDRESS
             && self.module().as_str() == "package"
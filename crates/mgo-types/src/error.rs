@@ -612,6 +612,11 @@ pub enum MgoError {
 
     #[error("Storage error: {0}")]
     Storage(String),
+
+    /// An error of a remote key-value store, decoded from its wire form into the variant it
+    /// had on the remote side.
+    #[error("Remote storage error: {0}")]
+    RemoteStorage(TypedStoreError),
 }
 
 #[repr(u64)]
@@ -774,6 +779,9 @@ impl MgoError {
             MgoError::TooOldTransactionPendingOnObject { .. } => (true, true),
             MgoError::TooManyTransactionsPendingConsensus => (true, true),
 
+            // Remote store error, as retryable as it was on the remote side
+            MgoError::RemoteStorage(error) => (error.is_retryable(), true),
+
             // Non retryable error
             MgoError::ExecutionError(..) => (false, true),
             MgoError::ByzantineAuthoritySuspicion { .. } => (false, true),
@@ -21,6 +21,7 @@ use crate::base_types::{ObjectID, MgoAddress, TransactionDigest};
 use crate::error::{MgoError, MgoResult};
 use crate::mgo_serde::BigInt;
 use crate::mgo_serde::Readable;
+use crate::mgo_system_state::PoolTokenExchangeRate;
 use crate::MGO_SYSTEM_ADDRESS;
 
 /// A universal Mgo event type encapsulating different types of events
@@ -140,6 +141,12 @@ impl Event {
             && self.type_.module.as_ident_str() == ident_str!("mgo_system_state_inner")
             && self.type_.name.as_ident_str() == ident_str!("SystemEpochInfoEvent")
     }
+
+    pub fn is_validator_epoch_info_event(&self) -> bool {
+        self.type_.address == MGO_SYSTEM_ADDRESS
+            && self.type_.module.as_ident_str() == ident_str!("validator_set")
+            && self.type_.name.as_ident_str() == ident_str!("ValidatorEpochInfoEventV2")
+    }
 }
 
 impl Event {
@@ -175,3 +182,20 @@ pub struct SystemEpochInfoEvent {
     pub total_stake_rewards_distributed: u64,
     pub leftover_storage_fund_inflow: u64,
 }
+
+// Event emitted in move code `fun advance_epoch` for every validator of the epoch that ends,
+// with the epoch that starts
+#[derive(Deserialize)]
+pub struct ValidatorEpochInfoEventV2 {
+    pub epoch: u64,
+    pub validator_address: MgoAddress,
+    pub reference_gas_survey_quote: u64,
+    pub stake: u64,
+    pub voting_power: u64,
+    pub commission_rate: u64,
+    pub pool_staking_reward: u64,
+    pub storage_fund_staking_reward: u64,
+    pub pool_token_exchange_rate: PoolTokenExchangeRate,
+    pub tallying_rule_reporters: Vec<MgoAddress>,
+    pub tallying_rule_global_score: u64,
+}
@@ -42,6 +42,20 @@ pub const VALIDATOR_VERY_LOW_STAKE_THRESHOLD_MIST: u64 = 15_000_000 * MIST_PER_M
 /// for this many epochs before being kicked out.
 pub const VALIDATOR_LOW_STAKE_GRACE_PERIOD: u64 = 7;
 
+/// Highest commission rate a validator can set, in basis points. Mirrors `MAX_COMMISSION_RATE`
+/// in `validator.move`.
+///
+/// 20%
+pub const MAX_VALIDATOR_COMMISSION_RATE: u64 = 2_000;
+
+/// Gas prices quoted by validators must be strictly below this. Mirrors
+/// `MAX_VALIDATOR_GAS_PRICE` in `validator.move`.
+pub const MAX_VALIDATOR_GAS_PRICE: u64 = 100_000;
+
+/// Maximum length in bytes of each validator metadata field. Mirrors
+/// `MAX_VALIDATOR_METADATA_LENGTH` in `validator.move`.
+pub const MAX_VALIDATOR_METADATA_LENGTH: usize = 256;
+
 pub const STAKING_POOL_MODULE_NAME: &IdentStr = ident_str!("staking_pool");
 pub const STAKED_MGO_STRUCT_NAME: &IdentStr = ident_str!("StakedMgo");
 
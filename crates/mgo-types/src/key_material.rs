@@ -0,0 +1,74 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Constant-time comparison of key material. Checks that authenticate a caller by a key, an
+//! address or an origin compare them with [`ct_eq`] or as [`KeyMaterial`], whose comparisons
+//! take the same time wherever the compared bytes differ, rather than with `==` on their bytes.
+
+use std::fmt;
+use std::hint::black_box;
+
+use fastcrypto::encoding::{Encoding, Hex};
+
+#[cfg(test)]
+#[path = "unit_tests/key_material_tests.rs"]
+mod key_material_tests;
+
+/// Whether `a` and `b` are equal, in a time that only depends on their lengths.
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    // Accumulating the differences of all the bytes, through `black_box` so that the compiler
+    // does not stop at the first difference.
+    let difference = a
+        .iter()
+        .zip(b)
+        .fold(0u8, |difference, (a, b)| black_box(difference | (a ^ b)));
+    difference == 0
+}
+
+/// Bytes of a public key or of an address, which only compare in constant time. They cannot be
+/// borrowed back as a slice, so that comparing them with `==` does not compare the slices.
+#[derive(Clone, Copy)]
+pub struct KeyMaterial<'a>(&'a [u8]);
+
+impl<'a> KeyMaterial<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self(bytes)
+    }
+
+    /// Whether this is equal to any of `candidates`, comparing it with every one of them
+    /// rather than stopping at the first match.
+    pub fn is_any_of<'b, I>(self, candidates: I) -> bool
+    where
+        I: IntoIterator,
+        I::Item: Into<KeyMaterial<'b>>,
+    {
+        candidates.into_iter().fold(false, |found, candidate| {
+            black_box(found | ct_eq(self.0, candidate.into().0))
+        })
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> From<&'a T> for KeyMaterial<'a> {
+    fn from(material: &'a T) -> Self {
+        Self(material.as_ref())
+    }
+}
+
+impl PartialEq for KeyMaterial<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        ct_eq(self.0, other.0)
+    }
+}
+
+impl Eq for KeyMaterial<'_> {}
+
+impl fmt::Debug for KeyMaterial<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("KeyMaterial")
+            .field(&Hex::encode(self.0))
+            .finish()
+    }
+}
@@ -54,6 +54,7 @@ pub mod governance;
 pub mod id;
 pub mod in_memory_storage;
 pub mod inner_temporary_store;
+pub mod key_material;
 pub mod message_envelope;
 pub mod messages_checkpoint;
 pub mod messages_consensus;
@@ -22,14 +22,14 @@ use super::epoch_start_mgo_system_state::EpochStartValidatorInfoV1;
 use super::mgo_system_state_summary::{MgoSystemStateSummary, MgoValidatorSummary};
 use super::{get_validators_from_table_vec, AdvanceEpochParams, MgoSystemStateTrait};
 
-const E_METADATA_INVALID_POP: u64 = 0;
-const E_METADATA_INVALID_PUBKEY: u64 = 1;
-const E_METADATA_INVALID_NET_PUBKEY: u64 = 2;
-const E_METADATA_INVALID_WORKER_PUBKEY: u64 = 3;
-const E_METADATA_INVALID_NET_ADDR: u64 = 4;
-const E_METADATA_INVALID_P2P_ADDR: u64 = 5;
-const E_METADATA_INVALID_PRIMARY_ADDR: u64 = 6;
-const E_METADATA_INVALID_WORKER_ADDR: u64 = 7;
+pub const E_METADATA_INVALID_POP: u64 = 0;
+pub const E_METADATA_INVALID_PUBKEY: u64 = 1;
+pub const E_METADATA_INVALID_NET_PUBKEY: u64 = 2;
+pub const E_METADATA_INVALID_WORKER_PUBKEY: u64 = 3;
+pub const E_METADATA_INVALID_NET_ADDR: u64 = 4;
+pub const E_METADATA_INVALID_P2P_ADDR: u64 = 5;
+pub const E_METADATA_INVALID_PRIMARY_ADDR: u64 = 6;
+pub const E_METADATA_INVALID_WORKER_ADDR: u64 = 7;
 
 /// Rust version of the Move mgo::mgo_system::SystemParameters type
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
@@ -408,6 +408,40 @@ pub struct PoolTokenExchangeRate {
 }
 
 impl PoolTokenExchangeRate {
+    pub fn new(mgo_amount: u64, pool_token_amount: u64) -> Self {
+        Self {
+            mgo_amount,
+            pool_token_amount,
+        }
+    }
+
+    pub fn mgo_amount(&self) -> u64 {
+        self.mgo_amount
+    }
+
+    pub fn pool_token_amount(&self) -> u64 {
+        self.pool_token_amount
+    }
+
+    /// Amount of Mgo that `token_amount` pool tokens are worth at this rate, rounded down as
+    /// the staking pool does on withdrawal.
+    pub fn get_mgo_amount(&self, token_amount: u64) -> u64 {
+        // When either amount is 0, the pool has no stakes and tokens are worth their amount.
+        if self.mgo_amount == 0 || self.pool_token_amount == 0 {
+            return token_amount;
+        }
+        (self.mgo_amount as u128 * token_amount as u128 / self.pool_token_amount as u128) as u64
+    }
+
+    /// Amount of pool tokens that `mgo_amount` Mgo buys at this rate, rounded down as the
+    /// staking pool does on staking.
+    pub fn get_token_amount(&self, mgo_amount: u64) -> u64 {
+        if self.mgo_amount == 0 || self.pool_token_amount == 0 {
+            return mgo_amount;
+        }
+        (self.pool_token_amount as u128 * mgo_amount as u128 / self.mgo_amount as u128) as u64
+    }
+
     /// Rate of the staking pool, pool token amount : Mgo amount
     pub fn rate(&self) -> f64 {
         if self.mgo_amount == 0 {
@@ -325,6 +325,46 @@ impl MoveObject {
         })
     }
 
+    /// Convert the top-level fields of `self` named in `fields` to the JSON representation
+    /// dictated by `layout`. The bytes of all other fields are skipped without being
+    /// deserialized, so large unrequested fields cost no more than walking over them.
+    /// Fields are returned in declaration order; names not in `layout` are ignored.
+    pub fn to_partial_move_struct(
+        &self,
+        layout: &MoveStructLayout,
+        fields: &[String],
+    ) -> Result<MoveStruct, MgoError> {
+        let serialization_error = |error: String| MgoError::ObjectSerializationError { error };
+        let requested = |name: &str| fields.iter().any(|f| f == name);
+        let wanted = layout
+            .fields
+            .iter()
+            .filter(|f| requested(f.name.as_str()))
+            .count();
+
+        let mut remaining = self.contents.as_slice();
+        let mut projected = Vec::with_capacity(wanted);
+        for field in &layout.fields {
+            if projected.len() == wanted {
+                break;
+            }
+            let len = bcs_value_len(remaining, &field.layout).ok_or_else(|| {
+                serialization_error(format!("Malformed contents for field {}", field.name))
+            })?;
+            let (bytes, rest) = remaining.split_at(len);
+            if requested(field.name.as_str()) {
+                let value = MoveValue::simple_deserialize(bytes, &field.layout)
+                    .map_err(|e| serialization_error(e.to_string()))?;
+                projected.push((field.name.clone(), value));
+            }
+            remaining = rest;
+        }
+        Ok(MoveStruct {
+            type_: layout.type_.clone(),
+            fields: projected,
+        })
+    }
+
     /// Convert `self` to the JSON representation dictated by `layout`.
     pub fn to_move_struct_with_resolver(
         &self,
@@ -1253,6 +1293,61 @@ impl Display for PastObjectRead {
     }
 }
 
+/// Size in bytes of a fixed-size BCS value of type `layout`, or `None` if values of that type
+/// vary in size.
+fn bcs_fixed_size(layout: &MoveTypeLayout) -> Option<usize> {
+    match layout {
+        MoveTypeLayout::Bool | MoveTypeLayout::U8 => Some(1),
+        MoveTypeLayout::U16 => Some(2),
+        MoveTypeLayout::U32 => Some(4),
+        MoveTypeLayout::U64 => Some(8),
+        MoveTypeLayout::U128 => Some(16),
+        MoveTypeLayout::U256 | MoveTypeLayout::Address | MoveTypeLayout::Signer => Some(32),
+        MoveTypeLayout::Vector(_) => None,
+        MoveTypeLayout::Struct(s) => s.fields.iter().map(|f| bcs_fixed_size(&f.layout)).sum(),
+    }
+}
+
+/// Length in bytes of the BCS value of type `layout` at the start of `bytes`, or `None` if
+/// `bytes` is too short to hold one.
+fn bcs_value_len(bytes: &[u8], layout: &MoveTypeLayout) -> Option<usize> {
+    let len = match layout {
+        MoveTypeLayout::Vector(element) => {
+            let (count, mut len) = read_uleb128_len(bytes)?;
+            match bcs_fixed_size(element) {
+                Some(size) => len += count.checked_mul(size)?,
+                None => {
+                    for _ in 0..count {
+                        len += bcs_value_len(bytes.get(len..)?, element)?;
+                    }
+                }
+            }
+            len
+        }
+        MoveTypeLayout::Struct(s) => {
+            let mut len = 0;
+            for field in &s.fields {
+                len += bcs_value_len(bytes.get(len..)?, &field.layout)?;
+            }
+            len
+        }
+        primitive => bcs_fixed_size(primitive)?,
+    };
+    (len <= bytes.len()).then_some(len)
+}
+
+/// Decodes a BCS sequence length, returning it along with the number of bytes it occupies.
+fn read_uleb128_len(bytes: &[u8]) -> Option<(usize, usize)> {
+    let mut value: u64 = 0;
+    for (i, byte) in bytes.iter().take(5).enumerate() {
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            return u32::try_from(value).ok().map(|v| (v as usize, i + 1));
+        }
+    }
+    None
+}
+
 // Ensure that object digest computation and bcs serialized format are not inadvertently changed.
 #[test]
 fn test_object_digest_and_serialized_format() {
@@ -1323,3 +1418,80 @@ fn test_set_coin_value_unsafe() {
     test_for_value(u32::MAX as u64 + 1);
     test_for_value(u64::MAX);
 }
+
+#[test]
+fn test_to_partial_move_struct() {
+    use crate::id::UID;
+    use move_core_types::annotated_value::MoveFieldLayout;
+    use move_core_types::ident_str;
+
+    const HUGE_LEN: usize = 2 * 1024 * 1024;
+    let struct_tag: StructTag = "0x42::blob::Blob".parse().unwrap();
+    let layout = MoveStructLayout {
+        type_: struct_tag.clone(),
+        fields: vec![
+            MoveFieldLayout::new(
+                ident_str!("id").to_owned(),
+                MoveTypeLayout::Struct(UID::layout()),
+            ),
+            MoveFieldLayout::new(
+                ident_str!("huge").to_owned(),
+                MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8)),
+            ),
+            MoveFieldLayout::new(ident_str!("small").to_owned(), MoveTypeLayout::U64),
+        ],
+    };
+    let id = ObjectID::random();
+    let mut contents = bcs::to_bytes(&id).unwrap();
+    contents.extend(bcs::to_bytes(&vec![7u8; HUGE_LEN]).unwrap());
+    contents.extend(bcs::to_bytes(&42u64).unwrap());
+    let object = unsafe {
+        MoveObject::new_from_execution_with_limit(
+            struct_tag.into(),
+            true,
+            OBJECT_START_VERSION,
+            contents,
+            u64::MAX,
+        )
+        .unwrap()
+    };
+
+    let small = object
+        .to_partial_move_struct(&layout, &["small".to_string()])
+        .unwrap();
+    assert_eq!(
+        small.fields,
+        vec![(ident_str!("small").to_owned(), MoveValue::U64(42))]
+    );
+
+    let huge = object
+        .to_partial_move_struct(&layout, &["huge".to_string()])
+        .unwrap();
+    let [(name, MoveValue::Vector(bytes))] = huge.fields.as_slice() else {
+        panic!("Expected only the huge vector field, got {:?}", huge.fields);
+    };
+    assert_eq!(name.as_str(), "huge");
+    assert_eq!(bytes.len(), HUGE_LEN);
+
+    // Projecting every field matches full deserialization.
+    let all = ["id", "huge", "small"].map(String::from);
+    assert_eq!(
+        object.to_partial_move_struct(&layout, &all).unwrap(),
+        object.to_move_struct(&layout).unwrap()
+    );
+
+    // Truncated contents are reported rather than read past.
+    let truncated = unsafe {
+        MoveObject::new_from_execution_with_limit(
+            object.type_().clone(),
+            true,
+            OBJECT_START_VERSION,
+            object.contents()[..100].to_vec(),
+            u64::MAX,
+        )
+        .unwrap()
+    };
+    assert!(truncated
+        .to_partial_move_struct(&layout, &["small".to_string()])
+        .is_err());
+}
@@ -66,6 +66,12 @@ pub type GroupedErrors = Vec<(MgoError, StakeUnit, Vec<ConciseAuthorityPublicKey
 pub enum ExecuteTransactionRequestType {
     WaitForEffectsCert,
     WaitForLocalExecution,
+    /// Waits for local execution, and then for a checkpoint executed by the node to include the
+    /// transaction, for at most `timeout_ms`.
+    #[serde(rename_all = "camelCase")]
+    WaitForCheckpointInclusion {
+        timeout_ms: u64,
+    },
 }
 
 #[derive(Debug)]
@@ -0,0 +1,44 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::base_types::MgoAddress;
+
+#[test]
+fn ct_eq_test() {
+    let key = [7u8; 32];
+    assert!(ct_eq(&key, &key.clone()));
+    assert!(ct_eq(&[], &[]));
+    for index in [0, 15, 31] {
+        let mut other = key;
+        other[index] ^= 1;
+        assert!(!ct_eq(&key, &other));
+    }
+    assert!(!ct_eq(&key, &key[..31]));
+    assert!(!ct_eq(&key[..31], &key));
+}
+
+#[test]
+fn key_material_eq_test() {
+    let address = MgoAddress::random_for_testing_only();
+    assert_eq!(
+        KeyMaterial::from(&address),
+        KeyMaterial::new(address.as_ref())
+    );
+    assert_ne!(
+        KeyMaterial::from(&address),
+        KeyMaterial::from(&MgoAddress::random_for_testing_only())
+    );
+    assert_ne!(
+        KeyMaterial::from(&address),
+        KeyMaterial::new(&address.as_ref()[1..])
+    );
+
+    assert!(KeyMaterial::from(&address).is_any_of([
+        &MgoAddress::random_for_testing_only(),
+        &address,
+        &MgoAddress::random_for_testing_only(),
+    ]));
+    assert!(!KeyMaterial::from(&address).is_any_of([&MgoAddress::random_for_testing_only()]));
+    assert!(!KeyMaterial::from(&address).is_any_of(Vec::<&MgoAddress>::new()));
+}
@@ -30,9 +30,10 @@ use shared_crypto::intent::Intent;
 use mgo_execution::verifier::VerifierOverrides;
 use mgo_json::MgoJsonValue;
 use mgo_json_rpc_types::{
-    DynamicFieldPage, MgoData, MgoObjectData, MgoObjectResponse, MgoObjectResponseQuery,
-    MgoParsedData, MgoRawData, MgoTransactionBlockEffectsAPI, MgoTransactionBlockResponse,
-    MgoTransactionBlockResponseOptions,
+    AddressStateExport, DynamicFieldPage, MgoData, MgoDynamicFieldInfo, MgoObjectData,
+    MgoObjectResponse, MgoObjectResponseQuery, MgoParsedData, MgoRawData,
+    MgoTransactionBlockEffectsAPI, MgoTransactionBlockResponse, MgoTransactionBlockResponseOptions,
+    StorageRebateEstimate,
 };
 use mgo_json_rpc_types::{MgoExecutionStatus, MgoObjectDataOptions};
 use mgo_keys::keystore::AccountKeystore;
@@ -50,7 +51,6 @@ use mgo_types::{
     base_types::{ObjectID, SequenceNumber, MgoAddress},
     crypto::{EmptySignInfo, SignatureScheme},
     digests::TransactionDigest,
-    dynamic_field::DynamicFieldInfo,
     error::MgoError,
     gas_coin::GasCoin,
     message_envelope::Envelope,
@@ -72,7 +72,10 @@ use tabled::{
 
 use tracing::info;
 
+use crate::gas_report::{gas_report, parse_since, GasReport};
 use crate::key_identity::{get_identity_address, KeyIdentity};
+use crate::multisig_commands::{MultisigCommand, MultisigCommandResult};
+use crate::object_inspector::{inspect_object, ObjectInspection};
 
 #[path = "unit_tests/profiler_tests.rs"]
 #[cfg(test)]
@@ -87,7 +90,8 @@ macro_rules! serialize_or_execute {
         if $serialize_unsigned {
             MgoClientCommandResult::SerializedUnsignedTransaction($tx_data)
         } else {
-            let signature = $context.config.keystore.sign_secure(
+            $context.check_guardrails(&$tx_data).await?;
+            let signature = $context.sign_secure(
                 &$tx_data.sender(),
                 &$tx_data,
                 Intent::mgo_transaction(),
@@ -218,6 +222,16 @@ pub enum MgoClientCommands {
         signed_tx_bytes: String,
     },
 
+    /// Export the owned objects, balances, stakes, latest transactions and pending object locks
+    /// of an address in one JSON document, for support. Only fullnodes that enable the support
+    /// API serve it.
+    #[clap(name = "export-address-state")]
+    ExportAddressState {
+        /// Address (or its alias) to export the state of
+        #[clap(name = "address")]
+        address: Option<KeyIdentity>,
+    },
+
     /// Request gas coin from faucet. By default, it will use the active address and the active network.
     #[clap[name = "faucet"]]
     Faucet {
@@ -240,6 +254,21 @@ pub enum MgoClientCommands {
         address: Option<KeyIdentity>,
     },
 
+    /// Report the gas spent by the transactions an address sent, per day and per Move function
+    /// called. Use `mgo client --json gas-report` for output that scripts can read.
+    #[clap(name = "gas-report")]
+    GasReport {
+        /// Address (or its alias) that sent the transactions, the active address by default
+        #[clap(long)]
+        #[arg(value_parser)]
+        address: Option<KeyIdentity>,
+
+        /// Only count transactions from this time on: milliseconds since the unix epoch, an
+        /// RFC 3339 time or date such as `2024-03-01` in UTC, or a duration back such as `7days`
+        #[clap(long, value_parser = parse_since)]
+        since: Option<u64>,
+    },
+
     /// Merge two coin objects into one coin
     MergeCoin {
         /// The address of the coin to merge into.
@@ -270,6 +299,14 @@ pub enum MgoClientCommands {
         serialize_signed_transaction: bool,
     },
 
+    /// Assemble a transaction of a multisig address whose participants sign separately: create
+    /// it, sign it as each participant, then combine the signatures and submit it.
+    #[clap(name = "multisig")]
+    Multisig {
+        #[clap(subcommand)]
+        cmd: MultisigCommand,
+    },
+
     /// Generate new address and keypair with keypair scheme flag {ed25519 | secp256k1 | secp256r1}
     /// with optional derivation path, default to m/44'/784'/0'/0'/0' for ed25519 or
     /// m/54'/784'/0'/0/0 for secp256k1 or m/74'/784'/0'/0/0 for secp256r1. Word length can be
@@ -305,6 +342,22 @@ pub enum MgoClientCommands {
         #[clap(long)]
         bcs: bool,
     },
+    /// Inspect an object, with its Move fields decoded into a tree and its Display fields.
+    /// Use `mgo client --json inspect-object` for output that scripts can read.
+    #[clap(name = "inspect-object")]
+    InspectObject {
+        /// Object ID of the object to inspect
+        #[clap(name = "object_id")]
+        id: ObjectID,
+
+        /// Inspect this past version of the object instead of its latest one
+        #[clap(long)]
+        version: Option<u64>,
+
+        /// List the last N versions of the object, with the transactions that created them
+        #[clap(long)]
+        history: Option<usize>,
+    },
     /// Obtain all objects owned by the address. It also accepts an address by its alias.
     #[clap(name = "objects")]
     Objects {
@@ -494,6 +547,20 @@ pub enum MgoClientCommands {
         serialize_signed_transaction: bool,
     },
 
+    /// Estimate the MGO returned from the storage rebate of an object when deleting it, or when
+    /// mutating it to a new size, at the storage price of the current epoch.
+    #[clap(name = "storage-rebate")]
+    StorageRebate {
+        /// Object ID of the object to estimate the rebate of
+        #[clap(name = "object_id")]
+        id: ObjectID,
+
+        /// Also estimate the rebate of a mutation leaving the object's contents this many bytes
+        /// long
+        #[clap(long)]
+        new_size: Option<u64>,
+    },
+
     /// Switch active address and network(e.g., devnet, local rpc server).
     #[clap(name = "switch")]
     Switch {
@@ -1034,6 +1101,18 @@ impl MgoClientCommands {
                 }
             }
 
+            MgoClientCommands::InspectObject {
+                id,
+                version,
+                history,
+            } => {
+                let client = context.get_client().await?;
+                let version = version.map(SequenceNumber::from_u64);
+                MgoClientCommandResult::InspectObject(
+                    inspect_object(&client, id, version, history).await?,
+                )
+            }
+
             MgoClientCommands::TransactionBlock { digest } => {
                 let client = context.get_client().await?;
                 let tx_read = client
@@ -1048,6 +1127,9 @@ impl MgoClientCommands {
                             show_object_changes: true,
                             show_balance_changes: false,
                             show_raw_effects: false,
+                            show_created_summary: false,
+                            show_signatures: false,
+                            include_checkpoint: false,
                         },
                     )
                     .await?;
@@ -1307,6 +1389,14 @@ impl MgoClientCommands {
                     .collect();
                 MgoClientCommandResult::Gas(coins)
             }
+            MgoClientCommands::Multisig { cmd } => {
+                MgoClientCommandResult::Multisig(cmd.execute(context).await?)
+            }
+            MgoClientCommands::GasReport { address, since } => {
+                let address = get_identity_address(address, context)?;
+                let client = context.get_client().await?;
+                MgoClientCommandResult::GasReport(gas_report(&client, address, since).await?)
+            }
             MgoClientCommands::Faucet { address, url } => {
                 let address = get_identity_address(address, context)?;
                 let url = if let Some(url) = url {
@@ -1400,6 +1490,15 @@ impl MgoClientCommands {
                     MergeCoin
                 )
             }
+            MgoClientCommands::StorageRebate { id, new_size } => {
+                let client = context.get_client().await?;
+                MgoClientCommandResult::StorageRebate(
+                    client
+                        .governance_api()
+                        .estimate_storage_rebate(id, new_size)
+                        .await?,
+                )
+            }
             MgoClientCommands::Switch { address, env } => {
                 let mut addr = None;
 
@@ -1467,6 +1566,13 @@ impl MgoClientCommands {
                 let response = context.execute_transaction_may_fail(transaction).await?;
                 MgoClientCommandResult::ExecuteSignedTx(response)
             }
+            MgoClientCommands::ExportAddressState { address } => {
+                let address = get_identity_address(address, context)?;
+                let client = context.get_client().await?;
+                MgoClientCommandResult::ExportAddressState(
+                    client.support_api().export_address_state(address).await?,
+                )
+            }
             MgoClientCommands::NewEnv { alias, rpc, ws } => {
                 if context.config.envs.iter().any(|env| env.alias == alias) {
                     return Err(anyhow!(
@@ -1844,6 +1950,43 @@ impl Display for MgoClientCommandResult {
             MgoClientCommandResult::Switch(response) => {
                 write!(writer, "{}", response)?;
             }
+            MgoClientCommandResult::StorageRebate(estimate) => {
+                let mut builder = TableBuilder::default();
+                builder.push_record(vec!["Object ID".to_string(), estimate.object_id.to_string()]);
+                builder.push_record(vec!["Version".to_string(), estimate.version.to_string()]);
+                builder.push_record(vec!["Epoch".to_string(), estimate.epoch.to_string()]);
+                builder.push_record(vec![
+                    "Storage gas price".to_string(),
+                    estimate.storage_gas_price.to_string(),
+                ]);
+                builder.push_record(vec!["Size".to_string(), estimate.size.to_string()]);
+                if let Some(reason) = estimate.no_rebate_reason {
+                    builder.push_record(vec!["No rebate".to_string(), format!("{reason:?}")]);
+                }
+                builder.push_record(vec![
+                    "Storage rebate".to_string(),
+                    estimate.storage_rebate.to_string(),
+                ]);
+                builder.push_record(vec![
+                    "Deletion rebate".to_string(),
+                    estimate.deletion_rebate.to_string(),
+                ]);
+                if let (Some(size), Some(cost), Some(delta)) = (
+                    estimate.new_contents_size,
+                    estimate.new_storage_cost,
+                    estimate.mutation_rebate_delta,
+                ) {
+                    builder.push_record(vec!["New contents size".to_string(), size.to_string()]);
+                    builder.push_record(vec!["New storage cost".to_string(), cost.to_string()]);
+                    builder.push_record(vec![
+                        "Mutation rebate delta".to_string(),
+                        delta.to_string(),
+                    ]);
+                }
+                let mut table = builder.build();
+                table.with(TableStyle::rounded());
+                write!(f, "{}", table)?
+            }
             MgoClientCommandResult::ActiveAddress(response) => {
                 match response {
                     Some(r) => write!(writer, "{}", r)?,
@@ -1878,6 +2021,19 @@ impl Display for MgoClientCommandResult {
             MgoClientCommandResult::VerifySource => {
                 writeln!(writer, "Source verification succeeded!")?;
             }
+            MgoClientCommandResult::InspectObject(inspection) => {
+                write!(writer, "{}", inspection)?;
+            }
+            MgoClientCommandResult::GasReport(report) => {
+                write!(writer, "{}", report)?;
+            }
+            MgoClientCommandResult::Multisig(output) => {
+                write!(writer, "{}", output)?;
+            }
+            MgoClientCommandResult::ExportAddressState(export) => {
+                let json = serde_json::to_string_pretty(export).map_err(|_| std::fmt::Error)?;
+                writeln!(writer, "{json}")?;
+            }
             MgoClientCommandResult::VerifyBytecodeMeter {
                 max_module_ticks,
                 max_function_ticks,
@@ -2037,7 +2193,7 @@ pub struct AddressesOutput {
 pub struct DynamicFieldOutput {
     pub has_next_page: bool,
     pub next_cursor: Option<ObjectID>,
-    pub data: Vec<DynamicFieldInfo>,
+    pub data: Vec<MgoDynamicFieldInfo>,
 }
 
 #[derive(Serialize)]
@@ -2154,8 +2310,12 @@ pub enum MgoClientCommandResult {
     DynamicFieldQuery(DynamicFieldPage),
     Envs(Vec<MgoEnv>, Option<String>),
     ExecuteSignedTx(MgoTransactionBlockResponse),
+    ExportAddressState(AddressStateExport),
     Gas(Vec<GasCoin>),
+    GasReport(GasReport),
+    InspectObject(ObjectInspection),
     MergeCoin(MgoTransactionBlockResponse),
+    Multisig(MultisigCommandResult),
     NewAddress(NewAddressOutput),
     NewEnv(MgoEnv),
     NoOutput,
@@ -2169,6 +2329,7 @@ pub enum MgoClientCommandResult {
     SerializedSignedTransaction(SenderSignedData),
     SerializedUnsignedTransaction(TransactionData),
     SplitCoin(MgoTransactionBlockResponse),
+    StorageRebate(StorageRebateEstimate),
     Switch(SwitchResponse),
     SyncClientState,
     TransactionBlock(MgoTransactionBlockResponse),
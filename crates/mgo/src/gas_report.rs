@@ -0,0 +1,244 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::anyhow;
+use mgo_json_rpc_types::{
+    MgoCommand, MgoTransactionBlockDataAPI, MgoTransactionBlockEffectsAPI, MgoTransactionBlockKind,
+    MgoTransactionBlockResponse, MgoTransactionBlockResponseOptions,
+    MgoTransactionBlockResponseQuery, TransactionFilter,
+};
+use mgo_sdk::MgoClient;
+use mgo_types::base_types::MgoAddress;
+use mgo_types::gas::GasCostSummary;
+use serde::Serialize;
+use tabled::builder::Builder as TableBuilder;
+use tabled::settings::Style as TableStyle;
+
+/// Transactions read per page of history.
+const GAS_REPORT_PAGE_SIZE: usize = 50;
+/// Key of the transactions whose time or Move calls are not known, e.g. once pruned.
+pub const UNKNOWN: &str = "unknown";
+/// Key of the transactions that call no Move function, such as plain transfers.
+pub const NO_MOVE_CALL: &str = "(no Move call)";
+
+/// Gas spent by the transactions an address sent, as shown by `mgo client gas-report`.
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GasReport {
+    pub address: MgoAddress,
+    /// Only transactions from this time on, in milliseconds since the unix epoch, are counted.
+    pub since_ms: Option<u64>,
+    pub total: GasUsage,
+    /// Gas by UTC day, as `YYYY-MM-DD`.
+    pub by_day: BTreeMap<String, GasUsage>,
+    /// Gas by Move function, as `package::module::function`. A transaction calling several
+    /// functions counts fully toward each of them, once.
+    pub by_function: BTreeMap<String, GasUsage>,
+}
+
+/// Gas spent by a set of transactions.
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GasUsage {
+    pub transactions: u64,
+    /// Transactions whose effects are missing, e.g. once pruned, so that their gas is unknown
+    /// and not part of the costs.
+    pub unknown_gas_transactions: u64,
+    pub computation_cost: u64,
+    pub storage_cost: u64,
+    pub storage_rebate: u64,
+}
+
+impl GasUsage {
+    /// Gas spent once storage rebates are refunded, negative when more was refunded.
+    pub fn net_gas(&self) -> i128 {
+        self.computation_cost as i128 + self.storage_cost as i128 - self.storage_rebate as i128
+    }
+
+    fn add(&mut self, gas: Option<&GasCostSummary>) {
+        self.transactions += 1;
+        match gas {
+            Some(gas) => {
+                self.computation_cost += gas.computation_cost;
+                self.storage_cost += gas.storage_cost;
+                self.storage_rebate += gas.storage_rebate;
+            }
+            None => self.unknown_gas_transactions += 1,
+        }
+    }
+}
+
+impl GasReport {
+    pub fn new(address: MgoAddress, since_ms: Option<u64>) -> Self {
+        Self {
+            address,
+            since_ms,
+            ..Default::default()
+        }
+    }
+
+    /// Counts the gas of `transaction`, unless it is known to predate the report. Transactions
+    /// with missing effects or input are counted as such rather than skipped.
+    pub fn add(&mut self, transaction: &MgoTransactionBlockResponse) {
+        if matches!(
+            (self.since_ms, transaction.timestamp_ms),
+            (Some(since), Some(timestamp)) if timestamp < since
+        ) {
+            return;
+        }
+        let gas = transaction
+            .effects
+            .as_ref()
+            .map(|effects| effects.gas_cost_summary());
+        self.total.add(gas);
+        let day = transaction.timestamp_ms.map_or_else(|| UNKNOWN.to_string(), utc_day);
+        self.by_day.entry(day).or_default().add(gas);
+        for function in called_functions(transaction) {
+            self.by_function.entry(function).or_default().add(gas);
+        }
+    }
+}
+
+/// The distinct Move functions `transaction` calls, or a placeholder for transactions calling
+/// none, or whose input is missing.
+fn called_functions(transaction: &MgoTransactionBlockResponse) -> Vec<String> {
+    let Some(block) = &transaction.transaction else {
+        return vec![UNKNOWN.to_string()];
+    };
+    let mut functions = vec![];
+    if let MgoTransactionBlockKind::ProgrammableTransaction(pt) = block.data.transaction() {
+        for command in &pt.commands {
+            if let MgoCommand::MoveCall(call) = command {
+                let function = format!("{}::{}::{}", call.package, call.module, call.function);
+                if !functions.contains(&function) {
+                    functions.push(function);
+                }
+            }
+        }
+    }
+    if functions.is_empty() {
+        functions.push(NO_MOVE_CALL.to_string());
+    }
+    functions
+}
+
+fn utc_day(timestamp_ms: u64) -> String {
+    let time = UNIX_EPOCH + Duration::from_millis(timestamp_ms);
+    humantime::format_rfc3339_seconds(time).to_string()[..10].to_string()
+}
+
+/// Parses the start of a report: milliseconds since the unix epoch, an RFC 3339 time or date
+/// such as `2024-03-01` (in UTC), or a duration back from now such as `7days`.
+pub fn parse_since(since: &str) -> Result<u64, anyhow::Error> {
+    if let Ok(ms) = since.parse::<u64>() {
+        return Ok(ms);
+    }
+    let date_time = if since.len() == 10 {
+        format!("{since}T00:00:00Z")
+    } else {
+        since.to_string()
+    };
+    let time = match humantime::parse_rfc3339_weak(&date_time) {
+        Ok(time) => time,
+        Err(_) => {
+            let ago = humantime::parse_duration(since).map_err(|_| {
+                anyhow!("Invalid time {since}, expected milliseconds, a date or a duration")
+            })?;
+            SystemTime::now()
+                .checked_sub(ago)
+                .ok_or_else(|| anyhow!("Duration {since} reaches before the unix epoch"))?
+        }
+    };
+    Ok(time.duration_since(UNIX_EPOCH)?.as_millis() as u64)
+}
+
+/// Reads the transactions `address` sent from `since_ms` on, latest first, into a report.
+pub async fn gas_report(
+    client: &MgoClient,
+    address: MgoAddress,
+    since_ms: Option<u64>,
+) -> Result<GasReport, anyhow::Error> {
+    let query = MgoTransactionBlockResponseQuery::new(
+        Some(TransactionFilter::FromAddress(address)),
+        Some(
+            MgoTransactionBlockResponseOptions::new()
+                .with_input()
+                .with_effects(),
+        ),
+    );
+    let mut report = GasReport::new(address, since_ms);
+    let mut cursor = None;
+    loop {
+        let page = client
+            .read_api()
+            .query_transaction_blocks(query.clone(), cursor, Some(GAS_REPORT_PAGE_SIZE), true)
+            .await?;
+        for transaction in &page.data {
+            report.add(transaction);
+        }
+        // Transactions are read latest first, so the rest of the history predates the report.
+        let reached_since = matches!(
+            (since_ms, page.data.last().and_then(|t| t.timestamp_ms)),
+            (Some(since), Some(timestamp)) if timestamp < since
+        );
+        if !page.has_next_page || reached_since {
+            break;
+        }
+        cursor = page.next_cursor;
+    }
+    Ok(report)
+}
+
+impl Display for GasReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Gas spent by {} over {} transactions",
+            self.address, self.total.transactions
+        )?;
+        if self.total.unknown_gas_transactions > 0 {
+            writeln!(
+                f,
+                "The gas of {} transactions is unknown, their effects are missing",
+                self.total.unknown_gas_transactions
+            )?;
+        }
+        writeln!(f, "{}", usage_table("Day", &self.by_day))?;
+        writeln!(f, "{}", usage_table("Function", &self.by_function))
+    }
+}
+
+fn usage_table(key: &str, usages: &BTreeMap<String, GasUsage>) -> String {
+    let mut builder = TableBuilder::default();
+    builder.set_header([
+        key,
+        "Transactions",
+        "Unknown gas",
+        "Computation",
+        "Storage",
+        "Rebate",
+        "Net gas",
+    ]);
+    for (name, usage) in usages {
+        builder.push_record([
+            name.clone(),
+            usage.transactions.to_string(),
+            usage.unknown_gas_transactions.to_string(),
+            usage.computation_cost.to_string(),
+            usage.storage_cost.to_string(),
+            usage.storage_rebate.to_string(),
+            usage.net_gas().to_string(),
+        ]);
+    }
+    let mut table = builder.build();
+    table.with(TableStyle::rounded());
+    table.to_string()
+}
+
+#[cfg(test)]
+#[path = "unit_tests/gas_report_tests.rs"]
+mod gas_report_tests;
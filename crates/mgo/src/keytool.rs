@@ -28,6 +28,9 @@ use shared_crypto::intent::{Intent, IntentMessage, IntentScope, PersonalMessage}
 use std::fmt::{Debug, Display, Formatter};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use mgo_keys::agent::{AgentClient, KeyAgent, UnlockedKeyInfo};
 use mgo_keys::key_derive::generate_new_key;
 use mgo_keys::keypair_file::{
     read_authority_keypair_from_file, read_keypair_from_file, write_authority_keypair_to_file,
@@ -59,6 +62,12 @@ mod keytool_tests;
 #[derive(Subcommand)]
 #[clap(rename_all = "kebab-case")]
 pub enum KeyToolCommand {
+    /// Run the key agent on the given socket. Started in the background by `unlock`.
+    #[clap(hide = true)]
+    Agent {
+        #[clap(long)]
+        socket: PathBuf,
+    },
     /// Update an old alias to a new one.
     /// If a new alias is not provided, a random one will be generated.
     #[clap(name = "update-alias")]
@@ -135,6 +144,12 @@ pub enum KeyToolCommand {
     /// (Base64 encoded `privkey`). This prints out the account keypair as Base64 encoded `flag || privkey`,
     /// the network keypair, worker keypair, protocol keypair as Base64 encoded `privkey`.
     LoadKeypair { file: PathBuf },
+    /// Drop the given key from the key agent, or every key if none is given. The agent exits
+    /// once it holds no keys.
+    Lock {
+        #[clap(long, visible_alias = "alias")]
+        key_identity: Option<KeyIdentity>,
+    },
     /// To MultiSig Mgo Address. Pass in a list of all public keys `flag || pk` in Base64.
     /// See `keytool list` for example public keys.
     MultiSigAddress {
@@ -211,6 +226,16 @@ pub enum KeyToolCommand {
     /// outputs the keypair into a file at the current directory where the address is the filename,
     /// and prints out its Mgo address, Base64 encoded public key, the key scheme, and the key scheme flag.
     Unpack { keypair: String },
+    /// Hand the private key of the given key identity to the key agent, starting it if needed.
+    /// Wallet commands sign with the agent's copy of the key until the TTL runs out or the key
+    /// is locked.
+    Unlock {
+        #[clap(long, visible_alias = "alias")]
+        key_identity: KeyIdentity,
+        /// How long the key stays unlocked, e.g. `15m` or `1h`.
+        #[clap(long, value_parser = humantime::parse_duration, default_value = "15m")]
+        ttl: Duration,
+    },
 
     /// Given the max_epoch, generate an OAuth url, ask user to paste the redirect with id_token, call salt server, then call the prover server,
     /// create a test transaction, use the ephemeral key to sign and execute it by assembling to a serialized zkLogin signature.
@@ -304,6 +329,13 @@ pub struct DecodedMultiSigOutput {
     transaction_result: String,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentKeys {
+    agent_socket: PathBuf,
+    unlocked_keys: Vec<UnlockedKeyInfo>,
+}
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Key {
@@ -428,6 +460,7 @@ pub struct ZkLoginInsecureSignPersonalMessage {
 #[derive(Serialize)]
 #[serde(untagged)]
 pub enum CommandOutput {
+    Agent(AgentKeys),
     Alias(AliasUpdate),
     Convert(ConvertOutput),
     DecodeMultiSig(DecodedMultiSigOutput),
@@ -453,6 +486,14 @@ pub enum CommandOutput {
 impl KeyToolCommand {
     pub async fn execute(self, keystore: &mut Keystore) -> Result<CommandOutput, anyhow::Error> {
         let cmd_result = Ok(match self {
+            KeyToolCommand::Agent { socket } => {
+                let agent = KeyAgent::bind(&socket)?;
+                tokio::task::spawn_blocking(move || agent.run()).await??;
+                CommandOutput::Agent(AgentKeys {
+                    agent_socket: socket,
+                    unlocked_keys: vec![],
+                })
+            }
             KeyToolCommand::Alias {
                 old_alias,
                 new_alias,
@@ -620,6 +661,22 @@ impl KeyToolCommand {
                 CommandOutput::List(keys)
             }
 
+            KeyToolCommand::Lock { key_identity } => {
+                let agent = agent_client(keystore)?;
+                let address = key_identity
+                    .map(|key_identity| get_identity_address_from_keystore(key_identity, keystore))
+                    .transpose()?;
+                let unlocked_keys = if agent.is_running() {
+                    agent.lock(address)?
+                } else {
+                    vec![]
+                };
+                CommandOutput::Agent(AgentKeys {
+                    agent_socket: agent.path().to_path_buf(),
+                    unlocked_keys,
+                })
+            }
+
             KeyToolCommand::LoadKeypair { file } => {
                 let output = match read_keypair_from_file(&file) {
                     Ok(keypair) => {
@@ -857,6 +914,19 @@ impl KeyToolCommand {
                 CommandOutput::Show(key)
             }
 
+            KeyToolCommand::Unlock { key_identity, ttl } => {
+                let address = get_identity_address_from_keystore(key_identity, keystore)?;
+                let agent = agent_client(keystore)?;
+                if !agent.is_running() {
+                    start_key_agent(&agent)?;
+                }
+                let unlocked_keys = agent.unlock(keystore.get_key(&address)?, ttl)?;
+                CommandOutput::Agent(AgentKeys {
+                    agent_socket: agent.path().to_path_buf(),
+                    unlocked_keys,
+                })
+            }
+
             KeyToolCommand::ZkLoginInsecureSignPersonalMessage { data } => {
                 let msg = PersonalMessage {
                     message: data.as_bytes().to_vec(),
@@ -1241,6 +1311,33 @@ fn convert_private_key_to_bech32(value: String) -> Result<ConvertOutput, anyhow:
     })
 }
 
+fn agent_client(keystore: &Keystore) -> Result<AgentClient, anyhow::Error> {
+    AgentClient::for_keystore(keystore)
+        .ok_or_else(|| anyhow!("The key agent is only available for file based keystores"))
+}
+
+/// Starts `mgo keytool agent` in the background and waits until it accepts connections.
+fn start_key_agent(agent: &AgentClient) -> Result<(), anyhow::Error> {
+    Command::new(std::env::current_exe()?)
+        .args(["keytool", "agent", "--socket"])
+        .arg(agent.path())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| anyhow!("Cannot start the key agent: {e}"))?;
+    for _ in 0..50 {
+        if agent.is_running() {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    Err(anyhow!(
+        "Key agent did not start listening on {}",
+        agent.path().display()
+    ))
+}
+
 fn anemo_styling(pk: &PublicKey) -> Option<String> {
     if let PublicKey::Ed25519(public_key) = pk {
         Some(anemo::PeerId(public_key.0).to_string())
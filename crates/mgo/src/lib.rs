@@ -5,10 +5,13 @@
 pub mod client_commands;
 pub mod console;
 pub mod fire_drill;
+pub mod gas_report;
 pub mod genesis_ceremony;
 pub mod genesis_inspector;
 pub mod key_identity;
 pub mod keytool;
+pub mod multisig_commands;
+pub mod object_inspector;
 pub mod shell;
 pub mod mgo_commands;
 pub mod validator_commands;
@@ -12,7 +12,7 @@ use clap::*;
 use fastcrypto::traits::KeyPair;
 use move_package::BuildConfig;
 use rand::rngs::OsRng;
-use std::io::{stderr, stdout, Write};
+use std::io::{stderr, stdout, IsTerminal, Write};
 use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 use std::{fs, io};
@@ -28,6 +28,7 @@ use mgo_config::{
 use mgo_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
 use mgo_move::{self, execute_move_command};
 use mgo_move_build::MgoPackageHooks;
+use mgo_sdk::guardrail::GuardrailConfirmation;
 use mgo_sdk::mgo_client_config::{MgoClientConfig, MgoEnv};
 use mgo_sdk::wallet_context::WalletContext;
 use mgo_swarm::memory::Swarm;
@@ -118,6 +119,8 @@ pub enum MgoCommand {
         /// Return command outputs in json format.
         #[clap(long, global = true)]
         json: bool,
+        /// Accept the defaults of prompts, and sign transactions breaking the wallet guardrails
+        /// without asking for confirmation.
         #[clap(short = 'y', long = "yes")]
         accept_defaults: bool,
     },
@@ -284,7 +287,15 @@ impl MgoCommand {
             } => {
                 let config_path = config.unwrap_or(mgo_config_dir()?.join(MGO_CLIENT_CONFIG));
                 prompt_if_no_config(&config_path, accept_defaults).await?;
+                let guardrail_confirmation = if accept_defaults {
+                    GuardrailConfirmation::Accept
+                } else if io::stdin().is_terminal() {
+                    GuardrailConfirmation::Prompt
+                } else {
+                    GuardrailConfirmation::Reject
+                };
                 let mut context = WalletContext::new(&config_path, None, None).await?;
+                context.set_guardrail_confirmation(guardrail_confirmation);
                 if let Some(cmd) = cmd {
                     cmd.execute(&mut context).await?.print(!json);
                 } else {
@@ -642,6 +653,7 @@ async fn prompt_if_no_config(
                 envs: vec![env],
                 active_address: Some(new_address),
                 active_env: Some(alias),
+                guardrails: Default::default(),
             }
             .persisted(wallet_conf_path)
             .save()?;
@@ -0,0 +1,469 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! `mgo client multisig`: the transactions of a multisig address, assembled by its participants
+//! in turns. One participant writes the unsigned transaction to a file with `init-tx`, each
+//! signer signs it into a signature file of their own with `sign`, and anyone combines the
+//! signatures with `combine` once their weights reach the threshold, then submits it.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, ensure, Context};
+use clap::Subcommand;
+use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::traits::EncodeDecodeBase64;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use shared_crypto::intent::{Intent, IntentMessage};
+use tabled::builder::Builder as TableBuilder;
+use tabled::settings::Style as TableStyle;
+
+use mgo_json_rpc_types::MgoTransactionBlockResponse;
+use mgo_keys::keypair_file::sign_transaction_with_keyfiles;
+use mgo_sdk::wallet_context::WalletContext;
+use mgo_types::base_types::MgoAddress;
+use mgo_types::crypto::{default_hash, MgoSignature, PublicKey, Signature};
+use mgo_types::digests::TransactionDigest;
+use mgo_types::multisig::{MultiSig, MultiSigPublicKey, ThresholdUnit, WeightUnit};
+use mgo_types::signature::GenericSignature;
+use mgo_types::transaction::{SenderSignedData, Transaction, TransactionData, TransactionDataAPI};
+
+use crate::key_identity::{get_identity_address, KeyIdentity};
+
+#[derive(Subcommand)]
+#[clap(rename_all = "kebab-case")]
+pub enum MultisigCommand {
+    /// Write an unsigned transaction of a multisig address to a file, for its participants to
+    /// sign. The transaction is the output of any `mgo client` command run with
+    /// `--serialize-unsigned-transaction`, sent by the multisig address.
+    #[clap(name = "init-tx")]
+    InitTx {
+        /// BCS serialized transaction data, as Base64
+        #[clap(long)]
+        tx_bytes: String,
+        /// Public keys `flag || pk` of every participant of the multisig address, in Base64
+        #[clap(long, num_args(1..))]
+        pks: Vec<PublicKey>,
+        /// Weights of the participants, in the order of their public keys
+        #[clap(long, num_args(1..))]
+        weights: Vec<WeightUnit>,
+        #[clap(long)]
+        threshold: ThresholdUnit,
+        /// File to write the transaction to
+        #[clap(long)]
+        out: PathBuf,
+    },
+
+    /// Sign a transaction written by `init-tx` as one of its participants, and write the
+    /// signature to a file.
+    #[clap(name = "sign")]
+    Sign {
+        /// File written by `init-tx`
+        tx: PathBuf,
+        /// File of the participant's key, as a PKCS#8 PEM or as Base64 `flag || privkey`
+        #[clap(long, required_unless_present = "address", conflicts_with = "address")]
+        key: Option<PathBuf>,
+        /// Address (or its alias) of a participant whose key is in the keystore, instead of a
+        /// key file
+        #[clap(long)]
+        #[arg(value_parser)]
+        address: Option<KeyIdentity>,
+        /// File to write the signature to
+        #[clap(long)]
+        out: PathBuf,
+    },
+
+    /// Combine the signatures written by `sign` into a multisig signature, once the weights of
+    /// their participants reach the threshold, and print the signed transaction or submit it.
+    #[clap(name = "combine")]
+    Combine {
+        /// File written by `init-tx`
+        tx: PathBuf,
+        /// Files written by `sign`, at most one per participant
+        #[clap(num_args(1..), required = true)]
+        signatures: Vec<PathBuf>,
+        /// Execute the signed transaction instead of printing it
+        #[clap(long)]
+        submit: bool,
+    },
+}
+
+/// Unsigned transaction of a multisig address, as written by `init-tx`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MultisigTransaction {
+    /// BCS serialized transaction data, as Base64
+    pub tx_bytes: String,
+    pub digest: TransactionDigest,
+    pub multisig_address: MgoAddress,
+    pub threshold: ThresholdUnit,
+    /// Participants, in the order of the public keys of the multisig address
+    pub participants: Vec<MultisigParticipant>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MultisigParticipant {
+    pub address: MgoAddress,
+    /// `flag || pk`, in Base64
+    pub public_key: String,
+    pub weight: WeightUnit,
+}
+
+/// Signature of a participant, as written by `sign`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MultisigPartialSignature {
+    /// Digest of the transaction signed
+    pub digest: TransactionDigest,
+    pub signer: MgoAddress,
+    /// `flag || sig || pk`, in Base64
+    pub signature: String,
+}
+
+/// Participants whose signatures were combined, and the transaction they signed.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MultisigCombination {
+    pub multisig_address: MgoAddress,
+    pub threshold: ThresholdUnit,
+    /// Sum of the weights of the participants who signed
+    pub signed_weight: ThresholdUnit,
+    pub signers: Vec<MgoAddress>,
+    /// Participants who did not sign
+    pub missing: Vec<MgoAddress>,
+    /// `flag || multisig`, in Base64, for `mgo client execute-signed-tx`
+    pub multisig: String,
+    /// BCS serialized signed transaction, in Base64, for
+    /// `mgo client execute-combined-signed-tx`, unless it was submitted
+    pub signed_tx_bytes: Option<String>,
+    /// Response to the transaction, when it was submitted
+    pub response: Option<MgoTransactionBlockResponse>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum MultisigCommandResult {
+    InitTx(MultisigTransaction),
+    Sign(MultisigPartialSignature),
+    Combine(Box<MultisigCombination>),
+}
+
+impl MultisigCommand {
+    pub async fn execute(
+        self,
+        context: &mut WalletContext,
+    ) -> Result<MultisigCommandResult, anyhow::Error> {
+        Ok(match self {
+            MultisigCommand::InitTx {
+                tx_bytes,
+                pks,
+                weights,
+                threshold,
+                out,
+            } => {
+                let tx_data: TransactionData = bcs::from_bytes(&decode_base64(&tx_bytes)?)
+                    .map_err(|_| {
+                        anyhow!("Failed to parse tx bytes, check if it matches the output of mgo client commands with --serialize-unsigned-transaction")
+                    })?;
+                let multisig_pk = MultiSigPublicKey::new(pks, weights, threshold)?;
+                let transaction = MultisigTransaction::new(&tx_data, &multisig_pk)?;
+                write_json(&out, &transaction)?;
+                MultisigCommandResult::InitTx(transaction)
+            }
+
+            MultisigCommand::Sign {
+                tx,
+                key,
+                address,
+                out,
+            } => {
+                let transaction: MultisigTransaction = read_json(&tx)?;
+                let tx_data = transaction.tx_data()?;
+                let signature = match key {
+                    Some(key) => sign_transaction_with_keyfiles(
+                        &tx_data,
+                        &[key],
+                        Intent::mgo_transaction(),
+                    )?
+                    .remove(0),
+                    None => {
+                        let address = get_identity_address(address, context)?;
+                        context.sign_secure(&address, &tx_data, Intent::mgo_transaction())?
+                    }
+                };
+                let signer = signer_of(&signature)?;
+                ensure!(
+                    transaction.participant(&signer).is_some(),
+                    "{signer} is not a participant of multisig address {}",
+                    transaction.multisig_address
+                );
+                let partial = MultisigPartialSignature {
+                    digest: transaction.digest,
+                    signer,
+                    signature: signature.encode_base64(),
+                };
+                write_json(&out, &partial)?;
+                MultisigCommandResult::Sign(partial)
+            }
+
+            MultisigCommand::Combine {
+                tx,
+                signatures,
+                submit,
+            } => {
+                let transaction: MultisigTransaction = read_json(&tx)?;
+                let tx_data = transaction.tx_data()?;
+                let partials = signatures
+                    .iter()
+                    .map(|path| read_json(path))
+                    .collect::<Result<Vec<MultisigPartialSignature>, _>>()?;
+                let (signatures, mut combination) =
+                    transaction.check_signatures(&tx_data, partials)?;
+
+                let multisig: GenericSignature =
+                    MultiSig::combine(signatures, transaction.multisig_pk()?)?.into();
+                combination.multisig = multisig.encode_base64();
+                let signed =
+                    SenderSignedData::new(tx_data, Intent::mgo_transaction(), vec![multisig]);
+                if submit {
+                    let response = context
+                        .execute_transaction_may_fail(Transaction::new(signed))
+                        .await?;
+                    combination.response = Some(response);
+                } else {
+                    combination.signed_tx_bytes = Some(Base64::encode(bcs::to_bytes(&signed)?));
+                }
+                MultisigCommandResult::Combine(Box::new(combination))
+            }
+        })
+    }
+}
+
+impl MultisigTransaction {
+    pub fn new(
+        tx_data: &TransactionData,
+        multisig_pk: &MultiSigPublicKey,
+    ) -> Result<Self, anyhow::Error> {
+        let multisig_address = MgoAddress::from(multisig_pk);
+        ensure!(
+            tx_data.sender() == multisig_address,
+            "The transaction is sent by {}, not by multisig address {multisig_address}",
+            tx_data.sender()
+        );
+        Ok(Self {
+            tx_bytes: Base64::encode(bcs::to_bytes(tx_data)?),
+            digest: TransactionDigest::new(default_hash(tx_data)),
+            multisig_address,
+            threshold: *multisig_pk.threshold(),
+            participants: multisig_pk
+                .pubkeys()
+                .iter()
+                .map(|(pk, weight)| MultisigParticipant {
+                    address: MgoAddress::from(pk),
+                    public_key: pk.encode_base64(),
+                    weight: *weight,
+                })
+                .collect(),
+        })
+    }
+
+    /// The transaction data, checked against the digest it was written with.
+    pub fn tx_data(&self) -> Result<TransactionData, anyhow::Error> {
+        let tx_data: TransactionData = bcs::from_bytes(&decode_base64(&self.tx_bytes)?)
+            .context("Failed to parse the tx bytes of the transaction file")?;
+        ensure!(
+            TransactionDigest::new(default_hash(&tx_data)) == self.digest,
+            "The tx bytes of the transaction file do not match its digest {}",
+            self.digest
+        );
+        Ok(tx_data)
+    }
+
+    /// The public key of the multisig address, checked against the address.
+    pub fn multisig_pk(&self) -> Result<MultiSigPublicKey, anyhow::Error> {
+        let pks = self
+            .participants
+            .iter()
+            .map(|p| PublicKey::decode_base64(&p.public_key).map_err(|e| anyhow!(e)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let weights = self.participants.iter().map(|p| p.weight).collect();
+        let multisig_pk = MultiSigPublicKey::new(pks, weights, self.threshold)?;
+        ensure!(
+            MgoAddress::from(&multisig_pk) == self.multisig_address,
+            "The participants of the transaction file do not make up multisig address {}",
+            self.multisig_address
+        );
+        Ok(multisig_pk)
+    }
+
+    fn participant(&self, address: &MgoAddress) -> Option<&MultisigParticipant> {
+        self.participants.iter().find(|p| p.address == *address)
+    }
+
+    /// Checks that every signature is a participant's valid signature of `tx_data`, at most one
+    /// per participant, and that their weights reach the threshold. Returns the signatures in
+    /// the order of the participants, as multisig signatures combine them, with the multisig
+    /// signature of the combination left to set.
+    fn check_signatures(
+        &self,
+        tx_data: &TransactionData,
+        partials: Vec<MultisigPartialSignature>,
+    ) -> Result<(Vec<GenericSignature>, MultisigCombination), anyhow::Error> {
+        let intent_msg = IntentMessage::new(Intent::mgo_transaction(), tx_data);
+        let mut by_signer = BTreeMap::new();
+        for partial in partials {
+            ensure!(
+                partial.digest == self.digest,
+                "The signature of {} is of transaction {}, not of {}",
+                partial.signer,
+                partial.digest,
+                self.digest
+            );
+            let signature = Signature::decode_base64(&partial.signature)
+                .map_err(|e| anyhow!("Invalid signature of {}: {e}", partial.signer))?;
+            let signer = signer_of(&signature)?;
+            ensure!(
+                signer == partial.signer,
+                "The signature attributed to {} was made by {signer}",
+                partial.signer
+            );
+            ensure!(
+                self.participant(&signer).is_some(),
+                "{signer} is not a participant of multisig address {}",
+                self.multisig_address
+            );
+            signature
+                .verify_secure(&intent_msg, signer, signature.scheme())
+                .map_err(|e| anyhow!("Invalid signature of {signer}: {e}"))?;
+            if by_signer.insert(signer, signature).is_some() {
+                bail!("{signer} signed more than once");
+            }
+        }
+
+        let mut signatures = vec![];
+        let mut signers = vec![];
+        let mut missing = vec![];
+        let mut signed_weight: ThresholdUnit = 0;
+        for participant in &self.participants {
+            match by_signer.remove(&participant.address) {
+                Some(signature) => {
+                    signatures.push(GenericSignature::Signature(signature));
+                    signers.push(participant.address);
+                    signed_weight += participant.weight as ThresholdUnit;
+                }
+                None => missing.push(participant.address),
+            }
+        }
+        if signed_weight < self.threshold {
+            bail!(
+                "Signatures of {} weigh {signed_weight}, below the threshold of {}. Missing signatures of {}",
+                join(&signers),
+                self.threshold,
+                join(&missing)
+            );
+        }
+        Ok((
+            signatures,
+            MultisigCombination {
+                multisig_address: self.multisig_address,
+                threshold: self.threshold,
+                signed_weight,
+                signers,
+                missing,
+                multisig: String::new(),
+                signed_tx_bytes: None,
+                response: None,
+            },
+        ))
+    }
+}
+
+fn signer_of(signature: &Signature) -> Result<MgoAddress, anyhow::Error> {
+    let pk = GenericSignature::Signature(signature.clone()).to_public_key()?;
+    Ok(MgoAddress::from(&pk))
+}
+
+fn decode_base64(encoded: &str) -> Result<Vec<u8>, anyhow::Error> {
+    Base64::decode(encoded).map_err(|_| anyhow!("Invalid Base64 encoding"))
+}
+
+fn join(addresses: &[MgoAddress]) -> String {
+    if addresses.is_empty() {
+        return "none".to_string();
+    }
+    addresses
+        .iter()
+        .map(|a| a.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, anyhow::Error> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&contents).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), anyhow::Error> {
+    std::fs::write(path, serde_json::to_string_pretty(value)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+impl Display for MultisigCommandResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MultisigCommandResult::InitTx(transaction) => {
+                writeln!(
+                    f,
+                    "Transaction {} of multisig address {}, threshold {}",
+                    transaction.digest, transaction.multisig_address, transaction.threshold
+                )?;
+                let mut builder = TableBuilder::default();
+                builder.set_header(["participant", "weight"]);
+                for participant in &transaction.participants {
+                    builder.push_record([
+                        participant.address.to_string(),
+                        participant.weight.to_string(),
+                    ]);
+                }
+                let mut table = builder.build();
+                table.with(TableStyle::rounded());
+                write!(f, "{table}")
+            }
+            MultisigCommandResult::Sign(partial) => write!(
+                f,
+                "Signed transaction {} as {}",
+                partial.digest, partial.signer
+            ),
+            MultisigCommandResult::Combine(combination) => {
+                writeln!(
+                    f,
+                    "Signatures of multisig address {} weigh {} of the threshold {}",
+                    combination.multisig_address, combination.signed_weight, combination.threshold
+                )?;
+                let mut builder = TableBuilder::default();
+                builder.set_header(["participant", "signed"]);
+                for signer in &combination.signers {
+                    builder.push_record([signer.to_string(), "yes".to_string()]);
+                }
+                for missing in &combination.missing {
+                    builder.push_record([missing.to_string(), "no".to_string()]);
+                }
+                let mut table = builder.build();
+                table.with(TableStyle::rounded());
+                writeln!(f, "{table}")?;
+                writeln!(f, "Multisig: {}", combination.multisig)?;
+                match (&combination.response, &combination.signed_tx_bytes) {
+                    (Some(response), _) => write!(f, "{response}"),
+                    (None, Some(signed_tx_bytes)) => {
+                        write!(f, "Signed transaction: {signed_tx_bytes}")
+                    }
+                    (None, None) => Ok(()),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,348 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter, Write};
+
+use anyhow::anyhow;
+use mgo_json_rpc_types::{
+    MgoMoveStruct, MgoMoveValue, MgoObjectData, MgoObjectDataOptions, MgoParsedData,
+    MgoTransactionBlockEffectsAPI, MgoTransactionBlockResponseOptions,
+};
+use mgo_sdk::MgoClient;
+use mgo_types::base_types::{ObjectID, SequenceNumber};
+use mgo_types::digests::{ObjectDigest, TransactionDigest};
+use mgo_types::object::Owner;
+use serde::Serialize;
+use tracing::info;
+
+/// An object as shown by `mgo client inspect-object`: its Move fields, its Display fields and
+/// optionally the versions it went through.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectInspection {
+    pub object_id: ObjectID,
+    pub version: SequenceNumber,
+    pub digest: ObjectDigest,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub owner: Option<Owner>,
+    pub previous_transaction: Option<TransactionDigest>,
+    /// Fields of a Move object, none for packages.
+    pub fields: Option<MgoMoveStruct>,
+    /// Display fields of the object, none if its type has no Display.
+    pub display: Option<BTreeMap<String, String>>,
+    /// Versions of the object, latest first, when its history was asked for.
+    pub history: Option<Vec<ObjectVersion>>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectVersion {
+    pub version: SequenceNumber,
+    pub digest: ObjectDigest,
+    /// The transaction that created this version.
+    pub previous_transaction: Option<TransactionDigest>,
+}
+
+impl From<&MgoObjectData> for ObjectVersion {
+    fn from(data: &MgoObjectData) -> Self {
+        Self {
+            version: data.version,
+            digest: data.digest,
+            previous_transaction: data.previous_transaction,
+        }
+    }
+}
+
+impl From<MgoObjectData> for ObjectInspection {
+    fn from(data: MgoObjectData) -> Self {
+        let fields = match data.content {
+            Some(MgoParsedData::MoveObject(object)) => Some(object.fields),
+            Some(MgoParsedData::Package(_)) | None => None,
+        };
+        Self {
+            object_id: data.object_id,
+            version: data.version,
+            digest: data.digest,
+            type_: data.type_.map(|t| t.to_string()),
+            owner: data.owner,
+            previous_transaction: data.previous_transaction,
+            fields,
+            display: data.display.and_then(|display| display.data),
+            history: None,
+        }
+    }
+}
+
+/// Fetches `id` at `version`, or its latest version, along with its last `history` versions.
+pub async fn inspect_object(
+    client: &MgoClient,
+    id: ObjectID,
+    version: Option<SequenceNumber>,
+    history: Option<usize>,
+) -> Result<ObjectInspection, anyhow::Error> {
+    let options = MgoObjectDataOptions::full_content().with_display();
+    let data = match version {
+        Some(version) => past_object(client, id, version, options).await?,
+        None => client
+            .read_api()
+            .get_object_with_options(id, options)
+            .await?
+            .into_object()?,
+    };
+    let versions = match history {
+        Some(history) => Some(object_history(client, &data, history).await),
+        None => None,
+    };
+    Ok(ObjectInspection {
+        history: versions,
+        ..ObjectInspection::from(data)
+    })
+}
+
+async fn past_object(
+    client: &MgoClient,
+    id: ObjectID,
+    version: SequenceNumber,
+    options: MgoObjectDataOptions,
+) -> Result<MgoObjectData, anyhow::Error> {
+    Ok(client
+        .read_api()
+        .try_get_parsed_past_object(id, version, options)
+        .await?
+        .into_object()?)
+}
+
+/// The last `limit` versions of the object of `data`, starting from `data`. Each version is
+/// reached from the next one through the transaction that created it, so the history stops
+/// early at the version the object was created at, or at versions the fullnode has pruned.
+async fn object_history(
+    client: &MgoClient,
+    data: &MgoObjectData,
+    limit: usize,
+) -> Vec<ObjectVersion> {
+    let mut versions = vec![ObjectVersion::from(data)];
+    while versions.len() < limit {
+        let Some(transaction) = versions[versions.len() - 1].previous_transaction else {
+            break;
+        };
+        match previous_version(client, data.object_id, transaction).await {
+            Ok(Some(previous)) => versions.push(ObjectVersion::from(&previous)),
+            // The object was created by this transaction.
+            Ok(None) => break,
+            Err(e) => {
+                info!("History of {} ends early: {e}", data.object_id);
+                break;
+            }
+        }
+    }
+    versions.truncate(limit);
+    versions
+}
+
+/// The version of `id` that `transaction` took as input, if it did not create `id`.
+async fn previous_version(
+    client: &MgoClient,
+    id: ObjectID,
+    transaction: TransactionDigest,
+) -> Result<Option<MgoObjectData>, anyhow::Error> {
+    if transaction == TransactionDigest::genesis_marker() {
+        return Ok(None);
+    }
+    let effects = client
+        .read_api()
+        .get_transaction_with_options(
+            transaction,
+            MgoTransactionBlockResponseOptions::new().with_effects(),
+        )
+        .await?
+        .effects
+        .ok_or_else(|| anyhow!("Missing effects of transaction {transaction}"))?;
+    let Some((_, version)) = effects
+        .modified_at_versions()
+        .into_iter()
+        .find(|(modified, _)| *modified == id)
+    else {
+        return Ok(None);
+    };
+    let options = MgoObjectDataOptions::new().with_previous_transaction();
+    Ok(Some(past_object(client, id, version, options).await?))
+}
+
+impl Display for ObjectInspection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Object {} at version {}",
+            self.object_id,
+            self.version.value()
+        )?;
+        writeln!(f, "  digest: {}", self.digest)?;
+        if let Some(type_) = &self.type_ {
+            writeln!(f, "  type: {type_}")?;
+        }
+        if let Some(owner) = &self.owner {
+            writeln!(f, "  owner: {owner}")?;
+        }
+        if let Some(transaction) = &self.previous_transaction {
+            writeln!(f, "  previousTransaction: {transaction}")?;
+        }
+        if let Some(fields) = &self.fields {
+            writeln!(f, "Fields")?;
+            let mut tree = String::new();
+            write_struct(&mut tree, "  ", fields)?;
+            write!(f, "{tree}")?;
+        }
+        if let Some(display) = &self.display {
+            writeln!(f, "Display")?;
+            for (i, (name, value)) in display.iter().enumerate() {
+                let branch = if i + 1 == display.len() {
+                    "└─"
+                } else {
+                    "├─"
+                };
+                writeln!(f, "  {branch} {name}: {value}")?;
+            }
+        }
+        if let Some(history) = &self.history {
+            writeln!(f, "History")?;
+            for version in history {
+                let transaction = version
+                    .previous_transaction
+                    .map_or_else(|| "unknown".to_string(), |t| t.to_string());
+                writeln!(
+                    f,
+                    "  version {}  digest {}  transaction {}",
+                    version.version.value(),
+                    version.digest,
+                    transaction
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn struct_fields(move_struct: &MgoMoveStruct) -> Vec<(String, &MgoMoveValue)> {
+    match move_struct {
+        MgoMoveStruct::Runtime(values) => values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| (format!("[{i}]"), value))
+            .collect(),
+        MgoMoveStruct::WithTypes { fields, .. } | MgoMoveStruct::WithFields(fields) => fields
+            .iter()
+            .map(|(name, value)| (name.clone(), value))
+            .collect(),
+    }
+}
+
+/// Writes the fields of `move_struct` as the branches of a tree, each line starting with
+/// `indent`.
+fn write_struct(out: &mut String, indent: &str, move_struct: &MgoMoveStruct) -> std::fmt::Result {
+    write_branches(out, indent, struct_fields(move_struct))
+}
+
+fn write_branches(
+    out: &mut String,
+    indent: &str,
+    branches: Vec<(String, &MgoMoveValue)>,
+) -> std::fmt::Result {
+    let count = branches.len();
+    for (i, (name, value)) in branches.into_iter().enumerate() {
+        let last = i + 1 == count;
+        let branch = if last { "└─" } else { "├─" };
+        let child_indent = format!("{indent}{}", if last { "   " } else { "│  " });
+        match value {
+            MgoMoveValue::Struct(move_struct) => {
+                match move_struct {
+                    MgoMoveStruct::WithTypes { type_, .. } => {
+                        writeln!(out, "{indent}{branch} {name}: {type_}")?
+                    }
+                    _ => writeln!(out, "{indent}{branch} {name}")?,
+                }
+                write_struct(out, &child_indent, move_struct)?;
+            }
+            MgoMoveValue::Vector(values) if render_leaf(value).is_none() => {
+                writeln!(out, "{indent}{branch} {name}")?;
+                let items = values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| (format!("[{i}]"), value))
+                    .collect();
+                write_branches(out, &child_indent, items)?;
+            }
+            MgoMoveValue::Option(option) if render_leaf(value).is_none() => {
+                writeln!(out, "{indent}{branch} {name}: some")?;
+                if let Some(value) = &**option {
+                    write_branches(out, &child_indent, vec![("value".to_string(), value)])?;
+                }
+            }
+            _ => {
+                let leaf = render_leaf(value).unwrap_or_default();
+                writeln!(out, "{indent}{branch} {name}: {leaf}")?
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `value` on a single line, if it fits on one: anything but structs, and vectors and options
+/// of such values.
+fn render_leaf(value: &MgoMoveValue) -> Option<String> {
+    Some(match value {
+        MgoMoveValue::Number(n) => n.to_string(),
+        MgoMoveValue::Bool(b) => b.to_string(),
+        MgoMoveValue::Address(address) => address.to_string(),
+        MgoMoveValue::String(s) => s.clone(),
+        MgoMoveValue::UID { id } => id.to_string(),
+        MgoMoveValue::Option(option) => match &**option {
+            None => "none".to_string(),
+            Some(value) => format!("some({})", render_leaf(value)?),
+        },
+        MgoMoveValue::Vector(values) => match as_bytes(values) {
+            Some(bytes) => render_bytes(&bytes),
+            None => {
+                let items = values.iter().map(render_leaf).collect::<Option<Vec<_>>>()?;
+                format!("[{}]", items.join(", "))
+            }
+        },
+        MgoMoveValue::Struct(_) => return None,
+    })
+}
+
+/// The bytes of a `vector<u8>`, as far as can be told from its decoded values: a non-empty
+/// vector of small numbers.
+fn as_bytes(values: &[MgoMoveValue]) -> Option<Vec<u8>> {
+    if values.is_empty() {
+        return None;
+    }
+    values
+        .iter()
+        .map(|value| match value {
+            MgoMoveValue::Number(n) => u8::try_from(*n).ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Bytes as quoted text when they are printable UTF-8, as is common for names and URLs, and as
+/// hex otherwise.
+fn render_bytes(bytes: &[u8]) -> String {
+    let len = bytes.len();
+    match std::str::from_utf8(bytes) {
+        Ok(text) if !text.chars().any(char::is_control) => format!("{text:?} ({len} bytes)"),
+        _ => {
+            let hex = bytes.iter().fold(String::new(), |mut hex, byte| {
+                let _ = write!(hex, "{byte:02x}");
+                hex
+            });
+            format!("0x{hex} ({len} bytes)")
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "unit_tests/object_inspector_tests.rs"]
+mod object_inspector_tests;
@@ -0,0 +1,208 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use mgo_json_rpc_types::{
+    MgoExecutionStatus, MgoGasData, MgoObjectRef, MgoProgrammableMoveCall,
+    MgoProgrammableTransactionBlock, MgoTransactionBlock, MgoTransactionBlockData,
+    MgoTransactionBlockDataV1, MgoTransactionBlockEffects, MgoTransactionBlockEffectsV1,
+    OwnedObjectRef,
+};
+use mgo_types::base_types::{random_object_ref, ObjectID};
+use mgo_types::digests::TransactionDigest;
+use mgo_types::object::Owner;
+
+use super::*;
+
+/// 2024-03-01T00:00:00Z
+const MARCH_1_MS: u64 = 1_709_251_200_000;
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+fn sender() -> MgoAddress {
+    MgoAddress::from(ObjectID::from_hex_literal("0xa11ce").unwrap())
+}
+
+fn move_call(module: &str, function: &str) -> MgoCommand {
+    MgoCommand::MoveCall(Box::new(MgoProgrammableMoveCall {
+        package: ObjectID::from_hex_literal("0x42").unwrap(),
+        module: module.to_string(),
+        function: function.to_string(),
+        type_arguments: vec![],
+        arguments: vec![],
+    }))
+}
+
+fn input(commands: Vec<MgoCommand>) -> MgoTransactionBlock {
+    MgoTransactionBlock {
+        data: MgoTransactionBlockData::V1(MgoTransactionBlockDataV1 {
+            transaction: MgoTransactionBlockKind::ProgrammableTransaction(
+                MgoProgrammableTransactionBlock {
+                    inputs: vec![],
+                    commands,
+                },
+            ),
+            sender: sender(),
+            gas_data: MgoGasData {
+                payment: vec![],
+                owner: sender(),
+                price: 1000,
+                budget: 10_000_000,
+            },
+        }),
+        tx_signatures: vec![],
+    }
+}
+
+fn effects(computation: u64, storage: u64, rebate: u64) -> MgoTransactionBlockEffects {
+    MgoTransactionBlockEffects::V1(MgoTransactionBlockEffectsV1 {
+        status: MgoExecutionStatus::Success,
+        executed_epoch: 0,
+        gas_used: GasCostSummary::new(computation, storage, rebate, 0),
+        modified_at_versions: vec![],
+        shared_objects: vec![],
+        transaction_digest: TransactionDigest::random(),
+        created: vec![],
+        mutated: vec![],
+        unwrapped: vec![],
+        deleted: vec![],
+        unwrapped_then_deleted: vec![],
+        wrapped: vec![],
+        gas_object: OwnedObjectRef {
+            owner: Owner::AddressOwner(sender()),
+            reference: MgoObjectRef::from(random_object_ref()),
+        },
+        events_digest: None,
+        dependencies: vec![],
+    })
+}
+
+fn transaction(
+    timestamp_ms: Option<u64>,
+    commands: Option<Vec<MgoCommand>>,
+    effects: Option<MgoTransactionBlockEffects>,
+) -> MgoTransactionBlockResponse {
+    MgoTransactionBlockResponse {
+        digest: TransactionDigest::random(),
+        transaction: commands.map(input),
+        effects,
+        timestamp_ms,
+        ..Default::default()
+    }
+}
+
+fn usage(transactions: u64, unknown: u64, gas: (u64, u64, u64)) -> GasUsage {
+    GasUsage {
+        transactions,
+        unknown_gas_transactions: unknown,
+        computation_cost: gas.0,
+        storage_cost: gas.1,
+        storage_rebate: gas.2,
+    }
+}
+
+#[test]
+fn test_gas_by_day_and_function() {
+    let mint = || move_call("nft", "mint");
+    let burn = || move_call("nft", "burn");
+    let mut report = GasReport::new(sender(), None);
+    for transaction in [
+        // Calls mint twice, counted once.
+        transaction(
+            Some(MARCH_1_MS + 10),
+            Some(vec![mint(), mint()]),
+            Some(effects(100, 50, 10)),
+        ),
+        transaction(
+            Some(MARCH_1_MS + DAY_MS - 1),
+            Some(vec![mint(), burn()]),
+            Some(effects(200, 0, 40)),
+        ),
+        // A transfer, on the next day.
+        transaction(Some(MARCH_1_MS + DAY_MS), Some(vec![]), Some(effects(7, 3, 0))),
+    ] {
+        report.add(&transaction);
+    }
+
+    assert_eq!(report.total, usage(3, 0, (307, 53, 50)));
+    assert_eq!(report.total.net_gas(), 310);
+    assert_eq!(
+        report.by_day,
+        BTreeMap::from([
+            ("2024-03-01".to_string(), usage(2, 0, (300, 50, 50))),
+            ("2024-03-02".to_string(), usage(1, 0, (7, 3, 0))),
+        ])
+    );
+    let package = ObjectID::from_hex_literal("0x42").unwrap();
+    assert_eq!(
+        report.by_function,
+        BTreeMap::from([
+            (format!("{package}::nft::burn"), usage(1, 0, (200, 0, 40))),
+            (format!("{package}::nft::mint"), usage(2, 0, (300, 50, 50))),
+            (NO_MOVE_CALL.to_string(), usage(1, 0, (7, 3, 0))),
+        ])
+    );
+    // Refunds can outweigh the costs.
+    assert_eq!(report.by_function[&format!("{package}::nft::burn")].net_gas(), 160);
+}
+
+#[test]
+fn test_missing_effects_and_input_are_unknown() {
+    let mut report = GasReport::new(sender(), None);
+    for transaction in [
+        transaction(
+            Some(MARCH_1_MS),
+            Some(vec![move_call("game", "play")]),
+            None,
+        ),
+        transaction(None, None, Some(effects(10, 0, 0))),
+        transaction(None, None, None),
+    ] {
+        report.add(&transaction);
+    }
+
+    assert_eq!(report.total, usage(3, 2, (10, 0, 0)));
+    assert_eq!(
+        report.by_day,
+        BTreeMap::from([
+            ("2024-03-01".to_string(), usage(1, 1, (0, 0, 0))),
+            (UNKNOWN.to_string(), usage(2, 1, (10, 0, 0))),
+        ])
+    );
+    let package = ObjectID::from_hex_literal("0x42").unwrap();
+    assert_eq!(
+        report.by_function,
+        BTreeMap::from([
+            (format!("{package}::game::play"), usage(1, 1, (0, 0, 0))),
+            (UNKNOWN.to_string(), usage(2, 1, (10, 0, 0))),
+        ])
+    );
+}
+
+#[test]
+fn test_transactions_before_since_are_skipped() {
+    let mut report = GasReport::new(sender(), Some(MARCH_1_MS));
+    for transaction in [
+        transaction(Some(MARCH_1_MS - 1), Some(vec![]), Some(effects(1, 0, 0))),
+        transaction(Some(MARCH_1_MS), Some(vec![]), Some(effects(2, 0, 0))),
+        // Of unknown time, so kept.
+        transaction(None, Some(vec![]), Some(effects(4, 0, 0))),
+    ] {
+        report.add(&transaction);
+    }
+    assert_eq!(report.total, usage(2, 0, (6, 0, 0)));
+}
+
+#[test]
+fn test_parse_since() {
+    assert_eq!(parse_since("1709251200000").unwrap(), MARCH_1_MS);
+    assert_eq!(parse_since("2024-03-01").unwrap(), MARCH_1_MS);
+    assert_eq!(parse_since("2024-03-01T12:00:00Z").unwrap(), MARCH_1_MS + DAY_MS / 2);
+
+    let now_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+    let week_ago = parse_since("7days").unwrap();
+    assert!(week_ago <= now_ms - 7 * DAY_MS && week_ago > now_ms - 8 * DAY_MS);
+
+    assert!(parse_since("yesterday").is_err());
+}
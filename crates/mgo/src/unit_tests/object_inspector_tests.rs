@@ -0,0 +1,188 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use expect_test::expect;
+use mgo_json_rpc_types::{DisplayFieldsResponse, MgoParsedMoveObject};
+use mgo_types::base_types::{MoveObjectType, ObjectType};
+use mgo_types::parse_mgo_struct_tag;
+use serde_json::Value;
+
+use super::*;
+
+fn bytes(bytes: &[u8]) -> MgoMoveValue {
+    MgoMoveValue::Vector(
+        bytes
+            .iter()
+            .map(|b| MgoMoveValue::Number(*b as u32))
+            .collect(),
+    )
+}
+
+fn fixture_object() -> MgoObjectData {
+    let id = ObjectID::from_hex_literal("0x5").unwrap();
+    let owner = ObjectID::from_hex_literal("0x42").unwrap();
+    let stats = MgoMoveStruct::WithTypes {
+        type_: parse_mgo_struct_tag("0x2::example::Stats").unwrap(),
+        fields: BTreeMap::from([
+            ("attack".to_string(), MgoMoveValue::Number(7)),
+            ("alive".to_string(), MgoMoveValue::Bool(true)),
+        ]),
+    };
+    let hero_type = parse_mgo_struct_tag("0x2::example::Hero").unwrap();
+    let fields = MgoMoveStruct::WithTypes {
+        type_: hero_type.clone(),
+        fields: BTreeMap::from([
+            ("id".to_string(), MgoMoveValue::UID { id }),
+            ("name".to_string(), bytes(b"Excalibur")),
+            ("hash".to_string(), bytes(&[0, 255, 16])),
+            ("level".to_string(), MgoMoveValue::String("12".to_string())),
+            (
+                "tags".to_string(),
+                MgoMoveValue::Vector(vec![
+                    MgoMoveValue::String("a".to_string()),
+                    MgoMoveValue::String("b".to_string()),
+                ]),
+            ),
+            ("note".to_string(), MgoMoveValue::Option(Box::new(None))),
+            ("stats".to_string(), MgoMoveValue::Struct(stats)),
+        ]),
+    };
+    MgoObjectData {
+        object_id: id,
+        version: SequenceNumber::from_u64(7),
+        digest: ObjectDigest::new([1; 32]),
+        type_: Some(ObjectType::Struct(MoveObjectType::from(hero_type.clone()))),
+        latest_type: None,
+        owner: Some(Owner::AddressOwner(owner.into())),
+        previous_transaction: Some(TransactionDigest::new([2; 32])),
+        previous_transaction_details: None,
+        storage_rebate: Some(100),
+        display: Some(DisplayFieldsResponse {
+            data: Some(BTreeMap::from([
+                ("name".to_string(), "Excalibur".to_string()),
+                (
+                    "image_url".to_string(),
+                    "https://example.com/excalibur.png".to_string(),
+                ),
+            ])),
+            error: None,
+        }),
+        content: Some(MgoParsedData::MoveObject(MgoParsedMoveObject {
+            type_: hero_type,
+            has_public_transfer: true,
+            fields,
+        })),
+        bcs: None,
+    }
+}
+
+fn fixture_inspection() -> ObjectInspection {
+    let data = fixture_object();
+    let history = vec![
+        ObjectVersion::from(&data),
+        ObjectVersion {
+            version: SequenceNumber::from_u64(4),
+            digest: ObjectDigest::new([4; 32]),
+            previous_transaction: Some(TransactionDigest::new([3; 32])),
+        },
+    ];
+    ObjectInspection {
+        history: Some(history),
+        ..ObjectInspection::from(data)
+    }
+}
+
+#[test]
+fn test_human_output() {
+    let expect = expect![[r#"
+        Object 0x0000000000000000000000000000000000000000000000000000000000000005 at version 7
+          digest: 4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi
+          type: 0x2::example::Hero
+          owner: Account Address ( 0x0000000000000000000000000000000000000000000000000000000000000042 )
+          previousTransaction: 8qbHbw2BbbTHBW1sbeqakYXVKRQM8Ne7pLK7m6CVfeR
+        Fields
+          ├─ hash: 0x00ff10 (3 bytes)
+          ├─ id: 0x0000000000000000000000000000000000000000000000000000000000000005
+          ├─ level: 12
+          ├─ name: "Excalibur" (9 bytes)
+          ├─ note: none
+          ├─ stats: 0x2::example::Stats
+          │  ├─ alive: true
+          │  └─ attack: 7
+          └─ tags: [a, b]
+        Display
+          ├─ image_url: https://example.com/excalibur.png
+          └─ name: Excalibur
+        History
+          version 7  digest 4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi  transaction 8qbHbw2BbbTHBW1sbeqakYXVKRQM8Ne7pLK7m6CVfeR
+          version 4  digest GgBaCs3NCBuZN12kCJgAW63ydqohFkHEdfdEXBPzLHq  transaction CktRuQ2mttgRGkXJtyksdKHjUdc2C4TgDzyB98oEzy8
+    "#]];
+    expect.assert_eq(&fixture_inspection().to_string());
+}
+
+#[test]
+fn test_byte_vector_heuristics() {
+    let render = |value: &[u8]| render_leaf(&bytes(value)).unwrap();
+    assert_eq!(render(b"https://a.b/c"), r#""https://a.b/c" (13 bytes)"#);
+    assert_eq!(render(b"line\nbreak"), "0x6c696e650a627265616b (10 bytes)");
+    assert_eq!(render(&[0xde, 0xad]), "0xdead (2 bytes)");
+    // Not bytes: empty vectors and vectors of larger numbers.
+    assert_eq!(render_leaf(&MgoMoveValue::Vector(vec![])).unwrap(), "[]");
+    assert_eq!(
+        render_leaf(&MgoMoveValue::Vector(vec![
+            MgoMoveValue::Number(1),
+            MgoMoveValue::Number(1000),
+        ]))
+        .unwrap(),
+        "[1, 1000]"
+    );
+}
+
+#[test]
+fn test_json_output_schema() {
+    let json = serde_json::to_value(fixture_inspection()).unwrap();
+    let object = json.as_object().unwrap();
+    let mut keys = object.keys().map(String::as_str).collect::<Vec<_>>();
+    keys.sort();
+    assert_eq!(
+        keys,
+        [
+            "digest",
+            "display",
+            "fields",
+            "history",
+            "objectId",
+            "owner",
+            "previousTransaction",
+            "type",
+            "version"
+        ]
+    );
+    assert!(json["objectId"].is_string());
+    assert_eq!(json["version"], 7);
+    assert_eq!(json["type"], "0x2::example::Hero");
+    assert_eq!(json["display"]["name"], "Excalibur");
+    // Fields keep their raw values, for scripts to decode themselves.
+    assert_eq!(json["fields"]["fields"]["level"], "12");
+    assert!(json["fields"]["fields"]["name"].is_array());
+
+    let history = json["history"].as_array().unwrap();
+    assert_eq!(history.len(), 2);
+    for version in history {
+        assert!(version["version"].is_u64());
+        assert!(version["digest"].is_string());
+        assert!(version["previousTransaction"].is_string());
+    }
+
+    // Optional parts are null rather than missing.
+    let package = ObjectInspection {
+        fields: None,
+        display: None,
+        history: None,
+        ..fixture_inspection()
+    };
+    let json = serde_json::to_value(package).unwrap();
+    for key in ["fields", "display", "history"] {
+        assert_eq!(json[key], Value::Null, "{key}");
+    }
+}
@@ -2,14 +2,38 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::validator_commands::{
-    get_validator_summary, MgoValidatorCommand, MgoValidatorCommandResponse,
+    compute_rewards, generate_validator_keys, get_validator_summary, validate_next_epoch_update,
+    MetadataChange, MetadataUpdate, MgoValidatorCommand, MgoValidatorCommandResponse,
+    ProposedUpdate, ValidatorRegistrationBundle, ValidatorStatus, Violation, NETWORK_KEY_FILE_NAME,
+    WORKER_KEY_FILE_NAME,
 };
 use anyhow::Ok;
 use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::traits::{KeyPair, ToFromBytes};
+use mgo_json_rpc_types::{Stake, StakeStatus};
+use mgo_genesis_builder::validator_info::{GenesisValidatorInfo, ValidatorInfo};
+use mgo_keys::keypair_file::{
+    read_authority_keypair_from_file, read_keypair_from_file, read_network_keypair_from_file,
+    write_authority_keypair_to_file,
+};
+use mgo_keys::keystore::AccountKeystore;
 use shared_crypto::intent::{Intent, IntentMessage};
-use mgo_types::crypto::MgoKeyPair;
-use mgo_types::transaction::TransactionData;
+use mgo_types::crypto::{
+    generate_proof_of_possession, get_authority_key_pair, get_key_pair, EncodeDecodeBase64,
+    MgoKeyPair, NetworkKeyPair,
+};
+use mgo_test_transaction_builder::TestTransactionBuilder;
+use mgo_types::governance::{
+    MAX_VALIDATOR_COMMISSION_RATE, MAX_VALIDATOR_GAS_PRICE, MAX_VALIDATOR_METADATA_LENGTH,
+    MIN_VALIDATOR_JOINING_STAKE_MIST,
+};
+use mgo_types::multiaddr::Multiaddr;
+use mgo_types::mgo_system_state::mgo_system_state_summary::MgoValidatorSummary;
+use mgo_types::mgo_system_state::PoolTokenExchangeRate;
+use mgo_types::transaction::{TransactionData, TransactionDataAPI};
 use mgo_types::{base_types::MgoAddress, crypto::Signature, transaction::Transaction};
+use mgo_types::base_types::ObjectID;
+use std::collections::{BTreeMap, BTreeSet};
 use test_cluster::TestClusterBuilder;
 
 #[tokio::test]
@@ -65,3 +89,709 @@ async fn test_print_raw_rgp_txn() -> Result<(), anyhow::Error> {
     assert_eq!(summary.next_epoch_gas_price, 42);
     Ok(())
 }
+
+#[tokio::test]
+async fn test_print_raw_commission_rate_and_metadata_txns() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let keypair: &MgoKeyPair = test_cluster
+        .swarm
+        .config()
+        .validator_configs
+        .first()
+        .unwrap()
+        .account_key_pair
+        .keypair();
+    let validator_address: MgoAddress = MgoAddress::from(&keypair.public());
+    let mut context = test_cluster.wallet;
+    let mgo_client = context.get_client().await?;
+
+    // Sign the serialized data offline, as a cold key would.
+    let sign_offline = |data: TransactionData, serialized_data: String| {
+        let deserialized_data =
+            bcs::from_bytes::<TransactionData>(&Base64::decode(&serialized_data).unwrap()).unwrap();
+        assert_eq!(deserialized_data, data);
+        let signature = Signature::new_secure(
+            &IntentMessage::new(Intent::mgo_transaction(), deserialized_data),
+            keypair,
+        );
+        Transaction::from_data(data, vec![signature])
+    };
+
+    let response = MgoValidatorCommand::DisplayCommissionRateUpdateRawTxn {
+        sender_address: validator_address,
+        new_commission_rate: 500,
+        gas_budget: None,
+    }
+    .execute(&mut context)
+    .await?;
+    let MgoValidatorCommandResponse::DisplayCommissionRateUpdateRawTxn {
+        data,
+        serialized_data,
+    } = response
+    else {
+        panic!("Expected DisplayCommissionRateUpdateRawTxn");
+    };
+    context
+        .execute_transaction_must_succeed(sign_offline(data, serialized_data))
+        .await;
+
+    let response = MgoValidatorCommand::DisplayMetadataUpdateRawTxn {
+        sender_address: validator_address,
+        metadata: MetadataUpdate::Description {
+            description: "Signed offline".to_string(),
+        },
+        gas_budget: Some(100_000_000),
+    }
+    .execute(&mut context)
+    .await?;
+    let MgoValidatorCommandResponse::DisplayMetadataUpdateRawTxn {
+        data,
+        serialized_data,
+    } = response
+    else {
+        panic!("Expected DisplayMetadataUpdateRawTxn");
+    };
+    assert_eq!(data.gas_budget(), 100_000_000);
+    context
+        .execute_transaction_must_succeed(sign_offline(data, serialized_data))
+        .await;
+
+    let (_, summary) = get_validator_summary(&mgo_client, validator_address)
+        .await?
+        .unwrap();
+    assert_eq!(summary.next_epoch_commission_rate, 500);
+    assert_eq!(summary.description, "Signed offline");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_add_self_stake() -> Result<(), anyhow::Error> {
+    const STAKE_AMOUNT: u64 = 10_000_000_000; // 10 MGO
+
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let keypair: &MgoKeyPair = test_cluster
+        .swarm
+        .config()
+        .validator_configs
+        .first()
+        .unwrap()
+        .account_key_pair
+        .keypair();
+    let validator_address: MgoAddress = MgoAddress::from(&keypair.public());
+    let keypair = MgoKeyPair::decode_base64(&keypair.encode_base64()).unwrap();
+    let mgo_client = test_cluster.wallet.get_client().await?;
+    let (_, before) = get_validator_summary(&mgo_client, validator_address)
+        .await?
+        .unwrap();
+
+    // Act as the validator from the wallet.
+    let context = &mut test_cluster.wallet;
+    context.config.keystore.add_key(None, keypair)?;
+    context.config.active_address = Some(validator_address);
+
+    let response = MgoValidatorCommand::AddSelfStake {
+        amount: STAKE_AMOUNT,
+        force: false,
+        gas_budget: None,
+        serialize_unsigned_transaction: false,
+    }
+    .execute(context)
+    .await?;
+    let MgoValidatorCommandResponse::AddSelfStake { response, stake } = response else {
+        panic!("Expected AddSelfStake");
+    };
+    assert!(response.status_ok().unwrap());
+    assert_eq!(stake.pending_stake, before.pending_stake + STAKE_AMOUNT);
+    assert_eq!(
+        stake.next_epoch_stake,
+        before.next_epoch_stake + STAKE_AMOUNT
+    );
+    assert_eq!(stake.active_stake, before.staking_pool_mgo_balance);
+
+    // The stake becomes active at the next epoch.
+    test_cluster.trigger_reconfiguration().await;
+    let (_, after) = get_validator_summary(&mgo_client, validator_address)
+        .await?
+        .unwrap();
+    assert!(after.staking_pool_mgo_balance >= before.staking_pool_mgo_balance + STAKE_AMOUNT);
+    assert_eq!(after.pending_stake, 0);
+    let self_stake = mgo_client
+        .governance_api()
+        .get_stakes(validator_address)
+        .await?
+        .into_iter()
+        .filter(|delegated| delegated.validator_address == validator_address)
+        .flat_map(|delegated| delegated.stakes)
+        .find(|s| s.principal == STAKE_AMOUNT)
+        .expect("self stake should be owned by the validator");
+    assert!(matches!(self_stake.status, StakeStatus::Active { .. }));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_display_and_withdraw_rewards() -> Result<(), anyhow::Error> {
+    const STAKE_AMOUNT: u64 = 10_000_000_000; // 10 MGO
+
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let encoded_key = test_cluster
+        .swarm
+        .config()
+        .validator_configs
+        .first()
+        .unwrap()
+        .account_key_pair
+        .keypair()
+        .encode_base64();
+    let keypair = MgoKeyPair::decode_base64(&encoded_key).unwrap();
+    let validator_address: MgoAddress = MgoAddress::from(&keypair.public());
+    let mgo_client = test_cluster.wallet.get_client().await?;
+
+    // Act as the validator from the wallet, keeping the key to sign offline.
+    let context = &mut test_cluster.wallet;
+    let wallet_key = MgoKeyPair::decode_base64(&encoded_key).unwrap();
+    context.config.keystore.add_key(None, wallet_key)?;
+    context.config.active_address = Some(validator_address);
+    MgoValidatorCommand::AddSelfStake {
+        amount: STAKE_AMOUNT,
+        force: false,
+        gas_budget: None,
+        serialize_unsigned_transaction: false,
+    }
+    .execute(context)
+    .await?;
+
+    // The stake becomes active at the next epoch, and earns rewards from then on.
+    for _ in 0..3 {
+        test_cluster.trigger_reconfiguration().await;
+    }
+    let context = &mut test_cluster.wallet;
+    let response = MgoValidatorCommand::DisplayRewards {
+        validator_address: None,
+    }
+    .execute(context)
+    .await?;
+    let MgoValidatorCommandResponse::DisplayRewards(rewards) = response else {
+        panic!("Expected DisplayRewards");
+    };
+    assert!(rewards.stakes.iter().any(|s| s.principal == STAKE_AMOUNT));
+    assert!(rewards.claimable_reward > 0);
+    assert_eq!(
+        rewards.epochs.iter().map(|e| e.reward).sum::<u64>(),
+        rewards.claimable_reward
+    );
+
+    // The latest exchange rate is the one of the pool balances at the start of the epoch.
+    let (_, summary) = get_validator_summary(&mgo_client, validator_address)
+        .await?
+        .unwrap();
+    let latest = rewards.epochs.last().unwrap();
+    assert_eq!(latest.epoch, rewards.epoch);
+    assert_eq!(latest.mgo_amount, summary.staking_pool_mgo_balance);
+    assert_eq!(latest.pool_token_amount, summary.pool_token_balance);
+    for stake in &rewards.stakes {
+        let mgo_amount = (summary.staking_pool_mgo_balance as u128
+            * stake.pool_token_amount as u128
+            / summary.pool_token_balance as u128) as u64;
+        assert_eq!(stake.reward, mgo_amount - stake.principal);
+    }
+
+    // Claim the rewards, signing offline.
+    let response = MgoValidatorCommand::WithdrawRewardsRawTxn {
+        amount: None,
+        gas_budget: None,
+    }
+    .execute(context)
+    .await?;
+    let MgoValidatorCommandResponse::WithdrawRewardsRawTxn {
+        data,
+        serialized_data,
+        amount,
+        ..
+    } = response
+    else {
+        panic!("Expected WithdrawRewardsRawTxn");
+    };
+    assert_eq!(amount, rewards.claimable_reward);
+    let deserialized_data =
+        bcs::from_bytes::<TransactionData>(&Base64::decode(&serialized_data).unwrap())?;
+    assert_eq!(deserialized_data, data);
+    let signature = Signature::new_secure(
+        &IntentMessage::new(Intent::mgo_transaction(), deserialized_data),
+        &keypair,
+    );
+    context
+        .execute_transaction_must_succeed(Transaction::from_data(data, vec![signature]))
+        .await;
+
+    // Exactly the principal of the withdrawn stakes is staked again, so that the claimed amount
+    // is exactly what the pool paid out in rewards.
+    let withdrawn_principal = rewards
+        .stakes
+        .iter()
+        .filter(|s| s.reward > 0)
+        .map(|s| s.principal)
+        .sum::<u64>();
+    let restaked = mgo_client
+        .governance_api()
+        .get_stakes(validator_address)
+        .await?
+        .into_iter()
+        .filter(|delegated| delegated.validator_address == validator_address)
+        .flat_map(|delegated| delegated.stakes)
+        .find(|s| matches!(s.status, StakeStatus::Pending))
+        .expect("principal should be staked again");
+    assert_eq!(restaked.principal, withdrawn_principal);
+    Ok(())
+}
+
+#[test]
+fn test_compute_rewards() {
+    let stake = |principal: u64, stake_active_epoch: u64| Stake {
+        staked_mgo_id: ObjectID::random(),
+        stake_request_epoch: stake_active_epoch - 1,
+        stake_active_epoch,
+        principal,
+        status: StakeStatus::Active {
+            estimated_reward: 0,
+        },
+    };
+    // The pool has no rates for epochs 3 and 5, where the rates of epochs 2 and 4 apply.
+    let rates = BTreeMap::from([
+        (1, PoolTokenExchangeRate::new(1_000, 1_000)),
+        (2, PoolTokenExchangeRate::new(1_100, 1_000)),
+        (4, PoolTokenExchangeRate::new(1_300, 1_000)),
+        (6, PoolTokenExchangeRate::new(1_500, 1_000)),
+    ]);
+    let stakes = [stake(1_000, 1), stake(550, 3)];
+
+    let (stake_rewards, epochs, claimable) = compute_rewards(&stakes, &rates, 6, u64::MAX);
+    // 1000 pool tokens bought at epoch 1, and 550 * 1000 / 1100 = 500 at epoch 3.
+    assert_eq!(stake_rewards[0].pool_token_amount, 1_000);
+    assert_eq!(stake_rewards[1].pool_token_amount, 500);
+    assert_eq!(stake_rewards[0].reward, 500);
+    assert_eq!(stake_rewards[1].reward, 200);
+    assert_eq!(claimable, 700);
+
+    let epoch_rewards = epochs
+        .iter()
+        .map(|e| (e.epoch, e.reward, e.accrued_reward))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        epoch_rewards,
+        vec![
+            (1, 0, 0),
+            (2, 100, 100),
+            (3, 0, 100),
+            (4, 300, 400),
+            (5, 0, 400),
+            (6, 300, 700),
+        ]
+    );
+
+    // Claims are paid out of the pool's rewards balance.
+    let (_, _, claimable) = compute_rewards(&stakes, &rates, 6, 600);
+    assert_eq!(claimable, 600);
+
+    // Stakes that are not active yet earn nothing.
+    let (stake_rewards, epochs, claimable) = compute_rewards(&[stake(1_000, 7)], &rates, 6, 600);
+    assert!(stake_rewards.is_empty());
+    assert!(epochs.is_empty());
+    assert_eq!(claimable, 0);
+}
+
+/// Summary of a validator with valid metadata.
+fn validator_summary() -> MgoValidatorSummary {
+    let (mgo_address, protocol_key_pair) = get_authority_key_pair();
+    let (_, network_key_pair): (_, NetworkKeyPair) = get_key_pair();
+    let (_, worker_key_pair): (_, NetworkKeyPair) = get_key_pair();
+    MgoValidatorSummary {
+        mgo_address,
+        protocol_pubkey_bytes: protocol_key_pair.public().as_bytes().to_vec(),
+        network_pubkey_bytes: network_key_pair.public().as_bytes().to_vec(),
+        worker_pubkey_bytes: worker_key_pair.public().as_bytes().to_vec(),
+        proof_of_possession_bytes: generate_proof_of_possession(&protocol_key_pair, mgo_address)
+            .as_ref()
+            .to_vec(),
+        net_address: "/dns/localhost/tcp/8080/http".to_string(),
+        p2p_address: "/dns/localhost/udp/8084".to_string(),
+        primary_address: "/dns/localhost/udp/8081".to_string(),
+        worker_address: "/dns/localhost/udp/8082".to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_validate_gas_price_update() {
+    let summary = validator_summary();
+    let update = ProposedUpdate::GasPrice(MAX_VALIDATOR_GAS_PRICE - 1);
+    assert!(validate_next_epoch_update(&summary, &update).is_empty());
+
+    let update = ProposedUpdate::GasPrice(MAX_VALIDATOR_GAS_PRICE);
+    assert_eq!(
+        validate_next_epoch_update(&summary, &update),
+        vec![Violation::GasPriceTooHigh {
+            gas_price: MAX_VALIDATOR_GAS_PRICE,
+            max: MAX_VALIDATOR_GAS_PRICE,
+        }]
+    );
+}
+
+#[test]
+fn test_validate_commission_rate_update() {
+    let summary = validator_summary();
+    let update = ProposedUpdate::CommissionRate(MAX_VALIDATOR_COMMISSION_RATE);
+    assert!(validate_next_epoch_update(&summary, &update).is_empty());
+
+    let update = ProposedUpdate::CommissionRate(MAX_VALIDATOR_COMMISSION_RATE + 1);
+    assert_eq!(
+        validate_next_epoch_update(&summary, &update),
+        vec![Violation::CommissionRateTooHigh {
+            commission_rate: MAX_VALIDATOR_COMMISSION_RATE + 1,
+            max: MAX_VALIDATOR_COMMISSION_RATE,
+        }]
+    );
+}
+
+#[test]
+fn test_validate_metadata_length() {
+    let summary = validator_summary();
+    let name = "a".repeat(MAX_VALIDATOR_METADATA_LENGTH);
+    let update = ProposedUpdate::Metadata(MetadataChange::Name(name));
+    assert!(validate_next_epoch_update(&summary, &update).is_empty());
+
+    let description = "a".repeat(MAX_VALIDATOR_METADATA_LENGTH + 1);
+    let update = ProposedUpdate::Metadata(MetadataChange::Description(description));
+    assert_eq!(
+        validate_next_epoch_update(&summary, &update),
+        vec![Violation::MetadataTooLong {
+            field: "description",
+            length: MAX_VALIDATOR_METADATA_LENGTH + 1,
+            max: MAX_VALIDATOR_METADATA_LENGTH,
+        }]
+    );
+}
+
+#[test]
+fn test_validate_next_epoch_metadata() {
+    let summary = validator_summary();
+    let update = ProposedUpdate::Metadata(MetadataChange::NetworkAddress(
+        "/dns/example.com/tcp/8080/http".parse().unwrap(),
+    ));
+    assert!(validate_next_epoch_update(&summary, &update).is_empty());
+
+    // The worker key must differ from the network key.
+    let (_, network_key_pair): (_, NetworkKeyPair) = get_key_pair();
+    let network_key = network_key_pair.public().clone();
+    let mut summary_with_next_network_key = summary.clone();
+    summary_with_next_network_key.next_epoch_network_pubkey_bytes =
+        Some(network_key.as_bytes().to_vec());
+    let update = ProposedUpdate::Metadata(MetadataChange::WorkerPubKey(network_key));
+    assert_eq!(
+        validate_next_epoch_update(&summary_with_next_network_key, &update),
+        vec![Violation::InvalidMetadata {
+            field: "worker public key",
+        }]
+    );
+
+    // A proof of possession generated for another address is rejected.
+    let (other_address, protocol_key_pair) = get_authority_key_pair();
+    let update = ProposedUpdate::Metadata(MetadataChange::ProtocolPubKey {
+        protocol_pub_key: protocol_key_pair.public().clone(),
+        proof_of_possession: generate_proof_of_possession(&protocol_key_pair, other_address),
+    });
+    assert_eq!(
+        validate_next_epoch_update(&summary, &update),
+        vec![Violation::InvalidMetadata {
+            field: "proof of possession",
+        }]
+    );
+}
+
+#[test]
+fn test_generate_keys() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output_dir = temp_dir.path().join("keys");
+    let keys = generate_validator_keys(&output_dir, false).unwrap();
+
+    let account_key = read_keypair_from_file(&keys.account_key.path).unwrap();
+    let account_address = keys.account_address;
+    assert_eq!(MgoAddress::from(&account_key.public()), account_address);
+    assert_eq!(
+        account_key.public().encode_base64(),
+        keys.account_key.public_key
+    );
+    let protocol_key = read_authority_keypair_from_file(&keys.protocol_key.path).unwrap();
+    assert_eq!(
+        protocol_key.public().encode_base64(),
+        keys.protocol_key.public_key
+    );
+    for key in [&keys.network_key, &keys.worker_key] {
+        let network_key = read_network_keypair_from_file(&key.path).unwrap();
+        assert_eq!(network_key.public().encode_base64(), key.public_key);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&keys.protocol_key.path)
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    let account_key_path = keys.account_key.path.clone();
+    let protocol_public_key = keys.protocol_key.public_key.clone();
+    let printed = MgoValidatorCommandResponse::GenerateKeys(keys).to_string();
+    assert!(printed.contains(&format!("Account address: {account_address}")));
+    assert!(printed.contains(&format!("Protocol public key: {protocol_public_key}")));
+
+    // Existing keys are kept unless overwriting is asked for.
+    let err = generate_validator_keys(&output_dir, false).unwrap_err();
+    assert!(err.to_string().contains("--overwrite"), "{err}");
+    let account_key = read_keypair_from_file(&account_key_path).unwrap();
+    assert_eq!(MgoAddress::from(&account_key.public()), account_address);
+
+    let rotated = generate_validator_keys(&output_dir, true).unwrap();
+    assert_ne!(rotated.account_address, account_address);
+    let account_key = read_keypair_from_file(&account_key_path).unwrap();
+    assert_eq!(
+        MgoAddress::from(&account_key.public()),
+        rotated.account_address
+    );
+}
+
+#[tokio::test]
+async fn test_update_commission_rate_above_max_requires_force() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let keypair: &MgoKeyPair = test_cluster
+        .swarm
+        .config()
+        .validator_configs
+        .first()
+        .unwrap()
+        .account_key_pair
+        .keypair();
+    let validator_address: MgoAddress = MgoAddress::from(&keypair.public());
+    let keypair = MgoKeyPair::decode_base64(&keypair.encode_base64()).unwrap();
+    let mgo_client = test_cluster.wallet.get_client().await?;
+    let (_, before) = get_validator_summary(&mgo_client, validator_address)
+        .await?
+        .unwrap();
+
+    // Act as the validator from the wallet.
+    let context = &mut test_cluster.wallet;
+    context.config.keystore.add_key(None, keypair)?;
+    context.config.active_address = Some(validator_address);
+
+    // The violating rate is refused before any transaction is built.
+    let error = MgoValidatorCommand::UpdateCommissionRate {
+        commission_rate: MAX_VALIDATOR_COMMISSION_RATE + 1,
+        force: false,
+        gas_budget: None,
+    }
+    .execute(context)
+    .await
+    .unwrap_err();
+    assert!(error.to_string().contains("--force"), "{error}");
+    let (_, after) = get_validator_summary(&mgo_client, validator_address)
+        .await?
+        .unwrap();
+    assert_eq!(
+        after.next_epoch_commission_rate,
+        before.next_epoch_commission_rate
+    );
+
+    // With --force the transaction is submitted, and aborts on chain.
+    let response = MgoValidatorCommand::UpdateCommissionRate {
+        commission_rate: MAX_VALIDATOR_COMMISSION_RATE + 1,
+        force: true,
+        gas_budget: None,
+    }
+    .execute(context)
+    .await?;
+    let MgoValidatorCommandResponse::UpdateCommissionRate(response) = response else {
+        panic!("Expected UpdateCommissionRate");
+    };
+    assert!(!response.status_ok().unwrap());
+
+    // A rate within the limit goes through.
+    MgoValidatorCommand::UpdateCommissionRate {
+        commission_rate: MAX_VALIDATOR_COMMISSION_RATE,
+        force: false,
+        gas_budget: None,
+    }
+    .execute(context)
+    .await?;
+    let (_, after) = get_validator_summary(&mgo_client, validator_address)
+        .await?
+        .unwrap();
+    assert_eq!(
+        after.next_epoch_commission_rate,
+        MAX_VALIDATOR_COMMISSION_RATE
+    );
+    Ok(())
+}
+
+#[test]
+fn test_make_registration_bundle_checklist() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let keys = generate_validator_keys(temp_dir.path(), false).unwrap();
+    let bundle = ValidatorRegistrationBundle::from_key_dir(temp_dir.path()).unwrap();
+    assert_eq!(bundle.account_address, keys.account_address);
+    bundle.verify().unwrap();
+
+    // Swapping in a protocol key breaks the proof of possession.
+    let mut tampered = ValidatorRegistrationBundle::from_key_dir(temp_dir.path()).unwrap();
+    tampered.protocol_public_key = get_authority_key_pair().1.public().into();
+    assert!(tampered.verify().is_err());
+
+    // A missing key file and one of the wrong scheme are both reported, next to the valid ones.
+    let network_path = temp_dir.path().join(NETWORK_KEY_FILE_NAME);
+    let worker_path = temp_dir.path().join(WORKER_KEY_FILE_NAME);
+    write_authority_keypair_to_file(&get_authority_key_pair().1, &network_path).unwrap();
+    std::fs::remove_file(&worker_path).unwrap();
+    let err = ValidatorRegistrationBundle::from_key_dir(temp_dir.path())
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains(&format!("[x] {}", keys.account_key.path.display())),
+        "{err}"
+    );
+    assert!(
+        err.contains(&format!("[x] {}", keys.protocol_key.path.display())),
+        "{err}"
+    );
+    assert!(
+        err.contains(&format!("[ ] {}: ", network_path.display())),
+        "{err}"
+    );
+    assert!(
+        err.contains(&format!("[ ] {}: missing", worker_path.display())),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn test_join_committee_with_registration_bundle() -> Result<(), anyhow::Error> {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let keys = generate_validator_keys(temp_dir.path(), false)?;
+    let address = keys.account_address;
+    let mut test_cluster = TestClusterBuilder::new()
+        .with_validator_candidates([address])
+        .build()
+        .await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let context = &mut test_cluster.wallet;
+    context
+        .config
+        .keystore
+        .add_key(None, read_keypair_from_file(&keys.account_key.path)?)?;
+    context.config.active_address = Some(address);
+
+    let bundle_path = temp_dir.path().join("bundle.json");
+    let response = MgoValidatorCommand::MakeRegistrationBundle {
+        dir: temp_dir.path().to_path_buf(),
+        out: bundle_path.clone(),
+    }
+    .execute(context)
+    .await?;
+    let MgoValidatorCommandResponse::MakeRegistrationBundle { path, bundle } = response else {
+        panic!("Expected MakeRegistrationBundle");
+    };
+    assert_eq!(path, bundle_path);
+    assert_eq!(bundle.account_address, address);
+    let bundle = ValidatorRegistrationBundle::read_from_file(&bundle_path)?;
+    assert_eq!(
+        bundle.protocol_public_key.as_ref(),
+        read_authority_keypair_from_file(&keys.protocol_key.path)?
+            .public()
+            .as_bytes()
+    );
+
+    // Register the candidate with the keys of the bundle.
+    let validator_info = GenesisValidatorInfo {
+        info: ValidatorInfo {
+            name: "bundled".to_string(),
+            protocol_key: bundle.protocol_public_key,
+            worker_key: bundle.worker_public_key.clone(),
+            account_address: address,
+            network_key: bundle.network_public_key.clone(),
+            gas_price: rgp,
+            commission_rate: mgo_config::node::DEFAULT_COMMISSION_RATE,
+            network_address: Multiaddr::try_from("/dns/localhost/tcp/8080/http".to_string())?,
+            p2p_address: Multiaddr::try_from("/dns/localhost/udp/8084".to_string())?,
+            narwhal_primary_address: Multiaddr::try_from("/dns/localhost/udp/8081".to_string())?,
+            narwhal_worker_address: Multiaddr::try_from("/dns/localhost/udp/8082".to_string())?,
+            description: String::new(),
+            image_url: String::new(),
+            project_url: String::new(),
+        },
+        proof_of_possession: bundle.proof_of_possession.clone(),
+    };
+    let validator_info_path = temp_dir.path().join("validator.info");
+    std::fs::write(
+        &validator_info_path,
+        serde_yaml::to_string(&validator_info)?,
+    )?;
+    let response = MgoValidatorCommand::BecomeCandidate {
+        file: validator_info_path,
+        gas_budget: None,
+    }
+    .execute(context)
+    .await?;
+    let MgoValidatorCommandResponse::BecomeCandidate(response) = response else {
+        panic!("Expected BecomeCandidate");
+    };
+    assert!(response.status_ok().unwrap());
+
+    let stake_coin = context
+        .gas_for_owner_budget(address, MIN_VALIDATOR_JOINING_STAKE_MIST, BTreeSet::new())
+        .await?
+        .1
+        .object_ref();
+    let gas = context
+        .gas_for_owner_budget(address, 0, BTreeSet::from([stake_coin.0]))
+        .await?
+        .1
+        .object_ref();
+    let stake_tx = TestTransactionBuilder::new(address, gas, rgp)
+        .call_staking(stake_coin, address)
+        .build();
+    context
+        .execute_transaction_must_succeed(context.sign_transaction(&stake_tx))
+        .await;
+
+    // A bundle that does not match the registered keys is refused.
+    let mismatched_path = temp_dir.path().join("mismatched.json");
+    let mismatched = ValidatorRegistrationBundle {
+        worker_public_key: get_key_pair::<NetworkKeyPair>().1.public().clone(),
+        ..ValidatorRegistrationBundle::read_from_file(&bundle_path)?
+    };
+    std::fs::write(&mismatched_path, serde_json::to_string(&mismatched)?)?;
+    let err = MgoValidatorCommand::JoinCommittee {
+        gas_budget: None,
+        bundle: Some(mismatched_path),
+    }
+    .execute(context)
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("worker keys"), "{err}");
+
+    let response = MgoValidatorCommand::JoinCommittee {
+        gas_budget: None,
+        bundle: Some(bundle_path),
+    }
+    .execute(context)
+    .await?;
+    let MgoValidatorCommandResponse::JoinCommittee(response) = response else {
+        panic!("Expected JoinCommittee");
+    };
+    assert!(response.status_ok().unwrap());
+    let mgo_client = context.get_client().await?;
+    let (status, summary) = get_validator_summary(&mgo_client, address)
+        .await?
+        .unwrap();
+    assert_eq!(status, ValidatorStatus::Pending);
+    assert_eq!(summary.network_pubkey_bytes, bundle.network_public_key.as_bytes());
+    Ok(())
+}
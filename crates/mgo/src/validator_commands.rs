@@ -7,20 +7,38 @@ use std::{
     collections::{BTreeMap, HashSet},
     fmt::{self, Debug, Display, Formatter, Write},
     fs,
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 use mgo_genesis_builder::validator_info::GenesisValidatorInfo;
 
 use mgo_types::{
     base_types::{ObjectID, ObjectRef, MgoAddress},
-    crypto::{AuthorityPublicKey, NetworkPublicKey, Signable, DEFAULT_EPOCH_ID},
+    coin::COIN_MODULE_NAME,
+    committee::EpochId,
+    dynamic_field::{DynamicFieldName, Field},
+    crypto::{
+        AuthorityPublicKey, AuthoritySignature, NetworkPublicKey, Signable, DEFAULT_EPOCH_ID,
+    },
+    gas_coin::GAS,
+    governance::{
+        ADD_STAKE_FUN_NAME, MAX_VALIDATOR_COMMISSION_RATE, MAX_VALIDATOR_GAS_PRICE,
+        MAX_VALIDATOR_METADATA_LENGTH, STAKING_POOL_MODULE_NAME, WITHDRAW_STAKE_FUN_NAME,
+    },
     multiaddr::Multiaddr,
     object::Owner,
     mgo_system_state::{
-        mgo_system_state_inner_v1::{UnverifiedValidatorOperationCapV1, ValidatorV1},
+        mgo_system_state_inner_v1::{
+            UnverifiedValidatorOperationCapV1, ValidatorMetadataV1, ValidatorV1,
+            E_METADATA_INVALID_NET_ADDR, E_METADATA_INVALID_NET_PUBKEY,
+            E_METADATA_INVALID_P2P_ADDR, E_METADATA_INVALID_POP, E_METADATA_INVALID_PRIMARY_ADDR,
+            E_METADATA_INVALID_PUBKEY, E_METADATA_INVALID_WORKER_ADDR,
+            E_METADATA_INVALID_WORKER_PUBKEY,
+        },
         mgo_system_state_summary::{MgoSystemStateSummary, MgoValidatorSummary},
+        PoolTokenExchangeRate, ValidatorWrapper, MGO_SYSTEM_MODULE_NAME,
     },
-    MGO_SYSTEM_PACKAGE_ID,
+    programmable_transaction_builder::ProgrammableTransactionBuilder,
+    TypeTag, MGO_FRAMEWORK_PACKAGE_ID, MGO_SYSTEM_PACKAGE_ID,
 };
 use tap::tap::TapOptional;
 
@@ -30,12 +48,13 @@ use colored::Colorize;
 use fastcrypto::traits::ToFromBytes;
 use fastcrypto::{
     encoding::{Base64, Encoding},
-    traits::KeyPair,
+    traits::{EncodeDecodeBase64, KeyPair},
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use shared_crypto::intent::{Intent, IntentMessage, IntentScope};
 use mgo_json_rpc_types::{
-    MgoObjectDataOptions, MgoTransactionBlockResponse, MgoTransactionBlockResponseOptions,
+    MgoObjectDataOptions, MgoTransactionBlockResponse, MgoTransactionBlockResponseOptions, Stake,
+    StakeStatus,
 };
 use mgo_keys::keystore::AccountKeystore;
 use mgo_keys::{
@@ -48,10 +67,11 @@ use mgo_keys::{
 use mgo_sdk::wallet_context::WalletContext;
 use mgo_sdk::MgoClient;
 use mgo_types::crypto::{
-    generate_proof_of_possession, get_authority_key_pair, AuthorityPublicKeyBytes,
+    generate_proof_of_possession, get_authority_key_pair, verify_proof_of_possession,
+    AuthorityPublicKeyBytes,
 };
 use mgo_types::crypto::{AuthorityKeyPair, NetworkKeyPair, SignatureScheme, MgoKeyPair};
-use mgo_types::transaction::{CallArg, ObjectArg, Transaction, TransactionData};
+use mgo_types::transaction::{Argument, CallArg, Command, ObjectArg, Transaction, TransactionData};
 
 #[path = "unit_tests/validator_tests.rs"]
 #[cfg(test)]
@@ -59,6 +79,11 @@ mod validator_tests;
 
 const DEFAULT_GAS_BUDGET: u64 = 200_000_000; // 0.2 MGO
 
+pub const ACCOUNT_KEY_FILE_NAME: &str = "account.key";
+pub const PROTOCOL_KEY_FILE_NAME: &str = "protocol.key";
+pub const NETWORK_KEY_FILE_NAME: &str = "network.key";
+pub const WORKER_KEY_FILE_NAME: &str = "worker.key";
+
 #[derive(Parser)]
 #[clap(rename_all = "kebab-case")]
 pub enum MgoValidatorCommand {
@@ -71,6 +96,27 @@ pub enum MgoValidatorCommand {
         host_name: String,
         gas_price: u64,
     },
+    /// Generate a new set of account, protocol, network and worker key files.
+    #[clap(name = "generate-keys")]
+    GenerateKeys {
+        /// Directory to write the key files to, created if missing.
+        #[clap(name = "output-dir", long, default_value = ".")]
+        output_dir: PathBuf,
+        /// Replace existing key files, rotating all keys.
+        #[clap(name = "overwrite", long)]
+        overwrite: bool,
+    },
+    /// Read the key files in a directory, as written by `generate-keys`, and write the public
+    /// keys and proof of possession a validator registers with to a JSON bundle.
+    #[clap(name = "make-registration-bundle")]
+    MakeRegistrationBundle {
+        /// Directory holding the account, protocol, network and worker key files.
+        #[clap(name = "dir", long, default_value = ".")]
+        dir: PathBuf,
+        /// File to write the bundle to.
+        #[clap(name = "out", long, default_value = "bundle.json")]
+        out: PathBuf,
+    },
     #[clap(name = "become-candidate")]
     BecomeCandidate {
         #[clap(name = "validator-info-path")]
@@ -83,6 +129,10 @@ pub enum MgoValidatorCommand {
         /// Gas budget for this transaction.
         #[clap(name = "gas-budget", long)]
         gas_budget: Option<u64>,
+        /// Registration bundle the validator candidate must have been registered with. Joining
+        /// is refused if any of its keys differ.
+        #[clap(name = "bundle", long)]
+        bundle: Option<PathBuf>,
     },
     #[clap(name = "leave-committee")]
     LeaveCommittee {
@@ -101,6 +151,9 @@ pub enum MgoValidatorCommand {
     UpdateMetadata {
         #[clap(subcommand)]
         metadata: MetadataUpdate,
+        /// Proceed even if the new metadata violates network constraints.
+        #[clap(name = "force", long)]
+        force: bool,
         /// Gas budget for this transaction.
         #[clap(name = "gas-budget", long)]
         gas_budget: Option<u64>,
@@ -115,6 +168,22 @@ pub enum MgoValidatorCommand {
         operation_cap_id: Option<ObjectID>,
         #[clap(name = "gas-price")]
         gas_price: u64,
+        /// Proceed even if the gas price violates network constraints.
+        #[clap(name = "force", long)]
+        force: bool,
+        /// Gas budget for this transaction.
+        #[clap(name = "gas-budget", long)]
+        gas_budget: Option<u64>,
+    },
+    /// Update the commission rate, in basis points, charged on stake rewards from the next epoch.
+    /// The sender must be the validator itself.
+    #[clap(name = "update-commission-rate")]
+    UpdateCommissionRate {
+        #[clap(name = "commission-rate")]
+        commission_rate: u64,
+        /// Proceed even if the commission rate violates network constraints.
+        #[clap(name = "force", long)]
+        force: bool,
         /// Gas budget for this transaction.
         #[clap(name = "gas-budget", long)]
         gas_budget: Option<u64>,
@@ -148,6 +217,69 @@ pub enum MgoValidatorCommand {
         #[clap(name = "protocol-public-key", long)]
         protocol_public_key: AuthorityPublicKeyBytes,
     },
+    /// Stake MGO from the validator's own account into its staking pool.
+    /// The sender must be the validator itself.
+    #[clap(name = "add-self-stake")]
+    AddSelfStake {
+        /// Amount of MIST to stake, split off the gas coin.
+        #[clap(name = "amount")]
+        amount: u64,
+        /// Proceed even if the validator's next epoch stake stays below the network minimum.
+        #[clap(name = "force", long)]
+        force: bool,
+        /// Gas budget for this transaction.
+        #[clap(name = "gas-budget", long)]
+        gas_budget: Option<u64>,
+        /// Instead of executing the transaction, print the serialized unsigned transaction data.
+        #[clap(name = "serialize-unsigned-transaction", long)]
+        serialize_unsigned_transaction: bool,
+    },
+    /// Withdraw stake the validator's own account holds in its staking pool.
+    /// The sender must be the validator itself.
+    #[clap(name = "withdraw-self-stake")]
+    WithdrawSelfStake {
+        /// ID of a StakedMgo object to withdraw in full.
+        #[clap(
+            name = "staked-mgo-id",
+            long,
+            conflicts_with = "amount",
+            required_unless_present = "amount"
+        )]
+        staked_mgo_id: Option<ObjectID>,
+        /// Amount of MIST to withdraw, split off one of the validator's StakedMgo objects.
+        #[clap(name = "amount", long)]
+        amount: Option<u64>,
+        /// Proceed even if the validator's next epoch stake drops below the network minimum.
+        #[clap(name = "force", long)]
+        force: bool,
+        /// Gas budget for this transaction.
+        #[clap(name = "gas-budget", long)]
+        gas_budget: Option<u64>,
+        /// Instead of executing the transaction, print the serialized unsigned transaction data.
+        #[clap(name = "serialize-unsigned-transaction", long)]
+        serialize_unsigned_transaction: bool,
+    },
+    /// Display the staking rewards the validator's own stakes accrued in each epoch, and the
+    /// rewards that can be claimed now.
+    #[clap(name = "display-rewards")]
+    DisplayRewards {
+        /// Validator address, the active address if not provided.
+        #[clap(name = "validator-address")]
+        validator_address: Option<MgoAddress>,
+    },
+    /// Print out the serialized data of a transaction that claims staking rewards of the
+    /// validator's own stakes. The stakes are withdrawn, the rewards sent to the validator and
+    /// the principal staked again, active from the next epoch.
+    /// The sender must be the validator itself.
+    #[clap(name = "withdraw-rewards-raw-txn")]
+    WithdrawRewardsRawTxn {
+        /// Amount of MIST to claim, all claimable rewards if not provided.
+        #[clap(name = "amount", long)]
+        amount: Option<u64>,
+        /// Gas budget for this transaction.
+        #[clap(name = "gas-budget", long)]
+        gas_budget: Option<u64>,
+    },
     /// Print out the serialized data of a transaction that sets the gas price quote for a validator.
     DisplayGasPriceUpdateRawTxn {
         /// Address of the transaction sender.
@@ -163,24 +295,318 @@ pub enum MgoValidatorCommand {
         #[clap(name = "gas-budget", long)]
         gas_budget: Option<u64>,
     },
+    /// Print out the serialized data of a transaction that sets the commission rate of a validator.
+    /// The sender must be the validator itself.
+    DisplayCommissionRateUpdateRawTxn {
+        /// Address of the transaction sender.
+        #[clap(name = "sender-address", long)]
+        sender_address: MgoAddress,
+        /// Commission rate to be set to, in basis points.
+        #[clap(name = "new-commission-rate", long)]
+        new_commission_rate: u64,
+        /// Gas budget for this transaction.
+        #[clap(name = "gas-budget", long)]
+        gas_budget: Option<u64>,
+    },
+    /// Print out the serialized data of a transaction that updates the metadata of a validator.
+    /// The sender must be the validator itself.
+    DisplayMetadataUpdateRawTxn {
+        /// Address of the transaction sender.
+        #[clap(name = "sender-address", long)]
+        sender_address: MgoAddress,
+        #[clap(subcommand)]
+        metadata: MetadataUpdate,
+        /// Gas budget for this transaction.
+        #[clap(name = "gas-budget", long)]
+        gas_budget: Option<u64>,
+    },
 }
 
 #[derive(Serialize)]
 #[serde(untagged)]
 pub enum MgoValidatorCommandResponse {
     MakeValidatorInfo,
+    GenerateKeys(GeneratedValidatorKeys),
+    MakeRegistrationBundle {
+        path: PathBuf,
+        bundle: ValidatorRegistrationBundle,
+    },
     DisplayMetadata,
     BecomeCandidate(MgoTransactionBlockResponse),
     JoinCommittee(MgoTransactionBlockResponse),
     LeaveCommittee(MgoTransactionBlockResponse),
     UpdateMetadata(MgoTransactionBlockResponse),
     UpdateGasPrice(MgoTransactionBlockResponse),
+    UpdateCommissionRate(MgoTransactionBlockResponse),
     ReportValidator(MgoTransactionBlockResponse),
     SerializedPayload(String),
     DisplayGasPriceUpdateRawTxn {
         data: TransactionData,
         serialized_data: String,
     },
+    DisplayCommissionRateUpdateRawTxn {
+        data: TransactionData,
+        serialized_data: String,
+    },
+    DisplayMetadataUpdateRawTxn {
+        data: TransactionData,
+        serialized_data: String,
+    },
+    AddSelfStake {
+        response: MgoTransactionBlockResponse,
+        stake: ValidatorStakeSummary,
+    },
+    WithdrawSelfStake {
+        response: MgoTransactionBlockResponse,
+        stake: ValidatorStakeSummary,
+    },
+    SerializedSelfStakeTxn {
+        data: TransactionData,
+        serialized_data: String,
+        projected_stake: ValidatorStakeSummary,
+    },
+    DisplayRewards(ValidatorRewardsSummary),
+    WithdrawRewardsRawTxn {
+        data: TransactionData,
+        serialized_data: String,
+        rewards: ValidatorRewardsSummary,
+        /// Rewards the transaction claims, in MIST.
+        amount: u64,
+    },
+}
+
+/// Stake figures of a validator's staking pool, in MIST.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorStakeSummary {
+    pub validator_address: MgoAddress,
+    pub staking_pool_id: ObjectID,
+    /// Stake active in the pool during the current epoch.
+    pub active_stake: u64,
+    /// Stake added during the current epoch, active from the next epoch.
+    pub pending_stake: u64,
+    /// Stake withdrawn during the current epoch, removed at the next epoch.
+    pub pending_withdraw: u64,
+    /// Stake the validator will have at the next epoch.
+    pub next_epoch_stake: u64,
+    /// Minimum stake the validator must keep to avoid being removed from the committee.
+    pub min_stake: u64,
+}
+
+/// Staking rewards of a validator's own active stakes, in MIST, as computed by the staking pool
+/// from its exchange rates.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorRewardsSummary {
+    pub validator_address: MgoAddress,
+    pub staking_pool_id: ObjectID,
+    pub epoch: EpochId,
+    /// Active self stakes, whose rewards are reported.
+    pub stakes: Vec<StakeRewards>,
+    /// Principal of the active self stakes.
+    pub principal: u64,
+    /// Rewards accrued in each epoch since the earliest active self stake became active.
+    pub epochs: Vec<EpochRewards>,
+    /// Rewards withdrawing all active self stakes would pay out in the current epoch.
+    pub claimable_reward: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StakeRewards {
+    pub staked_mgo_id: ObjectID,
+    pub stake_active_epoch: EpochId,
+    pub principal: u64,
+    /// Pool tokens the principal bought when it became active.
+    pub pool_token_amount: u64,
+    /// Rewards withdrawing this stake alone would pay out in the current epoch.
+    pub reward: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpochRewards {
+    pub epoch: EpochId,
+    /// Exchange rate of the pool in the epoch.
+    pub mgo_amount: u64,
+    pub pool_token_amount: u64,
+    /// Rewards of the self stakes accrued up to the start of the epoch.
+    pub accrued_reward: u64,
+    /// Rewards accrued since the previous epoch.
+    pub reward: u64,
+}
+
+/// Key files written by `generate-keys`, with the public keys validator info is built from.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedValidatorKeys {
+    pub account_address: MgoAddress,
+    pub account_key: GeneratedKeyFile,
+    pub protocol_key: GeneratedKeyFile,
+    pub network_key: GeneratedKeyFile,
+    pub worker_key: GeneratedKeyFile,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedKeyFile {
+    pub path: PathBuf,
+    /// Base64 encoded public key.
+    pub public_key: String,
+}
+
+/// Generates a full validator key set into `output_dir`. Existing key files are only replaced
+/// when `overwrite` is set, and the new files are only readable by their owner on unix.
+pub fn generate_validator_keys(
+    output_dir: &Path,
+    overwrite: bool,
+) -> anyhow::Result<GeneratedValidatorKeys> {
+    let paths = [
+        ACCOUNT_KEY_FILE_NAME,
+        PROTOCOL_KEY_FILE_NAME,
+        NETWORK_KEY_FILE_NAME,
+        WORKER_KEY_FILE_NAME,
+    ]
+    .map(|file_name| output_dir.join(file_name));
+    let existing = paths
+        .iter()
+        .filter(|path| path.exists())
+        .collect::<Vec<_>>();
+    if !overwrite && !existing.is_empty() {
+        bail!(
+            "Key files already exist: {:?}. Use --overwrite to replace them.",
+            existing
+        );
+    }
+    fs::create_dir_all(output_dir)?;
+    let [account_path, protocol_path, network_path, worker_path] = paths;
+
+    let (_, account_key, _, _) = generate_new_key(SignatureScheme::ED25519, None, None)?;
+    write_keypair_to_file(&account_key, &account_path)?;
+    let (_, protocol_key) = get_authority_key_pair();
+    write_authority_keypair_to_file(&protocol_key, &protocol_path)?;
+    let (_, network_key, _, _) = generate_new_key(SignatureScheme::ED25519, None, None)?;
+    write_keypair_to_file(&network_key, &network_path)?;
+    let (_, worker_key, _, _) = generate_new_key(SignatureScheme::ED25519, None, None)?;
+    write_keypair_to_file(&worker_key, &worker_path)?;
+
+    #[cfg(unix)]
+    for path in [&account_path, &protocol_path, &network_path, &worker_path] {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    }
+
+    Ok(GeneratedValidatorKeys {
+        account_address: MgoAddress::from(&account_key.public()),
+        account_key: GeneratedKeyFile {
+            path: account_path,
+            public_key: account_key.public().encode_base64(),
+        },
+        protocol_key: GeneratedKeyFile {
+            path: protocol_path,
+            public_key: protocol_key.public().encode_base64(),
+        },
+        network_key: GeneratedKeyFile {
+            path: network_path,
+            public_key: network_key.public().encode_base64(),
+        },
+        worker_key: GeneratedKeyFile {
+            path: worker_path,
+            public_key: worker_key.public().encode_base64(),
+        },
+    })
+}
+
+/// Public keys and proof of possession a validator registers with, as written by
+/// `make-registration-bundle`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorRegistrationBundle {
+    pub account_address: MgoAddress,
+    pub protocol_public_key: AuthorityPublicKeyBytes,
+    pub network_public_key: NetworkPublicKey,
+    pub worker_public_key: NetworkPublicKey,
+    /// Proof of possession of the protocol key over the account address.
+    pub proof_of_possession: AuthoritySignature,
+}
+
+impl ValidatorRegistrationBundle {
+    /// Reads the key files of `dir`, failing with a checklist of the missing or invalid ones.
+    pub fn from_key_dir(dir: &Path) -> anyhow::Result<Self> {
+        let account_path = dir.join(ACCOUNT_KEY_FILE_NAME);
+        let protocol_path = dir.join(PROTOCOL_KEY_FILE_NAME);
+        let network_path = dir.join(NETWORK_KEY_FILE_NAME);
+        let worker_path = dir.join(WORKER_KEY_FILE_NAME);
+        let account_key = read_keypair_from_file(&account_path);
+        let protocol_key = read_authority_keypair_from_file(&protocol_path);
+        let network_key = read_network_keypair_from_file(&network_path);
+        let worker_key = read_network_keypair_from_file(&worker_path);
+
+        let (Ok(account_key), Ok(protocol_key), Ok(network_key), Ok(worker_key)) =
+            (&account_key, &protocol_key, &network_key, &worker_key)
+        else {
+            let checklist = [
+                checklist_entry(&account_path, &account_key),
+                checklist_entry(&protocol_path, &protocol_key),
+                checklist_entry(&network_path, &network_key),
+                checklist_entry(&worker_path, &worker_key),
+            ];
+            bail!(
+                "Cannot make a registration bundle from {}:\n{}",
+                dir.display(),
+                checklist.join("\n")
+            );
+        };
+
+        let account_address = MgoAddress::from(&account_key.public());
+        let bundle = Self {
+            account_address,
+            protocol_public_key: protocol_key.public().into(),
+            network_public_key: network_key.public().clone(),
+            worker_public_key: worker_key.public().clone(),
+            proof_of_possession: generate_proof_of_possession(protocol_key, account_address),
+        };
+        bundle.verify()?;
+        Ok(bundle)
+    }
+
+    pub fn read_from_file(path: &Path) -> anyhow::Result<Self> {
+        let bundle = serde_json::from_slice(&fs::read(path)?)
+            .map_err(|e| anyhow!("Invalid registration bundle {}: {e}", path.display()))?;
+        Ok(bundle)
+    }
+
+    /// Checks that the proof of possession is the protocol key's over the account address, and
+    /// that the network and worker keys are distinct.
+    pub fn verify(&self) -> anyhow::Result<()> {
+        let protocol_key = AuthorityPublicKey::from_bytes(self.protocol_public_key.as_ref())?;
+        verify_proof_of_possession(
+            &self.proof_of_possession,
+            &protocol_key,
+            self.account_address,
+        )
+        .map_err(|e| {
+            anyhow!(
+                "Proof of possession does not match the protocol key and account {}: {e}",
+                self.account_address
+            )
+        })?;
+        if self.network_public_key == self.worker_public_key {
+            bail!(
+                "Network and worker keys must be distinct, both are {}",
+                self.network_public_key.encode_base64()
+            );
+        }
+        Ok(())
+    }
+}
+
+fn checklist_entry<T>(path: &Path, key: &anyhow::Result<T>) -> String {
+    match key {
+        Ok(_) => format!("  [x] {}", path.display()),
+        Err(_) if !path.exists() => format!("  [ ] {}: missing", path.display()),
+        Err(e) => format!("  [ ] {}: {e}", path.display()),
+    }
 }
 
 fn make_key_files(
@@ -294,6 +720,18 @@ impl MgoValidatorCommand {
                 );
                 MgoValidatorCommandResponse::MakeValidatorInfo
             }
+            MgoValidatorCommand::GenerateKeys {
+                output_dir,
+                overwrite,
+            } => MgoValidatorCommandResponse::GenerateKeys(generate_validator_keys(
+                &output_dir,
+                overwrite,
+            )?),
+            MgoValidatorCommand::MakeRegistrationBundle { dir, out } => {
+                let bundle = ValidatorRegistrationBundle::from_key_dir(&dir)?;
+                fs::write(&out, serde_json::to_string_pretty(&bundle)?)?;
+                MgoValidatorCommandResponse::MakeRegistrationBundle { path: out, bundle }
+            }
             MgoValidatorCommand::BecomeCandidate { file, gas_budget } => {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let validator_info_bytes = fs::read(file)?;
@@ -343,7 +781,11 @@ impl MgoValidatorCommand {
                 MgoValidatorCommandResponse::BecomeCandidate(response)
             }
 
-            MgoValidatorCommand::JoinCommittee { gas_budget } => {
+            MgoValidatorCommand::JoinCommittee { gas_budget, bundle } => {
+                if let Some(bundle) = bundle {
+                    let bundle = ValidatorRegistrationBundle::read_from_file(&bundle)?;
+                    check_candidate_matches_bundle(context, &bundle).await?;
+                }
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let response =
                     call_0x5(context, "request_add_validator", vec![], gas_budget).await?;
@@ -352,8 +794,7 @@ impl MgoValidatorCommand {
 
             MgoValidatorCommand::LeaveCommittee { gas_budget } => {
                 // Only an active validator can leave committee.
-                let _status =
-                    check_status(context, HashSet::from([ValidatorStatus::Active])).await?;
+                let _ = check_status(context, HashSet::from([ValidatorStatus::Active])).await?;
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let response =
                     call_0x5(context, "request_remove_validator", vec![], gas_budget).await?;
@@ -373,24 +814,38 @@ impl MgoValidatorCommand {
 
             MgoValidatorCommand::UpdateMetadata {
                 metadata,
+                force,
                 gas_budget,
             } => {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
-                let resp = update_metadata(context, metadata, gas_budget).await?;
+                let resp = update_metadata(context, metadata, force, gas_budget).await?;
                 MgoValidatorCommandResponse::UpdateMetadata(resp)
             }
 
             MgoValidatorCommand::UpdateGasPrice {
                 operation_cap_id,
                 gas_price,
+                force,
                 gas_budget,
             } => {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let resp =
-                    update_gas_price(context, operation_cap_id, gas_price, gas_budget).await?;
+                    update_gas_price(context, operation_cap_id, gas_price, force, gas_budget)
+                        .await?;
                 MgoValidatorCommandResponse::UpdateGasPrice(resp)
             }
 
+            MgoValidatorCommand::UpdateCommissionRate {
+                commission_rate,
+                force,
+                gas_budget,
+            } => {
+                let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
+                let resp =
+                    update_commission_rate(context, commission_rate, force, gas_budget).await?;
+                MgoValidatorCommandResponse::UpdateCommissionRate(resp)
+            }
+
             MgoValidatorCommand::ReportValidator {
                 operation_cap_id,
                 reportee_address,
@@ -454,6 +909,94 @@ impl MgoValidatorCommand {
                     serialized_data,
                 }
             }
+
+            MgoValidatorCommand::DisplayCommissionRateUpdateRawTxn {
+                sender_address,
+                new_commission_rate,
+                gas_budget,
+            } => {
+                let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
+                let args = vec![CallArg::Pure(bcs::to_bytes(&new_commission_rate).unwrap())];
+                let data = construct_unsigned_0x5_txn(
+                    context,
+                    sender_address,
+                    "request_set_commission_rate",
+                    args,
+                    gas_budget,
+                )
+                .await?;
+                let serialized_data = Base64::encode(bcs::to_bytes(&data)?);
+                MgoValidatorCommandResponse::DisplayCommissionRateUpdateRawTxn {
+                    data,
+                    serialized_data,
+                }
+            }
+
+            MgoValidatorCommand::DisplayMetadataUpdateRawTxn {
+                sender_address,
+                metadata,
+                gas_budget,
+            } => {
+                let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
+                let change = MetadataChange::from_metadata_update(metadata, sender_address)?;
+                let (function, args) = change.call()?;
+                let data =
+                    construct_unsigned_0x5_txn(context, sender_address, function, args, gas_budget)
+                        .await?;
+                let serialized_data = Base64::encode(bcs::to_bytes(&data)?);
+                MgoValidatorCommandResponse::DisplayMetadataUpdateRawTxn {
+                    data,
+                    serialized_data,
+                }
+            }
+
+            MgoValidatorCommand::AddSelfStake {
+                amount,
+                force,
+                gas_budget,
+                serialize_unsigned_transaction,
+            } => {
+                let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
+                add_self_stake(
+                    context,
+                    amount,
+                    force,
+                    gas_budget,
+                    serialize_unsigned_transaction,
+                )
+                .await?
+            }
+
+            MgoValidatorCommand::WithdrawSelfStake {
+                staked_mgo_id,
+                amount,
+                force,
+                gas_budget,
+                serialize_unsigned_transaction,
+            } => {
+                let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
+                withdraw_self_stake(
+                    context,
+                    staked_mgo_id,
+                    amount,
+                    force,
+                    gas_budget,
+                    serialize_unsigned_transaction,
+                )
+                .await?
+            }
+
+            MgoValidatorCommand::DisplayRewards { validator_address } => {
+                let validator_address = validator_address.unwrap_or(context.active_address()?);
+                let mgo_client = context.get_client().await?;
+                let rewards = get_rewards_summary(&mgo_client, validator_address).await?;
+                MgoValidatorCommandResponse::DisplayRewards(rewards)
+            }
+
+            MgoValidatorCommand::WithdrawRewardsRawTxn { amount, gas_budget } => {
+                let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
+                withdraw_rewards_txn(context, amount, gas_budget).await?
+            }
         });
         ret
     }
@@ -512,13 +1055,65 @@ async fn get_cap_object_ref(
     }
 }
 
+/// Checks that the sender registered as a validator candidate with the keys of `bundle`, so the
+/// committee is not joined with keys the validator does not run with.
+async fn check_candidate_matches_bundle(
+    context: &mut WalletContext,
+    bundle: &ValidatorRegistrationBundle,
+) -> anyhow::Result<()> {
+    bundle.verify()?;
+    let sender = context.active_address()?;
+    if sender != bundle.account_address {
+        bail!(
+            "Registration bundle is for account {}, but the active address is {}",
+            bundle.account_address,
+            sender
+        );
+    }
+    let mgo_client = context.get_client().await?;
+    let validator_candidates_id = mgo_client
+        .governance_api()
+        .get_latest_mgo_system_state()
+        .await?
+        .validator_candidates_id;
+    let candidate = get_validator_candidate(&mgo_client, sender, validator_candidates_id)
+        .await?
+        .ok_or_else(|| {
+            anyhow!(
+                "{sender} is not a validator candidate, register it with become-candidate first."
+            )
+        })?;
+
+    let metadata = candidate.verified_metadata();
+    let mut mismatched = vec![];
+    if metadata.protocol_pubkey.as_bytes() != bundle.protocol_public_key.as_ref() {
+        mismatched.push("protocol");
+    }
+    if metadata.network_pubkey != bundle.network_public_key {
+        mismatched.push("network");
+    }
+    if metadata.worker_pubkey != bundle.worker_public_key {
+        mismatched.push("worker");
+    }
+    if !mismatched.is_empty() {
+        bail!(
+            "Validator candidate {} was registered with {} keys different from the registration bundle.",
+            sender,
+            mismatched.join(", ")
+        );
+    }
+    Ok(())
+}
+
 async fn update_gas_price(
     context: &mut WalletContext,
     operation_cap_id: Option<ObjectID>,
     gas_price: u64,
+    force: bool,
     gas_budget: u64,
 ) -> Result<MgoTransactionBlockResponse> {
-    let (_status, _summary, cap_obj_ref) = get_cap_object_ref(context, operation_cap_id).await?;
+    let (_status, summary, cap_obj_ref) = get_cap_object_ref(context, operation_cap_id).await?;
+    check_next_epoch_update(&summary, &ProposedUpdate::GasPrice(gas_price), force)?;
 
     // TODO: Only active/pending validators can set gas price.
 
@@ -529,6 +1124,23 @@ async fn update_gas_price(
     call_0x5(context, "request_set_gas_price", args, gas_budget).await
 }
 
+async fn update_commission_rate(
+    context: &mut WalletContext,
+    commission_rate: u64,
+    force: bool,
+    gas_budget: u64,
+) -> Result<MgoTransactionBlockResponse> {
+    use ValidatorStatus::*;
+    let (_status, summary) = check_status(context, HashSet::from([Pending, Active])).await?;
+    check_next_epoch_update(
+        &summary,
+        &ProposedUpdate::CommissionRate(commission_rate),
+        force,
+    )?;
+    let args = vec![CallArg::Pure(bcs::to_bytes(&commission_rate).unwrap())];
+    call_0x5(context, "request_set_commission_rate", args, gas_budget).await
+}
+
 async fn report_validator(
     context: &mut WalletContext,
     reportee_address: MgoAddress,
@@ -559,84 +1171,627 @@ async fn report_validator(
     call_0x5(context, function_name, args, gas_budget).await
 }
 
-async fn get_validator_summary_from_cap_id(
+/// Minimum principal of a StakedMgo object, enforced by `staking_pool::split`.
+const MIN_STAKED_MGO_PRINCIPAL: u64 = 1_000_000_000; // 1 MGO
+
+impl ValidatorStakeSummary {
+    fn new(summary: MgoValidatorSummary, min_stake: u64) -> Self {
+        Self {
+            validator_address: summary.mgo_address,
+            staking_pool_id: summary.staking_pool_id,
+            active_stake: summary.staking_pool_mgo_balance,
+            pending_stake: summary.pending_stake,
+            pending_withdraw: summary.pending_total_mgo_withdraw,
+            next_epoch_stake: summary.next_epoch_stake,
+            min_stake,
+        }
+    }
+}
+
+/// Fetches the stake figures of `validator_address`'s staking pool, along with the minimum
+/// stake it must keep: the low stake threshold for active validators, and the joining stake
+/// for pending ones.
+async fn get_stake_summary(
     client: &MgoClient,
-    operation_cap_id: ObjectID,
-) -> anyhow::Result<(ValidatorStatus, MgoValidatorSummary)> {
-    let resp = client
-        .read_api()
-        .get_object_with_options(operation_cap_id, MgoObjectDataOptions::default().with_bcs())
-        .await?;
-    let bcs = resp.move_object_bcs().ok_or_else(|| {
-        anyhow::anyhow!(
-            "Object {} does not exist or does not return bcs bytes",
-            operation_cap_id
-        )
-    })?;
-    let cap = bcs::from_bytes::<UnverifiedValidatorOperationCapV1>(bcs).map_err(|e| {
-        anyhow::anyhow!(
-            "Can't convert bcs bytes of object {} to UnverifiedValidatorOperationCapV1: {}",
-            operation_cap_id,
-            e,
-        )
-    })?;
-    let validator_address = cap.authorizer_validator_address;
+    validator_address: MgoAddress,
+) -> anyhow::Result<ValidatorStakeSummary> {
     let (status, summary) = get_validator_summary(client, validator_address)
         .await?
-        .ok_or_else(|| anyhow::anyhow!("{} is not a validator", validator_address))?;
-    if summary.operation_cap_id != operation_cap_id {
-        anyhow::bail!(
-            "Validator {}'s current operation cap id is {}",
-            validator_address,
-            summary.operation_cap_id
+        .ok_or_else(|| anyhow!("{} is not a Validator.", validator_address))?;
+    let system_state = client
+        .governance_api()
+        .get_latest_mgo_system_state()
+        .await?;
+    let min_stake = match status {
+        ValidatorStatus::Active => system_state.validator_low_stake_threshold,
+        ValidatorStatus::Pending => system_state.min_validator_joining_stake,
+    };
+    Ok(ValidatorStakeSummary::new(summary, min_stake))
+}
+
+fn check_min_stake(projected_stake: &ValidatorStakeSummary, force: bool) -> anyhow::Result<()> {
+    if projected_stake.next_epoch_stake < projected_stake.min_stake {
+        if !force {
+            bail!(
+                "Validator {}'s next epoch stake would be {} MIST, below the minimum of {} MIST. Use --force to proceed anyway.",
+                projected_stake.validator_address,
+                projected_stake.next_epoch_stake,
+                projected_stake.min_stake,
+            );
+        }
+        println!(
+            "{}",
+            format!(
+                "Warning: validator {}'s next epoch stake will be {} MIST, below the minimum of {} MIST.",
+                projected_stake.validator_address,
+                projected_stake.next_epoch_stake,
+                projected_stake.min_stake,
+            )
+            .yellow()
         );
     }
-    Ok((status, summary))
+    Ok(())
 }
 
-async fn construct_unsigned_0x5_txn(
+async fn add_self_stake(
     context: &mut WalletContext,
-    sender: MgoAddress,
-    function: &'static str,
-    call_args: Vec<CallArg>,
+    amount: u64,
+    force: bool,
     gas_budget: u64,
-) -> anyhow::Result<TransactionData> {
+    serialize_unsigned_transaction: bool,
+) -> anyhow::Result<MgoValidatorCommandResponse> {
+    let validator_address = context.active_address()?;
     let mgo_client = context.get_client().await?;
-    let mut args = vec![CallArg::MGO_SYSTEM_MUT];
-    args.extend(call_args);
+    let stake = get_stake_summary(&mgo_client, validator_address).await?;
+    let projected_stake = ValidatorStakeSummary {
+        pending_stake: stake.pending_stake.saturating_add(amount),
+        next_epoch_stake: stake.next_epoch_stake.saturating_add(amount),
+        ..stake
+    };
+    check_min_stake(&projected_stake, force)?;
+
+    // The stake is split off the gas coin, so it must cover both the stake and the budget.
+    let minimal_balance = amount
+        .checked_add(gas_budget)
+        .ok_or_else(|| anyhow!("Stake amount {} is too large", amount))?;
+    let gas_obj_ref = get_gas_obj_ref(validator_address, &mgo_client, minimal_balance).await?;
     let rgp = mgo_client
         .governance_api()
         .get_reference_gas_price()
         .await?;
 
-    let gas_obj_ref = get_gas_obj_ref(sender, &mgo_client, gas_budget).await?;
-    TransactionData::new_move_call(
-        sender,
+    let mut builder = ProgrammableTransactionBuilder::new();
+    let amount_arg = builder.pure(amount)?;
+    let coin = builder.command(Command::SplitCoins(Argument::GasCoin, vec![amount_arg]));
+    let system = builder.input(CallArg::MGO_SYSTEM_MUT)?;
+    let validator = builder.pure(validator_address)?;
+    builder.programmable_move_call(
         MGO_SYSTEM_PACKAGE_ID,
-        ident_str!("mgo_system").to_owned(),
-        ident_str!(function).to_owned(),
+        MGO_SYSTEM_MODULE_NAME.to_owned(),
+        ADD_STAKE_FUN_NAME.to_owned(),
         vec![],
-        gas_obj_ref,
-        args,
+        vec![system, coin, validator],
+    );
+    let data = TransactionData::new_programmable(
+        validator_address,
+        vec![gas_obj_ref],
+        builder.finish(),
         gas_budget,
         rgp,
-    )
+    );
+
+    if serialize_unsigned_transaction {
+        let serialized_data = Base64::encode(bcs::to_bytes(&data)?);
+        return Ok(MgoValidatorCommandResponse::SerializedSelfStakeTxn {
+            data,
+            serialized_data,
+            projected_stake,
+        });
+    }
+    let response = sign_and_execute(context, data).await?;
+    let stake = get_stake_summary(&mgo_client, validator_address).await?;
+    Ok(MgoValidatorCommandResponse::AddSelfStake { response, stake })
 }
 
-async fn call_0x5(
+async fn withdraw_self_stake(
     context: &mut WalletContext,
-    function: &'static str,
-    call_args: Vec<CallArg>,
+    staked_mgo_id: Option<ObjectID>,
+    amount: Option<u64>,
+    force: bool,
     gas_budget: u64,
-) -> anyhow::Result<MgoTransactionBlockResponse> {
-    let sender = context.active_address()?;
-    let tx_data =
+    serialize_unsigned_transaction: bool,
+) -> anyhow::Result<MgoValidatorCommandResponse> {
+    let validator_address = context.active_address()?;
+    let mgo_client = context.get_client().await?;
+    let stake = get_stake_summary(&mgo_client, validator_address).await?;
+
+    // Only stakes the validator's own account holds in its own pool are self stakes.
+    let self_stakes = mgo_client
+        .governance_api()
+        .get_stakes(validator_address)
+        .await?
+        .into_iter()
+        .filter(|delegated| delegated.validator_address == validator_address)
+        .flat_map(|delegated| delegated.stakes)
+        .filter(|s| !matches!(s.status, StakeStatus::Unstaked))
+        .collect::<Vec<_>>();
+    let (staked_mgo, split_amount) = match (staked_mgo_id, amount) {
+        (Some(staked_mgo_id), _) => {
+            let staked_mgo = self_stakes
+                .into_iter()
+                .find(|s| s.staked_mgo_id == staked_mgo_id)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "{} is not a StakedMgo object of validator {}",
+                        staked_mgo_id,
+                        validator_address
+                    )
+                })?;
+            (staked_mgo, None)
+        }
+        (None, Some(amount)) => select_self_stake(self_stakes, amount)?,
+        (None, None) => bail!("Either --staked-mgo-id or --amount must be provided"),
+    };
+
+    // Withdrawing also removes the rewards the withdrawn principal earned.
+    let reward = match staked_mgo.status {
+        StakeStatus::Active { estimated_reward } => estimated_reward,
+        _ => 0,
+    };
+    let (principal, reward) = match split_amount {
+        Some(amount) => (
+            amount,
+            (reward as u128 * amount as u128 / staked_mgo.principal as u128) as u64,
+        ),
+        None => (staked_mgo.principal, reward),
+    };
+    let withdrawn = principal.saturating_add(reward);
+    let projected_stake = ValidatorStakeSummary {
+        pending_withdraw: stake.pending_withdraw.saturating_add(withdrawn),
+        next_epoch_stake: stake.next_epoch_stake.saturating_sub(withdrawn),
+        ..stake
+    };
+    check_min_stake(&projected_stake, force)?;
+
+    let staked_mgo_ref = mgo_client
+        .read_api()
+        .get_object_with_options(staked_mgo.staked_mgo_id, MgoObjectDataOptions::default())
+        .await?
+        .object_ref_if_exists()
+        .ok_or_else(|| anyhow!("StakedMgo object {} not found", staked_mgo.staked_mgo_id))?;
+    let gas_obj_ref = get_gas_obj_ref(validator_address, &mgo_client, gas_budget).await?;
+    let rgp = mgo_client
+        .governance_api()
+        .get_reference_gas_price()
+        .await?;
+
+    let mut builder = ProgrammableTransactionBuilder::new();
+    let system = builder.input(CallArg::MGO_SYSTEM_MUT)?;
+    let staked = builder.obj(ObjectArg::ImmOrOwnedObject(staked_mgo_ref))?;
+    match split_amount {
+        None => {
+            builder.programmable_move_call(
+                MGO_SYSTEM_PACKAGE_ID,
+                MGO_SYSTEM_MODULE_NAME.to_owned(),
+                WITHDRAW_STAKE_FUN_NAME.to_owned(),
+                vec![],
+                vec![system, staked],
+            );
+        }
+        Some(amount) => {
+            let amount = builder.pure(amount)?;
+            let split = builder.programmable_move_call(
+                MGO_SYSTEM_PACKAGE_ID,
+                STAKING_POOL_MODULE_NAME.to_owned(),
+                ident_str!("split").to_owned(),
+                vec![],
+                vec![staked, amount],
+            );
+            let balance = builder.programmable_move_call(
+                MGO_SYSTEM_PACKAGE_ID,
+                MGO_SYSTEM_MODULE_NAME.to_owned(),
+                ident_str!("request_withdraw_stake_non_entry").to_owned(),
+                vec![],
+                vec![system, split],
+            );
+            let coin = builder.programmable_move_call(
+                MGO_FRAMEWORK_PACKAGE_ID,
+                COIN_MODULE_NAME.to_owned(),
+                ident_str!("from_balance").to_owned(),
+                vec![GAS::type_tag()],
+                vec![balance],
+            );
+            builder.transfer_arg(validator_address, coin);
+        }
+    }
+    let data = TransactionData::new_programmable(
+        validator_address,
+        vec![gas_obj_ref],
+        builder.finish(),
+        gas_budget,
+        rgp,
+    );
+
+    if serialize_unsigned_transaction {
+        let serialized_data = Base64::encode(bcs::to_bytes(&data)?);
+        return Ok(MgoValidatorCommandResponse::SerializedSelfStakeTxn {
+            data,
+            serialized_data,
+            projected_stake,
+        });
+    }
+    let response = sign_and_execute(context, data).await?;
+    let stake = get_stake_summary(&mgo_client, validator_address).await?;
+    Ok(MgoValidatorCommandResponse::WithdrawSelfStake { response, stake })
+}
+
+/// Picks the StakedMgo to withdraw `amount` from: one with exactly that principal, or else the
+/// smallest one that can be split so that both parts keep the minimum principal.
+fn select_self_stake(stakes: Vec<Stake>, amount: u64) -> anyhow::Result<(Stake, Option<u64>)> {
+    if let Some(exact) = stakes.iter().find(|s| s.principal == amount) {
+        return Ok((exact.clone(), None));
+    }
+    if amount < MIN_STAKED_MGO_PRINCIPAL {
+        bail!(
+            "Cannot withdraw {} MIST, the minimum withdrawal is {} MIST",
+            amount,
+            MIN_STAKED_MGO_PRINCIPAL
+        );
+    }
+    let stake = stakes
+        .into_iter()
+        .filter(|s| s.principal >= amount.saturating_add(MIN_STAKED_MGO_PRINCIPAL))
+        .min_by_key(|s| s.principal)
+        .ok_or_else(|| {
+            anyhow!(
+                "No self stake can be split to withdraw {} MIST while leaving at least {} MIST staked",
+                amount,
+                MIN_STAKED_MGO_PRINCIPAL
+            )
+        })?;
+    Ok((stake, Some(amount)))
+}
+
+/// Fetches the exchange rates of `summary`'s staking pool, by the epoch they took effect in.
+async fn get_exchange_rates(
+    client: &MgoClient,
+    summary: &MgoValidatorSummary,
+) -> anyhow::Result<BTreeMap<EpochId, PoolTokenExchangeRate>> {
+    let mut rates = BTreeMap::new();
+    let mut cursor = None;
+    loop {
+        let page = client
+            .read_api()
+            .get_dynamic_fields(summary.exchange_rates_id, cursor, None)
+            .await?;
+        let resps = client
+            .read_api()
+            .multi_get_object_with_options(
+                page.data.into_iter().map(|df| df.object_id).collect(),
+                MgoObjectDataOptions::default().with_bcs(),
+            )
+            .await?;
+        for resp in resps {
+            let object_id = resp.object_id()?;
+            let bcs = resp.move_object_bcs().ok_or_else(|| {
+                anyhow!(
+                    "Exchange rate {} does not exist or does not return bcs bytes",
+                    object_id
+                )
+            })?;
+            let rate = bcs::from_bytes::<Field<EpochId, PoolTokenExchangeRate>>(bcs)?;
+            rates.insert(rate.name, rate.value);
+        }
+        if !page.has_next_page {
+            break;
+        }
+        cursor = page.next_cursor;
+    }
+    Ok(rates)
+}
+
+/// Exchange rate of a staking pool in `epoch`: the latest one that took effect by then, or the
+/// initial rate if none did, as the staking pool looks it up.
+fn exchange_rate_at_epoch(
+    rates: &BTreeMap<EpochId, PoolTokenExchangeRate>,
+    epoch: EpochId,
+) -> PoolTokenExchangeRate {
+    rates
+        .range(..=epoch)
+        .next_back()
+        .map(|(_, rate)| rate.clone())
+        .unwrap_or_default()
+}
+
+/// Computes the rewards of `stakes` in `epoch` from the exchange rates of their pool, with the
+/// integer math of the staking pool. Withdrawals pay rewards out of the pool's rewards balance,
+/// so the claimable rewards are capped by `rewards_pool`.
+fn compute_rewards(
+    stakes: &[Stake],
+    rates: &BTreeMap<EpochId, PoolTokenExchangeRate>,
+    epoch: EpochId,
+    rewards_pool: u64,
+) -> (Vec<StakeRewards>, Vec<EpochRewards>, u64) {
+    let stakes = stakes
+        .iter()
+        .filter(|s| s.stake_active_epoch <= epoch)
+        .map(|s| {
+            let pool_token_amount =
+                exchange_rate_at_epoch(rates, s.stake_active_epoch).get_token_amount(s.principal);
+            StakeRewards {
+                staked_mgo_id: s.staked_mgo_id,
+                stake_active_epoch: s.stake_active_epoch,
+                principal: s.principal,
+                pool_token_amount,
+                reward: 0,
+            }
+        })
+        .collect::<Vec<_>>();
+    let accrued_reward = |stake: &StakeRewards, rate: &PoolTokenExchangeRate| {
+        rate.get_mgo_amount(stake.pool_token_amount)
+            .saturating_sub(stake.principal)
+    };
+
+    let mut epochs = vec![];
+    let first_epoch = stakes.iter().map(|s| s.stake_active_epoch).min();
+    if let Some(first_epoch) = first_epoch {
+        let mut previous = 0;
+        for e in first_epoch..=epoch {
+            let rate = exchange_rate_at_epoch(rates, e);
+            let accrued = stakes
+                .iter()
+                .filter(|s| s.stake_active_epoch <= e)
+                .map(|s| accrued_reward(s, &rate))
+                .sum::<u64>();
+            epochs.push(EpochRewards {
+                epoch: e,
+                mgo_amount: rate.mgo_amount(),
+                pool_token_amount: rate.pool_token_amount(),
+                accrued_reward: accrued,
+                reward: accrued.saturating_sub(previous),
+            });
+            previous = accrued;
+        }
+    }
+
+    let rate = exchange_rate_at_epoch(rates, epoch);
+    let stakes = stakes
+        .into_iter()
+        .map(|s| StakeRewards {
+            reward: accrued_reward(&s, &rate),
+            ..s
+        })
+        .collect::<Vec<_>>();
+    let claimable_reward = stakes
+        .iter()
+        .map(|s| s.reward)
+        .sum::<u64>()
+        .min(rewards_pool);
+    (stakes, epochs, claimable_reward)
+}
+
+async fn get_rewards_summary(
+    client: &MgoClient,
+    validator_address: MgoAddress,
+) -> anyhow::Result<ValidatorRewardsSummary> {
+    let (_status, summary) = get_validator_summary(client, validator_address)
+        .await?
+        .ok_or_else(|| anyhow!("{} is not a Validator.", validator_address))?;
+    let epoch = client
+        .governance_api()
+        .get_latest_mgo_system_state()
+        .await?
+        .epoch;
+    let self_stakes = client
+        .governance_api()
+        .get_stakes(validator_address)
+        .await?
+        .into_iter()
+        .filter(|delegated| delegated.validator_address == validator_address)
+        .flat_map(|delegated| delegated.stakes)
+        .filter(|s| matches!(s.status, StakeStatus::Active { .. }))
+        .collect::<Vec<_>>();
+    let rates = get_exchange_rates(client, &summary).await?;
+    let (stakes, epochs, claimable_reward) =
+        compute_rewards(&self_stakes, &rates, epoch, summary.rewards_pool);
+    Ok(ValidatorRewardsSummary {
+        validator_address,
+        staking_pool_id: summary.staking_pool_id,
+        epoch,
+        principal: stakes.iter().map(|s| s.principal).sum(),
+        stakes,
+        epochs,
+        claimable_reward,
+    })
+}
+
+/// Builds the transaction claiming `amount` of the validator's rewards, or all of them. The
+/// self stakes with the most rewards are withdrawn until they cover `amount`, which is sent to
+/// the validator, and the rest of the withdrawn Mgo is staked again.
+async fn withdraw_rewards_txn(
+    context: &mut WalletContext,
+    amount: Option<u64>,
+    gas_budget: u64,
+) -> anyhow::Result<MgoValidatorCommandResponse> {
+    let validator_address = context.active_address()?;
+    let mgo_client = context.get_client().await?;
+    let rewards = get_rewards_summary(&mgo_client, validator_address).await?;
+    let amount = amount.unwrap_or(rewards.claimable_reward);
+    if amount == 0 {
+        bail!("Validator {} has no rewards to claim", validator_address);
+    }
+    if amount > rewards.claimable_reward {
+        bail!(
+            "Cannot claim {} MIST, validator {} can claim at most {} MIST",
+            amount,
+            validator_address,
+            rewards.claimable_reward
+        );
+    }
+
+    let mut stakes = rewards.stakes.clone();
+    stakes.sort_by_key(|s| std::cmp::Reverse(s.reward));
+    let mut selected = vec![];
+    let mut selected_reward = 0u64;
+    for stake in stakes {
+        if selected_reward >= amount {
+            break;
+        }
+        selected_reward = selected_reward.saturating_add(stake.reward);
+        selected.push(stake.staked_mgo_id);
+    }
+
+    let staked_mgo_refs = mgo_client
+        .read_api()
+        .multi_get_object_with_options(selected, MgoObjectDataOptions::default())
+        .await?
+        .into_iter()
+        .map(|resp| {
+            let object_id = resp.object_id()?;
+            resp.object_ref_if_exists()
+                .ok_or_else(|| anyhow!("StakedMgo object {} not found", object_id))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let gas_obj_ref = get_gas_obj_ref(validator_address, &mgo_client, gas_budget).await?;
+    let rgp = mgo_client
+        .governance_api()
+        .get_reference_gas_price()
+        .await?;
+
+    let mut builder = ProgrammableTransactionBuilder::new();
+    let system = builder.input(CallArg::MGO_SYSTEM_MUT)?;
+    let mut coins = vec![];
+    for staked_mgo_ref in staked_mgo_refs {
+        let staked = builder.obj(ObjectArg::ImmOrOwnedObject(staked_mgo_ref))?;
+        let balance = builder.programmable_move_call(
+            MGO_SYSTEM_PACKAGE_ID,
+            MGO_SYSTEM_MODULE_NAME.to_owned(),
+            ident_str!("request_withdraw_stake_non_entry").to_owned(),
+            vec![],
+            vec![system, staked],
+        );
+        coins.push(builder.programmable_move_call(
+            MGO_FRAMEWORK_PACKAGE_ID,
+            COIN_MODULE_NAME.to_owned(),
+            ident_str!("from_balance").to_owned(),
+            vec![GAS::type_tag()],
+            vec![balance],
+        ));
+    }
+    let coin = coins.remove(0);
+    if !coins.is_empty() {
+        builder.command(Command::MergeCoins(coin, coins));
+    }
+    let amount_arg = builder.pure(amount)?;
+    let reward = builder.command(Command::SplitCoins(coin, vec![amount_arg]));
+    builder.transfer_arg(validator_address, reward);
+    let validator = builder.pure(validator_address)?;
+    let staked = builder.programmable_move_call(
+        MGO_SYSTEM_PACKAGE_ID,
+        MGO_SYSTEM_MODULE_NAME.to_owned(),
+        ident_str!("request_add_stake_non_entry").to_owned(),
+        vec![],
+        vec![system, coin, validator],
+    );
+    builder.transfer_arg(validator_address, staked);
+    let data = TransactionData::new_programmable(
+        validator_address,
+        vec![gas_obj_ref],
+        builder.finish(),
+        gas_budget,
+        rgp,
+    );
+    let serialized_data = Base64::encode(bcs::to_bytes(&data)?);
+    Ok(MgoValidatorCommandResponse::WithdrawRewardsRawTxn {
+        data,
+        serialized_data,
+        rewards,
+        amount,
+    })
+}
+
+async fn get_validator_summary_from_cap_id(
+    client: &MgoClient,
+    operation_cap_id: ObjectID,
+) -> anyhow::Result<(ValidatorStatus, MgoValidatorSummary)> {
+    let resp = client
+        .read_api()
+        .get_object_with_options(operation_cap_id, MgoObjectDataOptions::default().with_bcs())
+        .await?;
+    let bcs = resp.move_object_bcs().ok_or_else(|| {
+        anyhow::anyhow!(
+            "Object {} does not exist or does not return bcs bytes",
+            operation_cap_id
+        )
+    })?;
+    let cap = bcs::from_bytes::<UnverifiedValidatorOperationCapV1>(bcs).map_err(|e| {
+        anyhow::anyhow!(
+            "Can't convert bcs bytes of object {} to UnverifiedValidatorOperationCapV1: {}",
+            operation_cap_id,
+            e,
+        )
+    })?;
+    let validator_address = cap.authorizer_validator_address;
+    let (status, summary) = get_validator_summary(client, validator_address)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("{} is not a validator", validator_address))?;
+    if summary.operation_cap_id != operation_cap_id {
+        anyhow::bail!(
+            "Validator {}'s current operation cap id is {}",
+            validator_address,
+            summary.operation_cap_id
+        );
+    }
+    Ok((status, summary))
+}
+
+async fn construct_unsigned_0x5_txn(
+    context: &mut WalletContext,
+    sender: MgoAddress,
+    function: &'static str,
+    call_args: Vec<CallArg>,
+    gas_budget: u64,
+) -> anyhow::Result<TransactionData> {
+    let mgo_client = context.get_client().await?;
+    let mut args = vec![CallArg::MGO_SYSTEM_MUT];
+    args.extend(call_args);
+    let rgp = mgo_client
+        .governance_api()
+        .get_reference_gas_price()
+        .await?;
+
+    let gas_obj_ref = get_gas_obj_ref(sender, &mgo_client, gas_budget).await?;
+    TransactionData::new_move_call(
+        sender,
+        MGO_SYSTEM_PACKAGE_ID,
+        ident_str!("mgo_system").to_owned(),
+        ident_str!(function).to_owned(),
+        vec![],
+        gas_obj_ref,
+        args,
+        gas_budget,
+        rgp,
+    )
+}
+
+async fn call_0x5(
+    context: &mut WalletContext,
+    function: &'static str,
+    call_args: Vec<CallArg>,
+    gas_budget: u64,
+) -> anyhow::Result<MgoTransactionBlockResponse> {
+    let sender = context.active_address()?;
+    let tx_data =
         construct_unsigned_0x5_txn(context, sender, function, call_args, gas_budget).await?;
-    let signature =
-        context
-            .config
-            .keystore
-            .sign_secure(&sender, &tx_data, Intent::mgo_transaction())?;
+    sign_and_execute(context, tx_data).await
+}
+
+async fn sign_and_execute(
+    context: &mut WalletContext,
+    tx_data: TransactionData,
+) -> anyhow::Result<MgoTransactionBlockResponse> {
+    let sender = tx_data.sender();
+    let signature = context.sign_secure(&sender, &tx_data, Intent::mgo_transaction())?;
     let transaction = Transaction::from_data(tx_data, vec![signature]);
     let mgo_client = context.get_client().await?;
     mgo_client
@@ -657,6 +1812,12 @@ impl Display for MgoValidatorCommandResponse {
         let mut writer = String::new();
         match self {
             MgoValidatorCommandResponse::MakeValidatorInfo => {}
+            MgoValidatorCommandResponse::GenerateKeys(keys) => {
+                write!(writer, "{}", write_generated_keys(keys)?)?;
+            }
+            MgoValidatorCommandResponse::MakeRegistrationBundle { path, bundle } => {
+                write!(writer, "{}", write_registration_bundle(path, bundle)?)?;
+            }
             MgoValidatorCommandResponse::DisplayMetadata => {}
             MgoValidatorCommandResponse::BecomeCandidate(response) => {
                 write!(writer, "{}", write_transaction_response(response)?)?;
@@ -673,6 +1834,9 @@ impl Display for MgoValidatorCommandResponse {
             MgoValidatorCommandResponse::UpdateGasPrice(response) => {
                 write!(writer, "{}", write_transaction_response(response)?)?;
             }
+            MgoValidatorCommandResponse::UpdateCommissionRate(response) => {
+                write!(writer, "{}", write_transaction_response(response)?)?;
+            }
             MgoValidatorCommandResponse::ReportValidator(response) => {
                 write!(writer, "{}", write_transaction_response(response)?)?;
             }
@@ -682,6 +1846,14 @@ impl Display for MgoValidatorCommandResponse {
             MgoValidatorCommandResponse::DisplayGasPriceUpdateRawTxn {
                 data,
                 serialized_data,
+            }
+            | MgoValidatorCommandResponse::DisplayCommissionRateUpdateRawTxn {
+                data,
+                serialized_data,
+            }
+            | MgoValidatorCommandResponse::DisplayMetadataUpdateRawTxn {
+                data,
+                serialized_data,
             } => {
                 write!(
                     writer,
@@ -689,6 +1861,40 @@ impl Display for MgoValidatorCommandResponse {
                     data, serialized_data
                 )?;
             }
+            MgoValidatorCommandResponse::AddSelfStake { response, stake }
+            | MgoValidatorCommandResponse::WithdrawSelfStake { response, stake } => {
+                write!(writer, "{}", write_transaction_response(response)?)?;
+                write!(writer, "{}", write_stake_summary(stake)?)?;
+            }
+            MgoValidatorCommandResponse::SerializedSelfStakeTxn {
+                data,
+                serialized_data,
+                projected_stake,
+            } => {
+                write!(
+                    writer,
+                    "Transaction: {:?}, \nSerialized transaction: {:?}\n",
+                    data, serialized_data
+                )?;
+                write!(writer, "{}", write_stake_summary(projected_stake)?)?;
+            }
+            MgoValidatorCommandResponse::DisplayRewards(rewards) => {
+                write!(writer, "{}", write_rewards_summary(rewards)?)?;
+            }
+            MgoValidatorCommandResponse::WithdrawRewardsRawTxn {
+                data,
+                serialized_data,
+                rewards,
+                amount,
+            } => {
+                write!(
+                    writer,
+                    "Transaction: {:?}, \nSerialized transaction: {:?}\n",
+                    data, serialized_data
+                )?;
+                write!(writer, "{}", write_rewards_summary(rewards)?)?;
+                write!(writer, "Claimed reward: {}", amount)?;
+            }
         }
         write!(f, "{}", writer.trim_end_matches('\n'))
     }
@@ -715,6 +1921,95 @@ pub fn write_transaction_response(
     Ok(writer)
 }
 
+fn write_generated_keys(keys: &GeneratedValidatorKeys) -> Result<String, fmt::Error> {
+    let mut writer = String::new();
+    writeln!(writer, "----- Validator Keys ----")?;
+    writeln!(writer, "Account address: {}", keys.account_address)?;
+    for (name, key) in [
+        ("Account", &keys.account_key),
+        ("Protocol", &keys.protocol_key),
+        ("Network", &keys.network_key),
+        ("Worker", &keys.worker_key),
+    ] {
+        writeln!(writer, "{name} key file: {}", key.path.display())?;
+        writeln!(writer, "{name} public key: {}", key.public_key)?;
+    }
+    Ok(writer)
+}
+
+fn write_registration_bundle(
+    path: &Path,
+    bundle: &ValidatorRegistrationBundle,
+) -> Result<String, fmt::Error> {
+    let mut writer = String::new();
+    writeln!(writer, "----- Registration Bundle ----")?;
+    writeln!(writer, "Bundle file: {}", path.display())?;
+    writeln!(writer, "Account address: {}", bundle.account_address)?;
+    writeln!(
+        writer,
+        "Protocol public key: {}",
+        Base64::encode(bundle.protocol_public_key.as_ref())
+    )?;
+    writeln!(
+        writer,
+        "Network public key: {}",
+        bundle.network_public_key.encode_base64()
+    )?;
+    writeln!(
+        writer,
+        "Worker public key: {}",
+        bundle.worker_public_key.encode_base64()
+    )?;
+    writeln!(
+        writer,
+        "Proof of possession: {}",
+        Base64::encode(bundle.proof_of_possession.as_ref())
+    )?;
+    Ok(writer)
+}
+
+fn write_stake_summary(stake: &ValidatorStakeSummary) -> Result<String, fmt::Error> {
+    let mut writer = String::new();
+    writeln!(writer, "----- Validator Stake (MIST) ----")?;
+    writeln!(writer, "Validator: {}", stake.validator_address)?;
+    writeln!(writer, "Staking pool: {}", stake.staking_pool_id)?;
+    writeln!(writer, "Active stake: {}", stake.active_stake)?;
+    writeln!(writer, "Pending stake: {}", stake.pending_stake)?;
+    writeln!(writer, "Pending withdraw: {}", stake.pending_withdraw)?;
+    writeln!(writer, "Next epoch stake: {}", stake.next_epoch_stake)?;
+    writeln!(writer, "Minimum stake: {}", stake.min_stake)?;
+    Ok(writer)
+}
+
+fn write_rewards_summary(rewards: &ValidatorRewardsSummary) -> Result<String, fmt::Error> {
+    let mut writer = String::new();
+    writeln!(writer, "----- Validator Rewards (MIST) ----")?;
+    writeln!(writer, "Validator: {}", rewards.validator_address)?;
+    writeln!(writer, "Staking pool: {}", rewards.staking_pool_id)?;
+    writeln!(writer, "Epoch: {}", rewards.epoch)?;
+    writeln!(writer, "Self stake principal: {}", rewards.principal)?;
+    for stake in &rewards.stakes {
+        writeln!(
+            writer,
+            "StakedMgo {}: principal {}, active since epoch {}, reward {}",
+            stake.staked_mgo_id, stake.principal, stake.stake_active_epoch, stake.reward
+        )?;
+    }
+    for epoch in &rewards.epochs {
+        writeln!(
+            writer,
+            "Epoch {}: rate {} MIST / {} pool tokens, reward {}, accrued {}",
+            epoch.epoch,
+            epoch.mgo_amount,
+            epoch.pool_token_amount,
+            epoch.reward,
+            epoch.accrued_reward
+        )?;
+    }
+    writeln!(writer, "Claimable reward: {}", rewards.claimable_reward)?;
+    Ok(writer)
+}
+
 impl Debug for MgoValidatorCommandResponse {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let string = serde_json::to_string_pretty(self);
@@ -812,6 +2107,43 @@ async fn display_metadata(
     Ok(())
 }
 
+async fn get_validator_candidate(
+    mgo_client: &MgoClient,
+    validator_address: MgoAddress,
+    validator_candidates_id: ObjectID,
+) -> anyhow::Result<Option<ValidatorV1>> {
+    let wrapper = mgo_client
+        .read_api()
+        .get_dynamic_field_object(
+            validator_candidates_id,
+            DynamicFieldName {
+                type_: TypeTag::Address,
+                value: serde_json::json!(validator_address),
+            },
+        )
+        .await?;
+    let Some(bcs) = wrapper.move_object_bcs() else {
+        return Ok(None);
+    };
+    let wrapper = bcs::from_bytes::<Field<MgoAddress, ValidatorWrapper>>(bcs)?;
+    let versioned = wrapper.value.inner;
+    let validator = mgo_client
+        .read_api()
+        .get_dynamic_field_object(
+            versioned.id.id.bytes,
+            DynamicFieldName {
+                type_: TypeTag::U64,
+                value: serde_json::json!(versioned.version.to_string()),
+            },
+        )
+        .await?;
+    let bcs = validator.move_object_bcs().ok_or_else(|| {
+        anyhow!("Validator candidate {validator_address} has no inner validator object")
+    })?;
+    let validator = bcs::from_bytes::<Field<u64, ValidatorV1>>(bcs)?;
+    Ok(Some(validator.value))
+}
+
 async fn get_pending_candidate_summary(
     validator_address: MgoAddress,
     mgo_client: &MgoClient,
@@ -891,149 +2223,400 @@ pub enum MetadataUpdate {
     },
 }
 
-async fn update_metadata(
-    context: &mut WalletContext,
-    metadata: MetadataUpdate,
-    gas_budget: u64,
-) -> anyhow::Result<MgoTransactionBlockResponse> {
-    use ValidatorStatus::*;
-    match metadata {
-        MetadataUpdate::Name { name } => {
-            let args = vec![CallArg::Pure(bcs::to_bytes(&name.into_bytes()).unwrap())];
-            call_0x5(context, "update_validator_name", args, gas_budget).await
-        }
-        MetadataUpdate::Description { description } => {
-            let args = vec![CallArg::Pure(
-                bcs::to_bytes(&description.into_bytes()).unwrap(),
-            )];
-            call_0x5(context, "update_validator_description", args, gas_budget).await
-        }
-        MetadataUpdate::ImageUrl { image_url } => {
-            let args = vec![CallArg::Pure(
-                bcs::to_bytes(&image_url.into_bytes()).unwrap(),
-            )];
-            call_0x5(context, "update_validator_image_url", args, gas_budget).await
+/// A validator metadata change, with key files read and proofs of possession generated.
+#[derive(Debug, Clone)]
+pub enum MetadataChange {
+    Name(String),
+    Description(String),
+    ImageUrl(String),
+    ProjectUrl(String),
+    NetworkAddress(Multiaddr),
+    PrimaryAddress(Multiaddr),
+    WorkerAddress(Multiaddr),
+    P2pAddress(Multiaddr),
+    NetworkPubKey(NetworkPublicKey),
+    WorkerPubKey(NetworkPublicKey),
+    ProtocolPubKey {
+        protocol_pub_key: AuthorityPublicKey,
+        proof_of_possession: AuthoritySignature,
+    },
+}
+
+impl MetadataChange {
+    fn from_metadata_update(metadata: MetadataUpdate, mgo_address: MgoAddress) -> Result<Self> {
+        Ok(match metadata {
+            MetadataUpdate::Name { name } => Self::Name(name),
+            MetadataUpdate::Description { description } => Self::Description(description),
+            MetadataUpdate::ImageUrl { image_url } => Self::ImageUrl(image_url),
+            MetadataUpdate::ProjectUrl { project_url } => Self::ProjectUrl(project_url),
+            MetadataUpdate::NetworkAddress { network_address } => {
+                Self::NetworkAddress(network_address)
+            }
+            MetadataUpdate::PrimaryAddress { primary_address } => {
+                Self::PrimaryAddress(primary_address)
+            }
+            MetadataUpdate::WorkerAddress { worker_address } => Self::WorkerAddress(worker_address),
+            MetadataUpdate::P2pAddress { p2p_address } => Self::P2pAddress(p2p_address),
+            MetadataUpdate::NetworkPubKey { file } => {
+                Self::NetworkPubKey(read_network_keypair_from_file(file)?.public().clone())
+            }
+            MetadataUpdate::WorkerPubKey { file } => {
+                Self::WorkerPubKey(read_network_keypair_from_file(file)?.public().clone())
+            }
+            MetadataUpdate::ProtocolPubKey { file } => {
+                let protocol_key_pair: AuthorityKeyPair = read_authority_keypair_from_file(file)?;
+                Self::ProtocolPubKey {
+                    protocol_pub_key: protocol_key_pair.public().clone(),
+                    proof_of_possession: generate_proof_of_possession(
+                        &protocol_key_pair,
+                        mgo_address,
+                    ),
+                }
+            }
+        })
+    }
+
+    /// Whether the change only takes effect from the next epoch.
+    fn is_next_epoch(&self) -> bool {
+        !matches!(
+            self,
+            Self::Name(_) | Self::Description(_) | Self::ImageUrl(_) | Self::ProjectUrl(_)
+        )
+    }
+
+    /// Name and length of the field, for the fields whose length is limited on chain.
+    fn limited_field(&self) -> Option<(&'static str, usize)> {
+        match self {
+            Self::Name(name) => Some(("name", name.len())),
+            Self::Description(description) => Some(("description", description.len())),
+            Self::ImageUrl(image_url) => Some(("image url", image_url.len())),
+            Self::ProjectUrl(project_url) => Some(("project url", project_url.len())),
+            Self::NetworkAddress(address) => Some(("network address", address.to_string().len())),
+            Self::PrimaryAddress(address) => Some(("primary address", address.to_string().len())),
+            Self::WorkerAddress(address) => Some(("worker address", address.to_string().len())),
+            Self::P2pAddress(address) => Some(("p2p address", address.to_string().len())),
+            Self::NetworkPubKey(_) | Self::WorkerPubKey(_) | Self::ProtocolPubKey { .. } => None,
         }
-        MetadataUpdate::ProjectUrl { project_url } => {
-            let args = vec![CallArg::Pure(
-                bcs::to_bytes(&project_url.into_bytes()).unwrap(),
-            )];
-            call_0x5(context, "update_validator_project_url", args, gas_budget).await
+    }
+
+    fn apply(&self, metadata: &mut ValidatorMetadataV1) {
+        match self {
+            Self::Name(name) => metadata.name = name.clone(),
+            Self::Description(description) => metadata.description = description.clone(),
+            Self::ImageUrl(image_url) => metadata.image_url = image_url.clone(),
+            Self::ProjectUrl(project_url) => metadata.project_url = project_url.clone(),
+            Self::NetworkAddress(address) => {
+                metadata.next_epoch_net_address = Some(address.to_string())
+            }
+            Self::PrimaryAddress(address) => {
+                metadata.next_epoch_primary_address = Some(address.to_string())
+            }
+            Self::WorkerAddress(address) => {
+                metadata.next_epoch_worker_address = Some(address.to_string())
+            }
+            Self::P2pAddress(address) => {
+                metadata.next_epoch_p2p_address = Some(address.to_string())
+            }
+            Self::NetworkPubKey(key) => {
+                metadata.next_epoch_network_pubkey_bytes = Some(key.as_bytes().to_vec())
+            }
+            Self::WorkerPubKey(key) => {
+                metadata.next_epoch_worker_pubkey_bytes = Some(key.as_bytes().to_vec())
+            }
+            Self::ProtocolPubKey {
+                protocol_pub_key,
+                proof_of_possession,
+            } => {
+                metadata.next_epoch_protocol_pubkey_bytes =
+                    Some(protocol_pub_key.as_bytes().to_vec());
+                metadata.next_epoch_proof_of_possession =
+                    Some(proof_of_possession.as_ref().to_vec());
+            }
         }
-        MetadataUpdate::NetworkAddress { network_address } => {
-            let _status = check_status(context, HashSet::from([Pending, Active])).await?;
-            let args = vec![CallArg::Pure(bcs::to_bytes(&network_address).unwrap())];
-            call_0x5(
-                context,
+    }
+
+    /// The `mgo_system` function applying the change and its arguments after the system state.
+    fn call(&self) -> Result<(&'static str, Vec<CallArg>)> {
+        Ok(match self {
+            Self::Name(name) => (
+                "update_validator_name",
+                vec![CallArg::Pure(bcs::to_bytes(name.as_bytes()).unwrap())],
+            ),
+            Self::Description(description) => (
+                "update_validator_description",
+                vec![CallArg::Pure(
+                    bcs::to_bytes(description.as_bytes()).unwrap(),
+                )],
+            ),
+            Self::ImageUrl(image_url) => (
+                "update_validator_image_url",
+                vec![CallArg::Pure(bcs::to_bytes(image_url.as_bytes()).unwrap())],
+            ),
+            Self::ProjectUrl(project_url) => (
+                "update_validator_project_url",
+                vec![CallArg::Pure(
+                    bcs::to_bytes(project_url.as_bytes()).unwrap(),
+                )],
+            ),
+            Self::NetworkAddress(network_address) => (
                 "update_validator_next_epoch_network_address",
-                args,
-                gas_budget,
-            )
-            .await
-        }
-        MetadataUpdate::PrimaryAddress { primary_address } => {
-            let _status = check_status(context, HashSet::from([Pending, Active])).await?;
-            let args = vec![CallArg::Pure(bcs::to_bytes(&primary_address).unwrap())];
-            call_0x5(
-                context,
+                vec![CallArg::Pure(bcs::to_bytes(network_address).unwrap())],
+            ),
+            Self::PrimaryAddress(primary_address) => (
                 "update_validator_next_epoch_primary_address",
-                args,
-                gas_budget,
-            )
-            .await
-        }
-        MetadataUpdate::WorkerAddress { worker_address } => {
-            // Only an active validator can leave committee.
-            let _status = check_status(context, HashSet::from([Pending, Active])).await?;
-            let args = vec![CallArg::Pure(bcs::to_bytes(&worker_address).unwrap())];
-            call_0x5(
-                context,
+                vec![CallArg::Pure(bcs::to_bytes(primary_address).unwrap())],
+            ),
+            Self::WorkerAddress(worker_address) => (
                 "update_validator_next_epoch_worker_address",
-                args,
-                gas_budget,
-            )
-            .await
-        }
-        MetadataUpdate::P2pAddress { p2p_address } => {
-            let _status = check_status(context, HashSet::from([Pending, Active])).await?;
-            let args = vec![CallArg::Pure(bcs::to_bytes(&p2p_address).unwrap())];
-            call_0x5(
-                context,
+                vec![CallArg::Pure(bcs::to_bytes(worker_address).unwrap())],
+            ),
+            Self::P2pAddress(p2p_address) => (
                 "update_validator_next_epoch_p2p_address",
-                args,
-                gas_budget,
-            )
-            .await
-        }
-        MetadataUpdate::NetworkPubKey { file } => {
-            let _status = check_status(context, HashSet::from([Pending, Active])).await?;
-            let network_pub_key: NetworkPublicKey =
-                read_network_keypair_from_file(file)?.public().clone();
-            let args = vec![CallArg::Pure(
-                bcs::to_bytes(&network_pub_key.as_bytes().to_vec()).unwrap(),
-            )];
-            call_0x5(
-                context,
+                vec![CallArg::Pure(bcs::to_bytes(p2p_address).unwrap())],
+            ),
+            Self::NetworkPubKey(network_pub_key) => (
                 "update_validator_next_epoch_network_pubkey",
-                args,
-                gas_budget,
-            )
-            .await
-        }
-        MetadataUpdate::WorkerPubKey { file } => {
-            let _status = check_status(context, HashSet::from([Pending, Active])).await?;
-            let worker_pub_key: NetworkPublicKey =
-                read_network_keypair_from_file(file)?.public().clone();
-            let args = vec![CallArg::Pure(
-                bcs::to_bytes(&worker_pub_key.as_bytes().to_vec()).unwrap(),
-            )];
-            call_0x5(
-                context,
+                vec![CallArg::Pure(
+                    bcs::to_bytes(&network_pub_key.as_bytes().to_vec()).unwrap(),
+                )],
+            ),
+            Self::WorkerPubKey(worker_pub_key) => (
                 "update_validator_next_epoch_worker_pubkey",
-                args,
-                gas_budget,
-            )
-            .await
-        }
-        MetadataUpdate::ProtocolPubKey { file } => {
-            let _status = check_status(context, HashSet::from([Pending, Active])).await?;
-            let mgo_address = context.active_address()?;
-            let protocol_key_pair: AuthorityKeyPair = read_authority_keypair_from_file(file)?;
-            let protocol_pub_key: AuthorityPublicKey = protocol_key_pair.public().clone();
-            let pop = generate_proof_of_possession(&protocol_key_pair, mgo_address);
-            let args = vec![
-                CallArg::Pure(
-                    bcs::to_bytes(&AuthorityPublicKeyBytes::from_bytes(
-                        protocol_pub_key.as_bytes(),
-                    )?)
-                    .unwrap(),
-                ),
-                CallArg::Pure(bcs::to_bytes(&pop.as_ref().to_vec()).unwrap()),
-            ];
-            call_0x5(
-                context,
+                vec![CallArg::Pure(
+                    bcs::to_bytes(&worker_pub_key.as_bytes().to_vec()).unwrap(),
+                )],
+            ),
+            Self::ProtocolPubKey {
+                protocol_pub_key,
+                proof_of_possession,
+            } => (
                 "update_validator_next_epoch_protocol_pubkey",
-                args,
-                gas_budget,
-            )
-            .await
+                vec![
+                    CallArg::Pure(
+                        bcs::to_bytes(&AuthorityPublicKeyBytes::from_bytes(
+                            protocol_pub_key.as_bytes(),
+                        )?)
+                        .unwrap(),
+                    ),
+                    CallArg::Pure(bcs::to_bytes(&proof_of_possession.as_ref().to_vec()).unwrap()),
+                ],
+            ),
+        })
+    }
+}
+
+async fn update_metadata(
+    context: &mut WalletContext,
+    metadata: MetadataUpdate,
+    force: bool,
+    gas_budget: u64,
+) -> anyhow::Result<MgoTransactionBlockResponse> {
+    use ValidatorStatus::*;
+    let validator_address = context.active_address()?;
+    let change = MetadataChange::from_metadata_update(metadata, validator_address)?;
+    let summary = if change.is_next_epoch() {
+        check_status(context, HashSet::from([Pending, Active]))
+            .await?
+            .1
+    } else {
+        // Candidates can update these fields too. Only their lengths are checked, which does
+        // not need the summary.
+        let mgo_client = context.get_client().await?;
+        get_validator_summary(&mgo_client, validator_address)
+            .await?
+            .map(|(_status, summary)| summary)
+            .unwrap_or_default()
+    };
+    check_next_epoch_update(&summary, &ProposedUpdate::Metadata(change.clone()), force)?;
+    let (function, args) = change.call()?;
+    call_0x5(context, function, args, gas_budget).await
+}
+
+/// A change to a validator, checked against network constraints before being submitted.
+#[derive(Debug, Clone)]
+pub enum ProposedUpdate {
+    GasPrice(u64),
+    CommissionRate(u64),
+    Metadata(MetadataChange),
+}
+
+/// A network constraint that a [`ProposedUpdate`] would break.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// Commission rate, in basis points, above the maximum.
+    CommissionRateTooHigh { commission_rate: u64, max: u64 },
+    /// Gas price at or above the maximum.
+    GasPriceTooHigh { gas_price: u64, max: u64 },
+    /// Metadata field longer than the maximum, in bytes.
+    MetadataTooLong {
+        field: &'static str,
+        length: usize,
+        max: usize,
+    },
+    /// Next epoch metadata rejected by the on-chain metadata validation.
+    InvalidMetadata { field: &'static str },
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::CommissionRateTooHigh {
+                commission_rate,
+                max,
+            } => write!(
+                f,
+                "commission rate of {commission_rate} basis points is above the maximum of {max}"
+            ),
+            Violation::GasPriceTooHigh { gas_price, max } => {
+                write!(f, "gas price of {gas_price} MIST must be below {max}")
+            }
+            Violation::MetadataTooLong { field, length, max } => {
+                write!(
+                    f,
+                    "{field} is {length} bytes long, above the maximum of {max}"
+                )
+            }
+            Violation::InvalidMetadata { field } => write!(f, "next epoch {field} is invalid"),
+        }
+    }
+}
+
+/// Checks `update` against the constraints the system package enforces when it is submitted
+/// for `summary`'s validator. Next epoch metadata is validated the same way as on chain, the
+/// other limits are constants of the system package.
+pub fn validate_next_epoch_update(
+    summary: &MgoValidatorSummary,
+    update: &ProposedUpdate,
+) -> Vec<Violation> {
+    let mut violations = vec![];
+    match update {
+        ProposedUpdate::GasPrice(gas_price) => {
+            if *gas_price >= MAX_VALIDATOR_GAS_PRICE {
+                violations.push(Violation::GasPriceTooHigh {
+                    gas_price: *gas_price,
+                    max: MAX_VALIDATOR_GAS_PRICE,
+                });
+            }
+        }
+        ProposedUpdate::CommissionRate(commission_rate) => {
+            if *commission_rate > MAX_VALIDATOR_COMMISSION_RATE {
+                violations.push(Violation::CommissionRateTooHigh {
+                    commission_rate: *commission_rate,
+                    max: MAX_VALIDATOR_COMMISSION_RATE,
+                });
+            }
+        }
+        ProposedUpdate::Metadata(change) => {
+            if let Some((field, length)) = change.limited_field() {
+                if length > MAX_VALIDATOR_METADATA_LENGTH {
+                    violations.push(Violation::MetadataTooLong {
+                        field,
+                        length,
+                        max: MAX_VALIDATOR_METADATA_LENGTH,
+                    });
+                }
+            }
+            if change.is_next_epoch() {
+                let mut metadata = validator_metadata(summary);
+                change.apply(&mut metadata);
+                if let Err(code) = metadata.verify() {
+                    violations.push(Violation::InvalidMetadata {
+                        field: invalid_metadata_field(code),
+                    });
+                }
+            }
         }
     }
+    violations
+}
+
+fn validator_metadata(summary: &MgoValidatorSummary) -> ValidatorMetadataV1 {
+    ValidatorMetadataV1 {
+        mgo_address: summary.mgo_address,
+        protocol_pubkey_bytes: summary.protocol_pubkey_bytes.clone(),
+        network_pubkey_bytes: summary.network_pubkey_bytes.clone(),
+        worker_pubkey_bytes: summary.worker_pubkey_bytes.clone(),
+        proof_of_possession_bytes: summary.proof_of_possession_bytes.clone(),
+        name: summary.name.clone(),
+        description: summary.description.clone(),
+        image_url: summary.image_url.clone(),
+        project_url: summary.project_url.clone(),
+        net_address: summary.net_address.clone(),
+        p2p_address: summary.p2p_address.clone(),
+        primary_address: summary.primary_address.clone(),
+        worker_address: summary.worker_address.clone(),
+        next_epoch_protocol_pubkey_bytes: summary.next_epoch_protocol_pubkey_bytes.clone(),
+        next_epoch_proof_of_possession: summary.next_epoch_proof_of_possession.clone(),
+        next_epoch_network_pubkey_bytes: summary.next_epoch_network_pubkey_bytes.clone(),
+        next_epoch_worker_pubkey_bytes: summary.next_epoch_worker_pubkey_bytes.clone(),
+        next_epoch_net_address: summary.next_epoch_net_address.clone(),
+        next_epoch_p2p_address: summary.next_epoch_p2p_address.clone(),
+        next_epoch_primary_address: summary.next_epoch_primary_address.clone(),
+        next_epoch_worker_address: summary.next_epoch_worker_address.clone(),
+        extra_fields: Default::default(),
+    }
+}
+
+/// Metadata field named by an error code of `ValidatorMetadataV1::verify`.
+fn invalid_metadata_field(code: u64) -> &'static str {
+    match code {
+        E_METADATA_INVALID_POP => "proof of possession",
+        E_METADATA_INVALID_PUBKEY => "protocol public key",
+        E_METADATA_INVALID_NET_PUBKEY => "network public key",
+        E_METADATA_INVALID_WORKER_PUBKEY => "worker public key",
+        E_METADATA_INVALID_NET_ADDR => "network address",
+        E_METADATA_INVALID_P2P_ADDR => "p2p address",
+        E_METADATA_INVALID_PRIMARY_ADDR => "primary address",
+        E_METADATA_INVALID_WORKER_ADDR => "worker address",
+        _ => "metadata",
+    }
+}
+
+fn check_next_epoch_update(
+    summary: &MgoValidatorSummary,
+    update: &ProposedUpdate,
+    force: bool,
+) -> anyhow::Result<()> {
+    let violations = validate_next_epoch_update(summary, update);
+    if violations.is_empty() {
+        return Ok(());
+    }
+    let violations = violations
+        .iter()
+        .map(|violation| format!("  - {violation}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    if !force {
+        bail!(
+            "Validator {}'s update violates network constraints:\n{}\nUse --force to proceed anyway.",
+            summary.mgo_address,
+            violations,
+        );
+    }
+    println!(
+        "{}",
+        format!(
+            "Warning: validator {}'s update violates network constraints:\n{}",
+            summary.mgo_address, violations,
+        )
+        .yellow()
+    );
+    Ok(())
 }
 
 async fn check_status(
     context: &mut WalletContext,
     allowed_status: HashSet<ValidatorStatus>,
-) -> Result<ValidatorStatus> {
+) -> Result<(ValidatorStatus, MgoValidatorSummary)> {
     let mgo_client = context.get_client().await?;
     let validator_address = context.active_address()?;
     let summary = get_validator_summary(&mgo_client, validator_address).await?;
     if summary.is_none() {
         bail!("{validator_address} is not a Validator.");
     }
-    let (status, _summary) = summary.unwrap();
+    let (status, summary) = summary.unwrap();
     if allowed_status.contains(&status) {
-        return Ok(status);
+        return Ok((status, summary));
     }
     bail!("Validator {validator_address} is {:?}, this operation is not supported in this tool or prohibited.", status)
 }
@@ -8,9 +8,11 @@ use std::str::FromStr;
 use std::{fmt::Write, fs::read_dir, path::PathBuf, str, thread, time::Duration};
 
 use expect_test::expect;
+use fastcrypto::encoding::{Base64, Encoding};
 use move_package::BuildConfig as MoveBuildConfig;
 use serde_json::json;
 use mgo::key_identity::{get_identity_address, KeyIdentity};
+use mgo::multisig_commands::{MultisigCommand, MultisigCommandResult};
 use mgo_test_transaction_builder::batch_make_transfer_transactions;
 use mgo_types::object::Owner;
 use mgo_types::transaction::{
@@ -34,9 +36,11 @@ use mgo_json_rpc_types::{
     OwnedObjectRef, MgoObjectData, MgoObjectDataFilter, MgoObjectDataOptions, MgoObjectResponse,
     MgoObjectResponseQuery, MgoTransactionBlockEffects, MgoTransactionBlockEffectsAPI,
 };
+use mgo_keys::keypair_file::write_keypair_to_file;
 use mgo_keys::keystore::AccountKeystore;
 use mgo_macros::sim_test;
 use mgo_move_build::{BuildConfig, MgoPackageHooks};
+use mgo_sdk::guardrail::GuardrailConfirmation;
 use mgo_sdk::mgo_client_config::MgoClientConfig;
 use mgo_sdk::wallet_context::WalletContext;
 use mgo_swarm_config::genesis_config::{AccountConfig, GenesisConfig};
@@ -44,8 +48,10 @@ use mgo_swarm_config::network_config::NetworkConfig;
 use mgo_types::base_types::MgoAddress;
 use mgo_types::crypto::{
     Ed25519MgoSignature, Secp256k1MgoSignature, SignatureScheme, MgoKeyPair, MgoSignatureInner,
+    PublicKey,
 };
 use mgo_types::error::MgoObjectResponseError;
+use mgo_types::multisig::MultiSigPublicKey;
 use mgo_types::{base_types::ObjectID, crypto::get_key_pair, gas_coin::GasCoin};
 use test_cluster::TestClusterBuilder;
 
@@ -2674,3 +2680,308 @@ async fn key_identity_test() {
         get_identity_address(None, context).unwrap()
     );
 }
+
+async fn transfer_mgo_with_guardrails(
+    context: &mut WalletContext,
+    coin: ObjectID,
+    to: MgoAddress,
+    amount: u64,
+    gas_budget: u64,
+) -> Result<MgoClientCommandResult, anyhow::Error> {
+    MgoClientCommands::TransferMgo {
+        to: KeyIdentity::Address(to),
+        mgo_coin_object_id: coin,
+        gas_budget,
+        amount: Some(amount),
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await
+}
+
+#[sim_test]
+async fn test_guardrail_max_mgo_outflow() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address = test_cluster.get_address_0();
+    let address1 = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let coin = context
+        .get_one_gas_object_owned_by_address(address)
+        .await?
+        .unwrap()
+        .0;
+    let gas_budget = rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER;
+
+    context.config.guardrails.max_mgo_outflow = Some(1_000);
+    let err = transfer_mgo_with_guardrails(context, coin, address1, 1_000_000, gas_budget)
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("more than the maximum of 1000 MIST"),
+        "{err}"
+    );
+
+    // The transaction was not executed, and the same coin can be spent within a larger limit.
+    context.config.guardrails.max_mgo_outflow = Some(1_000_000 + gas_budget);
+    transfer_mgo_with_guardrails(context, coin, address1, 1_000_000, gas_budget).await?;
+
+    // Overriding the guardrails signs the transaction anyway.
+    context.config.guardrails.max_mgo_outflow = Some(1_000);
+    context.set_guardrail_confirmation(GuardrailConfirmation::Accept);
+    transfer_mgo_with_guardrails(context, coin, address1, 1_000_000, gas_budget).await?;
+    Ok(())
+}
+
+#[sim_test]
+async fn test_guardrail_deny_unknown_recipients() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address = test_cluster.get_address_0();
+    let address1 = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let coin = context
+        .get_one_gas_object_owned_by_address(address)
+        .await?
+        .unwrap()
+        .0;
+    let gas_budget = rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER;
+    context.config.guardrails.deny_unknown_recipients = true;
+
+    // Addresses managed by the wallet are known.
+    transfer_mgo_with_guardrails(context, coin, address1, 1, gas_budget).await?;
+
+    let stranger = MgoAddress::random_for_testing_only();
+    let err = transfer_mgo_with_guardrails(context, coin, stranger, 1, gas_budget)
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains(&format!("unknown address {stranger}")),
+        "{err}"
+    );
+
+    // Confirming the transfer remembers the recipient, which is then known.
+    context.set_guardrail_confirmation(GuardrailConfirmation::Accept);
+    transfer_mgo_with_guardrails(context, coin, stranger, 1, gas_budget).await?;
+    assert!(context
+        .config
+        .guardrails
+        .known_recipients
+        .contains(&stranger));
+    context.set_guardrail_confirmation(GuardrailConfirmation::Reject);
+    transfer_mgo_with_guardrails(context, coin, stranger, 1, gas_budget).await?;
+    Ok(())
+}
+
+async fn publish_with_guardrails(
+    context: &mut WalletContext,
+    gas: ObjectID,
+    gas_budget: u64,
+    serialize_unsigned_transaction: bool,
+) -> Result<MgoClientCommandResult, anyhow::Error> {
+    let mut package_path = PathBuf::from(TEST_DATA_DIR);
+    package_path.push("dummy_modules_publish");
+    MgoClientCommands::Publish {
+        package_path,
+        build_config: BuildConfig::new_for_testing().config,
+        gas: Some(gas),
+        gas_budget,
+        skip_dependency_verification: false,
+        with_unpublished_dependencies: false,
+        serialize_unsigned_transaction,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await
+}
+
+#[sim_test]
+async fn test_guardrail_deny_publish() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address = test_cluster.get_address_0();
+    let context = &mut test_cluster.wallet;
+    let gas = context
+        .get_one_gas_object_owned_by_address(address)
+        .await?
+        .unwrap()
+        .0;
+    let gas_budget = rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH;
+    context.config.guardrails.deny_publish = true;
+
+    let err = publish_with_guardrails(context, gas, gas_budget, false)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("publishes a package"), "{err}");
+
+    // Serializing the unsigned transaction does not sign it, and is not checked.
+    let resp = publish_with_guardrails(context, gas, gas_budget, true).await?;
+    assert!(matches!(
+        resp,
+        MgoClientCommandResult::SerializedUnsignedTransaction(_)
+    ));
+
+    context.set_guardrail_confirmation(GuardrailConfirmation::Accept);
+    let resp = publish_with_guardrails(context, gas, gas_budget, false).await?;
+    assert!(matches!(resp, MgoClientCommandResult::Publish(_)));
+    Ok(())
+}
+
+#[sim_test]
+async fn test_multisig_workflow() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let temp_dir = tempfile::tempdir()?;
+    let dir = temp_dir.path();
+
+    // A 2-of-3 multisig address, whose first two participants sign with key files and the
+    // third with the wallet's keystore.
+    let mut keys: Vec<MgoKeyPair> = (0..3)
+        .map(|_| MgoKeyPair::Ed25519(get_key_pair().1))
+        .collect();
+    let pks: Vec<PublicKey> = keys.iter().map(|k| k.public()).collect();
+    let participants: Vec<MgoAddress> = pks.iter().map(MgoAddress::from).collect();
+    context.config.keystore.add_key(None, keys.pop().unwrap())?;
+    let key_files: Vec<PathBuf> = keys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| {
+            let path = dir.join(format!("key{i}"));
+            write_keypair_to_file(key, &path).unwrap();
+            path
+        })
+        .collect();
+    let multisig_address: MgoAddress =
+        (&MultiSigPublicKey::new(pks.clone(), vec![1, 1, 1], 2)?).into();
+
+    // Fund the multisig address, then serialize a transfer from it.
+    let client = context.get_client().await?;
+    let gas = context
+        .get_one_gas_object_owned_by_address(address)
+        .await?
+        .unwrap()
+        .0;
+    MgoClientCommands::TransferMgo {
+        to: KeyIdentity::Address(multisig_address),
+        mgo_coin_object_id: gas,
+        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        amount: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER * 10),
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await?;
+    let multisig_coin = client
+        .coin_read_api()
+        .get_coins(multisig_address, None, None, None)
+        .await?
+        .data[0]
+        .coin_object_id;
+    let MgoClientCommandResult::SerializedUnsignedTransaction(tx_data) =
+        MgoClientCommands::TransferMgo {
+            to: KeyIdentity::Address(recipient),
+            mgo_coin_object_id: multisig_coin,
+            gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+            amount: Some(1),
+            serialize_unsigned_transaction: true,
+            serialize_signed_transaction: false,
+        }
+        .execute(context)
+        .await?
+    else {
+        panic!("Expected a serialized unsigned transaction");
+    };
+
+    let tx_file = dir.join("tx.json");
+    let MgoClientCommandResult::Multisig(MultisigCommandResult::InitTx(transaction)) =
+        MgoClientCommands::Multisig {
+            cmd: MultisigCommand::InitTx {
+                tx_bytes: Base64::encode(bcs::to_bytes(&tx_data)?),
+                pks,
+                weights: vec![1, 1, 1],
+                threshold: 2,
+                out: tx_file.clone(),
+            },
+        }
+        .execute(context)
+        .await?
+    else {
+        panic!("Expected a multisig transaction");
+    };
+    assert_eq!(transaction.multisig_address, multisig_address);
+    assert_eq!(transaction.participants.len(), 3);
+
+    let sign = |key: Option<PathBuf>, address: Option<MgoAddress>, out: &str| {
+        MgoClientCommands::Multisig {
+            cmd: MultisigCommand::Sign {
+                tx: tx_file.clone(),
+                key,
+                address: address.map(KeyIdentity::Address),
+                out: dir.join(out),
+            },
+        }
+    };
+    sign(Some(key_files[0].clone()), None, "sig0.json")
+        .execute(context)
+        .await?;
+    // A key outside of the multisig address signs nothing.
+    let outsider = dir.join("outsider");
+    write_keypair_to_file(&MgoKeyPair::Ed25519(get_key_pair().1), &outsider)?;
+    let err = sign(Some(outsider), None, "outsider.json")
+        .execute(context)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("not a participant"), "{err}");
+
+    let combine = |signatures: &[&str], submit: bool| MgoClientCommands::Multisig {
+        cmd: MultisigCommand::Combine {
+            tx: tx_file.clone(),
+            signatures: signatures.iter().map(|s| dir.join(s)).collect(),
+            submit,
+        },
+    };
+    // A single signature is below the threshold, and the missing participants are reported.
+    let err = combine(&["sig0.json"], true)
+        .execute(context)
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("below the threshold of 2"), "{err}");
+    assert!(err.contains(&participants[1].to_string()), "{err}");
+    assert!(err.contains(&participants[2].to_string()), "{err}");
+
+    sign(None, Some(participants[2]), "sig2.json")
+        .execute(context)
+        .await?;
+    // Signatures are combined in the order of the participants, whatever their files' order.
+    let MgoClientCommandResult::Multisig(MultisigCommandResult::Combine(combination)) =
+        combine(&["sig2.json", "sig0.json"], false)
+            .execute(context)
+            .await?
+    else {
+        panic!("Expected a multisig combination");
+    };
+    assert_eq!(combination.signers, vec![participants[0], participants[2]]);
+    assert_eq!(combination.missing, vec![participants[1]]);
+    assert_eq!(combination.signed_weight, 2);
+    assert!(combination.signed_tx_bytes.is_some());
+    assert!(combination.response.is_none());
+
+    let MgoClientCommandResult::Multisig(MultisigCommandResult::Combine(combination)) =
+        combine(&["sig0.json", "sig2.json"], true)
+            .execute(context)
+            .await?
+    else {
+        panic!("Expected a multisig combination");
+    };
+    let response = combination.response.unwrap();
+    assert!(response.effects.unwrap().status().is_ok());
+    assert_eq!(response.digest, transaction.digest);
+    Ok(())
+}
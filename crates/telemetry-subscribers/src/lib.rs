@@ -47,6 +47,9 @@ pub type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
 #[derive(Default, Clone, Debug)]
 pub struct TelemetryConfig {
     pub enable_otlp_tracing: bool,
+    /// OTLP collector to export spans to. Falls back to the `OTLP_ENDPOINT` environment variable,
+    /// then to `http://localhost:4317`.
+    pub otlp_endpoint: Option<String>,
     /// Enables Tokio Console debugging on port 6669
     pub tokio_console: bool,
     /// Output JSON logs.
@@ -248,6 +251,7 @@ impl TelemetryConfig {
     pub fn new() -> Self {
         Self {
             enable_otlp_tracing: false,
+            otlp_endpoint: None,
             tokio_console: false,
             json_log_output: false,
             log_file: None,
@@ -300,6 +304,14 @@ impl TelemetryConfig {
         self
     }
 
+    /// Exports spans to the OTLP collector at `endpoint`. Which spans are exported is controlled
+    /// by `TRACE_FILTER`, defaulting to `info`.
+    pub fn with_otlp_endpoint(mut self, endpoint: &str) -> Self {
+        self.enable_otlp_tracing = true;
+        self.otlp_endpoint = Some(endpoint.to_owned());
+        self
+    }
+
     pub fn with_env(mut self) -> Self {
         if env::var("CRASH_ON_PANIC").is_ok() {
             self.crash_on_panic = true
@@ -383,6 +395,7 @@ impl TelemetryConfig {
 
         if config.enable_otlp_tracing {
             let trace_file = env::var("TRACE_FILE").ok();
+            let otlp_endpoint = config.otlp_endpoint.clone();
 
             let config = sdk::trace::config()
                 .with_resource(Resource::new(vec![opentelemetry::KeyValue::new(
@@ -409,8 +422,9 @@ impl TelemetryConfig {
 
                 tracing_opentelemetry::layer().with_tracer(tracer)
             } else {
-                let endpoint = env::var("OTLP_ENDPOINT")
-                    .unwrap_or_else(|_| "http://localhost:4317".to_string());
+                let endpoint = otlp_endpoint
+                    .or_else(|| env::var("OTLP_ENDPOINT").ok())
+                    .unwrap_or_else(|| "http://localhost:4317".to_string());
 
                 let tracer = opentelemetry_otlp::new_pipeline()
                     .tracing()
@@ -431,7 +445,8 @@ impl TelemetryConfig {
                 opentelemetry::sdk::propagation::TraceContextPropagator::new(),
             );
 
-            let trace_env_filter = EnvFilter::try_from_env("TRACE_FILTER").unwrap();
+            let trace_env_filter =
+                EnvFilter::try_from_env("TRACE_FILTER").unwrap_or_else(|_| EnvFilter::new("info"));
             let (trace_env_filter, reload_handle) = reload::Layer::new(trace_env_filter);
             trace_filter_handle = Some(FilterHandle(reload_handle));
 
@@ -1036,6 +1036,7 @@ impl TestClusterBuilder {
             envs: Default::default(),
             active_address,
             active_env: Default::default(),
+            guardrails: Default::default(),
         }
         .save(wallet_path)?;
 
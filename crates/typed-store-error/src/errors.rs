@@ -4,8 +4,11 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+/// Serialized as its [`TypedStoreErrorWire`] form, so that errors of a remote store decode into
+/// the same variants, or into [`TypedStoreError::Unknown`] for variants added after this node.
 #[non_exhaustive]
 #[derive(Error, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Ord, PartialOrd)]
+#[serde(into = "TypedStoreErrorWire", from = "TypedStoreErrorWire")]
 pub enum TypedStoreError {
     #[error("rocksdb error: {0}")]
     RocksDBError(String),
@@ -17,6 +20,215 @@ pub enum TypedStoreError {
     CrossDBBatch,
     #[error("Metric reporting thread failed with error")]
     MetricsReporting,
+    /// Conflict of an optimistic transaction with a concurrent write, as reported by stores that
+    /// do not tell [`TypedStoreError::RetryableConflict`] apart.
     #[error("Transaction should be retried")]
     RetryableTransactionError,
+    /// An error of a remote store with a code this node does not know of.
+    #[error("unknown store error {code}: {message}")]
+    Unknown { code: u32, message: String },
+    /// Conflict of an optimistic transaction with a concurrent write, which a retry of the whole
+    /// transaction resolves. `key_hint` names where the transaction wrote, for logging only.
+    #[error("write conflict on {key_hint}, transaction should be retried")]
+    RetryableConflict { key_hint: String },
+}
+
+/// Wire form of a [`TypedStoreError`]: a code identifying the variant, which never changes once
+/// assigned, and the message of the variants that carry one.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct TypedStoreErrorWire {
+    pub code: u32,
+    pub message: String,
+}
+
+/// How bad a [`TypedStoreError`] is, which tells whether the operation that failed is worth
+/// retrying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    /// The store is busy, stalled or failed an IO call, the same operation may succeed later.
+    Transient,
+    /// The operation itself is wrong, retrying it fails the same way.
+    Permanent,
+    /// The data on disk is corrupted.
+    Corruption,
+}
+
+/// Prefixes of the RocksDB status messages of errors that may go away on their own, e.g. a
+/// busy column family, a write stall with `no_slowdown` set, or a failed IO call.
+const TRANSIENT_ROCKSDB_ERRORS: [&str; 6] = [
+    "Resource busy",
+    "Operation timed out",
+    "Result incomplete",
+    "Operation failed. Try again",
+    "Operation aborted",
+    "IO error",
+];
+
+impl TypedStoreError {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::RocksDBError(message) if message.starts_with("Corruption") => {
+                Severity::Corruption
+            }
+            Self::RocksDBError(message)
+                if TRANSIENT_ROCKSDB_ERRORS
+                    .iter()
+                    .any(|prefix| message.starts_with(prefix)) =>
+            {
+                Severity::Transient
+            }
+            Self::RetryableTransactionError | Self::RetryableConflict { .. } => Severity::Transient,
+            Self::RocksDBError(_)
+            | Self::SerializationError(_)
+            | Self::UnregisteredColumn(_)
+            | Self::CrossDBBatch
+            | Self::MetricsReporting
+            | Self::Unknown { .. } => Severity::Permanent,
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        self.severity() == Severity::Transient
+    }
+
+    /// Stable code of the variant, as sent over the wire.
+    pub fn code(&self) -> u32 {
+        match self {
+            Self::RocksDBError(_) => 1,
+            Self::SerializationError(_) => 2,
+            Self::UnregisteredColumn(_) => 3,
+            Self::CrossDBBatch => 4,
+            Self::MetricsReporting => 5,
+            Self::RetryableTransactionError => 6,
+            Self::RetryableConflict { .. } => 7,
+            Self::Unknown { code, .. } => *code,
+        }
+    }
+
+    /// The code and message the error is sent over the wire as. Variants without a message of
+    /// their own are sent with their description, which is only there for readers that do not
+    /// know the code.
+    pub fn to_code_and_message(&self) -> (u32, String) {
+        let message = match self {
+            Self::RocksDBError(message)
+            | Self::SerializationError(message)
+            | Self::UnregisteredColumn(message)
+            | Self::Unknown { message, .. }
+            | Self::RetryableConflict { key_hint: message } => message.clone(),
+            Self::CrossDBBatch | Self::MetricsReporting | Self::RetryableTransactionError => {
+                self.to_string()
+            }
+        };
+        (self.code(), message)
+    }
+
+    /// The error sent over the wire as `code` and `message`.
+    pub fn from_code_and_message(code: u32, message: String) -> Self {
+        match code {
+            1 => Self::RocksDBError(message),
+            2 => Self::SerializationError(message),
+            3 => Self::UnregisteredColumn(message),
+            4 => Self::CrossDBBatch,
+            5 => Self::MetricsReporting,
+            6 => Self::RetryableTransactionError,
+            7 => Self::RetryableConflict { key_hint: message },
+            code => Self::Unknown { code, message },
+        }
+    }
+}
+
+impl From<TypedStoreError> for TypedStoreErrorWire {
+    fn from(error: TypedStoreError) -> Self {
+        let (code, message) = error.to_code_and_message();
+        Self { code, message }
+    }
+}
+
+impl From<TypedStoreErrorWire> for TypedStoreError {
+    fn from(wire: TypedStoreErrorWire) -> Self {
+        Self::from_code_and_message(wire.code, wire.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_severity() {
+        use Severity::*;
+        use TypedStoreError::*;
+        let rocksdb = |message: &str| RocksDBError(message.to_string());
+        let cases = [
+            (rocksdb("Resource busy: "), Transient),
+            (rocksdb("Operation timed out: "), Transient),
+            (rocksdb("Result incomplete: Write stall"), Transient),
+            (rocksdb("Operation failed. Try again.: "), Transient),
+            (rocksdb("Operation aborted: "), Transient),
+            (rocksdb("IO error: No space left on device"), Transient),
+            (rocksdb("Corruption: block checksum mismatch"), Corruption),
+            (rocksdb("Invalid argument: "), Permanent),
+            (RetryableTransactionError, Transient),
+            (
+                RetryableConflict {
+                    key_hint: "cf".to_string(),
+                },
+                Transient,
+            ),
+            (SerializationError(String::new()), Permanent),
+            (UnregisteredColumn("cf".to_string()), Permanent),
+            (CrossDBBatch, Permanent),
+            (MetricsReporting, Permanent),
+            (
+                Unknown {
+                    code: 99,
+                    message: String::new(),
+                },
+                Permanent,
+            ),
+        ];
+        for (error, severity) in cases {
+            assert_eq!(error.severity(), severity, "{error}");
+            assert_eq!(error.is_retryable(), severity == Transient, "{error}");
+        }
+    }
+
+    #[test]
+    fn test_code_and_message_round_trip() {
+        use TypedStoreError::*;
+        let errors = [
+            RocksDBError("Resource busy: ".to_string()),
+            SerializationError("unexpected end of input".to_string()),
+            UnregisteredColumn("cf".to_string()),
+            CrossDBBatch,
+            MetricsReporting,
+            RetryableTransactionError,
+            RetryableConflict {
+                key_hint: "cf".to_string(),
+            },
+            Unknown {
+                code: 99,
+                message: "added by a newer node".to_string(),
+            },
+        ];
+        let codes: std::collections::BTreeSet<_> = errors.iter().map(|e| e.code()).collect();
+        assert_eq!(codes.len(), errors.len());
+        for error in errors {
+            let (code, message) = error.to_code_and_message();
+            let decoded = TypedStoreError::from_code_and_message(code, message);
+            assert_eq!(decoded, error);
+            assert_eq!(decoded.severity(), error.severity());
+            let wire = TypedStoreErrorWire::from(error.clone());
+            assert_eq!(TypedStoreError::from(wire), error);
+        }
+
+        // A code this node does not know of keeps its message.
+        assert_eq!(
+            TypedStoreError::from_code_and_message(8, "new".to_string()),
+            Unknown {
+                code: 8,
+                message: "new".to_string()
+            }
+        );
+    }
 }
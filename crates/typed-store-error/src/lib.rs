@@ -9,6 +9,8 @@
 )]
 
 pub mod errors;
+pub mod retry;
 
-pub use errors::TypedStoreError;
+pub use errors::{Severity, TypedStoreError, TypedStoreErrorWire};
+pub use retry::{retry_with_backoff, RetryPolicy, RetryableError};
 pub type StoreError = errors::TypedStoreError;
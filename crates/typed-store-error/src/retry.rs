@@ -0,0 +1,129 @@
+// Copyright (c) MangoNet Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use crate::errors::TypedStoreError;
+
+/// Errors that tell whether the operation that returned them may succeed if tried again.
+pub trait RetryableError {
+    fn is_retryable(&self) -> bool;
+}
+
+impl RetryableError for TypedStoreError {
+    fn is_retryable(&self) -> bool {
+        TypedStoreError::is_retryable(self)
+    }
+}
+
+/// Bounded exponential backoff for [`retry_with_backoff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of times the operation is run at most, including the first one.
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled before every later one.
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(50),
+            max_delay: Duration::from_secs(2),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry`, counting from 0.
+    fn delay(&self, retry: u32) -> Duration {
+        self.initial_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay)
+    }
+}
+
+/// Runs `operation` until it succeeds, fails with an error that is not retryable, or has been
+/// run `policy.max_attempts` times, sleeping between attempts. Returns the last result.
+pub fn retry_with_backoff<T, E: RetryableError>(
+    policy: &RetryPolicy,
+    mut operation: impl FnMut() -> Result<T, E>,
+) -> Result<T, E> {
+    let mut attempt = 1;
+    loop {
+        match operation() {
+            Err(e) if e.is_retryable() && attempt < policy.max_attempts => {
+                std::thread::sleep(policy.delay(attempt - 1));
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: RetryPolicy = RetryPolicy {
+        max_attempts: 3,
+        initial_delay: Duration::ZERO,
+        max_delay: Duration::ZERO,
+    };
+
+    fn busy() -> TypedStoreError {
+        TypedStoreError::RocksDBError("Resource busy: ".to_string())
+    }
+
+    #[test]
+    fn test_retry_gives_up_after_max_attempts() {
+        let mut attempts = 0;
+        let result: Result<(), _> = retry_with_backoff(&POLICY, || {
+            attempts += 1;
+            Err(busy())
+        });
+        assert_eq!(result, Err(busy()));
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn test_retry_until_success() {
+        let mut attempts = 0;
+        let result = retry_with_backoff(&POLICY, || {
+            attempts += 1;
+            if attempts < 3 {
+                Err(TypedStoreError::RetryableTransactionError)
+            } else {
+                Ok(attempts)
+            }
+        });
+        assert_eq!(result, Ok(3));
+    }
+
+    #[test]
+    fn test_no_retry_on_permanent_error() {
+        let mut attempts = 0;
+        let result: Result<(), _> = retry_with_backoff(&POLICY, || {
+            attempts += 1;
+            Err(TypedStoreError::CrossDBBatch)
+        });
+        assert_eq!(result, Err(TypedStoreError::CrossDBBatch));
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn test_backoff_is_bounded() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            initial_delay: Duration::from_millis(50),
+            max_delay: Duration::from_millis(300),
+        };
+        let delays = (0..5).map(|retry| policy.delay(retry)).collect::<Vec<_>>();
+        assert_eq!(
+            delays,
+            [50, 100, 200, 300, 300].map(Duration::from_millis).to_vec()
+        );
+    }
+}
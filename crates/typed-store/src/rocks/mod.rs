@@ -34,7 +34,7 @@ use serde::{de::DeserializeOwned, Serialize};
 use std::ops::Bound;
 use std::{
     borrow::Borrow,
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     env,
     marker::PhantomData,
     ops::RangeBounds,
@@ -157,7 +157,10 @@ macro_rules! retry_transaction {
         loop {
             let status = $transaction;
             match status {
-                Err(TypedStoreError::RetryableTransactionError) => {
+                Err(
+                    TypedStoreError::RetryableTransactionError
+                    | TypedStoreError::RetryableConflict { .. },
+                ) => {
                     retries += 1;
                     // Randomized delay to help racing transactions get out of each other's way.
                     let delay = {
@@ -1481,6 +1484,8 @@ impl DBBatch {
 pub struct DBTransaction<'a> {
     rocksdb: Arc<RocksDB>,
     transaction: Transaction<'a, rocksdb::OptimisticTransactionDB>,
+    // the column families written by the transaction, reported when its commit conflicts
+    written_cfs: BTreeSet<String>,
 }
 
 impl<'a> DBTransaction<'a> {
@@ -1488,6 +1493,7 @@ impl<'a> DBTransaction<'a> {
         Ok(Self {
             rocksdb: db.clone(),
             transaction: db.transaction()?,
+            written_cfs: BTreeSet::new(),
         })
     }
 
@@ -1495,6 +1501,7 @@ impl<'a> DBTransaction<'a> {
         Ok(Self {
             rocksdb: db.clone(),
             transaction: db.transaction_without_snapshot()?,
+            written_cfs: BTreeSet::new(),
         })
     }
 
@@ -1506,6 +1513,7 @@ impl<'a> DBTransaction<'a> {
         if !Arc::ptr_eq(&db.rocksdb, &self.rocksdb) {
             return Err(TypedStoreError::CrossDBBatch);
         }
+        self.written_cfs.insert(db.cf.clone());
 
         new_vals
             .into_iter()
@@ -1529,6 +1537,7 @@ impl<'a> DBTransaction<'a> {
         if !Arc::ptr_eq(&db.rocksdb, &self.rocksdb) {
             return Err(TypedStoreError::CrossDBBatch);
         }
+        self.written_cfs.insert(db.cf.clone());
         purged_vals
             .into_iter()
             .try_for_each::<_, Result<_, TypedStoreError>>(|k| {
@@ -1657,10 +1666,13 @@ impl<'a> DBTransaction<'a> {
 
     pub fn commit(self) -> Result<(), TypedStoreError> {
         fail_point!("transaction-commit");
+        let written_cfs = self.written_cfs;
         self.transaction.commit().map_err(|e| match e.kind() {
             // empirically, this is what you get when there is a write conflict. it is not
             // documented whether this is the only time you can get this error.
-            ErrorKind::Busy | ErrorKind::TryAgain => TypedStoreError::RetryableTransactionError,
+            ErrorKind::Busy | ErrorKind::TryAgain => TypedStoreError::RetryableConflict {
+                key_hint: written_cfs.into_iter().collect::<Vec<_>>().join(", "),
+            },
             _ => typed_store_err_from_rocks_err(e),
         })?;
         Ok(())
@@ -1138,7 +1138,7 @@ async fn test_transaction_snapshot() {
     db.insert(&key, &"1".to_string()).unwrap();
     assert!(matches!(
         tx1.commit(),
-        Err(TypedStoreError::RetryableTransactionError)
+        Err(TypedStoreError::RetryableConflict { .. })
     ));
     assert_eq!(db.get(&key).unwrap().unwrap(), "1".to_string());
 
@@ -1150,7 +1150,7 @@ async fn test_transaction_snapshot() {
         .unwrap();
     assert!(matches!(
         tx1.commit(),
-        Err(TypedStoreError::RetryableTransactionError)
+        Err(TypedStoreError::RetryableConflict { .. })
     ));
 
     let mut tx1 = db.transaction().expect("failed to initiate transaction");
@@ -1175,7 +1175,7 @@ async fn test_transaction_snapshot() {
     tx1.commit().expect("failed to commit");
     assert!(matches!(
         tx2.commit(),
-        Err(TypedStoreError::RetryableTransactionError)
+        Err(TypedStoreError::RetryableConflict { .. })
     ));
 
     // IMPORTANT: a race is still possible if one tx commits before the other writes.
@@ -1252,6 +1252,68 @@ async fn test_retry_transaction() {
     .unwrap_err();
 }
 
+#[test]
+fn test_retry_conflicting_writers() {
+    let key = "log".to_string();
+    let path = temp_dir();
+    let opt = rocksdb::Options::default();
+    let rocksdb =
+        open_cf_opts_transactional(path, None, MetricConf::default(), &[("cf", opt)]).unwrap();
+    let db = DBMap::<String, Vec<String>>::reopen(&rocksdb, None, &ReadWriteOptions::default())
+        .expect("Failed to re-open storage");
+
+    // Both writers stage their first attempt before either commits, so that one of them
+    // conflicts and has to run its transaction again.
+    let staged = std::sync::Barrier::new(2);
+    let policy = typed_store_error::RetryPolicy {
+        max_attempts: 5,
+        initial_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(10),
+    };
+    let conflicts = std::sync::Mutex::new(vec![]);
+    let mut attempts: Vec<u32> = std::thread::scope(|s| {
+        let writers: Vec<_> = ["a", "b"]
+            .into_iter()
+            .map(|writer| {
+                let (db, key, staged, conflicts) = (db.clone(), key.clone(), &staged, &conflicts);
+                s.spawn(move || {
+                    let mut attempts = 0;
+                    typed_store_error::retry_with_backoff(&policy, || {
+                        attempts += 1;
+                        let mut tx = db.transaction()?;
+                        let mut log = tx.get_for_update(&db, &key)?.unwrap_or_default();
+                        log.push(writer.to_string());
+                        tx.insert_batch(&db, [(&key, log)])?;
+                        if attempts == 1 {
+                            staged.wait();
+                        }
+                        tx.commit().map_err(|e| {
+                            conflicts.lock().unwrap().push(e.clone());
+                            e
+                        })
+                    })
+                    .expect("writer failed after retries");
+                    attempts
+                })
+            })
+            .collect();
+        writers.into_iter().map(|w| w.join().unwrap()).collect()
+    });
+
+    attempts.sort();
+    assert_eq!(attempts, vec![1, 2]);
+    assert_eq!(
+        conflicts.into_inner().unwrap(),
+        vec![TypedStoreError::RetryableConflict {
+            key_hint: "cf".to_string()
+        }]
+    );
+    // Neither write is lost.
+    let mut log = db.get(&key).unwrap().unwrap();
+    log.sort();
+    assert_eq!(log, vec!["a".to_string(), "b".to_string()]);
+}
+
 #[tokio::test]
 async fn test_transaction_read_your_write() {
     let key1 = "key1";
@@ -3,16 +3,45 @@
 
 use std::{
     collections::{BTreeSet, HashMap},
+    env, fmt, fs,
     path::PathBuf,
 };
 
 use cargo_metadata::{DependencyKind, Metadata, MetadataCommand, Package};
 use petgraph::{algo::all_simple_paths, prelude::DiGraphMap};
+use serde::{Deserialize, Serialize};
 
 type PackageGraph<'p> = DiGraphMap<&'p str, ()>;
 
 struct Packages(HashMap<String, Package>);
 
+/// If set, the directory each test writes its [`EncapsulationReport`] to, as JSON, whether or not
+/// it passes.
+const REPORT_DIR_ENV_VAR: &str = "MGO_ENCAPSULATION_REPORT_DIR";
+
+/// Crates exempted from the encapsulation tests, as listed in `encapsulation_allowlist.toml`.
+#[derive(Debug, Deserialize)]
+struct Allowlist {
+    /// Crates that the execution layer depends on but which are not directly part of it.
+    shared: BTreeSet<String>,
+}
+
+/// The dependency paths that break the encapsulation of the execution layer.
+#[derive(Debug, Default, Serialize)]
+struct EncapsulationReport {
+    violations: Vec<Violation>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct Violation {
+    /// The crate the path starts from.
+    root: String,
+    /// The execution crate the path ends at.
+    execution_crate: String,
+    /// The crates on the path, from `root` to `execution_crate`.
+    path: Vec<String>,
+}
+
 #[test]
 /// Make sure that all accesses to execution layer crates in the `mgo-node` and `mgo-replay` crates
 /// go via the `mgo-execution` crate (in other words, the `mgo-execution` crate dominates execution
@@ -23,55 +52,19 @@ struct Packages(HashMap<String, Package>);
 fn test_encapsulation() {
     let metadata = cargo_metadata().unwrap();
     let packages = Packages::new(&metadata);
+    let allowlist = Allowlist::load();
 
-    // Identify the crates that are part of the execution layer
-    let mut exec_crates: BTreeSet<_> = packages.normal_deps("mgo-execution").collect();
-
-    // Remove the crates that the execution layer depends on but which are not directly part of the
-    // execution layer -- these don't need to be accessed exclusively via `mgo-execution`.
-    exec_crates.remove("mgo-protocol-config");
-    exec_crates.remove("mgo-types");
-    exec_crates.remove("move-binary-format");
-    exec_crates.remove("move-bytecode-utils");
-    exec_crates.remove("move-core-types");
-    exec_crates.remove("move-vm-config");
-    exec_crates.remove("workspace-hack");
-
-    // Capture problematic paths from roots to execution crates
-    let mut examples = vec![];
-
-    for root in ["mgo-node", "mgo-replay"] {
-        let mut graph = packages.graph(root);
-
-        // If we can still create a path from `root` to an execution crate after removing these
-        // nodes then we know that we can potential bypass "mgo-execution".
-        graph.remove_node("mgo-execution");
-        graph.remove_node("workspace-hack");
-
-        for exec_crate in &exec_crates {
-            let paths = all_simple_paths::<Vec<&str>, &PackageGraph>(
-                &graph, root, exec_crate, /* min_intermediate_nodes */ 0,
-                /* max_intermediate_nodes */ None,
-            );
-
-            examples.extend(paths.map(|p| p.join(" -> ")));
-        }
-    }
-
-    if examples.is_empty() {
-        return;
-    }
+    let exec_crates = packages.execution_crates(&allowlist);
+    let report = packages.bypassing_paths(&["mgo-node", "mgo-replay"], &exec_crates);
 
-    panic!(
-        "protocol-sensitive binaries depend on execution crates outside of 'mgo-execution', e.g.:\n\
-         \n  {}\n\
-         \n\
-         This can cause execution to fork by not respecting the execution layer version set in the \
+    report.check(
+        "encapsulation",
+        "protocol-sensitive binaries depend on execution crates outside of 'mgo-execution', e.g.:",
+        "This can cause execution to fork by not respecting the execution layer version set in the \
          protocol config.  Fix this by depending on these crates via 'mgo-execution'.\n\
          \n\
-         P.S. if you believe one of these crates should not be part of 'mgo-execution' then update \
-         the test to exclude this crate.",
-        examples.join("\n  "),
+         P.S. if you believe one of these crates should not be part of 'mgo-execution' then add \
+         it to `shared` in 'encapsulation_allowlist.toml'.",
     );
 }
 
@@ -86,6 +79,49 @@ fn cargo_metadata() -> cargo_metadata::Result<Metadata> {
         .exec()
 }
 
+impl Allowlist {
+    fn load() -> Self {
+        toml::from_str(include_str!("encapsulation_allowlist.toml"))
+            .expect("'encapsulation_allowlist.toml' should be a valid allowlist")
+    }
+}
+
+impl EncapsulationReport {
+    /// Writes the report to `<name>.json` in the report directory, if one is set, and panics if it
+    /// has any violations, listing them between `summary` and `advice`.
+    fn check(&self, name: &str, summary: &str, advice: &str) {
+        if let Some(dir) = env::var_os(REPORT_DIR_ENV_VAR) {
+            let dir = PathBuf::from(dir);
+            fs::create_dir_all(&dir).unwrap();
+            let report = serde_json::to_string_pretty(self).unwrap();
+            fs::write(dir.join(format!("{name}.json")), report).unwrap();
+        }
+
+        if self.violations.is_empty() {
+            return;
+        }
+
+        let violations: Vec<_> = self.violations.iter().map(|v| v.to_string()).collect();
+        panic!("{summary}\n\n  {}\n\n{advice}", violations.join("\n  "));
+    }
+}
+
+impl Violation {
+    fn new(root: &str, execution_crate: &str, path: Vec<&str>) -> Self {
+        Self {
+            root: root.to_string(),
+            execution_crate: execution_crate.to_string(),
+            path: path.into_iter().map(String::from).collect(),
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.join(" -> "))
+    }
+}
+
 impl Packages {
     /// Create a mapping from package names to package `metadata` (from the output of `cargo
     /// metadata`).
@@ -98,6 +134,42 @@ impl Packages {
         ))
     }
 
+    /// The crates that are part of the execution layer: the normal dependencies of
+    /// `mgo-execution` that are not shared with the rest of the codebase.
+    fn execution_crates(&self, allowlist: &Allowlist) -> BTreeSet<&str> {
+        self.normal_deps("mgo-execution")
+            .filter(|dep| !allowlist.shared.contains(*dep))
+            .collect()
+    }
+
+    /// Every path from one of `roots` to one of `exec_crates` that does not go through
+    /// `mgo-execution`.
+    fn bypassing_paths(&self, roots: &[&str], exec_crates: &BTreeSet<&str>) -> EncapsulationReport {
+        let mut report = EncapsulationReport::default();
+
+        for &root in roots {
+            let mut graph = self.graph(root);
+
+            // If we can still create a path from `root` to an execution crate after removing these
+            // nodes then we know that we can potential bypass "mgo-execution".
+            graph.remove_node("mgo-execution");
+            graph.remove_node("workspace-hack");
+
+            for exec_crate in exec_crates {
+                let paths = all_simple_paths::<Vec<&str>, &PackageGraph>(
+                    &graph, root, exec_crate, /* min_intermediate_nodes */ 0,
+                    /* max_intermediate_nodes */ None,
+                );
+
+                report
+                    .violations
+                    .extend(paths.map(|path| Violation::new(root, exec_crate, path)));
+            }
+        }
+
+        report
+    }
+
     /// Extract the transitive dependency sub-graph of the package named `root`.  The graph is a
     /// directed, unweighted graph with nodes representing packages, identified by their name (a
     /// `&str`).
@@ -140,3 +212,154 @@ impl Packages {
         self.normal_edges(pkg).map(move |(_, to)| to)
     }
 }
+
+/// A dependency in a [`fixture`]: its name, its kind as printed by `cargo metadata` (`None` for
+/// normal dependencies) and the platform it is specific to, if any.
+type FixtureDependency<'a> = (&'a str, Option<&'a str>, Option<&'a str>);
+
+/// The `cargo metadata` of a synthetic workspace made of `packages` and their dependencies.
+fn fixture(packages: &[(&str, &[FixtureDependency])]) -> Metadata {
+    let packages: Vec<_> = packages
+        .iter()
+        .map(|(name, dependencies)| {
+            let dependencies: Vec<_> = dependencies
+                .iter()
+                .map(|(dep, kind, target)| {
+                    serde_json::json!({
+                        "name": dep,
+                        "source": null,
+                        "req": "*",
+                        "kind": kind,
+                        "rename": null,
+                        "optional": false,
+                        "uses_default_features": true,
+                        "features": [],
+                        "target": target,
+                        "registry": null,
+                        "path": null,
+                    })
+                })
+                .collect();
+            serde_json::json!({
+                "name": name,
+                "version": "0.1.0",
+                "id": format!("{name} 0.1.0 (path+file:///workspace/{name})"),
+                "license": null,
+                "license_file": null,
+                "description": null,
+                "source": null,
+                "dependencies": dependencies,
+                "targets": [],
+                "features": {},
+                "manifest_path": format!("/workspace/{name}/Cargo.toml"),
+                "metadata": null,
+                "publish": null,
+                "authors": [],
+                "categories": [],
+                "keywords": [],
+                "readme": null,
+                "repository": null,
+                "homepage": null,
+                "documentation": null,
+                "edition": "2021",
+                "links": null,
+                "default_run": null,
+                "rust_version": null,
+            })
+        })
+        .collect();
+    let workspace_members: Vec<_> = packages.iter().map(|p| p["id"].clone()).collect();
+
+    serde_json::from_value(serde_json::json!({
+        "packages": packages,
+        "workspace_members": workspace_members,
+        "resolve": null,
+        "workspace_root": "/workspace",
+        "target_directory": "/workspace/target",
+        "version": 1,
+        "metadata": null,
+    }))
+    .unwrap()
+}
+
+fn allowlist(shared: &[&str]) -> Allowlist {
+    Allowlist {
+        shared: shared.iter().map(|s| s.to_string()).collect(),
+    }
+}
+
+#[test]
+fn test_graph_follows_normal_dependencies() {
+    let metadata = fixture(&[
+        (
+            "node",
+            &[
+                ("a", None, None),
+                ("test-utils", Some("dev"), None),
+                ("codegen", Some("build"), None),
+                ("simulator", None, Some("cfg(msim)")),
+            ],
+        ),
+        ("a", &[("b", None, None)]),
+        ("b", &[("a", None, None), ("c", None, None)]),
+        ("test-utils", &[("d", None, None)]),
+        ("unrelated", &[("a", None, None)]),
+    ]);
+    let packages = Packages::new(&metadata);
+    let graph = packages.graph("node");
+
+    let mut nodes: Vec<_> = graph.nodes().collect();
+    nodes.sort();
+    assert_eq!(nodes, vec!["a", "b", "c", "node"]);
+
+    let mut edges: Vec<_> = graph.all_edges().map(|(from, to, _)| (from, to)).collect();
+    edges.sort();
+    assert_eq!(edges, vec![("a", "b"), ("b", "c"), ("node", "a")]);
+}
+
+#[test]
+fn test_bypassing_paths() {
+    let metadata = fixture(&[
+        (
+            "mgo-execution",
+            &[("adapter", None, None), ("types", None, None)],
+        ),
+        (
+            "node",
+            &[
+                ("mgo-execution", None, None),
+                ("tool", None, None),
+                ("types", None, None),
+            ],
+        ),
+        ("tool", &[("adapter", None, None)]),
+        ("replay", &[("mgo-execution", None, None)]),
+    ]);
+    let packages = Packages::new(&metadata);
+
+    let exec_crates = packages.execution_crates(&allowlist(&["types"]));
+    assert_eq!(exec_crates, BTreeSet::from(["adapter"]));
+
+    let report = packages.bypassing_paths(&["node", "replay"], &exec_crates);
+    assert_eq!(
+        report.violations,
+        vec![Violation::new(
+            "node",
+            "adapter",
+            vec!["node", "tool", "adapter"]
+        )]
+    );
+    assert_eq!(report.violations[0].to_string(), "node -> tool -> adapter");
+
+    let report: serde_json::Value = serde_json::to_value(&report).unwrap();
+    assert_eq!(
+        report,
+        serde_json::json!({
+            "violations": [{
+                "root": "node",
+                "execution_crate": "adapter",
+                "path": ["node", "tool", "adapter"],
+            }],
+        })
+    );
+}